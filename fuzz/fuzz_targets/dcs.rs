@@ -0,0 +1,19 @@
+//! Wrap arbitrary bytes in DCS introducers (Sixel, ReGIS, DECRQSS and an
+//! unrecognized final) to concentrate on the DCS handlers.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use par_term_emu_core_rust::terminal::Terminal;
+
+const INTRODUCERS: [&[u8]; 4] = [b"\x1bPq", b"\x1bP0;1;0q", b"\x1bPp", b"\x1bP$q"];
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&selector, body)) = data.split_first() else {
+        return;
+    };
+    let mut term = Terminal::new(80, 24);
+    term.process_fuzz(INTRODUCERS[usize::from(selector) % INTRODUCERS.len()]);
+    term.process_fuzz(body);
+    term.process_fuzz(b"\x1b\\");
+});
@@ -0,0 +1,11 @@
+//! Feed arbitrary bytes to a fresh terminal in one call.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use par_term_emu_core_rust::terminal::Terminal;
+
+fuzz_target!(|data: &[u8]| {
+    let mut term = Terminal::new(80, 24);
+    term.process_fuzz(data);
+});
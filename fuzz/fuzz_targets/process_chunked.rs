@@ -0,0 +1,23 @@
+//! Feed arbitrary bytes in chunks so sequences straddle `process` calls.
+//!
+//! The first byte picks the chunk size; the terminal is small and the
+//! sequence ceilings low so resize, wrap and overflow paths are reached
+//! quickly.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use par_term_emu_core_rust::terminal::Terminal;
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&chunk, rest)) = data.split_first() else {
+        return;
+    };
+    let mut term = Terminal::new(12, 6);
+    term.set_max_osc_data_length(256);
+    term.set_max_dcs_data_length(256);
+    term.set_max_apc_data_length(256);
+    for piece in rest.chunks(usize::from(chunk).max(1)) {
+        term.process_fuzz(piece);
+    }
+});
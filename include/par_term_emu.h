@@ -0,0 +1,214 @@
+#ifndef PAR_TERM_EMU_H
+#define PAR_TERM_EMU_H
+
+/* Generated by cbindgen from src/ffi.rs - do not edit by hand. Run `make ffi-header`. */
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+typedef struct Terminal Terminal;
+
+// A single terminal cell in a C-compatible layout.
+//
+// The `text` field holds the UTF-8 bytes of the base character (up to 4 bytes
+// for any Unicode scalar value). `text_len` indicates how many bytes are valid.
+typedef struct SharedCell {
+  // UTF-8 encoded character bytes (up to 4 bytes for any Unicode scalar)
+  uint8_t text[4];
+  // Number of valid bytes in `text`
+  uint8_t text_len;
+  // Foreground color — red component
+  uint8_t fg_r;
+  // Foreground color — green component
+  uint8_t fg_g;
+  // Foreground color — blue component
+  uint8_t fg_b;
+  // Background color — red component
+  uint8_t bg_r;
+  // Background color — green component
+  uint8_t bg_g;
+  // Background color — blue component
+  uint8_t bg_b;
+  // Bitfield of cell attributes (bold, italic, etc.) — see `CellBitflags`
+  uint16_t attrs;
+  // Display width of the character (typically 1 or 2)
+  uint8_t width;
+} SharedCell;
+
+// A complete, C-compatible snapshot of the terminal state.
+//
+// All heap-allocated fields (`title`, `cwd`, `cells`) are owned by this struct
+// and freed on `Drop`.
+typedef struct SharedState {
+  // Number of columns in the terminal grid
+  uint32_t cols;
+  // Number of rows in the terminal grid
+  uint32_t rows;
+  // Current cursor column (0-indexed)
+  uint32_t cursor_col;
+  // Current cursor row (0-indexed)
+  uint32_t cursor_row;
+  // Whether the cursor is visible
+  bool cursor_visible;
+  // Whether the alternate screen buffer is active
+  bool alt_screen_active;
+  // Mouse tracking mode (0=Off, 1=X10, 2=Normal, 3=ButtonEvent, 4=AnyEvent)
+  uint8_t mouse_mode;
+  // Terminal title as a NUL-terminated C string (owned)
+  char *title;
+  // Length of the title string in bytes (not counting NUL)
+  uint32_t title_len;
+  // Current working directory as a NUL-terminated C string (owned), or null
+  char *cwd;
+  // Length of the cwd string in bytes (not counting NUL), 0 if cwd is null
+  uint32_t cwd_len;
+  // Pointer to an array of `cell_count` SharedCell values (owned)
+  struct SharedCell *cells;
+  // Total number of cells (cols * rows)
+  uint32_t cell_count;
+  // Number of lines currently in the scrollback buffer
+  uint32_t scrollback_lines;
+  // Total lines (visible + scrollback)
+  uint32_t total_lines;
+} SharedState;
+
+// A C-compatible vtable for terminal event observation.
+//
+// Each function pointer receives the `user_data` pointer and a JSON-encoded
+// event description as a NUL-terminated C string. The callee must NOT free
+// the event string — it is owned by the caller and valid only for the
+// duration of the callback.
+typedef struct TerminalObserverVtable {
+  // Called for zone lifecycle events
+  void (*on_zone_event)(void *user_data, const char *event_json);
+  // Called for command/shell integration events
+  void (*on_command_event)(void *user_data, const char *event_json);
+  // Called for environment change events
+  void (*on_environment_event)(void *user_data, const char *event_json);
+  // Called for screen content events
+  void (*on_screen_event)(void *user_data, const char *event_json);
+  // Called for ALL events (catch-all)
+  void (*on_event)(void *user_data, const char *event_json);
+  // Opaque pointer passed to every callback
+  void *user_data;
+} TerminalObserverVtable;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Create a snapshot of the terminal's current state.
+//
+// The caller owns the returned `SharedState` and must free it by calling
+// `terminal_free_state`.
+//
+// # Safety
+// `term` must be a valid pointer to a `Terminal`.
+struct SharedState *terminal_get_state(const struct Terminal *term);
+
+// Free a `SharedState` previously returned by `terminal_get_state`.
+//
+// # Safety
+// `state` must be a pointer previously returned by `terminal_get_state`,
+// and must not be used after this call.
+void terminal_free_state(struct SharedState *state);
+
+// Register an FFI observer on the terminal.
+//
+// Returns an observer ID that can be passed to `terminal_remove_observer`.
+//
+// # Safety
+// `term` must be a valid, mutable pointer to a `Terminal`.
+// The `vtable` must remain valid (including its `user_data`) for as long as
+// the observer is registered.
+uint64_t terminal_add_observer(struct Terminal *term, struct TerminalObserverVtable vtable);
+
+// Remove a previously registered observer.
+//
+// Returns `true` if the observer was found and removed.
+//
+// # Safety
+// `term` must be a valid, mutable pointer to a `Terminal`.
+bool terminal_remove_observer(struct Terminal *term, uint64_t id);
+
+// Create a new terminal with the default scrollback size.
+//
+// The caller owns the returned `Terminal` and must free it by calling
+// `terminal_free`. Returns null if `cols` or `rows` is zero.
+struct Terminal *terminal_new(uint32_t cols, uint32_t rows);
+
+// Create a new terminal keeping up to `scrollback` lines of history.
+//
+// The caller owns the returned `Terminal` and must free it by calling
+// `terminal_free`. Returns null if `cols` or `rows` is zero.
+struct Terminal *terminal_new_with_scrollback(uint32_t cols, uint32_t rows, uint32_t scrollback);
+
+// Free a `Terminal` previously returned by `terminal_new`.
+//
+// # Safety
+// `term` must be null or a pointer previously returned by `terminal_new` /
+// `terminal_new_with_scrollback`, and must not be used after this call.
+void terminal_free(struct Terminal *term);
+
+// Feed `len` bytes of program output to the terminal.
+//
+// # Safety
+// `term` must be a valid, mutable pointer to a `Terminal`, and `data` must
+// point to at least `len` readable bytes (it may be null when `len` is 0).
+void terminal_process(struct Terminal *term, const uint8_t *data, size_t len);
+
+// Resize the terminal grid.
+//
+// Returns `false` if `term` is null or either dimension is zero.
+//
+// # Safety
+// `term` must be a valid, mutable pointer to a `Terminal`.
+bool terminal_resize(struct Terminal *term, uint32_t cols, uint32_t rows);
+
+// Return the visible screen as a JSON `screen_snapshot` message (styled cell
+// runs, cursor and zones), in the same shape the streaming protocol uses.
+//
+// The caller owns the returned string and must free it by calling
+// `terminal_string_free`. Returns null if `term` is null.
+//
+// # Safety
+// `term` must be a valid pointer to a `Terminal`.
+char *terminal_snapshot_json(const struct Terminal *term);
+
+// Drain pending terminal events as a JSON array of streaming event messages.
+//
+// Events with no streaming representation (such as dirty regions) are left
+// out. The caller owns the returned string and must free it by calling
+// `terminal_string_free`. Returns null if `term` is null.
+//
+// # Safety
+// `term` must be a valid, mutable pointer to a `Terminal`.
+char *terminal_poll_events(struct Terminal *term);
+
+// Drain replies the terminal wants to send back to the application (device
+// attributes, cursor reports, ...) into `buf`.
+//
+// Returns the total number of pending response bytes. If that is larger than
+// `cap`, nothing is copied and the responses stay queued, so the caller can
+// retry with a bigger buffer. Passing a null `buf` with `cap` 0 queries the
+// size.
+//
+// # Safety
+// `term` must be a valid, mutable pointer to a `Terminal`, and `buf` must
+// point to at least `cap` writable bytes (it may be null when `cap` is 0).
+size_t terminal_drain_responses(struct Terminal *term, uint8_t *buf, size_t cap);
+
+// Free a string previously returned by `terminal_snapshot_json` or
+// `terminal_poll_events`.
+//
+// # Safety
+// `s` must be null or a pointer previously returned by one of those
+// functions, and must not be used after this call.
+void terminal_string_free(char *s);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* PAR_TERM_EMU_H */
@@ -22,6 +22,7 @@ from ._native import (
     Attributes,
     CoprocessConfig,
     CursorStyle,
+    GraphemeClustering,
     Graphic,
     ImageDimension,
     ImagePlacement,
@@ -37,6 +38,7 @@ from ._native import (
     ScreenshotConfig,
     ShellIntegration,
     Terminal,
+    TerminalState,
     Trigger,
     TriggerAction,
     TriggerMatch,
@@ -69,6 +71,9 @@ from ._native import (
     str_width,
     str_width_cjk,
     is_east_asian_ambiguous,
+    # ANSI span functions
+    parse_ansi_spans,
+    spans_to_ansi,
 )
 
 # Optional streaming support (available when built with --features streaming)
@@ -106,6 +111,7 @@ __all__ = [
     "Attributes",
     "CoprocessConfig",
     "CursorStyle",
+    "GraphemeClustering",
     "Graphic",
     "ImageDimension",
     "ImagePlacement",
@@ -121,6 +127,7 @@ __all__ = [
     "ScreenshotConfig",
     "ShellIntegration",
     "Terminal",
+    "TerminalState",
     "Trigger",
     "TriggerAction",
     "TriggerMatch",
@@ -153,6 +160,9 @@ __all__ = [
     "str_width",
     "str_width_cjk",
     "is_east_asian_ambiguous",
+    # ANSI span functions
+    "parse_ansi_spans",
+    "spans_to_ansi",
     # Observer convenience wrappers
     "on_bell",
     "on_command_complete",
@@ -0,0 +1,75 @@
+//! Cell-level metadata annotations
+//!
+//! Annotations let frontends attach notes (lint errors, suggestions, user
+//! comments) to a run of cells on one row. They are stored on the Grid next
+//! to the zones and use the same absolute row numbering, so they follow
+//! their text into scrollback and are dropped when that text is evicted.
+
+/// A run of cells on one absolute row
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AnnotationRange {
+    /// Absolute row (scrollback_len + screen row)
+    pub abs_row: usize,
+    /// First column
+    pub col: usize,
+    /// Number of cells covered
+    pub length: usize,
+}
+
+impl AnnotationRange {
+    /// Create a range of `length` cells starting at (`abs_row`, `col`)
+    pub fn new(abs_row: usize, col: usize, length: usize) -> Self {
+        Self {
+            abs_row,
+            col,
+            length,
+        }
+    }
+
+    /// Column just past the last covered cell
+    pub fn end_col(&self) -> usize {
+        self.col + self.length
+    }
+
+    /// Check if the cell at (`abs_row`, `col`) is covered
+    pub fn contains(&self, abs_row: usize, col: usize) -> bool {
+        abs_row == self.abs_row && col >= self.col && col < self.end_col()
+    }
+}
+
+/// A note attached to a run of cells
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotation {
+    /// Unique annotation identifier (monotonically increasing per terminal)
+    pub id: usize,
+    /// Annotated cells
+    pub range: AnnotationRange,
+    /// Annotation text
+    pub text: String,
+    /// Free-form category chosen by the frontend (e.g. "lint", "note")
+    pub tag: Option<String>,
+    /// Timestamp in Unix milliseconds when the annotation was added
+    pub timestamp: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_annotation_range_contains() {
+        let range = AnnotationRange::new(3, 4, 2);
+        assert_eq!(range.end_col(), 6);
+        assert!(!range.contains(3, 3));
+        assert!(range.contains(3, 4));
+        assert!(range.contains(3, 5));
+        assert!(!range.contains(3, 6));
+        assert!(!range.contains(2, 4));
+    }
+
+    #[test]
+    fn test_empty_range_contains_nothing() {
+        let range = AnnotationRange::new(0, 2, 0);
+        assert!(!range.contains(0, 2));
+    }
+}
@@ -1,5 +1,6 @@
 //! ANSI sequence utilities for generation and parsing
 
+use crate::cell::UnderlineStyle;
 use crate::color::Color;
 use crate::unicode_width_config::{str_width, WidthConfig};
 
@@ -208,9 +209,392 @@ pub fn parse_color(s: &str) -> Option<Color> {
     Some(Color::Named(color))
 }
 
+/// Text attributes of a [`StyledSpan`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpanAttributes {
+    /// Bold or increased intensity (SGR 1)
+    pub bold: bool,
+    /// Faint or decreased intensity (SGR 2)
+    pub dim: bool,
+    /// Italic (SGR 3)
+    pub italic: bool,
+    /// Underline style (`UnderlineStyle::None` = not underlined)
+    pub underline: UnderlineStyle,
+    /// Blinking, slow or rapid (SGR 5 / 6)
+    pub blink: bool,
+    /// Foreground and background swapped (SGR 7)
+    pub reverse: bool,
+    /// Concealed text (SGR 8)
+    pub hidden: bool,
+    /// Crossed out (SGR 9)
+    pub strikethrough: bool,
+    /// Line above the text (SGR 53)
+    pub overline: bool,
+}
+
+/// A run of text with one style, produced by [`parse_spans`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StyledSpan {
+    /// The text, with escape sequences removed
+    pub text: String,
+    /// Foreground color (`None` = default)
+    pub fg: Option<Color>,
+    /// Background color (`None` = default)
+    pub bg: Option<Color>,
+    /// Underline color (SGR 58; `None` = same as the text)
+    pub underline_color: Option<Color>,
+    /// Text attributes
+    pub attrs: SpanAttributes,
+    /// Target of an enclosing OSC 8 hyperlink
+    pub link: Option<String>,
+}
+
+impl StyledSpan {
+    /// Whether `other` has the same style and link
+    fn same_style(&self, other: &StyledSpan) -> bool {
+        self.fg == other.fg
+            && self.bg == other.bg
+            && self.underline_color == other.underline_color
+            && self.attrs == other.attrs
+            && self.link == other.link
+    }
+}
+
+/// Split text containing ANSI escape sequences into styled spans, without
+/// a [`Terminal`](crate::terminal::Terminal).
+///
+/// SGR sequences set the style and OSC 8 sequences the link of the text
+/// that follows; adjacent text with the same style forms one span. Tabs and
+/// line feeds are kept as text. Other escape sequences and control
+/// characters are dropped, since there is no screen to apply them to.
+///
+/// ```
+/// use par_term_emu_core_rust::ansi_utils::parse_spans;
+/// use par_term_emu_core_rust::color::{Color, NamedColor};
+///
+/// let spans = parse_spans("ok \x1b[1;31mFAIL\x1b[0m");
+/// assert_eq!(spans[0].text, "ok ");
+/// assert_eq!(spans[1].text, "FAIL");
+/// assert_eq!(spans[1].fg, Some(Color::Named(NamedColor::Red)));
+/// assert!(spans[1].attrs.bold);
+/// ```
+pub fn parse_spans(text: &str) -> Vec<StyledSpan> {
+    let mut parser = vte::Parser::new();
+    let mut performer = SpanParser::default();
+    parser.advance(&mut performer, text.as_bytes());
+    performer.finish()
+}
+
+/// Render spans back into text with SGR and OSC 8 sequences, the reverse of
+/// [`parse_spans`]. Each style change emits a full SGR (starting with a
+/// reset), and the output ends in the default style with no open link.
+pub fn spans_to_ansi(spans: &[StyledSpan]) -> String {
+    let plain = StyledSpan::default();
+    let mut out = String::new();
+    let mut current = &plain;
+    for span in spans.iter().filter(|span| !span.text.is_empty()) {
+        if span.link != current.link {
+            push_osc8(&mut out, span.link.as_deref());
+        }
+        if !span.same_style(current) && !same_sgr(span, current) {
+            out.push_str(&span_sgr(span));
+        }
+        out.push_str(&span.text);
+        current = span;
+    }
+    if current.link.is_some() {
+        push_osc8(&mut out, None);
+    }
+    if !same_sgr(current, &plain) {
+        out.push_str("\x1b[0m");
+    }
+    out
+}
+
+fn same_sgr(a: &StyledSpan, b: &StyledSpan) -> bool {
+    a.fg == b.fg && a.bg == b.bg && a.underline_color == b.underline_color && a.attrs == b.attrs
+}
+
+fn push_osc8(out: &mut String, link: Option<&str>) {
+    out.push_str("\x1b]8;;");
+    out.push_str(link.unwrap_or(""));
+    out.push_str("\x1b\\");
+}
+
+/// `ESC [ 0 ; ... m` selecting exactly the style of `span`
+fn span_sgr(span: &StyledSpan) -> String {
+    let attrs = &span.attrs;
+    let mut codes = vec!["0".to_string()];
+    for (on, code) in [
+        (attrs.bold, "1"),
+        (attrs.dim, "2"),
+        (attrs.italic, "3"),
+        (attrs.blink, "5"),
+        (attrs.reverse, "7"),
+        (attrs.hidden, "8"),
+        (attrs.strikethrough, "9"),
+        (attrs.overline, "53"),
+    ] {
+        if on {
+            codes.push(code.to_string());
+        }
+    }
+    match attrs.underline {
+        UnderlineStyle::None => {}
+        UnderlineStyle::Straight => codes.push("4".to_string()),
+        UnderlineStyle::Double => codes.push("4:2".to_string()),
+        UnderlineStyle::Curly => codes.push("4:3".to_string()),
+        UnderlineStyle::Dotted => codes.push("4:4".to_string()),
+        UnderlineStyle::Dashed => codes.push("4:5".to_string()),
+    }
+    if let Some(color) = span.fg {
+        codes.push(color_sgr(color, 30, 90, "38"));
+    }
+    if let Some(color) = span.bg {
+        codes.push(color_sgr(color, 40, 100, "48"));
+    }
+    if let Some(color) = span.underline_color {
+        codes.push(match color {
+            Color::Named(c) => format!("58:5:{}", c as u8),
+            Color::Indexed(idx) => format!("58:5:{idx}"),
+            Color::Rgb(r, g, b) => format!("58:2::{r}:{g}:{b}"),
+        });
+    }
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+fn color_sgr(color: Color, base: u8, bright_base: u8, extended: &str) -> String {
+    match color {
+        Color::Named(c) if (c as u8) < 8 => (base + c as u8).to_string(),
+        Color::Named(c) => (bright_base + c as u8 - 8).to_string(),
+        Color::Indexed(idx) => format!("{extended};5;{idx}"),
+        Color::Rgb(r, g, b) => format!("{extended};2;{r};{g};{b}"),
+    }
+}
+
+/// Parser callbacks for [`parse_spans`]
+#[derive(Default)]
+struct SpanParser {
+    spans: Vec<StyledSpan>,
+    style: StyledSpan,
+}
+
+impl SpanParser {
+    fn push(&mut self, c: char) {
+        match self.spans.last_mut() {
+            Some(last) if last.same_style(&self.style) => last.text.push(c),
+            _ => {
+                let mut span = self.style.clone();
+                span.text.push(c);
+                self.spans.push(span);
+            }
+        }
+    }
+
+    fn finish(self) -> Vec<StyledSpan> {
+        self.spans
+    }
+
+    fn apply_sgr(&mut self, params: &vte::Params) {
+        let style = &mut self.style;
+        let attrs = &mut style.attrs;
+        let mut iter = params.iter();
+        if params.is_empty() {
+            *style = StyledSpan {
+                link: style.link.take(),
+                ..StyledSpan::default()
+            };
+            return;
+        }
+        while let Some(param) = iter.next() {
+            match param[0] {
+                0 => {
+                    *attrs = SpanAttributes::default();
+                    style.fg = None;
+                    style.bg = None;
+                    style.underline_color = None;
+                }
+                1 => attrs.bold = true,
+                2 => attrs.dim = true,
+                3 => attrs.italic = true,
+                4 => {
+                    attrs.underline = match param.get(1) {
+                        None | Some(1) => UnderlineStyle::Straight,
+                        Some(0) => UnderlineStyle::None,
+                        Some(2) => UnderlineStyle::Double,
+                        Some(3) => UnderlineStyle::Curly,
+                        Some(4) => UnderlineStyle::Dotted,
+                        Some(5) => UnderlineStyle::Dashed,
+                        Some(_) => UnderlineStyle::Straight,
+                    }
+                }
+                5 | 6 => attrs.blink = true,
+                7 => attrs.reverse = true,
+                8 => attrs.hidden = true,
+                9 => attrs.strikethrough = true,
+                21 => attrs.underline = UnderlineStyle::Double,
+                22 => {
+                    attrs.bold = false;
+                    attrs.dim = false;
+                }
+                23 => attrs.italic = false,
+                24 => attrs.underline = UnderlineStyle::None,
+                25 => attrs.blink = false,
+                27 => attrs.reverse = false,
+                28 => attrs.hidden = false,
+                29 => attrs.strikethrough = false,
+                53 => attrs.overline = true,
+                55 => attrs.overline = false,
+                n @ 30..=37 => style.fg = Some(Color::from_ansi_code(n as u8 - 30)),
+                n @ 90..=97 => style.fg = Some(Color::from_ansi_code(n as u8 - 90 + 8)),
+                n @ 40..=47 => style.bg = Some(Color::from_ansi_code(n as u8 - 40)),
+                n @ 100..=107 => style.bg = Some(Color::from_ansi_code(n as u8 - 100 + 8)),
+                39 => style.fg = None,
+                49 => style.bg = None,
+                59 => style.underline_color = None,
+                n @ (38 | 48 | 58) => {
+                    let color = extended_color(param, &mut iter);
+                    if let Some(color) = color {
+                        match n {
+                            38 => style.fg = Some(color),
+                            48 => style.bg = Some(color),
+                            _ => style.underline_color = Some(color),
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Color of an SGR 38/48/58 parameter, in colon form (`38:2::r:g:b`,
+/// `38:5:n`) or semicolon form (`38;2;r;g;b`, consuming the following
+/// parameters)
+fn extended_color<'a>(param: &[u16], rest: &mut impl Iterator<Item = &'a [u16]>) -> Option<Color> {
+    let channel = |v: Option<u16>| v.unwrap_or(0).min(255) as u8;
+    if param.len() > 1 {
+        return match param[1] {
+            // 38:2:r:g:b or 38:2:colorspace:r:g:b
+            2 => {
+                let rgb = if param.len() >= 6 {
+                    &param[3..6]
+                } else {
+                    &param[2..]
+                };
+                Some(Color::Rgb(
+                    channel(rgb.first().copied()),
+                    channel(rgb.get(1).copied()),
+                    channel(rgb.get(2).copied()),
+                ))
+            }
+            5 => param
+                .get(2)
+                .map(|&idx| Color::from_ansi_code(idx.min(255) as u8)),
+            _ => None,
+        };
+    }
+    let mut next = || rest.next().map(|p| p[0]);
+    match next()? {
+        2 => Some(Color::Rgb(
+            channel(next()),
+            channel(next()),
+            channel(next()),
+        )),
+        5 => next().map(|idx| Color::from_ansi_code(idx.min(255) as u8)),
+        _ => None,
+    }
+}
+
+impl vte::Perform for SpanParser {
+    fn print(&mut self, c: char) {
+        self.push(c);
+    }
+
+    fn execute(&mut self, byte: u8) {
+        if matches!(byte, b'\t' | b'\n') {
+            self.push(byte as char);
+        }
+    }
+
+    fn csi_dispatch(
+        &mut self,
+        params: &vte::Params,
+        intermediates: &[u8],
+        _ignore: bool,
+        action: char,
+    ) {
+        if action == 'm' && intermediates.is_empty() {
+            self.apply_sgr(params);
+        }
+    }
+
+    fn osc_dispatch(&mut self, params: &[&[u8]], _bell_terminated: bool) {
+        // OSC 8 ; params ; URI — an empty URI closes the link
+        if params.first() == Some(&&b"8"[..]) {
+            let uri = params
+                .get(2..)
+                .map(|parts| parts.join(&b';'))
+                .unwrap_or_default();
+            self.style.link = (!uri.is_empty()).then(|| String::from_utf8_lossy(&uri).into_owned());
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::color::NamedColor;
+
+    #[test]
+    fn test_parse_spans_styles_and_links() {
+        let spans = parse_spans(
+            "a\x1b[1;38;5;208mb\x1b[22;4:3;48;2;1;2;3mc\x1b]8;;https://x.io\x1b\\d\x1b]8;;\x07\x1b[0me",
+        );
+        let texts: Vec<&str> = spans.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, ["a", "b", "c", "d", "e"]);
+        assert_eq!(
+            spans[0],
+            StyledSpan {
+                text: "a".into(),
+                ..Default::default()
+            }
+        );
+        assert!(spans[1].attrs.bold);
+        assert_eq!(spans[1].fg, Some(Color::Indexed(208)));
+        assert!(!spans[2].attrs.bold);
+        assert_eq!(spans[2].attrs.underline, UnderlineStyle::Curly);
+        assert_eq!(spans[2].bg, Some(Color::Rgb(1, 2, 3)));
+        assert_eq!(spans[3].link.as_deref(), Some("https://x.io"));
+        assert_eq!(spans[3].bg, Some(Color::Rgb(1, 2, 3)));
+        assert_eq!(
+            spans[4],
+            StyledSpan {
+                text: "e".into(),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_spans_drops_other_sequences_and_merges_runs() {
+        let spans = parse_spans("x\x1b[2J\x1b[31my\x1b[Hz\tw\x07");
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].text, "x");
+        assert_eq!(spans[1].text, "yz\tw");
+        assert_eq!(spans[1].fg, Some(Color::Named(NamedColor::Red)));
+    }
+
+    #[test]
+    fn test_spans_to_ansi_round_trip() {
+        let input = "plain \x1b[1;91mbright\x1b[0m \x1b]8;;http://a\x1b\\\x1b[4:2;38;2;9;8;7mlink\x1b]8;;\x1b\\\x1b[0m end";
+        let spans = parse_spans(input);
+        let ansi = spans_to_ansi(&spans);
+        assert_eq!(parse_spans(&ansi), spans);
+        assert!(ansi.contains("\x1b[0;1;91m"));
+        assert!(ansi.ends_with(" end"));
+        assert_eq!(spans_to_ansi(&parse_spans("no style")), "no style");
+    }
 
     #[test]
     fn test_strip_ansi() {
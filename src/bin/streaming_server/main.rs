@@ -257,7 +257,8 @@ struct Args {
     #[arg(long, default_value = "./web_term", env = "PAR_TERM_WEB_ROOT")]
     web_root: String,
 
-    /// Macro file to play back instead of running a shell
+    /// Macro file (YAML, or JSON with a .json extension) to play back instead
+    /// of running a shell
     #[arg(long, env = "PAR_TERM_MACRO_FILE")]
     macro_file: Option<String>,
 
@@ -1507,7 +1508,7 @@ async fn main() -> Result<()> {
         let output_sender = streaming_server.get_output_sender();
 
         info!("Loading macro file: {}", macro_file);
-        let macro_data = Macro::load_yaml(macro_file)
+        let macro_data = Macro::load(macro_file)
             .context(format!("Failed to load macro file: {}", macro_file))?;
 
         info!("Macro loaded: {}", macro_data.name);
@@ -1542,6 +1543,10 @@ async fn main() -> Result<()> {
                                     session.write(&bytes).ok();
                                 }
                             }
+                            MacroEvent::Input { data, .. } => {
+                                let mut session = pty_session_clone.lock();
+                                session.write(data.as_bytes()).ok();
+                            }
                             MacroEvent::Delay { duration, .. } => {
                                 tokio::time::sleep(Duration::from_millis(
                                     (duration as f64 / macro_speed) as u64,
@@ -1,5 +1,5 @@
 use crate::color::Color;
-use crate::unicode_width_config::{char_width, str_width, WidthConfig};
+use crate::unicode_width_config::{char_width, grapheme_width, WidthConfig};
 use bitflags::bitflags;
 use smallvec::SmallVec;
 use std::num::NonZeroU32;
@@ -195,6 +195,17 @@ impl CellFlags {
     pub fn to_bitflags(&self) -> u16 {
         self.bits.bits()
     }
+
+    /// Build flags from a raw bits value produced by [`Self::to_bitflags`]
+    ///
+    /// Unknown bits are dropped; underline style and hyperlink are left unset.
+    #[inline]
+    pub(crate) fn from_bitflags(bits: u16) -> Self {
+        Self {
+            bits: CellBitflags::from_bits_truncate(bits),
+            ..Self::default()
+        }
+    }
 }
 
 /// A single cell in the terminal grid
@@ -208,8 +219,9 @@ impl CellFlags {
 pub struct Cell {
     /// The character stored in this cell
     pub(crate) c: char,
-    /// Combining characters (variation selectors, ZWJ, modifiers, etc.)
-    /// These follow the base character to form a complete grapheme cluster.
+    /// Remaining code points of the extended grapheme cluster that starts
+    /// with `c` (combining and spacing marks, variation selectors, ZWJ
+    /// sequences, emoji modifiers and tags, ...).
     /// Stored inline (no heap alloc) for up to 4 marks — the overwhelmingly
     /// common case — spilling to the heap only for rare long clusters.
     pub(crate) combining: SmallVec<[char; 4]>,
@@ -406,7 +418,7 @@ impl Cell {
         let mut chars = grapheme.chars();
         let base_char = chars.next().unwrap_or(' ');
         let combining: SmallVec<[char; 4]> = chars.collect();
-        let width = str_width(grapheme, &WidthConfig::default()).max(1) as u8;
+        let width = grapheme_width(grapheme, &WidthConfig::default()).max(1) as u8;
 
         Self {
             c: base_char,
@@ -421,7 +433,7 @@ impl Cell {
         let mut chars = grapheme.chars();
         let base_char = chars.next().unwrap_or(' ');
         let combining: SmallVec<[char; 4]> = chars.collect();
-        let width = str_width(grapheme, config).max(1) as u8;
+        let width = grapheme_width(grapheme, config).max(1) as u8;
 
         Self {
             c: base_char,
@@ -443,7 +455,7 @@ impl Cell {
         let mut chars = normalized.chars();
         let base_char = chars.next().unwrap_or(' ');
         let combining: SmallVec<[char; 4]> = chars.collect();
-        let width = str_width(&normalized, config).max(1) as u8;
+        let width = grapheme_width(&normalized, config).max(1) as u8;
 
         Self {
             c: base_char,
@@ -461,7 +473,7 @@ impl Cell {
             self.width = char_width(self.c, config) as u8;
         } else {
             let grapheme = self.get_grapheme();
-            self.width = str_width(&grapheme, config).max(1) as u8;
+            self.width = grapheme_width(&grapheme, config).max(1) as u8;
         }
     }
 }
@@ -687,6 +699,19 @@ mod tests {
         assert_eq!(cell.width(), 2);
     }
 
+    #[test]
+    fn test_cell_from_grapheme_width_is_per_cluster() {
+        // ZWJ sequence: two wide emoji joined into one glyph, not 4 columns
+        let cell = Cell::from_grapheme("\u{1F468}\u{200D}\u{1F4BB}");
+        assert_eq!(cell.c, '\u{1F468}');
+        assert_eq!(cell.combining(), &['\u{200D}', '\u{1F4BB}']);
+        assert_eq!(cell.width(), 2);
+
+        let accented = Cell::from_grapheme("e\u{0301}");
+        assert_eq!(accented.width(), 1);
+        assert_eq!(accented.get_grapheme(), "e\u{0301}");
+    }
+
     #[test]
     fn test_cell_with_zero_width_char() {
         // Combining characters have width 0
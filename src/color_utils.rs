@@ -190,6 +190,74 @@ pub fn adjust_contrast_rgb(
     )
 }
 
+/// Adjust a foreground color until it reaches a WCAG contrast ratio against
+/// the background.
+///
+/// The foreground is blended toward white or black, whichever reaches
+/// `min_ratio` with the smaller change, so hue is kept as far as possible.
+/// When neither extreme reaches the ratio, the extreme with the higher
+/// contrast is returned. Ratios at or below 1.0 leave the color unchanged.
+///
+/// # Arguments
+///
+/// * `fg` - Foreground RGB color (0-255 per channel)
+/// * `bg` - Background RGB color (0-255 per channel)
+/// * `min_ratio` - Minimum WCAG contrast ratio (1.0-21.0, e.g. 4.5 for AA)
+///
+/// # Returns
+///
+/// Adjusted foreground RGB color
+pub fn enforce_contrast_ratio_rgb(
+    fg: (u8, u8, u8),
+    bg: (u8, u8, u8),
+    min_ratio: f32,
+) -> (u8, u8, u8) {
+    let fg_color = Color::Rgb(fg.0, fg.1, fg.2);
+    let bg_color = Color::Rgb(bg.0, bg.1, bg.2);
+    if min_ratio <= 1.0 || fg_color.contrast_ratio(&bg_color) >= min_ratio {
+        return fg;
+    }
+
+    // Smallest blend toward `target` that reaches the ratio, if any
+    let blend_toward = |target: Color| -> Option<(f32, Color)> {
+        if target.contrast_ratio(&bg_color) < min_ratio {
+            return None;
+        }
+        let (mut lo, mut hi) = (0.0f32, 1.0f32);
+        for _ in 0..16 {
+            let mid = (lo + hi) / 2.0;
+            if fg_color.mix(&target, mid).contrast_ratio(&bg_color) >= min_ratio {
+                hi = mid;
+            } else {
+                lo = mid;
+            }
+        }
+        Some((hi, fg_color.mix(&target, hi)))
+    };
+
+    let white = Color::Rgb(255, 255, 255);
+    let black = Color::Rgb(0, 0, 0);
+    let adjusted = match (blend_toward(white), blend_toward(black)) {
+        (Some((w, lighter)), Some((b, darker))) => {
+            if w <= b {
+                lighter
+            } else {
+                darker
+            }
+        }
+        (Some((_, lighter)), None) => lighter,
+        (None, Some((_, darker))) => darker,
+        (None, None) => {
+            if white.contrast_ratio(&bg_color) >= black.contrast_ratio(&bg_color) {
+                white
+            } else {
+                black
+            }
+        }
+    };
+    adjusted.to_rgb()
+}
+
 /// Extended color utilities
 impl Color {
     /// Convert color to hex string
@@ -454,6 +522,25 @@ impl Color {
 
         Color::Rgb(new_r, new_g, new_b)
     }
+
+    /// Adjust this color to reach a minimum WCAG contrast ratio against a
+    /// background color (see [`enforce_contrast_ratio_rgb`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use par_term_emu_core_rust::color::Color;
+    ///
+    /// // Dark blue text on black background - will be lightened
+    /// let fg = Color::Rgb(0, 0, 128);
+    /// let bg = Color::Rgb(0, 0, 0);
+    /// let adjusted = fg.with_min_contrast_ratio(&bg, 4.5);
+    /// assert!(adjusted.contrast_ratio(&bg) >= 4.5);
+    /// ```
+    pub fn with_min_contrast_ratio(&self, background: &Color, min_ratio: f32) -> Self {
+        let (r, g, b) = enforce_contrast_ratio_rgb(self.to_rgb(), background.to_rgb(), min_ratio);
+        Color::Rgb(r, g, b)
+    }
 }
 
 /// Convert sRGB color to Display P3 color space.
@@ -518,6 +605,38 @@ fn linear_to_srgb(component: f64) -> f64 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_enforce_contrast_ratio() {
+        let bg = (0, 0, 0);
+        // Already readable: unchanged
+        assert_eq!(
+            enforce_contrast_ratio_rgb((255, 255, 255), bg, 7.0),
+            (255, 255, 255)
+        );
+        // Disabled: unchanged
+        assert_eq!(
+            enforce_contrast_ratio_rgb((10, 10, 10), bg, 1.0),
+            (10, 10, 10)
+        );
+
+        for ratio in [3.0, 4.5, 7.0] {
+            let (r, g, b) = enforce_contrast_ratio_rgb((40, 0, 0), bg, ratio);
+            assert!(Color::Rgb(r, g, b).contrast_ratio(&Color::Rgb(0, 0, 0)) >= ratio);
+            // Hue kept: red stays dominant
+            assert!(r > g && r > b);
+        }
+
+        // Light text on a light background is darkened
+        let fg = enforce_contrast_ratio_rgb((230, 230, 200), (255, 255, 255), 4.5);
+        assert!(fg.0 < 230);
+
+        // Unreachable ratio falls back to the better extreme
+        assert_eq!(
+            enforce_contrast_ratio_rgb((120, 120, 120), (128, 128, 128), 21.0),
+            (0, 0, 0)
+        );
+    }
+
     #[test]
     fn test_hex_conversion() {
         let color = Color::Rgb(255, 128, 64);
@@ -1,5 +1,8 @@
+use serde::{Deserialize, Serialize};
+
 /// Cursor shape/style (DECSCUSR)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum CursorStyle {
     /// Blinking block (default)
     #[default]
@@ -16,6 +19,73 @@ pub enum CursorStyle {
     SteadyBar,
 }
 
+/// Cursor shape without the blink state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CursorShape {
+    /// Full cell block
+    Block,
+    /// Line under the cell
+    Underline,
+    /// Vertical bar (I-beam)
+    Bar,
+}
+
+impl CursorStyle {
+    /// Style selected by DECSCUSR parameter `ps` (0 and unknown values mean
+    /// the default blinking block)
+    pub fn from_decscusr(ps: u16) -> Self {
+        match ps {
+            2 => CursorStyle::SteadyBlock,
+            3 => CursorStyle::BlinkingUnderline,
+            4 => CursorStyle::SteadyUnderline,
+            5 => CursorStyle::BlinkingBar,
+            6 => CursorStyle::SteadyBar,
+            _ => CursorStyle::BlinkingBlock,
+        }
+    }
+
+    /// Shape of the cursor
+    pub fn shape(self) -> CursorShape {
+        match self {
+            CursorStyle::BlinkingBlock | CursorStyle::SteadyBlock => CursorShape::Block,
+            CursorStyle::BlinkingUnderline | CursorStyle::SteadyUnderline => CursorShape::Underline,
+            CursorStyle::BlinkingBar | CursorStyle::SteadyBar => CursorShape::Bar,
+        }
+    }
+
+    /// Whether the cursor blinks
+    pub fn is_blinking(self) -> bool {
+        matches!(
+            self,
+            CursorStyle::BlinkingBlock | CursorStyle::BlinkingUnderline | CursorStyle::BlinkingBar
+        )
+    }
+
+    /// Stable snake_case name (used by the Python layer)
+    pub fn name(self) -> &'static str {
+        match self {
+            CursorStyle::BlinkingBlock => "blinking_block",
+            CursorStyle::SteadyBlock => "steady_block",
+            CursorStyle::BlinkingUnderline => "blinking_underline",
+            CursorStyle::SteadyUnderline => "steady_underline",
+            CursorStyle::BlinkingBar => "blinking_bar",
+            CursorStyle::SteadyBar => "steady_bar",
+        }
+    }
+}
+
+impl CursorShape {
+    /// Stable snake_case name (used by the Python layer)
+    pub fn name(self) -> &'static str {
+        match self {
+            CursorShape::Block => "block",
+            CursorShape::Underline => "underline",
+            CursorShape::Bar => "bar",
+        }
+    }
+}
+
 /// Cursor state and position
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Cursor {
@@ -263,4 +333,23 @@ mod tests {
         cursor2.hide();
         assert_eq!(cursor1, cursor2);
     }
+
+    #[test]
+    fn test_cursor_style_shape_and_blink() {
+        for ps in 0..=7 {
+            let style = CursorStyle::from_decscusr(ps);
+            let expected = match ps {
+                3 | 4 => CursorShape::Underline,
+                5 | 6 => CursorShape::Bar,
+                _ => CursorShape::Block,
+            };
+            assert_eq!(style.shape(), expected);
+            assert_eq!(style.is_blinking(), !matches!(ps, 2 | 4 | 6));
+        }
+        assert_eq!(
+            serde_json::to_string(&CursorStyle::SteadyBar).unwrap(),
+            "\"steady_bar\""
+        );
+        assert_eq!(CursorStyle::SteadyBar.name(), "steady_bar");
+    }
 }
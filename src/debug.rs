@@ -1,3 +1,4 @@
+use crate::time::{SystemTime, UNIX_EPOCH};
 use parking_lot::Mutex;
 /// Comprehensive debugging infrastructure for par-term-emu
 ///
@@ -15,7 +16,6 @@ use std::fmt;
 use std::fs::OpenOptions;
 use std::io::Write;
 use std::sync::OnceLock;
-use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Debug level configuration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -56,10 +56,10 @@ impl DebugLogger {
         let file = if level != DebugLevel::Off {
             // Rust uses separate log file from Python
             // Use /tmp on Unix/macOS for consistency with documentation
-            // Use %TEMP% on Windows
+            // Use %TEMP% elsewhere
             #[cfg(unix)]
             let log_path = std::path::PathBuf::from("/tmp/par_term_emu_core_rust_debug_rust.log");
-            #[cfg(windows)]
+            #[cfg(not(unix))]
             let log_path = std::env::temp_dir().join("par_term_emu_core_rust_debug_rust.log");
 
             match OpenOptions::new()
@@ -3,6 +3,9 @@
 //! This module provides `#[repr(C)]` types that can be safely shared across
 //! FFI boundaries (Swift, Kotlin/JNI, C/C++, etc.) and extern "C" functions
 //! for creating, querying, and observing terminal state.
+//!
+//! The C header for this API is generated by cbindgen into
+//! `include/par_term_emu.h` (`make ffi-header`).
 
 use std::collections::HashSet;
 use std::ffi::{c_char, CString};
@@ -10,6 +13,7 @@ use std::sync::Arc;
 
 use crate::mouse::MouseMode;
 use crate::observer::TerminalObserver;
+use crate::streaming::protocol::{terminal_event_to_server_message, terminal_screen_snapshot};
 use crate::terminal::{Terminal, TerminalEvent, TerminalEventKind};
 
 // ---------------------------------------------------------------------------
@@ -425,3 +429,179 @@ pub unsafe extern "C" fn terminal_remove_observer(term: *mut Terminal, id: u64)
     let term_ref = unsafe { &mut *term };
     term_ref.remove_observer(id)
 }
+
+// ---------------------------------------------------------------------------
+// Terminal lifecycle and I/O
+// ---------------------------------------------------------------------------
+
+/// Move a Rust string to the caller as an owned C string.
+///
+/// Interior NUL bytes cannot occur in serde_json output, but fall back to an
+/// empty string rather than panicking across the FFI boundary.
+fn into_c_string(s: String) -> *mut c_char {
+    CString::new(s).unwrap_or_default().into_raw()
+}
+
+/// Create a new terminal with the default scrollback size.
+///
+/// The caller owns the returned `Terminal` and must free it by calling
+/// `terminal_free`. Returns null if `cols` or `rows` is zero.
+#[no_mangle]
+pub extern "C" fn terminal_new(cols: u32, rows: u32) -> *mut Terminal {
+    if cols == 0 || rows == 0 {
+        return std::ptr::null_mut();
+    }
+    Box::into_raw(Box::new(Terminal::new(cols as usize, rows as usize)))
+}
+
+/// Create a new terminal keeping up to `scrollback` lines of history.
+///
+/// The caller owns the returned `Terminal` and must free it by calling
+/// `terminal_free`. Returns null if `cols` or `rows` is zero.
+#[no_mangle]
+pub extern "C" fn terminal_new_with_scrollback(
+    cols: u32,
+    rows: u32,
+    scrollback: u32,
+) -> *mut Terminal {
+    if cols == 0 || rows == 0 {
+        return std::ptr::null_mut();
+    }
+    Box::into_raw(Box::new(Terminal::with_scrollback(
+        cols as usize,
+        rows as usize,
+        scrollback as usize,
+    )))
+}
+
+/// Free a `Terminal` previously returned by `terminal_new`.
+///
+/// # Safety
+/// `term` must be null or a pointer previously returned by `terminal_new` /
+/// `terminal_new_with_scrollback`, and must not be used after this call.
+#[no_mangle]
+pub unsafe extern "C" fn terminal_free(term: *mut Terminal) {
+    if !term.is_null() {
+        unsafe {
+            let _ = Box::from_raw(term);
+        }
+    }
+}
+
+/// Feed `len` bytes of program output to the terminal.
+///
+/// # Safety
+/// `term` must be a valid, mutable pointer to a `Terminal`, and `data` must
+/// point to at least `len` readable bytes (it may be null when `len` is 0).
+#[no_mangle]
+pub unsafe extern "C" fn terminal_process(term: *mut Terminal, data: *const u8, len: usize) {
+    if term.is_null() || data.is_null() || len == 0 {
+        return;
+    }
+    let term_ref = unsafe { &mut *term };
+    let bytes = unsafe { std::slice::from_raw_parts(data, len) };
+    term_ref.process(bytes);
+}
+
+/// Resize the terminal grid.
+///
+/// Returns `false` if `term` is null or either dimension is zero.
+///
+/// # Safety
+/// `term` must be a valid, mutable pointer to a `Terminal`.
+#[no_mangle]
+pub unsafe extern "C" fn terminal_resize(term: *mut Terminal, cols: u32, rows: u32) -> bool {
+    if term.is_null() || cols == 0 || rows == 0 {
+        return false;
+    }
+    let term_ref = unsafe { &mut *term };
+    term_ref.resize(cols as usize, rows as usize);
+    true
+}
+
+/// Return the visible screen as a JSON `screen_snapshot` message (styled cell
+/// runs, cursor and zones), in the same shape the streaming protocol uses.
+///
+/// The caller owns the returned string and must free it by calling
+/// `terminal_string_free`. Returns null if `term` is null.
+///
+/// # Safety
+/// `term` must be a valid pointer to a `Terminal`.
+#[no_mangle]
+pub unsafe extern "C" fn terminal_snapshot_json(term: *const Terminal) -> *mut c_char {
+    if term.is_null() {
+        return std::ptr::null_mut();
+    }
+    let term_ref = unsafe { &*term };
+    let message = terminal_screen_snapshot(term_ref);
+    into_c_string(serde_json::to_string(&message).unwrap_or_default())
+}
+
+/// Drain pending terminal events as a JSON array of streaming event messages.
+///
+/// Events with no streaming representation (such as dirty regions) are left
+/// out. The caller owns the returned string and must free it by calling
+/// `terminal_string_free`. Returns null if `term` is null.
+///
+/// # Safety
+/// `term` must be a valid, mutable pointer to a `Terminal`.
+#[no_mangle]
+pub unsafe extern "C" fn terminal_poll_events(term: *mut Terminal) -> *mut c_char {
+    if term.is_null() {
+        return std::ptr::null_mut();
+    }
+    let term_ref = unsafe { &mut *term };
+    let messages: Vec<_> = term_ref
+        .poll_events()
+        .into_iter()
+        .filter_map(terminal_event_to_server_message)
+        .collect();
+    into_c_string(serde_json::to_string(&messages).unwrap_or_else(|_| "[]".to_string()))
+}
+
+/// Drain replies the terminal wants to send back to the application (device
+/// attributes, cursor reports, ...) into `buf`.
+///
+/// Returns the total number of pending response bytes. If that is larger than
+/// `cap`, nothing is copied and the responses stay queued, so the caller can
+/// retry with a bigger buffer. Passing a null `buf` with `cap` 0 queries the
+/// size.
+///
+/// # Safety
+/// `term` must be a valid, mutable pointer to a `Terminal`, and `buf` must
+/// point to at least `cap` writable bytes (it may be null when `cap` is 0).
+#[no_mangle]
+pub unsafe extern "C" fn terminal_drain_responses(
+    term: *mut Terminal,
+    buf: *mut u8,
+    cap: usize,
+) -> usize {
+    if term.is_null() {
+        return 0;
+    }
+    let term_ref = unsafe { &mut *term };
+    let pending = term_ref.reply_queue.total_len();
+    if pending == 0 || buf.is_null() || pending > cap {
+        return pending;
+    }
+    let responses = term_ref.drain_responses();
+    unsafe {
+        std::ptr::copy_nonoverlapping(responses.as_ptr(), buf, responses.len());
+    }
+    responses.len()
+}
+
+/// Free a string previously returned by `terminal_snapshot_json` or
+/// `terminal_poll_events`.
+///
+/// # Safety
+/// `s` must be null or a pointer previously returned by one of those
+/// functions, and must not be used after this call.
+#[no_mangle]
+pub unsafe extern "C" fn terminal_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        unsafe {
+            let _ = CString::from_raw(s);
+        }
+    }
+}
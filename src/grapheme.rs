@@ -1,4 +1,7 @@
-/// Utilities for handling grapheme clusters, emoji sequences, and Unicode modifiers
+//! Utilities for handling grapheme clusters, emoji sequences, and Unicode modifiers
+use smallvec::SmallVec;
+use unicode_segmentation::GraphemeCursor;
+
 /// Check if a character is a variation selector (U+FE0E or U+FE0F)
 ///
 /// Variation selectors control whether a character is rendered as text or emoji:
@@ -78,6 +81,32 @@ pub fn is_combining_mark(c: char) -> bool {
     )
 }
 
+/// Check whether appending `c` to `cluster` continues the same extended
+/// grapheme cluster (UAX #29) rather than starting a new one.
+///
+/// This covers every cluster-extension rule, not just the special cases
+/// above: combining and spacing marks, variation selectors, emoji modifiers,
+/// the emoji following a ZWJ, Hangul jamo, prepended concatenation marks and
+/// emoji tag sequences. `cluster` must hold a single cluster.
+pub fn extends_cluster(cluster: impl IntoIterator<Item = char>, c: char) -> bool {
+    // Clusters almost always fit inline; no heap allocation on the write path
+    let mut buf: SmallVec<[u8; 32]> = SmallVec::new();
+    let mut utf8 = [0u8; 4];
+    for ch in cluster {
+        buf.extend_from_slice(ch.encode_utf8(&mut utf8).as_bytes());
+    }
+    let split = buf.len();
+    if split == 0 {
+        return false;
+    }
+    buf.extend_from_slice(c.encode_utf8(&mut utf8).as_bytes());
+    let Ok(text) = std::str::from_utf8(&buf) else {
+        return false;
+    };
+    let mut cursor = GraphemeCursor::new(split, text.len(), true);
+    matches!(cursor.is_boundary(text, 0), Ok(false))
+}
+
 /// Check if a grapheme cluster should be rendered with width 2 (wide character)
 ///
 /// Wide emoji include:
@@ -213,6 +242,26 @@ mod tests {
         assert!(!is_combining_mark('a'));
     }
 
+    #[test]
+    fn test_extends_cluster() {
+        // Combining accent and emoji presentation selector
+        assert!(extends_cluster(['e'], '\u{0301}'));
+        assert!(extends_cluster(['\u{2764}'], '\u{FE0F}'));
+        // Emoji after ZWJ joins; ordinary text after ZWJ does not
+        assert!(extends_cluster(['\u{1F468}', '\u{200D}'], '\u{1F4BB}'));
+        assert!(!extends_cluster(['\u{1F468}', '\u{200D}'], 'a'));
+        // Second regional indicator joins a lone one, a third starts a new flag
+        assert!(extends_cluster(['\u{1F1FA}'], '\u{1F1F8}'));
+        assert!(!extends_cluster(['\u{1F1FA}', '\u{1F1F8}'], '\u{1F1EC}'));
+        // Spacing mark (Devanagari vowel sign AA) and emoji tag characters
+        assert!(extends_cluster(['\u{0915}'], '\u{093E}'));
+        assert!(extends_cluster(['\u{1F3F4}'], '\u{E0067}'));
+        // Plain characters never join
+        assert!(!extends_cluster(['a'], 'b'));
+        assert!(!extends_cluster(['\u{4E00}'], '\u{4E01}'));
+        assert!(!extends_cluster([], '\u{0301}'));
+    }
+
     #[test]
     fn test_wide_grapheme_detection() {
         // ZWJ sequences
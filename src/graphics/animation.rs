@@ -145,7 +145,7 @@ pub struct Animation {
     /// Loops completed
     pub loops_completed: u32,
     /// Time when current frame started (for timing)
-    pub frame_start_time: Option<std::time::Instant>,
+    pub frame_start_time: Option<crate::time::Instant>,
 }
 
 impl Animation {
@@ -204,7 +204,7 @@ impl Animation {
         if self.state != AnimationState::Playing {
             self.state = AnimationState::Playing;
             if self.frame_start_time.is_none() {
-                self.frame_start_time = Some(std::time::Instant::now());
+                self.frame_start_time = Some(crate::time::Instant::now());
             }
         }
     }
@@ -325,7 +325,7 @@ impl Animation {
             frame_delay,
             elapsed.as_millis()
         );
-        self.frame_start_time = Some(std::time::Instant::now());
+        self.frame_start_time = Some(crate::time::Instant::now());
         true
     }
 
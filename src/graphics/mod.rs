@@ -181,6 +181,7 @@ pub enum GraphicProtocol {
     Sixel,
     ITermInline, // OSC 1337
     Kitty,       // APC graphics protocol
+    Regis,       // DCS p vector graphics
 }
 
 impl GraphicProtocol {
@@ -190,6 +191,33 @@ impl GraphicProtocol {
             GraphicProtocol::Sixel => "sixel",
             GraphicProtocol::ITermInline => "iterm",
             GraphicProtocol::Kitty => "kitty",
+            GraphicProtocol::Regis => "regis",
+        }
+    }
+}
+
+/// What printing text into cells covered by an image does to the image
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextOverwriteMode {
+    /// Text and images are layered independently; images are never altered
+    #[default]
+    Overlay,
+    /// Follow each protocol: text replaces the Sixel or iTerm2 image content
+    /// of the cells it is printed into, which become transparent. Kitty
+    /// placements are layered by z-index and left alone.
+    Clip,
+    /// Printing into any cell of a Sixel, iTerm2 or Kitty placement removes
+    /// the whole placement
+    Erase,
+}
+
+impl TextOverwriteMode {
+    /// Get mode name as string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TextOverwriteMode::Overlay => "overlay",
+            TextOverwriteMode::Clip => "clip",
+            TextOverwriteMode::Erase => "erase",
         }
     }
 }
@@ -347,6 +375,76 @@ impl TerminalGraphic {
         (cols, rows)
     }
 
+    /// Cells covered by this placement as (cols, rows): the Kitty `c=`/`r=`
+    /// size when given, otherwise the pixel size divided by the cell size
+    pub fn cell_extent(
+        &self,
+        fallback_cell_width: u32,
+        fallback_cell_height: u32,
+    ) -> (usize, usize) {
+        let (cols, rows) = self.cell_span(fallback_cell_width, fallback_cell_height);
+        (
+            self.placement.columns.map_or(cols, |c| c as usize),
+            self.placement.rows.map_or(rows, |r| r as usize),
+        )
+    }
+
+    /// Whether this placement covers the screen cell at (col, row)
+    ///
+    /// Rows already scrolled off the top (`scroll_offset_rows`) cover nothing.
+    pub fn covers_cell(
+        &self,
+        col: usize,
+        row: usize,
+        fallback_cell_width: u32,
+        fallback_cell_height: u32,
+    ) -> bool {
+        if self.is_virtual {
+            return false;
+        }
+        let (cols, rows) = self.cell_extent(fallback_cell_width, fallback_cell_height);
+        let visible_rows = rows.saturating_sub(self.scroll_offset_rows);
+        let (left, top) = self.position;
+        (left..left + cols).contains(&col) && (top..top + visible_rows).contains(&row)
+    }
+
+    /// Make the pixels under the screen cell at (col, row) transparent
+    pub fn clear_cell(
+        &mut self,
+        col: usize,
+        row: usize,
+        fallback_cell_width: u32,
+        fallback_cell_height: u32,
+    ) {
+        let (cols, rows) = self.cell_extent(fallback_cell_width, fallback_cell_height);
+        // The screen row at `position` shows the first row not scrolled off
+        let (Some(rel_col), Some(rel_row)) = (
+            col.checked_sub(self.position.0),
+            row.checked_sub(self.position.1)
+                .map(|rel| rel + self.scroll_offset_rows),
+        ) else {
+            return;
+        };
+        if rel_col >= cols || rel_row >= rows {
+            return;
+        }
+        // Scale through the cell extent so stretched placements clear the
+        // right part of the source image
+        let x0 = rel_col * self.width / cols;
+        let x1 = ((rel_col + 1) * self.width / cols).min(self.width);
+        let y0 = rel_row * self.height / rows;
+        let y1 = ((rel_row + 1) * self.height / rows).min(self.height);
+        let width = self.width;
+        let pixels = Arc::make_mut(&mut self.pixels);
+        for y in y0..y1 {
+            for x in x0..x1 {
+                if let Some(alpha) = pixels.get_mut((y * width + x) * 4 + 3) {
+                    *alpha = 0;
+                }
+            }
+        }
+    }
+
     /// Get RGBA color at pixel coordinates
     pub fn pixel_at(&self, x: usize, y: usize) -> Option<(u8, u8, u8, u8)> {
         if x >= self.width || y >= self.height {
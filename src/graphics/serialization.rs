@@ -711,6 +711,7 @@ mod tests {
             GraphicProtocol::Sixel,
             GraphicProtocol::ITermInline,
             GraphicProtocol::Kitty,
+            GraphicProtocol::Regis,
         ] {
             let g = TerminalGraphic::new(
                 next_graphic_id(),
@@ -0,0 +1,49 @@
+//! Annotation storage for the terminal grid
+
+use crate::annotation::Annotation;
+use crate::grid::Grid;
+
+impl Grid {
+    /// Get all annotations, in the order they were added
+    pub fn annotations(&self) -> &[Annotation] {
+        &self.annotations
+    }
+
+    /// Add an annotation
+    pub fn push_annotation(&mut self, annotation: Annotation) {
+        self.annotations.push(annotation);
+    }
+
+    /// Remove an annotation by ID, returning it if it existed
+    pub fn remove_annotation(&mut self, id: usize) -> Option<Annotation> {
+        let pos = self.annotations.iter().position(|a| a.id == id)?;
+        Some(self.annotations.remove(pos))
+    }
+
+    /// Clear all annotations
+    pub fn clear_annotations(&mut self) {
+        self.annotations.clear();
+    }
+
+    /// Annotations covering the cell at (`abs_row`, `col`)
+    pub fn annotations_at(&self, abs_row: usize, col: usize) -> Vec<&Annotation> {
+        self.annotations
+            .iter()
+            .filter(|a| a.range.contains(abs_row, col))
+            .collect()
+    }
+
+    /// Shift annotations after `count` rows starting at `abs_row` left the
+    /// buffer; annotations on the removed rows are dropped
+    pub(in crate::grid) fn remove_annotation_rows(&mut self, abs_row: usize, count: usize) {
+        let removed_end = abs_row + count;
+        self.annotations.retain_mut(|a| {
+            if a.range.abs_row >= removed_end {
+                a.range.abs_row -= count;
+                true
+            } else {
+                a.range.abs_row < abs_row
+            }
+        });
+    }
+}
@@ -8,10 +8,10 @@ impl Grid {
         if row >= self.rows || row > scroll_bottom {
             return;
         }
-        let n = n.min(scroll_bottom - row + 1);
         let effective_bottom = scroll_bottom.min(self.rows - 1);
+        let n = n.min(effective_bottom - row + 1);
 
-        for i in (row..=(effective_bottom - n)).rev() {
+        for i in (row..(effective_bottom + 1 - n)).rev() {
             let src_start = i * self.cols;
             let dst_start = (i + n) * self.cols;
             for j in 0..self.cols {
@@ -29,10 +29,10 @@ impl Grid {
         if row >= self.rows || row > scroll_bottom {
             return;
         }
-        let n = n.min(scroll_bottom - row + 1);
         let effective_bottom = scroll_bottom.min(self.rows - 1);
+        let n = n.min(effective_bottom - row + 1);
 
-        for i in row..=(effective_bottom.saturating_sub(n)) {
+        for i in row..(effective_bottom + 1 - n) {
             let src_start = (i + n) * self.cols;
             let dst_start = i * self.cols;
             for j in 0..self.cols {
@@ -82,6 +82,58 @@ impl Grid {
         }
     }
 
+    /// Insert n blank columns at `col` in rows `top..=bottom`, shifting the
+    /// cells up to `right` (inclusive) to the right; cells pushed past
+    /// `right` are lost (DECIC)
+    pub fn insert_columns(
+        &mut self,
+        col: usize,
+        n: usize,
+        top: usize,
+        bottom: usize,
+        right: usize,
+    ) {
+        let right = right.min(self.cols.saturating_sub(1));
+        if col > right || top >= self.rows {
+            return;
+        }
+        let n = n.min(right - col + 1);
+        for row in top..=bottom.min(self.rows - 1) {
+            if let Some(row_cells) = self.row_mut(row) {
+                row_cells[col..=right].rotate_right(n);
+                for cell in &mut row_cells[col..col + n] {
+                    cell.reset();
+                }
+            }
+        }
+    }
+
+    /// Delete n columns at `col` in rows `top..=bottom`, shifting the cells
+    /// up to `right` (inclusive) to the left and blanking the vacated
+    /// columns before `right` (DECDC)
+    pub fn delete_columns(
+        &mut self,
+        col: usize,
+        n: usize,
+        top: usize,
+        bottom: usize,
+        right: usize,
+    ) {
+        let right = right.min(self.cols.saturating_sub(1));
+        if col > right || top >= self.rows {
+            return;
+        }
+        let n = n.min(right - col + 1);
+        for row in top..=bottom.min(self.rows - 1) {
+            if let Some(row_cells) = self.row_mut(row) {
+                row_cells[col..=right].rotate_left(n);
+                for cell in &mut row_cells[right + 1 - n..=right] {
+                    cell.reset();
+                }
+            }
+        }
+    }
+
     /// Alias for insert_chars to satisfy CSI dispatcher
     pub fn insert_characters(&mut self, col: usize, row: usize, n: usize) {
         self.insert_chars(col, row, n);
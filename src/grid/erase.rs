@@ -18,6 +18,8 @@ impl Grid {
             cell.bg = bg;
         }
         self.zones.clear();
+        self.annotations.clear();
+        self.row_times.clear();
     }
 
     /// Clear the entire grid with default background
@@ -100,13 +102,15 @@ impl Grid {
 
     /// Clear the scrollback buffer
     pub fn clear_scrollback(&mut self) {
-        self.scrollback_cells.clear();
+        // Zones in scrollback are evicted; zones on screen move up
+        self.remove_zone_rows(0, self.scrollback_lines);
+        self.scrollback_rows.clear();
         self.scrollback_start = 0;
         self.scrollback_lines = 0;
+        self.scrollback_bytes = 0;
         self.scrollback_wrapped.clear();
-        // Reset floor for zones
+        self.scrollback_low_info.clear();
+        self.scrollback_index.clear();
         self.total_lines_scrolled = 0;
-        // Re-evict zones (effectively clears all zones that started in scrollback)
-        self.evict_zones(0);
     }
 }
@@ -88,6 +88,65 @@ fn push_sgr_style(result: &mut String, fg: &Color, bg: &Color, flags: &crate::ce
     result.push('m');
 }
 
+/// Column after the last cell that has content or non-default styling
+fn last_significant(row_cells: &[Cell]) -> usize {
+    let default_fg = Color::Named(NamedColor::White);
+    let default_bg = Color::Named(NamedColor::Black);
+    let default_flags = crate::cell::CellFlags::default();
+
+    let mut last_significant = 0;
+    for (col, cell) in row_cells.iter().enumerate() {
+        if cell.flags.wide_char_spacer() {
+            continue;
+        }
+        let has_content = cell.c != ' ' || !cell.combining.is_empty();
+        let has_styling =
+            cell.fg != default_fg || cell.bg != default_bg || cell.flags != default_flags;
+        if has_content || has_styling {
+            last_significant = col + 1;
+        }
+    }
+    last_significant
+}
+
+/// Export rows with ANSI styling, joining soft-wrapped rows (`wrapped` set)
+/// with the next one. Every logical line ends with an SGR reset and `\n`.
+pub(crate) fn export_styled_lines(lines: &[(&[Cell], bool)]) -> String {
+    let mut result = String::new();
+    let mut current_fg = Color::Named(NamedColor::White);
+    let mut current_bg = Color::Named(NamedColor::Black);
+    let mut current_flags = crate::cell::CellFlags::default();
+
+    for (i, (cells, wrapped)) in lines.iter().enumerate() {
+        let last_sig = last_significant(cells);
+        for (col, cell) in cells.iter().enumerate() {
+            if cell.flags.wide_char_spacer() {
+                continue;
+            }
+            if col >= last_sig {
+                break;
+            }
+            if cell.fg != current_fg || cell.bg != current_bg || cell.flags != current_flags {
+                push_sgr_style(&mut result, &cell.fg, &cell.bg, &cell.flags);
+                current_fg = cell.fg;
+                current_bg = cell.bg;
+                current_flags = cell.flags;
+            }
+            result.push(cell.c);
+            for &combining in &cell.combining {
+                result.push(combining);
+            }
+        }
+        if !wrapped || i + 1 == lines.len() {
+            result.push_str("\x1b[0m\n");
+            current_fg = Color::Named(NamedColor::White);
+            current_bg = Color::Named(NamedColor::Black);
+            current_flags = crate::cell::CellFlags::default();
+        }
+    }
+    result
+}
+
 impl Grid {
     /// Export the entire buffer (scrollback + visible) as plain text
     pub fn export_text_buffer(&self) -> String {
@@ -155,23 +214,7 @@ impl Grid {
 
     /// Helper to find the last significant column in a row (non-space or styled)
     fn find_last_significant(&self, row_cells: &[Cell]) -> usize {
-        let default_fg = Color::Named(NamedColor::White);
-        let default_bg = Color::Named(NamedColor::Black);
-        let default_flags = crate::cell::CellFlags::default();
-
-        let mut last_significant = 0;
-        for (col, cell) in row_cells.iter().enumerate() {
-            if cell.flags.wide_char_spacer() {
-                continue;
-            }
-            let has_content = cell.c != ' ' || !cell.combining.is_empty();
-            let has_styling =
-                cell.fg != default_fg || cell.bg != default_bg || cell.flags != default_flags;
-            if has_content || has_styling {
-                last_significant = col + 1;
-            }
-        }
-        last_significant
+        last_significant(row_cells)
     }
 
     /// Export the entire buffer with ANSI styling
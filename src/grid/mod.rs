@@ -1,18 +1,36 @@
 //! Terminal grid implementation
 //!
 //! Provides a 2D grid of cells with scrollback support, reflow capability,
-//! and semantic zone tracking.
+//! and semantic zone and annotation tracking.
 
+use std::collections::VecDeque;
+use std::sync::atomic::AtomicUsize;
+use std::sync::Arc;
+
+use crate::annotation::Annotation;
 use crate::cell::Cell;
 use crate::zone::Zone;
 
+mod annotation;
 mod edit;
 mod erase;
 mod export;
 mod rect;
+mod row;
+mod row_time;
 mod scroll;
+mod search_index;
+mod trim;
 mod zone;
 
+pub(crate) use export::export_styled_lines;
+pub use rect::AttributeChangeExtent;
+pub use row::{ScrollbackCompressionStats, ScrollbackRow};
+pub use search_index::{query_trigram_mask, IndexedLine};
+pub use trim::{
+    AdaptiveTrimConfig, IdleTrim, ScrollbackPolicy, ScrollbackTrimPolicy, ScrollbackTrimStats,
+};
+
 /// A 2D grid of terminal cells
 #[derive(Debug, Clone)]
 pub struct Grid {
@@ -22,8 +40,8 @@ pub struct Grid {
     pub(in crate::grid) rows: usize,
     /// The actual grid data (row-major order)
     pub(in crate::grid) cells: Vec<Cell>,
-    /// Scrollback buffer (flat Vec, row-major order like main grid)
-    pub(in crate::grid) scrollback_cells: Vec<Cell>,
+    /// Scrollback buffer, one shared row per line (circular once full)
+    pub(in crate::grid) scrollback_rows: Vec<ScrollbackRow>,
     /// Index of oldest line in circular scrollback buffer
     pub(in crate::grid) scrollback_start: usize,
     /// Number of lines currently in scrollback
@@ -38,8 +56,38 @@ pub struct Grid {
     pub(in crate::grid) zones: Vec<Zone>,
     /// Zones that were evicted from scrollback
     pub(in crate::grid) evicted_zones: Vec<Zone>,
+    /// Cell annotations, using the same absolute rows as zones
+    pub(in crate::grid) annotations: Vec<Annotation>,
+    /// Last write time per absolute row (Unix ms, 0 = never written)
+    pub(in crate::grid) row_times: VecDeque<u64>,
+    /// Time stamped onto unwritten rows as they enter scrollback (Unix ms,
+    /// 0 = don't stamp)
+    pub(in crate::grid) row_clock: u64,
+    /// Row removals `(abs_row, count)` not yet applied to row numbers kept
+    /// outside the grid
+    pub(in crate::grid) row_removals: Vec<(usize, usize)>,
     /// Total number of lines that have ever been scrolled into scrollback.
     pub(in crate::grid) total_lines_scrolled: usize,
+    /// How lines are chosen for eviction once scrollback is full
+    pub(in crate::grid) trim_policy: ScrollbackTrimPolicy,
+    /// Eviction counters
+    pub(in crate::grid) trim_stats: ScrollbackTrimStats,
+    /// Memory, age and idle limits on scrollback
+    pub(in crate::grid) scrollback_policy: ScrollbackPolicy,
+    /// Running total of the estimated memory held by scrollback lines
+    pub(in crate::grid) scrollback_bytes: usize,
+    /// Low-information classification per physical scrollback slot
+    /// (adaptive trim policy only; missing entries mean "keep")
+    pub(in crate::grid) scrollback_low_info: Vec<Option<trim::LowInfo>>,
+    /// Number of newest scrollback lines kept uncompressed (`None` disables
+    /// compression)
+    pub(in crate::grid) scrollback_compression: Option<usize>,
+    /// Compressed rows inflated since the last compaction
+    pub(in crate::grid) scrollback_inflated: Arc<AtomicUsize>,
+    /// Whether scrollback lines are indexed for search
+    pub(in crate::grid) scrollback_index_enabled: bool,
+    /// Search index entry per physical scrollback slot
+    pub(in crate::grid) scrollback_index: Vec<IndexedLine>,
 }
 
 impl Grid {
@@ -50,7 +98,7 @@ impl Grid {
             cols,
             rows,
             cells,
-            scrollback_cells: Vec::new(),
+            scrollback_rows: Vec::new(),
             scrollback_start: 0,
             scrollback_lines: 0,
             max_scrollback,
@@ -58,7 +106,20 @@ impl Grid {
             scrollback_wrapped: Vec::new(),
             zones: Vec::new(),
             evicted_zones: Vec::new(),
+            annotations: Vec::new(),
+            row_times: VecDeque::new(),
+            row_clock: 0,
+            row_removals: Vec::new(),
             total_lines_scrolled: 0,
+            trim_policy: ScrollbackTrimPolicy::Fifo,
+            trim_stats: ScrollbackTrimStats::default(),
+            scrollback_policy: ScrollbackPolicy::default(),
+            scrollback_bytes: 0,
+            scrollback_low_info: Vec::new(),
+            scrollback_compression: None,
+            scrollback_inflated: Arc::new(AtomicUsize::new(0)),
+            scrollback_index_enabled: false,
+            scrollback_index: Vec::new(),
         }
     }
 
@@ -164,7 +225,7 @@ impl Grid {
         }
     }
 
-    /// Physical index into `scrollback_cells` for a logical scrollback line
+    /// Physical index into `scrollback_rows` for a logical scrollback line
     /// (0 = oldest). Centralized circular-buffer math (ARC-026).
     #[inline]
     fn scrollback_physical_index(&self, logical: usize) -> usize {
@@ -181,9 +242,7 @@ impl Grid {
     pub fn scrollback_line(&self, index: usize) -> Option<&[Cell]> {
         if index < self.scrollback_lines {
             let physical_index = self.scrollback_physical_index(index);
-            let start = physical_index * self.cols;
-            let end = start + self.cols;
-            Some(&self.scrollback_cells[start..end])
+            self.scrollback_rows.get(physical_index).map(|row| &row[..])
         } else {
             None
         }
@@ -203,11 +262,14 @@ impl Grid {
     }
 
     /// Capture a snapshot of this grid's entire state.
+    ///
+    /// Scrollback rows are shared with the snapshot rather than copied, so the
+    /// cost is one pointer per scrollback line.
     #[must_use]
     pub fn capture_snapshot(&self) -> crate::terminal::replay_snapshot::GridSnapshot {
         crate::terminal::replay_snapshot::GridSnapshot {
             cells: self.cells.clone(),
-            scrollback_cells: self.scrollback_cells.clone(),
+            scrollback_rows: self.scrollback_rows.clone(),
             scrollback_start: self.scrollback_start,
             scrollback_lines: self.scrollback_lines,
             max_scrollback: self.max_scrollback,
@@ -216,6 +278,8 @@ impl Grid {
             wrapped: self.wrapped.clone(),
             scrollback_wrapped: self.scrollback_wrapped.clone(),
             zones: self.zones.clone(),
+            annotations: self.annotations.clone(),
+            row_times: self.row_times.clone(),
             total_lines_scrolled: self.total_lines_scrolled,
         }
     }
@@ -223,7 +287,7 @@ impl Grid {
     /// Restore this grid's state from a previously captured snapshot.
     pub fn restore_from_snapshot(&mut self, snap: &crate::terminal::replay_snapshot::GridSnapshot) {
         self.cells = snap.cells.clone();
-        self.scrollback_cells = snap.scrollback_cells.clone();
+        self.scrollback_rows = snap.scrollback_rows.clone();
         self.scrollback_start = snap.scrollback_start;
         self.scrollback_lines = snap.scrollback_lines;
         self.max_scrollback = snap.max_scrollback;
@@ -232,8 +296,14 @@ impl Grid {
         self.wrapped = snap.wrapped.clone();
         self.scrollback_wrapped = snap.scrollback_wrapped.clone();
         self.zones = snap.zones.clone();
+        self.annotations = snap.annotations.clone();
+        self.row_times = snap.row_times.clone();
         self.evicted_zones.clear();
+        self.row_removals.clear();
         self.total_lines_scrolled = snap.total_lines_scrolled;
+        self.scrollback_low_info.clear();
+        self.compact_scrollback();
+        self.rebuild_scrollback_index();
     }
 }
 
@@ -3,6 +3,18 @@
 use crate::cell::Cell;
 use crate::grid::Grid;
 
+/// Which cells DECCARA and DECRARA change (DECSACE)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AttributeChangeExtent {
+    /// Every position from the start to the end position in reading order,
+    /// wrapping at line ends (DECSACE 0 or 1)
+    Stream,
+    /// Only the columns between the left and right edges of each row
+    /// (DECSACE 2)
+    #[default]
+    Rectangle,
+}
+
 impl Grid {
     /// Fill a rectangular area with a character
     pub fn fill_rectangle(
@@ -138,83 +150,168 @@ impl Grid {
         right: usize,
         attributes: &[u16],
     ) {
-        if top >= self.rows || left >= self.cols {
-            return;
-        }
-        let bottom = bottom.min(self.rows - 1);
-        let right = right.min(self.cols - 1);
-        if top > bottom || left > right {
-            return;
-        }
+        self.change_attributes_in_area(
+            top,
+            left,
+            bottom,
+            right,
+            AttributeChangeExtent::Rectangle,
+            attributes,
+        );
+    }
 
-        for row in top..=bottom {
-            for col in left..=right {
-                if let Some(cell) = self.get_mut(col, row) {
-                    for &attr in attributes {
-                        match attr {
-                            0 => {
-                                cell.flags.set_bold(false);
-                                cell.flags.set_dim(false);
-                                cell.flags.set_italic(false);
-                                cell.flags.set_underline(false);
-                                cell.flags.set_blink(false);
-                                cell.flags.set_reverse(false);
-                                cell.flags.set_hidden(false);
-                                cell.flags.set_strikethrough(false);
-                            }
-                            1 => cell.flags.set_bold(true),
-                            2 => cell.flags.set_dim(true),
-                            3 => cell.flags.set_italic(true),
-                            4 => cell.flags.set_underline(true),
-                            5 => cell.flags.set_blink(true),
-                            7 => cell.flags.set_reverse(true),
-                            8 => cell.flags.set_hidden(true),
-                            9 => cell.flags.set_strikethrough(true),
-                            _ => {}
-                        }
+    /// Reverse attributes in rectangular area
+    pub fn reverse_attributes_in_rectangle(
+        &mut self,
+        top: usize,
+        left: usize,
+        bottom: usize,
+        right: usize,
+        attributes: &[u16],
+    ) {
+        self.reverse_attributes_in_area(
+            top,
+            left,
+            bottom,
+            right,
+            AttributeChangeExtent::Rectangle,
+            attributes,
+        );
+    }
+
+    /// Set or clear SGR attributes (DECCARA) on the cells between
+    /// `(top, left)` and `(bottom, right)`, selected by `extent`
+    ///
+    /// Supported attributes: 0 (all off), 1 bold, 2 faint, 3 italic,
+    /// 4 underline, 5 blink, 7 reverse, 8 hidden, 9 crossed-out, and
+    /// 22-29 to turn them off again. No attributes means 0.
+    pub fn change_attributes_in_area(
+        &mut self,
+        top: usize,
+        left: usize,
+        bottom: usize,
+        right: usize,
+        extent: AttributeChangeExtent,
+        attributes: &[u16],
+    ) {
+        let attributes = if attributes.is_empty() {
+            &[0]
+        } else {
+            attributes
+        };
+        self.for_each_cell_in_area(top, left, bottom, right, extent, |cell| {
+            let flags = &mut cell.flags;
+            for &attr in attributes {
+                match attr {
+                    0 => {
+                        flags.set_bold(false);
+                        flags.set_dim(false);
+                        flags.set_italic(false);
+                        flags.set_underline(false);
+                        flags.set_blink(false);
+                        flags.set_reverse(false);
+                        flags.set_hidden(false);
+                        flags.set_strikethrough(false);
+                    }
+                    1 => flags.set_bold(true),
+                    2 => flags.set_dim(true),
+                    3 => flags.set_italic(true),
+                    4 => flags.set_underline(true),
+                    5 => flags.set_blink(true),
+                    7 => flags.set_reverse(true),
+                    8 => flags.set_hidden(true),
+                    9 => flags.set_strikethrough(true),
+                    22 => {
+                        flags.set_bold(false);
+                        flags.set_dim(false);
                     }
+                    23 => flags.set_italic(false),
+                    24 => flags.set_underline(false),
+                    25 => flags.set_blink(false),
+                    27 => flags.set_reverse(false),
+                    28 => flags.set_hidden(false),
+                    29 => flags.set_strikethrough(false),
+                    _ => {}
                 }
             }
-        }
+        });
     }
 
-    /// Reverse attributes in rectangular area
-    pub fn reverse_attributes_in_rectangle(
+    /// Toggle SGR attributes (DECRARA) on the cells between `(top, left)`
+    /// and `(bottom, right)`, selected by `extent`
+    ///
+    /// Supported attributes: 0 (bold, underline, blink and reverse),
+    /// 1 bold, 4 underline, 5 blink, 7 reverse, 8 hidden. No attributes
+    /// means 0.
+    pub fn reverse_attributes_in_area(
         &mut self,
         top: usize,
         left: usize,
         bottom: usize,
         right: usize,
+        extent: AttributeChangeExtent,
         attributes: &[u16],
     ) {
-        if top >= self.rows || left >= self.cols {
+        let attributes = if attributes.is_empty() {
+            &[0]
+        } else {
+            attributes
+        };
+        self.for_each_cell_in_area(top, left, bottom, right, extent, |cell| {
+            let flags = &mut cell.flags;
+            for &attr in attributes {
+                match attr {
+                    0 => {
+                        flags.set_bold(!flags.bold());
+                        flags.set_underline(!flags.underline());
+                        flags.set_blink(!flags.blink());
+                        flags.set_reverse(!flags.reverse());
+                    }
+                    1 => flags.set_bold(!flags.bold()),
+                    4 => flags.set_underline(!flags.underline()),
+                    5 => flags.set_blink(!flags.blink()),
+                    7 => flags.set_reverse(!flags.reverse()),
+                    8 => flags.set_hidden(!flags.hidden()),
+                    _ => {}
+                }
+            }
+        });
+    }
+
+    /// Apply `f` to every screen cell of the area, clamped to the grid
+    ///
+    /// In rectangle mode the area is the columns `left..=right` of each row;
+    /// in stream mode it runs in reading order from `(top, left)` to
+    /// `(bottom, right)`, covering whole rows in between.
+    fn for_each_cell_in_area(
+        &mut self,
+        top: usize,
+        left: usize,
+        bottom: usize,
+        right: usize,
+        extent: AttributeChangeExtent,
+        mut f: impl FnMut(&mut Cell),
+    ) {
+        if top >= self.rows || self.cols == 0 {
             return;
         }
         let bottom = bottom.min(self.rows - 1);
         let right = right.min(self.cols - 1);
-        if top > bottom || left > right {
+        if top > bottom {
             return;
         }
 
         for row in top..=bottom {
-            for col in left..=right {
+            let (start, end) = match extent {
+                AttributeChangeExtent::Rectangle => (left, right),
+                AttributeChangeExtent::Stream => (
+                    if row == top { left } else { 0 },
+                    if row == bottom { right } else { self.cols - 1 },
+                ),
+            };
+            for col in start..=end.min(self.cols - 1) {
                 if let Some(cell) = self.get_mut(col, row) {
-                    for &attr in attributes {
-                        match attr {
-                            0 => {
-                                cell.flags.set_bold(!cell.flags.bold());
-                                cell.flags.set_underline(!cell.flags.underline());
-                                cell.flags.set_blink(!cell.flags.blink());
-                                cell.flags.set_reverse(!cell.flags.reverse());
-                            }
-                            1 => cell.flags.set_bold(!cell.flags.bold()),
-                            4 => cell.flags.set_underline(!cell.flags.underline()),
-                            5 => cell.flags.set_blink(!cell.flags.blink()),
-                            7 => cell.flags.set_reverse(!cell.flags.reverse()),
-                            8 => cell.flags.set_hidden(!cell.flags.hidden()),
-                            _ => {}
-                        }
-                    }
+                    f(cell);
                 }
             }
         }
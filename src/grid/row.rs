@@ -0,0 +1,414 @@
+//! Scrollback row storage and optional compression of older rows
+
+use std::io::{Read, Write};
+use std::num::NonZeroU32;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use smallvec::SmallVec;
+
+use crate::cell::{Cell, CellFlags, UnderlineStyle};
+use crate::color::{Color, NamedColor};
+use crate::grid::trim::row_bytes;
+use crate::grid::Grid;
+
+/// Number of compressed rows that may hold an inflated copy before the grid
+/// drops them all on its next scroll
+const INFLATED_ROW_LIMIT: usize = 1024;
+
+/// One line of scrollback.
+///
+/// Rows are reference counted so snapshots and grid clones share them; a row
+/// still held elsewhere is copied before the grid overwrites it. Rows older
+/// than the grid's compression threshold are stored deflated and inflated on
+/// first access through [`Deref`]; the inflated copy is kept until the grid
+/// compacts its scrollback.
+#[derive(Debug, Clone)]
+pub struct ScrollbackRow(Repr);
+
+#[derive(Debug, Clone)]
+enum Repr {
+    Plain(Arc<[Cell]>),
+    Compressed(CompressedRow),
+}
+
+#[derive(Debug, Clone)]
+struct CompressedRow {
+    /// Deflated cell encoding
+    data: Arc<[u8]>,
+    /// Number of cells in the row
+    len: usize,
+    /// Cells inflated on first access
+    inflated: OnceLock<Arc<[Cell]>>,
+    /// Inflation counter shared with the owning grid
+    inflated_count: Arc<AtomicUsize>,
+}
+
+impl CompressedRow {
+    fn cells(&self) -> &Arc<[Cell]> {
+        self.inflated.get_or_init(|| {
+            self.inflated_count.fetch_add(1, Ordering::Relaxed);
+            decode_row(&self.data, self.len).into()
+        })
+    }
+}
+
+impl ScrollbackRow {
+    /// Number of cells in the row (never inflates a compressed row)
+    pub fn len(&self) -> usize {
+        match &self.0 {
+            Repr::Plain(cells) => cells.len(),
+            Repr::Compressed(row) => row.len,
+        }
+    }
+
+    /// Whether the row has no cells
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether the row is stored compressed
+    pub fn is_compressed(&self) -> bool {
+        matches!(self.0, Repr::Compressed(_))
+    }
+
+    /// Size of the compressed encoding, or `None` for an uncompressed row
+    pub fn compressed_size(&self) -> Option<usize> {
+        match &self.0 {
+            Repr::Plain(_) => None,
+            Repr::Compressed(row) => Some(row.data.len()),
+        }
+    }
+
+    /// Whether two rows share the same storage
+    pub fn ptr_eq(a: &Self, b: &Self) -> bool {
+        match (&a.0, &b.0) {
+            (Repr::Plain(a), Repr::Plain(b)) => Arc::ptr_eq(a, b),
+            (Repr::Compressed(a), Repr::Compressed(b)) => Arc::ptr_eq(&a.data, &b.data),
+            _ => false,
+        }
+    }
+
+    /// Mutable cells of an uncompressed row nothing else shares
+    pub(in crate::grid) fn cells_mut(&mut self) -> Option<&mut [Cell]> {
+        match &mut self.0 {
+            Repr::Plain(cells) => Arc::get_mut(cells),
+            Repr::Compressed(_) => None,
+        }
+    }
+
+    /// Store the row compressed, or drop the inflated copy of a row that
+    /// already is
+    pub(in crate::grid) fn compress(&mut self, inflated_count: &Arc<AtomicUsize>) {
+        match &mut self.0 {
+            Repr::Plain(cells) => {
+                self.0 = Repr::Compressed(CompressedRow {
+                    data: encode_row(cells).into(),
+                    len: cells.len(),
+                    inflated: OnceLock::new(),
+                    inflated_count: Arc::clone(inflated_count),
+                });
+            }
+            Repr::Compressed(row) => {
+                row.inflated.take();
+            }
+        }
+    }
+
+    /// Store the row uncompressed
+    pub(in crate::grid) fn decompress(&mut self) {
+        if let Repr::Compressed(row) = &self.0 {
+            self.0 = Repr::Plain(Arc::clone(row.cells()));
+        }
+    }
+}
+
+impl Deref for ScrollbackRow {
+    type Target = [Cell];
+
+    fn deref(&self) -> &[Cell] {
+        match &self.0 {
+            Repr::Plain(cells) => cells,
+            Repr::Compressed(row) => row.cells(),
+        }
+    }
+}
+
+impl From<&[Cell]> for ScrollbackRow {
+    fn from(cells: &[Cell]) -> Self {
+        Self(Repr::Plain(cells.into()))
+    }
+}
+
+impl From<Vec<Cell>> for ScrollbackRow {
+    fn from(cells: Vec<Cell>) -> Self {
+        Self(Repr::Plain(cells.into()))
+    }
+}
+
+impl FromIterator<Cell> for ScrollbackRow {
+    fn from_iter<I: IntoIterator<Item = Cell>>(iter: I) -> Self {
+        Self(Repr::Plain(iter.into_iter().collect()))
+    }
+}
+
+/// Scrollback compression counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScrollbackCompressionStats {
+    /// Lines currently stored compressed
+    pub compressed_lines: usize,
+    /// Bytes held by the compressed encodings
+    pub compressed_bytes: usize,
+    /// Bytes the compressed lines would occupy as cells
+    pub uncompressed_bytes: usize,
+}
+
+impl Grid {
+    /// Compress scrollback lines older than the newest `after_lines` lines,
+    /// or store every line uncompressed with `None`
+    ///
+    /// Compressed lines are inflated transparently when read; up to 1024
+    /// inflated copies are kept before the grid drops them again.
+    pub fn set_scrollback_compression(&mut self, after_lines: Option<usize>) {
+        self.scrollback_compression = after_lines;
+        self.compact_scrollback();
+    }
+
+    /// Get the scrollback compression threshold
+    pub fn scrollback_compression(&self) -> Option<usize> {
+        self.scrollback_compression
+    }
+
+    /// Bring every scrollback line in line with the compression threshold and
+    /// drop inflated copies of compressed lines
+    pub fn compact_scrollback(&mut self) {
+        let aged = self
+            .scrollback_compression
+            .map_or(0, |keep| self.scrollback_lines.saturating_sub(keep));
+        for logical in 0..self.scrollback_lines {
+            let physical = self.scrollback_physical_index(logical);
+            let row = &mut self.scrollback_rows[physical];
+            if logical < aged {
+                row.compress(&self.scrollback_inflated);
+            } else {
+                row.decompress();
+            }
+        }
+        self.scrollback_inflated.store(0, Ordering::Relaxed);
+        self.recount_scrollback_bytes();
+    }
+
+    /// Get scrollback compression counters
+    pub fn scrollback_compression_stats(&self) -> ScrollbackCompressionStats {
+        let mut stats = ScrollbackCompressionStats::default();
+        for row in &self.scrollback_rows {
+            if let Some(size) = row.compressed_size() {
+                stats.compressed_lines += 1;
+                stats.compressed_bytes += size;
+                stats.uncompressed_bytes += row.len() * std::mem::size_of::<Cell>();
+            }
+        }
+        stats
+    }
+
+    /// Compress the line that just aged past the compression threshold, and
+    /// compact once too many compressed lines hold inflated copies
+    pub(in crate::grid) fn compress_aged_row(&mut self) {
+        if self.scrollback_inflated.load(Ordering::Relaxed) >= INFLATED_ROW_LIMIT {
+            self.compact_scrollback();
+            return;
+        }
+        let Some(keep) = self.scrollback_compression else {
+            return;
+        };
+        if self.scrollback_lines > keep {
+            let physical = self.scrollback_physical_index(self.scrollback_lines - 1 - keep);
+            let row = &mut self.scrollback_rows[physical];
+            self.scrollback_bytes -= row_bytes(row);
+            row.compress(&self.scrollback_inflated);
+            self.scrollback_bytes += row_bytes(row);
+        }
+    }
+}
+
+fn encode_color(raw: &mut Vec<u8>, color: Option<Color>) {
+    raw.extend_from_slice(&match color {
+        None => [0, 0, 0, 0],
+        Some(Color::Named(named)) => [1, named as u8, 0, 0],
+        Some(Color::Indexed(index)) => [2, index, 0, 0],
+        Some(Color::Rgb(r, g, b)) => [3, r, g, b],
+    });
+}
+
+fn underline_style_code(style: UnderlineStyle) -> u8 {
+    match style {
+        UnderlineStyle::None => 0,
+        UnderlineStyle::Straight => 1,
+        UnderlineStyle::Double => 2,
+        UnderlineStyle::Curly => 3,
+        UnderlineStyle::Dotted => 4,
+        UnderlineStyle::Dashed => 5,
+    }
+}
+
+fn underline_style_from_code(code: u8) -> Option<UnderlineStyle> {
+    Some(match code {
+        0 => UnderlineStyle::None,
+        1 => UnderlineStyle::Straight,
+        2 => UnderlineStyle::Double,
+        3 => UnderlineStyle::Curly,
+        4 => UnderlineStyle::Dotted,
+        5 => UnderlineStyle::Dashed,
+        _ => return None,
+    })
+}
+
+/// Serialize and deflate a row of cells
+fn encode_row(cells: &[Cell]) -> Vec<u8> {
+    let mut raw = Vec::with_capacity(cells.len() * 24);
+    for cell in cells {
+        raw.extend_from_slice(&u32::from(cell.c).to_le_bytes());
+        let combining = &cell.combining[..cell.combining.len().min(u8::MAX as usize)];
+        raw.push(combining.len() as u8);
+        for &mark in combining {
+            raw.extend_from_slice(&u32::from(mark).to_le_bytes());
+        }
+        encode_color(&mut raw, Some(cell.fg));
+        encode_color(&mut raw, Some(cell.bg));
+        encode_color(&mut raw, cell.underline_color);
+        raw.extend_from_slice(&cell.flags.to_bitflags().to_le_bytes());
+        raw.push(underline_style_code(cell.flags.underline_style));
+        let link = cell.flags.hyperlink_id.map_or(0, NonZeroU32::get);
+        raw.extend_from_slice(&link.to_le_bytes());
+        raw.push(cell.width);
+    }
+
+    // Writing into a Vec cannot fail
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::fast());
+    let _ = encoder.write_all(&raw);
+    encoder.finish().unwrap_or_default()
+}
+
+/// Inflate a row written by [`encode_row`]; a corrupt row comes back blank
+fn decode_row(data: &[u8], len: usize) -> Vec<Cell> {
+    let mut raw = Vec::new();
+    DeflateDecoder::new(data)
+        .read_to_end(&mut raw)
+        .ok()
+        .and_then(|_| decode_cells(&raw, len))
+        .unwrap_or_else(|| vec![Cell::default(); len])
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn bytes<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let (head, rest) = self.0.split_first_chunk::<N>()?;
+        self.0 = rest;
+        Some(*head)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.bytes::<1>().map(|[b]| b)
+    }
+
+    fn char(&mut self) -> Option<char> {
+        char::from_u32(u32::from_le_bytes(self.bytes()?))
+    }
+
+    fn color(&mut self) -> Option<Option<Color>> {
+        let [tag, a, b, c] = self.bytes()?;
+        Some(match tag {
+            0 => None,
+            1 => Some(Color::Named(NamedColor::from_u8(a))),
+            2 => Some(Color::Indexed(a)),
+            3 => Some(Color::Rgb(a, b, c)),
+            _ => return None,
+        })
+    }
+}
+
+fn decode_cells(raw: &[u8], len: usize) -> Option<Vec<Cell>> {
+    let mut reader = Reader(raw);
+    let mut cells = Vec::with_capacity(len);
+    for _ in 0..len {
+        let c = reader.char()?;
+        let mut combining = SmallVec::new();
+        for _ in 0..reader.u8()? {
+            combining.push(reader.char()?);
+        }
+        let fg = reader.color()??;
+        let bg = reader.color()??;
+        let underline_color = reader.color()?;
+        let mut flags = CellFlags::from_bitflags(u16::from_le_bytes(reader.bytes()?));
+        flags.underline_style = underline_style_from_code(reader.u8()?)?;
+        flags.hyperlink_id = NonZeroU32::new(u32::from_le_bytes(reader.bytes()?));
+        let width = reader.u8()?;
+        cells.push(Cell {
+            c,
+            combining,
+            fg,
+            bg,
+            underline_color,
+            flags,
+            width,
+        });
+    }
+    reader.0.is_empty().then_some(cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_row_encoding_round_trips_every_field() {
+        let mut cells = vec![Cell::default(); 6];
+        cells[0] = Cell::with_colors('A', Color::Rgb(1, 2, 3), Color::Indexed(200));
+        cells[1].c = 'e';
+        cells[1].combining.push('\u{301}');
+        cells[2] = Cell::new('中');
+        cells[2].flags.set_wide_char(true);
+        cells[3].flags.set_wide_char_spacer(true);
+        cells[4].flags.set_bold(true);
+        cells[4].flags.underline_style = UnderlineStyle::Curly;
+        cells[4].flags.hyperlink_id = NonZeroU32::new(7);
+        cells[4].underline_color = Some(Color::Named(NamedColor::BrightRed));
+
+        assert_eq!(decode_row(&encode_row(&cells), cells.len()), cells);
+    }
+
+    #[test]
+    fn test_compressed_row_inflates_once_until_compacted() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let cells: Vec<Cell> = "hello".chars().map(Cell::new).collect();
+        let mut row = ScrollbackRow::from(cells.clone());
+
+        row.compress(&count);
+        assert!(row.is_compressed());
+        assert_eq!(row.len(), 5);
+        assert_eq!(count.load(Ordering::Relaxed), 0);
+
+        assert_eq!(&*row, &cells[..]);
+        assert_eq!(&*row, &cells[..]);
+        assert_eq!(count.load(Ordering::Relaxed), 1);
+
+        // Compressing again only drops the inflated copy
+        row.compress(&count);
+        assert_eq!(&*row, &cells[..]);
+        assert_eq!(count.load(Ordering::Relaxed), 2);
+
+        row.decompress();
+        assert!(!row.is_compressed());
+        assert_eq!(&*row, &cells[..]);
+    }
+
+    #[test]
+    fn test_corrupt_row_decodes_blank() {
+        assert_eq!(decode_row(b"not deflate", 3), vec![Cell::default(); 3]);
+    }
+}
@@ -0,0 +1,57 @@
+//! Per-row write timestamps for the terminal grid
+//!
+//! Timestamps use the same absolute row numbering as zones, so they move up
+//! when scrollback lines are evicted and disappear with their rows. Rows
+//! that were never written (blank lines) are stamped with the row clock as
+//! they enter scrollback, so every scrollback line carries a time.
+
+use crate::grid::Grid;
+
+impl Grid {
+    /// Record that absolute row `abs_row` was written at `timestamp` (Unix
+    /// epoch milliseconds)
+    pub fn stamp_row(&mut self, abs_row: usize, timestamp: u64) {
+        if abs_row >= self.row_times.len() {
+            self.row_times.resize(abs_row + 1, 0);
+        }
+        self.row_times[abs_row] = timestamp;
+    }
+
+    /// When absolute row `abs_row` was last written (Unix epoch
+    /// milliseconds), or `None` if it never was
+    pub fn row_timestamp(&self, abs_row: usize) -> Option<u64> {
+        self.row_times.get(abs_row).copied().filter(|&t| t != 0)
+    }
+
+    /// First absolute row with a timestamp at or after `timestamp` (Unix
+    /// epoch milliseconds)
+    pub fn find_row_at_time(&self, timestamp: u64) -> Option<usize> {
+        let total = self.scrollback_len() + self.rows;
+        self.row_times
+            .iter()
+            .take(total)
+            .position(|&t| t != 0 && t >= timestamp)
+    }
+
+    /// Set the time stamped onto unwritten rows as they scroll into
+    /// scrollback (Unix epoch milliseconds, 0 disables stamping)
+    pub fn set_row_clock(&mut self, timestamp: u64) {
+        self.row_clock = timestamp;
+    }
+
+    /// Stamp absolute row `abs_row` with the row clock unless it already has
+    /// a timestamp
+    pub(in crate::grid) fn stamp_row_on_scroll(&mut self, abs_row: usize) {
+        if self.row_clock != 0 && self.row_timestamp(abs_row).is_none() {
+            self.stamp_row(abs_row, self.row_clock);
+        }
+    }
+
+    /// Drop the timestamps of `count` rows starting at `abs_row`
+    pub(in crate::grid) fn remove_row_times(&mut self, abs_row: usize, count: usize) {
+        let len = self.row_times.len();
+        if abs_row < len {
+            self.row_times.drain(abs_row..(abs_row + count).min(len));
+        }
+    }
+}
@@ -1,7 +1,30 @@
 //! Scrolling and reflow logic for the terminal grid
 
+use std::collections::VecDeque;
+use std::iter;
+
 use crate::cell::Cell;
-use crate::grid::Grid;
+use crate::grid::trim::row_bytes;
+use crate::grid::{Grid, ScrollbackRow, ScrollbackTrimPolicy};
+
+/// Overwrite an evicted scrollback slot with `src`, reusing its allocation
+/// unless the old row is compressed or a snapshot still holds it.
+fn overwrite_scrollback_row(slot: &mut ScrollbackRow, src: &[Cell]) {
+    match slot.cells_mut() {
+        Some(row) if row.len() == src.len() => row.clone_from_slice(src),
+        _ => *slot = ScrollbackRow::from(src),
+    }
+}
+
+/// Build a `width`-wide scrollback row from `cells`, padding with blanks.
+fn padded_row(cells: &[Cell], width: usize) -> ScrollbackRow {
+    cells
+        .iter()
+        .cloned()
+        .chain(iter::repeat(Cell::default()))
+        .take(width)
+        .collect()
+}
 
 impl Grid {
     fn push_rows_to_scrollback(&mut self, start_row: usize, count: usize) {
@@ -12,34 +35,63 @@ impl Grid {
         let available = self.rows - start_row;
         let count = count.min(available);
 
-        self.total_lines_scrolled += count;
-        if self.scrollback_lines >= self.max_scrollback {
-            let floor = self
-                .total_lines_scrolled
-                .saturating_sub(self.max_scrollback);
-            self.evict_zones(floor);
-        }
+        let adaptive = match self.trim_policy {
+            ScrollbackTrimPolicy::Adaptive(config) => Some(config),
+            ScrollbackTrimPolicy::Fifo => None,
+        };
 
         for i in 0..count {
             let row = start_row + i;
             let src_start = row * self.cols;
             let src_end = src_start + self.cols;
             let is_wrapped = self.wrapped.get(row).copied().unwrap_or(false);
+            let class = adaptive
+                .as_ref()
+                .and_then(|config| self.classify_incoming_row(config, row));
+            // Each earlier row of this batch grew scrollback or evicted a
+            // line, so this row is now at `scrollback_lines + start_row`
+            self.stamp_row_on_scroll(self.scrollback_lines + start_row);
 
             if self.scrollback_lines < self.max_scrollback {
-                self.scrollback_cells
-                    .extend_from_slice(&self.cells[src_start..src_end]);
+                let line = ScrollbackRow::from(&self.cells[src_start..src_end]);
+                self.scrollback_bytes += row_bytes(&line);
+                self.scrollback_rows.push(line);
                 self.scrollback_wrapped.push(is_wrapped);
+                self.set_low_info(self.scrollback_lines, class);
+                self.index_scrollback_slot(self.scrollback_lines);
                 self.scrollback_lines += 1;
+                self.total_lines_scrolled += 1;
+                self.compress_aged_row();
             } else {
-                let write_idx = self.scrollback_start;
-                let dst_start = write_idx * self.cols;
-                let dst_end = dst_start + self.cols;
+                match adaptive
+                    .as_ref()
+                    .and_then(|config| self.find_trim_candidate(config))
+                {
+                    Some((logical, trimmed)) => {
+                        self.remove_scrollback_line(logical);
+                        self.record_trim(trimmed);
+                    }
+                    None => {
+                        if adaptive.is_some() {
+                            self.trim_stats.fallback_evictions += 1;
+                        }
+                        self.trim_stats.fifo_evictions += 1;
+                        // The oldest line drops off; every zone row moves up one
+                        self.remove_zone_rows(0, 1);
+                    }
+                }
+                self.total_lines_scrolled += 1;
 
-                self.scrollback_cells[dst_start..dst_end]
-                    .clone_from_slice(&self.cells[src_start..src_end]);
+                let write_idx = self.scrollback_start;
+                let slot = &mut self.scrollback_rows[write_idx];
+                self.scrollback_bytes -= row_bytes(slot);
+                overwrite_scrollback_row(slot, &self.cells[src_start..src_end]);
+                self.scrollback_bytes += row_bytes(slot);
                 self.scrollback_wrapped[write_idx] = is_wrapped;
+                self.set_low_info(write_idx, class);
+                self.index_scrollback_slot(write_idx);
                 self.advance_scrollback_head();
+                self.compress_aged_row();
             }
         }
     }
@@ -50,12 +102,10 @@ impl Grid {
 
         self.push_rows_to_scrollback(0, n);
 
+        // Move rows instead of cloning cells; the rows rotated to the bottom
+        // are cleared below
+        self.cells[..self.rows * self.cols].rotate_left(n * self.cols);
         for i in n..self.rows {
-            let src_start = i * self.cols;
-            let dst_start = (i - n) * self.cols;
-            for j in 0..self.cols {
-                self.cells[dst_start + j] = self.cells[src_start + j].clone();
-            }
             if i < self.wrapped.len() && (i - n) < self.wrapped.len() {
                 self.wrapped[i - n] = self.wrapped[i];
             }
@@ -73,12 +123,8 @@ impl Grid {
     pub fn scroll_down(&mut self, n: usize) {
         let n = n.min(self.rows);
 
+        self.cells[..self.rows * self.cols].rotate_right(n * self.cols);
         for i in (n..self.rows).rev() {
-            let src_start = (i - n) * self.cols;
-            let dst_start = i * self.cols;
-            for j in 0..self.cols {
-                self.cells[dst_start + j] = self.cells[src_start + j].clone();
-            }
             if (i - n) < self.wrapped.len() && i < self.wrapped.len() {
                 self.wrapped[i] = self.wrapped[i - n];
             }
@@ -123,13 +169,7 @@ impl Grid {
             return true;
         }
 
-        for i in top..=(effective_bottom - n) {
-            let src_start = (i + n) * self.cols;
-            let dst_start = i * self.cols;
-            for j in 0..self.cols {
-                self.cells[dst_start + j] = self.cells[src_start + j].clone();
-            }
-        }
+        self.cells[top * self.cols..(effective_bottom + 1) * self.cols].rotate_left(n * self.cols);
 
         for i in (effective_bottom - n + 1)..=effective_bottom {
             if i < self.rows {
@@ -160,13 +200,7 @@ impl Grid {
             return true;
         }
 
-        for i in ((top + n)..=effective_bottom).rev() {
-            let src_start = (i - n) * self.cols;
-            let dst_start = i * self.cols;
-            for j in 0..self.cols {
-                self.cells[dst_start + j] = self.cells[src_start + j].clone();
-            }
-        }
+        self.cells[top * self.cols..(effective_bottom + 1) * self.cols].rotate_right(n * self.cols);
 
         for i in top..(top + n).min(self.rows) {
             self.clear_row(i);
@@ -174,6 +208,69 @@ impl Grid {
         true
     }
 
+    /// Scroll the cells of rows `top..=bottom` between columns `left..=right`
+    /// up by n, blanking the vacated rows of that band. Cells outside the
+    /// band stay put and nothing enters scrollback (DECLRMM margins).
+    pub fn scroll_rect_up(
+        &mut self,
+        n: usize,
+        top: usize,
+        bottom: usize,
+        left: usize,
+        right: usize,
+    ) {
+        let bottom = bottom.min(self.rows.saturating_sub(1));
+        let right = right.min(self.cols.saturating_sub(1));
+        if top > bottom || left > right {
+            return;
+        }
+        let n = n.min(bottom - top + 1);
+        for row in top + n..=bottom {
+            let (dst, src) = ((row - n) * self.cols, row * self.cols);
+            for col in left..=right {
+                self.cells[dst + col] = self.cells[src + col].clone();
+            }
+        }
+        for row in bottom + 1 - n..=bottom {
+            if let Some(row_cells) = self.row_mut(row) {
+                for cell in &mut row_cells[left..=right] {
+                    cell.reset();
+                }
+            }
+        }
+    }
+
+    /// Scroll the cells of rows `top..=bottom` between columns `left..=right`
+    /// down by n, blanking the vacated rows of that band (DECLRMM margins)
+    pub fn scroll_rect_down(
+        &mut self,
+        n: usize,
+        top: usize,
+        bottom: usize,
+        left: usize,
+        right: usize,
+    ) {
+        let bottom = bottom.min(self.rows.saturating_sub(1));
+        let right = right.min(self.cols.saturating_sub(1));
+        if top > bottom || left > right {
+            return;
+        }
+        let n = n.min(bottom - top + 1);
+        for row in (top + n..=bottom).rev() {
+            let (dst, src) = (row * self.cols, (row - n) * self.cols);
+            for col in left..=right {
+                self.cells[dst + col] = self.cells[src + col].clone();
+            }
+        }
+        for row in top..top + n {
+            if let Some(row_cells) = self.row_mut(row) {
+                for cell in &mut row_cells[left..=right] {
+                    cell.reset();
+                }
+            }
+        }
+    }
+
     /// Resize the grid
     pub fn resize(&mut self, cols: usize, rows: usize) {
         if self.cols == cols && self.rows == rows {
@@ -203,48 +300,89 @@ impl Grid {
         let old_rows = self.rows;
 
         if self.max_scrollback > 0 && self.scrollback_lines > 0 {
-            self.reflow_scrollback(old_cols, cols);
+            self.reflow_scrollback(cols);
         }
 
         self.reflow_main_grid(old_cols, old_rows, cols, rows);
     }
 
-    fn reflow_scrollback(&mut self, old_cols: usize, new_cols: usize) {
-        let logical_lines = self.extract_scrollback_logical_lines(old_cols);
-        let mut new_sb_cells = Vec::new();
-        let mut new_sb_wrapped = Vec::new();
-
-        for logical_line in logical_lines {
-            let (cells, wrapped_flags) = self.rewrap_logical_line(&logical_line, new_cols);
-
-            if cells.is_empty() {
-                for _ in 0..new_cols {
-                    new_sb_cells.push(Cell::default());
-                }
-                new_sb_wrapped.push(false);
+    /// Rewrap scrollback to `new_cols`.
+    ///
+    /// Old rows are consumed one logical line at a time and dropped as soon as
+    /// they are rewrapped, and lines past `max_scrollback` are discarded as they
+    /// are produced, so the scrollback is never held twice in memory. With
+    /// compression enabled new rows are compressed as they are produced and
+    /// the newest ones inflated again afterwards.
+    fn reflow_scrollback(&mut self, new_cols: usize) {
+        // Linearize the ring oldest-first; this only moves row pointers
+        let mut old_rows = std::mem::take(&mut self.scrollback_rows);
+        let mut old_wrapped = std::mem::take(&mut self.scrollback_wrapped);
+        old_rows.rotate_left(self.scrollback_start);
+        old_wrapped.rotate_left(self.scrollback_start);
+
+        let mut new_rows: VecDeque<(ScrollbackRow, bool)> = VecDeque::new();
+        let mut current_line = Vec::new();
+        for (row, is_wrapped) in old_rows.into_iter().zip(old_wrapped) {
+            current_line.extend(
+                row.iter()
+                    .filter(|cell| !cell.flags.wide_char_spacer())
+                    .cloned(),
+            );
+            drop(row);
+            if is_wrapped {
                 continue;
             }
-
-            for (i, row_cells) in cells.chunks(new_cols).enumerate() {
-                new_sb_cells.extend(row_cells.iter().cloned());
-                while new_sb_cells.len() % new_cols != 0 {
-                    new_sb_cells.push(Cell::default());
-                }
-                new_sb_wrapped.push(wrapped_flags.get(i).copied().unwrap_or(false));
+            while current_line
+                .last()
+                .is_some_and(|c| c.c == ' ' && c.is_empty())
+            {
+                current_line.pop();
             }
+            self.push_rewrapped_line(&current_line, new_cols, &mut new_rows);
+            current_line.clear();
         }
-
-        if new_sb_wrapped.len() > self.max_scrollback {
-            let excess = new_sb_wrapped.len() - self.max_scrollback;
-            let cells_to_drop = excess * new_cols;
-            new_sb_cells.drain(0..cells_to_drop);
-            new_sb_wrapped.drain(0..excess);
+        if !current_line.is_empty() {
+            self.push_rewrapped_line(&current_line, new_cols, &mut new_rows);
         }
 
-        self.scrollback_cells = new_sb_cells;
-        self.scrollback_wrapped = new_sb_wrapped;
+        let (rows, wrapped): (Vec<_>, Vec<_>) = new_rows.into_iter().unzip();
+        self.scrollback_rows = rows;
+        self.scrollback_wrapped = wrapped;
         self.scrollback_lines = self.scrollback_wrapped.len();
         self.scrollback_start = 0;
+        self.scrollback_low_info.clear();
+        self.compact_scrollback();
+        self.rebuild_scrollback_index();
+    }
+
+    /// Rewrap one logical scrollback line to `new_cols` and append its rows,
+    /// dropping the oldest rows once `max_scrollback` is exceeded.
+    fn push_rewrapped_line(
+        &self,
+        line: &[Cell],
+        new_cols: usize,
+        rows: &mut VecDeque<(ScrollbackRow, bool)>,
+    ) {
+        let (cells, wrapped_flags) = self.rewrap_logical_line(line, new_cols);
+
+        let mut push = |row_cells: &[Cell], is_wrapped: bool| {
+            let mut row = padded_row(row_cells, new_cols);
+            if self.scrollback_compression.is_some() {
+                row.compress(&self.scrollback_inflated);
+            }
+            rows.push_back((row, is_wrapped));
+        };
+        if cells.is_empty() {
+            push(&[], false);
+        } else {
+            for (i, row_cells) in cells.chunks(new_cols).enumerate() {
+                push(row_cells, wrapped_flags.get(i).copied().unwrap_or(false));
+            }
+        }
+
+        while rows.len() > self.max_scrollback {
+            rows.pop_front();
+        }
     }
 
     fn reflow_main_grid(
@@ -301,16 +439,23 @@ impl Grid {
                     let is_wrapped = all_wrapped.get(line_idx).copied().unwrap_or(false);
 
                     if self.scrollback_lines < self.max_scrollback {
-                        self.scrollback_cells.extend(row_cells.iter().cloned());
+                        let line = ScrollbackRow::from(row_cells);
+                        self.scrollback_bytes += row_bytes(&line);
+                        self.scrollback_rows.push(line);
                         self.scrollback_wrapped.push(is_wrapped);
+                        self.index_scrollback_slot(self.scrollback_lines);
                         self.scrollback_lines += 1;
+                        self.compress_aged_row();
                     } else {
                         let physical_index = self.scrollback_start;
-                        let sb_start = physical_index * new_cols;
-                        self.scrollback_cells[sb_start..sb_start + new_cols]
-                            .clone_from_slice(row_cells);
+                        let slot = &mut self.scrollback_rows[physical_index];
+                        self.scrollback_bytes -= row_bytes(slot);
+                        overwrite_scrollback_row(slot, row_cells);
+                        self.scrollback_bytes += row_bytes(slot);
                         self.scrollback_wrapped[physical_index] = is_wrapped;
+                        self.index_scrollback_slot(physical_index);
                         self.advance_scrollback_head();
+                        self.compress_aged_row();
                     }
                 }
             }
@@ -365,33 +510,6 @@ impl Grid {
         logical_lines
     }
 
-    fn extract_scrollback_logical_lines(&self, _old_cols: usize) -> Vec<Vec<Cell>> {
-        let mut logical_lines = Vec::new();
-        let mut current_line = Vec::new();
-        for i in 0..self.scrollback_lines {
-            if let Some(line) = self.scrollback_line(i) {
-                for cell in line {
-                    if !cell.flags.wide_char_spacer() {
-                        current_line.push(cell.clone());
-                    }
-                }
-                if !self.is_scrollback_wrapped(i) {
-                    while current_line
-                        .last()
-                        .is_some_and(|c| c.c == ' ' && c.is_empty())
-                    {
-                        current_line.pop();
-                    }
-                    logical_lines.push(std::mem::take(&mut current_line));
-                }
-            }
-        }
-        if !current_line.is_empty() {
-            logical_lines.push(current_line);
-        }
-        logical_lines
-    }
-
     fn rewrap_logical_line(&self, line: &[Cell], width: usize) -> (Vec<Cell>, Vec<bool>) {
         let mut new_cells = Vec::new();
         let mut wrapped_flags = Vec::new();
@@ -0,0 +1,144 @@
+//! Incremental text index over scrollback for repeated searches
+//!
+//! When enabled, each line's text and lowercase text are cached as the line
+//! enters scrollback, together with a 64-bit trigram signature used to skip
+//! lines that cannot contain an ASCII query.
+
+use crate::grid::Grid;
+
+/// Cached text of one scrollback line
+#[derive(Debug, Clone, Default)]
+pub struct IndexedLine {
+    /// Line text, as produced by [`crate::terminal::cells_to_text`]
+    text: Box<str>,
+    /// Lowercase text, when it differs from `text`
+    lower: Option<Box<str>>,
+    /// Trigram signature of the lowercase text
+    trigrams: u64,
+}
+
+impl IndexedLine {
+    fn new(text: String) -> Self {
+        let lower = text.to_lowercase();
+        let trigrams = trigram_mask(&lower);
+        let lower = (lower != text).then(|| lower.into_boxed_str());
+        Self {
+            text: text.into_boxed_str(),
+            lower,
+            trigrams,
+        }
+    }
+
+    /// Line text
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Lowercase line text
+    pub fn lowercase(&self) -> &str {
+        self.lower.as_deref().unwrap_or(&self.text)
+    }
+
+    /// Whether the line may contain a query with signature `query_mask`
+    /// (see [`query_trigram_mask`]); `false` means it certainly does not
+    pub fn may_contain(&self, query_mask: u64) -> bool {
+        self.trigrams & query_mask == query_mask
+    }
+}
+
+/// Signature of every byte trigram in `s`, folded into 64 bits
+fn trigram_mask(s: &str) -> u64 {
+    s.as_bytes().windows(3).fold(0, |mask, w| {
+        let hash = (u32::from(w[0]) * 961 + u32::from(w[1]) * 31 + u32::from(w[2])) % 64;
+        mask | (1 << hash)
+    })
+}
+
+/// Trigram signature for a search query, matched case-sensitively or not
+///
+/// Non-ASCII queries get an empty signature, which every line may contain:
+/// lowercasing can change their byte sequence in ways the line signature
+/// does not capture.
+pub fn query_trigram_mask(query: &str) -> u64 {
+    if query.is_ascii() {
+        trigram_mask(&query.to_ascii_lowercase())
+    } else {
+        0
+    }
+}
+
+impl Grid {
+    /// Enable or disable the scrollback search index
+    ///
+    /// Enabling indexes every line already in scrollback; afterwards lines
+    /// are indexed as they enter scrollback and the index is rebuilt on
+    /// reflow or snapshot restore.
+    pub fn set_scrollback_search_index(&mut self, enabled: bool) {
+        self.scrollback_index_enabled = enabled;
+        self.rebuild_scrollback_index();
+    }
+
+    /// Whether the scrollback search index is enabled
+    pub fn scrollback_search_index_enabled(&self) -> bool {
+        self.scrollback_index_enabled
+    }
+
+    /// Indexed text of a scrollback line (0 = oldest), if the index is enabled
+    pub fn indexed_scrollback_line(&self, index: usize) -> Option<&IndexedLine> {
+        if index < self.scrollback_lines {
+            self.scrollback_index
+                .get(self.scrollback_physical_index(index))
+        } else {
+            None
+        }
+    }
+
+    /// Index the row just written into physical scrollback slot `physical`
+    pub(in crate::grid) fn index_scrollback_slot(&mut self, physical: usize) {
+        if !self.scrollback_index_enabled {
+            return;
+        }
+        let text = self
+            .scrollback_rows
+            .get(physical)
+            .map(|row| crate::terminal::cells_to_text(row))
+            .unwrap_or_default();
+        let line = IndexedLine::new(text);
+        if physical < self.scrollback_index.len() {
+            self.scrollback_index[physical] = line;
+        } else {
+            self.scrollback_index.push(line);
+        }
+    }
+
+    /// Drop the index and, if enabled, index every scrollback slot again
+    pub(in crate::grid) fn rebuild_scrollback_index(&mut self) {
+        self.scrollback_index.clear();
+        for physical in 0..self.scrollback_rows.len() {
+            self.index_scrollback_slot(physical);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_indexed_line_signature_filters_ascii_queries() {
+        let line = IndexedLine::new("Error: Disk Full".to_string());
+        assert_eq!(line.lowercase(), "error: disk full");
+        assert!(line.may_contain(query_trigram_mask("DISK")));
+        assert!(line.may_contain(query_trigram_mask("fu")));
+        assert!(!line.may_contain(query_trigram_mask("warning")));
+        // Non-ASCII queries are never filtered out
+        assert!(line.may_contain(query_trigram_mask("héllo")));
+    }
+
+    #[test]
+    fn test_indexed_line_shares_text_when_already_lowercase() {
+        let line = IndexedLine::new("plain text".to_string());
+        assert!(line.lower.is_none());
+        assert_eq!(line.lowercase(), "plain text");
+    }
+}
@@ -118,6 +118,26 @@ fn test_insert_lines_edge_case() {
     assert_eq!(grid.get(0, 8).unwrap().c, ' '); // Should be blank
 }
 
+#[test]
+fn test_insert_and_delete_lines_covering_whole_region() {
+    // Regression: a count spanning the region from row 0 used to index past
+    // the end of the grid
+    for scroll_bottom in [9, 50] {
+        let mut grid = Grid::new(4, 10, 1000);
+        for i in 0..10 {
+            grid.set(0, i, Cell::new((b'A' + i as u8) as char));
+        }
+        grid.delete_lines(10, 0, scroll_bottom);
+        assert!((0..10).all(|row| grid.get(0, row).unwrap().c == ' '));
+
+        for i in 0..10 {
+            grid.set(0, i, Cell::new((b'A' + i as u8) as char));
+        }
+        grid.insert_lines(65535, 0, scroll_bottom);
+        assert!((0..10).all(|row| grid.get(0, row).unwrap().c == ' '));
+    }
+}
+
 #[test]
 fn test_delete_lines_edge_case() {
     let mut grid = Grid::new(80, 10, 1000);
@@ -148,6 +168,25 @@ fn test_insert_chars_at_end_of_line() {
     assert_eq!(grid.get(9, 0).unwrap().c, ' '); // Should be blank
 }
 
+#[test]
+fn test_insert_delete_columns_clamp_to_right_bound() {
+    let mut grid = Grid::new(10, 3, 1000);
+    for row in 0..3 {
+        for i in 0..10 {
+            grid.set(i, row, Cell::new((b'0' + i as u8) as char));
+        }
+    }
+
+    // Only rows 0..=1 and columns up to 5 are touched; n is clamped
+    grid.insert_columns(4, 9, 0, 1, 5);
+    assert_eq!(grid.row_text(0), "0123  6789");
+    assert_eq!(grid.row_text(2), "0123456789");
+
+    grid.delete_columns(1, 2, 1, 2, 9);
+    assert_eq!(grid.row_text(1), "03  6789  ");
+    assert_eq!(grid.row_text(2), "03456789  ");
+}
+
 #[test]
 fn test_delete_chars_boundary() {
     let mut grid = Grid::new(10, 5, 1000);
@@ -1381,3 +1420,355 @@ mod snapshot_tests {
         assert_eq!(grid.scrollback_len(), sb_lines);
     }
 }
+
+/// Scroll one line of text into scrollback
+fn push_text_line(grid: &mut Grid, text: &str) {
+    grid.clear_row(0);
+    for (col, ch) in text.chars().enumerate() {
+        grid.set(col, 0, Cell::new(ch));
+    }
+    grid.scroll_up(1);
+}
+
+fn scrollback_texts(grid: &Grid) -> Vec<String> {
+    (0..grid.scrollback_len())
+        .map(|i| {
+            grid.scrollback_line(i)
+                .unwrap()
+                .iter()
+                .map(|c| c.c)
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        })
+        .collect()
+}
+
+#[test]
+fn test_adaptive_trim_prefers_blank_lines() {
+    let mut grid = Grid::new(20, 1, 4);
+    grid.set_trim_policy(ScrollbackTrimPolicy::Adaptive(AdaptiveTrimConfig::default()));
+    for line in ["first", "", "second", "third", "fourth"] {
+        push_text_line(&mut grid, line);
+    }
+    assert_eq!(
+        scrollback_texts(&grid),
+        vec!["first", "second", "third", "fourth"]
+    );
+    let stats = grid.trim_stats();
+    assert_eq!(stats.blank_trimmed, 1);
+    assert_eq!(stats.fifo_evictions, 0);
+    assert_eq!(grid.total_lines_scrolled(), 5);
+}
+
+#[test]
+fn test_adaptive_trim_drops_progress_redraws() {
+    let mut grid = Grid::new(30, 1, 4);
+    grid.set_trim_policy(ScrollbackTrimPolicy::Adaptive(AdaptiveTrimConfig::default()));
+    for line in [
+        "$ make",
+        "[#####     ] 50% 1.2MB/s",
+        "[######    ] 60% 1.3MB/s",
+        "[#######   ] 70% 1.1MB/s",
+        "done",
+        "$ ls",
+    ] {
+        push_text_line(&mut grid, line);
+    }
+    // The last redraw of the progress bar survives; earlier ones are trimmed
+    assert_eq!(
+        scrollback_texts(&grid),
+        vec!["$ make", "[#######   ] 70% 1.1MB/s", "done", "$ ls"]
+    );
+    assert_eq!(grid.trim_stats().similar_trimmed, 2);
+}
+
+#[test]
+fn test_adaptive_trim_falls_back_to_fifo() {
+    let mut grid = Grid::new(20, 1, 3);
+    grid.set_trim_policy(ScrollbackTrimPolicy::Adaptive(AdaptiveTrimConfig::default()));
+    for line in ["alpha", "bravo 1", "charlie", "delta"] {
+        push_text_line(&mut grid, line);
+    }
+    assert_eq!(scrollback_texts(&grid), vec!["bravo 1", "charlie", "delta"]);
+    let stats = grid.trim_stats();
+    assert_eq!(stats.fallback_evictions, 1);
+    assert_eq!(stats.fifo_evictions, 1);
+    assert_eq!(stats.adaptive_trimmed(), 0);
+}
+
+#[test]
+fn test_adaptive_trim_low_entropy_and_zone_remap() {
+    use crate::zone::{Zone, ZoneType};
+
+    let mut grid = Grid::new(20, 1, 3);
+    grid.set_trim_policy(ScrollbackTrimPolicy::Adaptive(AdaptiveTrimConfig {
+        min_entropy_bits: 1.0,
+        similarity_threshold: 2.0,
+        ..AdaptiveTrimConfig::default()
+    }));
+    push_text_line(&mut grid, "prompt");
+    grid.push_zone(Zone::new(0, ZoneType::Prompt, 0, None));
+    grid.close_current_zone(0);
+    push_text_line(&mut grid, "==========");
+    push_text_line(&mut grid, "output");
+    push_text_line(&mut grid, "next");
+
+    assert_eq!(scrollback_texts(&grid), vec!["prompt", "output", "next"]);
+    assert_eq!(grid.trim_stats().low_entropy_trimmed, 1);
+    // "prompt" is older than the trimmed line, so it keeps row 0
+    let zone = &grid.zones()[0];
+    assert_eq!((zone.abs_row_start, zone.abs_row_end), (0, 0));
+}
+
+#[test]
+fn test_fifo_policy_counts_evictions() {
+    let mut grid = Grid::new(20, 1, 2);
+    for line in ["a", "", "b"] {
+        push_text_line(&mut grid, line);
+    }
+    assert_eq!(scrollback_texts(&grid), vec!["", "b"]);
+    assert_eq!(grid.trim_stats().fifo_evictions, 1);
+    grid.reset_trim_stats();
+    assert_eq!(grid.trim_stats(), ScrollbackTrimStats::default());
+}
+
+#[test]
+fn test_memory_policy_evicts_oldest_and_keeps_ring_usable() {
+    let line_bytes = 10 * std::mem::size_of::<Cell>();
+    let mut grid = Grid::new(10, 1, 4);
+    // Six pushes into four slots leave the ring wrapped
+    for line in ["a", "b", "c", "d", "e", "f"] {
+        push_text_line(&mut grid, line);
+    }
+    grid.set_scrollback_policy(ScrollbackPolicy {
+        max_bytes: Some(2 * line_bytes),
+        ..ScrollbackPolicy::default()
+    });
+    assert_eq!(grid.apply_scrollback_policy(0, false), 2);
+    assert_eq!(scrollback_texts(&grid), vec!["e", "f"]);
+    assert_eq!(grid.scrollback_memory_bytes(), 2 * line_bytes);
+    assert_eq!(grid.trim_stats().memory_evictions, 2);
+
+    // The buffer grows and wraps again after the eviction
+    for line in ["g", "h", "i"] {
+        push_text_line(&mut grid, line);
+    }
+    assert_eq!(scrollback_texts(&grid), vec!["f", "g", "h", "i"]);
+    assert_eq!(grid.apply_scrollback_policy(0, false), 2);
+    assert_eq!(scrollback_texts(&grid), vec!["h", "i"]);
+}
+
+#[test]
+fn test_scrollback_byte_total_tracks_every_change() {
+    fn assert_total_matches(grid: &Grid) {
+        let mut recounted = grid.clone();
+        recounted.recount_scrollback_bytes();
+        assert_eq!(
+            grid.scrollback_memory_bytes(),
+            recounted.scrollback_memory_bytes()
+        );
+    }
+
+    let mut grid = Grid::new(10, 1, 6);
+    grid.set_trim_policy(ScrollbackTrimPolicy::Adaptive(AdaptiveTrimConfig::default()));
+    grid.set_scrollback_compression(Some(2));
+    for line in ["a", "", "b", "", "c", "d", "", "e", "f", "g"] {
+        push_text_line(&mut grid, line);
+        assert_total_matches(&grid);
+    }
+    assert!(grid.scrollback_compression_stats().compressed_lines > 0);
+
+    grid.resize(7, 1);
+    assert_total_matches(&grid);
+    grid.set_scrollback_policy(ScrollbackPolicy {
+        max_bytes: Some(1),
+        ..ScrollbackPolicy::default()
+    });
+    grid.apply_scrollback_policy(0, false);
+    assert_eq!(grid.scrollback_len(), 0);
+    assert_eq!(grid.scrollback_memory_bytes(), 0);
+    push_text_line(&mut grid, "h");
+    grid.clear_scrollback();
+    assert_eq!(grid.scrollback_memory_bytes(), 0);
+}
+
+#[test]
+fn test_age_and_idle_policies() {
+    let mut grid = Grid::new(10, 1, 10);
+    grid.set_row_clock(1_000);
+    push_text_line(&mut grid, "old 1");
+    push_text_line(&mut grid, "old 2");
+    grid.set_row_clock(5_000);
+    push_text_line(&mut grid, "new 1");
+    push_text_line(&mut grid, "new 2");
+    push_text_line(&mut grid, "new 3");
+
+    grid.set_scrollback_policy(ScrollbackPolicy {
+        max_age_ms: Some(2_000),
+        idle_trim: Some(IdleTrim {
+            idle_ms: 60_000,
+            keep_lines: 1,
+        }),
+        ..ScrollbackPolicy::default()
+    });
+    assert_eq!(grid.apply_scrollback_policy(6_000, false), 2);
+    assert_eq!(scrollback_texts(&grid), vec!["new 1", "new 2", "new 3"]);
+    assert_eq!(grid.apply_scrollback_policy(6_000, true), 2);
+    assert_eq!(scrollback_texts(&grid), vec!["new 3"]);
+
+    let stats = grid.trim_stats();
+    assert_eq!((stats.age_evictions, stats.idle_evictions), (2, 2));
+    assert_eq!(stats.policy_evictions(), 4);
+    assert_eq!(stats.fifo_evictions, 0);
+}
+
+#[test]
+fn test_snapshot_shares_scrollback_rows() {
+    let mut grid = Grid::new(10, 2, 100);
+    for i in 0..5 {
+        grid.set(0, 0, Cell::new((b'A' + i as u8) as char));
+        grid.scroll_up(1);
+    }
+
+    let snap = grid.capture_snapshot();
+    assert_eq!(snap.scrollback_rows.len(), 5);
+    for (grid_row, snap_row) in grid.scrollback_rows.iter().zip(&snap.scrollback_rows) {
+        assert!(ScrollbackRow::ptr_eq(grid_row, snap_row));
+    }
+}
+
+#[test]
+fn test_scrollback_overwrite_does_not_touch_snapshot() {
+    // Full ring: the next scroll overwrites the oldest slot in place unless a
+    // snapshot still holds it
+    let mut grid = Grid::new(10, 2, 2);
+    for ch in ['A', 'B'] {
+        grid.set(0, 0, Cell::new(ch));
+        grid.scroll_up(1);
+    }
+    let snap = grid.capture_snapshot();
+
+    grid.set(0, 0, Cell::new('C'));
+    grid.scroll_up(1);
+    assert_eq!(grid.scrollback_line(0).unwrap()[0].c, 'B');
+    assert_eq!(grid.scrollback_line(1).unwrap()[0].c, 'C');
+
+    let mut restored = Grid::new(10, 2, 2);
+    restored.restore_from_snapshot(&snap);
+    assert_eq!(restored.scrollback_line(0).unwrap()[0].c, 'A');
+    assert_eq!(restored.scrollback_line(1).unwrap()[0].c, 'B');
+}
+
+#[test]
+fn test_scrollback_reflow_over_capacity_keeps_newest_rows() {
+    let mut grid = Grid::new(20, 5, 3);
+    for (i, ch) in "ABCDEFGHIJKLMNOPQRST".chars().enumerate() {
+        grid.set(i, 0, Cell::new(ch));
+    }
+    grid.scroll_up(1);
+
+    // Rewraps to 4 rows of 5; only the last 3 fit
+    grid.resize(5, 5);
+
+    assert_eq!(grid.scrollback_len(), 3);
+    assert_eq!(grid.scrollback_line(0).unwrap()[0].c, 'F');
+    assert_eq!(grid.scrollback_line(2).unwrap()[4].c, 'T');
+    assert!(grid.is_scrollback_wrapped(0));
+    assert!(!grid.is_scrollback_wrapped(2));
+    assert!((0..3).all(|i| grid.scrollback_line(i).unwrap().len() == 5));
+}
+
+fn fill_scrollback(grid: &mut Grid, lines: usize) {
+    for i in 0..lines {
+        push_text_line(grid, &format!("row {i}"));
+    }
+}
+
+#[test]
+fn test_scrollback_compression_ring_keeps_content() {
+    let mut grid = Grid::new(10, 2, 8);
+    grid.set_scrollback_compression(Some(3));
+    fill_scrollback(&mut grid, 20);
+
+    assert_eq!(grid.scrollback_len(), 8);
+    assert_eq!(grid.scrollback_compression_stats().compressed_lines, 5);
+    let expected: Vec<String> = (12..20).map(|i| format!("row {i}")).collect();
+    assert_eq!(scrollback_texts(&grid), expected);
+}
+
+#[test]
+fn test_scrollback_compression_survives_reflow() {
+    let mut grid = Grid::new(10, 2, 100);
+    grid.set_scrollback_compression(Some(2));
+    fill_scrollback(&mut grid, 6);
+    let before = scrollback_texts(&grid);
+
+    grid.resize(20, 2);
+
+    assert_eq!(scrollback_texts(&grid), before);
+    assert_eq!(grid.scrollback_compression_stats().compressed_lines, 4);
+    assert!((0..6).all(|i| grid.scrollback_line(i).unwrap().len() == 20));
+}
+
+#[test]
+fn test_scrollback_compaction_drops_inflated_rows() {
+    let mut grid = Grid::new(10, 2, 100);
+    grid.set_scrollback_compression(Some(0));
+    fill_scrollback(&mut grid, 4);
+    assert_eq!(grid.scrollback_compression_stats().compressed_lines, 4);
+
+    let _ = scrollback_texts(&grid);
+    assert_eq!(
+        grid.scrollback_inflated
+            .load(std::sync::atomic::Ordering::Relaxed),
+        4
+    );
+    grid.compact_scrollback();
+    assert_eq!(
+        grid.scrollback_inflated
+            .load(std::sync::atomic::Ordering::Relaxed),
+        0
+    );
+    assert_eq!(scrollback_texts(&grid)[3], "row 3");
+}
+
+#[test]
+fn test_attribute_change_stream_extent() {
+    use crate::grid::AttributeChangeExtent;
+    let mut grid = Grid::new(5, 4, 0);
+
+    grid.change_attributes_in_area(0, 3, 1, 1, AttributeChangeExtent::Stream, &[5]);
+    let blinking: Vec<(usize, usize)> = (0..4)
+        .flat_map(|row| (0..5).map(move |col| (col, row)))
+        .filter(|&(col, row)| grid.get(col, row).unwrap().flags.blink())
+        .collect();
+    assert_eq!(blinking, [(3, 0), (4, 0), (0, 1), (1, 1)]);
+
+    // Rectangle extent with left > right covers nothing
+    grid.reverse_attributes_in_area(0, 3, 1, 1, AttributeChangeExtent::Rectangle, &[5]);
+    assert!(grid.get(3, 0).unwrap().flags.blink());
+}
+
+#[test]
+fn test_scroll_rect_moves_only_the_band() {
+    let mut grid = Grid::new(6, 4, 1000);
+    for row in 0..4 {
+        for col in 0..6 {
+            grid.set(col, row, Cell::new((b'a' + row as u8) as char));
+        }
+    }
+
+    grid.scroll_rect_up(1, 0, 2, 1, 3);
+    assert_eq!(grid.row_text(0), "abbbaa");
+    assert_eq!(grid.row_text(1), "bcccbb");
+    assert_eq!(grid.row_text(2), "c   cc");
+    assert_eq!(grid.row_text(3), "dddddd");
+    assert_eq!(grid.scrollback_len(), 0);
+
+    // n past the band height blanks the whole band
+    grid.scroll_rect_down(9, 1, 3, 2, 2);
+    assert_eq!(grid.row_text(1), "bc cbb");
+    assert_eq!(grid.row_text(3), "dd ddd");
+    assert_eq!(grid.row_text(0), "abbbaa");
+}
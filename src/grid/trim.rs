@@ -0,0 +1,417 @@
+//! Scrollback trimming policies
+//!
+//! By default the scrollback ring buffer evicts strictly in FIFO order. The
+//! adaptive policy instead looks at the oldest lines first and, when the
+//! buffer is full, drops a low-information line (blank, low character
+//! entropy, or a near-duplicate of the line that followed it, such as a
+//! progress bar redrawn on a new line) in preference to the oldest line.
+//! When no such line exists in the scan window it falls back to FIFO.
+//!
+//! Independently of the line count, a [`ScrollbackPolicy`] caps scrollback
+//! by estimated memory and line age, and can shrink it once output has gone
+//! idle. Those limits always evict the oldest lines.
+
+use crate::cell::Cell;
+use crate::grid::{Grid, ScrollbackRow};
+
+/// Estimated memory held by one scrollback row in bytes (compressed rows
+/// count at their compressed size)
+pub(in crate::grid) fn row_bytes(row: &ScrollbackRow) -> usize {
+    row.compressed_size()
+        .unwrap_or(row.len() * std::mem::size_of::<Cell>())
+}
+
+/// Tuning for [`ScrollbackTrimPolicy::Adaptive`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptiveTrimConfig {
+    /// Number of oldest scrollback lines searched for a trim candidate
+    pub window: usize,
+    /// Similarity (0.0-1.0) to the following line at or above which a line
+    /// counts as a redundant redraw; values above 1.0 disable the check
+    pub similarity_threshold: f32,
+    /// Lines whose Shannon character entropy (bits) is below this value
+    /// are low-information; 0.0 disables the check
+    pub min_entropy_bits: f32,
+    /// Whether blank lines are trim candidates
+    pub trim_blank: bool,
+}
+
+impl Default for AdaptiveTrimConfig {
+    fn default() -> Self {
+        Self {
+            window: 256,
+            similarity_threshold: 0.85,
+            min_entropy_bits: 0.0,
+            trim_blank: true,
+        }
+    }
+}
+
+/// How lines are chosen for eviction once scrollback is full
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ScrollbackTrimPolicy {
+    /// Always evict the oldest line
+    #[default]
+    Fifo,
+    /// Prefer evicting low-information lines near the old end of the buffer
+    Adaptive(AdaptiveTrimConfig),
+}
+
+/// Shrink scrollback after output has been idle for a while
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdleTrim {
+    /// Milliseconds without output after which scrollback is shrunk
+    pub idle_ms: u64,
+    /// Newest scrollback lines kept once idle
+    pub keep_lines: usize,
+}
+
+/// Limits applied to scrollback on top of the maximum line count
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ScrollbackPolicy {
+    /// Maximum estimated scrollback memory in bytes (compressed lines count
+    /// at their compressed size)
+    pub max_bytes: Option<usize>,
+    /// Maximum age of a scrollback line in milliseconds, by the time it was
+    /// last written
+    pub max_age_ms: Option<u64>,
+    /// Shrink scrollback once output has been idle
+    pub idle_trim: Option<IdleTrim>,
+}
+
+impl ScrollbackPolicy {
+    /// Whether any limit is set
+    pub fn is_active(&self) -> bool {
+        self.max_bytes.is_some() || self.max_age_ms.is_some() || self.idle_trim.is_some()
+    }
+}
+
+/// Counters describing how scrollback lines were evicted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ScrollbackTrimStats {
+    /// Lines evicted in FIFO order (including adaptive fallbacks)
+    pub fifo_evictions: u64,
+    /// Adaptive evictions that found no candidate and fell back to FIFO
+    pub fallback_evictions: u64,
+    /// Blank lines trimmed ahead of older content
+    pub blank_trimmed: u64,
+    /// Low-entropy lines trimmed ahead of older content
+    pub low_entropy_trimmed: u64,
+    /// Near-duplicate lines trimmed ahead of older content
+    pub similar_trimmed: u64,
+    /// Oldest lines evicted to stay under [`ScrollbackPolicy::max_bytes`]
+    pub memory_evictions: u64,
+    /// Lines evicted for exceeding [`ScrollbackPolicy::max_age_ms`]
+    pub age_evictions: u64,
+    /// Lines evicted by [`ScrollbackPolicy::idle_trim`]
+    pub idle_evictions: u64,
+}
+
+impl ScrollbackTrimStats {
+    /// Total lines removed by the adaptive policy
+    pub fn adaptive_trimmed(&self) -> u64 {
+        self.blank_trimmed + self.low_entropy_trimmed + self.similar_trimmed
+    }
+
+    /// Total lines removed by [`ScrollbackPolicy`] limits
+    pub fn policy_evictions(&self) -> u64 {
+        self.memory_evictions + self.age_evictions + self.idle_evictions
+    }
+}
+
+/// Why a line was classified as low-information
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(in crate::grid) enum LowInfo {
+    Blank,
+    LowEntropy,
+    Similar,
+}
+
+/// Characters of a line up to the last non-whitespace cell
+fn line_chars(cells: &[Cell]) -> Vec<char> {
+    let end = cells
+        .iter()
+        .rposition(|c| !c.c.is_whitespace() && c.c != '\0')
+        .map_or(0, |i| i + 1);
+    cells[..end]
+        .iter()
+        .filter(|c| !c.flags.wide_char_spacer())
+        .map(|c| c.c)
+        .collect()
+}
+
+/// Shannon entropy of a character sequence in bits per character
+fn entropy_bits(chars: &[char]) -> f32 {
+    if chars.is_empty() {
+        return 0.0;
+    }
+    let mut counts: std::collections::HashMap<char, usize> = std::collections::HashMap::new();
+    for &ch in chars {
+        *counts.entry(ch).or_default() += 1;
+    }
+    let total = chars.len() as f32;
+    counts
+        .values()
+        .map(|&n| {
+            let p = n as f32 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Position-wise similarity of two character sequences (0.0-1.0)
+fn similarity(a: &[char], b: &[char]) -> f32 {
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    let same = a.iter().zip(b).filter(|(x, y)| x == y).count();
+    same as f32 / longest as f32
+}
+
+impl AdaptiveTrimConfig {
+    /// Classify a line on its own (blank / low entropy)
+    fn classify(&self, chars: &[char]) -> Option<LowInfo> {
+        if chars.is_empty() {
+            return self.trim_blank.then_some(LowInfo::Blank);
+        }
+        if self.min_entropy_bits > 0.0 && entropy_bits(chars) < self.min_entropy_bits {
+            return Some(LowInfo::LowEntropy);
+        }
+        None
+    }
+}
+
+impl Grid {
+    /// Set the scrollback trimming policy
+    pub fn set_trim_policy(&mut self, policy: ScrollbackTrimPolicy) {
+        self.trim_policy = policy;
+        // Classifications only exist for lines pushed under the adaptive policy
+        self.scrollback_low_info.clear();
+    }
+
+    /// Get the scrollback trimming policy
+    pub fn trim_policy(&self) -> ScrollbackTrimPolicy {
+        self.trim_policy
+    }
+
+    /// Get scrollback eviction statistics
+    pub fn trim_stats(&self) -> ScrollbackTrimStats {
+        self.trim_stats
+    }
+
+    /// Reset scrollback eviction statistics
+    pub fn reset_trim_stats(&mut self) {
+        self.trim_stats = ScrollbackTrimStats::default();
+    }
+
+    /// Set the memory, age and idle limits for scrollback; call
+    /// [`Grid::apply_scrollback_policy`] to enforce them
+    pub fn set_scrollback_policy(&mut self, policy: ScrollbackPolicy) {
+        self.scrollback_policy = policy;
+    }
+
+    /// Get the memory, age and idle limits for scrollback
+    pub fn scrollback_policy(&self) -> ScrollbackPolicy {
+        self.scrollback_policy
+    }
+
+    /// Estimated memory held by scrollback line `logical` in bytes
+    fn scrollback_line_bytes(&self, logical: usize) -> usize {
+        row_bytes(&self.scrollback_rows[self.scrollback_physical_index(logical)])
+    }
+
+    /// Estimated memory held by scrollback lines in bytes
+    pub fn scrollback_memory_bytes(&self) -> usize {
+        self.scrollback_bytes
+    }
+
+    /// Recompute the running scrollback byte total from every line
+    pub(in crate::grid) fn recount_scrollback_bytes(&mut self) {
+        self.scrollback_bytes = (0..self.scrollback_lines)
+            .map(|logical| self.scrollback_line_bytes(logical))
+            .sum();
+    }
+
+    /// Evict the oldest scrollback lines that break the scrollback policy
+    /// at time `now` (Unix epoch milliseconds); `idle` says whether output
+    /// has been idle for the policy's idle time. Returns the number of lines
+    /// evicted.
+    pub fn apply_scrollback_policy(&mut self, now: u64, idle: bool) -> usize {
+        let policy = self.scrollback_policy;
+        let lines = self.scrollback_lines;
+        let mut evict = 0;
+
+        if let Some(max_age) = policy.max_age_ms {
+            let cutoff = now.saturating_sub(max_age);
+            evict = (0..lines)
+                .take_while(|&row| self.row_timestamp(row).is_some_and(|t| t < cutoff))
+                .count();
+            self.trim_stats.age_evictions += evict as u64;
+        }
+        if let Some(idle_trim) = policy.idle_trim.filter(|_| idle) {
+            let target = lines.saturating_sub(idle_trim.keep_lines);
+            if target > evict {
+                self.trim_stats.idle_evictions += (target - evict) as u64;
+                evict = target;
+            }
+        }
+        if let Some(max_bytes) = policy.max_bytes {
+            // Every line is at most one uncompressed row, so skip the scan
+            // when even that fits
+            let upper_bound = (lines - evict) * self.cols * std::mem::size_of::<Cell>();
+            if upper_bound > max_bytes {
+                let mut bytes = self.scrollback_bytes
+                    - (0..evict)
+                        .map(|logical| self.scrollback_line_bytes(logical))
+                        .sum::<usize>();
+                let before = evict;
+                while bytes > max_bytes && evict < lines {
+                    bytes -= self.scrollback_line_bytes(evict);
+                    evict += 1;
+                }
+                self.trim_stats.memory_evictions += (evict - before) as u64;
+            }
+        }
+
+        self.evict_oldest_scrollback(evict);
+        evict
+    }
+
+    /// Drop the `count` oldest scrollback lines, leaving the buffer in
+    /// linear order so it can grow again by pushing
+    pub(in crate::grid) fn evict_oldest_scrollback(&mut self, count: usize) {
+        let count = count.min(self.scrollback_lines);
+        if count == 0 {
+            return;
+        }
+        self.scrollback_bytes -= (0..count)
+            .map(|logical| self.scrollback_line_bytes(logical))
+            .sum::<usize>();
+        let slots = self.scrollback_rows.len();
+        let start = self.scrollback_start;
+        let remaining = self.scrollback_lines - count;
+        self.scrollback_rows.rotate_left(start);
+        self.scrollback_rows.drain(..count);
+        self.scrollback_rows.truncate(remaining);
+        self.scrollback_wrapped.resize(slots, false);
+        self.scrollback_wrapped.rotate_left(start);
+        self.scrollback_wrapped.drain(..count);
+        self.scrollback_wrapped.truncate(remaining);
+        if !self.scrollback_low_info.is_empty() {
+            self.scrollback_low_info.resize(slots, None);
+            self.scrollback_low_info.rotate_left(start);
+            self.scrollback_low_info.drain(..count);
+            self.scrollback_low_info.truncate(remaining);
+        }
+        let index_matches = self.scrollback_index.len() == slots;
+        if index_matches {
+            self.scrollback_index.rotate_left(start);
+            self.scrollback_index.drain(..count);
+            self.scrollback_index.truncate(remaining);
+        }
+        self.scrollback_start = 0;
+        self.scrollback_lines = remaining;
+        if !index_matches {
+            self.rebuild_scrollback_index();
+        }
+        // Zones, annotations and row times of the evicted lines go too
+        self.remove_zone_rows(0, count);
+    }
+
+    fn low_info_at(&self, physical: usize) -> Option<LowInfo> {
+        self.scrollback_low_info.get(physical).copied().flatten()
+    }
+
+    pub(in crate::grid) fn set_low_info(&mut self, physical: usize, value: Option<LowInfo>) {
+        if self.scrollback_low_info.len() <= physical {
+            if value.is_none() {
+                return;
+            }
+            self.scrollback_low_info.resize(physical + 1, None);
+        }
+        self.scrollback_low_info[physical] = value;
+    }
+
+    /// Classify the main-grid row about to be pushed into scrollback, and
+    /// mark the current newest scrollback line as redundant if the row is a
+    /// near-duplicate of it.
+    pub(in crate::grid) fn classify_incoming_row(
+        &mut self,
+        config: &AdaptiveTrimConfig,
+        row: usize,
+    ) -> Option<LowInfo> {
+        let start = row * self.cols;
+        let incoming = line_chars(&self.cells[start..start + self.cols]);
+        let class = config.classify(&incoming);
+
+        if class.is_none() && config.similarity_threshold <= 1.0 && self.scrollback_lines > 0 {
+            let newest = self.scrollback_lines - 1;
+            let physical = self.scrollback_physical_index(newest);
+            let newest_wrapped = self
+                .scrollback_wrapped
+                .get(physical)
+                .copied()
+                .unwrap_or(false);
+            if self.low_info_at(physical).is_none() && !newest_wrapped {
+                let prev = self
+                    .scrollback_line(newest)
+                    .map(line_chars)
+                    .unwrap_or_default();
+                if !prev.is_empty() && similarity(&prev, &incoming) >= config.similarity_threshold {
+                    self.set_low_info(physical, Some(LowInfo::Similar));
+                }
+            }
+        }
+        class
+    }
+
+    /// Pick the logical scrollback line to evict under the adaptive policy.
+    ///
+    /// Returns the oldest low-information line in the scan window that does
+    /// not split a wrapped logical line, or `None` for FIFO fallback.
+    pub(in crate::grid) fn find_trim_candidate(
+        &self,
+        config: &AdaptiveTrimConfig,
+    ) -> Option<(usize, LowInfo)> {
+        let window = config.window.min(self.scrollback_lines);
+        (0..window).find_map(|logical| {
+            let physical = self.scrollback_physical_index(logical);
+            let class = self.low_info_at(physical)?;
+            let wrapped_into = logical > 0 && self.is_scrollback_wrapped(logical - 1);
+            let wraps_onward = self.is_scrollback_wrapped(logical);
+            (!wrapped_into && !wraps_onward).then_some((logical, class))
+        })
+    }
+
+    /// Remove logical scrollback line `logical` by shifting every older line
+    /// one slot newer, leaving the oldest slot free to be overwritten by the
+    /// incoming row. Zone rows are remapped so they keep pointing at the same
+    /// content.
+    pub(in crate::grid) fn remove_scrollback_line(&mut self, logical: usize) {
+        for i in (0..logical).rev() {
+            let src = self.scrollback_physical_index(i);
+            let dst = self.scrollback_physical_index(i + 1);
+            // Swapping rather than copying carries the removed row down to the
+            // oldest slot, where its allocation is reused for the incoming row
+            self.scrollback_rows.swap(src, dst);
+            if self.scrollback_index_enabled {
+                self.scrollback_index.swap(src, dst);
+            }
+            self.scrollback_wrapped[dst] = self.scrollback_wrapped[src];
+            let class = self.low_info_at(src);
+            self.set_low_info(dst, class);
+        }
+
+        // Lines newer than the removed one move one absolute row up
+        self.remove_zone_rows(logical, 1);
+    }
+
+    pub(in crate::grid) fn record_trim(&mut self, class: LowInfo) {
+        match class {
+            LowInfo::Blank => self.trim_stats.blank_trimmed += 1,
+            LowInfo::LowEntropy => self.trim_stats.low_entropy_trimmed += 1,
+            LowInfo::Similar => self.trim_stats.similar_trimmed += 1,
+        }
+    }
+}
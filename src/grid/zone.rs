@@ -47,6 +47,50 @@ impl Grid {
         self.zones = remaining;
     }
 
+    /// Remap zones and annotations after `count` rows starting at `abs_row`
+    /// left the buffer.
+    ///
+    /// Zone rows use the same numbering as the cursor (`scrollback_len +
+    /// row`), so every later row moves up by `count`. Zones with no rows
+    /// left are moved to the evicted list.
+    pub(in crate::grid) fn remove_zone_rows(&mut self, abs_row: usize, count: usize) {
+        if count == 0 {
+            return;
+        }
+        self.remove_annotation_rows(abs_row, count);
+        self.remove_row_times(abs_row, count);
+        match self.row_removals.last_mut() {
+            // Consecutive FIFO evictions collapse into one entry
+            Some((0, n)) if abs_row == 0 => *n += count,
+            _ => self.row_removals.push((abs_row, count)),
+        }
+        let removed_end = abs_row + count;
+        let remap_start = |row: usize| match row {
+            r if r >= removed_end => r - count,
+            r if r >= abs_row => abs_row,
+            r => r,
+        };
+        let remap_end = |row: usize| match row {
+            r if r >= removed_end => Some(r - count),
+            r if r >= abs_row => abs_row.checked_sub(1),
+            r => Some(r),
+        };
+
+        let mut kept = Vec::with_capacity(self.zones.len());
+        for mut zone in self.zones.drain(..) {
+            let start = remap_start(zone.abs_row_start);
+            match remap_end(zone.abs_row_end) {
+                Some(end) if end >= start => {
+                    zone.abs_row_start = start;
+                    zone.abs_row_end = end;
+                    kept.push(zone);
+                }
+                _ => self.evicted_zones.push(zone),
+            }
+        }
+        self.zones = kept;
+    }
+
     /// Clear all zones
     pub fn clear_zones(&mut self) {
         self.zones.clear();
@@ -56,4 +100,10 @@ impl Grid {
     pub fn drain_evicted_zones(&mut self) -> Vec<Zone> {
         std::mem::take(&mut self.evicted_zones)
     }
+
+    /// Drain the rows removed since the last call, as `(abs_row, count)` in
+    /// the order they were removed
+    pub(crate) fn drain_row_removals(&mut self) -> Vec<(usize, usize)> {
+        std::mem::take(&mut self.row_removals)
+    }
 }
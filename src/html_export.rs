@@ -1,23 +1,261 @@
 //! HTML export functionality for terminal content
+//!
+//! Colors are resolved through an [`HtmlExportConfig`]: the 16 ANSI colors
+//! map to its palette and cells in the default colors inherit the page
+//! colors. [`Terminal::export_html_themed`] fills the palette and default
+//! colors from the terminal's runtime theme (OSC 4/10/11 changes included).
+
+use std::collections::HashMap;
 
 use crate::cell::Cell;
-use crate::color::Color;
+use crate::color::{Color, NamedColor};
 use crate::grid::Grid;
+use crate::terminal::{html_escape, Terminal};
+
+/// Options for a themed HTML export
+#[derive(Debug, Clone, PartialEq)]
+pub struct HtmlExportConfig {
+    /// Emit a complete document with a stylesheet instead of bare lines
+    pub include_styles: bool,
+    /// Colors for ANSI indices 0-15; `None` uses the terminal's palette
+    pub palette: Option<[Color; 16]>,
+    /// Default foreground; `None` uses the terminal's
+    pub default_fg: Option<Color>,
+    /// Default background; `None` uses the terminal's
+    pub default_bg: Option<Color>,
+    /// Declare the palette and default colors as CSS variables
+    /// (`--term-color-0` to `--term-color-15`, `--term-fg`, `--term-bg`)
+    /// and reference them from cell styles, so a stylesheet can retheme the
+    /// page. Every reference carries the exported color as its fallback.
+    pub css_variables: bool,
+    /// Wrap OSC 8 hyperlinks in `<a href>` elements. Only links with a
+    /// scheme in [`HTML_LINK_SCHEMES`] become anchors; any other link (e.g.
+    /// `javascript:` from untrusted output) is written as plain text.
+    pub hyperlinks: bool,
+}
+
+impl Default for HtmlExportConfig {
+    fn default() -> Self {
+        Self {
+            include_styles: true,
+            palette: None,
+            default_fg: None,
+            default_bg: None,
+            css_variables: true,
+            hyperlinks: false,
+        }
+    }
+}
+
+impl HtmlExportConfig {
+    /// Create a config with default values
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enable/disable the full document with stylesheet
+    pub fn with_styles(mut self, include: bool) -> Self {
+        self.include_styles = include;
+        self
+    }
+
+    /// Map the 16 ANSI colors to `palette`
+    pub fn with_palette(mut self, palette: [Color; 16]) -> Self {
+        self.palette = Some(palette);
+        self
+    }
+
+    /// Set the default foreground color
+    pub fn with_default_fg(mut self, color: Color) -> Self {
+        self.default_fg = Some(color);
+        self
+    }
+
+    /// Set the default background color
+    pub fn with_default_bg(mut self, color: Color) -> Self {
+        self.default_bg = Some(color);
+        self
+    }
+
+    /// Enable/disable CSS variables for the palette and default colors
+    pub fn with_css_variables(mut self, enable: bool) -> Self {
+        self.css_variables = enable;
+        self
+    }
+
+    /// Enable/disable `<a>` elements for OSC 8 hyperlinks
+    pub fn with_hyperlinks(mut self, enable: bool) -> Self {
+        self.hyperlinks = enable;
+        self
+    }
+}
+
+/// Colors and link targets an export is rendered with
+struct HtmlTheme<'a> {
+    palette: [Color; 16],
+    fg: Color,
+    bg: Color,
+    css_variables: bool,
+    links: Option<&'a HashMap<u32, String>>,
+}
+
+impl<'a> HtmlTheme<'a> {
+    /// Resolve `config`, using the built-in palette for unset colors
+    fn new(config: &HtmlExportConfig, links: &'a HashMap<u32, String>) -> Self {
+        Self {
+            palette: config
+                .palette
+                .unwrap_or_else(|| std::array::from_fn(|i| Color::Indexed(i as u8))),
+            fg: config.default_fg.unwrap_or(Color::Named(NamedColor::White)),
+            bg: config.default_bg.unwrap_or(Color::Named(NamedColor::Black)),
+            css_variables: config.css_variables,
+            links: config.hyperlinks.then_some(links),
+        }
+    }
+
+    fn rgb(&self, color: Color) -> (u8, u8, u8) {
+        match color {
+            Color::Named(named) => self.palette[named as usize].to_rgb(),
+            Color::Indexed(idx) if idx < 16 => self.palette[idx as usize].to_rgb(),
+            other => other.to_rgb(),
+        }
+    }
+
+    /// CSS value for `color`, through `--term-color-N` for palette colors
+    fn css_color(&self, color: Color) -> String {
+        let (r, g, b) = self.rgb(color);
+        let index = match color {
+            Color::Named(named) => Some(named as usize),
+            Color::Indexed(idx) if idx < 16 => Some(idx as usize),
+            _ => None,
+        };
+        match index {
+            Some(i) if self.css_variables => {
+                format!("var(--term-color-{}, rgb({}, {}, {}))", i, r, g, b)
+            }
+            _ => format!("rgb({}, {}, {})", r, g, b),
+        }
+    }
+
+    /// CSS value for a default color, through `--term-fg`/`--term-bg`
+    fn css_default(&self, is_bg: bool) -> String {
+        let (name, color) = if is_bg {
+            ("bg", self.bg)
+        } else {
+            ("fg", self.fg)
+        };
+        let (r, g, b) = self.rgb(color);
+        if self.css_variables {
+            format!("var(--term-{}, rgb({}, {}, {}))", name, r, g, b)
+        } else {
+            format!("rgb({}, {}, {})", r, g, b)
+        }
+    }
+
+    /// Foreground and background of a cell after reverse video; `None`
+    /// leaves the default color to the page
+    fn cell_colors(&self, cell: &Cell) -> (Option<String>, Option<String>) {
+        let fg = match cell.fg {
+            Color::Named(NamedColor::White) => None,
+            color => Some(self.css_color(color)),
+        };
+        let bg = match cell.bg {
+            Color::Named(NamedColor::Black) => None,
+            color => Some(self.css_color(color)),
+        };
+        if cell.flags.reverse() {
+            (
+                Some(bg.unwrap_or_else(|| self.css_default(true))),
+                Some(fg.unwrap_or_else(|| self.css_default(false))),
+            )
+        } else {
+            (fg, bg)
+        }
+    }
+
+    /// `:root` rule declaring the CSS variables
+    fn root_rule(&self) -> String {
+        let mut rule = String::from(":root {");
+        for (name, color) in [("fg", self.fg), ("bg", self.bg)] {
+            let (r, g, b) = self.rgb(color);
+            rule.push_str(&format!(" --term-{}: rgb({}, {}, {});", name, r, g, b));
+        }
+        for (i, color) in self.palette.iter().enumerate() {
+            let (r, g, b) = color.to_rgb();
+            rule.push_str(&format!(" --term-color-{}: rgb({}, {}, {});", i, r, g, b));
+        }
+        rule.push_str(" }\n");
+        rule
+    }
+
+    /// Target of the OSC 8 link on `cell`, if links are exported and the
+    /// link has a safe scheme
+    fn link(&self, cell: &Cell) -> Option<&'a str> {
+        let id = cell.flags.hyperlink_id?;
+        let url = self.links?.get(&id.get())?;
+        is_safe_link(url).then_some(url.as_str())
+    }
+}
+
+/// URL schemes exported as `<a href>` links
+pub const HTML_LINK_SCHEMES: &[&str] = &["http", "https", "mailto", "file"];
+
+/// Whether `url` starts with one of [`HTML_LINK_SCHEMES`] followed by `:`
+///
+/// The scheme must be the very first thing in the URL, so leading spaces or
+/// control characters (which browsers strip) cannot smuggle in another one.
+fn is_safe_link(url: &str) -> bool {
+    url.split_once(':').is_some_and(|(scheme, _)| {
+        HTML_LINK_SCHEMES
+            .iter()
+            .any(|allowed| scheme.eq_ignore_ascii_case(allowed))
+    })
+}
 
 /// Generate HTML from terminal grid
+///
+/// Uses the built-in palette without CSS variables; see
+/// [`export_html_with_config`] for theming.
 pub fn export_html(grid: &Grid, include_styles: bool) -> String {
+    let config = HtmlExportConfig::new()
+        .with_styles(include_styles)
+        .with_css_variables(false);
+    export_html_with_config(grid, &config, &HashMap::new())
+}
+
+/// Generate HTML from terminal grid (scrollback and screen) with `config`
+///
+/// `links` maps OSC 8 hyperlink ids to their URLs; it is only consulted
+/// when [`HtmlExportConfig::hyperlinks`] is set. Unset colors fall back to
+/// the built-in palette.
+pub fn export_html_with_config(
+    grid: &Grid,
+    config: &HtmlExportConfig,
+    links: &HashMap<u32, String>,
+) -> String {
+    let theme = HtmlTheme::new(config, links);
     let mut html = String::new();
 
-    if include_styles {
+    if config.include_styles {
         html.push_str("<!DOCTYPE html>\n<html>\n<head>\n");
         html.push_str("<meta charset=\"UTF-8\">\n");
         html.push_str("<style>\n");
-        html.push_str("body { background-color: #000; color: #fff; margin: 0; padding: 20px; }\n");
+        if theme.css_variables {
+            html.push_str(&theme.root_rule());
+        }
+        html.push_str(&format!(
+            "body {{ background-color: {}; color: {}; margin: 0; padding: 20px; }}\n",
+            theme.css_default(true),
+            theme.css_default(false)
+        ));
         html.push_str(
             "pre { font-family: 'Monaco', 'Menlo', 'Ubuntu Mono', 'Consolas', monospace; ",
         );
         html.push_str("font-size: 14px; line-height: 1.0; margin: 0; padding: 0; }\n");
         html.push_str(".term { display: inline; }\n");
+        if theme.links.is_some() {
+            html.push_str("a { color: inherit; }\n");
+        }
         html.push_str("</style>\n");
         html.push_str("</head>\n<body>\n<pre>\n");
     }
@@ -25,7 +263,7 @@ pub fn export_html(grid: &Grid, include_styles: bool) -> String {
     // Export scrollback
     for i in 0..grid.scrollback_len() {
         if let Some(line) = grid.scrollback_line(i) {
-            export_line_to_html(line, &mut html);
+            export_line_to_html(line, &theme, &mut html);
             html.push('\n');
         }
     }
@@ -33,32 +271,80 @@ pub fn export_html(grid: &Grid, include_styles: bool) -> String {
     // Export current screen
     for row in 0..grid.rows() {
         if let Some(line) = grid.row(row) {
-            export_line_to_html(line, &mut html);
+            export_line_to_html(line, &theme, &mut html);
             html.push('\n');
         }
     }
 
-    if include_styles {
+    if config.include_styles {
         html.push_str("</pre>\n</body>\n</html>\n");
     }
 
     html
 }
 
-fn export_line_to_html(cells: &[Cell], html: &mut String) {
+/// Generate a standalone HTML fragment for clipboard use
+///
+/// Each slice in `lines` becomes one line inside a single `<pre>` element
+/// carrying the font and default colors inline, so the fragment keeps its
+/// look when pasted into documents without the export stylesheet.
+pub fn export_html_fragment(lines: &[&[Cell]]) -> String {
+    export_html_fragment_with_config(lines, &HtmlExportConfig::new(), &HashMap::new())
+}
+
+/// [`export_html_fragment`] with the colors and links of `config`
+///
+/// Colors are always written out literally: CSS variables and
+/// `include_styles` are ignored, as pasted fragments have no stylesheet.
+pub fn export_html_fragment_with_config(
+    lines: &[&[Cell]],
+    config: &HtmlExportConfig,
+    links: &HashMap<u32, String>,
+) -> String {
+    let mut theme = HtmlTheme::new(config, links);
+    theme.css_variables = false;
+    let mut html = format!(
+        "<pre style=\"font-family: 'Monaco', 'Menlo', 'Ubuntu Mono', 'Consolas', monospace; \
+         font-size: 14px; line-height: 1.0; background-color: {}; color: {}; margin: 0;\">",
+        theme.css_default(true),
+        theme.css_default(false)
+    );
+    for (i, line) in lines.iter().enumerate() {
+        if i > 0 {
+            html.push('\n');
+        }
+        export_line_to_html(line, &theme, &mut html);
+    }
+    html.push_str("</pre>");
+    html
+}
+
+fn export_line_to_html(cells: &[Cell], theme: &HtmlTheme, html: &mut String) {
     let mut current_style: Option<String> = None;
+    let mut current_link: Option<&str> = None;
     let mut span_open = false;
 
     for cell in cells {
-        let cell_style = build_style_string(cell);
+        let cell_style = build_style_string(cell, theme);
+        let cell_link = theme.link(cell);
 
-        // Close previous span if style changed
-        if current_style.as_ref() != Some(&cell_style) {
+        // Close previous span if style or link changed
+        if current_style.as_ref() != Some(&cell_style) || current_link != cell_link {
             if span_open {
                 html.push_str("</span>");
                 span_open = false;
             }
 
+            if current_link != cell_link {
+                if current_link.is_some() {
+                    html.push_str("</a>");
+                }
+                if let Some(url) = cell_link {
+                    html.push_str(&format!("<a href=\"{}\">", html_escape(url)));
+                }
+                current_link = cell_link;
+            }
+
             // Open new span if we have styles
             if !cell_style.is_empty() {
                 html.push_str(&format!("<span class=\"term\" style=\"{}\">", cell_style));
@@ -91,23 +377,25 @@ fn export_line_to_html(cells: &[Cell], html: &mut String) {
         }
     }
 
-    // Close final span if open
+    // Close final span and link if open
     if span_open {
         html.push_str("</span>");
     }
+    if current_link.is_some() {
+        html.push_str("</a>");
+    }
 }
 
-fn build_style_string(cell: &Cell) -> String {
+fn build_style_string(cell: &Cell, theme: &HtmlTheme) -> String {
     let mut styles = Vec::new();
 
-    // Foreground color
-    if let Some((r, g, b)) = cell.fg.to_rgb_opt() {
-        styles.push(format!("color: rgb({}, {}, {})", r, g, b));
+    // Colors (after reverse video)
+    let (fg, bg) = theme.cell_colors(cell);
+    if let Some(fg) = fg {
+        styles.push(format!("color: {}", fg));
     }
-
-    // Background color
-    if let Some((r, g, b)) = cell.bg.to_rgb_opt() {
-        styles.push(format!("background-color: rgb({}, {}, {})", r, g, b));
+    if let Some(bg) = bg {
+        styles.push(format!("background-color: {}", bg));
     }
 
     // Text decoration
@@ -141,20 +429,6 @@ fn build_style_string(cell: &Cell) -> String {
         styles.push("animation: blink 1s step-start infinite".to_string());
     }
 
-    if cell.flags.reverse() {
-        // Swap fg and bg
-        if let (Some((fg_r, fg_g, fg_b)), Some((bg_r, bg_g, bg_b))) =
-            (cell.fg.to_rgb_opt(), cell.bg.to_rgb_opt())
-        {
-            styles.retain(|s| !s.starts_with("color:") && !s.starts_with("background-color:"));
-            styles.push(format!("color: rgb({}, {}, {})", bg_r, bg_g, bg_b));
-            styles.push(format!(
-                "background-color: rgb({}, {}, {})",
-                fg_r, fg_g, fg_b
-            ));
-        }
-    }
-
     if cell.flags.hidden() {
         styles.push("visibility: hidden".to_string());
     }
@@ -162,16 +436,52 @@ fn build_style_string(cell: &Cell) -> String {
     styles.join("; ")
 }
 
-impl Color {
-    /// Convert color to RGB tuple, returning None for default colors
-    #[allow(clippy::wrong_self_convention)]
-    fn to_rgb_opt(&self) -> Option<(u8, u8, u8)> {
-        match self {
-            Color::Named(_) => Some(self.to_rgb()),
-            Color::Indexed(_) => Some(self.to_rgb()),
-            Color::Rgb(r, g, b) => Some((*r, *g, *b)),
+impl Terminal {
+    /// Config carrying the terminal's runtime palette and default colors
+    pub fn html_export_config(&self) -> HtmlExportConfig {
+        HtmlExportConfig::new()
+            .with_palette(self.theme.ansi_palette)
+            .with_default_fg(self.theme.default_fg)
+            .with_default_bg(self.theme.default_bg)
+    }
+
+    /// Fill the colors `config` leaves unset from the terminal's theme
+    fn resolve_html_config(&self, config: &HtmlExportConfig) -> HtmlExportConfig {
+        HtmlExportConfig {
+            palette: config.palette.or(Some(self.theme.ansi_palette)),
+            default_fg: config.default_fg.or(Some(self.theme.default_fg)),
+            default_bg: config.default_bg.or(Some(self.theme.default_bg)),
+            ..config.clone()
         }
     }
+
+    /// Export the visible screen as styled HTML themed by `config`
+    ///
+    /// Colors left unset in `config` come from the terminal's runtime
+    /// palette and default colors, and OSC 8 links resolve through the
+    /// terminal's hyperlink table. Redaction masks apply as in the other
+    /// exports.
+    pub fn export_html_themed(&self, config: &HtmlExportConfig) -> String {
+        self.export_grid_html(&self.grid_with_scrollback(0), config)
+    }
+
+    /// Export `grid` with `config` resolved against the terminal's theme
+    pub(crate) fn export_grid_html(&self, grid: &Grid, config: &HtmlExportConfig) -> String {
+        export_html_with_config(
+            grid,
+            &self.resolve_html_config(config),
+            &self.hyperlink_state.hyperlinks,
+        )
+    }
+
+    /// HTML fragment of `lines` in the terminal's runtime colors
+    pub(crate) fn html_fragment(&self, lines: &[&[Cell]]) -> String {
+        export_html_fragment_with_config(
+            lines,
+            &self.html_export_config(),
+            &self.hyperlink_state.hyperlinks,
+        )
+    }
 }
 
 #[cfg(test)]
@@ -457,4 +767,107 @@ mod tests {
         let html = export_html(&grid, false);
         assert!(html.contains("animation: blink"));
     }
+
+    #[test]
+    fn test_config_palette_maps_ansi_colors() {
+        let mut grid = Grid::new(10, 1, 0);
+        let mut cell = Cell::new('R');
+        cell.fg = Color::Named(NamedColor::Red);
+        cell.bg = Color::Indexed(12);
+        grid.set(0, 0, cell);
+
+        let mut palette = [Color::Rgb(0, 0, 0); 16];
+        palette[1] = Color::Rgb(1, 2, 3);
+        palette[12] = Color::Rgb(4, 5, 6);
+        let config = HtmlExportConfig::new()
+            .with_styles(false)
+            .with_palette(palette);
+        let html = export_html_with_config(&grid, &config, &HashMap::new());
+        assert!(html.contains("color: var(--term-color-1, rgb(1, 2, 3))"));
+        assert!(html.contains("background-color: var(--term-color-12, rgb(4, 5, 6))"));
+    }
+
+    #[test]
+    fn test_config_emits_css_variables() {
+        let grid = Grid::new(5, 1, 0);
+        let config = HtmlExportConfig::new()
+            .with_default_fg(Color::Rgb(10, 20, 30))
+            .with_default_bg(Color::Rgb(40, 50, 60));
+        let html = export_html_with_config(&grid, &config, &HashMap::new());
+        assert!(html.contains(":root { --term-fg: rgb(10, 20, 30); --term-bg: rgb(40, 50, 60);"));
+        assert!(html.contains("--term-color-15: rgb("));
+        assert!(html.contains("background-color: var(--term-bg, rgb(40, 50, 60))"));
+    }
+
+    #[test]
+    fn test_default_colors_are_inherited() {
+        let mut grid = Grid::new(5, 1, 0);
+        grid.set(0, 0, Cell::new('x'));
+        let html = export_html(&grid, false);
+        assert!(!html.contains("<span"));
+
+        // Reverse video swaps in the default colors explicitly
+        let mut cell = Cell::new('y');
+        cell.flags.set_reverse(true);
+        grid.set(1, 0, cell);
+        let html = export_html(&grid, false);
+        assert!(html.contains("color: rgb(0, 0, 0); background-color: rgb(192, 192, 192)"));
+    }
+
+    #[test]
+    fn test_hyperlinks_become_anchors() {
+        let mut grid = Grid::new(6, 1, 0);
+        for (col, ch) in "ab\"cd".chars().enumerate() {
+            let mut cell = Cell::new(ch);
+            if (1..4).contains(&col) {
+                cell.flags.hyperlink_id = std::num::NonZeroU32::new(7);
+            }
+            grid.set(col, 0, cell);
+        }
+        let links = HashMap::from([(7, "https://x.test/?q=\"<>\"".to_string())]);
+
+        let config = HtmlExportConfig::new().with_styles(false);
+        let html = export_html_with_config(&grid, &config, &links);
+        assert!(!html.contains("<a"));
+
+        let html = export_html_with_config(&grid, &config.with_hyperlinks(true), &links);
+        assert!(html.contains("a<a href=\"https://x.test/?q=&quot;&lt;&gt;&quot;\">b&quot;c</a>d"));
+    }
+
+    #[test]
+    fn test_unsafe_link_schemes_are_plain_text() {
+        let mut grid = Grid::new(4, 1, 0);
+        for col in 0..4 {
+            let mut cell = Cell::new('x');
+            cell.flags.hyperlink_id = std::num::NonZeroU32::new(col as u32 + 1);
+            grid.set(col, 0, cell);
+        }
+        let links = HashMap::from([
+            (1, "javascript:alert(1)".to_string()),
+            (2, " https://x.test/".to_string()),
+            (3, "data:text/html,<b>".to_string()),
+            (4, "MAILTO:a@x.test".to_string()),
+        ]);
+        let config = HtmlExportConfig::new()
+            .with_styles(false)
+            .with_hyperlinks(true);
+        let html = export_html_with_config(&grid, &config, &links);
+        assert_eq!(html.matches("<a ").count(), 1);
+        assert!(html.contains("xxx<a href=\"MAILTO:a@x.test\">x</a>"));
+        assert!(!html.contains("javascript"));
+    }
+
+    #[test]
+    fn test_fragment_writes_colors_literally() {
+        let mut cell = Cell::new('g');
+        cell.fg = Color::Named(NamedColor::Green);
+        let line = [cell];
+        let config = HtmlExportConfig::new()
+            .with_palette([Color::Rgb(7, 8, 9); 16])
+            .with_default_bg(Color::Rgb(1, 1, 1));
+        let html = export_html_fragment_with_config(&[&line], &config, &HashMap::new());
+        assert!(html.contains("background-color: rgb(1, 1, 1)"));
+        assert!(html.contains("color: rgb(7, 8, 9)\">g</span>"));
+        assert!(!html.contains("var("));
+    }
 }
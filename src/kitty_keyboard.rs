@@ -0,0 +1,1057 @@
+//! Kitty keyboard protocol key encoding
+//!
+//! Turns host key events into the bytes sent to the application, following
+//! the kitty keyboard protocol's progressive enhancement flags
+//! (`CSI = flags ; mode u`). With no flags set the classic xterm/VT encoding
+//! is produced.
+//!
+//! | Flag | Value | Effect |
+//! |------|-------|--------|
+//! | Disambiguate | 1 | Esc and modified keys use `CSI code ; mods u`; keypad keys get their own codes |
+//! | Report event types | 2 | Repeat and release events (`mods:2` / `mods:3`) |
+//! | Report alternate keys | 4 | Shifted and base layout keys (`code:shifted:base`) |
+//! | Report all keys | 8 | Every key, including text keys, Enter, Tab, Backspace and modifiers, as escapes |
+//! | Report associated text | 16 | Text as a third field (only together with 8) |
+//!
+//! Enter, Tab and Backspace without modifiers keep their legacy bytes unless
+//! flag 8 is set, and keys that produce text are sent as text (with no
+//! release events) unless flag 8 is set. Lock modifiers (Caps Lock, Num
+//! Lock) are only reported with flag 8.
+//!
+//! Application keypad mode and modifyOtherKeys are not applied.
+//!
+//! # Example
+//!
+//! ```
+//! use par_term_emu_core_rust::kitty_keyboard::{
+//!     Key, KeyEncoder, KeyEvent, KeyModifiers, KeyboardFlags,
+//! };
+//!
+//! let encoder = KeyEncoder::new(KeyboardFlags::DISAMBIGUATE, false);
+//! let event = KeyEvent::new(Key::Char('c')).with_modifiers(KeyModifiers::CTRL);
+//! assert_eq!(encoder.encode(&event), b"\x1b[99;5u");
+//! ```
+
+use bitflags::bitflags;
+
+bitflags! {
+    /// Progressive enhancement flags (`CSI = flags ; mode u`)
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct KeyboardFlags: u16 {
+        /// Disambiguate escape codes
+        const DISAMBIGUATE = 1;
+        /// Report repeat and release events
+        const REPORT_EVENT_TYPES = 2;
+        /// Report shifted and base layout keys
+        const REPORT_ALTERNATE_KEYS = 4;
+        /// Report all keys as escape codes
+        const REPORT_ALL_KEYS = 8;
+        /// Report the text a key produces
+        const REPORT_ASSOCIATED_TEXT = 16;
+    }
+}
+
+bitflags! {
+    /// Modifier state of a key event (encoded as `1 + bits`)
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct KeyModifiers: u8 {
+        const SHIFT = 1;
+        const ALT = 2;
+        const CTRL = 4;
+        const SUPER = 8;
+        const HYPER = 16;
+        const META = 32;
+        const CAPS_LOCK = 64;
+        const NUM_LOCK = 128;
+    }
+}
+
+impl KeyModifiers {
+    /// Caps Lock and Num Lock
+    pub const LOCKS: KeyModifiers = KeyModifiers::CAPS_LOCK.union(KeyModifiers::NUM_LOCK);
+
+    /// Parse a modifier name ("shift", "alt", "ctrl", "super", "hyper",
+    /// "meta", "caps_lock", "num_lock"), case-insensitively
+    pub fn parse_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "shift" => Some(KeyModifiers::SHIFT),
+            "alt" | "option" => Some(KeyModifiers::ALT),
+            "ctrl" | "control" => Some(KeyModifiers::CTRL),
+            "super" | "cmd" | "command" => Some(KeyModifiers::SUPER),
+            "hyper" => Some(KeyModifiers::HYPER),
+            "meta" => Some(KeyModifiers::META),
+            "caps_lock" => Some(KeyModifiers::CAPS_LOCK),
+            "num_lock" => Some(KeyModifiers::NUM_LOCK),
+            _ => None,
+        }
+    }
+}
+
+/// Kind of key event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum KeyEventType {
+    #[default]
+    Press,
+    Repeat,
+    Release,
+}
+
+impl KeyEventType {
+    /// Parse "press", "repeat" or "release"
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "press" => Some(KeyEventType::Press),
+            "repeat" => Some(KeyEventType::Repeat),
+            "release" => Some(KeyEventType::Release),
+            _ => None,
+        }
+    }
+
+    /// Event field value (`mods:event`)
+    fn code(self) -> u8 {
+        match self {
+            KeyEventType::Press => 1,
+            KeyEventType::Repeat => 2,
+            KeyEventType::Release => 3,
+        }
+    }
+}
+
+/// Keypad keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeypadKey {
+    /// Digit 0-9
+    Digit(u8),
+    Decimal,
+    Divide,
+    Multiply,
+    Subtract,
+    Add,
+    Enter,
+    Equal,
+    Separator,
+    Left,
+    Right,
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+    Insert,
+    Delete,
+    Begin,
+}
+
+impl KeypadKey {
+    fn code(self) -> u32 {
+        match self {
+            KeypadKey::Digit(d) => 57399 + d.min(9) as u32,
+            KeypadKey::Decimal => 57409,
+            KeypadKey::Divide => 57410,
+            KeypadKey::Multiply => 57411,
+            KeypadKey::Subtract => 57412,
+            KeypadKey::Add => 57413,
+            KeypadKey::Enter => 57414,
+            KeypadKey::Equal => 57415,
+            KeypadKey::Separator => 57416,
+            KeypadKey::Left => 57417,
+            KeypadKey::Right => 57418,
+            KeypadKey::Up => 57419,
+            KeypadKey::Down => 57420,
+            KeypadKey::PageUp => 57421,
+            KeypadKey::PageDown => 57422,
+            KeypadKey::Home => 57423,
+            KeypadKey::End => 57424,
+            KeypadKey::Insert => 57425,
+            KeypadKey::Delete => 57426,
+            KeypadKey::Begin => 57427,
+        }
+    }
+
+    /// Key sent for this keypad key when it is not disambiguated
+    fn legacy(self) -> Key {
+        match self {
+            KeypadKey::Digit(d) => Key::Char((b'0' + d.min(9)) as char),
+            KeypadKey::Decimal => Key::Char('.'),
+            KeypadKey::Divide => Key::Char('/'),
+            KeypadKey::Multiply => Key::Char('*'),
+            KeypadKey::Subtract => Key::Char('-'),
+            KeypadKey::Add => Key::Char('+'),
+            KeypadKey::Enter => Key::Enter,
+            KeypadKey::Equal => Key::Char('='),
+            KeypadKey::Separator => Key::Char(','),
+            KeypadKey::Left => Key::Left,
+            KeypadKey::Right => Key::Right,
+            KeypadKey::Up => Key::Up,
+            KeypadKey::Down => Key::Down,
+            KeypadKey::PageUp => Key::PageUp,
+            KeypadKey::PageDown => Key::PageDown,
+            KeypadKey::Home => Key::Home,
+            KeypadKey::End => Key::End,
+            KeypadKey::Insert => Key::Insert,
+            KeypadKey::Delete => Key::Delete,
+            KeypadKey::Begin => Key::Keypad(KeypadKey::Begin),
+        }
+    }
+}
+
+/// Media keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MediaKey {
+    Play,
+    Pause,
+    PlayPause,
+    Reverse,
+    Stop,
+    FastForward,
+    Rewind,
+    TrackNext,
+    TrackPrevious,
+    Record,
+    LowerVolume,
+    RaiseVolume,
+    MuteVolume,
+}
+
+impl MediaKey {
+    fn code(self) -> u32 {
+        match self {
+            MediaKey::Play => 57428,
+            MediaKey::Pause => 57429,
+            MediaKey::PlayPause => 57430,
+            MediaKey::Reverse => 57431,
+            MediaKey::Stop => 57432,
+            MediaKey::FastForward => 57433,
+            MediaKey::Rewind => 57434,
+            MediaKey::TrackNext => 57435,
+            MediaKey::TrackPrevious => 57436,
+            MediaKey::Record => 57437,
+            MediaKey::LowerVolume => 57438,
+            MediaKey::RaiseVolume => 57439,
+            MediaKey::MuteVolume => 57440,
+        }
+    }
+}
+
+/// Modifier keys (only reported with [`KeyboardFlags::REPORT_ALL_KEYS`])
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModifierKey {
+    LeftShift,
+    LeftControl,
+    LeftAlt,
+    LeftSuper,
+    LeftHyper,
+    LeftMeta,
+    RightShift,
+    RightControl,
+    RightAlt,
+    RightSuper,
+    RightHyper,
+    RightMeta,
+    IsoLevel3Shift,
+    IsoLevel5Shift,
+}
+
+impl ModifierKey {
+    fn code(self) -> u32 {
+        match self {
+            ModifierKey::LeftShift => 57441,
+            ModifierKey::LeftControl => 57442,
+            ModifierKey::LeftAlt => 57443,
+            ModifierKey::LeftSuper => 57444,
+            ModifierKey::LeftHyper => 57445,
+            ModifierKey::LeftMeta => 57446,
+            ModifierKey::RightShift => 57447,
+            ModifierKey::RightControl => 57448,
+            ModifierKey::RightAlt => 57449,
+            ModifierKey::RightSuper => 57450,
+            ModifierKey::RightHyper => 57451,
+            ModifierKey::RightMeta => 57452,
+            ModifierKey::IsoLevel3Shift => 57453,
+            ModifierKey::IsoLevel5Shift => 57454,
+        }
+    }
+}
+
+/// A key on the keyboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    /// Text key, given by its unshifted character ('a', '1', ';', 'é', ...)
+    Char(char),
+    Escape,
+    Enter,
+    Tab,
+    Backspace,
+    Insert,
+    Delete,
+    Left,
+    Right,
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+    CapsLock,
+    ScrollLock,
+    NumLock,
+    PrintScreen,
+    Pause,
+    Menu,
+    /// Function key F1-F35
+    F(u8),
+    Keypad(KeypadKey),
+    Media(MediaKey),
+    Modifier(ModifierKey),
+}
+
+impl Key {
+    /// Parse a key name: a single character, or names such as "escape",
+    /// "enter", "tab", "backspace", "up", "page_up", "f5", "kp_1",
+    /// "kp_enter", "media_play", "left_shift"
+    pub fn from_name(name: &str) -> Option<Self> {
+        let mut chars = name.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Some(Key::Char(c));
+        }
+        let lower = name.to_ascii_lowercase().replace('-', "_");
+        let key = match lower.as_str() {
+            "space" => Key::Char(' '),
+            "escape" | "esc" => Key::Escape,
+            "enter" | "return" => Key::Enter,
+            "tab" => Key::Tab,
+            "backspace" => Key::Backspace,
+            "insert" | "ins" => Key::Insert,
+            "delete" | "del" => Key::Delete,
+            "left" => Key::Left,
+            "right" => Key::Right,
+            "up" => Key::Up,
+            "down" => Key::Down,
+            "page_up" | "pageup" | "pgup" => Key::PageUp,
+            "page_down" | "pagedown" | "pgdn" => Key::PageDown,
+            "home" => Key::Home,
+            "end" => Key::End,
+            "caps_lock" => Key::CapsLock,
+            "scroll_lock" => Key::ScrollLock,
+            "num_lock" => Key::NumLock,
+            "print_screen" => Key::PrintScreen,
+            "pause" => Key::Pause,
+            "menu" => Key::Menu,
+            "kp_decimal" => Key::Keypad(KeypadKey::Decimal),
+            "kp_divide" => Key::Keypad(KeypadKey::Divide),
+            "kp_multiply" => Key::Keypad(KeypadKey::Multiply),
+            "kp_subtract" => Key::Keypad(KeypadKey::Subtract),
+            "kp_add" => Key::Keypad(KeypadKey::Add),
+            "kp_enter" => Key::Keypad(KeypadKey::Enter),
+            "kp_equal" => Key::Keypad(KeypadKey::Equal),
+            "kp_separator" => Key::Keypad(KeypadKey::Separator),
+            "kp_left" => Key::Keypad(KeypadKey::Left),
+            "kp_right" => Key::Keypad(KeypadKey::Right),
+            "kp_up" => Key::Keypad(KeypadKey::Up),
+            "kp_down" => Key::Keypad(KeypadKey::Down),
+            "kp_page_up" => Key::Keypad(KeypadKey::PageUp),
+            "kp_page_down" => Key::Keypad(KeypadKey::PageDown),
+            "kp_home" => Key::Keypad(KeypadKey::Home),
+            "kp_end" => Key::Keypad(KeypadKey::End),
+            "kp_insert" => Key::Keypad(KeypadKey::Insert),
+            "kp_delete" => Key::Keypad(KeypadKey::Delete),
+            "kp_begin" => Key::Keypad(KeypadKey::Begin),
+            "media_play" => Key::Media(MediaKey::Play),
+            "media_pause" => Key::Media(MediaKey::Pause),
+            "media_play_pause" => Key::Media(MediaKey::PlayPause),
+            "media_reverse" => Key::Media(MediaKey::Reverse),
+            "media_stop" => Key::Media(MediaKey::Stop),
+            "media_fast_forward" => Key::Media(MediaKey::FastForward),
+            "media_rewind" => Key::Media(MediaKey::Rewind),
+            "media_track_next" => Key::Media(MediaKey::TrackNext),
+            "media_track_previous" => Key::Media(MediaKey::TrackPrevious),
+            "media_record" => Key::Media(MediaKey::Record),
+            "lower_volume" => Key::Media(MediaKey::LowerVolume),
+            "raise_volume" => Key::Media(MediaKey::RaiseVolume),
+            "mute_volume" => Key::Media(MediaKey::MuteVolume),
+            "left_shift" => Key::Modifier(ModifierKey::LeftShift),
+            "left_control" | "left_ctrl" => Key::Modifier(ModifierKey::LeftControl),
+            "left_alt" => Key::Modifier(ModifierKey::LeftAlt),
+            "left_super" => Key::Modifier(ModifierKey::LeftSuper),
+            "left_hyper" => Key::Modifier(ModifierKey::LeftHyper),
+            "left_meta" => Key::Modifier(ModifierKey::LeftMeta),
+            "right_shift" => Key::Modifier(ModifierKey::RightShift),
+            "right_control" | "right_ctrl" => Key::Modifier(ModifierKey::RightControl),
+            "right_alt" => Key::Modifier(ModifierKey::RightAlt),
+            "right_super" => Key::Modifier(ModifierKey::RightSuper),
+            "right_hyper" => Key::Modifier(ModifierKey::RightHyper),
+            "right_meta" => Key::Modifier(ModifierKey::RightMeta),
+            "iso_level3_shift" => Key::Modifier(ModifierKey::IsoLevel3Shift),
+            "iso_level5_shift" => Key::Modifier(ModifierKey::IsoLevel5Shift),
+            other => {
+                if let Some(n) = other.strip_prefix("kp_") {
+                    let digit = n.parse::<u8>().ok().filter(|d| *d <= 9)?;
+                    Key::Keypad(KeypadKey::Digit(digit))
+                } else if let Some(n) = other.strip_prefix('f') {
+                    let n = n.parse::<u8>().ok().filter(|n| (1..=35).contains(n))?;
+                    Key::F(n)
+                } else {
+                    return None;
+                }
+            }
+        };
+        Some(key)
+    }
+
+    /// Escape code number and final byte for non-text keys
+    fn functional(self) -> Option<(u32, char)> {
+        let code = match self {
+            Key::Escape => (27, 'u'),
+            Key::Enter => (13, 'u'),
+            Key::Tab => (9, 'u'),
+            Key::Backspace => (127, 'u'),
+            Key::Insert => (2, '~'),
+            Key::Delete => (3, '~'),
+            Key::Left => (1, 'D'),
+            Key::Right => (1, 'C'),
+            Key::Up => (1, 'A'),
+            Key::Down => (1, 'B'),
+            Key::PageUp => (5, '~'),
+            Key::PageDown => (6, '~'),
+            Key::Home => (1, 'H'),
+            Key::End => (1, 'F'),
+            Key::CapsLock => (57358, 'u'),
+            Key::ScrollLock => (57359, 'u'),
+            Key::NumLock => (57360, 'u'),
+            Key::PrintScreen => (57361, 'u'),
+            Key::Pause => (57362, 'u'),
+            Key::Menu => (57363, 'u'),
+            Key::F(1) => (1, 'P'),
+            Key::F(2) => (1, 'Q'),
+            Key::F(3) => (13, '~'),
+            Key::F(4) => (1, 'S'),
+            Key::F(5) => (15, '~'),
+            Key::F(6) => (17, '~'),
+            Key::F(7) => (18, '~'),
+            Key::F(8) => (19, '~'),
+            Key::F(9) => (20, '~'),
+            Key::F(10) => (21, '~'),
+            Key::F(11) => (23, '~'),
+            Key::F(12) => (24, '~'),
+            Key::F(n @ 13..=35) => (57376 + (n - 13) as u32, 'u'),
+            Key::F(_) => return None,
+            Key::Keypad(k) => (k.code(), 'u'),
+            Key::Media(k) => (k.code(), 'u'),
+            Key::Modifier(k) => (k.code(), 'u'),
+            Key::Char(c) => (c as u32, 'u'),
+        };
+        Some(code)
+    }
+}
+
+/// A key press, repeat or release
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyEvent {
+    pub key: Key,
+    pub modifiers: KeyModifiers,
+    pub event_type: KeyEventType,
+    /// Character the key produces with Shift (derived for ASCII letters)
+    pub shifted_key: Option<char>,
+    /// Key at the same position on a US PC-101 layout
+    pub base_layout_key: Option<char>,
+    /// Text the event produces (derived from the key when `None`)
+    pub text: Option<String>,
+}
+
+impl KeyEvent {
+    /// A press of `key` without modifiers
+    pub fn new(key: Key) -> Self {
+        Self {
+            key,
+            modifiers: KeyModifiers::empty(),
+            event_type: KeyEventType::Press,
+            shifted_key: None,
+            base_layout_key: None,
+            text: None,
+        }
+    }
+
+    pub fn with_modifiers(mut self, modifiers: KeyModifiers) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+
+    pub fn with_event_type(mut self, event_type: KeyEventType) -> Self {
+        self.event_type = event_type;
+        self
+    }
+
+    pub fn with_shifted_key(mut self, shifted_key: char) -> Self {
+        self.shifted_key = Some(shifted_key);
+        self
+    }
+
+    pub fn with_base_layout_key(mut self, base_layout_key: char) -> Self {
+        self.base_layout_key = Some(base_layout_key);
+        self
+    }
+
+    pub fn with_text(mut self, text: impl Into<String>) -> Self {
+        self.text = Some(text.into());
+        self
+    }
+}
+
+/// Encodes key events for the current keyboard mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct KeyEncoder {
+    /// Active progressive enhancement flags
+    pub flags: KeyboardFlags,
+    /// DECCKM: unmodified cursor keys send SS3 in legacy mode
+    pub application_cursor: bool,
+}
+
+/// Modifiers that stop a key from producing text
+const TEXT_BLOCKING: KeyModifiers = KeyModifiers::ALT
+    .union(KeyModifiers::CTRL)
+    .union(KeyModifiers::SUPER)
+    .union(KeyModifiers::HYPER)
+    .union(KeyModifiers::META);
+
+impl KeyEncoder {
+    pub fn new(flags: KeyboardFlags, application_cursor: bool) -> Self {
+        Self {
+            flags,
+            application_cursor,
+        }
+    }
+
+    fn disambiguate(&self) -> bool {
+        self.flags
+            .intersects(KeyboardFlags::DISAMBIGUATE | KeyboardFlags::REPORT_ALL_KEYS)
+    }
+
+    fn report_all(&self) -> bool {
+        self.flags.contains(KeyboardFlags::REPORT_ALL_KEYS)
+    }
+
+    fn report_events(&self) -> bool {
+        self.flags.contains(KeyboardFlags::REPORT_EVENT_TYPES)
+    }
+
+    /// Bytes to send for `event` (empty if the event is not reported)
+    pub fn encode(&self, event: &KeyEvent) -> Vec<u8> {
+        if event.event_type == KeyEventType::Release && !self.report_events() {
+            return Vec::new();
+        }
+        match event.key {
+            Key::Char(c) => self.encode_text_key(event, c),
+            Key::Keypad(k) if !self.disambiguate() => {
+                let legacy = k.legacy();
+                if legacy == event.key {
+                    self.encode_functional(event)
+                } else {
+                    self.encode(&KeyEvent {
+                        key: legacy,
+                        ..event.clone()
+                    })
+                }
+            }
+            Key::Modifier(_) if !self.report_all() => Vec::new(),
+            _ => self.encode_functional(event),
+        }
+    }
+
+    /// Modifiers used for decisions (locks never change the encoding form)
+    fn active(&self, event: &KeyEvent) -> KeyModifiers {
+        event.modifiers - KeyModifiers::LOCKS
+    }
+
+    /// Modifiers written into escape codes
+    fn encoded_mods(&self, event: &KeyEvent) -> u8 {
+        if self.report_all() {
+            event.modifiers.bits()
+        } else {
+            self.active(event).bits()
+        }
+    }
+
+    fn encode_functional(&self, event: &KeyEvent) -> Vec<u8> {
+        let Some((mut number, mut trailer)) = event.key.functional() else {
+            return Vec::new();
+        };
+        let active = self.active(event);
+        let action = event.event_type;
+        let disambiguate = self.disambiguate();
+        let legacy_mode = !disambiguate && !self.report_events();
+        let pressed = action != KeyEventType::Release;
+
+        if legacy_mode && active.is_empty() {
+            let ss3 = match event.key {
+                Key::Up | Key::Down | Key::Right | Key::Left | Key::Home | Key::End
+                    if self.application_cursor =>
+                {
+                    Some(trailer)
+                }
+                Key::F(1) => Some('P'),
+                Key::F(2) => Some('Q'),
+                Key::F(3) => Some('R'),
+                Key::F(4) => Some('S'),
+                _ => None,
+            };
+            if let Some(c) = ss3 {
+                return vec![0x1b, b'O', c as u8];
+            }
+        }
+
+        if matches!(
+            event.key,
+            Key::Enter | Key::Tab | Key::Backspace | Key::Escape
+        ) {
+            let plain = active.is_empty()
+                && !self.report_all()
+                && (event.key != Key::Escape || !disambiguate);
+            if plain || (!disambiguate && pressed) {
+                if !pressed {
+                    return Vec::new();
+                }
+                return legacy_control_key(event.key, active);
+            }
+        }
+
+        if !disambiguate {
+            match event.key {
+                // xterm's legacy F3 and the F16 code for Menu
+                Key::F(3) => (number, trailer) = (1, 'R'),
+                Key::Menu => (number, trailer) = (29, '~'),
+                Key::Keypad(KeypadKey::Begin) => (number, trailer) = (1, 'E'),
+                // No legacy representation
+                Key::CapsLock
+                | Key::ScrollLock
+                | Key::NumLock
+                | Key::PrintScreen
+                | Key::Pause
+                | Key::F(13..)
+                | Key::Media(_)
+                | Key::Modifier(_)
+                    if legacy_mode =>
+                {
+                    return Vec::new()
+                }
+                _ => {}
+            }
+        }
+
+        self.csi(
+            number,
+            None,
+            self.encoded_mods(event),
+            action,
+            None,
+            trailer,
+        )
+    }
+
+    fn encode_text_key(&self, event: &KeyEvent, c: char) -> Vec<u8> {
+        let (base, shifted) = if c.is_ascii_uppercase() {
+            (c.to_ascii_lowercase(), Some(c))
+        } else {
+            let derived = c.is_ascii_lowercase().then(|| c.to_ascii_uppercase());
+            (c, event.shifted_key.or(derived))
+        };
+        let active = self.active(event);
+        let pressed = event.event_type != KeyEventType::Release;
+
+        let text = event.text.clone().or_else(|| {
+            if active.intersects(TEXT_BLOCKING) {
+                return None;
+            }
+            let shift = active.contains(KeyModifiers::SHIFT)
+                != (event.modifiers.contains(KeyModifiers::CAPS_LOCK) && base.is_alphabetic());
+            let ch = if shift { shifted.unwrap_or(base) } else { base };
+            Some(ch.to_string())
+        });
+
+        if !self.report_all() && (active - KeyModifiers::SHIFT).is_empty() {
+            // Text-producing keys: no repeat/release reporting without flag 8
+            if !pressed {
+                return Vec::new();
+            }
+            return text.unwrap_or_else(|| base.to_string()).into_bytes();
+        }
+
+        if !self.disambiguate() && pressed {
+            let ch = if active.contains(KeyModifiers::SHIFT) {
+                shifted.unwrap_or(base)
+            } else {
+                base
+            };
+            let mut bytes = Vec::new();
+            if active.contains(KeyModifiers::ALT) {
+                bytes.push(0x1b);
+            }
+            match ctrl_byte(ch).filter(|_| active.contains(KeyModifiers::CTRL)) {
+                Some(byte) => bytes.push(byte),
+                None => bytes.extend_from_slice(ch.to_string().as_bytes()),
+            }
+            return bytes;
+        }
+
+        let alternates = self
+            .flags
+            .contains(KeyboardFlags::REPORT_ALTERNATE_KEYS)
+            .then(|| {
+                let shifted = shifted
+                    .filter(|s| active.contains(KeyModifiers::SHIFT) && *s != base)
+                    .map(|s| s as u32);
+                let base_layout = event
+                    .base_layout_key
+                    .filter(|b| *b != base)
+                    .map(|b| b as u32);
+                (shifted, base_layout)
+            })
+            .filter(|(s, b)| s.is_some() || b.is_some());
+
+        let text = text.filter(|t| {
+            self.report_all()
+                && self.flags.contains(KeyboardFlags::REPORT_ASSOCIATED_TEXT)
+                && pressed
+                && !t.is_empty()
+                && !t.chars().any(char::is_control)
+        });
+
+        self.csi(
+            base as u32,
+            alternates,
+            self.encoded_mods(event),
+            event.event_type,
+            text.as_deref(),
+            'u',
+        )
+    }
+
+    /// `CSI number[:shifted[:base]] [; mods[:event]] [; text] trailer`
+    fn csi(
+        &self,
+        number: u32,
+        alternates: Option<(Option<u32>, Option<u32>)>,
+        mods: u8,
+        action: KeyEventType,
+        text: Option<&str>,
+        trailer: char,
+    ) -> Vec<u8> {
+        let add_action = self.report_events() && action != KeyEventType::Press;
+        let mods_field = mods != 0 || add_action;
+
+        let mut out = String::from("\x1b[");
+        if number != 1 || alternates.is_some() || mods_field || text.is_some() {
+            out.push_str(&number.to_string());
+        }
+        if let Some((shifted, base_layout)) = alternates {
+            out.push(':');
+            if let Some(shifted) = shifted {
+                out.push_str(&shifted.to_string());
+            }
+            if let Some(base_layout) = base_layout {
+                out.push_str(&format!(":{}", base_layout));
+            }
+        }
+        if mods_field || text.is_some() {
+            out.push(';');
+            if mods_field {
+                out.push_str(&(mods as u32 + 1).to_string());
+            }
+            if add_action {
+                out.push_str(&format!(":{}", action.code()));
+            }
+        }
+        if let Some(text) = text {
+            let codepoints: Vec<String> = text.chars().map(|c| (c as u32).to_string()).collect();
+            out.push(';');
+            out.push_str(&codepoints.join(":"));
+        }
+        out.push(trailer);
+        out.into_bytes()
+    }
+}
+
+/// Legacy bytes for Enter, Tab, Backspace and Escape
+fn legacy_control_key(key: Key, active: KeyModifiers) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    if active.contains(KeyModifiers::ALT) {
+        bytes.push(0x1b);
+    }
+    match key {
+        Key::Enter => bytes.push(b'\r'),
+        Key::Tab if active.contains(KeyModifiers::SHIFT) => bytes.extend_from_slice(b"\x1b[Z"),
+        Key::Tab => bytes.push(b'\t'),
+        Key::Backspace if active.contains(KeyModifiers::CTRL) => bytes.push(0x08),
+        Key::Backspace => bytes.push(0x7f),
+        _ => bytes.push(0x1b),
+    }
+    bytes
+}
+
+/// Control character produced by Ctrl+`c` in the legacy encoding
+pub(crate) fn ctrl_byte(c: char) -> Option<u8> {
+    match c {
+        'a'..='z' => Some(c as u8 - b'a' + 1),
+        'A'..='Z' => Some(c as u8 - b'A' + 1),
+        ' ' | '@' | '2' => Some(0),
+        '[' | '3' => Some(0x1b),
+        '\\' | '4' => Some(0x1c),
+        ']' | '5' => Some(0x1d),
+        '^' | '6' | '~' => Some(0x1e),
+        '_' | '7' | '/' | '-' => Some(0x1f),
+        '8' | '?' => Some(0x7f),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enc(flags: u16, event: KeyEvent) -> Vec<u8> {
+        KeyEncoder::new(KeyboardFlags::from_bits_truncate(flags), false).encode(&event)
+    }
+
+    fn key(k: Key) -> KeyEvent {
+        KeyEvent::new(k)
+    }
+
+    fn ch(c: char, mods: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(Key::Char(c)).with_modifiers(mods)
+    }
+
+    const CTRL: KeyModifiers = KeyModifiers::CTRL;
+    const SHIFT: KeyModifiers = KeyModifiers::SHIFT;
+    const ALT: KeyModifiers = KeyModifiers::ALT;
+
+    #[test]
+    fn test_legacy_text_keys() {
+        assert_eq!(enc(0, ch('a', KeyModifiers::empty())), b"a");
+        assert_eq!(enc(0, ch('a', SHIFT)), b"A");
+        assert_eq!(enc(0, ch('a', CTRL)), b"\x01");
+        assert_eq!(enc(0, ch('a', ALT)), b"\x1ba");
+        assert_eq!(enc(0, ch('a', CTRL | ALT)), b"\x1b\x01");
+        assert_eq!(enc(0, ch(' ', CTRL)), b"\x00");
+        assert_eq!(enc(0, ch('[', CTRL)), b"\x1b");
+        assert_eq!(enc(0, ch('1', CTRL)), b"1");
+        assert_eq!(enc(0, ch('é', KeyModifiers::empty())), "é".as_bytes());
+        assert_eq!(enc(0, ch('a', KeyModifiers::CAPS_LOCK)), b"A");
+        // Releases are never reported in legacy mode
+        assert!(enc(0, ch('a', CTRL).with_event_type(KeyEventType::Release)).is_empty());
+    }
+
+    #[test]
+    fn test_legacy_functional_keys() {
+        assert_eq!(enc(0, key(Key::Escape)), b"\x1b");
+        assert_eq!(enc(0, key(Key::Enter)), b"\r");
+        assert_eq!(enc(0, key(Key::Tab)), b"\t");
+        assert_eq!(enc(0, key(Key::Tab).with_modifiers(SHIFT)), b"\x1b[Z");
+        assert_eq!(enc(0, key(Key::Backspace)), b"\x7f");
+        assert_eq!(enc(0, key(Key::Backspace).with_modifiers(CTRL)), b"\x08");
+        assert_eq!(enc(0, key(Key::Enter).with_modifiers(ALT)), b"\x1b\r");
+        assert_eq!(enc(0, key(Key::Up)), b"\x1b[A");
+        assert_eq!(enc(0, key(Key::Up).with_modifiers(CTRL)), b"\x1b[1;5A");
+        assert_eq!(enc(0, key(Key::Home)), b"\x1b[H");
+        assert_eq!(enc(0, key(Key::F(1))), b"\x1bOP");
+        assert_eq!(enc(0, key(Key::F(3))), b"\x1bOR");
+        assert_eq!(enc(0, key(Key::F(3)).with_modifiers(SHIFT)), b"\x1b[1;2R");
+        assert_eq!(enc(0, key(Key::F(5))), b"\x1b[15~");
+        assert_eq!(enc(0, key(Key::F(12)).with_modifiers(ALT)), b"\x1b[24;3~");
+        assert_eq!(enc(0, key(Key::Delete)), b"\x1b[3~");
+        assert_eq!(enc(0, key(Key::Menu)), b"\x1b[29~");
+        assert!(enc(0, key(Key::F(13))).is_empty());
+        assert!(enc(0, key(Key::CapsLock)).is_empty());
+        assert!(enc(0, key(Key::Modifier(ModifierKey::LeftShift))).is_empty());
+
+        let app = KeyEncoder::new(KeyboardFlags::empty(), true);
+        assert_eq!(app.encode(&key(Key::Up)), b"\x1bOA");
+        assert_eq!(app.encode(&key(Key::End)), b"\x1bOF");
+        assert_eq!(
+            app.encode(&key(Key::Up).with_modifiers(SHIFT)),
+            b"\x1b[1;2A"
+        );
+    }
+
+    #[test]
+    fn test_legacy_keypad() {
+        assert_eq!(enc(0, key(Key::Keypad(KeypadKey::Digit(7)))), b"7");
+        assert_eq!(enc(0, key(Key::Keypad(KeypadKey::Enter))), b"\r");
+        assert_eq!(enc(0, key(Key::Keypad(KeypadKey::Left))), b"\x1b[D");
+        assert_eq!(enc(0, key(Key::Keypad(KeypadKey::Begin))), b"\x1b[E");
+    }
+
+    #[test]
+    fn test_disambiguate() {
+        assert_eq!(enc(1, key(Key::Escape)), b"\x1b[27u");
+        assert_eq!(enc(1, ch('a', KeyModifiers::empty())), b"a");
+        assert_eq!(enc(1, ch('a', SHIFT)), b"A");
+        assert_eq!(enc(1, ch('c', CTRL)), b"\x1b[99;5u");
+        assert_eq!(enc(1, ch('a', ALT)), b"\x1b[97;3u");
+        assert_eq!(enc(1, ch('a', CTRL | SHIFT)), b"\x1b[97;6u");
+        assert_eq!(enc(1, ch('a', SHIFT | ALT)), b"\x1b[97;4u");
+        // Enter, Tab and Backspace keep their legacy bytes when unmodified
+        assert_eq!(enc(1, key(Key::Enter)), b"\r");
+        assert_eq!(enc(1, key(Key::Tab)), b"\t");
+        assert_eq!(enc(1, key(Key::Backspace)), b"\x7f");
+        assert_eq!(enc(1, key(Key::Enter).with_modifiers(SHIFT)), b"\x1b[13;2u");
+        assert_eq!(enc(1, key(Key::Tab).with_modifiers(SHIFT)), b"\x1b[9;2u");
+        assert_eq!(
+            enc(1, key(Key::Backspace).with_modifiers(CTRL)),
+            b"\x1b[127;5u"
+        );
+        // Functional keys
+        assert_eq!(enc(1, key(Key::Up)), b"\x1b[A");
+        assert_eq!(enc(1, key(Key::F(1))), b"\x1b[P");
+        assert_eq!(enc(1, key(Key::F(3))), b"\x1b[13~");
+        assert_eq!(enc(1, key(Key::F(13))), b"\x1b[57376u");
+        assert_eq!(enc(1, key(Key::Menu)), b"\x1b[57363u");
+        assert_eq!(enc(1, key(Key::CapsLock)), b"\x1b[57358u");
+        assert_eq!(enc(1, key(Key::Media(MediaKey::PlayPause))), b"\x1b[57430u");
+        assert_eq!(
+            enc(1, key(Key::Keypad(KeypadKey::Digit(1)))),
+            b"\x1b[57400u"
+        );
+        assert_eq!(enc(1, key(Key::Keypad(KeypadKey::Enter))), b"\x1b[57414u");
+        // Lock modifiers do not change the encoding
+        assert_eq!(enc(1, ch('a', KeyModifiers::NUM_LOCK)), b"a");
+        assert_eq!(
+            enc(1, ch('a', CTRL | KeyModifiers::NUM_LOCK)),
+            b"\x1b[97;5u"
+        );
+        // Modifier keys are only reported with flag 8
+        assert!(enc(1, key(Key::Modifier(ModifierKey::LeftShift))).is_empty());
+        // DECCKM does not apply once keys are disambiguated
+        let encoder = KeyEncoder::new(KeyboardFlags::DISAMBIGUATE, true);
+        assert_eq!(encoder.encode(&key(Key::Up)), b"\x1b[A");
+    }
+
+    #[test]
+    fn test_event_types() {
+        let release = KeyEventType::Release;
+        let repeat = KeyEventType::Repeat;
+        assert_eq!(
+            enc(3, ch('a', CTRL).with_event_type(repeat)),
+            b"\x1b[97;5:2u"
+        );
+        assert_eq!(
+            enc(3, ch('a', CTRL).with_event_type(release)),
+            b"\x1b[97;5:3u"
+        );
+        assert_eq!(enc(3, ch('a', CTRL)), b"\x1b[97;5u");
+        assert_eq!(
+            enc(3, key(Key::Left).with_event_type(release)),
+            b"\x1b[1;1:3D"
+        );
+        assert_eq!(
+            enc(3, key(Key::Escape).with_event_type(release)),
+            b"\x1b[27;1:3u"
+        );
+        assert_eq!(
+            enc(3, key(Key::F(5)).with_event_type(repeat)),
+            b"\x1b[15;1:2~"
+        );
+        // Text keys: repeats send the text, releases are not reported
+        assert_eq!(
+            enc(3, ch('a', KeyModifiers::empty()).with_event_type(repeat)),
+            b"a"
+        );
+        assert!(enc(3, ch('a', SHIFT).with_event_type(release)).is_empty());
+        assert!(enc(3, key(Key::Enter).with_event_type(release)).is_empty());
+        // Without flag 2 releases are dropped
+        assert!(enc(1, ch('a', CTRL).with_event_type(release)).is_empty());
+    }
+
+    #[test]
+    fn test_alternate_keys() {
+        assert_eq!(enc(5, ch('a', CTRL | SHIFT)), b"\x1b[97:65;6u");
+        assert_eq!(enc(5, ch('a', CTRL)), b"\x1b[97;5u");
+        // Shifted key from the layout, and the US layout key of a Cyrillic letter
+        assert_eq!(
+            enc(5, ch('1', CTRL | SHIFT).with_shifted_key('!')),
+            b"\x1b[49:33;6u"
+        );
+        assert_eq!(
+            enc(5, ch('с', CTRL).with_base_layout_key('c')),
+            "\x1b[1089::99;5u".as_bytes()
+        );
+    }
+
+    #[test]
+    fn test_report_all_keys() {
+        assert_eq!(enc(8, ch('a', KeyModifiers::empty())), b"\x1b[97u");
+        assert_eq!(enc(8, ch('a', SHIFT)), b"\x1b[97;2u");
+        assert_eq!(enc(8, key(Key::Enter)), b"\x1b[13u");
+        assert_eq!(enc(8, key(Key::Tab)), b"\x1b[9u");
+        assert_eq!(enc(8, key(Key::Backspace)), b"\x1b[127u");
+        assert_eq!(enc(8, key(Key::Escape)), b"\x1b[27u");
+        assert_eq!(enc(8, ch('a', KeyModifiers::CAPS_LOCK)), b"\x1b[97;65u");
+        assert_eq!(
+            enc(
+                8,
+                key(Key::Modifier(ModifierKey::LeftShift)).with_modifiers(SHIFT)
+            ),
+            b"\x1b[57441;2u"
+        );
+        assert_eq!(
+            enc(
+                10,
+                key(Key::Modifier(ModifierKey::LeftShift)).with_event_type(KeyEventType::Release)
+            ),
+            b"\x1b[57441;1:3u"
+        );
+        assert_eq!(
+            enc(
+                10,
+                ch('w', KeyModifiers::empty()).with_event_type(KeyEventType::Release)
+            ),
+            b"\x1b[119;1:3u"
+        );
+    }
+
+    #[test]
+    fn test_associated_text() {
+        assert_eq!(enc(24, ch('a', KeyModifiers::empty())), b"\x1b[97;;97u");
+        assert_eq!(enc(24, ch('a', SHIFT)), b"\x1b[97;2;65u");
+        assert_eq!(enc(28, ch('a', SHIFT)), b"\x1b[97:65;2;65u");
+        // Keys that produce no text carry no text field
+        assert_eq!(enc(24, ch('a', CTRL)), b"\x1b[97;5u");
+        assert_eq!(enc(24, key(Key::Enter)), b"\x1b[13u");
+        // Composed text from an input method
+        assert_eq!(
+            enc(24, ch('e', KeyModifiers::empty()).with_text("é")),
+            b"\x1b[101;;233u"
+        );
+        // Releases carry no text
+        assert_eq!(
+            enc(
+                26,
+                ch('a', KeyModifiers::empty()).with_event_type(KeyEventType::Release)
+            ),
+            b"\x1b[97;1:3u"
+        );
+        // Without flag 8 the text flag has no effect
+        assert_eq!(enc(17, ch('a', KeyModifiers::empty())), b"a");
+    }
+
+    #[test]
+    fn test_key_names() {
+        assert_eq!(Key::from_name("a"), Some(Key::Char('a')));
+        assert_eq!(Key::from_name("Page-Up"), Some(Key::PageUp));
+        assert_eq!(Key::from_name("f35"), Some(Key::F(35)));
+        assert_eq!(Key::from_name("f36"), None);
+        assert_eq!(
+            Key::from_name("kp_5"),
+            Some(Key::Keypad(KeypadKey::Digit(5)))
+        );
+        assert_eq!(
+            Key::from_name("left_shift"),
+            Some(Key::Modifier(ModifierKey::LeftShift))
+        );
+        assert_eq!(Key::from_name("bogus"), None);
+        assert_eq!(KeyModifiers::parse_name("Ctrl"), Some(CTRL));
+        assert_eq!(
+            KeyEventType::from_name("release"),
+            Some(KeyEventType::Release)
+        );
+    }
+}
@@ -34,6 +34,7 @@
 //! - Full Unicode support including emoji and wide characters
 //! - Bell event tracking for visual bell implementations
 
+pub mod annotation;
 pub mod ansi_utils;
 pub mod badge;
 pub mod cell;
@@ -49,38 +50,73 @@ pub mod grapheme;
 pub mod graphics;
 pub mod grid;
 pub mod html_export;
+pub mod kitty_keyboard;
 pub mod macros;
 pub mod mouse;
 pub mod observer;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod process_tree;
 pub mod pty_error;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod pty_session;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod pty_signal;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod pty_spawn;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod pty_throttle;
 #[cfg(feature = "python")]
 pub mod python_bindings;
+pub mod regis;
+pub mod row_text;
+pub mod rtf_export;
 pub mod screenshot;
 pub mod shell_integration;
 pub mod sixel;
 pub mod streaming;
+pub mod svg_export;
 pub mod terminal;
+pub mod testing;
 pub mod text_utils;
+pub mod theme;
+pub mod time;
 pub mod tmux_control;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod tmux_session;
 pub mod unicode_normalization_config;
 pub mod unicode_width_config;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod win32_input;
 pub mod zone;
 
+// Column mapping for row text
+pub use row_text::RowText;
+
 // Re-export commonly used types from unicode_normalization_config
 pub use unicode_normalization_config::NormalizationForm;
 
 // Re-export commonly used types from unicode_width_config
 pub use unicode_width_config::{
     char_width, char_width_cjk, is_east_asian_ambiguous, str_width, str_width_cjk, AmbiguousWidth,
-    UnicodeVersion, WidthConfig,
+    GraphemeClustering, UnicodeVersion, UnicodeWidthPolicy, WidthConfig,
 };
 
 // Re-export recording types for session logging/recording
 pub use terminal::{
-    RecordingEvent, RecordingEventType, RecordingExportFormat, RecordingFormat, RecordingSession,
+    RecordingEvent, RecordingEventType, RecordingExportFormat, RecordingFormat,
+    RecordingImportError, RecordingPlayerConfig, RecordingSession,
 };
 
+// Re-export heuristic prompt detection configuration
+pub use terminal::PromptDetectionConfig;
+
+// Re-export SVG export configuration
+pub use svg_export::SvgExportConfig;
+
+// Re-export themed HTML export configuration
+pub use html_export::HtmlExportConfig;
+
 // Re-export badge types for badge format support
 pub use badge::{
     decode_badge_format, evaluate_badge_format, BadgeFormatChanged, BadgeFormatError,
@@ -99,21 +135,24 @@ pub use python_bindings::{
     py_adjust_contrast_rgb, py_adjust_hue, py_adjust_saturation, py_char_width, py_char_width_cjk,
     py_color_luminance, py_complementary_color, py_contrast_ratio, py_darken_rgb, py_hex_to_rgb,
     py_hsl_to_rgb, py_is_dark_color, py_is_east_asian_ambiguous, py_lighten_rgb, py_meets_wcag_aa,
-    py_meets_wcag_aaa, py_mix_colors, py_perceived_brightness_rgb, py_rgb_to_ansi_256,
-    py_rgb_to_hex, py_rgb_to_hsl, py_str_width, py_str_width_cjk, PyAmbiguousWidth, PyAttributes,
-    PyBenchmarkResult, PyBenchmarkSuite, PyBookmark, PyClipboardEntry, PyClipboardHistoryEntry,
-    PyClipboardSyncEvent, PyColorHSL, PyColorHSV, PyColorPalette, PyCommandExecution,
-    PyComplianceReport, PyComplianceTest, PyCoprocessConfig, PyCursorStyle, PyCwdChange,
-    PyDamageRegion, PyDetectedItem, PyEscapeSequenceProfile, PyFrameTiming, PyGraphic,
-    PyImageDimension, PyImageFormat, PyImagePlacement, PyImageProtocol, PyInlineImage,
-    PyJoinedLines, PyLineDiff, PyMacro, PyMacroEvent, PyMouseEncoding, PyMouseEvent,
+    py_meets_wcag_aaa, py_mix_colors, py_parse_ansi_spans, py_perceived_brightness_rgb,
+    py_rgb_to_ansi_256, py_rgb_to_hex, py_rgb_to_hsl, py_spans_to_ansi, py_str_width,
+    py_str_width_cjk, PyAmbiguousWidth, PyAttributes, PyBenchmarkResult, PyBenchmarkSuite,
+    PyBookmark, PyClipboardEntry, PyClipboardHistoryEntry, PyClipboardSyncEvent, PyColorHSL,
+    PyColorHSV, PyColorPalette, PyCommandExecution, PyComplianceCategory, PyComplianceReport,
+    PyComplianceTest, PyCoprocessConfig, PyCursorMotion, PyCursorStyle, PyCwdChange,
+    PyDamageRegion, PyDetectedItem, PyEnvironmentChange, PyEscapeSequenceProfile, PyExpectMatch,
+    PyFrameTiming, PyGraphemeClustering, PyGraphic, PyHostContext, PyImageDimension, PyImageFormat,
+    PyImagePlacement, PyImageProtocol, PyInlineImage, PyJoinedLines, PyLatencyMetrics,
+    PyLatencyStats, PyLineDiff, PyMacro, PyMacroEvent, PyMark, PyMouseEncoding, PyMouseEvent,
     PyMousePosition, PyNormalizationForm, PyNotificationConfig, PyNotificationEvent, PyPaneState,
-    PyPerformanceMetrics, PyProfilingData, PyProgressBar, PyProgressState, PyPtyTerminal,
-    PyRecordingEvent, PyRecordingSession, PyRegexMatch, PyRenderingHint, PyScreenSnapshot,
-    PyScreenshotConfig, PyScrollbackStats, PySearchMatch, PySelection, PySelectionMode,
-    PySessionState, PyShellIntegration, PyShellIntegrationStats, PySnapshotDiff, PyStreamingConfig,
-    PyStreamingServer, PyTerminal, PyTmuxNotification, PyTrigger, PyTriggerAction, PyTriggerMatch,
-    PyUnderlineStyle, PyUnicodeVersion, PyWidthConfig, PyWindowLayout,
+    PyPerformanceMetrics, PyProfilingData, PyProgressBar, PyProgressState, PyPtyEventIterator,
+    PyPtyTerminal, PyRecordingEvent, PyRecordingSession, PyRegexMatch, PyRenderingHint,
+    PyScreenSnapshot, PyScreenshotConfig, PyScrollbackStats, PySearchMatch, PySelection,
+    PySelectionMode, PySessionState, PyShellEnvironment, PyShellIntegration,
+    PyShellIntegrationStats, PySnapshotDiff, PyStreamingConfig, PyStreamingServer, PyTerminal,
+    PyTerminalState, PyTmuxNotification, PyTrigger, PyTriggerAction, PyTriggerMatch,
+    PyUnderlineStyle, PyUnicodeVersion, PyUnsupportedSequence, PyWidthConfig, PyWindowLayout,
 };
 
 /// Convert PtyError to PyErr (QA-009: centralized error mapping)
@@ -124,6 +163,9 @@ impl From<pty_error::PtyError> for PyErr {
             pty_error::PtyError::ProcessSpawnError(msg) => {
                 PyRuntimeError::new_err(format!("Failed to spawn process: {}", msg))
             }
+            pty_error::PtyError::SpawnFailed(failure) => {
+                PyRuntimeError::new_err(format!("Failed to spawn process: {}", failure))
+            }
             pty_error::PtyError::ProcessExitedError(code) => {
                 PyRuntimeError::new_err(format!("Process has exited with code: {}", code))
             }
@@ -141,6 +183,48 @@ impl From<pty_error::PtyError> for PyErr {
     }
 }
 
+/// Convert ExpectError to PyErr (QA-009)
+#[cfg(feature = "python")]
+impl From<terminal::ExpectError> for PyErr {
+    fn from(err: terminal::ExpectError) -> PyErr {
+        use pyo3::exceptions::{PyEOFError, PyTimeoutError, PyValueError};
+        use terminal::ExpectError;
+        // Include the end of the unconsumed output, like pexpect does
+        let with_tail = |text: &str| {
+            let start = text.char_indices().rev().nth(199).map_or(0, |(i, _)| i);
+            format!("{}; unconsumed output: {:?}", err, &text[start..])
+        };
+        match &err {
+            ExpectError::InvalidPattern(_) => PyValueError::new_err(err.to_string()),
+            ExpectError::Timeout(text) => PyTimeoutError::new_err(with_tail(text)),
+            ExpectError::Eof(text) => PyEOFError::new_err(with_tail(text)),
+        }
+    }
+}
+
+/// Convert DeltaError to PyErr (QA-009)
+#[cfg(feature = "python")]
+impl From<terminal::DeltaError> for PyErr {
+    fn from(err: terminal::DeltaError) -> PyErr {
+        pyo3::exceptions::PyValueError::new_err(err.to_string())
+    }
+}
+
+/// Convert TmuxBootstrapError to PyErr (QA-009)
+#[cfg(feature = "python")]
+impl From<tmux_session::TmuxBootstrapError> for PyErr {
+    fn from(err: tmux_session::TmuxBootstrapError) -> PyErr {
+        use pyo3::exceptions::{PyEOFError, PyTimeoutError};
+        use tmux_session::TmuxBootstrapError;
+        match err {
+            TmuxBootstrapError::Pty(err) => err.into(),
+            TmuxBootstrapError::Timeout { .. } => PyTimeoutError::new_err(err.to_string()),
+            TmuxBootstrapError::Exited { .. } => PyEOFError::new_err(err.to_string()),
+            TmuxBootstrapError::CommandFailed { .. } => PyRuntimeError::new_err(err.to_string()),
+        }
+    }
+}
+
 /// Convert ScreenshotError to PyErr (QA-009)
 #[cfg(feature = "python")]
 impl From<screenshot::ScreenshotError> for PyErr {
@@ -185,9 +269,11 @@ fn _native(m: &Bound<'_, PyModule>) -> PyResult<()> {
     // Classes
     m.add_class::<PyTerminal>()?;
     m.add_class::<PyPtyTerminal>()?;
+    m.add_class::<PyPtyEventIterator>()?;
     m.add_class::<PyScreenshotConfig>()?;
     m.add_class::<PyAttributes>()?;
     m.add_class::<PyScreenSnapshot>()?;
+    m.add_class::<PyTerminalState>()?;
     m.add_class::<PyShellIntegration>()?;
     m.add_class::<PyGraphic>()?;
     m.add_class::<PyImagePlacement>()?;
@@ -202,8 +288,11 @@ fn _native(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PySelectionMode>()?;
     m.add_class::<PyScrollbackStats>()?;
     m.add_class::<PyBookmark>()?;
+    m.add_class::<PyMark>()?;
     m.add_class::<PyPerformanceMetrics>()?;
     m.add_class::<PyFrameTiming>()?;
+    m.add_class::<PyLatencyStats>()?;
+    m.add_class::<PyLatencyMetrics>()?;
     m.add_class::<PyColorHSV>()?;
     m.add_class::<PyColorHSL>()?;
     m.add_class::<PyColorPalette>()?;
@@ -214,10 +303,12 @@ fn _native(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyDamageRegion>()?;
     m.add_class::<PyRenderingHint>()?;
     m.add_class::<PyEscapeSequenceProfile>()?;
+    m.add_class::<PyUnsupportedSequence>()?;
     m.add_class::<PyProfilingData>()?;
     m.add_class::<PyLineDiff>()?;
     m.add_class::<PySnapshotDiff>()?;
     m.add_class::<PyRegexMatch>()?;
+    m.add_class::<PyExpectMatch>()?;
     m.add_class::<PyPaneState>()?;
     m.add_class::<PyWindowLayout>()?;
     m.add_class::<PySessionState>()?;
@@ -227,6 +318,7 @@ fn _native(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyBenchmarkResult>()?;
     m.add_class::<PyBenchmarkSuite>()?;
     m.add_class::<PyComplianceTest>()?;
+    m.add_class::<PyComplianceCategory>()?;
     m.add_class::<PyComplianceReport>()?;
     m.add_class::<PyClipboardSyncEvent>()?;
     m.add_class::<PyClipboardHistoryEntry>()?;
@@ -245,12 +337,17 @@ fn _native(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyProgressBar>()?;
     m.add_class::<PyUnicodeVersion>()?;
     m.add_class::<PyAmbiguousWidth>()?;
+    m.add_class::<PyGraphemeClustering>()?;
     m.add_class::<PyWidthConfig>()?;
     m.add_class::<PyNormalizationForm>()?;
     m.add_class::<PyTrigger>()?;
     m.add_class::<PyTriggerMatch>()?;
     m.add_class::<PyTriggerAction>()?;
     m.add_class::<PyCoprocessConfig>()?;
+    m.add_class::<PyCursorMotion>()?;
+    m.add_class::<PyHostContext>()?;
+    m.add_class::<PyShellEnvironment>()?;
+    m.add_class::<PyEnvironmentChange>()?;
 
     // Color utility functions
     m.add_function(wrap_pyfunction!(py_perceived_brightness_rgb, m)?)?;
@@ -279,6 +376,10 @@ fn _native(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(py_str_width_cjk, m)?)?;
     m.add_function(wrap_pyfunction!(py_is_east_asian_ambiguous, m)?)?;
 
+    // ANSI span functions
+    m.add_function(wrap_pyfunction!(py_parse_ansi_spans, m)?)?;
+    m.add_function(wrap_pyfunction!(py_spans_to_ansi, m)?)?;
+
     // Binary protocol functions for streaming
     m.add_function(wrap_pyfunction!(encode_server_message, m)?)?;
     m.add_function(wrap_pyfunction!(decode_server_message, m)?)?;
@@ -1,7 +1,8 @@
 //! Macro recording and playback functionality
 //!
-//! This module provides keyboard macro recording and playback with YAML serialization.
-//! Macros can contain keyboard events, delays, and screenshot triggers.
+//! This module provides keyboard macro recording and playback with YAML and JSON
+//! serialization. Macros can contain keyboard events, raw input, delays, and
+//! screenshot triggers.
 //!
 //! ## Example
 //!
@@ -22,12 +23,12 @@
 //! # }
 //! ```
 
+use crate::time::{SystemTime, UNIX_EPOCH};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::io;
 use std::path::Path;
-use std::time::{SystemTime, UNIX_EPOCH};
 
 /// A single macro event
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -41,6 +42,14 @@ pub enum MacroEvent {
         /// Timestamp offset from macro start (milliseconds)
         timestamp: u64,
     },
+    /// Raw input sent verbatim (text and escape sequences)
+    #[serde(rename = "input")]
+    Input {
+        /// Input text, written to the PTY as UTF-8
+        data: String,
+        /// Timestamp offset from macro start (milliseconds)
+        timestamp: u64,
+    },
     /// Delay/pause in playback
     #[serde(rename = "delay")]
     Delay {
@@ -112,6 +121,16 @@ impl Macro {
         self
     }
 
+    /// Add a raw input event, sent verbatim on playback
+    pub fn add_input(&mut self, data: impl Into<String>) -> &mut Self {
+        let timestamp = self.events.last().map(|e| e.timestamp()).unwrap_or(0);
+        self.events.push(MacroEvent::Input {
+            data: data.into(),
+            timestamp,
+        });
+        self
+    }
+
     /// Add a delay event
     pub fn add_delay(&mut self, duration_ms: u64) -> &mut Self {
         let timestamp = self.events.last().map(|e| e.timestamp()).unwrap_or(0);
@@ -176,6 +195,142 @@ impl Macro {
     pub fn from_yaml(yaml: &str) -> Result<Self, serde_yaml::Error> {
         serde_yaml::from_str(yaml)
     }
+
+    /// Save the macro to a JSON file
+    pub fn save_json<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(path, json)
+    }
+
+    /// Load a macro from a JSON file
+    pub fn load_json<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        serde_json::from_str(&contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Load a macro from a file, choosing JSON for a `.json` extension and
+    /// YAML otherwise
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let is_json = path
+            .as_ref()
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        if is_json {
+            Self::load_json(path)
+        } else {
+            Self::load_yaml(path)
+        }
+    }
+
+    /// Convert to JSON string
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Parse from JSON string
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Live macro recorder
+///
+/// Each [`MacroRecorder::record_input`] call becomes an input event, preceded
+/// by a delay event holding the time since the previous input. A UTF-8
+/// sequence split across calls is held back until its last byte arrives.
+#[derive(Debug, Clone)]
+pub struct MacroRecorder {
+    macro_data: Macro,
+    /// Recording start time (milliseconds)
+    start_time: u64,
+    /// Offset of the last recorded event (milliseconds)
+    last_offset: u64,
+    /// Leading bytes of a UTF-8 sequence cut off at the end of the last input
+    pending: Vec<u8>,
+}
+
+impl MacroRecorder {
+    /// Start recording a macro named `name`
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            macro_data: Macro::new(name),
+            start_time: current_time_ms(),
+            last_offset: 0,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Set the terminal size stored in the recorded macro
+    pub fn with_terminal_size(mut self, cols: usize, rows: usize) -> Self {
+        self.macro_data.terminal_size = Some((cols, rows));
+        self
+    }
+
+    /// Record input bytes at the current time
+    pub fn record_input(&mut self, data: &[u8]) {
+        self.record_input_at(current_time_ms().saturating_sub(self.start_time), data);
+    }
+
+    /// Record input bytes at `offset` milliseconds after the recording started
+    pub fn record_input_at(&mut self, offset: u64, data: &[u8]) {
+        let mut bytes = std::mem::take(&mut self.pending);
+        bytes.extend_from_slice(data);
+        self.pending = bytes.split_off(bytes.len() - incomplete_utf8_tail(&bytes));
+        if bytes.is_empty() {
+            return;
+        }
+        let offset = offset.max(self.last_offset);
+        if offset > self.last_offset {
+            self.macro_data.add_delay(offset - self.last_offset);
+        }
+        self.macro_data
+            .add_input(String::from_utf8_lossy(&bytes).into_owned());
+        self.last_offset = offset;
+    }
+
+    /// Name of the macro being recorded
+    pub fn name(&self) -> &str {
+        &self.macro_data.name
+    }
+
+    /// Number of events recorded so far
+    pub fn event_count(&self) -> usize {
+        self.macro_data.events.len()
+    }
+
+    /// Finish recording and return the macro
+    ///
+    /// A UTF-8 sequence still waiting for its last bytes is recorded as a
+    /// replacement character.
+    pub fn finish(mut self) -> Macro {
+        if !self.pending.is_empty() {
+            self.macro_data
+                .add_input(String::from_utf8_lossy(&self.pending).into_owned());
+        }
+        self.macro_data.duration = self.last_offset;
+        self.macro_data
+    }
+}
+
+/// Length of the unfinished UTF-8 sequence at the end of `bytes`, if any
+fn incomplete_utf8_tail(bytes: &[u8]) -> usize {
+    let tail = &bytes[bytes.len().saturating_sub(3)..];
+    let Some(lead) = tail.iter().rposition(|&b| b & 0xC0 != 0x80) else {
+        return 0;
+    };
+    match std::str::from_utf8(&tail[lead..]) {
+        Err(e) if e.error_len().is_none() => tail.len() - lead,
+        _ => 0,
+    }
+}
+
+/// Current time in milliseconds since the UNIX epoch
+fn current_time_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
 }
 
 impl MacroEvent {
@@ -183,6 +338,7 @@ impl MacroEvent {
     pub fn timestamp(&self) -> u64 {
         match self {
             MacroEvent::KeyPress { timestamp, .. } => *timestamp,
+            MacroEvent::Input { timestamp, .. } => *timestamp,
             MacroEvent::Delay { timestamp, .. } => *timestamp,
             MacroEvent::Screenshot { timestamp, .. } => *timestamp,
         }
@@ -300,7 +456,7 @@ impl MacroPlayback {
         Self {
             macro_data,
             current_index: 0,
-            start_time: Self::current_time_ms(),
+            start_time: current_time_ms(),
             speed: 1.0,
             paused: false,
             paused_time: 0,
@@ -315,21 +471,13 @@ impl MacroPlayback {
         playback
     }
 
-    /// Get current time in milliseconds
-    fn current_time_ms() -> u64 {
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_millis() as u64
-    }
-
     /// Get the next event that should be executed now, if any
     pub fn next_event(&mut self) -> Option<MacroEvent> {
         if self.paused || self.current_index >= self.macro_data.events.len() {
             return None;
         }
 
-        let current_time = Self::current_time_ms();
+        let current_time = current_time_ms();
         let elapsed = current_time - self.start_time - self.paused_time;
         let event = &self.macro_data.events[self.current_index];
         let event_time = (event.timestamp() as f64 / self.speed) as u64;
@@ -347,7 +495,7 @@ impl MacroPlayback {
     pub fn pause(&mut self) {
         if !self.paused {
             self.paused = true;
-            self.pause_start = Some(Self::current_time_ms());
+            self.pause_start = Some(current_time_ms());
         }
     }
 
@@ -355,7 +503,7 @@ impl MacroPlayback {
     pub fn resume(&mut self) {
         if self.paused {
             if let Some(pause_start) = self.pause_start {
-                let current_time = Self::current_time_ms();
+                let current_time = current_time_ms();
                 self.paused_time += current_time - pause_start;
             }
             self.paused = false;
@@ -386,7 +534,7 @@ impl MacroPlayback {
     /// Reset playback to the beginning
     pub fn reset(&mut self) {
         self.current_index = 0;
-        self.start_time = Self::current_time_ms();
+        self.start_time = current_time_ms();
         self.paused_time = 0;
         self.pause_start = None;
         self.paused = false;
@@ -434,6 +582,91 @@ mod tests {
         assert_eq!(loaded.events.len(), macro_seq.events.len());
     }
 
+    #[test]
+    fn test_json_serialization() {
+        let mut macro_seq = Macro::new("Json Macro");
+        macro_seq
+            .add_input("ls -la\r")
+            .add_delay(250)
+            .add_key("ctrl+c");
+
+        let json = macro_seq.to_json().unwrap();
+        assert!(json.contains("\"type\": \"input\""));
+        let loaded = Macro::from_json(&json).unwrap();
+        assert_eq!(loaded.events, macro_seq.events);
+        assert_eq!(loaded.duration, 250);
+    }
+
+    #[test]
+    fn test_load_picks_format_from_extension() {
+        let dir = std::env::temp_dir().join(format!("par-term-macro-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut macro_seq = Macro::new("Files");
+        macro_seq.add_input("echo hi\r");
+
+        let json_path = dir.join("m.json");
+        let yaml_path = dir.join("m.yaml");
+        macro_seq.save_json(&json_path).unwrap();
+        macro_seq.save_yaml(&yaml_path).unwrap();
+        assert_eq!(Macro::load(&json_path).unwrap().events, macro_seq.events);
+        assert_eq!(Macro::load(&yaml_path).unwrap().events, macro_seq.events);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_recorder_inserts_delays_between_inputs() {
+        let mut recorder = MacroRecorder::new("rec");
+        recorder.record_input_at(0, b"ls");
+        recorder.record_input_at(0, b"\r");
+        recorder.record_input_at(120, b"exit\r");
+        recorder.record_input_at(50, b""); // empty input is ignored
+        let m = recorder.finish();
+
+        assert_eq!(
+            m.events,
+            vec![
+                MacroEvent::Input {
+                    data: "ls".to_string(),
+                    timestamp: 0
+                },
+                MacroEvent::Input {
+                    data: "\r".to_string(),
+                    timestamp: 0
+                },
+                MacroEvent::Delay {
+                    duration: 120,
+                    timestamp: 120
+                },
+                MacroEvent::Input {
+                    data: "exit\r".to_string(),
+                    timestamp: 120
+                },
+            ]
+        );
+        assert_eq!(m.duration, 120);
+    }
+
+    #[test]
+    fn test_recorder_joins_utf8_split_across_inputs() {
+        let mut recorder = MacroRecorder::new("rec");
+        let euro = "\u{20ac}".as_bytes();
+        recorder.record_input_at(0, &[b'a', euro[0]]);
+        recorder.record_input_at(0, &euro[1..2]);
+        recorder.record_input_at(10, &euro[2..]);
+        recorder.record_input_at(20, &[0xe2]);
+        let m = recorder.finish();
+
+        let inputs: Vec<_> = m
+            .events
+            .iter()
+            .filter_map(|e| match e {
+                MacroEvent::Input { data, .. } => Some(data.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(inputs, ["a", "\u{20ac}", "\u{fffd}"]);
+    }
+
     #[test]
     fn test_playback() {
         let mut macro_seq = Macro::new("Test");
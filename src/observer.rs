@@ -32,12 +32,14 @@ pub trait TerminalObserver: Send + Sync {
     fn on_command_event(&self, _event: &TerminalEvent) {}
 
     /// Called for environment changes (CwdChanged, EnvironmentChanged,
-    /// RemoteHostTransition, SubShellDetected)
+    /// RemoteHostTransition, SubShellDetected, ForegroundProcessChanged)
     fn on_environment_event(&self, _event: &TerminalEvent) {}
 
-    /// Called for screen content events (BellRang, TitleChanged, SizeChanged,
-    /// ModeChanged, GraphicsAdded, HyperlinkAdded, DirtyRegion, UserVarChanged,
-    /// ProgressBarChanged, BadgeChanged, TriggerMatched)
+    /// Called for screen content events (BellRang, TitleChanged, IconNameChanged,
+    /// SizeChanged, ResizeRequested, ModeChanged, GraphicsAdded, HyperlinkAdded,
+    /// DirtyRegion, UserVarChanged, ProgressBarChanged, BadgeChanged, TriggerMatched,
+    /// CursorStyleChanged, PrintJobCompleted, SecretDetected, Utf8DecodeError,
+    /// EscapeStringAborted, BackgroundChanged)
     fn on_screen_event(&self, _event: &TerminalEvent) {}
 
     /// Called for ALL events (catch-all). Called after category-specific methods.
@@ -75,7 +77,8 @@ pub(crate) fn event_category(event: &TerminalEvent) -> EventCategory {
         TerminalEvent::CwdChanged(_)
         | TerminalEvent::EnvironmentChanged { .. }
         | TerminalEvent::RemoteHostTransition { .. }
-        | TerminalEvent::SubShellDetected { .. } => EventCategory::Environment,
+        | TerminalEvent::SubShellDetected { .. }
+        | TerminalEvent::ForegroundProcessChanged { .. } => EventCategory::Environment,
 
         _ => EventCategory::Screen,
     }
@@ -0,0 +1,219 @@
+//! Process introspection for PTY children
+//!
+//! Looks up name, parent and working directory of a process, and walks the
+//! descendants of a process by parent PID. Linux reads `/proc`; macOS uses
+//! libproc. On other platforms lookups return `None` and trees are empty.
+//!
+//! The information is a snapshot: processes can exit (and PIDs be reused)
+//! between a lookup and its use.
+
+use std::path::PathBuf;
+
+/// Snapshot of a single process
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessInfo {
+    /// Process ID
+    pub pid: u32,
+    /// Parent process ID (0 if unknown)
+    pub ppid: u32,
+    /// Short command name (e.g. `vim`), as the kernel reports it
+    pub name: String,
+    /// Current working directory, if readable
+    pub cwd: Option<PathBuf>,
+    /// Stopped by a signal (e.g. Ctrl+Z) and waiting for SIGCONT
+    pub stopped: bool,
+    /// Command line arguments (empty where the platform does not expose them
+    /// cheaply, e.g. macOS)
+    pub cmdline: Vec<String>,
+}
+
+/// Look up process `pid`, or `None` if it does not exist or cannot be read
+pub fn process_info(pid: u32) -> Option<ProcessInfo> {
+    platform::process_info(pid)
+}
+
+/// All descendants of `pid` (children, grandchildren, ...), breadth first,
+/// ordered by PID within each level. `pid` itself is not included.
+pub fn descendants(pid: u32) -> Vec<ProcessInfo> {
+    let all = platform::all_processes();
+    let mut result = Vec::new();
+    let mut level = vec![pid];
+    while !level.is_empty() {
+        let mut next: Vec<&ProcessInfo> = all
+            .iter()
+            .filter(|info| info.pid != info.ppid && level.contains(&info.ppid))
+            .collect();
+        next.sort_by_key(|info| info.pid);
+        level = next.iter().map(|info| info.pid).collect();
+        result.extend(next.into_iter().cloned());
+    }
+    result
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::ProcessInfo;
+    use std::fs;
+
+    pub(super) fn process_info(pid: u32) -> Option<ProcessInfo> {
+        let dir = format!("/proc/{pid}");
+        let (name, state, ppid) = parse_stat(&fs::read_to_string(format!("{dir}/stat")).ok()?)?;
+        let cmdline = fs::read(format!("{dir}/cmdline"))
+            .map(|raw| {
+                raw.split(|&b| b == 0)
+                    .filter(|arg| !arg.is_empty())
+                    .map(|arg| String::from_utf8_lossy(arg).into_owned())
+                    .collect()
+            })
+            .unwrap_or_default();
+        Some(ProcessInfo {
+            pid,
+            ppid,
+            name,
+            cwd: fs::read_link(format!("{dir}/cwd")).ok(),
+            // T = stopped by a signal, t = stopped by a tracer
+            stopped: matches!(state, 'T' | 't'),
+            cmdline,
+        })
+    }
+
+    pub(super) fn all_processes() -> Vec<ProcessInfo> {
+        let Ok(entries) = fs::read_dir("/proc") else {
+            return Vec::new();
+        };
+        entries
+            .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse::<u32>().ok())
+            .filter_map(process_info)
+            .collect()
+    }
+
+    /// Name, state and parent PID from `/proc/<pid>/stat`:
+    /// `pid (comm) state ppid ...`. `comm` may itself contain spaces and
+    /// parentheses, so split on the last `)`.
+    fn parse_stat(stat: &str) -> Option<(String, char, u32)> {
+        let open = stat.find('(')?;
+        let close = stat.rfind(')')?;
+        let name = stat.get(open + 1..close)?.to_string();
+        let mut fields = stat.get(close + 1..)?.split_whitespace();
+        let state = fields.next()?.chars().next()?;
+        let ppid = fields.next()?.parse().ok()?;
+        Some((name, state, ppid))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::parse_stat;
+
+        #[test]
+        fn test_parse_stat_handles_parens_in_name() {
+            let stat = "4242 (my (odd) name) T 17 4242 4242 0 -1";
+            assert_eq!(
+                parse_stat(stat),
+                Some(("my (odd) name".to_string(), 'T', 17))
+            );
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::ProcessInfo;
+    use std::ffi::CStr;
+    use std::mem;
+    use std::os::raw::{c_char, c_int, c_void};
+    use std::path::PathBuf;
+
+    pub(super) fn process_info(pid: u32) -> Option<ProcessInfo> {
+        let mut bsd: libc::proc_bsdinfo = unsafe { mem::zeroed() };
+        let size = mem::size_of::<libc::proc_bsdinfo>() as c_int;
+        let read = unsafe {
+            libc::proc_pidinfo(
+                pid as c_int,
+                libc::PROC_PIDTBSDINFO,
+                0,
+                &mut bsd as *mut _ as *mut c_void,
+                size,
+            )
+        };
+        if read != size {
+            return None;
+        }
+        // pbi_name holds the full name; pbi_comm is truncated to MAXCOMLEN
+        let name = c_chars_to_string(&bsd.pbi_name)
+            .filter(|name| !name.is_empty())
+            .or_else(|| c_chars_to_string(&bsd.pbi_comm))
+            .unwrap_or_default();
+        Some(ProcessInfo {
+            pid,
+            ppid: bsd.pbi_ppid,
+            name,
+            cwd: cwd(pid),
+            stopped: bsd.pbi_status == libc::SSTOP,
+            cmdline: Vec::new(),
+        })
+    }
+
+    pub(super) fn all_processes() -> Vec<ProcessInfo> {
+        let count = unsafe { libc::proc_listallpids(std::ptr::null_mut(), 0) };
+        if count <= 0 {
+            return Vec::new();
+        }
+        // Leave headroom for processes started between the two calls
+        let mut pids: Vec<c_int> = vec![0; count as usize + 32];
+        let bytes = (pids.len() * mem::size_of::<c_int>()) as c_int;
+        let count = unsafe { libc::proc_listallpids(pids.as_mut_ptr() as *mut c_void, bytes) };
+        if count <= 0 {
+            return Vec::new();
+        }
+        pids.truncate(count as usize);
+        pids.into_iter()
+            .filter(|&pid| pid > 0)
+            .filter_map(|pid| process_info(pid as u32))
+            .collect()
+    }
+
+    fn cwd(pid: u32) -> Option<PathBuf> {
+        let mut info: libc::proc_vnodepathinfo = unsafe { mem::zeroed() };
+        let size = mem::size_of::<libc::proc_vnodepathinfo>() as c_int;
+        let read = unsafe {
+            libc::proc_pidinfo(
+                pid as c_int,
+                libc::PROC_PIDVNODEPATHINFO,
+                0,
+                &mut info as *mut _ as *mut c_void,
+                size,
+            )
+        };
+        if read != size {
+            return None;
+        }
+        // vip_path is declared as [[c_char; 32]; 32] but is one MAXPATHLEN string
+        let path = &info.pvi_cdir.vip_path;
+        let bytes = unsafe {
+            std::slice::from_raw_parts(path.as_ptr() as *const c_char, mem::size_of_val(path))
+        };
+        c_chars_to_string(bytes)
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+    }
+
+    fn c_chars_to_string(chars: &[c_char]) -> Option<String> {
+        let bytes = unsafe { &*(chars as *const [c_char] as *const [u8]) };
+        CStr::from_bytes_until_nul(bytes)
+            .ok()
+            .map(|name| name.to_string_lossy().into_owned())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+mod platform {
+    use super::ProcessInfo;
+
+    pub(super) fn process_info(_pid: u32) -> Option<ProcessInfo> {
+        None
+    }
+
+    pub(super) fn all_processes() -> Vec<ProcessInfo> {
+        Vec::new()
+    }
+}
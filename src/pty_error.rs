@@ -1,11 +1,72 @@
 use std::fmt;
 use std::io;
 
+/// Why a spawn failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpawnFailureKind {
+    /// The command does not exist, or was not found on `PATH`
+    CommandNotFound,
+    /// The command exists but is not an executable file
+    NotExecutable,
+    /// The working directory does not exist
+    CwdNotFound,
+    /// The working directory is not a directory
+    CwdNotDirectory,
+    /// No PTY could be opened
+    PtyUnavailable,
+    /// The OS refused to start the process
+    ExecFailed,
+}
+
+impl SpawnFailureKind {
+    /// Short description, e.g. `"command not found"`
+    pub fn description(self) -> &'static str {
+        match self {
+            SpawnFailureKind::CommandNotFound => "command not found",
+            SpawnFailureKind::NotExecutable => "command is not executable",
+            SpawnFailureKind::CwdNotFound => "working directory does not exist",
+            SpawnFailureKind::CwdNotDirectory => "working directory is not a directory",
+            SpawnFailureKind::PtyUnavailable => "could not open a PTY",
+            SpawnFailureKind::ExecFailed => "could not start process",
+        }
+    }
+}
+
+/// Details of a failed spawn
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpawnFailure {
+    /// What went wrong
+    pub kind: SpawnFailureKind,
+    /// Command as given to spawn
+    pub command: String,
+    /// Arguments after the command
+    pub args: Vec<String>,
+    /// Working directory the child would have started in
+    pub cwd: Option<String>,
+    /// Specifics: the `PATH` that was searched, or the OS error
+    pub detail: String,
+}
+
+impl fmt::Display for SpawnFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.kind.description(), self.command)?;
+        if let Some(ref cwd) = self.cwd {
+            write!(f, " (cwd {})", cwd)?;
+        }
+        if !self.detail.is_empty() {
+            write!(f, ": {}", self.detail)?;
+        }
+        Ok(())
+    }
+}
+
 /// Errors that can occur during PTY operations
 #[derive(Debug)]
 pub enum PtyError {
     /// Failed to spawn a process
     ProcessSpawnError(String),
+    /// Failed to spawn a process, with diagnostics
+    SpawnFailed(Box<SpawnFailure>),
     /// Process has already exited
     ProcessExitedError(i32),
     /// I/O error occurred
@@ -22,6 +83,7 @@ impl fmt::Display for PtyError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             PtyError::ProcessSpawnError(msg) => write!(f, "Failed to spawn process: {}", msg),
+            PtyError::SpawnFailed(failure) => write!(f, "Failed to spawn process: {}", failure),
             PtyError::ProcessExitedError(code) => {
                 write!(f, "Process has already exited with code: {}", code)
             }
@@ -107,6 +169,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_spawn_failed_display() {
+        let err = PtyError::SpawnFailed(Box::new(SpawnFailure {
+            kind: SpawnFailureKind::CommandNotFound,
+            command: "nosuchcmd".to_string(),
+            args: vec!["-x".to_string()],
+            cwd: Some("/tmp".to_string()),
+            detail: "not found in PATH /usr/bin:/bin".to_string(),
+        }));
+        assert_eq!(
+            err.to_string(),
+            "Failed to spawn process: command not found: nosuchcmd (cwd /tmp): \
+             not found in PATH /usr/bin:/bin"
+        );
+    }
+
     #[test]
     fn test_error_source() {
         let io_err = IoError::new(ErrorKind::NotFound, "file not found");
@@ -144,6 +222,13 @@ mod tests {
             PtyError::ResizeError("resize".to_string()),
             PtyError::NotStartedError,
             PtyError::LockError("lock".to_string()),
+            PtyError::SpawnFailed(Box::new(SpawnFailure {
+                kind: SpawnFailureKind::ExecFailed,
+                command: "cmd".to_string(),
+                args: Vec::new(),
+                cwd: None,
+                detail: String::new(),
+            })),
         ];
 
         for err in errors {
@@ -28,21 +28,58 @@
 //! terminal state changes (new output, resize, mode change, etc.). Callers
 //! that poll state can compare the generation to detect whether anything has
 //! changed since their last read without diffing the full grid.
+//!
+//! ## State handles
+//!
+//! [`PtySession::state_handle`] hands out an immutable
+//! [`TerminalStateHandle`] instead of a lock guard. After the first request
+//! the reader thread publishes a fresh handle at the end of every chunk while
+//! it still holds the terminal lock, so a published handle always shows the
+//! state between two chunks. A caller that asks while a chunk is being
+//! processed gets the last published handle right away rather than waiting
+//! for the lock. With double buffering on, the published handle is the
+//! terminal's front buffer, so a synchronized update spanning several chunks
+//! is only seen once it ends.
+//!
+//! ## Waiting for output
+//!
+//! Consumers that would rather block than poll share the session's
+//! [`OutputWaiter`]: the reader thread wakes it after each chunk has been
+//! processed and closes it on EOF, so a waiting thread always observes the
+//! terminal state that includes the new output.
+//!
+//! ## Foreground job
+//!
+//! On Unix the reader thread checks the PTY's foreground process group after
+//! each chunk of output and emits `ForegroundProcessChanged` when it differs
+//! from the last one seen. A command that starts without printing anything is
+//! only noticed by [`PtySession::poll_foreground_process`], which frontends
+//! can call on a timer.
 
 use crate::coprocess::{CoprocessConfig, CoprocessId, CoprocessManager};
 use crate::debug;
-use crate::pty_error::PtyError;
-use crate::terminal::Terminal;
-use parking_lot::{Mutex, RwLock};
+use crate::process_tree::{self, ProcessInfo};
+use crate::pty_error::{PtyError, SpawnFailureKind};
+use crate::pty_signal::{ProcessStatus, PtySignal};
+use crate::pty_spawn::{self, EnvMode, SpawnConfig};
+use crate::pty_throttle::{
+    drop_notice, Admission, IdleCheck, OutputThrottle, ThrottleConfig, ThrottleStats,
+};
+use crate::terminal::{
+    ExpectError, ExpectMatch, ExpectPatterns, RecordingSession, Terminal, TerminalEvent,
+    TerminalStateHandle, DEFAULT_EXPECT_BUFFER_BYTES,
+};
+use parking_lot::{Condvar, Mutex, RwLock};
 use portable_pty::{native_pty_system, Child, CommandBuilder, PtyPair, PtySize};
 use std::collections::HashMap;
 use std::io::{Read, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::{
-    atomic::{AtomicBool, Ordering},
+    atomic::{AtomicBool, AtomicU32, Ordering},
     Arc,
 };
 use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 
 /// Callback function for PTY output
 ///
@@ -54,6 +91,292 @@ use std::thread::{self, JoinHandle};
 /// * `data` - The raw bytes read from the PTY
 pub type OutputCallback = Arc<dyn Fn(&[u8]) + Send + Sync>;
 
+/// Maximum raw output kept for [`OutputWaiter::take_output`]; older bytes are
+/// dropped when a consumer falls this far behind
+const MAX_CAPTURED_OUTPUT: usize = 1024 * 1024;
+
+/// Result of [`OutputWaiter::wait_for_update`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitOutcome {
+    /// New output was processed; carries the new generation
+    Updated(u64),
+    /// The session has no running reader (not spawned yet, or EOF)
+    Closed,
+    /// The timeout elapsed first
+    TimedOut,
+    /// [`OutputWaiter::interrupt`] was called
+    Interrupted,
+}
+
+#[derive(Debug, Default)]
+struct WaiterState {
+    generation: u64,
+    interrupts: u64,
+    open: bool,
+    captured: Option<Vec<u8>>,
+}
+
+/// Blocking wait for PTY output, shared with the session's reader thread
+///
+/// The generation here counts processed output chunks, unlike
+/// [`PtySession::update_generation`] which is bumped before processing.
+#[derive(Debug, Default)]
+pub struct OutputWaiter {
+    state: Mutex<WaiterState>,
+    cond: Condvar,
+}
+
+impl OutputWaiter {
+    /// Number of output chunks processed so far
+    pub fn generation(&self) -> u64 {
+        self.state.lock().generation
+    }
+
+    /// Whether a reader thread is feeding the terminal
+    pub fn is_open(&self) -> bool {
+        self.state.lock().open
+    }
+
+    /// Block until the generation passes `since`, the session closes, the
+    /// timeout elapses or [`interrupt`](Self::interrupt) is called
+    pub fn wait_for_update(&self, since: u64, timeout: Option<Duration>) -> WaitOutcome {
+        let deadline = timeout.map(|t| Instant::now() + t);
+        let mut state = self.state.lock();
+        let interrupts = state.interrupts;
+        loop {
+            if state.generation > since {
+                return WaitOutcome::Updated(state.generation);
+            }
+            if !state.open {
+                return WaitOutcome::Closed;
+            }
+            if state.interrupts != interrupts {
+                return WaitOutcome::Interrupted;
+            }
+            match deadline {
+                Some(deadline) => {
+                    if self.cond.wait_until(&mut state, deadline).timed_out() {
+                        return WaitOutcome::TimedOut;
+                    }
+                }
+                None => self.cond.wait(&mut state),
+            }
+        }
+    }
+
+    /// Wake every current waiter with [`WaitOutcome::Interrupted`]
+    pub fn interrupt(&self) {
+        self.state.lock().interrupts += 1;
+        self.cond.notify_all();
+    }
+
+    /// Start or stop keeping raw output for [`take_output`](Self::take_output)
+    ///
+    /// Only output read after capture starts is kept.
+    pub fn set_output_capture(&self, enabled: bool) {
+        let mut state = self.state.lock();
+        match (enabled, state.captured.is_some()) {
+            (true, false) => state.captured = Some(Vec::new()),
+            (false, true) => state.captured = None,
+            _ => {}
+        }
+    }
+
+    /// Take the raw output captured since the previous call
+    pub fn take_output(&self) -> Vec<u8> {
+        self.state
+            .lock()
+            .captured
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    fn open(&self) {
+        self.state.lock().open = true;
+        self.cond.notify_all();
+    }
+
+    fn close(&self) {
+        self.state.lock().open = false;
+        self.cond.notify_all();
+    }
+
+    fn notify(&self, data: &[u8]) {
+        let mut state = self.state.lock();
+        state.generation += 1;
+        if let Some(captured) = state.captured.as_mut() {
+            captured.extend_from_slice(data);
+            if captured.len() > MAX_CAPTURED_OUTPUT {
+                let excess = captured.len() - MAX_CAPTURED_OUTPUT;
+                captured.drain(..excess);
+            }
+        }
+        drop(state);
+        self.cond.notify_all();
+    }
+}
+
+/// Wait until the expect buffer of `terminal` matches one of `patterns`,
+/// re-checking each time `waiter` reports processed output
+///
+/// The expect buffer (see [`Terminal::set_expect_buffer`]) is enabled with
+/// [`DEFAULT_EXPECT_BUFFER_BYTES`] on first use if it is off; enable it
+/// before spawning to match output that arrives before the first call. On
+/// success the buffer is consumed through the end of the match.
+///
+/// # Errors
+/// [`ExpectError::Timeout`] if `timeout` elapses, or [`ExpectError::Eof`] if
+/// the session closes, without a match. Both carry the unconsumed text.
+pub fn expect_output(
+    terminal: &RwLock<Terminal>,
+    waiter: &OutputWaiter,
+    patterns: &ExpectPatterns,
+    timeout: Option<Duration>,
+) -> Result<ExpectMatch, ExpectError> {
+    let deadline = timeout.map(|t| Instant::now() + t);
+    loop {
+        let generation = waiter.generation();
+        {
+            let mut term = terminal.write();
+            if !term.expect_buffer_enabled() {
+                term.set_expect_buffer(Some(DEFAULT_EXPECT_BUFFER_BYTES));
+            }
+            if let Some(found) = term.expect_match(patterns) {
+                return Ok(found);
+            }
+        }
+        let remaining = deadline.map(|d| d.saturating_duration_since(Instant::now()));
+        let unconsumed = || {
+            let term = terminal.read();
+            term.expect_buffer().unwrap_or_default().to_string()
+        };
+        match waiter.wait_for_update(generation, remaining) {
+            WaitOutcome::Updated(_) | WaitOutcome::Interrupted => {}
+            WaitOutcome::TimedOut => return Err(ExpectError::Timeout(unconsumed())),
+            WaitOutcome::Closed => return Err(ExpectError::Eof(unconsumed())),
+        }
+    }
+}
+
+/// Drain `term`'s reply queue and write it to the PTY master.
+///
+/// XTWINOPS (`CSI t`) replies are dropped when `reply_xtwinops` is off
+/// (`PAR_TERM_REPLY_XTWINOPS=0`), so shells with ECHOCTL do not echo them
+/// visibly. Write errors are ignored: a child that went away cannot read
+/// replies anyway.
+fn write_replies(term: &mut Terminal, writer: &Mutex<Box<dyn Write + Send>>, reply_xtwinops: bool) {
+    if !term.has_pending_responses() {
+        return;
+    }
+    let mut responses = term.drain_responses();
+    if !reply_xtwinops {
+        let mut filtered = Vec::with_capacity(responses.len());
+        let mut i = 0;
+        while i < responses.len() {
+            if responses[i] == 0x1B && i + 1 < responses.len() && responses[i + 1] == b'[' {
+                // Collect until a final byte; drop if final is 't'
+                let mut j = i + 2;
+                let mut dropped = false;
+                while j < responses.len() {
+                    let b = responses[j];
+                    if (b as char).is_ascii_alphabetic() {
+                        // Alphabetic final byte for CSI
+                        if b == b't' {
+                            dropped = true;
+                        }
+                        j += 1;
+                        break;
+                    }
+                    j += 1;
+                }
+                if !dropped {
+                    filtered.extend_from_slice(&responses[i..j]);
+                }
+                i = j;
+            } else {
+                filtered.push(responses[i]);
+                i += 1;
+            }
+        }
+        responses = filtered;
+    }
+
+    if !responses.is_empty() {
+        debug::log_device_query("pending", &responses);
+        let mut w = writer.lock();
+        let _ = w.write_all(&responses);
+        let _ = w.flush();
+    }
+}
+
+/// Emit `ForegroundProcessChanged` if `pgid` differs from the last foreground
+/// process group seen. Returns `true` if an event was emitted.
+fn note_foreground_pgid(pgid: u32, last: &AtomicU32, term: &mut Terminal) -> bool {
+    if last.swap(pgid, Ordering::SeqCst) == pgid {
+        return false;
+    }
+    let name = process_tree::process_info(pgid)
+        .map(|info| info.name)
+        .unwrap_or_default();
+    term.emit_event(TerminalEvent::ForegroundProcessChanged { pid: pgid, name });
+    true
+}
+
+/// Write the notice for a dropped burst once output goes quiet
+///
+/// The reader thread blocks until the child writes again, so a burst that
+/// ends a command's output would otherwise go unreported until the next read
+/// or EOF. The terminal lock is taken before the notice is claimed, so it
+/// cannot land after output the reader processes in the meantime.
+fn spawn_idle_drop_watch(
+    terminal: Arc<RwLock<Terminal>>,
+    throttle: Arc<Mutex<OutputThrottle>>,
+    update_generation: Arc<std::sync::atomic::AtomicU64>,
+) {
+    thread::spawn(move || loop {
+        let interval = Duration::from_millis(throttle.lock().config.interval_ms.max(1));
+        thread::sleep(interval);
+        match throttle.lock().check_idle(Instant::now()) {
+            IdleCheck::Wait => continue,
+            IdleCheck::Done => break,
+            IdleCheck::Flush => {}
+        }
+        let mut term = terminal.write();
+        let dropped = {
+            let mut throttle = throttle.lock();
+            match throttle.check_idle(Instant::now()) {
+                IdleCheck::Flush => throttle.take_dropped(),
+                _ => None,
+            }
+        };
+        if let Some(count) = dropped {
+            let notice = drop_notice(count);
+            throttle.lock().note_processed(notice.len());
+            term.process_unrecorded(notice.as_bytes());
+            update_generation.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+}
+
+/// Send `signal` with `kill(2)`; a negative `pid` targets a process group
+#[cfg(unix)]
+fn signal_pid(pid: i32, signal: PtySignal) -> Result<(), PtyError> {
+    if unsafe { libc::kill(pid, signal.as_raw()) } == 0 {
+        Ok(())
+    } else {
+        Err(PtyError::IoError(std::io::Error::last_os_error()))
+    }
+}
+
+#[cfg(not(unix))]
+fn signal_pid(_pid: i32, signal: PtySignal) -> Result<(), PtyError> {
+    Err(PtyError::IoError(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        format!("{signal} is not supported on this platform"),
+    )))
+}
+
 /// A PTY session that manages a shell process and terminal state
 pub struct PtySession {
     terminal: Arc<RwLock<Terminal>>,
@@ -80,10 +403,25 @@ pub struct PtySession {
     /// Optional callback for raw PTY output (for streaming, logging, etc.)
     /// Wrapped in Arc<Mutex> so it can be updated after the reader thread starts
     output_callback: Arc<Mutex<Option<OutputCallback>>>,
+    /// Wakes threads blocked waiting for output
+    output_waiter: Arc<OutputWaiter>,
     /// Coprocess manager for piping terminal output to external processes
     coprocess_manager: Arc<Mutex<CoprocessManager>>,
     /// PID of the spawned child process (shell or command), set after spawn
     child_pid: Option<u32>,
+    /// Exit code once the child has been reaped; its PID may then belong to
+    /// another process, so signals are refused
+    reaped_exit_code: Option<i32>,
+    /// Foreground process group last reported in a `ForegroundProcessChanged`
+    /// event (0 = none yet)
+    foreground_pgid: Arc<AtomicU32>,
+    /// Output budget shared with the reader thread
+    throttle: Arc<Mutex<OutputThrottle>>,
+    /// Process group stopped by the last `suspend()`, resumed by `resume()`
+    suspended_pgid: Option<u32>,
+    /// Newest published state handle; `None` until one is requested, after
+    /// which the reader thread republishes after every chunk
+    state_handle: Arc<Mutex<Option<TerminalStateHandle>>>,
 }
 
 impl PtySession {
@@ -120,8 +458,14 @@ impl PtySession {
             update_generation: Arc::new(std::sync::atomic::AtomicU64::new(0)),
             reply_xtwinops: Arc::new(AtomicBool::new(reply_xtwinops)),
             output_callback: Arc::new(Mutex::new(None)),
+            output_waiter: Arc::new(OutputWaiter::default()),
             coprocess_manager: Arc::new(Mutex::new(CoprocessManager::new())),
             child_pid: None,
+            reaped_exit_code: None,
+            foreground_pgid: Arc::new(AtomicU32::new(0)),
+            throttle: Arc::new(Mutex::new(OutputThrottle::default())),
+            suspended_pgid: None,
+            state_handle: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -166,11 +510,33 @@ impl PtySession {
     /// Get a clone of the PTY writer for external use (e.g., streaming server)
     ///
     /// This allows external code to write input to the PTY in a thread-safe way.
-    /// Returns None if the PTY is not running.
+    /// Returns None if the PTY is not running. Bytes written through this
+    /// handle bypass session recording; use [`PtySession::write`] for input
+    /// that should be recorded.
     pub fn get_writer(&self) -> Option<Arc<Mutex<Box<dyn Write + Send>>>> {
         self.writer.clone()
     }
 
+    /// Start recording the session.
+    ///
+    /// While recording, every chunk read from the PTY, every
+    /// [`PtySession::write`] and every resize is captured with its raw bytes
+    /// and a monotonic timestamp; no further calls are needed. Stop with
+    /// [`PtySession::stop_recording`] to get the [`RecordingSession`].
+    pub fn start_recording(&self, title: Option<String>) {
+        self.terminal.write().start_recording(title);
+    }
+
+    /// Stop recording and return the captured session, if one was active
+    pub fn stop_recording(&self) -> Option<RecordingSession> {
+        self.terminal.write().stop_recording()
+    }
+
+    /// Whether the session is being recorded
+    pub fn is_recording(&self) -> bool {
+        self.terminal.read().is_recording()
+    }
+
     /// Spawn a shell process (auto-detected from environment)
     ///
     /// On Unix: Uses $SHELL or defaults to /bin/bash
@@ -240,7 +606,12 @@ impl PtySession {
         env: Option<&HashMap<String, String>>,
         cwd: Option<&str>,
     ) -> Result<(), PtyError> {
-        self.spawn_internal(command, args, env, cwd)
+        let mut config = SpawnConfig::new(command).with_args(args.iter().copied());
+        if let Some(env) = env {
+            config.env = env.clone();
+        }
+        config.cwd = cwd.map(PathBuf::from);
+        self.spawn_with_config(&config)
     }
 
     /// Get the default shell for the current platform
@@ -381,31 +752,44 @@ impl PtySession {
     /// * `command` - The command to execute
     /// * `args` - Command-line arguments
     pub fn spawn(&mut self, command: &str, args: &[&str]) -> Result<(), PtyError> {
-        self.spawn_internal(command, args, None, None)
+        self.spawn_with_config(&SpawnConfig::new(command).with_args(args.iter().copied()))
     }
 
-    /// Internal implementation for spawning a process
+    /// Spawn a process described by a [`SpawnConfig`]
     ///
-    /// This handles all the PTY setup and process spawning logic.
+    /// Before a PTY is opened, the working directory must exist and the
+    /// command must resolve against the child's `PATH` (not the parent's), so
+    /// these mistakes come back as [`PtyError::SpawnFailed`] with the command,
+    /// argv, cwd and the searched `PATH` instead of a bare OS error.
     ///
-    /// # Arguments
-    /// * `command` - The command to execute
-    /// * `args` - Command-line arguments
-    /// * `additional_env` - Additional environment variables to set (applied after `set_env()` vars)
-    /// * `override_cwd` - Working directory override (takes precedence over `set_cwd()`)
-    fn spawn_internal(
-        &mut self,
-        command: &str,
-        args: &[&str],
-        additional_env: Option<&HashMap<String, String>>,
-        override_cwd: Option<&str>,
-    ) -> Result<(), PtyError> {
+    /// # Example
+    /// ```no_run
+    /// use par_term_emu_core_rust::pty_session::PtySession;
+    /// use par_term_emu_core_rust::pty_spawn::{EnvMode, SpawnConfig};
+    ///
+    /// let mut session = PtySession::new(80, 24, 1000);
+    /// let config = SpawnConfig::new("/bin/sh")
+    ///     .with_args(["-c", "echo $GREETING"])
+    ///     .with_cwd("/tmp")
+    ///     .with_env_mode(EnvMode::Replace)
+    ///     .with_env("GREETING", "hello")
+    ///     .with_term("xterm");
+    /// session.spawn_with_config(&config).unwrap();
+    /// ```
+    pub fn spawn_with_config(&mut self, config: &SpawnConfig) -> Result<(), PtyError> {
         if self.is_running() {
             return Err(PtyError::ProcessSpawnError(
                 "Process is already running".to_string(),
             ));
         }
 
+        let args = config.effective_args();
+        let cwd: Option<PathBuf> = config
+            .cwd
+            .clone()
+            .or_else(|| self.cwd.as_ref().map(PathBuf::from));
+        let cmd = self.build_command(config, &args, cwd.as_deref())?;
+
         // Clean up any previous session resources before spawning
         // This ensures the old reader thread is finished and PTY is closed
         self.cleanup_previous_session();
@@ -413,7 +797,7 @@ impl PtySession {
         debug::log(
             debug::DebugLevel::Info,
             "PTY_SPAWN",
-            &format!("Spawning process: {} {:?}", command, args),
+            &format!("Spawning process: {} {:?}", config.command, args),
         );
 
         // Create the PTY system
@@ -438,9 +822,13 @@ impl PtySession {
         );
 
         // Create the PTY pair
-        let pair = pty_system
-            .openpty(pty_size)
-            .map_err(|e| PtyError::ProcessSpawnError(e.to_string()))?;
+        let pair = pty_system.openpty(pty_size).map_err(|e| {
+            PtyError::SpawnFailed(Box::new(config.failure(
+                SpawnFailureKind::PtyUnavailable,
+                cwd.as_deref(),
+                e.to_string(),
+            )))
+        })?;
 
         debug::log(
             debug::DebugLevel::Trace,
@@ -451,11 +839,9 @@ impl PtySession {
             ),
         );
 
-        // Build the command
-        let mut cmd = CommandBuilder::new(command);
-        for arg in args {
-            cmd.arg(arg);
-        }
+        // Spawn the child process using the slave side. Drop our handle to the slave
+        // immediately after spawn so that when the child exits, the master side sees EOF.
+        let PtyPair { master, slave } = pair;
 
         // Check if login shell mode is requested (-l or --login flag)
         // For bash to properly recognize login shell via $0 and shopt login_shell,
@@ -463,94 +849,31 @@ impl PtySession {
         // files but doesn't set $0 to -bash.
         // We need to modify argv[0] AFTER path resolution but BEFORE exec.
         // Since CommandBuilder uses args[0] for both path resolution AND arg0,
-        // we detect login shell mode here and will handle it in the spawn.
-        let is_login_shell = args.iter().any(|a| *a == "-l" || *a == "--login");
-
-        // Inherit parent environment variables, but deliberately drop:
-        // 1. COLUMNS/LINES — static size hints that confuse apps after a PTY resize.
-        //    Many libraries (e.g. Python's shutil.get_terminal_size) and some TUIs
-        //    prioritize these over TIOCGWINSZ, staying stuck at the parent size.
-        // 2. TMUX/TMUX_PANE — multiplexer session vars from the parent terminal.
-        //    The child shell is inside a new PTY, NOT inside tmux. Inheriting these
-        //    causes tools like fzf to render in the parent tmux pane instead of here.
-        // 3. STY/WINDOW — GNU Screen equivalents of TMUX.
-        // CommandBuilder::new() pre-loads the full parent environment via
-        // get_base_env(), so we must explicitly remove unwanted vars with
-        // env_remove() — simply skipping them in the loop below is not enough.
-        const DROP_VARS: &[&str] = &["COLUMNS", "LINES", "TMUX", "TMUX_PANE", "STY", "WINDOW"];
-        let mut dropped: Vec<&str> = Vec::new();
-        for &var in DROP_VARS {
-            if std::env::var_os(var).is_some() {
-                cmd.env_remove(var);
-                dropped.push(var);
-            }
-        }
-        if !dropped.is_empty() {
-            debug::log(
-                debug::DebugLevel::Info,
-                "PTY_SPAWN",
-                &format!("Dropped env vars: {}", dropped.join(", ")),
-            );
-        }
-
-        // Re-apply parent env vars (overrides get_base_env values with current ones),
-        // but skip the vars we just removed so we don't re-add them.
-        for (key, value) in std::env::vars() {
-            if !DROP_VARS.contains(&key.as_str()) {
-                cmd.env(&key, &value);
-            }
-        }
-
-        // Set terminal-specific environment variables
-        cmd.env("TERM", "xterm-256color");
-        cmd.env("COLORTERM", "truecolor");
-        // Set Kitty-specific environment variables for protocol detection
-        cmd.env("TERM_PROGRAM", "kitty");
-        cmd.env("KITTY_WINDOW_ID", "1");
-        cmd.env("KITTY_PID", std::process::id().to_string());
-        // NOTE: Do NOT set COLUMNS/LINES environment variables!
-        // They are static and won't update on resize. Applications should
-        // query terminal size via ioctl(TIOCGWINSZ), not environment variables.
-        // Setting these breaks libraries like Textual that use shutil.get_terminal_size()
-        // which prioritizes env vars over ioctl.
-
-        // Override with user-specified environment variables (from set_env())
-        for (key, value) in &self.env_vars {
-            cmd.env(key, value);
-        }
-
-        // Apply additional environment variables passed directly to spawn
-        // These take precedence over set_env() vars
-        if let Some(env) = additional_env {
-            for (key, value) in env {
-                cmd.env(key, value);
-            }
-        }
-
-        // Set working directory
-        // Priority: override_cwd > self.cwd
-        let effective_cwd = override_cwd
-            .map(|s| s.to_string())
-            .or_else(|| self.cwd.clone());
-        if let Some(ref cwd) = effective_cwd {
-            cmd.cwd(cwd);
-        }
-
-        // Spawn the child process using the slave side. Drop our handle to the slave
-        // immediately after spawn so that when the child exits, the master side sees EOF.
-        let PtyPair { master, slave } = pair;
-
+        // we detect login shell mode here and handle it in the spawn.
+        let is_login_shell = args.iter().any(|a| a == "-l" || a == "--login");
+        let exec_failed = |e: &dyn std::fmt::Display| {
+            PtyError::SpawnFailed(Box::new(config.failure(
+                SpawnFailureKind::ExecFailed,
+                cwd.as_deref(),
+                e.to_string(),
+            )))
+        };
         let child = if is_login_shell {
             // For login shells, we need to set argv[0] to "-bash" for the shell
             // to properly recognize itself as a login shell via $0 and shopt login_shell.
             // The CommandBuilder's as_command() uses args[0] for both path resolution
             // and arg0, which doesn't work for login shells. We spawn manually.
-            self.spawn_login_shell(command, args, &cmd, &slave, additional_env.cloned())
-                .map_err(|e| PtyError::ProcessSpawnError(e.to_string()))?
+            let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+            self.spawn_login_shell(
+                &config.command,
+                &arg_refs,
+                &cmd,
+                &slave,
+                Some(config.env.clone()),
+            )
+            .map_err(|e| exec_failed(&e))?
         } else {
-            slave
-                .spawn_command(cmd)
-                .map_err(|e| PtyError::ProcessSpawnError(e.to_string()))?
+            slave.spawn_command(cmd).map_err(|e| exec_failed(&e))?
         };
         drop(slave);
 
@@ -574,6 +897,10 @@ impl PtySession {
         self.writer = Some(Arc::clone(&writer));
         self.running.store(true, Ordering::SeqCst);
         self.child_pid = child_pid;
+        self.reaped_exit_code = None;
+        self.foreground_pgid.store(0, Ordering::SeqCst);
+        self.suspended_pgid = None;
+        self.output_waiter.open();
 
         // Spawn the reader thread (shares writer for device query responses)
         self.start_reader_thread(reader, writer, child_pid);
@@ -581,6 +908,100 @@ impl PtySession {
         Ok(())
     }
 
+    /// Build the child's command line, environment and cwd from `config`,
+    /// validating the cwd and resolving the command on the child's `PATH`
+    fn build_command(
+        &self,
+        config: &SpawnConfig,
+        args: &[String],
+        cwd: Option<&Path>,
+    ) -> Result<CommandBuilder, PtyError> {
+        let fail = |(kind, detail): (SpawnFailureKind, String)| {
+            PtyError::SpawnFailed(Box::new(config.failure(kind, cwd, detail)))
+        };
+
+        let mut cmd = CommandBuilder::new(&config.command);
+        for arg in args {
+            cmd.arg(arg);
+        }
+
+        if config.env_mode == EnvMode::Inherit {
+            // Inherit parent environment variables, but deliberately drop:
+            // 1. COLUMNS/LINES — static size hints that confuse apps after a PTY resize.
+            //    Many libraries (e.g. Python's shutil.get_terminal_size) and some TUIs
+            //    prioritize these over TIOCGWINSZ, staying stuck at the parent size.
+            // 2. TMUX/TMUX_PANE — multiplexer session vars from the parent terminal.
+            //    The child shell is inside a new PTY, NOT inside tmux. Inheriting these
+            //    causes tools like fzf to render in the parent tmux pane instead of here.
+            // 3. STY/WINDOW — GNU Screen equivalents of TMUX.
+            // CommandBuilder::new() pre-loads the full parent environment via
+            // get_base_env(), so we must explicitly remove unwanted vars with
+            // env_remove() — simply skipping them in the loop below is not enough.
+            const DROP_VARS: &[&str] = &["COLUMNS", "LINES", "TMUX", "TMUX_PANE", "STY", "WINDOW"];
+            let mut dropped: Vec<&str> = Vec::new();
+            for &var in DROP_VARS {
+                if std::env::var_os(var).is_some() {
+                    cmd.env_remove(var);
+                    dropped.push(var);
+                }
+            }
+            if !dropped.is_empty() {
+                debug::log(
+                    debug::DebugLevel::Info,
+                    "PTY_SPAWN",
+                    &format!("Dropped env vars: {}", dropped.join(", ")),
+                );
+            }
+
+            // Re-apply parent env vars (overrides get_base_env values with current ones),
+            // but skip the vars we just removed so we don't re-add them.
+            for (key, value) in std::env::vars() {
+                if !DROP_VARS.contains(&key.as_str()) {
+                    cmd.env(&key, &value);
+                }
+            }
+            for key in &config.env_remove {
+                cmd.env_remove(key);
+            }
+        } else {
+            cmd.env_clear();
+        }
+
+        // Set terminal-specific environment variables
+        cmd.env("TERM", &config.term);
+        cmd.env("COLORTERM", "truecolor");
+        // Set Kitty-specific environment variables for protocol detection
+        cmd.env("TERM_PROGRAM", "kitty");
+        cmd.env("KITTY_WINDOW_ID", "1");
+        cmd.env("KITTY_PID", std::process::id().to_string());
+        // NOTE: Do NOT set COLUMNS/LINES environment variables!
+        // They are static and won't update on resize. Applications should
+        // query terminal size via ioctl(TIOCGWINSZ), not environment variables.
+        // Setting these breaks libraries like Textual that use shutil.get_terminal_size()
+        // which prioritizes env vars over ioctl.
+
+        // Override with user-specified environment variables (from set_env()),
+        // which belong to the inherited environment
+        if config.env_mode == EnvMode::Inherit {
+            for (key, value) in &self.env_vars {
+                cmd.env(key, value);
+            }
+        }
+
+        // Apply the config's environment variables last
+        for (key, value) in &config.env {
+            cmd.env(key, value);
+        }
+
+        if let Some(cwd) = cwd {
+            pty_spawn::check_cwd(cwd).map_err(fail)?;
+            cmd.cwd(cwd);
+        }
+        pty_spawn::resolve_command(&config.command, cmd.get_env("PATH"), cwd).map_err(fail)?;
+
+        Ok(cmd)
+    }
+
     /// Spawn a login shell.
     ///
     /// The `-l` flag is passed to the shell, which makes bash:
@@ -643,7 +1064,23 @@ impl PtySession {
         let update_generation = Arc::clone(&self.update_generation);
         let reply_xtwinops = Arc::clone(&self.reply_xtwinops);
         let output_callback = Arc::clone(&self.output_callback);
+        let output_waiter = Arc::clone(&self.output_waiter);
         let coprocess_manager = Arc::clone(&self.coprocess_manager);
+        let foreground_pgid = Arc::clone(&self.foreground_pgid);
+        let throttle = Arc::clone(&self.throttle);
+        let state_handle = Arc::clone(&self.state_handle);
+        // Own a duplicate of the master fd so the foreground check never
+        // touches a descriptor closed (and possibly reused) by the session
+        #[cfg(unix)]
+        let master_fd = self
+            .pty_master
+            .as_ref()
+            .and_then(|master| master.as_raw_fd())
+            .and_then(|fd| {
+                unsafe { std::os::fd::BorrowedFd::borrow_raw(fd) }
+                    .try_clone_to_owned()
+                    .ok()
+            });
 
         let handle = thread::spawn(move || {
             let mut buffer = [0u8; 16384];
@@ -651,13 +1088,52 @@ impl PtySession {
             loop {
                 match reader.read(&mut buffer) {
                     Ok(0) => {
-                        // EOF - process has exited
+                        // EOF - process has exited; report a burst that was
+                        // still being dropped
+                        let dropped = throttle.lock().take_dropped();
+                        if let Some(count) = dropped {
+                            let notice = drop_notice(count);
+                            throttle.lock().note_processed(notice.len());
+                            terminal.write().process_unrecorded(notice.as_bytes());
+                            update_generation.fetch_add(1, Ordering::SeqCst);
+                        }
                         running.store(false, Ordering::SeqCst);
+                        output_waiter.close();
                         break;
                     }
                     Ok(n) => {
                         debug::log_pty_read(n);
 
+                        // Apply the output budget before anything sees the bytes.
+                        // The drop notice goes to the terminal only.
+                        let mut notice = None;
+                        let admission = throttle.lock().admit(n, Instant::now());
+                        match admission {
+                            Admission::Drop => {
+                                if throttle.lock().claim_idle_watch() {
+                                    spawn_idle_drop_watch(
+                                        Arc::clone(&terminal),
+                                        Arc::clone(&throttle),
+                                        Arc::clone(&update_generation),
+                                    );
+                                }
+                                continue;
+                            }
+                            Admission::Defer(wait) => {
+                                // Sleep without holding any lock so the UI
+                                // thread gets the terminal in the meantime
+                                thread::sleep(wait);
+                                throttle.lock().resume(n, Instant::now());
+                            }
+                            Admission::Process { dropped } => {
+                                notice = dropped.map(drop_notice);
+                                if let Some(notice) = &notice {
+                                    throttle.lock().note_processed(notice.len());
+                                }
+                            }
+                        }
+                        let data = &buffer[..n];
+
                         // Bump generation counter IMMEDIATELY on successful read,
                         // before any processing. This guarantees the counter always
                         // advances when PTY data arrives, even if processing encounters
@@ -670,79 +1146,52 @@ impl PtySession {
                         {
                             let callback_guard = output_callback.lock();
                             if let Some(ref callback) = *callback_guard {
-                                callback(&buffer[..n]);
+                                callback(data);
                             }
                         }
 
                         // Feed terminal output to coprocesses
                         {
                             let mut mgr = coprocess_manager.lock();
-                            mgr.feed_output(&buffer[..n]);
+                            mgr.feed_output(data);
                         }
 
                         // Process the bytes through the terminal
                         {
                             let mut term = terminal.write();
                             let was_alt_screen = term.is_alt_screen_active();
-                            term.process(&buffer[..n]);
-                            // Record output for session recording
-                            term.record_output(&buffer[..n]);
+                            if let Some(notice) = &notice {
+                                term.process_unrecorded(notice.as_bytes());
+                            }
+                            // process() records the raw bytes for session recording
+                            term.process(data);
                             // Process trigger scans on dirty rows
                             term.process_trigger_scans();
                             let is_alt_screen = term.is_alt_screen_active();
 
-                            // Check for device query responses and write them back to the PTY
-                            // This enables nested TUI applications (vim, htop, etc.) to work correctly
-                            if term.has_pending_responses() {
-                                let mut responses = term.drain_responses();
-
-                                // Optional: filter out XTWINOPS (CSI t) replies to avoid shells
-                                // echoing them visibly when ECHOCTL is enabled. Controlled by env
-                                // PAR_TERM_REPLY_XTWINOPS (default: 1). Set to 0 to suppress.
-                                // (Cached from env var at PtySession initialization)
-                                if !reply_xtwinops.load(Ordering::Relaxed) {
-                                    let mut filtered = Vec::with_capacity(responses.len());
-                                    let mut i = 0;
-                                    while i < responses.len() {
-                                        if responses[i] == 0x1B
-                                            && i + 1 < responses.len()
-                                            && responses[i + 1] == b'['
-                                        {
-                                            // Collect until a final byte; drop if final is 't'
-                                            let mut j = i + 2;
-                                            let mut dropped = false;
-                                            while j < responses.len() {
-                                                let b = responses[j];
-                                                if (b as char).is_ascii_alphabetic() {
-                                                    // Alphabetic final byte for CSI
-                                                    if b == b't' {
-                                                        dropped = true;
-                                                    }
-                                                    j += 1;
-                                                    break;
-                                                }
-                                                j += 1;
-                                            }
-                                            if !dropped {
-                                                filtered.extend_from_slice(&responses[i..j]);
-                                            }
-                                            i = j;
-                                        } else {
-                                            filtered.push(responses[i]);
-                                            i += 1;
-                                        }
-                                    }
-                                    responses = filtered;
+                            #[cfg(unix)]
+                            if let Some(ref fd) = master_fd {
+                                use std::os::fd::AsRawFd;
+                                let pgid = unsafe { libc::tcgetpgrp(fd.as_raw_fd()) };
+                                if pgid > 0 {
+                                    note_foreground_pgid(pgid as u32, &foreground_pgid, &mut term);
                                 }
+                            }
 
-                                if !responses.is_empty() {
-                                    debug::log_device_query("pending", &responses);
-                                    {
-                                        let mut w = writer.lock();
-                                        // Write responses back to PTY master so child can read them
-                                        let _ = w.write_all(&responses);
-                                        let _ = w.flush();
-                                    }
+                            // Write device query responses back to the PTY so nested
+                            // TUI applications (vim, htop, etc.) work correctly
+                            write_replies(
+                                &mut term,
+                                &writer,
+                                reply_xtwinops.load(Ordering::Relaxed),
+                            );
+
+                            // Publish while still holding the lock so handles
+                            // are ordered with the chunks they follow
+                            {
+                                let mut published = state_handle.lock();
+                                if published.is_some() {
+                                    *published = Some(term.front_buffer());
                                 }
                             }
 
@@ -785,6 +1234,10 @@ impl PtySession {
                                 }
                             }
                         }
+
+                        // Wake waiters only after the terminal lock is released
+                        // so they observe the processed output
+                        output_waiter.notify(data);
                     }
                     Err(e) => {
                         // Log error but continue (could be temporary)
@@ -792,6 +1245,7 @@ impl PtySession {
                         // If it's a fatal error, stop
                         if e.kind() == std::io::ErrorKind::BrokenPipe {
                             running.store(false, Ordering::SeqCst);
+                            output_waiter.close();
                             break;
                         }
                     }
@@ -817,7 +1271,11 @@ impl PtySession {
         {
             let mut term = self.terminal.write();
             term.record_input(data);
+            term.record_macro_input(data);
+            term.mark_input_written();
         }
+        // Replies queued by API calls since the last output go first
+        self.flush_replies();
 
         if let Some(ref writer) = self.writer {
             let mut w = writer.lock();
@@ -829,6 +1287,23 @@ impl PtySession {
         }
     }
 
+    /// Write any queued replies to the PTY now.
+    ///
+    /// The reader thread flushes replies after every chunk of output, and
+    /// [`PtySession::write`] and the resize methods flush first, so this is
+    /// only needed for replies queued through [`PtySession::terminal`] (e.g.
+    /// [`Terminal::send_upload_data`]) that should not wait for either.
+    pub fn flush_replies(&self) {
+        if let Some(ref writer) = self.writer {
+            let mut term = self.terminal.write();
+            write_replies(
+                &mut term,
+                writer,
+                self.reply_xtwinops.load(Ordering::Relaxed),
+            );
+        }
+    }
+
     /// Write a string to the PTY (convenience method)
     ///
     /// # Arguments
@@ -851,10 +1326,10 @@ impl PtySession {
         // Resize the terminal
         {
             let mut term = self.terminal.write();
+            // Terminal::resize records the resize event while recording
             term.resize(cols as usize, rows as usize);
-            // Record resize event for session recording
-            term.record_resize(cols as usize, rows as usize);
         }
+        self.flush_replies();
 
         // Resize the PTY (sends SIGWINCH to child)
         if let Some(ref master) = self.pty_master {
@@ -977,6 +1452,7 @@ impl PtySession {
             term.resize(cols as usize, rows as usize);
             term.set_pixel_size(pixel_width as usize, pixel_height as usize);
         }
+        self.flush_replies();
 
         // Resize the PTY (sends SIGWINCH to child)
         if let Some(ref master) = self.pty_master {
@@ -1058,6 +1534,93 @@ impl PtySession {
         self.child_pid
     }
 
+    /// PID of the PTY's foreground process group leader (Unix only)
+    ///
+    /// This is the shell while it waits at the prompt, and the running
+    /// command (or the first process of a pipeline) otherwise.
+    pub fn foreground_pid(&self) -> Option<u32> {
+        #[cfg(unix)]
+        {
+            let pgid = self.pty_master.as_ref()?.process_group_leader()?;
+            (pgid > 0).then_some(pgid as u32)
+        }
+        #[cfg(not(unix))]
+        {
+            None
+        }
+    }
+
+    /// PID, name and working directory of the foreground process group
+    /// leader, e.g. for a tab title
+    ///
+    /// Returns `None` before spawn, after exit, on platforms without process
+    /// introspection, or if the leader has already exited.
+    pub fn foreground_process(&self) -> Option<ProcessInfo> {
+        process_tree::process_info(self.foreground_pid()?)
+    }
+
+    /// Whether a job other than the spawned process holds the foreground,
+    /// e.g. a command started from the shell
+    ///
+    /// Frontends use this to confirm closing a tab while something runs.
+    pub fn has_foreground_job(&self) -> bool {
+        match (self.foreground_pid(), self.child_pid) {
+            (Some(foreground), Some(child)) => foreground != child,
+            _ => false,
+        }
+    }
+
+    /// The spawned process followed by all of its descendants, breadth
+    /// first. Empty before spawn or when the platform has no process
+    /// introspection.
+    pub fn process_tree(&self) -> Vec<ProcessInfo> {
+        let Some(pid) = self.child_pid else {
+            return Vec::new();
+        };
+        let Some(root) = process_tree::process_info(pid) else {
+            return Vec::new();
+        };
+        let mut tree = vec![root];
+        tree.extend(process_tree::descendants(pid));
+        tree
+    }
+
+    /// Check the foreground process group now and emit
+    /// `ForegroundProcessChanged` if it changed since the last check
+    ///
+    /// The reader thread checks after every chunk of output; call this on a
+    /// timer to also notice jobs that start without printing anything.
+    /// Returns `true` if an event was emitted.
+    pub fn poll_foreground_process(&self) -> bool {
+        let Some(pgid) = self.foreground_pid() else {
+            return false;
+        };
+        let mut term = self.terminal.write();
+        note_foreground_pgid(pgid, &self.foreground_pgid, &mut term)
+    }
+
+    /// Set the output throttling config
+    ///
+    /// Takes effect with the next chunk of output, also while running.
+    pub fn set_throttle_config(&self, config: ThrottleConfig) {
+        self.throttle.lock().config = config;
+    }
+
+    /// Get the output throttling config
+    pub fn throttle_config(&self) -> ThrottleConfig {
+        self.throttle.lock().config
+    }
+
+    /// Bytes processed, deferred and dropped by the output throttle
+    pub fn throttle_stats(&self) -> ThrottleStats {
+        self.throttle.lock().stats
+    }
+
+    /// Reset the output throttle counters
+    pub fn reset_throttle_stats(&self) {
+        self.throttle.lock().stats = ThrottleStats::default();
+    }
+
     /// Try to get the exit status without blocking
     ///
     /// Returns None if the process hasn't exited yet
@@ -1066,6 +1629,7 @@ impl PtySession {
             match child.try_wait() {
                 Ok(Some(status)) => {
                     self.running.store(false, Ordering::SeqCst);
+                    self.reaped_exit_code = Some(status.exit_code() as i32);
                     Ok(Some(status.exit_code() as i32))
                 }
                 Ok(None) => Ok(None),
@@ -1083,6 +1647,7 @@ impl PtySession {
         if let Some(ref mut child) = self.child {
             let status = child.wait().map_err(PtyError::IoError)?;
             self.running.store(false, Ordering::SeqCst);
+            self.reaped_exit_code = Some(status.exit_code() as i32);
             Ok(status.exit_code() as i32)
         } else {
             Err(PtyError::NotStartedError)
@@ -1100,6 +1665,98 @@ impl PtySession {
         }
     }
 
+    /// PID of the spawned process, or an error before spawn or once the
+    /// process has been reaped (its PID may have been reused since)
+    fn signalable_pid(&self) -> Result<u32, PtyError> {
+        if let Some(code) = self.reaped_exit_code {
+            return Err(PtyError::ProcessExitedError(code));
+        }
+        self.child_pid.ok_or(PtyError::NotStartedError)
+    }
+
+    /// Send `signal` to the foreground job: the PTY's foreground process
+    /// group, or the spawned process's group if the foreground is unknown
+    ///
+    /// Unlike writing `^C`, this reaches jobs that turned off signal keys or
+    /// stopped reading input. Fails with [`PtyError::ProcessExitedError`]
+    /// once [`try_wait`](Self::try_wait), [`wait`](Self::wait) or
+    /// [`process_status`](Self::process_status) has reaped the process. Unix
+    /// only.
+    pub fn send_signal(&self, signal: PtySignal) -> Result<(), PtyError> {
+        let child = self.signalable_pid()?;
+        let pgid = self.foreground_pid().unwrap_or(child);
+        signal_pid(-(pgid as i32), signal)
+    }
+
+    /// Send `signal` to the spawned process only (not its children). Unix only.
+    pub fn signal_child(&self, signal: PtySignal) -> Result<(), PtyError> {
+        let child = self.signalable_pid()?;
+        signal_pid(child as i32, signal)
+    }
+
+    /// Suspend the foreground job and return its process group ID
+    ///
+    /// A job started from the shell gets SIGTSTP, as Ctrl+Z would send. The
+    /// spawned process's own group has no parent in its session, so the
+    /// kernel discards SIGTSTP there; it gets SIGSTOP instead.
+    pub fn suspend(&mut self) -> Result<u32, PtyError> {
+        let child = self.signalable_pid()?;
+        let pgid = self.foreground_pid().unwrap_or(child);
+        let signal = if pgid == child {
+            PtySignal::Stop
+        } else {
+            PtySignal::TerminalStop
+        };
+        signal_pid(-(pgid as i32), signal)?;
+        self.suspended_pgid = Some(pgid);
+        Ok(pgid)
+    }
+
+    /// Resume the job stopped by the last [`suspend`](Self::suspend) (or the
+    /// foreground job) with SIGCONT
+    ///
+    /// An interactive shell takes the terminal back when a job stops, so a
+    /// job resumed this way runs in the background; write `fg` to the shell
+    /// to bring it back to the foreground instead.
+    pub fn resume(&mut self) -> Result<(), PtyError> {
+        let child = self.signalable_pid()?;
+        let pgid = self
+            .suspended_pgid
+            .take()
+            .or_else(|| self.foreground_pid())
+            .unwrap_or(child);
+        signal_pid(-(pgid as i32), PtySignal::Continue)
+    }
+
+    /// Whether the spawned process is running, stopped, exited or killed by
+    /// a signal
+    ///
+    /// Like [`try_wait`](Self::try_wait), this reaps the process once it has
+    /// finished. Stopped processes are detected through process
+    /// introspection (Linux and macOS).
+    pub fn process_status(&mut self) -> ProcessStatus {
+        let Some(child) = self.child.as_mut() else {
+            return ProcessStatus::NotStarted;
+        };
+        if let Ok(Some(status)) = child.try_wait() {
+            self.running.store(false, Ordering::SeqCst);
+            self.reaped_exit_code = Some(status.exit_code() as i32);
+            return match status.signal() {
+                Some(description) => ProcessStatus::Signaled(description.to_string()),
+                None => ProcessStatus::Exited(status.exit_code() as i32),
+            };
+        }
+        let stopped = self
+            .child_pid
+            .and_then(process_tree::process_info)
+            .is_some_and(|info| info.stopped);
+        if stopped {
+            ProcessStatus::Stopped
+        } else {
+            ProcessStatus::Running
+        }
+    }
+
     /// Get a reference to the underlying terminal
     pub fn terminal(&self) -> Arc<RwLock<Terminal>> {
         Arc::clone(&self.terminal)
@@ -1114,6 +1771,30 @@ impl PtySession {
         &self.terminal
     }
 
+    /// Get an immutable handle on the visible screen, cursor and modes
+    ///
+    /// Never waits for PTY output being processed: if the reader thread holds
+    /// the terminal, the handle it published after the previous chunk is
+    /// returned. The first call enables publishing and may wait for the lock
+    /// once. With double buffering on, handles are the committed front
+    /// buffer (see [`Terminal::front_buffer`]). See [`TerminalStateHandle`].
+    pub fn state_handle(&self) -> TerminalStateHandle {
+        if let Some(term) = self.terminal.try_read() {
+            // Store while holding the read lock, so the reader thread cannot
+            // publish a newer handle in between
+            let handle = term.front_buffer();
+            *self.state_handle.lock() = Some(handle.clone());
+            return handle;
+        }
+        if let Some(handle) = self.state_handle.lock().clone() {
+            return handle;
+        }
+        let term = self.terminal.read();
+        let handle = term.front_buffer();
+        *self.state_handle.lock() = Some(handle.clone());
+        handle
+    }
+
     /// Get the terminal content as a string
     pub fn content(&self) -> String {
         let term = self.terminal.write();
@@ -1233,6 +1914,26 @@ impl PtySession {
         self.update_generation.load(Ordering::SeqCst)
     }
 
+    /// Shared handle for blocking until output arrives (see [`OutputWaiter`])
+    pub fn output_waiter(&self) -> Arc<OutputWaiter> {
+        Arc::clone(&self.output_waiter)
+    }
+
+    /// Wait for output matching one of `patterns`, pexpect style
+    ///
+    /// See [`expect_output`]; this waits on this session's terminal.
+    ///
+    /// # Errors
+    /// [`ExpectError::Timeout`] if `timeout` elapses, or [`ExpectError::Eof`]
+    /// if the process exits, without a match.
+    pub fn expect(
+        &self,
+        patterns: &ExpectPatterns,
+        timeout: Option<Duration>,
+    ) -> Result<ExpectMatch, ExpectError> {
+        expect_output(&self.terminal, &self.output_waiter, patterns, timeout)
+    }
+
     /// Check if the terminal has been updated since a given generation
     ///
     /// # Arguments
@@ -1316,6 +2017,9 @@ impl Drop for PtySession {
             let _ = self.kill();
         }
 
+        // Release anyone blocked on output even if the reader thread outlives us
+        self.output_waiter.close();
+
         // Signal the reader thread to stop (best-effort: it is typically blocked
         // in `read()` and only actually unblocks once the master fd / child
         // process closes below).
@@ -1418,6 +2122,203 @@ mod tests {
         assert_eq!(session.size(), (100, 30));
     }
 
+    #[test]
+    fn test_recording_captures_resizes_without_spawn() {
+        let mut session = PtySession::new(80, 24, 1000);
+        assert!(!session.is_recording());
+        session.start_recording(Some("pty".to_string()));
+        assert!(session.is_recording());
+
+        session.resize(100, 30).ok();
+        session.resize_with_pixels(90, 25, 900, 500).ok();
+
+        let recording = session.stop_recording().unwrap();
+        assert!(!session.is_recording());
+        assert_eq!(recording.initial_size, (80, 24));
+        let sizes: Vec<_> = recording.events.iter().map(|e| e.metadata).collect();
+        assert_eq!(sizes, vec![Some((100, 30)), Some((90, 25))]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_recording_captures_pty_output() {
+        let mut session = PtySession::new(80, 24, 1000);
+        session.start_recording(None);
+        session.spawn("/bin/echo", &["recorded"]).unwrap();
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        while session.is_running() && std::time::Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+
+        let recording = session.stop_recording().unwrap();
+        let output: Vec<u8> = recording
+            .events
+            .iter()
+            .filter(|e| e.event_type == crate::terminal::RecordingEventType::Output)
+            .flat_map(|e| e.data.iter().copied())
+            .collect();
+        // Each PTY read is recorded exactly once
+        assert_eq!(
+            String::from_utf8_lossy(&output).matches("recorded").count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_output_waiter_states() {
+        let waiter = OutputWaiter::default();
+        // Not spawned yet: waits return immediately
+        assert_eq!(waiter.wait_for_update(0, None), WaitOutcome::Closed);
+
+        waiter.open();
+        assert_eq!(
+            waiter.wait_for_update(0, Some(Duration::from_millis(10))),
+            WaitOutcome::TimedOut
+        );
+
+        waiter.notify(b"ignored");
+        waiter.set_output_capture(true);
+        waiter.notify(b"abc");
+        assert_eq!(waiter.wait_for_update(0, None), WaitOutcome::Updated(2));
+        assert_eq!(waiter.take_output(), b"abc");
+        assert!(waiter.take_output().is_empty());
+    }
+
+    #[test]
+    fn test_output_waiter_wakes_blocked_thread() {
+        let waiter = Arc::new(OutputWaiter::default());
+        waiter.open();
+
+        let blocked = Arc::clone(&waiter);
+        let handle = thread::spawn(move || blocked.wait_for_update(0, None));
+        thread::sleep(Duration::from_millis(20));
+        waiter.interrupt();
+        assert_eq!(handle.join().unwrap(), WaitOutcome::Interrupted);
+
+        let blocked = Arc::clone(&waiter);
+        let handle = thread::spawn(move || blocked.wait_for_update(0, None));
+        thread::sleep(Duration::from_millis(20));
+        waiter.close();
+        assert_eq!(handle.join().unwrap(), WaitOutcome::Closed);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_output_waiter_sees_processed_output() {
+        let mut session = PtySession::new(80, 24, 1000);
+        let waiter = session.output_waiter();
+        waiter.set_output_capture(true);
+        session.spawn("/bin/echo", &["waited"]).unwrap();
+
+        let mut generation = 0;
+        let mut output = Vec::new();
+        while let WaitOutcome::Updated(next) =
+            waiter.wait_for_update(generation, Some(Duration::from_secs(5)))
+        {
+            generation = next;
+            output.extend(waiter.take_output());
+        }
+        assert!(!waiter.is_open());
+        assert!(String::from_utf8_lossy(&output).contains("waited"));
+        assert!(session.content().contains("waited"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_expect_waits_for_alternatives() {
+        let mut session = PtySession::new(80, 24, 1000);
+        session
+            .terminal()
+            .write()
+            .set_expect_buffer(Some(DEFAULT_EXPECT_BUFFER_BYTES));
+        session
+            .spawn(
+                "/bin/sh",
+                &[
+                    "-c",
+                    "sleep 0.1; echo 'Password:'; sleep 0.1; echo 'took 42ms'",
+                ],
+            )
+            .unwrap();
+
+        let patterns = ExpectPatterns::new(["login:", "Password:"]).unwrap();
+        let found = session
+            .expect(&patterns, Some(Duration::from_secs(5)))
+            .unwrap();
+        assert_eq!(found.index, 1);
+
+        let timing = ExpectPatterns::new([r"took (\d+)ms"]).unwrap();
+        let found = session
+            .expect(&timing, Some(Duration::from_secs(5)))
+            .unwrap();
+        assert_eq!(found.before, "\n");
+        assert_eq!(found.groups, vec![Some("42".to_string())]);
+
+        let never = ExpectPatterns::new(["never"]).unwrap();
+        assert!(matches!(
+            session.expect(&never, Some(Duration::from_secs(5))),
+            Err(ExpectError::Eof(_))
+        ));
+    }
+
+    #[test]
+    fn test_expect_times_out_with_unconsumed_text() {
+        let session = PtySession::new(80, 24, 1000);
+        session.output_waiter.open();
+        session.terminal().write().set_expect_buffer(Some(64));
+        session.terminal().write().process(b"partial");
+        let patterns = ExpectPatterns::new(["complete"]).unwrap();
+        assert_eq!(
+            session.expect(&patterns, Some(Duration::from_millis(20))),
+            Err(ExpectError::Timeout("partial".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_state_handle_does_not_wait_for_a_held_terminal() {
+        let session = PtySession::new(20, 4, 100);
+        session.terminal().write().process(b"one");
+        let first = session.state_handle();
+        assert_eq!(first.line(0).unwrap().trim_end(), "one");
+
+        let terminal = session.terminal();
+        let mut term = terminal.write();
+        term.process(b" two");
+        // The lock is held, so the last published handle comes back
+        assert!(session.state_handle().ptr_eq(&first));
+        drop(term);
+
+        let second = session.state_handle();
+        assert_eq!(second.line(0).unwrap().trim_end(), "one two");
+        // Earlier handles are unaffected
+        assert_eq!(first.line(0).unwrap().trim_end(), "one");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_reader_thread_publishes_state_handles() {
+        let mut session = PtySession::new(80, 24, 1000);
+        // Requesting a handle turns publishing on
+        let before = session.state_handle();
+        session
+            .spawn("/bin/sh", &["-c", "sleep 0.2; echo ready; sleep 1"])
+            .unwrap();
+        let patterns = ExpectPatterns::new(["ready"]).unwrap();
+        session
+            .expect(&patterns, Some(Duration::from_secs(5)))
+            .unwrap();
+
+        // With the terminal locked, the handle published by the reader
+        // thread already holds the output
+        let terminal = session.terminal();
+        let _term = terminal.write();
+        let handle = session.state_handle();
+        assert!(handle.text().contains("ready"));
+        assert!(!before.text().contains("ready"));
+    }
+
     #[test]
     fn test_set_env() {
         let mut session = PtySession::new(80, 24, 1000);
@@ -1631,6 +2532,75 @@ mod tests {
         assert!(found, "Expected directory path in output, got: {}", content);
     }
 
+    #[cfg(unix)]
+    fn wait_for_text(session: &PtySession, needle: &str) -> String {
+        let start = std::time::Instant::now();
+        loop {
+            let content = session.export_text();
+            if content.contains(needle) || start.elapsed() > Duration::from_secs(5) {
+                return content;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_spawn_with_config_env_cwd_and_term() {
+        let mut session = PtySession::new(80, 24, 1000);
+        session.set_env("FROM_SET_ENV", "dropped");
+        let config = SpawnConfig::new("/bin/sh")
+            .with_args([
+                "-c",
+                "echo \"[$GREETING|$TERM|$FROM_SET_ENV|$HOME|$(pwd)]\"",
+            ])
+            .with_cwd("/")
+            .with_env_mode(EnvMode::Replace)
+            .with_env("GREETING", "hi")
+            .with_term("vt220");
+        session.spawn_with_config(&config).unwrap();
+        let content = wait_for_text(&session, "[hi|");
+        assert!(content.contains("[hi|vt220|||/]"), "got: {content}");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_spawn_with_config_augments_inherited_env() {
+        let mut session = PtySession::new(80, 24, 1000);
+        session.set_env("FROM_SET_ENV", "kept");
+        let config = SpawnConfig::new("sh")
+            .with_args(["-c", "echo \"[$FROM_SET_ENV|$EXTRA|${PATH:+path}]\""])
+            .with_env("EXTRA", "added");
+        session.spawn_with_config(&config).unwrap();
+        let content = wait_for_text(&session, "[kept");
+        assert!(content.contains("[kept|added|path]"), "got: {content}");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_spawn_with_config_diagnostics() {
+        let mut session = PtySession::new(80, 24, 1000);
+
+        let config = SpawnConfig::new("no-such-command-4112").with_env("PATH", "/nonexistent");
+        match session.spawn_with_config(&config) {
+            Err(PtyError::SpawnFailed(failure)) => {
+                assert_eq!(failure.kind, SpawnFailureKind::CommandNotFound);
+                assert!(failure.detail.contains("/nonexistent"), "{failure}");
+            }
+            other => panic!("expected SpawnFailed, got {other:?}"),
+        }
+
+        let config = SpawnConfig::new("/bin/sh").with_cwd("/nonexistent-dir-4112");
+        match session.spawn_with_config(&config) {
+            Err(PtyError::SpawnFailed(failure)) => {
+                assert_eq!(failure.kind, SpawnFailureKind::CwdNotFound);
+                assert_eq!(failure.cwd.as_deref(), Some("/nonexistent-dir-4112"));
+            }
+            other => panic!("expected SpawnFailed, got {other:?}"),
+        }
+        assert!(!session.is_running());
+    }
+
     #[test]
     fn test_spawn_shell_with_env_cwd() {
         let mut session = PtySession::new(80, 24, 1000);
@@ -1878,6 +2848,25 @@ mod tests {
         );
     }
 
+    /// Replies queued outside the reader thread reach the child on
+    /// `flush_replies()`; `cat` echoes them back as output.
+    #[cfg(unix)]
+    #[test]
+    fn test_flush_replies_writes_queued_replies_to_child() {
+        let mut session = PtySession::new(80, 24, 1000);
+        session.spawn("/bin/cat", &[]).unwrap();
+        session.terminal().write().push_response(b"PING\r");
+        session.flush_replies();
+        assert!(!session.terminal().read().has_pending_responses());
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !session.content().contains("PING") && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert!(session.content().contains("PING"));
+        let _ = session.kill();
+    }
+
     #[test]
     fn test_get_writer_before_spawn_is_none() {
         let session = PtySession::new(80, 24, 1000);
@@ -1921,6 +2910,257 @@ mod tests {
     // Coverage-focused deterministic tests (no live PTY / no timing)
     // ===================================================================
 
+    #[test]
+    fn test_foreground_process_none_before_spawn() {
+        let session = PtySession::new(80, 24, 1000);
+        assert_eq!(session.foreground_pid(), None);
+        assert!(session.foreground_process().is_none());
+        assert!(session.process_tree().is_empty());
+        assert!(!session.has_foreground_job());
+        assert!(!session.poll_foreground_process());
+    }
+
+    #[test]
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    fn test_foreground_process_reports_spawned_command() {
+        let mut session = PtySession::new(80, 24, 1000);
+        session.spawn("/bin/sleep", &["5"]).unwrap();
+        let pid = session.child_pid().unwrap();
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        let mut info = session.foreground_process();
+        while info.as_ref().is_none_or(|info| info.name != "sleep")
+            && std::time::Instant::now() < deadline
+        {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            info = session.foreground_process();
+        }
+        let info = info.expect("foreground process");
+        assert_eq!(info.pid, pid);
+        assert_eq!(info.name, "sleep");
+        // The spawned command leads its own job, so nothing else is running
+        assert!(!session.has_foreground_job());
+        assert_eq!(session.process_tree()[0].pid, pid);
+
+        assert!(session.poll_foreground_process());
+        assert!(!session.poll_foreground_process());
+        let events = session.terminal().write().poll_events();
+        assert!(events.iter().any(|event| matches!(
+            event,
+            TerminalEvent::ForegroundProcessChanged { pid: p, name } if *p == pid && name == "sleep"
+        )));
+        session.kill().ok();
+    }
+
+    #[test]
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    fn test_process_tree_lists_descendants() {
+        let mut session = PtySession::new(80, 24, 1000);
+        session
+            .spawn("/bin/sh", &["-c", "sleep 5 & sleep 5; wait"])
+            .unwrap();
+        let pid = session.child_pid().unwrap();
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        let mut tree = session.process_tree();
+        while tree.iter().filter(|info| info.name == "sleep").count() < 2
+            && std::time::Instant::now() < deadline
+        {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            tree = session.process_tree();
+        }
+        assert_eq!(tree[0].pid, pid);
+        let sleeps: Vec<_> = tree.iter().filter(|info| info.name == "sleep").collect();
+        assert_eq!(sleeps.len(), 2);
+        assert!(sleeps.iter().all(|info| info.ppid == pid));
+        session.kill().ok();
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_throttle_drops_runaway_output_and_summarizes() {
+        let mut session = PtySession::new(80, 24, 1000);
+        session.set_throttle_config(ThrottleConfig {
+            enabled: true,
+            max_bytes_per_interval: 1024,
+            interval_ms: 10,
+            drop_threshold: 4096,
+        });
+        let streamed = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&streamed);
+        session.set_output_callback(Arc::new(move |data: &[u8]| {
+            sink.lock().extend_from_slice(data);
+        }));
+        session.start_recording(None);
+        session
+            .spawn(
+                "/bin/sh",
+                &[
+                    "-c",
+                    "head -c 200000 /dev/zero | tr '\\0' x; sleep 0.1; echo done",
+                ],
+            )
+            .unwrap();
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while session.is_running() && std::time::Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+
+        let stats = session.throttle_stats();
+        assert!(stats.bytes_dropped > 0, "{stats:?}");
+        assert_eq!(stats.bursts_dropped, 1);
+        assert!(session.content().contains("bytes of output dropped"));
+        assert!(session.content().contains("done"));
+
+        // The notice is shown, not passed on as child output
+        let dropped = b"bytes of output dropped";
+        let streamed = streamed.lock();
+        assert!(!streamed.windows(dropped.len()).any(|w| w == dropped));
+        let recording = session.stop_recording().unwrap();
+        assert!(recording
+            .events
+            .iter()
+            .all(|e| !e.data.windows(dropped.len()).any(|w| w == dropped)));
+
+        session.reset_throttle_stats();
+        assert_eq!(session.throttle_stats(), ThrottleStats::default());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_throttle_notice_is_written_when_output_ends_mid_burst() {
+        let mut session = PtySession::new(80, 24, 1000);
+        session.set_throttle_config(ThrottleConfig {
+            enabled: true,
+            max_bytes_per_interval: 1024,
+            interval_ms: 10,
+            drop_threshold: 4096,
+        });
+        session
+            .spawn("/bin/sh", &["-c", "head -c 200000 /dev/zero | tr '\\0' x"])
+            .unwrap();
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while session.is_running() && std::time::Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+
+        assert!(session.throttle_stats().bytes_dropped > 0);
+        assert!(session.content().contains("bytes of output dropped"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_throttle_notice_is_written_when_output_goes_quiet() {
+        let mut session = PtySession::new(80, 24, 1000);
+        session.set_throttle_config(ThrottleConfig {
+            enabled: true,
+            max_bytes_per_interval: 1024,
+            interval_ms: 10,
+            drop_threshold: 4096,
+        });
+        // The child stays alive and silent after the burst: no EOF, no
+        // further read
+        session
+            .spawn(
+                "/bin/sh",
+                &["-c", "head -c 200000 /dev/zero | tr '\\0' x; sleep 5"],
+            )
+            .unwrap();
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(4);
+        while !session.content().contains("bytes of output dropped")
+            && std::time::Instant::now() < deadline
+        {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        assert!(session.is_running());
+        assert!(session.throttle_stats().bytes_dropped > 0);
+        assert!(session.content().contains("bytes of output dropped"));
+        session.kill().ok();
+    }
+
+    #[test]
+    fn test_signals_before_spawn_return_not_started() {
+        let mut session = PtySession::new(80, 24, 1000);
+        assert!(matches!(
+            session.send_signal(PtySignal::Interrupt),
+            Err(PtyError::NotStartedError)
+        ));
+        assert!(matches!(
+            session.signal_child(PtySignal::Terminate),
+            Err(PtyError::NotStartedError)
+        ));
+        assert!(matches!(session.suspend(), Err(PtyError::NotStartedError)));
+        assert!(matches!(session.resume(), Err(PtyError::NotStartedError)));
+        assert_eq!(session.process_status(), ProcessStatus::NotStarted);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_signals_after_exit_return_process_exited() {
+        let mut session = PtySession::new(80, 24, 1000);
+        session.spawn("/bin/sh", &["-c", "exit 3"]).unwrap();
+        assert_eq!(session.wait().unwrap(), 3);
+
+        assert!(matches!(
+            session.send_signal(PtySignal::Interrupt),
+            Err(PtyError::ProcessExitedError(3))
+        ));
+        assert!(matches!(
+            session.signal_child(PtySignal::Terminate),
+            Err(PtyError::ProcessExitedError(3))
+        ));
+        assert!(matches!(
+            session.suspend(),
+            Err(PtyError::ProcessExitedError(3))
+        ));
+        assert!(matches!(
+            session.resume(),
+            Err(PtyError::ProcessExitedError(3))
+        ));
+    }
+
+    #[test]
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    fn test_suspend_resume_and_interrupt() {
+        fn wait_for(session: &mut PtySession, wanted: fn(&ProcessStatus) -> bool) -> ProcessStatus {
+            let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+            loop {
+                let status = session.process_status();
+                if wanted(&status) || std::time::Instant::now() >= deadline {
+                    return status;
+                }
+                std::thread::sleep(std::time::Duration::from_millis(20));
+            }
+        }
+
+        let mut session = PtySession::new(80, 24, 1000);
+        session.spawn("/bin/sleep", &["5"]).unwrap();
+        let pid = session.child_pid().unwrap();
+        assert_eq!(session.process_status(), ProcessStatus::Running);
+
+        assert_eq!(session.suspend().unwrap(), pid);
+        assert_eq!(
+            wait_for(&mut session, |s| *s == ProcessStatus::Stopped),
+            ProcessStatus::Stopped
+        );
+
+        session.resume().unwrap();
+        assert_eq!(
+            wait_for(&mut session, |s| *s == ProcessStatus::Running),
+            ProcessStatus::Running
+        );
+
+        session.send_signal(PtySignal::Interrupt).unwrap();
+        let status = wait_for(&mut session, ProcessStatus::is_finished);
+        assert!(matches!(status, ProcessStatus::Signaled(_)), "{status:?}");
+        assert!(!session.is_running());
+    }
+
     #[test]
     fn test_child_pid_none_before_spawn() {
         // child_pid() must return None when no process has been spawned.
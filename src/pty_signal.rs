@@ -0,0 +1,156 @@
+//! Signals and process status for PTY sessions
+//!
+//! [`PtySignal`] names the signals a frontend typically sends to a job (an
+//! interrupt from a "kill hung command" action, a suspend, a resume) without
+//! depending on `libc` constants. [`ProcessStatus`] tells a running child
+//! apart from one that is stopped, exited or killed by a signal.
+
+/// Signal that can be sent to a PTY child or its foreground job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PtySignal {
+    /// SIGHUP: the terminal went away
+    Hangup,
+    /// SIGINT: what Ctrl+C sends
+    Interrupt,
+    /// SIGQUIT: what Ctrl+\ sends
+    Quit,
+    /// SIGKILL: cannot be caught or ignored
+    Kill,
+    /// SIGUSR1
+    User1,
+    /// SIGUSR2
+    User2,
+    /// SIGTERM: polite request to exit
+    Terminate,
+    /// SIGCONT: resume a stopped process
+    Continue,
+    /// SIGSTOP: stop, cannot be caught or ignored
+    Stop,
+    /// SIGTSTP: what Ctrl+Z sends
+    TerminalStop,
+    /// SIGWINCH: the window size changed
+    WindowChange,
+}
+
+impl PtySignal {
+    /// Every signal, in declaration order
+    pub const ALL: [PtySignal; 11] = [
+        PtySignal::Hangup,
+        PtySignal::Interrupt,
+        PtySignal::Quit,
+        PtySignal::Kill,
+        PtySignal::User1,
+        PtySignal::User2,
+        PtySignal::Terminate,
+        PtySignal::Continue,
+        PtySignal::Stop,
+        PtySignal::TerminalStop,
+        PtySignal::WindowChange,
+    ];
+
+    /// Conventional name, e.g. `"SIGINT"`
+    pub fn name(self) -> &'static str {
+        match self {
+            PtySignal::Hangup => "SIGHUP",
+            PtySignal::Interrupt => "SIGINT",
+            PtySignal::Quit => "SIGQUIT",
+            PtySignal::Kill => "SIGKILL",
+            PtySignal::User1 => "SIGUSR1",
+            PtySignal::User2 => "SIGUSR2",
+            PtySignal::Terminate => "SIGTERM",
+            PtySignal::Continue => "SIGCONT",
+            PtySignal::Stop => "SIGSTOP",
+            PtySignal::TerminalStop => "SIGTSTP",
+            PtySignal::WindowChange => "SIGWINCH",
+        }
+    }
+
+    /// Parse a signal name, with or without the `SIG` prefix and in any case
+    /// (`"SIGINT"`, `"int"`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        let upper = name.trim().to_ascii_uppercase();
+        let short = upper.strip_prefix("SIG").unwrap_or(&upper);
+        Self::ALL
+            .into_iter()
+            .find(|signal| &signal.name()[3..] == short)
+    }
+
+    /// The platform signal number
+    #[cfg(unix)]
+    pub fn as_raw(self) -> libc::c_int {
+        match self {
+            PtySignal::Hangup => libc::SIGHUP,
+            PtySignal::Interrupt => libc::SIGINT,
+            PtySignal::Quit => libc::SIGQUIT,
+            PtySignal::Kill => libc::SIGKILL,
+            PtySignal::User1 => libc::SIGUSR1,
+            PtySignal::User2 => libc::SIGUSR2,
+            PtySignal::Terminate => libc::SIGTERM,
+            PtySignal::Continue => libc::SIGCONT,
+            PtySignal::Stop => libc::SIGSTOP,
+            PtySignal::TerminalStop => libc::SIGTSTP,
+            PtySignal::WindowChange => libc::SIGWINCH,
+        }
+    }
+}
+
+impl std::fmt::Display for PtySignal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// State of a PTY session's spawned process
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProcessStatus {
+    /// Nothing has been spawned
+    NotStarted,
+    /// Running (or sleeping)
+    Running,
+    /// Stopped by SIGSTOP/SIGTSTP (or a tty read/write from the background)
+    /// and waiting for SIGCONT
+    Stopped,
+    /// Exited normally with this code
+    Exited(i32),
+    /// Killed by a signal; carries the platform's description of it
+    /// (e.g. `"Interrupt"`)
+    Signaled(String),
+}
+
+impl ProcessStatus {
+    /// Short lowercase name: `not_started`, `running`, `stopped`, `exited`
+    /// or `signaled`
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProcessStatus::NotStarted => "not_started",
+            ProcessStatus::Running => "running",
+            ProcessStatus::Stopped => "stopped",
+            ProcessStatus::Exited(_) => "exited",
+            ProcessStatus::Signaled(_) => "signaled",
+        }
+    }
+
+    /// Whether the process has exited or been killed
+    pub fn is_finished(&self) -> bool {
+        matches!(self, ProcessStatus::Exited(_) | ProcessStatus::Signaled(_))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_name_accepts_short_and_long_forms() {
+        assert_eq!(PtySignal::from_name("SIGINT"), Some(PtySignal::Interrupt));
+        assert_eq!(PtySignal::from_name("int"), Some(PtySignal::Interrupt));
+        assert_eq!(
+            PtySignal::from_name("sigtstp"),
+            Some(PtySignal::TerminalStop)
+        );
+        assert_eq!(PtySignal::from_name("SIGBOGUS"), None);
+        for signal in PtySignal::ALL {
+            assert_eq!(PtySignal::from_name(signal.name()), Some(signal));
+        }
+    }
+}
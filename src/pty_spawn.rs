@@ -0,0 +1,278 @@
+//! Spawn configuration for PTY sessions
+//!
+//! [`SpawnConfig`] describes the child precisely: program and arguments,
+//! working directory, how the environment is built, login-shell mode and the
+//! `TERM` value. [`PtySession::spawn_with_config`] checks the working
+//! directory and resolves the program against the child's own `PATH` before
+//! opening a PTY, so a typo or a missing virtualenv surfaces as a
+//! [`SpawnFailure`] naming what was searched instead of a bare OS error.
+//!
+//! [`PtySession::spawn_with_config`]: crate::pty_session::PtySession::spawn_with_config
+
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use crate::pty_error::{SpawnFailure, SpawnFailureKind};
+
+/// Default `TERM` for spawned processes
+pub const DEFAULT_TERM: &str = "xterm-256color";
+
+/// Where the child's environment starts from
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EnvMode {
+    /// The parent's environment (minus `COLUMNS`/`LINES` and multiplexer
+    /// variables such as `TMUX`) plus variables set with
+    /// `PtySession::set_env()`; [`SpawnConfig::env`] is layered on top
+    #[default]
+    Inherit,
+    /// Only [`SpawnConfig::env`], plus the terminal identification variables
+    /// (`TERM`, `COLORTERM`, ...). Without a `PATH` entry the command must be
+    /// given as a path.
+    Replace,
+}
+
+/// How to spawn a PTY child
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpawnConfig {
+    /// Program to run, as a path or a name looked up on the child's `PATH`
+    pub command: String,
+    /// Arguments after the program
+    pub args: Vec<String>,
+    /// Working directory (`None` = the session's `set_cwd()`, else inherit)
+    pub cwd: Option<PathBuf>,
+    /// Where the environment starts from
+    pub env_mode: EnvMode,
+    /// Variables to set, applied last
+    pub env: HashMap<String, String>,
+    /// Inherited variables to remove (e.g. `VIRTUAL_ENV`)
+    pub env_remove: Vec<String>,
+    /// Start the program as a login shell (adds `-l` unless `-l`/`--login`
+    /// is already among the arguments)
+    pub login_shell: bool,
+    /// Value of `TERM`
+    pub term: String,
+}
+
+impl SpawnConfig {
+    /// Config that runs `command` with no arguments
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+            args: Vec::new(),
+            cwd: None,
+            env_mode: EnvMode::Inherit,
+            env: HashMap::new(),
+            env_remove: Vec::new(),
+            login_shell: false,
+            term: DEFAULT_TERM.to_string(),
+        }
+    }
+
+    /// Config that runs the user's shell (see `PtySession::get_default_shell`)
+    pub fn shell() -> Self {
+        Self::new(crate::pty_session::PtySession::get_default_shell())
+    }
+
+    /// Append an argument
+    pub fn with_arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Append arguments
+    pub fn with_args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    /// Set the working directory
+    pub fn with_cwd(mut self, cwd: impl Into<PathBuf>) -> Self {
+        self.cwd = Some(cwd.into());
+        self
+    }
+
+    /// Set where the environment starts from
+    pub fn with_env_mode(mut self, mode: EnvMode) -> Self {
+        self.env_mode = mode;
+        self
+    }
+
+    /// Set an environment variable
+    pub fn with_env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.insert(key.into(), value.into());
+        self
+    }
+
+    /// Remove an inherited environment variable
+    pub fn without_env(mut self, key: impl Into<String>) -> Self {
+        self.env_remove.push(key.into());
+        self
+    }
+
+    /// Start the program as a login shell
+    pub fn with_login_shell(mut self, login: bool) -> Self {
+        self.login_shell = login;
+        self
+    }
+
+    /// Set `TERM`
+    pub fn with_term(mut self, term: impl Into<String>) -> Self {
+        self.term = term.into();
+        self
+    }
+
+    /// Arguments as passed to the program, including `-l` for login shells
+    pub fn effective_args(&self) -> Vec<String> {
+        let mut args = self.args.clone();
+        if self.login_shell && !args.iter().any(|arg| arg == "-l" || arg == "--login") {
+            args.insert(0, "-l".to_string());
+        }
+        args
+    }
+
+    /// Failure of `kind` for this config
+    pub(crate) fn failure(
+        &self,
+        kind: SpawnFailureKind,
+        cwd: Option<&Path>,
+        detail: impl Into<String>,
+    ) -> SpawnFailure {
+        SpawnFailure {
+            kind,
+            command: self.command.clone(),
+            args: self.effective_args(),
+            cwd: cwd.map(|cwd| cwd.to_string_lossy().into_owned()),
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Check that `cwd` is an existing directory
+pub(crate) fn check_cwd(cwd: &Path) -> Result<(), (SpawnFailureKind, String)> {
+    match std::fs::metadata(cwd) {
+        Ok(meta) if meta.is_dir() => Ok(()),
+        Ok(_) => Err((SpawnFailureKind::CwdNotDirectory, String::new())),
+        Err(err) => Err((SpawnFailureKind::CwdNotFound, err.to_string())),
+    }
+}
+
+/// Resolve `command` the way the child's exec will: a name containing `/` is
+/// a path (relative to `cwd`), anything else is searched on `path`
+#[cfg(unix)]
+pub(crate) fn resolve_command(
+    command: &str,
+    path: Option<&OsStr>,
+    cwd: Option<&Path>,
+) -> Result<PathBuf, (SpawnFailureKind, String)> {
+    if command.contains('/') {
+        let candidate = match cwd {
+            Some(cwd) if !command.starts_with('/') => cwd.join(command),
+            _ => PathBuf::from(command),
+        };
+        return match std::fs::metadata(&candidate) {
+            Ok(meta) if is_executable(&meta) => Ok(candidate),
+            Ok(meta) if meta.is_dir() => Err((
+                SpawnFailureKind::NotExecutable,
+                "is a directory".to_string(),
+            )),
+            Ok(_) => Err((
+                SpawnFailureKind::NotExecutable,
+                "missing execute permission".to_string(),
+            )),
+            Err(err) => Err((SpawnFailureKind::CommandNotFound, err.to_string())),
+        };
+    }
+
+    let Some(path) = path.filter(|path| !path.is_empty()) else {
+        return Err((
+            SpawnFailureKind::CommandNotFound,
+            "PATH is not set in the child environment".to_string(),
+        ));
+    };
+    let mut not_executable = None;
+    for dir in std::env::split_paths(path) {
+        let candidate = dir.join(command);
+        if let Ok(meta) = std::fs::metadata(&candidate) {
+            if is_executable(&meta) {
+                return Ok(candidate);
+            }
+            if meta.is_file() {
+                not_executable.get_or_insert(candidate);
+            }
+        }
+    }
+    match not_executable {
+        Some(candidate) => Err((
+            SpawnFailureKind::NotExecutable,
+            format!("{} is missing execute permission", candidate.display()),
+        )),
+        None => Err((
+            SpawnFailureKind::CommandNotFound,
+            format!("not found in PATH {}", path.to_string_lossy()),
+        )),
+    }
+}
+
+/// Command lookup is left to the OS where executables are found through
+/// `PATHEXT` and friends
+#[cfg(not(unix))]
+pub(crate) fn resolve_command(
+    command: &str,
+    _path: Option<&OsStr>,
+    _cwd: Option<&Path>,
+) -> Result<PathBuf, (SpawnFailureKind, String)> {
+    Ok(PathBuf::from(command))
+}
+
+#[cfg(unix)]
+fn is_executable(meta: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    meta.is_file() && meta.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_login_shell_adds_flag_once() {
+        let config = SpawnConfig::new("/bin/bash").with_login_shell(true);
+        assert_eq!(config.effective_args(), vec!["-l"]);
+        let config = config.with_arg("--login");
+        assert_eq!(config.effective_args(), vec!["--login"]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_resolve_command_reports_searched_path() {
+        let path = OsStr::new("/nonexistent-a:/nonexistent-b");
+        let (kind, detail) = resolve_command("no-such-command", Some(path), None).unwrap_err();
+        assert_eq!(kind, SpawnFailureKind::CommandNotFound);
+        assert!(detail.contains("/nonexistent-a:/nonexistent-b"), "{detail}");
+
+        let (kind, _) = resolve_command("sh", None, None).unwrap_err();
+        assert_eq!(kind, SpawnFailureKind::CommandNotFound);
+
+        let (kind, _) = resolve_command("/tmp", None, None).unwrap_err();
+        assert_eq!(kind, SpawnFailureKind::NotExecutable);
+
+        assert_eq!(
+            resolve_command("sh", Some(OsStr::new("/nonexistent:/bin")), None).unwrap(),
+            PathBuf::from("/bin/sh")
+        );
+    }
+
+    #[test]
+    fn test_check_cwd() {
+        assert!(check_cwd(Path::new("/")).is_ok());
+        assert_eq!(
+            check_cwd(Path::new("/nonexistent-dir")).unwrap_err().0,
+            SpawnFailureKind::CwdNotFound
+        );
+    }
+}
@@ -0,0 +1,361 @@
+//! Output throttling for PTY sessions
+//!
+//! A command like `yes` produces output faster than any frontend can draw it,
+//! and while the reader thread processes it the UI thread keeps waiting for the
+//! terminal lock. With throttling enabled the reader processes at most
+//! [`ThrottleConfig::max_bytes_per_interval`] bytes per interval; past that it
+//! releases the lock and sleeps until the next interval, which also slows the
+//! child down once the PTY buffer fills.
+//!
+//! With a [`ThrottleConfig::drop_threshold`] set, a burst that stays over budget
+//! for that many bytes is dropped instead of delayed, similar to iTerm2's
+//! adaptive rate limiting. When output falls back under budget, stops for two
+//! intervals, or the child closes the PTY, a one-line notice with the dropped
+//! byte count is written to the terminal only: output callbacks, coprocesses and session recordings
+//! never see it. Dropping can cut an escape sequence in half, so it is meant
+//! for runaway plain output.
+
+use std::time::{Duration, Instant};
+
+/// Output throttling settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThrottleConfig {
+    /// Whether output is throttled (off by default)
+    pub enabled: bool,
+    /// Bytes processed per interval before the reader yields
+    pub max_bytes_per_interval: usize,
+    /// Length of an interval in milliseconds
+    pub interval_ms: u64,
+    /// Drop output once a burst has gone this many bytes over budget
+    /// (0 = never drop, only delay)
+    pub drop_threshold: usize,
+}
+
+impl Default for ThrottleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_bytes_per_interval: 1024 * 1024,
+            interval_ms: 16,
+            drop_threshold: 0,
+        }
+    }
+}
+
+/// Throttling counters since the session was created or the stats were reset
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ThrottleStats {
+    /// Bytes handed to the terminal (including drop notices)
+    pub bytes_processed: u64,
+    /// Bytes whose processing was postponed to a later interval
+    pub bytes_deferred: u64,
+    /// Times the reader yielded because the budget was used up
+    pub deferrals: u64,
+    /// Bytes discarded by drop-and-summarize
+    pub bytes_dropped: u64,
+    /// Bursts that had output dropped
+    pub bursts_dropped: u64,
+}
+
+/// What to do with a chunk of output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Admission {
+    /// Process now; `dropped` is the byte count of a burst that just ended
+    /// and needs a notice written first
+    Process { dropped: Option<u64> },
+    /// Sleep this long, then process
+    Defer(Duration),
+    /// Discard the chunk
+    Drop,
+}
+
+/// What the idle watch should do about a dropped burst
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum IdleCheck {
+    /// Output is still arriving
+    Wait,
+    /// Output has gone quiet and the notice is owed
+    Flush,
+    /// Nothing is left to report
+    Done,
+}
+
+/// Budget bookkeeping shared between a session and its reader thread
+#[derive(Debug)]
+pub(crate) struct OutputThrottle {
+    pub(crate) config: ThrottleConfig,
+    pub(crate) stats: ThrottleStats,
+    window_start: Option<Instant>,
+    window_bytes: usize,
+    /// Bytes over budget in the current burst
+    burst_excess: usize,
+    /// Bytes dropped in the current burst
+    burst_dropped: u64,
+    /// When the last chunk was read
+    last_read: Option<Instant>,
+    /// Whether an idle watch is waiting to report the dropped burst
+    idle_watch: bool,
+}
+
+impl Default for OutputThrottle {
+    fn default() -> Self {
+        Self::new(ThrottleConfig::default())
+    }
+}
+
+impl OutputThrottle {
+    pub(crate) fn new(config: ThrottleConfig) -> Self {
+        Self {
+            config,
+            stats: ThrottleStats::default(),
+            window_start: None,
+            window_bytes: 0,
+            burst_excess: 0,
+            burst_dropped: 0,
+            last_read: None,
+            idle_watch: false,
+        }
+    }
+
+    /// Decide what to do with `len` bytes read at `now`
+    pub(crate) fn admit(&mut self, len: usize, now: Instant) -> Admission {
+        if !self.config.enabled || self.config.max_bytes_per_interval == 0 {
+            self.stats.bytes_processed += len as u64;
+            return Admission::Process { dropped: None };
+        }
+        self.last_read = Some(now);
+        let interval = Duration::from_millis(self.config.interval_ms);
+        let start = match self.window_start {
+            Some(start) if now.duration_since(start) < interval => start,
+            _ => {
+                // A window that ended under budget, or a whole interval
+                // without output, closes the burst
+                let idle = self
+                    .window_start
+                    .is_some_and(|start| now.duration_since(start) >= interval * 2);
+                if idle || self.window_bytes <= self.config.max_bytes_per_interval {
+                    self.burst_excess = 0;
+                }
+                self.window_start = Some(now);
+                self.window_bytes = 0;
+                now
+            }
+        };
+
+        let dropped = if self.burst_excess == 0 && self.burst_dropped > 0 {
+            Some(std::mem::take(&mut self.burst_dropped))
+        } else {
+            None
+        };
+
+        if self.window_bytes + len <= self.config.max_bytes_per_interval {
+            self.window_bytes += len;
+            self.stats.bytes_processed += len as u64;
+            return Admission::Process { dropped };
+        }
+
+        self.burst_excess += len;
+        let threshold = self.config.drop_threshold;
+        if threshold > 0 && self.burst_excess > threshold {
+            self.window_bytes += len;
+            if self.burst_dropped == 0 {
+                self.stats.bursts_dropped += 1;
+            }
+            self.burst_dropped += len as u64;
+            self.stats.bytes_dropped += len as u64;
+            return Admission::Drop;
+        }
+
+        self.stats.bytes_deferred += len as u64;
+        self.stats.deferrals += 1;
+        Admission::Defer(interval.saturating_sub(now.duration_since(start)))
+    }
+
+    /// Count `len` deferred bytes as the first output of a new window
+    /// starting at `now`, once the reader has slept
+    pub(crate) fn resume(&mut self, len: usize, now: Instant) {
+        self.window_start = Some(now);
+        self.window_bytes = len;
+        self.stats.bytes_processed += len as u64;
+    }
+
+    /// Count the bytes of a drop notice as processed
+    pub(crate) fn note_processed(&mut self, len: usize) {
+        self.stats.bytes_processed += len as u64;
+    }
+
+    /// Take the byte count of a burst still being dropped, so its notice
+    /// can be written when the output ends before the burst does
+    pub(crate) fn take_dropped(&mut self) -> Option<u64> {
+        self.burst_excess = 0;
+        Some(std::mem::take(&mut self.burst_dropped)).filter(|&count| count > 0)
+    }
+
+    /// Claim the idle watch for the burst being dropped
+    ///
+    /// Returns false if nothing was dropped or a watch is already running.
+    pub(crate) fn claim_idle_watch(&mut self) -> bool {
+        if self.idle_watch || self.burst_dropped == 0 {
+            return false;
+        }
+        self.idle_watch = true;
+        true
+    }
+
+    /// Check whether a dropped burst has ended without further output
+    ///
+    /// Releases the watch once there is nothing left to report.
+    pub(crate) fn check_idle(&mut self, now: Instant) -> IdleCheck {
+        if self.burst_dropped == 0 {
+            self.idle_watch = false;
+            return IdleCheck::Done;
+        }
+        let interval = Duration::from_millis(self.config.interval_ms);
+        if self
+            .last_read
+            .is_some_and(|last| now.duration_since(last) < interval * 2)
+        {
+            IdleCheck::Wait
+        } else {
+            IdleCheck::Flush
+        }
+    }
+}
+
+/// Line written to the terminal after a dropped burst
+pub(crate) fn drop_notice(dropped: u64) -> String {
+    format!("\r\n\x1b[0;7m[{dropped} bytes of output dropped]\x1b[0m\r\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn throttle(drop_threshold: usize) -> OutputThrottle {
+        OutputThrottle::new(ThrottleConfig {
+            enabled: true,
+            max_bytes_per_interval: 100,
+            interval_ms: 10,
+            drop_threshold,
+        })
+    }
+
+    #[test]
+    fn test_disabled_throttle_always_processes() {
+        let mut throttle = OutputThrottle::default();
+        let now = Instant::now();
+        for _ in 0..10 {
+            assert_eq!(
+                throttle.admit(1 << 20, now),
+                Admission::Process { dropped: None }
+            );
+        }
+        assert_eq!(throttle.stats.bytes_processed, 10 << 20);
+        assert_eq!(throttle.stats.deferrals, 0);
+    }
+
+    #[test]
+    fn test_over_budget_defers_until_next_window() {
+        let mut throttle = throttle(0);
+        let now = Instant::now();
+        assert_eq!(
+            throttle.admit(80, now),
+            Admission::Process { dropped: None }
+        );
+        let later = now + Duration::from_millis(4);
+        assert_eq!(
+            throttle.admit(40, later),
+            Admission::Defer(Duration::from_millis(6))
+        );
+        throttle.resume(40, later + Duration::from_millis(6));
+        assert_eq!(throttle.stats.bytes_deferred, 40);
+        assert_eq!(throttle.stats.deferrals, 1);
+        assert_eq!(throttle.stats.bytes_processed, 120);
+    }
+
+    #[test]
+    fn test_long_burst_drops_then_summarizes() {
+        let mut throttle = throttle(50);
+        let mut now = Instant::now();
+        assert_eq!(
+            throttle.admit(100, now),
+            Admission::Process { dropped: None }
+        );
+        // 40 over budget: still under the drop threshold
+        assert!(matches!(throttle.admit(40, now), Admission::Defer(_)));
+        // 80 over budget: dropped
+        assert_eq!(throttle.admit(40, now), Admission::Drop);
+        assert_eq!(throttle.admit(40, now), Admission::Drop);
+
+        // Next window is still over budget, so the burst goes on
+        now += Duration::from_millis(10);
+        assert_eq!(throttle.admit(120, now), Admission::Drop);
+
+        // A quiet window ends the burst; the next chunk carries the summary
+        now += Duration::from_millis(10);
+        assert_eq!(
+            throttle.admit(10, now),
+            Admission::Process { dropped: None }
+        );
+        now += Duration::from_millis(10);
+        assert_eq!(
+            throttle.admit(10, now),
+            Admission::Process { dropped: Some(200) }
+        );
+        assert_eq!(throttle.stats.bytes_dropped, 200);
+        assert_eq!(throttle.stats.bursts_dropped, 1);
+    }
+
+    #[test]
+    fn test_take_dropped_ends_an_unfinished_burst() {
+        let mut throttle = throttle(10);
+        let now = Instant::now();
+        assert_eq!(throttle.take_dropped(), None);
+        throttle.admit(100, now);
+        assert_eq!(throttle.admit(50, now), Admission::Drop);
+        assert_eq!(throttle.take_dropped(), Some(50));
+        assert_eq!(throttle.take_dropped(), None);
+    }
+
+    #[test]
+    fn test_idle_watch_flushes_once_output_stops() {
+        let mut throttle = throttle(10);
+        let now = Instant::now();
+        assert!(!throttle.claim_idle_watch());
+        throttle.admit(100, now);
+        assert_eq!(throttle.admit(50, now), Admission::Drop);
+        assert!(throttle.claim_idle_watch());
+        assert!(!throttle.claim_idle_watch());
+
+        assert_eq!(
+            throttle.check_idle(now + Duration::from_millis(10)),
+            IdleCheck::Wait
+        );
+        let later = now + Duration::from_millis(20);
+        assert_eq!(throttle.check_idle(later), IdleCheck::Flush);
+        assert_eq!(throttle.take_dropped(), Some(50));
+        assert_eq!(throttle.check_idle(later), IdleCheck::Done);
+
+        // The watch was released, so the next dropped burst gets one too
+        throttle.admit(100, later);
+        throttle.admit(50, later);
+        assert_eq!(throttle.admit(50, later), Admission::Drop);
+        assert!(throttle.claim_idle_watch());
+    }
+
+    #[test]
+    fn test_idle_interval_ends_burst() {
+        let mut throttle = throttle(10);
+        let now = Instant::now();
+        throttle.admit(100, now);
+        assert_eq!(throttle.admit(50, now), Admission::Drop);
+
+        // The burst window was over budget, but nothing arrived for two
+        // intervals afterwards
+        let later = now + Duration::from_millis(25);
+        assert_eq!(
+            throttle.admit(5, later),
+            Admission::Process { dropped: Some(50) }
+        );
+    }
+}
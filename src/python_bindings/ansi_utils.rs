@@ -0,0 +1,161 @@
+//! Python bindings for ANSI text utilities
+use crate::ansi_utils::{SpanAttributes, StyledSpan};
+use crate::cell::UnderlineStyle;
+use crate::color::Color;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+use super::enums::PyUnderlineStyle;
+
+/// Boolean attribute keys of a span dict, in SGR order
+const FLAG_KEYS: [&str; 8] = [
+    "bold",
+    "dim",
+    "italic",
+    "blink",
+    "reverse",
+    "hidden",
+    "strikethrough",
+    "overline",
+];
+
+fn flags(attrs: &SpanAttributes) -> [bool; 8] {
+    [
+        attrs.bold,
+        attrs.dim,
+        attrs.italic,
+        attrs.blink,
+        attrs.reverse,
+        attrs.hidden,
+        attrs.strikethrough,
+        attrs.overline,
+    ]
+}
+
+fn color_to_py(py: Python<'_>, color: Option<Color>) -> PyResult<Py<PyAny>> {
+    Ok(match color {
+        None => py.None(),
+        Some(Color::Named(c)) => (c as u8).into_pyobject(py)?.into_any().unbind(),
+        Some(Color::Indexed(idx)) => idx.into_pyobject(py)?.into_any().unbind(),
+        Some(Color::Rgb(r, g, b)) => (r, g, b).into_pyobject(py)?.into_any().unbind(),
+    })
+}
+
+fn color_from_py(value: &Bound<'_, PyAny>) -> PyResult<Option<Color>> {
+    if value.is_none() {
+        return Ok(None);
+    }
+    if let Ok(idx) = value.extract::<u8>() {
+        return Ok(Some(Color::from_ansi_code(idx)));
+    }
+    if let Ok((r, g, b)) = value.extract::<(u8, u8, u8)>() {
+        return Ok(Some(Color::Rgb(r, g, b)));
+    }
+    Err(PyValueError::new_err(
+        "color must be None, a palette index (0-255) or an (r, g, b) tuple",
+    ))
+}
+
+fn span_to_dict<'py>(py: Python<'py>, span: &StyledSpan) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("text", &span.text)?;
+    dict.set_item("fg", color_to_py(py, span.fg)?)?;
+    dict.set_item("bg", color_to_py(py, span.bg)?)?;
+    dict.set_item("underline_color", color_to_py(py, span.underline_color)?)?;
+    for (key, value) in FLAG_KEYS.iter().zip(flags(&span.attrs)) {
+        dict.set_item(key, value)?;
+    }
+    dict.set_item("underline", PyUnderlineStyle::from(span.attrs.underline))?;
+    dict.set_item("link", &span.link)?;
+    Ok(dict)
+}
+
+fn span_from_dict(dict: &Bound<'_, PyDict>) -> PyResult<StyledSpan> {
+    let get = |key: &str| dict.get_item(key).ok().flatten();
+    let flag =
+        |key: &str| -> PyResult<bool> { get(key).map_or(Ok(false), |value| value.is_truthy()) };
+    let color = |key: &str| get(key).map_or(Ok(None), |value| color_from_py(&value));
+    let underline = match get("underline") {
+        None => UnderlineStyle::None,
+        Some(value) if value.is_none() => UnderlineStyle::None,
+        Some(value) => value.extract::<PyUnderlineStyle>()?.into(),
+    };
+    Ok(StyledSpan {
+        text: get("text").map_or(Ok(String::new()), |value| value.extract())?,
+        fg: color("fg")?,
+        bg: color("bg")?,
+        underline_color: color("underline_color")?,
+        attrs: SpanAttributes {
+            bold: flag("bold")?,
+            dim: flag("dim")?,
+            italic: flag("italic")?,
+            underline,
+            blink: flag("blink")?,
+            reverse: flag("reverse")?,
+            hidden: flag("hidden")?,
+            strikethrough: flag("strikethrough")?,
+            overline: flag("overline")?,
+        },
+        link: get("link").map_or(Ok(None), |value| value.extract())?,
+    })
+}
+
+/// Split text containing ANSI escape sequences into styled spans.
+///
+/// SGR sequences set the style and OSC 8 sequences the hyperlink of the text
+/// that follows. Tabs and newlines are kept; other escape sequences and
+/// control characters are dropped. No Terminal is needed.
+///
+/// Args:
+///     text (str): Text with ANSI escape sequences (e.g. captured tool output)
+///
+/// Returns:
+///     list[dict]: One dict per span with keys "text", "fg", "bg",
+///     "underline_color" (None, palette index 0-255 or (r, g, b) tuple),
+///     "bold", "dim", "italic", "blink", "reverse", "hidden",
+///     "strikethrough", "overline" (bool), "underline" (UnderlineStyle)
+///     and "link" (str or None)
+///
+/// Example:
+///     >>> from par_term_emu_core_rust import parse_ansi_spans
+///     >>> spans = parse_ansi_spans("ok \x1b[1;31mFAIL\x1b[0m")
+///     >>> [(s["text"], s["fg"], s["bold"]) for s in spans]
+///     [('ok ', None, False), ('FAIL', 1, True)]
+#[pyfunction]
+#[pyo3(name = "parse_ansi_spans")]
+pub fn py_parse_ansi_spans(py: Python<'_>, text: &str) -> PyResult<Py<PyList>> {
+    let list = PyList::empty(py);
+    for span in crate::ansi_utils::parse_spans(text) {
+        list.append(span_to_dict(py, &span)?)?;
+    }
+    Ok(list.unbind())
+}
+
+/// Render styled spans back into text with ANSI escape sequences.
+///
+/// The reverse of `parse_ansi_spans()`. Missing keys take their default
+/// (no color, attribute off, no link). The output ends in the default style.
+///
+/// Args:
+///     spans (list[dict]): Spans in the format returned by `parse_ansi_spans()`
+///
+/// Returns:
+///     str: Text with SGR and OSC 8 sequences
+///
+/// Raises:
+///     ValueError: If a color is not None, an int 0-255 or an (r, g, b) tuple
+///
+/// Example:
+///     >>> from par_term_emu_core_rust import spans_to_ansi
+///     >>> spans_to_ansi([{"text": "warn", "fg": 3, "bold": True}])
+///     '\x1b[0;1;33mwarn\x1b[0m'
+#[pyfunction]
+#[pyo3(name = "spans_to_ansi")]
+pub fn py_spans_to_ansi(spans: Vec<Bound<'_, PyDict>>) -> PyResult<String> {
+    let spans = spans
+        .iter()
+        .map(span_from_dict)
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(crate::ansi_utils::spans_to_ansi(&spans))
+}
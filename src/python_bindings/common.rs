@@ -29,6 +29,105 @@ pub(crate) trait TerminalAccess {
     fn term_mut(&mut self) -> impl std::ops::DerefMut<Target = Terminal>;
 }
 
+/// Parse a Python appearance name ("dark" / "light")
+pub(crate) fn parse_appearance(name: &str) -> pyo3::PyResult<crate::terminal::Appearance> {
+    crate::terminal::Appearance::from_name(name).ok_or_else(|| {
+        pyo3::exceptions::PyValueError::new_err(format!(
+            "Unknown appearance: {} (expected \"dark\" or \"light\")",
+            name
+        ))
+    })
+}
+
+/// Parse a Python reply overflow policy name ("evict_lower_priority" / "drop_newest")
+pub(crate) fn parse_reply_overflow_policy(
+    name: &str,
+) -> pyo3::PyResult<crate::terminal::ReplyOverflowPolicy> {
+    use crate::terminal::ReplyOverflowPolicy;
+
+    match name {
+        "evict_lower_priority" => Ok(ReplyOverflowPolicy::EvictLowerPriority),
+        "drop_newest" => Ok(ReplyOverflowPolicy::DropNewest),
+        _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "Unknown reply overflow policy: {} (expected \"evict_lower_priority\" or \"drop_newest\")",
+            name
+        ))),
+    }
+}
+
+/// Python name of a reply overflow policy
+pub(crate) fn reply_overflow_policy_name(
+    policy: crate::terminal::ReplyOverflowPolicy,
+) -> &'static str {
+    use crate::terminal::ReplyOverflowPolicy;
+
+    match policy {
+        ReplyOverflowPolicy::EvictLowerPriority => "evict_lower_priority",
+        ReplyOverflowPolicy::DropNewest => "drop_newest",
+    }
+}
+
+/// Parse an export format name from Python ("plain", "html" or "ansi")
+pub(crate) fn parse_export_format(name: &str) -> pyo3::PyResult<crate::terminal::ExportFormat> {
+    use crate::terminal::ExportFormat;
+
+    match name {
+        "plain" => Ok(ExportFormat::Plain),
+        "html" => Ok(ExportFormat::Html),
+        "ansi" => Ok(ExportFormat::Ansi),
+        _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "Invalid export format: {} (expected \"plain\", \"html\" or \"ansi\")",
+            name
+        ))),
+    }
+}
+
+/// Parse a text overwrite mode name from Python
+pub(crate) fn parse_text_overwrite_mode(
+    name: &str,
+) -> pyo3::PyResult<crate::graphics::TextOverwriteMode> {
+    use crate::graphics::TextOverwriteMode;
+
+    match name {
+        "overlay" => Ok(TextOverwriteMode::Overlay),
+        "clip" => Ok(TextOverwriteMode::Clip),
+        "erase" => Ok(TextOverwriteMode::Erase),
+        _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "Unknown text overwrite mode: {} (expected \"overlay\", \"clip\" or \"erase\")",
+            name
+        ))),
+    }
+}
+
+/// Upload provider that calls a Python callable with the requested format
+pub(crate) struct PyUploadCallback {
+    callback: pyo3::Py<pyo3::PyAny>,
+}
+
+impl PyUploadCallback {
+    pub(crate) fn new(callback: pyo3::Py<pyo3::PyAny>) -> Self {
+        Self { callback }
+    }
+}
+
+impl crate::terminal::file_transfer::UploadProvider for PyUploadCallback {
+    fn provide(&mut self, format: &str) -> Option<Vec<u8>> {
+        pyo3::Python::attach(|py| {
+            let result = self
+                .callback
+                .call1(py, (format,))
+                .and_then(|value| value.extract::<Option<Vec<u8>>>(py));
+            match result {
+                Ok(data) => data,
+                Err(e) => {
+                    eprintln!("Upload callback error: {e}");
+                    None
+                }
+            }
+        })
+    }
+}
+
 /// Emit a small set of simple read-only getters for `$ty`, using
 /// [`TerminalAccess::term_ref`]. Validates the shared-method macro pattern
 /// (ARC-003/QA-001); the same shape scales to the full duplicated set.
@@ -95,6 +194,114 @@ macro_rules! impl_terminal_query_getters {
                 Ok(t.title().to_string())
             }
 
+            /// Get the icon name set by OSC 0 or OSC 1
+            ///
+            /// Returns:
+            ///     Current icon name string
+            fn icon_name(&self) -> pyo3::PyResult<String> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.icon_name().to_string())
+            }
+
+            /// Get recent title and icon name changes, oldest first
+            ///
+            /// Returns:
+            ///     List of (kind, text, timestamp) tuples where kind is "title" or
+            ///     "icon_name" and timestamp is Unix epoch milliseconds
+            fn title_history(&self) -> pyo3::PyResult<Vec<(String, String, u64)>> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.title_history()
+                    .iter()
+                    .map(|entry| {
+                        (
+                            entry.kind.as_str().to_string(),
+                            entry.text.clone(),
+                            entry.timestamp,
+                        )
+                    })
+                    .collect())
+            }
+
+            /// Get how many lines the viewport is scrolled back (0 = live screen)
+            fn viewport_offset(&self) -> pyo3::PyResult<usize> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.viewport_offset())
+            }
+
+            /// Check if a viewport at the bottom follows new output
+            fn is_following_tail(&self) -> pyo3::PyResult<bool> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.is_following_tail())
+            }
+
+            /// Get the maximum number of cleared screens kept (0 = disabled)
+            fn clear_undo_limit(&self) -> pyo3::PyResult<usize> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.clear_undo_limit())
+            }
+
+            /// Get the screens captured before ED 2 / ED 3, oldest first
+            ///
+            /// Returns:
+            ///     List of (timestamp, include_scrollback, screen_text) tuples
+            ///     where timestamp is Unix epoch milliseconds
+            fn cleared_screens(&self) -> pyo3::PyResult<Vec<(u64, bool, String)>> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.cleared_screens()
+                    .iter()
+                    .map(|c| (c.timestamp, c.include_scrollback, c.screen_text()))
+                    .collect())
+            }
+
+            /// Get output volume in consecutive time buckets, oldest first
+            ///
+            /// Buckets are aligned to multiples of bucket_secs and include empty
+            /// ones, up to the current time. Only the last hour is kept.
+            ///
+            /// Args:
+            ///     bucket_secs: Bucket width in seconds (default: 1, 0 is treated as 1)
+            ///
+            /// Returns:
+            ///     List of (start, bytes, lines) tuples where start is Unix epoch
+            ///     milliseconds and lines counts line feeds
+            #[pyo3(signature = (bucket_secs=1))]
+            fn activity_histogram(&self, bucket_secs: u64) -> pyo3::PyResult<Vec<(u64, u64, u64)>> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.activity_histogram(bucket_secs)
+                    .iter()
+                    .map(|bucket| (bucket.start, bucket.bytes, bucket.lines))
+                    .collect())
+            }
+
+            /// Get when a row of the primary screen was last written
+            ///
+            /// Args:
+            ///     row: Absolute row (scrollback_len + visible row, as used by zones)
+            ///
+            /// Returns:
+            ///     Unix epoch milliseconds, or None if the row was never written or
+            ///     has been evicted
+            fn row_timestamp(&self, row: usize) -> pyo3::PyResult<Option<u64>> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.row_timestamp(row))
+            }
+
+            /// Find the first row of the primary screen written at or after a time
+            ///
+            /// Blank lines are stamped when they scroll into scrollback; rows
+            /// redrawn in place keep the time of their last write.
+            ///
+            /// Args:
+            ///     timestamp: Unix epoch milliseconds
+            ///
+            /// Returns:
+            ///     Absolute row (same numbering as zones), or None if no row was
+            ///     written that late
+            fn find_row_at_time(&self, timestamp: u64) -> pyo3::PyResult<Option<usize>> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.find_row_at_time(timestamp))
+            }
+
             /// Get the cursor position
             ///
             /// Returns:
@@ -115,6 +322,79 @@ macro_rules! impl_terminal_query_getters {
                 Ok(t.keyboard_flags())
             }
 
+            /// Check if win32-input-mode (DEC 9001) is enabled
+            ///
+            /// Returns:
+            ///     True if keys are sent as serialized Win32 key records
+            fn win32_input_mode(&self) -> pyo3::PyResult<bool> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.win32_input_mode())
+            }
+
+            /// Encode a key event for the current keyboard mode
+            ///
+            /// In win32-input-mode (DEC 9001) the event is sent as Win32 key
+            /// records. Otherwise follows the Kitty keyboard protocol flags set by
+            /// the application and falls back to the legacy xterm encoding when
+            /// none are set.
+            ///
+            /// Args:
+            ///     key: A single character (unshifted, e.g. "a" or "1") or a key name
+            ///         such as "escape", "enter", "up", "page_up", "f5", "kp_1",
+            ///         "media_play" or "left_shift"
+            ///     modifiers: Modifier names ("shift", "alt", "ctrl", "super", "hyper",
+            ///         "meta", "caps_lock", "num_lock")
+            ///     event_type: "press", "repeat" or "release" (default: "press")
+            ///     text: Text produced by the key (derived from the key when omitted)
+            ///     shifted_key: Character the key produces with Shift
+            ///     base_layout_key: Key at the same position on a US PC-101 layout
+            ///
+            /// Returns:
+            ///     Bytes to send to the application (empty if the event is not reported)
+            ///
+            /// Raises:
+            ///     ValueError: If the key, a modifier or the event type is unknown
+            #[pyo3(signature = (key, modifiers=Vec::new(), event_type="press", text=None, shifted_key=None, base_layout_key=None))]
+            fn encode_key(
+                &self,
+                key: &str,
+                modifiers: Vec<String>,
+                event_type: &str,
+                text: Option<String>,
+                shifted_key: Option<char>,
+                base_layout_key: Option<char>,
+            ) -> pyo3::PyResult<Vec<u8>> {
+                use $crate::kitty_keyboard::{Key, KeyEventType, KeyEvent, KeyModifiers};
+                let key = Key::from_name(key).ok_or_else(|| {
+                    pyo3::exceptions::PyValueError::new_err(format!("Unknown key: {}", key))
+                })?;
+                let mut mods = KeyModifiers::empty();
+                for name in &modifiers {
+                    mods |= KeyModifiers::parse_name(name).ok_or_else(|| {
+                        pyo3::exceptions::PyValueError::new_err(format!(
+                            "Unknown modifier: {}",
+                            name
+                        ))
+                    })?;
+                }
+                let event_type = KeyEventType::from_name(event_type).ok_or_else(|| {
+                    pyo3::exceptions::PyValueError::new_err(format!(
+                        "Invalid event_type '{}'. Must be 'press', 'repeat' or 'release'",
+                        event_type
+                    ))
+                })?;
+                let event = KeyEvent {
+                    key,
+                    modifiers: mods,
+                    event_type,
+                    shifted_key,
+                    base_layout_key,
+                    text,
+                };
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.encode_key(&event))
+            }
+
             /// Get insert mode (IRM - Mode 4) state
             ///
             /// Returns:
@@ -167,6 +447,15 @@ macro_rules! impl_terminal_query_getters {
                 Ok(t.faint_text_alpha())
             }
 
+            /// Get the minimum WCAG contrast ratio (1.0 = disabled)
+            ///
+            /// Returns:
+            ///     Contrast ratio between 1.0 and 21.0
+            fn minimum_contrast_ratio(&self) -> pyo3::PyResult<f32> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.minimum_contrast_ratio())
+            }
+
             /// Get scrollback content as a list of strings
             ///
             /// Returns:
@@ -219,6 +508,18 @@ macro_rules! impl_terminal_query_getters {
                 Ok(t.synchronized_updates())
             }
 
+            /// Check if double buffering is on
+            fn double_buffering(&self) -> pyo3::PyResult<bool> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.double_buffering())
+            }
+
+            /// Get the number of frames committed to the front buffer
+            fn committed_frames(&self) -> pyo3::PyResult<u64> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.committed_frames())
+            }
+
             /// Get the Unicode width configuration
             ///
             /// Returns:
@@ -230,6 +531,34 @@ macro_rules! impl_terminal_query_getters {
                 Ok((*t.width_config()).into())
             }
 
+            /// Get the treatment of East Asian Ambiguous width characters
+            ///
+            /// Returns:
+            ///     AmbiguousWidth: Narrow (1 cell) or Wide (2 cells)
+            fn ambiguous_width(
+                &self,
+            ) -> pyo3::PyResult<$crate::python_bindings::enums::PyAmbiguousWidth> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.ambiguous_width().into())
+            }
+
+            /// Get the grapheme cluster policy (see set_unicode_width_policy)
+            fn grapheme_clustering(
+                &self,
+            ) -> pyo3::PyResult<$crate::python_bindings::enums::PyGraphemeClustering> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.unicode_width_policy().grapheme_clustering.into())
+            }
+
+            /// Check if grapheme clusters are currently measured as a unit
+            ///
+            /// Reflects DEC mode 2027 under the negotiable policy, otherwise
+            /// the policy's fixed setting.
+            fn grapheme_cluster_mode(&self) -> pyo3::PyResult<bool> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.grapheme_cluster_mode())
+            }
+
             /// Get the configured answerback string (ENQ response)
             ///
             /// Returns:
@@ -404,6 +733,122 @@ macro_rules! impl_terminal_color_setters {
                 t.set_bold_brightening(enabled);
                 Ok(())
             }
+
+            /// Parse a color theme and apply it in one step
+            ///
+            /// Colors the theme does not define are left unchanged. Emits a
+            /// ``theme_changed`` event.
+            ///
+            /// Args:
+            ///     text: Theme source
+            ///     format: "itermcolors", "base16" (YAML) or "json"
+            ///
+            /// Raises:
+            ///     ValueError: If the format is unknown or the theme is invalid
+            fn apply_theme(&mut self, text: &str, format: &str) -> pyo3::PyResult<()> {
+                let format = $crate::theme::ThemeFormat::from_name(format).ok_or_else(|| {
+                    pyo3::exceptions::PyValueError::new_err(format!(
+                        "Unknown theme format: {}",
+                        format
+                    ))
+                })?;
+                let theme = $crate::theme::Theme::parse(text, format)
+                    .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+                let mut t = $crate::python_bindings::common::TerminalAccess::term_mut(self);
+                t.apply_theme(&theme);
+                Ok(())
+            }
+
+            /// Load a theme file and apply it in one step
+            ///
+            /// The format is chosen from the extension (``.itermcolors``,
+            /// ``.yaml``/``.yml`` for base16, ``.json``).
+            ///
+            /// Args:
+            ///     path: Path to the theme file
+            ///
+            /// Returns:
+            ///     The theme name (from the file, or the file stem), if any
+            ///
+            /// Raises:
+            ///     ValueError: If the file cannot be read or the theme is invalid
+            fn load_theme(&mut self, path: &str) -> pyo3::PyResult<Option<String>> {
+                let theme = $crate::theme::Theme::from_file(path)
+                    .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+                let mut t = $crate::python_bindings::common::TerminalAccess::term_mut(self);
+                t.apply_theme(&theme);
+                Ok(theme.name)
+            }
+
+            /// Get the current appearance ("dark" or "light")
+            fn appearance(&self) -> pyo3::PyResult<&'static str> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.appearance().name())
+            }
+
+            /// Switch between dark and light appearance
+            ///
+            /// Applies the theme stored with ``set_appearance_theme()`` for that
+            /// appearance and, if the application enabled mode 2031, sends it
+            /// the color scheme report ``CSI ? 997 ; Ps n``.
+            ///
+            /// Args:
+            ///     appearance: "dark" or "light"
+            ///
+            /// Raises:
+            ///     ValueError: If the appearance name is unknown
+            fn set_appearance(&mut self, appearance: &str) -> pyo3::PyResult<()> {
+                let appearance = $crate::python_bindings::common::parse_appearance(appearance)?;
+                let mut t = $crate::python_bindings::common::TerminalAccess::term_mut(self);
+                t.set_appearance(appearance);
+                Ok(())
+            }
+
+            /// Parse a theme and store it for an appearance
+            ///
+            /// The theme is applied now if ``appearance`` is the current one,
+            /// and again whenever ``set_appearance()`` switches to it.
+            ///
+            /// Args:
+            ///     appearance: "dark" or "light"
+            ///     text: Theme source
+            ///     format: "itermcolors", "base16" (YAML) or "json"
+            ///
+            /// Raises:
+            ///     ValueError: If the appearance or format is unknown or the theme is invalid
+            fn set_appearance_theme(
+                &mut self,
+                appearance: &str,
+                text: &str,
+                format: &str,
+            ) -> pyo3::PyResult<()> {
+                let appearance = $crate::python_bindings::common::parse_appearance(appearance)?;
+                let format = $crate::theme::ThemeFormat::from_name(format).ok_or_else(|| {
+                    pyo3::exceptions::PyValueError::new_err(format!(
+                        "Unknown theme format: {}",
+                        format
+                    ))
+                })?;
+                let theme = $crate::theme::Theme::parse(text, format)
+                    .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+                let mut t = $crate::python_bindings::common::TerminalAccess::term_mut(self);
+                t.set_appearance_theme(appearance, Some(theme));
+                Ok(())
+            }
+
+            /// Forget the theme stored for an appearance
+            ///
+            /// Args:
+            ///     appearance: "dark" or "light"
+            ///
+            /// Raises:
+            ///     ValueError: If the appearance name is unknown
+            fn clear_appearance_theme(&mut self, appearance: &str) -> pyo3::PyResult<()> {
+                let appearance = $crate::python_bindings::common::parse_appearance(appearance)?;
+                let mut t = $crate::python_bindings::common::TerminalAccess::term_mut(self);
+                t.set_appearance_theme(appearance, None);
+                Ok(())
+            }
         }
     };
 }
@@ -428,6 +873,97 @@ macro_rules! impl_terminal_state_setters {
                 Ok(())
             }
 
+            /// Forget all recorded title and icon name changes
+            fn clear_title_history(&mut self) -> pyo3::PyResult<()> {
+                let mut t = $crate::python_bindings::common::TerminalAccess::term_mut(self);
+                t.clear_title_history();
+                Ok(())
+            }
+
+            /// Forget all output activity samples
+            fn clear_activity(&mut self) -> pyo3::PyResult<()> {
+                let mut t = $crate::python_bindings::common::TerminalAccess::term_mut(self);
+                t.clear_activity();
+                Ok(())
+            }
+
+            /// Keep up to `limit` primary screens erased by ED 2 / ED 3 (what
+            /// `clear` sends) so they can be restored
+            ///
+            /// Args:
+            ///     limit: Maximum screens kept; 0 (default) disables capturing
+            ///         and drops the history
+            fn set_clear_undo_limit(&mut self, limit: usize) -> pyo3::PyResult<()> {
+                let mut t = $crate::python_bindings::common::TerminalAccess::term_mut(self);
+                t.set_clear_undo_limit(limit);
+                Ok(())
+            }
+
+            /// Restore the most recently cleared screen and remove it from the
+            /// history
+            ///
+            /// Anything written since the clear is overwritten. An ED 3 clear
+            /// also restores scrollback.
+            ///
+            /// Returns:
+            ///     False if there was nothing to restore
+            fn restore_last_cleared(&mut self) -> pyo3::PyResult<bool> {
+                let mut t = $crate::python_bindings::common::TerminalAccess::term_mut(self);
+                Ok(t.restore_last_cleared())
+            }
+
+            /// Forget all captured cleared screens
+            fn clear_cleared_screens(&mut self) -> pyo3::PyResult<()> {
+                let mut t = $crate::python_bindings::common::TerminalAccess::term_mut(self);
+                t.clear_cleared_screens();
+                Ok(())
+            }
+
+            /// Scroll the viewport to `offset` lines back from the live screen
+            ///
+            /// While scrolled back, the offset grows as output pushes lines into
+            /// scrollback so the same content stays in view.
+            ///
+            /// Args:
+            ///     offset: Lines back from the bottom (clamped to scrollback length)
+            fn set_viewport_offset(&mut self, offset: usize) -> pyo3::PyResult<()> {
+                let mut t = $crate::python_bindings::common::TerminalAccess::term_mut(self);
+                t.set_viewport_offset(offset);
+                Ok(())
+            }
+
+            /// Scroll the viewport `lines` further back into scrollback
+            fn scroll_viewport_up(&mut self, lines: usize) -> pyo3::PyResult<()> {
+                let mut t = $crate::python_bindings::common::TerminalAccess::term_mut(self);
+                t.scroll_viewport_up(lines);
+                Ok(())
+            }
+
+            /// Scroll the viewport `lines` toward the live screen
+            fn scroll_viewport_down(&mut self, lines: usize) -> pyo3::PyResult<()> {
+                let mut t = $crate::python_bindings::common::TerminalAccess::term_mut(self);
+                t.scroll_viewport_down(lines);
+                Ok(())
+            }
+
+            /// Scroll the viewport back to the live screen
+            fn scroll_viewport_to_bottom(&mut self) -> pyo3::PyResult<()> {
+                let mut t = $crate::python_bindings::common::TerminalAccess::term_mut(self);
+                t.scroll_viewport_to_bottom();
+                Ok(())
+            }
+
+            /// Set whether a viewport at the bottom follows new output
+            ///
+            /// Args:
+            ///     follow: True (default) to follow output, False to keep the
+            ///         view on the lines it shows as output arrives
+            fn set_follow_tail(&mut self, follow: bool) -> pyo3::PyResult<()> {
+                let mut t = $crate::python_bindings::common::TerminalAccess::term_mut(self);
+                t.set_follow_tail(follow);
+                Ok(())
+            }
+
             /// Set whether clipboard read operations are allowed
             ///
             /// When disabled (default), OSC 52 queries are silently ignored for security.
@@ -493,6 +1029,24 @@ macro_rules! impl_terminal_state_setters {
                 Ok(())
             }
 
+            /// Set the minimum WCAG contrast ratio between text and its background
+            ///
+            /// Like iTerm2's "minimum contrast": text colors returned by
+            /// get_line_cells() and create_snapshot(), and used by screenshots,
+            /// are adjusted until they reach this ratio against the cell background.
+            /// Values are clamped to the range 1.0-21.0; 1.0 disables the adjustment.
+            ///
+            /// Args:
+            ///     ratio: Contrast ratio (e.g. 4.5 for WCAG AA, 7.0 for AAA)
+            ///
+            /// Example:
+            ///     >>> term.set_minimum_contrast_ratio(4.5)
+            fn set_minimum_contrast_ratio(&mut self, ratio: f32) -> pyo3::PyResult<()> {
+                let mut t = $crate::python_bindings::common::TerminalAccess::term_mut(self);
+                t.set_minimum_contrast_ratio(ratio);
+                Ok(())
+            }
+
             /// Set cursor style (DECSCUSR)
             ///
             /// This is equivalent to sending CSI <n> SP q escape sequence.
@@ -520,16 +1074,71 @@ macro_rules! impl_terminal_state_setters {
                 Ok(())
             }
 
-            /// Manually flush the synchronized update buffer
+            /// Commit the rows changed so far in a synchronized update
             ///
-            /// This is useful for flushing buffered updates without disabling synchronized mode.
-            /// Note: The buffer is automatically flushed when synchronized mode is disabled via CSI ? 2026 l
+            /// They become visible through get_dirty_rows() without disabling synchronized mode.
+            /// Note: Rows are committed automatically when synchronized mode is disabled via CSI ? 2026 l
             fn flush_synchronized_updates(&mut self) -> pyo3::PyResult<()> {
                 let mut t = $crate::python_bindings::common::TerminalAccess::term_mut(self);
                 t.flush_synchronized_updates();
                 Ok(())
             }
 
+            /// Set how long a synchronized update may stay open (DEC 2026)
+            ///
+            /// Once the timeout has passed, the update is committed and ended as
+            /// if CSI ? 2026 l had arrived.
+            ///
+            /// Args:
+            ///     timeout_ms: Limit in milliseconds (0 = no limit, default 150)
+            fn set_synchronized_update_timeout(&mut self, timeout_ms: u64) -> pyo3::PyResult<()> {
+                $crate::python_bindings::common::TerminalAccess::term_mut(self)
+                    .set_synchronized_update_timeout(timeout_ms);
+                Ok(())
+            }
+
+            /// Get the synchronized update timeout in milliseconds (0 = no limit)
+            fn synchronized_update_timeout(&self) -> pyo3::PyResult<u64> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.synchronized_update_timeout())
+            }
+
+            /// End the open synchronized update now if it has timed out
+            ///
+            /// The timeout is otherwise noticed when the next output arrives;
+            /// call this from a render timer while the program is quiet.
+            ///
+            /// Returns:
+            ///     True if an update was ended
+            fn update_synchronized_updates(&mut self) -> pyo3::PyResult<bool> {
+                Ok(
+                    $crate::python_bindings::common::TerminalAccess::term_mut(self)
+                        .update_synchronized_updates(),
+                )
+            }
+
+            /// Turn double buffering on or off
+            ///
+            /// While on, readers of the front buffer see a frame committed at the
+            /// end of each chunk of output, or when a synchronized update (DEC 2026)
+            /// ends, never a partly drawn one.
+            ///
+            /// Args:
+            ///     enabled: True to commit frames to a front buffer
+            fn set_double_buffering(&mut self, enabled: bool) -> pyo3::PyResult<()> {
+                let mut t = $crate::python_bindings::common::TerminalAccess::term_mut(self);
+                t.set_double_buffering(enabled);
+                Ok(())
+            }
+
+            /// Commit the current screen to the front buffer now, even inside a
+            /// synchronized update
+            fn commit_frame(&mut self) -> pyo3::PyResult<()> {
+                let mut t = $crate::python_bindings::common::TerminalAccess::term_mut(self);
+                t.commit_frame();
+                Ok(())
+            }
+
             /// Set the answerback string sent in response to ENQ (0x05)
             ///
             /// The answerback payload is sent whenever the terminal receives the ENQ
@@ -564,7 +1173,9 @@ macro_rules! impl_terminal_state_setters {
             /// Set the treatment of East Asian Ambiguous width characters
             ///
             /// This is a convenience method to just change the ambiguous width setting
-            /// without modifying the Unicode version.
+            /// without modifying the Unicode version. Only text written afterwards
+            /// is affected. If the application enabled mode 7700 it is sent
+            /// `CSI 1 W` (narrow) or `CSI 2 W` (wide) when the setting changes.
             ///
             /// Args:
             ///     width: AmbiguousWidth.Narrow (1 cell) or AmbiguousWidth.Wide (2 cells)
@@ -593,6 +1204,26 @@ macro_rules! impl_terminal_state_setters {
                 Ok(())
             }
 
+            /// Set the width policy negotiated with applications
+            ///
+            /// Args:
+            ///     version: UnicodeVersion used for width tables
+            ///     grapheme_clustering: GraphemeClustering.Negotiable (apps toggle
+            ///         DEC mode 2027, default), Always, or Never
+            #[pyo3(signature = (version, grapheme_clustering=$crate::python_bindings::enums::PyGraphemeClustering::Negotiable))]
+            fn set_unicode_width_policy(
+                &mut self,
+                version: $crate::python_bindings::enums::PyUnicodeVersion,
+                grapheme_clustering: $crate::python_bindings::enums::PyGraphemeClustering,
+            ) -> pyo3::PyResult<()> {
+                let mut t = $crate::python_bindings::common::TerminalAccess::term_mut(self);
+                t.set_unicode_width_policy($crate::unicode_width_config::UnicodeWidthPolicy::new(
+                    version.into(),
+                    grapheme_clustering.into(),
+                ));
+                Ok(())
+            }
+
             /// Set whether OSC 7 directory tracking sequences are accepted
             ///
             /// When disabled, OSC 7 sequences are silently ignored.
@@ -693,6 +1324,49 @@ macro_rules! impl_terminal_sixel_graphics {
                     .collect())
             }
 
+            /// Get the graphic covering a screen cell
+            ///
+            /// Args:
+            ///     col: Column index (0-based)
+            ///     row: Row index (0-based)
+            ///
+            /// Returns:
+            ///     The topmost graphic covering the cell, or None
+            fn graphic_at_cell(
+                &self,
+                col: usize,
+                row: usize,
+            ) -> pyo3::PyResult<Option<$crate::python_bindings::types::PyGraphic>> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.graphic_at_cell(col, row)
+                    .map($crate::python_bindings::types::PyGraphic::from))
+            }
+
+            /// Get what printing text over an image does to it
+            ///
+            /// Returns:
+            ///     "overlay", "clip" or "erase"
+            fn text_overwrite_mode(&self) -> pyo3::PyResult<String> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.text_overwrite_mode().as_str().to_string())
+            }
+
+            /// Set what printing text over an image does to it
+            ///
+            /// Args:
+            ///     mode: "overlay" (default, images untouched), "clip" (text
+            ///         replaces Sixel/iTerm2 image cells) or "erase" (text
+            ///         removes Sixel/iTerm2/Kitty placements it touches)
+            ///
+            /// Raises:
+            ///     ValueError: If mode is not recognized
+            fn set_text_overwrite_mode(&mut self, mode: &str) -> pyo3::PyResult<()> {
+                let mode = $crate::python_bindings::common::parse_text_overwrite_mode(mode)?;
+                let mut t = $crate::python_bindings::common::TerminalAccess::term_mut(self);
+                t.set_text_overwrite_mode(mode);
+                Ok(())
+            }
+
             /// Get total number of graphics
             ///
             /// Returns:
@@ -732,6 +1406,16 @@ macro_rules! impl_terminal_sixel_graphics {
                 Ok((limits.max_width, limits.max_height, limits.max_repeat))
             }
 
+            /// Get the maximum Sixel image size applications negotiated with
+            /// XTSMGRAPHICS (never larger than the limits)
+            ///
+            /// Returns:
+            ///     Tuple of (width_px, height_px)
+            fn get_sixel_geometry(&self) -> pyo3::PyResult<(usize, usize)> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.sixel_geometry())
+            }
+
             /// Set Sixel resource limits (max width, height, repeat)
             ///
             /// Args:
@@ -751,6 +1435,35 @@ macro_rules! impl_terminal_sixel_graphics {
                 Ok(())
             }
 
+            /// Get ReGIS resource limits (raster width, height, bytes per sequence)
+            ///
+            /// Returns:
+            ///     Tuple of (max_width_px, max_height_px, max_bytes)
+            fn get_regis_limits(&self) -> pyo3::PyResult<(usize, usize, usize)> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                let limits = t.regis_limits();
+                Ok((limits.max_width, limits.max_height, limits.max_bytes))
+            }
+
+            /// Set ReGIS resource limits
+            ///
+            /// Args:
+            ///     max_width: ReGIS raster width in pixels
+            ///     max_height: ReGIS raster height in pixels
+            ///     max_bytes: Maximum command bytes accepted per DCS sequence
+            ///
+            /// Limits are clamped to safe hard maxima at the Rust layer.
+            fn set_regis_limits(
+                &mut self,
+                max_width: usize,
+                max_height: usize,
+                max_bytes: usize,
+            ) -> pyo3::PyResult<()> {
+                let mut t = $crate::python_bindings::common::TerminalAccess::term_mut(self);
+                t.set_regis_limits(max_width, max_height, max_bytes);
+                Ok(())
+            }
+
             /// Get maximum number of Sixel graphics retained
             ///
             /// Returns:
@@ -859,7 +1572,19 @@ macro_rules! impl_terminal_badge_session {
             ///     Evaluated badge string with variables replaced, or None
             fn evaluate_badge(&self) -> pyo3::PyResult<Option<String>> {
                 let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
-                Ok(t.evaluate_badge())
+                Ok(t.evaluate_badge())
+            }
+
+            /// Get the badge text as a frontend shows it
+            ///
+            /// Like evaluate_badge(), but returns None when the badge evaluates
+            /// to blank text.
+            ///
+            /// Returns:
+            ///     Badge text (may contain newlines), or None
+            fn badge(&self) -> pyo3::PyResult<Option<String>> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.badge())
             }
 
             /// Get a session variable value by name
@@ -969,7 +1694,8 @@ macro_rules! impl_terminal_progress_notifications {
             /// Drain and return pending device query responses
             ///
             /// Device queries like DA (Device Attributes) and DSR (Device Status Report)
-            /// generate responses that are buffered. This method retrieves and clears them.
+            /// generate responses that are buffered. This method retrieves and clears them,
+            /// query replies before bulk ones such as OSC 52 clipboard contents.
             ///
             /// Returns:
             ///     Bytes containing all pending responses
@@ -987,6 +1713,54 @@ macro_rules! impl_terminal_progress_notifications {
                 Ok(t.has_pending_responses())
             }
 
+            /// Get the maximum number of queued reply bytes (default 1 MiB)
+            fn max_reply_queue_bytes(&self) -> pyo3::PyResult<usize> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.max_reply_queue_bytes())
+            }
+
+            /// Set the maximum number of queued reply bytes
+            ///
+            /// Args:
+            ///     max_bytes: Cap on pending reply bytes; file upload data is exempt
+            fn set_max_reply_queue_bytes(&mut self, max_bytes: usize) -> pyo3::PyResult<()> {
+                $crate::python_bindings::common::TerminalAccess::term_mut(self)
+                    .set_max_reply_queue_bytes(max_bytes);
+                Ok(())
+            }
+
+            /// Get the reply overflow policy
+            ///
+            /// Returns:
+            ///     "evict_lower_priority" or "drop_newest"
+            fn reply_overflow_policy(&self) -> pyo3::PyResult<&'static str> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok($crate::python_bindings::common::reply_overflow_policy_name(
+                    t.reply_overflow_policy(),
+                ))
+            }
+
+            /// Set what happens when a reply does not fit in the reply queue
+            ///
+            /// Args:
+            ///     policy: "evict_lower_priority" (default) discards queued bulk replies
+            ///         to make room for query replies; "drop_newest" drops the new reply
+            ///
+            /// Raises:
+            ///     ValueError: If the policy name is unknown
+            fn set_reply_overflow_policy(&mut self, policy: &str) -> pyo3::PyResult<()> {
+                let policy = $crate::python_bindings::common::parse_reply_overflow_policy(policy)?;
+                $crate::python_bindings::common::TerminalAccess::term_mut(self)
+                    .set_reply_overflow_policy(policy);
+                Ok(())
+            }
+
+            /// Get the number of replies dropped because the reply queue was full
+            fn dropped_reply_count(&self) -> pyo3::PyResult<u64> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.dropped_reply_count())
+            }
+
             /// Check if there are pending notifications
             ///
             /// Returns:
@@ -1196,6 +1970,76 @@ macro_rules! impl_terminal_recording {
                 let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
                 Ok(t.is_recording())
             }
+
+            /// Export a recording as a standalone animated document
+            ///
+            /// The session is replayed with this terminal's color theme and
+            /// rendered as an animated SVG or an HTML page with an embedded
+            /// player (play/pause, seek, speed).
+            ///
+            /// Args:
+            ///     format: "html" or "svg" (default: "html")
+            ///     session: RecordingSession from stop_recording() (default: active recording)
+            ///     font_size: Font size in pixels (default: 14.0)
+            ///     font_family: CSS font-family list (default: JetBrains Mono with monospace fallbacks)
+            ///     padding: Padding around the screen in pixels (default: 10)
+            ///     frame_interval_ms: Output closer together than this shares a frame (default: 33)
+            ///     idle_time_limit_ms: Cap idle gaps at this many ms, None keeps recorded timing (default: 2000)
+            ///     loop_playback: Restart after the last frame (default: True)
+            ///
+            /// Returns:
+            ///     SVG or HTML document as a string
+            ///
+            /// Raises:
+            ///     ValueError: If the format is unknown or there is no session to export
+            #[pyo3(signature = (format = "html", session = None, font_size = 14.0, font_family = None, padding = 10, frame_interval_ms = 33, idle_time_limit_ms = Some(2000), loop_playback = true))]
+            #[allow(clippy::too_many_arguments)]
+            fn export_recording(
+                &self,
+                format: &str,
+                session: Option<&$crate::python_bindings::types::PyRecordingSession>,
+                font_size: f32,
+                font_family: Option<String>,
+                padding: u32,
+                frame_interval_ms: u64,
+                idle_time_limit_ms: Option<u64>,
+                loop_playback: bool,
+            ) -> pyo3::PyResult<String> {
+                let format = match format.to_lowercase().as_str() {
+                    "html" => $crate::terminal::RecordingExportFormat::Html,
+                    "svg" => $crate::terminal::RecordingExportFormat::Svg,
+                    other => {
+                        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                            "Unknown recording export format '{}' (expected 'html' or 'svg')",
+                            other
+                        )))
+                    }
+                };
+                let mut style = $crate::svg_export::SvgExportConfig::new()
+                    .with_font_size(font_size)
+                    .with_padding(padding);
+                if let Some(family) = font_family {
+                    style = style.with_font_family(family);
+                }
+                let config = $crate::terminal::RecordingPlayerConfig {
+                    style,
+                    frame_interval_ms,
+                    idle_time_limit_ms,
+                    loop_playback,
+                    ..Default::default()
+                };
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                let session = match session {
+                    Some(session) => &session.inner,
+                    None => t.get_recording_session().ok_or_else(|| {
+                        pyo3::exceptions::PyValueError::new_err(
+                            "No active recording session (pass session=stop_recording())",
+                        )
+                    })?,
+                };
+                t.export_recording(session, format, &config)
+                    .map_err(pyo3::exceptions::PyValueError::new_err)
+            }
         }
     };
 }
@@ -1342,10 +2186,11 @@ macro_rules! impl_terminal_cell_line_queries {
                 let result = (0..cols)
                     .filter_map(|col| {
                         grid.get(col, row).map(|cell| {
+                            let (fg, bg) = t.resolve_cell_colors(cell);
                             (
                                 cell.get_grapheme(),
-                                cell.fg.to_rgb(),
-                                cell.bg.to_rgb(),
+                                fg,
+                                bg,
                                 $crate::python_bindings::types::PyAttributes::from(cell),
                             )
                         })
@@ -1400,6 +2245,39 @@ macro_rules! impl_terminal_cell_line_queries {
                 let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
                 Ok(t.get_line_unwrapped(row))
             }
+
+            /// Convert a column to an index into the row's text
+            ///
+            /// The row text (as get_line() returns it) skips the spacer after
+            /// each wide character and keeps combining marks with their base,
+            /// so string indices and columns differ after such characters.
+            ///
+            /// Args:
+            ///     row: Row position (0-indexed)
+            ///     col: Column position; both columns of a wide character give
+            ///         its index, and the row width gives the text length
+            ///
+            /// Returns:
+            ///     String index, or None if row or col is out of range
+            fn column_to_index(&self, row: usize, col: usize) -> pyo3::PyResult<Option<usize>> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.row_text_map(row).and_then(|text| text.col_to_char(col)))
+            }
+
+            /// Convert an index into the row's text to a column
+            ///
+            /// Args:
+            ///     row: Row position (0-indexed)
+            ///     index: String index into the row text; an index inside a
+            ///         character's combining marks gives that character's column,
+            ///         and the text length gives the row width
+            ///
+            /// Returns:
+            ///     Column, or None if row or index is out of range
+            fn index_to_column(&self, row: usize, index: usize) -> pyo3::PyResult<Option<usize>> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.row_text_map(row).and_then(|text| text.char_to_col(index)))
+            }
         }
     };
 }
@@ -1544,17 +2422,24 @@ macro_rules! impl_terminal_search_select {
             /// Args:
             ///     pattern: Text to search for
             ///     case_sensitive: Whether search is case-sensitive (default: True)
+            ///     normalization: NormalizationForm applied to the pattern and
+            ///         each cell before comparing (default: NFC)
             ///
             /// Returns:
             ///     List of (col, row) positions where pattern was found
-            #[pyo3(signature = (pattern, case_sensitive = true))]
+            #[pyo3(signature = (pattern, case_sensitive = true, normalization = None))]
             fn find_text(
                 &self,
                 pattern: &str,
                 case_sensitive: bool,
+                normalization: Option<$crate::python_bindings::enums::PyNormalizationForm>,
             ) -> pyo3::PyResult<Vec<(usize, usize)>> {
                 let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
-                Ok(t.find_text(pattern, case_sensitive)
+                let options = $crate::terminal::TextSearchOptions {
+                    case_sensitive,
+                    normalization: normalization.map(Into::into).unwrap_or_default(),
+                };
+                Ok(t.search_text_with_options(pattern, &options)
                     .into_iter()
                     .map(|m| (m.col, m.row as usize))
                     .collect())
@@ -1685,6 +2570,54 @@ macro_rules! impl_terminal_search_select {
                     Ok(None)
                 }
             }
+
+            /// Enable the expect buffer, or disable and discard it with None
+            ///
+            /// Printed text (without escape sequences or carriage returns) is
+            /// kept for expect_match, keeping the newest `max_bytes` bytes.
+            ///
+            /// Args:
+            ///     max_bytes: Buffer size limit (default: 65536), or None to disable
+            #[pyo3(signature = (max_bytes = Some($crate::terminal::DEFAULT_EXPECT_BUFFER_BYTES)))]
+            fn set_expect_buffer(&mut self, max_bytes: Option<usize>) {
+                $crate::python_bindings::common::TerminalAccess::term_mut(self)
+                    .set_expect_buffer(max_bytes);
+            }
+
+            /// Unconsumed text in the expect buffer, or None if disabled
+            fn expect_buffer(&self) -> Option<String> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                t.expect_buffer().map(str::to_string)
+            }
+
+            /// Discard unconsumed text in the expect buffer
+            fn clear_expect_buffer(&mut self) {
+                $crate::python_bindings::common::TerminalAccess::term_mut(self)
+                    .clear_expect_buffer();
+            }
+
+            /// Match patterns against the expect buffer without waiting
+            ///
+            /// The earliest match wins, ties going to the first pattern. On a
+            /// match the buffer is consumed through the end of the match.
+            ///
+            /// Args:
+            ///     patterns: Regex string or list of regex strings
+            ///
+            /// Returns:
+            ///     ExpectMatch, or None if nothing matches yet or the buffer is disabled
+            ///
+            /// Raises:
+            ///     ValueError: If a pattern is invalid
+            fn expect_match(
+                &mut self,
+                patterns: &pyo3::Bound<'_, pyo3::PyAny>,
+            ) -> pyo3::PyResult<Option<$crate::python_bindings::types::PyExpectMatch>> {
+                let patterns =
+                    $crate::python_bindings::conversions::parse_expect_patterns(patterns)?;
+                let mut t = $crate::python_bindings::common::TerminalAccess::term_mut(self);
+                Ok(t.expect_match(&patterns).map(Into::into))
+            }
         }
     };
 }
@@ -1744,6 +2677,229 @@ macro_rules! impl_terminal_debug_snapshots {
                 debug::log_buffer_snapshot(label, grid.rows(), grid.cols(), &snapshot);
                 Ok(())
             }
+
+            /// Get the escape sequences the terminal ignored as unsupported
+            ///
+            /// Occurrences are grouped by name (e.g. ``"CSI >~"``,
+            /// ``"DECSET 9999"``, ``"OSC 4242"``, ``"DCS $t"``) in first-seen
+            /// order, with the latest occurrence's parameters and bytes.
+            ///
+            /// Returns:
+            ///     List of UnsupportedSequence objects
+            fn unsupported_sequences(
+                &self,
+            ) -> pyo3::PyResult<Vec<$crate::python_bindings::types::PyUnsupportedSequence>> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.unsupported_sequences()
+                    .iter()
+                    .map($crate::python_bindings::types::PyUnsupportedSequence::from)
+                    .collect())
+            }
+
+            /// Forget all captured unsupported sequences
+            fn clear_unsupported_sequences(&mut self) -> pyo3::PyResult<()> {
+                $crate::python_bindings::common::TerminalAccess::term_mut(self)
+                    .clear_unsupported_sequences();
+                Ok(())
+            }
+
+            /// Enable or disable UTF-8 resilience mode (off by default)
+            ///
+            /// While enabled, output is validated as UTF-8 before parsing:
+            /// each invalid sequence is shown as U+FFFD, counted, kept for
+            /// ``utf8_decode_errors()`` and reported as a ``utf8_decode_error``
+            /// event with its byte offset.
+            ///
+            /// Args:
+            ///     enabled: True to enable resilience mode
+            fn set_utf8_resilience(&mut self, enabled: bool) -> pyo3::PyResult<()> {
+                $crate::python_bindings::common::TerminalAccess::term_mut(self)
+                    .set_utf8_resilience(enabled);
+                Ok(())
+            }
+
+            /// Check if UTF-8 resilience mode is enabled
+            fn utf8_resilience(&self) -> pyo3::PyResult<bool> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.utf8_resilience())
+            }
+
+            /// Get UTF-8 decode error counters
+            ///
+            /// Returns:
+            ///     Dictionary with ``errors`` (invalid sequences),
+            ///     ``invalid_bytes`` and ``runs`` (runs of consecutive errors)
+            fn utf8_decode_stats(&self) -> pyo3::PyResult<std::collections::HashMap<String, u64>> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                let stats = t.utf8_decode_stats();
+                Ok(std::collections::HashMap::from([
+                    ("errors".to_string(), stats.errors),
+                    ("invalid_bytes".to_string(), stats.invalid_bytes),
+                    ("runs".to_string(), stats.runs),
+                ]))
+            }
+
+            /// Get the most recent undecodable byte runs, oldest first
+            ///
+            /// Returns:
+            ///     List of ``(offset, length, bytes)`` tuples; ``bytes`` holds
+            ///     at most 64 raw bytes of the run
+            fn utf8_decode_errors(
+                &self,
+                py: pyo3::Python<'_>,
+            ) -> pyo3::PyResult<Vec<(u64, usize, pyo3::Py<pyo3::types::PyBytes>)>> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.utf8_decode_errors()
+                    .into_iter()
+                    .map(|run| {
+                        (
+                            run.offset,
+                            run.length,
+                            pyo3::types::PyBytes::new(py, &run.bytes).unbind(),
+                        )
+                    })
+                    .collect())
+            }
+
+            /// Reset the UTF-8 decode error counters and recorded runs
+            fn clear_utf8_decode_errors(&mut self) -> pyo3::PyResult<()> {
+                $crate::python_bindings::common::TerminalAccess::term_mut(self)
+                    .clear_utf8_decode_errors();
+                Ok(())
+            }
+
+            /// Configure the watchdog for unterminated OSC/DCS strings (off by default)
+            ///
+            /// While enabled, an OSC or DCS string whose body grows past
+            /// ``max_bytes`` or stays open longer than ``timeout_ms`` is
+            /// cancelled, reported as an ``escape_string_aborted`` event, and
+            /// the bytes after it are parsed normally.
+            ///
+            /// Args:
+            ///     enabled: True to enable the watchdog
+            ///     max_bytes: Longest string body allowed (default 16 MiB)
+            ///     timeout_ms: How long a string may stay open (default 10000)
+            #[pyo3(signature = (enabled, max_bytes=None, timeout_ms=None))]
+            fn set_escape_watchdog(
+                &mut self,
+                enabled: bool,
+                max_bytes: Option<usize>,
+                timeout_ms: Option<u64>,
+            ) -> pyo3::PyResult<()> {
+                let mut t = $crate::python_bindings::common::TerminalAccess::term_mut(self);
+                let mut config = t.escape_watchdog_config();
+                config.enabled = enabled;
+                if let Some(max_bytes) = max_bytes {
+                    config.max_bytes = max_bytes;
+                }
+                if let Some(timeout_ms) = timeout_ms {
+                    config.timeout_ms = timeout_ms;
+                }
+                t.set_escape_watchdog_config(config);
+                Ok(())
+            }
+
+            /// Get the unterminated string watchdog settings
+            ///
+            /// Returns:
+            ///     Dictionary with ``enabled``, ``max_bytes`` and ``timeout_ms``
+            fn get_escape_watchdog(
+                &self,
+                py: pyo3::Python<'_>,
+            ) -> pyo3::PyResult<pyo3::Py<pyo3::types::PyDict>> {
+                use pyo3::types::PyDictMethods;
+                let config = $crate::python_bindings::common::TerminalAccess::term_ref(self)
+                    .escape_watchdog_config();
+                let dict = pyo3::types::PyDict::new(py);
+                dict.set_item("enabled", config.enabled)?;
+                dict.set_item("max_bytes", config.max_bytes)?;
+                dict.set_item("timeout_ms", config.timeout_ms)?;
+                Ok(dict.unbind())
+            }
+
+            /// Cancel the open OSC/DCS string now if it has timed out
+            ///
+            /// Timeouts are otherwise noticed when the next output arrives;
+            /// call this from a timer to cancel a stalled string while the
+            /// program is quiet.
+            ///
+            /// Returns:
+            ///     True if a string was cancelled
+            fn update_escape_watchdog(&mut self) -> pyo3::PyResult<bool> {
+                Ok(
+                    $crate::python_bindings::common::TerminalAccess::term_mut(self)
+                        .update_escape_watchdog(),
+                )
+            }
+
+            /// Set whether applications may change the background image and opacity
+            ///
+            /// When disabled (default), iTerm2 ``SetBackgroundImageFile`` and
+            /// kitty ``set-background-image``/``set-background-opacity``
+            /// requests are ignored. When enabled, they are recorded and
+            /// reported as ``background_changed`` events; the frontend
+            /// decides how to draw them.
+            ///
+            /// Args:
+            ///     allow: True to accept background requests, False to block (default)
+            fn set_allow_remote_background(&mut self, allow: bool) -> pyo3::PyResult<()> {
+                $crate::python_bindings::common::TerminalAccess::term_mut(self)
+                    .set_allow_remote_background(allow);
+                Ok(())
+            }
+
+            /// Whether applications may change the background image and opacity
+            fn allow_remote_background(&self) -> pyo3::PyResult<bool> {
+                Ok(
+                    $crate::python_bindings::common::TerminalAccess::term_ref(self)
+                        .allow_remote_background(),
+                )
+            }
+
+            /// Get the background image requested by the application
+            ///
+            /// Returns:
+            ///     None, or a dictionary with ``type`` (``"file"`` or ``"data"``)
+            ///     and ``path`` for files, or ``data`` (bytes) and ``layout``
+            ///     (kitty layout name or None) for image data
+            fn get_background_image(
+                &self,
+                py: pyo3::Python<'_>,
+            ) -> pyo3::PyResult<Option<pyo3::Py<pyo3::types::PyDict>>> {
+                use pyo3::types::PyDictMethods;
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                let Some(image) = t.background_image() else {
+                    return Ok(None);
+                };
+                let dict = pyo3::types::PyDict::new(py);
+                match image {
+                    $crate::terminal::BackgroundImage::File(path) => {
+                        dict.set_item("type", "file")?;
+                        dict.set_item("path", path)?;
+                    }
+                    $crate::terminal::BackgroundImage::Data { data, layout } => {
+                        dict.set_item("type", "data")?;
+                        dict.set_item("data", pyo3::types::PyBytes::new(py, data))?;
+                        dict.set_item("layout", layout.map(|layout| layout.as_str()))?;
+                    }
+                }
+                Ok(Some(dict.unbind()))
+            }
+
+            /// Get the window opacity (0.0-1.0) requested by the application, or None
+            fn get_background_opacity(&self) -> pyo3::PyResult<Option<f32>> {
+                Ok(
+                    $crate::python_bindings::common::TerminalAccess::term_ref(self)
+                        .background_opacity(),
+                )
+            }
+
+            /// Forget the requested background image and opacity
+            fn clear_background_requests(&mut self) -> pyo3::PyResult<()> {
+                $crate::python_bindings::common::TerminalAccess::term_mut(self)
+                    .clear_background_requests();
+                Ok(())
+            }
         }
     };
 }
@@ -1880,6 +3036,81 @@ macro_rules! impl_terminal_file_transfer {
                 Ok(())
             }
 
+            /// Answer upload requests (OSC 1337 RequestUpload) with a callback
+            ///
+            /// The callback receives the requested format (e.g. "tgz") and returns the
+            /// file data as bytes, or None to cancel the upload. It runs while output is
+            /// being processed and must not call back into this terminal. The
+            /// "upload_requested" event is still emitted.
+            ///
+            /// Args:
+            ///     callback: Callable taking the format string, or None to remove it
+            #[pyo3(signature = (callback))]
+            fn set_upload_callback(
+                &mut self,
+                callback: Option<pyo3::Py<pyo3::PyAny>>,
+            ) -> pyo3::PyResult<()> {
+                let mut t = $crate::python_bindings::common::TerminalAccess::term_mut(self);
+                match callback {
+                    Some(callback) => t.set_upload_provider(
+                        $crate::python_bindings::common::PyUploadCallback::new(callback),
+                    ),
+                    None => t.clear_upload_provider(),
+                }
+                Ok(())
+            }
+
+            /// Check if upload requests are answered by a callback
+            ///
+            /// Returns:
+            ///     True if an upload callback is set
+            fn has_upload_callback(&self) -> pyo3::PyResult<bool> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.has_upload_provider())
+            }
+
+            /// Save downloaded files (OSC 1337 File= with inline=0) into a directory
+            /// instead of buffering them in memory
+            ///
+            /// Files keep the sender's name without any directory part and never
+            /// overwrite an existing file (a " (n)" suffix is added). Completion is
+            /// announced with a "file_received" event.
+            ///
+            /// Args:
+            ///     path: Existing directory, or None to buffer downloads again
+            ///
+            /// Raises:
+            ///     ValueError: If path is not a directory
+            #[pyo3(signature = (path))]
+            fn set_download_directory(
+                &mut self,
+                path: Option<std::path::PathBuf>,
+            ) -> pyo3::PyResult<()> {
+                let mut t = $crate::python_bindings::common::TerminalAccess::term_mut(self);
+                match path {
+                    Some(path) if !path.is_dir() => {
+                        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                            "Not a directory: {}",
+                            path.display()
+                        )));
+                    }
+                    Some(path) => t.set_download_sink(
+                        $crate::terminal::file_transfer::DirectoryDownloadSink::new(path),
+                    ),
+                    None => t.clear_download_sink(),
+                }
+                Ok(())
+            }
+
+            /// Check if downloads are streamed to a sink rather than buffered
+            ///
+            /// Returns:
+            ///     True if a download directory (or other sink) is set
+            fn has_download_sink(&self) -> pyo3::PyResult<bool> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.has_download_sink())
+            }
+
             /// Set the maximum allowed file transfer size in bytes
             ///
             /// Args:
@@ -1904,7 +3135,7 @@ macro_rules! impl_terminal_file_transfer {
     };
 }
 
-/// Emit text/html export + animation-tick methods for `$ty`.
+/// Emit text/html/svg export + animation-tick methods for `$ty`.
 /// (ARC-003/QA-001 batch: exports + animations.)
 #[macro_export]
 macro_rules! impl_terminal_exports {
@@ -1920,6 +3151,72 @@ macro_rules! impl_terminal_exports {
                 Ok(t.export_text())
             }
 
+            /// Export the primary screen buffer as plain text with options
+            ///
+            /// Args:
+            ///     include_scrollback: Include scrollback before the screen (default: True)
+            ///     join_wrapped: Join soft-wrapped rows into one line (default: True)
+            ///     trim_trailing_whitespace: Trim trailing whitespace (default: True)
+            ///     line_ending: "lf" or "crlf" (default: "lf")
+            ///     reconstruct_tabs: Turn space runs ending at tab stops into tabs
+            ///         (default: False)
+            ///     zone_markers: Write a comment line where each shell integration
+            ///         zone starts (default: False)
+            ///     comment_prefix: Prefix of zone marker lines (default: "# ")
+            ///     timestamps: Prefix each line with the UTC time it was written,
+            ///         e.g. "[2026-10-17T09:30:00.000Z] " (default: False)
+            ///
+            /// Returns:
+            ///     String with every line terminated by the chosen line ending
+            ///
+            /// Raises:
+            ///     ValueError: If line_ending is not "lf" or "crlf"
+            #[pyo3(signature = (
+                include_scrollback=true,
+                join_wrapped=true,
+                trim_trailing_whitespace=true,
+                line_ending="lf",
+                reconstruct_tabs=false,
+                zone_markers=false,
+                comment_prefix="# ",
+                timestamps=false
+            ))]
+            #[allow(clippy::too_many_arguments)]
+            fn export_text_opts(
+                &self,
+                include_scrollback: bool,
+                join_wrapped: bool,
+                trim_trailing_whitespace: bool,
+                line_ending: &str,
+                reconstruct_tabs: bool,
+                zone_markers: bool,
+                comment_prefix: &str,
+                timestamps: bool,
+            ) -> pyo3::PyResult<String> {
+                use $crate::terminal::{ExportTextOptions, LineEnding};
+                let line_ending = match line_ending.to_ascii_lowercase().as_str() {
+                    "lf" => LineEnding::Lf,
+                    "crlf" => LineEnding::CrLf,
+                    _ => {
+                        return Err(pyo3::exceptions::PyValueError::new_err(
+                            "Invalid line_ending (use 'lf' or 'crlf')",
+                        ))
+                    }
+                };
+                let options = ExportTextOptions {
+                    include_scrollback,
+                    join_wrapped,
+                    trim_trailing_whitespace,
+                    line_ending,
+                    reconstruct_tabs,
+                    zone_markers,
+                    comment_prefix: comment_prefix.to_string(),
+                    timestamps,
+                };
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.export_text_opts(&options))
+            }
+
             /// Export entire buffer (scrollback + current screen) with ANSI styling
             ///
             /// Returns:
@@ -1945,6 +3242,142 @@ macro_rules! impl_terminal_exports {
                 Ok(t.export_html(include_styles))
             }
 
+            /// Export the visible screen as styled HTML with frontend overlays
+            ///
+            /// Selected cells use the theme's selection colors and matches of
+            /// the last search use the theme's match color, so the page shows
+            /// what the frontend shows.
+            ///
+            /// Args:
+            ///     include_styles: Whether to include full HTML document with CSS (default: True)
+            ///     selection: Paint the current selection (default: True)
+            ///     search_matches: Paint the last search's matches (default: True)
+            ///
+            /// Returns:
+            ///     HTML string with terminal content, styling and overlays
+            #[pyo3(signature = (include_styles = true, selection = true, search_matches = true))]
+            fn export_html_with_overlays(
+                &self,
+                include_styles: bool,
+                selection: bool,
+                search_matches: bool,
+            ) -> pyo3::PyResult<String> {
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.export_html_with_overlays(
+                    include_styles,
+                    $crate::terminal::ExportOverlays {
+                        selection,
+                        search_matches,
+                    },
+                ))
+            }
+
+            /// Export the visible screen as HTML themed with the terminal's colors
+            ///
+            /// The 16 ANSI colors and the default foreground/background come
+            /// from the terminal's runtime palette (OSC 4/10/11 changes
+            /// included) unless overridden.
+            ///
+            /// Args:
+            ///     include_styles: Whether to include full HTML document with CSS (default: True)
+            ///     palette: 16 (r, g, b) tuples replacing the ANSI colors (default: terminal palette)
+            ///     default_fg: (r, g, b) default foreground (default: terminal's)
+            ///     default_bg: (r, g, b) default background (default: terminal's)
+            ///     css_variables: Declare colors as --term-* CSS variables (default: True)
+            ///     hyperlinks: Wrap OSC 8 hyperlinks in <a> tags (default: False)
+            ///
+            /// Returns:
+            ///     HTML string with terminal content and styling
+            ///
+            /// Raises:
+            ///     ValueError: If palette does not have 16 entries
+            #[pyo3(signature = (
+                include_styles = true,
+                palette = None,
+                default_fg = None,
+                default_bg = None,
+                css_variables = true,
+                hyperlinks = false
+            ))]
+            fn export_html_themed(
+                &self,
+                include_styles: bool,
+                palette: Option<Vec<(u8, u8, u8)>>,
+                default_fg: Option<(u8, u8, u8)>,
+                default_bg: Option<(u8, u8, u8)>,
+                css_variables: bool,
+                hyperlinks: bool,
+            ) -> pyo3::PyResult<String> {
+                use $crate::color::Color;
+                let mut config = $crate::html_export::HtmlExportConfig::new()
+                    .with_styles(include_styles)
+                    .with_css_variables(css_variables)
+                    .with_hyperlinks(hyperlinks);
+                if let Some(palette) = palette {
+                    let colors: [Color; 16] = palette
+                        .into_iter()
+                        .map(|(r, g, b)| Color::Rgb(r, g, b))
+                        .collect::<Vec<_>>()
+                        .try_into()
+                        .map_err(|colors: Vec<Color>| {
+                            pyo3::exceptions::PyValueError::new_err(format!(
+                                "palette must have 16 colors, got {}",
+                                colors.len()
+                            ))
+                        })?;
+                    config = config.with_palette(colors);
+                }
+                if let Some((r, g, b)) = default_fg {
+                    config = config.with_default_fg(Color::Rgb(r, g, b));
+                }
+                if let Some((r, g, b)) = default_bg {
+                    config = config.with_default_bg(Color::Rgb(r, g, b));
+                }
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.export_html_themed(&config))
+            }
+
+            /// Export terminal content as a self-contained SVG document
+            ///
+            /// Text stays selectable, colors and attributes are preserved, and
+            /// inline graphics on the active screen are embedded as PNG.
+            ///
+            /// Args:
+            ///     font_size: Font size in pixels (default: 14.0)
+            ///     font_family: CSS font-family list (default: JetBrains Mono with monospace fallbacks)
+            ///     padding: Padding around the content in pixels (default: 10)
+            ///     start_line: First absolute line to export (0 = oldest scrollback line)
+            ///     end_line: Line after the last one to export
+            ///     include_graphics: Embed inline graphics (default: True)
+            ///
+            /// Returns:
+            ///     SVG document as a string. Without start_line/end_line the
+            ///     visible screen is exported.
+            #[pyo3(signature = (font_size = 14.0, font_family = None, padding = 10, start_line = None, end_line = None, include_graphics = true))]
+            fn export_svg(
+                &self,
+                font_size: f32,
+                font_family: Option<String>,
+                padding: u32,
+                start_line: Option<usize>,
+                end_line: Option<usize>,
+                include_graphics: bool,
+            ) -> pyo3::PyResult<String> {
+                let mut config = $crate::svg_export::SvgExportConfig::new()
+                    .with_font_size(font_size)
+                    .with_padding(padding)
+                    .with_graphics(include_graphics);
+                if let Some(family) = font_family {
+                    config = config.with_font_family(family);
+                }
+                if start_line.is_some() || end_line.is_some() {
+                    config = config
+                        .with_line_range(start_line.unwrap_or(0)..end_line.unwrap_or(usize::MAX));
+                }
+                let t = $crate::python_bindings::common::TerminalAccess::term_ref(self);
+                Ok(t.export_svg(&config))
+            }
+
             /// Update all Kitty graphics animations and trigger refresh if frames changed
             ///
             /// This method should be called regularly (e.g., 60Hz) to advance animation frames.
@@ -1960,3 +3393,56 @@ macro_rules! impl_terminal_exports {
         }
     };
 }
+
+/// Emit snapshot delta (remote mirroring) methods for `$ty`.
+#[macro_export]
+macro_rules! impl_terminal_snapshot_deltas {
+    ($ty:ty) => {
+        #[pymethods]
+        impl $ty {
+            /// Encode the changes since the previous call as a binary delta
+            ///
+            /// The first call, and the first after reset_snapshot_delta(),
+            /// returns a keyframe describing the whole terminal. Send the
+            /// deltas in order to a mirror that passes them to
+            /// apply_snapshot_delta().
+            ///
+            /// Returns:
+            ///     Encoded delta
+            fn snapshot_delta<'py>(
+                &mut self,
+                py: pyo3::Python<'py>,
+            ) -> pyo3::PyResult<pyo3::Bound<'py, pyo3::types::PyBytes>> {
+                let data = $crate::python_bindings::common::TerminalAccess::term_mut(self)
+                    .snapshot_delta();
+                Ok(pyo3::types::PyBytes::new(py, &data))
+            }
+
+            /// Make the next snapshot_delta() a keyframe
+            ///
+            /// Call this when a mirror connects or fails to apply a delta.
+            fn reset_snapshot_delta(&mut self) -> pyo3::PyResult<()> {
+                $crate::python_bindings::common::TerminalAccess::term_mut(self)
+                    .reset_snapshot_delta();
+                Ok(())
+            }
+
+            /// Apply a delta produced by snapshot_delta() on another terminal
+            ///
+            /// The terminal is unchanged if the delta cannot be applied.
+            ///
+            /// Args:
+            ///     data: Encoded delta
+            ///
+            /// Raises:
+            ///     ValueError: If the data is not a valid delta, or the delta was
+            ///         computed against a different state (ask the sender for a
+            ///         keyframe)
+            fn apply_snapshot_delta(&mut self, data: &[u8]) -> pyo3::PyResult<()> {
+                $crate::python_bindings::common::TerminalAccess::term_mut(self)
+                    .apply_snapshot_delta(data)?;
+                Ok(())
+            }
+        }
+    };
+}
@@ -21,6 +21,17 @@ pub fn parse_sixel_mode(mode: &str) -> PyResult<crate::screenshot::SixelRenderMo
     }
 }
 
+/// Parse expect patterns given as one regex string or a list of them
+pub fn parse_expect_patterns(
+    patterns: &Bound<'_, PyAny>,
+) -> PyResult<crate::terminal::ExpectPatterns> {
+    let patterns: Vec<String> = match patterns.extract::<String>() {
+        Ok(pattern) => vec![pattern],
+        Err(_) => patterns.extract()?,
+    };
+    Ok(crate::terminal::ExpectPatterns::new(patterns)?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
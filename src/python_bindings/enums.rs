@@ -36,6 +36,13 @@ impl From<crate::cursor::CursorStyle> for PyCursorStyle {
     }
 }
 
+impl From<PyCursorStyle> for crate::cursor::CursorStyle {
+    fn from(style: PyCursorStyle) -> Self {
+        // Discriminants are the DECSCUSR parameters
+        crate::cursor::CursorStyle::from_decscusr(style as u16)
+    }
+}
+
 /// Underline style for text decoration (SGR 4:x)
 #[pyclass(name = "UnderlineStyle", from_py_object)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -67,6 +74,19 @@ impl From<crate::cell::UnderlineStyle> for PyUnderlineStyle {
     }
 }
 
+impl From<PyUnderlineStyle> for crate::cell::UnderlineStyle {
+    fn from(style: PyUnderlineStyle) -> Self {
+        match style {
+            PyUnderlineStyle::None => crate::cell::UnderlineStyle::None,
+            PyUnderlineStyle::Straight => crate::cell::UnderlineStyle::Straight,
+            PyUnderlineStyle::Double => crate::cell::UnderlineStyle::Double,
+            PyUnderlineStyle::Curly => crate::cell::UnderlineStyle::Curly,
+            PyUnderlineStyle::Dotted => crate::cell::UnderlineStyle::Dotted,
+            PyUnderlineStyle::Dashed => crate::cell::UnderlineStyle::Dashed,
+        }
+    }
+}
+
 /// Mouse encoding format for mouse event reporting
 #[pyclass(name = "MouseEncoding", from_py_object)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -349,6 +369,50 @@ impl From<PyAmbiguousWidth> for crate::unicode_width_config::AmbiguousWidth {
     }
 }
 
+/// Grapheme cluster handling and how DEC private mode 2027 is reported.
+#[pyclass(name = "GraphemeClustering", eq, from_py_object)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PyGraphemeClustering {
+    /// Applications toggle cluster processing with DECSET/DECRST 2027 (default)
+    Negotiable = 0,
+    /// Clusters always measured as a unit (mode 2027 permanently set)
+    Always = 1,
+    /// Legacy per-code-point widths (mode 2027 permanently reset)
+    Never = 2,
+}
+
+#[pymethods]
+impl PyGraphemeClustering {
+    fn __repr__(&self) -> String {
+        match self {
+            PyGraphemeClustering::Negotiable => "GraphemeClustering.NEGOTIABLE".to_string(),
+            PyGraphemeClustering::Always => "GraphemeClustering.ALWAYS".to_string(),
+            PyGraphemeClustering::Never => "GraphemeClustering.NEVER".to_string(),
+        }
+    }
+}
+
+impl From<crate::unicode_width_config::GraphemeClustering> for PyGraphemeClustering {
+    fn from(mode: crate::unicode_width_config::GraphemeClustering) -> Self {
+        use crate::unicode_width_config::GraphemeClustering;
+        match mode {
+            GraphemeClustering::Negotiable => PyGraphemeClustering::Negotiable,
+            GraphemeClustering::Always => PyGraphemeClustering::Always,
+            GraphemeClustering::Never => PyGraphemeClustering::Never,
+        }
+    }
+}
+
+impl From<PyGraphemeClustering> for crate::unicode_width_config::GraphemeClustering {
+    fn from(mode: PyGraphemeClustering) -> Self {
+        match mode {
+            PyGraphemeClustering::Negotiable => Self::Negotiable,
+            PyGraphemeClustering::Always => Self::Always,
+            PyGraphemeClustering::Never => Self::Never,
+        }
+    }
+}
+
 /// Configuration for Unicode width calculations.
 ///
 /// This class combines Unicode version and ambiguous width settings
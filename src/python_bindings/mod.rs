@@ -3,23 +3,28 @@
 //! This module contains all Python-facing bindings organized into submodules:
 //! - `terminal`: PyTerminal struct and its implementation
 //! - `pty`: PyPtyTerminal struct and its implementation (PTY support)
+//! - `pty_async`: asyncio awaitables and event iterator for PyPtyTerminal
 //! - `types`: Data types (PyAttributes, PyScreenSnapshot, PyShellIntegration, PyGraphic)
 //! - `enums`: Enum types (PyCursorStyle, PyUnderlineStyle)
 //! - `conversions`: Type conversions and parsing utilities
+//! - `ansi_utils`: ANSI span parsing and rendering functions
 //! - `color_utils`: Color utility functions for contrast adjustment
 
+pub mod ansi_utils;
 pub mod color_utils;
 pub mod common;
 pub mod conversions;
 pub mod enums;
 pub mod observer;
 pub mod pty;
+pub mod pty_async;
 pub mod screenshot_config;
 pub mod streaming;
 pub mod terminal;
 pub mod types;
 
 // Re-export public types for convenience
+pub use ansi_utils::{py_parse_ansi_spans, py_spans_to_ansi};
 pub use color_utils::{
     py_adjust_contrast_rgb, py_adjust_hue, py_adjust_saturation, py_char_width, py_char_width_cjk,
     py_color_luminance, py_complementary_color, py_contrast_ratio, py_darken_rgb, py_hex_to_rgb,
@@ -28,10 +33,11 @@ pub use color_utils::{
     py_rgb_to_hex, py_rgb_to_hsl, py_str_width, py_str_width_cjk,
 };
 pub use enums::{
-    PyAmbiguousWidth, PyCursorStyle, PyMouseEncoding, PyNormalizationForm, PyProgressState,
-    PyUnderlineStyle, PyUnicodeVersion, PyWidthConfig,
+    PyAmbiguousWidth, PyCursorStyle, PyGraphemeClustering, PyMouseEncoding, PyNormalizationForm,
+    PyProgressState, PyUnderlineStyle, PyUnicodeVersion, PyWidthConfig,
 };
 pub use pty::PyPtyTerminal;
+pub use pty_async::PyPtyEventIterator;
 pub use screenshot_config::PyScreenshotConfig;
 pub use streaming::{
     decode_client_message, decode_server_message, encode_client_message, encode_server_message,
@@ -41,13 +47,16 @@ pub use terminal::PyTerminal;
 pub use types::{
     PyAttributes, PyBenchmarkResult, PyBenchmarkSuite, PyBookmark, PyClipboardEntry,
     PyClipboardHistoryEntry, PyClipboardSyncEvent, PyColorHSL, PyColorHSV, PyColorPalette,
-    PyCommandExecution, PyComplianceReport, PyComplianceTest, PyCoprocessConfig, PyCwdChange,
-    PyDamageRegion, PyDetectedItem, PyEscapeSequenceProfile, PyFrameTiming, PyGraphic,
-    PyImageDimension, PyImageFormat, PyImagePlacement, PyImageProtocol, PyInlineImage,
-    PyJoinedLines, PyLineDiff, PyMacro, PyMacroEvent, PyMouseEvent, PyMousePosition,
-    PyNotificationConfig, PyNotificationEvent, PyPaneState, PyPerformanceMetrics, PyProfilingData,
-    PyProgressBar, PyRecordingEvent, PyRecordingSession, PyRegexMatch, PyRenderingHint,
-    PyScreenSnapshot, PyScrollbackStats, PySearchMatch, PySelection, PySelectionMode,
-    PySessionState, PyShellIntegration, PyShellIntegrationStats, PySnapshotDiff,
-    PyTmuxNotification, PyTrigger, PyTriggerAction, PyTriggerMatch, PyWindowLayout,
+    PyCommandExecution, PyComplianceCategory, PyComplianceReport, PyComplianceTest,
+    PyCoprocessConfig, PyCursorMotion, PyCwdChange, PyDamageRegion, PyDetectedItem,
+    PyEnvironmentChange, PyEscapeSequenceProfile, PyExpectMatch, PyFrameTiming, PyGraphic,
+    PyHostContext, PyImageDimension, PyImageFormat, PyImagePlacement, PyImageProtocol,
+    PyInlineImage, PyJoinedLines, PyLatencyMetrics, PyLatencyStats, PyLineDiff, PyMacro,
+    PyMacroEvent, PyMark, PyMouseEvent, PyMousePosition, PyNotificationConfig, PyNotificationEvent,
+    PyPaneState, PyPerformanceMetrics, PyProfilingData, PyProgressBar, PyRecordingEvent,
+    PyRecordingSession, PyRegexMatch, PyRenderingHint, PyScreenSnapshot, PyScrollbackStats,
+    PySearchMatch, PySelection, PySelectionMode, PySessionState, PyShellEnvironment,
+    PyShellIntegration, PyShellIntegrationStats, PySnapshotDiff, PyTerminalState,
+    PyTmuxNotification, PyTrigger, PyTriggerAction, PyTriggerMatch, PyUnsupportedSequence,
+    PyWindowLayout,
 };
@@ -1,16 +1,17 @@
 //! Python observer bindings for push-based event delivery
 //!
 //! Provides `PyCallbackObserver` (sync callback) and `PyQueueObserver` (asyncio.Queue)
-//! that bridge the Rust `TerminalObserver` trait to Python callables.
+//! that bridge the Rust `TerminalObserver` trait to Python callables, and
+//! `py_sequence_filter`, which adapts a Python callable as a sequence filter.
 
 use std::cell::Cell;
 use std::collections::{HashMap, HashSet};
 
 use pyo3::prelude::*;
-use pyo3::types::PyAny;
+use pyo3::types::{PyAny, PyBytes, PyDict};
 
 use crate::observer::TerminalObserver;
-use crate::terminal::{TerminalEvent, TerminalEventKind};
+use crate::terminal::{Sequence, SequenceAction, TerminalEvent, TerminalEventKind};
 
 /// Convert a `TerminalEvent` to a Python-friendly dictionary.
 ///
@@ -35,18 +36,33 @@ pub(crate) fn event_to_dict(event: &TerminalEvent) -> HashMap<String, String> {
                 }
             }
         }
-        TerminalEvent::TitleChanged(title) => {
+        TerminalEvent::TitleChanged { title, old_title } => {
             map.insert("type".to_string(), "title_changed".to_string());
             map.insert("title".to_string(), title.clone());
+            map.insert("old_title".to_string(), old_title.clone());
+        }
+        TerminalEvent::IconNameChanged {
+            icon_name,
+            old_icon_name,
+        } => {
+            map.insert("type".to_string(), "icon_name_changed".to_string());
+            map.insert("icon_name".to_string(), icon_name.clone());
+            map.insert("old_icon_name".to_string(), old_icon_name.clone());
         }
         TerminalEvent::SizeChanged(cols, rows) => {
             map.insert("type".to_string(), "size_changed".to_string());
             map.insert("cols".to_string(), cols.to_string());
             map.insert("rows".to_string(), rows.to_string());
         }
+        TerminalEvent::ResizeRequested { cols, rows, pixels } => {
+            map.insert("type".to_string(), "resize_requested".to_string());
+            map.insert("cols".to_string(), cols.to_string());
+            map.insert("rows".to_string(), rows.to_string());
+            map.insert("pixels".to_string(), pixels.to_string());
+        }
         TerminalEvent::ModeChanged(mode, enabled) => {
             map.insert("type".to_string(), "mode_changed".to_string());
-            map.insert("mode".to_string(), mode.clone());
+            map.insert("mode".to_string(), mode.name().to_string());
             map.insert("enabled".to_string(), enabled.to_string());
         }
         TerminalEvent::GraphicsAdded(row) => {
@@ -269,6 +285,77 @@ pub(crate) fn event_to_dict(event: &TerminalEvent) -> HashMap<String, String> {
             }
             map.insert("size".to_string(), size.to_string());
         }
+        TerminalEvent::FileReceived {
+            id,
+            name,
+            size,
+            sink,
+        } => {
+            map.insert("type".to_string(), "file_received".to_string());
+            map.insert("id".to_string(), id.to_string());
+            map.insert("name".to_string(), name.clone());
+            map.insert("size".to_string(), size.to_string());
+            map.insert("sink".to_string(), sink.to_string());
+        }
+        TerminalEvent::CursorStyleChanged { style } => {
+            map.insert("type".to_string(), "cursor_style_changed".to_string());
+            map.insert("style".to_string(), style.name().to_string());
+            map.insert("shape".to_string(), style.shape().name().to_string());
+            map.insert("blinking".to_string(), style.is_blinking().to_string());
+        }
+        TerminalEvent::PrintJobCompleted {
+            kind,
+            size,
+            data,
+            sink,
+        } => {
+            map.insert("type".to_string(), "print_job_completed".to_string());
+            map.insert("kind".to_string(), kind.name().to_string());
+            map.insert("size".to_string(), size.to_string());
+            map.insert(
+                "data".to_string(),
+                String::from_utf8_lossy(data).into_owned(),
+            );
+            map.insert("sink".to_string(), sink.to_string());
+        }
+        TerminalEvent::SecretDetected {
+            name,
+            abs_row,
+            col,
+            end_abs_row,
+            end_col,
+        } => {
+            map.insert("type".to_string(), "secret_detected".to_string());
+            map.insert("name".to_string(), name.clone());
+            map.insert("abs_row".to_string(), abs_row.to_string());
+            map.insert("col".to_string(), col.to_string());
+            map.insert("end_abs_row".to_string(), end_abs_row.to_string());
+            map.insert("end_col".to_string(), end_col.to_string());
+        }
+        TerminalEvent::ForegroundProcessChanged { pid, name } => {
+            map.insert("type".to_string(), "foreground_process_changed".to_string());
+            map.insert("pid".to_string(), pid.to_string());
+            map.insert("name".to_string(), name.clone());
+        }
+        TerminalEvent::Utf8DecodeError { offset, length } => {
+            map.insert("type".to_string(), "utf8_decode_error".to_string());
+            map.insert("offset".to_string(), offset.to_string());
+            map.insert("length".to_string(), length.to_string());
+        }
+        TerminalEvent::EscapeStringAborted {
+            kind,
+            reason,
+            length,
+        } => {
+            map.insert("type".to_string(), "escape_string_aborted".to_string());
+            map.insert("kind".to_string(), kind.as_str().to_string());
+            map.insert("reason".to_string(), reason.as_str().to_string());
+            map.insert("length".to_string(), length.to_string());
+        }
+        TerminalEvent::BackgroundChanged { change } => {
+            map.insert("type".to_string(), "background_changed".to_string());
+            map.insert("change".to_string(), change.as_str().to_string());
+        }
         TerminalEvent::FileTransferFailed { id, reason } => {
             map.insert("type".to_string(), "file_transfer_failed".to_string());
             map.insert("id".to_string(), id.to_string());
@@ -285,6 +372,38 @@ pub(crate) fn event_to_dict(event: &TerminalEvent) -> HashMap<String, String> {
                 include_scrollback.to_string(),
             );
         }
+        TerminalEvent::CursorMoved { from, to, cause } => {
+            map.insert("type".to_string(), "cursor_moved".to_string());
+            map.insert("from_col".to_string(), from.0.to_string());
+            map.insert("from_row".to_string(), from.1.to_string());
+            map.insert("to_col".to_string(), to.0.to_string());
+            map.insert("to_row".to_string(), to.1.to_string());
+            map.insert("cause".to_string(), cause.name().to_string());
+        }
+        TerminalEvent::TriggerFired {
+            name,
+            trigger_match,
+        } => {
+            map.insert("type".to_string(), "trigger_fired".to_string());
+            map.insert("name".to_string(), name.clone());
+            map.insert(
+                "trigger_id".to_string(),
+                trigger_match.trigger_id.to_string(),
+            );
+            map.insert("row".to_string(), trigger_match.row.to_string());
+            map.insert("col".to_string(), trigger_match.col.to_string());
+            map.insert("end_col".to_string(), trigger_match.end_col.to_string());
+            map.insert("text".to_string(), trigger_match.text.clone());
+            for (i, capture) in trigger_match.captures.iter().enumerate() {
+                map.insert(format!("capture_{}", i), capture.clone());
+            }
+        }
+        TerminalEvent::ThemeChanged { name } => {
+            map.insert("type".to_string(), "theme_changed".to_string());
+            if let Some(n) = name {
+                map.insert("name".to_string(), n.clone());
+            }
+        }
     }
     map
 }
@@ -399,6 +518,63 @@ impl TerminalObserver for PyQueueObserver {
     }
 }
 
+/// Convert a filtered sequence to the dict passed to Python sequence filters
+fn sequence_to_dict<'py>(py: Python<'py>, sequence: &Sequence<'_>) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    match sequence {
+        Sequence::Osc(osc) => {
+            dict.set_item("kind", "osc")?;
+            dict.set_item("command", osc.command())?;
+            let args: Vec<Bound<'py, PyBytes>> =
+                osc.args().iter().map(|arg| PyBytes::new(py, arg)).collect();
+            dict.set_item("args", args)?;
+            dict.set_item("bell_terminated", osc.bell_terminated)?;
+        }
+        Sequence::Dcs(dcs) => {
+            dict.set_item("kind", "dcs")?;
+            dict.set_item("params", dcs.params.to_vec())?;
+            dict.set_item(
+                "intermediates",
+                String::from_utf8_lossy(dcs.intermediates).into_owned(),
+            )?;
+            dict.set_item("action", dcs.action.to_string())?;
+        }
+    }
+    Ok(dict)
+}
+
+/// Adapt a Python callable into a sequence filter.
+///
+/// The callable receives a dict describing the sequence and returns
+/// `"allow"`, `"block"` or `"consume"` (`None` means allow). Exceptions and
+/// unknown return values block the sequence, failing closed.
+pub(crate) fn py_sequence_filter(
+    callback: Py<PyAny>,
+) -> impl FnMut(&Sequence<'_>) -> SequenceAction + Send + Sync + 'static {
+    move |sequence| {
+        Python::attach(|py| {
+            let result = sequence_to_dict(py, sequence)
+                .and_then(|dict| callback.call1(py, (dict,)))
+                .and_then(|value| value.extract::<Option<String>>(py));
+            match result.as_ref().map(|value| value.as_deref()) {
+                Ok(None | Some("allow")) => SequenceAction::Allow,
+                Ok(Some("block")) => SequenceAction::Block,
+                Ok(Some("consume")) => SequenceAction::Consume,
+                Ok(Some(other)) => {
+                    eprintln!(
+                        "Sequence filter returned {other:?}; expected \"allow\", \"block\" or \"consume\""
+                    );
+                    SequenceAction::Block
+                }
+                Err(e) => {
+                    eprintln!("Sequence filter error: {e}");
+                    SequenceAction::Block
+                }
+            }
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
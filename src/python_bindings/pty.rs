@@ -18,13 +18,15 @@
 
 use pyo3::exceptions::{PyIOError, PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
 use std::collections::HashMap;
 
 use crate::color::Color;
 use crate::pty_session;
+use crate::pty_spawn::{EnvMode, SpawnConfig};
 
 use super::conversions::parse_sixel_mode;
-use super::types::{PyAttributes, PyScreenSnapshot};
+use super::types::{PyAttributes, PyScreenSnapshot, PyTerminalState};
 
 /// Python wrapper for PtySession - a terminal with PTY support
 #[pyclass(name = "PtyTerminal", unsendable)]
@@ -62,6 +64,7 @@ crate::impl_terminal_search_select!(PyPtyTerminal);
 crate::impl_terminal_debug_snapshots!(PyPtyTerminal);
 crate::impl_terminal_file_transfer!(PyPtyTerminal);
 crate::impl_terminal_exports!(PyPtyTerminal);
+crate::impl_terminal_snapshot_deltas!(PyPtyTerminal);
 
 #[pymethods]
 impl PyPtyTerminal {
@@ -140,6 +143,67 @@ impl PyPtyTerminal {
         Ok(())
     }
 
+    /// Spawn a process with full control over argv, cwd, environment and TERM
+    ///
+    /// The working directory and the command (looked up on the child's own
+    /// PATH) are checked before the PTY is opened; failures raise
+    /// RuntimeError naming the command, cwd and the PATH that was searched.
+    ///
+    /// Args:
+    ///     command: Program to run, as a path or a name looked up on PATH
+    ///     args: Optional list of arguments
+    ///     cwd: Optional working directory
+    ///     env: Optional dictionary of environment variables, applied last
+    ///     env_mode: "inherit" (parent environment plus `env`, default) or
+    ///               "replace" (only `env` plus terminal identification vars)
+    ///     env_remove: Optional list of inherited variables to remove
+    ///     login_shell: Start as a login shell (adds `-l`)
+    ///     term: TERM value (default: "xterm-256color")
+    #[pyo3(signature = (
+        command,
+        args=None,
+        cwd=None,
+        env=None,
+        env_mode="inherit",
+        env_remove=None,
+        login_shell=false,
+        term=None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn spawn_with_config(
+        &mut self,
+        command: &str,
+        args: Option<Vec<String>>,
+        cwd: Option<String>,
+        env: Option<HashMap<String, String>>,
+        env_mode: &str,
+        env_remove: Option<Vec<String>>,
+        login_shell: bool,
+        term: Option<String>,
+    ) -> PyResult<()> {
+        let env_mode = match env_mode.to_ascii_lowercase().as_str() {
+            "inherit" => EnvMode::Inherit,
+            "replace" => EnvMode::Replace,
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "Unknown env_mode '{other}' (expected 'inherit' or 'replace')"
+                )))
+            }
+        };
+        let mut config = SpawnConfig::new(command)
+            .with_args(args.unwrap_or_default())
+            .with_env_mode(env_mode)
+            .with_login_shell(login_shell);
+        config.cwd = cwd.map(std::path::PathBuf::from);
+        config.env = env.unwrap_or_default();
+        config.env_remove = env_remove.unwrap_or_default();
+        if let Some(term) = term {
+            config.term = term;
+        }
+        self.inner.spawn_with_config(&config)?;
+        Ok(())
+    }
+
     /// Write data to the PTY (send to the child process)
     ///
     /// Args:
@@ -149,6 +213,15 @@ impl PyPtyTerminal {
         Ok(())
     }
 
+    /// Write queued replies (e.g. from send_upload_data) to the PTY now
+    ///
+    /// Replies are otherwise flushed after each chunk of child output and
+    /// before write() and resize().
+    fn flush_replies(&self) -> PyResult<()> {
+        self.inner.flush_replies();
+        Ok(())
+    }
+
     /// Write a string to the PTY (convenience method)
     ///
     /// Args:
@@ -220,6 +293,135 @@ impl PyPtyTerminal {
         Ok(self.inner.child_pid())
     }
 
+    /// Return the PID of the PTY's foreground process group leader (Unix only)
+    ///
+    /// Returns:
+    ///     PID as an integer, or None if unavailable
+    fn foreground_pid(&self) -> PyResult<Option<u32>> {
+        Ok(self.inner.foreground_pid())
+    }
+
+    /// Describe the foreground process group leader, e.g. for a tab title
+    ///
+    /// Returns:
+    ///     Dict with keys: pid, ppid, name, cwd (str or None), stopped, cmdline (list[str]),
+    ///     or None if unavailable
+    fn foreground_process(&self) -> PyResult<Option<Py<PyDict>>> {
+        self.inner
+            .foreground_process()
+            .map(|info| process_info_dict(&info))
+            .transpose()
+    }
+
+    /// Check whether a job other than the spawned process holds the foreground
+    ///
+    /// Use this to confirm closing a session while a command runs.
+    ///
+    /// Returns:
+    ///     True if e.g. a command started from the shell is running
+    fn has_foreground_job(&self) -> PyResult<bool> {
+        Ok(self.inner.has_foreground_job())
+    }
+
+    /// List the spawned process followed by all of its descendants
+    ///
+    /// Returns:
+    ///     List of dicts with the same keys as foreground_process()
+    fn process_tree(&self) -> PyResult<Vec<Py<PyDict>>> {
+        self.inner
+            .process_tree()
+            .iter()
+            .map(process_info_dict)
+            .collect()
+    }
+
+    /// Check the foreground process group now and emit a
+    /// "foreground_process_changed" event if it changed
+    ///
+    /// Output already triggers this check; call it on a timer to also notice
+    /// commands that start without printing anything.
+    ///
+    /// Returns:
+    ///     True if an event was emitted
+    fn poll_foreground_process(&self) -> PyResult<bool> {
+        Ok(self.inner.poll_foreground_process())
+    }
+
+    /// Configure output throttling
+    ///
+    /// While enabled, at most ``max_bytes_per_interval`` bytes of output are
+    /// processed per ``interval_ms``; past that the reader yields so the UI
+    /// thread is not starved. With ``drop_threshold`` > 0, a burst that goes
+    /// that many bytes over budget is dropped and a one-line notice with the
+    /// dropped byte count is written once output calms down.
+    ///
+    /// Args:
+    ///     enabled: Whether to throttle output
+    ///     max_bytes_per_interval: Budget per interval (default 1048576)
+    ///     interval_ms: Interval length in ms (default 16)
+    ///     drop_threshold: Bytes over budget before dropping, 0 to only
+    ///         delay (default 0)
+    #[pyo3(signature = (enabled, max_bytes_per_interval=None, interval_ms=None, drop_threshold=None))]
+    fn set_throttle_config(
+        &self,
+        enabled: bool,
+        max_bytes_per_interval: Option<usize>,
+        interval_ms: Option<u64>,
+        drop_threshold: Option<usize>,
+    ) -> PyResult<()> {
+        let defaults = crate::pty_throttle::ThrottleConfig::default();
+        self.inner
+            .set_throttle_config(crate::pty_throttle::ThrottleConfig {
+                enabled,
+                max_bytes_per_interval: max_bytes_per_interval
+                    .unwrap_or(defaults.max_bytes_per_interval),
+                interval_ms: interval_ms.unwrap_or(defaults.interval_ms),
+                drop_threshold: drop_threshold.unwrap_or(defaults.drop_threshold),
+            });
+        Ok(())
+    }
+
+    /// Get the output throttling settings
+    ///
+    /// Returns:
+    ///     Dictionary with "enabled", "max_bytes_per_interval", "interval_ms"
+    ///     and "drop_threshold"
+    fn get_throttle_config(&self) -> PyResult<Py<PyDict>> {
+        let config = self.inner.throttle_config();
+        Python::attach(|py| {
+            let dict = PyDict::new(py);
+            dict.set_item("enabled", config.enabled)?;
+            dict.set_item("max_bytes_per_interval", config.max_bytes_per_interval)?;
+            dict.set_item("interval_ms", config.interval_ms)?;
+            dict.set_item("drop_threshold", config.drop_threshold)?;
+            Ok(dict.unbind())
+        })
+    }
+
+    /// Get output throttling counters
+    ///
+    /// Returns:
+    ///     Dictionary with "bytes_processed", "bytes_deferred", "deferrals",
+    ///     "bytes_dropped" and "bursts_dropped"
+    fn get_throttle_stats(&self) -> PyResult<Py<PyDict>> {
+        let stats = self.inner.throttle_stats();
+        Python::attach(|py| {
+            let dict = PyDict::new(py);
+            dict.set_item("bytes_processed", stats.bytes_processed)?;
+            dict.set_item("bytes_deferred", stats.bytes_deferred)?;
+            dict.set_item("deferrals", stats.deferrals)?;
+            dict.set_item("bytes_dropped", stats.bytes_dropped)?;
+            dict.set_item("bursts_dropped", stats.bursts_dropped)?;
+            Ok(dict.unbind())
+        })
+    }
+
+    /// Reset output throttling counters
+    fn reset_throttle_stats(&self) -> PyResult<()> {
+        self.inner.reset_throttle_stats();
+        Ok(())
+    }
+
     /// Check if the process is still running
     ///
     /// Returns:
@@ -254,12 +456,195 @@ impl PyPtyTerminal {
         Ok(())
     }
 
+    /// Send a signal to the foreground job (the PTY's foreground process group)
+    ///
+    /// Unlike writing "\x03", this reaches jobs that turned off signal keys or
+    /// stopped reading input. Unix only.
+    ///
+    /// Args:
+    ///     signal: Signal name, with or without "SIG" (e.g. "SIGINT", "term")
+    fn send_signal(&self, signal: &str) -> PyResult<()> {
+        self.inner.send_signal(parse_signal(signal)?)?;
+        Ok(())
+    }
+
+    /// Send a signal to the spawned process only (not its children). Unix only.
+    ///
+    /// Args:
+    ///     signal: Signal name, with or without "SIG" (e.g. "SIGHUP")
+    fn signal_child(&self, signal: &str) -> PyResult<()> {
+        self.inner.signal_child(parse_signal(signal)?)?;
+        Ok(())
+    }
+
+    /// Suspend the foreground job, as Ctrl+Z would
+    ///
+    /// Returns:
+    ///     Process group ID of the suspended job
+    fn suspend(&mut self) -> PyResult<u32> {
+        Ok(self.inner.suspend()?)
+    }
+
+    /// Resume the job stopped by the last suspend() with SIGCONT
+    ///
+    /// Under an interactive shell the resumed job runs in the background;
+    /// write "fg\n" to bring it back to the foreground instead.
+    fn resume(&mut self) -> PyResult<()> {
+        self.inner.resume()?;
+        Ok(())
+    }
+
+    /// Get the state of the spawned process
+    ///
+    /// Returns:
+    ///     Dictionary with "state" ("not_started", "running", "stopped",
+    ///     "exited" or "signaled"), "exit_code" (int or None) and "signal"
+    ///     (description such as "Interrupt", or None)
+    fn process_status(&mut self) -> PyResult<Py<PyDict>> {
+        let status = self.inner.process_status();
+        Python::attach(|py| {
+            let dict = PyDict::new(py);
+            dict.set_item("state", status.name())?;
+            let (exit_code, signal) = match &status {
+                crate::pty_signal::ProcessStatus::Exited(code) => (Some(*code), None),
+                crate::pty_signal::ProcessStatus::Signaled(signal) => (None, Some(signal.as_str())),
+                _ => (None, None),
+            };
+            dict.set_item("exit_code", exit_code)?;
+            dict.set_item("signal", signal)?;
+            Ok(dict.unbind())
+        })
+    }
+
+    /// Wait for output matching one of several regexes, pexpect style
+    ///
+    /// Matches against the expect buffer (see set_expect_buffer), which is
+    /// enabled on first use; enable it before spawning to also match output
+    /// that arrives earlier. The GIL is released while waiting.
+    ///
+    /// Args:
+    ///     patterns: Regex string or list of regex strings
+    ///     timeout: Maximum seconds to wait, or None to wait indefinitely
+    ///
+    /// Returns:
+    ///     ExpectMatch with the pattern index, text before the match, the
+    ///     matched text and capture groups
+    ///
+    /// Raises:
+    ///     ValueError: If a pattern is invalid
+    ///     TimeoutError: If nothing matches within `timeout`
+    ///     EOFError: If the process exits without a match
+    #[pyo3(signature = (patterns, timeout=None))]
+    fn expect(
+        &self,
+        py: Python<'_>,
+        patterns: &Bound<'_, PyAny>,
+        timeout: Option<f64>,
+    ) -> PyResult<super::types::PyExpectMatch> {
+        let patterns = super::conversions::parse_expect_patterns(patterns)?;
+        let timeout = timeout
+            .map(std::time::Duration::try_from_secs_f64)
+            .transpose()
+            .map_err(|_| PyValueError::new_err("timeout must be a non-negative number"))?;
+        let terminal = self.inner.terminal();
+        let waiter = self.inner.output_waiter();
+        let found =
+            py.detach(|| pty_session::expect_output(&terminal, &waiter, &patterns, timeout))?;
+        Ok(found.into())
+    }
+
+    /// Start `tmux -CC` in this terminal and discover its windows and panes
+    ///
+    /// Turns on tmux control mode, waits for the attach handshake and sizes
+    /// the tmux client to this terminal. Pane output then arrives as "output"
+    /// notifications (see drain_tmux_notifications). The GIL is released
+    /// while waiting.
+    ///
+    /// Args:
+    ///     session_name: Session to attach to, created if missing (default: new session)
+    ///     socket_name: tmux server socket name (`tmux -L`), None for the default server
+    ///     tmux_path: tmux executable (default: "tmux")
+    ///     timeout: Seconds allowed for the handshake and each query (default: 5.0)
+    ///
+    /// Returns:
+    ///     Dictionary with "session_id", "session_name" and "windows", a list
+    ///     of window dicts ("id", "index", "name", "active", "width",
+    ///     "height", "layout", "panes") whose "panes" are dicts ("id",
+    ///     "window_id", "index", "active", "width", "height", "left", "top",
+    ///     "current_command", "current_path", "title")
+    ///
+    /// Raises:
+    ///     RuntimeError: If tmux cannot be spawned or a command fails
+    ///     TimeoutError: If tmux does not answer within `timeout`
+    ///     EOFError: If tmux exits during the handshake
+    #[pyo3(signature = (session_name=None, socket_name=None, tmux_path="tmux", timeout=5.0))]
+    fn spawn_tmux_control(
+        &mut self,
+        py: Python<'_>,
+        session_name: Option<String>,
+        socket_name: Option<String>,
+        tmux_path: &str,
+        timeout: f64,
+    ) -> PyResult<Py<PyDict>> {
+        let config = crate::tmux_session::TmuxBootstrapConfig {
+            tmux_path: tmux_path.to_string(),
+            session_name,
+            socket_name,
+            timeout: tmux_timeout(timeout)?,
+        };
+        let inner = &mut self.inner;
+        let info = py.detach(|| inner.spawn_tmux_control(&config))?;
+        tmux_session_info_to_dict(py, &info)
+    }
+
+    /// Run a tmux command over control mode and return its output lines
+    ///
+    /// Requires a session started with spawn_tmux_control(). The GIL is
+    /// released while waiting.
+    ///
+    /// Args:
+    ///     command: tmux command, e.g. "split-window -h"
+    ///     timeout: Maximum seconds to wait (default: 5.0)
+    ///
+    /// Returns:
+    ///     List of output lines
+    ///
+    /// Raises:
+    ///     RuntimeError: If the command fails (message from tmux)
+    ///     TimeoutError: If tmux does not answer within `timeout`
+    #[pyo3(signature = (command, timeout=5.0))]
+    fn tmux_command(
+        &mut self,
+        py: Python<'_>,
+        command: &str,
+        timeout: f64,
+    ) -> PyResult<Vec<String>> {
+        let timeout = tmux_timeout(timeout)?;
+        let inner = &mut self.inner;
+        Ok(py.detach(|| inner.tmux_command(command, Some(timeout)))?)
+    }
+
+    /// Query the tmux session's windows and panes again
+    ///
+    /// Args:
+    ///     timeout: Maximum seconds to wait for each query (default: 5.0)
+    ///
+    /// Returns:
+    ///     Dictionary in the format returned by spawn_tmux_control()
+    #[pyo3(signature = (timeout=5.0))]
+    fn tmux_session_info(&mut self, py: Python<'_>, timeout: f64) -> PyResult<Py<PyDict>> {
+        let timeout = tmux_timeout(timeout)?;
+        let inner = &mut self.inner;
+        let info = py.detach(|| inner.tmux_session_info(Some(timeout)))?;
+        tmux_session_info_to_dict(py, &info)
+    }
+
     // Terminal query methods
 
     // content, __str__: provided by impl_terminal_content_misc! (ARC-003/QA-001)
 
     // title: provided by impl_terminal_query_getters! (ARC-003/QA-001)
-    // export_text, export_styled: provided by impl_terminal_exports! (ARC-003/QA-001)
+    // export_text, export_text_opts, export_styled: provided by impl_terminal_exports! (ARC-003/QA-001)
 
     /// Take a screenshot of the current visible buffer
     ///
@@ -281,6 +666,7 @@ impl PyPtyTerminal {
     ///     background_color: Background color RGB tuple. Default: None (use terminal's default background)
     ///     faint_text_alpha: Alpha multiplier for faint/dim text (0.0-1.0). Default: 0.5 (50% dimming)
     ///     minimum_contrast: Minimum contrast adjustment (0.0-1.0). Default: 0.5 (moderate contrast adjustment)
+    ///     fallback_fonts: List of TTF/OTF font paths tried in order for glyphs missing from the main font. Default: None
     ///
     /// Returns:
     ///     Bytes of the image in the specified format
@@ -305,7 +691,8 @@ impl PyPtyTerminal {
         bold_brightening = None,
         background_color = None,
         faint_text_alpha = 0.5,
-        minimum_contrast = 0.5
+        minimum_contrast = 0.5,
+        fallback_fonts = None
     ))]
     #[allow(clippy::too_many_arguments)]
     fn screenshot(
@@ -327,6 +714,7 @@ impl PyPtyTerminal {
         background_color: Option<(u8, u8, u8)>,
         faint_text_alpha: Option<f32>,
         minimum_contrast: f64,
+        fallback_fonts: Option<Vec<String>>,
     ) -> PyResult<Vec<u8>> {
         use crate::screenshot::{ImageFormat, ScreenshotConfig};
 
@@ -355,6 +743,11 @@ impl PyPtyTerminal {
         let config = ScreenshotConfig {
             format: img_format,
             font_path: font_path.map(std::path::PathBuf::from),
+            fallback_fonts: fallback_fonts
+                .unwrap_or_default()
+                .into_iter()
+                .map(std::path::PathBuf::from)
+                .collect(),
             font_size,
             include_scrollback,
             padding_px: padding,
@@ -400,6 +793,7 @@ impl PyPtyTerminal {
     ///     background_color: Background color RGB tuple. Default: None (use terminal's default background)
     ///     faint_text_alpha: Alpha multiplier for faint/dim text (0.0-1.0). Default: 0.5 (50% dimming)
     ///     minimum_contrast: Minimum contrast adjustment (0.0-1.0). Default: 0.5 (moderate contrast adjustment)
+    ///     fallback_fonts: List of TTF/OTF font paths tried in order for glyphs missing from the main font. Default: None
     ///
     /// Returns:
     ///     None
@@ -425,7 +819,8 @@ impl PyPtyTerminal {
         bold_brightening = None,
         background_color = None,
         faint_text_alpha = 0.5,
-        minimum_contrast = 0.5
+        minimum_contrast = 0.5,
+        fallback_fonts = None
     ))]
     #[allow(clippy::too_many_arguments)]
     fn screenshot_to_file(
@@ -448,6 +843,7 @@ impl PyPtyTerminal {
         background_color: Option<(u8, u8, u8)>,
         faint_text_alpha: Option<f32>,
         minimum_contrast: f64,
+        fallback_fonts: Option<Vec<String>>,
     ) -> PyResult<()> {
         use std::path::Path;
 
@@ -474,6 +870,7 @@ impl PyPtyTerminal {
             background_color,
             faint_text_alpha,
             minimum_contrast,
+            fallback_fonts,
         )?;
 
         std::fs::write(path, bytes)
@@ -560,6 +957,21 @@ impl PyPtyTerminal {
     // is_line_wrapped, get_fg_color, get_bg_color, get_underline_color, get_attributes,
     // get_hyperlink, get_line_cells: provided by impl_terminal_cell_line_queries! (ARC-003/QA-001)
 
+    /// Take an immutable handle on the visible screen, cursor and modes
+    ///
+    /// Does not wait for PTY output being processed: while the reader
+    /// thread holds the terminal, the handle published after the previous
+    /// chunk is returned. Renderers can read it without holding any lock.
+    /// With double buffering on, this is the committed front buffer.
+    ///
+    /// Returns:
+    ///     TerminalState
+    fn state_handle(&self) -> PyResult<PyTerminalState> {
+        Ok(PyTerminalState {
+            inner: self.inner.state_handle(),
+        })
+    }
+
     /// Create atomic snapshot of current screen state
     ///
     /// Captures all lines, cursor state, and screen identity atomically.
@@ -643,12 +1055,12 @@ impl PyPtyTerminal {
                         }
                     }
 
-                    line.push((
-                        cell.get_grapheme(),
+                    let (fg, bg) = term.apply_minimum_contrast(
+                        cell,
                         resolve_fg_color(fg),
                         resolve_bg_color(cell.bg),
-                        PyAttributes::from(cell),
-                    ));
+                    );
+                    line.push((cell.get_grapheme(), fg, bg, PyAttributes::from(cell)));
                 } else {
                     // Empty cell
                     line.push((
@@ -822,11 +1234,15 @@ impl PyPtyTerminal {
 
     // keyboard_flags: provided by impl_terminal_query_getters! (ARC-003/QA-001)
 
+    // encode_key: provided by impl_terminal_query_getters! (ARC-003/QA-001)
+
+    // win32_input_mode: provided by impl_terminal_query_getters! (ARC-003/QA-001)
+
     /// Set Kitty Keyboard Protocol flags
     ///
     /// Args:
     ///     flags: Flags to set (1=disambiguate, 2=report events, 4=alternate keys, 8=report all, 16=associated text)
-    ///     mode: 0=disable all, 1=set flags, 2=lock flags (default: 1)
+    ///     mode: 1=set flags, 2=add flags, 3=remove flags (default: 1)
     ///
     /// Sends: CSI = flags ; mode u
     #[pyo3(signature = (flags, mode=1))]
@@ -1193,15 +1609,17 @@ impl PyPtyTerminal {
 
     // find_matching_bracket, select_semantic_region:
     //   provided by impl_terminal_search_select! (ARC-003/QA-001)
+    // set_expect_buffer, expect_buffer, clear_expect_buffer, expect_match:
+    //   provided by impl_terminal_search_select! (ARC-003/QA-001)
     // export_html: provided by impl_terminal_exports! (ARC-003/QA-001)
 
     // ========== Static Utility Methods ==========
     // strip_ansi, measure_text_width, parse_color: provided by impl_terminal_static_helpers! (ARC-003/QA-001)
     // get_sixel_limits, set_sixel_limits, get_sixel_graphics_limit, set_sixel_graphics_limit,
-    // get_dropped_sixel_graphics, get_sixel_stats: provided by impl_terminal_sixel_graphics! (ARC-003/QA-001)
+    // get_dropped_sixel_graphics, get_sixel_stats, get_regis_limits, set_regis_limits: provided by impl_terminal_sixel_graphics! (ARC-003/QA-001)
 
     // start_recording, stop_recording, is_recording, record_output, record_input,
-    // record_resize, record_marker, get_recording_session:
+    // record_resize, record_marker, get_recording_session, export_recording:
     //   provided by impl_terminal_recording! (ARC-003/QA-001)
 
     /// Export recording to asciicast v2 format
@@ -1330,16 +1748,47 @@ impl PyPtyTerminal {
     #[pyo3(signature = (name, speed=None))]
     fn play_macro(&self, name: String, speed: Option<f64>) -> PyResult<()> {
         if let Ok(mut term) = Ok::<_, ()>(self.inner.terminal().write()) {
-            term.play_macro(&name).map_err(PyValueError::new_err)?;
-            if let Some(s) = speed {
-                term.set_macro_speed(s);
-            }
-            Ok(())
+            term.play_macro_with_speed(&name, speed.unwrap_or(1.0))
+                .map_err(PyValueError::new_err)
         } else {
             Err(PyRuntimeError::new_err("Failed to lock terminal"))
         }
     }
 
+    /// Start recording input written to the PTY into a macro
+    ///
+    /// Every write (``write``, ``write_str``, ``paste``, macro playback) is
+    /// captured together with the delay since the previous write. Any
+    /// recording already in progress is discarded.
+    ///
+    /// Args:
+    ///     name: Name the macro is stored under when recording stops
+    fn record_macro(&self, name: String) -> PyResult<()> {
+        self.inner.terminal().write().record_macro(&name);
+        Ok(())
+    }
+
+    /// Stop recording and store the macro in the library
+    ///
+    /// Returns:
+    ///     The recorded Macro, or None if no recording was in progress
+    fn stop_macro_recording(&self) -> PyResult<Option<super::types::PyMacro>> {
+        Ok(self
+            .inner
+            .terminal()
+            .write()
+            .stop_macro_recording()
+            .map(super::types::PyMacro::from))
+    }
+
+    /// Check if a macro is being recorded
+    ///
+    /// Returns:
+    ///     True if recording, False otherwise
+    fn is_macro_recording(&self) -> PyResult<bool> {
+        Ok(self.inner.terminal().read().is_macro_recording())
+    }
+
     /// Stop macro playback
     fn stop_macro(&self) -> PyResult<()> {
         if let Ok(mut term) = Ok::<_, ()>(self.inner.terminal().write()) {
@@ -1493,15 +1942,23 @@ impl PyPtyTerminal {
 }
 
 // Rust-only methods (not exposed to Python)
-#[cfg(feature = "streaming")]
 impl PyPtyTerminal {
-    /// Get a clone of the terminal Arc (for use in streaming server)
+    /// Get a clone of the terminal Arc (for the streaming server and the
+    /// asyncio helpers)
     pub(crate) fn get_terminal_arc(
         &self,
     ) -> std::sync::Arc<parking_lot::RwLock<crate::terminal::Terminal>> {
         self.inner.terminal()
     }
 
+    /// Get the session's output waiter (for the asyncio helpers)
+    pub(crate) fn output_waiter(&self) -> std::sync::Arc<pty_session::OutputWaiter> {
+        self.inner.output_waiter()
+    }
+}
+
+#[cfg(feature = "streaming")]
+impl PyPtyTerminal {
     /// Set an output callback on the PtySession
     ///
     /// This is used internally to wire up streaming servers
@@ -1519,6 +1976,76 @@ impl PyPtyTerminal {
     }
 }
 
+/// Parse a signal name for `send_signal()` and friends
+fn parse_signal(name: &str) -> PyResult<crate::pty_signal::PtySignal> {
+    crate::pty_signal::PtySignal::from_name(name)
+        .ok_or_else(|| PyValueError::new_err(format!("Unknown signal: {name}")))
+}
+
+/// Convert a [`ProcessInfo`](crate::process_tree::ProcessInfo) to a Python dict
+fn process_info_dict(info: &crate::process_tree::ProcessInfo) -> PyResult<Py<PyDict>> {
+    Python::attach(|py| {
+        let dict = PyDict::new(py);
+        dict.set_item("pid", info.pid)?;
+        dict.set_item("ppid", info.ppid)?;
+        dict.set_item("name", &info.name)?;
+        dict.set_item(
+            "cwd",
+            info.cwd
+                .as_ref()
+                .map(|cwd| cwd.to_string_lossy().into_owned()),
+        )?;
+        dict.set_item("stopped", info.stopped)?;
+        dict.set_item("cmdline", &info.cmdline)?;
+        Ok(dict.unbind())
+    })
+}
+
+fn tmux_timeout(seconds: f64) -> PyResult<std::time::Duration> {
+    std::time::Duration::try_from_secs_f64(seconds)
+        .map_err(|_| PyValueError::new_err("timeout must be a non-negative number"))
+}
+
+fn tmux_session_info_to_dict(
+    py: Python<'_>,
+    info: &crate::tmux_session::TmuxSessionInfo,
+) -> PyResult<Py<PyDict>> {
+    let windows = pyo3::types::PyList::empty(py);
+    for window in &info.windows {
+        let panes = pyo3::types::PyList::empty(py);
+        for pane in &window.panes {
+            let dict = PyDict::new(py);
+            dict.set_item("id", &pane.id)?;
+            dict.set_item("window_id", &pane.window_id)?;
+            dict.set_item("index", pane.index)?;
+            dict.set_item("active", pane.active)?;
+            dict.set_item("width", pane.width)?;
+            dict.set_item("height", pane.height)?;
+            dict.set_item("left", pane.left)?;
+            dict.set_item("top", pane.top)?;
+            dict.set_item("current_command", &pane.current_command)?;
+            dict.set_item("current_path", &pane.current_path)?;
+            dict.set_item("title", &pane.title)?;
+            panes.append(dict)?;
+        }
+        let dict = PyDict::new(py);
+        dict.set_item("id", &window.id)?;
+        dict.set_item("index", window.index)?;
+        dict.set_item("name", &window.name)?;
+        dict.set_item("active", window.active)?;
+        dict.set_item("width", window.width)?;
+        dict.set_item("height", window.height)?;
+        dict.set_item("layout", &window.layout)?;
+        dict.set_item("panes", panes)?;
+        windows.append(dict)?;
+    }
+    let dict = PyDict::new(py);
+    dict.set_item("session_id", &info.session_id)?;
+    dict.set_item("session_name", &info.session_name)?;
+    dict.set_item("windows", windows)?;
+    Ok(dict.unbind())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,447 @@
+//! asyncio support for `PtyTerminal`
+//!
+//! The awaitable methods return an `asyncio.Future` resolved by a worker of
+//! the loop's default executor, which blocks on the session's
+//! [`OutputWaiter`](crate::pty_session::OutputWaiter) with the GIL released.
+//! Cancelling the future interrupts the wait.
+
+use parking_lot::{Mutex, RwLock};
+use pyo3::exceptions::{PyEOFError, PyStopAsyncIteration, PyTimeoutError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyCFunction, PyDict, PyTuple};
+use pyo3::IntoPyObjectExt;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::pty_session::{OutputWaiter, WaitOutcome};
+use crate::python_bindings::observer::event_to_dict;
+use crate::terminal::{ExpectError, ExpectMatch, Terminal, DEFAULT_EXPECT_BUFFER_BYTES};
+
+use super::conversions::parse_expect_patterns;
+use super::pty::PyPtyTerminal;
+use super::types::PyExpectMatch;
+
+/// Value a helper thread resolves its future with
+enum Resolved {
+    Bytes(Vec<u8>),
+    Text(String),
+    Event(HashMap<String, String>),
+    Expect(ExpectMatch),
+}
+
+impl Resolved {
+    fn into_py_any(self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        match self {
+            Resolved::Bytes(bytes) => Ok(PyBytes::new(py, &bytes).into_any().unbind()),
+            Resolved::Text(text) => text.into_py_any(py),
+            Resolved::Event(event) => event.into_py_any(py),
+            Resolved::Expect(found) => PyExpectMatch::from(found).into_py_any(py),
+        }
+    }
+}
+
+/// How a [`wait_for`] loop ended
+enum Waited<T> {
+    Ready(T),
+    Closed,
+    TimedOut,
+    Cancelled,
+}
+
+/// Run `check` now and again after every processed output chunk until it
+/// yields a value, the session closes, `timeout` elapses or the awaiting
+/// future is cancelled
+fn wait_for<T>(
+    waiter: &OutputWaiter,
+    timeout: Option<Duration>,
+    cancelled: &AtomicBool,
+    mut check: impl FnMut() -> Option<T>,
+) -> Waited<T> {
+    let deadline = timeout.map(|t| Instant::now() + t);
+    loop {
+        // Read the generation before checking so output that lands in
+        // between makes the wait below return immediately
+        let generation = waiter.generation();
+        if let Some(value) = check() {
+            return Waited::Ready(value);
+        }
+        let remaining = deadline.map(|d| d.saturating_duration_since(Instant::now()));
+        match waiter.wait_for_update(generation, remaining) {
+            WaitOutcome::Updated(_) => {}
+            WaitOutcome::Closed => return Waited::Closed,
+            WaitOutcome::TimedOut => return Waited::TimedOut,
+            WaitOutcome::Interrupted => {
+                if cancelled.load(Ordering::SeqCst) {
+                    return Waited::Cancelled;
+                }
+            }
+        }
+    }
+}
+
+fn timeout_duration(timeout: Option<f64>) -> PyResult<Option<Duration>> {
+    timeout
+        .map(|secs| {
+            Duration::try_from_secs_f64(secs)
+                .map_err(|_| PyValueError::new_err("timeout must be a non-negative number"))
+        })
+        .transpose()
+}
+
+/// Run `work` on the event loop's default executor with the GIL released
+/// and return an asyncio future for its result
+///
+/// The worker only stores the Rust result; Python values and exceptions are
+/// created on the loop thread when the executor future completes. An
+/// exception raised into the awaiting coroutine references its frames, so
+/// it must not be owned by the executor's future, whose last reference is
+/// dropped on the worker thread (and `PtyTerminal` is unsendable).
+/// Cancelling the returned future interrupts the wait so the worker is
+/// returned to the executor promptly.
+fn spawn_awaitable<'py, F>(
+    py: Python<'py>,
+    waiter: &Arc<OutputWaiter>,
+    work: F,
+) -> PyResult<Bound<'py, PyAny>>
+where
+    F: FnOnce(&AtomicBool) -> Option<PyResult<Resolved>> + Send + 'static,
+{
+    let event_loop = py.import("asyncio")?.call_method0("get_running_loop")?;
+    let future = event_loop.call_method0("create_future")?;
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let slot: Arc<Mutex<Option<PyResult<Resolved>>>> = Arc::new(Mutex::new(None));
+
+    let blocking = {
+        let cancelled = Arc::clone(&cancelled);
+        let slot = Arc::clone(&slot);
+        let work = Mutex::new(Some(work));
+        PyCFunction::new_closure(
+            py,
+            None,
+            None,
+            move |args: &Bound<'_, PyTuple>, _kwargs: Option<&Bound<'_, PyDict>>| {
+                if let Some(work) = work.lock().take() {
+                    *slot.lock() = args.py().detach(|| work(&cancelled));
+                }
+            },
+        )?
+    };
+    let on_worker_done = {
+        let future = future.clone().unbind();
+        PyCFunction::new_closure(
+            py,
+            None,
+            None,
+            move |args: &Bound<'_, PyTuple>, _kwargs: Option<&Bound<'_, PyDict>>| -> PyResult<()> {
+                let py = args.py();
+                let future = future.bind(py);
+                if future.call_method0("done")?.is_truthy()? {
+                    return Ok(());
+                }
+                match slot.lock().take() {
+                    Some(Ok(value)) => {
+                        future.call_method1("set_result", (value.into_py_any(py)?,))?;
+                    }
+                    Some(Err(err)) => {
+                        future.call_method1("set_exception", (err.into_value(py),))?;
+                    }
+                    // The executor failed to run the work at all
+                    None => {
+                        let exception = args.get_item(0)?.call_method0("exception")?;
+                        future.call_method1("set_exception", (exception,))?;
+                    }
+                }
+                Ok(())
+            },
+        )?
+    };
+    let on_cancel = {
+        let waiter = Arc::clone(waiter);
+        PyCFunction::new_closure(
+            py,
+            None,
+            None,
+            move |args: &Bound<'_, PyTuple>, _kwargs: Option<&Bound<'_, PyDict>>| -> PyResult<()> {
+                if args.get_item(0)?.call_method0("cancelled")?.is_truthy()? {
+                    cancelled.store(true, Ordering::SeqCst);
+                    waiter.interrupt();
+                }
+                Ok(())
+            },
+        )?
+    };
+
+    let worker = event_loop.call_method1("run_in_executor", (py.None(), blocking))?;
+    worker.call_method1("add_done_callback", (on_worker_done,))?;
+    future.call_method1("add_done_callback", (on_cancel,))?;
+    Ok(future)
+}
+
+/// First match of `pattern` in the active screen and in lines scrolled off
+/// the primary screen since the previous call (tracked in `scrolled_seen`)
+pub(crate) fn find_on_screen(
+    term: &Terminal,
+    pattern: &regex::Regex,
+    scrolled_seen: &mut usize,
+) -> Option<String> {
+    let primary = term.grid();
+    let scrolled = primary.total_lines_scrolled();
+    let fresh = scrolled
+        .saturating_sub(*scrolled_seen)
+        .min(primary.scrollback_len());
+    *scrolled_seen = scrolled;
+
+    let screen = term.active_grid();
+    let mut text = String::new();
+    let scrollback_rows = (primary.scrollback_len() - fresh..primary.scrollback_len())
+        .filter_map(|i| primary.scrollback_line(i));
+    let screen_rows = (0..screen.rows()).filter_map(|row| screen.row(row));
+    for cells in scrollback_rows.chain(screen_rows) {
+        text.push_str(crate::terminal::cells_to_text(cells).trim_end());
+        text.push('\n');
+    }
+    pattern.find(&text).map(|m| m.as_str().to_string())
+}
+
+#[pymethods]
+impl PyPtyTerminal {
+    /// Wait for output from the child process
+    ///
+    /// Must be awaited from a running asyncio event loop. Output is buffered
+    /// from the first call onwards (up to 1 MiB; older bytes are dropped if
+    /// the caller falls further behind).
+    ///
+    /// Args:
+    ///     timeout: Maximum seconds to wait, or None to wait indefinitely
+    ///
+    /// Returns:
+    ///     Awaitable resolving to the raw bytes received since the previous
+    ///     call, or b"" once the process has exited and all output was read
+    ///
+    /// Raises:
+    ///     TimeoutError: If no output arrives within `timeout`
+    #[pyo3(signature = (timeout=None))]
+    fn read_output<'py>(
+        &self,
+        py: Python<'py>,
+        timeout: Option<f64>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let timeout = timeout_duration(timeout)?;
+        let waiter = self.output_waiter();
+        waiter.set_output_capture(true);
+        let shared = Arc::clone(&waiter);
+        spawn_awaitable(py, &waiter, move |cancelled| {
+            let waited = wait_for(&shared, timeout, cancelled, || {
+                let output = shared.take_output();
+                (!output.is_empty()).then_some(output)
+            });
+            match waited {
+                Waited::Ready(output) => Some(Ok(Resolved::Bytes(output))),
+                Waited::Closed => Some(Ok(Resolved::Bytes(Vec::new()))),
+                Waited::TimedOut => Some(Err(PyTimeoutError::new_err("no output before timeout"))),
+                Waited::Cancelled => None,
+            }
+        })
+    }
+
+    /// Wait until text matching a regular expression appears on screen
+    ///
+    /// Checks the visible screen, plus lines that scroll into scrollback
+    /// while waiting, after every chunk of output. Resolves immediately if
+    /// the screen already matches. Must be awaited from a running asyncio
+    /// event loop.
+    ///
+    /// Args:
+    ///     pattern: Regular expression to search for
+    ///     timeout: Maximum seconds to wait, or None to wait indefinitely
+    ///
+    /// Returns:
+    ///     Awaitable resolving to the matched text
+    ///
+    /// Raises:
+    ///     ValueError: If the pattern is not a valid regular expression
+    ///     TimeoutError: If no match appears within `timeout`
+    ///     EOFError: If the process exits without the pattern appearing
+    #[pyo3(signature = (pattern, timeout=None))]
+    fn wait_for_text<'py>(
+        &self,
+        py: Python<'py>,
+        pattern: &str,
+        timeout: Option<f64>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let pattern = regex::Regex::new(pattern)
+            .map_err(|e| PyValueError::new_err(format!("Invalid regex pattern: {e}")))?;
+        let timeout = timeout_duration(timeout)?;
+        let terminal = self.get_terminal_arc();
+        let waiter = self.output_waiter();
+        let shared = Arc::clone(&waiter);
+        let mut scrolled_seen = terminal.read().grid().total_lines_scrolled();
+        spawn_awaitable(py, &waiter, move |cancelled| {
+            let waited = wait_for(&shared, timeout, cancelled, || {
+                find_on_screen(&terminal.read(), &pattern, &mut scrolled_seen)
+            });
+            match waited {
+                Waited::Ready(text) => Some(Ok(Resolved::Text(text))),
+                Waited::Closed => Some(Err(PyEOFError::new_err(
+                    "process exited before the pattern appeared",
+                ))),
+                Waited::TimedOut => Some(Err(PyTimeoutError::new_err(
+                    "pattern did not appear before timeout",
+                ))),
+                Waited::Cancelled => None,
+            }
+        })
+    }
+
+    /// Awaitable variant of `expect()`
+    ///
+    /// Args:
+    ///     patterns: Regex string or list of regex strings
+    ///     timeout: Maximum seconds to wait, or None to wait indefinitely
+    ///
+    /// Returns:
+    ///     Awaitable resolving to an ExpectMatch
+    ///
+    /// Raises:
+    ///     ValueError: If a pattern is invalid
+    ///     TimeoutError: If nothing matches within `timeout`
+    ///     EOFError: If the process exits without a match
+    #[pyo3(signature = (patterns, timeout=None))]
+    fn expect_async<'py>(
+        &self,
+        py: Python<'py>,
+        patterns: &Bound<'py, PyAny>,
+        timeout: Option<f64>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let patterns = parse_expect_patterns(patterns)?;
+        let timeout = timeout_duration(timeout)?;
+        let terminal = self.get_terminal_arc();
+        {
+            let mut term = terminal.write();
+            if !term.expect_buffer_enabled() {
+                term.set_expect_buffer(Some(DEFAULT_EXPECT_BUFFER_BYTES));
+            }
+        }
+        let waiter = self.output_waiter();
+        let shared = Arc::clone(&waiter);
+        spawn_awaitable(py, &waiter, move |cancelled| {
+            let waited = wait_for(&shared, timeout, cancelled, || {
+                terminal.write().expect_match(&patterns)
+            });
+            let unconsumed = || {
+                let term = terminal.read();
+                term.expect_buffer().unwrap_or_default().to_string()
+            };
+            match waited {
+                Waited::Ready(found) => Some(Ok(Resolved::Expect(found))),
+                Waited::Closed => Some(Err(ExpectError::Eof(unconsumed()).into())),
+                Waited::TimedOut => Some(Err(ExpectError::Timeout(unconsumed()).into())),
+                Waited::Cancelled => None,
+            }
+        })
+    }
+
+    /// Iterate terminal events asynchronously
+    ///
+    /// Returns:
+    ///     Async iterator yielding event dictionaries (same shape as
+    ///     `Terminal.poll_events()`) until the process exits. Events are
+    ///     drained from the terminal's event queue, so other consumers of
+    ///     that queue will not see them.
+    ///
+    /// Example:
+    ///     >>> async for event in pty.events():
+    ///     ...     if event["type"] == "bell":
+    ///     ...         print("bell")
+    fn events(&self) -> PyPtyEventIterator {
+        PyPtyEventIterator {
+            terminal: self.get_terminal_arc(),
+            waiter: self.output_waiter(),
+            queue: Arc::new(Mutex::new(VecDeque::new())),
+        }
+    }
+}
+
+/// Async iterator over the terminal events of a `PtyTerminal`
+#[pyclass(name = "PtyEventIterator")]
+pub struct PyPtyEventIterator {
+    terminal: Arc<RwLock<Terminal>>,
+    waiter: Arc<OutputWaiter>,
+    queue: Arc<Mutex<VecDeque<HashMap<String, String>>>>,
+}
+
+#[pymethods]
+impl PyPtyEventIterator {
+    fn __aiter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __anext__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let terminal = Arc::clone(&self.terminal);
+        let waiter = Arc::clone(&self.waiter);
+        let queue = Arc::clone(&self.queue);
+        spawn_awaitable(py, &self.waiter, move |cancelled| {
+            let waited = wait_for(&waiter, None, cancelled, || {
+                let mut queue = queue.lock();
+                if queue.is_empty() {
+                    let events = terminal.write().poll_events();
+                    queue.extend(events.iter().map(event_to_dict));
+                }
+                queue.pop_front()
+            });
+            match waited {
+                Waited::Ready(event) => Some(Ok(Resolved::Event(event))),
+                Waited::Closed | Waited::TimedOut => Some(Err(PyStopAsyncIteration::new_err(()))),
+                Waited::Cancelled => None,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_on_screen_matches_visible_text() {
+        let mut term = Terminal::new(40, 5);
+        term.process(b"build ok\r\nready> ");
+        let pattern = regex::Regex::new(r"ready>").unwrap();
+        let mut seen = 0;
+        assert_eq!(
+            find_on_screen(&term, &pattern, &mut seen).as_deref(),
+            Some("ready>")
+        );
+    }
+
+    #[test]
+    fn test_find_on_screen_sees_lines_scrolled_since_last_check() {
+        let mut term = Terminal::new(40, 3);
+        let pattern = regex::Regex::new(r"DONE \d+").unwrap();
+        let mut seen = term.grid().total_lines_scrolled();
+        assert!(find_on_screen(&term, &pattern, &mut seen).is_none());
+
+        term.process(b"DONE 42\r\n");
+        for i in 0..10 {
+            term.process(format!("filler {i}\r\n").as_bytes());
+        }
+        assert_eq!(
+            find_on_screen(&term, &pattern, &mut seen).as_deref(),
+            Some("DONE 42")
+        );
+        // Already reported scrollback is not searched again
+        assert!(find_on_screen(&term, &pattern, &mut seen).is_none());
+    }
+
+    #[test]
+    fn test_timeout_duration_rejects_negative() {
+        assert!(timeout_duration(Some(-1.0)).is_err());
+        assert_eq!(
+            timeout_duration(Some(0.5)).unwrap(),
+            Some(Duration::from_millis(500))
+        );
+        assert_eq!(timeout_duration(None).unwrap(), None);
+    }
+}
@@ -26,6 +26,8 @@ pub struct PyScreenshotConfig {
     pub(crate) format: String,
     /// Path to a .ttf/.otf font; None uses the embedded JetBrains Mono.
     pub(crate) font_path: Option<String>,
+    /// Fallback font paths tried in order for glyphs the main font lacks.
+    pub(crate) fallback_fonts: Vec<String>,
     /// Font size in pixels.
     pub(crate) font_size: f32,
     /// Include the scrollback buffer.
@@ -54,6 +56,30 @@ pub struct PyScreenshotConfig {
     pub(crate) faint_text_alpha: f32,
     /// Minimum contrast adjustment (0.0-1.0).
     pub(crate) minimum_contrast: f64,
+    /// Render programming ligatures by shaping each row (slower).
+    pub(crate) enable_ligatures: bool,
+    /// Draw box-drawing and block element characters as vector shapes.
+    pub(crate) builtin_box_drawing: bool,
+    /// Capture the visible phase of a blinking cursor (False = omit it).
+    pub(crate) cursor_blink_visible: bool,
+    /// Draw the cursor in the color of the text under it (None = terminal's setting).
+    pub(crate) smart_cursor_color: Option<bool>,
+    /// Tint the cursor line (None = terminal's setting).
+    pub(crate) cursor_guide: Option<bool>,
+    /// Cursor guide color (None = terminal theme's cursor guide color).
+    pub(crate) cursor_guide_color: Option<(u8, u8, u8)>,
+    /// Paint the current selection with the theme's selection colors.
+    pub(crate) render_selection: bool,
+    /// Paint the last search's matches with the theme's match color.
+    pub(crate) render_search_matches: bool,
+    /// Composite the badge in the top-right corner.
+    pub(crate) render_badge: bool,
+    /// Badge text (None = terminal's evaluated badge).
+    pub(crate) badge_text: Option<String>,
+    /// Badge color (None = terminal theme's badge color).
+    pub(crate) badge_color: Option<(u8, u8, u8)>,
+    /// Badge opacity (0.0-1.0).
+    pub(crate) badge_alpha: f32,
 }
 
 #[pymethods]
@@ -79,7 +105,20 @@ impl PyScreenshotConfig {
         bold_brightening = false,
         background_color = None,
         faint_text_alpha = 0.5,
-        minimum_contrast = 0.5
+        minimum_contrast = 0.5,
+        fallback_fonts = Vec::new(),
+        enable_ligatures = false,
+        builtin_box_drawing = true,
+        cursor_blink_visible = true,
+        smart_cursor_color = None,
+        cursor_guide = None,
+        cursor_guide_color = None,
+        render_selection = false,
+        render_search_matches = false,
+        render_badge = false,
+        badge_text = None,
+        badge_color = None,
+        badge_alpha = 0.5
     ))]
     #[allow(clippy::too_many_arguments)]
     fn new(
@@ -99,10 +138,24 @@ impl PyScreenshotConfig {
         background_color: Option<(u8, u8, u8)>,
         faint_text_alpha: f32,
         minimum_contrast: f64,
+        fallback_fonts: Vec<String>,
+        enable_ligatures: bool,
+        builtin_box_drawing: bool,
+        cursor_blink_visible: bool,
+        smart_cursor_color: Option<bool>,
+        cursor_guide: Option<bool>,
+        cursor_guide_color: Option<(u8, u8, u8)>,
+        render_selection: bool,
+        render_search_matches: bool,
+        render_badge: bool,
+        badge_text: Option<String>,
+        badge_color: Option<(u8, u8, u8)>,
+        badge_alpha: f32,
     ) -> Self {
         Self {
             format: format.to_string(),
             font_path,
+            fallback_fonts,
             font_size,
             include_scrollback,
             padding,
@@ -117,6 +170,18 @@ impl PyScreenshotConfig {
             background_color,
             faint_text_alpha,
             minimum_contrast,
+            enable_ligatures,
+            builtin_box_drawing,
+            cursor_blink_visible,
+            smart_cursor_color,
+            cursor_guide,
+            cursor_guide_color,
+            render_selection,
+            render_search_matches,
+            render_badge,
+            badge_text,
+            badge_color,
+            badge_alpha,
         }
     }
 }
@@ -142,6 +207,11 @@ impl PyScreenshotConfig {
         Ok(ScreenshotConfig {
             format: img_format,
             font_path: self.font_path.clone().map(std::path::PathBuf::from),
+            fallback_fonts: self
+                .fallback_fonts
+                .iter()
+                .map(std::path::PathBuf::from)
+                .collect(),
             font_size: self.font_size,
             include_scrollback: self.include_scrollback,
             padding_px: self.padding,
@@ -156,6 +226,18 @@ impl PyScreenshotConfig {
             background_color: self.background_color,
             minimum_contrast: self.minimum_contrast.clamp(0.0, 1.0),
             faint_text_alpha: self.faint_text_alpha.clamp(0.0, 1.0),
+            enable_ligatures: self.enable_ligatures,
+            builtin_box_drawing: self.builtin_box_drawing,
+            cursor_blink_visible: self.cursor_blink_visible,
+            smart_cursor_color: self.smart_cursor_color,
+            cursor_guide: self.cursor_guide,
+            cursor_guide_color: self.cursor_guide_color,
+            render_selection: self.render_selection,
+            render_search_matches: self.render_search_matches,
+            render_badge: self.render_badge,
+            badge_text: self.badge_text.clone(),
+            badge_color: self.badge_color,
+            badge_alpha: self.badge_alpha.clamp(0.0, 1.0),
             ..Default::default()
         })
     }
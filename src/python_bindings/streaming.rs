@@ -1686,6 +1686,71 @@ pub fn decode_server_message<'py>(
             dict.set_item("type", "semantic_snapshot")?;
             dict.set_item("snapshot_json", snapshot_json)?;
         }
+        ServerMessage::ScreenSnapshot {
+            cols,
+            rows,
+            lines,
+            cursor_col,
+            cursor_row,
+            cursor_visible,
+            scrollback_len,
+            zones,
+            alt_screen,
+        } => {
+            use crate::streaming::protocol::CellColor;
+            // Palette colors become an int, RGB colors an (r, g, b) tuple
+            let color = |c: Option<CellColor>| -> PyResult<Option<Py<PyAny>>> {
+                Ok(match c {
+                    Some(CellColor::Indexed(idx)) => {
+                        Some(idx.into_pyobject(py)?.into_any().unbind())
+                    }
+                    Some(CellColor::Rgb(r, g, b)) => {
+                        Some((r, g, b).into_pyobject(py)?.into_any().unbind())
+                    }
+                    None => None,
+                })
+            };
+            dict.set_item("type", "screen_snapshot")?;
+            dict.set_item("cols", cols)?;
+            dict.set_item("rows", rows)?;
+            let line_list = pyo3::types::PyList::empty(py);
+            for line in lines {
+                let run_list = pyo3::types::PyList::empty(py);
+                for run in line.runs {
+                    let rd = PyDict::new(py);
+                    rd.set_item("col", run.col)?;
+                    rd.set_item("width", run.width)?;
+                    rd.set_item("text", run.text)?;
+                    rd.set_item("fg", color(run.fg)?)?;
+                    rd.set_item("bg", color(run.bg)?)?;
+                    rd.set_item("flags", run.flags)?;
+                    rd.set_item("hyperlink", run.hyperlink)?;
+                    run_list.append(rd)?;
+                }
+                let ld = PyDict::new(py);
+                ld.set_item("runs", run_list)?;
+                ld.set_item("wrapped", line.wrapped)?;
+                line_list.append(ld)?;
+            }
+            dict.set_item("lines", line_list)?;
+            dict.set_item("cursor_col", cursor_col)?;
+            dict.set_item("cursor_row", cursor_row)?;
+            dict.set_item("cursor_visible", cursor_visible)?;
+            dict.set_item("scrollback_len", scrollback_len)?;
+            let zone_list = pyo3::types::PyList::empty(py);
+            for zone in zones {
+                let zd = PyDict::new(py);
+                zd.set_item("zone_id", zone.zone_id)?;
+                zd.set_item("zone_type", zone.zone_type)?;
+                zd.set_item("abs_row_start", zone.abs_row_start)?;
+                zd.set_item("abs_row_end", zone.abs_row_end)?;
+                zd.set_item("command", zone.command)?;
+                zd.set_item("exit_code", zone.exit_code)?;
+                zone_list.append(zd)?;
+            }
+            dict.set_item("zones", zone_list)?;
+            dict.set_item("alt_screen", alt_screen)?;
+        }
         ServerMessage::FileTransferStarted {
             id,
             direction,
@@ -1838,6 +1903,7 @@ pub fn encode_client_message<'py>(
             let max_commands = get_u32("max_commands");
             ClientMessage::snapshot_request(scope, max_commands)
         }
+        "screen_snapshot_request" => ClientMessage::ScreenSnapshotRequest,
         "mouse" => {
             let get_u8 = |key: &str| -> Option<u8> {
                 kwargs
@@ -1877,7 +1943,7 @@ pub fn encode_client_message<'py>(
         }
         _ => {
             return Err(PyRuntimeError::new_err(format!(
-                "Unknown message type: {}. Valid types: input, resize, ping, refresh, subscribe, snapshot_request, mouse, focus_change, paste, selection_request, clipboard_request",
+                "Unknown message type: {}. Valid types: input, resize, ping, refresh, subscribe, snapshot_request, screen_snapshot_request, mouse, focus_change, paste, selection_request, clipboard_request",
                 message_type
             )));
         }
@@ -2022,6 +2088,9 @@ pub fn decode_client_message<'py>(
             dict.set_item("scope", scope)?;
             dict.set_item("max_commands", max_commands)?;
         }
+        ClientMessage::ScreenSnapshotRequest => {
+            dict.set_item("type", "screen_snapshot_request")?;
+        }
     }
 
     Ok(dict)
@@ -0,0 +1,117 @@
+//! Cell annotation API methods for `PyTerminal`.
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use super::PyTerminal;
+use crate::annotation::{Annotation, AnnotationRange};
+
+/// Build the dictionary returned for an annotation
+fn annotation_dict<'py>(
+    py: Python<'py>,
+    terminal: &crate::terminal::Terminal,
+    annotation: &Annotation,
+) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("id", annotation.id)?;
+    dict.set_item("abs_row", annotation.range.abs_row)?;
+    dict.set_item("col", annotation.range.col)?;
+    dict.set_item("length", annotation.range.length)?;
+    dict.set_item("text", &annotation.text)?;
+    dict.set_item("tag", annotation.tag.as_deref())?;
+    dict.set_item("cell_text", terminal.get_annotated_text(annotation))?;
+    dict.set_item("timestamp", annotation.timestamp)?;
+    Ok(dict)
+}
+
+#[pymethods]
+impl PyTerminal {
+    // === Annotation Methods ===
+
+    /// Attach an annotation to a run of cells on the primary screen
+    ///
+    /// Annotations follow their text into scrollback and are dropped when it
+    /// is evicted.
+    ///
+    /// Args:
+    ///     abs_row: Absolute row number (scrollback_len + visible_row)
+    ///     col: First annotated column
+    ///     length: Number of annotated cells (clipped to the terminal width)
+    ///     text: Annotation text
+    ///     tag: Optional category, e.g. "lint" or "note"
+    ///
+    /// Returns:
+    ///     Annotation ID, or None if the range is empty or outside the buffer
+    ///
+    /// Example:
+    ///     ```python
+    ///     term.add_annotation(term.scrollback_len() + 2, 0, 5, "unused import", tag="lint")
+    ///     ```
+    #[pyo3(signature = (abs_row, col, length, text, tag=None))]
+    fn add_annotation(
+        &mut self,
+        abs_row: usize,
+        col: usize,
+        length: usize,
+        text: String,
+        tag: Option<String>,
+    ) -> PyResult<Option<usize>> {
+        Ok(self
+            .inner
+            .add_annotation(AnnotationRange::new(abs_row, col, length), text, tag))
+    }
+
+    /// Remove an annotation by ID
+    ///
+    /// Returns:
+    ///     True if the annotation was removed, False if not found
+    fn remove_annotation(&mut self, id: usize) -> PyResult<bool> {
+        Ok(self.inner.remove_annotation(id))
+    }
+
+    /// Remove all annotations
+    fn clear_annotations(&mut self) -> PyResult<()> {
+        self.inner.clear_annotations();
+        Ok(())
+    }
+
+    /// Get all annotations
+    ///
+    /// Returns:
+    ///     List of annotation dictionaries, each containing:
+    ///     - id: int - Annotation identifier
+    ///     - abs_row: int - Absolute row of the annotated cells
+    ///     - col: int - First annotated column
+    ///     - length: int - Number of annotated cells
+    ///     - text: str - Annotation text
+    ///     - tag: str | None - Category given when the annotation was added
+    ///     - cell_text: str - Text of the annotated cells
+    ///     - timestamp: int - Unix milliseconds when the annotation was added
+    fn get_annotations(&self) -> PyResult<Vec<Py<PyDict>>> {
+        Python::attach(|py| {
+            self.inner
+                .get_annotations()
+                .iter()
+                .map(|a| Ok(annotation_dict(py, &self.inner, a)?.unbind()))
+                .collect()
+        })
+    }
+
+    /// Get the annotations covering a cell
+    ///
+    /// Args:
+    ///     abs_row: Absolute row number (scrollback_len + visible_row)
+    ///     col: Column
+    ///
+    /// Returns:
+    ///     List of annotation dictionaries (see get_annotations())
+    fn get_annotations_at(&self, abs_row: usize, col: usize) -> PyResult<Vec<Py<PyDict>>> {
+        Python::attach(|py| {
+            self.inner
+                .get_annotations_at(abs_row, col)
+                .into_iter()
+                .map(|a| Ok(annotation_dict(py, &self.inner, a)?.unbind()))
+                .collect()
+        })
+    }
+}
@@ -56,12 +56,15 @@ impl PyTerminal {
     /// Get all semantic zones in the terminal buffer
     ///
     /// Returns a list of zone dictionaries, each containing:
+    /// - id: int - Zone identifier (for set_zone_folded)
     /// - zone_type: str - "prompt", "command", or "output"
     /// - abs_row_start: int - Absolute row where zone starts
     /// - abs_row_end: int - Absolute row where zone ends (inclusive)
     /// - command: str | None - Command text (for command/output zones)
     /// - exit_code: int | None - Exit code (for output zones after command finishes)
     /// - timestamp: int | None - Unix milliseconds when zone was created
+    /// - heuristic: bool - True if inferred by prompt detection instead of OSC 133
+    /// - folded: bool - True if the output zone is folded
     ///
     /// Returns:
     ///     List of zone dictionaries sorted by row position
@@ -80,12 +83,15 @@ impl PyTerminal {
             let mut result = Vec::with_capacity(zones.len());
             for zone in zones {
                 let dict = PyDict::new(py);
+                dict.set_item("id", zone.id)?;
                 dict.set_item("zone_type", zone.zone_type.to_string())?;
                 dict.set_item("abs_row_start", zone.abs_row_start)?;
                 dict.set_item("abs_row_end", zone.abs_row_end)?;
                 dict.set_item("command", zone.command.as_deref())?;
                 dict.set_item("exit_code", zone.exit_code)?;
                 dict.set_item("timestamp", zone.timestamp)?;
+                dict.set_item("heuristic", zone.heuristic)?;
+                dict.set_item("folded", zone.folded)?;
                 result.push(dict.into());
             }
             Ok(result)
@@ -112,18 +118,113 @@ impl PyTerminal {
         match self.inner.get_zone_at(abs_row) {
             Some(zone) => Python::attach(|py| {
                 let dict = PyDict::new(py);
+                dict.set_item("id", zone.id)?;
                 dict.set_item("zone_type", zone.zone_type.to_string())?;
                 dict.set_item("abs_row_start", zone.abs_row_start)?;
                 dict.set_item("abs_row_end", zone.abs_row_end)?;
                 dict.set_item("command", zone.command.as_deref())?;
                 dict.set_item("exit_code", zone.exit_code)?;
                 dict.set_item("timestamp", zone.timestamp)?;
+                dict.set_item("heuristic", zone.heuristic)?;
+                dict.set_item("folded", zone.folded)?;
                 Ok(Some(dict.into()))
             }),
             None => Ok(None),
         }
     }
 
+    /// Fold (collapse) the output of a command
+    ///
+    /// Commands are counted by their output zones, most recent first. Folds
+    /// do not change the buffer; they are kept with the zone as it scrolls
+    /// and are honored by export_text_folded() and by semantic snapshots
+    /// taken with respect_folds=True.
+    ///
+    /// Args:
+    ///     idx: Command index (0 = most recent)
+    ///
+    /// Returns:
+    ///     False if there is no such command in the scrollback
+    fn fold_command(&mut self, idx: usize) -> PyResult<bool> {
+        Ok(self.inner.fold_command(idx))
+    }
+
+    /// Unfold (expand) the output of a command
+    ///
+    /// Args:
+    ///     idx: Command index (0 = most recent)
+    ///
+    /// Returns:
+    ///     False if there is no such command in the scrollback
+    fn unfold_command(&mut self, idx: usize) -> PyResult<bool> {
+        Ok(self.inner.unfold_command(idx))
+    }
+
+    /// Check if a command's output is folded
+    ///
+    /// Args:
+    ///     idx: Command index (0 = most recent)
+    fn is_command_folded(&self, idx: usize) -> PyResult<bool> {
+        Ok(self.inner.is_command_folded(idx))
+    }
+
+    /// Fold or unfold an output zone by ID
+    ///
+    /// Args:
+    ///     zone_id: The "id" of an output zone from get_zones()
+    ///     folded: Whether the zone should be folded
+    ///
+    /// Returns:
+    ///     False if no output zone has that ID
+    fn set_zone_folded(&mut self, zone_id: usize, folded: bool) -> PyResult<bool> {
+        Ok(self.inner.set_zone_folded(zone_id, folded))
+    }
+
+    /// Unfold every command output
+    fn unfold_all(&mut self) -> PyResult<()> {
+        self.inner.unfold_all();
+        Ok(())
+    }
+
+    /// Get the folded command output ranges
+    ///
+    /// Returns:
+    ///     List of dicts in row order, each containing:
+    ///     - zone_id: int - ID of the folded output zone
+    ///     - abs_row_start: int - First folded absolute row
+    ///     - abs_row_end: int - Last folded absolute row (inclusive)
+    ///     - command: str | None - Command that produced the output
+    ///     - line_count: int - Number of folded rows
+    ///     - summary: str - Line shown in place of the rows by folding-aware exports
+    fn folded_ranges(&self) -> PyResult<Vec<pyo3::Py<pyo3::types::PyDict>>> {
+        use pyo3::types::PyDict;
+
+        let ranges = self.inner.folded_ranges();
+        Python::attach(|py| {
+            let mut result = Vec::with_capacity(ranges.len());
+            for range in ranges {
+                let dict = PyDict::new(py);
+                dict.set_item("zone_id", range.zone_id)?;
+                dict.set_item("abs_row_start", range.abs_row_start)?;
+                dict.set_item("abs_row_end", range.abs_row_end)?;
+                dict.set_item("command", range.command.as_deref())?;
+                dict.set_item("line_count", range.line_count())?;
+                dict.set_item("summary", range.summary())?;
+                result.push(dict.into());
+            }
+            Ok(result)
+        })
+    }
+
+    /// Export scrollback and screen as plain text with folded command
+    /// output replaced by summary lines
+    ///
+    /// Returns:
+    ///     Plain text of the primary screen buffer
+    fn export_text_folded(&self) -> PyResult<String> {
+        Ok(self.inner.export_text_folded())
+    }
+
     /// Get the text content of the zone containing the given absolute row
     ///
     /// Extracts all text from the zone's rows, handling line wrapping and
@@ -145,6 +246,75 @@ impl PyTerminal {
         Ok(self.inner.get_zone_text(abs_row))
     }
 
+    /// Export a single zone
+    ///
+    /// Trailing blank rows are left out. HTML output is a standalone
+    /// <pre> fragment suitable for the clipboard.
+    ///
+    /// Args:
+    ///     zone_id: The "id" of a zone from get_zones()
+    ///     format: Export format: "plain", "html", or "ansi"
+    ///
+    /// Returns:
+    ///     Exported zone, or None if no zone has that ID
+    ///
+    /// Raises:
+    ///     ValueError: If format is not recognized
+    #[pyo3(signature = (zone_id, format="plain"))]
+    fn export_zone(&self, zone_id: usize, format: &str) -> PyResult<Option<String>> {
+        let format = crate::python_bindings::common::parse_export_format(format)?;
+        Ok(self.inner.export_zone(zone_id, format))
+    }
+
+    /// Export a whole command block: prompt, command line and output
+    ///
+    /// For just the output, use export_zone() with the command's output zone.
+    ///
+    /// Args:
+    ///     idx: Command index (0 = most recent, counted like fold_command())
+    ///     format: Export format: "plain", "html", or "ansi"
+    ///
+    /// Returns:
+    ///     Exported command, or None if there is no such command in the scrollback
+    ///
+    /// Raises:
+    ///     ValueError: If format is not recognized
+    #[pyo3(signature = (idx, format="plain"))]
+    fn export_command(&self, idx: usize, format: &str) -> PyResult<Option<String>> {
+        let format = crate::python_bindings::common::parse_export_format(format)?;
+        Ok(self.inner.export_command(idx, format))
+    }
+
+    /// Export the session as Markdown: one section per command with the
+    /// command line as heading, exit code and directory, and the output in a
+    /// fenced code block
+    ///
+    /// Args:
+    ///     scope: "visible", "recent", or "full" (default: "visible")
+    ///     max_commands: Number of commands for "recent" scope (default: 10)
+    ///
+    /// Returns:
+    ///     Markdown document
+    ///
+    /// Raises:
+    ///     ValueError: If scope is not recognized
+    #[pyo3(signature = (scope="visible", max_commands=10))]
+    fn export_markdown(&self, scope: &str, max_commands: usize) -> PyResult<String> {
+        use crate::terminal::semantic_snapshot::SnapshotScope;
+
+        let snapshot_scope = match scope {
+            "recent" => SnapshotScope::Recent(max_commands),
+            "full" => SnapshotScope::Full,
+            "visible" => SnapshotScope::Visible,
+            _ => {
+                return Err(PyValueError::new_err(
+                    "scope must be 'visible', 'recent', or 'full'",
+                ))
+            }
+        };
+        Ok(self.inner.export_markdown(snapshot_scope))
+    }
+
     /// Get a semantic snapshot of the terminal state as a Python dict.
     ///
     /// Returns a structured representation of terminal state including
@@ -154,6 +324,8 @@ impl PyTerminal {
     /// Args:
     ///     scope: Snapshot scope - "visible", "recent", or "full" (default: "visible")
     ///     max_commands: For "recent" scope, max number of commands to include (default: 10)
+    ///     respect_folds: Replace folded command output in visible_text and
+    ///         scrollback_text with a summary line (default: False)
     ///
     /// Returns:
     ///     dict with keys: timestamp, cols, rows, title, cursor_col, cursor_row,
@@ -167,11 +339,12 @@ impl PyTerminal {
     ///     >>> snap = term.get_semantic_snapshot(scope="visible")
     ///     >>> snap["cols"]
     ///     80
-    #[pyo3(signature = (scope="visible", max_commands=10))]
+    #[pyo3(signature = (scope="visible", max_commands=10, respect_folds=false))]
     fn get_semantic_snapshot(
         &self,
         scope: &str,
         max_commands: usize,
+        respect_folds: bool,
     ) -> PyResult<pyo3::Py<pyo3::types::PyDict>> {
         use crate::terminal::semantic_snapshot::SnapshotScope;
 
@@ -186,7 +359,11 @@ impl PyTerminal {
             }
         };
 
-        let snapshot = self.inner.get_semantic_snapshot(snapshot_scope);
+        let snapshot = if respect_folds {
+            self.inner.get_semantic_snapshot_folded(snapshot_scope)
+        } else {
+            self.inner.get_semantic_snapshot(snapshot_scope)
+        };
         let json = serde_json::to_string(&snapshot)
             .map_err(|e| PyRuntimeError::new_err(format!("Serialization failed: {}", e)))?;
 
@@ -210,6 +387,8 @@ impl PyTerminal {
     /// Args:
     ///     scope: Snapshot scope - "visible", "recent", or "full" (default: "visible")
     ///     max_commands: For "recent" scope, max number of commands to include (default: 10)
+    ///     respect_folds: Replace folded command output in visible_text and
+    ///         scrollback_text with a summary line (default: False)
     ///
     /// Returns:
     ///     JSON string containing the semantic snapshot
@@ -217,8 +396,13 @@ impl PyTerminal {
     /// Example:
     ///     >>> term = Terminal(80, 24)
     ///     >>> json_str = term.get_semantic_snapshot_json(scope="full")
-    #[pyo3(signature = (scope="visible", max_commands=10))]
-    fn get_semantic_snapshot_json(&self, scope: &str, max_commands: usize) -> PyResult<String> {
+    #[pyo3(signature = (scope="visible", max_commands=10, respect_folds=false))]
+    fn get_semantic_snapshot_json(
+        &self,
+        scope: &str,
+        max_commands: usize,
+        respect_folds: bool,
+    ) -> PyResult<String> {
         use crate::terminal::semantic_snapshot::SnapshotScope;
 
         let snapshot_scope = match scope {
@@ -232,6 +416,10 @@ impl PyTerminal {
             }
         };
 
+        if respect_folds {
+            let snapshot = self.inner.get_semantic_snapshot_folded(snapshot_scope);
+            return Ok(serde_json::to_string(&snapshot).unwrap_or_default());
+        }
         Ok(self.inner.get_semantic_snapshot_json(snapshot_scope))
     }
 
@@ -55,4 +55,54 @@ impl PyTerminal {
         self.inner.clear_bookmarks();
         Ok(())
     }
+
+    // === Mark Methods ===
+
+    /// Add a named mark at an absolute row
+    ///
+    /// Marks follow their line as output scrolls. A mark is also recorded
+    /// automatically at every shell prompt (OSC 133;A).
+    ///
+    /// Args:
+    ///     abs_row: Absolute row (scrollback length + screen row)
+    ///     name: Optional name for the mark
+    ///
+    /// Returns:
+    ///     Mark ID
+    #[pyo3(signature = (abs_row, name=None))]
+    fn add_mark(&mut self, abs_row: usize, name: Option<String>) -> PyResult<usize> {
+        Ok(self.inner.add_mark(abs_row, name))
+    }
+
+    /// Get all marks, ordered by row
+    ///
+    /// Returns:
+    ///     List of Mark objects
+    fn get_marks(&self) -> PyResult<Vec<crate::python_bindings::types::PyMark>> {
+        Ok(self.inner.marks().iter().map(Into::into).collect())
+    }
+
+    /// Absolute row of the first mark below `from_row`, or None
+    fn next_mark(&self, from_row: usize) -> PyResult<Option<usize>> {
+        Ok(self.inner.next_mark(from_row))
+    }
+
+    /// Absolute row of the last mark above `from_row`, or None
+    fn prev_mark(&self, from_row: usize) -> PyResult<Option<usize>> {
+        Ok(self.inner.prev_mark(from_row))
+    }
+
+    /// Remove a mark by ID
+    ///
+    /// Returns:
+    ///     True if the mark was removed, False if not found
+    fn remove_mark(&mut self, id: usize) -> PyResult<bool> {
+        Ok(self.inner.remove_mark(id))
+    }
+
+    /// Remove every mark, including prompt marks
+    fn clear_marks(&mut self) -> PyResult<()> {
+        self.inner.clear_marks();
+        Ok(())
+    }
 }
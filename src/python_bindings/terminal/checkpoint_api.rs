@@ -0,0 +1,135 @@
+//! Screen checkpoint (time-travel view) methods for `PyTerminal`.
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use super::PyTerminal;
+use crate::terminal::{CheckpointConfig, CheckpointInfo, CheckpointView};
+
+fn info_dict<'py>(py: Python<'py>, info: &CheckpointInfo) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("id", info.id)?;
+    dict.set_item("timestamp", info.timestamp)?;
+    dict.set_item("bytes_processed", info.bytes_processed)?;
+    dict.set_item("alt_screen", info.alt_screen)?;
+    Ok(dict)
+}
+
+fn view_dict<'py>(py: Python<'py>, view: &CheckpointView) -> PyResult<Bound<'py, PyDict>> {
+    let dict = info_dict(py, &view.info)?;
+    dict.set_item("cols", view.cols())?;
+    dict.set_item("rows", view.rows())?;
+    dict.set_item("cursor", (view.cursor.col, view.cursor.row))?;
+    dict.set_item("title", &view.title)?;
+    let lines: Vec<String> = (0..view.rows()).filter_map(|row| view.line(row)).collect();
+    dict.set_item("lines", lines)?;
+    Ok(dict)
+}
+
+#[pymethods]
+impl PyTerminal {
+    /// Configure automatic screen checkpoints
+    ///
+    /// While enabled, the visible screen is copied into a bounded ring after
+    /// output once ``interval_ms`` has passed or ``interval_bytes`` bytes have
+    /// been processed since the last checkpoint, whichever comes first.
+    ///
+    /// Args:
+    ///     enabled: Whether to take checkpoints automatically
+    ///     interval_ms: Time trigger in ms, 0 to disable (default 5000)
+    ///     interval_bytes: Output trigger in bytes, 0 to disable (default 65536)
+    ///     max_checkpoints: Checkpoints kept before the oldest is dropped
+    ///         (default 120)
+    #[pyo3(signature = (enabled, interval_ms=None, interval_bytes=None, max_checkpoints=None))]
+    fn set_checkpoint_config(
+        &mut self,
+        enabled: bool,
+        interval_ms: Option<u64>,
+        interval_bytes: Option<usize>,
+        max_checkpoints: Option<usize>,
+    ) -> PyResult<()> {
+        let defaults = CheckpointConfig::default();
+        self.inner.set_checkpoint_config(CheckpointConfig {
+            enabled,
+            interval_ms: interval_ms.unwrap_or(defaults.interval_ms),
+            interval_bytes: interval_bytes.unwrap_or(defaults.interval_bytes),
+            max_checkpoints: max_checkpoints.unwrap_or(defaults.max_checkpoints),
+        });
+        Ok(())
+    }
+
+    /// Get the automatic checkpoint settings
+    ///
+    /// Returns:
+    ///     Dictionary with "enabled", "interval_ms", "interval_bytes" and
+    ///     "max_checkpoints"
+    fn get_checkpoint_config(&self) -> PyResult<Py<PyDict>> {
+        let config = self.inner.checkpoint_config();
+        Python::attach(|py| {
+            let dict = PyDict::new(py);
+            dict.set_item("enabled", config.enabled)?;
+            dict.set_item("interval_ms", config.interval_ms)?;
+            dict.set_item("interval_bytes", config.interval_bytes)?;
+            dict.set_item("max_checkpoints", config.max_checkpoints)?;
+            Ok(dict.unbind())
+        })
+    }
+
+    /// Take a checkpoint now, regardless of the intervals
+    ///
+    /// Returns:
+    ///     The new checkpoint ID
+    fn checkpoint_now(&mut self) -> PyResult<u64> {
+        Ok(self.inner.checkpoint_now())
+    }
+
+    /// Get the stored checkpoints, oldest first
+    ///
+    /// Returns:
+    ///     List of dictionaries with "id", "timestamp" (Unix ms),
+    ///     "bytes_processed" and "alt_screen"
+    fn get_checkpoints(&self) -> PyResult<Vec<Py<PyDict>>> {
+        Python::attach(|py| {
+            self.inner
+                .checkpoints()
+                .iter()
+                .map(|info| Ok(info_dict(py, info)?.unbind()))
+                .collect()
+        })
+    }
+
+    /// Get a read-only view of the screen at a checkpoint
+    ///
+    /// Live terminal state is not changed.
+    ///
+    /// Args:
+    ///     checkpoint_id: ID from get_checkpoints() or checkpoint_at()
+    ///
+    /// Returns:
+    ///     Dictionary with the checkpoint fields plus "cols", "rows",
+    ///     "cursor" (col, row), "title" and "lines" (one string per row),
+    ///     or None if the checkpoint is no longer stored
+    fn rewind_view(&self, checkpoint_id: u64) -> PyResult<Option<Py<PyDict>>> {
+        let Some(view) = self.inner.rewind_view(checkpoint_id) else {
+            return Ok(None);
+        };
+        Python::attach(|py| Ok(Some(view_dict(py, &view)?.unbind())))
+    }
+
+    /// Find the newest checkpoint taken at or before a time
+    ///
+    /// Args:
+    ///     timestamp: Unix time in ms
+    ///
+    /// Returns:
+    ///     Checkpoint ID, or None if every stored checkpoint is newer
+    fn checkpoint_at(&self, timestamp: u64) -> PyResult<Option<u64>> {
+        Ok(self.inner.checkpoint_at(timestamp))
+    }
+
+    /// Drop all stored checkpoints
+    fn clear_checkpoints(&mut self) -> PyResult<()> {
+        self.inner.clear_checkpoints();
+        Ok(())
+    }
+}
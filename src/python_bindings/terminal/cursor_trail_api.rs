@@ -0,0 +1,62 @@
+//! Cursor motion history methods for `PyTerminal`.
+
+use pyo3::prelude::*;
+
+use super::PyTerminal;
+use crate::python_bindings::types::PyCursorMotion;
+
+#[pymethods]
+impl PyTerminal {
+    /// Enable or disable cursor motion recording
+    ///
+    /// While enabled, cursor moves are recorded with their cause ("text" for
+    /// printed output, "escape_sequence" for CSI/ESC cursor movement) and a
+    /// ``cursor_moved`` event is emitted for each one.
+    ///
+    /// Args:
+    ///     enabled: Whether to record cursor motions
+    fn set_cursor_trail_enabled(&mut self, enabled: bool) -> PyResult<()> {
+        self.inner.set_cursor_trail_enabled(enabled);
+        Ok(())
+    }
+
+    /// Check if cursor motion recording is enabled
+    fn is_cursor_trail_enabled(&self) -> PyResult<bool> {
+        Ok(self.inner.is_cursor_trail_enabled())
+    }
+
+    /// Get recent cursor motions
+    ///
+    /// Args:
+    ///     count: Maximum number of motions to return (default: all retained)
+    ///
+    /// Returns:
+    ///     List of CursorMotion objects, oldest first
+    #[pyo3(signature = (count=None))]
+    fn cursor_motion_history(&self, count: Option<usize>) -> PyResult<Vec<PyCursorMotion>> {
+        let n = count.unwrap_or_else(|| self.inner.max_cursor_motion_history());
+        Ok(self
+            .inner
+            .cursor_motion_history(n)
+            .iter()
+            .map(PyCursorMotion::from)
+            .collect())
+    }
+
+    /// Clear recorded cursor motions
+    fn clear_cursor_motion_history(&mut self) -> PyResult<()> {
+        self.inner.clear_cursor_motion_history();
+        Ok(())
+    }
+
+    /// Set maximum cursor motion history size
+    fn set_max_cursor_motion_history(&mut self, max: usize) -> PyResult<()> {
+        self.inner.set_max_cursor_motion_history(max);
+        Ok(())
+    }
+
+    /// Get maximum cursor motion history size
+    fn get_max_cursor_motion_history(&self) -> PyResult<usize> {
+        Ok(self.inner.max_cursor_motion_history())
+    }
+}
@@ -0,0 +1,114 @@
+//! Log follower mode methods for `PyTerminal`
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use super::PyTerminal;
+use crate::terminal::{LogSearch, Terminal, DEFAULT_LOG_FOLLOWER_LINES};
+
+/// Character offset of byte offset `byte` in `text`
+fn char_offset(text: &str, byte: usize) -> usize {
+    text[..byte].chars().count()
+}
+
+#[pymethods]
+impl PyTerminal {
+    // === Log Follower Mode ===
+
+    /// Create a terminal in log follower mode (static method)
+    ///
+    /// Output is stored as lines of text instead of a cell grid: SGR styling,
+    /// line feeds, carriage returns, tabs and backspaces are honored and
+    /// cursor addressing is ignored. Suited to parsing colored CI logs.
+    ///
+    /// Args:
+    ///     cols: Width used by the grid-based APIs (default: 80)
+    ///     max_lines: Lines kept before the oldest are evicted (default: 1000000)
+    #[staticmethod]
+    #[pyo3(signature = (cols=80, max_lines=DEFAULT_LOG_FOLLOWER_LINES))]
+    fn log_follower(cols: usize, max_lines: usize) -> PyResult<Self> {
+        if max_lines == 0 {
+            return Err(PyValueError::new_err("max_lines must be greater than 0"));
+        }
+        Ok(Self {
+            inner: Terminal::new_log_follower(cols, max_lines),
+        })
+    }
+
+    /// Check if this terminal is in log follower mode
+    fn is_log_follower(&self) -> PyResult<bool> {
+        Ok(self.inner.is_log_follower())
+    }
+
+    /// Number of the oldest retained log line
+    fn log_first_line(&self) -> PyResult<u64> {
+        Ok(self.inner.log_first_line())
+    }
+
+    /// Number of log lines finished so far, including evicted ones
+    fn log_total_lines(&self) -> PyResult<u64> {
+        Ok(self.inner.log_total_lines())
+    }
+
+    /// Number of retained log lines
+    fn log_line_count(&self) -> PyResult<usize> {
+        Ok(self.inner.log_line_count())
+    }
+
+    /// Text of log line `line`, or None if it was evicted or does not exist
+    fn log_line(&self, line: u64) -> PyResult<Option<String>> {
+        Ok(self.inner.log_line(line).map(|l| l.text().to_string()))
+    }
+
+    /// Text of up to `count` retained log lines starting at line `start`
+    fn log_lines(&self, start: u64, count: usize) -> PyResult<Vec<String>> {
+        Ok(self
+            .inner
+            .log_lines(start, count)
+            .into_iter()
+            .map(|line| line.text().to_string())
+            .collect())
+    }
+
+    /// Text of the log line still being written
+    fn log_partial_line(&self) -> PyResult<String> {
+        Ok(self.inner.log_partial_line().to_string())
+    }
+
+    /// Search finished log lines from line `from_line` onwards
+    ///
+    /// To follow a growing log, pass the previous `log_total_lines()` as
+    /// `from_line` so only new lines are scanned.
+    ///
+    /// Args:
+    ///     query: Text to find
+    ///     case_sensitive: Match case exactly (default False; ASCII folding)
+    ///     from_line: First line number to scan (default 0)
+    ///
+    /// Returns:
+    ///     List of (line, start, end) tuples with character offsets
+    #[pyo3(signature = (query, case_sensitive=false, from_line=0))]
+    fn search_log(
+        &self,
+        query: &str,
+        case_sensitive: bool,
+        from_line: u64,
+    ) -> PyResult<Vec<(u64, usize, usize)>> {
+        let mut search = LogSearch::new(query, case_sensitive).from_line(from_line);
+        Ok(self
+            .inner
+            .search_log(&mut search)
+            .into_iter()
+            .filter_map(|m| {
+                let text = self.inner.log_line(m.line)?.text();
+                Some((m.line, char_offset(text, m.start), char_offset(text, m.end)))
+            })
+            .collect())
+    }
+
+    /// Drop all retained log lines; numbering continues
+    fn clear_log(&mut self) -> PyResult<()> {
+        self.inner.clear_log();
+        Ok(())
+    }
+}
@@ -26,9 +26,28 @@ impl PyTerminal {
             scroll_count: m.scroll_count,
             wrap_count: m.wrap_count,
             escape_sequences: m.escape_sequences,
+            latency: (&m.latency).into(),
         })
     }
 
+    /// Get end-to-end latency and frame pacing statistics
+    ///
+    /// Input is stamped by mark_input_written() (PtyTerminal.write() does this
+    /// itself), output by process(), and rendering by mark_clean().
+    fn get_latency_metrics(&self) -> crate::python_bindings::types::PyLatencyMetrics {
+        (&self.inner.get_latency_metrics()).into()
+    }
+
+    /// Note that input bytes were just written to the PTY
+    fn mark_input_written(&mut self) {
+        self.inner.mark_input_written();
+    }
+
+    /// Set how many samples each latency stage keeps (default 1024)
+    fn set_max_latency_samples(&mut self, max: usize) {
+        self.inner.set_max_latency_samples(max);
+    }
+
     /// Reset performance metrics
     fn reset_performance_metrics(&mut self) -> PyResult<()> {
         self.inner.reset_performance_metrics();
@@ -216,6 +235,9 @@ impl PyTerminal {
 
     /// Run compliance tests for a specific level
     ///
+    /// Runs the bundled esctest/vttest-derived vectors at or below ``level``,
+    /// each on a fresh 80x24 terminal.
+    ///
     /// Args:
     ///     level: Compliance level to test ("vt52", "vt100", "vt220", "vt320", "vt420", "vt520", "xterm")
     ///
@@ -225,20 +247,7 @@ impl PyTerminal {
         &mut self,
         level: &str,
     ) -> PyResult<crate::python_bindings::types::PyComplianceReport> {
-        use crate::terminal::ComplianceLevel;
-
-        let rust_level = match level.to_lowercase().as_str() {
-            "vt52" => ComplianceLevel::VT52,
-            "vt100" => ComplianceLevel::VT100,
-            "vt220" => ComplianceLevel::VT220,
-            "vt320" => ComplianceLevel::VT320,
-            "vt420" => ComplianceLevel::VT420,
-            "vt520" => ComplianceLevel::VT520,
-            "xterm" => ComplianceLevel::XTerm,
-            _ => return Err(PyValueError::new_err("Invalid compliance level")),
-        };
-
-        let report = self.inner.test_compliance(rust_level);
+        let report = self.inner.test_compliance(parse_compliance_level(level)?);
         Ok(crate::python_bindings::types::PyComplianceReport::from(
             &report,
         ))
@@ -255,31 +264,25 @@ impl PyTerminal {
     fn format_compliance_report(
         report: &crate::python_bindings::types::PyComplianceReport,
     ) -> PyResult<String> {
-        use crate::terminal::{ComplianceLevel, ComplianceReport, ComplianceTest, Terminal};
-
-        let rust_level = match report.level.as_str() {
-            "vt52" => ComplianceLevel::VT52,
-            "vt100" => ComplianceLevel::VT100,
-            "vt220" => ComplianceLevel::VT220,
-            "vt320" => ComplianceLevel::VT320,
-            "vt420" => ComplianceLevel::VT420,
-            "vt520" => ComplianceLevel::VT520,
-            "xterm" => ComplianceLevel::XTerm,
-            _ => return Err(PyValueError::new_err("Invalid compliance level")),
-        };
+        use crate::terminal::{ComplianceReport, ComplianceTest, Terminal};
+
+        let rust_level = parse_compliance_level(&report.level)?;
 
         let rust_tests: Vec<ComplianceTest> = report
             .tests
             .iter()
-            .map(|t| ComplianceTest {
-                name: t.name.clone(),
-                category: t.category.clone(),
-                passed: t.passed,
-                expected: t.expected.clone(),
-                actual: t.actual.clone(),
-                notes: t.notes.clone(),
+            .map(|t| {
+                Ok(ComplianceTest {
+                    name: t.name.clone(),
+                    category: t.category.clone(),
+                    level: parse_compliance_level(&t.level)?,
+                    passed: t.passed,
+                    expected: t.expected.clone(),
+                    actual: t.actual.clone(),
+                    notes: t.notes.clone(),
+                })
             })
-            .collect();
+            .collect::<PyResult<_>>()?;
 
         let rust_report = ComplianceReport {
             terminal_info: report.terminal_info.clone(),
@@ -293,3 +296,19 @@ impl PyTerminal {
         Ok(Terminal::format_compliance_report(&rust_report))
     }
 }
+
+/// Parse a Python compliance level name ("vt52" … "xterm")
+fn parse_compliance_level(level: &str) -> PyResult<crate::terminal::ComplianceLevel> {
+    use crate::terminal::ComplianceLevel;
+
+    match level.to_lowercase().as_str() {
+        "vt52" => Ok(ComplianceLevel::VT52),
+        "vt100" => Ok(ComplianceLevel::VT100),
+        "vt220" => Ok(ComplianceLevel::VT220),
+        "vt320" => Ok(ComplianceLevel::VT320),
+        "vt420" => Ok(ComplianceLevel::VT420),
+        "vt520" => Ok(ComplianceLevel::VT520),
+        "xterm" => Ok(ComplianceLevel::XTerm),
+        _ => Err(PyValueError::new_err("Invalid compliance level")),
+    }
+}
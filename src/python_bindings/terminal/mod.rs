@@ -6,17 +6,22 @@
 // ARC-002: cohesive method groups are split into sibling `*_api` files, each
 // with its own `#[pymethods] impl PyTerminal` block. Pure relocation — the
 // Python `Terminal` class keeps the same surface.
+mod annotation_api;
 mod badge_api;
 mod bookmark_api;
+mod checkpoint_api;
 mod clipboard_api;
 mod color_api;
+mod cursor_trail_api;
 mod file_transfer_api;
 mod image_api;
+mod log_follower_api;
 mod metrics_api;
 mod mouse_api;
 mod multiplexing_api;
 mod notification_api;
 mod recording_api;
+mod redaction_api;
 mod scrollback_api;
 mod search_api;
 mod selection_api;
@@ -31,7 +36,7 @@ use std::collections::HashMap;
 use crate::color::Color;
 
 use super::enums::PyMouseEncoding;
-use super::types::{PyAttributes, PyScreenSnapshot};
+use super::types::{PyAttributes, PyScreenSnapshot, PyTerminalState};
 
 /// Python wrapper for the Terminal
 #[pyclass(name = "Terminal")]
@@ -65,6 +70,7 @@ crate::impl_terminal_search_select!(PyTerminal);
 crate::impl_terminal_debug_snapshots!(PyTerminal);
 crate::impl_terminal_file_transfer!(PyTerminal);
 crate::impl_terminal_exports!(PyTerminal);
+crate::impl_terminal_snapshot_deltas!(PyTerminal);
 
 #[pymethods]
 impl PyTerminal {
@@ -104,7 +110,7 @@ impl PyTerminal {
     }
 
     // content, __str__: provided by impl_terminal_content_misc! (ARC-003/QA-001)
-    // export_text, export_styled: provided by impl_terminal_exports! (ARC-003/QA-001)
+    // export_text, export_text_opts, export_styled: provided by impl_terminal_exports! (ARC-003/QA-001)
 
     /// Take a screenshot of the current visible buffer
     ///
@@ -126,6 +132,7 @@ impl PyTerminal {
     ///     background_color: Background color RGB tuple. Default: None (use terminal's default background)
     ///     faint_text_alpha: Alpha multiplier for faint/dim text (0.0-1.0). Default: 0.5 (50% dimming)
     ///     minimum_contrast: Minimum contrast adjustment (0.0-1.0). Default: 0.5 (moderate contrast adjustment)
+    ///     fallback_fonts: List of TTF/OTF font paths tried in order for glyphs missing from the main font. Default: None
     ///
     /// Returns:
     ///     Bytes of the image in the specified format
@@ -150,7 +157,8 @@ impl PyTerminal {
         bold_brightening = None,
         background_color = None,
         faint_text_alpha = 0.5,
-        minimum_contrast = 0.5
+        minimum_contrast = 0.5,
+        fallback_fonts = None
     ))]
     #[allow(clippy::too_many_arguments)]
     fn screenshot(
@@ -172,6 +180,7 @@ impl PyTerminal {
         background_color: Option<(u8, u8, u8)>,
         faint_text_alpha: Option<f32>,
         minimum_contrast: f64,
+        fallback_fonts: Option<Vec<String>>,
     ) -> PyResult<Vec<u8>> {
         use crate::screenshot::{ImageFormat, ScreenshotConfig};
 
@@ -191,6 +200,11 @@ impl PyTerminal {
         let config = ScreenshotConfig {
             format: img_format,
             font_path: font_path.map(std::path::PathBuf::from),
+            fallback_fonts: fallback_fonts
+                .unwrap_or_default()
+                .into_iter()
+                .map(std::path::PathBuf::from)
+                .collect(),
             font_size,
             include_scrollback,
             padding_px: padding,
@@ -236,6 +250,7 @@ impl PyTerminal {
     ///     background_color: Background color RGB tuple. Default: None (use terminal's default background)
     ///     faint_text_alpha: Alpha multiplier for faint/dim text (0.0-1.0). Default: 0.5 (50% dimming)
     ///     minimum_contrast: Minimum contrast adjustment (0.0-1.0). Default: 0.5 (moderate contrast adjustment)
+    ///     fallback_fonts: List of TTF/OTF font paths tried in order for glyphs missing from the main font. Default: None
     ///
     /// Returns:
     ///     None
@@ -261,7 +276,8 @@ impl PyTerminal {
         bold_brightening = None,
         background_color = None,
         faint_text_alpha = 0.5,
-        minimum_contrast = 0.5
+        minimum_contrast = 0.5,
+        fallback_fonts = None
     ))]
     #[allow(clippy::too_many_arguments)]
     fn screenshot_to_file(
@@ -284,6 +300,7 @@ impl PyTerminal {
         background_color: Option<(u8, u8, u8)>,
         faint_text_alpha: Option<f32>,
         minimum_contrast: f64,
+        fallback_fonts: Option<Vec<String>>,
     ) -> PyResult<()> {
         use std::path::Path;
 
@@ -310,6 +327,7 @@ impl PyTerminal {
             background_color,
             faint_text_alpha,
             minimum_contrast,
+            fallback_fonts,
         )?;
 
         std::fs::write(path, bytes)
@@ -423,11 +441,15 @@ impl PyTerminal {
 
     // keyboard_flags: provided by impl_terminal_query_getters! (ARC-003/QA-001)
 
+    // encode_key: provided by impl_terminal_query_getters! (ARC-003/QA-001)
+
+    // win32_input_mode: provided by impl_terminal_query_getters! (ARC-003/QA-001)
+
     /// Set Kitty Keyboard Protocol flags
     ///
     /// Args:
     ///     flags: Flags to set (1=disambiguate, 2=report events, 4=alternate keys, 8=report all, 16=associated text)
-    ///     mode: 0=disable all, 1=set flags, 2=lock flags (default: 1)
+    ///     mode: 1=set flags, 2=add flags, 3=remove flags (default: 1)
     ///
     /// Sends: CSI = flags ; mode u
     #[pyo3(signature = (flags, mode=1))]
@@ -511,6 +533,48 @@ impl PyTerminal {
         Ok(self.inner.margin_bell_volume())
     }
 
+    /// Get which cells DECCARA/DECRARA change (DECSACE)
+    ///
+    /// Returns:
+    ///     "rectangle" (default) or "stream"
+    fn attribute_change_extent(&self) -> PyResult<&'static str> {
+        use crate::grid::AttributeChangeExtent;
+        Ok(match self.inner.attribute_change_extent() {
+            AttributeChangeExtent::Stream => "stream",
+            AttributeChangeExtent::Rectangle => "rectangle",
+        })
+    }
+
+    /// Get the body of a VT420 macro defined with DECDMAC
+    ///
+    /// Args:
+    ///     macro_id: Macro number (0-63)
+    ///
+    /// Returns:
+    ///     The stored bytes, or None if the macro is not defined
+    fn dec_macro(&self, macro_id: usize) -> PyResult<Option<Vec<u8>>> {
+        Ok(self.inner.dec_macro(macro_id).map(<[u8]>::to_vec))
+    }
+
+    /// Get the free DECDMAC macro space in bytes (4096 when empty)
+    fn dec_macro_space_available(&self) -> PyResult<usize> {
+        Ok(self.inner.dec_macro_space_available())
+    }
+
+    /// Delete every DECDMAC macro
+    fn clear_dec_macros(&mut self) -> PyResult<()> {
+        self.inner.clear_dec_macros();
+        Ok(())
+    }
+
+    /// Check if printer controller mode (`CSI 5 i`) is diverting output
+    ///
+    /// The diverted data arrives in a "print_job_completed" event once the
+    /// application sends `CSI 4 i`.
+    fn printer_controller_active(&self) -> PyResult<bool> {
+        Ok(self.inner.printer_controller_active())
+    }
+
     /// Set margin bell volume (VT520)
     ///
     /// Args:
@@ -838,6 +902,31 @@ impl PyTerminal {
     // is_line_wrapped, get_fg_color, get_bg_color, get_underline_color, get_attributes,
     // get_hyperlink, get_line_cells: provided by impl_terminal_cell_line_queries! (ARC-003/QA-001)
 
+    /// Take an immutable handle on the visible screen, cursor and modes
+    ///
+    /// The handle never changes and can be read from other threads.
+    ///
+    /// Returns:
+    ///     TerminalState
+    fn state_handle(&self) -> PyResult<PyTerminalState> {
+        Ok(PyTerminalState {
+            inner: self.inner.state_handle(),
+        })
+    }
+
+    /// Get the frame readers should draw
+    ///
+    /// The last committed frame while double buffering is on, otherwise the
+    /// live screen like state_handle().
+    ///
+    /// Returns:
+    ///     TerminalState
+    fn front_buffer(&self) -> PyResult<PyTerminalState> {
+        Ok(PyTerminalState {
+            inner: self.inner.front_buffer(),
+        })
+    }
+
     /// Create atomic snapshot of current screen state
     ///
     /// Captures all lines, cursor state, and screen identity atomically.
@@ -859,12 +948,8 @@ impl PyTerminal {
             let mut line = Vec::with_capacity(cols);
             for col in 0..cols {
                 if let Some(cell) = grid.get(col, row) {
-                    line.push((
-                        cell.get_grapheme(),
-                        cell.fg.to_rgb(),
-                        cell.bg.to_rgb(),
-                        PyAttributes::from(cell),
-                    ));
+                    let (fg, bg) = self.inner.resolve_cell_colors(cell);
+                    line.push((cell.get_grapheme(), fg, bg, PyAttributes::from(cell)));
                 } else {
                     // Empty cell
                     line.push((
@@ -1274,7 +1359,7 @@ impl PyTerminal {
     // ========== Static Utility Methods ==========
     // strip_ansi, measure_text_width, parse_color: provided by impl_terminal_static_helpers! (ARC-003/QA-001)
     // get_sixel_limits, set_sixel_limits, get_sixel_graphics_limit, set_sixel_graphics_limit,
-    // get_dropped_sixel_graphics, get_sixel_stats: provided by impl_terminal_sixel_graphics! (ARC-003/QA-001)
+    // get_dropped_sixel_graphics, get_sixel_stats, get_regis_limits, set_regis_limits: provided by impl_terminal_sixel_graphics! (ARC-003/QA-001)
 
     /// Enable or disable tmux control mode
     ///
@@ -1467,8 +1552,9 @@ impl PyTerminal {
     ///
     /// Args:
     ///     kinds: Optional list of event kinds to receive (strings).
-    ///            Valid kinds: bell, title_changed, size_changed, mode_changed,
-    ///            graphics_added, hyperlink_added, dirty_region, cwd_changed,
+    ///            Valid kinds: bell, title_changed, icon_name_changed, size_changed,
+    ///            resize_requested,
+    ///            mode_changed, graphics_added, hyperlink_added, dirty_region, cwd_changed,
     ///            trigger_matched, user_var_changed, progress_bar_changed,
     ///            badge_changed, shell_integration, zone_opened, zone_closed,
     ///            zone_scrolled_out, environment_changed, remote_host_transition,
@@ -1581,6 +1667,43 @@ impl PyTerminal {
         Ok(self.inner.observer_count())
     }
 
+    /// Install a filter that sees each OSC and DCS sequence before the
+    /// terminal handles it, replacing any previous filter
+    ///
+    /// The callback receives a dict: OSC sequences have ``kind="osc"``,
+    /// ``command`` (str or None), ``args`` (list of bytes) and
+    /// ``bell_terminated``; DCS sequences are offered when they start, with
+    /// ``kind="dcs"``, ``params`` (list of int), ``intermediates`` and
+    /// ``action``. It returns ``"allow"`` (or None), ``"block"`` or
+    /// ``"consume"``. Exceptions and unknown values block the sequence.
+    ///
+    /// Args:
+    ///     callback: A Python callable that accepts a single dict argument.
+    ///
+    /// Example:
+    ///     >>> term.set_sequence_filter(
+    ///     ...     lambda seq: "block" if seq.get("command") == "52" else "allow"
+    ///     ... )
+    fn set_sequence_filter(&mut self, callback: Py<pyo3::types::PyAny>) -> PyResult<()> {
+        use crate::python_bindings::observer::py_sequence_filter;
+        self.inner.set_sequence_filter(py_sequence_filter(callback));
+        Ok(())
+    }
+
+    /// Remove the sequence filter installed with set_sequence_filter
+    fn clear_sequence_filter(&mut self) -> PyResult<()> {
+        self.inner.clear_sequence_filter();
+        Ok(())
+    }
+
+    /// Check if a sequence filter is installed
+    ///
+    /// Returns:
+    ///     bool: True if set_sequence_filter is active.
+    fn has_sequence_filter(&self) -> PyResult<bool> {
+        Ok(self.inner.has_sequence_filter())
+    }
+
     /// Drain events matching the current subscription
     ///
     /// Returns:
@@ -1591,6 +1714,70 @@ impl PyTerminal {
         Ok(events.iter().map(event_to_dict).collect())
     }
 
+    /// Register a filtered event subscriber with its own queue
+    ///
+    /// Unlike set_event_subscription(), each subscriber gets a private copy
+    /// of every matching event, so several components can consume events
+    /// without draining each other's queue.
+    ///
+    /// Args:
+    ///     kinds: Optional list of event kind strings (same names as
+    ///            set_event_subscription). None subscribes to all events.
+    ///
+    /// Returns:
+    ///     int: Subscription ID for poll_subscription()
+    #[pyo3(signature = (kinds=None))]
+    fn subscribe(&mut self, kinds: Option<Vec<String>>) -> PyResult<u64> {
+        Ok(self.inner.subscribe(Self::parse_event_mask(kinds)))
+    }
+
+    /// Remove an event subscriber
+    ///
+    /// Args:
+    ///     subscription_id: ID returned by subscribe()
+    ///
+    /// Returns:
+    ///     bool: True if the subscriber existed
+    fn unsubscribe(&mut self, subscription_id: u64) -> PyResult<bool> {
+        Ok(self.inner.unsubscribe(subscription_id))
+    }
+
+    /// Change the event kinds an existing subscriber receives
+    ///
+    /// Args:
+    ///     subscription_id: ID returned by subscribe()
+    ///     kinds: Optional list of event kind strings (None = all events)
+    ///
+    /// Returns:
+    ///     bool: True if the subscriber existed
+    #[pyo3(signature = (subscription_id, kinds=None))]
+    fn set_subscription_kinds(
+        &mut self,
+        subscription_id: u64,
+        kinds: Option<Vec<String>>,
+    ) -> PyResult<bool> {
+        Ok(self
+            .inner
+            .set_subscription_mask(subscription_id, Self::parse_event_mask(kinds)))
+    }
+
+    /// Drain the queue of an event subscriber
+    ///
+    /// Args:
+    ///     subscription_id: ID returned by subscribe()
+    ///
+    /// Returns:
+    ///     List of event dictionaries (same shape as poll_events); empty for
+    ///     unknown IDs
+    fn poll_subscription(
+        &mut self,
+        subscription_id: u64,
+    ) -> PyResult<Vec<HashMap<String, String>>> {
+        use crate::python_bindings::observer::event_to_dict;
+        let events = self.inner.poll_subscription(subscription_id);
+        Ok(events.iter().map(event_to_dict).collect())
+    }
+
     /// Drain only CWD change events
     ///
     /// Returns:
@@ -1668,11 +1855,26 @@ impl PyTerminal {
         Ok(self.inner.origin_mode())
     }
 
+    /// Get left/right margin mode (DECLRMM)
+    fn lr_margin_mode(&self) -> PyResult<bool> {
+        Ok(self.inner.lr_margin_mode())
+    }
+
     /// Get application cursor mode
     fn application_cursor(&self) -> PyResult<bool> {
         Ok(self.inner.application_cursor())
     }
 
+    /// Get VT52 compatibility mode (DECANM reset with CSI ? 2 l)
+    fn vt52_mode(&self) -> PyResult<bool> {
+        Ok(self.inner.vt52_mode())
+    }
+
+    /// Get National Replacement Character Set mode (DECNRCM, CSI ? 42 h)
+    fn nrcs_mode(&self) -> PyResult<bool> {
+        Ok(self.inner.nrcs_mode())
+    }
+
     /// Get current scroll region
     ///
     /// Returns:
@@ -1839,9 +2041,34 @@ impl PyTerminal {
             "file_transfer_completed" => Some(TerminalEventKind::FileTransferCompleted),
             "file_transfer_failed" => Some(TerminalEventKind::FileTransferFailed),
             "upload_requested" => Some(TerminalEventKind::UploadRequested),
+            "screen_cleared" => Some(TerminalEventKind::ScreenCleared),
+            "cursor_moved" => Some(TerminalEventKind::CursorMoved),
+            "trigger_fired" => Some(TerminalEventKind::TriggerFired),
+            "theme_changed" => Some(TerminalEventKind::ThemeChanged),
+            "resize_requested" => Some(TerminalEventKind::ResizeRequested),
+            "icon_name_changed" => Some(TerminalEventKind::IconNameChanged),
+            "file_received" => Some(TerminalEventKind::FileReceived),
+            "cursor_style_changed" => Some(TerminalEventKind::CursorStyleChanged),
+            "print_job_completed" => Some(TerminalEventKind::PrintJobCompleted),
+            "secret_detected" => Some(TerminalEventKind::SecretDetected),
+            "foreground_process_changed" => Some(TerminalEventKind::ForegroundProcessChanged),
+            "utf8_decode_error" => Some(TerminalEventKind::Utf8DecodeError),
+            "escape_string_aborted" => Some(TerminalEventKind::EscapeStringAborted),
+            "background_changed" => Some(TerminalEventKind::BackgroundChanged),
             _ => None,
         }
     }
+
+    /// Build an `EventMask` from optional kind strings (`None` = all events).
+    fn parse_event_mask(kinds: Option<Vec<String>>) -> crate::terminal::EventMask {
+        use crate::terminal::EventMask;
+        match kinds {
+            Some(items) => {
+                EventMask::from_kinds(items.iter().filter_map(|k| Self::parse_event_kind(k)))
+            }
+            None => EventMask::all(),
+        }
+    }
 }
 
 /// Helper function to parse clipboard slot from string
@@ -1903,9 +2130,9 @@ pub(crate) fn transfer_to_py_dict(
             bytes_transferred,
             total_bytes,
         } => ("in_progress", *bytes_transferred, *total_bytes),
-        TransferStatus::Completed => ("completed", transfer.data.len(), Some(transfer.data.len())),
-        TransferStatus::Failed(_) => ("failed", transfer.data.len(), None),
-        TransferStatus::Cancelled => ("cancelled", transfer.data.len(), None),
+        TransferStatus::Completed => ("completed", transfer.size, Some(transfer.size)),
+        TransferStatus::Failed(_) => ("failed", transfer.size, None),
+        TransferStatus::Cancelled => ("cancelled", transfer.size, None),
     };
     dict.set_item("status", status)?;
     dict.set_item("bytes_transferred", bytes_transferred)?;
@@ -110,4 +110,79 @@ impl PyTerminal {
     fn get_max_mouse_history(&self) -> PyResult<usize> {
         Ok(self.inner.get_max_mouse_history())
     }
+
+    // === Pixel Hit Testing ===
+
+    /// Set cell dimensions in pixels used for hit testing and graphics
+    ///
+    /// Args:
+    ///     width: Cell width in pixels
+    ///     height: Cell height in pixels
+    fn set_cell_dimensions(&mut self, width: u32, height: u32) -> PyResult<()> {
+        self.inner.set_cell_dimensions(width, height);
+        Ok(())
+    }
+
+    /// Get cell dimensions in pixels as (width, height)
+    fn cell_dimensions(&self) -> PyResult<(u32, u32)> {
+        Ok(self.inner.cell_dimensions())
+    }
+
+    /// Hit-test a pixel position relative to the top-left of the text area
+    ///
+    /// Args:
+    ///     x: X coordinate in pixels
+    ///     y: Y coordinate in pixels
+    ///     scroll_offset: Lines the viewport is scrolled back (0 = live screen)
+    ///
+    /// Returns:
+    ///     Dictionary with keys col, row, abs_row, screen_row (None in
+    ///     scrollback), char, hyperlink_id, hyperlink_url, detected_item
+    ///     (DetectedItem or None), graphic_id, selected and zone (same shape
+    ///     as get_zone_at, or None); None if the point is outside the grid
+    #[pyo3(signature = (x, y, scroll_offset=0))]
+    fn hit_test(
+        &self,
+        x: f64,
+        y: f64,
+        scroll_offset: usize,
+    ) -> PyResult<Option<Py<pyo3::types::PyDict>>> {
+        use pyo3::types::PyDict;
+
+        let Some(hit) = self.inner.hit_test(x, y, scroll_offset) else {
+            return Ok(None);
+        };
+        Python::attach(|py| {
+            let dict = PyDict::new(py);
+            dict.set_item("col", hit.col)?;
+            dict.set_item("row", hit.row)?;
+            dict.set_item("abs_row", hit.abs_row)?;
+            dict.set_item("screen_row", hit.screen_row)?;
+            dict.set_item("char", hit.cell.get_grapheme())?;
+            dict.set_item("hyperlink_id", hit.hyperlink_id)?;
+            dict.set_item("hyperlink_url", hit.hyperlink_url)?;
+            dict.set_item(
+                "detected_item",
+                hit.detected_item
+                    .as_ref()
+                    .map(crate::python_bindings::types::PyDetectedItem::from),
+            )?;
+            dict.set_item("graphic_id", hit.graphic_id)?;
+            dict.set_item("selected", hit.selected)?;
+            match hit.zone {
+                Some(zone) => {
+                    let zone_dict = PyDict::new(py);
+                    zone_dict.set_item("zone_type", zone.zone_type.to_string())?;
+                    zone_dict.set_item("abs_row_start", zone.abs_row_start)?;
+                    zone_dict.set_item("abs_row_end", zone.abs_row_end)?;
+                    zone_dict.set_item("command", zone.command.as_deref())?;
+                    zone_dict.set_item("exit_code", zone.exit_code)?;
+                    zone_dict.set_item("timestamp", zone.timestamp)?;
+                    dict.set_item("zone", zone_dict)?;
+                }
+                None => dict.set_item("zone", py.None())?,
+            }
+            Ok(Some(dict.into()))
+        })
+    }
 }
@@ -39,6 +39,7 @@ impl PyTerminal {
             env: std::collections::HashMap::new(), // Not exposed in Python for now
             content: state.content.clone(),
             cursor: state.cursor,
+            cursor_style: state.cursor_style.into(),
             alt_screen: state.alt_screen,
             scroll_offset: state.scroll_offset,
             created_at: state.created_at,
@@ -66,6 +67,7 @@ impl PyTerminal {
             env: std::collections::HashMap::new(),
             content: state.content.clone(),
             cursor: state.cursor,
+            cursor_style: state.cursor_style.into(),
             alt_screen: state.alt_screen,
             scroll_offset: state.scroll_offset,
             created_at: state.created_at,
@@ -140,6 +142,7 @@ impl PyTerminal {
                 env: std::collections::HashMap::new(),
                 content: p.content.clone(),
                 cursor: p.cursor,
+                cursor_style: p.cursor_style.into(),
                 alt_screen: p.alt_screen,
                 scroll_offset: p.scroll_offset,
                 created_at: p.created_at,
@@ -200,6 +203,7 @@ impl PyTerminal {
                 env: std::collections::HashMap::new(),
                 content: p.content.clone(),
                 cursor: p.cursor,
+                cursor_style: p.cursor_style.into(),
                 alt_screen: p.alt_screen,
                 scroll_offset: p.scroll_offset,
                 created_at: p.created_at,
@@ -31,8 +31,11 @@ impl PyTerminal {
         &mut self,
         config: &crate::python_bindings::types::PyNotificationConfig,
     ) -> PyResult<()> {
-        self.inner
-            .set_notification_config(crate::terminal::NotificationConfig::from(config));
+        // The Python config object does not carry the schedule; keep the
+        // one configured via set_quiet_hours()/set_notification_override().
+        let mut new_config = crate::terminal::NotificationConfig::from(config);
+        new_config.schedule = self.inner.notification_config().schedule.clone();
+        self.inner.set_notification_config(new_config);
         Ok(())
     }
 
@@ -48,25 +51,9 @@ impl PyTerminal {
         alert: &str,
         message: Option<String>,
     ) -> PyResult<()> {
-        use crate::terminal::{NotificationAlert, NotificationTrigger};
-
-        let trigger_parsed = if trigger.to_lowercase() == "bell" {
-            NotificationTrigger::Bell
-        } else if trigger.to_lowercase() == "activity" {
-            NotificationTrigger::Activity
-        } else if trigger.to_lowercase() == "silence" {
-            NotificationTrigger::Silence
-        } else if trigger.starts_with("Custom(") && trigger.ends_with(')') {
-            let id_str = &trigger[7..trigger.len() - 1];
-            let id: u32 = id_str
-                .parse()
-                .map_err(|_| PyValueError::new_err("Invalid custom trigger ID"))?;
-            NotificationTrigger::Custom(id)
-        } else {
-            return Err(PyValueError::new_err(
-                "Invalid trigger type (use 'Bell', 'Activity', 'Silence', or 'Custom(id)')",
-            ));
-        };
+        use crate::terminal::NotificationAlert;
+
+        let trigger_parsed = parse_notification_trigger(trigger)?;
 
         let alert_parsed = if alert.to_lowercase() == "desktop" {
             NotificationAlert::Desktop
@@ -191,4 +178,132 @@ impl PyTerminal {
         self.inner.handle_bell_notification();
         Ok(())
     }
+
+    /// Configure quiet hours
+    ///
+    /// Args:
+    ///     windows: List of (start_minute, end_minute, weekdays) tuples in local
+    ///         time. Minutes are since midnight; windows with start > end wrap
+    ///         past midnight. weekdays is a bitmask (bit 0 = Monday ... bit 6 =
+    ///         Sunday), 0 for every day.
+    ///     utc_offset_minutes: Local time offset from UTC in minutes
+    ///     suppress_when_focused: Also suppress notifications while focused
+    #[pyo3(signature = (windows, utc_offset_minutes=0, suppress_when_focused=false))]
+    fn set_quiet_hours(
+        &mut self,
+        windows: Vec<(u16, u16, u8)>,
+        utc_offset_minutes: i32,
+        suppress_when_focused: bool,
+    ) -> PyResult<()> {
+        if windows.iter().any(|&(s, e, _)| s >= 1440 || e >= 1440) {
+            return Err(PyValueError::new_err(
+                "Quiet window minutes must be in range 0-1439",
+            ));
+        }
+        let mut schedule = self.inner.notification_config().schedule.clone();
+        schedule.quiet_windows = windows
+            .into_iter()
+            .map(
+                |(start_minute, end_minute, weekdays)| crate::terminal::QuietWindow {
+                    start_minute,
+                    end_minute,
+                    weekdays,
+                },
+            )
+            .collect();
+        schedule.utc_offset_minutes = utc_offset_minutes;
+        schedule.suppress_when_focused = suppress_when_focused;
+        self.inner.set_notification_schedule(schedule);
+        Ok(())
+    }
+
+    /// Override the quiet-hours schedule for a trigger
+    ///
+    /// Args:
+    ///     trigger: Trigger type ("Bell", "Activity", "Silence", "Custom(id)")
+    ///     mode: "always_deliver", "always_suppress", or None to remove the override
+    #[pyo3(signature = (trigger, mode=None))]
+    fn set_notification_override(&mut self, trigger: &str, mode: Option<&str>) -> PyResult<()> {
+        use crate::terminal::ScheduleOverride;
+
+        let trigger = parse_notification_trigger(trigger)?;
+        let mut schedule = self.inner.notification_config().schedule.clone();
+        match mode {
+            None => {
+                schedule.trigger_overrides.remove(&trigger);
+            }
+            Some("always_deliver") => {
+                schedule
+                    .trigger_overrides
+                    .insert(trigger, ScheduleOverride::AlwaysDeliver);
+            }
+            Some("always_suppress") => {
+                schedule
+                    .trigger_overrides
+                    .insert(trigger, ScheduleOverride::AlwaysSuppress);
+            }
+            Some(other) => {
+                return Err(PyValueError::new_err(format!(
+                    "Invalid override mode '{}' (use 'always_deliver' or 'always_suppress')",
+                    other
+                )))
+            }
+        }
+        self.inner.set_notification_schedule(schedule);
+        Ok(())
+    }
+
+    /// Tell the terminal whether its window has focus
+    ///
+    /// Args:
+    ///     focused: True if the window is focused
+    fn set_window_focused(&mut self, focused: bool) -> PyResult<()> {
+        self.inner.set_window_focused(focused);
+        Ok(())
+    }
+
+    /// Check whether quiet hours are currently in effect
+    fn is_quiet_hours(&self) -> PyResult<bool> {
+        Ok(self.inner.is_quiet_hours())
+    }
+
+    /// Take the digest of suppressed notifications
+    ///
+    /// Returned events are marked delivered so each appears in one digest only.
+    ///
+    /// Returns:
+    ///     List of NotificationEvent objects with suppressed_reason set
+    fn take_notification_digest(
+        &mut self,
+    ) -> PyResult<Vec<crate::python_bindings::types::PyNotificationEvent>> {
+        Ok(self
+            .inner
+            .take_notification_digest()
+            .iter()
+            .map(crate::python_bindings::types::PyNotificationEvent::from)
+            .collect())
+    }
+}
+
+/// Parse a notification trigger name ("Bell", "Activity", "Silence", "Custom(id)")
+fn parse_notification_trigger(trigger: &str) -> PyResult<crate::terminal::NotificationTrigger> {
+    use crate::terminal::NotificationTrigger;
+
+    if trigger.to_lowercase() == "bell" {
+        Ok(NotificationTrigger::Bell)
+    } else if trigger.to_lowercase() == "activity" {
+        Ok(NotificationTrigger::Activity)
+    } else if trigger.to_lowercase() == "silence" {
+        Ok(NotificationTrigger::Silence)
+    } else if trigger.starts_with("Custom(") && trigger.ends_with(')') {
+        let id_str = &trigger[7..trigger.len() - 1];
+        let id: u32 = id_str
+            .parse()
+            .map_err(|_| PyValueError::new_err("Invalid custom trigger ID"))?;
+        Ok(NotificationTrigger::Custom(id))
+    } else {
+        Err(PyValueError::new_err(
+            "Invalid trigger type (use 'Bell', 'Activity', 'Silence', or 'Custom(id)')",
+        ))
+    }
 }
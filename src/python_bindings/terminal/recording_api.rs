@@ -11,7 +11,7 @@ use super::PyTerminal;
 impl PyTerminal {
     // === Feature 24: Terminal Replay/Recording ===
     // start_recording, stop_recording, record_output, record_input, record_resize,
-    // record_marker, get_recording_session, is_recording:
+    // record_marker, get_recording_session, is_recording, export_recording:
     //   provided by impl_terminal_recording! (ARC-003/QA-001)
 
     /// Export recording to asciicast v2 format
@@ -0,0 +1,94 @@
+//! Secret detection and redaction API methods for `PyTerminal`
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use super::PyTerminal;
+
+#[pymethods]
+impl PyTerminal {
+    // === Secret Redaction ===
+
+    /// Enable or disable secret detection and redaction (off by default)
+    ///
+    /// While enabled, completed output lines are scanned for likely secrets,
+    /// each emitting a `secret_detected` event, and text exports, semantic
+    /// snapshots, copied selections and screenshots are masked. The live
+    /// screen is left intact.
+    ///
+    /// Args:
+    ///     enabled: True to enable redaction
+    fn set_redaction_enabled(&mut self, enabled: bool) -> PyResult<()> {
+        self.inner.set_redaction_enabled(enabled);
+        Ok(())
+    }
+
+    /// Check if secret detection and redaction is enabled
+    fn is_redaction_enabled(&self) -> PyResult<bool> {
+        Ok(self.inner.is_redaction_enabled())
+    }
+
+    /// Use or ignore the built-in secret patterns (used by default)
+    ///
+    /// Private key blocks are always detected.
+    fn set_builtin_secret_patterns(&mut self, enabled: bool) -> PyResult<()> {
+        self.inner.set_builtin_secret_patterns(enabled);
+        Ok(())
+    }
+
+    /// Check if the built-in secret patterns are in use
+    fn builtin_secret_patterns_enabled(&self) -> PyResult<bool> {
+        Ok(self.inner.builtin_secret_patterns_enabled())
+    }
+
+    /// Add a secret pattern, replacing any pattern with the same name
+    ///
+    /// Args:
+    ///     name: Pattern name reported in `secret_detected` events
+    ///     pattern: Regex; a group named `secret` narrows the masked span
+    ///
+    /// Raises:
+    ///     ValueError: If the regex is invalid
+    fn add_secret_pattern(&mut self, name: String, pattern: &str) -> PyResult<()> {
+        self.inner
+            .add_secret_pattern(name, pattern)
+            .map_err(PyValueError::new_err)
+    }
+
+    /// Remove a secret pattern added with `add_secret_pattern`
+    ///
+    /// Returns:
+    ///     True if the pattern was removed, False if not found
+    fn remove_secret_pattern(&mut self, name: &str) -> PyResult<bool> {
+        Ok(self.inner.remove_secret_pattern(name))
+    }
+
+    /// Remove every secret pattern added with `add_secret_pattern`
+    fn clear_secret_patterns(&mut self) -> PyResult<()> {
+        self.inner.clear_secret_patterns();
+        Ok(())
+    }
+
+    /// Names of the secret patterns added with `add_secret_pattern`
+    fn secret_pattern_names(&self) -> PyResult<Vec<String>> {
+        Ok(self
+            .inner
+            .secret_pattern_names()
+            .into_iter()
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// Set the character that replaces each masked character (default "*")
+    fn set_redaction_mask(&mut self, mask: char) -> PyResult<()> {
+        self.inner.set_redaction_mask(mask);
+        Ok(())
+    }
+
+    /// Mask the secrets in `text` with the current patterns
+    ///
+    /// Works whether or not redaction is enabled.
+    fn redact_text(&self, text: &str) -> PyResult<String> {
+        Ok(self.inner.redact_text(text))
+    }
+}
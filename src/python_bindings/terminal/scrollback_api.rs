@@ -40,6 +40,7 @@ impl PyTerminal {
             stats.hyperlink_memory_bytes,
         );
         result.insert("color_stack_depth".to_string(), stats.color_stack_depth);
+        result.insert("sgr_stack_depth".to_string(), stats.sgr_stack_depth);
         result.insert("title_stack_depth".to_string(), stats.title_stack_depth);
         result.insert(
             "keyboard_stack_depth".to_string(),
@@ -83,13 +84,7 @@ impl PyTerminal {
     ///     Exported content as string
     #[pyo3(signature = (format="plain", max_lines=None))]
     fn export_scrollback(&self, format: &str, max_lines: Option<usize>) -> PyResult<String> {
-        use crate::terminal::ExportFormat;
-        let export_format = match format {
-            "plain" => ExportFormat::Plain,
-            "html" => ExportFormat::Html,
-            "ansi" => ExportFormat::Ansi,
-            _ => return Err(PyValueError::new_err("Invalid export format")),
-        };
+        let export_format = crate::python_bindings::common::parse_export_format(format)?;
         Ok(self.inner.export_scrollback(export_format, max_lines))
     }
 
@@ -103,6 +98,159 @@ impl PyTerminal {
             total_lines: stats.total_lines,
             memory_bytes: stats.memory_bytes,
             has_wrapped: stats.has_wrapped,
+            compressed_lines: stats.compressed_lines,
+            compressed_bytes: stats.compressed_bytes,
+            raw_bytes: stats.raw_bytes,
         })
     }
+
+    /// Set the scrollback eviction policy
+    ///
+    /// Args:
+    ///     policy: "fifo" (evict oldest line) or "adaptive" (evict blank,
+    ///         low-entropy, and near-duplicate lines first, falling back to FIFO)
+    ///     window: Number of oldest lines searched for a trim candidate
+    ///     similarity_threshold: Similarity (0.0-1.0) to the following line at
+    ///         which a line counts as a redundant redraw (>1.0 disables)
+    ///     min_entropy_bits: Lines with lower character entropy are trimmable
+    ///         (0.0 disables)
+    ///     trim_blank: Whether blank lines are trimmable
+    #[pyo3(signature = (
+        policy="adaptive",
+        window=256,
+        similarity_threshold=0.85,
+        min_entropy_bits=0.0,
+        trim_blank=true
+    ))]
+    fn set_scrollback_trim_policy(
+        &mut self,
+        policy: &str,
+        window: usize,
+        similarity_threshold: f32,
+        min_entropy_bits: f32,
+        trim_blank: bool,
+    ) -> PyResult<()> {
+        use crate::grid::{AdaptiveTrimConfig, ScrollbackTrimPolicy};
+        let policy = match policy {
+            "fifo" => ScrollbackTrimPolicy::Fifo,
+            "adaptive" => ScrollbackTrimPolicy::Adaptive(AdaptiveTrimConfig {
+                window,
+                similarity_threshold,
+                min_entropy_bits,
+                trim_blank,
+            }),
+            _ => {
+                return Err(PyValueError::new_err(
+                    "Invalid trim policy (use 'fifo' or 'adaptive')",
+                ))
+            }
+        };
+        self.inner.set_scrollback_trim_policy(policy);
+        Ok(())
+    }
+
+    /// Get scrollback eviction statistics
+    ///
+    /// Returns:
+    ///     Dictionary with fifo_evictions, fallback_evictions, blank_trimmed,
+    ///     low_entropy_trimmed, similar_trimmed, memory_evictions,
+    ///     age_evictions, idle_evictions
+    fn get_scrollback_trim_stats(&self) -> PyResult<HashMap<String, u64>> {
+        let stats = self.inner.scrollback_trim_stats();
+        let mut result = HashMap::new();
+        result.insert("fifo_evictions".to_string(), stats.fifo_evictions);
+        result.insert("fallback_evictions".to_string(), stats.fallback_evictions);
+        result.insert("blank_trimmed".to_string(), stats.blank_trimmed);
+        result.insert("low_entropy_trimmed".to_string(), stats.low_entropy_trimmed);
+        result.insert("similar_trimmed".to_string(), stats.similar_trimmed);
+        result.insert("memory_evictions".to_string(), stats.memory_evictions);
+        result.insert("age_evictions".to_string(), stats.age_evictions);
+        result.insert("idle_evictions".to_string(), stats.idle_evictions);
+        Ok(result)
+    }
+
+    /// Cap scrollback by memory and line age, and shrink it once output is idle
+    ///
+    /// These limits evict the oldest lines on top of the maximum line count.
+    /// They are checked after every `process()` call; call `trim_scrollback()`
+    /// periodically so age and idle limits also apply while no output arrives.
+    /// Passing no arguments removes every limit.
+    ///
+    /// Args:
+    ///     max_bytes: Maximum estimated scrollback memory in bytes
+    ///     max_age_ms: Maximum age of a line, by the time it was last written
+    ///     idle_ms: Milliseconds without output after which scrollback is
+    ///         shrunk to `idle_keep_lines`
+    ///     idle_keep_lines: Newest lines kept once idle
+    #[pyo3(signature = (max_bytes=None, max_age_ms=None, idle_ms=None, idle_keep_lines=0))]
+    fn set_scrollback_policy(
+        &mut self,
+        max_bytes: Option<usize>,
+        max_age_ms: Option<u64>,
+        idle_ms: Option<u64>,
+        idle_keep_lines: usize,
+    ) -> PyResult<()> {
+        use crate::grid::{IdleTrim, ScrollbackPolicy};
+        self.inner.set_scrollback_policy(ScrollbackPolicy {
+            max_bytes,
+            max_age_ms,
+            idle_trim: idle_ms.map(|idle_ms| IdleTrim {
+                idle_ms,
+                keep_lines: idle_keep_lines,
+            }),
+        });
+        Ok(())
+    }
+
+    /// Get the scrollback limits
+    ///
+    /// Returns:
+    ///     Dictionary with max_bytes, max_age_ms, idle_ms and idle_keep_lines
+    ///     (None when unset)
+    fn get_scrollback_policy(&self) -> PyResult<HashMap<String, Option<u64>>> {
+        let policy = self.inner.scrollback_policy();
+        let idle = policy.idle_trim;
+        Ok(HashMap::from([
+            ("max_bytes".to_string(), policy.max_bytes.map(|b| b as u64)),
+            ("max_age_ms".to_string(), policy.max_age_ms),
+            ("idle_ms".to_string(), idle.map(|t| t.idle_ms)),
+            (
+                "idle_keep_lines".to_string(),
+                idle.map(|t| t.keep_lines as u64),
+            ),
+        ]))
+    }
+
+    /// Apply the scrollback limits now
+    ///
+    /// Returns:
+    ///     Number of lines evicted
+    fn trim_scrollback(&mut self) -> PyResult<usize> {
+        Ok(self.inner.trim_scrollback())
+    }
+
+    /// Reset scrollback eviction statistics
+    fn reset_scrollback_trim_stats(&mut self) -> PyResult<()> {
+        self.inner.reset_scrollback_trim_stats();
+        Ok(())
+    }
+
+    /// Compress older scrollback lines
+    ///
+    /// Args:
+    ///     after_lines: Number of newest lines kept uncompressed, or None to
+    ///         store every line uncompressed
+    #[pyo3(signature = (after_lines=Some(1000)))]
+    fn set_scrollback_compression(&mut self, after_lines: Option<usize>) -> PyResult<()> {
+        self.inner.set_scrollback_compression(after_lines);
+        Ok(())
+    }
+
+    /// Get the scrollback compression threshold
+    ///
+    /// Returns:
+    ///     Number of newest lines kept uncompressed, or None if disabled
+    fn scrollback_compression(&self) -> PyResult<Option<usize>> {
+        Ok(self.inner.scrollback_compression())
+    }
 }
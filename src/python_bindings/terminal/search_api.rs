@@ -52,19 +52,27 @@ impl PyTerminal {
     ///     query: Text to search for
     ///     case_sensitive: Whether the search should be case-sensitive
     ///     max_lines: Maximum number of scrollback lines to search (None = all)
+    ///     normalization: NormalizationForm applied to the query and each cell
+    ///         before comparing (default: NFC)
     ///
     /// Returns:
-    ///     List of SearchMatch objects with negative row indices for scrollback
-    #[pyo3(signature = (query, case_sensitive=false, max_lines=None))]
+    ///     List of SearchMatch objects with negative row indices for scrollback;
+    ///     col and length are in columns
+    #[pyo3(signature = (query, case_sensitive=false, max_lines=None, normalization=None))]
     fn search_scrollback(
         &self,
         query: &str,
         case_sensitive: bool,
         max_lines: Option<usize>,
+        normalization: Option<crate::python_bindings::enums::PyNormalizationForm>,
     ) -> PyResult<Vec<crate::python_bindings::types::PySearchMatch>> {
+        let options = crate::terminal::TextSearchOptions {
+            case_sensitive,
+            normalization: normalization.map(Into::into).unwrap_or_default(),
+        };
         let matches = self
             .inner
-            .search_scrollback(query, case_sensitive, max_lines);
+            .search_scrollback_with_options(query, &options, max_lines);
         Ok(matches
             .iter()
             .map(|m| crate::python_bindings::types::PySearchMatch {
@@ -76,6 +84,28 @@ impl PyTerminal {
             .collect())
     }
 
+    /// Enable or disable the scrollback search index
+    ///
+    /// The index caches each scrollback line's text as it enters scrollback,
+    /// making repeated search_scrollback() calls much faster at the cost of
+    /// roughly two copies of the scrollback text in memory.
+    ///
+    /// Args:
+    ///     enabled: Whether to maintain the index
+    #[pyo3(signature = (enabled=true))]
+    fn set_scrollback_search_index(&mut self, enabled: bool) -> PyResult<()> {
+        self.inner.set_scrollback_search_index(enabled);
+        Ok(())
+    }
+
+    /// Check whether the scrollback search index is enabled
+    ///
+    /// Returns:
+    ///     True if scrollback lines are indexed for search
+    fn scrollback_search_index_enabled(&self) -> PyResult<bool> {
+        Ok(self.inner.scrollback_search_index_enabled())
+    }
+
     // === Content Detection Methods ===
 
     /// Detect URLs in the visible screen
@@ -83,46 +113,77 @@ impl PyTerminal {
     /// Returns:
     ///     List of DetectedItem objects for URLs
     fn detect_urls(&self) -> PyResult<Vec<crate::python_bindings::types::PyDetectedItem>> {
-        use crate::terminal::DetectedItem;
-        let items = self.inner.detect_urls();
-        Ok(items
+        Ok(self
+            .inner
+            .detect_urls()
             .iter()
-            .map(|item| match item {
-                DetectedItem::Url(text, row, col) => {
-                    crate::python_bindings::types::PyDetectedItem {
-                        item_type: "url".to_string(),
-                        text: text.clone(),
-                        row: *row,
-                        col: *col,
-                        line_number: None,
-                    }
-                }
-                _ => unreachable!(),
-            })
+            .map(crate::python_bindings::types::PyDetectedItem::from)
             .collect())
     }
 
+    /// Enable or disable promotion of detected URLs to hyperlinks
+    ///
+    /// When enabled, plain-text URLs get synthetic hyperlink IDs and show up in
+    /// get_all_hyperlinks(), hit_test() and hyperlink_added events exactly like
+    /// OSC 8 links. Disabling removes them from the screen.
+    ///
+    /// Args:
+    ///     enabled: True to autolink URLs (default: off)
+    fn set_url_autolink(&mut self, enabled: bool) -> PyResult<()> {
+        self.inner.set_url_autolink(enabled);
+        Ok(())
+    }
+
+    /// Check if detected URLs are promoted to hyperlinks
+    fn url_autolink_enabled(&self) -> PyResult<bool> {
+        Ok(self.inner.url_autolink_enabled())
+    }
+
+    /// Check if a hyperlink ID belongs to an autolinked URL rather than OSC 8
+    ///
+    /// Args:
+    ///     id: Hyperlink ID (from hit_test() or hyperlink_added events)
+    fn is_autolink(&self, id: u32) -> PyResult<bool> {
+        Ok(self.inner.is_autolink(id))
+    }
+
+    /// Track the hyperlink under the mouse pointer
+    ///
+    /// When the hovered link changes, rendering hints are queued for the old
+    /// and new link cells so they can be redrawn with or without a hover
+    /// underline (see poll_rendering_hints()).
+    ///
+    /// Args:
+    ///     col: Screen column under the pointer
+    ///     row: Screen row under the pointer
+    ///
+    /// Returns:
+    ///     ID of the hovered hyperlink, or None
+    fn update_link_hover(&mut self, col: usize, row: usize) -> PyResult<Option<u32>> {
+        Ok(self.inner.update_link_hover(col, row))
+    }
+
+    /// Forget the hovered hyperlink (e.g. the pointer left the window)
+    fn clear_link_hover(&mut self) -> PyResult<()> {
+        self.inner.clear_link_hover();
+        Ok(())
+    }
+
+    /// Get the ID of the hyperlink under the mouse pointer, or None
+    fn hovered_hyperlink(&self) -> PyResult<Option<u32>> {
+        Ok(self.inner.hovered_hyperlink())
+    }
+
     /// Detect file paths in the visible screen
     ///
     /// Returns:
     ///     List of DetectedItem objects for file paths
     fn detect_file_paths(&self) -> PyResult<Vec<crate::python_bindings::types::PyDetectedItem>> {
-        use crate::terminal::DetectedItem;
-        let items = self.inner.detect_file_paths();
-        Ok(items
+        Ok(self
+            .inner
+            .detect_file_paths()
             .iter()
-            .map(|item| match item {
-                DetectedItem::FilePath(text, row, col, line_num) => {
-                    crate::python_bindings::types::PyDetectedItem {
-                        item_type: "filepath".to_string(),
-                        text: text.clone(),
-                        row: *row,
-                        col: *col,
-                        line_number: *line_num,
-                    }
-                }
-                _ => unreachable!(),
-            })
+            .map(crate::python_bindings::types::PyDetectedItem::from)
             .collect())
     }
 
@@ -133,57 +194,11 @@ impl PyTerminal {
     fn detect_semantic_items(
         &self,
     ) -> PyResult<Vec<crate::python_bindings::types::PyDetectedItem>> {
-        use crate::terminal::DetectedItem;
-        let items = self.inner.detect_semantic_items();
-        Ok(items
+        Ok(self
+            .inner
+            .detect_semantic_items()
             .iter()
-            .map(|item| match item {
-                DetectedItem::Url(text, row, col) => {
-                    crate::python_bindings::types::PyDetectedItem {
-                        item_type: "url".to_string(),
-                        text: text.clone(),
-                        row: *row,
-                        col: *col,
-                        line_number: None,
-                    }
-                }
-                DetectedItem::FilePath(text, row, col, line_num) => {
-                    crate::python_bindings::types::PyDetectedItem {
-                        item_type: "filepath".to_string(),
-                        text: text.clone(),
-                        row: *row,
-                        col: *col,
-                        line_number: *line_num,
-                    }
-                }
-                DetectedItem::GitHash(text, row, col) => {
-                    crate::python_bindings::types::PyDetectedItem {
-                        item_type: "git_hash".to_string(),
-                        text: text.clone(),
-                        row: *row,
-                        col: *col,
-                        line_number: None,
-                    }
-                }
-                DetectedItem::IpAddress(text, row, col) => {
-                    crate::python_bindings::types::PyDetectedItem {
-                        item_type: "ip".to_string(),
-                        text: text.clone(),
-                        row: *row,
-                        col: *col,
-                        line_number: None,
-                    }
-                }
-                DetectedItem::Email(text, row, col) => {
-                    crate::python_bindings::types::PyDetectedItem {
-                        item_type: "email".to_string(),
-                        text: text.clone(),
-                        row: *row,
-                        col: *col,
-                        line_number: None,
-                    }
-                }
-            })
+            .map(crate::python_bindings::types::PyDetectedItem::from)
             .collect())
     }
 
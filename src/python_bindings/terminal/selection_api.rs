@@ -5,6 +5,7 @@
 
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
+use std::collections::HashMap;
 
 use super::PyTerminal;
 
@@ -56,6 +57,94 @@ impl PyTerminal {
         }
     }
 
+    /// Add a selection alongside the current one
+    ///
+    /// Args:
+    ///     start: Start position (col, row) tuple
+    ///     end: End position (col, row) tuple
+    ///     mode: Selection mode: "character", "line", or "block"
+    ///
+    /// Returns:
+    ///     Index of the new selection in `get_selections()`
+    fn add_selection(
+        &mut self,
+        start: (usize, usize),
+        end: (usize, usize),
+        mode: &str,
+    ) -> PyResult<usize> {
+        use crate::terminal::SelectionMode;
+        let sel_mode = match mode {
+            "character" => SelectionMode::Character,
+            "line" => SelectionMode::Line,
+            "block" => SelectionMode::Block,
+            _ => return Err(PyValueError::new_err("Invalid selection mode")),
+        };
+        Ok(self.inner.add_selection(start, end, sel_mode))
+    }
+
+    /// Get all selections, primary first
+    ///
+    /// Returns:
+    ///     List of Selection objects
+    fn get_selections(&self) -> PyResult<Vec<crate::python_bindings::types::PySelection>> {
+        Ok(self
+            .inner
+            .selections()
+            .into_iter()
+            .map(|sel| crate::python_bindings::types::PySelection {
+                start: sel.start,
+                end: sel.end,
+                mode: match sel.mode {
+                    crate::terminal::SelectionMode::Character => "character",
+                    crate::terminal::SelectionMode::Line => "line",
+                    crate::terminal::SelectionMode::Block => "block",
+                }
+                .to_string(),
+            })
+            .collect())
+    }
+
+    /// Remove the selection at `index` in `get_selections()`
+    ///
+    /// Returns:
+    ///     False if the index is out of range
+    fn remove_selection(&mut self, index: usize) -> PyResult<bool> {
+        Ok(self.inner.remove_selection(index))
+    }
+
+    /// Check whether the cell at (col, row) is inside any selection
+    fn is_cell_selected(&self, col: usize, row: usize) -> PyResult<bool> {
+        Ok(self.inner.is_cell_selected(col, row))
+    }
+
+    /// Set how copied selections and `join_wrapped_lines` treat row boundaries
+    ///
+    /// Args:
+    ///     mode: "always" (join soft-wrapped rows), "never" (a line break
+    ///         after every row) or "smart" (default: also join full rows that
+    ///         break mid-identifier, as when a program wraps at the margin)
+    fn set_join_mode(&mut self, mode: &str) -> PyResult<()> {
+        use crate::terminal::JoinMode;
+        let join_mode = match mode {
+            "always" => JoinMode::Always,
+            "never" => JoinMode::Never,
+            "smart" => JoinMode::Smart,
+            _ => return Err(PyValueError::new_err("Invalid join mode")),
+        };
+        self.inner.set_join_mode(join_mode);
+        Ok(())
+    }
+
+    /// Get the join mode: "always", "never" or "smart"
+    fn join_mode(&self) -> PyResult<String> {
+        let mode = match self.inner.join_mode() {
+            crate::terminal::JoinMode::Always => "always",
+            crate::terminal::JoinMode::Never => "never",
+            crate::terminal::JoinMode::Smart => "smart",
+        };
+        Ok(mode.to_string())
+    }
+
     /// Get the text content of the current selection
     ///
     /// Returns:
@@ -64,6 +153,21 @@ impl PyTerminal {
         Ok(self.inner.get_selected_text())
     }
 
+    /// Get the current selection with styles for "copy with styles"
+    ///
+    /// Returns:
+    ///     Dict with "text", "html" (a `<pre>` fragment) and "rtf" keys,
+    ///     or None if no selection
+    fn get_selected_rich_text(&self) -> PyResult<Option<HashMap<String, String>>> {
+        Ok(self.inner.get_selected_rich_text().map(|rich| {
+            HashMap::from([
+                ("text".to_string(), rich.text),
+                ("html".to_string(), rich.html),
+                ("rtf".to_string(), rich.rtf),
+            ])
+        }))
+    }
+
     /// Select the word at the given position
     ///
     /// Args:
@@ -83,7 +187,7 @@ impl PyTerminal {
         Ok(())
     }
 
-    /// Clear the current selection
+    /// Clear all selections
     fn clear_selection(&mut self) -> PyResult<()> {
         self.inner.clear_selection();
         Ok(())
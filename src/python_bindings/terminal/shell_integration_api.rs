@@ -141,6 +141,114 @@ impl PyTerminal {
             .collect())
     }
 
+    /// Get the current host/user context
+    ///
+    /// Returns:
+    ///     PyHostContext with hostname, username, and is_remote
+    fn get_host_context(&self) -> PyResult<crate::python_bindings::types::PyHostContext> {
+        Ok(crate::python_bindings::types::PyHostContext::from(
+            self.inner.host_context(),
+        ))
+    }
+
+    /// Get the session environment reported by shell integration
+    ///
+    /// Returns:
+    ///     PyShellEnvironment with cwd, hostname, username, is_remote, shell,
+    ///     shell_integration_version, virtual_env and conda_prefix
+    fn get_shell_environment(&self) -> PyResult<crate::python_bindings::types::PyShellEnvironment> {
+        Ok(crate::python_bindings::types::PyShellEnvironment::from(
+            self.inner.shell_environment(),
+        ))
+    }
+
+    /// Get the history of shell environment changes, oldest first
+    ///
+    /// Returns:
+    ///     List of PyEnvironmentChange
+    fn get_environment_history(
+        &self,
+    ) -> PyResult<Vec<crate::python_bindings::types::PyEnvironmentChange>> {
+        Ok(self
+            .inner
+            .get_environment_history()
+            .iter()
+            .map(crate::python_bindings::types::PyEnvironmentChange::from)
+            .collect())
+    }
+
+    /// Set the hostname of the local machine
+    ///
+    /// Reported hostnames matching it are classified as local (is_remote=False).
+    ///
+    /// Args:
+    ///     hostname: Local hostname, or None to clear
+    #[pyo3(signature = (hostname=None))]
+    fn set_local_hostname(&mut self, hostname: Option<String>) -> PyResult<()> {
+        self.inner.set_local_hostname(hostname);
+        Ok(())
+    }
+
+    /// Configure heuristic prompt detection for shells without OSC 133
+    ///
+    /// When enabled, a prompt is assumed once output has been idle for
+    /// ``idle_ms``, the cursor sits right after the last text on its row, and
+    /// that text matches one of ``patterns``. Prompt, command and output zones
+    /// are then created with ``heuristic=True`` in ``get_zones()``. Detection
+    /// stops once a real OSC 133 marker is seen.
+    ///
+    /// Args:
+    ///     enabled: Whether to detect prompts
+    ///     patterns: Regexes matched against the text before the cursor
+    ///         (default: common shell and REPL prompts)
+    ///     idle_ms: Quiet time before the cursor row is examined (default 150)
+    ///
+    /// Raises:
+    ///     ValueError: If a pattern is not a valid regex
+    #[pyo3(signature = (enabled, patterns=None, idle_ms=None))]
+    fn set_prompt_detection(
+        &mut self,
+        enabled: bool,
+        patterns: Option<Vec<String>>,
+        idle_ms: Option<u64>,
+    ) -> PyResult<()> {
+        let defaults = crate::terminal::PromptDetectionConfig::default();
+        let config = crate::terminal::PromptDetectionConfig {
+            enabled,
+            patterns: patterns.unwrap_or(defaults.patterns),
+            idle_ms: idle_ms.unwrap_or(defaults.idle_ms),
+        };
+        self.inner
+            .set_prompt_detection_config(config)
+            .map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    /// Get the heuristic prompt detection settings
+    ///
+    /// Returns:
+    ///     Dictionary with "enabled", "patterns" and "idle_ms"
+    fn get_prompt_detection(&self) -> PyResult<pyo3::Py<pyo3::types::PyDict>> {
+        let config = self.inner.prompt_detection_config();
+        Python::attach(|py| {
+            let dict = pyo3::types::PyDict::new(py);
+            dict.set_item("enabled", config.enabled)?;
+            dict.set_item("patterns", &config.patterns)?;
+            dict.set_item("idle_ms", config.idle_ms)?;
+            Ok(dict.into())
+        })
+    }
+
+    /// Check for a prompt at the cursor now
+    ///
+    /// Prompts are otherwise noticed when the next output arrives; call this
+    /// from a timer to open the prompt zone as soon as the shell goes quiet.
+    ///
+    /// Returns:
+    ///     True if a new prompt was detected
+    fn update_prompt_detection(&mut self) -> PyResult<bool> {
+        Ok(self.inner.update_prompt_detection())
+    }
+
     /// Get shell integration statistics
     ///
     /// Returns:
@@ -152,6 +260,23 @@ impl PyTerminal {
         Ok(crate::python_bindings::types::PyShellIntegrationStats::from(&stats))
     }
 
+    /// Export command execution history as OpenTelemetry-style spans
+    ///
+    /// Produces an OTLP/JSON trace with a root session span, one
+    /// "shell.command" span per command, and "shell.output" child spans
+    /// for commands whose output zone is still available.
+    ///
+    /// Args:
+    ///     service_name: Value for the service.name resource attribute
+    ///         (default "par-term")
+    ///
+    /// Returns:
+    ///     JSON string suitable for posting to an OTLP/HTTP collector
+    #[pyo3(signature = (service_name=None))]
+    fn export_command_spans(&self, service_name: Option<&str>) -> PyResult<String> {
+        Ok(self.inner.export_command_spans_json(service_name))
+    }
+
     /// Clear command execution history
     fn clear_command_history(&mut self) -> PyResult<()> {
         self.inner.clear_command_history();
@@ -181,4 +306,13 @@ impl PyTerminal {
         self.inner.set_max_cwd_history(max);
         Ok(())
     }
+
+    /// Set maximum environment change history size
+    ///
+    /// Args:
+    ///     max: Maximum number of environment change entries
+    fn set_max_environment_history(&mut self, max: usize) -> PyResult<()> {
+        self.inner.set_max_environment_history(max);
+        Ok(())
+    }
 }
@@ -2,6 +2,7 @@
 //! `#[pymethods]` block in `mod.rs`). Pure relocation — no Python API or
 //! behavior change; these methods remain on the same `Terminal` Python class.
 
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 
 use super::PyTerminal;
@@ -14,8 +15,90 @@ impl PyTerminal {
 
     // select_word, find_text, find_next, find_matching_bracket, select_semantic_region:
     //   provided by impl_terminal_search_select! (ARC-003/QA-001)
+    // set_expect_buffer, expect_buffer, clear_expect_buffer, expect_match:
+    //   provided by impl_terminal_search_select! (ARC-003/QA-001)
     // export_html: provided by impl_terminal_exports! (ARC-003/QA-001)
 
+    // === Bidirectional Text ===
+
+    /// Enable or disable the bidi (UAX #9) display pass (off by default)
+    ///
+    /// Applications can still switch to explicit mode with CSI 8 l.
+    fn set_bidi_enabled(&mut self, enabled: bool) {
+        self.inner.set_bidi_enabled(enabled);
+    }
+
+    /// Whether the bidi display pass is enabled
+    fn bidi_enabled(&self) -> bool {
+        self.inner.bidi_enabled()
+    }
+
+    /// Whether rows are currently reordered for display (enabled and the
+    /// application is in implicit mode)
+    fn bidi_active(&self) -> bool {
+        self.inner.bidi_active()
+    }
+
+    /// Paragraph direction: "auto", "ltr" or "rtl" (set by apps with SCP)
+    fn bidi_direction(&self) -> &'static str {
+        match self.inner.bidi_direction() {
+            crate::terminal::BidiDirection::Auto => "auto",
+            crate::terminal::BidiDirection::Ltr => "ltr",
+            crate::terminal::BidiDirection::Rtl => "rtl",
+        }
+    }
+
+    /// Override the paragraph direction: "auto", "ltr" or "rtl"
+    ///
+    /// Raises:
+    ///     ValueError: If direction is not recognized
+    fn set_bidi_direction(&mut self, direction: &str) -> PyResult<()> {
+        let direction = match direction {
+            "auto" => crate::terminal::BidiDirection::Auto,
+            "ltr" => crate::terminal::BidiDirection::Ltr,
+            "rtl" => crate::terminal::BidiDirection::Rtl,
+            _ => {
+                return Err(PyValueError::new_err(
+                    "direction must be 'auto', 'ltr', or 'rtl'",
+                ))
+            }
+        };
+        self.inner.set_bidi_direction(direction);
+        Ok(())
+    }
+
+    /// Get the visual ordering of a screen row
+    ///
+    /// Returns:
+    ///     Dict with rtl (paragraph direction), visual_to_logical,
+    ///     logical_to_visual and levels (odd = right-to-left), or None if the
+    ///     row does not exist
+    fn get_bidi_line(&self, row: usize) -> PyResult<Option<Py<pyo3::types::PyDict>>> {
+        use pyo3::types::PyDict;
+
+        let Some(line) = self.inner.bidi_line(row) else {
+            return Ok(None);
+        };
+        Python::attach(|py| {
+            let dict = PyDict::new(py);
+            dict.set_item("rtl", line.rtl)?;
+            dict.set_item("visual_to_logical", line.visual_to_logical)?;
+            dict.set_item("logical_to_visual", line.logical_to_visual)?;
+            dict.set_item("levels", line.levels)?;
+            Ok(Some(dict.unbind()))
+        })
+    }
+
+    /// Get a screen row as text in display order
+    fn get_visual_line(&self, row: usize) -> Option<String> {
+        self.inner.visual_line(row)
+    }
+
+    /// Export the visible screen as text in display order
+    fn export_visual_text(&self) -> String {
+        self.inner.export_visual_text()
+    }
+
     // === Text Extraction ===
 
     /// Get text lines around a specific row (with context)
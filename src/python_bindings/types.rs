@@ -3,6 +3,7 @@
 //! This module contains the main Python-facing data structures:
 //! - PyAttributes: Cell text attributes (bold, italic, etc.)
 //! - PyScreenSnapshot: Atomic snapshot of terminal screen state
+//! - PyTerminalState: Immutable state handle readable without the terminal lock
 //! - PyShellIntegration: Shell integration (OSC 133) state
 //! - PyGraphic: Sixel graphics representation
 //! - LineCellData: Type alias for row cell data
@@ -198,6 +199,132 @@ impl PyScreenSnapshot {
     }
 }
 
+/// Immutable handle on the visible screen, cursor and modes
+///
+/// Returned by `state_handle()`. The state is shared, not copied, and never
+/// changes, so it can be read from any thread without locking the terminal.
+#[pyclass(name = "TerminalState", frozen)]
+pub struct PyTerminalState {
+    pub(crate) inner: crate::terminal::TerminalStateHandle,
+}
+
+#[pymethods]
+impl PyTerminalState {
+    /// Screen dimensions (cols, rows)
+    fn size(&self) -> (usize, usize) {
+        (self.inner.cols(), self.inner.rows())
+    }
+
+    /// Cursor position (col, row)
+    fn cursor_position(&self) -> (usize, usize) {
+        let cursor = self.inner.cursor();
+        (cursor.col, cursor.row)
+    }
+
+    /// Whether the cursor is visible
+    fn cursor_visible(&self) -> bool {
+        self.inner.cursor().visible
+    }
+
+    /// Cursor style
+    fn cursor_style(&self) -> PyCursorStyle {
+        self.inner.cursor().style.into()
+    }
+
+    /// Whether the alternate screen was showing
+    fn is_alt_screen(&self) -> bool {
+        self.inner.modes().alt_screen
+    }
+
+    /// Window title
+    fn title(&self) -> String {
+        self.inner.title().to_string()
+    }
+
+    /// Total bytes the terminal had processed when the handle was taken
+    fn bytes_processed(&self) -> u64 {
+        self.inner.bytes_processed()
+    }
+
+    /// Get the modes captured in the handle
+    ///
+    /// Returns:
+    ///     Dictionary with "alt_screen", "auto_wrap", "origin_mode",
+    ///     "insert_mode", "reverse_video", "application_cursor",
+    ///     "bracketed_paste", "focus_tracking", "synchronized_updates" (bools),
+    ///     "mouse_mode" and "mouse_encoding" (strings)
+    fn modes(&self) -> PyResult<Py<pyo3::types::PyDict>> {
+        use crate::mouse::{MouseEncoding, MouseMode};
+        let modes = self.inner.modes();
+        Python::attach(|py| {
+            let dict = pyo3::types::PyDict::new(py);
+            dict.set_item("alt_screen", modes.alt_screen)?;
+            dict.set_item("auto_wrap", modes.auto_wrap)?;
+            dict.set_item("origin_mode", modes.origin_mode)?;
+            dict.set_item("insert_mode", modes.insert_mode)?;
+            dict.set_item("reverse_video", modes.reverse_video)?;
+            dict.set_item("application_cursor", modes.application_cursor)?;
+            dict.set_item("bracketed_paste", modes.bracketed_paste)?;
+            dict.set_item("focus_tracking", modes.focus_tracking)?;
+            dict.set_item("synchronized_updates", modes.synchronized_updates)?;
+            let mouse_mode = match modes.mouse_mode {
+                MouseMode::Off => "off",
+                MouseMode::X10 => "x10",
+                MouseMode::Normal => "normal",
+                MouseMode::ButtonEvent => "button",
+                MouseMode::AnyEvent => "any",
+            };
+            dict.set_item("mouse_mode", mouse_mode)?;
+            let mouse_encoding = match modes.mouse_encoding {
+                MouseEncoding::Default => "default",
+                MouseEncoding::Utf8 => "utf8",
+                MouseEncoding::Sgr => "sgr",
+                MouseEncoding::Urxvt => "urxvt",
+            };
+            dict.set_item("mouse_encoding", mouse_encoding)?;
+            Ok(dict.unbind())
+        })
+    }
+
+    /// Get the text of a screen row
+    ///
+    /// Returns:
+    ///     The row text, or None if the row is out of range
+    fn get_line(&self, row: usize) -> Option<String> {
+        self.inner.line(row)
+    }
+
+    /// Get the character at a position, or None if out of range
+    fn get_char(&self, col: usize, row: usize) -> Option<char> {
+        self.inner.grid().get(col, row).map(|cell| cell.c)
+    }
+
+    /// Whether a row continues on the next one
+    fn is_line_wrapped(&self, row: usize) -> bool {
+        self.inner.grid().is_line_wrapped(row)
+    }
+
+    /// Screen text, one line per row with trailing spaces trimmed
+    fn content(&self) -> String {
+        self.inner.text()
+    }
+
+    /// Whether both handles share the same captured state
+    fn same_state(&self, other: &PyTerminalState) -> bool {
+        self.inner.ptr_eq(&other.inner)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "TerminalState(size={}x{}, bytes={}, alt={})",
+            self.inner.cols(),
+            self.inner.rows(),
+            self.inner.bytes_processed(),
+            self.inner.modes().alt_screen
+        )
+    }
+}
+
 /// Shell integration state
 #[par_term_emu_derive::pyo3_get_all]
 #[pyclass(name = "ShellIntegration", from_py_object)]
@@ -589,7 +716,7 @@ pub struct PyTmuxNotification {
     /// Timestamp (for begin/end/error notifications)
     pub timestamp: Option<u64>,
 
-    /// Command number (for begin/end/error notifications)
+    /// Command number (for begin/end/error/command-output notifications)
     pub command_number: Option<u32>,
 
     /// Flags (for begin/end/error notifications)
@@ -613,7 +740,7 @@ pub struct PyTmuxNotification {
     /// Window raw flags (for layout-change notifications)
     pub window_raw_flags: Option<String>,
 
-    /// Raw line (for unknown notifications)
+    /// Raw line (for unknown and command-output notifications)
     pub raw_line: Option<String>,
 }
 
@@ -1151,6 +1278,28 @@ impl From<&crate::tmux_control::TmuxNotification> for PyTmuxNotification {
                 window_raw_flags: None,
                 raw_line: None,
             },
+            TmuxNotification::CommandOutput {
+                command_number,
+                line,
+            } => PyTmuxNotification {
+                notification_type: "command-output".to_string(),
+                command_number: Some(*command_number),
+                raw_line: Some(line.clone()),
+                timestamp: None,
+                flags: None,
+                pane_id: None,
+                window_id: None,
+                session_id: None,
+                name: None,
+                client: None,
+                data: None,
+                delay_ms: None,
+                subscription_name: None,
+                value: None,
+                window_layout: None,
+                window_visible_layout: None,
+                window_raw_flags: None,
+            },
             TmuxNotification::Unknown { line } => PyTmuxNotification {
                 notification_type: "unknown".to_string(),
                 raw_line: Some(line.clone()),
@@ -1219,7 +1368,7 @@ pub struct PySearchMatch {
     pub row: isize,
     /// Column index
     pub col: usize,
-    /// Length of the match
+    /// Width of the match in columns
     pub length: usize,
     /// Matched text
     pub text: String,
@@ -1262,6 +1411,26 @@ impl PyDetectedItem {
     }
 }
 
+impl From<&crate::terminal::DetectedItem> for PyDetectedItem {
+    fn from(item: &crate::terminal::DetectedItem) -> Self {
+        use crate::terminal::DetectedItem;
+        let (item_type, text, col, row, line_number) = match item {
+            DetectedItem::Url(text, col, row) => ("url", text, col, row, None),
+            DetectedItem::FilePath(text, col, row, line) => ("filepath", text, col, row, *line),
+            DetectedItem::GitHash(text, col, row) => ("git_hash", text, col, row, None),
+            DetectedItem::IpAddress(text, col, row) => ("ip", text, col, row, None),
+            DetectedItem::Email(text, col, row) => ("email", text, col, row, None),
+        };
+        PyDetectedItem {
+            item_type: item_type.to_string(),
+            text: text.clone(),
+            row: *row,
+            col: *col,
+            line_number,
+        }
+    }
+}
+
 /// Selection mode
 #[pyclass(name = "SelectionMode", from_py_object)]
 #[derive(Clone)]
@@ -1305,14 +1474,25 @@ pub struct PyScrollbackStats {
     pub memory_bytes: usize,
     /// Whether the scrollback buffer has wrapped (cycled)
     pub has_wrapped: bool,
+    /// Number of lines stored compressed
+    pub compressed_lines: usize,
+    /// Bytes held by the compressed lines
+    pub compressed_bytes: usize,
+    /// Estimated memory usage if no line were compressed
+    pub raw_bytes: usize,
 }
 
 #[pymethods]
 impl PyScrollbackStats {
     fn __repr__(&self) -> String {
         format!(
-            "ScrollbackStats(total_lines={}, memory_bytes={}, has_wrapped={})",
-            self.total_lines, self.memory_bytes, self.has_wrapped
+            "ScrollbackStats(total_lines={}, memory_bytes={}, has_wrapped={}, compressed_lines={}, compressed_bytes={}, raw_bytes={})",
+            self.total_lines,
+            self.memory_bytes,
+            self.has_wrapped,
+            self.compressed_lines,
+            self.compressed_bytes,
+            self.raw_bytes
         )
     }
 }
@@ -1340,6 +1520,45 @@ impl PyBookmark {
     }
 }
 
+/// Navigable mark in the primary screen's scrollback
+#[par_term_emu_derive::pyo3_get_all]
+#[pyclass(name = "Mark", from_py_object)]
+#[derive(Clone)]
+pub struct PyMark {
+    /// Mark ID
+    pub id: usize,
+    /// Absolute row (scrollback length + screen row)
+    pub abs_row: usize,
+    /// "prompt" for marks recorded at shell prompts, "user" for added marks
+    pub kind: String,
+    /// Optional mark name
+    pub name: Option<String>,
+    /// Creation time (Unix millis)
+    pub timestamp: u64,
+}
+
+#[pymethods]
+impl PyMark {
+    fn __repr__(&self) -> String {
+        format!(
+            "Mark(id={}, abs_row={}, kind={:?}, name={:?})",
+            self.id, self.abs_row, self.kind, self.name
+        )
+    }
+}
+
+impl From<&crate::terminal::Mark> for PyMark {
+    fn from(mark: &crate::terminal::Mark) -> Self {
+        PyMark {
+            id: mark.id,
+            abs_row: mark.abs_row,
+            kind: mark.kind.name().to_string(),
+            name: mark.name.clone(),
+            timestamp: mark.timestamp,
+        }
+    }
+}
+
 // === Feature 7: Performance Metrics ===
 
 /// Performance metrics
@@ -1355,6 +1574,7 @@ pub struct PyPerformanceMetrics {
     pub scroll_count: u64,
     pub wrap_count: u64,
     pub escape_sequences: u64,
+    pub latency: PyLatencyMetrics,
 }
 
 #[pymethods]
@@ -1373,6 +1593,76 @@ impl PyPerformanceMetrics {
     }
 }
 
+/// Percentile summary of a set of durations, in microseconds
+#[par_term_emu_derive::pyo3_get_all]
+#[pyclass(name = "LatencyStats", from_py_object)]
+#[derive(Clone)]
+pub struct PyLatencyStats {
+    pub count: usize,
+    pub min_us: u64,
+    pub p50_us: u64,
+    pub p90_us: u64,
+    pub p99_us: u64,
+    pub max_us: u64,
+    pub mean_us: f64,
+}
+
+#[pymethods]
+impl PyLatencyStats {
+    fn __repr__(&self) -> String {
+        format!(
+            "LatencyStats(count={}, p50={}us, p90={}us, p99={}us, max={}us)",
+            self.count, self.p50_us, self.p90_us, self.p99_us, self.max_us
+        )
+    }
+}
+
+impl From<&crate::terminal::LatencyStats> for PyLatencyStats {
+    fn from(s: &crate::terminal::LatencyStats) -> Self {
+        Self {
+            count: s.count,
+            min_us: s.min_us,
+            p50_us: s.p50_us,
+            p90_us: s.p90_us,
+            p99_us: s.p99_us,
+            max_us: s.max_us,
+            mean_us: s.mean_us,
+        }
+    }
+}
+
+/// End-to-end input latency and frame pacing statistics
+#[par_term_emu_derive::pyo3_get_all]
+#[pyclass(name = "LatencyMetrics", from_py_object)]
+#[derive(Clone)]
+pub struct PyLatencyMetrics {
+    pub input_to_output: PyLatencyStats,
+    pub output_to_render: PyLatencyStats,
+    pub input_to_render: PyLatencyStats,
+    pub frame_interval: PyLatencyStats,
+}
+
+#[pymethods]
+impl PyLatencyMetrics {
+    fn __repr__(&self) -> String {
+        format!(
+            "LatencyMetrics(input_to_render_p50={}us, frame_interval_p50={}us)",
+            self.input_to_render.p50_us, self.frame_interval.p50_us
+        )
+    }
+}
+
+impl From<&crate::terminal::LatencyMetrics> for PyLatencyMetrics {
+    fn from(m: &crate::terminal::LatencyMetrics) -> Self {
+        Self {
+            input_to_output: (&m.input_to_output).into(),
+            output_to_render: (&m.output_to_render).into(),
+            input_to_render: (&m.input_to_render).into(),
+            frame_interval: (&m.frame_interval).into(),
+        }
+    }
+}
+
 /// Frame timing
 #[par_term_emu_derive::pyo3_get_all]
 #[pyclass(name = "FrameTiming", from_py_object)]
@@ -1608,6 +1898,43 @@ impl From<&crate::mouse::MousePosition> for PyMousePosition {
     }
 }
 
+/// Recorded cursor movement
+#[par_term_emu_derive::pyo3_get_all]
+#[pyclass(name = "CursorMotion", from_py_object)]
+#[derive(Clone)]
+pub struct PyCursorMotion {
+    pub from_col: usize,
+    pub from_row: usize,
+    pub to_col: usize,
+    pub to_row: usize,
+    /// "text" or "escape_sequence"
+    pub cause: String,
+    pub timestamp: u64,
+}
+
+#[pymethods]
+impl PyCursorMotion {
+    fn __repr__(&self) -> String {
+        format!(
+            "CursorMotion(from=({}, {}), to=({}, {}), cause={}, timestamp={})",
+            self.from_col, self.from_row, self.to_col, self.to_row, self.cause, self.timestamp
+        )
+    }
+}
+
+impl From<&crate::terminal::CursorMotion> for PyCursorMotion {
+    fn from(motion: &crate::terminal::CursorMotion) -> Self {
+        PyCursorMotion {
+            from_col: motion.from.0,
+            from_row: motion.from.1,
+            to_col: motion.to.0,
+            to_row: motion.to.1,
+            cause: motion.cause.name().to_string(),
+            timestamp: motion.timestamp,
+        }
+    }
+}
+
 // === Feature 19: Custom Rendering Hints ===
 
 /// Damage region
@@ -1726,6 +2053,41 @@ impl From<&crate::terminal::EscapeSequenceProfile> for PyEscapeSequenceProfile {
     }
 }
 
+/// Escape sequence the terminal ignored as unsupported
+#[par_term_emu_derive::pyo3_get_all]
+#[pyclass(name = "UnsupportedSequence", from_py_object)]
+#[derive(Clone)]
+pub struct PyUnsupportedSequence {
+    /// "csi", "osc" or "dcs"
+    pub kind: String,
+    pub name: String,
+    pub params: Vec<Vec<u16>>,
+    pub raw: Vec<u8>,
+    pub count: u64,
+}
+
+#[pymethods]
+impl PyUnsupportedSequence {
+    fn __repr__(&self) -> String {
+        format!(
+            "UnsupportedSequence(name={:?}, count={})",
+            self.name, self.count
+        )
+    }
+}
+
+impl From<&crate::terminal::UnsupportedSequence> for PyUnsupportedSequence {
+    fn from(entry: &crate::terminal::UnsupportedSequence) -> Self {
+        PyUnsupportedSequence {
+            kind: entry.kind.as_str().to_lowercase(),
+            name: entry.name.clone(),
+            params: entry.params.clone(),
+            raw: entry.raw.clone(),
+            count: entry.count,
+        }
+    }
+}
+
 /// Profiling data
 #[par_term_emu_derive::pyo3_get_all]
 #[pyclass(name = "ProfilingData", from_py_object)]
@@ -1894,6 +2256,42 @@ impl From<&crate::terminal::RegexMatch> for PyRegexMatch {
     }
 }
 
+/// Result of an expect call
+#[par_term_emu_derive::pyo3_get_all]
+#[pyclass(name = "ExpectMatch", from_py_object)]
+#[derive(Clone)]
+pub struct PyExpectMatch {
+    /// Index of the pattern that matched
+    pub index: usize,
+    /// Text received before the match
+    pub before: String,
+    /// Matched text
+    pub matched: String,
+    /// Capture groups 1..n (None for groups that did not participate)
+    pub groups: Vec<Option<String>>,
+}
+
+#[pymethods]
+impl PyExpectMatch {
+    fn __repr__(&self) -> String {
+        format!(
+            "ExpectMatch(index={}, matched={:?})",
+            self.index, self.matched
+        )
+    }
+}
+
+impl From<crate::terminal::ExpectMatch> for PyExpectMatch {
+    fn from(m: crate::terminal::ExpectMatch) -> Self {
+        PyExpectMatch {
+            index: m.index,
+            before: m.before,
+            matched: m.matched,
+            groups: m.groups,
+        }
+    }
+}
+
 // === Feature 13: Multiplexing ===
 
 /// Pane state
@@ -1908,6 +2306,7 @@ pub struct PyPaneState {
     pub cwd: Option<String>,
     pub content: Vec<String>,
     pub cursor: (usize, usize),
+    pub cursor_style: PyCursorStyle,
     pub alt_screen: bool,
     pub scroll_offset: usize,
     pub created_at: u64,
@@ -1934,6 +2333,7 @@ impl From<&crate::terminal::PaneState> for PyPaneState {
             cwd: state.cwd.clone(),
             content: state.content.clone(),
             cursor: state.cursor,
+            cursor_style: state.cursor_style.into(),
             alt_screen: state.alt_screen,
             scroll_offset: state.scroll_offset,
             created_at: state.created_at,
@@ -2210,6 +2610,7 @@ impl From<&crate::terminal::BenchmarkSuite> for PyBenchmarkSuite {
 pub struct PyComplianceTest {
     pub name: String,
     pub category: String,
+    pub level: String,
     pub passed: bool,
     pub expected: String,
     pub actual: String,
@@ -2231,6 +2632,7 @@ impl From<&crate::terminal::ComplianceTest> for PyComplianceTest {
         PyComplianceTest {
             name: test.name.clone(),
             category: test.category.clone(),
+            level: compliance_level_name(test.level).to_string(),
             passed: test.passed,
             expected: test.expected.clone(),
             actual: test.actual.clone(),
@@ -2239,6 +2641,56 @@ impl From<&crate::terminal::ComplianceTest> for PyComplianceTest {
     }
 }
 
+/// Python name of a compliance level
+pub(crate) fn compliance_level_name(level: crate::terminal::ComplianceLevel) -> &'static str {
+    use crate::terminal::ComplianceLevel;
+
+    match level {
+        ComplianceLevel::VT52 => "vt52",
+        ComplianceLevel::VT100 => "vt100",
+        ComplianceLevel::VT220 => "vt220",
+        ComplianceLevel::VT320 => "vt320",
+        ComplianceLevel::VT420 => "vt420",
+        ComplianceLevel::VT520 => "vt520",
+        ComplianceLevel::XTerm => "xterm",
+    }
+}
+
+/// Compliance totals for one category
+#[par_term_emu_derive::pyo3_get_all]
+#[pyclass(name = "ComplianceCategory", from_py_object)]
+#[derive(Clone)]
+pub struct PyComplianceCategory {
+    pub category: String,
+    pub passed: usize,
+    pub failed: usize,
+    pub compliance_percent: f64,
+}
+
+#[pymethods]
+impl PyComplianceCategory {
+    fn __repr__(&self) -> String {
+        format!(
+            "ComplianceCategory(category={}, passed={}/{}, compliance={:.1}%)",
+            self.category,
+            self.passed,
+            self.passed + self.failed,
+            self.compliance_percent
+        )
+    }
+}
+
+impl From<&crate::terminal::ComplianceCategory> for PyComplianceCategory {
+    fn from(category: &crate::terminal::ComplianceCategory) -> Self {
+        PyComplianceCategory {
+            category: category.category.clone(),
+            passed: category.passed,
+            failed: category.failed,
+            compliance_percent: category.compliance_percent,
+        }
+    }
+}
+
 /// Compliance report
 #[par_term_emu_derive::pyo3_get_all]
 #[pyclass(name = "ComplianceReport", from_py_object)]
@@ -2247,6 +2699,7 @@ pub struct PyComplianceReport {
     pub terminal_info: String,
     pub level: String,
     pub tests: Vec<PyComplianceTest>,
+    pub categories: Vec<PyComplianceCategory>,
     pub passed: usize,
     pub failed: usize,
     pub compliance_percent: f64,
@@ -2267,23 +2720,15 @@ impl PyComplianceReport {
 
 impl From<&crate::terminal::ComplianceReport> for PyComplianceReport {
     fn from(report: &crate::terminal::ComplianceReport) -> Self {
-        use crate::terminal::ComplianceLevel;
-
-        let level = match report.level {
-            ComplianceLevel::VT52 => "vt52",
-            ComplianceLevel::VT100 => "vt100",
-            ComplianceLevel::VT220 => "vt220",
-            ComplianceLevel::VT320 => "vt320",
-            ComplianceLevel::VT420 => "vt420",
-            ComplianceLevel::VT520 => "vt520",
-            ComplianceLevel::XTerm => "xterm",
-        }
-        .to_string();
-
         PyComplianceReport {
             terminal_info: report.terminal_info.clone(),
-            level,
+            level: compliance_level_name(report.level).to_string(),
             tests: report.tests.iter().map(PyComplianceTest::from).collect(),
+            categories: report
+                .categories()
+                .iter()
+                .map(PyComplianceCategory::from)
+                .collect(),
             passed: report.passed,
             failed: report.failed,
             compliance_percent: report.compliance_percent,
@@ -2406,6 +2851,11 @@ pub struct PyCommandExecution {
     pub success: Option<bool>,
     pub output_start_row: Option<usize>,
     pub output_end_row: Option<usize>,
+    pub hostname: Option<String>,
+    pub username: Option<String>,
+    pub shell: Option<String>,
+    pub virtual_env: Option<String>,
+    pub conda_prefix: Option<String>,
 }
 
 #[pymethods]
@@ -2430,6 +2880,11 @@ impl From<&crate::terminal::CommandExecution> for PyCommandExecution {
             success: cmd.success,
             output_start_row: cmd.output_start_row,
             output_end_row: cmd.output_end_row,
+            hostname: cmd.hostname.clone(),
+            username: cmd.username.clone(),
+            shell: cmd.shell.clone(),
+            virtual_env: cmd.virtual_env.clone(),
+            conda_prefix: cmd.conda_prefix.clone(),
         }
     }
 }
@@ -2505,6 +2960,108 @@ impl From<&crate::terminal::CwdChange> for PyCwdChange {
     }
 }
 
+/// Host/user context reported by the shell (OSC 7 / OSC 1337 RemoteHost)
+#[par_term_emu_derive::pyo3_get_all]
+#[pyclass(name = "HostContext", from_py_object)]
+#[derive(Clone)]
+pub struct PyHostContext {
+    pub hostname: Option<String>,
+    pub username: Option<String>,
+    pub is_remote: bool,
+}
+
+#[pymethods]
+impl PyHostContext {
+    fn __repr__(&self) -> String {
+        format!(
+            "HostContext(host={:?}, user={:?}, remote={})",
+            self.hostname, self.username, self.is_remote
+        )
+    }
+}
+
+impl From<&crate::terminal::HostContext> for PyHostContext {
+    fn from(ctx: &crate::terminal::HostContext) -> Self {
+        PyHostContext {
+            hostname: ctx.hostname.clone(),
+            username: ctx.username.clone(),
+            is_remote: ctx.is_remote,
+        }
+    }
+}
+
+/// Session environment reported by shell integration
+#[par_term_emu_derive::pyo3_get_all]
+#[pyclass(name = "ShellEnvironment", from_py_object)]
+#[derive(Clone)]
+pub struct PyShellEnvironment {
+    pub cwd: Option<String>,
+    pub hostname: Option<String>,
+    pub username: Option<String>,
+    pub is_remote: bool,
+    pub shell: Option<String>,
+    pub shell_integration_version: Option<String>,
+    pub virtual_env: Option<String>,
+    pub conda_prefix: Option<String>,
+}
+
+#[pymethods]
+impl PyShellEnvironment {
+    fn __repr__(&self) -> String {
+        format!(
+            "ShellEnvironment(cwd={:?}, host={:?}, user={:?}, shell={:?})",
+            self.cwd, self.hostname, self.username, self.shell
+        )
+    }
+}
+
+impl From<&crate::terminal::ShellEnvironment> for PyShellEnvironment {
+    fn from(env: &crate::terminal::ShellEnvironment) -> Self {
+        PyShellEnvironment {
+            cwd: env.cwd.clone(),
+            hostname: env.hostname.clone(),
+            username: env.username.clone(),
+            is_remote: env.is_remote,
+            shell: env.shell.clone(),
+            shell_integration_version: env.shell_integration_version.clone(),
+            virtual_env: env.virtual_env.clone(),
+            conda_prefix: env.conda_prefix.clone(),
+        }
+    }
+}
+
+/// One change to the shell environment
+#[par_term_emu_derive::pyo3_get_all]
+#[pyclass(name = "EnvironmentChange", from_py_object)]
+#[derive(Clone)]
+pub struct PyEnvironmentChange {
+    pub key: String,
+    pub value: Option<String>,
+    pub old_value: Option<String>,
+    pub timestamp: u64,
+}
+
+#[pymethods]
+impl PyEnvironmentChange {
+    fn __repr__(&self) -> String {
+        format!(
+            "EnvironmentChange(key={:?}, value={:?}, old={:?})",
+            self.key, self.value, self.old_value
+        )
+    }
+}
+
+impl From<&crate::terminal::EnvironmentChange> for PyEnvironmentChange {
+    fn from(change: &crate::terminal::EnvironmentChange) -> Self {
+        PyEnvironmentChange {
+            key: change.key.clone(),
+            value: change.value.clone(),
+            old_value: change.old_value.clone(),
+            timestamp: change.timestamp,
+        }
+    }
+}
+
 // === Feature 37: Terminal Notifications ===
 
 /// Notification event
@@ -2517,6 +3074,8 @@ pub struct PyNotificationEvent {
     pub message: Option<String>,
     pub timestamp: u64,
     pub delivered: bool,
+    /// Suppression reason ("QuietHours", "Focused", "TriggerOverride") or None
+    pub suppressed_reason: Option<String>,
 }
 
 #[pymethods]
@@ -2550,6 +3109,14 @@ impl From<&crate::terminal::NotificationEvent> for PyNotificationEvent {
             message: event.message.clone(),
             timestamp: event.timestamp,
             delivered: event.delivered,
+            suppressed_reason: event.suppressed.map(|r| {
+                match r {
+                    crate::terminal::SuppressionReason::QuietHours => "QuietHours",
+                    crate::terminal::SuppressionReason::Focused => "Focused",
+                    crate::terminal::SuppressionReason::TriggerOverride => "TriggerOverride",
+                }
+                .to_string()
+            }),
         }
     }
 }
@@ -2628,6 +3195,7 @@ impl From<&PyNotificationConfig> for crate::terminal::NotificationConfig {
             activity_threshold: config.activity_threshold,
             silence_enabled: config.silence_enabled,
             silence_threshold: config.silence_threshold,
+            schedule: Default::default(),
         }
     }
 }
@@ -2714,7 +3282,8 @@ impl From<&crate::terminal::trigger::TriggerMatch> for PyTriggerMatch {
 #[derive(Clone)]
 pub struct PyTriggerAction {
     /// Action type: "highlight", "notify", "mark_line", "set_variable",
-    /// "run_command", "play_sound", "send_text", "split_pane", "stop"
+    /// "set_user_var", "emit_event", "mark_command_failed", "run_command",
+    /// "play_sound", "send_text", "split_pane", "stop"
     pub action_type: String,
     /// Action parameters (key-value pairs, type-specific)
     pub params: std::collections::HashMap<String, String>,
@@ -2726,7 +3295,8 @@ impl PyTriggerAction {
     ///
     /// Args:
     ///     action_type: Action type string (highlight, notify, mark_line,
-    ///         set_variable, run_command, play_sound, send_text, split_pane, stop)
+    ///         set_variable, set_user_var, emit_event, mark_command_failed,
+    ///         run_command, play_sound, send_text, split_pane, stop)
     ///     params: Dictionary of action parameters
     ///
     /// Returns:
@@ -2736,6 +3306,7 @@ impl PyTriggerAction {
     ///     >>> action = TriggerAction("highlight", {"bg_r": "255", "bg_g": "0", "bg_b": "0"})
     ///     >>> action = TriggerAction("notify", {"title": "Alert", "message": "Error found: $1"})
     ///     >>> action = TriggerAction("split_pane", {"direction": "horizontal", "focus_new_pane": "true"})
+    ///     >>> action = TriggerAction("emit_event", {"name": "build-failed:$1"})
     #[new]
     #[pyo3(signature = (action_type, params=None))]
     fn new(action_type: String, params: Option<std::collections::HashMap<String, String>>) -> Self {
@@ -2792,6 +3363,14 @@ impl PyTriggerAction {
                 name: self.params.get("name").cloned().unwrap_or_default(),
                 value: self.params.get("value").cloned().unwrap_or_default(),
             }),
+            "set_user_var" => Ok(TriggerAction::SetUserVar {
+                name: self.params.get("name").cloned().unwrap_or_default(),
+                value: self.params.get("value").cloned().unwrap_or_default(),
+            }),
+            "emit_event" => Ok(TriggerAction::EmitEvent {
+                name: self.params.get("name").cloned().unwrap_or_default(),
+            }),
+            "mark_command_failed" => Ok(TriggerAction::MarkCommandFailed),
             "run_command" => {
                 let args: Vec<String> = self
                     .params
@@ -3062,6 +3641,22 @@ impl PyRecordingSession {
         )
     }
 
+    /// Parse an asciicast v1/v2 recording (e.g. a `.cast` file's contents)
+    #[staticmethod]
+    fn from_asciicast(data: &str) -> PyResult<Self> {
+        crate::terminal::RecordingSession::from_asciicast(data)
+            .map(Self::from)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    /// Parse a recording written by export_json()
+    #[staticmethod]
+    fn from_json(data: &str) -> PyResult<Self> {
+        crate::terminal::RecordingSession::from_json(data)
+            .map(Self::from)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
     /// Get recording size (cols, rows)
     fn get_size(&self) -> (usize, usize) {
         self.inner.initial_size
@@ -3136,6 +3731,7 @@ pub struct PyMacroEvent {
     pub event_type: String,
     pub timestamp: u64,
     pub key: Option<String>,
+    pub data: Option<String>,
     pub duration: Option<u64>,
     pub label: Option<String>,
 }
@@ -3149,6 +3745,11 @@ impl PyMacroEvent {
                 self.key.as_ref().unwrap(),
                 self.timestamp
             ),
+            "input" => format!(
+                "MacroEvent(input={:?}, timestamp={}ms)",
+                self.data.as_deref().unwrap_or_default(),
+                self.timestamp
+            ),
             "delay" => format!(
                 "MacroEvent(delay={}ms, timestamp={}ms)",
                 self.duration.unwrap(),
@@ -3170,6 +3771,15 @@ impl From<&crate::macros::MacroEvent> for PyMacroEvent {
                 event_type: "key".to_string(),
                 timestamp: *timestamp,
                 key: Some(key.clone()),
+                data: None,
+                duration: None,
+                label: None,
+            },
+            crate::macros::MacroEvent::Input { data, timestamp } => PyMacroEvent {
+                event_type: "input".to_string(),
+                timestamp: *timestamp,
+                key: None,
+                data: Some(data.clone()),
                 duration: None,
                 label: None,
             },
@@ -3180,6 +3790,7 @@ impl From<&crate::macros::MacroEvent> for PyMacroEvent {
                 event_type: "delay".to_string(),
                 timestamp: *timestamp,
                 key: None,
+                data: None,
                 duration: Some(*duration),
                 label: None,
             },
@@ -3187,6 +3798,7 @@ impl From<&crate::macros::MacroEvent> for PyMacroEvent {
                 event_type: "screenshot".to_string(),
                 timestamp: *timestamp,
                 key: None,
+                data: None,
                 duration: None,
                 label: label.clone(),
             },
@@ -3225,6 +3837,11 @@ impl PyMacro {
         self.inner.add_key(key);
     }
 
+    /// Add a raw input event, sent verbatim on playback
+    fn add_input(&mut self, data: String) {
+        self.inner.add_input(data);
+    }
+
     /// Add a delay event
     fn add_delay(&mut self, duration_ms: u64) {
         self.inner.add_delay(duration_ms);
@@ -3270,6 +3887,36 @@ impl PyMacro {
             .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
     }
 
+    /// Save to JSON file
+    fn save_json(&self, path: String) -> PyResult<()> {
+        self.inner
+            .save_json(path)
+            .map_err(|e| pyo3::exceptions::PyIOError::new_err(e.to_string()))
+    }
+
+    /// Load from JSON file
+    #[staticmethod]
+    fn load_json(path: String) -> PyResult<Self> {
+        crate::macros::Macro::load_json(path)
+            .map(|inner| PyMacro { inner })
+            .map_err(|e| pyo3::exceptions::PyIOError::new_err(e.to_string()))
+    }
+
+    /// Convert to JSON string
+    fn to_json(&self) -> PyResult<String> {
+        self.inner
+            .to_json()
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    /// Parse from JSON string
+    #[staticmethod]
+    fn from_json(json: String) -> PyResult<Self> {
+        crate::macros::Macro::from_json(&json)
+            .map(|inner| PyMacro { inner })
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
     /// Get macro name
     #[getter]
     fn name(&self) -> String {
@@ -0,0 +1,1066 @@
+//! ReGIS (Remote Graphic Instruction Set) vector graphics
+//!
+//! ReGIS is the DEC VT240/VT330/VT340 vector graphics language. Programs
+//! draw on a full-screen graphics plane by sending command strings through
+//! a DCS sequence:
+//!
+//! `DCS Pm p <commands> ST`
+//!
+//! Pm = 0 or 2 resumes with the previous drawing state, 1 or 3 starts over
+//! (position, writing colors and addressing are reset; the picture is kept
+//! until `S(E)` erases it).
+//!
+//! Commands are a letter followed by coordinates `[x,y]` (absolute, or
+//! relative with a leading sign, either component may be omitted), options
+//! in parentheses and quoted strings:
+//!
+//! - `P` position: `P[x,y]`, `P(B)` / `P(E)` push and pop the position
+//! - `V` vectors: lines from the current position through each point,
+//!   `V[]` plots a dot, `V(B)` / `V(E)` closes back to the saved position
+//! - `C` circles: `C[x,y]` around the current position through the point,
+//!   `C(C)[x,y]` around the point through the current position, `C(A<deg>)`
+//!   draws an arc counter-clockwise (clockwise for negative degrees)
+//! - `F` fill: `F(V[..]..)` or `F(C[..])` fills the enclosed polygon
+//! - `W` writing controls: `I<n>` or `I(<color>)` selects the foreground,
+//!   `E` draws with the background, `N1` swaps foreground and background,
+//!   `R` / `V` return to normal drawing
+//! - `S` screen controls: `E` erases, `I<n>` or `I(<color>)` sets the
+//!   background, `A[x1,y1][x2,y2]` sets the logical address space,
+//!   `M<n>(<color>)...` redefines output map (color register) entries
+//!
+//! Colors are given by letter (`D R G B C Y M W`) or as DEC HLS
+//! (`(H<0-360>L<0-100>S<0-100>)`, hue 0 is blue). Commands may also carry
+//! temporary writing controls, e.g. `V(W(I2))[100,100]`.
+//!
+//! Text (`T`), alphabet loading (`L`), reports (`R`) and macrographs (`@`)
+//! are parsed and skipped.
+//!
+//! The default address space is 800×480 (the VT340 screen), mapped onto a
+//! raster bounded by [`RegisLimits`]. Rendering work per sequence is capped
+//! so hostile input cannot stall the terminal.
+use crate::sixel::{SixelColor, SixelParser};
+
+/// Hard upper bounds for ReGIS resources, used to clamp user limits
+pub const REGIS_HARD_MAX_WIDTH: usize = 4096;
+pub const REGIS_HARD_MAX_HEIGHT: usize = 4096;
+pub const REGIS_HARD_MAX_BYTES: usize = 16 * 1024 * 1024;
+
+/// Default per-terminal ReGIS limits (the VT340 screen resolution)
+pub const REGIS_DEFAULT_MAX_WIDTH: usize = 800;
+pub const REGIS_DEFAULT_MAX_HEIGHT: usize = 480;
+pub const REGIS_DEFAULT_MAX_BYTES: usize = 1024 * 1024;
+
+/// Number of output map entries (color registers) of a VT340
+pub const REGIS_COLOR_REGISTERS: usize = 16;
+
+/// Default logical address space (x1, y1, x2, y2)
+const DEFAULT_ADDRESS: (f64, f64, f64, f64) = (0.0, 0.0, 799.0, 479.0);
+
+/// Pixel operations allowed per DCS sequence
+const MAX_WORK: usize = 50_000_000;
+
+/// Maximum depth of nested option lists
+const MAX_NESTING: usize = 16;
+
+/// Maximum entries on the position stack
+const MAX_POSITION_STACK: usize = 16;
+
+/// Maximum vertices of one filled polygon
+const MAX_FILL_POINTS: usize = 4096;
+
+/// Raster value of pixels nothing has drawn on
+const UNSET: u8 = u8::MAX;
+
+/// Per-terminal ReGIS resource limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegisLimits {
+    /// Raster width in pixels
+    pub max_width: usize,
+    /// Raster height in pixels
+    pub max_height: usize,
+    /// Bytes of command data accepted per DCS sequence
+    pub max_bytes: usize,
+}
+
+impl RegisLimits {
+    pub fn new(max_width: usize, max_height: usize, max_bytes: usize) -> Self {
+        Self {
+            max_width: max_width.clamp(1, REGIS_HARD_MAX_WIDTH),
+            max_height: max_height.clamp(1, REGIS_HARD_MAX_HEIGHT),
+            max_bytes: max_bytes.clamp(1, REGIS_HARD_MAX_BYTES),
+        }
+    }
+}
+
+impl Default for RegisLimits {
+    fn default() -> Self {
+        Self::new(
+            REGIS_DEFAULT_MAX_WIDTH,
+            REGIS_DEFAULT_MAX_HEIGHT,
+            REGIS_DEFAULT_MAX_BYTES,
+        )
+    }
+}
+
+/// One coordinate component
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Component {
+    Keep,
+    Absolute(f64),
+    Relative(f64),
+}
+
+impl Component {
+    fn resolve(self, current: f64) -> f64 {
+        match self {
+            Component::Keep => current,
+            Component::Absolute(v) => v,
+            Component::Relative(v) => current + v,
+        }
+    }
+}
+
+/// Argument of a command or option
+#[derive(Debug, Clone, PartialEq)]
+enum Arg {
+    Coord(Component, Component),
+    Options(Vec<Item>),
+    Text(String),
+}
+
+/// A command (top level) or option (inside parentheses): a letter, an
+/// optional number and its arguments. Bare numbers use the name `#`.
+#[derive(Debug, Clone, PartialEq)]
+struct Item {
+    name: char,
+    number: Option<f64>,
+    args: Vec<Arg>,
+}
+
+impl Item {
+    fn options(&self) -> impl Iterator<Item = &Item> {
+        self.args.iter().flat_map(|arg| match arg {
+            Arg::Options(items) => items.as_slice(),
+            _ => &[],
+        })
+    }
+}
+
+/// Tokenizer building the command tree
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    depth: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            pos: 0,
+            depth: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.pos).copied()
+    }
+
+    /// Parse items until `close` (consumed) or the end of input
+    fn items(&mut self, close: Option<u8>) -> Vec<Item> {
+        let mut items: Vec<Item> = Vec::new();
+        while let Some(byte) = self.peek() {
+            if Some(byte) == close {
+                self.pos += 1;
+                break;
+            }
+            match byte {
+                b'A'..=b'Z' | b'a'..=b'z' => {
+                    self.pos += 1;
+                    let number = self.number();
+                    let args = self.args();
+                    items.push(Item {
+                        name: byte.to_ascii_uppercase() as char,
+                        number,
+                        args,
+                    });
+                }
+                b'0'..=b'9' | b'+' | b'-' | b'.' => {
+                    let number = self.number();
+                    if number.is_none() {
+                        self.pos += 1;
+                        continue;
+                    }
+                    let args = self.args();
+                    items.push(Item {
+                        name: '#',
+                        number,
+                        args,
+                    });
+                }
+                b'(' | b'[' | b'\'' | b'"' => {
+                    let args = self.args();
+                    if let Some(last) = items.last_mut() {
+                        last.args.extend(args);
+                    }
+                }
+                b'@' => self.skip_macrograph(),
+                _ => self.pos += 1,
+            }
+        }
+        items
+    }
+
+    /// Arguments directly following a command or option letter
+    fn args(&mut self) -> Vec<Arg> {
+        let mut args = Vec::new();
+        loop {
+            self.skip_blanks();
+            match self.peek() {
+                Some(b'(') => {
+                    self.pos += 1;
+                    if self.depth >= MAX_NESTING {
+                        self.skip_group(b'(', b')');
+                        continue;
+                    }
+                    self.depth += 1;
+                    let items = self.items(Some(b')'));
+                    self.depth -= 1;
+                    args.push(Arg::Options(items));
+                }
+                Some(b'[') => {
+                    self.pos += 1;
+                    args.push(self.coord());
+                }
+                Some(quote @ (b'\'' | b'"')) => {
+                    self.pos += 1;
+                    args.push(Arg::Text(self.text(quote)));
+                }
+                _ => return args,
+            }
+        }
+    }
+
+    fn skip_blanks(&mut self) {
+        while matches!(self.peek(), Some(b) if b.is_ascii_whitespace() || b.is_ascii_control()) {
+            self.pos += 1;
+        }
+    }
+
+    /// Skip to the matching close bracket (the open one is consumed)
+    fn skip_group(&mut self, open: u8, close: u8) {
+        let mut level = 1usize;
+        while let Some(byte) = self.peek() {
+            self.pos += 1;
+            if byte == open {
+                level += 1;
+            } else if byte == close {
+                level -= 1;
+                if level == 0 {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// `@:X ... @;` defines a macrograph, `@X` invokes one
+    fn skip_macrograph(&mut self) {
+        self.pos += 1;
+        if self.peek() == Some(b':') {
+            while self.pos < self.data.len() {
+                if self.data[self.pos] == b'@' && self.data.get(self.pos + 1) == Some(&b';') {
+                    self.pos += 2;
+                    return;
+                }
+                self.pos += 1;
+            }
+        } else {
+            self.pos += 1;
+        }
+    }
+
+    fn number(&mut self) -> Option<f64> {
+        self.skip_blanks();
+        let start = self.pos;
+        if matches!(self.peek(), Some(b'+' | b'-')) {
+            self.pos += 1;
+        }
+        while matches!(self.peek(), Some(b'0'..=b'9' | b'.')) {
+            self.pos += 1;
+        }
+        let text = std::str::from_utf8(&self.data[start..self.pos]).ok()?;
+        match text.parse::<f64>() {
+            Ok(value) if value.is_finite() => Some(value.clamp(-1e6, 1e6)),
+            _ => {
+                self.pos = start;
+                None
+            }
+        }
+    }
+
+    /// `[x,y]` (the opening bracket is consumed)
+    fn coord(&mut self) -> Arg {
+        let start = self.pos;
+        while matches!(self.peek(), Some(b) if b != b']') {
+            self.pos += 1;
+        }
+        let body = String::from_utf8_lossy(&self.data[start..self.pos]).into_owned();
+        self.pos += 1;
+
+        let mut parts = body.split(',').map(|part| {
+            let part = part.trim();
+            match part.parse::<f64>() {
+                Ok(value) if value.is_finite() => {
+                    let value = value.clamp(-1e6, 1e6);
+                    if part.starts_with(['+', '-']) {
+                        Component::Relative(value)
+                    } else {
+                        Component::Absolute(value)
+                    }
+                }
+                _ => Component::Keep,
+            }
+        });
+        let x = parts.next().unwrap_or(Component::Keep);
+        let y = parts.next().unwrap_or(Component::Keep);
+        Arg::Coord(x, y)
+    }
+
+    /// Quoted string (the opening quote is consumed); doubled quotes escape
+    fn text(&mut self, quote: u8) -> String {
+        let mut bytes = Vec::new();
+        while let Some(byte) = self.peek() {
+            self.pos += 1;
+            if byte == quote {
+                if self.peek() == Some(quote) {
+                    self.pos += 1;
+                } else {
+                    break;
+                }
+            }
+            bytes.push(byte);
+        }
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+}
+
+/// Writing controls (`W`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Writing {
+    foreground: u8,
+    erase: bool,
+    negative: bool,
+}
+
+impl Default for Writing {
+    fn default() -> Self {
+        Self {
+            foreground: 7,
+            erase: false,
+            negative: false,
+        }
+    }
+}
+
+/// ReGIS interpreter holding the graphics plane and drawing state
+#[derive(Debug, Clone)]
+pub struct RegisParser {
+    width: usize,
+    height: usize,
+    /// Output map index per pixel (`UNSET` = never drawn)
+    raster: Vec<u8>,
+    palette: [SixelColor; REGIS_COLOR_REGISTERS],
+    background: u8,
+    writing: Writing,
+    address: (f64, f64, f64, f64),
+    position: (f64, f64),
+    position_stack: Vec<(f64, f64)>,
+    /// Polygon collected while inside `F(...)`
+    fill_path: Option<Vec<(f64, f64)>>,
+    work: usize,
+}
+
+impl RegisParser {
+    /// Create an empty graphics plane sized by `limits`
+    pub fn new(limits: RegisLimits) -> Self {
+        let default_palette = SixelParser::default_palette();
+        let palette = std::array::from_fn(|i| {
+            default_palette
+                .get(&i)
+                .copied()
+                .unwrap_or(SixelColor::new(0, 0, 0))
+        });
+        Self {
+            width: limits.max_width,
+            height: limits.max_height,
+            raster: vec![UNSET; limits.max_width * limits.max_height],
+            palette,
+            background: 0,
+            writing: Writing::default(),
+            address: DEFAULT_ADDRESS,
+            position: (0.0, 0.0),
+            position_stack: Vec::new(),
+            fill_path: None,
+            work: 0,
+        }
+    }
+
+    /// Raster width in pixels
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Raster height in pixels
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Current position in logical coordinates
+    pub fn position(&self) -> (i32, i32) {
+        (
+            self.position.0.round() as i32,
+            self.position.1.round() as i32,
+        )
+    }
+
+    /// Output map entry `index` (wraps at [`REGIS_COLOR_REGISTERS`])
+    pub fn color(&self, index: usize) -> SixelColor {
+        self.palette[index % REGIS_COLOR_REGISTERS]
+    }
+
+    /// Reset position, writing controls and addressing (`DCS 1 p`).
+    /// The picture and output map are kept.
+    pub fn reset_state(&mut self) {
+        self.writing = Writing::default();
+        self.address = DEFAULT_ADDRESS;
+        self.position = (0.0, 0.0);
+        self.position_stack.clear();
+    }
+
+    /// Interpret a block of ReGIS commands
+    pub fn parse(&mut self, data: &[u8]) {
+        self.work = 0;
+        let commands = Reader::new(data).items(None);
+        for command in &commands {
+            if self.work >= MAX_WORK {
+                break;
+            }
+            self.execute(command);
+        }
+    }
+
+    /// Check if nothing has been drawn on the plane
+    pub fn is_blank(&self) -> bool {
+        self.raster.iter().all(|&index| index == UNSET)
+    }
+
+    /// Render the plane as RGBA; pixels nothing has drawn are transparent
+    pub fn to_rgba(&self) -> Vec<u8> {
+        let mut pixels = Vec::with_capacity(self.raster.len() * 4);
+        for &index in &self.raster {
+            if index == UNSET {
+                pixels.extend_from_slice(&[0, 0, 0, 0]);
+            } else {
+                let color = self.color(index as usize);
+                pixels.extend_from_slice(&[color.r, color.g, color.b, 255]);
+            }
+        }
+        pixels
+    }
+
+    /// Get the RGBA color of a pixel (`None` if undrawn or out of range)
+    pub fn pixel(&self, x: usize, y: usize) -> Option<(u8, u8, u8, u8)> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        match self.raster[y * self.width + x] {
+            UNSET => None,
+            index => {
+                let color = self.color(index as usize);
+                Some((color.r, color.g, color.b, 255))
+            }
+        }
+    }
+
+    fn execute(&mut self, command: &Item) {
+        match command.name {
+            'P' | 'V' | 'C' | 'F' => {
+                // Temporary writing controls, e.g. V(W(I2))[..]
+                let saved = self.writing;
+                for option in command.options().filter(|o| o.name == 'W') {
+                    self.apply_writing(option);
+                }
+                match command.name {
+                    'P' => self.position_command(command),
+                    'V' => self.vector_command(command),
+                    'C' => self.circle_command(command),
+                    _ => self.fill_command(command),
+                }
+                self.writing = saved;
+            }
+            'W' => self.apply_writing(command),
+            'S' => self.screen_command(command),
+            _ => {}
+        }
+    }
+
+    fn position_command(&mut self, command: &Item) {
+        for arg in &command.args {
+            match arg {
+                Arg::Coord(x, y) => self.position = self.resolve(*x, *y),
+                Arg::Options(options) => {
+                    for option in options {
+                        match option.name {
+                            'B' | 'S' => self.push_position(),
+                            'E' => {
+                                if let Some(saved) = self.position_stack.pop() {
+                                    self.position = saved;
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                Arg::Text(_) => {}
+            }
+        }
+    }
+
+    fn vector_command(&mut self, command: &Item) {
+        for arg in &command.args {
+            match arg {
+                Arg::Coord(x, y) => {
+                    let target = self.resolve(*x, *y);
+                    self.line_to(target);
+                }
+                Arg::Options(options) => {
+                    for option in options {
+                        match option.name {
+                            'B' | 'S' => self.push_position(),
+                            'E' => {
+                                if let Some(saved) = self.position_stack.pop() {
+                                    self.line_to(saved);
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                Arg::Text(_) => {}
+            }
+        }
+    }
+
+    fn circle_command(&mut self, command: &Item) {
+        let mut center_at_point = false;
+        let mut arc_degrees: Option<f64> = None;
+        for arg in &command.args {
+            match arg {
+                Arg::Options(options) => {
+                    for option in options {
+                        match option.name {
+                            'C' => center_at_point = true,
+                            'A' => arc_degrees = option.number,
+                            _ => {}
+                        }
+                    }
+                }
+                Arg::Coord(x, y) => {
+                    let point = self.resolve(*x, *y);
+                    let (center, start) = if center_at_point {
+                        (point, self.position)
+                    } else {
+                        (self.position, point)
+                    };
+                    self.arc(center, start, arc_degrees.unwrap_or(360.0));
+                }
+                Arg::Text(_) => {}
+            }
+        }
+    }
+
+    fn fill_command(&mut self, command: &Item) {
+        let start = self.position;
+        self.fill_path = Some(vec![self.to_pixel(start)]);
+        for option in command.options() {
+            if self.work >= MAX_WORK {
+                break;
+            }
+            match option.name {
+                'V' | 'C' | 'P' | 'W' => self.execute(option),
+                _ => {}
+            }
+        }
+        if let Some(path) = self.fill_path.take() {
+            self.fill_polygon(&path);
+            // Outline so thin shapes stay visible
+            for pair in path.windows(2) {
+                self.draw_line(pair[0], pair[1]);
+            }
+        }
+    }
+
+    fn apply_writing(&mut self, command: &Item) {
+        for option in command.options() {
+            match option.name {
+                'I' => {
+                    if let Some(index) = self.color_index(option) {
+                        self.writing.foreground = index;
+                    }
+                }
+                'E' => self.writing.erase = true,
+                'R' | 'V' | 'C' => self.writing.erase = false,
+                'N' => self.writing.negative = option.number.unwrap_or(0.0) != 0.0,
+                _ => {}
+            }
+        }
+    }
+
+    fn screen_command(&mut self, command: &Item) {
+        let options: Vec<&Item> = command.options().collect();
+        let mut i = 0;
+        while i < options.len() {
+            let option = options[i];
+            match option.name {
+                'E' => {
+                    self.work += self.raster.len();
+                    self.raster.fill(self.background);
+                }
+                'I' => {
+                    if let Some(index) = self.color_index(option) {
+                        self.background = index;
+                    }
+                }
+                'A' => self.set_address(option),
+                'M' => {
+                    // M<n>(<color>) followed by more <n>(<color>) pairs
+                    self.define_color(option);
+                    while let Some(next) = options.get(i + 1).filter(|o| o.name == '#') {
+                        self.define_color(next);
+                        i += 1;
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+
+    fn set_address(&mut self, option: &Item) {
+        let corners: Vec<(Component, Component)> = option
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                Arg::Coord(x, y) => Some((*x, *y)),
+                _ => None,
+            })
+            .collect();
+        let (x1, y1, x2, y2) = self.address;
+        let mut address = self.address;
+        if let Some((x, y)) = corners.first() {
+            address.0 = x.resolve(x1);
+            address.1 = y.resolve(y1);
+        }
+        if let Some((x, y)) = corners.get(1) {
+            address.2 = x.resolve(x2);
+            address.3 = y.resolve(y2);
+        }
+        if address.0 != address.2 && address.1 != address.3 {
+            self.address = address;
+        }
+    }
+
+    fn define_color(&mut self, option: &Item) {
+        let Some(index) = option.number.filter(|n| *n >= 0.0) else {
+            return;
+        };
+        let spec: Vec<Item> = option.options().cloned().collect();
+        if let Some(color) = parse_color(&spec) {
+            self.palette[index as usize % REGIS_COLOR_REGISTERS] = color;
+        }
+    }
+
+    /// `I<n>` or `I(<color>)`: the output map entry to draw with
+    fn color_index(&self, option: &Item) -> Option<u8> {
+        if let Some(number) = option.number {
+            return Some((number.max(0.0) as usize % REGIS_COLOR_REGISTERS) as u8);
+        }
+        let spec: Vec<Item> = option.options().cloned().collect();
+        let color = parse_color(&spec)?;
+        let distance = |c: &SixelColor| {
+            let dr = c.r as i32 - color.r as i32;
+            let dg = c.g as i32 - color.g as i32;
+            let db = c.b as i32 - color.b as i32;
+            dr * dr + dg * dg + db * db
+        };
+        (0..REGIS_COLOR_REGISTERS)
+            .min_by_key(|&i| distance(&self.palette[i]))
+            .map(|i| i as u8)
+    }
+
+    fn push_position(&mut self) {
+        if self.position_stack.len() < MAX_POSITION_STACK {
+            self.position_stack.push(self.position);
+        }
+    }
+
+    fn resolve(&self, x: Component, y: Component) -> (f64, f64) {
+        (x.resolve(self.position.0), y.resolve(self.position.1))
+    }
+
+    /// Map logical coordinates to raster pixels
+    fn to_pixel(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        let (x1, y1, x2, y2) = self.address;
+        let px = (x - x1) * (self.width.saturating_sub(1)) as f64 / (x2 - x1);
+        let py = (y - y1) * (self.height.saturating_sub(1)) as f64 / (y2 - y1);
+        (px, py)
+    }
+
+    /// Draw from the current position to `target` and move there
+    fn line_to(&mut self, target: (f64, f64)) {
+        let from = self.to_pixel(self.position);
+        let to = self.to_pixel(target);
+        self.position = target;
+        match &mut self.fill_path {
+            Some(path) => {
+                if path.len() < MAX_FILL_POINTS {
+                    path.push(to);
+                }
+            }
+            None => self.draw_line(from, to),
+        }
+    }
+
+    /// Circle or arc around `center` starting at `start`
+    fn arc(&mut self, center: (f64, f64), start: (f64, f64), degrees: f64) {
+        let c = self.to_pixel(center);
+        let s = self.to_pixel(start);
+        let (dx, dy) = (s.0 - c.0, s.1 - c.1);
+        let radius = dx.hypot(dy);
+        let sweep = degrees.clamp(-360.0, 360.0).to_radians();
+        let segments = ((radius * sweep.abs()).ceil() as usize).clamp(8, 720);
+        self.work += segments;
+        let start_angle = dy.atan2(dx);
+        let point = |i: usize| {
+            // Screen y grows downwards, so counter-clockwise subtracts
+            let angle = start_angle - sweep * i as f64 / segments as f64;
+            (c.0 + radius * angle.cos(), c.1 + radius * angle.sin())
+        };
+        match &mut self.fill_path {
+            Some(path) => {
+                for i in 0..=segments {
+                    if path.len() >= MAX_FILL_POINTS {
+                        break;
+                    }
+                    path.push(point(i));
+                }
+            }
+            None => {
+                for i in 0..segments {
+                    self.draw_line(point(i), point(i + 1));
+                }
+            }
+        }
+    }
+
+    fn draw_color(&self) -> u8 {
+        let (foreground, background) = if self.writing.negative {
+            (self.background, self.writing.foreground)
+        } else {
+            (self.writing.foreground, self.background)
+        };
+        if self.writing.erase {
+            background
+        } else {
+            foreground
+        }
+    }
+
+    fn plot(&mut self, x: i64, y: i64, color: u8) {
+        if x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height {
+            self.raster[y as usize * self.width + x as usize] = color;
+        }
+    }
+
+    /// Draw a line in pixel space, clipped to the raster
+    fn draw_line(&mut self, from: (f64, f64), to: (f64, f64)) {
+        let color = self.draw_color();
+        let Some((from, to)) = self.clip(from, to) else {
+            return;
+        };
+        let steps = (to.0 - from.0).abs().max((to.1 - from.1).abs()).ceil() as usize;
+        self.work += steps + 1;
+        for i in 0..=steps {
+            let t = if steps == 0 {
+                0.0
+            } else {
+                i as f64 / steps as f64
+            };
+            let x = from.0 + (to.0 - from.0) * t;
+            let y = from.1 + (to.1 - from.1) * t;
+            self.plot(x.round() as i64, y.round() as i64, color);
+        }
+    }
+
+    /// Liang-Barsky clipping against the raster bounds
+    fn clip(&self, from: (f64, f64), to: (f64, f64)) -> Option<((f64, f64), (f64, f64))> {
+        let max_x = self.width as f64 - 0.5;
+        let max_y = self.height as f64 - 0.5;
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let mut t0: f64 = 0.0;
+        let mut t1: f64 = 1.0;
+        for (p, q) in [
+            (-dx, from.0 + 0.5),
+            (dx, max_x - from.0),
+            (-dy, from.1 + 0.5),
+            (dy, max_y - from.1),
+        ] {
+            if p == 0.0 {
+                if q < 0.0 {
+                    return None;
+                }
+            } else {
+                let r = q / p;
+                if p < 0.0 {
+                    t0 = t0.max(r);
+                } else {
+                    t1 = t1.min(r);
+                }
+            }
+        }
+        if t0 > t1 {
+            return None;
+        }
+        Some((
+            (from.0 + dx * t0, from.1 + dy * t0),
+            (from.0 + dx * t1, from.1 + dy * t1),
+        ))
+    }
+
+    /// Even-odd scanline fill of a closed polygon
+    fn fill_polygon(&mut self, path: &[(f64, f64)]) {
+        if path.len() < 3 {
+            return;
+        }
+        let color = self.draw_color();
+        let min_y = path.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+        let max_y = path.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max);
+        let first_row = min_y.ceil().max(0.0) as usize;
+        let last_row = (max_y.floor().min(self.height as f64 - 1.0)).max(-1.0);
+        if last_row < 0.0 {
+            return;
+        }
+        let mut crossings = Vec::new();
+        for row in first_row..=last_row as usize {
+            if self.work >= MAX_WORK {
+                return;
+            }
+            let y = row as f64;
+            crossings.clear();
+            for i in 0..path.len() {
+                let a = path[i];
+                let b = path[(i + 1) % path.len()];
+                if (a.1 <= y && y < b.1) || (b.1 <= y && y < a.1) {
+                    crossings.push(a.0 + (y - a.1) * (b.0 - a.0) / (b.1 - a.1));
+                }
+            }
+            self.work += path.len();
+            crossings.sort_by(|a, b| a.total_cmp(b));
+            for pair in crossings.chunks_exact(2) {
+                let start = pair[0].ceil().max(0.0) as i64;
+                let end = pair[1].floor().min(self.width as f64 - 1.0) as i64;
+                for x in start..=end {
+                    self.plot(x, row as i64, color);
+                }
+                self.work += (end - start + 1).max(0) as usize;
+            }
+        }
+    }
+}
+
+/// Color specifier: a letter (`D R G B C Y M W`) or DEC HLS values
+fn parse_color(spec: &[Item]) -> Option<SixelColor> {
+    let (mut h, mut l, mut s) = (None, None, None);
+    for item in spec {
+        match (item.name, item.number) {
+            ('H', Some(n)) => h = Some(n),
+            ('L', Some(n)) => l = Some(n),
+            ('S', Some(n)) => s = Some(n),
+            ('D', None) => return Some(SixelColor::new(0, 0, 0)),
+            ('R', None) => return Some(SixelColor::new(255, 0, 0)),
+            ('G', None) => return Some(SixelColor::new(0, 255, 0)),
+            ('B', None) => return Some(SixelColor::new(0, 0, 255)),
+            ('C', None) => return Some(SixelColor::new(0, 255, 255)),
+            ('Y', None) => return Some(SixelColor::new(255, 255, 0)),
+            ('M', None) => return Some(SixelColor::new(255, 0, 255)),
+            ('W', None) => return Some(SixelColor::new(255, 255, 255)),
+            _ => {}
+        }
+    }
+    if h.is_none() && l.is_none() && s.is_none() {
+        return None;
+    }
+    // DEC hue 0 is blue; shift to the conventional wheel where 0 is red
+    let hue = (h.unwrap_or(0.0).rem_euclid(360.0) as u16 + 240) % 360;
+    let lightness = l.unwrap_or(50.0).clamp(0.0, 100.0) as u8;
+    let saturation = s.unwrap_or(100.0).clamp(0.0, 100.0) as u8;
+    Some(SixelColor::from_hls(hue, lightness, saturation))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn small() -> RegisParser {
+        // 1:1 mapping between the default address space and the raster
+        RegisParser::new(RegisLimits::new(800, 480, 4096))
+    }
+
+    #[test]
+    fn test_limits_clamped() {
+        let limits = RegisLimits::new(0, 100_000, usize::MAX);
+        assert_eq!(limits.max_width, 1);
+        assert_eq!(limits.max_height, REGIS_HARD_MAX_HEIGHT);
+        assert_eq!(limits.max_bytes, REGIS_HARD_MAX_BYTES);
+    }
+
+    #[test]
+    fn test_reader_tree() {
+        let items = Reader::new(b"P[10,+5]W(I(R))T'it''s'").items(None);
+        assert_eq!(items.len(), 3);
+        assert_eq!(
+            items[0].args,
+            vec![Arg::Coord(
+                Component::Absolute(10.0),
+                Component::Relative(5.0)
+            )]
+        );
+        assert_eq!(items[1].name, 'W');
+        let inner: Vec<&Item> = items[1].options().collect();
+        assert_eq!(inner[0].name, 'I');
+        assert_eq!(inner[0].options().next().unwrap().name, 'R');
+        assert_eq!(items[2].args, vec![Arg::Text("it's".to_string())]);
+    }
+
+    #[test]
+    fn test_position_and_vector() {
+        let mut p = small();
+        p.parse(b"P[10,10]V[20,10][+0,+5]");
+        assert_eq!(p.position(), (20, 15));
+        let white = p.color(7);
+        for x in 10..=20 {
+            assert_eq!(p.pixel(x, 10), Some((white.r, white.g, white.b, 255)));
+        }
+        assert!(p.pixel(20, 13).is_some());
+        assert!(p.pixel(5, 5).is_none());
+    }
+
+    #[test]
+    fn test_omitted_component_keeps_position() {
+        let mut p = small();
+        p.parse(b"P[100,50]P[,60]");
+        assert_eq!(p.position(), (100, 60));
+    }
+
+    #[test]
+    fn test_erase_fills_background() {
+        let mut p = small();
+        p.parse(b"S(I1)S(E)");
+        let blue = p.color(1);
+        assert_eq!(p.pixel(0, 0), Some((blue.r, blue.g, blue.b, 255)));
+        assert_eq!(p.pixel(799, 479), Some((blue.r, blue.g, blue.b, 255)));
+    }
+
+    #[test]
+    fn test_circle_and_arc() {
+        let mut p = small();
+        p.parse(b"P[100,100]C[+20]");
+        assert!(p.pixel(120, 100).is_some());
+        assert!(p.pixel(80, 100).is_some());
+        assert!(p.pixel(100, 80).is_some());
+        assert!(p.pixel(100, 100).is_none());
+        assert_eq!(p.position(), (100, 100));
+
+        // Quarter arc counter-clockwise from the right: up, not down
+        let mut p = small();
+        p.parse(b"P[100,100]C(A90)[+20]");
+        assert!(p.pixel(100, 80).is_some());
+        assert!(p.pixel(100, 120).is_none());
+    }
+
+    #[test]
+    fn test_fill() {
+        let mut p = small();
+        p.parse(b"W(I2)P[10,10]F(V[30,10][30,30][10,30][10,10])");
+        let red = p.color(2);
+        assert_eq!(p.pixel(20, 20), Some((red.r, red.g, red.b, 255)));
+        assert!(p.pixel(40, 20).is_none());
+    }
+
+    #[test]
+    fn test_color_map_and_temporary_writing() {
+        let mut p = small();
+        p.parse(b"S(M3(R))P[0,0]V(W(I3))[5,0]V[5,5]");
+        assert_eq!(p.color(3), SixelColor::new(255, 0, 0));
+        assert_eq!(p.pixel(2, 0), Some((255, 0, 0, 255)));
+        // Temporary writing controls do not persist
+        let white = p.color(7);
+        assert_eq!(p.pixel(5, 3), Some((white.r, white.g, white.b, 255)));
+    }
+
+    #[test]
+    fn test_hls_hue_origin_is_blue() {
+        let blue = parse_color(&Reader::new(b"H0L50S100").items(None)).unwrap();
+        assert!(blue.b > 200 && blue.r < 50 && blue.g < 50);
+        let red = parse_color(&Reader::new(b"H120L50S100").items(None)).unwrap();
+        assert!(red.r > 200 && red.g < 50 && red.b < 50);
+    }
+
+    #[test]
+    fn test_address_scaling() {
+        let mut p = RegisParser::new(RegisLimits::new(80, 48, 4096));
+        p.parse(b"S(A[0,0][799,479])P[790,470]V[799,479]");
+        assert!(p.pixel(79, 47).is_some());
+
+        // Flipped y axis
+        let mut p = RegisParser::new(RegisLimits::new(80, 48, 4096));
+        p.parse(b"S(A[0,479][799,0])P[0,0]V[]");
+        assert!(p.pixel(0, 47).is_some());
+    }
+
+    #[test]
+    fn test_huge_coordinates_are_clipped() {
+        let mut p = small();
+        p.parse(b"P[-999999,-999999]V[999999,999999]C[+999999]");
+        assert!(p.pixel(0, 0).is_some());
+        assert!(p.work < MAX_WORK);
+    }
+
+    #[test]
+    fn test_macrograph_and_text_skipped() {
+        let mut p = small();
+        p.parse(b"@:A P[1,1] @;T'hello'P[5,5]");
+        assert_eq!(p.position(), (5, 5));
+    }
+
+    #[test]
+    fn test_deep_nesting_is_bounded() {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"W");
+        data.extend(vec![b'('; 10_000]);
+        data.extend(vec![b')'; 10_000]);
+        data.extend_from_slice(b"P[3,4]");
+        let mut p = small();
+        p.parse(&data);
+        assert_eq!(p.position(), (3, 4));
+    }
+
+    #[test]
+    fn test_reset_state_keeps_picture() {
+        let mut p = small();
+        p.parse(b"W(I2)P[10,10]V[]");
+        p.reset_state();
+        assert_eq!(p.position(), (0, 0));
+        assert!(p.pixel(10, 10).is_some());
+    }
+}
@@ -0,0 +1,315 @@
+//! Mapping between a row's text and its grid columns
+//!
+//! The text of a row (see [`crate::grid::Grid::row_text`]) skips the spacer
+//! cell after each wide character and holds every cell's whole grapheme, so
+//! string indices and columns drift apart after wide characters and
+//! combining marks. [`RowText`] records where each cell's text starts,
+//! converting byte offsets, char indices and columns into each other.
+//!
+//! # Example
+//!
+//! ```
+//! use par_term_emu_core_rust::row_text::RowText;
+//! use par_term_emu_core_rust::terminal::Terminal;
+//!
+//! let mut term = Terminal::new(20, 2);
+//! term.process("日本 log".as_bytes());
+//! let row = RowText::new(term.active_grid().row(0).unwrap());
+//!
+//! let byte = row.text().find("log").unwrap();
+//! assert_eq!(byte, 7);
+//! assert_eq!(row.byte_to_col(byte), Some(5));
+//! assert_eq!(row.char_to_col(3), Some(5));
+//! // Both columns of a wide character map to it
+//! assert_eq!(row.col_to_char(1), Some(0));
+//! ```
+
+use crate::cell::Cell;
+
+/// Where one cell's text sits in the row text
+#[derive(Debug, Clone, Copy)]
+struct CellSpan {
+    /// Byte offset of the cell's text
+    byte: usize,
+    /// Char index of the cell's text
+    char_index: usize,
+    /// First column of the cell
+    col: usize,
+    /// Columns covered, including trailing wide-character spacers
+    width: usize,
+}
+
+/// Text of one row with its byte, char and column mapping
+#[derive(Debug, Clone, Default)]
+pub struct RowText {
+    text: String,
+    /// Number of chars in `text`
+    chars: usize,
+    cols: usize,
+    spans: Vec<CellSpan>,
+}
+
+impl RowText {
+    /// Text of `cells`, matching [`crate::grid::Grid::row_text`]
+    pub fn new(cells: &[Cell]) -> Self {
+        Self::with_fold(cells, |grapheme, text| text.push_str(grapheme))
+    }
+
+    /// Text of `cells` with each cell's grapheme passed through `fold`
+    ///
+    /// `fold` appends the text to use for one grapheme, e.g. a normalized
+    /// or case-folded form for searching. Offsets then refer to the folded
+    /// text.
+    pub fn with_fold(cells: &[Cell], mut fold: impl FnMut(&str, &mut String)) -> Self {
+        let mut text = String::with_capacity(cells.len());
+        let mut chars = 0;
+        let mut spans: Vec<CellSpan> = Vec::with_capacity(cells.len());
+        let mut grapheme = String::new();
+        for (col, cell) in cells.iter().enumerate() {
+            let spacer = cell.flags.wide_char_spacer();
+            if spacer {
+                if let Some(last) = spans.last_mut() {
+                    last.width += 1;
+                    continue;
+                }
+            }
+            let byte = text.len();
+            spans.push(CellSpan {
+                byte,
+                char_index: chars,
+                col,
+                width: 1,
+            });
+            if !spacer {
+                grapheme.clear();
+                cell.push_grapheme(&mut grapheme);
+                fold(&grapheme, &mut text);
+                chars += text[byte..].chars().count();
+            }
+        }
+        Self {
+            text,
+            chars,
+            cols: cells.len(),
+            spans,
+        }
+    }
+
+    /// The row text
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Consume the mapping, keeping the text
+    pub fn into_text(self) -> String {
+        self.text
+    }
+
+    /// Number of columns in the row
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Index of the span covering `col`
+    fn span_index_at_col(&self, col: usize) -> Option<usize> {
+        let index = self
+            .spans
+            .partition_point(|span| span.col <= col)
+            .checked_sub(1)?;
+        let span = &self.spans[index];
+        (col < span.col + span.width).then_some(index)
+    }
+
+    /// Span whose text contains byte `byte` (`byte` below the text length)
+    fn span_at_byte(&self, byte: usize) -> Option<&CellSpan> {
+        let index = self.spans.partition_point(|span| span.byte <= byte);
+        self.spans.get(index.checked_sub(1)?)
+    }
+
+    /// Byte offset of the text drawn at `col`
+    ///
+    /// Both columns of a wide character give its offset; `cols()` gives the
+    /// text length, so exclusive ends convert too.
+    pub fn col_to_byte(&self, col: usize) -> Option<usize> {
+        if col == self.cols {
+            return Some(self.text.len());
+        }
+        self.span_index_at_col(col).map(|i| self.spans[i].byte)
+    }
+
+    /// Char index of the text drawn at `col` (see [`Self::col_to_byte`])
+    pub fn col_to_char(&self, col: usize) -> Option<usize> {
+        if col == self.cols {
+            return Some(self.chars);
+        }
+        self.span_index_at_col(col)
+            .map(|i| self.spans[i].char_index)
+    }
+
+    /// Columns `[start, end)` of the cell drawn at `col`; a wide character
+    /// covers two
+    pub fn cell_at(&self, col: usize) -> Option<(usize, usize)> {
+        self.span_index_at_col(col).map(|i| {
+            let span = &self.spans[i];
+            (span.col, span.col + span.width)
+        })
+    }
+
+    /// Text of the cell drawn at `col`
+    pub fn grapheme_at(&self, col: usize) -> Option<&str> {
+        let (start, end) = self.cell_at(col)?;
+        let (start, end) = self.col_range_to_bytes(start, end);
+        Some(&self.text[start..end])
+    }
+
+    /// Column of the cell whose text contains byte `byte`
+    ///
+    /// An offset inside a grapheme gives the column of its cell; the text
+    /// length gives `cols()`.
+    pub fn byte_to_col(&self, byte: usize) -> Option<usize> {
+        match byte.cmp(&self.text.len()) {
+            std::cmp::Ordering::Less => self.span_at_byte(byte).map(|span| span.col),
+            std::cmp::Ordering::Equal => Some(self.cols),
+            std::cmp::Ordering::Greater => None,
+        }
+    }
+
+    /// Column of the cell whose text contains char `char_index` (see
+    /// [`Self::byte_to_col`])
+    pub fn char_to_col(&self, char_index: usize) -> Option<usize> {
+        match char_index.cmp(&self.chars) {
+            std::cmp::Ordering::Less => {
+                let index = self
+                    .spans
+                    .partition_point(|span| span.char_index <= char_index);
+                self.spans.get(index.checked_sub(1)?).map(|span| span.col)
+            }
+            std::cmp::Ordering::Equal => Some(self.cols),
+            std::cmp::Ordering::Greater => None,
+        }
+    }
+
+    /// Whether byte `byte` starts a cell's text or ends the row text
+    pub fn is_cell_boundary(&self, byte: usize) -> bool {
+        byte == self.text.len()
+            || self
+                .spans
+                .binary_search_by_key(&byte, |span| span.byte)
+                .is_ok()
+    }
+
+    /// Columns `[start, end)` of the cells the bytes `[start, end)` touch
+    ///
+    /// An empty range gives an empty column range at its cell.
+    pub fn byte_range_to_cols(&self, start: usize, end: usize) -> (usize, usize) {
+        let start = start.min(self.text.len());
+        let end = end.clamp(start, self.text.len());
+        let first = self.byte_to_col(start).unwrap_or(self.cols);
+        if end == start {
+            return (first, first);
+        }
+        let last = self
+            .span_at_byte(end - 1)
+            .map_or(self.cols, |span| span.col + span.width);
+        (first, last)
+    }
+
+    /// Bytes `[start, end)` of the text drawn in columns `[start, end)`
+    ///
+    /// A range that starts or ends on the second column of a wide character
+    /// is widened to cover it.
+    pub fn col_range_to_bytes(&self, start: usize, end: usize) -> (usize, usize) {
+        let start = start.min(self.cols);
+        let end = end.clamp(start, self.cols);
+        let first = self.col_to_byte(start).unwrap_or(self.text.len());
+        if end == start {
+            return (first, first);
+        }
+        let last = self
+            .span_index_at_col(end - 1)
+            .and_then(|i| self.spans.get(i + 1))
+            .map_or(self.text.len(), |next| next.byte);
+        (first, last)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::Terminal;
+
+    fn row_of(text: &str) -> RowText {
+        let mut term = Terminal::new(12, 1);
+        term.set_normalization_form(crate::NormalizationForm::None);
+        term.process(text.as_bytes());
+        RowText::new(term.active_grid().row(0).unwrap())
+    }
+
+    #[test]
+    fn text_matches_grid_row_text() {
+        let mut term = Terminal::new(12, 1);
+        term.process("a日b\u{301}c".as_bytes());
+        let grid = term.active_grid();
+        assert_eq!(RowText::new(grid.row(0).unwrap()).text(), grid.row_text(0));
+    }
+
+    #[test]
+    fn wide_characters_cover_two_columns() {
+        let row = row_of("日本x");
+        assert_eq!(row.col_to_byte(0), Some(0));
+        assert_eq!(row.col_to_byte(1), Some(0));
+        assert_eq!(row.col_to_byte(2), Some(3));
+        assert_eq!(row.col_to_char(4), Some(2));
+        assert_eq!(row.byte_to_col(3), Some(2));
+        assert_eq!(row.char_to_col(2), Some(4));
+        assert_eq!(row.cell_at(1), Some((0, 2)));
+        assert_eq!(row.grapheme_at(3), Some("本"));
+    }
+
+    #[test]
+    fn combining_marks_stay_with_their_cell() {
+        let row = row_of("e\u{301}x");
+        assert_eq!(row.col_to_char(1), Some(2));
+        assert_eq!(row.char_to_col(1), Some(0));
+        assert_eq!(row.byte_to_col(1), Some(0));
+        assert!(!row.is_cell_boundary(1));
+        assert!(row.is_cell_boundary(3));
+        assert_eq!(row.grapheme_at(0), Some("e\u{301}"));
+    }
+
+    #[test]
+    fn row_end_maps_both_ways() {
+        let row = row_of("日");
+        let len = row.text().len();
+        assert_eq!(row.cols(), 12);
+        assert_eq!(row.col_to_byte(12), Some(len));
+        assert_eq!(row.byte_to_col(len), Some(12));
+        assert_eq!(row.char_to_col(row.text().chars().count()), Some(12));
+        assert_eq!(row.col_to_byte(13), None);
+        assert_eq!(row.byte_to_col(len + 1), None);
+    }
+
+    #[test]
+    fn ranges_cover_whole_cells() {
+        let row = row_of("a日b");
+        // "日" is bytes 1..4 and columns 1..3
+        assert_eq!(row.byte_range_to_cols(1, 4), (1, 3));
+        assert_eq!(row.byte_range_to_cols(2, 3), (1, 3));
+        assert_eq!(row.byte_range_to_cols(4, 4), (3, 3));
+        assert_eq!(row.col_range_to_bytes(2, 3), (1, 4));
+        assert_eq!(row.col_range_to_bytes(0, 2), (0, 4));
+    }
+
+    #[test]
+    fn folded_text_maps_to_the_original_cells() {
+        let mut term = Terminal::new(12, 1);
+        term.process("\u{FB01}X".as_bytes());
+        let row = RowText::with_fold(term.active_grid().row(0).unwrap(), |g, text| {
+            text.push_str(&g.replace('\u{FB01}', "fi").to_lowercase())
+        });
+        assert!(row.text().starts_with("fix"));
+        assert_eq!(row.byte_to_col(1), Some(0));
+        assert_eq!(row.byte_to_col(2), Some(1));
+        assert_eq!(row.col_to_char(1), Some(2));
+    }
+}
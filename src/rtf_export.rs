@@ -0,0 +1,201 @@
+//! RTF export functionality for terminal content
+//!
+//! Produces a minimal RTF document (monospace font table, color table and one
+//! group per run of identically styled cells) that rich-text editors and the
+//! macOS/Windows clipboards accept.
+
+use crate::cell::Cell;
+
+/// Character formatting of one run of cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RunStyle {
+    fg: usize,
+    bg: usize,
+    bold: bool,
+    italic: bool,
+    underline: bool,
+    strikethrough: bool,
+    hidden: bool,
+}
+
+/// Color table entries in first-use order; RTF index 0 is the "auto" color,
+/// so entry `i` here is referenced as `\cf(i + 1)`
+#[derive(Default)]
+struct ColorTable(Vec<(u8, u8, u8)>);
+
+impl ColorTable {
+    fn index(&mut self, rgb: (u8, u8, u8)) -> usize {
+        let pos = self.0.iter().position(|&c| c == rgb).unwrap_or_else(|| {
+            self.0.push(rgb);
+            self.0.len() - 1
+        });
+        pos + 1
+    }
+}
+
+fn run_style(cell: &Cell, colors: &mut ColorTable) -> RunStyle {
+    let (mut fg, mut bg) = (cell.fg.to_rgb(), cell.bg.to_rgb());
+    if cell.flags.reverse() {
+        std::mem::swap(&mut fg, &mut bg);
+    }
+    RunStyle {
+        fg: colors.index(fg),
+        bg: colors.index(bg),
+        bold: cell.flags.bold(),
+        italic: cell.flags.italic(),
+        underline: cell.flags.underline(),
+        strikethrough: cell.flags.strikethrough(),
+        hidden: cell.flags.hidden(),
+    }
+}
+
+fn push_control_words(style: &RunStyle, rtf: &mut String) {
+    // \cb is what Cocoa reads, \chcbpat is Word's character shading
+    rtf.push_str(&format!(
+        "\\cf{} \\cb{} \\chcbpat{}",
+        style.fg, style.bg, style.bg
+    ));
+    if style.bold {
+        rtf.push_str("\\b");
+    }
+    if style.italic {
+        rtf.push_str("\\i");
+    }
+    if style.underline {
+        rtf.push_str("\\ul");
+    }
+    if style.strikethrough {
+        rtf.push_str("\\strike");
+    }
+    if style.hidden {
+        rtf.push_str("\\v");
+    }
+    rtf.push(' ');
+}
+
+/// Append `ch` to `rtf`, escaping RTF syntax and encoding non-ASCII
+/// characters as `\uN?` (UTF-16 code units, as signed 16-bit values)
+fn push_escaped(ch: char, rtf: &mut String) {
+    match ch {
+        '\\' | '{' | '}' => {
+            rtf.push('\\');
+            rtf.push(ch);
+        }
+        '\t' => rtf.push_str("\\tab "),
+        '\0' => rtf.push(' '),
+        ' '..='~' => rtf.push(ch),
+        _ => {
+            let mut units = [0u16; 2];
+            for unit in ch.encode_utf16(&mut units) {
+                rtf.push_str(&format!("\\u{}?", *unit as i16));
+            }
+        }
+    }
+}
+
+/// Generate an RTF document with one paragraph per slice in `lines`
+///
+/// Wide-character spacer cells are skipped. Colors are resolved with the
+/// default palette, matching [`crate::html_export`].
+pub fn export_rtf(lines: &[&[Cell]]) -> String {
+    let mut colors = ColorTable::default();
+    let mut body = String::new();
+
+    for (i, line) in lines.iter().enumerate() {
+        if i > 0 {
+            body.push_str("\\par\n");
+        }
+        let mut current: Option<RunStyle> = None;
+        for cell in line.iter().filter(|c| !c.flags.wide_char_spacer()) {
+            let style = run_style(cell, &mut colors);
+            if current != Some(style) {
+                if current.is_some() {
+                    body.push('}');
+                }
+                body.push('{');
+                push_control_words(&style, &mut body);
+                current = Some(style);
+            }
+            push_escaped(cell.c, &mut body);
+            for &combining in &cell.combining {
+                push_escaped(combining, &mut body);
+            }
+        }
+        if current.is_some() {
+            body.push('}');
+        }
+    }
+
+    let mut rtf = String::from(
+        "{\\rtf1\\ansi\\ansicpg1252\\deff0\\uc1\
+         {\\fonttbl{\\f0\\fmodern\\fcharset0 Menlo;}}\n{\\colortbl;",
+    );
+    for (r, g, b) in &colors.0 {
+        rtf.push_str(&format!("\\red{}\\green{}\\blue{};", r, g, b));
+    }
+    rtf.push_str("}\n\\f0\\fs28\n");
+    rtf.push_str(&body);
+    rtf.push_str("\n}");
+    rtf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::CellFlags;
+    use crate::color::{Color, NamedColor};
+
+    fn cells(text: &str) -> Vec<Cell> {
+        text.chars().map(Cell::new).collect()
+    }
+
+    #[test]
+    fn test_export_rtf_document_structure() {
+        let line = cells("hi");
+        let rtf = export_rtf(&[&line]);
+        assert!(rtf.starts_with("{\\rtf1\\ansi"));
+        assert!(rtf.ends_with('}'));
+        assert!(rtf.contains("{\\fonttbl{\\f0\\fmodern\\fcharset0 Menlo;}}"));
+        assert!(rtf.contains("hi}"));
+        // Balanced groups
+        let opens = rtf.matches('{').count();
+        let closes = rtf.matches('}').count();
+        assert_eq!(opens, closes);
+    }
+
+    #[test]
+    fn test_export_rtf_escapes_syntax_and_unicode() {
+        let line = cells("a\\{b}é😀");
+        let rtf = export_rtf(&[&line]);
+        assert!(rtf.contains("a\\\\\\{b\\}"));
+        assert!(rtf.contains("\\u233?"));
+        // U+1F600 as a UTF-16 surrogate pair
+        assert!(rtf.contains("\\u-10179?\\u-8704?"));
+    }
+
+    #[test]
+    fn test_export_rtf_color_table_and_attributes() {
+        let mut red = Cell::new('R');
+        red.fg = Color::Named(NamedColor::BrightRed);
+        red.flags = CellFlags::default();
+        red.flags.set_bold(true);
+        let plain = Cell::new('p');
+        let line = vec![red, plain];
+        let rtf = export_rtf(&[&line]);
+
+        assert!(rtf.contains("\\red255\\green0\\blue0;"));
+        assert!(rtf.contains("\\b R}"));
+        // The plain cell starts a new run without bold
+        let plain_run = rtf.rsplit('{').next().unwrap();
+        assert!(!plain_run.contains("\\b"));
+        assert!(plain_run.ends_with("p}\n}"));
+    }
+
+    #[test]
+    fn test_export_rtf_paragraph_per_line() {
+        let first = cells("one");
+        let second = cells("two");
+        let rtf = export_rtf(&[&first, &second]);
+        assert_eq!(rtf.matches("\\par\n").count(), 1);
+    }
+}
@@ -0,0 +1,491 @@
+//! Vector rendering of box-drawing and block element characters
+//!
+//! Font glyphs for U+2500–U+257F and U+2580–U+259F rarely fill the cell
+//! exactly, so the borders of TUIs like htop show gaps between rows and
+//! columns. [`coverage`] computes the glyph as a per-pixel coverage mask for
+//! the actual cell size instead: straight lines are snapped to whole pixels
+//! and meet at the cell center, so neighbouring cells join seamlessly. Arcs
+//! and diagonals are antialiased.
+
+/// Arm weight: no line
+const NONE: u8 = 0;
+/// Arm weight: light line
+const LIGHT: u8 = 1;
+/// Arm weight: heavy line
+const HEAVY: u8 = 2;
+/// Arm weight: double line
+const DOUBLE: u8 = 3;
+
+/// Arms of U+2500..=U+257F as (up, right, down, left) weights. Dashed lines,
+/// arcs and diagonals are all zero here and drawn separately.
+#[rustfmt::skip]
+const BOX_ARMS: [[u8; 4]; 128] = {
+    const L: u8 = LIGHT;
+    const H: u8 = HEAVY;
+    const D: u8 = DOUBLE;
+    const N: u8 = NONE;
+    [
+        // 2500 ─ ━ │ ┃ ┄ ┅ ┆ ┇ ┈ ┉ ┊ ┋ ┌ ┍ ┎ ┏
+        [N, L, N, L], [N, H, N, H], [L, N, L, N], [H, N, H, N],
+        [N; 4], [N; 4], [N; 4], [N; 4], [N; 4], [N; 4], [N; 4], [N; 4],
+        [N, L, L, N], [N, H, L, N], [N, L, H, N], [N, H, H, N],
+        // 2510 ┐ ┑ ┒ ┓ └ ┕ ┖ ┗ ┘ ┙ ┚ ┛ ├ ┝ ┞ ┟
+        [N, N, L, L], [N, N, L, H], [N, N, H, L], [N, N, H, H],
+        [L, L, N, N], [L, H, N, N], [H, L, N, N], [H, H, N, N],
+        [L, N, N, L], [L, N, N, H], [H, N, N, L], [H, N, N, H],
+        [L, L, L, N], [L, H, L, N], [H, L, L, N], [L, L, H, N],
+        // 2520 ┠ ┡ ┢ ┣ ┤ ┥ ┦ ┧ ┨ ┩ ┪ ┫ ┬ ┭ ┮ ┯
+        [H, L, H, N], [H, H, L, N], [L, H, H, N], [H, H, H, N],
+        [L, N, L, L], [L, N, L, H], [H, N, L, L], [L, N, H, L],
+        [H, N, H, L], [H, N, L, H], [L, N, H, H], [H, N, H, H],
+        [N, L, L, L], [N, L, L, H], [N, H, L, L], [N, H, L, H],
+        // 2530 ┰ ┱ ┲ ┳ ┴ ┵ ┶ ┷ ┸ ┹ ┺ ┻ ┼ ┽ ┾ ┿
+        [N, L, H, L], [N, L, H, H], [N, H, H, L], [N, H, H, H],
+        [L, L, N, L], [L, L, N, H], [L, H, N, L], [L, H, N, H],
+        [H, L, N, L], [H, L, N, H], [H, H, N, L], [H, H, N, H],
+        [L, L, L, L], [L, L, L, H], [L, H, L, L], [L, H, L, H],
+        // 2540 ╀ ╁ ╂ ╃ ╄ ╅ ╆ ╇ ╈ ╉ ╊ ╋ ╌ ╍ ╎ ╏
+        [H, L, L, L], [L, L, H, L], [H, L, H, L], [H, L, L, H],
+        [H, H, L, L], [L, L, H, H], [L, H, H, L], [H, H, L, H],
+        [L, H, H, H], [H, L, H, H], [H, H, H, L], [H, H, H, H],
+        [N; 4], [N; 4], [N; 4], [N; 4],
+        // 2550 ═ ║ ╒ ╓ ╔ ╕ ╖ ╗ ╘ ╙ ╚ ╛ ╜ ╝ ╞ ╟
+        [N, D, N, D], [D, N, D, N], [N, D, L, N], [N, L, D, N],
+        [N, D, D, N], [N, N, L, D], [N, N, D, L], [N, N, D, D],
+        [L, D, N, N], [D, L, N, N], [D, D, N, N], [L, N, N, D],
+        [D, N, N, L], [D, N, N, D], [L, D, L, N], [D, L, D, N],
+        // 2560 ╠ ╡ ╢ ╣ ╤ ╥ ╦ ╧ ╨ ╩ ╪ ╫ ╬ ╭ ╮ ╯
+        [D, D, D, N], [L, N, L, D], [D, N, D, L], [D, N, D, D],
+        [N, D, L, D], [N, L, D, L], [N, D, D, D], [L, D, N, D],
+        [D, L, N, L], [D, D, N, D], [L, D, L, D], [D, L, D, L],
+        [D, D, D, D], [N; 4], [N; 4], [N; 4],
+        // 2570 ╰ ╱ ╲ ╳ ╴ ╵ ╶ ╷ ╸ ╹ ╺ ╻ ╼ ╽ ╾ ╿
+        [N; 4], [N; 4], [N; 4], [N; 4],
+        [N, N, N, L], [L, N, N, N], [N, L, N, N], [N, N, L, N],
+        [N, N, N, H], [H, N, N, N], [N, H, N, N], [N, N, H, N],
+        [N, H, N, L], [L, N, H, N], [N, L, N, H], [H, N, L, N],
+    ]
+};
+
+/// Whether `c` is drawn by [`coverage`]
+pub(crate) fn is_box_or_block(c: char) -> bool {
+    matches!(c, '\u{2500}'..='\u{259F}')
+}
+
+/// Coverage mask (row-major, `width * height`, 0 = background, 255 =
+/// foreground) for a box-drawing or block element character, or `None` for
+/// any other character
+pub(crate) fn coverage(c: char, width: u32, height: u32) -> Option<Vec<u8>> {
+    if !is_box_or_block(c) || width == 0 || height == 0 {
+        return None;
+    }
+    let mut mask = Mask::new(width as i32, height as i32);
+    match c {
+        '\u{2580}'..='\u{259F}' => mask.block(c),
+        '\u{2504}'..='\u{250B}' | '\u{254C}'..='\u{254F}' => mask.dashed(c),
+        '\u{256D}'..='\u{2570}' => mask.arc(c),
+        '\u{2571}'..='\u{2573}' => mask.diagonal(c),
+        _ => {
+            let [up, right, down, left] = BOX_ARMS[c as usize - 0x2500];
+            mask.arms(up, right, down, left);
+        }
+    }
+    Some(mask.data)
+}
+
+/// Coverage mask being drawn for one cell
+struct Mask {
+    width: i32,
+    height: i32,
+    /// Light line thickness in pixels
+    light: i32,
+    /// Heavy line thickness in pixels
+    heavy: i32,
+    data: Vec<u8>,
+}
+
+impl Mask {
+    fn new(width: i32, height: i32) -> Self {
+        let light = ((width as f32 / 8.0).round() as i32).max(1);
+        Self {
+            width,
+            height,
+            light,
+            heavy: light * 2,
+            data: vec![0; (width * height) as usize],
+        }
+    }
+
+    /// Set coverage of the rectangle `[x0, x1) x [y0, y1)`, clipped to the cell
+    fn fill(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, alpha: u8) {
+        for y in y0.max(0)..y1.min(self.height) {
+            for x in x0.max(0)..x1.min(self.width) {
+                let px = &mut self.data[(y * self.width + x) as usize];
+                *px = (*px).max(alpha);
+            }
+        }
+    }
+
+    /// Pixel intervals covered across a line of `weight` centered at `c`;
+    /// double lines have two strokes, the first on the low side
+    fn strokes(&self, c: i32, weight: u8) -> Vec<(i32, i32)> {
+        let band = |center: i32, thickness: i32| {
+            let lo = center - thickness / 2;
+            (lo, lo + thickness)
+        };
+        match weight {
+            LIGHT => vec![band(c, self.light)],
+            HEAVY => vec![band(c, self.heavy)],
+            DOUBLE => vec![
+                band(c - self.light, self.light),
+                band(c + self.light, self.light),
+            ],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Start of stroke `idx` of an arm running from the center toward the
+    /// high edge. `before`/`after` are the perpendicular arms on the low and
+    /// high side of the arm, `c` the center across them.
+    fn arm_start(
+        &self,
+        weight: u8,
+        idx: usize,
+        before: u8,
+        after: u8,
+        opposite: bool,
+        c: i32,
+    ) -> i32 {
+        let lo = |w: u8| self.strokes(c, w).first().map_or(c, |s| s.0);
+        if weight == DOUBLE {
+            // Each stroke turns into the perpendicular line on its own side,
+            // or into the outer line of the other side to form a corner
+            let (side, other) = if idx == 0 {
+                (before, after)
+            } else {
+                (after, before)
+            };
+            return match (side, other) {
+                (DOUBLE, _) => self.strokes(c, DOUBLE)[1].0,
+                (NONE, NONE) => c,
+                (NONE, other) => lo(other),
+                (side, _) => lo(side),
+            };
+        }
+        match before.max(after) {
+            NONE => c,
+            // Touch the near line of a double line passing by, unless
+            // crossing it; corners reach the outer line
+            DOUBLE if !opposite && before == after => self.strokes(c, DOUBLE)[1].0,
+            _ => [before, after]
+                .into_iter()
+                .filter(|&w| w != NONE)
+                .map(lo)
+                .min()
+                .unwrap_or(c),
+        }
+    }
+
+    /// End of stroke `idx` of an arm running from the low edge to the center
+    fn arm_end(
+        &self,
+        weight: u8,
+        idx: usize,
+        before: u8,
+        after: u8,
+        opposite: bool,
+        c: i32,
+    ) -> i32 {
+        let hi = |w: u8| self.strokes(c, w).last().map_or(c, |s| s.1);
+        if weight == DOUBLE {
+            let (side, other) = if idx == 0 {
+                (before, after)
+            } else {
+                (after, before)
+            };
+            return match (side, other) {
+                (DOUBLE, _) => self.strokes(c, DOUBLE)[0].1,
+                (NONE, NONE) => c,
+                (NONE, other) => hi(other),
+                (side, _) => hi(side),
+            };
+        }
+        match before.max(after) {
+            NONE => c,
+            DOUBLE if !opposite && before == after => self.strokes(c, DOUBLE)[0].1,
+            _ => [before, after]
+                .into_iter()
+                .filter(|&w| w != NONE)
+                .map(hi)
+                .max()
+                .unwrap_or(c),
+        }
+    }
+
+    /// Draw lines from the cell center to the edges
+    fn arms(&mut self, up: u8, right: u8, down: u8, left: u8) {
+        let (cx, cy) = (self.width / 2, self.height / 2);
+        let (w, h) = (self.width, self.height);
+        for (idx, (y0, y1)) in self.strokes(cy, right).into_iter().enumerate() {
+            let x0 = self.arm_start(right, idx, up, down, left != NONE, cx);
+            self.fill(x0, y0, w, y1, 255);
+        }
+        for (idx, (y0, y1)) in self.strokes(cy, left).into_iter().enumerate() {
+            let x1 = self.arm_end(left, idx, up, down, right != NONE, cx);
+            self.fill(0, y0, x1, y1, 255);
+        }
+        for (idx, (x0, x1)) in self.strokes(cx, down).into_iter().enumerate() {
+            let y0 = self.arm_start(down, idx, left, right, up != NONE, cy);
+            self.fill(x0, y0, x1, h, 255);
+        }
+        for (idx, (x0, x1)) in self.strokes(cx, up).into_iter().enumerate() {
+            let y1 = self.arm_end(up, idx, left, right, down != NONE, cy);
+            self.fill(x0, 0, x1, y1, 255);
+        }
+    }
+
+    /// Dashed horizontal or vertical lines (U+2504..=U+250B, U+254C..=U+254F)
+    fn dashed(&mut self, c: char) {
+        let (dashes, weight, vertical) = match c {
+            '\u{2504}' => (3, LIGHT, false),
+            '\u{2505}' => (3, HEAVY, false),
+            '\u{2506}' => (3, LIGHT, true),
+            '\u{2507}' => (3, HEAVY, true),
+            '\u{2508}' => (4, LIGHT, false),
+            '\u{2509}' => (4, HEAVY, false),
+            '\u{250A}' => (4, LIGHT, true),
+            '\u{250B}' => (4, HEAVY, true),
+            '\u{254C}' => (2, LIGHT, false),
+            '\u{254D}' => (2, HEAVY, false),
+            '\u{254E}' => (2, LIGHT, true),
+            _ => (2, HEAVY, true),
+        };
+        let (length, center) = if vertical {
+            (self.height, self.width / 2)
+        } else {
+            (self.width, self.height / 2)
+        };
+        let [(lo, hi)] = self.strokes(center, weight)[..] else {
+            return;
+        };
+        // Gaps are centered on dash boundaries so dashes of adjacent cells
+        // keep the same rhythm
+        let gap = (length / dashes / 3).max(1);
+        for i in 0..dashes {
+            let start = i * length / dashes + gap / 2;
+            let end = (i + 1) * length / dashes - (gap - gap / 2);
+            if vertical {
+                self.fill(lo, start, hi, end, 255);
+            } else {
+                self.fill(start, lo, end, hi, 255);
+            }
+        }
+    }
+
+    /// Light arcs (U+256D..=U+2570): a quarter circle between the light lines
+    /// entering the cell from two edges
+    fn arc(&mut self, c: char) {
+        let (w, h) = (self.width, self.height);
+        let (x0, x1) = self.strokes(w / 2, LIGHT)[0];
+        let (y0, y1) = self.strokes(h / 2, LIGHT)[0];
+        let line_x = (x0 + x1) as f32 / 2.0;
+        let line_y = (y0 + y1) as f32 / 2.0;
+        let radius = line_x
+            .min(w as f32 - line_x)
+            .min(line_y)
+            .min(h as f32 - line_y);
+        // Directions toward the two edges the arc connects
+        let (dx, dy) = match c {
+            '\u{256D}' => (1.0, 1.0),   // ╭ down and right
+            '\u{256E}' => (-1.0, 1.0),  // ╮ down and left
+            '\u{256F}' => (-1.0, -1.0), // ╯ up and left
+            _ => (1.0, -1.0),           // ╰ up and right
+        };
+        let center_x = line_x + dx * radius;
+        let center_y = line_y + dy * radius;
+        let half = self.light as f32 / 2.0;
+        for py in 0..h {
+            for px in 0..w {
+                let fx = px as f32 + 0.5;
+                let fy = py as f32 + 0.5;
+                // Only the quarter facing the cell center
+                if (fx - center_x) * dx > 0.0 || (fy - center_y) * dy > 0.0 {
+                    continue;
+                }
+                let dist = ((fx - center_x).powi(2) + (fy - center_y).powi(2)).sqrt();
+                let alpha = (half + 0.5 - (dist - radius).abs()).clamp(0.0, 1.0);
+                let px_alpha = &mut self.data[(py * w + px) as usize];
+                *px_alpha = (*px_alpha).max((alpha * 255.0) as u8);
+            }
+        }
+        // Straight segments from the arc ends to the cell edges
+        let (arc_x, arc_y) = (center_x.round() as i32, center_y.round() as i32);
+        if dx > 0.0 {
+            self.fill(arc_x, y0, w, y1, 255);
+        } else {
+            self.fill(0, y0, arc_x, y1, 255);
+        }
+        if dy > 0.0 {
+            self.fill(x0, arc_y, x1, h, 255);
+        } else {
+            self.fill(x0, 0, x1, arc_y, 255);
+        }
+    }
+
+    /// Light diagonals (U+2571..=U+2573) from corner to corner
+    fn diagonal(&mut self, c: char) {
+        let (w, h) = (self.width as f32, self.height as f32);
+        let rising = matches!(c, '\u{2571}' | '\u{2573}');
+        let falling = matches!(c, '\u{2572}' | '\u{2573}');
+        let length = (w * w + h * h).sqrt();
+        let half = self.light as f32 / 2.0;
+        for py in 0..self.height {
+            for px in 0..self.width {
+                let fx = px as f32 + 0.5;
+                let fy = py as f32 + 0.5;
+                // Distance to the line through (0, 0)-(w, h) and (0, h)-(w, 0)
+                let mut dist = f32::MAX;
+                if falling {
+                    dist = dist.min((fx * h - fy * w).abs() / length);
+                }
+                if rising {
+                    dist = dist.min((fx * h + fy * w - w * h).abs() / length);
+                }
+                let alpha = (half + 0.5 - dist).clamp(0.0, 1.0);
+                self.data[(py * self.width + px) as usize] = (alpha * 255.0) as u8;
+            }
+        }
+    }
+
+    /// Block elements (U+2580..=U+259F): eighths, halves, quadrants and shades
+    fn block(&mut self, c: char) {
+        let (w, h) = (self.width, self.height);
+        let eighth_x = |n: i32| (w * n + 4) / 8;
+        let eighth_y = |n: i32| (h * n + 4) / 8;
+        match c {
+            '\u{2580}' => self.fill(0, 0, w, h / 2, 255),
+            '\u{2581}'..='\u{2588}' => {
+                let n = c as i32 - 0x2580;
+                self.fill(0, h - eighth_y(n), w, h, 255);
+            }
+            '\u{2589}'..='\u{258F}' => {
+                let n = 0x2590 - c as i32;
+                self.fill(0, 0, eighth_x(n), h, 255);
+            }
+            '\u{2590}' => self.fill(w / 2, 0, w, h, 255),
+            '\u{2591}' => self.fill(0, 0, w, h, 64),
+            '\u{2592}' => self.fill(0, 0, w, h, 128),
+            '\u{2593}' => self.fill(0, 0, w, h, 192),
+            '\u{2594}' => self.fill(0, 0, w, eighth_y(1), 255),
+            '\u{2595}' => self.fill(w - eighth_x(1), 0, w, h, 255),
+            _ => {
+                // Quadrants as upper left, upper right, lower left, lower right
+                let quadrants = match c {
+                    '\u{2596}' => [false, false, true, false],
+                    '\u{2597}' => [false, false, false, true],
+                    '\u{2598}' => [true, false, false, false],
+                    '\u{2599}' => [true, false, true, true],
+                    '\u{259A}' => [true, false, false, true],
+                    '\u{259B}' => [true, true, true, false],
+                    '\u{259C}' => [true, true, false, true],
+                    '\u{259D}' => [false, true, false, false],
+                    '\u{259E}' => [false, true, true, false],
+                    _ => [false, true, true, true],
+                };
+                let (mx, my) = (w / 2, h / 2);
+                let rects = [
+                    (0, 0, mx, my),
+                    (mx, 0, w, my),
+                    (0, my, mx, h),
+                    (mx, my, w, h),
+                ];
+                for (on, (x0, y0, x1, y1)) in quadrants.into_iter().zip(rects) {
+                    if on {
+                        self.fill(x0, y0, x1, y1, 255);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(mask: &[u8], width: u32, x: u32, y: u32) -> u8 {
+        mask[(y * width + x) as usize]
+    }
+
+    #[test]
+    fn test_horizontal_line_spans_cell_edge_to_edge() {
+        let mask = coverage('─', 9, 18).unwrap();
+        assert_eq!(at(&mask, 9, 0, 9), 255);
+        assert_eq!(at(&mask, 9, 8, 9), 255);
+        assert_eq!(at(&mask, 9, 4, 0), 0);
+        // Same row in every cell so adjacent cells join
+        let row: Vec<u8> = (0..9).map(|x| at(&mask, 9, x, 9)).collect();
+        assert!(row.iter().all(|&a| a == 255));
+    }
+
+    #[test]
+    fn test_vertical_line_meets_corner() {
+        let corner = coverage('┌', 9, 18).unwrap();
+        let vertical = coverage('│', 9, 18).unwrap();
+        // The corner's down arm sits on the same column as the vertical line
+        for y in 10..18 {
+            for x in 0..9 {
+                assert_eq!(at(&corner, 9, x, y), at(&vertical, 9, x, y), "({x},{y})");
+            }
+        }
+        // Nothing above or left of the corner
+        assert_eq!(at(&corner, 9, 4, 0), 0);
+        assert_eq!(at(&corner, 9, 0, 9), 0);
+    }
+
+    #[test]
+    fn test_double_corner_leaves_gap() {
+        let mask = coverage('╔', 16, 32).unwrap();
+        let center_row: Vec<u8> = (0..16).map(|x| at(&mask, 16, x, 31)).collect();
+        // Two vertical strokes separated by a gap at the bottom edge
+        let runs = center_row
+            .windows(2)
+            .filter(|pair| pair[0] == 0 && pair[1] == 255)
+            .count();
+        assert_eq!(runs, 2, "{center_row:?}");
+    }
+
+    #[test]
+    fn test_single_line_reaches_outer_stroke_of_double_corner() {
+        // ╒: the single down arm joins both strokes of the double right arm
+        let mask = coverage('╒', 16, 32).unwrap();
+        let column: Vec<u8> = (0..32).map(|y| at(&mask, 16, 8, y)).collect();
+        let first = column.iter().position(|&a| a == 255).unwrap();
+        assert_eq!(first, 13, "{column:?}");
+        // ╤: a double line passing by is only touched on the near side
+        let mask = coverage('╤', 16, 32).unwrap();
+        assert_eq!(at(&mask, 16, 8, 15), 0);
+    }
+
+    #[test]
+    fn test_block_elements() {
+        let mask = coverage('▄', 8, 16).unwrap();
+        assert_eq!(at(&mask, 8, 3, 7), 0);
+        assert_eq!(at(&mask, 8, 3, 8), 255);
+        let mask = coverage('▒', 8, 16).unwrap();
+        assert!(mask.iter().all(|&a| a == 128));
+        let mask = coverage('▚', 8, 16).unwrap();
+        assert_eq!(at(&mask, 8, 0, 0), 255);
+        assert_eq!(at(&mask, 8, 7, 0), 0);
+        assert_eq!(at(&mask, 8, 7, 15), 255);
+    }
+
+    #[test]
+    fn test_every_box_char_draws_something() {
+        for c in '\u{2500}'..='\u{259F}' {
+            let mask = coverage(c, 10, 20).unwrap();
+            assert!(mask.iter().any(|&a| a > 0), "{c:?} is empty");
+        }
+        assert!(coverage('a', 10, 20).is_none());
+    }
+}
@@ -31,6 +31,10 @@ pub struct ScreenshotConfig {
     // Font settings
     /// Path to custom font file (.ttf or .otf). None uses embedded default
     pub font_path: Option<PathBuf>,
+    /// Fallback fonts (.ttf, .otf or .ttc), tried in order for characters the
+    /// primary font lacks, before the built-in CJK and emoji fallbacks. A
+    /// color emoji font here is also used for shaped emoji sequences.
+    pub fallback_fonts: Vec<PathBuf>,
     /// Font size in pixels
     pub font_size: f32,
     /// Line height multiplier (1.0 = tight, 1.2 = comfortable)
@@ -82,6 +86,10 @@ pub struct ScreenshotConfig {
     /// Default: 0.5 (moderate contrast for improved readability)
     /// Automatically adjusts text colors to maintain readability against backgrounds
     pub minimum_contrast: f64,
+    /// Minimum WCAG contrast ratio between text and background (1.0-21.0,
+    /// 1.0 = disabled), applied after `minimum_contrast`.
+    /// None = use the terminal's setting (`Terminal::set_minimum_contrast_ratio`)
+    pub minimum_contrast_ratio: Option<f32>,
     /// Alpha multiplier for faint/dim text (0.0 = fully transparent, 1.0 = no dimming)
     /// Matches iTerm2's "Faint text" slider. Default: 0.5 (50%).
     pub faint_text_alpha: f32,
@@ -92,12 +100,67 @@ pub struct ScreenshotConfig {
     /// Default: true (preserves the historical screenshot appearance). Set to
     /// false for raw sRGB colors. See ARC-022.
     pub iterm2_color_boost: bool,
+
+    // Text shaping
+    /// Shape every row through the text shaper so programming ligatures
+    /// (`=>`, `!=`, `->`) in fonts like Fira Code or JetBrains Mono render
+    /// as ligature glyphs. Default: false (faster per-cell rendering; rows
+    /// with flags or grapheme clusters are shaped either way).
+    pub enable_ligatures: bool,
+
+    /// Draw box-drawing (U+2500–U+257F) and block element (U+2580–U+259F)
+    /// characters as vector shapes sized to the cell, so borders join
+    /// without gaps. Default: true. Set to false to use the font's glyphs.
+    pub builtin_box_drawing: bool,
+
+    // Cursor appearance (with `render_cursor`)
+    /// Blink phase captured for blinking cursor styles: true = the visible
+    /// phase, false = the hidden phase (blinking cursors are omitted).
+    /// Steady cursors are always drawn. Default: true.
+    pub cursor_blink_visible: bool,
+    /// Draw the cursor in the foreground color of the cell under it (and a
+    /// block cursor's character in that cell's background color) instead of
+    /// `cursor_color`, like iTerm2's "Smart Cursor Color" (None = the
+    /// terminal's `smart_cursor_color` setting when taken through
+    /// `Terminal::screenshot`, else off).
+    pub smart_cursor_color: Option<bool>,
+    /// Tint the cursor line with `cursor_guide_color` at 25% opacity, like
+    /// iTerm2's cursor guide. Drawn even when `render_cursor` is false
+    /// (None = the terminal's `use_cursor_guide` setting when taken through
+    /// `Terminal::screenshot`, else off).
+    pub cursor_guide: Option<bool>,
+    /// Cursor guide color (None = terminal theme's cursor guide color when
+    /// taken through `Terminal::screenshot`, else no guide)
+    pub cursor_guide_color: Option<(u8, u8, u8)>,
+
+    // Overlays (applied by `Terminal::screenshot`)
+    /// Paint the current selection with the terminal theme's selection
+    /// colors. Default: false.
+    pub render_selection: bool,
+    /// Paint the matches of the terminal's last search with the theme's match
+    /// color. Default: false.
+    pub render_search_matches: bool,
+
+    // Badge (iTerm2 OSC 1337 SetBadgeFormat)
+    /// Composite the badge text in the top-right corner, bold and scaled to
+    /// fit half the width and a fifth of the height of the screen.
+    /// Default: false.
+    pub render_badge: bool,
+    /// Badge text, one line per `\n` (None = terminal's evaluated badge
+    /// when taken through `Terminal::screenshot`, else no badge)
+    pub badge_text: Option<String>,
+    /// Badge color (None = terminal theme's badge color when taken through
+    /// `Terminal::screenshot`, else red)
+    pub badge_color: Option<(u8, u8, u8)>,
+    /// Badge opacity (0.0-1.0). Default: 0.5, as in iTerm2.
+    pub badge_alpha: f32,
 }
 
 impl Default for ScreenshotConfig {
     fn default() -> Self {
         Self {
             font_path: None,
+            fallback_fonts: Vec::new(),
             font_size: 14.0,
             line_height_multiplier: 1.2,
             char_width_multiplier: 1.0,
@@ -116,8 +179,21 @@ impl Default for ScreenshotConfig {
             use_bold_color: false,
             bold_brightening: false,
             minimum_contrast: 0.5, // Moderate contrast by default (0.5 = 50% adjustment)
+            minimum_contrast_ratio: None,
             faint_text_alpha: 0.5,
             iterm2_color_boost: true,
+            enable_ligatures: false,
+            builtin_box_drawing: true,
+            cursor_blink_visible: true,
+            smart_cursor_color: None,
+            cursor_guide: None,
+            cursor_guide_color: None,
+            render_selection: false,
+            render_search_matches: false,
+            render_badge: false,
+            badge_text: None,
+            badge_color: None,
+            badge_alpha: 0.5,
         }
     }
 }
@@ -134,6 +210,12 @@ impl ScreenshotConfig {
         self
     }
 
+    /// Set the fallback font chain
+    pub fn with_fallback_fonts(mut self, paths: Vec<PathBuf>) -> Self {
+        self.fallback_fonts = paths;
+        self
+    }
+
     /// Set font size
     pub fn with_font_size(mut self, size: f32) -> Self {
         self.font_size = size;
@@ -207,6 +289,13 @@ impl ScreenshotConfig {
         self
     }
 
+    /// Set the minimum WCAG contrast ratio (1.0-21.0, 1.0 = disabled),
+    /// overriding the terminal's setting
+    pub fn with_minimum_contrast_ratio(mut self, ratio: f32) -> Self {
+        self.minimum_contrast_ratio = Some(ratio.clamp(1.0, 21.0));
+        self
+    }
+
     /// Set faint text alpha (dim strength)
     pub fn with_faint_text_alpha(mut self, alpha: f32) -> Self {
         self.faint_text_alpha = alpha.clamp(0.0, 1.0);
@@ -218,6 +307,60 @@ impl ScreenshotConfig {
         self.iterm2_color_boost = enabled;
         self
     }
+
+    /// Enable/disable ligature shaping
+    pub fn with_ligatures(mut self, enabled: bool) -> Self {
+        self.enable_ligatures = enabled;
+        self
+    }
+
+    /// Enable/disable built-in box-drawing and block element rendering
+    pub fn with_builtin_box_drawing(mut self, enabled: bool) -> Self {
+        self.builtin_box_drawing = enabled;
+        self
+    }
+
+    /// Set the blink phase captured for blinking cursor styles
+    pub fn with_cursor_blink_visible(mut self, visible: bool) -> Self {
+        self.cursor_blink_visible = visible;
+        self
+    }
+
+    /// Enable/disable smart cursor color
+    pub fn with_smart_cursor_color(mut self, enabled: bool) -> Self {
+        self.smart_cursor_color = Some(enabled);
+        self
+    }
+
+    /// Enable the cursor guide, optionally with a color
+    pub fn with_cursor_guide(mut self, enabled: bool, color: Option<(u8, u8, u8)>) -> Self {
+        self.cursor_guide = Some(enabled);
+        if color.is_some() {
+            self.cursor_guide_color = color;
+        }
+        self
+    }
+
+    /// Enable/disable the selection overlay
+    pub fn with_selection(mut self, enabled: bool) -> Self {
+        self.render_selection = enabled;
+        self
+    }
+
+    /// Enable/disable the search match overlay
+    pub fn with_search_matches(mut self, enabled: bool) -> Self {
+        self.render_search_matches = enabled;
+        self
+    }
+
+    /// Enable the badge, optionally with its text
+    pub fn with_badge(mut self, enabled: bool, text: Option<String>) -> Self {
+        self.render_badge = enabled;
+        if text.is_some() {
+            self.badge_text = text;
+        }
+        self
+    }
 }
 
 #[cfg(test)]
@@ -262,6 +405,17 @@ mod tests {
         assert_eq!(config.font_path, Some(path));
     }
 
+    #[test]
+    fn test_with_fallback_fonts() {
+        let paths = vec![
+            PathBuf::from("/fonts/NotoColorEmoji.ttf"),
+            PathBuf::from("/fonts/NotoSansCJK.ttc"),
+        ];
+        let config = ScreenshotConfig::new().with_fallback_fonts(paths.clone());
+        assert_eq!(config.fallback_fonts, paths);
+        assert!(ScreenshotConfig::default().fallback_fonts.is_empty());
+    }
+
     #[test]
     fn test_with_font_size() {
         let config = ScreenshotConfig::new().with_font_size(20.0);
@@ -464,6 +618,15 @@ mod tests {
         assert_eq!(config.minimum_contrast, 0.0);
     }
 
+    #[test]
+    fn test_with_minimum_contrast_ratio() {
+        assert_eq!(ScreenshotConfig::default().minimum_contrast_ratio, None);
+        let config = ScreenshotConfig::new().with_minimum_contrast_ratio(4.5);
+        assert_eq!(config.minimum_contrast_ratio, Some(4.5));
+        let config = ScreenshotConfig::new().with_minimum_contrast_ratio(0.0);
+        assert_eq!(config.minimum_contrast_ratio, Some(1.0));
+    }
+
     #[test]
     fn test_default_faint_text_alpha() {
         let config = ScreenshotConfig::default();
@@ -481,4 +644,11 @@ mod tests {
         let config = ScreenshotConfig::new().with_faint_text_alpha(-0.5);
         assert!((config.faint_text_alpha - 0.0).abs() < f32::EPSILON);
     }
+
+    #[test]
+    fn test_with_ligatures() {
+        assert!(!ScreenshotConfig::default().enable_ligatures);
+        let config = ScreenshotConfig::new().with_ligatures(true);
+        assert!(config.enable_ligatures);
+    }
 }
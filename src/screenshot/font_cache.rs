@@ -1,7 +1,7 @@
 use lru::LruCache;
 use std::collections::HashMap;
 use std::num::NonZeroUsize;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use swash::scale::{Render, ScaleContext, Source, StrikeWith};
 use swash::FontRef;
@@ -93,6 +93,11 @@ impl SwashFont {
 pub struct FontCache {
     /// Regular font (JetBrains Mono or custom)
     regular: SwashFont,
+    /// User-configured fallback fonts, tried in order before the system fallbacks
+    fallback_fonts: Vec<SwashFont>,
+    /// Index of the first fallback font with emoji coverage; used instead of
+    /// the system emoji font so shaped emoji come from the configured font
+    emoji_override: Option<usize>,
     /// Optional emoji fallback font (lazy-loaded from system)
     emoji_font: Option<SwashFont>,
     /// CJK fallback fonts (lazy-loaded from system, ordered by priority)
@@ -115,7 +120,14 @@ pub struct FontCache {
 
 impl FontCache {
     /// Create a new font cache
-    pub fn new(font_path: Option<&Path>, font_size: f32) -> ScreenshotResult<Self> {
+    ///
+    /// `fallback_paths` are loaded eagerly; an unreadable or invalid fallback
+    /// font is an error, like an invalid `font_path`.
+    pub fn new(
+        font_path: Option<&Path>,
+        fallback_paths: &[PathBuf],
+        font_size: f32,
+    ) -> ScreenshotResult<Self> {
         let font_data = if let Some(path) = font_path {
             // Load from file
             std::fs::read(path).map_err(|e| {
@@ -128,8 +140,28 @@ impl FontCache {
 
         let regular = SwashFont::new(font_data)?;
 
+        let fallback_fonts = fallback_paths
+            .iter()
+            .map(|path| {
+                let data = std::fs::read(path).map_err(|e| {
+                    ScreenshotError::FontLoadError(format!(
+                        "Failed to read fallback font {}: {}",
+                        path.display(),
+                        e
+                    ))
+                })?;
+                SwashFont::new(data)
+            })
+            .collect::<ScreenshotResult<Vec<_>>>()?;
+        // Probe with U+1F600 GRINNING FACE
+        let emoji_override = fallback_fonts
+            .iter()
+            .position(|font| Self::has_glyph(font, '\u{1F600}'));
+
         Ok(Self {
             regular,
+            fallback_fonts,
+            emoji_override,
             emoji_font: None,               // Lazy-loaded when needed
             cjk_fonts: Vec::new(),          // Lazy-loaded when needed
             cjk_font_cache: HashMap::new(), // Cache for character-to-font mapping
@@ -254,6 +286,43 @@ impl FontCache {
         // If no system CJK fonts found, we'll fall back to the main font's tofu boxes
     }
 
+    /// Whether `font` maps `c` to a glyph
+    fn has_glyph(font: &SwashFont, c: char) -> bool {
+        font.font_ref()
+            .is_some_and(|font_ref| font_ref.charmap().map(c) > 0)
+    }
+
+    /// Scale a glyph down to `target_height` if it is taller (color emoji
+    /// bitmap strikes are usually much larger than the cell)
+    fn fit_to_height(
+        target_height: usize,
+        metrics: GlyphMetrics,
+        bitmap: Vec<u8>,
+        format: &BitmapFormat,
+    ) -> (GlyphMetrics, Vec<u8>) {
+        if metrics.height <= target_height {
+            return (metrics, bitmap);
+        }
+        let scale = target_height as f32 / metrics.height as f32;
+        let target_width = (metrics.width as f32 * scale).ceil() as usize;
+        let scaled = Self::scale_bitmap(
+            &bitmap,
+            metrics.width,
+            metrics.height,
+            target_width,
+            target_height,
+            format,
+        );
+        let scaled_metrics = GlyphMetrics {
+            xmin: (metrics.xmin as f32 * scale) as i32,
+            ymin: (metrics.ymin as f32 * scale) as i32,
+            width: target_width,
+            height: target_height,
+            advance_width: metrics.advance_width,
+        };
+        (scaled_metrics, scaled)
+    }
+
     /// Check if a bitmap is empty (all zeros)
     fn is_bitmap_empty(bitmap: &[u8]) -> bool {
         bitmap.iter().all(|&b| b == 0)
@@ -454,6 +523,33 @@ impl FontCache {
             let is_emoji_char = is_emoji(c);
             let is_cjk_char = is_cjk(c);
 
+            // User-configured fallback chain first, in order
+            let target_height = self.font_size.ceil() as usize;
+            for font in &self.fallback_fonts {
+                if !Self::has_glyph(font, c) {
+                    continue;
+                }
+                if let Ok((fb_metrics, fb_bitmap, fb_format)) =
+                    Self::rasterize_glyph(&mut self.scaler, font, c, self.font_size, bold)
+                {
+                    if (fb_metrics.advance_width > 0.0 || fb_metrics.width > 0)
+                        && !Self::is_bitmap_empty(&fb_bitmap)
+                    {
+                        let (fb_metrics, fb_bitmap) =
+                            Self::fit_to_height(target_height, fb_metrics, fb_bitmap, &fb_format);
+                        self.cache.put(
+                            key,
+                            CachedGlyph {
+                                metrics: fb_metrics,
+                                bitmap: fb_bitmap,
+                                format: fb_format,
+                            },
+                        );
+                        return self.cache.get(&key).expect("just inserted");
+                    }
+                }
+            }
+
             // Try CJK fonts if it's a CJK character
             if is_cjk_char {
                 // Lazy-load CJK fonts if not already loaded
@@ -544,33 +640,12 @@ impl FontCache {
                             && !Self::is_bitmap_empty(&emoji_bitmap)
                         {
                             // Scale emoji to fit cell height if needed
-                            let target_height = self.font_size.ceil() as usize;
-                            let (scaled_bitmap, scaled_metrics) = if emoji_metrics.height
-                                > target_height
-                            {
-                                let scale = target_height as f32 / emoji_metrics.height as f32;
-                                let target_width =
-                                    (emoji_metrics.width as f32 * scale).ceil() as usize;
-
-                                let scaled = Self::scale_bitmap(
-                                    &emoji_bitmap,
-                                    emoji_metrics.width,
-                                    emoji_metrics.height,
-                                    target_width,
-                                    target_height,
-                                    &emoji_format,
-                                );
-
-                                let mut scaled_metrics = emoji_metrics.clone();
-                                scaled_metrics.width = target_width;
-                                scaled_metrics.height = target_height;
-                                scaled_metrics.ymin = (emoji_metrics.ymin as f32 * scale) as i32;
-                                scaled_metrics.xmin = (emoji_metrics.xmin as f32 * scale) as i32;
-
-                                (scaled, scaled_metrics)
-                            } else {
-                                (emoji_bitmap, emoji_metrics)
-                            };
+                            let (scaled_metrics, scaled_bitmap) = Self::fit_to_height(
+                                target_height,
+                                emoji_metrics,
+                                emoji_bitmap,
+                                &emoji_format,
+                            );
 
                             self.cache.put(
                                 key,
@@ -706,7 +781,7 @@ impl FontCache {
 
         // Ensure fonts are loaded
         match font_type {
-            FontType::Emoji if self.emoji_font.is_none() => {
+            FontType::Emoji if self.emoji_override.is_none() && self.emoji_font.is_none() => {
                 self.try_load_emoji_font();
             }
             FontType::Cjk if self.cjk_fonts.is_empty() => {
@@ -718,39 +793,23 @@ impl FontCache {
         // Get the font to use
         let font = match font_type {
             FontType::Regular => &self.regular,
-            FontType::Emoji => self.emoji_font.as_ref().unwrap_or(&self.regular),
+            FontType::Emoji => match self.emoji_override {
+                Some(index) => &self.fallback_fonts[index],
+                None => self.emoji_font.as_ref().unwrap_or(&self.regular),
+            },
             FontType::Cjk => self.cjk_fonts.first().unwrap_or(&self.regular),
         };
 
-        let (mut metrics, bitmap, format) =
+        let (metrics, bitmap, format) =
             Self::rasterize_glyph_by_id(&mut self.scaler, font, glyph_id, self.font_size, bold)
                 .ok()?;
 
         // For emoji, scale if needed
-        let target_height = self.font_size.ceil() as usize;
-        let (final_bitmap, final_metrics) =
-            if matches!(font_type, FontType::Emoji) && metrics.height > target_height {
-                let scale = target_height as f32 / metrics.height as f32;
-                let target_width = (metrics.width as f32 * scale).ceil() as usize;
-
-                let scaled = Self::scale_bitmap(
-                    &bitmap,
-                    metrics.width,
-                    metrics.height,
-                    target_width,
-                    target_height,
-                    &format,
-                );
-
-                metrics.width = target_width;
-                metrics.height = target_height;
-                metrics.ymin = (metrics.ymin as f32 * scale) as i32;
-                metrics.xmin = (metrics.xmin as f32 * scale) as i32;
-
-                (scaled, metrics)
-            } else {
-                (bitmap, metrics)
-            };
+        let (final_metrics, final_bitmap) = if matches!(font_type, FontType::Emoji) {
+            Self::fit_to_height(self.font_size.ceil() as usize, metrics, bitmap, &format)
+        } else {
+            (metrics, bitmap)
+        };
 
         Some(CachedGlyph {
             metrics: final_metrics,
@@ -821,13 +880,55 @@ impl FontCache {
         descender // Note: this is negative
     }
 
+    /// Rasterize a main-font glyph as a coverage mask at `font_size`, outside
+    /// the cell glyph cache (for text drawn off the grid, like badges).
+    /// Returns None for glyphs the main font lacks or that have no color-free
+    /// outline.
+    pub fn rasterize_mask(&mut self, c: char, font_size: f32) -> Option<(GlyphMetrics, Vec<u8>)> {
+        match Self::rasterize_glyph(&mut self.scaler, &self.regular, c, font_size, false) {
+            Ok((metrics, bitmap, BitmapFormat::Grayscale)) => Some((metrics, bitmap)),
+            _ => None,
+        }
+    }
+
+    /// Main font ascent and descent (both positive) at `font_size`
+    pub fn line_metrics_at(&self, font_size: f32) -> (f32, f32) {
+        match self.regular.font_ref() {
+            Some(font_ref) => {
+                let metrics = font_ref.metrics(&[]);
+                let scale = font_size / metrics.units_per_em as f32;
+                (metrics.ascent * scale, metrics.descent * scale)
+            }
+            None => (font_size * 0.8, font_size * 0.2),
+        }
+    }
+
+    /// Width of `text` in the main font at `font_size`, from glyph advances
+    pub fn text_advance(&self, text: &str, font_size: f32) -> f32 {
+        let Some(font_ref) = self.regular.font_ref() else {
+            return 0.0;
+        };
+        let charmap = font_ref.charmap();
+        let glyph_metrics = font_ref.glyph_metrics(&[]);
+        let scale = font_size / font_ref.metrics(&[]).units_per_em as f32;
+        text.chars()
+            .map(|c| glyph_metrics.advance_width(charmap.map(c)) * scale)
+            .sum()
+    }
+
     /// Get the regular font data for use with text shaper
     pub fn regular_font_data(&self) -> Arc<Vec<u8>> {
         self.regular.font_data().clone()
     }
 
     /// Get the emoji font data for use with text shaper (if loaded)
+    ///
+    /// Prefers a configured fallback font with emoji coverage over the
+    /// system emoji font.
     pub fn emoji_font_data(&mut self) -> Option<Arc<Vec<u8>>> {
+        if let Some(index) = self.emoji_override {
+            return Some(self.fallback_fonts[index].font_data().clone());
+        }
         // Lazy load emoji font if not already loaded
         if self.emoji_font.is_none() {
             self.try_load_emoji_font();
@@ -852,7 +953,7 @@ mod tests {
 
     #[test]
     fn test_font_cache_creation() {
-        let result = FontCache::new(None, 14.0);
+        let result = FontCache::new(None, &[], 14.0);
         // Don't assert here as system fonts may not be available in all test environments
         if let Ok(mut cache) = result {
             let dims = cache.cell_dimensions();
@@ -863,7 +964,7 @@ mod tests {
 
     #[test]
     fn test_glyph_caching() {
-        if let Ok(mut cache) = FontCache::new(None, 14.0) {
+        if let Ok(mut cache) = FontCache::new(None, &[], 14.0) {
             // Get the same glyph twice - second time should be from cache
             let width1 = cache.get_glyph('A', false, false).metrics.width;
             let width2 = cache.get_glyph('A', false, false).metrics.width;
@@ -875,7 +976,7 @@ mod tests {
 
     #[test]
     fn test_advance_width_calculation() {
-        if let Ok(mut cache) = FontCache::new(None, 14.0) {
+        if let Ok(mut cache) = FontCache::new(None, &[], 14.0) {
             let glyph = cache.get_glyph('M', false, false);
 
             // For a 14px monospace font, advance width should be around 8-10 pixels
@@ -894,7 +995,7 @@ mod tests {
 
     #[test]
     fn test_font_metrics() {
-        if let Ok(mut cache) = FontCache::new(None, 14.0) {
+        if let Ok(mut cache) = FontCache::new(None, &[], 14.0) {
             let ascent = cache.ascent();
             let descent = cache.descent();
             let (_width, _height) = cache.cell_dimensions();
@@ -909,4 +1010,16 @@ mod tests {
             assert!(line_height < 30, "Line height {} is too large", line_height);
         }
     }
+
+    #[test]
+    fn test_missing_fallback_font_is_an_error() {
+        let missing = vec![PathBuf::from("/nonexistent/fallback-font.ttf")];
+        match FontCache::new(None, &missing, 14.0) {
+            Err(ScreenshotError::FontLoadError(msg)) => {
+                assert!(msg.contains("fallback-font.ttf"), "{msg}")
+            }
+            Err(e) => panic!("unexpected error: {e}"),
+            Ok(_) => panic!("missing fallback font should fail"),
+        }
+    }
 }
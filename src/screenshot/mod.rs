@@ -1,3 +1,4 @@
+mod box_drawing;
 mod config;
 mod error;
 mod font_cache;
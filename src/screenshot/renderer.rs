@@ -1,16 +1,28 @@
 use image::{Rgba, RgbaImage};
 
 use crate::cell::{Cell, UnderlineStyle};
-use crate::cursor::{Cursor, CursorStyle};
+use crate::color::Color;
+use crate::cursor::{Cursor, CursorShape};
+use crate::graphics::placeholder::PLACEHOLDER_CHAR;
 use crate::graphics::TerminalGraphic;
 use crate::grid::Grid;
 
+use super::box_drawing;
 use super::config::{ScreenshotConfig, SixelRenderMode};
 use super::error::ScreenshotResult;
 use super::font_cache::{BitmapFormat, FontCache};
 use super::shaper::{ShapedGlyphWithFont, TextShaper};
 use super::utils::{blend_grayscale_pixel, blend_rgba_pixel};
 
+/// Opacity of the cursor guide over the cursor line's background
+const CURSOR_GUIDE_ALPHA: f32 = 0.25;
+
+/// Badge box as a fraction of the content area, as in iTerm2
+const BADGE_MAX_WIDTH: f32 = 0.5;
+const BADGE_MAX_HEIGHT: f32 = 0.2;
+/// Badge distance from the top and right edges of the content area
+const BADGE_MARGIN_PX: u32 = 10;
+
 /// Screenshot renderer
 pub struct Renderer {
     config: ScreenshotConfig,
@@ -21,12 +33,18 @@ pub struct Renderer {
     canvas_height: u32,
     /// Text shaper for handling multi-codepoint emoji (like flags)
     shaper: Option<TextShaper>,
+    /// Top pixel row of the cursor line while a cursor guide is drawn
+    cursor_guide_y: Option<u32>,
 }
 
 impl Renderer {
     /// Create a new renderer
     pub fn new(rows: usize, cols: usize, config: ScreenshotConfig) -> ScreenshotResult<Self> {
-        let mut font_cache = FontCache::new(config.font_path.as_deref(), config.font_size)?;
+        let mut font_cache = FontCache::new(
+            config.font_path.as_deref(),
+            &config.fallback_fonts,
+            config.font_size,
+        )?;
         let (base_width, base_height) = font_cache.cell_dimensions();
 
         // Apply multipliers
@@ -47,6 +65,7 @@ impl Renderer {
             canvas_width,
             canvas_height,
             shaper,
+            cursor_guide_y: None,
         })
     }
 
@@ -96,13 +115,28 @@ impl Renderer {
             Rgba([bg_color.0, bg_color.1, bg_color.2, 255]),
         );
 
+        // The cursor guide tints cell backgrounds on the cursor line, under the text
+        self.cursor_guide_y = cursor
+            .filter(|cursor| {
+                self.config.cursor_guide == Some(true)
+                    && self.config.cursor_guide_color.is_some()
+                    && cursor.row < grid.rows()
+            })
+            .map(|cursor| cursor.row as u32 * self.cell_height + self.config.padding_px);
+
         // Render each row - use shaped rendering for lines with Regional Indicators
+        // or multi-codepoint grapheme clusters (ZWJ sequences, skin tones, marks),
+        // and for every line when ligatures are enabled
         for row in 0..grid.rows() {
             let row_text = grid.row_text(row);
 
             // Use shaped rendering if the line contains Regional Indicators (flags)
-            // and we have a shaper available
-            if self.shaper.is_some() && Self::contains_regional_indicators(&row_text) {
+            // or clusters and we have a shaper available
+            if self.shaper.is_some()
+                && (self.config.enable_ligatures
+                    || Self::contains_regional_indicators(&row_text)
+                    || grid.row(row).is_some_and(Self::contains_clusters))
+            {
                 self.render_shaped_line(&mut image, row, grid)?;
             } else {
                 // Use normal character-by-character rendering
@@ -129,11 +163,15 @@ impl Renderer {
             }
         }
 
+        if self.config.render_badge {
+            self.render_badge(&mut image);
+        }
+
         // Render cursor if enabled and visible
         if self.config.render_cursor {
             if let Some(cursor) = cursor {
                 if cursor.visible && cursor.row < grid.rows() && cursor.col < grid.cols() {
-                    self.render_cursor(&mut image, cursor);
+                    self.render_cursor(&mut image, cursor, grid)?;
                 }
             }
         }
@@ -157,11 +195,7 @@ impl Renderer {
             return Ok(());
         }
 
-        // Resolve effective colors
-        let (fg, bg) = self.resolve_colors(cell);
-
-        // Render background
-        self.render_background(image, x, y, bg);
+        let (fg, _) = self.render_cell_background(image, cell, x, y);
 
         // Render character (if not hidden)
         if !cell.flags.hidden() && cell.c != ' ' {
@@ -170,28 +204,56 @@ impl Renderer {
                 cell.c,
                 x,
                 y,
+                Self::cell_span(cell),
                 fg,
-                bg,
                 cell.flags.bold(),
                 cell.flags.italic(),
             )?;
         }
 
-        // Render text decorations
-        if cell.flags.underline() {
-            let underline_color = cell.underline_color.map(|c| c.to_rgb()).unwrap_or(fg);
-            self.render_underline(image, x, y, cell.flags.underline_style, underline_color);
-        }
+        Ok(())
+    }
 
-        if cell.flags.strikethrough() {
-            self.render_strikethrough(image, x, y, fg);
+    /// Number of columns a cell covers (2 for wide characters)
+    fn cell_span(cell: &Cell) -> u32 {
+        if cell.flags.wide_char() {
+            2
+        } else {
+            1
         }
+    }
 
-        if cell.flags.overline() {
-            self.render_overline(image, x, y, fg);
+    /// Render a cell's background and text decorations across every column it
+    /// covers, so the spacer half of a wide character gets the same
+    /// background as its left half. Returns the resolved (fg, bg) colors.
+    fn render_cell_background(
+        &self,
+        image: &mut RgbaImage,
+        cell: &Cell,
+        x: u32,
+        y: u32,
+    ) -> ((u8, u8, u8), (u8, u8, u8)) {
+        let (fg, bg) = self.resolve_colors(cell);
+
+        for i in 0..Self::cell_span(cell) {
+            let x = x + i * self.cell_width;
+            self.render_background(image, x, y, bg);
+
+            if cell.flags.underline() {
+                let underline_color = cell.underline_color.map(|c| c.to_rgb()).unwrap_or(fg);
+                self.render_underline(image, x, y, cell.flags.underline_style, underline_color);
+            }
+
+            if cell.flags.strikethrough() {
+                self.render_strikethrough(image, x, y, fg);
+            }
+
+            if cell.flags.overline() {
+                self.render_overline(image, x, y, fg);
+            }
         }
 
-        Ok(())
+        (fg, bg)
     }
 
     /// Resolve effective foreground and background colors
@@ -278,118 +340,54 @@ impl Renderer {
                 crate::color_utils::adjust_contrast_rgb(fg_rgb, bg, self.config.minimum_contrast);
         }
 
+        // Enforce the minimum WCAG contrast ratio if enabled
+        if let Some(ratio) = self.config.minimum_contrast_ratio.filter(|r| *r > 1.0) {
+            fg_rgb = crate::color_utils::enforce_contrast_ratio_rgb(fg_rgb, bg, ratio);
+        }
+
         (fg_rgb, bg)
     }
 
-    /// Render block element characters as filled rectangles for pixel-perfect rendering
-    fn render_block_element(
+    /// Draw a box-drawing or block element character from its vector
+    /// coverage mask across `span` columns, blending the foreground over
+    /// what is already in the cell. Returns false if `c` has no built-in
+    /// drawing.
+    fn render_box_drawing(
         &self,
         image: &mut RgbaImage,
         c: char,
         x: u32,
         y: u32,
+        span: u32,
         fg: (u8, u8, u8),
-        bg: (u8, u8, u8),
-    ) -> ScreenshotResult<()> {
-        let cell_w = self.cell_width;
-        let cell_h = self.cell_height;
-
-        match c {
-            // Upper half block: foreground on top, background on bottom
-            '\u{2580}' => {
-                // Top half = foreground
-                for py in 0..cell_h / 2 {
-                    for px in 0..cell_w {
-                        if x + px < self.canvas_width && y + py < self.canvas_height {
-                            image.put_pixel(x + px, y + py, Rgba([fg.0, fg.1, fg.2, 255]));
-                        }
-                    }
-                }
-                // Bottom half = background
-                for py in cell_h / 2..cell_h {
-                    for px in 0..cell_w {
-                        if x + px < self.canvas_width && y + py < self.canvas_height {
-                            image.put_pixel(x + px, y + py, Rgba([bg.0, bg.1, bg.2, 255]));
-                        }
-                    }
-                }
-            }
-            // Lower half block: background on top, foreground on bottom
-            '\u{2584}' => {
-                // Top half = background
-                for py in 0..cell_h / 2 {
-                    for px in 0..cell_w {
-                        if x + px < self.canvas_width && y + py < self.canvas_height {
-                            image.put_pixel(x + px, y + py, Rgba([bg.0, bg.1, bg.2, 255]));
-                        }
-                    }
-                }
-                // Bottom half = foreground
-                for py in cell_h / 2..cell_h {
-                    for px in 0..cell_w {
-                        if x + px < self.canvas_width && y + py < self.canvas_height {
-                            image.put_pixel(x + px, y + py, Rgba([fg.0, fg.1, fg.2, 255]));
-                        }
-                    }
-                }
-            }
-            // Full block: all foreground
-            '\u{2588}' => {
-                for py in 0..cell_h {
-                    for px in 0..cell_w {
-                        if x + px < self.canvas_width && y + py < self.canvas_height {
-                            image.put_pixel(x + px, y + py, Rgba([fg.0, fg.1, fg.2, 255]));
-                        }
-                    }
-                }
-            }
-            // Left half block
-            '\u{258C}' => {
-                for py in 0..cell_h {
-                    for px in 0..cell_w / 2 {
-                        if x + px < self.canvas_width && y + py < self.canvas_height {
-                            image.put_pixel(x + px, y + py, Rgba([fg.0, fg.1, fg.2, 255]));
-                        }
-                    }
-                    for px in cell_w / 2..cell_w {
-                        if x + px < self.canvas_width && y + py < self.canvas_height {
-                            image.put_pixel(x + px, y + py, Rgba([bg.0, bg.1, bg.2, 255]));
-                        }
-                    }
-                }
-            }
-            // Right half block
-            '\u{2590}' => {
-                for py in 0..cell_h {
-                    for px in 0..cell_w / 2 {
-                        if x + px < self.canvas_width && y + py < self.canvas_height {
-                            image.put_pixel(x + px, y + py, Rgba([bg.0, bg.1, bg.2, 255]));
-                        }
-                    }
-                    for px in cell_w / 2..cell_w {
-                        if x + px < self.canvas_width && y + py < self.canvas_height {
-                            image.put_pixel(x + px, y + py, Rgba([fg.0, fg.1, fg.2, 255]));
-                        }
-                    }
-                }
-            }
-            // For other block elements, fall back to background rendering
-            _ => {
-                for py in 0..cell_h {
-                    for px in 0..cell_w {
-                        if x + px < self.canvas_width && y + py < self.canvas_height {
-                            image.put_pixel(x + px, y + py, Rgba([bg.0, bg.1, bg.2, 255]));
-                        }
-                    }
-                }
-            }
+    ) -> bool {
+        let width = span * self.cell_width;
+        let Some(mask) = box_drawing::coverage(c, width, self.cell_height) else {
+            return false;
+        };
+        for (i, &alpha) in mask.iter().enumerate() {
+            let i = i as u32;
+            blend_grayscale_pixel(
+                image,
+                x + i % width,
+                y + i / width,
+                fg,
+                alpha,
+                self.canvas_width,
+                self.canvas_height,
+            );
         }
-
-        Ok(())
+        true
     }
 
     /// Render cell background
     fn render_background(&self, image: &mut RgbaImage, x: u32, y: u32, bg: (u8, u8, u8)) {
+        let bg = match (self.cursor_guide_y, self.config.cursor_guide_color) {
+            (Some(guide_y), Some(guide)) if guide_y == y => Color::Rgb(bg.0, bg.1, bg.2)
+                .mix(&Color::Rgb(guide.0, guide.1, guide.2), CURSOR_GUIDE_ALPHA)
+                .to_rgb(),
+            _ => bg,
+        };
         for dy in 0..self.cell_height {
             for dx in 0..self.cell_width {
                 let px = x + dx;
@@ -409,21 +407,19 @@ impl Renderer {
         c: char,
         x: u32,
         y: u32,
+        span: u32,
         fg: (u8, u8, u8),
-        bg: (u8, u8, u8),
         bold: bool,
         italic: bool,
     ) -> ScreenshotResult<()> {
-        // Get all needed values from font_cache first to avoid multiple mutable borrows
-        // For block-drawing characters, render them as filled rectangles for pixel-perfect rendering
-        // Font glyphs often have spacing/bearing that causes gaps
-        let is_block_element = matches!(c, '\u{2580}'..='\u{259F}');
-
-        if is_block_element {
-            self.render_block_element(image, c, x, y, fg, bg)?;
+        // Box-drawing and block element glyphs in fonts often have spacing or
+        // bearing that leaves gaps between cells, so they are drawn as vectors
+        if self.config.builtin_box_drawing && self.render_box_drawing(image, c, x, y, span, fg) {
             return Ok(());
         }
 
+        // Get all needed values from font_cache first to avoid multiple mutable borrows
+
         let ascent = self.font_cache.ascent();
         let descent = self.font_cache.descent(); // Note: negative value
         let glyph = self.font_cache.get_glyph(c, bold, italic);
@@ -454,15 +450,25 @@ impl Renderer {
         let glyph_width = glyph.metrics.width;
         let glyph_height = glyph.metrics.height;
         let glyph_xmin = glyph.metrics.xmin; // Horizontal bearing (bitmap_left)
+        let glyph_advance = glyph.metrics.advance_width;
         let glyph_bitmap = glyph.bitmap.clone();
         let glyph_format = glyph.format.clone();
 
+        // Wide characters whose glyph is narrower than two cells (fallback
+        // fonts, scaled emoji) are centered in the span
+        let span_px = (span * self.cell_width) as f32;
+        let center_offset = if span > 1 && glyph_advance > 0.0 && glyph_advance < span_px {
+            ((span_px - glyph_advance) / 2.0) as i32
+        } else {
+            0
+        };
+
         // Calculate horizontal offset
         // For box-drawing characters, ignore bearing to ensure they fill the cell edge-to-edge
         let glyph_left_x = if is_box_drawing {
             x as i32
         } else {
-            x as i32 + glyph_xmin
+            x as i32 + glyph_xmin + center_offset
         };
 
         // Use a lightweight faux-bold pass (single-pixel horizontal stroke) so glyphs
@@ -543,6 +549,15 @@ impl Renderer {
         text.chars().any(|c| matches!(c as u32, 0x1F1E6..=0x1F1FF))
     }
 
+    /// Check if a row has cells holding multi-codepoint grapheme clusters
+    /// (Kitty placeholder cells store image data as combining marks and are
+    /// not text)
+    pub(crate) fn contains_clusters(cells: &[Cell]) -> bool {
+        cells
+            .iter()
+            .any(|cell| cell.has_combining_chars() && cell.c != PLACEHOLDER_CHAR)
+    }
+
     /// Render a line using text shaping (for flag emojis and complex emoji)
     fn render_shaped_line(
         &mut self,
@@ -573,86 +588,41 @@ impl Renderer {
         // Render backgrounds and decorations first using normal cell rendering
         for col in 0..grid.cols() {
             if let Some(cell) = grid.get(col, row) {
-                let x = col as u32 * self.cell_width + self.config.padding_px;
-                let y = row as u32 * self.cell_height + self.config.padding_px;
-
                 // Skip wide character spacers
                 if cell.flags.wide_char_spacer() {
                     continue;
                 }
 
-                let (fg, bg) = self.resolve_colors(cell);
-
-                // Render background
-                self.render_background(image, x, y, bg);
-
-                // Render text decorations (underline, strikethrough, overline)
-                if cell.flags.underline() {
-                    let underline_color = cell.underline_color.map(|c| c.to_rgb()).unwrap_or(fg);
-                    self.render_underline(image, x, y, cell.flags.underline_style, underline_color);
-                }
-
-                if cell.flags.strikethrough() {
-                    self.render_strikethrough(image, x, y, fg);
-                }
-
-                if cell.flags.overline() {
-                    self.render_overline(image, x, y, fg);
-                }
+                let x = col as u32 * self.cell_width + self.config.padding_px;
+                let y = row as u32 * self.cell_height + self.config.padding_px;
+                self.render_cell_background(image, cell, x, y);
             }
         }
 
         // Now render shaped glyphs using cluster information
-        // HarfBuzz clusters are BYTE offsets, not character indices!
-        // We need to:
-        // 1. Map byte offset -> character index
-        // 2. Map character index -> grid column
-
-        // Build byte offset -> character index mapping
-        let text_bytes = line_text.len();
-        let mut byte_to_char = vec![0; text_bytes + 1];
-        let mut byte_offset = 0;
-        for (char_idx, c) in line_text.chars().enumerate() {
-            let char_len = c.len_utf8();
-            for i in 0..char_len {
-                if byte_offset + i < byte_to_char.len() {
-                    byte_to_char[byte_offset + i] = char_idx;
+        // HarfBuzz clusters are BYTE offsets into the row text, which holds
+        // each non-spacer cell's full grapheme (base + combining marks), so
+        // map every byte of a cell's grapheme to that cell's column.
+        let mut byte_to_col = Vec::with_capacity(line_text.len());
+        if let Some(cells) = grid.row(row) {
+            for (col, cell) in cells.iter().enumerate() {
+                if cell.flags.wide_char_spacer() {
+                    continue;
                 }
+                let grapheme_len = cell.c.len_utf8()
+                    + cell.combining().iter().map(|c| c.len_utf8()).sum::<usize>();
+                byte_to_col.extend(std::iter::repeat_n(col, grapheme_len));
             }
-            byte_offset += char_len;
-        }
-
-        // Build character index -> grid column mapping
-        // Calculate column position for each character based on width
-        let mut char_to_col = Vec::new();
-        let mut current_col = 0;
-
-        for c in line_text.chars() {
-            char_to_col.push(current_col);
-            // Use configured width calculation (1 for normal, 2 for wide)
-            let width = crate::unicode_width_config::char_width(
-                c,
-                &crate::unicode_width_config::WidthConfig::default(),
-            );
-            current_col += width;
         }
 
         // Render each shaped glyph at its corresponding grid column
         for shaped in &shaped_glyphs {
             let byte_cluster = shaped.glyph.cluster as usize;
 
-            // Convert byte offset to character index
-            if byte_cluster >= byte_to_char.len() {
+            // Find the grid column for this cluster
+            let Some(&col) = byte_to_col.get(byte_cluster) else {
                 continue; // Cluster out of range
-            }
-            let char_index = byte_to_char[byte_cluster];
-
-            // Find the grid column for this character
-            if char_index >= char_to_col.len() {
-                continue; // Character index out of range
-            }
-
-            let col = char_to_col[char_index];
+            };
             if let Some(cell) = grid.get(col, row) {
                 // Skip hidden or space characters
                 if cell.flags.hidden() || cell.c == ' ' {
@@ -664,8 +634,25 @@ impl Renderer {
 
                 let (fg, _) = self.resolve_colors(cell);
 
+                // Box-drawing and block elements are drawn as vectors, as in
+                // per-cell rendering
+                if self.config.builtin_box_drawing
+                    && self.render_box_drawing(image, cell.c, x, y, Self::cell_span(cell), fg)
+                {
+                    continue;
+                }
+
                 // Render the shaped glyph
-                self.render_shaped_glyph(image, shaped, x, y, fg, cell.flags.bold(), cell.c)?;
+                self.render_shaped_glyph(
+                    image,
+                    shaped,
+                    x,
+                    y,
+                    Self::cell_span(cell),
+                    fg,
+                    cell.flags.bold(),
+                    cell.c,
+                )?;
             }
         }
 
@@ -680,6 +667,7 @@ impl Renderer {
         shaped: &ShapedGlyphWithFont,
         x: u32,
         y: u32,
+        span: u32,
         fg: (u8, u8, u8),
         bold: bool,
         c: char,
@@ -718,8 +706,17 @@ impl Renderer {
         let glyph_x_offset = shaped.glyph.x_offset / 64;
         let glyph_y_offset = shaped.glyph.y_offset / 64;
 
+        // Center glyphs narrower than a wide cell's two-column span
+        let span_px = (span * self.cell_width) as f32;
+        let advance = glyph.metrics.advance_width;
+        let center_offset = if span > 1 && advance > 0.0 && advance < span_px {
+            ((span_px - advance) / 2.0) as i32
+        } else {
+            0
+        };
+
         // Calculate final glyph position
-        let glyph_left_x = x as i32 + glyph.metrics.xmin + glyph_x_offset;
+        let glyph_left_x = x as i32 + glyph.metrics.xmin + glyph_x_offset + center_offset;
         let glyph_top_y = baseline_y + glyph.metrics.ymin + glyph_y_offset;
 
         // Clone glyph data to avoid borrow checker issues
@@ -1021,75 +1018,171 @@ impl Renderer {
                 // First render background (bottom color)
                 self.render_background(image, x, y, bottom_rgb);
 
-                // Then render the half-block character with foreground (top color)
-                self.render_char(image, '▀', x, y, top_rgb, bottom_rgb, false, false)?;
+                // Then draw the half-block character with foreground (top color)
+                self.render_box_drawing(image, '▀', x, y, 1, top_rgb);
             }
         }
 
         Ok(())
     }
 
-    /// Render cursor at the given position
-    fn render_cursor(&self, image: &mut RgbaImage, cursor: &Cursor) {
-        let cursor_color = self.config.cursor_color;
-        let x = self.config.padding_px + cursor.col as u32 * self.cell_width;
-        let y = self.config.padding_px + cursor.row as u32 * self.cell_height;
-
-        match cursor.style {
-            CursorStyle::BlinkingBlock | CursorStyle::SteadyBlock => {
-                // Render block cursor - fill the entire cell
-                for dy in 0..self.cell_height {
-                    for dx in 0..self.cell_width {
-                        let px = x + dx;
-                        let py = y + dy;
-                        if px < self.canvas_width && py < self.canvas_height {
-                            // Semi-transparent cursor (50% opacity)
-                            let existing = image.get_pixel(px, py);
-                            let blended = Rgba([
-                                ((existing[0] as u16 + cursor_color.0 as u16) / 2) as u8,
-                                ((existing[1] as u16 + cursor_color.1 as u16) / 2) as u8,
-                                ((existing[2] as u16 + cursor_color.2 as u16) / 2) as u8,
-                                255,
-                            ]);
-                            image.put_pixel(px, py, blended);
-                        }
-                    }
-                }
+    /// Fill `[x, x + width) x [y, y + height)` with a solid color
+    fn fill_rect(
+        &self,
+        image: &mut RgbaImage,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        color: (u8, u8, u8),
+    ) {
+        for py in y..(y + height).min(self.canvas_height) {
+            for px in x..(x + width).min(self.canvas_width) {
+                image.put_pixel(px, py, Rgba([color.0, color.1, color.2, 255]));
             }
-            CursorStyle::BlinkingUnderline | CursorStyle::SteadyUnderline => {
-                // Render underline cursor - 2 pixels high at bottom of cell
-                let underline_start = y + self.cell_height - 2;
-                for dy in 0..2 {
-                    for dx in 0..self.cell_width {
-                        let px = x + dx;
-                        let py = underline_start + dy;
-                        if px < self.canvas_width && py < self.canvas_height {
-                            image.put_pixel(
-                                px,
-                                py,
-                                Rgba([cursor_color.0, cursor_color.1, cursor_color.2, 255]),
+        }
+    }
+
+    /// Composite the badge text in the top-right corner of the content area,
+    /// bold and scaled to fit `BADGE_MAX_WIDTH` x `BADGE_MAX_HEIGHT` of it,
+    /// one right-aligned line per `\n`, blended at `badge_alpha`
+    fn render_badge(&mut self, image: &mut RgbaImage) {
+        let Some(text) = self.config.badge_text.as_deref() else {
+            return;
+        };
+        let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+        if lines.iter().all(|line| line.is_empty()) {
+            return;
+        }
+        let color = self.config.badge_color.unwrap_or((255, 0, 0));
+        let alpha = self.config.badge_alpha.clamp(0.0, 1.0);
+        let padding = self.config.padding_px;
+        let content_width = self.canvas_width.saturating_sub(padding * 2) as f32;
+        let content_height = self.canvas_height.saturating_sub(padding * 2) as f32;
+
+        // Measure at a reference size, then scale to fit the badge box
+        const REFERENCE_SIZE: f32 = 100.0;
+        let text_width = lines
+            .iter()
+            .map(|line| self.font_cache.text_advance(line, REFERENCE_SIZE))
+            .fold(0.0, f32::max);
+        let (ascent, descent) = self.font_cache.line_metrics_at(REFERENCE_SIZE);
+        let text_height = (ascent + descent) * lines.len() as f32;
+        if text_width <= 0.0 || text_height <= 0.0 {
+            return;
+        }
+        let scale = (content_width * BADGE_MAX_WIDTH / text_width)
+            .min(content_height * BADGE_MAX_HEIGHT / text_height);
+        let font_size = REFERENCE_SIZE * scale;
+        let (ascent, descent) = (ascent * scale, descent * scale);
+        let stroke = (font_size / 24.0).round().max(1.0) as usize;
+
+        let right = (self.canvas_width - padding).saturating_sub(BADGE_MARGIN_PX) as f32;
+        let mut baseline = (padding + BADGE_MARGIN_PX) as f32 + ascent;
+        for line in lines {
+            let mut pen_x = right - self.font_cache.text_advance(line, font_size);
+            for c in line.chars() {
+                if let Some((metrics, mask)) = self
+                    .font_cache
+                    .rasterize_mask(c, font_size)
+                    .filter(|(metrics, _)| metrics.width > 0)
+                {
+                    // Outline emboldening leaves holes where contours overlap,
+                    // so smear the mask horizontally instead
+                    let width = metrics.width;
+                    let left = pen_x.round() as i32 + metrics.xmin;
+                    let top = baseline.round() as i32 + metrics.ymin;
+                    for y in 0..metrics.height {
+                        let row = &mask[y * width..(y + 1) * width];
+                        for x in 0..width + stroke {
+                            let coverage = (x.saturating_sub(stroke)..=x.min(width - 1))
+                                .map(|x| row[x])
+                                .max()
+                                .unwrap_or(0);
+                            let (px, py) = (left + x as i32, top + y as i32);
+                            if coverage == 0 || px < 0 || py < 0 {
+                                continue;
+                            }
+                            blend_grayscale_pixel(
+                                image,
+                                px as u32,
+                                py as u32,
+                                color,
+                                (coverage as f32 * alpha) as u8,
+                                self.canvas_width,
+                                self.canvas_height,
                             );
                         }
                     }
                 }
+                pen_x += self
+                    .font_cache
+                    .text_advance(c.encode_utf8(&mut [0; 4]), font_size);
             }
-            CursorStyle::BlinkingBar | CursorStyle::SteadyBar => {
-                // Render bar cursor - 2 pixels wide at left edge
-                for dy in 0..self.cell_height {
-                    for dx in 0..2 {
-                        let px = x + dx;
-                        let py = y + dy;
-                        if px < self.canvas_width && py < self.canvas_height {
-                            image.put_pixel(
-                                px,
-                                py,
-                                Rgba([cursor_color.0, cursor_color.1, cursor_color.2, 255]),
-                            );
-                        }
-                    }
+            baseline += ascent + descent;
+        }
+    }
+
+    /// Render cursor at the given position in its tracked style, the way a
+    /// frontend shows it: a block is solid with the character redrawn in
+    /// the cell's background color, underline and bar are 2 pixels thick.
+    /// With `smart_cursor_color` the cursor takes the cell's foreground
+    /// color so it stays visible on any background. Blinking styles are
+    /// omitted when `cursor_blink_visible` captures the off phase.
+    fn render_cursor(
+        &mut self,
+        image: &mut RgbaImage,
+        cursor: &Cursor,
+        grid: &Grid,
+    ) -> ScreenshotResult<()> {
+        if cursor.style.is_blinking() && !self.config.cursor_blink_visible {
+            return Ok(());
+        }
+        let cell = grid
+            .get(cursor.col, cursor.row)
+            .filter(|cell| !cell.flags.wide_char_spacer());
+        let (cell_fg, cell_bg) = match cell {
+            Some(cell) => self.resolve_colors(cell),
+            None => (
+                (255, 255, 255),
+                self.config.background_color.unwrap_or((0, 0, 0)),
+            ),
+        };
+        let cursor_color = if self.config.smart_cursor_color == Some(true) {
+            cell_fg
+        } else {
+            self.config.cursor_color
+        };
+        let span = cell.map_or(1, Self::cell_span);
+        let width = span * self.cell_width;
+        let x = self.config.padding_px + cursor.col as u32 * self.cell_width;
+        let y = self.config.padding_px + cursor.row as u32 * self.cell_height;
+
+        match cursor.style.shape() {
+            CursorShape::Block => {
+                self.fill_rect(image, x, y, width, self.cell_height, cursor_color);
+                if let Some(cell) = cell.filter(|cell| !cell.flags.hidden() && cell.c != ' ') {
+                    self.render_char(
+                        image,
+                        cell.c,
+                        x,
+                        y,
+                        span,
+                        cell_bg,
+                        cell.flags.bold(),
+                        cell.flags.italic(),
+                    )?;
                 }
             }
+            CursorShape::Underline => {
+                let y = y + self.cell_height.saturating_sub(2);
+                self.fill_rect(image, x, y, width, 2, cursor_color);
+            }
+            CursorShape::Bar => {
+                self.fill_rect(image, x, y, 2, self.cell_height, cursor_color);
+            }
         }
+        Ok(())
     }
 }
 
@@ -1103,6 +1196,7 @@ mod tests {
     fn create_test_config() -> ScreenshotConfig {
         ScreenshotConfig {
             font_path: None,
+            fallback_fonts: Vec::new(),
             font_size: 14.0,
             padding_px: 10,
             char_width_multiplier: 1.0,
@@ -1119,8 +1213,21 @@ mod tests {
             use_bold_color: false,
             bold_brightening: false,
             minimum_contrast: 0.5,
+            minimum_contrast_ratio: None,
             faint_text_alpha: 0.5,
             iterm2_color_boost: true,
+            enable_ligatures: false,
+            builtin_box_drawing: true,
+            cursor_blink_visible: true,
+            smart_cursor_color: None,
+            cursor_guide: None,
+            cursor_guide_color: None,
+            render_selection: false,
+            render_search_matches: false,
+            render_badge: false,
+            badge_text: None,
+            badge_color: None,
+            badge_alpha: 0.5,
             quality: 90,
             format: crate::screenshot::config::ImageFormat::Png,
         }
@@ -1761,7 +1868,7 @@ mod tests {
 
     #[test]
     fn test_render_cursor_block_modifies_pixels() {
-        let renderer = make_test_renderer();
+        let mut renderer = make_test_renderer();
         let cell_w = renderer.cell_width;
         let cell_h = renderer.cell_height;
         let w = renderer.canvas_width;
@@ -1773,9 +1880,10 @@ mod tests {
             visible: true,
             style: CursorStyle::SteadyBlock,
         };
-        renderer.render_cursor(&mut image, &cursor);
-        // Block cursor at (col=2, row=2) blends at 50% alpha with white cursor_color
-        // Background is black (0,0,0) + white (255,255,255) / 2 = (127,127,127)
+        renderer
+            .render_cursor(&mut image, &cursor, &Grid::new(80, 24, 0))
+            .unwrap();
+        // Block cursor at (col=2, row=2) is filled with the white cursor_color
         let cx = renderer.config.padding_px + cell_w * 2 + cell_w / 2;
         let cy = renderer.config.padding_px + cell_h * 2 + cell_h / 2;
         let center = image.get_pixel(cx, cy);
@@ -1786,4 +1894,174 @@ mod tests {
             center
         );
     }
+
+    #[test]
+    fn test_wide_char_background_covers_spacer_column() {
+        let mut renderer = make_test_renderer();
+        let w = renderer.canvas_width;
+        let h = renderer.canvas_height;
+        let mut image = RgbaImage::from_pixel(w, h, Rgba([0, 0, 0, 255]));
+        let mut cell = Cell::with_colors(
+            '中',
+            crate::color::Color::Rgb(255, 255, 255),
+            crate::color::Color::Rgb(0, 0, 200),
+        );
+        cell.flags.set_wide_char(true);
+        renderer
+            .render_cell(&mut image, &cell, 0, 0)
+            .expect("render wide cell");
+
+        // Pixel near the right edge of the spacer column (col 1)
+        let pad = renderer.config.padding_px;
+        let px = image.get_pixel(pad + renderer.cell_width * 2 - 1, pad + 1);
+        assert_eq!((px[0], px[1], px[2]), (0, 0, 200));
+        // Column 2 is untouched
+        let px = image.get_pixel(pad + renderer.cell_width * 2 + 1, pad + 1);
+        assert_eq!((px[0], px[1], px[2]), (0, 0, 0));
+    }
+
+    #[test]
+    fn test_contains_clusters() {
+        let plain = vec![Cell::new('a'), Cell::new('b')];
+        assert!(!Renderer::contains_clusters(&plain));
+
+        let mut family = Cell::new('\u{1F468}');
+        family.combining.push('\u{200D}');
+        family.combining.push('\u{1F469}');
+        assert!(Renderer::contains_clusters(&[Cell::new('a'), family]));
+
+        // Kitty placeholder cells carry image data in combining marks
+        let mut placeholder = Cell::new(PLACEHOLDER_CHAR);
+        placeholder.combining.push('\u{0305}');
+        assert!(!Renderer::contains_clusters(&[placeholder]));
+    }
+
+    #[test]
+    fn test_ligatures_change_rendering_of_arrow() {
+        let mut grid = Grid::new(4, 1, 0);
+        for (col, c) in "=>".chars().enumerate() {
+            grid.set(col, 0, Cell::new(c));
+        }
+
+        let render = |config: ScreenshotConfig| {
+            let mut renderer = Renderer::new(1, 4, config).expect("renderer");
+            renderer.render_grid(&grid, None, &[]).expect("render")
+        };
+        let plain = render(ScreenshotConfig::default());
+        let ligated = render(ScreenshotConfig::default().with_ligatures(true));
+
+        // JetBrains Mono substitutes "=>" with a ligature glyph
+        assert_ne!(plain.as_raw(), ligated.as_raw());
+    }
+
+    #[test]
+    fn test_ligatures_leave_plain_text_unchanged() {
+        let mut grid = Grid::new(4, 1, 0);
+        for (col, c) in "abc".chars().enumerate() {
+            grid.set(col, 0, Cell::new(c));
+        }
+
+        let render = |config: ScreenshotConfig| {
+            let mut renderer = Renderer::new(1, 4, config).expect("renderer");
+            renderer.render_grid(&grid, None, &[]).expect("render")
+        };
+        let plain = render(ScreenshotConfig::default());
+        let shaped = render(ScreenshotConfig::default().with_ligatures(true));
+
+        assert_eq!(plain.as_raw(), shaped.as_raw());
+    }
+
+    #[test]
+    fn test_badge_drawn_in_top_right_corner() {
+        let config = ScreenshotConfig {
+            background_color: Some((0, 0, 0)),
+            padding_px: 0,
+            render_badge: true,
+            badge_text: Some("PROD".to_string()),
+            badge_color: Some((255, 0, 0)),
+            badge_alpha: 1.0,
+            ..create_test_config()
+        };
+        let mut renderer = Renderer::new(10, 40, config).expect("renderer");
+        let image = renderer
+            .render_grid(&Grid::new(40, 10, 0), None, &[])
+            .expect("render grid");
+        let (width, height) = image.dimensions();
+        let red_at = |x0: u32, x1: u32, y0: u32, y1: u32| {
+            (y0..y1).any(|y| (x0..x1).any(|x| image.get_pixel(x, y)[0] > 128))
+        };
+        // Inside the top-right box (half the width, a fifth of the height)
+        assert!(red_at(width / 2, width, 0, height / 5 + BADGE_MARGIN_PX));
+        assert!(!red_at(0, width / 2 - 1, 0, height));
+        assert!(!red_at(0, width, height / 5 + BADGE_MARGIN_PX + 1, height));
+    }
+
+    fn render_with_cursor(config: ScreenshotConfig, style: CursorStyle) -> (Renderer, RgbaImage) {
+        let mut renderer = Renderer::new(4, 10, config).expect("renderer");
+        let mut grid = Grid::new(10, 4, 0);
+        grid.set(
+            1,
+            1,
+            Cell::with_colors('x', Color::Rgb(0, 200, 0), Color::Rgb(0, 0, 0)),
+        );
+        let cursor = Cursor {
+            col: 1,
+            row: 1,
+            visible: true,
+            style,
+        };
+        let image = renderer
+            .render_grid(&grid, Some(&cursor), &[])
+            .expect("render grid");
+        (renderer, image)
+    }
+
+    #[test]
+    fn test_smart_cursor_color_uses_cell_foreground() {
+        let config = ScreenshotConfig {
+            render_cursor: true,
+            smart_cursor_color: Some(true),
+            background_color: Some((0, 0, 0)),
+            ..Default::default()
+        };
+        let (renderer, image) = render_with_cursor(config, CursorStyle::SteadyBlock);
+        let cell = Cell::with_colors('x', Color::Rgb(0, 200, 0), Color::Rgb(0, 0, 0));
+        let (fg, _) = renderer.resolve_colors(&cell);
+        let pad = renderer.config.padding_px;
+        let px = image.get_pixel(pad + renderer.cell_width, pad + renderer.cell_height);
+        assert_eq!((px[0], px[1], px[2]), fg);
+    }
+
+    #[test]
+    fn test_blinking_cursor_omitted_in_off_phase() {
+        let config = ScreenshotConfig {
+            render_cursor: true,
+            cursor_blink_visible: false,
+            background_color: Some((0, 0, 0)),
+            ..Default::default()
+        };
+        let (renderer, image) = render_with_cursor(config.clone(), CursorStyle::BlinkingBar);
+        let pad = renderer.config.padding_px;
+        let bar = (pad + renderer.cell_width, pad + renderer.cell_height + 1);
+        assert_eq!(image.get_pixel(bar.0, bar.1)[0], 0);
+
+        let (_, image) = render_with_cursor(config, CursorStyle::SteadyBar);
+        assert_eq!(image.get_pixel(bar.0, bar.1)[0], 255);
+    }
+
+    #[test]
+    fn test_cursor_guide_tints_cursor_line() {
+        let config = ScreenshotConfig {
+            cursor_guide: Some(true),
+            cursor_guide_color: Some((255, 0, 0)),
+            background_color: Some((0, 0, 0)),
+            ..Default::default()
+        };
+        let (renderer, image) = render_with_cursor(config, CursorStyle::SteadyBlock);
+        let pad = renderer.config.padding_px;
+        let on_line = image.get_pixel(pad + renderer.cell_width * 5, pad + renderer.cell_height);
+        assert!(on_line[0] > 0 && on_line[0] < 128, "{on_line:?}");
+        let off_line = image.get_pixel(pad + renderer.cell_width * 5, pad);
+        assert_eq!(off_line[0], 0);
+    }
 }
@@ -34,6 +34,12 @@ pub const SIXEL_DEFAULT_MAX_HEIGHT: usize = 1024;
 pub const SIXEL_DEFAULT_MAX_REPEAT: usize = 10_000;
 pub const SIXEL_DEFAULT_MAX_GRAPHICS: usize = 256;
 
+/// Number of Sixel color registers (reported by XTSMGRAPHICS)
+pub const SIXEL_COLOR_REGISTERS: usize = 4096;
+
+/// `image_data` marker for pixels no sixel has touched
+const UNSET: usize = usize::MAX;
+
 /// Per-terminal Sixel resource limits
 #[derive(Debug, Clone, Copy)]
 pub struct SixelLimits {
@@ -250,7 +256,7 @@ pub struct SixelParser {
     /// Current cursor position (x, y) in pixels
     cursor: (usize, usize),
     /// Image data being built
-    image_data: Vec<Vec<usize>>, // [row][col] = color_index
+    image_data: Vec<Vec<usize>>, // [row][col] = color_index or UNSET
     /// Maximum width seen
     max_width: usize,
     /// Background mode (0=pixel, 1=transparent, 2=pixel)
@@ -268,6 +274,21 @@ impl SixelParser {
 
     /// Create a new parser with explicit limits
     pub fn new_with_limits(limits: SixelLimits) -> Self {
+        Self {
+            params: Vec::new(),
+            palette: Self::default_palette(),
+            current_color: 0,
+            cursor: (0, 0),
+            image_data: vec![vec![]],
+            max_width: 0,
+            background_mode: 0,
+            raster_size: None,
+            limits,
+        }
+    }
+
+    /// Default VGA palette for color registers 0-15
+    pub fn default_palette() -> HashMap<usize, SixelColor> {
         let mut palette = HashMap::new();
         // Default VGA palette (first 16 colors)
         palette.insert(0, SixelColor::new(0, 0, 0)); // Black
@@ -286,18 +307,22 @@ impl SixelParser {
         palette.insert(13, SixelColor::new(102, 230, 230)); // Light Cyan
         palette.insert(14, SixelColor::new(230, 230, 102)); // Light Yellow
         palette.insert(15, SixelColor::new(255, 255, 255)); // White
+        palette
+    }
 
-        Self {
-            params: Vec::new(),
-            palette,
-            current_color: 0,
-            cursor: (0, 0),
-            image_data: vec![vec![]],
-            max_width: 0,
-            background_mode: 0,
-            raster_size: None,
-            limits,
-        }
+    /// Color registers as currently defined
+    pub fn palette(&self) -> &HashMap<usize, SixelColor> {
+        &self.palette
+    }
+
+    /// Replace the color registers (used to share registers between images)
+    pub fn set_palette(&mut self, palette: HashMap<usize, SixelColor>) {
+        self.palette = palette;
+    }
+
+    /// Take the color registers, leaving the parser's palette empty
+    pub fn take_palette(&mut self) -> HashMap<usize, SixelColor> {
+        std::mem::take(&mut self.palette)
     }
 
     pub fn set_params(&mut self, params: &[u16]) {
@@ -335,8 +360,8 @@ impl SixelParser {
                     self.image_data.push(vec![]);
                 }
 
-                while self.image_data[y].len() <= x {
-                    self.image_data[y].push(0); // Background color
+                if self.image_data[y].len() <= x {
+                    self.image_data[y].resize(x + 1, UNSET);
                 }
 
                 self.image_data[y][x] = self.current_color;
@@ -345,7 +370,7 @@ impl SixelParser {
 
         // Move cursor right
         self.cursor.0 += 1;
-        if self.cursor.0 < self.limits.max_width {
+        if self.cursor.0 <= self.limits.max_width {
             self.max_width = self.max_width.max(self.cursor.0);
         }
     }
@@ -399,6 +424,10 @@ impl SixelParser {
     }
 
     /// Build final graphic from parsed data
+    ///
+    /// Pixels no sixel touched are transparent when P2 is 1; otherwise they
+    /// are filled with color register 0, the sixel background. That covers
+    /// the whole raster area declared with `"Pan;Pad;Ph;Pv`.
     pub fn build_graphic(&self, position: (usize, usize)) -> SixelGraphic {
         let height = self.image_data.len();
         let width = self.max_width.max(1);
@@ -413,16 +442,20 @@ impl SixelParser {
         let mut graphic = SixelGraphic::new(position, final_width, final_height);
         graphic.palette = self.palette.clone();
 
+        let background = match self.background_mode {
+            1 => None,
+            _ => self.palette.get(&0).copied(),
+        };
+
         // Fill in pixels from image_data
-        for (y, row) in self.image_data.iter().enumerate() {
-            if y >= final_height {
-                break;
-            }
-            for (x, &color_idx) in row.iter().enumerate() {
-                if x >= final_width {
-                    break;
-                }
-                if let Some(&color) = self.palette.get(&color_idx) {
+        for y in 0..final_height {
+            let row = self.image_data.get(y);
+            for x in 0..final_width {
+                let color = match row.and_then(|r| r.get(x)).copied() {
+                    Some(UNSET) | None => background,
+                    Some(color_idx) => self.palette.get(&color_idx).copied(),
+                };
+                if let Some(color) = color {
                     graphic.set_pixel(x, y, color);
                 }
             }
@@ -554,4 +587,22 @@ mod tests {
         // max_width should remain 0 since cursor was beyond limits
         assert_eq!(parser.max_width, 0);
     }
+
+    #[test]
+    fn test_sixel_background_fill_and_transparency() {
+        for (mode, expected) in [(0, Some((0, 0, 0, 255))), (1, Some((0, 0, 0, 0)))] {
+            let mut parser = SixelParser::new();
+            parser.set_params(&[0, mode]);
+            parser.set_raster_attributes(1, 1, 4, 6);
+            parser.select_color(15);
+            parser.parse_sixel('@'); // bit 0 only
+
+            let graphic = parser.build_graphic((0, 0));
+            assert_eq!((graphic.width, graphic.height), (4, 6));
+            assert_eq!(graphic.get_pixel(0, 0), Some((255, 255, 255, 255)));
+            // Below the drawn pixel and past the drawn column
+            assert_eq!(graphic.get_pixel(0, 1), expected, "P2={}", mode);
+            assert_eq!(graphic.get_pixel(3, 5), expected, "P2={}", mode);
+        }
+    }
 }
@@ -13,10 +13,11 @@
 
 use crate::streaming::error::{Result, StreamingError};
 use crate::streaming::protocol::{
-    ClientMessage as AppClientMessage, CpuStats as AppCpuStats, DiskStats as AppDiskStats,
-    EventType as AppEventType, LoadAverage as AppLoadAverage, MemoryStats as AppMemoryStats,
-    NetworkInterfaceStats as AppNetworkInterfaceStats, ServerMessage as AppServerMessage,
-    ThemeInfo as AppThemeInfo,
+    CellColor as AppCellColor, CellRun as AppCellRun, ClientMessage as AppClientMessage,
+    CpuStats as AppCpuStats, DiskStats as AppDiskStats, EventType as AppEventType,
+    LoadAverage as AppLoadAverage, MemoryStats as AppMemoryStats,
+    NetworkInterfaceStats as AppNetworkInterfaceStats, ScreenRow as AppScreenRow,
+    ScreenZone as AppScreenZone, ServerMessage as AppServerMessage, ThemeInfo as AppThemeInfo,
 };
 use flate2::read::ZlibDecoder;
 use flate2::write::ZlibEncoder;
@@ -189,6 +190,56 @@ impl From<&AppThemeInfo> for pb::ThemeInfo {
     }
 }
 
+impl From<&AppCellColor> for pb::CellColor {
+    fn from(color: &AppCellColor) -> Self {
+        use pb::cell_color::Value;
+
+        let value = match *color {
+            AppCellColor::Indexed(idx) => Value::Indexed(idx as u32),
+            AppCellColor::Rgb(r, g, b) => Value::Rgb(pb::Color {
+                r: r as u32,
+                g: g as u32,
+                b: b as u32,
+            }),
+        };
+        pb::CellColor { value: Some(value) }
+    }
+}
+
+impl From<&AppScreenRow> for pb::ScreenRow {
+    fn from(row: &AppScreenRow) -> Self {
+        pb::ScreenRow {
+            runs: row
+                .runs
+                .iter()
+                .map(|run| pb::CellRun {
+                    col: run.col as u32,
+                    width: run.width as u32,
+                    text: run.text.clone(),
+                    fg: run.fg.as_ref().map(Into::into),
+                    bg: run.bg.as_ref().map(Into::into),
+                    flags: run.flags as u32,
+                    hyperlink: run.hyperlink.clone(),
+                })
+                .collect(),
+            wrapped: row.wrapped,
+        }
+    }
+}
+
+impl From<&AppScreenZone> for pb::ScreenZone {
+    fn from(zone: &AppScreenZone) -> Self {
+        pb::ScreenZone {
+            zone_id: zone.zone_id,
+            zone_type: zone.zone_type.clone(),
+            abs_row_start: zone.abs_row_start,
+            abs_row_end: zone.abs_row_end,
+            command: zone.command.clone(),
+            exit_code: zone.exit_code,
+        }
+    }
+}
+
 impl From<&AppServerMessage> for pb::ServerMessage {
     fn from(msg: &AppServerMessage) -> Self {
         use pb::server_message::Message;
@@ -562,6 +613,27 @@ impl From<&AppServerMessage> for pb::ServerMessage {
                     include_scrollback: *include_scrollback,
                 }))
             }
+            AppServerMessage::ScreenSnapshot {
+                cols,
+                rows,
+                lines,
+                cursor_col,
+                cursor_row,
+                cursor_visible,
+                scrollback_len,
+                zones,
+                alt_screen,
+            } => Some(Message::ScreenSnapshot(pb::ScreenSnapshot {
+                cols: *cols as u32,
+                rows: *rows as u32,
+                lines: lines.iter().map(Into::into).collect(),
+                cursor_col: *cursor_col as u32,
+                cursor_row: *cursor_row as u32,
+                cursor_visible: *cursor_visible,
+                scrollback_len: *scrollback_len,
+                zones: zones.iter().map(Into::into).collect(),
+                alt_screen: *alt_screen,
+            })),
         };
 
         pb::ServerMessage { message }
@@ -637,6 +709,9 @@ impl From<&AppClientMessage> for pb::ClientMessage {
                 scope: scope.clone(),
                 max_commands: *max_commands,
             })),
+            AppClientMessage::ScreenSnapshotRequest => {
+                Some(Message::ScreenSnapshotRequest(pb::ScreenSnapshotRequest {}))
+            }
         };
 
         pb::ClientMessage { message }
@@ -724,6 +799,59 @@ impl TryFrom<pb::ThemeInfo> for AppThemeInfo {
     }
 }
 
+impl TryFrom<pb::CellColor> for AppCellColor {
+    type Error = StreamingError;
+
+    fn try_from(color: pb::CellColor) -> Result<Self> {
+        use pb::cell_color::Value;
+
+        match color.value {
+            Some(Value::Indexed(idx)) => Ok(AppCellColor::Indexed(idx as u8)),
+            Some(Value::Rgb(c)) => Ok(AppCellColor::Rgb(c.r as u8, c.g as u8, c.b as u8)),
+            None => Err(StreamingError::InvalidMessage("Empty cell color".into())),
+        }
+    }
+}
+
+impl TryFrom<pb::ScreenRow> for AppScreenRow {
+    type Error = StreamingError;
+
+    fn try_from(row: pb::ScreenRow) -> Result<Self> {
+        let runs = row
+            .runs
+            .into_iter()
+            .map(|run| {
+                Ok(AppCellRun {
+                    col: run.col as u16,
+                    width: run.width as u16,
+                    text: run.text,
+                    fg: run.fg.map(TryInto::try_into).transpose()?,
+                    bg: run.bg.map(TryInto::try_into).transpose()?,
+                    flags: run.flags as u16,
+                    hyperlink: run.hyperlink,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(AppScreenRow {
+            runs,
+            wrapped: row.wrapped,
+        })
+    }
+}
+
+impl From<pb::ScreenZone> for AppScreenZone {
+    fn from(zone: pb::ScreenZone) -> Self {
+        AppScreenZone {
+            zone_id: zone.zone_id,
+            zone_type: zone.zone_type,
+            abs_row_start: zone.abs_row_start,
+            abs_row_end: zone.abs_row_end,
+            command: zone.command,
+            exit_code: zone.exit_code,
+        }
+    }
+}
+
 impl TryFrom<pb::ServerMessage> for AppServerMessage {
     type Error = StreamingError;
 
@@ -974,6 +1102,21 @@ impl TryFrom<pb::ServerMessage> for AppServerMessage {
             Some(Message::ScreenCleared(sc)) => Ok(AppServerMessage::ScreenCleared {
                 include_scrollback: sc.include_scrollback,
             }),
+            Some(Message::ScreenSnapshot(snap)) => Ok(AppServerMessage::ScreenSnapshot {
+                cols: snap.cols as u16,
+                rows: snap.rows as u16,
+                lines: snap
+                    .lines
+                    .into_iter()
+                    .map(TryInto::try_into)
+                    .collect::<Result<_>>()?,
+                cursor_col: snap.cursor_col as u16,
+                cursor_row: snap.cursor_row as u16,
+                cursor_visible: snap.cursor_visible,
+                scrollback_len: snap.scrollback_len,
+                zones: snap.zones.into_iter().map(Into::into).collect(),
+                alt_screen: snap.alt_screen,
+            }),
             None => Err(StreamingError::InvalidMessage(
                 "Empty server message".into(),
             )),
@@ -1036,6 +1179,7 @@ impl TryFrom<pb::ClientMessage> for AppClientMessage {
                 scope: req.scope,
                 max_commands: req.max_commands,
             }),
+            Some(Message::ScreenSnapshotRequest(_)) => Ok(AppClientMessage::ScreenSnapshotRequest),
             None => Err(StreamingError::InvalidMessage(
                 "Empty client message".into(),
             )),
@@ -1823,4 +1967,44 @@ mod tests {
             _ => panic!("Wrong message type"),
         }
     }
+
+    #[test]
+    fn test_screen_snapshot_round_trip() {
+        let mut term = crate::terminal::Terminal::new(20, 4);
+        term.process(b"\x1b]133;A\x07$ \x1b]133;B\x07ls\r\n\x1b]133;C;ls\x07");
+        term.process(b"\x1b[38;2;1;2;3mrgb\x1b[0m \x1b]8;;https://x.test\x07link\x1b]8;;\x07\r\n");
+        term.process(b"\x1b]133;D;0\x07");
+
+        let msg = crate::streaming::protocol::terminal_screen_snapshot(&term);
+        let encoded = encode_server_message(&msg).unwrap();
+        let decoded = decode_server_message(&encoded).unwrap();
+        match (&decoded, &msg) {
+            (
+                AppServerMessage::ScreenSnapshot {
+                    lines, zones, cols, ..
+                },
+                AppServerMessage::ScreenSnapshot {
+                    lines: orig_lines,
+                    zones: orig_zones,
+                    ..
+                },
+            ) => {
+                assert_eq!(*cols, 20);
+                assert_eq!(lines, orig_lines);
+                assert_eq!(zones, orig_zones);
+                let runs = &lines[1].runs;
+                assert_eq!(runs[0].fg, Some(AppCellColor::Rgb(1, 2, 3)));
+                assert_eq!(runs[2].hyperlink.as_deref(), Some("https://x.test"));
+            }
+            _ => panic!("Wrong message type"),
+        }
+    }
+
+    #[test]
+    fn test_screen_snapshot_request_round_trip() {
+        let msg = AppClientMessage::screen_snapshot_request();
+        let encoded = encode_client_message(&msg).unwrap();
+        let decoded = decode_client_message(&encoded).unwrap();
+        assert!(matches!(decoded, AppClientMessage::ScreenSnapshotRequest));
+    }
 }
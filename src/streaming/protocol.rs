@@ -3,8 +3,10 @@
 //! This module defines the message formats used for WebSocket-based
 //! terminal streaming between the server and web clients.
 
+use crate::cell::Cell;
+use crate::color::{Color, NamedColor};
 use crate::terminal::file_transfer::TransferDirection;
-use crate::terminal::TerminalEvent;
+use crate::terminal::{Terminal, TerminalEvent};
 use serde::{Deserialize, Serialize};
 
 /// Convert a `TerminalEvent` into the `ServerMessage` a streaming client
@@ -17,7 +19,7 @@ use serde::{Deserialize, Serialize};
 pub fn terminal_event_to_server_message(event: TerminalEvent) -> Option<ServerMessage> {
     Some(match event {
         TerminalEvent::BellRang(_) => ServerMessage::bell(),
-        TerminalEvent::TitleChanged(title) => ServerMessage::title(title),
+        TerminalEvent::TitleChanged { title, .. } => ServerMessage::title(title),
         TerminalEvent::SizeChanged(cols, rows) => ServerMessage::resize(cols as u16, rows as u16),
         TerminalEvent::CwdChanged(cwd) => ServerMessage::cwd_changed_full(
             cwd.old_cwd,
@@ -35,7 +37,9 @@ pub fn terminal_event_to_server_message(event: TerminalEvent) -> Option<ServerMe
             tm.captures,
             tm.timestamp,
         ),
-        TerminalEvent::ModeChanged(mode, enabled) => ServerMessage::mode_changed(mode, enabled),
+        TerminalEvent::ModeChanged(mode, enabled) => {
+            ServerMessage::mode_changed(mode.name().to_string(), enabled)
+        }
         TerminalEvent::GraphicsAdded(row) => ServerMessage::graphics_added(row as u16),
         TerminalEvent::HyperlinkAdded { url, row, col, id } => {
             if let Some(id) = id {
@@ -144,9 +148,223 @@ pub fn terminal_event_to_server_message(event: TerminalEvent) -> Option<ServerMe
         TerminalEvent::ScreenCleared { include_scrollback } => {
             ServerMessage::screen_cleared(include_scrollback)
         }
+        // Cursor position already reaches clients through screen updates
+        TerminalEvent::CursorMoved { .. } => return None,
+        // The underlying match already streams as TriggerMatched
+        TerminalEvent::TriggerFired { .. } => return None,
+        // Clients receive theme colors on connect; mid-session changes are not streamed
+        TerminalEvent::ThemeChanged { .. } => return None,
+        // Only the hosting application can resize the PTY; clients follow via SizeChanged
+        TerminalEvent::ResizeRequested { .. } => return None,
+        TerminalEvent::IconNameChanged { .. } => return None,
+        // Clients already receive FileTransferCompleted for the same download
+        TerminalEvent::FileReceived { .. } => return None,
+        TerminalEvent::CursorStyleChanged { .. } => return None,
+        TerminalEvent::PrintJobCompleted { .. } => return None,
+        // Positions of secrets stay with the hosting application
+        TerminalEvent::SecretDetected { .. } => return None,
+        // Process details stay with the hosting application
+        TerminalEvent::ForegroundProcessChanged { .. } => return None,
+        TerminalEvent::Utf8DecodeError { .. } => return None,
+        TerminalEvent::EscapeStringAborted { .. } => return None,
+        // Background requests are for the hosting application's window
+        TerminalEvent::BackgroundChanged { .. } => return None,
     })
 }
 
+/// Attribute bits carried by [`CellRun::flags`]: bold through guarded. The
+/// wide-character bits are dropped because runs already hold whole graphemes.
+const RUN_FLAG_MASK: u16 = 0x03FF;
+
+/// Build a [`ServerMessage::ScreenSnapshot`] from the terminal's visible screen
+///
+/// Each row is split into runs of cells sharing colors, attributes and
+/// hyperlink, so consumers get styled text without parsing ANSI sequences.
+/// Trailing unstyled blanks are left out. Zones come from the primary screen
+/// and use absolute rows (`abs_row - scrollback_len` is the screen row).
+/// Secrets are masked when redaction is enabled.
+pub fn terminal_screen_snapshot(terminal: &Terminal) -> ServerMessage {
+    let (cols, rows) = terminal.size();
+    // The screenshot view carries the masked screen
+    let view;
+    let grid = if terminal.is_redaction_enabled() {
+        view = terminal.grid_with_scrollback(0);
+        &view
+    } else {
+        terminal.active_grid()
+    };
+
+    let lines = (0..rows)
+        .map(|row| ScreenRow {
+            runs: grid
+                .row(row)
+                .map(|cells| cell_runs(terminal, cells))
+                .unwrap_or_default(),
+            wrapped: grid.is_line_wrapped(row),
+        })
+        .collect();
+    let zones = terminal
+        .get_zones()
+        .iter()
+        .map(|zone| ScreenZone {
+            zone_id: zone.id as u64,
+            zone_type: zone.zone_type.to_string(),
+            abs_row_start: zone.abs_row_start as u64,
+            abs_row_end: zone.abs_row_end as u64,
+            command: zone.command.clone(),
+            exit_code: zone.exit_code,
+        })
+        .collect();
+    let cursor = terminal.cursor();
+
+    ServerMessage::ScreenSnapshot {
+        cols: cols as u16,
+        rows: rows as u16,
+        lines,
+        cursor_col: cursor.col as u16,
+        cursor_row: cursor.row as u16,
+        cursor_visible: cursor.visible,
+        scrollback_len: terminal.grid().scrollback_len() as u64,
+        zones,
+        alt_screen: terminal.is_alt_screen_active(),
+    }
+}
+
+/// Split one row of cells into style runs
+fn cell_runs(terminal: &Terminal, cells: &[Cell]) -> Vec<CellRun> {
+    let hyperlink = |cell: &Cell| {
+        cell.flags()
+            .hyperlink_id
+            .and_then(|id| terminal.get_hyperlink_url(id.get()))
+    };
+    let is_plain_blank = |cell: &Cell| {
+        cell.c() == ' '
+            && !cell.has_combining_chars()
+            && CellColor::from_fg(cell.fg()).is_none()
+            && CellColor::from_bg(cell.bg()).is_none()
+            && cell.flags().to_bitflags() & RUN_FLAG_MASK == 0
+            && cell.flags().hyperlink_id.is_none()
+    };
+    let end = cells
+        .iter()
+        .rposition(|cell| !is_plain_blank(cell))
+        .map_or(0, |last| last + 1);
+
+    let mut runs: Vec<CellRun> = Vec::new();
+    for (col, cell) in cells[..end].iter().enumerate() {
+        if cell.flags().wide_char_spacer() {
+            if let Some(run) = runs.last_mut() {
+                run.width += 1;
+            }
+            continue;
+        }
+        let fg = CellColor::from_fg(cell.fg());
+        let bg = CellColor::from_bg(cell.bg());
+        let flags = cell.flags().to_bitflags() & RUN_FLAG_MASK;
+        let link = hyperlink(cell);
+        match runs.last_mut() {
+            Some(run)
+                if run.fg == fg && run.bg == bg && run.flags == flags && run.hyperlink == link =>
+            {
+                cell.push_grapheme(&mut run.text);
+                run.width += 1;
+            }
+            _ => runs.push(CellRun {
+                col: col as u16,
+                width: 1,
+                text: cell.get_grapheme(),
+                fg,
+                bg,
+                flags,
+                hyperlink: link,
+            }),
+        }
+    }
+    runs
+}
+
+/// Color of a [`CellRun`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CellColor {
+    /// Palette index (0-15 are the named ANSI colors)
+    Indexed(u8),
+    /// 24-bit RGB color
+    Rgb(u8, u8, u8),
+}
+
+impl CellColor {
+    fn from_color(color: Color) -> Self {
+        match color {
+            Color::Named(named) => Self::Indexed(named as u8),
+            Color::Indexed(idx) => Self::Indexed(idx),
+            Color::Rgb(r, g, b) => Self::Rgb(r, g, b),
+        }
+    }
+
+    /// Foreground run color, `None` for the default foreground
+    fn from_fg(color: Color) -> Option<Self> {
+        (color != Color::Named(NamedColor::White)).then(|| Self::from_color(color))
+    }
+
+    /// Background run color, `None` for the default background
+    fn from_bg(color: Color) -> Option<Self> {
+        (color != Color::Named(NamedColor::Black)).then(|| Self::from_color(color))
+    }
+}
+
+/// Run of adjacent cells on one row sharing the same style
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CellRun {
+    /// First column of the run (0-indexed)
+    pub col: u16,
+    /// Columns covered (wide characters count 2)
+    pub width: u16,
+    /// Run text (graphemes, no wide-char spacers)
+    pub text: String,
+    /// Foreground color (`None` = default)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fg: Option<CellColor>,
+    /// Background color (`None` = default)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bg: Option<CellColor>,
+    /// Attribute bits: 1=bold, 2=dim, 4=italic, 8=underline, 16=blink,
+    /// 32=reverse, 64=hidden, 128=strikethrough, 256=overline, 512=guarded
+    #[serde(default)]
+    pub flags: u16,
+    /// OSC 8 hyperlink URL
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hyperlink: Option<String>,
+}
+
+/// One visible screen row of a [`ServerMessage::ScreenSnapshot`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScreenRow {
+    /// Style runs; trailing unstyled blanks are omitted
+    pub runs: Vec<CellRun>,
+    /// Whether the row soft-wraps into the next row
+    pub wrapped: bool,
+}
+
+/// Semantic zone carried by a [`ServerMessage::ScreenSnapshot`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScreenZone {
+    /// Zone ID
+    pub zone_id: u64,
+    /// Zone type ("prompt", "command", "output")
+    pub zone_type: String,
+    /// Absolute row where the zone starts
+    pub abs_row_start: u64,
+    /// Absolute row where the zone ends (inclusive)
+    pub abs_row_end: u64,
+    /// Command text, on command and output zones
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Exit code, on finished output zones
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+}
+
 /// Theme information for terminal color scheme
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThemeInfo {
@@ -685,6 +903,29 @@ pub enum ServerMessage {
         /// Whether scrollback was also cleared (ED 3J vs ED 2J)
         include_scrollback: bool,
     },
+
+    /// Structured screen snapshot (styled cell runs instead of ANSI bytes)
+    #[serde(rename = "screen_snapshot")]
+    ScreenSnapshot {
+        /// Terminal width in columns
+        cols: u16,
+        /// Terminal height in rows
+        rows: u16,
+        /// One entry per visible row, top to bottom
+        lines: Vec<ScreenRow>,
+        /// Cursor column (0-indexed)
+        cursor_col: u16,
+        /// Cursor row (0-indexed)
+        cursor_row: u16,
+        /// Whether the cursor is visible
+        cursor_visible: bool,
+        /// Lines in the primary screen's scrollback
+        scrollback_len: u64,
+        /// Semantic zones of the primary screen
+        zones: Vec<ScreenZone>,
+        /// Whether the alternate screen is active
+        alt_screen: bool,
+    },
 }
 
 /// Messages sent from client to server
@@ -783,6 +1024,10 @@ pub enum ClientMessage {
         #[serde(skip_serializing_if = "Option::is_none")]
         max_commands: Option<u32>,
     },
+
+    /// Request a structured screen snapshot
+    #[serde(rename = "screen_snapshot_request")]
+    ScreenSnapshotRequest,
 }
 
 /// Event types that clients can subscribe to
@@ -1494,6 +1739,11 @@ impl ClientMessage {
             max_commands,
         }
     }
+
+    /// Create a screen snapshot request message
+    pub fn screen_snapshot_request() -> Self {
+        Self::ScreenSnapshotRequest
+    }
 }
 
 #[cfg(test)]
@@ -1757,4 +2007,46 @@ mod tests {
         let json = serde_json::to_string(&events).unwrap();
         assert!(json.contains(r#""snapshot""#));
     }
+
+    #[test]
+    fn test_screen_snapshot_runs_split_on_style() {
+        let mut term = Terminal::new(20, 3);
+        term.process(b"ab\x1b[1;31mcd\x1b[0m \x1b[44mx\x1b[0m\r\n\xe4\xb8\xad!");
+
+        let ServerMessage::ScreenSnapshot {
+            cols, rows, lines, ..
+        } = terminal_screen_snapshot(&term)
+        else {
+            panic!("Wrong message type");
+        };
+        assert_eq!((cols, rows), (20, 3));
+        let texts: Vec<_> = lines[0].runs.iter().map(|r| r.text.as_str()).collect();
+        assert_eq!(texts, ["ab", "cd", " ", "x"]);
+        assert_eq!(lines[0].runs[1].fg, Some(CellColor::Indexed(1)));
+        assert_eq!(lines[0].runs[1].flags, 1);
+        assert_eq!(lines[0].runs[3].bg, Some(CellColor::Indexed(4)));
+        assert_eq!(lines[0].runs[3].col, 5);
+        assert_eq!(lines[1].runs[0].text, "中!");
+        assert_eq!(lines[1].runs[0].width, 3);
+        assert!(lines[2].runs.is_empty());
+    }
+
+    #[test]
+    fn test_screen_snapshot_carries_zones() {
+        let mut term = Terminal::new(20, 5);
+        term.process(b"\x1b]133;A\x07$ \x1b]133;B\x07ls\r\n\x1b]133;C;ls\x07a\r\n\x1b]133;D;2\x07");
+
+        let msg = terminal_screen_snapshot(&term);
+        let ServerMessage::ScreenSnapshot { zones, .. } = &msg else {
+            panic!("Wrong message type");
+        };
+        let output = zones.iter().find(|z| z.zone_type == "output").unwrap();
+        assert_eq!(output.command.as_deref(), Some("ls"));
+        assert_eq!(output.exit_code, Some(2));
+
+        let json = serde_json::to_string(&msg).unwrap();
+        assert!(json.contains(r#""type":"screen_snapshot""#));
+        let deserialized: ServerMessage = serde_json::from_str(&json).unwrap();
+        assert!(matches!(deserialized, ServerMessage::ScreenSnapshot { .. }));
+    }
 }
@@ -1950,6 +1950,15 @@ impl StreamingServer {
         }
     }
 
+    /// Build a structured screen-snapshot message from the session's visible
+    /// terminal state. Shared by the tungstenite and axum WebSocket handlers.
+    fn build_screen_snapshot_message(
+        terminal_for_refresh: &Arc<RwLock<Terminal>>,
+    ) -> ServerMessage {
+        let terminal = terminal_for_refresh.read();
+        crate::streaming::protocol::terminal_screen_snapshot(&terminal)
+    }
+
     /// Handle a new WebSocket connection (already upgraded)
     async fn handle_connection_ws(
         self: &Arc<Self>,
@@ -2313,6 +2322,12 @@ impl StreamingServer {
                                         crate::debug_error!("STREAMING", "Failed to send snapshot to {} {}: {}", transport_label, client_id, e);
                                     }
                                 }
+                                crate::streaming::protocol::ClientMessage::ScreenSnapshotRequest => {
+                                    let msg = Self::build_screen_snapshot_message(&terminal_for_refresh);
+                                    if let Err(e) = client.send(msg).await {
+                                        crate::debug_error!("STREAMING", "Failed to send screen snapshot to {} {}: {}", transport_label, client_id, e);
+                                    }
+                                }
                             }
                         }
                         None => {
@@ -2645,6 +2660,12 @@ impl StreamingServer {
                                                 let _ = ws_tx.send(AxumMessage::Binary(bytes.into())).await;
                                             }
                                         }
+                                        crate::streaming::protocol::ClientMessage::ScreenSnapshotRequest => {
+                                            let msg = Self::build_screen_snapshot_message(&terminal_for_refresh);
+                                            if let Ok(bytes) = encode_server_message(&msg) {
+                                                let _ = ws_tx.send(AxumMessage::Binary(bytes.into())).await;
+                                            }
+                                        }
                                         crate::streaming::protocol::ClientMessage::Subscribe { events } => {
                                             subscriptions = Some(events.into_iter().collect());
                                         }
@@ -2749,7 +2770,9 @@ fn should_send(
         ServerMessage::EnvironmentChanged { .. } => subs.contains(&EventType::Environment),
         ServerMessage::RemoteHostTransition { .. } => subs.contains(&EventType::RemoteHost),
         ServerMessage::SubShellDetected { .. } => subs.contains(&EventType::SubShell),
-        ServerMessage::SemanticSnapshot { .. } => subs.contains(&EventType::Snapshot),
+        ServerMessage::SemanticSnapshot { .. } | ServerMessage::ScreenSnapshot { .. } => {
+            subs.contains(&EventType::Snapshot)
+        }
         ServerMessage::FileTransferStarted { .. }
         | ServerMessage::FileTransferProgress { .. }
         | ServerMessage::FileTransferCompleted { .. }
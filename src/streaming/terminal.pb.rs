@@ -29,7 +29,7 @@ pub struct ThemeInfo {
 pub struct ServerMessage {
     #[prost(
         oneof = "server_message::Message",
-        tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37"
+        tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38"
     )]
     pub message: ::core::option::Option<server_message::Message>,
 }
@@ -111,6 +111,8 @@ pub mod server_message {
         UploadRequested(super::UploadRequested),
         #[prost(message, tag = "37")]
         ScreenCleared(super::ScreenCleared),
+        #[prost(message, tag = "38")]
+        ScreenSnapshot(super::ScreenSnapshot),
     }
 }
 /// Terminal output data (very high frequency)
@@ -574,11 +576,108 @@ pub struct SnapshotRequest {
     #[prost(uint32, optional, tag = "2")]
     pub max_commands: ::core::option::Option<u32>,
 }
+/// Cell color: a palette index (0-15 are the named ANSI colors) or 24-bit RGB.
+/// An absent CellColor means the terminal's default foreground/background.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct CellColor {
+    #[prost(oneof = "cell_color::Value", tags = "1, 2")]
+    pub value: ::core::option::Option<cell_color::Value>,
+}
+/// Nested message and enum types in `CellColor`.
+pub mod cell_color {
+    #[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Oneof)]
+    pub enum Value {
+        #[prost(uint32, tag = "1")]
+        Indexed(u32),
+        #[prost(message, tag = "2")]
+        Rgb(super::Color),
+    }
+}
+/// Run of adjacent cells on one row sharing the same style
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct CellRun {
+    /// First column of the run (0-indexed)
+    #[prost(uint32, tag = "1")]
+    pub col: u32,
+    /// Columns covered (wide characters count 2)
+    #[prost(uint32, tag = "2")]
+    pub width: u32,
+    /// Run text (graphemes, no wide-char spacers)
+    #[prost(string, tag = "3")]
+    pub text: ::prost::alloc::string::String,
+    /// Foreground (absent = default)
+    #[prost(message, optional, tag = "4")]
+    pub fg: ::core::option::Option<CellColor>,
+    /// Background (absent = default)
+    #[prost(message, optional, tag = "5")]
+    pub bg: ::core::option::Option<CellColor>,
+    /// Attribute bits: 1=bold, 2=dim, 4=italic, 8=underline,
+    /// 16=blink, 32=reverse, 64=hidden, 128=strikethrough,
+    /// 256=overline, 512=guarded (DECSCA)
+    #[prost(uint32, tag = "6")]
+    pub flags: u32,
+    /// OSC 8 URL
+    #[prost(string, optional, tag = "7")]
+    pub hyperlink: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// One visible screen row
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct ScreenRow {
+    /// Style runs; trailing unstyled blanks are omitted
+    #[prost(message, repeated, tag = "1")]
+    pub runs: ::prost::alloc::vec::Vec<CellRun>,
+    /// Row soft-wraps into the next row
+    #[prost(bool, tag = "2")]
+    pub wrapped: bool,
+}
+/// Semantic zone (prompt, command, or output block)
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct ScreenZone {
+    #[prost(uint64, tag = "1")]
+    pub zone_id: u64,
+    /// "prompt", "command", "output"
+    #[prost(string, tag = "2")]
+    pub zone_type: ::prost::alloc::string::String,
+    /// Absolute rows: screen row = abs_row - scrollback_len
+    #[prost(uint64, tag = "3")]
+    pub abs_row_start: u64,
+    #[prost(uint64, tag = "4")]
+    pub abs_row_end: u64,
+    #[prost(string, optional, tag = "5")]
+    pub command: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(int32, optional, tag = "6")]
+    pub exit_code: ::core::option::Option<i32>,
+}
+/// Structured screen snapshot: styled cell runs instead of ANSI bytes
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct ScreenSnapshot {
+    #[prost(uint32, tag = "1")]
+    pub cols: u32,
+    #[prost(uint32, tag = "2")]
+    pub rows: u32,
+    /// One entry per visible row, top to bottom
+    #[prost(message, repeated, tag = "3")]
+    pub lines: ::prost::alloc::vec::Vec<ScreenRow>,
+    #[prost(uint32, tag = "4")]
+    pub cursor_col: u32,
+    #[prost(uint32, tag = "5")]
+    pub cursor_row: u32,
+    #[prost(bool, tag = "6")]
+    pub cursor_visible: bool,
+    /// Lines in scrollback above the screen
+    #[prost(uint64, tag = "7")]
+    pub scrollback_len: u64,
+    #[prost(message, repeated, tag = "8")]
+    pub zones: ::prost::alloc::vec::Vec<ScreenZone>,
+    /// Alternate screen is active
+    #[prost(bool, tag = "9")]
+    pub alt_screen: bool,
+}
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct ClientMessage {
     #[prost(
         oneof = "client_message::Message",
-        tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11"
+        tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12"
     )]
     pub message: ::core::option::Option<client_message::Message>,
 }
@@ -608,6 +707,8 @@ pub mod client_message {
         Clipboard(super::ClipboardRequest),
         #[prost(message, tag = "11")]
         SnapshotRequest(super::SnapshotRequest),
+        #[prost(message, tag = "12")]
+        ScreenSnapshotRequest(super::ScreenSnapshotRequest),
     }
 }
 /// Keyboard input from client
@@ -631,6 +732,9 @@ pub struct Ping {}
 /// Client requests full screen refresh
 #[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct RequestRefresh {}
+/// Client requests a structured screen snapshot
+#[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct ScreenSnapshotRequest {}
 /// Client subscribes to specific event types
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct Subscribe {
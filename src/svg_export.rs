@@ -0,0 +1,552 @@
+//! SVG export of terminal content
+//!
+//! Produces a self-contained SVG document of the visible screen or a range of
+//! scrollback lines: text as real `<text>` elements (selectable, crisp at any
+//! zoom), cell backgrounds as rectangles, SGR attributes as font/decoration
+//! properties, and optionally inline graphics embedded as base64 PNG images.
+//! Unlike [`crate::screenshot`] no font rasterization happens, so the output
+//! is small enough to embed in documentation and CI artifacts.
+
+use std::fmt::Write as _;
+use std::ops::Range;
+
+use base64::Engine as _;
+
+use crate::cell::Cell;
+use crate::color::Color;
+use crate::graphics::TerminalGraphic;
+use crate::terminal::{html_escape, Terminal};
+
+/// Configuration for [`Terminal::export_svg`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgExportConfig {
+    /// CSS font-family list for the text
+    pub font_family: String,
+    /// Font size in SVG user units (pixels)
+    pub font_size: f32,
+    /// Cell width as a fraction of the font size (0.6 fits most monospace fonts)
+    pub cell_width_ratio: f32,
+    /// Line height as a multiple of the font size
+    pub line_height_multiplier: f32,
+    /// Padding around the content
+    pub padding: u32,
+    /// Absolute lines to export (0 = oldest scrollback line, the first
+    /// screen row is at `scrollback_len`); `None` exports the visible screen.
+    /// The range is clamped to the available lines.
+    pub line_range: Option<Range<usize>>,
+    /// Embed inline graphics (Sixel/iTerm2/Kitty) on the active screen as
+    /// base64 PNG `<image>` elements
+    pub include_graphics: bool,
+}
+
+impl Default for SvgExportConfig {
+    fn default() -> Self {
+        Self {
+            font_family: "'JetBrains Mono', 'Menlo', 'Consolas', 'DejaVu Sans Mono', monospace"
+                .to_string(),
+            font_size: 14.0,
+            cell_width_ratio: 0.6,
+            line_height_multiplier: 1.2,
+            padding: 10,
+            line_range: None,
+            include_graphics: true,
+        }
+    }
+}
+
+impl SvgExportConfig {
+    /// Create a config with default values
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the CSS font-family list
+    pub fn with_font_family(mut self, family: impl Into<String>) -> Self {
+        self.font_family = family.into();
+        self
+    }
+
+    /// Set the font size
+    pub fn with_font_size(mut self, size: f32) -> Self {
+        self.font_size = size;
+        self
+    }
+
+    /// Set the padding around the content
+    pub fn with_padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Export a range of absolute lines instead of the visible screen
+    pub fn with_line_range(mut self, range: Range<usize>) -> Self {
+        self.line_range = Some(range);
+        self
+    }
+
+    /// Enable/disable embedding of inline graphics
+    pub fn with_graphics(mut self, include: bool) -> Self {
+        self.include_graphics = include;
+        self
+    }
+}
+
+type Rgb = (u8, u8, u8);
+
+/// Resolved drawing attributes shared by a run of cells
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct RunStyle {
+    fg: Rgb,
+    bold: bool,
+    italic: bool,
+    dim: bool,
+    underline: bool,
+    strikethrough: bool,
+    overline: bool,
+}
+
+impl RunStyle {
+    fn write_attrs(&self, out: &mut String) {
+        let _ = write!(out, r#" fill="{}""#, hex(self.fg));
+        if self.bold {
+            out.push_str(r#" font-weight="bold""#);
+        }
+        if self.italic {
+            out.push_str(r#" font-style="italic""#);
+        }
+        if self.dim {
+            out.push_str(r#" opacity="0.5""#);
+        }
+        let decorations: Vec<&str> = [
+            (self.underline, "underline"),
+            (self.strikethrough, "line-through"),
+            (self.overline, "overline"),
+        ]
+        .iter()
+        .filter_map(|&(on, name)| on.then_some(name))
+        .collect();
+        if !decorations.is_empty() {
+            let _ = write!(out, r#" text-decoration="{}""#, decorations.join(" "));
+        }
+    }
+}
+
+pub(crate) fn hex((r, g, b): Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Geometry of the exported document
+pub(crate) struct Layout {
+    pub(crate) cell_w: f32,
+    pub(crate) line_h: f32,
+    font_size: f32,
+}
+
+impl Layout {
+    pub(crate) fn new(config: &SvgExportConfig) -> Self {
+        Self {
+            cell_w: config.font_size * config.cell_width_ratio,
+            line_h: config.font_size * config.line_height_multiplier,
+            font_size: config.font_size,
+        }
+    }
+
+    pub(crate) fn x(&self, col: usize) -> f32 {
+        col as f32 * self.cell_w
+    }
+
+    pub(crate) fn y(&self, line: usize) -> f32 {
+        line as f32 * self.line_h
+    }
+
+    /// Text baseline for a line, leaving room for descenders
+    fn baseline(&self, line: usize) -> f32 {
+        self.y(line) + (self.line_h - self.font_size) / 2.0 + self.font_size * 0.8
+    }
+}
+
+/// `<style>` element applying the configured font to all text
+pub(crate) fn svg_style(config: &SvgExportConfig) -> String {
+    format!(
+        "<style>text {{ font-family: {}; font-size: {}px; white-space: pre; }}</style>\n",
+        html_escape(&config.font_family),
+        config.font_size
+    )
+}
+
+impl Terminal {
+    /// Resolve a cell color through the terminal theme; `None` for the
+    /// default background (left to the canvas)
+    fn svg_color(&self, color: Color, is_bg: bool) -> Option<Rgb> {
+        let palette = &self.theme.ansi_palette;
+        match color {
+            Color::Named(crate::color::NamedColor::Black) if is_bg => None,
+            Color::Named(crate::color::NamedColor::White) if !is_bg => {
+                Some(self.theme.default_fg.to_rgb())
+            }
+            Color::Named(named) => Some(palette[named as usize].to_rgb()),
+            Color::Indexed(idx) if idx < 16 => Some(palette[idx as usize].to_rgb()),
+            other => Some(other.to_rgb()),
+        }
+    }
+
+    /// Foreground/background of a cell after reverse video
+    fn svg_cell_colors(&self, cell: &Cell) -> (Rgb, Option<Rgb>) {
+        let fg = self
+            .svg_color(cell.fg, false)
+            .unwrap_or_else(|| self.theme.default_fg.to_rgb());
+        let bg = self.svg_color(cell.bg, true);
+        if cell.flags.reverse() {
+            (
+                bg.unwrap_or_else(|| self.theme.default_bg.to_rgb()),
+                Some(fg),
+            )
+        } else {
+            (fg, bg)
+        }
+    }
+
+    fn svg_run_style(&self, cell: &Cell) -> RunStyle {
+        RunStyle {
+            fg: self.svg_cell_colors(cell).0,
+            bold: cell.flags.bold(),
+            italic: cell.flags.italic(),
+            dim: cell.flags.dim(),
+            underline: cell.flags.underline(),
+            strikethrough: cell.flags.strikethrough(),
+            overline: cell.flags.overline(),
+        }
+    }
+
+    /// Append background rectangles for runs of equal non-default background
+    fn svg_line_backgrounds(&self, out: &mut String, cells: &[Cell], line: usize, l: &Layout) {
+        let mut col = 0;
+        while col < cells.len() {
+            let Some(bg) = self.svg_cell_colors(&cells[col]).1 else {
+                col += 1;
+                continue;
+            };
+            let start = col;
+            while col < cells.len() && self.svg_cell_colors(&cells[col]).1 == Some(bg) {
+                col += 1;
+            }
+            let _ = writeln!(
+                out,
+                r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+                l.x(start),
+                l.y(line),
+                l.x(col - start),
+                l.line_h,
+                hex(bg)
+            );
+        }
+    }
+
+    /// Append `<text>` elements for a line. Runs of single-width cells with
+    /// the same style share an element stretched to the run's cell span;
+    /// wide characters get their own element centered on their two cells.
+    fn svg_line_text(&self, out: &mut String, cells: &[Cell], line: usize, l: &Layout) {
+        let baseline = l.baseline(line);
+        let mut col = 0;
+        while col < cells.len() {
+            let cell = &cells[col];
+            if cell.flags.wide_char_spacer() || cell.flags.hidden() {
+                col += 1;
+                continue;
+            }
+            let style = self.svg_run_style(cell);
+
+            if cell.flags.wide_char() {
+                let mut text = String::new();
+                cell.push_grapheme(&mut text);
+                let _ = write!(
+                    out,
+                    r#"<text x="{}" y="{}" text-anchor="middle""#,
+                    l.x(col) + l.cell_w,
+                    baseline
+                );
+                style.write_attrs(out);
+                let _ = writeln!(out, ">{}</text>", html_escape(&text));
+                col += 1;
+                continue;
+            }
+
+            let start = col;
+            let mut text = String::new();
+            while col < cells.len() {
+                let c = &cells[col];
+                if c.flags.wide_char()
+                    || c.flags.wide_char_spacer()
+                    || c.flags.hidden()
+                    || self.svg_run_style(c) != style
+                {
+                    break;
+                }
+                if c.c == '\0' {
+                    text.push(' ');
+                } else {
+                    c.push_grapheme(&mut text);
+                }
+                col += 1;
+            }
+
+            // Trailing blanks only matter when decorated; the span counts
+            // cells, not code points (combining marks share a cell)
+            let decorated = style.underline || style.strikethrough || style.overline;
+            let span = if decorated {
+                col - start
+            } else {
+                cells[start..col]
+                    .iter()
+                    .rposition(|c| c.c != ' ' && c.c != '\0')
+                    .map_or(0, |i| i + 1)
+            };
+            if span == 0 {
+                continue;
+            }
+            let text = if decorated {
+                text.as_str()
+            } else {
+                text.trim_end_matches(' ')
+            };
+            let _ = write!(
+                out,
+                r#"<text x="{}" y="{}" textLength="{}""#,
+                l.x(start),
+                baseline,
+                l.x(span)
+            );
+            style.write_attrs(out);
+            let _ = writeln!(out, ">{}</text>", html_escape(text));
+        }
+    }
+
+    /// Append an `<image>` for a graphic whose top line is `top_line`
+    /// (relative to the first exported line)
+    fn svg_graphic(&self, out: &mut String, g: &TerminalGraphic, top_line: f32, l: &Layout) {
+        let expected = g.width * g.height * 4;
+        if g.pixels.len() < expected {
+            return;
+        }
+        let Some(img) = image::RgbaImage::from_raw(
+            g.width as u32,
+            g.height as u32,
+            g.pixels[..expected].to_vec(),
+        ) else {
+            return;
+        };
+        let mut png = Vec::new();
+        if img
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .is_err()
+        {
+            return;
+        }
+
+        let (cell_w_px, cell_h_px) = g.cell_dimensions.unwrap_or(self.cell_dimensions());
+        let width = g.width as f32 / cell_w_px.max(1) as f32 * l.cell_w;
+        let height = g.height as f32 / cell_h_px.max(1) as f32 * l.line_h;
+        let _ = writeln!(
+            out,
+            r#"<image x="{}" y="{}" width="{}" height="{}" preserveAspectRatio="none" href="data:image/png;base64,{}"/>"#,
+            l.x(g.position.0),
+            top_line * l.line_h,
+            width,
+            height,
+            base64::engine::general_purpose::STANDARD.encode(&png)
+        );
+    }
+
+    /// Append backgrounds, text and (optionally) graphics for the absolute
+    /// lines in `range` of the active grid
+    pub(crate) fn svg_write_content(
+        &self,
+        out: &mut String,
+        range: Range<usize>,
+        layout: &Layout,
+        include_graphics: bool,
+    ) {
+        let grid = self.active_grid();
+        let scrollback_len = grid.scrollback_len();
+        let source_cells = |abs: usize| {
+            if abs < scrollback_len {
+                grid.scrollback_line(abs)
+            } else {
+                grid.row(abs - scrollback_len)
+            }
+        };
+        // Secrets are masked in copies of the exported rows
+        let rows: Vec<(&[Cell], bool)> = range
+            .clone()
+            .map(|abs| {
+                let wrapped = if abs < scrollback_len {
+                    grid.is_scrollback_wrapped(abs)
+                } else {
+                    grid.is_line_wrapped(abs - scrollback_len)
+                };
+                (source_cells(abs).unwrap_or_default(), wrapped)
+            })
+            .collect();
+        let from_row = (!self.alt_screen_active).then_some(range.start);
+        let masked = self.redacted_lines(&rows, from_row);
+        let line_cells = |abs: usize| match &masked {
+            Some(lines) => lines.get(abs - range.start).map(Vec::as_slice),
+            None => source_cells(abs),
+        };
+
+        for (line, abs) in range.clone().enumerate() {
+            if let Some(cells) = line_cells(abs) {
+                self.svg_line_backgrounds(out, cells, line, layout);
+            }
+        }
+        for (line, abs) in range.clone().enumerate() {
+            if let Some(cells) = line_cells(abs) {
+                self.svg_line_text(out, cells, line, layout);
+            }
+        }
+
+        if include_graphics {
+            for g in self.all_graphics() {
+                if g.is_virtual {
+                    continue;
+                }
+                let top = (scrollback_len + g.position.1) as f32
+                    - g.scroll_offset_rows as f32
+                    - range.start as f32;
+                let (_, cell_h_px) = g.cell_dimensions.unwrap_or(self.cell_dimensions());
+                let rows_tall = g.height as f32 / cell_h_px.max(1) as f32;
+                if top + rows_tall <= 0.0 || top >= range.len() as f32 {
+                    continue;
+                }
+                self.svg_graphic(out, g, top, layout);
+            }
+        }
+    }
+
+    /// Export terminal content as a self-contained SVG document.
+    ///
+    /// Exports the visible screen, or the absolute line range in
+    /// `config.line_range` (scrollback lines followed by screen rows, same
+    /// numbering as semantic zones). Colors are resolved through the
+    /// terminal's current theme and ANSI palette. Graphics are embedded
+    /// only for placements on the active screen.
+    pub fn export_svg(&self, config: &SvgExportConfig) -> String {
+        let grid = self.active_grid();
+        let (cols, rows) = self.size();
+        let scrollback_len = grid.scrollback_len();
+        let total = scrollback_len + rows;
+        let range = match &config.line_range {
+            Some(r) => r.start.min(total)..r.end.min(total).max(r.start.min(total)),
+            None => scrollback_len..total,
+        };
+
+        let layout = Layout::new(config);
+        let pad = config.padding as f32;
+        let width = layout.x(cols) + pad * 2.0;
+        let height = layout.y(range.len()) + pad * 2.0;
+
+        let mut svg = String::new();
+        let _ = writeln!(svg, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            w = width,
+            h = height
+        );
+        svg.push_str(&svg_style(config));
+        let _ = writeln!(
+            svg,
+            r#"<rect width="100%" height="100%" fill="{}"/>"#,
+            hex(self.theme.default_bg.to_rgb())
+        );
+        let _ = writeln!(svg, r#"<g transform="translate({}, {})">"#, pad, pad);
+
+        self.svg_write_content(&mut svg, range, &layout, config.include_graphics);
+        svg.push_str("</g>\n</svg>\n");
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term_with(input: &[u8]) -> Terminal {
+        let mut term = Terminal::new(20, 4);
+        term.process(input);
+        term
+    }
+
+    #[test]
+    fn test_export_svg_document_structure() {
+        let svg = term_with(b"hello").export_svg(&SvgExportConfig::default());
+        assert!(svg.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>"));
+        assert!(svg.contains("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.contains(">hello</text>"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+
+    #[test]
+    fn test_export_svg_attributes_and_palette() {
+        let term = term_with(b"\x1b[1;4;31mX\x1b[0m");
+        let red = hex(term.theme.ansi_palette[1].to_rgb());
+        let svg = term.export_svg(&SvgExportConfig::default());
+        let line = svg.lines().find(|l| l.ends_with(">X</text>")).unwrap();
+        assert!(line.contains(&format!("fill=\"{}\"", red)));
+        assert!(line.contains("font-weight=\"bold\""));
+        assert!(line.contains("text-decoration=\"underline\""));
+    }
+
+    #[test]
+    fn test_export_svg_background_runs() {
+        let svg = term_with(b"\x1b[44m   \x1b[0m").export_svg(&SvgExportConfig::default());
+        let config = SvgExportConfig::default();
+        let width = 3.0 * config.font_size * config.cell_width_ratio;
+        let rects: Vec<_> = svg.lines().filter(|l| l.starts_with("<rect x=")).collect();
+        assert_eq!(rects.len(), 1);
+        assert!(rects[0].contains(&format!("width=\"{}\"", width)));
+    }
+
+    #[test]
+    fn test_export_svg_wide_char_centered() {
+        let svg = term_with("中".as_bytes()).export_svg(&SvgExportConfig::default());
+        let line = svg.lines().find(|l| l.ends_with(">中</text>")).unwrap();
+        assert!(line.contains("text-anchor=\"middle\""));
+    }
+
+    #[test]
+    fn test_export_svg_escapes_text() {
+        let svg = term_with(b"a<b&c").export_svg(&SvgExportConfig::default());
+        assert!(svg.contains(">a&lt;b&amp;c</text>"));
+    }
+
+    #[test]
+    fn test_export_svg_line_range_includes_scrollback() {
+        let term = term_with(b"line0\r\nline1\r\nline2\r\nline3\r\nline4\r\nline5");
+        let visible = term.export_svg(&SvgExportConfig::default());
+        assert!(!visible.contains("line0"));
+        assert!(visible.contains("line5"));
+
+        let range = term.export_svg(&SvgExportConfig::default().with_line_range(0..2));
+        assert!(range.contains(">line0</text>"));
+        assert!(range.contains(">line1</text>"));
+        assert!(!range.contains("line2"));
+
+        // Out-of-range requests are clamped
+        let clamped = term.export_svg(&SvgExportConfig::default().with_line_range(4..100));
+        assert!(clamped.contains("line5"));
+    }
+
+    #[test]
+    fn test_export_svg_embeds_graphics() {
+        // 1x6 pixel red sixel
+        let term = term_with(b"\x1bPq#0;2;100;0;0#0~\x1b\\");
+        assert_eq!(term.all_graphics().len(), 1);
+
+        let svg = term.export_svg(&SvgExportConfig::default());
+        assert!(svg.contains("href=\"data:image/png;base64,"));
+
+        let svg = term.export_svg(&SvgExportConfig::default().with_graphics(false));
+        assert!(!svg.contains("<image"));
+    }
+}
@@ -84,6 +84,19 @@ fn encode_params(buf: &mut Vec<u8>, params: &[Vec<u16>]) {
     }
 }
 
+/// Encode a CSI/DCS body after its introducer. `vte` collects private markers
+/// (`<`, `=`, `>`, `?`) with the intermediates, but on the wire they precede
+/// the parameters.
+fn encode_header(buf: &mut Vec<u8>, params: &[Vec<u16>], intermediates: &[u8], action: char) {
+    let (private, rest): (Vec<u8>, Vec<u8>) = intermediates
+        .iter()
+        .partition(|&&b| (0x3c..=0x3f).contains(&b));
+    buf.extend_from_slice(&private);
+    encode_params(buf, params);
+    buf.extend_from_slice(&rest);
+    push_char(buf, action);
+}
+
 impl TerminalAction {
     /// Reconstruct the canonical byte form of this action (ARC-021).
     ///
@@ -105,11 +118,7 @@ impl TerminalAction {
                 ..
             } => {
                 let mut buf = vec![0x1b, b'['];
-                encode_params(&mut buf, params);
-                for &inter in intermediates {
-                    buf.push(inter);
-                }
-                push_char(&mut buf, *action);
+                encode_header(&mut buf, params, intermediates, *action);
                 buf
             }
             TerminalAction::OscDispatch {
@@ -137,11 +146,7 @@ impl TerminalAction {
                 ..
             } => {
                 let mut buf = vec![0x1b, b'P'];
-                encode_params(&mut buf, params);
-                for &inter in intermediates {
-                    buf.push(inter);
-                }
-                push_char(&mut buf, *action);
+                encode_header(&mut buf, params, intermediates, *action);
                 buf
             }
             TerminalAction::DcsPut(b) => vec![*b],
@@ -177,6 +182,7 @@ impl Terminal {
         if !bytes.is_empty() {
             let mut parser = vte::Parser::new();
             parser.advance(self, &bytes);
+            self.flush_print_run();
         }
     }
 
@@ -193,12 +199,13 @@ impl Terminal {
         if !bytes.is_empty() {
             let mut parser = vte::Parser::new();
             parser.advance(self, &bytes);
+            self.flush_print_run();
         }
     }
 }
 
 /// Collect `vte::Params` into an owned `Vec<Vec<u16>>` (parameters × sub-params).
-fn params_to_owned(params: &Params) -> Vec<Vec<u16>> {
+pub(crate) fn params_to_owned(params: &Params) -> Vec<Vec<u16>> {
     params.iter().map(|sub| sub.to_vec()).collect()
 }
 
@@ -319,6 +326,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn private_marker_precedes_params() {
+        let actions = parse_to_actions(b"\x1b[?1049h\x1b[>4;2m\x1b[2 q");
+        let encoded: Vec<Vec<u8>> = actions.iter().map(TerminalAction::to_bytes).collect();
+        assert_eq!(
+            encoded,
+            vec![
+                b"\x1b[?1049h".to_vec(),
+                b"\x1b[>4;2m".to_vec(),
+                b"\x1b[2 q".to_vec()
+            ]
+        );
+
+        let mut term = Terminal::new(20, 4);
+        term.apply_actions(actions);
+        assert!(term.is_alt_screen_active());
+    }
+
     #[test]
     fn execute_action_moves_cursor() {
         let mut term = Terminal::new(20, 4);
@@ -0,0 +1,243 @@
+//! Output activity statistics
+//!
+//! Every chunk passed to [`Terminal::process`] is counted into per-second
+//! samples of bytes and line feeds, kept for the last
+//! [`MAX_ACTIVITY_SECONDS`] seconds, which [`Terminal::activity_histogram`]
+//! aggregates into buckets for minimap-style activity displays. Rows of the
+//! primary screen also remember when they were last written
+//! ([`Terminal::row_timestamp`]), so a frontend can show when a line of
+//! scrollback appeared or jump to a point in time
+//! ([`Terminal::find_row_at_time`]).
+
+use std::collections::VecDeque;
+
+use crate::terminal::{unix_millis, Terminal};
+
+/// Seconds of per-second activity samples kept; older samples are discarded
+pub const MAX_ACTIVITY_SECONDS: usize = 3600;
+
+/// Output volume during one histogram bucket
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ActivityBucket {
+    /// Start of the bucket (Unix epoch milliseconds)
+    pub start: u64,
+    /// Bytes of output processed
+    pub bytes: u64,
+    /// Line feeds (`\n`) in the output
+    pub lines: u64,
+}
+
+/// Output counted during one second
+#[derive(Debug, Clone, Copy)]
+struct ActivitySample {
+    /// Unix epoch second
+    second: u64,
+    bytes: u64,
+    lines: u64,
+}
+
+/// Per-second output samples and the time of the chunk being processed
+#[derive(Debug, Default)]
+pub(crate) struct ActivityState {
+    samples: VecDeque<ActivitySample>,
+    /// Time the current `process()` call started (Unix ms), stamped onto
+    /// written rows; 0 before any output
+    now: u64,
+}
+
+impl ActivityState {
+    /// When the last chunk of output arrived (Unix ms), 0 before any output
+    pub(super) fn last_output(&self) -> u64 {
+        self.now
+    }
+
+    /// Count a chunk of output received at `now` (Unix ms)
+    fn record(&mut self, now: u64, data: &[u8]) {
+        self.now = now;
+        let second = now / 1000;
+        let lines = data.iter().filter(|&&b| b == b'\n').count() as u64;
+        match self.samples.back_mut() {
+            Some(sample) if sample.second == second => {
+                sample.bytes += data.len() as u64;
+                sample.lines += lines;
+            }
+            _ => {
+                self.samples.push_back(ActivitySample {
+                    second,
+                    bytes: data.len() as u64,
+                    lines,
+                });
+            }
+        }
+        while self
+            .samples
+            .front()
+            .is_some_and(|s| s.second + (MAX_ACTIVITY_SECONDS as u64) <= second)
+        {
+            self.samples.pop_front();
+        }
+    }
+}
+
+impl Terminal {
+    /// Output volume in consecutive buckets of `bucket_secs` seconds
+    /// (at least 1), oldest first.
+    ///
+    /// Buckets are aligned to multiples of `bucket_secs` since the Unix
+    /// epoch and run from the one holding the oldest sample through the
+    /// current one, including empty buckets, so the result can be drawn
+    /// directly. Only the last [`MAX_ACTIVITY_SECONDS`] seconds are kept;
+    /// the result is empty before any output.
+    pub fn activity_histogram(&self, bucket_secs: u64) -> Vec<ActivityBucket> {
+        self.activity_histogram_at(bucket_secs, unix_millis())
+    }
+
+    fn activity_histogram_at(&self, bucket_secs: u64, now: u64) -> Vec<ActivityBucket> {
+        let now_second = now / 1000;
+        let horizon = now_second.saturating_sub(MAX_ACTIVITY_SECONDS as u64 - 1);
+        let mut samples = self
+            .activity
+            .samples
+            .iter()
+            .filter(|s| s.second >= horizon)
+            .peekable();
+        let Some(first) = samples.peek() else {
+            return Vec::new();
+        };
+        let bucket_secs = bucket_secs.max(1);
+        let first_bucket = first.second / bucket_secs;
+        let last_second = self
+            .activity
+            .samples
+            .back()
+            .map_or(now_second, |s| s.second.max(now_second));
+        let last_bucket = last_second / bucket_secs;
+
+        let mut buckets: Vec<ActivityBucket> = (first_bucket..=last_bucket)
+            .map(|b| ActivityBucket {
+                start: b * bucket_secs * 1000,
+                ..ActivityBucket::default()
+            })
+            .collect();
+        for sample in samples {
+            let bucket = &mut buckets[(sample.second / bucket_secs - first_bucket) as usize];
+            bucket.bytes += sample.bytes;
+            bucket.lines += sample.lines;
+        }
+        buckets
+    }
+
+    /// Forget all activity samples
+    pub fn clear_activity(&mut self) {
+        self.activity.samples.clear();
+    }
+
+    /// When absolute row `row` of the primary screen was last written (Unix
+    /// epoch milliseconds), or `None` if it never was or has been evicted
+    ///
+    /// Rows use the same numbering as zones (`scrollback_len + screen row`).
+    pub fn row_timestamp(&self, row: usize) -> Option<u64> {
+        self.grid.row_timestamp(row)
+    }
+
+    /// First absolute row of the primary screen written at or after
+    /// `timestamp` (Unix epoch milliseconds), for jumping to a point in time
+    ///
+    /// Blank lines are stamped when they scroll into scrollback. Rows keep
+    /// the time of their last write, so a line redrawn in place (such as a
+    /// progress bar) is found by its final update. Returns `None` if no row
+    /// was written that late.
+    ///
+    /// ```
+    /// use par_term_emu_core_rust::terminal::Terminal;
+    ///
+    /// let mut term = Terminal::new(80, 24);
+    /// term.process(b"deploy started\r\n");
+    /// let row = term.find_row_at_time(0).unwrap();
+    /// assert_eq!(row, 0);
+    /// assert_eq!(term.find_row_at_time(u64::MAX), None);
+    /// ```
+    pub fn find_row_at_time(&self, timestamp: u64) -> Option<usize> {
+        self.grid.find_row_at_time(timestamp)
+    }
+
+    /// Count a chunk of output at the start of [`Terminal::process`]
+    pub(super) fn record_activity(&mut self, data: &[u8]) {
+        let now = unix_millis();
+        self.activity.record(now, data);
+        self.grid.set_row_clock(now);
+    }
+
+    /// Stamp screen row `row` with the time of the current output chunk
+    pub(super) fn note_row_written(&mut self, row: usize) {
+        if self.alt_screen_active || self.activity.now == 0 {
+            return;
+        }
+        let abs_row = self.grid.scrollback_len() + row;
+        self.grid.stamp_row(abs_row, self.activity.now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn samples_merge_per_second_and_expire() {
+        let mut state = ActivityState::default();
+        state.record(10_000, b"ab\n");
+        state.record(10_500, b"c\n\n");
+        state.record(11_000, b"d");
+        assert_eq!(state.samples.len(), 2);
+        assert_eq!((state.samples[0].bytes, state.samples[0].lines), (6, 3));
+
+        state.record(10_000 + MAX_ACTIVITY_SECONDS as u64 * 1000, b"e");
+        assert_eq!(state.samples.front().map(|s| s.second), Some(11));
+    }
+
+    #[test]
+    fn histogram_fills_gaps_and_aligns_buckets() {
+        let mut term = Terminal::new(80, 24);
+        term.activity.record(61_000, b"x\n");
+        term.activity.record(65_000, b"yy");
+        term.activity.record(125_000, b"z");
+
+        let buckets = term.activity_histogram_at(60, 130_000);
+        assert_eq!(
+            buckets,
+            [
+                ActivityBucket {
+                    start: 60_000,
+                    bytes: 4,
+                    lines: 1
+                },
+                ActivityBucket {
+                    start: 120_000,
+                    bytes: 1,
+                    lines: 0
+                },
+            ]
+        );
+
+        let buckets = term.activity_histogram_at(1, 125_000);
+        assert_eq!(buckets.len(), 65);
+        assert_eq!(buckets[0].start, 61_000);
+        assert_eq!(buckets[4].bytes, 2);
+        assert!(buckets[1..4].iter().all(|b| b.bytes == 0));
+
+        // Samples older than the retention window are left out
+        let late = 65_000 + MAX_ACTIVITY_SECONDS as u64 * 1000;
+        let buckets = term.activity_histogram_at(60, late);
+        assert_eq!(buckets.first().map(|b| b.start), Some(120_000));
+        assert!(term
+            .activity_histogram_at(60, late + 60_000 * 1000)
+            .is_empty());
+    }
+
+    #[test]
+    fn zero_bucket_size_is_one_second() {
+        let mut term = Terminal::new(80, 24);
+        term.activity.record(5_000, b"a");
+        assert_eq!(term.activity_histogram_at(0, 6_999).len(), 2);
+    }
+}
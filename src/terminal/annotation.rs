@@ -0,0 +1,87 @@
+//! Cell annotation API
+//!
+//! Frontends attach [`Annotation`]s to runs of cells on the primary screen
+//! (see [`crate::annotation`]). Positions use absolute rows
+//! (`scrollback_len + row`), like zones, and are kept in step as lines scroll
+//! into and out of scrollback.
+
+use crate::annotation::{Annotation, AnnotationRange};
+use crate::terminal::Terminal;
+
+impl Terminal {
+    /// Annotate a run of cells on the primary screen.
+    ///
+    /// The range is clipped to the grid width. Returns the new annotation's
+    /// ID, or `None` if the range is empty or lies outside the buffer.
+    pub fn add_annotation(
+        &mut self,
+        range: AnnotationRange,
+        text: String,
+        tag: Option<String>,
+    ) -> Option<usize> {
+        let cols = self.grid.cols();
+        let total_rows = self.grid.scrollback_len() + self.grid.rows();
+        if range.length == 0 || range.col >= cols || range.abs_row >= total_rows {
+            return None;
+        }
+        let range = AnnotationRange {
+            length: range.length.min(cols - range.col),
+            ..range
+        };
+
+        let id = self.bookmarks_state.next_annotation_id;
+        self.bookmarks_state.next_annotation_id += 1;
+        self.grid.push_annotation(Annotation {
+            id,
+            range,
+            text,
+            tag,
+            timestamp: crate::terminal::unix_millis(),
+        });
+        Some(id)
+    }
+
+    /// Remove an annotation by ID
+    pub fn remove_annotation(&mut self, id: usize) -> bool {
+        self.grid.remove_annotation(id).is_some()
+    }
+
+    /// Remove all annotations
+    pub fn clear_annotations(&mut self) {
+        self.grid.clear_annotations();
+    }
+
+    /// Get all annotations, in the order they were added
+    pub fn get_annotations(&self) -> &[Annotation] {
+        self.grid.annotations()
+    }
+
+    /// Get the annotations covering the cell at (`abs_row`, `col`)
+    pub fn get_annotations_at(&self, abs_row: usize, col: usize) -> Vec<&Annotation> {
+        self.grid.annotations_at(abs_row, col)
+    }
+
+    /// Get the annotations on absolute rows `start..=end`, ordered by position
+    pub fn get_annotations_in_rows(&self, start: usize, end: usize) -> Vec<&Annotation> {
+        let mut annotations: Vec<_> = self
+            .grid
+            .annotations()
+            .iter()
+            .filter(|a| a.range.abs_row >= start && a.range.abs_row <= end)
+            .collect();
+        annotations.sort_by_key(|a| (a.range.abs_row, a.range.col, a.id));
+        annotations
+    }
+
+    /// Text of the cells an annotation covers
+    pub fn get_annotated_text(&self, annotation: &Annotation) -> String {
+        let range = annotation.range;
+        self.primary_row(range.abs_row)
+            .map(|(cells, _)| {
+                let end = range.end_col().min(cells.len());
+                let start = range.col.min(end);
+                crate::terminal::cells_to_text(&cells[start..end])
+            })
+            .unwrap_or_default()
+    }
+}
@@ -51,15 +51,32 @@ pub(crate) struct CompletedKittyApc<'a> {
 ///
 /// `on_kitty` receives the payload as a borrowed slice; it must process the
 /// data synchronously (the buffer is reused for subsequent payloads).
+///
+/// Payloads longer than `max_len` bytes stop accumulating one byte past the
+/// limit and are dropped without invoking `on_kitty`, so an unterminated APC
+/// holds at most `max_len + 1` bytes.
 pub(crate) fn feed<F>(
     state: &mut ApcFilterState,
     apc_buffer: &mut Vec<u8>,
     data: &[u8],
     passthrough: &mut Vec<u8>,
+    max_len: usize,
     mut on_kitty: F,
 ) where
     F: FnMut(CompletedKittyApc<'_>),
 {
+    let push = |apc_buffer: &mut Vec<u8>, byte: u8| {
+        if apc_buffer.len() <= max_len {
+            apc_buffer.push(byte);
+        }
+    };
+    let mut complete = |apc_buffer: &[u8]| {
+        if apc_buffer.len() <= max_len {
+            on_kitty(CompletedKittyApc {
+                payload: apc_buffer,
+            });
+        }
+    };
     for &byte in data {
         match *state {
             ApcFilterState::Outside => {
@@ -111,33 +128,29 @@ pub(crate) fn feed<F>(
                 }
                 0x9c => {
                     // 8-bit ST: terminate Kitty APC.
-                    on_kitty(CompletedKittyApc {
-                        payload: apc_buffer.as_slice(),
-                    });
+                    complete(apc_buffer);
                     *state = ApcFilterState::Outside;
                 }
                 other => {
-                    apc_buffer.push(other);
+                    push(apc_buffer, other);
                 }
             },
             ApcFilterState::InKittyApcSawEsc => match byte {
                 b'\\' => {
                     // 7-bit ST: terminate Kitty APC.
-                    on_kitty(CompletedKittyApc {
-                        payload: apc_buffer.as_slice(),
-                    });
+                    complete(apc_buffer);
                     *state = ApcFilterState::Outside;
                 }
                 0x1b => {
                     // ESC ESC inside APC — keep the first ESC as data and
                     // remain in `InKittyApcSawEsc` for the new ESC.
-                    apc_buffer.push(0x1b);
+                    push(apc_buffer, 0x1b);
                 }
                 other => {
                     // ESC followed by something other than `\` — treat as
                     // payload bytes and continue.
-                    apc_buffer.push(0x1b);
-                    apc_buffer.push(other);
+                    push(apc_buffer, 0x1b);
+                    push(apc_buffer, other);
                     *state = ApcFilterState::InKittyApc;
                 }
             },
@@ -150,12 +163,16 @@ mod tests {
     use super::*;
 
     fn run(chunks: &[&[u8]]) -> (Vec<u8>, Vec<Vec<u8>>) {
+        run_with_limit(chunks, usize::MAX)
+    }
+
+    fn run_with_limit(chunks: &[&[u8]], max_len: usize) -> (Vec<u8>, Vec<Vec<u8>>) {
         let mut state = ApcFilterState::Outside;
         let mut buf = Vec::new();
         let mut pass = Vec::new();
         let mut completed: Vec<Vec<u8>> = Vec::new();
         for chunk in chunks {
-            feed(&mut state, &mut buf, chunk, &mut pass, |apc| {
+            feed(&mut state, &mut buf, chunk, &mut pass, max_len, |apc| {
                 completed.push(apc.payload.to_vec());
             });
         }
@@ -240,4 +257,21 @@ mod tests {
         assert_eq!(&comp[0], b"a=t,i=1;A");
         assert_eq!(&comp[1], b"a=t,i=2;B");
     }
+
+    #[test]
+    fn oversized_payload_is_capped_and_dropped() {
+        let mut state = ApcFilterState::Outside;
+        let mut buf = Vec::new();
+        let mut pass = Vec::new();
+        feed(&mut state, &mut buf, b"\x1b_Ga=t;", &mut pass, 16, |_| {});
+        feed(&mut state, &mut buf, &[b'A'; 100], &mut pass, 16, |_| {});
+        assert_eq!(buf.len(), 17);
+
+        let (pass, comp) = run_with_limit(&[b"\x1b_Ga=t;", &[b'A'; 100], b"\x1b\\ok"], 16);
+        assert_eq!(pass, b"ok");
+        assert!(comp.is_empty());
+
+        let (_, comp) = run_with_limit(&[b"\x1b_Ga=t;ABCDEFGH\x1b\\"], 16);
+        assert_eq!(comp, vec![b"a=t;ABCDEFGH".to_vec()]);
+    }
 }
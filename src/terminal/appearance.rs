@@ -0,0 +1,104 @@
+//! Dark/light appearance switching
+//!
+//! A terminal can hold one [`Theme`] per [`Appearance`]. Switching the
+//! appearance (e.g. when the host OS changes between dark and light mode)
+//! applies the matching theme and, when an application has enabled DEC
+//! private mode 2031, sends it the color scheme report `CSI ? 997 ; Ps n`
+//! (Ps = 1 dark, 2 light) so it can re-query the palette with OSC 4/10/11.
+
+use crate::terminal::Terminal;
+use crate::theme::Theme;
+
+/// Preferred color scheme of the host
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Appearance {
+    /// Dark background (default)
+    #[default]
+    Dark,
+    /// Light background
+    Light,
+}
+
+impl Appearance {
+    /// Stable lowercase name ("dark" / "light")
+    pub fn name(&self) -> &'static str {
+        match self {
+            Appearance::Dark => "dark",
+            Appearance::Light => "light",
+        }
+    }
+
+    /// Parse a name produced by [`Appearance::name`]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "dark" => Some(Appearance::Dark),
+            "light" => Some(Appearance::Light),
+            _ => None,
+        }
+    }
+
+    /// Ps value of the color scheme report (`CSI ? 997 ; Ps n`)
+    fn report_param(&self) -> u8 {
+        match self {
+            Appearance::Dark => 1,
+            Appearance::Light => 2,
+        }
+    }
+}
+
+impl Terminal {
+    /// Get the current appearance
+    pub fn appearance(&self) -> Appearance {
+        self.theme.appearance
+    }
+
+    /// Store the theme used for `appearance` (`None` clears it).
+    ///
+    /// If `appearance` is the current one the theme is applied immediately.
+    pub fn set_appearance_theme(&mut self, appearance: Appearance, theme: Option<Theme>) {
+        if appearance == self.theme.appearance {
+            if let Some(theme) = &theme {
+                self.apply_theme(theme);
+            }
+        }
+        match appearance {
+            Appearance::Dark => self.theme.dark_theme = theme,
+            Appearance::Light => self.theme.light_theme = theme,
+        }
+    }
+
+    /// Get the theme stored for `appearance`
+    pub fn appearance_theme(&self, appearance: Appearance) -> Option<&Theme> {
+        match appearance {
+            Appearance::Dark => self.theme.dark_theme.as_ref(),
+            Appearance::Light => self.theme.light_theme.as_ref(),
+        }
+    }
+
+    /// Switch between dark and light appearance.
+    ///
+    /// Applies the theme stored for `appearance` (see
+    /// [`Terminal::set_appearance_theme`]), which swaps the default colors and
+    /// ANSI palette and emits `TerminalEvent::ThemeChanged`. Minimum contrast
+    /// is enforced when cell colors are resolved, so it follows the new
+    /// colors. If mode 2031 is enabled the application is sent the color
+    /// scheme report. Does nothing if the appearance is unchanged.
+    pub fn set_appearance(&mut self, appearance: Appearance) {
+        if appearance == self.theme.appearance {
+            return;
+        }
+        self.theme.appearance = appearance;
+        if let Some(theme) = self.appearance_theme(appearance).cloned() {
+            self.apply_theme(&theme);
+        }
+        if self.modes.color_scheme_updates {
+            self.report_color_scheme();
+        }
+    }
+
+    /// Send the color scheme report (`CSI ? 997 ; Ps n`)
+    pub(crate) fn report_color_scheme(&mut self) {
+        let response = format!("\x1b[?997;{}n", self.theme.appearance.report_param());
+        self.push_response(response.as_bytes());
+    }
+}
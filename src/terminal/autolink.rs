@@ -0,0 +1,249 @@
+//! Promotion of detected plain-text URLs to hyperlinks
+//!
+//! With autolinking enabled, URLs found by the detector in rows that changed
+//! are stamped into the hyperlink map with synthetic IDs, the same way OSC 8
+//! links are. `get_all_hyperlinks`, hit testing, screenshots and snapshots
+//! then treat a typed URL exactly like an application-provided link.
+//! Synthetic links are recomputed whenever their row changes, and never
+//! override a link set by OSC 8.
+
+use std::collections::HashSet;
+use std::num::NonZeroU32;
+
+use crate::row_text::RowText;
+use crate::terminal::screen::{AnimationHint, DamageRegion, RenderingHint, UpdatePriority, ZLayer};
+use crate::terminal::{Terminal, TerminalEvent};
+
+/// Autolink settings and bookkeeping
+#[derive(Debug, Default)]
+pub(crate) struct AutolinkState {
+    /// Promote detected URLs to hyperlinks
+    pub(crate) enabled: bool,
+    /// Rows changed since the last promotion pass
+    pub(crate) pending_rows: HashSet<usize>,
+    /// Hyperlink IDs allocated for detected URLs (not OSC 8)
+    pub(crate) synthetic_ids: HashSet<u32>,
+    /// Hyperlink under the mouse pointer
+    pub(crate) hovered: Option<u32>,
+}
+
+impl Terminal {
+    /// Enable or disable promotion of detected URLs to hyperlinks (off by
+    /// default)
+    ///
+    /// Enabling scans the visible screen right away; afterwards rows are
+    /// rescanned as output changes them. Disabling removes the synthetic
+    /// links from the screen.
+    pub fn set_url_autolink(&mut self, enabled: bool) {
+        if self.autolink.enabled == enabled {
+            return;
+        }
+        self.autolink.enabled = enabled;
+        if enabled {
+            let rows = self.active_grid().rows();
+            self.autolink.pending_rows.extend(0..rows);
+            self.promote_detected_urls();
+        } else {
+            self.autolink.pending_rows.clear();
+            for row in 0..self.active_grid().rows() {
+                self.clear_synthetic_links(row);
+            }
+        }
+    }
+
+    /// Whether detected URLs are promoted to hyperlinks
+    pub fn url_autolink_enabled(&self) -> bool {
+        self.autolink.enabled
+    }
+
+    /// Whether hyperlink `id` was created for a detected URL rather than by
+    /// OSC 8
+    pub fn is_autolink(&self, id: u32) -> bool {
+        self.autolink.synthetic_ids.contains(&id)
+    }
+
+    /// Queue `row` for the next promotion pass
+    pub(crate) fn mark_autolink_row(&mut self, row: usize) {
+        if self.autolink.enabled {
+            self.autolink.pending_rows.insert(row);
+        }
+    }
+
+    /// Stamp detected URLs on changed rows with synthetic hyperlink IDs
+    ///
+    /// Runs at the end of every `process()` call. Emits `HyperlinkAdded` for
+    /// links that were not on the row before.
+    pub(crate) fn promote_detected_urls(&mut self) {
+        if !self.autolink.enabled || self.autolink.pending_rows.is_empty() {
+            return;
+        }
+        let mut rows: Vec<usize> = self.autolink.pending_rows.drain().collect();
+        rows.sort_unstable();
+        for row in rows {
+            self.promote_row(row);
+        }
+    }
+
+    fn promote_row(&mut self, row: usize) {
+        let Some(cells) = self.active_grid().row(row) else {
+            return;
+        };
+
+        let row_text = RowText::new(cells);
+        let mut before: HashSet<(usize, u32)> = HashSet::new();
+        for (col, cell) in cells.iter().enumerate() {
+            if let Some(id) = cell.flags.hyperlink_id {
+                let starts_link = col == 0 || cells[col - 1].flags.hyperlink_id != Some(id);
+                if starts_link && self.autolink.synthetic_ids.contains(&id.get()) {
+                    before.insert((col, id.get()));
+                }
+            }
+        }
+
+        let mut links = Vec::new();
+        let text = row_text.text();
+        for (start, end) in crate::terminal::search::find_urls(text) {
+            let (first, last) = row_text.byte_range_to_cols(start, end);
+            // Leave text that an application already linked with OSC 8 alone
+            let explicit = cells[first..last].iter().any(|cell| {
+                cell.flags
+                    .hyperlink_id
+                    .is_some_and(|id| !self.autolink.synthetic_ids.contains(&id.get()))
+            });
+            if first < last && !explicit {
+                links.push((first, last, text[start..end].to_string()));
+            }
+        }
+
+        self.clear_synthetic_links(row);
+        for (first, last, url) in links {
+            let id = self.synthetic_link_id(&url);
+            if let Some(cells) = self.active_grid_mut().row_mut(row) {
+                for cell in &mut cells[first..last] {
+                    cell.flags.hyperlink_id = NonZeroU32::new(id);
+                }
+            }
+            if !before.contains(&(first, id)) {
+                self.events
+                    .terminal_events
+                    .push(TerminalEvent::HyperlinkAdded {
+                        url,
+                        row,
+                        col: first,
+                        id: Some(id),
+                    });
+            }
+        }
+    }
+
+    /// Synthetic ID for `url`, shared by every occurrence of the URL
+    fn synthetic_link_id(&mut self, url: &str) -> u32 {
+        let existing =
+            self.autolink.synthetic_ids.iter().copied().find(|id| {
+                self.hyperlink_state.hyperlinks.get(id).map(String::as_str) == Some(url)
+            });
+        existing.unwrap_or_else(|| {
+            let id = self.hyperlink_state.next_hyperlink_id;
+            self.hyperlink_state.hyperlinks.insert(id, url.to_string());
+            self.hyperlink_state.next_hyperlink_id += 1;
+            self.autolink.synthetic_ids.insert(id);
+            id
+        })
+    }
+
+    fn clear_synthetic_links(&mut self, row: usize) {
+        let synthetic_ids = std::mem::take(&mut self.autolink.synthetic_ids);
+        if let Some(cells) = self.active_grid_mut().row_mut(row) {
+            for cell in cells.iter_mut() {
+                if cell
+                    .flags
+                    .hyperlink_id
+                    .is_some_and(|id| synthetic_ids.contains(&id.get()))
+                {
+                    cell.flags.hyperlink_id = None;
+                }
+            }
+        }
+        self.autolink.synthetic_ids = synthetic_ids;
+    }
+
+    /// Track the hyperlink under the mouse pointer at screen cell
+    /// (`col`, `row`) and return its ID
+    ///
+    /// When the hovered link changes, a rendering hint (overlay layer, high
+    /// priority) is queued for every run of cells of the old and the new
+    /// link, so the frontend can redraw them with or without a hover
+    /// underline. Works the same for OSC 8 and autolinked URLs.
+    pub fn update_link_hover(&mut self, col: usize, row: usize) -> Option<u32> {
+        let id = self
+            .active_grid()
+            .get(col, row)
+            .and_then(|cell| cell.flags.hyperlink_id)
+            .map(NonZeroU32::get);
+        self.set_hovered_link(id);
+        id
+    }
+
+    /// Forget the hovered hyperlink (e.g. the pointer left the window)
+    pub fn clear_link_hover(&mut self) {
+        self.set_hovered_link(None);
+    }
+
+    /// ID of the hyperlink under the mouse pointer, if any
+    pub fn hovered_hyperlink(&self) -> Option<u32> {
+        self.autolink.hovered
+    }
+
+    fn set_hovered_link(&mut self, id: Option<u32>) {
+        let previous = self.autolink.hovered;
+        if previous == id {
+            return;
+        }
+        self.autolink.hovered = id;
+        for link in [previous, id].into_iter().flatten() {
+            for damage in self.hyperlink_runs(link) {
+                self.add_rendering_hint(RenderingHint {
+                    damage,
+                    layer: ZLayer::Overlay,
+                    animation: AnimationHint::None,
+                    priority: UpdatePriority::High,
+                });
+            }
+        }
+    }
+
+    /// Runs of visible cells that carry hyperlink `id`, one region per run
+    fn hyperlink_runs(&self, id: u32) -> Vec<DamageRegion> {
+        let grid = self.active_grid();
+        let mut regions = Vec::new();
+        for row in 0..grid.rows() {
+            let Some(cells) = grid.row(row) else { continue };
+            let mut start = None;
+            for (col, cell) in cells.iter().enumerate() {
+                let linked = cell.flags.hyperlink_id.map(NonZeroU32::get) == Some(id);
+                match (linked, start) {
+                    (true, None) => start = Some(col),
+                    (false, Some(left)) => {
+                        regions.push(DamageRegion {
+                            left,
+                            top: row,
+                            right: col,
+                            bottom: row + 1,
+                        });
+                        start = None;
+                    }
+                    _ => {}
+                }
+            }
+            if let Some(left) = start {
+                regions.push(DamageRegion {
+                    left,
+                    top: row,
+                    right: cells.len(),
+                    bottom: row + 1,
+                });
+            }
+        }
+        regions
+    }
+}
@@ -0,0 +1,305 @@
+//! Background image and opacity requests
+//!
+//! Applications can ask the terminal to change its window background:
+//!
+//! - iTerm2: `OSC 1337 ; SetBackgroundImageFile=<base64 path> ST` names an
+//!   image file; an empty path removes the image.
+//! - kitty remote control: `DCS @kitty-cmd{json} ST` with the
+//!   `set-background-image` command streams base64 image data in chunks
+//!   (`"data": "-"` removes the image) and `set-background-opacity` sets the
+//!   window opacity.
+//!
+//! The core draws nothing. It records the latest request, readable through
+//! [`Terminal::background_image`] and [`Terminal::background_opacity`], and
+//! emits [`TerminalEvent::BackgroundChanged`] so the frontend can honor it.
+//!
+//! Anything printed to the terminal can send these sequences, including
+//! programs on remote hosts, so they are refused until the embedder calls
+//! [`Terminal::set_allow_remote_background`], and always while
+//! `disable_insecure_sequences` is set. File paths are passed on as sent:
+//! the frontend decides whether to read them.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde_json::{json, Value};
+
+use crate::debug;
+use crate::terminal::{Terminal, TerminalEvent};
+
+/// Largest image accepted from a kitty `set-background-image` stream
+/// (decoded bytes); longer streams are dropped
+pub const MAX_BACKGROUND_IMAGE_BYTES: usize = 32 * 1024 * 1024;
+
+/// How a background image should be laid out (kitty `--layout`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BackgroundLayout {
+    /// Repeated across the window
+    Tiled,
+    /// Repeated with every other copy mirrored
+    MirrorTiled,
+    /// Stretched to the window size
+    Scaled,
+    /// Drawn once at the top left, edge pixels extended
+    Clamped,
+    /// Drawn once in the center
+    Centered,
+    /// Scaled to fit while keeping its aspect ratio, centered
+    CenterScaled,
+}
+
+impl BackgroundLayout {
+    /// kitty layout name (`"tiled"`, `"mirror-tiled"`, `"scaled"`,
+    /// `"clamped"`, `"centered"` or `"cscaled"`)
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BackgroundLayout::Tiled => "tiled",
+            BackgroundLayout::MirrorTiled => "mirror-tiled",
+            BackgroundLayout::Scaled => "scaled",
+            BackgroundLayout::Clamped => "clamped",
+            BackgroundLayout::Centered => "centered",
+            BackgroundLayout::CenterScaled => "cscaled",
+        }
+    }
+
+    /// Parse a kitty layout name
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "tiled" => BackgroundLayout::Tiled,
+            "mirror-tiled" => BackgroundLayout::MirrorTiled,
+            "scaled" => BackgroundLayout::Scaled,
+            "clamped" => BackgroundLayout::Clamped,
+            "centered" => BackgroundLayout::Centered,
+            "cscaled" => BackgroundLayout::CenterScaled,
+            _ => return None,
+        })
+    }
+}
+
+/// Background image requested by the application
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BackgroundImage {
+    /// Path of an image file (iTerm2 `SetBackgroundImageFile`)
+    File(String),
+    /// Encoded image data, PNG for kitty
+    Data {
+        /// The image file contents
+        data: Vec<u8>,
+        /// Requested layout; `None` leaves it to the frontend
+        layout: Option<BackgroundLayout>,
+    },
+}
+
+/// What a [`TerminalEvent::BackgroundChanged`] event changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BackgroundChange {
+    /// The image was set or removed
+    Image,
+    /// The opacity was set or reset
+    Opacity,
+}
+
+impl BackgroundChange {
+    /// Short name (`"image"` or `"opacity"`)
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BackgroundChange::Image => "image",
+            BackgroundChange::Opacity => "opacity",
+        }
+    }
+}
+
+/// kitty image stream still receiving chunks
+#[derive(Debug)]
+struct PendingImage {
+    id: Option<String>,
+    data: Vec<u8>,
+    layout: Option<BackgroundLayout>,
+}
+
+/// Background requests and the policy gating them
+#[derive(Debug, Default)]
+pub(crate) struct BackgroundState {
+    allow_remote: bool,
+    image: Option<BackgroundImage>,
+    opacity: Option<f32>,
+    pending: Option<PendingImage>,
+}
+
+impl Terminal {
+    /// Whether applications may change the background image and opacity
+    pub fn allow_remote_background(&self) -> bool {
+        self.background.allow_remote
+    }
+
+    /// Set whether applications may change the background image and opacity
+    ///
+    /// Off by default. While off, or while insecure sequences are disabled,
+    /// background requests are ignored (kitty commands get an error reply).
+    pub fn set_allow_remote_background(&mut self, allow: bool) {
+        self.background.allow_remote = allow;
+        if !allow {
+            self.background.pending = None;
+        }
+    }
+
+    /// Background image the application asked for, if any
+    pub fn background_image(&self) -> Option<&BackgroundImage> {
+        self.background.image.as_ref()
+    }
+
+    /// Window opacity (0.0-1.0) the application asked for, if any
+    pub fn background_opacity(&self) -> Option<f32> {
+        self.background.opacity
+    }
+
+    /// Forget the requested image and opacity, e.g. when the user restores
+    /// their own settings; emits no event
+    pub fn clear_background_requests(&mut self) {
+        self.background.image = None;
+        self.background.opacity = None;
+        self.background.pending = None;
+    }
+
+    fn background_allowed(&self, source: &str) -> bool {
+        let allowed =
+            self.background.allow_remote && !self.security_state.disable_insecure_sequences;
+        if !allowed {
+            debug::log(
+                debug::DebugLevel::Debug,
+                "SECURITY",
+                &format!("Blocked background request from {}", source),
+            );
+        }
+        allowed
+    }
+
+    fn set_background_image(&mut self, image: Option<BackgroundImage>) {
+        self.background.image = image;
+        self.events
+            .terminal_events
+            .push(TerminalEvent::BackgroundChanged {
+                change: BackgroundChange::Image,
+            });
+    }
+
+    /// OSC 1337 SetBackgroundImageFile=<base64 path>
+    pub(crate) fn handle_set_background_image_file(&mut self, encoded: &str) {
+        if !self.background_allowed("OSC 1337") {
+            return;
+        }
+        let encoded = encoded.trim();
+        if encoded.is_empty() {
+            self.set_background_image(None);
+            return;
+        }
+        match BASE64.decode(encoded).map(String::from_utf8) {
+            Ok(Ok(path)) if !path.is_empty() => {
+                self.set_background_image(Some(BackgroundImage::File(path)))
+            }
+            Ok(Ok(_)) => self.set_background_image(None),
+            _ => debug::log(
+                debug::DebugLevel::Debug,
+                "OSC1337",
+                "Invalid SetBackgroundImageFile path",
+            ),
+        }
+    }
+
+    /// `DCS @ kitty-cmd{json} ST`; `body` is everything after the `@`
+    pub(crate) fn handle_kitty_remote_command(&mut self, body: &[u8]) {
+        let Some(json) = body.strip_prefix(b"kitty-cmd") else {
+            return;
+        };
+        let Ok(command) = serde_json::from_slice::<Value>(json) else {
+            debug::log(debug::DebugLevel::Debug, "DCS", "Invalid kitty-cmd JSON");
+            return;
+        };
+        let name = command["cmd"].as_str().unwrap_or_default();
+        let payload = &command["payload"];
+        let reply = command["no_response"].as_bool() != Some(true);
+
+        let result = match name {
+            "set-background-image" | "set-background-opacity"
+                if !self.background_allowed("kitty remote control") =>
+            {
+                Some(Err("Remote background changes are disabled".to_string()))
+            }
+            "set-background-image" => self.kitty_background_image(payload),
+            "set-background-opacity" => Some(self.kitty_background_opacity(payload)),
+            _ => Some(Err(format!("Unknown command: {}", name))),
+        };
+        if let (true, Some(result)) = (reply, result) {
+            let response = match result {
+                Ok(()) => json!({ "ok": true }),
+                Err(error) => json!({ "ok": false, "error": error }),
+            };
+            self.push_response(format!("\x1bP@kitty-cmd{}\x1b\\", response).as_bytes());
+        }
+    }
+
+    /// One chunk of a `set-background-image` stream; `None` while the
+    /// stream is still open
+    fn kitty_background_image(&mut self, payload: &Value) -> Option<Result<(), String>> {
+        let data = payload["data"].as_str().unwrap_or_default();
+        if data == "-" {
+            self.background.pending = None;
+            self.set_background_image(None);
+            return Some(Ok(()));
+        }
+
+        let id = payload["img_id"].as_str().map(str::to_string);
+        let layout = payload["layout"]
+            .as_str()
+            .and_then(BackgroundLayout::from_name);
+        let pending = match self.background.pending.take() {
+            Some(pending) if pending.id == id => pending,
+            _ => PendingImage {
+                id,
+                data: Vec::new(),
+                layout,
+            },
+        };
+
+        if data.is_empty() {
+            if pending.data.is_empty() {
+                return Some(Err("No image data".to_string()));
+            }
+            self.set_background_image(Some(BackgroundImage::Data {
+                data: pending.data,
+                layout: pending.layout,
+            }));
+            return Some(Ok(()));
+        }
+
+        let mut pending = pending;
+        match BASE64.decode(data) {
+            Ok(chunk) if pending.data.len() + chunk.len() <= MAX_BACKGROUND_IMAGE_BYTES => {
+                pending.data.extend_from_slice(&chunk);
+                self.background.pending = Some(pending);
+                None
+            }
+            Ok(_) => Some(Err("Image too large".to_string())),
+            Err(_) => Some(Err("Invalid base64 image data".to_string())),
+        }
+    }
+
+    fn kitty_background_opacity(&mut self, payload: &Value) -> Result<(), String> {
+        let opacity = payload["opacity"]
+            .as_f64()
+            .ok_or_else(|| "Missing opacity".to_string())?
+            .clamp(0.0, 1.0) as f32;
+        // With toggle, asking for the current opacity restores the default
+        self.background.opacity = if payload["toggle"].as_bool() == Some(true)
+            && self.background.opacity == Some(opacity)
+        {
+            None
+        } else {
+            Some(opacity)
+        };
+        self.events
+            .terminal_events
+            .push(TerminalEvent::BackgroundChanged {
+                change: BackgroundChange::Opacity,
+            });
+        Ok(())
+    }
+}
@@ -0,0 +1,287 @@
+//! Bidirectional text (UAX #9) display ordering
+//!
+//! The grid always stores text in logical order, the order the application
+//! wrote it. When bidi is enabled, each row is run through the Unicode
+//! Bidirectional Algorithm as its own paragraph to get a visual order, which
+//! export, screenshot and render paths apply on the way out. The grid itself
+//! is never reordered, so cursor addressing and editing are unaffected.
+//!
+//! Applications control the pass with the sequences from the terminal-wg
+//! BiDi proposal (as implemented by VTE):
+//! - BDSM (`CSI 8 h` / `CSI 8 l`): implicit mode, where the terminal reorders,
+//!   versus explicit mode, where the application has already laid the text
+//!   out visually and the terminal must not touch it
+//! - SCP (`CSI Ps SP k`): paragraph direction, `0` = detect per line from the
+//!   first strong character, `1` = left-to-right, `2` = right-to-left
+
+use unicode_bidi::{get_base_direction, Direction, Level, ParagraphBidiInfo};
+
+use crate::cell::Cell;
+use crate::terminal::Terminal;
+
+/// Paragraph direction used for the bidi pass
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BidiDirection {
+    /// Detect per line from the first strong character (LTR if none)
+    #[default]
+    Auto,
+    /// Left-to-right paragraphs
+    Ltr,
+    /// Right-to-left paragraphs
+    Rtl,
+}
+
+/// Bidi settings
+#[derive(Debug, Clone)]
+pub(crate) struct BidiState {
+    /// Embedder switch; the pass never runs while this is off
+    pub(crate) enabled: bool,
+    /// BDSM: `true` = implicit (terminal reorders), `false` = explicit
+    pub(crate) implicit: bool,
+    /// SCP paragraph direction
+    pub(crate) direction: BidiDirection,
+}
+
+impl Default for BidiState {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            implicit: true,
+            direction: BidiDirection::Auto,
+        }
+    }
+}
+
+/// Visual ordering of one row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BidiLine {
+    /// Whether the row's paragraph direction is right-to-left
+    pub rtl: bool,
+    /// Logical column shown at each visual column
+    pub visual_to_logical: Vec<usize>,
+    /// Visual column of each logical column
+    pub logical_to_visual: Vec<usize>,
+    /// Resolved embedding level of each logical column (odd = right-to-left)
+    pub levels: Vec<u8>,
+}
+
+impl BidiLine {
+    fn identity(cols: usize) -> Self {
+        Self {
+            rtl: false,
+            visual_to_logical: (0..cols).collect(),
+            logical_to_visual: (0..cols).collect(),
+            levels: vec![0; cols],
+        }
+    }
+
+    /// Whether the visual order is the same as the logical order
+    pub fn is_identity(&self) -> bool {
+        self.visual_to_logical
+            .iter()
+            .enumerate()
+            .all(|(visual, &logical)| visual == logical)
+    }
+
+    /// Whether the character at logical column `col` is displayed
+    /// right-to-left
+    pub fn is_rtl_at(&self, col: usize) -> bool {
+        self.levels.get(col).is_some_and(|level| level % 2 == 1)
+    }
+}
+
+/// Compute the visual order of a row of cells
+///
+/// A wide character and its spacer move together and keep their left/right
+/// order, so a reordered row can still be drawn cell by cell.
+pub fn bidi_line(cells: &[Cell], direction: BidiDirection) -> BidiLine {
+    // One character per display unit (a wide character spans two columns)
+    let mut text = String::with_capacity(cells.len());
+    let mut units: Vec<(usize, usize)> = Vec::with_capacity(cells.len());
+    for (col, cell) in cells.iter().enumerate() {
+        if cell.flags.wide_char_spacer() && !units.is_empty() {
+            units.last_mut().expect("checked non-empty").1 += 1;
+            continue;
+        }
+        text.push(if cell.c == '\0' { ' ' } else { cell.c });
+        units.push((col, 1));
+    }
+
+    let level = match direction {
+        BidiDirection::Ltr => Level::ltr(),
+        BidiDirection::Rtl => Level::rtl(),
+        BidiDirection::Auto => match get_base_direction(text.as_str()) {
+            Direction::Rtl => Level::rtl(),
+            _ => Level::ltr(),
+        },
+    };
+    let info = ParagraphBidiInfo::new(&text, Some(level));
+    if info.is_pure_ltr && level.is_ltr() {
+        return BidiLine::identity(cells.len());
+    }
+
+    let unit_levels = info.reordered_levels_per_char(0..text.len());
+    let mut line = BidiLine {
+        rtl: level.is_rtl(),
+        visual_to_logical: Vec::with_capacity(cells.len()),
+        logical_to_visual: vec![0; cells.len()],
+        levels: vec![0; cells.len()],
+    };
+    for (&(start, width), level) in units.iter().zip(&unit_levels) {
+        line.levels[start..start + width].fill(level.number());
+    }
+    for unit in ParagraphBidiInfo::reorder_visual(&unit_levels) {
+        let (start, width) = units[unit];
+        for col in start..start + width {
+            line.logical_to_visual[col] = line.visual_to_logical.len();
+            line.visual_to_logical.push(col);
+        }
+    }
+    line
+}
+
+/// Mirrored form of a paired punctuation character (Bidi_Mirroring_Glyph)
+fn mirror(c: char) -> char {
+    match c {
+        '(' => ')',
+        ')' => '(',
+        '[' => ']',
+        ']' => '[',
+        '{' => '}',
+        '}' => '{',
+        '<' => '>',
+        '>' => '<',
+        '«' => '»',
+        '»' => '«',
+        '‹' => '›',
+        '›' => '‹',
+        '≤' => '≥',
+        '≥' => '≤',
+        _ => c,
+    }
+}
+
+/// Rearrange a row of cells into visual order
+///
+/// Paired punctuation in right-to-left runs is mirrored.
+pub fn reorder_cells(cells: &[Cell], line: &BidiLine) -> Vec<Cell> {
+    line.visual_to_logical
+        .iter()
+        .map(|&col| {
+            let mut cell = cells[col].clone();
+            if line.is_rtl_at(col) {
+                cell.c = mirror(cell.c);
+            }
+            cell
+        })
+        .collect()
+}
+
+impl Terminal {
+    /// Enable or disable the bidi display pass (off by default)
+    ///
+    /// When enabled, and the application has not selected explicit mode with
+    /// `CSI 8 l`, [`Terminal::visual_line`], [`Terminal::export_visual_text`]
+    /// and screenshots show rows in UAX #9 visual order.
+    pub fn set_bidi_enabled(&mut self, enabled: bool) {
+        self.bidi.enabled = enabled;
+    }
+
+    /// Whether the bidi display pass is enabled by the embedder
+    pub fn bidi_enabled(&self) -> bool {
+        self.bidi.enabled
+    }
+
+    /// Whether rows are currently reordered for display: bidi is enabled and
+    /// the application is in implicit mode (BDSM set)
+    pub fn bidi_active(&self) -> bool {
+        self.bidi.enabled && self.bidi.implicit
+    }
+
+    /// Paragraph direction selected with SCP (`CSI Ps SP k`)
+    pub fn bidi_direction(&self) -> BidiDirection {
+        self.bidi.direction
+    }
+
+    /// Override the paragraph direction (same effect as SCP)
+    pub fn set_bidi_direction(&mut self, direction: BidiDirection) {
+        self.bidi.direction = direction;
+    }
+
+    /// Visual ordering of screen row `row`, or `None` if the row does not
+    /// exist
+    ///
+    /// Returns the identity mapping while the bidi pass is inactive.
+    pub fn bidi_line(&self, row: usize) -> Option<BidiLine> {
+        let cells = self.active_grid().row(row)?;
+        Some(if self.bidi_active() {
+            bidi_line(cells, self.bidi.direction)
+        } else {
+            BidiLine::identity(cells.len())
+        })
+    }
+
+    /// Screen row `row` as text in display order
+    pub fn visual_line(&self, row: usize) -> Option<String> {
+        let cells = self.active_grid().row(row)?;
+        if !self.bidi_active() {
+            return Some(crate::terminal::cells_to_text(cells));
+        }
+        let line = bidi_line(cells, self.bidi.direction);
+        Some(crate::terminal::cells_to_text(&reorder_cells(cells, &line)))
+    }
+
+    /// Visible screen as text in display order, one line per row with
+    /// trailing spaces trimmed
+    pub fn export_visual_text(&self) -> String {
+        let rows = self.active_grid().rows();
+        (0..rows)
+            .filter_map(|row| self.visual_line(row))
+            .map(|line| line.trim_end().to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Reorder a screen view in place for display and move `cursor_col` on
+    /// `cursor_row` to its visual column
+    pub(crate) fn apply_bidi_to_view(
+        &self,
+        view: &mut crate::grid::Grid,
+        cursor: Option<(usize, &mut usize)>,
+    ) {
+        if !self.bidi_active() {
+            return;
+        }
+        let mut cursor = cursor;
+        for row in 0..view.rows() {
+            let Some(cells) = view.row(row) else { continue };
+            let line = bidi_line(cells, self.bidi.direction);
+            if line.is_identity() {
+                continue;
+            }
+            let visual = reorder_cells(cells, &line);
+            if let Some(dst) = view.row_mut(row) {
+                dst.clone_from_slice(&visual);
+            }
+            if let Some((cursor_row, col)) = cursor.as_mut() {
+                if *cursor_row == row && **col < line.logical_to_visual.len() {
+                    **col = line.logical_to_visual[**col];
+                }
+            }
+        }
+    }
+
+    /// BDSM (`CSI 8 h` / `CSI 8 l`)
+    pub(crate) fn set_bidi_implicit(&mut self, implicit: bool) {
+        self.bidi.implicit = implicit;
+    }
+
+    /// SCP (`CSI Ps SP k`): select the paragraph direction
+    pub(crate) fn handle_scp(&mut self, ps: u16) {
+        self.bidi.direction = match ps {
+            1 => BidiDirection::Ltr,
+            2 => BidiDirection::Rtl,
+            _ => BidiDirection::Auto,
+        };
+    }
+}
@@ -0,0 +1,108 @@
+//! Export of single logical blocks (one zone or one command)
+//!
+//! Frontends implementing "copy last command output" or "share this command
+//! as HTML" export just the rows of a shell integration zone, or of a whole
+//! command (prompt, command line and output), instead of slicing a full
+//! buffer export themselves. Rows come from the primary screen, since that is
+//! where zones live.
+
+use crate::cell::Cell;
+use crate::terminal::{ExportFormat, Terminal};
+use crate::zone::ZoneType;
+
+impl Terminal {
+    /// Export the rows of zone `zone_id` in `format`.
+    ///
+    /// Returns `None` if no zone has that ID (it may have been evicted from
+    /// scrollback). A zone that is still open covers only the rows it had
+    /// when it was opened, as with [`Terminal::get_zone_text`].
+    pub fn export_zone(&self, zone_id: usize, format: ExportFormat) -> Option<String> {
+        let zone = self.grid.zones().iter().find(|z| z.id == zone_id)?;
+        self.export_rows(zone.abs_row_start, zone.abs_row_end, format)
+    }
+
+    /// Export command `idx` in `format`: its prompt, command line and output.
+    ///
+    /// Commands are counted by their Output zones, most recent first, like
+    /// [`Terminal::fold_command`]. The block starts at the Prompt and Command
+    /// zones directly before the output when present. Use
+    /// [`Terminal::export_zone`] with the Output zone for the output alone.
+    /// Returns `None` if there is no such command in the scrollback.
+    ///
+    /// ```
+    /// use par_term_emu_core_rust::terminal::{ExportFormat, Terminal};
+    ///
+    /// let mut term = Terminal::new(80, 24);
+    /// term.process(b"\x1b]133;A\x07$ \x1b]133;B\x07ls\r\n\x1b]133;C;ls\x07a.txt\r\n\x1b]133;D;0\x07");
+    /// assert_eq!(term.export_command(0, ExportFormat::Plain).unwrap(), "$ ls\na.txt\n");
+    /// ```
+    pub fn export_command(&self, idx: usize, format: ExportFormat) -> Option<String> {
+        let output_id = self.command_output_zone_id(idx)?;
+        let zones = self.grid.zones();
+        let pos = zones.iter().position(|z| z.id == output_id)?;
+
+        let mut start = zones[pos].abs_row_start;
+        let mut expected = [ZoneType::Command, ZoneType::Prompt].into_iter();
+        for zone in zones[..pos].iter().rev() {
+            // Skip types that are absent (e.g. heuristic prompts without a
+            // Command zone) but stop at anything out of order
+            if !expected.any(|t| t == zone.zone_type) {
+                break;
+            }
+            start = start.min(zone.abs_row_start);
+        }
+        self.export_rows(start, zones[pos].abs_row_end, format)
+    }
+
+    /// Export absolute rows `start..=end` of the primary screen in `format`,
+    /// leaving out blank rows at the end (an Output zone usually ends on the
+    /// empty row the next prompt is drawn on)
+    fn export_rows(&self, start: usize, end: usize, format: ExportFormat) -> Option<String> {
+        let mut end = end;
+        while end > start
+            && self.primary_row(end).is_some_and(|(cells, _)| {
+                cells.iter().all(|c| c.c == ' ' && c.combining.is_empty())
+            })
+        {
+            end -= 1;
+        }
+        match format {
+            ExportFormat::Plain => self
+                .extract_text_from_row_range(start, end)
+                .map(|mut text| {
+                    text.push('\n');
+                    self.redact_export(text, Some(start))
+                }),
+            ExportFormat::Html => {
+                let rows = self.block_rows(start, end)?;
+                let masked = self.redacted_lines(&rows, Some(start));
+                let cells: Vec<&[Cell]> = match &masked {
+                    Some(lines) => lines.iter().map(Vec::as_slice).collect(),
+                    None => rows.iter().map(|(cells, _)| *cells).collect(),
+                };
+                Some(self.html_fragment(&cells))
+            }
+            ExportFormat::Ansi => {
+                let rows = self.block_rows(start, end)?;
+                let masked = self.redacted_lines(&rows, Some(start));
+                let rows: Vec<(&[Cell], bool)> = match &masked {
+                    Some(lines) => lines
+                        .iter()
+                        .zip(&rows)
+                        .map(|(cells, (_, wrapped))| (cells.as_slice(), *wrapped))
+                        .collect(),
+                    None => rows,
+                };
+                Some(crate::grid::export_styled_lines(&rows))
+            }
+        }
+    }
+
+    /// Cells and soft-wrap flags of the rows still present in `start..=end`
+    fn block_rows(&self, start: usize, end: usize) -> Option<Vec<(&[Cell], bool)>> {
+        let rows: Vec<_> = (start..=end)
+            .filter_map(|row| self.primary_row(row))
+            .collect();
+        (!rows.is_empty()).then_some(rows)
+    }
+}
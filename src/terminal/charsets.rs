@@ -0,0 +1,194 @@
+//! Character set designation and invocation
+//!
+//! SCS (`ESC ( F`, `ESC ) F`, `ESC * F`, `ESC + F`) designates a character
+//! set into one of the four slots G0-G3. SI/SO, LS2 and LS3 invoke a slot
+//! into GL (printable ASCII), LS1R/LS2R/LS3R invoke one into GR (the
+//! Latin-1 range U+00A1-U+00FE, i.e. bytes 0xA1-0xFE), and SS2/SS3 pick G2
+//! or G3 for the next graphic character only. Nothing is invoked into GR
+//! until a locking shift asks for it, so Latin-1 text passes through
+//! unchanged even when G2 or G3 hold a set meant for single shifts.
+//!
+//! The VT220 National Replacement Character Sets replace up to twelve ASCII
+//! punctuation positions with national letters. They can only be designated
+//! while DECNRCM (`CSI ? 42 h`) is set, except British, which the VT100
+//! already had; resetting DECNRCM puts ASCII back in any slot holding one.
+
+use crate::debug;
+use crate::terminal::{Charset, CharsetState, Terminal};
+
+/// ASCII positions an NRCS may replace
+const NRCS_POSITIONS: [char; 12] = ['#', '@', '[', '\\', ']', '^', '_', '`', '{', '|', '}', '~'];
+
+/// Replacement table for one NRCS, in `NRCS_POSITIONS` order (`None` keeps ASCII)
+type NrcsTable = [Option<char>; 12];
+
+#[rustfmt::skip]
+const BRITISH: NrcsTable = [Some('£'), None, None, None, None, None, None, None, None, None, None, None];
+#[rustfmt::skip]
+const DUTCH: NrcsTable = [Some('£'), Some('¾'), Some('ĳ'), Some('½'), Some('|'), None, None, None, Some('¨'), Some('ƒ'), Some('¼'), Some('´')];
+#[rustfmt::skip]
+const FINNISH: NrcsTable = [None, None, Some('Ä'), Some('Ö'), Some('Å'), Some('Ü'), None, Some('é'), Some('ä'), Some('ö'), Some('å'), Some('ü')];
+#[rustfmt::skip]
+const FRENCH: NrcsTable = [Some('£'), Some('à'), Some('°'), Some('ç'), Some('§'), None, None, None, Some('é'), Some('ù'), Some('è'), Some('¨')];
+#[rustfmt::skip]
+const FRENCH_CANADIAN: NrcsTable = [None, Some('à'), Some('â'), Some('ç'), Some('ê'), Some('î'), None, Some('ô'), Some('é'), Some('ù'), Some('è'), Some('û')];
+#[rustfmt::skip]
+const GERMAN: NrcsTable = [None, Some('§'), Some('Ä'), Some('Ö'), Some('Ü'), None, None, None, Some('ä'), Some('ö'), Some('ü'), Some('ß')];
+#[rustfmt::skip]
+const ITALIAN: NrcsTable = [Some('£'), Some('§'), Some('°'), Some('ç'), Some('é'), None, None, Some('ù'), Some('à'), Some('ò'), Some('è'), Some('ì')];
+#[rustfmt::skip]
+const NORWEGIAN_DANISH: NrcsTable = [None, Some('Ä'), Some('Æ'), Some('Ø'), Some('Å'), Some('Ü'), None, Some('ä'), Some('æ'), Some('ø'), Some('å'), Some('ü')];
+#[rustfmt::skip]
+const PORTUGUESE: NrcsTable = [None, None, Some('Ã'), Some('Ç'), Some('Õ'), None, None, None, Some('ã'), Some('ç'), Some('õ'), None];
+#[rustfmt::skip]
+const SPANISH: NrcsTable = [Some('£'), Some('§'), Some('¡'), Some('Ñ'), Some('¿'), None, None, None, Some('°'), Some('ñ'), Some('ç'), None];
+#[rustfmt::skip]
+const SWEDISH: NrcsTable = [None, Some('É'), Some('Ä'), Some('Ö'), Some('Å'), Some('Ü'), None, Some('é'), Some('ä'), Some('ö'), Some('å'), Some('ü')];
+#[rustfmt::skip]
+const SWISS: NrcsTable = [Some('ù'), Some('à'), Some('é'), Some('ç'), Some('ê'), Some('î'), Some('è'), Some('ô'), Some('ä'), Some('ö'), Some('ü'), Some('û')];
+
+/// Translate `c` through an NRCS replacement table
+pub(crate) fn nrcs_translate(charset: Charset, c: char) -> char {
+    let table = match charset {
+        Charset::British => &BRITISH,
+        Charset::Dutch => &DUTCH,
+        Charset::Finnish => &FINNISH,
+        Charset::French => &FRENCH,
+        Charset::FrenchCanadian => &FRENCH_CANADIAN,
+        Charset::German => &GERMAN,
+        Charset::Italian => &ITALIAN,
+        Charset::NorwegianDanish => &NORWEGIAN_DANISH,
+        Charset::Portuguese => &PORTUGUESE,
+        Charset::Spanish => &SPANISH,
+        Charset::Swedish => &SWEDISH,
+        Charset::Swiss => &SWISS,
+        Charset::Ascii | Charset::DecLineDrawing => return c,
+    };
+    NRCS_POSITIONS
+        .iter()
+        .position(|&p| p == c)
+        .and_then(|i| table[i])
+        .unwrap_or(c)
+}
+
+impl Charset {
+    /// Charset named by an SCS final byte, with any intermediates after the
+    /// slot selector (`%` for Portuguese); `None` for unknown sets
+    pub fn from_scs(intermediates: &[u8], final_byte: u8) -> Option<Charset> {
+        Some(match (intermediates, final_byte) {
+            ([], b'B') => Charset::Ascii,
+            ([], b'0') => Charset::DecLineDrawing,
+            ([], b'A') => Charset::British,
+            ([], b'4') => Charset::Dutch,
+            ([], b'C' | b'5') => Charset::Finnish,
+            ([], b'R' | b'f') => Charset::French,
+            ([], b'Q' | b'9') => Charset::FrenchCanadian,
+            ([], b'K') => Charset::German,
+            ([], b'Y') => Charset::Italian,
+            ([], b'E' | b'6' | b'`') => Charset::NorwegianDanish,
+            ([b'%'], b'6') => Charset::Portuguese,
+            ([], b'Z') => Charset::Spanish,
+            ([], b'H' | b'7') => Charset::Swedish,
+            ([], b'=') => Charset::Swiss,
+            _ => return None,
+        })
+    }
+
+    /// Whether this is a national set that needs DECNRCM to be designated
+    pub fn is_nrcs(self) -> bool {
+        !matches!(
+            self,
+            Charset::Ascii | Charset::DecLineDrawing | Charset::British
+        )
+    }
+}
+
+impl CharsetState {
+    /// Charset designated into slot `g` (0-3)
+    pub(crate) fn slot(&self, g: u8) -> Charset {
+        match g {
+            0 => self.g0_charset,
+            1 => self.g1_charset,
+            2 => self.g2_charset,
+            _ => self.g3_charset,
+        }
+    }
+
+    fn slot_mut(&mut self, g: u8) -> &mut Charset {
+        match g {
+            0 => &mut self.g0_charset,
+            1 => &mut self.g1_charset,
+            2 => &mut self.g2_charset,
+            _ => &mut self.g3_charset,
+        }
+    }
+}
+
+impl Terminal {
+    /// Whether National Replacement Character Set mode (DECNRCM) is set
+    pub fn nrcs_mode(&self) -> bool {
+        self.modes.nrcs_mode
+    }
+
+    /// Set or reset DECNRCM; resetting it replaces national sets with ASCII
+    pub(crate) fn set_nrcs_mode(&mut self, enabled: bool) {
+        self.modes.nrcs_mode = enabled;
+        if !enabled {
+            for g in 0..4 {
+                let slot = self.charset_state.slot_mut(g);
+                if slot.is_nrcs() {
+                    *slot = Charset::Ascii;
+                }
+            }
+        }
+    }
+
+    /// SCS - designate a charset into G0-G3 (`ESC ( F` ... `ESC + F`)
+    pub(crate) fn designate_charset(&mut self, intermediates: &[u8], final_byte: u8) {
+        let g = match intermediates.first() {
+            Some(b'(') => 0,
+            Some(b')') => 1,
+            Some(b'*') => 2,
+            Some(b'+') => 3,
+            _ => return,
+        };
+        let Some(charset) = Charset::from_scs(&intermediates[1..], final_byte) else {
+            debug::log(
+                debug::DebugLevel::Debug,
+                "SCS",
+                &format!("Unsupported character set: {}", final_byte as char),
+            );
+            return;
+        };
+        if charset.is_nrcs() && !self.modes.nrcs_mode {
+            return;
+        }
+        *self.charset_state.slot_mut(g) = charset;
+    }
+
+    /// Map a printed character through the charsets invoked into GL and GR,
+    /// consuming a pending single shift
+    pub(crate) fn map_charset(&mut self, c: char) -> char {
+        if c.is_control() {
+            return c;
+        }
+        let state = &mut self.charset_state;
+        let gl = state.single_shift.take().unwrap_or(state.active_g);
+        if c.is_ascii_graphic() {
+            return state.slot(gl).translate(c);
+        }
+        // GR: the Latin-1 code points stand in for bytes 0xA1-0xFE
+        let Some(gr) = state.active_gr.map(|g| state.slot(g)) else {
+            return c;
+        };
+        if gr != Charset::Ascii && ('\u{a1}'..='\u{fe}').contains(&c) {
+            let translated = gr.translate(char::from(c as u8 - 0x80));
+            if translated.is_ascii() {
+                // The GR set has no glyph of its own here
+                return c;
+            }
+            return translated;
+        }
+        c
+    }
+}
@@ -0,0 +1,223 @@
+//! Automatic screen checkpoints for time-travel views
+//!
+//! While enabled, the visible screen is copied into a bounded ring every
+//! [`CheckpointConfig::interval_ms`] milliseconds or
+//! [`CheckpointConfig::interval_bytes`] bytes of output, whichever comes
+//! first. [`Terminal::rewind_view`] hands out a read-only view of a
+//! checkpoint, so a frontend can show what the screen looked like earlier
+//! without touching live state. Unlike the Instant Replay snapshots
+//! (`snapshot_manager`), checkpoints hold only the visible cells and cursor,
+//! which keeps a ring of a few hundred frames cheap.
+//!
+//! Intervals are checked at the end of each [`Terminal::process`] call, so a
+//! checkpoint is only taken after output arrives; an idle screen does not
+//! fill the ring with copies of itself.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use crate::cursor::Cursor;
+use crate::grid::Grid;
+use crate::terminal::Terminal;
+use crate::time::Instant;
+
+/// Default number of checkpoints kept in the ring
+pub const DEFAULT_MAX_CHECKPOINTS: usize = 120;
+
+/// Settings for automatic checkpoints
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckpointConfig {
+    /// Whether checkpoints are taken automatically
+    pub enabled: bool,
+    /// Take a checkpoint when this long has passed since the last one
+    /// (ms, 0 = no time trigger)
+    pub interval_ms: u64,
+    /// Take a checkpoint after this many bytes of output (0 = no byte trigger)
+    pub interval_bytes: usize,
+    /// Checkpoints kept; the oldest is dropped when the ring is full
+    pub max_checkpoints: usize,
+}
+
+impl Default for CheckpointConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_ms: 5_000,
+            interval_bytes: 64 * 1024,
+            max_checkpoints: DEFAULT_MAX_CHECKPOINTS,
+        }
+    }
+}
+
+/// Summary of a stored checkpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckpointInfo {
+    /// Checkpoint ID, increasing over the terminal's lifetime
+    pub id: u64,
+    /// When the checkpoint was taken (Unix ms)
+    pub timestamp: u64,
+    /// Total bytes processed by the terminal when the checkpoint was taken
+    pub bytes_processed: u64,
+    /// Whether the alternate screen was showing
+    pub alt_screen: bool,
+}
+
+/// Read-only view of the screen at a checkpoint
+///
+/// Cheap to clone: the cells are shared with the ring.
+#[derive(Debug, Clone)]
+pub struct CheckpointView {
+    /// Checkpoint metadata
+    pub info: CheckpointInfo,
+    /// Cursor at the time of the checkpoint
+    pub cursor: Cursor,
+    /// Window title at the time of the checkpoint
+    pub title: String,
+    grid: Arc<Grid>,
+}
+
+impl CheckpointView {
+    /// The screen cells (no scrollback)
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    /// Screen width in columns
+    pub fn cols(&self) -> usize {
+        self.grid.cols()
+    }
+
+    /// Screen height in rows
+    pub fn rows(&self) -> usize {
+        self.grid.rows()
+    }
+
+    /// Text of screen row `row`, or `None` if out of range
+    pub fn line(&self, row: usize) -> Option<String> {
+        self.grid.row(row).map(crate::terminal::cells_to_text)
+    }
+
+    /// Screen text, one line per row with trailing spaces trimmed
+    pub fn text(&self) -> String {
+        (0..self.rows())
+            .filter_map(|row| self.line(row))
+            .map(|line| line.trim_end().to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Checkpoint ring and trigger bookkeeping
+#[derive(Debug, Default)]
+pub(crate) struct CheckpointState {
+    pub(crate) config: CheckpointConfig,
+    ring: VecDeque<CheckpointView>,
+    next_id: u64,
+    last_taken: Option<Instant>,
+    bytes_since_last: usize,
+}
+
+impl Terminal {
+    /// Set the automatic checkpoint config
+    ///
+    /// Shrinking `max_checkpoints` drops the oldest checkpoints right away.
+    pub fn set_checkpoint_config(&mut self, config: CheckpointConfig) {
+        self.checkpoints.config = config;
+        self.trim_checkpoints();
+    }
+
+    /// Get the automatic checkpoint config
+    pub fn checkpoint_config(&self) -> &CheckpointConfig {
+        &self.checkpoints.config
+    }
+
+    /// Take a checkpoint now, regardless of the intervals, and return its ID
+    ///
+    /// Works while automatic checkpoints are disabled too.
+    pub fn checkpoint_now(&mut self) -> u64 {
+        let id = self.checkpoints.next_id;
+        self.checkpoints.next_id += 1;
+        let view = CheckpointView {
+            info: CheckpointInfo {
+                id,
+                timestamp: crate::terminal::unix_millis(),
+                bytes_processed: self.bytes_processed,
+                alt_screen: self.alt_screen_active,
+            },
+            cursor: self.cursor,
+            title: self.title().to_string(),
+            grid: Arc::new(self.grid_with_scrollback(0)),
+        };
+        self.checkpoints.ring.push_back(view);
+        self.checkpoints.last_taken = Some(Instant::now());
+        self.checkpoints.bytes_since_last = 0;
+        self.trim_checkpoints();
+        id
+    }
+
+    /// Stored checkpoints, oldest first
+    pub fn checkpoints(&self) -> Vec<CheckpointInfo> {
+        self.checkpoints.ring.iter().map(|view| view.info).collect()
+    }
+
+    /// Read-only view of checkpoint `checkpoint_id`, or `None` if it was
+    /// never taken or has been dropped from the ring
+    ///
+    /// Live terminal state is not touched.
+    pub fn rewind_view(&self, checkpoint_id: u64) -> Option<CheckpointView> {
+        let ring = &self.checkpoints.ring;
+        let index = ring
+            .binary_search_by_key(&checkpoint_id, |view| view.info.id)
+            .ok()?;
+        ring.get(index).cloned()
+    }
+
+    /// ID of the newest checkpoint taken at or before `timestamp` (Unix ms),
+    /// or `None` if every stored checkpoint is newer
+    ///
+    /// For "30 seconds ago", pass `unix_millis() - 30_000`.
+    pub fn checkpoint_at(&self, timestamp: u64) -> Option<u64> {
+        self.checkpoints
+            .ring
+            .iter()
+            .rev()
+            .find(|view| view.info.timestamp <= timestamp)
+            .map(|view| view.info.id)
+    }
+
+    /// Drop all stored checkpoints (IDs keep increasing)
+    pub fn clear_checkpoints(&mut self) {
+        self.checkpoints.ring.clear();
+        self.checkpoints.last_taken = None;
+        self.checkpoints.bytes_since_last = 0;
+    }
+
+    /// Count `len` processed bytes towards the byte interval and take a
+    /// checkpoint if an interval has been reached. Called at the end of
+    /// `process()`.
+    pub(crate) fn maybe_checkpoint(&mut self, len: usize) {
+        let config = &self.checkpoints.config;
+        if !config.enabled || len == 0 {
+            return;
+        }
+        self.checkpoints.bytes_since_last += len;
+        let due = match self.checkpoints.last_taken {
+            None => true,
+            Some(last) => {
+                (config.interval_ms > 0 && last.elapsed().as_millis() >= config.interval_ms as u128)
+                    || (config.interval_bytes > 0
+                        && self.checkpoints.bytes_since_last >= config.interval_bytes)
+            }
+        };
+        if due {
+            self.checkpoint_now();
+        }
+    }
+
+    fn trim_checkpoints(&mut self) {
+        let max = self.checkpoints.config.max_checkpoints;
+        while self.checkpoints.ring.len() > max {
+            self.checkpoints.ring.pop_front();
+        }
+    }
+}
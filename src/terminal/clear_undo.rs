@@ -0,0 +1,178 @@
+//! Undo for destructive screen clears
+//!
+//! When enabled with [`Terminal::set_clear_undo_limit`], the primary screen is
+//! snapshotted before every ED 2 (erase display) and ED 3 (erase display and
+//! scrollback), which is what `clear` emits. [`Terminal::restore_last_cleared`]
+//! puts the most recent snapshot back so a frontend can offer "undo clear".
+//! Clears on the alternate screen are not recorded: full-screen applications
+//! redraw with ED 2 all the time and their screens are not worth keeping.
+//!
+//! A `clear` typically sends ED 2 followed by ED 3; the second erase finds a
+//! blank screen and only extends the first snapshot to cover scrollback, so
+//! one `clear` is one undo step.
+
+use std::collections::VecDeque;
+
+use crate::terminal::replay_snapshot::GridSnapshot;
+use crate::terminal::{unix_millis, Terminal};
+
+/// Primary screen contents captured before a clear
+#[derive(Debug, Clone)]
+pub struct ClearedScreen {
+    /// When the clear happened (Unix epoch milliseconds)
+    pub timestamp: u64,
+    /// Whether the clear also erased scrollback (ED 3)
+    pub include_scrollback: bool,
+    /// Cursor position (col, row) before the clear
+    pub cursor: (usize, usize),
+    /// The primary grid before the clear, including scrollback
+    pub grid: GridSnapshot,
+}
+
+impl ClearedScreen {
+    /// Text of the captured visible screen, one line per row with trailing
+    /// blanks trimmed
+    pub fn screen_text(&self) -> String {
+        let cols = self.grid.cols.max(1);
+        self.grid
+            .cells
+            .chunks(cols)
+            .map(|row| {
+                let mut line = String::with_capacity(row.len());
+                for cell in row.iter().filter(|cell| !cell.flags.wide_char_spacer()) {
+                    cell.push_grapheme(&mut line);
+                }
+                line.trim_end().to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Bounded history of cleared screens, newest last
+#[derive(Debug, Default)]
+pub(crate) struct ClearUndoState {
+    /// Maximum snapshots kept; 0 disables capturing
+    limit: usize,
+    history: VecDeque<ClearedScreen>,
+}
+
+impl Terminal {
+    /// Keep up to `limit` cleared screens for [`Terminal::restore_last_cleared`];
+    /// 0 (the default) disables capturing and drops the history
+    pub fn set_clear_undo_limit(&mut self, limit: usize) {
+        let state = &mut self.clear_undo;
+        state.limit = limit;
+        while state.history.len() > limit {
+            state.history.pop_front();
+        }
+    }
+
+    /// Maximum number of cleared screens kept (0 = disabled)
+    pub fn clear_undo_limit(&self) -> usize {
+        self.clear_undo.limit
+    }
+
+    /// Captured screens, oldest first
+    pub fn cleared_screens(&self) -> &VecDeque<ClearedScreen> {
+        &self.clear_undo.history
+    }
+
+    /// Forget all captured screens
+    pub fn clear_cleared_screens(&mut self) {
+        self.clear_undo.history.clear();
+    }
+
+    /// Put the most recently cleared screen back on the primary grid and
+    /// remove it from the history; returns false when there is nothing to
+    /// restore.
+    ///
+    /// Anything written to the screen since the clear is overwritten. After
+    /// an ED 2 only the visible rows are restored; after an ED 3 the whole
+    /// grid is, including scrollback, which moves absolute rows recorded
+    /// since the clear (marks, command records) out of step with the grid.
+    pub fn restore_last_cleared(&mut self) -> bool {
+        let Some(cleared) = self.clear_undo.history.pop_back() else {
+            return false;
+        };
+        let (cols, rows) = (self.grid.cols(), self.grid.rows());
+        if cleared.include_scrollback {
+            self.grid.restore_from_snapshot(&cleared.grid);
+            if (cleared.grid.cols, cleared.grid.rows) != (cols, rows) {
+                self.grid.resize(cols, rows);
+            }
+        } else {
+            let snap = &cleared.grid;
+            for row in 0..rows.min(snap.rows) {
+                let src = &snap.cells[row * snap.cols..(row + 1) * snap.cols];
+                if let Some(dst) = self.grid.row_mut(row) {
+                    let n = dst.len().min(src.len());
+                    dst[..n].clone_from_slice(&src[..n]);
+                    for cell in &mut dst[n..] {
+                        cell.reset();
+                    }
+                }
+                self.grid
+                    .set_line_wrapped(row, snap.wrapped.get(row).copied().unwrap_or(false));
+            }
+            for row in snap.rows..rows {
+                if let Some(dst) = self.grid.row_mut(row) {
+                    dst.iter_mut().for_each(|cell| cell.reset());
+                }
+                self.grid.set_line_wrapped(row, false);
+            }
+        }
+        if !self.alt_screen_active {
+            let (col, row) = cleared.cursor;
+            self.cursor.col = col.min(cols.saturating_sub(1));
+            self.cursor.row = row.min(rows.saturating_sub(1));
+            self.pending_wrap = false;
+            // Redraw, but don't run triggers over output they already saw
+            if self.sync_state.synchronized_updates {
+                self.sync_state.pending_dirty_rows.extend(0..rows);
+            } else {
+                self.dirty_rows.extend(0..rows);
+            }
+        }
+        true
+    }
+
+    /// Snapshot the primary screen before an ED 2 / ED 3 when undo is enabled
+    pub(in crate::terminal) fn capture_before_clear(&mut self, include_scrollback: bool) {
+        if self.clear_undo.limit == 0 || self.alt_screen_active {
+            return;
+        }
+        let grid = &self.grid;
+        let screen_blank = (0..grid.rows())
+            .filter_map(|row| grid.row(row))
+            .all(|cells| cells.iter().all(|cell| cell.is_empty()));
+        if screen_blank {
+            // Nothing visible is lost. If scrollback is about to go and the
+            // last snapshot already holds it (ED 3 right after ED 2), let that
+            // snapshot restore it too
+            if include_scrollback && grid.scrollback_len() > 0 {
+                if let Some(last) = self.clear_undo.history.back_mut() {
+                    if last.grid.scrollback_lines == grid.scrollback_len()
+                        && last.grid.total_lines_scrolled == grid.total_lines_scrolled()
+                    {
+                        last.include_scrollback = true;
+                        return;
+                    }
+                }
+            } else {
+                return;
+            }
+        }
+        let cleared = ClearedScreen {
+            timestamp: unix_millis(),
+            include_scrollback,
+            cursor: (self.cursor.col, self.cursor.row),
+            grid: self.grid.capture_snapshot(),
+        };
+        let state = &mut self.clear_undo;
+        if state.history.len() >= state.limit {
+            state.history.pop_front();
+        }
+        state.history.push_back(cleared);
+    }
+}
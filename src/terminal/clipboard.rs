@@ -83,6 +83,22 @@ pub struct ClipboardHistoryEntry {
     pub timestamp: u64,
 }
 
+/// Selection rendered for "copy with styles"
+///
+/// Frontends place all three flavors on the system clipboard so the paste
+/// target can pick the richest one it understands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RichText {
+    /// Plain text, one line per selected row
+    pub text: String,
+    /// HTML fragment (a single `<pre>` element with inline styles)
+    pub html: String,
+    /// RTF document
+    pub rtf: String,
+}
+
+use crate::cell::Cell;
+use crate::terminal::screen::{Selection, SelectionMode};
 use crate::terminal::Terminal;
 
 impl Terminal {
@@ -322,6 +338,90 @@ impl Terminal {
     pub fn remote_session_id(&self) -> Option<&str> {
         self.clipboard_sync.remote_session_id.as_deref()
     }
+
+    /// Get the current selections as plain text, HTML and RTF, keeping
+    /// colors and text attributes
+    ///
+    /// Uses the same bounds as [`Selection::contains`](crate::terminal::Selection::contains),
+    /// with columns counted in cells. Wide-character spacers and trailing
+    /// blank cells on each row are dropped. With several selections their
+    /// rows follow each other in screen order, as in
+    /// [`Terminal::get_selected_text`].
+    pub fn get_selected_rich_text(&self) -> Option<RichText> {
+        let sels = self.selections_in_screen_order();
+        if sels.is_empty() {
+            return None;
+        }
+        let lines: Vec<Vec<Cell>> = sels
+            .into_iter()
+            .flat_map(|sel| self.selected_cell_lines(sel))
+            .collect();
+        let slices: Vec<&[Cell]> = lines.iter().map(Vec::as_slice).collect();
+        let text = slices
+            .iter()
+            .map(|line| crate::terminal::cells_to_text(line))
+            .collect::<Vec<_>>()
+            .join("\n");
+        Some(RichText {
+            text,
+            html: self.html_fragment(&slices),
+            rtf: crate::rtf_export::export_rtf(&slices),
+        })
+    }
+
+    /// Rows of one selection with secrets masked, trimmed to its columns
+    fn selected_cell_lines(&self, sel: &Selection) -> Vec<Vec<Cell>> {
+        let grid = self.active_grid();
+        let top = sel.start.1.min(sel.end.1);
+        let bottom = sel
+            .start
+            .1
+            .max(sel.end.1)
+            .min(grid.rows().saturating_sub(1));
+
+        // Secrets are masked in copies of the selected rows
+        let rows: Vec<(&[Cell], bool)> = (top..=bottom)
+            .map_while(|row| {
+                grid.row(row)
+                    .map(|cells| (cells, grid.is_line_wrapped(row)))
+            })
+            .collect();
+        let masked = self.redacted_lines(&rows, self.screen_start_row().map(|row| row + top));
+
+        let mut lines = Vec::new();
+        for (i, row) in (top..=bottom).enumerate() {
+            let cells = match &masked {
+                Some(masked) => masked.get(i).map(Vec::as_slice),
+                None => rows.get(i).map(|(cells, _)| *cells),
+            };
+            let Some(cells) = cells else {
+                break;
+            };
+            let (from, to) = match sel.mode {
+                SelectionMode::Line => (0, cells.len()),
+                _ => {
+                    let mut cols = (0..cells.len()).filter(|&col| sel.contains(col, row));
+                    let from = cols.next().unwrap_or(0);
+                    (from, cols.next_back().map_or(from, |col| col + 1).max(from))
+                }
+            };
+            let mut line: Vec<Cell> = cells[from.min(cells.len())..to.min(cells.len())]
+                .iter()
+                .filter(|c| !c.flags.wide_char_spacer())
+                .cloned()
+                .collect();
+            let blank_bg = Cell::default().bg;
+            while line
+                .last()
+                .is_some_and(|c| c.is_empty() && c.combining.is_empty() && c.bg == blank_bg)
+            {
+                line.pop();
+            }
+            lines.push(line);
+        }
+
+        lines
+    }
 }
 
 #[cfg(test)]
@@ -499,4 +599,55 @@ mod tests {
         term.set_max_clipboard_sync_events(100);
         assert_eq!(term.max_clipboard_sync_events(), 100);
     }
+
+    #[test]
+    fn test_get_selected_rich_text_without_selection() {
+        let term = Terminal::new(80, 24);
+        assert!(term.get_selected_rich_text().is_none());
+    }
+
+    #[test]
+    fn test_get_selected_rich_text_character_selection() {
+        let mut term = Terminal::new(80, 24);
+        term.process(b"plain \x1b[1;31mbold red\x1b[0m tail\r\nsecond line");
+        term.set_selection((6, 0), (6, 1), crate::terminal::SelectionMode::Character);
+
+        let rich = term.get_selected_rich_text().unwrap();
+        assert_eq!(rich.text, "bold red tail\nsecond");
+        assert!(rich.html.starts_with("<pre style="));
+        assert!(rich.html.contains("font-weight: bold"));
+        assert!(rich.html.contains("bold red</span>"));
+        assert!(rich.rtf.starts_with("{\\rtf1"));
+        assert!(rich.rtf.contains("\\b bold red}"));
+        assert_eq!(rich.rtf.matches("\\par\n").count(), 1);
+        assert!(rich.rtf.contains(" second}"));
+    }
+
+    #[test]
+    fn test_get_selected_rich_text_block_selection_keeps_colored_blanks() {
+        let mut term = Terminal::new(20, 5);
+        term.process(b"abcdef\r\nuv\x1b[44m  \x1b[0m");
+        term.set_selection((1, 0), (4, 1), crate::terminal::SelectionMode::Block);
+
+        let rich = term.get_selected_rich_text().unwrap();
+        // Block columns 1..4 on both rows; the blue blanks are kept
+        assert_eq!(rich.text, "bcd\nv  ");
+        assert!(rich.html.contains("background-color: rgb(48, 101, 184)"));
+    }
+    #[test]
+    fn test_get_selected_rich_text_covers_every_selection() {
+        let mut term = Terminal::new(20, 5);
+        term.process(b"first\r\n\x1b[1msecond\x1b[0m\r\nthird");
+        // Added out of screen order
+        term.set_selection((0, 2), (5, 2), crate::terminal::SelectionMode::Character);
+        term.add_selection((0, 1), (6, 1), crate::terminal::SelectionMode::Character);
+
+        let rich = term.get_selected_rich_text().unwrap();
+        assert_eq!(rich.text, term.get_selected_text().unwrap());
+        assert_eq!(rich.text, "second\nthird");
+        assert!(rich.html.contains("second</span>"));
+        assert!(rich.html.contains("third"));
+        assert!(rich.rtf.contains("\\b second}"));
+        assert!(rich.rtf.contains("third"));
+    }
 }
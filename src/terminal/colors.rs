@@ -8,9 +8,13 @@
 //! - Link colors
 //! - Bold text colors
 //! - Color mode flags
+//! - Minimum contrast enforcement
+//! - Theme application
 
+use crate::cell::Cell;
 use crate::color::Color;
 use crate::terminal::Terminal;
+use crate::theme::Theme;
 
 impl Terminal {
     /// Get default ANSI color palette
@@ -203,6 +207,39 @@ impl Terminal {
         Ok(())
     }
 
+    /// Apply a parsed [`Theme`] in one step.
+    ///
+    /// Every color the theme defines replaces the current value; the rest
+    /// are left unchanged. Emits [`TerminalEvent::ThemeChanged`] afterwards.
+    ///
+    /// [`TerminalEvent::ThemeChanged`]: crate::terminal::TerminalEvent::ThemeChanged
+    pub fn apply_theme(&mut self, theme: &Theme) {
+        let slots = [
+            (theme.foreground, &mut self.theme.default_fg),
+            (theme.background, &mut self.theme.default_bg),
+            (theme.cursor, &mut self.theme.cursor_color),
+            (theme.selection_bg, &mut self.theme.selection_bg_color),
+            (theme.selection_fg, &mut self.theme.selection_fg_color),
+            (theme.link, &mut self.theme.link_color),
+            (theme.bold, &mut self.theme.bold_color),
+        ];
+        for (color, slot) in slots {
+            if let Some(color) = color {
+                *slot = color;
+            }
+        }
+        for (slot, color) in self.theme.ansi_palette.iter_mut().zip(theme.palette) {
+            if let Some(color) = color {
+                *slot = color;
+            }
+        }
+        self.events
+            .terminal_events
+            .push(crate::terminal::TerminalEvent::ThemeChanged {
+                name: theme.name.clone(),
+            });
+    }
+
     /// Set the faint/dim text alpha multiplier
     pub fn set_faint_text_alpha(&mut self, alpha: f32) {
         self.theme.faint_text_alpha = alpha.clamp(0.0, 1.0);
@@ -213,6 +250,56 @@ impl Terminal {
         self.theme.faint_text_alpha
     }
 
+    /// Set the minimum WCAG contrast ratio between text and its background.
+    ///
+    /// Like iTerm2's "minimum contrast", [`Terminal::resolve_cell_colors`]
+    /// then adjusts the text color of each cell until it reaches this ratio
+    /// (e.g. 4.5 for WCAG AA). Screenshots use the same setting unless their
+    /// config overrides it. Values are clamped to 1.0-21.0; 1.0 disables the
+    /// adjustment.
+    pub fn set_minimum_contrast_ratio(&mut self, ratio: f32) {
+        self.theme.minimum_contrast_ratio = ratio.clamp(1.0, 21.0);
+    }
+
+    /// Get the minimum WCAG contrast ratio (1.0 = disabled)
+    pub fn minimum_contrast_ratio(&self) -> f32 {
+        self.theme.minimum_contrast_ratio
+    }
+
+    /// Resolve a cell's foreground and background to RGB, enforcing the
+    /// minimum contrast ratio (see [`Terminal::apply_minimum_contrast`]).
+    pub fn resolve_cell_colors(&self, cell: &Cell) -> ((u8, u8, u8), (u8, u8, u8)) {
+        self.apply_minimum_contrast(cell, cell.fg.to_rgb(), cell.bg.to_rgb())
+    }
+
+    /// Enforce the minimum contrast ratio on colors already resolved for
+    /// `cell` (e.g. through a custom palette).
+    ///
+    /// Colors stay in their cell slots. For reverse-video cells the text is
+    /// drawn in the background slot, so that is the color adjusted.
+    pub fn apply_minimum_contrast(
+        &self,
+        cell: &Cell,
+        fg: (u8, u8, u8),
+        bg: (u8, u8, u8),
+    ) -> ((u8, u8, u8), (u8, u8, u8)) {
+        let ratio = self.theme.minimum_contrast_ratio;
+        if ratio <= 1.0 {
+            return (fg, bg);
+        }
+        if cell.flags.reverse() {
+            (
+                fg,
+                crate::color_utils::enforce_contrast_ratio_rgb(bg, fg, ratio),
+            )
+        } else {
+            (
+                crate::color_utils::enforce_contrast_ratio_rgb(fg, bg, ratio),
+                bg,
+            )
+        }
+    }
+
     /// Get current ANSI palette (0-15)
     pub fn get_ansi_palette(&self) -> &[Color; 16] {
         &self.theme.ansi_palette
@@ -583,6 +670,39 @@ mod tests {
         assert_eq!(term.smart_cursor_color(), original);
     }
 
+    #[test]
+    fn test_resolve_cell_colors_enforces_minimum_contrast() {
+        let mut term = Terminal::new(80, 24);
+        let mut cell = Cell::new('x');
+        cell.fg = Color::Rgb(30, 30, 60);
+        cell.bg = Color::Rgb(0, 0, 0);
+
+        // Disabled by default: colors pass through
+        assert!((term.minimum_contrast_ratio() - 1.0).abs() < f32::EPSILON);
+        assert_eq!(term.resolve_cell_colors(&cell), ((30, 30, 60), (0, 0, 0)));
+
+        term.set_minimum_contrast_ratio(4.5);
+        let (fg, bg) = term.resolve_cell_colors(&cell);
+        assert_eq!(bg, (0, 0, 0));
+        let contrast = Color::Rgb(fg.0, fg.1, fg.2).contrast_ratio(&Color::Rgb(0, 0, 0));
+        assert!(contrast >= 4.5);
+
+        // Reverse video: the background slot holds the text color
+        cell.flags.set_reverse(true);
+        cell.fg = Color::Rgb(0, 0, 0);
+        cell.bg = Color::Rgb(30, 30, 60);
+        let (fg, text) = term.resolve_cell_colors(&cell);
+        assert_eq!(fg, (0, 0, 0));
+        assert_ne!(text, (30, 30, 60));
+
+        term.set_minimum_contrast_ratio(50.0);
+        assert!((term.minimum_contrast_ratio() - 21.0).abs() < f32::EPSILON);
+
+        // An embedder setting, so RIS keeps it
+        term.process(b"\x1bc");
+        assert!((term.minimum_contrast_ratio() - 21.0).abs() < f32::EPSILON);
+    }
+
     #[test]
     fn test_faint_text_alpha_get_set() {
         let mut term = create_test_terminal();
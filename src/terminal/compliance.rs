@@ -1,179 +0,0 @@
-//! Terminal compliance testing
-//!
-//! Provides types and Terminal implementation for VT sequence compliance testing.
-
-use crate::terminal::Terminal;
-
-/// VT sequence support level
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-pub enum ComplianceLevel {
-    /// VT52
-    VT52,
-    /// VT100
-    VT100,
-    /// VT220
-    VT220,
-    /// VT320
-    VT320,
-    /// VT420
-    VT420,
-    /// VT520
-    VT520,
-    /// xterm
-    XTerm,
-}
-
-/// Compliance test result
-#[derive(Debug, Clone)]
-pub struct ComplianceTest {
-    /// Test name
-    pub name: String,
-    /// Test category
-    pub category: String,
-    /// Whether test passed
-    pub passed: bool,
-    /// Expected result
-    pub expected: String,
-    /// Actual result
-    pub actual: String,
-    /// Notes or error message
-    pub notes: Option<String>,
-}
-
-/// Compliance report
-#[derive(Debug, Clone)]
-pub struct ComplianceReport {
-    /// Terminal name/version
-    pub terminal_info: String,
-    /// Compliance level tested
-    pub level: ComplianceLevel,
-    /// All test results
-    pub tests: Vec<ComplianceTest>,
-    /// Number of passed tests
-    pub passed: usize,
-    /// Number of failed tests
-    pub failed: usize,
-    /// Overall compliance percentage
-    pub compliance_percent: f64,
-}
-
-impl Terminal {
-    // === Feature 29: Terminal Compliance Testing ===
-
-    /// Run compliance tests for a specific VT level
-    pub fn test_compliance(&mut self, level: ComplianceLevel) -> ComplianceReport {
-        // Add basic tests (all levels should support)
-        let tests = vec![ComplianceTest {
-            name: "Cursor Position".to_string(),
-            category: "Cursor".to_string(),
-            passed: true,
-            expected: "Success".to_string(),
-            actual: "Success".to_string(),
-            notes: None,
-        }];
-
-        let passed = tests.iter().filter(|t| t.passed).count();
-        let failed = tests.len() - passed;
-        let compliance_percent = if !tests.is_empty() {
-            (passed as f64 / tests.len() as f64) * 100.0
-        } else {
-            100.0
-        };
-
-        ComplianceReport {
-            terminal_info: "par-term-emu-core-rust".to_string(),
-            level,
-            tests,
-            passed,
-            failed,
-            compliance_percent,
-        }
-    }
-
-    /// Format a compliance report as a human-readable string
-    pub fn format_compliance_report(report: &ComplianceReport) -> String {
-        let mut output = format!(
-            "Compliance Report for {}
-",
-            report.terminal_info
-        );
-        output.push_str(&format!(
-            "Level: {:?}
-",
-            report.level
-        ));
-        output.push_str(&format!(
-            "Score: {:.1}% ({} passed, {} failed)
-",
-            report.compliance_percent, report.passed, report.failed
-        ));
-        output.push_str(
-            "
-Results:
-",
-        );
-
-        for test in &report.tests {
-            let status = if test.passed { "PASS" } else { "FAIL" };
-            output.push_str(&format!(
-                "[{}] {}: {}
-",
-                status, test.category, test.name
-            ));
-        }
-
-        output
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::terminal::Terminal;
-
-    #[test]
-    fn test_compliance_seeds_a_passing_report() {
-        let mut term = Terminal::new(80, 24);
-        let report = term.test_compliance(ComplianceLevel::VT100);
-
-        assert_eq!(report.terminal_info, "par-term-emu-core-rust");
-        assert_eq!(report.level, ComplianceLevel::VT100);
-        assert!(!report.tests.is_empty());
-        assert_eq!(report.passed + report.failed, report.tests.len());
-        // The single seeded test passes, so the percentage is 100.
-        assert_eq!(report.compliance_percent, 100.0);
-        assert_eq!(report.failed, 0);
-    }
-
-    #[test]
-    fn test_compliance_runs_for_every_level() {
-        let mut term = Terminal::new(80, 24);
-        let levels = [
-            ComplianceLevel::VT52,
-            ComplianceLevel::VT100,
-            ComplianceLevel::VT220,
-            ComplianceLevel::VT320,
-            ComplianceLevel::VT420,
-            ComplianceLevel::VT520,
-            ComplianceLevel::XTerm,
-        ];
-        for level in levels {
-            let report = term.test_compliance(level);
-            assert_eq!(report.level, level);
-            assert!(report.compliance_percent >= 0.0 && report.compliance_percent <= 100.0);
-        }
-    }
-
-    #[test]
-    fn format_compliance_report_renders_expected_sections() {
-        let mut term = Terminal::new(80, 24);
-        let report = term.test_compliance(ComplianceLevel::VT220);
-        let text = Terminal::format_compliance_report(&report);
-
-        assert!(text.contains("Compliance Report for par-term-emu-core-rust"));
-        assert!(text.contains("Level: VT220"));
-        assert!(text.contains("Score:"));
-        assert!(text.contains("[PASS]"));
-        assert!(text.contains("Cursor Position"));
-    }
-}
@@ -0,0 +1,407 @@
+//! Terminal compliance testing
+//!
+//! Runs the bundled esctest/vttest-derived vectors (see [`vectors`]) and
+//! reports the results per category. Each vector declares the lowest VT
+//! level that requires it; a run at a given level includes every vector at
+//! or below that level.
+
+mod vectors;
+
+use crate::terminal::Terminal;
+use crate::testing::ScreenDump;
+use vectors::{Check, Vector, VECTORS};
+
+/// Screen size every vector runs at
+const VECTOR_COLS: usize = 80;
+const VECTOR_ROWS: usize = 24;
+
+/// VT sequence support level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ComplianceLevel {
+    /// VT52
+    VT52,
+    /// VT100
+    VT100,
+    /// VT220
+    VT220,
+    /// VT320
+    VT320,
+    /// VT420
+    VT420,
+    /// VT520
+    VT520,
+    /// xterm
+    XTerm,
+}
+
+/// Compliance test result
+#[derive(Debug, Clone)]
+pub struct ComplianceTest {
+    /// Test name
+    pub name: String,
+    /// Test category
+    pub category: String,
+    /// Lowest level that requires the tested behavior
+    pub level: ComplianceLevel,
+    /// Whether test passed
+    pub passed: bool,
+    /// Expected result
+    pub expected: String,
+    /// Actual result
+    pub actual: String,
+    /// Notes or error message
+    pub notes: Option<String>,
+}
+
+/// Compliance report
+#[derive(Debug, Clone)]
+pub struct ComplianceReport {
+    /// Terminal name/version
+    pub terminal_info: String,
+    /// Compliance level tested
+    pub level: ComplianceLevel,
+    /// All test results
+    pub tests: Vec<ComplianceTest>,
+    /// Number of passed tests
+    pub passed: usize,
+    /// Number of failed tests
+    pub failed: usize,
+    /// Overall compliance percentage
+    pub compliance_percent: f64,
+}
+
+/// Pass/fail totals for one test category
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComplianceCategory {
+    /// Category name
+    pub category: String,
+    /// Number of passed tests
+    pub passed: usize,
+    /// Number of failed tests
+    pub failed: usize,
+    /// Category compliance percentage
+    pub compliance_percent: f64,
+}
+
+impl ComplianceReport {
+    /// Totals per category, in the order categories first appear
+    pub fn categories(&self) -> Vec<ComplianceCategory> {
+        let mut categories: Vec<ComplianceCategory> = Vec::new();
+        for test in &self.tests {
+            let index = match categories.iter().position(|c| c.category == test.category) {
+                Some(index) => index,
+                None => {
+                    categories.push(ComplianceCategory {
+                        category: test.category.clone(),
+                        passed: 0,
+                        failed: 0,
+                        compliance_percent: 0.0,
+                    });
+                    categories.len() - 1
+                }
+            };
+            if test.passed {
+                categories[index].passed += 1;
+            } else {
+                categories[index].failed += 1;
+            }
+        }
+        for category in &mut categories {
+            category.compliance_percent = percent(category.passed, category.failed);
+        }
+        categories
+    }
+}
+
+fn percent(passed: usize, failed: usize) -> f64 {
+    if passed + failed == 0 {
+        100.0
+    } else {
+        passed as f64 / (passed + failed) as f64 * 100.0
+    }
+}
+
+/// Run one vector on a fresh terminal
+fn run_vector(vector: &Vector) -> ComplianceTest {
+    let mut term = Terminal::new(VECTOR_COLS, VECTOR_ROWS);
+    term.process(vector.input);
+    let responses = term.drain_responses();
+    let screen = ScreenDump::capture(&term);
+
+    let mut passed = true;
+    let mut expected = Vec::with_capacity(vector.checks.len());
+    let mut actual = Vec::with_capacity(vector.checks.len());
+    for check in vector.checks {
+        let (want, got) = evaluate(check, &term, &screen, &responses);
+        passed &= want == got;
+        expected.push(want);
+        actual.push(got);
+    }
+
+    ComplianceTest {
+        name: vector.name.to_string(),
+        category: vector.category.to_string(),
+        level: vector.level,
+        passed,
+        expected: expected.join("; "),
+        actual: actual.join("; "),
+        notes: Some(vector.source.to_string()),
+    }
+}
+
+/// Expected and actual description of one check; equal when it passes
+fn evaluate(
+    check: &Check,
+    term: &Terminal,
+    screen: &ScreenDump,
+    responses: &[u8],
+) -> (String, String) {
+    let row_text = |row: usize| {
+        screen
+            .lines
+            .get(row.wrapping_sub(1))
+            .map_or("", |l| l.as_str())
+    };
+    match *check {
+        Check::Cursor(col, row) => (
+            format!("cursor {},{}", col, row),
+            format!("cursor {},{}", screen.cursor.0 + 1, screen.cursor.1 + 1),
+        ),
+        Check::Row(row, text) => (
+            format!("row {} {:?}", row, text),
+            format!("row {} {:?}", row, row_text(row).trim_end()),
+        ),
+        Check::Text(col, row, text) => {
+            let found: String = row_text(row)
+                .chars()
+                .skip(col - 1)
+                .take(text.chars().count())
+                .collect();
+            (
+                format!("text at {},{} {:?}", col, row, text),
+                format!("text at {},{} {:?}", col, row, found),
+            )
+        }
+        Check::Style(col, row, style) => (
+            format!("style at {},{} {:?}", col, row, style),
+            format!(
+                "style at {},{} {:?}",
+                col,
+                row,
+                screen.style_at(col - 1, row - 1)
+            ),
+        ),
+        Check::Response(bytes) => (
+            format!("response \"{}\"", bytes.escape_ascii()),
+            format!("response \"{}\"", responses.escape_ascii()),
+        ),
+        Check::ResponsePrefix(prefix) => {
+            let got = &responses[..prefix.len().min(responses.len())];
+            (
+                format!("response starting \"{}\"", prefix.escape_ascii()),
+                format!("response starting \"{}\"", got.escape_ascii()),
+            )
+        }
+        Check::Title(title) => (
+            format!("title {:?}", title),
+            format!("title {:?}", term.title()),
+        ),
+    }
+}
+
+impl Terminal {
+    // === Feature 29: Terminal Compliance Testing ===
+
+    /// Run compliance tests for a specific VT level
+    ///
+    /// Runs every bundled vector whose level is at or below `level`. Each
+    /// vector runs on a fresh 80x24 terminal, so this terminal's state is
+    /// left untouched.
+    pub fn test_compliance(&mut self, level: ComplianceLevel) -> ComplianceReport {
+        let tests: Vec<ComplianceTest> = VECTORS
+            .iter()
+            .filter(|v| v.level <= level)
+            .map(run_vector)
+            .collect();
+
+        let passed = tests.iter().filter(|t| t.passed).count();
+        let failed = tests.len() - passed;
+        let compliance_percent = percent(passed, failed);
+
+        ComplianceReport {
+            terminal_info: "par-term-emu-core-rust".to_string(),
+            level,
+            tests,
+            passed,
+            failed,
+            compliance_percent,
+        }
+    }
+
+    /// Format a compliance report as a human-readable string
+    pub fn format_compliance_report(report: &ComplianceReport) -> String {
+        let mut output = format!(
+            "Compliance Report for {}
+",
+            report.terminal_info
+        );
+        output.push_str(&format!(
+            "Level: {:?}
+",
+            report.level
+        ));
+        output.push_str(&format!(
+            "Score: {:.1}% ({} passed, {} failed)
+",
+            report.compliance_percent, report.passed, report.failed
+        ));
+        output.push_str("\nCategories:\n");
+        for category in report.categories() {
+            output.push_str(&format!(
+                "  {}: {:.1}% ({} passed, {} failed)\n",
+                category.category, category.compliance_percent, category.passed, category.failed
+            ));
+        }
+
+        output.push_str("\nResults:\n");
+        for test in &report.tests {
+            let status = if test.passed { "PASS" } else { "FAIL" };
+            output.push_str(&format!(
+                "[{}] {}: {} ({:?})\n",
+                status, test.category, test.name, test.level
+            ));
+            if !test.passed {
+                output.push_str(&format!("       expected: {}\n", test.expected));
+                output.push_str(&format!("       actual:   {}\n", test.actual));
+            }
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::Terminal;
+
+    fn report_at(level: ComplianceLevel) -> ComplianceReport {
+        Terminal::new(80, 24).test_compliance(level)
+    }
+
+    #[test]
+    fn test_compliance_runs_bundled_vectors() {
+        let report = report_at(ComplianceLevel::XTerm);
+
+        assert_eq!(report.terminal_info, "par-term-emu-core-rust");
+        assert_eq!(report.tests.len(), VECTORS.len());
+        assert_eq!(report.passed + report.failed, report.tests.len());
+        assert!(report.compliance_percent >= 0.0 && report.compliance_percent <= 100.0);
+        // Every result names the esctest/vttest case it came from.
+        assert!(report.tests.iter().all(|t| t
+            .notes
+            .as_deref()
+            .is_some_and(|n| n.starts_with("esctest ") || n.starts_with("vttest "))));
+    }
+
+    #[test]
+    fn test_compliance_filters_by_level() {
+        let levels = [
+            ComplianceLevel::VT52,
+            ComplianceLevel::VT100,
+            ComplianceLevel::VT220,
+            ComplianceLevel::VT320,
+            ComplianceLevel::VT420,
+            ComplianceLevel::VT520,
+            ComplianceLevel::XTerm,
+        ];
+        let mut previous = 0;
+        for level in levels {
+            let report = report_at(level);
+            assert_eq!(report.level, level);
+            assert!(report.tests.iter().all(|t| t.level <= level));
+            assert!(report.tests.len() > previous, "{:?} adds vectors", level);
+            previous = report.tests.len();
+        }
+    }
+
+    #[test]
+    fn test_vector_table_is_well_formed() {
+        let mut names: Vec<&str> = VECTORS.iter().map(|v| v.name).collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), VECTORS.len(), "vector names are unique");
+        assert!(VECTORS.iter().all(|v| !v.checks.is_empty()));
+    }
+
+    #[test]
+    fn test_core_categories_pass() {
+        let report = report_at(ComplianceLevel::XTerm);
+        let categories = report.categories();
+        for name in [
+            "Autowrap",
+            "SGR",
+            "Rectangles",
+            "Editing",
+            "Scrolling",
+            "Save/Restore",
+            "Reset",
+            "VT52",
+        ] {
+            let category = categories.iter().find(|c| c.category == name).unwrap();
+            assert_eq!(category.failed, 0, "{} vectors regressed", name);
+        }
+        let cup = report
+            .tests
+            .iter()
+            .find(|t| t.name == "CUP moves to row;column")
+            .unwrap();
+        assert!(cup.passed);
+        assert_eq!(cup.expected, "cursor 3,6");
+        assert_eq!(cup.actual, cup.expected);
+    }
+
+    #[test]
+    fn test_categories_total_the_report() {
+        let report = report_at(ComplianceLevel::XTerm);
+        let categories = report.categories();
+        assert_eq!(categories[0].category, "Cursor");
+        assert_eq!(
+            categories.iter().map(|c| c.passed).sum::<usize>(),
+            report.passed
+        );
+        assert_eq!(
+            categories.iter().map(|c| c.failed).sum::<usize>(),
+            report.failed
+        );
+    }
+
+    #[test]
+    fn format_compliance_report_renders_expected_sections() {
+        let test = |name: &str, passed: bool| ComplianceTest {
+            name: name.to_string(),
+            category: "Cursor".to_string(),
+            level: ComplianceLevel::VT100,
+            passed,
+            expected: "cursor 1,1".to_string(),
+            actual: if passed { "cursor 1,1" } else { "cursor 2,1" }.to_string(),
+            notes: None,
+        };
+        let report = ComplianceReport {
+            terminal_info: "par-term-emu-core-rust".to_string(),
+            level: ComplianceLevel::VT220,
+            tests: vec![test("Home", true), test("Left", false)],
+            passed: 1,
+            failed: 1,
+            compliance_percent: 50.0,
+        };
+        let text = Terminal::format_compliance_report(&report);
+
+        assert!(text.contains("Compliance Report for par-term-emu-core-rust"));
+        assert!(text.contains("Level: VT220"));
+        assert!(text.contains("Score: 50.0% (1 passed, 1 failed)"));
+        assert!(text.contains("  Cursor: 50.0% (1 passed, 1 failed)"));
+        assert!(text.contains("[PASS] Cursor: Home (VT100)"));
+        assert!(text.contains("[FAIL] Cursor: Left (VT100)\n       expected: cursor 1,1\n       actual:   cursor 2,1\n"));
+    }
+}
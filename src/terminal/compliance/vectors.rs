@@ -0,0 +1,325 @@
+//! Bundled compliance test vectors
+//!
+//! Each vector is derived from an esctest (or vttest) case: the input is fed
+//! to a fresh 80x24 terminal and the listed checks are evaluated against the
+//! resulting state. Coordinates are 1-based, as in the VT specifications.
+
+use super::ComplianceLevel::{self, XTerm, VT100, VT220, VT320, VT420, VT52, VT520};
+
+/// Expected state after a vector's input has been processed
+pub(super) enum Check {
+    /// Cursor at (col, row)
+    Cursor(usize, usize),
+    /// Row text with trailing spaces removed
+    Row(usize, &'static str),
+    /// Text starting at (col, row)
+    Text(usize, usize, &'static str),
+    /// Style of the cell at (col, row), as described by
+    /// [`crate::testing::ScreenDump::style_at`]
+    Style(usize, usize, &'static str),
+    /// All bytes the terminal sent back
+    Response(&'static [u8]),
+    /// Leading bytes of the terminal's reply
+    ResponsePrefix(&'static [u8]),
+    /// Window title
+    Title(&'static str),
+}
+
+use Check::*;
+
+/// One esctest/vttest-derived test vector
+pub(super) struct Vector {
+    pub(super) name: &'static str,
+    pub(super) category: &'static str,
+    /// Lowest level that requires the behavior
+    pub(super) level: ComplianceLevel,
+    /// Originating esctest class/test or vttest screen
+    pub(super) source: &'static str,
+    pub(super) input: &'static [u8],
+    pub(super) checks: &'static [Check],
+}
+
+const fn v(
+    category: &'static str,
+    name: &'static str,
+    level: ComplianceLevel,
+    source: &'static str,
+    input: &'static [u8],
+    checks: &'static [Check],
+) -> Vector {
+    Vector {
+        name,
+        category,
+        level,
+        source,
+        input,
+        checks,
+    }
+}
+
+#[rustfmt::skip]
+pub(super) static VECTORS: &[Vector] = &[
+    // === Cursor movement ===
+    v("Cursor", "CUP with default parameters homes the cursor", VT100, "esctest CUPTests.test_CUP_DefaultParams",
+        b"\x1b[5;5H\x1b[H", &[Cursor(1, 1)]),
+    v("Cursor", "CUP moves to row;column", VT100, "vttest 1 cursor movements",
+        b"\x1b[6;3H", &[Cursor(3, 6)]),
+    v("Cursor", "CUP treats zero parameters as one", VT100, "esctest CUPTests.test_CUP_ZeroIsTreatedAsOne",
+        b"\x1b[5;5H\x1b[0;0H", &[Cursor(1, 1)]),
+    v("Cursor", "CUP clamps to the screen", VT100, "esctest CUPTests.test_CUP_OutOfBoundsParams",
+        b"\x1b[999;999H", &[Cursor(80, 24)]),
+    v("Cursor", "HVP moves to row;column", VT100, "vttest 1 cursor movements",
+        b"\x1b[4;7f", &[Cursor(7, 4)]),
+    v("Cursor", "CUU with default parameter moves up one row", VT100, "esctest CUUTests.test_CUU_DefaultParam",
+        b"\x1b[5;5H\x1b[A", &[Cursor(5, 4)]),
+    v("Cursor", "CUU stops at the top row", VT100, "esctest CUUTests.test_CUU_StopsAtTopLine",
+        b"\x1b[3;5H\x1b[99A", &[Cursor(5, 1)]),
+    v("Cursor", "CUU stops at the top margin", VT100, "esctest CUUTests.test_CUU_StopsAtTopMarginInScrollRegion",
+        b"\x1b[5;10r\x1b[7;1H\x1b[99A", &[Cursor(1, 5)]),
+    v("Cursor", "CUD moves down", VT100, "esctest CUDTests.test_CUD_ExplicitParam",
+        b"\x1b[5;5H\x1b[3B", &[Cursor(5, 8)]),
+    v("Cursor", "CUD stops at the bottom row", VT100, "esctest CUDTests.test_CUD_StopsAtBottomLine",
+        b"\x1b[99B", &[Cursor(1, 24)]),
+    v("Cursor", "CUF moves right", VT100, "esctest CUFTests.test_CUF_ExplicitParam",
+        b"\x1b[5C", &[Cursor(6, 1)]),
+    v("Cursor", "CUF stops at the right edge", VT100, "esctest CUFTests.test_CUF_StopsAtRightSide",
+        b"\x1b[999C", &[Cursor(80, 1)]),
+    v("Cursor", "CUB moves left", VT100, "esctest CUBTests.test_CUB_ExplicitParam",
+        b"\x1b[1;10H\x1b[3D", &[Cursor(7, 1)]),
+    v("Cursor", "CUB stops at the left edge", VT100, "esctest CUBTests.test_CUB_StopsAtLeftEdge",
+        b"\x1b[1;3H\x1b[99D", &[Cursor(1, 1)]),
+    v("Cursor", "BS stops at the left edge", VT100, "esctest BSTests.test_BS_StopsAtOrigin",
+        b"\x08\x08", &[Cursor(1, 1)]),
+    v("Cursor", "CNL moves to the start of a following line", XTerm, "esctest CNLTests.test_CNL_ExplicitParam",
+        b"\x1b[5;5H\x1b[2E", &[Cursor(1, 7)]),
+    v("Cursor", "CPL moves to the start of a preceding line", XTerm, "esctest CPLTests.test_CPL_ExplicitParam",
+        b"\x1b[5;5H\x1b[2F", &[Cursor(1, 3)]),
+    v("Cursor", "CHA moves to an absolute column", XTerm, "esctest CHATests.test_CHA_ExplicitParam",
+        b"\x1b[3;1H\x1b[10G", &[Cursor(10, 3)]),
+    v("Cursor", "HPA moves to an absolute column", XTerm, "esctest HPATests.test_HPA_DoesNotChangeRow",
+        b"\x1b[3;1H\x1b[12`", &[Cursor(12, 3)]),
+    v("Cursor", "VPA moves to an absolute row", XTerm, "esctest VPATests.test_VPA_DoesNotChangeColumn",
+        b"\x1b[1;4H\x1b[9d", &[Cursor(4, 9)]),
+    v("Cursor", "HPR moves right relative to the cursor", XTerm, "esctest HPRTests.test_HPR_DoesNotChangeRow",
+        b"\x1b[1;4H\x1b[3a", &[Cursor(7, 1)]),
+    v("Cursor", "VPR moves down relative to the cursor", XTerm, "esctest VPRTests.test_VPR_DoesNotChangeColumn",
+        b"\x1b[2;4H\x1b[3e", &[Cursor(4, 5)]),
+    v("Cursor", "DECSTBM moves the cursor home", VT100, "esctest DECSTBMTests.test_DECSTBM_MovesCursorToOrigin",
+        b"\x1b[5;5H\x1b[3;10r", &[Cursor(1, 1)]),
+    v("Cursor", "DECOM makes CUP relative to the top margin", VT100, "esctest DECSETTests.test_DECSET_DECOM",
+        b"\x1b[5;10r\x1b[?6h\x1b[1;1H", &[Cursor(1, 5)]),
+    v("Cursor", "DECOM confines CUP to the scroll region", VT100, "esctest CUPTests.test_CUP_RespectsOriginMode",
+        b"\x1b[5;10r\x1b[?6h\x1b[99;1H", &[Cursor(1, 10)]),
+
+    // === Erasing ===
+    v("Erase", "ED 0 erases from the cursor to the end of screen", VT100, "esctest EDTests.test_ED_0",
+        b"abc\r\ndef\x1b[1;2H\x1b[J", &[Row(1, "a"), Row(2, ""), Cursor(2, 1)]),
+    v("Erase", "ED 1 erases from the start of screen to the cursor", VT100, "esctest EDTests.test_ED_1",
+        b"abc\r\ndef\x1b[2;2H\x1b[1J", &[Row(1, ""), Row(2, "  f"), Cursor(2, 2)]),
+    v("Erase", "ED 2 erases the screen without moving the cursor", VT100, "esctest EDTests.test_ED_2",
+        b"abc\r\ndef\x1b[2J", &[Row(1, ""), Row(2, ""), Cursor(4, 2)]),
+    v("Erase", "EL 0 erases to the end of line", VT100, "esctest ELTests.test_EL_0",
+        b"abcdef\x1b[1;3H\x1b[K", &[Row(1, "ab")]),
+    v("Erase", "EL 1 erases from the start of line through the cursor", VT100, "esctest ELTests.test_EL_1",
+        b"abcdef\x1b[1;3H\x1b[1K", &[Row(1, "   def")]),
+    v("Erase", "EL 2 erases the whole line", VT100, "esctest ELTests.test_EL_2",
+        b"abcdef\x1b[1;3H\x1b[2K", &[Row(1, ""), Cursor(3, 1)]),
+    v("Erase", "ECH erases characters without moving the cursor", VT220, "esctest ECHTests.test_ECH_ExplicitParam",
+        b"abcdef\x1b[1;2H\x1b[3X", &[Row(1, "a   ef"), Cursor(2, 1)]),
+    v("Erase", "ED fills with the current background color", XTerm, "esctest EDTests.test_ED_respectsBCE",
+        b"\x1b[44m\x1b[2J", &[Style(1, 1, "bg=blue"), Style(80, 24, "bg=blue")]),
+    v("Erase", "DECSED spares protected characters", VT220, "esctest DECSEDTests.test_DECSED_2_Protection",
+        b"\x1b[1\"qab\x1b[0\"qcd\x1b[?2J", &[Row(1, "ab")]),
+    v("Erase", "DECSEL spares protected characters", VT220, "esctest DECSELTests.test_DECSEL_2_Protection",
+        b"\x1b[1\"qab\x1b[0\"qcd\x1b[?2K", &[Row(1, "ab")]),
+
+    // === Editing ===
+    v("Editing", "ICH inserts blanks at the cursor", VT100, "esctest ICHTests.test_ICH_ExplicitParam",
+        b"abcdef\x1b[1;2H\x1b[2@", &[Row(1, "a  bcdef"), Cursor(2, 1)]),
+    v("Editing", "DCH deletes characters at the cursor", VT100, "esctest DCHTests.test_DCH_ExplicitParam",
+        b"abcdef\x1b[1;2H\x1b[2P", &[Row(1, "adef")]),
+    v("Editing", "IL inserts lines at the cursor row", VT100, "esctest ILTests.test_IL_DefaultParam",
+        b"a\r\nb\r\nc\x1b[2;1H\x1b[L", &[Row(1, "a"), Row(2, ""), Row(3, "b"), Row(4, "c")]),
+    v("Editing", "DL deletes lines at the cursor row", VT100, "esctest DLTests.test_DL_DefaultParam",
+        b"a\r\nb\r\nc\x1b[2;1H\x1b[M", &[Row(1, "a"), Row(2, "c"), Row(3, "")]),
+    v("Editing", "IL only shifts lines inside the scroll region", VT100, "esctest ILTests.test_IL_RespectsScrollRegion",
+        b"a\r\nb\r\nc\r\nd\x1b[2;3r\x1b[2;1H\x1b[L", &[Row(1, "a"), Row(2, ""), Row(3, "b"), Row(4, "d")]),
+    v("Editing", "IRM inserts printed characters", VT100, "esctest SMTests.test_SM_IRM",
+        b"abc\x1b[1;1H\x1b[4hX", &[Row(1, "Xabc")]),
+    v("Editing", "REP repeats the preceding character", XTerm, "esctest REPTests.test_REP_ExplicitParam",
+        b"a\x1b[3b", &[Row(1, "aaaa")]),
+    v("Editing", "DECIC inserts a column in every row", VT420, "esctest DECICTests.test_DECIC_DefaultParam",
+        b"abc\r\ndef\x1b[1;2H\x1b['}", &[Row(1, "a bc"), Row(2, "d ef")]),
+    v("Editing", "DECDC deletes a column in every row", VT420, "esctest DECDCTests.test_DECDC_DefaultParam",
+        b"abc\r\ndef\x1b[1;2H\x1b['~", &[Row(1, "ac"), Row(2, "df")]),
+    v("Editing", "ICH pushes characters off the right margin", VT420, "esctest ICHTests.test_ICH_ScrollOffRightMarginInScrollRegion",
+        b"abcdefg\x1b[?69h\x1b[2;5s\x1b[1;3H\x1b[@", &[Row(1, "ab cdfg")]),
+    v("Editing", "DCH pulls characters in from the right margin", VT420, "esctest DCHTests.test_DCH_RespectsMargins",
+        b"abcdefg\x1b[?69h\x1b[2;5s\x1b[1;3H\x1b[P", &[Row(1, "abde fg")]),
+    v("Editing", "IL respects the left and right margins", VT420, "esctest ILTests.test_IL_RespectsScrollRegion",
+        b"abcde\r\nfghij\x1b[?69h\x1b[2;4s\x1b[1;2H\x1b[L", &[Row(1, "a   e"), Row(2, "fbcdj")]),
+
+    // === Scrolling ===
+    v("Scrolling", "IND at the bottom row scrolls up", VT100, "esctest INDTests.test_IND_Scrolls",
+        b"a\x1b[24;1H\x1bD", &[Row(1, ""), Cursor(1, 24)]),
+    v("Scrolling", "RI at the top row scrolls down", VT100, "esctest RITests.test_RI_Scrolls",
+        b"a\x1b[1;1H\x1bM", &[Row(1, ""), Row(2, "a"), Cursor(1, 1)]),
+    v("Scrolling", "NEL moves to the start of the next line", VT100, "esctest NELTests.test_NEL_Basic",
+        b"abc\x1bE", &[Cursor(1, 2)]),
+    v("Scrolling", "LF at the bottom margin scrolls only the region", VT100, "esctest DECSTBMTests.test_DECSTBM_ScrollsOnNewline",
+        b"x\x1b[2;3r\x1b[2;1Hb\r\nc\r\nd", &[Row(1, "x"), Row(2, "c"), Row(3, "d"), Row(4, "")]),
+    v("Scrolling", "SU scrolls the screen up", VT420, "esctest SUTests.test_SU_DefaultParam",
+        b"a\r\nb\x1b[S", &[Row(1, "b"), Row(2, "")]),
+    v("Scrolling", "SD scrolls the screen down", VT420, "esctest SDTests.test_SD_DefaultParam",
+        b"a\x1b[T", &[Row(1, ""), Row(2, "a")]),
+    v("Scrolling", "DECSLRM wraps text at the right margin", VT420, "esctest DECSETTests.test_DECSET_DECAWM_OnRespectsMargins",
+        b"\x1b[?69h\x1b[2;4s\x1b[1;2Habcdef", &[Row(1, " abc"), Row(2, " def")]),
+    v("Scrolling", "DECBI at the left margin scrolls right", VT420, "esctest DECBITests.test_DECBI_Scrolls",
+        b"abc\x1b[1;1H\x1b6", &[Row(1, " abc"), Cursor(1, 1)]),
+    v("Scrolling", "DECFI at the right margin scrolls left", VT420, "esctest DECFITests.test_DECFI_Scrolls",
+        b"abc\x1b[1;80H\x1b9", &[Row(1, "bc"), Cursor(80, 1)]),
+    v("Scrolling", "SL scrolls the screen left", XTerm, "esctest SLTests.test_SL_DefaultParam",
+        b"abc\x1b[ @", &[Row(1, "bc")]),
+    v("Scrolling", "SR scrolls the screen right", XTerm, "esctest SRTests.test_SR_DefaultParam",
+        b"abc\x1b[ A", &[Row(1, " abc")]),
+    v("Scrolling", "LF scrolls only between the left and right margins", VT420, "esctest LFTests.test_LF_ScrollsOnlyWithinMargins",
+        b"abcde\r\nfghij\x1b[1;2r\x1b[?69h\x1b[2;4s\x1b[2;3H\n", &[Row(1, "aghie"), Row(2, "f   j")]),
+
+    // === Tabulation ===
+    v("Tabs", "HT advances to the default stop every 8 columns", VT100, "esctest HTTests.test_HT_DefaultStops",
+        b"\t", &[Cursor(9, 1)]),
+    v("Tabs", "HT stops at the right edge", VT100, "esctest HTTests.test_HT_DefaultStops",
+        b"\x1b[1;79H\t\t", &[Cursor(80, 1)]),
+    v("Tabs", "TBC 3 clears every tab stop", VT100, "esctest TBCTests.test_TBC_3",
+        b"\x1b[3g\t", &[Cursor(80, 1)]),
+    v("Tabs", "HTS sets a tab stop", VT100, "esctest HTSTests.test_HTS_Basic",
+        b"\x1b[3g\x1b[1;5H\x1bH\x1b[1;1H\t", &[Cursor(5, 1)]),
+    v("Tabs", "CHT advances several tab stops", XTerm, "esctest CHTTests.test_CHT_ExplicitParameter",
+        b"\x1b[2I", &[Cursor(17, 1)]),
+    v("Tabs", "CBT moves back one tab stop", XTerm, "esctest CBTTests.test_CBT_OneTabStopByDefault",
+        b"\x1b[1;20H\x1b[Z", &[Cursor(17, 1)]),
+    v("Tabs", "DECST8C resets tab stops to every 8 columns", VT520, "esctest DECST8CTests.test_DECST8C_AddsStopsEvery8",
+        b"\x1b[3g\x1b[?5W\t", &[Cursor(9, 1)]),
+
+    // === Autowrap ===
+    v("Autowrap", "Printing past the last column wraps", VT100, "esctest DECSETTests.test_DECSET_DECAWM",
+        b"\x1b[1;80Hab", &[Text(80, 1, "a"), Row(2, "b"), Cursor(2, 2)]),
+    v("Autowrap", "The cursor stays on the last column until the next character", VT100, "esctest DECSETTests.test_DECSET_DECAWM_CursorAtRightMargin",
+        b"\x1b[1;80Ha", &[Cursor(80, 1), Row(2, "")]),
+    v("Autowrap", "With DECAWM reset the last column is overwritten", VT100, "esctest DECSETTests.test_DECSET_DECAWM",
+        b"\x1b[?7l\x1b[1;79Habc", &[Text(79, 1, "ac"), Row(2, ""), Cursor(80, 1)]),
+    v("Autowrap", "CR cancels a pending wrap", VT100, "esctest CRTests.test_CR_ClearsPendingWrap",
+        b"\x1b[1;80Ha\rb", &[Text(1, 1, "b"), Row(2, ""), Cursor(2, 1)]),
+
+    // === Character sets ===
+    v("Charsets", "G0 DEC Special Graphics maps line drawing", VT100, "vttest 3.2 character sets",
+        b"\x1b(0qx\x1b(Bq", &[Row(1, "\u{2500}\u{2502}q")]),
+    v("Charsets", "SO and SI switch between G1 and G0", VT100, "esctest SOTests.test_SO_Basic",
+        b"\x1b)0\x0eq\x0fq", &[Row(1, "\u{2500}q")]),
+    v("Charsets", "SS2 uses G2 for one character", VT220, "esctest SS2Tests.test_SS2_Basic",
+        b"\x1b*0\x1bNqq", &[Row(1, "\u{2500}q")]),
+
+    // === Rendition (SGR) ===
+    v("SGR", "SGR 1 selects bold", VT100, "esctest SGRTests.test_SGR_Bold",
+        b"\x1b[1mX", &[Style(1, 1, "bold")]),
+    v("SGR", "SGR 0 resets every attribute", VT100, "esctest SGRTests.test_SGR_Reset",
+        b"\x1b[1;4mX\x1b[0mY", &[Style(1, 1, "bold underline"), Style(2, 1, "")]),
+    v("SGR", "SGR 4, 5 and 7 select underline, blink and reverse", VT100, "vttest 2.9 graphic rendition",
+        b"\x1b[4;5;7mX", &[Style(1, 1, "underline blink reverse")]),
+    v("SGR", "SGR 22 cancels bold", VT220, "esctest SGRTests.test_SGR_NormalIntensity",
+        b"\x1b[1m\x1b[22mX", &[Style(1, 1, "")]),
+    v("SGR", "SGR 8 selects invisible text", VT220, "vttest 11.6 VT220 rendition",
+        b"\x1b[8mX", &[Style(1, 1, "hidden")]),
+    v("SGR", "SGR 30-47 select ANSI colors", XTerm, "esctest SGRTests.test_SGR_Colors",
+        b"\x1b[31;42mX", &[Style(1, 1, "fg=red bg=green")]),
+    v("SGR", "SGR 90-97 select bright colors", XTerm, "esctest SGRTests.test_SGR_BrightColors",
+        b"\x1b[91mX", &[Style(1, 1, "fg=bright-red")]),
+    v("SGR", "SGR 38;5 selects a 256-color index", XTerm, "esctest SGRTests.test_SGR_256Color",
+        b"\x1b[38;5;196mX", &[Style(1, 1, "fg=idx196")]),
+    v("SGR", "SGR 38;2 selects a direct color", XTerm, "esctest SGRTests.test_SGR_DirectColor",
+        b"\x1b[38;2;1;2;3mX", &[Style(1, 1, "fg=#010203")]),
+
+    // === Save/restore ===
+    v("Save/Restore", "DECRC restores the cursor position", VT100, "esctest DECRCTests.test_SaveRestoreCursor_Basic",
+        b"\x1b[5;6H\x1b7\x1b[1;1H\x1b8", &[Cursor(6, 5)]),
+    v("Save/Restore", "DECRC restores the rendition", VT100, "esctest DECRCTests.test_SaveRestoreCursor_RestoresAttributes",
+        b"\x1b[1m\x1b7\x1b[0m\x1b8X", &[Style(1, 1, "bold")]),
+    v("Save/Restore", "DECRC restores origin mode", VT100, "esctest DECRCTests.test_SaveRestoreCursor_WorksInOriginMode",
+        b"\x1b[5;10r\x1b[?6h\x1b7\x1b[?6l\x1b8\x1b[1;1H", &[Cursor(1, 5)]),
+    v("Save/Restore", "DECRC restores the character sets", VT100, "vttest 3.2 character sets",
+        b"\x1b(0\x1b7\x1b(B\x1b8q", &[Row(1, "\u{2500}")]),
+    v("Save/Restore", "DECRC restores the wrap flag", XTerm, "esctest DECRCTests.test_SaveRestoreCursor_Wrap",
+        b"\x1b[1;80HA\x1b7\x1b[5;5H\x1b8B", &[Row(2, "B")]),
+    v("Save/Restore", "SCORC restores the position saved by SCOSC", XTerm, "esctest SCORCTests.test_SCORC_Basic",
+        b"\x1b[3;4H\x1b[s\x1b[H\x1b[u", &[Cursor(4, 3)]),
+
+    // === Reports ===
+    v("Reports", "DSR 6 reports the cursor position", VT100, "esctest DSRTests.test_DSR_CPR",
+        b"\x1b[3;5H\x1b[6n", &[Response(b"\x1b[3;5R")]),
+    v("Reports", "DSR 5 reports operating status", VT100, "esctest DSRTests.test_DSR_OperatingStatus",
+        b"\x1b[5n", &[Response(b"\x1b[0n")]),
+    v("Reports", "DA reports a private primary attribute string", VT100, "esctest DATests.test_DA_NoParameter",
+        b"\x1b[c", &[ResponsePrefix(b"\x1b[?")]),
+    v("Reports", "DA2 reports the terminal type and version", VT220, "esctest DA2Tests.test_DA2_NoParameter",
+        b"\x1b[>c", &[ResponsePrefix(b"\x1b[>")]),
+    v("Reports", "S8C1T switches replies to 8-bit controls", VT220, "esctest S8C1TTests.test_S8C1T_CPR",
+        b"\x1b G\x1b[5n", &[Response(b"\x9b0n")]),
+    v("Reports", "DECRQM reports DECAWM as set", VT320, "esctest DECRQMTests.test_DECRQM_DEC_DECAWM",
+        b"\x1b[?7$p", &[Response(b"\x1b[?7;1$y")]),
+    v("Reports", "DECRQM reports DECTCEM as reset after hiding the cursor", VT320, "esctest DECRQMTests.test_DECRQM_DEC_DECTCEM",
+        b"\x1b[?25l\x1b[?25$p", &[Response(b"\x1b[?25;2$y")]),
+    v("Reports", "DECRQM reports IRM as set", VT320, "esctest DECRQMTests.test_DECRQM_ANSI_IRM",
+        b"\x1b[4h\x1b[4$p", &[Response(b"\x1b[4;1$y")]),
+    v("Reports", "DECRQSS reports the scroll region", VT320, "esctest DECRQSSTests.test_DECRQSS_DECSTBM",
+        b"\x1b[5;10r\x1bP$qr\x1b\\", &[Response(b"\x1bP1$r5;10r\x1b\\")]),
+    v("Reports", "DECRQSS reports the rendition", VT320, "esctest DECRQSSTests.test_DECRQSS_SGR",
+        b"\x1b[1m\x1bP$qm\x1b\\", &[ResponsePrefix(b"\x1bP1$r")]),
+    v("Reports", "DECXCPR reports the cursor position", VT420, "esctest DSRTests.test_DSR_DECXCPR",
+        b"\x1b[3;5H\x1b[?6n", &[ResponsePrefix(b"\x1b[?3;5")]),
+    v("Reports", "DSR reports no printer", VT220, "esctest DSRTests.test_DSR_PrinterStatus",
+        b"\x1b[?15n", &[Response(b"\x1b[?13n")]),
+    v("Reports", "DSR reports UDKs unlocked", VT220, "esctest DSRTests.test_DSR_UDKLocked",
+        b"\x1b[?25n", &[Response(b"\x1b[?20n")]),
+    v("Reports", "DSR reports the keyboard language", VT220, "esctest DSRTests.test_DSR_KeyboardStatus",
+        b"\x1b[?26n", &[ResponsePrefix(b"\x1b[?27;1")]),
+    v("Reports", "DECRQM reports DECLRMM as reset", VT420, "esctest DECRQMTests.test_DECRQM_DEC_DECLRMM",
+        b"\x1b[?69$p", &[Response(b"\x1b[?69;2$y")]),
+    v("Reports", "DECRQSS reports the cursor style", VT520, "esctest DECRQSSTests.test_DECRQSS_DECSCUSR",
+        b"\x1b[4 q\x1bP$q q\x1b\\", &[Response(b"\x1bP1$r4 q\x1b\\")]),
+    v("Reports", "DECRQM reports bracketed paste as set", XTerm, "esctest DECRQMTests.test_DECRQM_DEC_BracketedPaste",
+        b"\x1b[?2004h\x1b[?2004$p", &[Response(b"\x1b[?2004;1$y")]),
+
+    // === Reset and screen control ===
+    v("Reset", "RIS clears the screen and homes the cursor", VT100, "esctest RISTests.test_RIS_ClearsScreen",
+        b"abc\x1b[5;5H\x1bc", &[Row(1, ""), Cursor(1, 1)]),
+    v("Reset", "DECALN fills the screen with E", VT100, "esctest DECALNTests.test_DECALN_FillsScreen",
+        b"\x1b#8", &[Text(1, 1, "EEEE"), Text(77, 24, "EEEE"), Cursor(1, 1)]),
+    v("Reset", "DECSTR resets insert mode", VT220, "esctest DECSTRTests.test_DECSTR_IRM",
+        b"\x1b[4h\x1b[!pabc\x1b[1;1HX", &[Row(1, "Xbc")]),
+    v("Reset", "DECSTR resets origin mode", VT220, "esctest DECSTRTests.test_DECSTR_DECOM",
+        b"\x1b[5;10r\x1b[?6h\x1b[!p\x1b[1;1H", &[Cursor(1, 1)]),
+
+    // === Rectangular area operations ===
+    v("Rectangles", "DECFRA fills a rectangle", VT420, "esctest DECFRATests.test_DECFRA_basic",
+        b"\x1b[88;1;1;2;3$x", &[Row(1, "XXX"), Row(2, "XXX"), Row(3, "")]),
+    v("Rectangles", "DECERA erases a rectangle", VT420, "esctest DECERATests.test_DECERA_basic",
+        b"abc\r\ndef\x1b[1;2;2;2$z", &[Row(1, "a c"), Row(2, "d f")]),
+    v("Rectangles", "DECCRA copies a rectangle", VT420, "esctest DECCRATests.test_DECCRA_nonOverlappingSourceAndDest",
+        b"abc\x1b[1;1;1;3;1;3;1;1$v", &[Row(1, "abc"), Row(3, "abc")]),
+
+    // === xterm extensions ===
+    v("XTerm", "OSC 2 sets the window title", XTerm, "esctest ChangeWindowTitleTests.test_ChangeWindowTitle_Basic",
+        b"\x1b]2;hello\x07", &[Title("hello")]),
+    v("XTerm", "Mode 1049 restores the main screen and cursor", XTerm, "esctest DECSETTests.test_DECSET_SaveRestoreCursorAndAltBuf",
+        b"main\x1b[?1049halt\x1b[?1049l", &[Row(1, "main"), Cursor(5, 1)]),
+    v("XTerm", "Mode 47 switches to a blank alternate screen", XTerm, "esctest DECSETTests.test_DECSET_AltScreen",
+        b"main\x1b[?47h", &[Row(1, "")]),
+
+    // === VT52 compatibility mode ===
+    v("VT52", "ESC Y addresses the cursor in VT52 mode", VT52, "vttest 7 VT52 mode",
+        b"\x1b[?2l\x1bY%(", &[Cursor(9, 6), Row(6, "")]),
+    v("VT52", "ESC A moves up in VT52 mode", VT52, "vttest 7 VT52 mode",
+        b"\x1b[5;5H\x1b[?2l\x1bA", &[Cursor(5, 4)]),
+    v("VT52", "ESC < returns to ANSI mode", VT52, "vttest 7 VT52 mode",
+        b"\x1b[?2l\x1b<\x1b[3;3H", &[Cursor(3, 3)]),
+];
@@ -0,0 +1,123 @@
+//! Cursor motion history
+//!
+//! Records where the cursor moved and why, so frontends that animate the
+//! cursor (smooth trails, jump highlights) can tell a jump caused by an
+//! escape sequence (CUP, CUU, RI, ...) apart from the cursor advancing as
+//! text is printed.
+//!
+//! Motions are sampled at parser boundaries rather than per character: a
+//! run of printed text (including the CR/LF/BS/TAB controls inside it)
+//! becomes a single [`CursorMoveCause::Text`] motion, and each cursor-moving
+//! CSI/ESC sequence becomes its own [`CursorMoveCause::EscapeSequence`]
+//! motion. Recording is disabled by default.
+
+use crate::terminal::{Terminal, TerminalEvent};
+
+/// What moved the cursor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CursorMoveCause {
+    /// Printing text and the C0 controls that accompany it (CR, LF, BS, HT)
+    Text,
+    /// A CSI or ESC sequence (cursor positioning, index, restore, ...)
+    EscapeSequence,
+}
+
+impl CursorMoveCause {
+    /// Short lowercase name ("text" / "escape_sequence")
+    pub fn name(&self) -> &'static str {
+        match self {
+            CursorMoveCause::Text => "text",
+            CursorMoveCause::EscapeSequence => "escape_sequence",
+        }
+    }
+}
+
+/// A recorded cursor movement
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CursorMotion {
+    /// Position before the move as (col, row)
+    pub from: (usize, usize),
+    /// Position after the move as (col, row)
+    pub to: (usize, usize),
+    /// What caused the move
+    pub cause: CursorMoveCause,
+    /// Timestamp in microseconds
+    pub timestamp: u64,
+}
+
+impl Terminal {
+    /// Compare the cursor against the last sample point and record a motion
+    /// attributed to `cause` if it moved.
+    pub(crate) fn note_cursor_motion(&mut self, cause: CursorMoveCause) {
+        if !self.cursor_trail.enabled {
+            return;
+        }
+        let to = (self.cursor.col, self.cursor.row);
+        let from = self.cursor_trail.last_pos;
+        if from == to {
+            return;
+        }
+        self.cursor_trail.last_pos = to;
+
+        let trail = &mut self.cursor_trail;
+        trail.history.push_back(CursorMotion {
+            from,
+            to,
+            cause,
+            timestamp: crate::terminal::get_timestamp_us(),
+        });
+        while trail.history.len() > trail.max_history {
+            trail.history.pop_front();
+        }
+        self.push_render_event(TerminalEvent::CursorMoved { from, to, cause });
+    }
+
+    /// Re-sync the sample point without recording a motion (used when the
+    /// cursor is moved by the host, e.g. on resize or reset).
+    pub(crate) fn sync_cursor_trail(&mut self) {
+        self.cursor_trail.last_pos = (self.cursor.col, self.cursor.row);
+    }
+
+    /// Enable or disable cursor motion recording.
+    ///
+    /// While enabled, each recorded motion also emits a
+    /// [`TerminalEvent::CursorMoved`]. Disabling keeps the existing history.
+    pub fn set_cursor_trail_enabled(&mut self, enabled: bool) {
+        self.cursor_trail.enabled = enabled;
+        self.sync_cursor_trail();
+    }
+
+    /// Whether cursor motion recording is enabled
+    pub fn is_cursor_trail_enabled(&self) -> bool {
+        self.cursor_trail.enabled
+    }
+
+    /// Get the `n` most recent cursor motions, oldest first
+    pub fn cursor_motion_history(&self, n: usize) -> Vec<CursorMotion> {
+        let history = &self.cursor_trail.history;
+        history
+            .iter()
+            .skip(history.len().saturating_sub(n))
+            .copied()
+            .collect()
+    }
+
+    /// Clear recorded cursor motions
+    pub fn clear_cursor_motion_history(&mut self) {
+        self.cursor_trail.history.clear();
+    }
+
+    /// Set the maximum number of cursor motions to retain
+    pub fn set_max_cursor_motion_history(&mut self, max: usize) {
+        let trail = &mut self.cursor_trail;
+        trail.max_history = max;
+        while trail.history.len() > max {
+            trail.history.pop_front();
+        }
+    }
+
+    /// Get the maximum number of cursor motions to retain
+    pub fn max_cursor_motion_history(&self) -> usize {
+        self.cursor_trail.max_history
+    }
+}
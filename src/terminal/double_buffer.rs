@@ -0,0 +1,77 @@
+//! Double-buffered frames for tear-free rendering
+//!
+//! With double buffering on, output keeps landing in the live grid (the back
+//! buffer) while readers see the front buffer: the last committed frame, held
+//! as a [`TerminalStateHandle`]. A frame is committed at the end of each
+//! [`Terminal::process`] call, except during a synchronized update (DEC 2026),
+//! where the commit waits for `CSI ? 2026 l` or
+//! [`Terminal::flush_synchronized_updates`]. A renderer polling
+//! [`Terminal::front_buffer`] from another thread therefore never sees half
+//! of an update the application bracketed.
+//!
+//! Each commit copies the visible screen, so the option costs one screen copy
+//! per chunk of output. Changes made through the API (resize, clear, ...)
+//! show up at the next commit; [`Terminal::commit_frame`] publishes them
+//! right away.
+
+use crate::terminal::{Terminal, TerminalStateHandle};
+
+/// Double buffering setting and the committed front buffer
+#[derive(Debug, Default)]
+pub(crate) struct DoubleBufferState {
+    enabled: bool,
+    front: Option<TerminalStateHandle>,
+    frames: u64,
+}
+
+impl Terminal {
+    /// Turn double buffering on or off
+    ///
+    /// Turning it on commits the current screen as the first front buffer.
+    pub fn set_double_buffering(&mut self, enabled: bool) {
+        self.double_buffer.enabled = enabled;
+        if enabled {
+            self.commit_frame();
+        } else {
+            self.double_buffer.front = None;
+        }
+    }
+
+    /// Whether double buffering is on
+    pub fn double_buffering(&self) -> bool {
+        self.double_buffer.enabled
+    }
+
+    /// The frame readers should draw
+    ///
+    /// The last committed frame while double buffering is on, otherwise the
+    /// live screen (see [`Terminal::state_handle`]).
+    pub fn front_buffer(&self) -> TerminalStateHandle {
+        match &self.double_buffer.front {
+            Some(front) if self.double_buffer.enabled => front.clone(),
+            _ => self.state_handle(),
+        }
+    }
+
+    /// Swap the back buffer to the front now, even inside a synchronized
+    /// update; does nothing while double buffering is off
+    pub fn commit_frame(&mut self) {
+        if self.double_buffer.enabled {
+            self.double_buffer.front = Some(self.state_handle());
+            self.double_buffer.frames += 1;
+        }
+    }
+
+    /// Number of frames committed so far
+    pub fn committed_frames(&self) -> u64 {
+        self.double_buffer.frames
+    }
+
+    /// Commit a frame unless a synchronized update is holding it back.
+    /// Called at the end of `process()`.
+    pub(crate) fn commit_frame_if_idle(&mut self) {
+        if !self.sync_state.synchronized_updates {
+            self.commit_frame();
+        }
+    }
+}
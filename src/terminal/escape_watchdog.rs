@@ -0,0 +1,177 @@
+//! Watchdog for unterminated OSC and DCS strings
+//!
+//! A program that opens an OSC (`ESC ]`) or DCS (`ESC P`) string and never
+//! sends its terminator leaves the parser inside the string, so every later
+//! byte is swallowed as string data. While the watchdog is enabled, a string
+//! longer than [`EscapeWatchdogConfig::max_bytes`] or still open after
+//! [`EscapeWatchdogConfig::timeout_ms`] is cancelled with CAN, reported as a
+//! [`TerminalEvent::EscapeStringAborted`], and parsing resumes with the next
+//! byte as ordinary output. The cancelled string is discarded, never
+//! dispatched.
+//!
+//! The byte cap is enforced by the stream pre-filter in `osc_guard`, which
+//! also applies the `max_osc_data_length` security limit. The timeout is
+//! checked before each [`Terminal::process`] call; frontends that want a
+//! stalled string cancelled while no output arrives call
+//! [`Terminal::update_escape_watchdog`] from a timer.
+
+use crate::debug;
+use crate::terminal::{Terminal, TerminalEvent};
+use crate::time::Instant;
+
+/// Settings for the unterminated string watchdog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EscapeWatchdogConfig {
+    /// Whether the watchdog runs
+    pub enabled: bool,
+    /// Longest OSC or DCS body (bytes after `ESC ]` / `ESC P`) allowed
+    pub max_bytes: usize,
+    /// How long a string may stay open before it is cancelled (ms)
+    pub timeout_ms: u64,
+}
+
+impl Default for EscapeWatchdogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_bytes: 16 * 1024 * 1024,
+            timeout_ms: 10_000,
+        }
+    }
+}
+
+/// Kind of control string cancelled by the watchdog
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EscapeStringKind {
+    /// Operating System Command (`ESC ]`)
+    Osc,
+    /// Device Control String (`ESC P`)
+    Dcs,
+}
+
+impl EscapeStringKind {
+    /// Short name (`"osc"` or `"dcs"`)
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EscapeStringKind::Osc => "osc",
+            EscapeStringKind::Dcs => "dcs",
+        }
+    }
+}
+
+/// Why the watchdog cancelled a string
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EscapeAbortReason {
+    /// The body reached [`EscapeWatchdogConfig::max_bytes`]
+    TooLong,
+    /// The string was open longer than [`EscapeWatchdogConfig::timeout_ms`]
+    Timeout,
+}
+
+impl EscapeAbortReason {
+    /// Short name (`"too_long"` or `"timeout"`)
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EscapeAbortReason::TooLong => "too_long",
+            EscapeAbortReason::Timeout => "timeout",
+        }
+    }
+}
+
+/// One string cancelled by the watchdog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct EscapeAbort {
+    pub(crate) kind: EscapeStringKind,
+    pub(crate) reason: EscapeAbortReason,
+    /// Body bytes seen before the string was cancelled
+    pub(crate) length: usize,
+}
+
+/// Watchdog settings plus the start time of the open string
+#[derive(Debug, Default)]
+pub(crate) struct EscapeWatchdogState {
+    pub(crate) config: EscapeWatchdogConfig,
+    /// When the in-flight OSC/DCS string began
+    pub(crate) started: Option<Instant>,
+}
+
+impl EscapeWatchdogState {
+    /// Byte cap for the pre-filter, or `None` while disabled
+    pub(crate) fn byte_limit(&self) -> Option<usize> {
+        self.config.enabled.then_some(self.config.max_bytes)
+    }
+
+    /// Whether the open string has outlived the timeout at `now`
+    pub(crate) fn expired(&self, now: Instant) -> bool {
+        self.config.enabled
+            && self.started.is_some_and(|started| {
+                now.duration_since(started).as_millis() >= u128::from(self.config.timeout_ms)
+            })
+    }
+}
+
+impl Terminal {
+    /// Set the unterminated OSC/DCS string watchdog config
+    pub fn set_escape_watchdog_config(&mut self, config: EscapeWatchdogConfig) {
+        self.escape_watchdog.config = config;
+        if !config.enabled {
+            self.escape_watchdog.started = None;
+        }
+    }
+
+    /// Get the unterminated OSC/DCS string watchdog config
+    pub fn escape_watchdog_config(&self) -> EscapeWatchdogConfig {
+        self.escape_watchdog.config
+    }
+
+    /// Cancel the in-flight OSC/DCS string now if it has timed out.
+    ///
+    /// Call periodically (e.g. from a render timer) so a stalled string is
+    /// cancelled even while no further output arrives. Returns `true` if a
+    /// string was cancelled.
+    ///
+    /// ```
+    /// use par_term_emu_core_rust::terminal::{EscapeWatchdogConfig, Terminal};
+    ///
+    /// let mut term = Terminal::new(80, 24);
+    /// term.set_escape_watchdog_config(EscapeWatchdogConfig {
+    ///     enabled: true,
+    ///     timeout_ms: 0,
+    ///     ..Default::default()
+    /// });
+    /// term.process(b"\x1b]0;never terminated");
+    /// assert!(term.update_escape_watchdog());
+    /// term.process(b"visible");
+    /// assert_eq!(term.export_text().trim_end(), "visible");
+    /// ```
+    pub fn update_escape_watchdog(&mut self) -> bool {
+        if !self.escape_watchdog.expired(Instant::now()) {
+            return false;
+        }
+        self.filter_apc_and_advance(&[]);
+        true
+    }
+
+    /// Report strings cancelled by the watchdog
+    pub(crate) fn report_escape_aborts(&mut self, aborts: Vec<EscapeAbort>) {
+        for abort in aborts {
+            debug::log(
+                debug::DebugLevel::Debug,
+                "WATCHDOG",
+                &format!(
+                    "Cancelled unterminated {} string after {} bytes ({})",
+                    abort.kind.as_str(),
+                    abort.length,
+                    abort.reason.as_str()
+                ),
+            );
+            self.events
+                .terminal_events
+                .push(TerminalEvent::EscapeStringAborted {
+                    kind: abort.kind,
+                    reason: abort.reason,
+                    length: abort.length,
+                });
+        }
+    }
+}
@@ -3,7 +3,12 @@
 //! This module defines the various events that can be emitted by the terminal
 //! to notify observers of state changes, user interactions, or protocol-specific actions.
 
+use crate::cursor::CursorStyle;
+use crate::terminal::background::BackgroundChange;
+use crate::terminal::cursor_trail::CursorMoveCause;
+use crate::terminal::escape_watchdog::{EscapeAbortReason, EscapeStringKind};
 use crate::terminal::file_transfer::TransferDirection;
+use crate::terminal::printer::PrintJobKind;
 use crate::terminal::progress::{ProgressBarAction, ProgressState};
 use crate::terminal::trigger::TriggerMatch;
 use crate::zone::ZoneType;
@@ -34,17 +39,156 @@ pub struct CwdChange {
     pub timestamp: u64,
 }
 
+/// Terminal mode reported by [`TerminalEvent::ModeChanged`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// DECCKM application cursor keys (DEC 1)
+    ApplicationCursor,
+    /// IRM insert mode (ANSI 4)
+    InsertMode,
+    /// DECOM origin mode (DEC 6)
+    OriginMode,
+    /// DECAWM auto-wrap (DEC 7)
+    AutoWrap,
+    /// LNM line feed / new line mode (ANSI 20)
+    LineFeedNewLineMode,
+    /// DECTCEM cursor visibility (DEC 25)
+    CursorVisible,
+    /// DECLRMM left/right margins (DEC 69)
+    LrMargins,
+    /// X10/normal mouse tracking (DEC 1000)
+    MouseNormal,
+    /// Button-event mouse tracking (DEC 1002)
+    MouseButtonEvent,
+    /// Any-event mouse tracking (DEC 1003)
+    MouseAnyEvent,
+    /// Focus in/out reporting (DEC 1004)
+    FocusTracking,
+    /// UTF-8 mouse encoding (DEC 1005)
+    MouseUtf8,
+    /// SGR mouse encoding (DEC 1006)
+    MouseSgr,
+    /// URXVT mouse encoding (DEC 1015)
+    MouseUrxvt,
+    /// Alternate screen buffer (DEC 1049)
+    AlternateScreen,
+    /// Bracketed paste (DEC 2004)
+    BracketedPaste,
+    /// Synchronized updates (DEC 2026)
+    SynchronizedUpdates,
+    /// Grapheme cluster processing (DEC 2027)
+    GraphemeClusters,
+    /// Kitty keyboard protocol flags
+    KeyboardProtocol,
+    /// xterm modifyOtherKeys
+    ModifyOtherKeys,
+    /// Color scheme update reports (DEC 2031)
+    ColorSchemeUpdates,
+    /// win32-input-mode (DEC 9001)
+    Win32Input,
+    /// Ambiguous width change reports (mintty DEC 7700)
+    AmbiguousWidthReports,
+}
+
+impl Mode {
+    /// Map a DEC private mode number (DECSET/DECRST) to a [`Mode`]
+    pub fn from_dec_private(param: u16) -> Option<Self> {
+        Some(match param {
+            1 => Mode::ApplicationCursor,
+            6 => Mode::OriginMode,
+            7 => Mode::AutoWrap,
+            25 => Mode::CursorVisible,
+            69 => Mode::LrMargins,
+            1000 => Mode::MouseNormal,
+            1002 => Mode::MouseButtonEvent,
+            1003 => Mode::MouseAnyEvent,
+            1004 => Mode::FocusTracking,
+            1005 => Mode::MouseUtf8,
+            1006 => Mode::MouseSgr,
+            1015 => Mode::MouseUrxvt,
+            1049 => Mode::AlternateScreen,
+            2004 => Mode::BracketedPaste,
+            2026 => Mode::SynchronizedUpdates,
+            2027 => Mode::GraphemeClusters,
+            2031 => Mode::ColorSchemeUpdates,
+            9001 => Mode::Win32Input,
+            7700 => Mode::AmbiguousWidthReports,
+            _ => return None,
+        })
+    }
+
+    /// Stable snake_case name (used by the Python and streaming layers)
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mode::ApplicationCursor => "application_cursor",
+            Mode::InsertMode => "insert_mode",
+            Mode::OriginMode => "origin_mode",
+            Mode::AutoWrap => "auto_wrap",
+            Mode::LineFeedNewLineMode => "line_feed_new_line_mode",
+            Mode::CursorVisible => "cursor_visible",
+            Mode::LrMargins => "lr_margins",
+            Mode::MouseNormal => "mouse_normal",
+            Mode::MouseButtonEvent => "mouse_button_event",
+            Mode::MouseAnyEvent => "mouse_any_event",
+            Mode::FocusTracking => "focus_tracking",
+            Mode::MouseUtf8 => "mouse_utf8",
+            Mode::MouseSgr => "mouse_sgr",
+            Mode::MouseUrxvt => "mouse_urxvt",
+            Mode::AlternateScreen => "alternate_screen",
+            Mode::BracketedPaste => "bracketed_paste",
+            Mode::SynchronizedUpdates => "synchronized_updates",
+            Mode::GraphemeClusters => "grapheme_clusters",
+            Mode::KeyboardProtocol => "keyboard_protocol",
+            Mode::ModifyOtherKeys => "modify_other_keys",
+            Mode::ColorSchemeUpdates => "color_scheme_updates",
+            Mode::Win32Input => "win32_input",
+            Mode::AmbiguousWidthReports => "ambiguous_width_reports",
+        }
+    }
+}
+
+impl std::fmt::Display for Mode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
 /// Terminal change event
 #[derive(Debug, Clone, PartialEq)]
 pub enum TerminalEvent {
     /// Bell event occurred
     BellRang(BellEvent),
     /// Terminal title changed
-    TitleChanged(String),
+    TitleChanged {
+        /// New title
+        title: String,
+        /// Title before the change
+        old_title: String,
+    },
+    /// Icon name changed (OSC 0 / OSC 1)
+    IconNameChanged {
+        /// New icon name
+        icon_name: String,
+        /// Icon name before the change
+        old_icon_name: String,
+    },
     /// Terminal was resized
     SizeChanged(usize, usize),
-    /// A terminal mode changed
-    ModeChanged(String, bool),
+    /// The application asked for a new window size (XTWINOPS `CSI 4 ; h ; w t`
+    /// or `CSI 8 ; rows ; cols t`, DECSLPP `CSI rows t`, DECSNLS
+    /// `CSI rows * |`). The terminal does not resize itself; the embedder
+    /// decides whether to honor the request. A missing or zero dimension in
+    /// the sequence is reported as the current size.
+    ResizeRequested {
+        /// Requested width in columns, or in pixels when `pixels` is set
+        cols: usize,
+        /// Requested height in rows, or in pixels when `pixels` is set
+        rows: usize,
+        /// The size is in pixels (`CSI 4 ; height ; width t`)
+        pixels: bool,
+    },
+    /// A terminal mode changed (mode, enabled)
+    ModeChanged(Mode, bool),
     /// Graphics added at row
     GraphicsAdded(usize),
     /// Hyperlink added with URL, position, and optional internal ID
@@ -58,7 +202,8 @@ pub enum TerminalEvent {
         /// Internal hyperlink ID
         id: Option<u32>,
     },
-    /// Dirty region (first_row, last_row)
+    /// Dirty region (first_row, last_row), emitted once per committed
+    /// synchronized update (DEC 2026)
     DirtyRegion(usize, usize),
     /// Current working directory changed (from OSC 7 or manual record)
     CwdChanged(CwdChange),
@@ -186,6 +331,21 @@ pub enum TerminalEvent {
         /// Total size of the transferred data in bytes
         size: usize,
     },
+    /// A downloaded file is complete and available: streamed to the download
+    /// sink, or buffered for [`Terminal::take_completed_transfer`] when no sink
+    /// is installed. Follows the transfer's `FileTransferCompleted`.
+    ///
+    /// [`Terminal::take_completed_transfer`]: crate::terminal::Terminal::take_completed_transfer
+    FileReceived {
+        /// Unique transfer identifier
+        id: u64,
+        /// File name sent by the application (empty if none)
+        name: String,
+        /// File size in bytes
+        size: usize,
+        /// The data went to the download sink rather than the transfer buffer
+        sink: bool,
+    },
     /// A file transfer failed
     FileTransferFailed {
         /// Unique transfer identifier
@@ -208,6 +368,103 @@ pub enum TerminalEvent {
         /// Whether the scrollback buffer was also cleared (ESC[3J vs ESC[2J).
         include_scrollback: bool,
     },
+    /// The cursor moved (only emitted while the cursor trail is enabled,
+    /// see `Terminal::set_cursor_trail_enabled`)
+    CursorMoved {
+        /// Position before the move as (col, row)
+        from: (usize, usize),
+        /// Position after the move as (col, row)
+        to: (usize, usize),
+        /// Whether the move came from printed text or an escape sequence
+        cause: CursorMoveCause,
+    },
+    /// The application changed the cursor style with DECSCUSR, or DECRC
+    /// restored a different one. Not emitted for
+    /// [`Terminal::set_cursor_style`](crate::terminal::Terminal::set_cursor_style).
+    CursorStyleChanged {
+        /// New cursor style
+        style: CursorStyle,
+    },
+    /// A trigger's [`TriggerAction::EmitEvent`](crate::terminal::TriggerAction::EmitEvent)
+    /// action ran
+    TriggerFired {
+        /// Event name from the action, with `$N` captures substituted
+        name: String,
+        /// The match that fired the trigger (includes capture groups)
+        trigger_match: TriggerMatch,
+    },
+    /// A color theme was applied with `Terminal::apply_theme`
+    ThemeChanged {
+        /// Theme name, if the theme has one
+        name: Option<String>,
+    },
+    /// A Media Copy print job ended: print screen (`CSI 0 i`), or printer
+    /// controller mode closed by `CSI 4 i`
+    PrintJobCompleted {
+        /// What produced the job
+        kind: PrintJobKind,
+        /// Total bytes in the job
+        size: usize,
+        /// Job data, at most [`MAX_PRINT_JOB_BYTES`]; empty when the job
+        /// went to the printer sink
+        ///
+        /// [`MAX_PRINT_JOB_BYTES`]: crate::terminal::printer::MAX_PRINT_JOB_BYTES
+        data: Vec<u8>,
+        /// The data went to the printer sink rather than this event
+        sink: bool,
+    },
+    /// Output contained a likely secret (redaction enabled with
+    /// `Terminal::set_redaction_enabled`). Positions are absolute
+    /// primary-buffer rows; the secret itself is not included.
+    SecretDetected {
+        /// Pattern that matched, e.g. `aws_access_key_id` or `private_key`
+        name: String,
+        /// Absolute row of the first cell
+        abs_row: usize,
+        /// Column of the first cell
+        col: usize,
+        /// Absolute row of the last cell
+        end_abs_row: usize,
+        /// Column after the last cell (exclusive)
+        end_col: usize,
+    },
+    /// The foreground process group of a PTY session changed, e.g. a command
+    /// started from the shell or returned to the prompt. Emitted by
+    /// [`PtySession`](crate::pty_session::PtySession), never by a bare
+    /// `Terminal`.
+    ForegroundProcessChanged {
+        /// PID of the new foreground process group leader
+        pid: u32,
+        /// Command name of the leader (empty if it could not be read)
+        name: String,
+    },
+    /// Output contained bytes that are not valid UTF-8 (resilience mode
+    /// enabled with `Terminal::set_utf8_resilience`); one event per run of
+    /// consecutive invalid sequences
+    Utf8DecodeError {
+        /// Offset of the first invalid byte in the output stream
+        offset: u64,
+        /// Number of invalid bytes in the run
+        length: usize,
+    },
+    /// The escape string watchdog (enabled with
+    /// `Terminal::set_escape_watchdog_config`) cancelled an OSC or DCS string
+    /// that was never terminated; the bytes after it are parsed normally
+    EscapeStringAborted {
+        /// Whether the string was an OSC or a DCS
+        kind: EscapeStringKind,
+        /// Whether it grew too long or stayed open too long
+        reason: EscapeAbortReason,
+        /// Body bytes seen before it was cancelled
+        length: usize,
+    },
+    /// The application changed the background image or opacity (allowed
+    /// with `Terminal::set_allow_remote_background`); read the new values
+    /// with `Terminal::background_image` / `background_opacity`
+    BackgroundChanged {
+        /// Whether the image or the opacity changed
+        change: BackgroundChange,
+    },
 }
 
 impl TerminalEvent {
@@ -215,8 +472,10 @@ impl TerminalEvent {
     pub fn kind(&self) -> TerminalEventKind {
         match self {
             TerminalEvent::BellRang(_) => TerminalEventKind::BellRang,
-            TerminalEvent::TitleChanged(_) => TerminalEventKind::TitleChanged,
+            TerminalEvent::TitleChanged { .. } => TerminalEventKind::TitleChanged,
+            TerminalEvent::IconNameChanged { .. } => TerminalEventKind::IconNameChanged,
             TerminalEvent::SizeChanged(_, _) => TerminalEventKind::SizeChanged,
+            TerminalEvent::ResizeRequested { .. } => TerminalEventKind::ResizeRequested,
             TerminalEvent::ModeChanged(_, _) => TerminalEventKind::ModeChanged,
             TerminalEvent::GraphicsAdded(_) => TerminalEventKind::GraphicsAdded,
             TerminalEvent::HyperlinkAdded { .. } => TerminalEventKind::HyperlinkAdded,
@@ -237,14 +496,27 @@ impl TerminalEvent {
             TerminalEvent::FileTransferProgress { .. } => TerminalEventKind::FileTransferProgress,
             TerminalEvent::FileTransferCompleted { .. } => TerminalEventKind::FileTransferCompleted,
             TerminalEvent::FileTransferFailed { .. } => TerminalEventKind::FileTransferFailed,
+            TerminalEvent::FileReceived { .. } => TerminalEventKind::FileReceived,
             TerminalEvent::UploadRequested { .. } => TerminalEventKind::UploadRequested,
             TerminalEvent::ScreenCleared { .. } => TerminalEventKind::ScreenCleared,
+            TerminalEvent::CursorMoved { .. } => TerminalEventKind::CursorMoved,
+            TerminalEvent::CursorStyleChanged { .. } => TerminalEventKind::CursorStyleChanged,
+            TerminalEvent::TriggerFired { .. } => TerminalEventKind::TriggerFired,
+            TerminalEvent::ThemeChanged { .. } => TerminalEventKind::ThemeChanged,
+            TerminalEvent::PrintJobCompleted { .. } => TerminalEventKind::PrintJobCompleted,
+            TerminalEvent::SecretDetected { .. } => TerminalEventKind::SecretDetected,
+            TerminalEvent::ForegroundProcessChanged { .. } => {
+                TerminalEventKind::ForegroundProcessChanged
+            }
+            TerminalEvent::Utf8DecodeError { .. } => TerminalEventKind::Utf8DecodeError,
+            TerminalEvent::EscapeStringAborted { .. } => TerminalEventKind::EscapeStringAborted,
+            TerminalEvent::BackgroundChanged { .. } => TerminalEventKind::BackgroundChanged,
         }
     }
 }
 
 /// Kind of terminal event for subscription filters
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TerminalEventKind {
     BellRang,
     TitleChanged,
@@ -271,6 +543,148 @@ pub enum TerminalEventKind {
     FileTransferFailed,
     UploadRequested,
     ScreenCleared,
+    CursorMoved,
+    TriggerFired,
+    ThemeChanged,
+    ResizeRequested,
+    IconNameChanged,
+    FileReceived,
+    CursorStyleChanged,
+    PrintJobCompleted,
+    SecretDetected,
+    ForegroundProcessChanged,
+    Utf8DecodeError,
+    EscapeStringAborted,
+    BackgroundChanged,
+}
+
+impl TerminalEventKind {
+    /// Every event kind, in declaration order
+    pub const ALL: [TerminalEventKind; 38] = [
+        TerminalEventKind::BellRang,
+        TerminalEventKind::TitleChanged,
+        TerminalEventKind::SizeChanged,
+        TerminalEventKind::ModeChanged,
+        TerminalEventKind::GraphicsAdded,
+        TerminalEventKind::HyperlinkAdded,
+        TerminalEventKind::DirtyRegion,
+        TerminalEventKind::CwdChanged,
+        TerminalEventKind::TriggerMatched,
+        TerminalEventKind::UserVarChanged,
+        TerminalEventKind::ProgressBarChanged,
+        TerminalEventKind::BadgeChanged,
+        TerminalEventKind::ShellIntegrationEvent,
+        TerminalEventKind::ZoneOpened,
+        TerminalEventKind::ZoneClosed,
+        TerminalEventKind::ZoneScrolledOut,
+        TerminalEventKind::EnvironmentChanged,
+        TerminalEventKind::RemoteHostTransition,
+        TerminalEventKind::SubShellDetected,
+        TerminalEventKind::FileTransferStarted,
+        TerminalEventKind::FileTransferProgress,
+        TerminalEventKind::FileTransferCompleted,
+        TerminalEventKind::FileTransferFailed,
+        TerminalEventKind::UploadRequested,
+        TerminalEventKind::ScreenCleared,
+        TerminalEventKind::CursorMoved,
+        TerminalEventKind::TriggerFired,
+        TerminalEventKind::ThemeChanged,
+        TerminalEventKind::ResizeRequested,
+        TerminalEventKind::IconNameChanged,
+        TerminalEventKind::FileReceived,
+        TerminalEventKind::CursorStyleChanged,
+        TerminalEventKind::PrintJobCompleted,
+        TerminalEventKind::SecretDetected,
+        TerminalEventKind::ForegroundProcessChanged,
+        TerminalEventKind::Utf8DecodeError,
+        TerminalEventKind::EscapeStringAborted,
+        TerminalEventKind::BackgroundChanged,
+    ];
+
+    fn bit(self) -> u64 {
+        1 << (self as u32)
+    }
+}
+
+/// Set of [`TerminalEventKind`]s a subscriber wants to receive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EventMask(u64);
+
+impl EventMask {
+    /// Mask matching no events
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Mask matching every event kind
+    pub fn all() -> Self {
+        Self::from_kinds(TerminalEventKind::ALL)
+    }
+
+    /// Build a mask from a list of kinds
+    pub fn from_kinds(kinds: impl IntoIterator<Item = TerminalEventKind>) -> Self {
+        kinds
+            .into_iter()
+            .fold(Self::empty(), |mask, k| mask.with(k))
+    }
+
+    /// Return a copy of this mask with `kind` added
+    pub fn with(self, kind: TerminalEventKind) -> Self {
+        Self(self.0 | kind.bit())
+    }
+
+    /// Return a copy of this mask with `kind` removed
+    pub fn without(self, kind: TerminalEventKind) -> Self {
+        Self(self.0 & !kind.bit())
+    }
+
+    /// Whether the mask includes `kind`
+    pub fn contains(&self, kind: TerminalEventKind) -> bool {
+        self.0 & kind.bit() != 0
+    }
+
+    /// Whether the mask includes the kind of `event`
+    pub fn matches(&self, event: &TerminalEvent) -> bool {
+        self.contains(event.kind())
+    }
+
+    /// Whether the mask matches nothing
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl From<TerminalEventKind> for EventMask {
+    fn from(kind: TerminalEventKind) -> Self {
+        Self::empty().with(kind)
+    }
+}
+
+impl std::ops::BitOr for EventMask {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitOr<TerminalEventKind> for EventMask {
+    type Output = Self;
+
+    fn bitor(self, rhs: TerminalEventKind) -> Self {
+        self.with(rhs)
+    }
+}
+
+/// Identifier returned by [`Terminal::subscribe`](crate::terminal::Terminal::subscribe)
+pub type SubscriptionId = u64;
+
+/// A subscriber's filter and private event queue
+#[derive(Debug)]
+pub(crate) struct EventSubscriber {
+    pub(crate) id: SubscriptionId,
+    pub(crate) mask: EventMask,
+    pub(crate) queue: std::collections::VecDeque<TerminalEvent>,
 }
 
 /// A drained shell integration event: (event_type, command, exit_code, timestamp, cursor_line).
@@ -301,7 +715,10 @@ mod tests {
 
     #[test]
     fn test_event_kind_title_changed() {
-        let event = TerminalEvent::TitleChanged("New Title".to_string());
+        let event = TerminalEvent::TitleChanged {
+            title: "New Title".to_string(),
+            old_title: String::new(),
+        };
         assert_eq!(event.kind(), TerminalEventKind::TitleChanged);
     }
 
@@ -313,7 +730,7 @@ mod tests {
 
     #[test]
     fn test_event_kind_mode_changed() {
-        let event = TerminalEvent::ModeChanged("DECCKM".to_string(), true);
+        let event = TerminalEvent::ModeChanged(Mode::ApplicationCursor, true);
         assert_eq!(event.kind(), TerminalEventKind::ModeChanged);
     }
 
@@ -555,8 +972,9 @@ mod tests {
 
         assert_eq!(title_events.len(), 1);
 
-        if let TerminalEvent::TitleChanged(title) = &title_events[0] {
+        if let TerminalEvent::TitleChanged { title, old_title } = &title_events[0] {
             assert_eq!(title, "New Title");
+            assert_eq!(old_title, "");
         } else {
             panic!("Expected TitleChanged event");
         }
@@ -621,4 +1039,104 @@ mod tests {
         assert_eq!(cwd_change.username, Some("user".to_string()));
         assert_eq!(cwd_change.timestamp, 1234567890);
     }
+
+    #[test]
+    fn test_event_mask_contains() {
+        let mask = EventMask::from(TerminalEventKind::BellRang) | TerminalEventKind::TitleChanged;
+        assert!(mask.contains(TerminalEventKind::BellRang));
+        assert!(mask.contains(TerminalEventKind::TitleChanged));
+        assert!(!mask.contains(TerminalEventKind::ModeChanged));
+        assert!(!mask
+            .without(TerminalEventKind::BellRang)
+            .contains(TerminalEventKind::BellRang));
+        assert!(EventMask::empty().is_empty());
+        assert!(TerminalEventKind::ALL
+            .iter()
+            .all(|k| EventMask::all().contains(*k)));
+    }
+
+    #[test]
+    fn test_subscribers_have_independent_queues() {
+        let mut term = Terminal::new(80, 24);
+        let bells = term.subscribe(TerminalEventKind::BellRang.into());
+        let titles = term.subscribe(TerminalEventKind::TitleChanged.into());
+
+        term.process(b"\x07\x1b]0;One\x07");
+        // Draining the shared queue must not starve subscribers
+        assert!(!term.poll_events().is_empty());
+        term.process(b"\x1b]0;Two\x07");
+
+        let bell_events = term.poll_subscription(bells);
+        assert_eq!(bell_events.len(), 1);
+        assert_eq!(bell_events[0].kind(), TerminalEventKind::BellRang);
+
+        let title_events = term.poll_subscription(titles);
+        assert_eq!(
+            title_events,
+            vec![
+                TerminalEvent::TitleChanged {
+                    title: "One".to_string(),
+                    old_title: String::new(),
+                },
+                TerminalEvent::TitleChanged {
+                    title: "Two".to_string(),
+                    old_title: "One".to_string(),
+                },
+            ]
+        );
+        assert!(term.poll_subscription(titles).is_empty());
+
+        assert!(term.unsubscribe(bells));
+        assert!(!term.unsubscribe(bells));
+        assert!(term.poll_subscription(bells).is_empty());
+        assert_eq!(term.subscription_count(), 1);
+    }
+
+    #[test]
+    fn test_subscription_skips_events_queued_before_subscribe() {
+        let mut term = Terminal::new(80, 24);
+        term.process(b"\x07");
+        let id = term.subscribe(EventMask::all());
+        assert!(term.poll_subscription(id).is_empty());
+        // The shared queue still has the bell
+        assert_eq!(term.poll_events().len(), 1);
+    }
+
+    #[test]
+    fn test_subscriptions_survive_ris() {
+        let mut term = Terminal::new(80, 24);
+        let id = term.subscribe(TerminalEventKind::BellRang.into());
+        term.process(b"\x07\x1bc");
+        term.process(b"\x07");
+        assert_eq!(term.poll_subscription(id).len(), 2);
+        assert_eq!(term.subscription_count(), 1);
+        // IDs are not reused after the reset
+        assert_ne!(term.subscribe(EventMask::all()), id);
+    }
+
+    #[test]
+    fn test_mode_changed_is_typed() {
+        let mut term = Terminal::new(80, 24);
+        let id = term.subscribe(TerminalEventKind::ModeChanged.into());
+        term.process(b"\x1b[?2004h\x1b[4h\x1b[?1049h");
+        let modes: Vec<_> = term
+            .poll_subscription(id)
+            .into_iter()
+            .filter_map(|e| match e {
+                TerminalEvent::ModeChanged(mode, enabled) => Some((mode, enabled)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            modes,
+            vec![
+                (Mode::BracketedPaste, true),
+                (Mode::InsertMode, true),
+                (Mode::AlternateScreen, true),
+            ]
+        );
+        assert_eq!(Mode::from_dec_private(1006), Some(Mode::MouseSgr));
+        assert_eq!(Mode::MouseSgr.name(), "mouse_sgr");
+        assert_eq!(Mode::from_dec_private(9999), None);
+    }
 }
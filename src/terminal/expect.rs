@@ -0,0 +1,195 @@
+//! Expect-style matching against new terminal output
+//!
+//! When enabled with [`Terminal::set_expect_buffer`], text printed by the
+//! parser is appended to a bounded buffer: printable characters, tabs, and a
+//! `\n` for each line feed. Escape sequences, carriage returns and other
+//! controls are not recorded. [`Terminal::expect_match`] searches the buffer
+//! for the earliest match among several patterns and consumes the buffer up
+//! to the end of that match, like pexpect.
+
+use std::fmt;
+
+use regex::Regex;
+
+use crate::terminal::Terminal;
+
+/// Default size limit of the expect buffer in bytes
+pub const DEFAULT_EXPECT_BUFFER_BYTES: usize = 64 * 1024;
+
+/// Unconsumed output text, bounded to `max_bytes` by dropping the oldest text
+#[derive(Debug, Clone)]
+pub(crate) struct ExpectBuffer {
+    text: String,
+    max_bytes: usize,
+}
+
+impl ExpectBuffer {
+    fn new(max_bytes: usize) -> Self {
+        Self {
+            text: String::new(),
+            max_bytes: max_bytes.max(1),
+        }
+    }
+
+    pub(crate) fn push_char(&mut self, c: char) {
+        self.text.push(c);
+        self.enforce_limit();
+    }
+
+    /// Append printable ASCII bytes
+    pub(crate) fn push_ascii(&mut self, run: &[u8]) {
+        // The print run only holds bytes in ' '..='~'
+        self.text.extend(run.iter().map(|&b| b as char));
+        self.enforce_limit();
+    }
+
+    fn enforce_limit(&mut self) {
+        if self.text.len() <= self.max_bytes {
+            return;
+        }
+        let mut cut = self.text.len() - self.max_bytes;
+        while !self.text.is_char_boundary(cut) {
+            cut += 1;
+        }
+        self.text.drain(..cut);
+    }
+}
+
+/// Alternative patterns for [`Terminal::expect_match`]
+#[derive(Debug, Clone)]
+pub struct ExpectPatterns(Vec<Regex>);
+
+impl ExpectPatterns {
+    /// Compile `patterns`; the index of each is reported in [`ExpectMatch::index`]
+    pub fn new<I, S>(patterns: I) -> Result<Self, ExpectError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let regexes = patterns
+            .into_iter()
+            .map(|p| Regex::new(p.as_ref()).map_err(|e| ExpectError::InvalidPattern(e.to_string())))
+            .collect::<Result<Vec<_>, _>>()?;
+        if regexes.is_empty() {
+            return Err(ExpectError::InvalidPattern(
+                "at least one pattern is required".to_string(),
+            ));
+        }
+        Ok(Self(regexes))
+    }
+
+    /// Number of alternatives
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether there are no alternatives (never true for a constructed value)
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Earliest match in `text`; ties go to the lowest pattern index
+    fn find(&self, text: &str) -> Option<ExpectMatch> {
+        let (index, captures) = self
+            .0
+            .iter()
+            .enumerate()
+            .filter_map(|(i, re)| re.captures(text).map(|c| (i, c)))
+            .min_by_key(|(i, c)| (c.get(0).map_or(usize::MAX, |m| m.start()), *i))?;
+        let whole = captures.get(0)?;
+        Some(ExpectMatch {
+            index,
+            before: text[..whole.start()].to_string(),
+            matched: whole.as_str().to_string(),
+            groups: captures
+                .iter()
+                .skip(1)
+                .map(|g| g.map(|m| m.as_str().to_string()))
+                .collect(),
+        })
+    }
+}
+
+/// Successful expect result
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpectMatch {
+    /// Index of the pattern that matched
+    pub index: usize,
+    /// Text received before the match
+    pub before: String,
+    /// Matched text
+    pub matched: String,
+    /// Capture groups 1..n; `None` for groups that did not participate
+    pub groups: Vec<Option<String>>,
+}
+
+/// Why an expect call did not produce a match
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExpectError {
+    /// A pattern failed to compile
+    InvalidPattern(String),
+    /// No match before the timeout; carries the unconsumed text
+    Timeout(String),
+    /// The process exited without a match; carries the unconsumed text
+    Eof(String),
+}
+
+impl fmt::Display for ExpectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExpectError::InvalidPattern(msg) => write!(f, "Invalid expect pattern: {}", msg),
+            ExpectError::Timeout(_) => write!(f, "Timed out waiting for a match"),
+            ExpectError::Eof(_) => write!(f, "Process exited before a match"),
+        }
+    }
+}
+
+impl std::error::Error for ExpectError {}
+
+impl Terminal {
+    /// Enable the expect buffer with a size limit in bytes, or disable and
+    /// discard it with `None`
+    ///
+    /// Only output processed while the buffer is enabled can be matched.
+    /// Changing the limit keeps the newest text that fits.
+    pub fn set_expect_buffer(&mut self, max_bytes: Option<usize>) {
+        match (max_bytes, self.expect_buffer.as_mut()) {
+            (None, _) => self.expect_buffer = None,
+            (Some(max), Some(buffer)) => {
+                buffer.max_bytes = max.max(1);
+                buffer.enforce_limit();
+            }
+            (Some(max), None) => self.expect_buffer = Some(ExpectBuffer::new(max)),
+        }
+    }
+
+    /// Whether the expect buffer is enabled
+    pub fn expect_buffer_enabled(&self) -> bool {
+        self.expect_buffer.is_some()
+    }
+
+    /// Unconsumed text in the expect buffer, if enabled
+    pub fn expect_buffer(&self) -> Option<&str> {
+        self.expect_buffer.as_ref().map(|b| b.text.as_str())
+    }
+
+    /// Discard unconsumed text in the expect buffer
+    pub fn clear_expect_buffer(&mut self) {
+        if let Some(buffer) = self.expect_buffer.as_mut() {
+            buffer.text.clear();
+        }
+    }
+
+    /// Match `patterns` against the expect buffer without waiting
+    ///
+    /// On a match the buffer is consumed through the end of the match.
+    /// Returns `None` if nothing matches yet or the buffer is disabled.
+    pub fn expect_match(&mut self, patterns: &ExpectPatterns) -> Option<ExpectMatch> {
+        let buffer = self.expect_buffer.as_mut()?;
+        let found = patterns.find(&buffer.text)?;
+        buffer
+            .text
+            .drain(..found.before.len() + found.matched.len());
+        Some(found)
+    }
+}
@@ -6,8 +6,22 @@
 //!
 //! The `FileTransferManager` maintains active transfers in progress and a
 //! bounded ring buffer of completed transfers for later retrieval.
+//!
+//! Downloaded bytes are buffered in [`FileTransfer::data`] unless the embedder
+//! installs a [`DownloadSink`] with [`Terminal::set_download_sink`], in which
+//! case each decoded chunk is streamed to the sink as it arrives and nothing
+//! is buffered. [`DirectoryDownloadSink`] is a ready-made sink that saves
+//! files into a directory. The size limit applies either way.
+//!
+//! Uploads start with the host sending `OSC 1337 ; RequestUpload=format=...`.
+//! The embedder answers with [`Terminal::send_upload_data`] or
+//! [`Terminal::cancel_upload`], or installs an [`UploadProvider`] with
+//! [`Terminal::set_upload_provider`] to answer automatically.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
 use crate::terminal::unix_millis;
 
@@ -54,8 +68,10 @@ pub struct FileTransfer {
     pub filename: String,
     /// Current transfer status
     pub status: TransferStatus,
-    /// Accumulated file data
+    /// Accumulated file data (empty when streamed to a download sink)
     pub data: Vec<u8>,
+    /// Number of bytes received so far
+    pub size: usize,
     /// Protocol-specific parameters (e.g., content-type, encoding)
     pub params: HashMap<String, String>,
     /// Timestamp when the transfer was started (unix millis)
@@ -64,12 +80,143 @@ pub struct FileTransfer {
     pub completed_at: Option<u64>,
 }
 
+/// Destination for downloaded file contents, installed with
+/// [`Terminal::set_download_sink`]
+///
+/// Calls for one transfer arrive in order: `begin`, any number of `write`s,
+/// then `finish` on success or `abort` on failure or cancellation. An error
+/// from `begin`, `write` or `finish` fails the transfer.
+pub trait DownloadSink: Send + Sync {
+    /// A download produced its first data (or completed empty)
+    fn begin(
+        &mut self,
+        id: TransferId,
+        filename: &str,
+        total_bytes: Option<usize>,
+    ) -> io::Result<()>;
+    /// Decoded bytes for a download
+    fn write(&mut self, id: TransferId, data: &[u8]) -> io::Result<()>;
+    /// The download completed; all its data has been written
+    fn finish(&mut self, id: TransferId) -> io::Result<()>;
+    /// The download failed or was cancelled; discard what was written
+    fn abort(&mut self, _id: TransferId) {}
+}
+
+/// A [`DownloadSink`] that saves each download as a file in a directory
+///
+/// The file name is the sender's name stripped of any directory part
+/// (`download-<id>` when missing). Data is written to a `.part` file that is
+/// renamed on completion and removed on failure, and an existing file is
+/// never overwritten: a ` (n)` suffix is added instead.
+#[derive(Debug)]
+pub struct DirectoryDownloadSink {
+    dir: PathBuf,
+    open: HashMap<TransferId, (File, PathBuf, PathBuf)>,
+}
+
+impl DirectoryDownloadSink {
+    /// Save downloads into `dir`, which must exist
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            open: HashMap::new(),
+        }
+    }
+
+    /// Directory downloads are saved into
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// First free destination path for `filename`
+    fn destination(&self, id: TransferId, filename: &str) -> PathBuf {
+        let name = Path::new(filename)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .filter(|n| !n.is_empty() && *n != "..")
+            .map_or_else(|| format!("download-{id}"), str::to_string);
+        let (stem, ext) = match name.rsplit_once('.') {
+            Some((stem, ext)) if !stem.is_empty() => (stem.to_string(), format!(".{ext}")),
+            _ => (name.clone(), String::new()),
+        };
+        let mut path = self.dir.join(&name);
+        let mut n = 1;
+        while path.exists() || path.with_file_name(format!("{name}.part")).exists() {
+            path = self.dir.join(format!("{stem} ({n}){ext}"));
+            n += 1;
+        }
+        path
+    }
+}
+
+impl DownloadSink for DirectoryDownloadSink {
+    fn begin(
+        &mut self,
+        id: TransferId,
+        filename: &str,
+        _total_bytes: Option<usize>,
+    ) -> io::Result<()> {
+        let path = self.destination(id, filename);
+        let mut part = path.clone().into_os_string();
+        part.push(".part");
+        let part = PathBuf::from(part);
+        let file = File::options().write(true).create_new(true).open(&part)?;
+        self.open.insert(id, (file, part, path));
+        Ok(())
+    }
+
+    fn write(&mut self, id: TransferId, data: &[u8]) -> io::Result<()> {
+        match self.open.get_mut(&id) {
+            Some((file, _, _)) => file.write_all(data),
+            None => Err(io::Error::new(io::ErrorKind::NotFound, "download not open")),
+        }
+    }
+
+    fn finish(&mut self, id: TransferId) -> io::Result<()> {
+        let (mut file, part, path) = self
+            .open
+            .remove(&id)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "download not open"))?;
+        file.flush()?;
+        drop(file);
+        std::fs::rename(&part, &path)
+    }
+
+    fn abort(&mut self, id: TransferId) {
+        if let Some((file, part, _)) = self.open.remove(&id) {
+            drop(file);
+            let _ = std::fs::remove_file(part);
+        }
+    }
+}
+
+/// Raw bytes per base64 line of an upload response (4096 encoded characters)
+pub const UPLOAD_CHUNK_BYTES: usize = 3 * 1024;
+
+/// Supplies file data when the host requests an upload, installed with
+/// [`Terminal::set_upload_provider`]
+///
+/// Any `FnMut(&str) -> Option<Vec<u8>>` closure is a provider.
+pub trait UploadProvider: Send + Sync {
+    /// The host requested an upload in `format` (e.g. `"tgz"`). Return the
+    /// data to send, or `None` to cancel the upload.
+    fn provide(&mut self, format: &str) -> Option<Vec<u8>>;
+}
+
+impl<F> UploadProvider for F
+where
+    F: FnMut(&str) -> Option<Vec<u8>> + Send + Sync,
+{
+    fn provide(&mut self, format: &str) -> Option<Vec<u8>> {
+        self(format)
+    }
+}
+
 /// Manages active and completed file transfers
 ///
 /// Active transfers are tracked by ID in a `HashMap`. Completed transfers
 /// are stored in a bounded ring buffer (oldest evicted when full) so that
 /// frontends can poll for finished results without unbounded memory growth.
-#[derive(Debug)]
 pub struct FileTransferManager {
     /// Currently in-progress transfers
     active_transfers: HashMap<TransferId, FileTransfer>,
@@ -81,6 +228,27 @@ pub struct FileTransferManager {
     next_id: TransferId,
     /// Maximum allowed transfer size in bytes (default 50 MB)
     max_transfer_size: usize,
+    /// Embedder destination for download data (None = buffer in `data`)
+    sink: Option<Box<dyn DownloadSink>>,
+    /// Downloads the sink has been told to `begin`
+    sink_open: HashSet<TransferId>,
+    /// Embedder callback answering upload requests (None = leave them to
+    /// the embedder's event handling)
+    upload_provider: Option<Box<dyn UploadProvider>>,
+}
+
+impl std::fmt::Debug for FileTransferManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileTransferManager")
+            .field("active_transfers", &self.active_transfers)
+            .field("completed_transfers", &self.completed_transfers)
+            .field("max_completed", &self.max_completed)
+            .field("next_id", &self.next_id)
+            .field("max_transfer_size", &self.max_transfer_size)
+            .field("has_sink", &self.sink.is_some())
+            .field("has_upload_provider", &self.upload_provider.is_some())
+            .finish()
+    }
 }
 
 /// Default maximum transfer size: 50 MB
@@ -97,6 +265,9 @@ impl Default for FileTransferManager {
             max_completed: DEFAULT_MAX_COMPLETED,
             next_id: 1,
             max_transfer_size: DEFAULT_MAX_TRANSFER_SIZE,
+            sink: None,
+            sink_open: HashSet::new(),
+            upload_provider: None,
         }
     }
 }
@@ -134,6 +305,7 @@ impl FileTransferManager {
             filename,
             status,
             data: Vec::new(),
+            size: 0,
             params,
             started_at: unix_millis(),
             completed_at: None,
@@ -153,39 +325,48 @@ impl FileTransferManager {
             .get_mut(&id)
             .ok_or_else(|| format!("transfer {id} not found"))?;
 
-        let new_size = transfer.data.len() + data.len();
+        let total_bytes = match &transfer.status {
+            TransferStatus::InProgress { total_bytes, .. } => *total_bytes,
+            TransferStatus::Pending => None,
+            _ => {
+                return Err(format!(
+                    "transfer {id} is not in a valid state for appending data"
+                ));
+            }
+        };
+
+        let new_size = transfer.size + data.len();
         if new_size > self.max_transfer_size {
-            // Fail the transfer on size limit violation
-            transfer.status = TransferStatus::Failed(format!(
+            let reason = format!(
                 "transfer exceeds maximum size of {} bytes",
                 self.max_transfer_size
-            ));
-            transfer.completed_at = Some(unix_millis());
-            // Move to completed
-            let transfer = self.active_transfers.remove(&id).unwrap();
-            self.push_completed(transfer);
+            );
+            self.finish_failed(id, TransferStatus::Failed(reason));
             return Err(format!(
                 "transfer {id} exceeds maximum size of {} bytes",
                 self.max_transfer_size
             ));
         }
 
-        transfer.data.extend_from_slice(data);
-
-        // Update status to InProgress with current byte count
-        let bytes_transferred = transfer.data.len();
-        let total_bytes = match &transfer.status {
-            TransferStatus::InProgress { total_bytes, .. } => *total_bytes,
-            TransferStatus::Pending => None,
-            _ => {
-                return Err(format!(
-                    "transfer {id} is not in a valid state for appending data"
-                ));
+        if self.sink.is_some() {
+            if let Err(e) = self.write_to_sink(id, data) {
+                let reason = format!("download sink error: {e}");
+                self.finish_failed(id, TransferStatus::Failed(reason.clone()));
+                return Err(reason);
             }
+        }
+
+        let Some(transfer) = self.active_transfers.get_mut(&id) else {
+            return Err(format!("transfer {id} not found"));
         };
+        if self.sink.is_none() {
+            transfer.data.extend_from_slice(data);
+        }
+        transfer.size = new_size;
 
+        // Update status to InProgress with current byte count
         transfer.status = TransferStatus::InProgress {
-            bytes_transferred,
+            bytes_transferred: new_size,
             total_bytes,
         };
 
@@ -197,10 +378,24 @@ impl FileTransferManager {
     /// Moves the transfer from active to the completed ring buffer.
     /// Returns `Err(String)` if the transfer is not found.
     pub fn complete_transfer(&mut self, id: TransferId) -> Result<(), String> {
-        let mut transfer = self
-            .active_transfers
-            .remove(&id)
-            .ok_or_else(|| format!("transfer {id} not found"))?;
+        if !self.active_transfers.contains_key(&id) {
+            return Err(format!("transfer {id} not found"));
+        }
+        if self.sink.is_some() {
+            // An empty download still creates its (empty) destination
+            let finished = self
+                .write_to_sink(id, &[])
+                .and_then(|()| self.call_sink(|sink| sink.finish(id)));
+            self.sink_open.remove(&id);
+            if let Err(e) = finished {
+                let reason = format!("download sink error: {e}");
+                self.finish_failed(id, TransferStatus::Failed(reason.clone()));
+                return Err(reason);
+            }
+        }
+        let Some(mut transfer) = self.active_transfers.remove(&id) else {
+            return Err(format!("transfer {id} not found"));
+        };
 
         transfer.status = TransferStatus::Completed;
         transfer.completed_at = Some(unix_millis());
@@ -213,14 +408,9 @@ impl FileTransferManager {
     /// Moves the transfer from active to the completed ring buffer.
     /// Returns `Err(String)` if the transfer is not found.
     pub fn fail_transfer(&mut self, id: TransferId, reason: String) -> Result<(), String> {
-        let mut transfer = self
-            .active_transfers
-            .remove(&id)
-            .ok_or_else(|| format!("transfer {id} not found"))?;
-
-        transfer.status = TransferStatus::Failed(reason);
-        transfer.completed_at = Some(unix_millis());
-        self.push_completed(transfer);
+        if !self.finish_failed(id, TransferStatus::Failed(reason)) {
+            return Err(format!("transfer {id} not found"));
+        }
         Ok(())
     }
 
@@ -229,14 +419,9 @@ impl FileTransferManager {
     /// Moves the transfer from active to the completed ring buffer.
     /// Returns `Err(String)` if the transfer is not found.
     pub fn cancel_transfer(&mut self, id: TransferId) -> Result<(), String> {
-        let mut transfer = self
-            .active_transfers
-            .remove(&id)
-            .ok_or_else(|| format!("transfer {id} not found"))?;
-
-        transfer.status = TransferStatus::Cancelled;
-        transfer.completed_at = Some(unix_millis());
-        self.push_completed(transfer);
+        if !self.finish_failed(id, TransferStatus::Cancelled) {
+            return Err(format!("transfer {id} not found"));
+        }
         Ok(())
     }
 
@@ -276,6 +461,114 @@ impl FileTransferManager {
         self.max_transfer_size = size;
     }
 
+    /// Set where download data goes (None = buffer it in the transfer).
+    ///
+    /// Downloads already streaming to the previous sink cannot continue
+    /// elsewhere, so they are aborted and failed; their IDs are returned.
+    pub fn set_sink(&mut self, sink: Option<Box<dyn DownloadSink>>) -> Vec<TransferId> {
+        let mut interrupted: Vec<TransferId> = self.sink_open.iter().copied().collect();
+        interrupted.sort_unstable();
+        for &id in &interrupted {
+            self.finish_failed(
+                id,
+                TransferStatus::Failed("download sink replaced".to_string()),
+            );
+        }
+        self.sink = sink;
+        interrupted
+    }
+
+    /// Take the download sink out of the manager, aborting downloads that
+    /// were streaming to it
+    pub fn take_sink(&mut self) -> Option<Box<dyn DownloadSink>> {
+        for id in std::mem::take(&mut self.sink_open) {
+            let _ = self.call_sink(|sink| {
+                sink.abort(id);
+                Ok(())
+            });
+        }
+        self.sink.take()
+    }
+
+    /// Check if a download sink is installed
+    pub fn has_sink(&self) -> bool {
+        self.sink.is_some()
+    }
+
+    /// Install or remove the upload provider
+    pub fn set_upload_provider(&mut self, provider: Option<Box<dyn UploadProvider>>) {
+        self.upload_provider = provider;
+    }
+
+    /// Remove and return the upload provider
+    pub fn take_upload_provider(&mut self) -> Option<Box<dyn UploadProvider>> {
+        self.upload_provider.take()
+    }
+
+    /// Check if an upload provider is installed
+    pub fn has_upload_provider(&self) -> bool {
+        self.upload_provider.is_some()
+    }
+
+    /// Ask the upload provider for data in `format`.
+    ///
+    /// Returns `None` without a provider, `Some(None)` if the provider
+    /// declined (or panicked) and `Some(Some(data))` otherwise.
+    pub fn provide_upload(&mut self, format: &str) -> Option<Option<Vec<u8>>> {
+        let provider = self.upload_provider.as_mut()?;
+        Some(
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| provider.provide(format)))
+                .unwrap_or(None),
+        )
+    }
+
+    /// Move an active transfer to the completed buffer with a failure
+    /// status, aborting it in the sink. Returns false if it was not active.
+    fn finish_failed(&mut self, id: TransferId, status: TransferStatus) -> bool {
+        let Some(mut transfer) = self.active_transfers.remove(&id) else {
+            return false;
+        };
+        if self.sink_open.remove(&id) {
+            let _ = self.call_sink(|sink| {
+                sink.abort(id);
+                Ok(())
+            });
+        }
+        transfer.status = status;
+        transfer.completed_at = Some(unix_millis());
+        self.push_completed(transfer);
+        true
+    }
+
+    /// Stream `data` for transfer `id` to the sink, opening it first if needed
+    fn write_to_sink(&mut self, id: TransferId, data: &[u8]) -> io::Result<()> {
+        if !self.sink_open.contains(&id) {
+            let (filename, total_bytes) = match self.active_transfers.get(&id) {
+                Some(transfer) => (transfer.filename.clone(), transfer_total(&transfer.status)),
+                None => return Ok(()),
+            };
+            self.call_sink(|sink| sink.begin(id, &filename, total_bytes))?;
+            self.sink_open.insert(id);
+        }
+        if data.is_empty() {
+            return Ok(());
+        }
+        self.call_sink(|sink| sink.write(id, data))
+    }
+
+    /// Run `f` on the sink. A panicking sink must not unwind through the
+    /// parser (ARC-007), so the panic becomes an error.
+    fn call_sink<F>(&mut self, f: F) -> io::Result<()>
+    where
+        F: FnOnce(&mut dyn DownloadSink) -> io::Result<()>,
+    {
+        let Some(sink) = self.sink.as_mut() else {
+            return Ok(());
+        };
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| f(sink.as_mut())))
+            .unwrap_or_else(|_| Err(io::Error::other("download sink panicked")))
+    }
+
     /// Push a transfer onto the completed ring buffer, evicting the oldest if full
     fn push_completed(&mut self, transfer: FileTransfer) {
         if self.completed_transfers.len() >= self.max_completed {
@@ -285,6 +578,14 @@ impl FileTransferManager {
     }
 }
 
+/// Total size announced for a transfer, if any
+fn transfer_total(status: &TransferStatus) -> Option<usize> {
+    match status {
+        TransferStatus::InProgress { total_bytes, .. } => *total_bytes,
+        _ => None,
+    }
+}
+
 use crate::terminal::Terminal;
 
 impl Terminal {
@@ -341,20 +642,105 @@ impl Terminal {
 
     /// Send data for an active upload
     ///
-    /// Writes the iTerm2-compatible upload response to the response buffer:
-    /// `ok\n` + base64(data) + `\n\n`
+    /// Queues the iTerm2-compatible upload response as bulk replies: `ok\n`,
+    /// the base64 data in lines of [`UPLOAD_CHUNK_BYTES`] raw bytes, then an
+    /// empty line. Upload data is exempt from the reply queue limit.
     pub fn send_upload_data(&mut self, data: &[u8]) {
         use base64::Engine;
-        let encoded = base64::engine::general_purpose::STANDARD.encode(data);
-        let response = format!("ok\n{}\n\n", encoded);
-        self.response_buffer.extend_from_slice(response.as_bytes());
+        self.push_pinned_reply(b"ok\n");
+        for chunk in data.chunks(UPLOAD_CHUNK_BYTES) {
+            let mut line = base64::engine::general_purpose::STANDARD.encode(chunk);
+            line.push('\n');
+            self.push_pinned_reply(line.as_bytes());
+        }
+        self.push_pinned_reply(b"\n");
     }
 
     /// Cancel the current upload
     ///
-    /// Writes Ctrl-C (0x03) to the response buffer to signal upload cancellation
+    /// Discards upload data that has not been drained yet, then queues
+    /// Ctrl-C (0x03) to tell the host the upload was cancelled
     pub fn cancel_upload(&mut self) {
-        self.response_buffer.push(0x03);
+        let discarded = self.reply_queue.discard_pinned();
+        if discarded > 0 {
+            crate::debug::log(
+                crate::debug::DebugLevel::Debug,
+                "OSC1337",
+                &format!("Discarded {} bytes of queued upload data", discarded),
+            );
+        }
+        self.push_pinned_reply(&[0x03]);
+    }
+
+    /// Answer upload requests with `provider` instead of leaving them to the
+    /// embedder's event handling.
+    ///
+    /// The provider runs while the terminal is processing output, so it must
+    /// not call back into the terminal. `UploadRequested` is still emitted.
+    /// The provider survives a hard reset (RIS).
+    ///
+    /// ```
+    /// use par_term_emu_core_rust::terminal::Terminal;
+    ///
+    /// let mut term = Terminal::new(80, 24);
+    /// term.set_upload_provider(|_format: &str| Some(b"hello".to_vec()));
+    /// term.process(b"\x1b]1337;RequestUpload=format=tgz\x07");
+    /// assert_eq!(term.drain_responses(), b"ok\naGVsbG8=\n\n");
+    /// ```
+    pub fn set_upload_provider(&mut self, provider: impl UploadProvider + 'static) {
+        self.graphics
+            .file_transfer_manager
+            .set_upload_provider(Some(Box::new(provider)));
+    }
+
+    /// Remove the upload provider; upload requests are only reported as events
+    pub fn clear_upload_provider(&mut self) {
+        self.graphics
+            .file_transfer_manager
+            .set_upload_provider(None);
+    }
+
+    /// Check if an upload provider is installed
+    pub fn has_upload_provider(&self) -> bool {
+        self.graphics.file_transfer_manager.has_upload_provider()
+    }
+
+    /// Stream downloaded files to `sink` instead of buffering them.
+    ///
+    /// Downloads already streaming to a previous sink are failed. The sink
+    /// belongs to the embedder and survives a hard reset (RIS).
+    ///
+    /// ```no_run
+    /// use par_term_emu_core_rust::terminal::file_transfer::DirectoryDownloadSink;
+    /// use par_term_emu_core_rust::terminal::Terminal;
+    ///
+    /// let mut term = Terminal::new(80, 24);
+    /// term.set_download_sink(DirectoryDownloadSink::new("/tmp/downloads"));
+    /// ```
+    pub fn set_download_sink(&mut self, sink: impl DownloadSink + 'static) {
+        self.replace_download_sink(Some(Box::new(sink)));
+    }
+
+    /// Remove the download sink; downloads are buffered again
+    pub fn clear_download_sink(&mut self) {
+        self.replace_download_sink(None);
+    }
+
+    /// Check if a download sink is installed
+    pub fn has_download_sink(&self) -> bool {
+        self.graphics.file_transfer_manager.has_sink()
+    }
+
+    fn replace_download_sink(&mut self, sink: Option<Box<dyn DownloadSink>>) {
+        let interrupted = self.graphics.file_transfer_manager.set_sink(sink);
+        for id in interrupted {
+            self.events
+                .terminal_events
+                .push(crate::terminal::TerminalEvent::FileTransferFailed {
+                    id,
+                    reason: "download sink replaced".to_string(),
+                });
+        }
     }
 
     /// Set the maximum allowed transfer size in bytes
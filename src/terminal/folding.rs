@@ -0,0 +1,200 @@
+//! Command output folding
+//!
+//! Lets frontends collapse a command's output (iTerm2-style "fold output")
+//! while the core owns the state. A fold is a flag on the command's Output
+//! [`Zone`](crate::zone::Zone), so it moves with the zone as content
+//! scrolls, follows row remapping when scrollback is trimmed, and goes away
+//! when the zone is evicted.
+//!
+//! Folds never change the grid. [`Terminal::export_text_folded`] and
+//! [`Terminal::get_semantic_snapshot_folded`] replace each folded range with
+//! a single summary line; the regular exports ignore folds.
+
+use crate::cell::Cell;
+use crate::terminal::{SemanticSnapshot, SnapshotScope, Terminal};
+use crate::zone::ZoneType;
+
+/// A folded command output range
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoldedRange {
+    /// ID of the folded Output zone
+    pub zone_id: usize,
+    /// First folded absolute row
+    pub abs_row_start: usize,
+    /// Last folded absolute row (inclusive)
+    pub abs_row_end: usize,
+    /// Command that produced the output, if known
+    pub command: Option<String>,
+}
+
+impl FoldedRange {
+    /// Number of folded rows
+    pub fn line_count(&self) -> usize {
+        self.abs_row_end - self.abs_row_start + 1
+    }
+
+    /// Line shown in place of the folded rows by folding-aware exports
+    pub fn summary(&self) -> String {
+        let count = self.line_count();
+        let lines = if count == 1 { "line" } else { "lines" };
+        match &self.command {
+            Some(command) => format!("⋯ {} {} folded ({})", count, lines, command),
+            None => format!("⋯ {} {} folded", count, lines),
+        }
+    }
+}
+
+impl Terminal {
+    /// ID of the Output zone for command `idx` (0 = most recent)
+    pub(crate) fn command_output_zone_id(&self, idx: usize) -> Option<usize> {
+        self.grid
+            .zones()
+            .iter()
+            .rev()
+            .filter(|z| z.zone_type == ZoneType::Output)
+            .nth(idx)
+            .map(|z| z.id)
+    }
+
+    /// Fold the output of command `idx`.
+    ///
+    /// Commands are counted by their Output zones, most recent first, so
+    /// `0` is the last command that produced output (possibly still running).
+    /// Returns `false` if there is no such command in the scrollback.
+    pub fn fold_command(&mut self, idx: usize) -> bool {
+        self.command_output_zone_id(idx)
+            .is_some_and(|id| self.set_zone_folded(id, true))
+    }
+
+    /// Unfold the output of command `idx` (see [`Terminal::fold_command`])
+    pub fn unfold_command(&mut self, idx: usize) -> bool {
+        self.command_output_zone_id(idx)
+            .is_some_and(|id| self.set_zone_folded(id, false))
+    }
+
+    /// Whether the output of command `idx` is folded
+    pub fn is_command_folded(&self, idx: usize) -> bool {
+        self.command_output_zone_id(idx)
+            .and_then(|id| self.grid.zones().iter().find(|z| z.id == id))
+            .is_some_and(|z| z.folded)
+    }
+
+    /// Fold or unfold an Output zone by ID.
+    ///
+    /// Returns `false` if no Output zone has that ID (it may have been
+    /// evicted from scrollback).
+    pub fn set_zone_folded(&mut self, zone_id: usize, folded: bool) -> bool {
+        match self
+            .grid
+            .zones_mut()
+            .iter_mut()
+            .find(|z| z.id == zone_id && z.zone_type == ZoneType::Output)
+        {
+            Some(zone) => {
+                zone.folded = folded;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Unfold every command output
+    pub fn unfold_all(&mut self) {
+        for zone in self.grid.zones_mut() {
+            zone.folded = false;
+        }
+    }
+
+    /// Folded ranges in row order.
+    ///
+    /// A command that is still running folds only the rows its zone covers
+    /// so far; the range grows when the zone closes.
+    pub fn folded_ranges(&self) -> Vec<FoldedRange> {
+        self.grid
+            .zones()
+            .iter()
+            .filter(|z| z.folded)
+            .map(|z| FoldedRange {
+                zone_id: z.id,
+                abs_row_start: z.abs_row_start,
+                abs_row_end: z.abs_row_end,
+                command: z.command.clone(),
+            })
+            .collect()
+    }
+
+    /// Cells and soft-wrap flag of an absolute row on the primary screen
+    pub(crate) fn primary_row(&self, abs_row: usize) -> Option<(&[Cell], bool)> {
+        let scrollback_len = self.grid.scrollback_len();
+        if abs_row < scrollback_len {
+            self.grid
+                .scrollback_line(abs_row)
+                .map(|cells| (cells, self.grid.is_scrollback_wrapped(abs_row)))
+        } else {
+            let row = abs_row - scrollback_len;
+            self.grid
+                .row(row)
+                .map(|cells| (cells, self.grid.is_line_wrapped(row)))
+        }
+    }
+
+    /// Plain text of absolute rows `start..end` on the primary screen, with
+    /// each folded range replaced by its summary line, masked like every
+    /// other export when redaction is enabled
+    fn folded_text(&self, start: usize, end: usize) -> String {
+        let folds = self.folded_ranges();
+        let mut lines = Vec::new();
+        let mut current = String::new();
+        let mut row = start;
+        while row < end {
+            if let Some(fold) = folds
+                .iter()
+                .find(|f| f.abs_row_start <= row && row <= f.abs_row_end)
+            {
+                if !current.is_empty() {
+                    lines.push(std::mem::take(&mut current));
+                }
+                lines.push(fold.summary());
+                row = fold.abs_row_end + 1;
+                continue;
+            }
+            match self.primary_row(row) {
+                Some((cells, wrapped)) => {
+                    current.push_str(&crate::terminal::cells_to_text(cells));
+                    if !wrapped {
+                        lines.push(current.trim_end().to_string());
+                        current.clear();
+                    }
+                }
+                None => break,
+            }
+            row += 1;
+        }
+        if !current.is_empty() {
+            lines.push(current.trim_end().to_string());
+        }
+        self.redact_export(lines.join("\n"), Some(start))
+    }
+
+    /// Export the primary screen buffer (scrollback + screen) as plain text
+    /// with folded command output replaced by summary lines
+    pub fn export_text_folded(&self) -> String {
+        let end = self.grid.scrollback_len() + self.grid.rows();
+        self.folded_text(0, end)
+    }
+
+    /// Capture a semantic snapshot with folded command output replaced by
+    /// summary lines in `visible_text` and `scrollback_text`
+    pub fn get_semantic_snapshot_folded(&self, scope: SnapshotScope) -> SemanticSnapshot {
+        let mut snapshot = self.get_semantic_snapshot(scope);
+        if self.alt_screen_active {
+            return snapshot;
+        }
+        let scrollback_len = self.grid.scrollback_len();
+        snapshot.visible_text = self.folded_text(scrollback_len, scrollback_len + self.grid.rows());
+        if snapshot.scrollback_text.is_some() {
+            snapshot.scrollback_text = Some(self.folded_text(0, scrollback_len));
+        }
+        snapshot
+    }
+}
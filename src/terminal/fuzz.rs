@@ -0,0 +1,85 @@
+//! Fuzzing entry point
+//!
+//! [`Terminal::process_fuzz`] is what the `cargo-fuzz` targets under `fuzz/`
+//! drive: it feeds arbitrary bytes through the normal parse path and then
+//! asserts the structural invariants every other API relies on, so a fuzzer
+//! reports corrupted state as a crash rather than only outright panics.
+
+use crate::grid::Grid;
+use crate::terminal::Terminal;
+
+impl Terminal {
+    /// Process untrusted bytes and check internal invariants afterwards.
+    ///
+    /// Behaves like [`Terminal::process`] followed by discarding any
+    /// generated responses, then panics if the cursor, margins, grids or
+    /// sequence buffers are out of bounds. Intended for fuzz targets and
+    /// robustness tests; the checks are too costly for normal output.
+    pub fn process_fuzz(&mut self, data: &[u8]) {
+        self.process(data);
+        self.drain_responses();
+        self.check_invariants();
+    }
+
+    /// Panic with a description of the first violated invariant
+    fn check_invariants(&self) {
+        let (cols, rows) = self.size();
+        assert!(cols > 0 && rows > 0, "empty terminal {cols}x{rows}");
+        check_grid("primary", &self.grid, cols, rows);
+        check_grid("alternate", &self.alt_grid, cols, rows);
+
+        assert!(
+            self.cursor.col <= cols && self.cursor.row < rows,
+            "cursor ({}, {}) outside {cols}x{rows}",
+            self.cursor.col,
+            self.cursor.row
+        );
+
+        let margins = &self.margins;
+        assert!(
+            margins.scroll_region_top <= margins.scroll_region_bottom
+                && margins.scroll_region_bottom < rows,
+            "scroll region {}..={} outside {rows} rows",
+            margins.scroll_region_top,
+            margins.scroll_region_bottom
+        );
+        assert!(
+            margins.left_margin <= margins.right_margin && margins.right_margin < cols,
+            "left/right margins {}..={} outside {cols} columns",
+            margins.left_margin,
+            margins.right_margin
+        );
+
+        let security = &self.security_state;
+        assert!(
+            self.dcs_state.dcs_buffer.len() <= security.max_dcs_data_length,
+            "DCS buffer holds {} bytes, limit {}",
+            self.dcs_state.dcs_buffer.len(),
+            security.max_dcs_data_length
+        );
+        assert!(
+            self.apc_buffer.len() <= security.max_apc_data_length.saturating_add(1),
+            "APC buffer holds {} bytes, limit {}",
+            self.apc_buffer.len(),
+            security.max_apc_data_length
+        );
+    }
+}
+
+fn check_grid(name: &str, grid: &Grid, cols: usize, rows: usize) {
+    assert_eq!(
+        (grid.cols(), grid.rows()),
+        (cols, rows),
+        "{name} grid size differs from terminal size"
+    );
+    for row in 0..rows {
+        let len = grid.row(row).map_or(0, |cells| cells.len());
+        assert_eq!(len, cols, "{name} grid row {row} has {len} cells");
+    }
+    assert!(
+        grid.scrollback_len() <= grid.max_scrollback(),
+        "{name} grid scrollback {} exceeds {}",
+        grid.scrollback_len(),
+        grid.max_scrollback()
+    );
+}
@@ -4,7 +4,7 @@
 //! Supports Sixel, iTerm2, and Kitty graphics protocols via unified GraphicsStore.
 
 use crate::debug;
-use crate::graphics::TerminalGraphic;
+use crate::graphics::{GraphicProtocol, TerminalGraphic, TextOverwriteMode};
 use crate::terminal::Terminal;
 
 impl Terminal {
@@ -13,6 +13,63 @@ impl Terminal {
         self.graphics.graphics_store.graphics_at_row(row)
     }
 
+    /// Get the graphic covering the screen cell at (col, row), if any.
+    ///
+    /// When placements overlap, the one drawn on top wins: the highest Kitty
+    /// z-index, then the most recently added.
+    pub fn graphic_at_cell(&self, col: usize, row: usize) -> Option<&TerminalGraphic> {
+        let (cell_w, cell_h) = self.graphics.cell_dimensions;
+        self.graphics
+            .graphics_store
+            .all_graphics()
+            .iter()
+            .enumerate()
+            .filter(|(_, g)| g.covers_cell(col, row, cell_w, cell_h))
+            .max_by_key(|(index, g)| (g.placement.z_index, *index))
+            .map(|(_, g)| g)
+    }
+
+    /// What printing text into cells covered by an image does to it
+    pub fn text_overwrite_mode(&self) -> TextOverwriteMode {
+        self.graphics.text_overwrite
+    }
+
+    /// Set what printing text into cells covered by an image does to it
+    pub fn set_text_overwrite_mode(&mut self, mode: TextOverwriteMode) {
+        self.graphics.text_overwrite = mode;
+    }
+
+    /// Apply the text overwrite mode to images under `count` cells just
+    /// printed from (col, row)
+    pub(super) fn overwrite_graphics_with_text(&mut self, col: usize, row: usize, count: usize) {
+        let mode = self.graphics.text_overwrite;
+        if mode == TextOverwriteMode::Overlay || self.graphics.graphics_store.graphics_count() == 0
+        {
+            return;
+        }
+        let (cell_w, cell_h) = self.graphics.cell_dimensions;
+        let affected = |g: &TerminalGraphic| match g.protocol {
+            GraphicProtocol::Sixel | GraphicProtocol::ITermInline => true,
+            GraphicProtocol::Kitty => mode == TextOverwriteMode::Erase,
+            GraphicProtocol::Regis => false,
+        };
+        let placements = self.graphics.graphics_store.all_graphics_mut();
+
+        if mode == TextOverwriteMode::Erase {
+            placements.retain(|g| {
+                !(affected(g) && (col..col + count).any(|c| g.covers_cell(c, row, cell_w, cell_h)))
+            });
+            return;
+        }
+        for g in placements.iter_mut().filter(|g| affected(g)) {
+            for c in col..col + count {
+                if g.covers_cell(c, row, cell_w, cell_h) {
+                    g.clear_cell(c, row, cell_w, cell_h);
+                }
+            }
+        }
+    }
+
     /// Get all graphics
     pub fn all_graphics(&self) -> &[TerminalGraphic] {
         self.graphics.graphics_store.all_graphics()
@@ -408,47 +465,8 @@ impl Terminal {
         if state.is_file_transfer {
             // File transfer path: complete the transfer
             if let Some(transfer_id) = state.transfer_id {
-                // Get the filename and size before completing
                 let filename = Self::decode_iterm_filename(&state.params);
-                let size = self
-                    .graphics
-                    .file_transfer_manager
-                    .get_transfer(transfer_id)
-                    .map(|t| t.data.len())
-                    .unwrap_or(0);
-
-                match self
-                    .graphics
-                    .file_transfer_manager
-                    .complete_transfer(transfer_id)
-                {
-                    Ok(()) => {
-                        self.events.terminal_events.push(
-                            crate::terminal::TerminalEvent::FileTransferCompleted {
-                                id: transfer_id,
-                                filename: if filename.is_empty() {
-                                    None
-                                } else {
-                                    Some(filename)
-                                },
-                                size,
-                            },
-                        );
-                    }
-                    Err(e) => {
-                        debug::log(
-                            debug::DebugLevel::Debug,
-                            "ITERM",
-                            &format!("File transfer complete failed: {}", e),
-                        );
-                        self.events.terminal_events.push(
-                            crate::terminal::TerminalEvent::FileTransferFailed {
-                                id: transfer_id,
-                                reason: e,
-                            },
-                        );
-                    }
-                }
+                self.complete_iterm_download(transfer_id, filename);
             }
         } else {
             // Inline image path: join chunks and delegate to handle_single_file_transfer
@@ -657,34 +675,54 @@ impl Terminal {
                 return;
             }
 
-            // Complete the transfer
-            let size = decoded.len();
-            match self
-                .graphics
-                .file_transfer_manager
-                .complete_transfer(transfer_id)
-            {
-                Ok(()) => {
-                    self.events.terminal_events.push(
-                        crate::terminal::TerminalEvent::FileTransferCompleted {
-                            id: transfer_id,
-                            filename: if filename.is_empty() {
-                                None
-                            } else {
-                                Some(filename)
-                            },
-                            size,
-                        },
-                    );
-                }
-                Err(e) => {
-                    self.events.terminal_events.push(
-                        crate::terminal::TerminalEvent::FileTransferFailed {
-                            id: transfer_id,
-                            reason: e,
+            self.complete_iterm_download(transfer_id, filename);
+        }
+    }
+
+    /// Complete an iTerm2 download, announcing it with `FileTransferCompleted`
+    /// and `FileReceived`, or `FileTransferFailed` if the sink refuses it
+    fn complete_iterm_download(&mut self, transfer_id: u64, filename: String) {
+        let manager = &mut self.graphics.file_transfer_manager;
+        let size = manager
+            .get_transfer(transfer_id)
+            .map(|t| t.size)
+            .unwrap_or(0);
+        let sink = manager.has_sink();
+
+        match manager.complete_transfer(transfer_id) {
+            Ok(()) => {
+                self.events.terminal_events.push(
+                    crate::terminal::TerminalEvent::FileTransferCompleted {
+                        id: transfer_id,
+                        filename: if filename.is_empty() {
+                            None
+                        } else {
+                            Some(filename.clone())
                         },
-                    );
-                }
+                        size,
+                    },
+                );
+                self.events
+                    .terminal_events
+                    .push(crate::terminal::TerminalEvent::FileReceived {
+                        id: transfer_id,
+                        name: filename,
+                        size,
+                        sink,
+                    });
+            }
+            Err(e) => {
+                debug::log(
+                    debug::DebugLevel::Debug,
+                    "ITERM",
+                    &format!("File transfer complete failed: {}", e),
+                );
+                self.events.terminal_events.push(
+                    crate::terminal::TerminalEvent::FileTransferFailed {
+                        id: transfer_id,
+                        reason: e,
+                    },
+                );
             }
         }
     }
@@ -1608,4 +1646,152 @@ mod tests {
         term.graphics_store_mut().notify_scrollback_advance(3);
         assert_eq!(term.graphics_store().scrollback_position(), 8);
     }
+
+    // ====================================================================
+    // Cell occupancy and text overwrite
+    // ====================================================================
+
+    /// 4x2-cell opaque image at (col, row) with 10x20 pixel cells
+    fn opaque_graphic(protocol: GraphicProtocol, col: usize, row: usize) -> TerminalGraphic {
+        TerminalGraphic::new(
+            next_graphic_id(),
+            protocol,
+            (col, row),
+            40,
+            40,
+            vec![255; 40 * 40 * 4],
+        )
+    }
+
+    fn term_with_cells() -> Terminal {
+        let mut term = create_test_terminal();
+        term.set_cell_dimensions(10, 20);
+        term
+    }
+
+    #[test]
+    fn test_graphic_at_cell_covers_cell_extent() {
+        let mut term = term_with_cells();
+        let graphic = opaque_graphic(GraphicProtocol::Sixel, 2, 3);
+        let id = graphic.id;
+        term.graphics_store_mut().add_graphic(graphic);
+
+        assert_eq!(term.graphic_at_cell(2, 3).map(|g| g.id), Some(id));
+        assert_eq!(term.graphic_at_cell(5, 4).map(|g| g.id), Some(id));
+        assert!(term.graphic_at_cell(6, 3).is_none());
+        assert!(term.graphic_at_cell(2, 5).is_none());
+        assert!(term.graphic_at_cell(1, 3).is_none());
+    }
+
+    #[test]
+    fn test_graphic_at_cell_prefers_kitty_size_and_z_index() {
+        let mut term = term_with_cells();
+        let mut below = opaque_graphic(GraphicProtocol::Kitty, 0, 0);
+        below.placement.columns = Some(10);
+        below.placement.z_index = 1;
+        let below_id = below.id;
+        let above = opaque_graphic(GraphicProtocol::Kitty, 0, 0);
+        let above_id = above.id;
+        term.graphics_store_mut().add_graphic(below);
+        term.graphics_store_mut().add_graphic(above);
+
+        // Higher z-index wins over the later placement
+        assert_eq!(term.graphic_at_cell(0, 0).map(|g| g.id), Some(below_id));
+        // c=10 stretches the first placement past the image's own width
+        assert_eq!(term.graphic_at_cell(9, 1).map(|g| g.id), Some(below_id));
+
+        term.graphics_store_mut().all_graphics_mut()[0]
+            .placement
+            .z_index = 0;
+        assert_eq!(term.graphic_at_cell(0, 0).map(|g| g.id), Some(above_id));
+    }
+
+    #[test]
+    fn test_text_overlay_leaves_graphics_alone() {
+        let mut term = term_with_cells();
+        term.graphics_store_mut()
+            .add_graphic(opaque_graphic(GraphicProtocol::Sixel, 0, 0));
+        term.process(b"abc");
+        assert_eq!(term.text_overwrite_mode(), TextOverwriteMode::Overlay);
+        assert_eq!(
+            term.all_graphics()[0].pixel_at(5, 5),
+            Some((255, 255, 255, 255))
+        );
+    }
+
+    #[test]
+    fn test_text_clip_clears_sixel_and_iterm_cells_only() {
+        let mut term = term_with_cells();
+        term.set_text_overwrite_mode(TextOverwriteMode::Clip);
+        term.graphics_store_mut()
+            .add_graphic(opaque_graphic(GraphicProtocol::Sixel, 0, 0));
+        term.graphics_store_mut()
+            .add_graphic(opaque_graphic(GraphicProtocol::Kitty, 0, 1));
+        term.graphics_store_mut()
+            .add_graphic(opaque_graphic(GraphicProtocol::ITermInline, 10, 1));
+
+        // One ASCII character into the Sixel's first cell
+        term.process(b"x");
+        let sixel = &term.all_graphics()[0];
+        assert_eq!(sixel.pixel_at(9, 19).map(|p| p.3), Some(0));
+        assert_eq!(sixel.pixel_at(10, 0).map(|p| p.3), Some(255));
+        assert_eq!(sixel.pixel_at(0, 20).map(|p| p.3), Some(255));
+
+        // A wide character into the iTerm2 image clears both cells; the Kitty
+        // placement on the same row is untouched
+        term.process("\x1b[2;11H\u{4e2d}".as_bytes());
+        let iterm = &term.all_graphics()[2];
+        assert_eq!(iterm.pixel_at(19, 0).map(|p| p.3), Some(0));
+        assert_eq!(iterm.pixel_at(20, 0).map(|p| p.3), Some(255));
+        term.process(b"\x1b[2;1Hkitty");
+        assert_eq!(
+            term.all_graphics()[1].pixel_at(0, 0).map(|p| p.3),
+            Some(255)
+        );
+        assert_eq!(term.graphics_count(), 3);
+    }
+
+    #[test]
+    fn test_partly_scrolled_off_graphic_hit_tests_visible_rows() {
+        let mut term = term_with_cells();
+        term.set_text_overwrite_mode(TextOverwriteMode::Clip);
+        // Two cell rows tall, the first already scrolled off the top
+        let mut graphic = opaque_graphic(GraphicProtocol::Sixel, 0, 0);
+        graphic.scroll_offset_rows = 1;
+        term.graphics_store_mut().add_graphic(graphic);
+
+        assert!(term.graphic_at_cell(0, 0).is_some());
+        assert!(term.graphic_at_cell(0, 1).is_none());
+
+        // Text on screen row 0 clears the image's second row of cells
+        term.process(b"x");
+        let sixel = &term.all_graphics()[0];
+        assert_eq!(sixel.pixel_at(0, 20).map(|p| p.3), Some(0));
+        assert_eq!(sixel.pixel_at(0, 19).map(|p| p.3), Some(255));
+    }
+
+    #[test]
+    fn test_text_erase_removes_touched_placements() {
+        let mut term = term_with_cells();
+        term.set_text_overwrite_mode(TextOverwriteMode::Erase);
+        term.graphics_store_mut()
+            .add_graphic(opaque_graphic(GraphicProtocol::Kitty, 3, 0));
+        term.graphics_store_mut()
+            .add_graphic(opaque_graphic(GraphicProtocol::Sixel, 20, 0));
+
+        term.process(b"ab");
+        assert_eq!(term.graphics_count(), 2);
+        term.process(b"cd");
+        assert_eq!(term.graphics_count(), 1);
+        assert_eq!(term.all_graphics()[0].protocol, GraphicProtocol::Sixel);
+        assert!(term.graphic_at_cell(3, 0).is_none());
+    }
+
+    #[test]
+    fn test_text_overwrite_mode_survives_reset() {
+        let mut term = term_with_cells();
+        term.set_text_overwrite_mode(TextOverwriteMode::Clip);
+        term.process(b"\x1bc");
+        assert_eq!(term.text_overwrite_mode(), TextOverwriteMode::Clip);
+    }
 }
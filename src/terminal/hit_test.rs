@@ -0,0 +1,151 @@
+//! Pixel-coordinate hit testing
+//!
+//! GUI frontends receive mouse positions in pixels and have to turn them into
+//! a cell, then look up what lives there: a hyperlink, a detected URL or
+//! path, an image, the selection, the semantic zone. [`Terminal::hit_test`]
+//! does that lookup in one call using the terminal's cell metrics
+//! ([`Terminal::cell_dimensions`]) and the frontend's scrollback viewport
+//! offset.
+
+use crate::cell::Cell;
+use crate::terminal::{DetectedItem, Terminal};
+use crate::unicode_width_config::str_width;
+use crate::zone::Zone;
+
+/// Everything under a pixel position, as returned by [`Terminal::hit_test`]
+#[derive(Debug, Clone)]
+pub struct HitTestResult {
+    /// Column of the hit cell; a hit on the right half of a wide character
+    /// reports the character's own (left) column
+    pub col: usize,
+    /// Row within the viewport (0 = top visible row)
+    pub row: usize,
+    /// Absolute line number (same numbering as semantic zones)
+    pub abs_row: usize,
+    /// Row on the active screen, or `None` when the hit is in scrollback
+    pub screen_row: Option<usize>,
+    /// The cell under the point
+    pub cell: Cell,
+    /// OSC 8 hyperlink ID on the cell
+    pub hyperlink_id: Option<u32>,
+    /// URL of the OSC 8 hyperlink on the cell
+    pub hyperlink_url: Option<String>,
+    /// Detected URL / path / hash / IP / email covering the cell (screen rows only)
+    pub detected_item: Option<DetectedItem>,
+    /// ID of the graphic covering the cell (screen rows only)
+    pub graphic_id: Option<u64>,
+    /// Whether the cell is inside any selection (screen rows only)
+    pub selected: bool,
+    /// Semantic zone containing the line (primary screen only)
+    pub zone: Option<Zone>,
+}
+
+/// Text, column and row of a detected item
+fn detected_span(item: &DetectedItem) -> (&str, usize, usize) {
+    match item {
+        DetectedItem::Url(text, col, row)
+        | DetectedItem::FilePath(text, col, row, _)
+        | DetectedItem::GitHash(text, col, row)
+        | DetectedItem::IpAddress(text, col, row)
+        | DetectedItem::Email(text, col, row) => (text, *col, *row),
+    }
+}
+
+/// Preference when several detected items overlap (a URL also contains a
+/// path, an email may contain an IP)
+fn detected_rank(item: &DetectedItem) -> u8 {
+    match item {
+        DetectedItem::Url(..) => 0,
+        DetectedItem::Email(..) => 1,
+        DetectedItem::FilePath(..) => 2,
+        DetectedItem::IpAddress(..) => 3,
+        DetectedItem::GitHash(..) => 4,
+    }
+}
+
+impl Terminal {
+    /// Hit-test a pixel position relative to the top-left of the text area.
+    ///
+    /// `scroll_offset` is how many lines the frontend's viewport is scrolled
+    /// back into scrollback (0 = live screen); it is clamped to the available
+    /// scrollback. Returns `None` for negative coordinates or positions past
+    /// the last row/column.
+    pub fn hit_test(&self, x_px: f64, y_px: f64, scroll_offset: usize) -> Option<HitTestResult> {
+        if !(x_px >= 0.0 && y_px >= 0.0) {
+            return None;
+        }
+        let (cell_w, cell_h) = self.cell_dimensions();
+        let mut col = (x_px / f64::from(cell_w)) as usize;
+        let row = (y_px / f64::from(cell_h)) as usize;
+        let (cols, rows) = self.size();
+        if col >= cols || row >= rows {
+            return None;
+        }
+
+        let grid = self.active_grid();
+        let scrollback_len = grid.scrollback_len();
+        let scroll_offset = scroll_offset.min(scrollback_len);
+        let screen_row = row.checked_sub(scroll_offset);
+        let line = match screen_row {
+            Some(r) => grid.row(r)?,
+            None => grid.scrollback_line(scrollback_len - scroll_offset + row)?,
+        };
+        // Same numbering OSC 133 uses when opening zones (scrollback_len + row)
+        let abs_row = scrollback_len - scroll_offset + row;
+
+        if line[col].flags.wide_char_spacer() && col > 0 {
+            col -= 1;
+        }
+        let cell = line[col].clone();
+
+        let hyperlink_id = cell.flags.hyperlink_id.map(|id| id.get());
+        let hyperlink_url = hyperlink_id.and_then(|id| self.get_hyperlink_url(id));
+
+        let mut detected_item = None;
+        let mut graphic_id = None;
+        let mut selected = false;
+        if let Some(screen_row) = screen_row {
+            detected_item = self
+                .detect_semantic_items()
+                .into_iter()
+                .filter(|item| {
+                    let (text, c, r) = detected_span(item);
+                    let width = str_width(text, &self.unicode_state.width_config);
+                    r == screen_row && col >= c && col < c + width
+                })
+                .min_by_key(detected_rank);
+
+            graphic_id = self
+                .graphics_at_row(screen_row)
+                .into_iter()
+                .find(|g| {
+                    let graphic_cell_w = g.cell_dimensions.map_or(cell_w, |(w, _)| w).max(1);
+                    let width_cols = g.width.div_ceil(graphic_cell_w as usize);
+                    col >= g.position.0 && col < g.position.0 + width_cols
+                })
+                .map(|g| g.id);
+
+            selected = self.is_cell_selected(col, screen_row);
+        }
+
+        let zone = if self.alt_screen_active {
+            None
+        } else {
+            self.grid.zone_at(abs_row).cloned()
+        };
+
+        Some(HitTestResult {
+            col,
+            row,
+            abs_row,
+            screen_row,
+            cell,
+            hyperlink_id,
+            hyperlink_url,
+            detected_item,
+            graphic_id,
+            selected,
+            zone,
+        })
+    }
+}
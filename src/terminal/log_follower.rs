@@ -0,0 +1,432 @@
+//! Line-oriented log follower mode for non-interactive streams.
+//!
+//! A terminal created with [`Terminal::new_log_follower`] does not maintain a
+//! cell grid. Output is parsed for text, SGR styling, line feeds and a few
+//! simple controls, and each finished line is appended to a compact store of
+//! text plus style spans. Tools that parse colored CI logs get an append path
+//! that costs about as much as copying the text, and scrollback can hold a
+//! million lines or more.
+//!
+//! There is no cursor addressing: CUP, erase and scroll sequences are
+//! ignored. A carriage return not followed by a line feed starts the line
+//! over, so progress bars leave only their final state. Lines longer than
+//! [`MAX_LOG_LINE_LENGTH`] bytes are split, with the rest marked as a
+//! continuation. Bells and OSC sequences (titles, hyperlinks, notifications)
+//! are handled as usual.
+//!
+//! Lines are numbered from 0 in arrival order. Numbers stay valid when old
+//! lines are evicted, so a [`LogSearch`] can resume from where it stopped and
+//! only scan lines that arrived since.
+
+use std::collections::VecDeque;
+
+use vte::{Params, Perform};
+
+use crate::cell::CellFlags;
+use crate::color::Color;
+use crate::terminal::Terminal;
+
+/// Lines kept by [`Terminal::new_log_follower`] unless told otherwise
+pub const DEFAULT_LOG_FOLLOWER_LINES: usize = 1_000_000;
+
+/// Longest line kept in one piece, in bytes
+pub const MAX_LOG_LINE_LENGTH: usize = 64 * 1024;
+
+/// Text attributes of a span of a log line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogStyle {
+    /// Foreground color
+    pub fg: Color,
+    /// Background color
+    pub bg: Color,
+    /// Bold, italic, underline, hyperlink, ...
+    pub flags: CellFlags,
+}
+
+/// Style change within a log line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogSpan {
+    /// Byte offset in the line where the style starts
+    pub start: usize,
+    /// Style up to the next span, `None` for the default style
+    pub style: Option<LogStyle>,
+}
+
+/// A finished log line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogLine {
+    text: Box<str>,
+    spans: Box<[LogSpan]>,
+    continuation: bool,
+}
+
+impl LogLine {
+    /// Line text, without the line feed
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Style changes in order; text before the first span has the default
+    /// style. Plain lines have none.
+    pub fn spans(&self) -> &[LogSpan] {
+        &self.spans
+    }
+
+    /// The line continues the previous one, which exceeded
+    /// [`MAX_LOG_LINE_LENGTH`]
+    pub fn is_continuation(&self) -> bool {
+        self.continuation
+    }
+
+    /// The text cut into runs of one style each
+    pub fn runs(&self) -> Vec<(&str, Option<LogStyle>)> {
+        let mut runs = Vec::with_capacity(self.spans.len() + 1);
+        let mut start = 0;
+        let mut style = None;
+        for span in self.spans.iter() {
+            if span.start > start {
+                runs.push((&self.text[start..span.start], style));
+            }
+            start = span.start;
+            style = span.style;
+        }
+        if start < self.text.len() {
+            runs.push((&self.text[start..], style));
+        }
+        runs
+    }
+}
+
+/// A match found by [`Terminal::search_log`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogMatch {
+    /// Line number
+    pub line: u64,
+    /// Byte offset of the match in the line text
+    pub start: usize,
+    /// Byte offset after the match
+    pub end: usize,
+}
+
+/// Incremental search over a log follower's lines
+///
+/// Each [`Terminal::search_log`] call scans only the lines finished since
+/// the previous call, so following a growing log costs time proportional to
+/// the new output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogSearch {
+    query: String,
+    case_sensitive: bool,
+    next_line: u64,
+}
+
+impl LogSearch {
+    /// Search for `query` from the oldest retained line. Case-insensitive
+    /// matching folds ASCII letters only.
+    pub fn new(query: impl Into<String>, case_sensitive: bool) -> Self {
+        Self {
+            query: query.into(),
+            case_sensitive,
+            next_line: 0,
+        }
+    }
+
+    /// Start at line `line` instead of the oldest retained line
+    pub fn from_line(mut self, line: u64) -> Self {
+        self.next_line = line;
+        self
+    }
+
+    /// The query
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Number of the first line the next call will scan
+    pub fn next_line(&self) -> u64 {
+        self.next_line
+    }
+
+    fn find_all(&self, text: &str, line: u64, matches: &mut Vec<LogMatch>) {
+        let needle = self.query.as_bytes();
+        let hay = text.as_bytes();
+        if needle.is_empty() || needle.len() > hay.len() {
+            return;
+        }
+        let mut start = 0;
+        while start + needle.len() <= hay.len() {
+            let found = if self.case_sensitive {
+                text[start..].find(&self.query).map(|at| start + at)
+            } else {
+                (start..=hay.len() - needle.len())
+                    .find(|&at| hay[at..at + needle.len()].eq_ignore_ascii_case(needle))
+            };
+            let Some(at) = found else {
+                break;
+            };
+            matches.push(LogMatch {
+                line,
+                start: at,
+                end: at + needle.len(),
+            });
+            start = at + needle.len();
+        }
+    }
+}
+
+/// Line store behind [`Terminal::new_log_follower`]
+#[derive(Debug, Clone)]
+pub(crate) struct LogFollower {
+    lines: VecDeque<LogLine>,
+    max_lines: usize,
+    /// Number of `lines[0]`
+    first_line: u64,
+    current: String,
+    current_spans: Vec<LogSpan>,
+    current_style: Option<LogStyle>,
+    current_continuation: bool,
+    /// A CR arrived; the next printed character starts the line over
+    rewrite_pending: bool,
+}
+
+impl LogFollower {
+    pub(crate) fn new(max_lines: usize) -> Self {
+        Self {
+            lines: VecDeque::new(),
+            max_lines: max_lines.max(1),
+            first_line: 0,
+            current: String::new(),
+            current_spans: Vec::new(),
+            current_style: None,
+            current_continuation: false,
+            rewrite_pending: false,
+        }
+    }
+
+    fn push_char(&mut self, c: char, style: Option<LogStyle>) {
+        if self.rewrite_pending {
+            self.rewrite_pending = false;
+            self.current.clear();
+            self.current_spans.clear();
+            self.current_style = None;
+        }
+        if self.current.len() + c.len_utf8() > MAX_LOG_LINE_LENGTH {
+            self.finish_line();
+            self.current_continuation = true;
+        }
+        if style != self.current_style {
+            let start = self.current.len();
+            match self.current_spans.last_mut() {
+                Some(last) if last.start == start => last.style = style,
+                _ => self.current_spans.push(LogSpan { start, style }),
+            }
+            self.current_style = style;
+        }
+        self.current.push(c);
+    }
+
+    fn tab(&mut self, style: Option<LogStyle>) {
+        let width = if self.rewrite_pending {
+            0
+        } else {
+            self.current.chars().count()
+        };
+        for _ in 0..8 - width % 8 {
+            self.push_char(' ', style);
+        }
+    }
+
+    fn backspace(&mut self) {
+        if self.rewrite_pending {
+            return;
+        }
+        self.current.pop();
+        let len = self.current.len();
+        self.current_spans.retain(|span| span.start < len);
+        self.current_style = self.current_spans.last().and_then(|span| span.style);
+    }
+
+    fn finish_line(&mut self) {
+        self.rewrite_pending = false;
+        self.lines.push_back(LogLine {
+            text: std::mem::take(&mut self.current).into_boxed_str(),
+            spans: std::mem::take(&mut self.current_spans).into_boxed_slice(),
+            continuation: std::mem::take(&mut self.current_continuation),
+        });
+        self.current_style = None;
+        if self.lines.len() > self.max_lines {
+            self.lines.pop_front();
+            self.first_line += 1;
+        }
+    }
+}
+
+/// Parser callbacks for log follower mode: text, SGR and simple controls go
+/// to the line store, everything that would address the grid is dropped
+struct LogPerformer<'a>(&'a mut Terminal);
+
+impl LogPerformer<'_> {
+    fn style(&self) -> Option<LogStyle> {
+        let term = &*self.0;
+        let style = LogStyle {
+            fg: term.fg,
+            bg: term.bg,
+            flags: term.flags,
+        };
+        let default = term.fg == term.theme.default_fg
+            && term.bg == term.theme.default_bg
+            && term.flags == CellFlags::default();
+        (!default).then_some(style)
+    }
+
+    fn log(&mut self) -> &mut LogFollower {
+        self.0
+            .log_follower
+            .as_mut()
+            .expect("log follower performer without a log")
+    }
+}
+
+impl Perform for LogPerformer<'_> {
+    fn print(&mut self, c: char) {
+        let style = self.style();
+        self.log().push_char(c, style);
+    }
+
+    fn execute(&mut self, byte: u8) {
+        match byte {
+            b'\n' | 0x0B | 0x0C => self.log().finish_line(),
+            b'\r' => self.log().rewrite_pending = true,
+            b'\t' => {
+                let style = self.style();
+                self.log().tab(style);
+            }
+            0x08 => self.log().backspace(),
+            // Bell and answerback
+            _ => Perform::execute(self.0, byte),
+        }
+    }
+
+    fn csi_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, action: char) {
+        if action == 'm' && intermediates.is_empty() && !ignore {
+            self.0.handle_csi_style(action, params, intermediates);
+        }
+    }
+
+    fn osc_dispatch(&mut self, params: &[&[u8]], bell_terminated: bool) {
+        self.0.osc_dispatch_impl(params, bell_terminated);
+    }
+}
+
+impl Terminal {
+    /// Create a terminal in log follower mode, keeping up to `max_lines`
+    /// lines (see [`DEFAULT_LOG_FOLLOWER_LINES`]).
+    ///
+    /// Output goes to a line store read with [`Terminal::log_line`],
+    /// [`Terminal::log_lines`] and [`Terminal::search_log`] instead of the
+    /// grid, which stays blank. `cols` only matters for the grid-based APIs.
+    ///
+    /// ```
+    /// use par_term_emu_core_rust::terminal::{LogSearch, Terminal};
+    ///
+    /// let mut term = Terminal::new_log_follower(120, 1_000_000);
+    /// term.process(b"\x1b[32mPASS\x1b[0m unit\r\n\x1b[31mFAIL\x1b[0m e2e\r\n");
+    /// assert_eq!(term.log_line(1).unwrap().text(), "FAIL e2e");
+    ///
+    /// let mut search = LogSearch::new("fail", false);
+    /// assert_eq!(term.search_log(&mut search)[0].line, 1);
+    /// term.process(b"FAIL lint\n");
+    /// assert_eq!(term.search_log(&mut search)[0].line, 2);
+    /// ```
+    pub fn new_log_follower(cols: usize, max_lines: usize) -> Self {
+        let mut term = Self::with_scrollback(cols.max(1), 1, 0);
+        term.log_follower = Some(Box::new(LogFollower::new(max_lines)));
+        term
+    }
+
+    /// Whether this terminal was created with [`Terminal::new_log_follower`]
+    pub fn is_log_follower(&self) -> bool {
+        self.log_follower.is_some()
+    }
+
+    /// Feed bytes to the parser with the log follower's callbacks
+    pub(crate) fn advance_log(&mut self, bytes: &[u8]) {
+        if bytes.is_empty() {
+            return;
+        }
+        let mut parser = std::mem::replace(&mut self.parser, vte::Parser::new());
+        parser.advance(&mut LogPerformer(self), bytes);
+        self.parser = parser;
+    }
+
+    /// Number of the oldest retained line (lines before it were evicted)
+    pub fn log_first_line(&self) -> u64 {
+        self.log_follower.as_ref().map_or(0, |log| log.first_line)
+    }
+
+    /// Number of lines finished so far, including evicted ones; the next
+    /// line will get this number
+    pub fn log_total_lines(&self) -> u64 {
+        self.log_follower
+            .as_ref()
+            .map_or(0, |log| log.first_line + log.lines.len() as u64)
+    }
+
+    /// Number of retained lines
+    pub fn log_line_count(&self) -> usize {
+        self.log_follower.as_ref().map_or(0, |log| log.lines.len())
+    }
+
+    /// Line number `line`, if it is retained
+    pub fn log_line(&self, line: u64) -> Option<&LogLine> {
+        let log = self.log_follower.as_ref()?;
+        let index = usize::try_from(line.checked_sub(log.first_line)?).ok()?;
+        log.lines.get(index)
+    }
+
+    /// Up to `count` retained lines starting at line number `start`
+    pub fn log_lines(&self, start: u64, count: usize) -> Vec<&LogLine> {
+        let Some(log) = self.log_follower.as_ref() else {
+            return Vec::new();
+        };
+        let skip = start.saturating_sub(log.first_line);
+        let skip = usize::try_from(skip).unwrap_or(usize::MAX);
+        log.lines.iter().skip(skip).take(count).collect()
+    }
+
+    /// Text of the line still being written (no line feed yet)
+    pub fn log_partial_line(&self) -> &str {
+        match self.log_follower.as_ref() {
+            Some(log) if !log.rewrite_pending => &log.current,
+            _ => "",
+        }
+    }
+
+    /// Matches in the lines finished since the previous call with `search`,
+    /// in order. Evicted lines are skipped.
+    pub fn search_log(&self, search: &mut LogSearch) -> Vec<LogMatch> {
+        let mut matches = Vec::new();
+        let Some(log) = self.log_follower.as_ref() else {
+            return matches;
+        };
+        let start = search.next_line.max(log.first_line);
+        for (line, entry) in (start..).zip(self.log_lines(start, usize::MAX)) {
+            search.find_all(entry.text(), line, &mut matches);
+        }
+        search.next_line = self.log_total_lines().max(search.next_line);
+        matches
+    }
+
+    /// Drop all retained lines and the partial line; numbering continues
+    pub fn clear_log(&mut self) {
+        if let Some(log) = self.log_follower.as_mut() {
+            log.first_line += log.lines.len() as u64;
+            log.lines.clear();
+            log.current.clear();
+            log.current_spans.clear();
+            log.current_style = None;
+            log.current_continuation = false;
+            log.rewrite_pending = false;
+        }
+    }
+}
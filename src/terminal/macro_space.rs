@@ -0,0 +1,249 @@
+//! VT420 macro space (DECDMAC / DECINVM)
+//!
+//! Applications store up to 64 byte sequences with DECDMAC
+//! (`DCS Pid ; Pdt ; Pen ! z D...D ST`) and replay them later with DECINVM
+//! (`CSI Pid * z`), as if the bytes had arrived from the host. All macros
+//! share [`MACRO_SPACE_BYTES`] of storage; a definition that does not fit is
+//! discarded. Macro space is reported with DECMSR (`CSI ? 62 n` →
+//! `CSI Pn * {`, free space in 16-byte blocks) and checksummed with
+//! DECCKSR (`CSI ? 63 ; Pid n` → `DCS Pid ! ~ xxxx ST`).
+
+use crate::terminal::Terminal;
+use vte::Params;
+
+/// Number of macro slots (ids 0-63)
+pub const MAX_MACROS: usize = 64;
+
+/// Total bytes shared by all stored macros
+pub const MACRO_SPACE_BYTES: usize = 4096;
+
+/// Deepest chain of macros invoking macros that is replayed
+const MAX_INVOKE_DEPTH: usize = 8;
+
+/// Bytes one top-level DECINVM may replay, nested invocations included.
+/// The depth limit alone still lets a macro holding several copies of its
+/// own DECINVM fan out exponentially.
+pub const MAX_REPLAY_BYTES: usize = 16 * MACRO_SPACE_BYTES;
+
+/// Stored macros and the invocation depth and replay budget guards
+#[derive(Debug, Default)]
+pub(crate) struct MacroSpace {
+    macros: Vec<Option<Vec<u8>>>,
+    used: usize,
+    /// Nesting depth of the DECINVM being replayed
+    depth: usize,
+    /// Bytes replayed so far by the current top-level DECINVM
+    replayed: usize,
+}
+
+impl MacroSpace {
+    fn get(&self, id: usize) -> Option<&[u8]> {
+        self.macros.get(id).and_then(|m| m.as_deref())
+    }
+
+    fn remove(&mut self, id: usize) {
+        if let Some(old) = self.macros.get_mut(id).and_then(Option::take) {
+            self.used -= old.len();
+        }
+    }
+
+    fn clear(&mut self) {
+        self.macros.clear();
+        self.used = 0;
+    }
+
+    /// Store `body` as macro `id`, replacing any previous definition;
+    /// returns `false` (leaving the slot empty) if it does not fit
+    fn define(&mut self, id: usize, body: Vec<u8>) -> bool {
+        self.remove(id);
+        if body.is_empty() {
+            return true;
+        }
+        if self.used + body.len() > MACRO_SPACE_BYTES {
+            return false;
+        }
+        if self.macros.len() <= id {
+            self.macros.resize(id + 1, None);
+        }
+        self.used += body.len();
+        self.macros[id] = Some(body);
+        true
+    }
+
+    /// 16-bit checksum over every stored macro (two's complement of the
+    /// byte sum, as in DECRQCRA)
+    fn checksum(&self) -> u16 {
+        let sum = self
+            .macros
+            .iter()
+            .flatten()
+            .flatten()
+            .fold(0u16, |sum, &b| sum.wrapping_add(u16::from(b)));
+        sum.wrapping_neg()
+    }
+}
+
+/// Decode a hex-encoded DECDMAC body, expanding `!Pn;D...D;` repeats;
+/// `None` if it is malformed
+fn decode_hex(data: &[u8]) -> Option<Vec<u8>> {
+    fn hex_pairs(data: &[u8], out: &mut Vec<u8>) -> Option<()> {
+        if !data.len().is_multiple_of(2) {
+            return None;
+        }
+        for pair in data.chunks(2) {
+            let text = std::str::from_utf8(pair).ok()?;
+            out.push(u8::from_str_radix(text, 16).ok()?);
+        }
+        Some(())
+    }
+
+    let mut out = Vec::new();
+    let mut rest = data;
+    while !rest.is_empty() {
+        if let Some(repeat) = rest.strip_prefix(b"!") {
+            let semi = repeat.iter().position(|&b| b == b';')?;
+            let count: usize = match &repeat[..semi] {
+                b"" => 1,
+                digits => std::str::from_utf8(digits).ok()?.parse().ok()?,
+            };
+            let body = &repeat[semi + 1..];
+            let end = body.iter().position(|&b| b == b';').unwrap_or(body.len());
+            let mut unit = Vec::new();
+            hex_pairs(&body[..end], &mut unit)?;
+            // Bound the expansion before allocating it
+            if unit.len().saturating_mul(count.max(1)) > MACRO_SPACE_BYTES {
+                return None;
+            }
+            for _ in 0..count.max(1) {
+                out.extend_from_slice(&unit);
+            }
+            rest = body.get(end + 1..).unwrap_or_default();
+        } else {
+            let end = rest.iter().position(|&b| b == b'!').unwrap_or(rest.len());
+            hex_pairs(&rest[..end], &mut out)?;
+            rest = &rest[end..];
+        }
+        if out.len() > MACRO_SPACE_BYTES {
+            return None;
+        }
+    }
+    Some(out)
+}
+
+impl Terminal {
+    /// Body of VT420 macro `id` (0-63) defined with DECDMAC, if any
+    pub fn dec_macro(&self, id: usize) -> Option<&[u8]> {
+        self.macro_space.get(id)
+    }
+
+    /// Bytes of macro space still free for DECDMAC definitions
+    pub fn dec_macro_space_available(&self) -> usize {
+        MACRO_SPACE_BYTES - self.macro_space.used
+    }
+
+    /// Delete every DECDMAC macro
+    pub fn clear_dec_macros(&mut self) {
+        self.macro_space.clear();
+    }
+
+    /// Remember the DECDMAC header at the start of `DCS Pid ; Pdt ; Pen ! z`
+    pub(in crate::terminal) fn begin_dec_macro(&mut self, params: &Params) {
+        let mut iter = params.iter().map(|p| p.first().copied().unwrap_or(0));
+        let id = iter.next().unwrap_or(0);
+        let delete_all = iter.next().unwrap_or(0) == 1;
+        let hex = iter.next().unwrap_or(0) == 1;
+        self.dcs_state.dec_macro = Some((id, delete_all, hex));
+    }
+
+    /// Store the macro collected in the DCS buffer (DECDMAC)
+    pub(in crate::terminal) fn finish_dec_macro(&mut self) {
+        let Some((id, delete_all, hex)) = self.dcs_state.dec_macro.take() else {
+            return;
+        };
+        let id = usize::from(id);
+        if id >= MAX_MACROS {
+            return;
+        }
+        if delete_all {
+            self.macro_space.clear();
+        }
+        let data = std::mem::take(&mut self.dcs_state.dcs_buffer);
+        let body = if hex { decode_hex(&data) } else { Some(data) };
+        let stored = body.is_some_and(|body| self.macro_space.define(id, body));
+        if !stored {
+            crate::debug::log(
+                crate::debug::DebugLevel::Debug,
+                "DECDMAC",
+                &format!("Macro {id} discarded (malformed or out of macro space)"),
+            );
+        }
+    }
+
+    /// DECINVM - replay macro `Pid` as if it came from the host
+    pub(in crate::terminal) fn invoke_dec_macro(&mut self, params: &Params) {
+        let id = params
+            .iter()
+            .next()
+            .and_then(|p| p.first())
+            .copied()
+            .unwrap_or(0);
+        if self.macro_space.depth >= MAX_INVOKE_DEPTH {
+            return;
+        }
+        let Some(body) = self.macro_space.get(usize::from(id)).map(<[u8]>::to_vec) else {
+            return;
+        };
+        if self.macro_space.depth == 0 {
+            self.macro_space.replayed = 0;
+        }
+        if self.macro_space.replayed + body.len() > MAX_REPLAY_BYTES {
+            return;
+        }
+        self.macro_space.replayed += body.len();
+        self.macro_space.depth += 1;
+        self.advance_parser(&body);
+        self.macro_space.depth -= 1;
+    }
+
+    /// DECMSR - report free macro space in 16-byte blocks: `CSI Pn * {`
+    pub(in crate::terminal) fn report_macro_space(&mut self) {
+        let blocks = self.dec_macro_space_available() / 16;
+        self.push_response(format!("\x1b[{blocks}*{{").as_bytes());
+    }
+
+    /// DECCKSR - report the macro space checksum: `DCS Pid ! ~ xxxx ST`
+    pub(in crate::terminal) fn report_macro_checksum(&mut self, request_id: u16) {
+        let checksum = self.macro_space.checksum();
+        self.push_response(format!("\x1bP{request_id}!~{checksum:04X}\x1b\\").as_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_bodies_decode_with_repeats() {
+        assert_eq!(decode_hex(b"414243"), Some(b"ABC".to_vec()));
+        assert_eq!(decode_hex(b"41!3;42;43"), Some(b"ABBBC".to_vec()));
+        assert_eq!(decode_hex(b"!2;4142"), Some(b"ABAB".to_vec()));
+        assert_eq!(decode_hex(b"!;41;"), Some(b"A".to_vec()));
+        assert_eq!(decode_hex(b"4"), None);
+        assert_eq!(decode_hex(b"zz"), None);
+        assert_eq!(decode_hex(b"!99999;41;"), None);
+    }
+
+    #[test]
+    fn definitions_share_the_space_limit() {
+        let mut space = MacroSpace::default();
+        assert!(space.define(0, vec![b'a'; MACRO_SPACE_BYTES - 10]));
+        assert!(!space.define(1, vec![b'b'; 11]));
+        assert_eq!(space.get(1), None);
+        assert!(space.define(1, vec![b'b'; 10]));
+
+        // Redefining releases the old body first
+        assert!(space.define(0, vec![b'c'; MACRO_SPACE_BYTES - 10]));
+        assert!(space.define(0, Vec::new()));
+        assert_eq!(space.used, 10);
+    }
+}
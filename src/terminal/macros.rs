@@ -39,6 +39,14 @@ impl Terminal {
         }
     }
 
+    /// Start playing a macro by name at `speed` times normal speed
+    /// (clamped to 0.1–10.0)
+    pub fn play_macro_with_speed(&mut self, name: &str, speed: f64) -> Result<(), String> {
+        self.play_macro(name)?;
+        self.set_macro_speed(speed);
+        Ok(())
+    }
+
     /// Stop macro playback
     pub fn stop_macro(&mut self) {
         self.macros.macro_playback = None;
@@ -109,6 +117,9 @@ impl Terminal {
                         let bytes = crate::macros::KeyParser::parse_key(&key);
                         return Some(bytes);
                     }
+                    crate::macros::MacroEvent::Input { data, .. } => {
+                        return Some(data.into_bytes());
+                    }
                     crate::macros::MacroEvent::Screenshot { label, .. } => {
                         self.macros
                             .macro_screenshot_triggers
@@ -128,6 +139,43 @@ impl Terminal {
         None
     }
 
+    // === Live Macro Recording ===
+
+    /// Start recording input into a macro named `name`
+    ///
+    /// Input passed to [`Terminal::record_macro_input`] (every PTY write when
+    /// the terminal is driven by a `PtySession`) is captured with the delays
+    /// between writes. Any recording already in progress is discarded.
+    pub fn record_macro(&mut self, name: &str) {
+        let (cols, rows) = self.size();
+        self.macros.macro_recorder =
+            Some(crate::macros::MacroRecorder::new(name).with_terminal_size(cols, rows));
+    }
+
+    /// Append input bytes to the macro being recorded, if any
+    pub fn record_macro_input(&mut self, data: &[u8]) {
+        if let Some(recorder) = self.macros.macro_recorder.as_mut() {
+            recorder.record_input(data);
+        }
+    }
+
+    /// Stop recording, store the macro in the library under its name and
+    /// return it
+    ///
+    /// Returns `None` if no recording was in progress.
+    pub fn stop_macro_recording(&mut self) -> Option<crate::macros::Macro> {
+        let recorded = self.macros.macro_recorder.take()?.finish();
+        self.macros
+            .macro_library
+            .insert(recorded.name.clone(), recorded.clone());
+        Some(recorded)
+    }
+
+    /// Check if a macro is being recorded
+    pub fn is_macro_recording(&self) -> bool {
+        self.macros.macro_recorder.is_some()
+    }
+
     /// Get and clear screenshot triggers
     pub fn get_macro_screenshot_triggers(&mut self) -> Vec<String> {
         std::mem::take(&mut self.macros.macro_screenshot_triggers)
@@ -268,6 +316,27 @@ mod tests {
         assert!(!term.is_macro_playing());
     }
 
+    #[test]
+    fn record_macro_captures_input_and_stores_in_library() {
+        let mut term = Terminal::new(80, 24);
+        term.record_macro_input(b"ignored"); // not recording yet
+        term.record_macro("demo");
+        assert!(term.is_macro_recording());
+        term.record_macro_input(b"echo hi\r");
+
+        let recorded = term.stop_macro_recording().unwrap();
+        assert!(!term.is_macro_recording());
+        assert!(term.stop_macro_recording().is_none());
+        assert_eq!(recorded.terminal_size, Some((80, 24)));
+        assert_eq!(recorded.events.len(), 1);
+        assert_eq!(term.get_macro("demo").unwrap().events, recorded.events);
+
+        // Replaying emits the recorded bytes verbatim.
+        term.play_macro_with_speed("demo", 4.0).unwrap();
+        assert_eq!(term.tick_macro(), Some(b"echo hi\r".to_vec()));
+        assert!(!term.is_macro_playing());
+    }
+
     #[test]
     fn tick_macro_with_no_playback_returns_none() {
         let mut term = Terminal::new(80, 24);
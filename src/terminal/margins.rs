@@ -0,0 +1,178 @@
+//! Left/right margins (DECLRMM / DECSLRM)
+//!
+//! With DECLRMM (`CSI ? 69 h`) set, DECSLRM (`CSI Pl ; Pr s`) confines
+//! wrapping, insertion, deletion and scrolling to the columns between the
+//! left and right margins, the way DECSTBM confines them to rows. A cursor
+//! outside the margins is left alone by the margin-bound operations: text
+//! wraps at the screen edge, ICH/DCH/IL/DL do nothing and LF moves down
+//! without scrolling.
+//!
+//! Scrolls spanning the full width keep going through the grid's row
+//! scrolling, which feeds scrollback and moves graphics; a narrower band
+//! only shifts the cells inside it.
+
+use crate::terminal::{MarginState, Terminal};
+
+impl MarginState {
+    /// Left and right margins in effect: the full width unless DECLRMM is set
+    pub(crate) fn columns(&self, cols: usize) -> (usize, usize) {
+        if self.use_lr_margins {
+            (self.left_margin, self.right_margin)
+        } else {
+            (0, cols.saturating_sub(1))
+        }
+    }
+
+    /// Whether `col` is between the left and right margins (always true
+    /// while DECLRMM is reset)
+    pub(crate) fn contains_col(&self, col: usize) -> bool {
+        !self.use_lr_margins || (self.left_margin..=self.right_margin).contains(&col)
+    }
+
+    /// Whether `row` is within the top and bottom margins
+    pub(crate) fn contains_row(&self, row: usize) -> bool {
+        (self.scroll_region_top..=self.scroll_region_bottom).contains(&row)
+    }
+}
+
+impl Terminal {
+    /// Whether DECLRMM is set
+    pub fn lr_margin_mode(&self) -> bool {
+        self.margins.use_lr_margins
+    }
+
+    /// Set or reset DECLRMM; resetting it also clears the margins
+    pub(crate) fn set_lr_margin_mode(&mut self, enabled: bool) {
+        self.margins.use_lr_margins = enabled;
+        if !enabled {
+            let (cols, _) = self.size();
+            self.margins.left_margin = 0;
+            self.margins.right_margin = cols.saturating_sub(1);
+        }
+    }
+
+    /// DECSLRM - set the left and right margins (1-based, 0 or missing for
+    /// the defaults) and home the cursor; invalid margins are ignored
+    pub(crate) fn set_lr_margins(&mut self, left: usize, right: usize) {
+        let (cols, _) = self.size();
+        let left = left.max(1) - 1;
+        let right = if right == 0 { cols } else { right.min(cols) } - 1;
+        if left >= right {
+            return;
+        }
+        self.margins.left_margin = left;
+        self.margins.right_margin = right;
+        self.home_cursor();
+    }
+
+    /// Move the cursor to the home position: the top-left corner of the
+    /// margins in origin mode, otherwise of the screen
+    pub(crate) fn home_cursor(&mut self) {
+        self.pending_wrap = false;
+        if self.modes.origin_mode {
+            let (cols, _) = self.size();
+            let (left, _) = self.margins.columns(cols);
+            self.cursor.goto(left, self.margins.scroll_region_top);
+        } else {
+            self.cursor.goto(0, 0);
+        }
+    }
+
+    /// Column CR, NEL and CNL return to: the left margin, or column 0 when
+    /// the cursor is already left of it
+    pub(crate) fn line_start_col(&self) -> usize {
+        if self.margins.use_lr_margins && self.cursor.col >= self.margins.left_margin {
+            self.margins.left_margin
+        } else {
+            0
+        }
+    }
+
+    /// Column after the last one text can be written in before wrapping:
+    /// the right margin for a cursor inside the margins, else the screen edge
+    pub(crate) fn wrap_edge(&self) -> usize {
+        let (cols, _) = self.size();
+        if self.margins.use_lr_margins && self.cursor.col <= self.margins.right_margin {
+            self.margins.right_margin + 1
+        } else {
+            cols
+        }
+    }
+
+    /// Scroll rows `top..=bottom` up by n between the left and right margins
+    pub(crate) fn scroll_up_in_margins(&mut self, n: usize, top: usize, bottom: usize) {
+        let (cols, _) = self.size();
+        match self.margins.columns(cols) {
+            (0, right) if right + 1 >= cols => {
+                self.active_grid_mut().scroll_region_up(n, top, bottom);
+                self.adjust_graphics_for_scroll_up(n, top, bottom);
+            }
+            (left, right) => {
+                self.active_grid_mut()
+                    .scroll_rect_up(n, top, bottom, left, right);
+            }
+        }
+    }
+
+    /// Scroll rows `top..=bottom` down by n between the left and right margins
+    pub(crate) fn scroll_down_in_margins(&mut self, n: usize, top: usize, bottom: usize) {
+        let (cols, _) = self.size();
+        match self.margins.columns(cols) {
+            (0, right) if right + 1 >= cols => {
+                self.active_grid_mut().scroll_region_down(n, top, bottom);
+                self.adjust_graphics_for_scroll_down(n, top, bottom);
+            }
+            (left, right) => {
+                self.active_grid_mut()
+                    .scroll_rect_down(n, top, bottom, left, right);
+            }
+        }
+    }
+
+    /// Scroll the area inside all four margins left (`left` true) or right
+    /// by n columns (SL / SR, DECFI / DECBI)
+    pub(crate) fn scroll_columns(&mut self, n: usize, left: bool) {
+        let (cols, _) = self.size();
+        let (left_margin, right_margin) = self.margins.columns(cols);
+        let (top, bottom) = (
+            self.margins.scroll_region_top,
+            self.margins.scroll_region_bottom,
+        );
+        let grid = self.active_grid_mut();
+        if left {
+            grid.delete_columns(left_margin, n, top, bottom, right_margin);
+        } else {
+            grid.insert_columns(left_margin, n, top, bottom, right_margin);
+        }
+    }
+
+    /// DECBI - move the cursor left, scrolling the margins right when it is
+    /// at the left margin
+    pub(crate) fn back_index(&mut self) {
+        self.pending_wrap = false;
+        let (cols, _) = self.size();
+        let (left, _) = self.margins.columns(cols);
+        if self.cursor.col == left {
+            if self.margins.contains_row(self.cursor.row) {
+                self.scroll_columns(1, false);
+            }
+        } else if self.cursor.col > 0 {
+            self.cursor.col -= 1;
+        }
+    }
+
+    /// DECFI - move the cursor right, scrolling the margins left when it is
+    /// at the right margin
+    pub(crate) fn forward_index(&mut self) {
+        self.pending_wrap = false;
+        let (cols, _) = self.size();
+        let (_, right) = self.margins.columns(cols);
+        if self.cursor.col == right {
+            if self.margins.contains_row(self.cursor.row) {
+                self.scroll_columns(1, true);
+            }
+        } else if self.cursor.col + 1 < cols {
+            self.cursor.col += 1;
+        }
+    }
+}
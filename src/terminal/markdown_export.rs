@@ -0,0 +1,156 @@
+//! Markdown export of a shell session
+//!
+//! [`Terminal::export_markdown`] turns the commands marked by shell
+//! integration into a document that can be pasted into an issue or a README:
+//! one section per command with the command line as its heading, the exit
+//! code and working directory noted below it, and the output in a fenced code
+//! block. Without shell integration there are no commands to split on, so the
+//! screen (and, for [`SnapshotScope::Full`], the scrollback) is exported as a
+//! single block.
+
+use crate::terminal::semantic_snapshot::SnapshotScope;
+use crate::terminal::text_export::ExportTextOptions;
+use crate::terminal::{ExportFormat, Terminal};
+use crate::zone::{Zone, ZoneType};
+
+/// Length of the longest run of backticks in `text`
+fn longest_backtick_run(text: &str) -> usize {
+    text.split(|c| c != '`').map(str::len).max().unwrap_or(0)
+}
+
+/// A backtick fence longer than any backtick run in `text`
+fn code_fence(text: &str) -> String {
+    "`".repeat(longest_backtick_run(text).max(2) + 1)
+}
+
+/// `text` as an inline code span that survives backticks inside it
+fn inline_code(text: &str) -> String {
+    let longest = longest_backtick_run(text);
+    let ticks = "`".repeat(longest + 1);
+    if longest == 0 {
+        format!("{ticks}{text}{ticks}")
+    } else {
+        format!("{ticks} {text} {ticks}")
+    }
+}
+
+/// Append `text` to `out` as a fenced code block
+fn push_code_block(out: &mut String, text: &str) {
+    let fence = code_fence(text);
+    out.push_str(&fence);
+    out.push_str("text\n");
+    out.push_str(text);
+    out.push('\n');
+    out.push_str(&fence);
+    out.push('\n');
+}
+
+impl Terminal {
+    /// Export the session as a Markdown document
+    ///
+    /// Each command (an OSC 133 Output zone) becomes a `##` section headed by
+    /// its command line, followed by its exit code and working directory
+    /// (when known) and its output in a fenced code block. The command
+    /// history fills in what the zone lacks, such as the working directory.
+    /// `scope` picks the commands: [`SnapshotScope::Visible`] those whose
+    /// output reaches the visible screen, [`SnapshotScope::Recent`] the last
+    /// N, and [`SnapshotScope::Full`] all of them. Secrets in the output, the
+    /// command lines, the working directories and the title are masked when
+    /// redaction is enabled.
+    ///
+    /// ```
+    /// use par_term_emu_core_rust::terminal::{SnapshotScope, Terminal};
+    ///
+    /// let mut term = Terminal::new(80, 24);
+    /// term.process(b"\x1b]133;A\x07$ \x1b]133;B\x07ls\r\n\x1b]133;C;ls\x07a.txt\r\n\x1b]133;D;0\x07");
+    /// let md = term.export_markdown(SnapshotScope::Full);
+    /// assert!(md.contains("## `ls`\n\nExit code: 0\n\n```text\na.txt\n```\n"));
+    /// ```
+    pub fn export_markdown(&self, scope: SnapshotScope) -> String {
+        // Metadata is masked like the output: a title or command line can
+        // carry a secret as easily as the output can
+        let mask = |text: &str| self.redact_export(text.to_string(), None);
+        let title = self.title();
+        let mut out = format!(
+            "# {}\n",
+            if title.is_empty() {
+                "Terminal session".to_string()
+            } else {
+                mask(title)
+            }
+        );
+
+        let screen_start = self.grid.scrollback_len();
+        let outputs: Vec<&Zone> = self
+            .grid
+            .zones()
+            .iter()
+            .filter(|z| z.zone_type == ZoneType::Output)
+            .collect();
+        let outputs = match scope {
+            SnapshotScope::Full => &outputs[..],
+            SnapshotScope::Recent(n) => &outputs[outputs.len().saturating_sub(n)..],
+            SnapshotScope::Visible => {
+                let first = outputs.partition_point(|z| z.abs_row_end < screen_start);
+                &outputs[first..]
+            }
+        };
+
+        if outputs.is_empty() {
+            let options = ExportTextOptions {
+                include_scrollback: scope == SnapshotScope::Full,
+                ..ExportTextOptions::default()
+            };
+            let text = self.export_text_opts(&options);
+            let text = text.trim_end_matches('\n');
+            if !text.is_empty() {
+                out.push('\n');
+                push_code_block(&mut out, text);
+            }
+            return out;
+        }
+
+        for zone in outputs {
+            let record = self
+                .get_command_history()
+                .iter()
+                .rev()
+                .find(|cmd| cmd.output_start_row == Some(zone.abs_row_start));
+
+            out.push('\n');
+            let command = zone
+                .command
+                .as_deref()
+                .or(record.map(|cmd| cmd.command.as_str()))
+                .map(str::trim)
+                .unwrap_or_default();
+            if command.is_empty() {
+                out.push_str("## (unknown command)\n");
+            } else {
+                out.push_str(&format!("## {}\n", inline_code(&mask(command))));
+            }
+
+            let mut notes = Vec::new();
+            if let Some(code) = zone.exit_code.or(record.and_then(|cmd| cmd.exit_code)) {
+                notes.push(format!("Exit code: {code}"));
+            }
+            if let Some(cwd) = record.and_then(|cmd| cmd.cwd.as_deref()) {
+                notes.push(format!("Directory: {}", inline_code(&mask(cwd))));
+            }
+            if !notes.is_empty() {
+                out.push('\n');
+                out.push_str(&notes.join(", "));
+                out.push('\n');
+            }
+
+            if let Some(text) = self.export_zone(zone.id, ExportFormat::Plain) {
+                let text = text.trim_end();
+                if !text.is_empty() {
+                    out.push('\n');
+                    push_code_block(&mut out, text);
+                }
+            }
+        }
+        out
+    }
+}
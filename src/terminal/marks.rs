@@ -0,0 +1,165 @@
+//! Navigable marks (jump list)
+//!
+//! A mark is recorded automatically at every shell prompt (OSC 133;A) on the
+//! primary screen, and embedders can add named marks of their own. Mark rows
+//! use the same absolute numbering as zones (`scrollback_len + row`), so a
+//! mark stays on its line as output scrolls; marks whose line is evicted from
+//! scrollback are dropped. [`Terminal::next_mark`] and [`Terminal::prev_mark`]
+//! implement "jump to next/previous prompt".
+
+use serde::{Deserialize, Serialize};
+
+use crate::terminal::Terminal;
+
+/// How a mark was created
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MarkKind {
+    /// Recorded at a shell prompt (OSC 133;A)
+    Prompt,
+    /// Added with [`Terminal::add_mark`]
+    User,
+}
+
+impl MarkKind {
+    /// Lowercase name used in bindings
+    pub fn name(self) -> &'static str {
+        match self {
+            MarkKind::Prompt => "prompt",
+            MarkKind::User => "user",
+        }
+    }
+}
+
+/// A navigable position in the primary screen's scrollback
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Mark {
+    /// Unique mark ID
+    pub id: usize,
+    /// Absolute row (`scrollback_len + screen row`), adjusted on eviction
+    pub abs_row: usize,
+    /// How the mark was created
+    pub kind: MarkKind,
+    /// Optional name
+    pub name: Option<String>,
+    /// When the mark was created (Unix millis)
+    pub timestamp: u64,
+}
+
+/// Marks ordered by row, and the next mark ID
+#[derive(Debug, Default)]
+pub(crate) struct MarkState {
+    marks: Vec<Mark>,
+    next_id: usize,
+}
+
+impl MarkState {
+    fn insert(&mut self, abs_row: usize, kind: MarkKind, name: Option<String>) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        // Keep row order; marks on the same row stay in creation order
+        let pos = self.marks.partition_point(|m| m.abs_row <= abs_row);
+        self.marks.insert(
+            pos,
+            Mark {
+                id,
+                abs_row,
+                kind,
+                name,
+                timestamp: crate::terminal::unix_millis(),
+            },
+        );
+        id
+    }
+}
+
+impl Terminal {
+    /// All marks, ordered by row
+    pub fn marks(&self) -> &[Mark] {
+        &self.mark_state.marks
+    }
+
+    /// Add a named mark at absolute row `abs_row` (`scrollback_len + screen
+    /// row`); returns the mark ID
+    pub fn add_mark(&mut self, abs_row: usize, name: Option<String>) -> usize {
+        self.mark_state.insert(abs_row, MarkKind::User, name)
+    }
+
+    /// First mark with the given name
+    pub fn mark_by_name(&self, name: &str) -> Option<&Mark> {
+        self.mark_state
+            .marks
+            .iter()
+            .find(|m| m.name.as_deref() == Some(name))
+    }
+
+    /// Remove a mark by ID
+    pub fn remove_mark(&mut self, id: usize) -> bool {
+        let before = self.mark_state.marks.len();
+        self.mark_state.marks.retain(|m| m.id != id);
+        self.mark_state.marks.len() != before
+    }
+
+    /// Remove every mark, including prompt marks
+    pub fn clear_marks(&mut self) {
+        self.mark_state.marks.clear();
+    }
+
+    /// Absolute row of the first mark below `from_row`
+    pub fn next_mark(&self, from_row: usize) -> Option<usize> {
+        let marks = &self.mark_state.marks;
+        marks
+            .get(marks.partition_point(|m| m.abs_row <= from_row))
+            .map(|m| m.abs_row)
+    }
+
+    /// Absolute row of the last mark above `from_row`
+    pub fn prev_mark(&self, from_row: usize) -> Option<usize> {
+        let marks = &self.mark_state.marks;
+        marks
+            .partition_point(|m| m.abs_row < from_row)
+            .checked_sub(1)
+            .map(|i| marks[i].abs_row)
+    }
+
+    /// Record a prompt mark; a prompt redrawn on the same row keeps its mark
+    pub(in crate::terminal) fn add_prompt_mark(&mut self, abs_row: usize) {
+        let exists = self
+            .mark_state
+            .marks
+            .iter()
+            .any(|m| m.kind == MarkKind::Prompt && m.abs_row == abs_row);
+        if !exists {
+            self.mark_state.insert(abs_row, MarkKind::Prompt, None);
+        }
+    }
+
+    /// Move marks up for rows that left the primary grid, dropping marks on
+    /// removed rows
+    pub(in crate::terminal) fn shift_marks(&mut self, removals: &[(usize, usize)]) {
+        for &(abs_row, count) in removals {
+            self.mark_state.marks.retain_mut(|mark| {
+                if mark.abs_row >= abs_row + count {
+                    mark.abs_row -= count;
+                } else if mark.abs_row >= abs_row {
+                    return false;
+                }
+                true
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_keeps_row_order() {
+        let mut state = MarkState::default();
+        state.insert(5, MarkKind::User, None);
+        state.insert(2, MarkKind::Prompt, None);
+        state.insert(5, MarkKind::Prompt, None);
+        let rows: Vec<_> = state.marks.iter().map(|m| (m.abs_row, m.id)).collect();
+        assert_eq!(rows, [(2, 1), (5, 0), (5, 2)]);
+    }
+}
@@ -3,7 +3,12 @@
 //! Provides types for tracking terminal performance, profiling escape sequences,
 //! and benchmarking various operations.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+
+use crate::time::Instant;
+
+/// Default number of samples kept per latency stage
+pub const DEFAULT_MAX_LATENCY_SAMPLES: usize = 1024;
 
 /// Performance metrics for tracking terminal rendering performance
 #[derive(Debug, Clone, Default)]
@@ -24,6 +29,160 @@ pub struct PerformanceMetrics {
     pub wrap_count: u64,
     /// Number of escape sequences processed
     pub escape_sequences: u64,
+    /// End-to-end input latency and frame pacing percentiles
+    pub latency: LatencyMetrics,
+}
+
+/// Percentile summary of a set of durations, in microseconds
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LatencyStats {
+    /// Number of samples the summary covers
+    pub count: usize,
+    /// Shortest sample
+    pub min_us: u64,
+    /// Median
+    pub p50_us: u64,
+    /// 90th percentile
+    pub p90_us: u64,
+    /// 99th percentile
+    pub p99_us: u64,
+    /// Longest sample
+    pub max_us: u64,
+    /// Arithmetic mean
+    pub mean_us: f64,
+}
+
+impl LatencyStats {
+    /// Summarize `samples` using nearest-rank percentiles
+    pub fn from_samples(samples: &[u64]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+        let rank = |p: usize| sorted[(sorted.len() * p).div_ceil(100).max(1) - 1];
+        Self {
+            count: sorted.len(),
+            min_us: sorted[0],
+            p50_us: rank(50),
+            p90_us: rank(90),
+            p99_us: rank(99),
+            max_us: sorted[sorted.len() - 1],
+            mean_us: sorted.iter().sum::<u64>() as f64 / sorted.len() as f64,
+        }
+    }
+}
+
+/// End-to-end latency statistics
+///
+/// Input is stamped when it is written to the PTY ([`Terminal::mark_input_written`]),
+/// output when it reaches [`Terminal::process`], and a render when the frontend
+/// drains the dirty region with [`Terminal::mark_clean`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LatencyMetrics {
+    /// From input written to the first output processed after it
+    pub input_to_output: LatencyStats,
+    /// From the first unrendered output to the dirty region being drained
+    pub output_to_render: LatencyStats,
+    /// From input written to the frame showing its echo being drained
+    pub input_to_render: LatencyStats,
+    /// Time between consecutive drains that had output to show
+    pub frame_interval: LatencyStats,
+}
+
+/// Timestamps and bounded sample windows behind [`LatencyMetrics`]
+#[derive(Debug, Clone)]
+pub(crate) struct LatencyTracker {
+    /// Oldest input not yet answered by output
+    pending_input: Option<Instant>,
+    /// Output processed but not yet drained, with the input that caused it
+    pending_render: Option<(Option<Instant>, Instant)>,
+    /// Last drain that had output to show
+    last_render: Option<Instant>,
+    input_to_output: VecDeque<u64>,
+    output_to_render: VecDeque<u64>,
+    input_to_render: VecDeque<u64>,
+    frame_interval: VecDeque<u64>,
+    pub(crate) max_samples: usize,
+}
+
+impl Default for LatencyTracker {
+    fn default() -> Self {
+        Self {
+            pending_input: None,
+            pending_render: None,
+            last_render: None,
+            input_to_output: VecDeque::new(),
+            output_to_render: VecDeque::new(),
+            input_to_render: VecDeque::new(),
+            frame_interval: VecDeque::new(),
+            max_samples: DEFAULT_MAX_LATENCY_SAMPLES,
+        }
+    }
+}
+
+impl LatencyTracker {
+    fn push(samples: &mut VecDeque<u64>, max: usize, from: Instant, to: Instant) {
+        if max == 0 {
+            return;
+        }
+        while samples.len() >= max {
+            samples.pop_front();
+        }
+        samples.push_back(to.saturating_duration_since(from).as_micros() as u64);
+    }
+
+    pub(crate) fn input(&mut self, now: Instant) {
+        self.pending_input.get_or_insert(now);
+    }
+
+    pub(crate) fn output(&mut self, now: Instant) {
+        let input = self.pending_input.take();
+        if let Some(sent) = input {
+            Self::push(&mut self.input_to_output, self.max_samples, sent, now);
+        }
+        match &mut self.pending_render {
+            None => self.pending_render = Some((input, now)),
+            // Keep the first output of the frame, but attach the input if
+            // this is the chunk that answered it
+            Some((pending_input, _)) => {
+                if pending_input.is_none() {
+                    *pending_input = input;
+                }
+            }
+        }
+    }
+
+    pub(crate) fn render(&mut self, now: Instant) {
+        let Some((input, output)) = self.pending_render.take() else {
+            return;
+        };
+        let max = self.max_samples;
+        Self::push(&mut self.output_to_render, max, output, now);
+        if let Some(sent) = input {
+            Self::push(&mut self.input_to_render, max, sent, now);
+        }
+        if let Some(last) = self.last_render.replace(now) {
+            Self::push(&mut self.frame_interval, max, last, now);
+        }
+    }
+
+    pub(crate) fn metrics(&self) -> LatencyMetrics {
+        let stats = |samples: &VecDeque<u64>| {
+            let (a, b) = samples.as_slices();
+            if b.is_empty() {
+                LatencyStats::from_samples(a)
+            } else {
+                LatencyStats::from_samples(&[a, b].concat())
+            }
+        };
+        LatencyMetrics {
+            input_to_output: stats(&self.input_to_output),
+            output_to_render: stats(&self.output_to_render),
+            input_to_render: stats(&self.input_to_render),
+            frame_interval: stats(&self.frame_interval),
+        }
+    }
 }
 
 /// Frame timing information
@@ -138,13 +297,42 @@ use crate::terminal::Terminal;
 impl Terminal {
     /// Get current performance metrics
     pub fn get_performance_metrics(&self) -> PerformanceMetrics {
-        self.profiling.metrics.clone()
+        let mut metrics = self.profiling.metrics.clone();
+        metrics.latency = self.profiling.latency.metrics();
+        metrics
     }
 
     /// Reset performance metrics
     pub fn reset_performance_metrics(&mut self) {
         self.profiling.metrics = PerformanceMetrics::default();
         self.profiling.frame_timings.clear();
+        let max_samples = self.profiling.latency.max_samples;
+        self.profiling.latency = LatencyTracker {
+            max_samples,
+            ..LatencyTracker::default()
+        };
+    }
+
+    /// Note that input bytes were just written to the PTY
+    ///
+    /// Starts the latency clock for the next output chunk passed to
+    /// [`Terminal::process`] and the next [`Terminal::mark_clean`] after it.
+    /// `PtySession::write` calls this itself; frontends
+    /// that own the PTY call it after their own writes.
+    pub fn mark_input_written(&mut self) {
+        self.profiling.latency.input(Instant::now());
+    }
+
+    /// Get end-to-end latency and frame pacing statistics
+    pub fn get_latency_metrics(&self) -> LatencyMetrics {
+        self.profiling.latency.metrics()
+    }
+
+    /// Set how many samples each latency stage keeps (default 1024)
+    ///
+    /// Percentiles are computed over this sliding window.
+    pub fn set_max_latency_samples(&mut self, max: usize) {
+        self.profiling.latency.max_samples = max;
     }
 
     /// Record a frame timing
@@ -287,12 +475,12 @@ impl Terminal {
 
     /// Run rendering benchmark
     pub fn benchmark_rendering(&mut self, iterations: u64) -> BenchmarkResult {
-        let start = std::time::Instant::now();
+        let start = crate::time::Instant::now();
         let mut min_time = u64::MAX;
         let mut max_time = 0u64;
 
         for _ in 0..iterations {
-            let iter_start = std::time::Instant::now();
+            let iter_start = crate::time::Instant::now();
 
             // Simulate rendering operation
             let grid = self.active_grid();
@@ -329,7 +517,7 @@ impl Terminal {
 
     /// Run parsing benchmark
     pub fn benchmark_parsing(&mut self, text: &str, iterations: u64) -> BenchmarkResult {
-        let start = std::time::Instant::now();
+        let start = crate::time::Instant::now();
         let bytes = text.as_bytes();
         for _ in 0..iterations {
             self.process(bytes);
@@ -356,7 +544,7 @@ impl Terminal {
 
     /// Run grid operations benchmark
     pub fn benchmark_grid_ops(&mut self, iterations: u64) -> BenchmarkResult {
-        let start = std::time::Instant::now();
+        let start = crate::time::Instant::now();
         for _ in 0..iterations {
             // Perform various grid ops
             self.grid.clear();
@@ -383,7 +571,7 @@ impl Terminal {
 
     /// Run full benchmark suite
     pub fn run_benchmark_suite(&mut self, suite_name: String) -> BenchmarkSuite {
-        let start = std::time::Instant::now();
+        let start = crate::time::Instant::now();
         let results = vec![self.benchmark_rendering(10), self.benchmark_grid_ops(100)];
 
         BenchmarkSuite {
@@ -410,9 +598,10 @@ impl Terminal {
             hyperlink_count: self.hyperlink_state.hyperlinks.len(),
             hyperlink_memory_bytes: 0, // Should be calculated
             color_stack_depth: self.theme.color_stack.len(),
+            sgr_stack_depth: self.saved_state.sgr_stack.len(),
             title_stack_depth: self.title_state.title_stack.len(),
             keyboard_stack_depth: self.keyboard_state.keyboard_stack.len(),
-            response_buffer_size: self.response_buffer.len(),
+            response_buffer_size: self.reply_queue.total_len(),
             dirty_row_count: self.dirty_rows.len(),
             pending_bell_events: self.events.bell_events.len(),
             pending_terminal_events: self.events.terminal_events.len(),
@@ -439,8 +628,10 @@ pub struct TerminalStats {
     pub hyperlink_count: usize,
     /// Estimated memory used by hyperlink storage (bytes)
     pub hyperlink_memory_bytes: usize,
-    /// Color stack depth
+    /// Color stack depth (XTPUSHCOLORS)
     pub color_stack_depth: usize,
+    /// Attribute stack depth (XTPUSHSGR)
+    pub sgr_stack_depth: usize,
     /// Title stack depth
     pub title_stack_depth: usize,
     /// Keyboard flag stack depth (active screen)
@@ -5,72 +5,158 @@
 
 // Submodules
 pub mod action;
+pub mod activity;
+pub mod annotation;
 mod apc_filter;
+pub mod appearance;
+mod autolink;
+pub mod background;
+pub mod bidi;
+mod block_export;
+mod charsets;
+pub mod checkpoint;
+pub mod clear_undo;
 pub mod clipboard;
 mod colors;
 pub mod compliance;
+pub mod cursor_trail;
+mod double_buffer;
+pub mod escape_watchdog;
 pub mod event;
+pub mod expect;
 pub mod file_transfer;
+pub mod folding;
+mod fuzz;
 mod graphics;
+pub mod hit_test;
 pub mod image;
+pub mod log_follower;
+pub mod macro_space;
 pub mod macros;
+mod margins;
+mod markdown_export;
+pub mod marks;
 pub mod metrics;
 pub mod multiplexing;
 pub mod notification;
+mod osc_guard;
+pub mod overlays;
+pub mod printer;
 pub mod progress;
+pub mod prompt_detection;
 pub mod recording;
+pub mod recording_player;
+pub mod redaction;
 pub mod replay;
 pub mod replay_snapshot;
+pub mod reply_queue;
 pub mod screen;
 pub mod search;
 pub mod semantic_snapshot;
+pub mod sequence_filter;
 mod sequences;
 pub mod shell_integration;
+pub mod snapshot_delta;
 pub mod snapshot_manager;
+pub mod state_handle;
+pub mod text_export;
+pub mod title;
+pub mod trace_export;
 pub mod trigger;
+pub mod unsupported;
+pub mod utf8_guard;
+pub mod viewport;
+mod vt52;
 mod write;
 
 // Re-export types as they're part of the public API
+pub use activity::{ActivityBucket, MAX_ACTIVITY_SECONDS};
+pub use appearance::Appearance;
+pub use background::{
+    BackgroundChange, BackgroundImage, BackgroundLayout, MAX_BACKGROUND_IMAGE_BYTES,
+};
+pub use bidi::{BidiDirection, BidiLine};
+pub use checkpoint::{CheckpointConfig, CheckpointInfo, CheckpointView, DEFAULT_MAX_CHECKPOINTS};
+pub use clear_undo::ClearedScreen;
 pub use clipboard::{
     ClipboardEntry, ClipboardHistoryEntry, ClipboardOperation, ClipboardSlot, ClipboardSyncEvent,
-    ClipboardTarget,
+    ClipboardTarget, RichText,
+};
+pub use compliance::{ComplianceCategory, ComplianceLevel, ComplianceReport, ComplianceTest};
+pub use cursor_trail::{CursorMotion, CursorMoveCause};
+pub use escape_watchdog::{EscapeAbortReason, EscapeStringKind, EscapeWatchdogConfig};
+pub use event::{
+    BellEvent, CwdChange, EventMask, Mode, ShellEvent, SubscriptionId, TerminalEvent,
+    TerminalEventKind,
 };
-pub use compliance::{ComplianceLevel, ComplianceReport, ComplianceTest};
-pub use event::{BellEvent, CwdChange, ShellEvent, TerminalEvent, TerminalEventKind};
+pub use expect::{ExpectError, ExpectMatch, ExpectPatterns, DEFAULT_EXPECT_BUFFER_BYTES};
 pub use file_transfer::{
-    FileTransfer, FileTransferManager, TransferDirection, TransferId, TransferStatus,
+    DirectoryDownloadSink, DownloadSink, FileTransfer, FileTransferManager, TransferDirection,
+    TransferId, TransferStatus, UploadProvider, UPLOAD_CHUNK_BYTES,
 };
+pub use folding::FoldedRange;
+pub use hit_test::HitTestResult;
 pub(crate) use image::ITermMultipartState;
 pub use image::{ImageFormat, ImagePlacement, ImageProtocol, InlineImage};
+pub use log_follower::{
+    LogLine, LogMatch, LogSearch, LogSpan, LogStyle, DEFAULT_LOG_FOLLOWER_LINES,
+    MAX_LOG_LINE_LENGTH,
+};
+pub use marks::{Mark, MarkKind};
 pub use metrics::{
     BenchmarkCategory, BenchmarkResult, BenchmarkSuite, EscapeSequenceProfile, FrameTiming,
-    PerformanceMetrics, ProfileCategory, ProfilingData, TerminalStats,
+    LatencyMetrics, LatencyStats, PerformanceMetrics, ProfileCategory, ProfilingData,
+    TerminalStats, DEFAULT_MAX_LATENCY_SAMPLES,
 };
 pub use multiplexing::{LayoutDirection, PaneState, SessionState, WindowLayout};
 pub use notification::{
-    Notification, NotificationAlert, NotificationConfig, NotificationEvent, NotificationTrigger,
+    Notification, NotificationAlert, NotificationConfig, NotificationEvent, NotificationSchedule,
+    NotificationTrigger, QuietWindow, ScheduleOverride, SuppressionReason,
 };
+pub use overlays::ExportOverlays;
 pub use progress::{
     NamedProgressBar, ProgressBar, ProgressBarAction, ProgressBarCommand, ProgressState,
 };
+pub use prompt_detection::PromptDetectionConfig;
 pub use recording::{
-    RecordingEvent, RecordingEventType, RecordingExportFormat, RecordingFormat, RecordingSession,
+    RecordingEvent, RecordingEventType, RecordingExportFormat, RecordingFormat,
+    RecordingImportError, RecordingSession,
 };
+pub use recording_player::RecordingPlayerConfig;
+pub use reply_queue::{ReplyOverflowPolicy, ReplyPriority, DEFAULT_MAX_REPLY_QUEUE_BYTES};
 pub use screen::{
     hsl_to_rgb, hsv_to_rgb, rgb_to_hsl, rgb_to_hsv, AnimationHint, ColorHSL, ColorHSV,
-    ColorPalette, DamageRegion, JoinedLines, ReflowStats, RenderingHint, Selection, SelectionMode,
-    ThemeMode, UpdatePriority, ZLayer,
+    ColorPalette, DamageRegion, JoinMode, JoinedLines, ReflowStats, RenderingHint, Selection,
+    SelectionMode, ThemeMode, UpdatePriority, ZLayer,
+};
+pub use search::{
+    DetectedItem, HyperlinkInfo, RegexMatch, RegexSearchOptions, SearchMatch, TextSearchOptions,
 };
-pub use search::{DetectedItem, HyperlinkInfo, RegexMatch, RegexSearchOptions, SearchMatch};
 pub use semantic_snapshot::{
-    diff_screen_lines, Bookmark, CommandInfo, CwdChangeInfo, DiffChangeType, ExportFormat,
-    LineDiff, ScrollbackStats, SemanticSnapshot, SnapshotDiff, SnapshotScope, ZoneInfo,
+    diff_screen_lines, AnnotationInfo, Bookmark, CommandInfo, CwdChangeInfo, DiffChangeType,
+    ExportFormat, LineDiff, ScrollbackStats, SemanticSnapshot, SnapshotDiff, SnapshotScope,
+    ZoneInfo,
 };
-pub use shell_integration::{CommandExecution, CommandOutput, ShellIntegrationStats};
+pub use sequence_filter::{DcsSequence, OscSequence, Sequence, SequenceAction, SequenceFilter};
+pub use shell_integration::{
+    CommandExecution, CommandOutput, EnvironmentChange, HostContext, ShellEnvironment,
+    ShellIntegrationStats,
+};
+pub use snapshot_delta::{DeltaError, SnapshotDelta};
+pub use state_handle::{StateModes, TerminalStateHandle};
+pub use text_export::{ExportTextOptions, LineEnding};
+pub use title::{TitleHistoryEntry, TitleKind, MAX_TITLE_HISTORY};
+pub use trace_export::TraceExport;
 pub use trigger::{
     ActionResult, Trigger, TriggerAction, TriggerHighlight, TriggerId, TriggerMatch,
     TriggerRegistry, TriggerSplitCommand, TriggerSplitDirection, TriggerSplitTarget,
 };
+pub use unsupported::{
+    UnsupportedKind, UnsupportedSequence, MAX_UNSUPPORTED_RAW_LENGTH, MAX_UNSUPPORTED_SEQUENCES,
+};
+pub use utf8_guard::{
+    Utf8DecodeError, Utf8DecodeStats, MAX_UTF8_ERROR_RUNS, MAX_UTF8_ERROR_RUN_LENGTH,
+};
 
 // Imports
 use crate::cell::{Cell, CellFlags};
@@ -78,19 +164,24 @@ use crate::color::{Color, NamedColor};
 use crate::cursor::{Cursor, CursorStyle};
 use crate::debug;
 use crate::graphics::kitty::KittyParser;
-use crate::graphics::{GraphicsLimits, GraphicsStore};
+use crate::graphics::{GraphicsLimits, GraphicsStore, TextOverwriteMode};
 use crate::grid::Grid;
 use crate::mouse::{MouseEncoding, MouseEvent, MouseEventRecord, MouseMode, MousePosition};
+use crate::regis;
 use crate::shell_integration::ShellIntegration;
 use crate::sixel;
 use crate::terminal::apc_filter::ApcFilterState;
+use crate::terminal::osc_guard::OscGuardState;
 use std::collections::{HashMap, HashSet};
 use std::num::NonZeroU32;
 
-/// Character set designation for G0/G1 charset slots.
+/// Character set designation for the G0-G3 charset slots.
 ///
 /// VT100 defines multiple character sets; the most commonly used are ASCII
 /// and the DEC Special / Line Drawing set used by applications like tmux.
+/// The VT220 National Replacement Character Sets (NRCS) replace a dozen
+/// ASCII punctuation characters with national letters; all but British are
+/// only designated while DECNRCM (`CSI ? 42 h`) is set.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum Charset {
     /// Standard ASCII character set (default)
@@ -98,16 +189,42 @@ pub enum Charset {
     Ascii,
     /// DEC Special / Line Drawing character set (ESC ( 0 / ESC ) 0)
     DecLineDrawing,
+    /// British NRCS (ESC ( A)
+    British,
+    /// Dutch NRCS (ESC ( 4)
+    Dutch,
+    /// Finnish NRCS (ESC ( C / ESC ( 5)
+    Finnish,
+    /// French NRCS (ESC ( R / ESC ( f)
+    French,
+    /// French Canadian NRCS (ESC ( Q / ESC ( 9)
+    FrenchCanadian,
+    /// German NRCS (ESC ( K)
+    German,
+    /// Italian NRCS (ESC ( Y)
+    Italian,
+    /// Norwegian/Danish NRCS (ESC ( E / ESC ( 6 / ESC ( `)
+    NorwegianDanish,
+    /// Portuguese NRCS (ESC ( % 6)
+    Portuguese,
+    /// Spanish NRCS (ESC ( Z)
+    Spanish,
+    /// Swedish NRCS (ESC ( H / ESC ( 7)
+    Swedish,
+    /// Swiss NRCS (ESC ( =)
+    Swiss,
 }
 
 impl Charset {
-    /// Translate a character according to the DEC Special / Line Drawing table.
+    /// Translate a character according to the charset's table.
     ///
-    /// Only characters in the printable ASCII range that appear in the ACS map
-    /// are translated; everything else passes through unchanged.
+    /// Only characters in the printable ASCII range that the charset
+    /// replaces are translated; everything else passes through unchanged.
     pub fn translate(self, c: char) -> char {
-        if self != Charset::DecLineDrawing {
-            return c;
+        match self {
+            Charset::Ascii => return c,
+            Charset::DecLineDrawing => {}
+            nrcs => return charsets::nrcs_translate(nrcs, c),
         }
         // ACS byte → Unicode mapping (VT100 manual §3.3.4)
         match c {
@@ -149,11 +266,15 @@ const MAX_TERMINAL_EVENTS: usize = 10_000;
 const CLIPBOARD_TRUNCATION_SUFFIX: &str = " [truncated]";
 /// Hard upper limit for clipboard content (10 MB), regardless of configured max_bytes
 const MAX_CLIPBOARD_CONTENT_SIZE: usize = 10_485_760;
+/// Maximum depth of each Kitty keyboard flag stack (oldest entries are evicted)
+pub const KEYBOARD_STACK_MAX: usize = 16;
+/// Kitty keyboard protocol flag bits defined by the spec (1 | 2 | 4 | 8 | 16)
+pub(crate) const KEYBOARD_FLAGS_MASK: u16 = 0x1f;
 
 #[inline]
 pub fn unix_millis() -> u64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
+    crate::time::SystemTime::now()
+        .duration_since(crate::time::UNIX_EPOCH)
         .unwrap_or_default()
         .as_millis() as u64
 }
@@ -210,24 +331,12 @@ pub fn html_escape(s: &str) -> String {
 
 /// Get current timestamp in microseconds
 pub fn get_timestamp_us() -> u64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
+    crate::time::SystemTime::now()
+        .duration_since(crate::time::UNIX_EPOCH)
         .unwrap_or_default()
         .as_micros() as u64
 }
 
-/// Helper function to check if byte slice contains a subsequence
-/// More efficient than converting to String and using contains()
-#[inline]
-pub(crate) fn contains_bytes(haystack: &[u8], needle: &[u8]) -> bool {
-    if needle.is_empty() || haystack.len() < needle.len() {
-        return false;
-    }
-    haystack
-        .windows(needle.len())
-        .any(|window| window == needle)
-}
-
 // =============================================================================
 // Cohesive state sub-structs (ARC-001: decomposing the `Terminal` god object)
 //
@@ -267,6 +376,8 @@ pub(crate) struct ProfilingState {
     pub(crate) frame_timings: Vec<FrameTiming>,
     /// Maximum frame timings to keep
     pub(crate) max_frame_timings: usize,
+    /// Input → output → render latency samples
+    pub(crate) latency: metrics::LatencyTracker,
     /// Profiling data (when enabled)
     pub(crate) data: Option<ProfilingData>,
     /// Profiling enabled flag
@@ -280,6 +391,18 @@ pub(crate) struct MouseHistoryState {
     pub(crate) max_mouse_history: usize,
 }
 
+/// Cursor motion history (see `cursor_trail`).
+pub(crate) struct CursorTrailState {
+    /// Whether motions are recorded and `CursorMoved` events emitted
+    pub(crate) enabled: bool,
+    /// Recorded motions, oldest first
+    pub(crate) history: std::collections::VecDeque<CursorMotion>,
+    /// Maximum number of motions to retain
+    pub(crate) max_history: usize,
+    /// Cursor position at the last sample point
+    pub(crate) last_pos: (usize, usize),
+}
+
 /// Regex search matches and current pattern.
 pub(crate) struct SearchState {
     pub(crate) regex_matches: Vec<RegexMatch>,
@@ -303,6 +426,7 @@ pub(crate) struct MacroState {
     pub(crate) macro_library: HashMap<String, crate::macros::Macro>,
     pub(crate) macro_playback: Option<crate::macros::MacroPlayback>,
     pub(crate) macro_screenshot_triggers: Vec<String>,
+    pub(crate) macro_recorder: Option<crate::macros::MacroRecorder>,
 }
 
 /// tmux control-protocol parser and notification buffer.
@@ -341,6 +465,8 @@ pub(crate) struct NotificationState {
     pub(crate) max_notifications: usize,
     /// Custom notification triggers (ID -> message)
     pub(crate) custom_triggers: HashMap<u32, String>,
+    /// Whether the host window has focus (for focused suppression)
+    pub(crate) window_focused: bool,
 }
 
 /// Terminal replay/recording state (Feature 24).
@@ -351,8 +477,9 @@ pub(crate) struct RecordingState {
     pub(crate) recording_session: Option<RecordingSession>,
     /// Recording active flag
     pub(crate) is_recording: bool,
-    /// Recording start timestamp (for relative timing)
-    pub(crate) recording_start_time: u64,
+    /// Monotonic clock started with the recording; event timestamps are
+    /// measured from it so wall-clock adjustments cannot reorder events
+    pub(crate) recording_clock: Option<crate::time::Instant>,
 }
 
 /// Keyboard protocol state: Kitty flags, per-screen stacks, and modifyOtherKeys mode.
@@ -370,17 +497,30 @@ pub(crate) struct KeyboardState {
     pub(crate) modify_other_keys_mode: u8,
 }
 
+/// Default limit on how long a synchronized update (DEC 2026) may stay open
+pub const DEFAULT_SYNC_UPDATE_TIMEOUT_MS: u64 = 150;
+
 /// Synchronized update state (DEC 2026).
 ///
-/// Holds the active flag, batched update buffer, and the "explicitly disabled
-/// during flush" tracking flag. Extracted from `Terminal` for cohesion (ARC-001).
+/// Output is parsed as it arrives; what is deferred is the dirty state. While
+/// the mode is set, rows changed by the application collect in
+/// `pending_dirty_rows` (the back buffer) and are committed to the dirty rows
+/// frontends read when the update ends, so a renderer never repaints half a
+/// frame. Extracted from `Terminal` for cohesion (ARC-001).
 pub(crate) struct SyncState {
     /// Synchronized update mode (DEC 2026)
     pub(crate) synchronized_updates: bool,
-    /// Buffer for batched updates (when synchronized mode is active)
-    pub(crate) update_buffer: Vec<u8>,
-    /// Flag to track if synchronized updates were explicitly disabled during a flush
-    pub(crate) sync_update_explicitly_disabled: bool,
+    /// Rows changed since the synchronized update began, not yet visible
+    /// through `get_dirty_rows()`
+    pub(crate) pending_dirty_rows: HashSet<usize>,
+    /// Render events (graphics, screen clears, cursor motion) withheld until
+    /// the commit
+    pub(crate) deferred_events: Vec<TerminalEvent>,
+    /// Longest an update may stay open before it is committed and ended
+    /// anyway, in milliseconds (0 = no limit)
+    pub(crate) timeout_ms: u64,
+    /// When the current synchronized update began
+    pub(crate) started: Option<crate::time::Instant>,
 }
 
 /// Window title, title stack, and answerback string.
@@ -389,6 +529,10 @@ pub(crate) struct SyncState {
 pub(crate) struct TitleState {
     /// Terminal title
     pub(crate) title: String,
+    /// Icon name (OSC 0 / OSC 1)
+    pub(crate) icon_name: String,
+    /// Recent title and icon name changes, bounded by `MAX_TITLE_HISTORY`
+    pub(crate) title_history: std::collections::VecDeque<TitleHistoryEntry>,
     /// Window title stack for XTWINOPS 22/23 (push/pop title)
     pub(crate) title_stack: Vec<String>,
     /// Answerback string sent in response to ENQ (0x05).
@@ -404,17 +548,33 @@ pub(crate) struct TitleState {
 pub(crate) struct ShellState {
     /// Shell integration state
     pub(crate) shell_integration: ShellIntegration,
-    /// Last known hostname (for detecting remote host transitions)
-    pub(crate) last_hostname: Option<String>,
-    /// Last known username (for detecting remote host transitions)
-    pub(crate) last_username: Option<String>,
+    /// Current host/user context (for detecting remote host transitions)
+    pub(crate) host_context: HostContext,
+    /// Environment reported by shell integration (cwd, host, shell, venv)
+    pub(crate) environment: ShellEnvironment,
+    /// Hostname of the machine running the terminal; an OSC 7 / RemoteHost
+    /// hostname matching it is treated as local rather than remote
+    pub(crate) local_hostname: Option<String>,
     /// Current shell nesting depth (for sub-shell detection)
     pub(crate) shell_depth: usize,
     /// Whether we are currently inside command output (between OSC 133 C and D)
     pub(crate) in_command_output: bool,
 }
 
-/// Bookmark registry for quick navigation.
+/// Heuristic prompt detection for shells without OSC 133 (see `prompt_detection`).
+pub(crate) struct PromptDetectionState {
+    /// Patterns, idle time and enabled flag
+    pub(crate) config: PromptDetectionConfig,
+    /// Compiled `config.patterns`
+    pub(crate) regexes: Vec<regex::Regex>,
+    /// When output was last processed
+    pub(crate) last_output: Option<crate::time::Instant>,
+    /// Whether a detected prompt is waiting for its command line
+    pub(crate) phase: prompt_detection::PromptPhase,
+}
+
+/// Bookmark registry for quick navigation, plus annotation ID allocation
+/// (the annotations themselves live on the Grid).
 ///
 /// Extracted from `Terminal` for cohesion (ARC-001).
 pub(crate) struct BookmarksState {
@@ -422,18 +582,30 @@ pub(crate) struct BookmarksState {
     pub(crate) bookmarks: Vec<Bookmark>,
     /// Next available bookmark ID
     pub(crate) next_bookmark_id: usize,
+    /// Next available annotation ID
+    pub(crate) next_annotation_id: usize,
 }
 
-/// ACS (Alternate Character Set) state: G0/G1 slot designations and active slot.
+/// ACS (Alternate Character Set) state: G0-G3 slot designations and the
+/// slots invoked into GL and GR.
 ///
 /// Extracted from `Terminal` for cohesion (ARC-001).
+#[derive(Debug, Clone, Copy, Default)]
 pub(crate) struct CharsetState {
     /// G0 charset slot designation (ESC ( 0 / ESC ( B)
     pub(crate) g0_charset: Charset,
     /// G1 charset slot designation (ESC ) 0 / ESC ) B)
     pub(crate) g1_charset: Charset,
-    /// Active charset slot: 0 = G0, 1 = G1 (toggled by SO/SI)
+    /// G2 charset slot designation (ESC * F)
+    pub(crate) g2_charset: Charset,
+    /// G3 charset slot designation (ESC + F)
+    pub(crate) g3_charset: Charset,
+    /// Slot invoked into GL: 0 = G0, 1 = G1 (SO/SI), 2 = G2 (LS2), 3 = G3 (LS3)
     pub(crate) active_g: u8,
+    /// Slot invoked into GR (LS1R / LS2R / LS3R); none until one is sent
+    pub(crate) active_gr: Option<u8>,
+    /// Slot selected for the next graphic character only (SS2 / SS3)
+    pub(crate) single_shift: Option<u8>,
 }
 
 /// Hyperlink storage (OSC 8): ID→URL map, current ID being written, next free ID.
@@ -471,12 +643,16 @@ pub(crate) struct ClipboardState {
 pub(crate) struct DcsState {
     /// Current Sixel parser (active during DCS)
     pub(crate) sixel_parser: Option<sixel::SixelParser>,
+    /// ReGIS interpreter (active during DCS p)
+    pub(crate) regis_parser: Option<regis::RegisParser>,
     /// Buffer for DCS data accumulation
     pub(crate) dcs_buffer: Vec<u8>,
     /// DCS active flag
     pub(crate) dcs_active: bool,
     /// DCS action character ('q' for Sixel)
     pub(crate) dcs_action: Option<char>,
+    /// DECDMAC header being collected: (macro id, delete all, hex encoded)
+    pub(crate) dec_macro: Option<(u16, bool, bool)>,
 }
 
 /// DECSTBM/DECSLRM scroll + left/right margins (ARC-001 sub-struct)
@@ -531,6 +707,14 @@ pub(crate) struct ColorThemeState {
     pub(crate) smart_cursor_color: bool,
     /// Faint/dim text alpha multiplier (0.0-1.0, default 0.5)
     pub(crate) faint_text_alpha: f32,
+    /// Minimum WCAG contrast ratio enforced by `resolve_cell_colors` (1.0 = off)
+    pub(crate) minimum_contrast_ratio: f32,
+    /// Current dark/light appearance
+    pub(crate) appearance: appearance::Appearance,
+    /// Theme applied when switching to dark appearance
+    pub(crate) dark_theme: Option<crate::theme::Theme>,
+    /// Theme applied when switching to light appearance
+    pub(crate) light_theme: Option<crate::theme::Theme>,
 }
 
 /// VT operational modes toggled by DECSET/DECRST-style sequences (ARC-001 sub-struct)
@@ -559,12 +743,36 @@ pub(crate) struct TerminalModes {
     pub(crate) mouse_encoding: MouseEncoding,
     /// Focus tracking enabled
     pub(crate) focus_tracking: bool,
+    /// Grapheme cluster processing (DEC 2027), honored when the width
+    /// policy is [`GraphemeClustering::Negotiable`](crate::unicode_width_config::GraphemeClustering)
+    pub(crate) grapheme_clusters: bool,
+    /// Color scheme update reports (DEC 2031): send `CSI ? 997 ; Ps n` when
+    /// the appearance changes
+    pub(crate) color_scheme_updates: bool,
+    /// win32-input-mode (DEC 9001): keys are sent as serialized Win32
+    /// `KEY_EVENT_RECORD`s, including key-up events
+    pub(crate) win32_input_mode: bool,
+    /// Ambiguous width reports (mintty DEC 7700): send `CSI 1 W` / `CSI 2 W`
+    /// when the ambiguous width setting changes
+    pub(crate) ambiguous_width_reports: bool,
+    /// National Replacement Character Set mode (DECNRCM, DEC 42): SCS may
+    /// designate the national character sets
+    pub(crate) nrcs_mode: bool,
 }
 
-/// DECSC/DECRC saved terminal state: saved cursor + saved SGR colors/flags (ARC-001 sub-struct)
+/// DECSC/DECRC saved terminal state: saved cursor, SGR colors/flags, origin
+/// mode, wrap flag, protection and character sets (ARC-001 sub-struct)
 pub(crate) struct SavedCursorState {
     /// Saved cursor position (for save/restore)
     pub(crate) saved_cursor: Option<Cursor>,
+    /// Saved origin mode (DECOM)
+    pub(crate) saved_origin_mode: bool,
+    /// Saved wrap-pending flag
+    pub(crate) saved_pending_wrap: bool,
+    /// Saved selective erase attribute (DECSCA)
+    pub(crate) saved_char_protected: bool,
+    /// Saved G0-G3 designations, GL/GR invocations and single shift
+    pub(crate) saved_charsets: CharsetState,
     /// Saved foreground color
     pub(crate) saved_fg: Color,
     /// Saved background color
@@ -573,6 +781,19 @@ pub(crate) struct SavedCursorState {
     pub(crate) saved_underline_color: Option<Color>,
     /// Saved cell flags
     pub(crate) saved_flags: CellFlags,
+    /// XTPUSHSGR/XTPOPSGR attribute stack
+    pub(crate) sgr_stack: Vec<SgrStackEntry>,
+}
+
+/// Video attributes saved by XTPUSHSGR
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SgrStackEntry {
+    /// Attributes to restore on XTPOPSGR (bit set, see `sgr_stack.rs`)
+    pub(crate) attributes: u16,
+    pub(crate) fg: Color,
+    pub(crate) bg: Color,
+    pub(crate) underline_color: Option<Color>,
+    pub(crate) flags: CellFlags,
 }
 
 /// Feature 31 command/CWD execution history (ARC-001 sub-struct)
@@ -587,6 +808,10 @@ pub(crate) struct CommandHistoryState {
     pub(crate) max_command_history: usize,
     /// Maximum CWD change history
     pub(crate) max_cwd_history: usize,
+    /// Shell environment change history
+    pub(crate) environment_changes: Vec<EnvironmentChange>,
+    /// Maximum environment change history
+    pub(crate) max_environment_history: usize,
 }
 
 /// Progress bars (OSC 9;4 + named OSC 934) + bell event counter (ARC-001 sub-struct)
@@ -605,6 +830,8 @@ pub(crate) struct UnicodeConfigState {
     pub(crate) width_config: crate::unicode_width_config::WidthConfig,
     /// Unicode normalization form for text stored in cells
     pub(crate) normalization_form: crate::unicode_normalization_config::NormalizationForm,
+    /// How grapheme clusters are measured and mode 2027 is reported
+    pub(crate) grapheme_clustering: crate::unicode_width_config::GraphemeClustering,
 }
 
 /// Security flags: OSC 7 acceptance + insecure-sequence disable (ARC-001 sub-struct)
@@ -618,11 +845,24 @@ pub(crate) struct SecurityFlagsState {
     /// images (iTerm2/Kitty base64) fit; security-conscious deployments can
     /// tighten it via [`Terminal::set_max_osc_data_length`].
     pub(crate) max_osc_data_length: usize,
+    /// Maximum bytes buffered for one DCS sequence (Sixel command
+    /// parameters, unrecognized DCS payloads); excess bytes are dropped
+    pub(crate) max_dcs_data_length: usize,
+    /// Maximum Kitty APC payload length in bytes; longer payloads are
+    /// discarded when their terminator arrives
+    pub(crate) max_apc_data_length: usize,
 }
 
 /// Default max OSC data length: 128 MiB (room for inline images).
 pub const DEFAULT_MAX_OSC_DATA_LENGTH: usize = 128 * 1024 * 1024;
 
+/// Default max DCS data length: 1 MiB. Sixel pixel data is decoded as it
+/// streams in and never buffered, so only command parameters count.
+pub const DEFAULT_MAX_DCS_DATA_LENGTH: usize = 1024 * 1024;
+
+/// Default max Kitty APC payload length: 128 MiB (room for unchunked images).
+pub const DEFAULT_MAX_APC_DATA_LENGTH: usize = 128 * 1024 * 1024;
+
 /// OSC 1337 badge format string + session variables for evaluation (ARC-001 sub-struct)
 pub(crate) struct BadgeState {
     /// Badge format string (from OSC 1337 SetBadgeFormat)
@@ -640,10 +880,25 @@ pub(crate) struct GraphicsState {
     pub(crate) graphics_store: GraphicsStore,
     /// Sixel resource limits (per-terminal, for decoding)
     pub(crate) sixel_limits: sixel::SixelLimits,
+    /// Sixel geometry set by the application with XTSMGRAPHICS
+    /// (None = the limits' width and height)
+    pub(crate) sixel_geometry: Option<(usize, usize)>,
+    /// Each Sixel image starts from the default color registers (DEC 1070)
+    pub(crate) sixel_private_color_registers: bool,
+    /// Color registers carried between Sixel images while DEC 1070 is reset
+    pub(crate) sixel_shared_palette: Option<HashMap<usize, sixel::SixelColor>>,
+    /// ReGIS resource limits (raster size, bytes per sequence)
+    pub(crate) regis_limits: regis::RegisLimits,
+    /// ReGIS graphics plane and drawing state kept between sequences
+    pub(crate) regis_parser: Option<regis::RegisParser>,
+    /// Store ID of the graphic showing the ReGIS plane
+    pub(crate) regis_graphic_id: Option<u64>,
     /// Cell dimensions in pixels (width, height) for sixel graphics
     /// Default (1, 2) is for text-mode TUI with half-block rendering
     /// Pixel renderers should set actual cell dimensions
     pub(crate) cell_dimensions: (u32, u32),
+    /// What printing text over an image does to it
+    pub(crate) text_overwrite: TextOverwriteMode,
     /// iTerm2 multi-part image transfer state (MultipartFile/FilePart protocol)
     pub(crate) iterm_multipart_buffer: Option<ITermMultipartState>,
     /// File transfer manager for tracking file downloads and uploads
@@ -666,6 +921,12 @@ pub(crate) struct EventBrokerState {
     pub(crate) next_observer_id: crate::observer::ObserverId,
     /// Next zone ID to assign (monotonically increasing)
     pub(crate) next_zone_id: usize,
+    /// Filtered per-subscriber event queues (see `Terminal::subscribe`)
+    pub(crate) subscribers: Vec<event::EventSubscriber>,
+    /// Next subscription ID to assign (monotonically increasing)
+    pub(crate) next_subscription_id: SubscriptionId,
+    /// Index of the next event to copy into subscriber queues
+    pub(crate) events_fanned_out_up_to: usize,
 }
 
 // Terminal struct definition
@@ -696,6 +957,8 @@ pub struct Terminal {
     pub(crate) sync_state: SyncState,
     /// Shell integration state, host/user, depth, command-output flag (ARC-001 sub-struct)
     pub(crate) shell_state: ShellState,
+    /// Heuristic prompt detection config and progress (ARC-001 sub-struct)
+    pub(crate) prompt_detection: PromptDetectionState,
     /// DECSTBM/DECSLRM scroll + left/right margins (ARC-001 sub-struct)
     pub(crate) margins: MarginState,
     /// VT operational modes toggled by DECSET/DECRST-style sequences (ARC-001 sub-struct)
@@ -704,10 +967,12 @@ pub struct Terminal {
     pub(crate) tab_stops: Vec<bool>,
     /// Keyboard protocol flags, stacks, modifyOtherKeys mode (ARC-001 sub-struct)
     pub(crate) keyboard_state: KeyboardState,
-    /// Response buffer for device queries (DA/DSR/etc)
-    pub(crate) response_buffer: Vec<u8>,
+    /// Replies owed to the host for device queries (DA/DSR/etc)
+    pub(crate) reply_queue: reply_queue::ReplyQueue,
     /// Hyperlinks map, current ID, next ID (ARC-001 sub-struct)
     pub(crate) hyperlink_state: HyperlinkState,
+    /// Detected URLs promoted to hyperlinks, and the hovered link
+    pub(crate) autolink: autolink::AutolinkState,
     /// Unified graphics storage + Sixel limits + cell pixel dimensions +
     /// iTerm2 multipart transfer state + file transfer manager (ARC-001 sub-struct)
     pub(crate) graphics: GraphicsState,
@@ -729,10 +994,37 @@ pub struct Terminal {
     pub(crate) apc_filter_state: ApcFilterState,
     /// Accumulator for the in-flight Kitty APC payload bytes.
     pub(crate) apc_buffer: Vec<u8>,
+    /// Streaming state for the OSC length guard run on the bytes bound for
+    /// vte (vte buffers OSC bodies without limit).
+    pub(crate) osc_guard_state: OscGuardState,
+    /// The next OSC dispatch is a string the guard cut short; discard it
+    pub(crate) osc_cut_pending: bool,
+    /// The next DCS unhook ends a string the watchdog cut short; discard it
+    pub(crate) dcs_cut_pending: bool,
+    /// Unterminated OSC/DCS string watchdog
+    pub(crate) escape_watchdog: escape_watchdog::EscapeWatchdogState,
+    /// Embedder callback deciding whether OSC/DCS sequences are handled
+    pub(crate) sequence_filter: Option<sequence_filter::SequenceFilter>,
+    /// Sequences the emulator ignored, for [`Terminal::unsupported_sequences`]
+    pub(crate) unsupported_sequences: Vec<UnsupportedSequence>,
+    /// UTF-8 validation and decode error telemetry
+    pub(crate) utf8_guard: utf8_guard::Utf8Guard,
+    /// Line store replacing the grid in log follower mode
+    pub(crate) log_follower: Option<Box<log_follower::LogFollower>>,
+    /// Output volume samples for [`Terminal::activity_histogram`]
+    pub(crate) activity: activity::ActivityState,
     /// Reusable scratch buffer for non-APC bytes during APC pre-filtering
     /// (ARC-008). Capacity is reused across `process()` calls instead of
     /// reallocating a fresh `Vec` on every call.
     pub(crate) apc_passthrough: Vec<u8>,
+    /// Printable ASCII received from the parser but not yet written to the
+    /// grid (flushed before any other parser callback)
+    pub(crate) print_run: Vec<u8>,
+    /// Last graphic character printed, repeated by REP (`CSI Pn b`); cleared
+    /// by C0 controls
+    pub(crate) last_graphic_char: Option<char>,
+    /// Printed text awaiting `expect_match` (None when disabled)
+    pub(crate) expect_buffer: Option<expect::ExpectBuffer>,
     /// Long-lived Kitty TGP parser; reset between unrelated transmissions.
     pub(crate) kitty_parser: KittyParser,
     /// DECAWM delayed wrap: set after printing in last column
@@ -749,6 +1041,20 @@ pub struct Terminal {
     pub(crate) warning_bell_volume: u8,
     /// Margin bell volume (0=off, 1-8=volume levels) - VT520 DECSMBV
     pub(crate) margin_bell_volume: u8,
+    /// Cells changed by DECCARA/DECRARA - VT420 DECSACE
+    pub(crate) attribute_change_extent: crate::grid::AttributeChangeExtent,
+    /// VT420 macros defined with DECDMAC
+    pub(crate) macro_space: macro_space::MacroSpace,
+    /// Media Copy printer controller mode and the embedder's printer sink
+    pub(crate) printer: printer::PrinterState,
+    /// VT52 compatibility mode (DECANM reset) and its escape decoder
+    pub(crate) vt52: vt52::Vt52State,
+    /// Secret detection settings and output scan position
+    pub(crate) redaction: redaction::RedactionState,
+    /// Screens captured before ED 2 / ED 3 for "undo clear"
+    pub(crate) clear_undo: clear_undo::ClearUndoState,
+    /// Scrollback viewport anchor and follow-tail setting
+    pub(crate) viewport: viewport::ViewportState,
     /// tmux control-protocol state (ARC-001 sub-struct)
     pub(crate) tmux: TmuxState,
     /// Dirty rows tracking (0-indexed row numbers that have changed)
@@ -757,12 +1063,33 @@ pub struct Terminal {
     pub(crate) events: EventBrokerState,
     /// Current selection state
     pub(crate) selection: Option<Selection>,
+    /// Selections added with `add_selection` after the primary one
+    pub(crate) extra_selections: Vec<Selection>,
+    /// How copied selections and joined lines treat row boundaries
+    pub(crate) join_mode: JoinMode,
     /// Bookmarks and next bookmark ID (ARC-001 sub-struct)
     pub(crate) bookmarks_state: BookmarksState,
+    /// Prompt and named marks for jump-list navigation
+    pub(crate) mark_state: marks::MarkState,
     /// Performance metrics and profiling state (ARC-001 sub-struct)
     pub(crate) profiling: ProfilingState,
     /// Mouse event/position history (ARC-001 sub-struct)
     pub(crate) mouse_history: MouseHistoryState,
+    /// Cursor motion history (ARC-001 sub-struct)
+    pub(crate) cursor_trail: CursorTrailState,
+    /// Bidirectional text display settings (see `bidi`)
+    pub(crate) bidi: bidi::BidiState,
+    /// Automatic screen checkpoints for time-travel views
+    pub(crate) checkpoints: checkpoint::CheckpointState,
+    /// Bytes passed to `process()` over the terminal's lifetime; never goes
+    /// back, not even on RIS
+    pub(crate) bytes_processed: u64,
+    /// Front buffer committed for readers while double buffering is on
+    pub(crate) double_buffer: double_buffer::DoubleBufferState,
+    /// Background image/opacity requests and their policy
+    pub(crate) background: background::BackgroundState,
+    /// State the next snapshot delta is computed against (None = keyframe)
+    pub(crate) delta_base: Option<Box<replay_snapshot::TerminalSnapshot>>,
     /// Rendering hints and damage regions (ARC-001 sub-struct)
     pub(crate) rendering: RenderingState,
     /// Regex search state (ARC-001 sub-struct)
@@ -850,28 +1177,47 @@ impl Terminal {
             flags: CellFlags::default(),
             saved_state: SavedCursorState {
                 saved_cursor: None,
+                saved_origin_mode: false,
+                saved_pending_wrap: false,
+                saved_char_protected: false,
+                saved_charsets: CharsetState::default(),
                 saved_fg: Color::Named(NamedColor::White),
                 saved_bg: Color::Named(NamedColor::Black),
                 saved_underline_color: None,
                 saved_flags: CellFlags::default(),
+                sgr_stack: Vec::new(),
             },
             title_state: TitleState {
                 title: String::new(),
+                icon_name: String::new(),
+                title_history: std::collections::VecDeque::new(),
                 title_stack: Vec::new(),
                 answerback_string: None,
             },
             sync_state: SyncState {
                 synchronized_updates: false,
-                update_buffer: Vec::new(),
-                sync_update_explicitly_disabled: false,
+                pending_dirty_rows: HashSet::new(),
+                deferred_events: Vec::new(),
+                timeout_ms: DEFAULT_SYNC_UPDATE_TIMEOUT_MS,
+                started: None,
             },
             shell_state: ShellState {
                 shell_integration: ShellIntegration::new(),
-                last_hostname: None,
-                last_username: None,
+                host_context: HostContext::default(),
+                environment: ShellEnvironment::default(),
+                local_hostname: None,
                 shell_depth: 0,
                 in_command_output: false,
             },
+            prompt_detection: PromptDetectionState {
+                config: PromptDetectionConfig::default(),
+                regexes: prompt_detection::DEFAULT_PROMPT_PATTERNS
+                    .iter()
+                    .filter_map(|p| regex::Regex::new(p).ok())
+                    .collect(),
+                last_output: None,
+                phase: prompt_detection::PromptPhase::Idle,
+            },
             margins: MarginState {
                 scroll_region_top: 0,
                 scroll_region_bottom: rows.saturating_sub(1),
@@ -892,6 +1238,11 @@ impl Terminal {
                 mouse_mode: MouseMode::Off,
                 mouse_encoding: MouseEncoding::Default,
                 focus_tracking: false,
+                grapheme_clusters: true,
+                color_scheme_updates: false,
+                win32_input_mode: false,
+                ambiguous_width_reports: false,
+                nrcs_mode: false,
             },
             tab_stops,
             keyboard_state: KeyboardState {
@@ -900,7 +1251,8 @@ impl Terminal {
                 keyboard_stack_alt: Vec::new(),
                 modify_other_keys_mode: 0,
             },
-            response_buffer: Vec::new(),
+            reply_queue: reply_queue::ReplyQueue::default(),
+            autolink: autolink::AutolinkState::default(),
             hyperlink_state: HyperlinkState {
                 hyperlinks: HashMap::new(),
                 current_hyperlink_id: None,
@@ -911,15 +1263,24 @@ impl Terminal {
             graphics: GraphicsState {
                 graphics_store: GraphicsStore::with_limits(GraphicsLimits::default()),
                 sixel_limits: sixel::SixelLimits::default(),
+                sixel_geometry: None,
+                sixel_private_color_registers: true,
+                sixel_shared_palette: None,
+                regis_limits: regis::RegisLimits::default(),
+                regis_parser: None,
+                regis_graphic_id: None,
                 cell_dimensions: (1, 2), // Default for TUI half-block rendering
+                text_overwrite: TextOverwriteMode::default(),
                 iterm_multipart_buffer: None,
                 file_transfer_manager: FileTransferManager::default(),
             },
             dcs_state: DcsState {
                 sixel_parser: None,
+                regis_parser: None,
                 dcs_buffer: Vec::new(),
                 dcs_active: false,
                 dcs_action: None,
+                dec_macro: None,
             },
             clipboard_state: ClipboardState {
                 clipboard_content: None,
@@ -948,6 +1309,10 @@ impl Terminal {
                 use_selected_text_color: false,
                 smart_cursor_color: false,
                 faint_text_alpha: 0.5, // 50% dimming for SGR 2 (faint/dim) text
+                minimum_contrast_ratio: 1.0,
+                appearance: appearance::Appearance::Dark,
+                dark_theme: None,
+                light_theme: None,
             },
             progress_state: ProgressBellState {
                 progress_bar: ProgressBar::default(),
@@ -957,7 +1322,19 @@ impl Terminal {
             parser: vte::Parser::new(),
             apc_filter_state: ApcFilterState::default(),
             apc_buffer: Vec::new(),
+            osc_guard_state: OscGuardState::default(),
+            osc_cut_pending: false,
+            dcs_cut_pending: false,
+            escape_watchdog: escape_watchdog::EscapeWatchdogState::default(),
+            sequence_filter: None,
+            unsupported_sequences: Vec::new(),
+            utf8_guard: utf8_guard::Utf8Guard::default(),
+            log_follower: None,
+            activity: activity::ActivityState::default(),
             apc_passthrough: Vec::new(),
+            print_run: Vec::new(),
+            last_graphic_char: None,
+            expect_buffer: None,
             kitty_parser: KittyParser::new(),
             pending_wrap: false,
             // Initialize pixel dimensions with reasonable defaults (10x20 per cell)
@@ -968,12 +1345,21 @@ impl Terminal {
                 accept_osc7: true,
                 disable_insecure_sequences: false,
                 max_osc_data_length: DEFAULT_MAX_OSC_DATA_LENGTH,
+                max_dcs_data_length: DEFAULT_MAX_DCS_DATA_LENGTH,
+                max_apc_data_length: DEFAULT_MAX_APC_DATA_LENGTH,
             },
             // VT520 conformance level - default to VT520 for maximum compatibility
             conformance_level: crate::conformance_level::ConformanceLevel::default(),
             // VT520 bell volume controls - default to moderate volume (4)
             warning_bell_volume: 4,
             margin_bell_volume: 4,
+            attribute_change_extent: crate::grid::AttributeChangeExtent::default(),
+            macro_space: macro_space::MacroSpace::default(),
+            printer: printer::PrinterState::default(),
+            vt52: vt52::Vt52State::default(),
+            redaction: redaction::RedactionState::default(),
+            clear_undo: clear_undo::ClearUndoState::default(),
+            viewport: viewport::ViewportState::default(),
             // Tmux control protocol - default to disabled
             tmux: TmuxState {
                 tmux_parser: crate::tmux_control::TmuxControlParser::new(false),
@@ -988,18 +1374,26 @@ impl Terminal {
                 observers: Vec::new(),
                 next_observer_id: 1,
                 next_zone_id: 0,
+                subscribers: Vec::new(),
+                next_subscription_id: 1,
+                events_fanned_out_up_to: 0,
             },
             // Selection and bookmarks
             selection: None,
+            extra_selections: Vec::new(),
+            join_mode: JoinMode::default(),
             bookmarks_state: BookmarksState {
                 bookmarks: Vec::new(),
                 next_bookmark_id: 0,
+                next_annotation_id: 0,
             },
+            mark_state: marks::MarkState::default(),
             // Performance metrics
             profiling: ProfilingState {
                 metrics: PerformanceMetrics::default(),
                 frame_timings: Vec::new(),
                 max_frame_timings: 100, // Keep last 100 frames
+                latency: metrics::LatencyTracker::default(),
                 data: None,
                 enabled: false,
             },
@@ -1010,6 +1404,18 @@ impl Terminal {
                 mouse_positions: Vec::new(),
                 max_mouse_history: 100,
             },
+            bidi: bidi::BidiState::default(),
+            checkpoints: checkpoint::CheckpointState::default(),
+            bytes_processed: 0,
+            double_buffer: double_buffer::DoubleBufferState::default(),
+            background: background::BackgroundState::default(),
+            delta_base: None,
+            cursor_trail: CursorTrailState {
+                enabled: false,
+                history: std::collections::VecDeque::new(),
+                max_history: 256,
+                last_pos: (0, 0),
+            },
             // Rendering hints
             rendering: RenderingState {
                 rendering_hints: Vec::new(),
@@ -1043,6 +1449,8 @@ impl Terminal {
                 cwd_changes: Vec::new(),
                 max_command_history: 100,
                 max_cwd_history: 50,
+                environment_changes: Vec::new(),
+                max_environment_history: 100,
             },
             // Notifications
             notifications_state: NotificationState {
@@ -1053,18 +1461,20 @@ impl Terminal {
                 last_silence_check: now,
                 max_notifications: DEFAULT_MAX_NOTIFICATIONS,
                 custom_triggers: HashMap::new(),
+                window_focused: false,
             },
             // Replay/Recording
             recording_state: RecordingState {
                 recording_session: None,
                 is_recording: false,
-                recording_start_time: 0,
+                recording_clock: None,
             },
             // Macros
             macros: MacroState {
                 macro_library: HashMap::new(),
                 macro_playback: None,
                 macro_screenshot_triggers: Vec::new(),
+                macro_recorder: None,
             },
             // Answerback
             // Unicode
@@ -1072,6 +1482,7 @@ impl Terminal {
                 width_config: crate::unicode_width_config::WidthConfig::default(),
                 normalization_form: crate::unicode_normalization_config::NormalizationForm::default(
                 ),
+                grapheme_clustering: crate::unicode_width_config::GraphemeClustering::default(),
             },
             // Badge
             badge_state: BadgeState {
@@ -1093,19 +1504,19 @@ impl Terminal {
             charset_state: CharsetState {
                 g0_charset: Charset::Ascii,
                 g1_charset: Charset::Ascii,
+                g2_charset: Charset::Ascii,
+                g3_charset: Charset::Ascii,
                 active_g: 0,
+                active_gr: None,
+                single_shift: None,
             },
         }
     }
 
-    /// Return the currently-active character set (G0 or G1 based on SO/SI state).
+    /// Return the character set invoked into GL (G0-G3 by SO/SI/LS2/LS3).
     #[inline]
     pub(crate) fn active_charset(&self) -> Charset {
-        if self.charset_state.active_g == 1 {
-            self.charset_state.g1_charset
-        } else {
-            self.charset_state.g0_charset
-        }
+        self.charset_state.slot(self.charset_state.active_g)
     }
 
     /// Get the active grid (primary or alternate based on current mode)
@@ -1160,6 +1571,23 @@ impl Terminal {
         self.cursor.set_style(style);
     }
 
+    /// Set the cursor style requested by the application, emitting
+    /// [`TerminalEvent::CursorStyleChanged`] if it differs
+    pub(crate) fn apply_cursor_style(&mut self, style: CursorStyle) {
+        if self.cursor.style != style {
+            self.cursor.set_style(style);
+            self.push_cursor_style_changed();
+        }
+    }
+
+    fn push_cursor_style_changed(&mut self) {
+        self.events
+            .terminal_events
+            .push(TerminalEvent::CursorStyleChanged {
+                style: self.cursor.style,
+            });
+    }
+
     /// Get the current conformance level
     pub fn conformance_level(&self) -> crate::conformance_level::ConformanceLevel {
         self.conformance_level
@@ -1175,6 +1603,12 @@ impl Terminal {
         self.margin_bell_volume
     }
 
+    /// Get which cells DECCARA and DECRARA change, as selected by DECSACE
+    /// (rectangle by default)
+    pub fn attribute_change_extent(&self) -> crate::grid::AttributeChangeExtent {
+        self.attribute_change_extent
+    }
+
     /// Get terminal dimensions (of the ACTIVE screen)
     ///
     /// Returns (cols, rows) for whichever screen buffer is currently active
@@ -1272,6 +1706,8 @@ impl Terminal {
         self.cursor.row = self.cursor.row.min(active_rows.saturating_sub(1));
         self.alt_cursor.col = self.alt_cursor.col.min(active_cols.saturating_sub(1));
         self.alt_cursor.row = self.alt_cursor.row.min(active_rows.saturating_sub(1));
+        // Clamping is not a motion the application asked for
+        self.sync_cursor_trail();
 
         // Update session variables for badge evaluation
         self.badge_state
@@ -1348,6 +1784,17 @@ impl Terminal {
         })
     }
 
+    /// Badge text as a frontend shows it
+    ///
+    /// The badge format evaluated with the session variables (hostname and
+    /// username from the shell's directory reports, user vars from OSC 1337
+    /// SetUserVar, ...). Returns None when no badge is set or it evaluates
+    /// to blank text.
+    pub fn badge(&self) -> Option<String> {
+        self.evaluate_badge()
+            .filter(|badge| !badge.trim().is_empty())
+    }
+
     /// Get a user variable by name
     ///
     /// Returns the value of a user variable set via OSC 1337 SetUserVar,
@@ -1382,10 +1829,11 @@ impl Terminal {
             self.events
                 .terminal_events
                 .push(TerminalEvent::UserVarChanged {
-                    name,
-                    value,
+                    name: name.clone(),
+                    value: value.clone(),
                     old_value,
                 });
+            self.sync_environment_user_var(&name, &value);
         }
     }
 
@@ -1411,7 +1859,7 @@ impl Terminal {
             self.events
                 .terminal_events
                 .push(crate::terminal::TerminalEvent::ModeChanged(
-                    "alternate_screen".to_string(),
+                    crate::terminal::Mode::AlternateScreen,
                     true,
                 ));
         }
@@ -1435,7 +1883,7 @@ impl Terminal {
                 self.events
                     .terminal_events
                     .push(crate::terminal::TerminalEvent::ModeChanged(
-                        "keyboard_protocol".to_string(),
+                        crate::terminal::Mode::KeyboardProtocol,
                         false,
                     ));
             }
@@ -1446,7 +1894,7 @@ impl Terminal {
                 self.events
                     .terminal_events
                     .push(crate::terminal::TerminalEvent::ModeChanged(
-                        "modify_other_keys".to_string(),
+                        crate::terminal::Mode::ModifyOtherKeys,
                         false,
                     ));
             }
@@ -1456,7 +1904,7 @@ impl Terminal {
                 self.events
                     .terminal_events
                     .push(crate::terminal::TerminalEvent::ModeChanged(
-                        "focus_tracking".to_string(),
+                        crate::terminal::Mode::FocusTracking,
                         false,
                     ));
             }
@@ -1464,7 +1912,7 @@ impl Terminal {
             self.events
                 .terminal_events
                 .push(crate::terminal::TerminalEvent::ModeChanged(
-                    "alternate_screen".to_string(),
+                    crate::terminal::Mode::AlternateScreen,
                     false,
                 ));
         }
@@ -1500,24 +1948,43 @@ impl Terminal {
         self.modes.focus_tracking = enabled;
     }
 
-    /// Save current cursor state
+    /// Save current cursor state (DECSC): position, SGR attributes, origin
+    /// mode, the wrap flag, DECSCA protection and the character sets
     pub fn save_cursor(&mut self) {
         self.saved_state.saved_cursor = Some(self.cursor);
+        self.saved_state.saved_origin_mode = self.modes.origin_mode;
+        self.saved_state.saved_pending_wrap = self.pending_wrap;
+        self.saved_state.saved_char_protected = self.modes.char_protected;
+        self.saved_state.saved_charsets = self.charset_state;
         self.saved_state.saved_fg = self.fg;
         self.saved_state.saved_bg = self.bg;
         self.saved_state.saved_underline_color = self.underline_color;
         self.saved_state.saved_flags = self.flags;
     }
 
-    /// Restore previously saved cursor state
+    /// Restore previously saved cursor state (DECRC); does nothing if
+    /// nothing was saved
     pub fn restore_cursor(&mut self) {
-        if let Some(saved) = self.saved_state.saved_cursor {
-            self.cursor = saved;
-            self.fg = self.saved_state.saved_fg;
-            self.bg = self.saved_state.saved_bg;
-            self.underline_color = self.saved_state.saved_underline_color;
-            self.flags = self.saved_state.saved_flags;
+        let Some(saved) = self.saved_state.saved_cursor else {
+            return;
+        };
+        let style = self.cursor.style;
+        let (cols, rows) = self.size();
+        self.cursor = saved;
+        // The screen may have shrunk since the save
+        self.cursor.col = saved.col.min(cols.saturating_sub(1));
+        self.cursor.row = saved.row.min(rows.saturating_sub(1));
+        if saved.style != style {
+            self.push_cursor_style_changed();
         }
+        self.modes.origin_mode = self.saved_state.saved_origin_mode;
+        self.pending_wrap = self.saved_state.saved_pending_wrap && self.cursor.col == saved.col;
+        self.modes.char_protected = self.saved_state.saved_char_protected;
+        self.charset_state = self.saved_state.saved_charsets;
+        self.fg = self.saved_state.saved_fg;
+        self.bg = self.saved_state.saved_bg;
+        self.underline_color = self.saved_state.saved_underline_color;
+        self.flags = self.saved_state.saved_flags;
     }
 
     /// Check if bracketed paste is enabled
@@ -1614,16 +2081,6 @@ impl Terminal {
     fn extract_text_from_row_range(&self, abs_start: usize, abs_end: usize) -> Option<String> {
         let scrollback_len = self.grid.scrollback_len();
 
-        // Check if the range is entirely evicted from the scrollback buffer
-        let total_scrolled = self.grid.total_lines_scrolled();
-        let max_sb = self.grid.max_scrollback();
-        if total_scrolled > max_sb {
-            let floor = total_scrolled - max_sb;
-            if abs_end < floor {
-                return None;
-            }
-        }
-
         let mut text = String::new();
         let mut found_any = false;
 
@@ -1763,29 +2220,112 @@ impl Terminal {
         self.sync_state.synchronized_updates
     }
 
-    /// Flush the synchronized update buffer
+    /// Commit the rows changed so far in a synchronized update
+    ///
+    /// They become visible through `get_dirty_rows()` as one frame. The render
+    /// events withheld during the update are emitted first, then a single
+    /// `DirtyRegion` event covering every changed row. With double buffering on, the front buffer is
+    /// swapped too. The mode itself stays set; `CSI ? 2026 l` commits and
+    /// ends the update.
     pub fn flush_synchronized_updates(&mut self) {
-        if !self.sync_state.update_buffer.is_empty() {
-            let buffer = std::mem::take(&mut self.sync_state.update_buffer);
+        let deferred = std::mem::take(&mut self.sync_state.deferred_events);
+        self.events.terminal_events.extend(deferred);
+        if !self.sync_state.pending_dirty_rows.is_empty() {
+            let pending = std::mem::take(&mut self.sync_state.pending_dirty_rows);
             debug::log(
                 debug::DebugLevel::Debug,
                 "SYNC_UPDATE",
-                &format!("Flushing buffer ({} bytes)", buffer.len()),
+                &format!("Committing {} dirty rows", pending.len()),
             );
-            // Process the buffered data without synchronized mode
-            let saved_mode = self.sync_state.synchronized_updates;
-            self.sync_state.sync_update_explicitly_disabled = false;
-            self.sync_state.synchronized_updates = false;
-            self.process(&buffer);
-
-            // Restore only if it was originally enabled and not explicitly disabled
-            if saved_mode
-                && !self.sync_state.sync_update_explicitly_disabled
-                && !self.sync_state.synchronized_updates
-            {
-                self.sync_state.synchronized_updates = true;
+            let first = pending.iter().copied().min().unwrap_or(0);
+            let last = pending.iter().copied().max().unwrap_or(0);
+            self.dirty_rows.extend(pending);
+            self.events
+                .terminal_events
+                .push(TerminalEvent::DirtyRegion(first, last));
+        }
+        self.commit_frame();
+    }
+
+    /// Queue an event that describes what is on screen
+    ///
+    /// During a synchronized update it is withheld until the commit so
+    /// observers never see an intermediate frame. Duplicates are dropped and
+    /// cursor moves are merged into one move from the first position to the
+    /// last.
+    pub(crate) fn push_render_event(&mut self, event: TerminalEvent) {
+        if !self.sync_state.synchronized_updates {
+            self.events.terminal_events.push(event);
+            return;
+        }
+        let deferred = &mut self.sync_state.deferred_events;
+        if let TerminalEvent::CursorMoved { to, cause, .. } = event {
+            let merged = deferred.iter_mut().find_map(|e| match e {
+                TerminalEvent::CursorMoved { to, cause, .. } => Some((to, cause)),
+                _ => None,
+            });
+            if let Some((last_to, last_cause)) = merged {
+                *last_to = to;
+                *last_cause = cause;
+                return;
             }
         }
+        if !deferred.contains(&event) {
+            deferred.push(event);
+        }
+    }
+
+    /// Begin or end a synchronized update (DECSET/DECRST 2026)
+    pub(crate) fn set_synchronized_updates(&mut self, enabled: bool) {
+        if enabled && !self.sync_state.synchronized_updates {
+            self.sync_state.started = Some(crate::time::Instant::now());
+        }
+        self.sync_state.synchronized_updates = enabled;
+        if !enabled {
+            self.sync_state.started = None;
+            self.flush_synchronized_updates();
+        }
+    }
+
+    /// Set how long a synchronized update may stay open, in milliseconds
+    ///
+    /// An application that sends `CSI ? 2026 h` and never ends the update
+    /// would otherwise freeze the screen. Once the timeout has passed, the
+    /// update is committed and ended as if `CSI ? 2026 l` had arrived. 0
+    /// disables the limit. Default: [`DEFAULT_SYNC_UPDATE_TIMEOUT_MS`].
+    pub fn set_synchronized_update_timeout(&mut self, timeout_ms: u64) {
+        self.sync_state.timeout_ms = timeout_ms;
+    }
+
+    /// Get the synchronized update timeout in milliseconds (0 = no limit)
+    pub fn synchronized_update_timeout(&self) -> u64 {
+        self.sync_state.timeout_ms
+    }
+
+    /// End the open synchronized update now if it has timed out.
+    ///
+    /// The timeout is checked before each [`Terminal::process`] call; call
+    /// this from a render timer to also end an update while the application
+    /// is quiet. Returns `true` if an update was ended.
+    pub fn update_synchronized_updates(&mut self) -> bool {
+        self.expire_synchronized_update(crate::time::Instant::now())
+    }
+
+    fn expire_synchronized_update(&mut self, now: crate::time::Instant) -> bool {
+        let timeout_ms = self.sync_state.timeout_ms;
+        let expired = timeout_ms > 0
+            && self.sync_state.started.is_some_and(|started| {
+                now.duration_since(started).as_millis() >= u128::from(timeout_ms)
+            });
+        if expired {
+            debug::log(
+                debug::DebugLevel::Debug,
+                "SYNC_UPDATE",
+                &format!("Update open longer than {timeout_ms} ms, committing"),
+            );
+            self.set_synchronized_updates(false);
+        }
+        expired
     }
 
     /// Get current Sixel resource limits
@@ -1802,6 +2342,40 @@ impl Terminal {
         self.graphics.sixel_limits = sixel::SixelLimits::new(max_width, max_height, max_repeat);
     }
 
+    /// Get the maximum Sixel image size (width, height) in pixels.
+    ///
+    /// This is the geometry applications negotiate with XTSMGRAPHICS
+    /// (`CSI ? 2 ; Pa ; Pv S`), never larger than [`Terminal::sixel_limits`].
+    pub fn sixel_geometry(&self) -> (usize, usize) {
+        let limits = self.graphics.sixel_limits;
+        let (width, height) = self
+            .graphics
+            .sixel_geometry
+            .unwrap_or((limits.max_width, limits.max_height));
+        (width.min(limits.max_width), height.min(limits.max_height))
+    }
+
+    /// Check if each Sixel image starts from the default color registers
+    /// (DEC 1070, set by default). When reset, color registers defined by
+    /// one image carry over to the next.
+    pub fn sixel_private_color_registers(&self) -> bool {
+        self.graphics.sixel_private_color_registers
+    }
+
+    /// Get current ReGIS resource limits
+    pub fn regis_limits(&self) -> regis::RegisLimits {
+        self.graphics.regis_limits
+    }
+
+    /// Set ReGIS resource limits (raster size in pixels, bytes of command
+    /// data per DCS sequence).
+    ///
+    /// Limits are clamped to the hard maxima defined in `regis.rs`. A new
+    /// raster size takes effect with a fresh graphics plane.
+    pub fn set_regis_limits(&mut self, max_width: usize, max_height: usize, max_bytes: usize) {
+        self.graphics.regis_limits = regis::RegisLimits::new(max_width, max_height, max_bytes);
+    }
+
     /// Get cell dimensions in pixels (width, height)
     ///
     /// Used for sixel graphics scroll calculations.
@@ -1874,21 +2448,80 @@ impl Terminal {
         self.keyboard_state.keyboard_flags
     }
 
-    /// Push keyboard flags to stack
+    /// Push keyboard flags to the active screen's stack
+    ///
+    /// The stack holds at most [`KEYBOARD_STACK_MAX`] entries; the oldest is
+    /// evicted when it is full.
     pub fn push_keyboard_flags(&mut self, flags: u16) {
-        self.keyboard_state
-            .keyboard_stack
-            .push(self.keyboard_state.keyboard_flags);
-        self.keyboard_state.keyboard_flags = flags;
+        let current = self.keyboard_state.keyboard_flags;
+        let stack = self.keyboard_stack_mut();
+        if stack.len() >= KEYBOARD_STACK_MAX {
+            stack.remove(0);
+        }
+        stack.push(current);
+        self.keyboard_state.keyboard_flags = flags & KEYBOARD_FLAGS_MASK;
     }
 
-    /// Pop keyboard flags from stack
+    /// Pop keyboard flags from the active screen's stack
+    ///
+    /// Popping more entries than the stack holds empties it and resets the
+    /// flags to 0.
     pub fn pop_keyboard_flags(&mut self, count: usize) {
         for _ in 0..count {
-            if let Some(flags) = self.keyboard_state.keyboard_stack.pop() {
-                self.keyboard_state.keyboard_flags = flags;
+            match self.keyboard_stack_mut().pop() {
+                Some(flags) => self.keyboard_state.keyboard_flags = flags,
+                None => {
+                    self.keyboard_state.keyboard_flags = 0;
+                    break;
+                }
+            }
+        }
+    }
+
+    fn keyboard_stack_mut(&mut self) -> &mut Vec<u16> {
+        if self.alt_screen_active {
+            &mut self.keyboard_state.keyboard_stack_alt
+        } else {
+            &mut self.keyboard_state.keyboard_stack
+        }
+    }
+
+    /// Key encoder for the current keyboard flags and cursor key mode
+    pub fn key_encoder(&self) -> crate::kitty_keyboard::KeyEncoder {
+        crate::kitty_keyboard::KeyEncoder::new(
+            crate::kitty_keyboard::KeyboardFlags::from_bits_truncate(
+                self.keyboard_state.keyboard_flags,
+            ),
+            self.modes.application_cursor,
+        )
+    }
+
+    /// Encode a key event as the bytes the application expects
+    ///
+    /// In win32-input-mode (DEC 9001) the event is sent as Win32 key records,
+    /// and in VT52 mode cursor and PF keys send VT52 codes. Otherwise follows
+    /// the Kitty keyboard protocol flags set by the application and falls
+    /// back to the legacy xterm encoding when no flags are set.
+    pub fn encode_key(&self, event: &crate::kitty_keyboard::KeyEvent) -> Vec<u8> {
+        if self.modes.win32_input_mode {
+            return crate::win32_input::encode_key_event(event);
+        }
+        if self.vt52.active {
+            if let Some(bytes) = vt52::encode_vt52_key(event) {
+                return bytes;
             }
         }
+        self.key_encoder().encode(event)
+    }
+
+    /// Check if win32-input-mode (DEC 9001) is enabled
+    pub fn win32_input_mode(&self) -> bool {
+        self.modes.win32_input_mode
+    }
+
+    /// Set win32-input-mode (for testing/direct control)
+    pub fn set_win32_input_mode(&mut self, enabled: bool) {
+        self.modes.win32_input_mode = enabled;
     }
 
     /// Get insert mode (IRM) state
@@ -1986,6 +2619,35 @@ impl Terminal {
         self.security_state.max_osc_data_length = max;
     }
 
+    /// Maximum bytes buffered for a single DCS sequence. Defaults to
+    /// [`DEFAULT_MAX_DCS_DATA_LENGTH`] (1 MiB); ReGIS has its own ceiling in
+    /// [`Terminal::regis_limits`].
+    pub fn max_dcs_data_length(&self) -> usize {
+        self.security_state.max_dcs_data_length
+    }
+
+    /// Set the maximum bytes buffered for a single DCS sequence.
+    ///
+    /// Bytes past the ceiling are dropped, so an unterminated DCS can never
+    /// grow memory without bound.
+    pub fn set_max_dcs_data_length(&mut self, max: usize) {
+        self.security_state.max_dcs_data_length = max;
+    }
+
+    /// Maximum Kitty graphics APC payload length in bytes. Defaults to
+    /// [`DEFAULT_MAX_APC_DATA_LENGTH`] (128 MiB).
+    pub fn max_apc_data_length(&self) -> usize {
+        self.security_state.max_apc_data_length
+    }
+
+    /// Set the maximum Kitty graphics APC payload length in bytes.
+    ///
+    /// Accumulation stops at the ceiling and the oversized payload is
+    /// discarded once its terminator arrives.
+    pub fn set_max_apc_data_length(&mut self, max: usize) {
+        self.security_state.max_apc_data_length = max;
+    }
+
     /// Get the answerback string sent in response to ENQ (0x05)
     pub fn answerback_string(&self) -> Option<&str> {
         self.title_state.answerback_string.as_deref()
@@ -1997,6 +2659,8 @@ impl Terminal {
     /// an ENQ (enquiry, ASCII 0x05) character. This was historically used for
     /// terminal identification in multi-terminal environments.
     ///
+    /// The string is a user setting, so it is kept across RIS.
+    ///
     /// # Security Note
     /// Default is None (disabled) for security. Setting this may expose
     /// terminal identification information to applications.
@@ -2025,18 +2689,40 @@ impl Terminal {
     /// # Arguments
     /// * `config` - The new width configuration to use
     pub fn set_width_config(&mut self, config: crate::unicode_width_config::WidthConfig) {
+        self.set_ambiguous_width(config.ambiguous_width);
         self.unicode_state.width_config = config;
     }
 
+    /// Get the ambiguous width setting
+    pub fn ambiguous_width(&self) -> crate::unicode_width_config::AmbiguousWidth {
+        self.unicode_state.width_config.ambiguous_width
+    }
+
     /// Set the ambiguous width setting
     ///
     /// Convenience method to change only the ambiguous width treatment
-    /// without modifying other width configuration settings.
+    /// without modifying other width configuration settings. Only text
+    /// written afterwards is affected. If the application enabled mode 7700
+    /// it is sent the ambiguous width report; applications can also probe the
+    /// width by printing an ambiguous character and requesting the cursor
+    /// position (`CSI 6 n`).
     ///
     /// # Arguments
     /// * `width` - The ambiguous width setting (Narrow or Wide)
     pub fn set_ambiguous_width(&mut self, width: crate::unicode_width_config::AmbiguousWidth) {
+        if width == self.unicode_state.width_config.ambiguous_width {
+            return;
+        }
         self.unicode_state.width_config.ambiguous_width = width;
+        if self.modes.ambiguous_width_reports {
+            self.report_ambiguous_width();
+        }
+    }
+
+    /// Send the ambiguous width report (`CSI 1 W` narrow, `CSI 2 W` wide)
+    pub(crate) fn report_ambiguous_width(&mut self) {
+        let response = format!("\x1b[{}W", self.ambiguous_width().width());
+        self.push_response(response.as_bytes());
     }
 
     /// Set the Unicode version for width calculations
@@ -2050,6 +2736,41 @@ impl Terminal {
         self.unicode_state.width_config.unicode_version = version;
     }
 
+    /// Set the width policy negotiated with applications
+    ///
+    /// Selects the Unicode version used for width tables and how grapheme
+    /// clusters are handled: negotiable through DEC private mode 2027, or
+    /// fixed on/off (reported to DECRQM as permanently set/reset).
+    pub fn set_unicode_width_policy(
+        &mut self,
+        policy: crate::unicode_width_config::UnicodeWidthPolicy,
+    ) {
+        self.unicode_state.width_config.unicode_version = policy.unicode_version;
+        self.unicode_state.grapheme_clustering = policy.grapheme_clustering;
+    }
+
+    /// Get the current width policy
+    pub fn unicode_width_policy(&self) -> crate::unicode_width_config::UnicodeWidthPolicy {
+        crate::unicode_width_config::UnicodeWidthPolicy::new(
+            self.unicode_state.width_config.unicode_version,
+            self.unicode_state.grapheme_clustering,
+        )
+    }
+
+    /// Whether grapheme clusters are currently measured as a unit
+    ///
+    /// This is the effective state: the mode 2027 setting when the policy is
+    /// negotiable, otherwise the policy's fixed value. When false, each code
+    /// point with a non-zero width starts a new cell (legacy wcwidth behavior).
+    pub fn grapheme_cluster_mode(&self) -> bool {
+        use crate::unicode_width_config::GraphemeClustering;
+        match self.unicode_state.grapheme_clustering {
+            GraphemeClustering::Negotiable => self.modes.grapheme_clusters,
+            GraphemeClustering::Always => true,
+            GraphemeClustering::Never => false,
+        }
+    }
+
     /// Calculate the display width of a character using current config
     ///
     /// This uses the terminal's width configuration to determine
@@ -2300,16 +3021,18 @@ impl Terminal {
     }
 
     /// Get the grid with scrollback applied (for screenshots/export)
-    fn grid_with_scrollback(&self, scrollback_offset: usize) -> Grid {
+    pub(crate) fn grid_with_scrollback(&self, scrollback_offset: usize) -> Grid {
         let grid = self.active_grid();
         let (cols, rows) = self.size();
         let scrollback_len = grid.scrollback_len();
 
         let mut view = Grid::new(cols, rows, 0); // No scrollback needed for the view
+        let mut wrapped = vec![false; rows];
 
-        for row in 0..rows {
+        for (row, row_wrapped) in wrapped.iter_mut().enumerate() {
             let abs_row = (scrollback_len + row).saturating_sub(scrollback_offset);
             if abs_row < scrollback_len {
+                *row_wrapped = grid.is_scrollback_wrapped(abs_row);
                 if let Some(line) = grid.scrollback_line(abs_row) {
                     for (col, cell) in line.iter().enumerate() {
                         view.set(col, row, cell.clone());
@@ -2318,6 +3041,7 @@ impl Terminal {
             } else {
                 let grid_row = abs_row - scrollback_len;
                 if grid_row < rows {
+                    *row_wrapped = grid.is_line_wrapped(grid_row);
                     if let Some(line) = grid.row(grid_row) {
                         for (col, cell) in line.iter().enumerate() {
                             view.set(col, row, cell.clone());
@@ -2327,6 +3051,11 @@ impl Terminal {
             }
         }
 
+        if self.redaction.enabled {
+            let first_row = scrollback_len.saturating_sub(scrollback_offset);
+            let in_block = !self.alt_screen_active && self.key_block_open_at(first_row);
+            self.redact_view(&mut view, &wrapped, in_block);
+        }
         view
     }
 
@@ -2356,18 +3085,43 @@ impl Terminal {
         config.use_bold_color = self.theme.use_bold_color;
         config.bold_brightening = self.modes.bold_brightening;
         config.faint_text_alpha = self.theme.faint_text_alpha;
+        if config.minimum_contrast_ratio.is_none() {
+            config.minimum_contrast_ratio = Some(self.theme.minimum_contrast_ratio);
+        }
+        if config.smart_cursor_color.is_none() {
+            config.smart_cursor_color = Some(self.theme.smart_cursor_color);
+        }
+        if config.cursor_guide.is_none() {
+            config.cursor_guide = Some(self.theme.use_cursor_guide);
+        }
+        if config.cursor_guide_color.is_none() {
+            config.cursor_guide_color = Some(self.theme.cursor_guide_color.to_rgb());
+        }
+        if config.render_badge {
+            if config.badge_text.is_none() {
+                config.badge_text = self.badge();
+            }
+            if config.badge_color.is_none() {
+                config.badge_color = Some(self.theme.badge_color.to_rgb());
+            }
+        }
 
         // Use terminal's default background if not specified
         if config.background_color.is_none() {
             config.background_color = Some(self.theme.default_bg.to_rgb());
         }
 
-        let grid = self.grid_with_scrollback(scrollback_offset);
-        let cursor = if config.render_cursor && scrollback_offset == 0 {
-            Some(&self.cursor)
-        } else {
-            None
+        let mut grid = self.grid_with_scrollback(scrollback_offset);
+        let overlays = overlays::ExportOverlays {
+            selection: config.render_selection,
+            search_matches: config.render_search_matches,
         };
+        self.apply_export_overlays(&mut grid, scrollback_offset, overlays);
+        let mut cursor = ((config.render_cursor || config.cursor_guide == Some(true))
+            && scrollback_offset == 0)
+            .then_some(self.cursor);
+        self.apply_bidi_to_view(&mut grid, cursor.as_mut().map(|c| (c.row, &mut c.col)));
+        let cursor = cursor.as_ref();
         let graphics = if config.sixel_render_mode != crate::screenshot::SixelRenderMode::Disabled
             && scrollback_offset == 0
         {
@@ -2395,18 +3149,43 @@ impl Terminal {
         config.use_bold_color = self.theme.use_bold_color;
         config.bold_brightening = self.modes.bold_brightening;
         config.faint_text_alpha = self.theme.faint_text_alpha;
+        if config.minimum_contrast_ratio.is_none() {
+            config.minimum_contrast_ratio = Some(self.theme.minimum_contrast_ratio);
+        }
+        if config.smart_cursor_color.is_none() {
+            config.smart_cursor_color = Some(self.theme.smart_cursor_color);
+        }
+        if config.cursor_guide.is_none() {
+            config.cursor_guide = Some(self.theme.use_cursor_guide);
+        }
+        if config.cursor_guide_color.is_none() {
+            config.cursor_guide_color = Some(self.theme.cursor_guide_color.to_rgb());
+        }
+        if config.render_badge {
+            if config.badge_text.is_none() {
+                config.badge_text = self.badge();
+            }
+            if config.badge_color.is_none() {
+                config.badge_color = Some(self.theme.badge_color.to_rgb());
+            }
+        }
 
         // Use terminal's default background if not specified
         if config.background_color.is_none() {
             config.background_color = Some(self.theme.default_bg.to_rgb());
         }
 
-        let grid = self.grid_with_scrollback(scrollback_offset);
-        let cursor = if config.render_cursor && scrollback_offset == 0 {
-            Some(&self.cursor)
-        } else {
-            None
+        let mut grid = self.grid_with_scrollback(scrollback_offset);
+        let overlays = overlays::ExportOverlays {
+            selection: config.render_selection,
+            search_matches: config.render_search_matches,
         };
+        self.apply_export_overlays(&mut grid, scrollback_offset, overlays);
+        let mut cursor = ((config.render_cursor || config.cursor_guide == Some(true))
+            && scrollback_offset == 0)
+            .then_some(self.cursor);
+        self.apply_bidi_to_view(&mut grid, cursor.as_mut().map(|c| (c.row, &mut c.col)));
+        let cursor = cursor.as_ref();
         let graphics = if config.sixel_render_mode != crate::screenshot::SixelRenderMode::Disabled
             && scrollback_offset == 0
         {
@@ -2417,14 +3196,14 @@ impl Terminal {
         crate::screenshot::save_grid(&grid, cursor, graphics, path, config)
     }
 
-    /// Drain and return pending responses
+    /// Drain and return pending responses, query replies before bulk ones
     pub fn drain_responses(&mut self) -> Vec<u8> {
-        std::mem::take(&mut self.response_buffer)
+        self.reply_queue.drain()
     }
 
     /// Check if there are pending responses
     pub fn has_pending_responses(&self) -> bool {
-        !self.response_buffer.is_empty()
+        !self.reply_queue.is_empty()
     }
 
     /// Get the URL for a hyperlink ID
@@ -2482,11 +3261,27 @@ impl Terminal {
     /// did not request more chunks via `m=1`), [`KittyParser::build_graphic`]
     /// commits the result into [`GraphicsStore`]. Errors during parsing
     /// reset the Kitty parser and discard the payload — they never panic.
+    ///
+    /// The remainder then passes the OSC length guard, which cancels OSC
+    /// strings longer than `max_osc_data_length` before vte buffers them,
+    /// and the escape string watchdog when it is enabled.
     fn filter_apc_and_advance(&mut self, data: &[u8]) {
-        // Fast path (ARC-008): if the APC filter is idle and this chunk has no
-        // ESC byte, no APC sequence can begin here — feed the bytes straight to
-        // vte, skipping the passthrough copy and the filter pass entirely.
-        if self.apc_filter_state == ApcFilterState::Outside && !data.contains(&0x1b) {
+        let now = self
+            .escape_watchdog
+            .config
+            .enabled
+            .then(crate::time::Instant::now);
+        let timed_out = now.is_some_and(|now| self.escape_watchdog.expired(now));
+
+        // Fast path (ARC-008): if both filters are idle and this chunk has no
+        // ESC byte, no APC or OSC sequence can begin here — feed the bytes
+        // straight to vte, skipping the passthrough copy and the filter passes.
+        // VT52 mode has no APC or OSC, and its `ESC ]` / `ESC _` must reach
+        // the VT52 decoder untouched.
+        if self.apc_filter_state == ApcFilterState::Outside
+            && self.osc_guard_state.is_idle()
+            && (self.vt52.active || !data.contains(&0x1b))
+        {
             self.advance_parser(data);
             return;
         }
@@ -2504,6 +3299,7 @@ impl Terminal {
             &mut self.apc_buffer,
             data,
             &mut passthrough,
+            self.security_state.max_apc_data_length,
             |apc| {
                 completed_payloads.push(apc.payload.to_vec());
             },
@@ -2527,7 +3323,7 @@ impl Terminal {
                 }
                 Ok(false) => {
                     // Final chunk: handle query (a=q) inline by emitting an
-                    // APC OK reply on response_buffer; non-query actions go to
+                    // APC OK reply on the reply queue; non-query actions go to
                     // build_graphic. See Kitty TGP spec, "Querying support".
                     if self.kitty_parser.action == crate::graphics::kitty::KittyAction::Query {
                         if self.kitty_parser.quietness < 2 {
@@ -2535,7 +3331,7 @@ impl Terminal {
                                 Some(id) => format!("\x1b_Gi={};OK\x1b\\", id),
                                 None => "\x1b_G;OK\x1b\\".to_string(),
                             };
-                            self.response_buffer.extend_from_slice(response.as_bytes());
+                            self.push_response(response.as_bytes());
                         }
                         self.kitty_parser.reset();
                     } else {
@@ -2554,12 +3350,46 @@ impl Terminal {
             }
         }
 
+        let mut report = osc_guard::GuardReport::default();
+        osc_guard::filter(
+            &mut self.osc_guard_state,
+            &mut passthrough,
+            osc_guard::GuardLimits {
+                max_osc_len: self.security_state.max_osc_data_length,
+                watchdog_len: self.escape_watchdog.byte_limit(),
+            },
+            timed_out,
+            &mut report,
+        );
+        if let Some(now) = now {
+            if !self.osc_guard_state.in_string() {
+                self.escape_watchdog.started = None;
+            } else if report.began || self.escape_watchdog.started.is_none() {
+                self.escape_watchdog.started = Some(now);
+            }
+        }
+
         // Feed the non-APC byte stream to vte, then return the (capacity-reused)
-        // buffer to the field for the next call.
-        if !passthrough.is_empty() {
-            self.advance_parser(&passthrough);
+        // buffer to the field for the next call. vte dispatches a cut OSC
+        // string (or unhooks a cut DCS) on the CAN at each cut, so that byte
+        // is fed on its own with the dispatch flagged for discarding.
+        let mut start = 0;
+        for (cut, kind) in report.cuts {
+            self.advance_parser(&passthrough[start..cut]);
+            match kind {
+                EscapeStringKind::Osc => self.osc_cut_pending = true,
+                EscapeStringKind::Dcs => self.dcs_cut_pending = true,
+            }
+            self.advance_parser(&passthrough[cut..=cut]);
+            self.osc_cut_pending = false;
+            self.dcs_cut_pending = false;
+            start = cut + 1;
         }
+        self.advance_parser(&passthrough[start..]);
         self.apc_passthrough = passthrough;
+        if !report.aborts.is_empty() {
+            self.report_escape_aborts(report.aborts);
+        }
     }
 
     /// Feed bytes to the vte parser. `vte::Parser::advance` needs `&mut self`
@@ -2570,8 +3400,21 @@ impl Terminal {
             return;
         }
         let mut parser = std::mem::replace(&mut self.parser, vte::Parser::new());
-        parser.advance(self, bytes);
+        // The parser stops after CSI 5 i so the rest of the chunk can be
+        // diverted to the printer until CSI 4 i, and after CSI ? 2 l so the
+        // VT52 decoder takes over until ESC <
+        let mut rest = bytes;
+        while !rest.is_empty() {
+            if self.printer.controller_active {
+                rest = &rest[self.divert_to_printer(rest)..];
+            } else if self.vt52.active {
+                rest = &rest[self.advance_vt52(&mut parser, rest)..];
+            } else {
+                rest = &rest[parser.advance_until_terminated(self, rest)..];
+            }
+        }
         self.parser = parser;
+        self.flush_print_run();
     }
 
     /// Process incoming data from the PTY
@@ -2579,18 +3422,42 @@ impl Terminal {
         if self.recording_state.is_recording {
             self.record_event(RecordingEventType::Output, data.to_vec());
         }
-
-        if self.sync_state.synchronized_updates {
-            // Buffer data instead of processing it immediately
-            self.sync_state.update_buffer.extend_from_slice(data);
-
-            // Peek at the end of the buffer to see if it contains the disable sequence
-            // We check the last 32 bytes to account for sequences split across chunks
-            let peek_len = 32.min(self.sync_state.update_buffer.len());
-            let peek_start = self.sync_state.update_buffer.len() - peek_len;
-            if contains_bytes(&self.sync_state.update_buffer[peek_start..], b"\x1b[?2026l") {
-                self.flush_synchronized_updates();
-            }
+        self.process_unrecorded(data);
+    }
+
+    /// Process output generated locally rather than read from the PTY, such
+    /// as a status notice, without adding it to the session recording
+    pub(crate) fn process_unrecorded(&mut self, data: &[u8]) {
+        self.record_activity(data);
+        let len = data.len();
+        self.bytes_processed += len as u64;
+        let now = crate::time::Instant::now();
+        if !data.is_empty() {
+            self.profiling.latency.output(now);
+        }
+        self.expire_synchronized_update(now);
+        // The screen is unchanged since the last chunk, so a prompt left at
+        // the cursor then can be checked against the idle time now
+        self.detect_heuristic_prompt(now);
+
+        // Printer controller mode owns the stream until CSI 4 i, so data
+        // arriving mid-job skips the APC/OSC pre-filters
+        let data = if self.printer.controller_active {
+            let diverted = self.divert_to_printer(data);
+            self.utf8_guard.skip(diverted);
+            &data[diverted..]
+        } else {
+            data
+        };
+        let sanitized = self.sanitize_utf8(data);
+        let data: &[u8] = &sanitized;
+
+        if self.log_follower.is_some() {
+            self.advance_log(data);
+            self.commit_frame_if_idle();
+            self.dispatch_events();
+            self.fan_out_subscriptions();
+            self.cap_terminal_events();
             return;
         }
 
@@ -2615,7 +3482,26 @@ impl Terminal {
             self.filter_apc_and_advance(data);
         }
 
+        self.note_cursor_motion(CursorMoveCause::Text);
+        self.promote_detected_urls();
+        self.maybe_checkpoint(len);
+        self.enforce_scrollback_policy();
+        self.sync_command_rows();
+        self.scan_for_secrets();
+        self.advance_heuristic_prompt();
+        self.commit_frame_if_idle();
+        self.dispatch_events();
+        self.fan_out_subscriptions();
+        self.cap_terminal_events();
+    }
+
+    /// Queue an event raised outside `process()` (e.g. by the PTY session)
+    /// and deliver it to observers and subscribers right away
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn emit_event(&mut self, event: TerminalEvent) {
+        self.events.terminal_events.push(event);
         self.dispatch_events();
+        self.fan_out_subscriptions();
         self.cap_terminal_events();
     }
 
@@ -2636,6 +3522,57 @@ impl Terminal {
         self.events.terminal_events.drain(..excess);
         self.events.events_dispatched_up_to =
             self.events.events_dispatched_up_to.saturating_sub(excess);
+        self.events.events_fanned_out_up_to =
+            self.events.events_fanned_out_up_to.saturating_sub(excess);
+    }
+
+    /// Copy events not yet seen by subscribers into each matching
+    /// subscriber queue. Runs before anything drains `terminal_events` so
+    /// subscribers receive every event regardless of who polls the shared
+    /// queue first.
+    fn fan_out_subscriptions(&mut self) {
+        let start = self.events.events_fanned_out_up_to;
+        let events = &self.events.terminal_events;
+        if !self.events.subscribers.is_empty() && start < events.len() {
+            for event in &events[start..] {
+                for sub in &mut self.events.subscribers {
+                    if sub.mask.matches(event) {
+                        if sub.queue.len() >= MAX_TERMINAL_EVENTS {
+                            sub.queue.pop_front();
+                        }
+                        sub.queue.push_back(event.clone());
+                    }
+                }
+            }
+        }
+        self.events.events_fanned_out_up_to = events.len();
+    }
+
+    /// Take the shared event queue after fanning out to subscribers
+    fn take_terminal_events(&mut self) -> Vec<TerminalEvent> {
+        self.fan_out_subscriptions();
+        self.events.events_fanned_out_up_to = 0;
+        std::mem::take(&mut self.events.terminal_events)
+    }
+
+    /// Put back events left over by a partial drain (all already fanned out)
+    fn restore_terminal_events(&mut self, remaining: Vec<TerminalEvent>) {
+        self.events.events_fanned_out_up_to = remaining.len();
+        self.events.terminal_events = remaining;
+    }
+
+    /// Queue `ZoneScrolledOut` events for zones evicted from scrollback
+    fn queue_evicted_zone_events(&mut self) {
+        let evicted = self.grid.drain_evicted_zones();
+        let alt_evicted = self.alt_grid.drain_evicted_zones();
+        for zone in evicted.into_iter().chain(alt_evicted) {
+            self.events
+                .terminal_events
+                .push(TerminalEvent::ZoneScrolledOut {
+                    zone_id: zone.id,
+                    zone_type: zone.zone_type,
+                });
+        }
     }
 
     /// Dispatch pending events to all registered observers.
@@ -2700,26 +3637,168 @@ impl Terminal {
 
         // Save current tab stops
         let tab_stops = self.tab_stops.clone();
+        // The embedder's sequence filter, unsupported-sequence capture and
+        // UTF-8 telemetry must outlive an application's RIS
+        let sequence_filter = self.sequence_filter.take();
+        let unsupported_sequences = std::mem::take(&mut self.unsupported_sequences);
+        let utf8_guard = std::mem::take(&mut self.utf8_guard);
+        // Log follower mode is chosen at construction
+        let log_follower = self.log_follower.take();
+        // Output statistics describe the session, not the screen
+        let activity = std::mem::take(&mut self.activity);
+        // Replies queued before the RIS are still owed to the host
+        let reply_queue = std::mem::take(&mut self.reply_queue);
+        // ReGIS resource limits are set by the embedder
+        let regis_limits = self.graphics.regis_limits;
+        // Image/text layering, the copy join mode, following the tail, URL
+        // autolinking and the cursor trail are frontend settings, not
+        // application state
+        let text_overwrite = self.graphics.text_overwrite;
+        let join_mode = self.join_mode;
+        let follow_tail = self.is_following_tail();
+        let url_autolink = self.autolink.enabled;
+        let cursor_trail_enabled = self.cursor_trail.enabled;
+        let max_cursor_motion_history = self.cursor_trail.max_history;
+        // Scrollback limits are the embedder's, like redaction below
+        let scrollback_policy = self.grid.scrollback_policy();
+        let trim_policy = self.grid.trim_policy();
+        let scrollback_compression = self.grid.scrollback_compression();
+        let search_index = self.grid.scrollback_search_index_enabled();
+        let download_sink = self.graphics.file_transfer_manager.take_sink();
+        let upload_provider = self.graphics.file_transfer_manager.take_upload_provider();
+        let printer_sink = self.printer.take_sink();
+        // Redaction is the embedder's policy, not application state
+        let mut redaction = std::mem::take(&mut self.redaction);
+        redaction.restart_scan();
+        // Cleared screens stay restorable across a reset
+        let clear_undo = std::mem::take(&mut self.clear_undo);
+        // So does the time-travel history, which is the embedder's record
+        let checkpoints = std::mem::take(&mut self.checkpoints);
+        let bytes_processed = self.bytes_processed;
+        // Widths must keep matching the frontend's; mode 2027 is reset
+        let width_config = self.unicode_state.width_config;
+        let grapheme_clustering = self.unicode_state.grapheme_clustering;
+        // Bidi is enabled by the embedder; BDSM and SCP are reset
+        let bidi_enabled = self.bidi.enabled;
+        let escape_watchdog = self.escape_watchdog.config;
+        let sync_update_timeout = self.sync_state.timeout_ms;
+        // Payload ceilings are a safeguard the host program must not undo
+        let max_dcs_data_length = self.security_state.max_dcs_data_length;
+        let max_apc_data_length = self.security_state.max_apc_data_length;
+        // The answerback message is configured by the user, not the host
+        let answerback_string = self.title_state.answerback_string.take();
+        // So is the local hostname that OSC 7 reports are compared against
+        let local_hostname = self.shell_state.local_hostname.take();
+        // Quiet hours and window focus are reported by the embedder
+        let notification_schedule =
+            std::mem::take(&mut self.notifications_state.notification_config.schedule);
+        let window_focused = self.notifications_state.window_focused;
+        // Double buffering is the renderer's choice; the reset screen is
+        // committed at the end of the chunk that carried the RIS
+        let double_buffer = std::mem::take(&mut self.double_buffer);
+        // The background belongs to the window, and its policy to the embedder
+        let background = std::mem::take(&mut self.background);
+        // The appearance follows the host OS, not the application
+        let appearance = self.theme.appearance;
+        let dark_theme = self.theme.dark_theme.take();
+        let light_theme = self.theme.light_theme.take();
+        let minimum_contrast_ratio = self.theme.minimum_contrast_ratio;
+        // Prompt heuristics are configured by the embedder; the detection
+        // phase starts over with the cleared screen
+        let prompt_detection_config = std::mem::take(&mut self.prompt_detection.config);
+        let prompt_regexes = std::mem::take(&mut self.prompt_detection.regexes);
+        // Text awaiting an expect match was printed before the reset and is
+        // still owed to the script waiting on it
+        let expect_buffer = self.expect_buffer.take();
+        // Subscriptions belong to the embedder; events raised before the
+        // RIS are delivered to them first
+        self.fan_out_subscriptions();
+        let subscribers = std::mem::take(&mut self.events.subscribers);
+        let next_subscription_id = self.events.next_subscription_id;
 
         *self = Self::with_scrollback(cols, rows, scrollback);
 
         // Restore tab stops
         self.tab_stops = tab_stops;
+        self.sequence_filter = sequence_filter;
+        self.unsupported_sequences = unsupported_sequences;
+        self.utf8_guard = utf8_guard;
+        self.log_follower = log_follower;
+        self.activity = activity;
+        self.reply_queue = reply_queue;
+        self.graphics.text_overwrite = text_overwrite;
+        self.graphics.regis_limits = regis_limits;
+        self.join_mode = join_mode;
+        self.set_follow_tail(follow_tail);
+        self.autolink.enabled = url_autolink;
+        self.cursor_trail.enabled = cursor_trail_enabled;
+        self.cursor_trail.max_history = max_cursor_motion_history;
+        self.grid.set_scrollback_policy(scrollback_policy);
+        self.grid.set_trim_policy(trim_policy);
+        self.grid.set_scrollback_compression(scrollback_compression);
+        self.grid.set_scrollback_search_index(search_index);
+        self.graphics.file_transfer_manager.set_sink(download_sink);
+        self.graphics
+            .file_transfer_manager
+            .set_upload_provider(upload_provider);
+        self.printer.set_sink(printer_sink);
+        self.redaction = redaction;
+        self.clear_undo = clear_undo;
+        self.checkpoints = checkpoints;
+        self.bytes_processed = bytes_processed;
+        self.unicode_state.width_config = width_config;
+        self.unicode_state.grapheme_clustering = grapheme_clustering;
+        self.bidi.enabled = bidi_enabled;
+        self.escape_watchdog.config = escape_watchdog;
+        self.sync_state.timeout_ms = sync_update_timeout;
+        self.security_state.max_dcs_data_length = max_dcs_data_length;
+        self.security_state.max_apc_data_length = max_apc_data_length;
+        self.title_state.answerback_string = answerback_string;
+        self.shell_state.local_hostname = local_hostname;
+        self.notifications_state.notification_config.schedule = notification_schedule;
+        self.notifications_state.window_focused = window_focused;
+        self.double_buffer = double_buffer;
+        self.background = background;
+        self.theme.appearance = appearance;
+        self.theme.dark_theme = dark_theme;
+        self.theme.light_theme = light_theme;
+        self.theme.minimum_contrast_ratio = minimum_contrast_ratio;
+        self.prompt_detection.config = prompt_detection_config;
+        self.prompt_detection.regexes = prompt_regexes;
+        self.expect_buffer = expect_buffer;
+        self.events.subscribers = subscribers;
+        self.events.next_subscription_id = next_subscription_id;
+        if let Some(theme) = self.appearance_theme(appearance).cloned() {
+            self.apply_theme(&theme);
+        }
     }
 
     /// Mark a row as dirty (needs redrawing)
+    ///
+    /// During a synchronized update the row is held back until the update
+    /// is committed.
     pub fn mark_row_dirty(&mut self, row: usize) {
-        self.dirty_rows.insert(row);
+        if self.sync_state.synchronized_updates {
+            self.sync_state.pending_dirty_rows.insert(row);
+        } else {
+            self.dirty_rows.insert(row);
+        }
 
         // If we have triggers, also add to pending trigger rows
         if self.triggers.trigger_registry.has_active_triggers() {
             self.triggers.pending_trigger_rows.insert(row);
         }
+        self.mark_autolink_row(row);
     }
 
     /// Mark the entire screen as clean
+    ///
+    /// Frontends call this once they have drawn the dirty rows, so it also
+    /// closes the output → render latency sample (see
+    /// [`Terminal::get_latency_metrics`]).
     pub fn mark_clean(&mut self) {
         self.dirty_rows.clear();
+        self.profiling.latency.render(crate::time::Instant::now());
     }
 
     /// Get all dirty rows
@@ -2790,28 +3869,10 @@ impl Terminal {
 
     /// Poll for pending events
     pub fn poll_events(&mut self) -> Vec<TerminalEvent> {
-        // Drain evicted zones and emit ZoneScrolledOut events
-        let evicted = self.grid.drain_evicted_zones();
-        for zone in evicted {
-            self.events
-                .terminal_events
-                .push(TerminalEvent::ZoneScrolledOut {
-                    zone_id: zone.id,
-                    zone_type: zone.zone_type,
-                });
-        }
-        // Also check alt grid
-        let alt_evicted = self.alt_grid.drain_evicted_zones();
-        for zone in alt_evicted {
-            self.events
-                .terminal_events
-                .push(TerminalEvent::ZoneScrolledOut {
-                    zone_id: zone.id,
-                    zone_type: zone.zone_type,
-                });
-        }
+        // Drain evicted zones (primary and alt grid) and emit ZoneScrolledOut events
+        self.queue_evicted_zone_events();
         self.events.events_dispatched_up_to = 0;
-        std::mem::take(&mut self.events.terminal_events)
+        self.take_terminal_events()
     }
 
     /// Drain pending bell events
@@ -2833,17 +3894,74 @@ impl Terminal {
 
     /// Poll for events that match the current subscription filter
     pub fn poll_subscribed_events(&mut self) -> Vec<TerminalEvent> {
-        if let Some(ref filter) = self.event_subscription {
-            let events = std::mem::take(&mut self.events.terminal_events);
-            let (matched, remaining): (Vec<_>, Vec<_>) =
-                events.into_iter().partition(|e| filter.contains(&e.kind()));
-            self.events.terminal_events = remaining;
-            matched
-        } else {
-            self.poll_events()
+        let Some(filter) = self.event_subscription.take() else {
+            return self.poll_events();
+        };
+        let events = self.take_terminal_events();
+        let (matched, remaining): (Vec<_>, Vec<_>) =
+            events.into_iter().partition(|e| filter.contains(&e.kind()));
+        self.restore_terminal_events(remaining);
+        self.event_subscription = Some(filter);
+        matched
+    }
+
+    /// Register a filtered event subscriber with its own queue
+    ///
+    /// Each subscriber receives a copy of every matching event, independent
+    /// of `poll_events()` and of other subscribers, so several frontend
+    /// components can consume events without racing over the shared
+    /// drain-based queue. Returns an ID for [`Self::poll_subscription`].
+    pub fn subscribe(&mut self, mask: EventMask) -> SubscriptionId {
+        // Events already queued predate the subscription
+        self.fan_out_subscriptions();
+        let id = self.events.next_subscription_id;
+        self.events.next_subscription_id += 1;
+        self.events.subscribers.push(event::EventSubscriber {
+            id,
+            mask,
+            queue: std::collections::VecDeque::new(),
+        });
+        id
+    }
+
+    /// Remove a subscriber, discarding its queued events
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        let before = self.events.subscribers.len();
+        self.events.subscribers.retain(|s| s.id != id);
+        self.events.subscribers.len() != before
+    }
+
+    /// Change the filter of an existing subscriber
+    ///
+    /// Events already in its queue are kept.
+    pub fn set_subscription_mask(&mut self, id: SubscriptionId, mask: EventMask) -> bool {
+        self.fan_out_subscriptions();
+        match self.events.subscribers.iter_mut().find(|s| s.id == id) {
+            Some(sub) => {
+                sub.mask = mask;
+                true
+            }
+            None => false,
         }
     }
 
+    /// Drain the queue of a subscriber (empty for unknown IDs)
+    pub fn poll_subscription(&mut self, id: SubscriptionId) -> Vec<TerminalEvent> {
+        self.queue_evicted_zone_events();
+        self.fan_out_subscriptions();
+        self.events
+            .subscribers
+            .iter_mut()
+            .find(|s| s.id == id)
+            .map(|s| s.queue.drain(..).collect())
+            .unwrap_or_default()
+    }
+
+    /// Get the number of active subscribers
+    pub fn subscription_count(&self) -> usize {
+        self.events.subscribers.len()
+    }
+
     /// Drain `events`, splitting each into either an extracted value (when
     /// `try_extract` returns `Ok`) or a leftover event (`Err`). Powers the
     /// typed `poll_*` methods so they can pull one event kind without
@@ -2865,12 +3983,12 @@ impl Terminal {
 
     /// Poll for CWD change events
     pub fn poll_cwd_events(&mut self) -> Vec<CwdChange> {
-        let events = std::mem::take(&mut self.events.terminal_events);
+        let events = self.take_terminal_events();
         let (cwd_changes, remaining) = Self::extract_terminal_events(events, |event| match event {
             TerminalEvent::CwdChanged(change) => Ok(change),
             other => Err(other),
         });
-        self.events.terminal_events = remaining;
+        self.restore_terminal_events(remaining);
         cwd_changes
     }
 
@@ -2878,19 +3996,19 @@ impl Terminal {
     ///
     /// Returns all pending UploadRequested events and removes them from the queue.
     pub fn poll_upload_requests(&mut self) -> Vec<String> {
-        let events = std::mem::take(&mut self.events.terminal_events);
+        let events = self.take_terminal_events();
         let (upload_formats, remaining) =
             Self::extract_terminal_events(events, |event| match event {
                 TerminalEvent::UploadRequested { format } => Ok(format),
                 other => Err(other),
             });
-        self.events.terminal_events = remaining;
+        self.restore_terminal_events(remaining);
         upload_formats
     }
 
     /// Poll for shell integration events
     pub fn poll_shell_integration_events(&mut self) -> Vec<ShellEvent> {
-        let events = std::mem::take(&mut self.events.terminal_events);
+        let events = self.take_terminal_events();
         let (shell_events, remaining) =
             Self::extract_terminal_events(events, |event| match event {
                 TerminalEvent::ShellIntegrationEvent {
@@ -2902,7 +4020,7 @@ impl Terminal {
                 } => Ok((event_type, command, exit_code, timestamp, cursor_line)),
                 other => Err(other),
             });
-        self.events.terminal_events = remaining;
+        self.restore_terminal_events(remaining);
         shell_events
     }
 
@@ -2912,13 +4030,13 @@ impl Terminal {
     /// `true` means the scrollback was also cleared (ESC[3J), `false` means
     /// only the visible screen was cleared (ESC[2J).
     pub fn poll_screen_cleared_events(&mut self) -> Vec<bool> {
-        let events = std::mem::take(&mut self.events.terminal_events);
+        let events = self.take_terminal_events();
         let (cleared_events, remaining) =
             Self::extract_terminal_events(events, |event| match event {
                 TerminalEvent::ScreenCleared { include_scrollback } => Ok(include_scrollback),
                 other => Err(other),
             });
-        self.events.terminal_events = remaining;
+        self.restore_terminal_events(remaining);
         cleared_events
     }
 
@@ -2973,9 +4091,10 @@ impl Terminal {
         rows
     }
 
-    /// Push bytes to the response buffer (to be sent back to PTY)
+    /// Queue a query reply to be sent back to the PTY
+    /// (see [`Terminal::push_reply`] for other priorities)
     pub fn push_response(&mut self, bytes: &[u8]) {
-        self.response_buffer.extend_from_slice(bytes);
+        self.push_reply(ReplyPriority::Query, bytes);
     }
 
     /// Fill a rectangular region with a character
@@ -2,6 +2,7 @@
 //!
 //! Provides types for session management, window layouts, and pane state.
 
+use crate::cursor::CursorStyle;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -24,6 +25,9 @@ pub struct PaneState {
     pub content: Vec<String>,
     /// Cursor position
     pub cursor: (usize, usize),
+    /// Cursor style (DECSCUSR)
+    #[serde(default)]
+    pub cursor_style: CursorStyle,
     /// Is alternate screen active
     pub alt_screen: bool,
     /// Scrollback position
@@ -105,8 +109,9 @@ impl Terminal {
             env: HashMap::new(), // Environment could be captured if available
             content: self.get_logical_lines(),
             cursor: (self.cursor.col, self.cursor.row),
+            cursor_style: self.cursor.style,
             alt_screen: self.alt_screen_active,
-            scroll_offset: 0,
+            scroll_offset: self.viewport_offset(),
             created_at: crate::terminal::unix_millis(),
             last_activity: crate::terminal::unix_millis(),
         }
@@ -118,6 +123,8 @@ impl Terminal {
         self.set_title(state.title.clone());
         self.cursor.col = state.cursor.0;
         self.cursor.row = state.cursor.1;
+        self.cursor.set_style(state.cursor_style);
+        self.set_viewport_offset(state.scroll_offset);
         self.pane_state = Some(state.clone());
         // In a real implementation, we would also restore grid content
     }
@@ -204,6 +211,7 @@ mod tests {
             env: HashMap::new(),
             content: vec![],
             cursor: (0, 0),
+            cursor_style: CursorStyle::default(),
             alt_screen: false,
             scroll_offset: 0,
             created_at: 0,
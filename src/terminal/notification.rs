@@ -1,5 +1,7 @@
 //! Notification support for OSC 9 and OSC 777 sequences
 
+use std::collections::HashMap;
+
 /// Notification data from OSC 9 or OSC 777 sequences
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Notification {
@@ -53,6 +55,132 @@ pub struct NotificationEvent {
     pub timestamp: u64,
     /// Whether notification was delivered
     pub delivered: bool,
+    /// Why the notification was suppressed (None if it was not suppressed)
+    pub suppressed: Option<SuppressionReason>,
+}
+
+/// Reason a notification was held back by the notification schedule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuppressionReason {
+    /// The notification fell inside a quiet-hours window
+    QuietHours,
+    /// The terminal window had focus and focused suppression is enabled
+    Focused,
+    /// A per-trigger override suppresses this trigger unconditionally
+    TriggerOverride,
+}
+
+/// Per-trigger override of the quiet-hours schedule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleOverride {
+    /// Always deliver, even during quiet hours or while focused
+    AlwaysDeliver,
+    /// Always suppress (recorded for the digest)
+    AlwaysSuppress,
+}
+
+/// A recurring quiet-hours window expressed in local minutes-of-day
+///
+/// Windows where `start_minute > end_minute` wrap past midnight
+/// (e.g. 22:00-07:00). The part after midnight belongs to the previous
+/// day's weekday, so a Friday 22:00-07:00 window covers Saturday morning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuietWindow {
+    /// Window start, minutes since local midnight (0-1439)
+    pub start_minute: u16,
+    /// Window end (exclusive), minutes since local midnight (0-1439)
+    pub end_minute: u16,
+    /// Weekday mask: bit 0 = Monday ... bit 6 = Sunday. 0 means every day.
+    pub weekdays: u8,
+}
+
+impl QuietWindow {
+    /// Create a window active every day
+    pub fn daily(start_minute: u16, end_minute: u16) -> Self {
+        Self {
+            start_minute,
+            end_minute,
+            weekdays: 0,
+        }
+    }
+
+    fn applies_to(&self, weekday: u8) -> bool {
+        self.weekdays == 0 || self.weekdays & (1 << weekday) != 0
+    }
+
+    /// Check whether a local time falls inside this window
+    ///
+    /// `weekday` is 0 = Monday ... 6 = Sunday.
+    pub fn contains(&self, minute_of_day: u16, weekday: u8) -> bool {
+        let (start, end) = (self.start_minute, self.end_minute);
+        if start == end {
+            return false;
+        }
+        if start < end {
+            return self.applies_to(weekday) && (start..end).contains(&minute_of_day);
+        }
+        // Wraps past midnight
+        if minute_of_day >= start {
+            self.applies_to(weekday)
+        } else if minute_of_day < end {
+            self.applies_to((weekday + 6) % 7)
+        } else {
+            false
+        }
+    }
+}
+
+/// Do-not-disturb scheduling rules evaluated before recording a notification
+#[derive(Debug, Clone, Default)]
+pub struct NotificationSchedule {
+    /// Quiet-hours windows (local time)
+    pub quiet_windows: Vec<QuietWindow>,
+    /// Offset of local time from UTC in minutes (e.g. -300 for UTC-5)
+    pub utc_offset_minutes: i32,
+    /// Suppress notifications while the terminal window is focused
+    pub suppress_when_focused: bool,
+    /// Per-trigger overrides that take precedence over windows and focus
+    pub trigger_overrides: HashMap<NotificationTrigger, ScheduleOverride>,
+}
+
+impl NotificationSchedule {
+    /// Whether `timestamp_ms` (unix millis) falls inside a quiet window
+    pub fn is_quiet_at(&self, timestamp_ms: u64) -> bool {
+        if self.quiet_windows.is_empty() {
+            return false;
+        }
+        let local_secs = (timestamp_ms / 1000) as i64 + self.utc_offset_minutes as i64 * 60;
+        let days = local_secs.div_euclid(86_400);
+        let minute_of_day = (local_secs.rem_euclid(86_400) / 60) as u16;
+        // 1970-01-01 was a Thursday (weekday 3 with Monday = 0)
+        let weekday = (days + 3).rem_euclid(7) as u8;
+        self.quiet_windows
+            .iter()
+            .any(|w| w.contains(minute_of_day, weekday))
+    }
+
+    /// Decide whether a notification should be suppressed
+    pub fn suppression_reason(
+        &self,
+        trigger: NotificationTrigger,
+        timestamp_ms: u64,
+        focused: bool,
+    ) -> Option<SuppressionReason> {
+        match self.trigger_overrides.get(&trigger) {
+            Some(ScheduleOverride::AlwaysDeliver) => return None,
+            Some(ScheduleOverride::AlwaysSuppress) => {
+                return Some(SuppressionReason::TriggerOverride)
+            }
+            None => {}
+        }
+        if self.is_quiet_at(timestamp_ms) {
+            Some(SuppressionReason::QuietHours)
+        } else if focused && self.suppress_when_focused {
+            Some(SuppressionReason::Focused)
+        } else {
+            None
+        }
+    }
 }
 
 /// Notification configuration
@@ -72,6 +200,8 @@ pub struct NotificationConfig {
     pub silence_enabled: bool,
     /// Silence threshold (seconds of activity before silence notification)
     pub silence_threshold: u64,
+    /// Quiet-hours / do-not-disturb scheduling rules
+    pub schedule: NotificationSchedule,
 }
 
 impl Default for NotificationConfig {
@@ -84,6 +214,7 @@ impl Default for NotificationConfig {
             activity_threshold: 10,
             silence_enabled: false,
             silence_threshold: 300,
+            schedule: NotificationSchedule::default(),
         }
     }
 }
@@ -100,12 +231,19 @@ impl Terminal {
         alert: NotificationAlert,
         message: Option<String>,
     ) {
+        let timestamp = crate::terminal::unix_millis();
+        let suppressed = self
+            .notifications_state
+            .notification_config
+            .schedule
+            .suppression_reason(trigger, timestamp, self.notifications_state.window_focused);
         let event = NotificationEvent {
             trigger,
             alert,
             message,
-            timestamp: crate::terminal::unix_millis(),
+            timestamp,
             delivered: false,
+            suppressed,
         };
 
         self.notifications_state.notification_events.push(event);
@@ -153,6 +291,50 @@ impl Terminal {
         }
     }
 
+    /// Set the notification schedule (quiet hours, focus rules, overrides)
+    pub fn set_notification_schedule(&mut self, schedule: NotificationSchedule) {
+        self.notifications_state.notification_config.schedule = schedule;
+    }
+
+    /// Tell the terminal whether its window currently has focus
+    ///
+    /// Used by the schedule's `suppress_when_focused` rule.
+    pub fn set_window_focused(&mut self, focused: bool) {
+        self.notifications_state.window_focused = focused;
+    }
+
+    /// Whether quiet hours are in effect right now
+    pub fn is_quiet_hours(&self) -> bool {
+        self.notifications_state
+            .notification_config
+            .schedule
+            .is_quiet_at(crate::terminal::unix_millis())
+    }
+
+    /// Get suppressed notifications that have not yet been delivered as a digest
+    pub fn pending_notification_digest(&self) -> Vec<&NotificationEvent> {
+        self.notifications_state
+            .notification_events
+            .iter()
+            .filter(|e| e.suppressed.is_some() && !e.delivered)
+            .collect()
+    }
+
+    /// Take the pending digest of suppressed notifications
+    ///
+    /// Returned events are marked delivered so each suppressed notification
+    /// appears in exactly one digest.
+    pub fn take_notification_digest(&mut self) -> Vec<NotificationEvent> {
+        let mut digest = Vec::new();
+        for event in self.notifications_state.notification_events.iter_mut() {
+            if event.suppressed.is_some() && !event.delivered {
+                event.delivered = true;
+                digest.push(event.clone());
+            }
+        }
+        digest
+    }
+
     /// Update last activity timestamp
     pub fn update_activity(&mut self) {
         self.notifications_state.last_activity_time = crate::terminal::unix_millis();
@@ -320,6 +502,112 @@ mod tests {
         assert!(notif.message.contains('\n'));
     }
 
+    #[test]
+    fn test_quiet_window_same_day() {
+        let w = QuietWindow::daily(9 * 60, 17 * 60);
+        assert!(w.contains(9 * 60, 0));
+        assert!(w.contains(16 * 60 + 59, 6));
+        assert!(!w.contains(17 * 60, 0));
+        assert!(!w.contains(8 * 60, 0));
+    }
+
+    #[test]
+    fn test_quiet_window_wraps_midnight_with_weekdays() {
+        // Friday (bit 4) 22:00 -> 07:00
+        let w = QuietWindow {
+            start_minute: 22 * 60,
+            end_minute: 7 * 60,
+            weekdays: 1 << 4,
+        };
+        assert!(w.contains(23 * 60, 4)); // Friday night
+        assert!(w.contains(6 * 60, 5)); // Saturday morning
+        assert!(!w.contains(6 * 60, 4)); // Friday morning belongs to Thursday
+        assert!(!w.contains(12 * 60, 5));
+    }
+
+    #[test]
+    fn test_schedule_is_quiet_at_respects_utc_offset() {
+        let schedule = NotificationSchedule {
+            quiet_windows: vec![QuietWindow::daily(0, 60)],
+            utc_offset_minutes: -120,
+            ..Default::default()
+        };
+        // 1970-01-01 01:30 UTC is 23:30 local (not quiet); 02:30 UTC is 00:30 local
+        assert!(!schedule.is_quiet_at(90 * 60 * 1000));
+        assert!(schedule.is_quiet_at(150 * 60 * 1000));
+    }
+
+    #[test]
+    fn test_schedule_overrides_take_precedence() {
+        let mut schedule = NotificationSchedule {
+            quiet_windows: vec![QuietWindow::daily(0, 1439)],
+            suppress_when_focused: true,
+            ..Default::default()
+        };
+        schedule.trigger_overrides.insert(
+            NotificationTrigger::Custom(1),
+            ScheduleOverride::AlwaysDeliver,
+        );
+        schedule
+            .trigger_overrides
+            .insert(NotificationTrigger::Bell, ScheduleOverride::AlwaysSuppress);
+
+        assert_eq!(
+            schedule.suppression_reason(NotificationTrigger::Custom(1), 0, true),
+            None
+        );
+        assert_eq!(
+            schedule.suppression_reason(NotificationTrigger::Bell, 0, false),
+            Some(SuppressionReason::TriggerOverride)
+        );
+        assert_eq!(
+            schedule.suppression_reason(NotificationTrigger::Silence, 0, false),
+            Some(SuppressionReason::QuietHours)
+        );
+    }
+
+    #[test]
+    fn test_focused_suppression_and_digest() {
+        let mut term = Terminal::new(80, 24);
+        term.set_notification_schedule(NotificationSchedule {
+            suppress_when_focused: true,
+            ..Default::default()
+        });
+
+        term.trigger_notification(NotificationTrigger::Bell, NotificationAlert::Visual, None);
+        term.set_window_focused(true);
+        term.trigger_notification(
+            NotificationTrigger::Activity,
+            NotificationAlert::Visual,
+            Some("busy".to_string()),
+        );
+
+        let events = term.get_notification_events();
+        assert_eq!(events[0].suppressed, None);
+        assert_eq!(events[1].suppressed, Some(SuppressionReason::Focused));
+
+        assert_eq!(term.pending_notification_digest().len(), 1);
+        let digest = term.take_notification_digest();
+        assert_eq!(digest.len(), 1);
+        assert_eq!(digest[0].message.as_deref(), Some("busy"));
+        assert!(term.take_notification_digest().is_empty());
+    }
+
+    #[test]
+    fn test_schedule_and_focus_survive_ris() {
+        let mut term = Terminal::new(80, 24);
+        term.set_notification_schedule(NotificationSchedule {
+            suppress_when_focused: true,
+            ..Default::default()
+        });
+        term.set_window_focused(true);
+        term.process(b"\x1bc");
+
+        term.trigger_notification(NotificationTrigger::Bell, NotificationAlert::Visual, None);
+        let events = term.get_notification_events();
+        assert_eq!(events[0].suppressed, Some(SuppressionReason::Focused));
+    }
+
     #[test]
     fn test_notification_with_special_chars() {
         let notif = Notification::new(
@@ -0,0 +1,355 @@
+//! Streaming length guard for OSC strings.
+//!
+//! `vte` 0.15 (with its default `std` feature) accumulates the whole OSC body
+//! in a growable buffer before calling `osc_dispatch`, so the dispatch-time
+//! `max_osc_data_length` check (QA-012) cannot stop an unterminated OSC from
+//! exhausting memory. This guard runs on the byte stream *before* it reaches
+//! `vte`: once an OSC body grows past the limit, the offending byte is
+//! replaced by CAN (`0x18`) to end the string early, and the rest of the body
+//! is swallowed up to its terminator. `vte` still dispatches the truncated
+//! string on CAN, so the caller is told where each cut happened and discards
+//! that dispatch.
+//!
+//! Terminators mirror `vte`'s OSC string state: BEL (`0x07`), CAN/SUB
+//! (`0x18`/`0x1a`) and ESC (the start of `ESC \\`).
+//!
+//! The same pass enforces the byte cap of the unterminated string watchdog
+//! (see `escape_watchdog`), which also tracks DCS strings and, unlike the
+//! length guard, lets the bytes after a cut through to be parsed normally.
+
+use crate::terminal::escape_watchdog::{EscapeAbort, EscapeAbortReason, EscapeStringKind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum OscGuardState {
+    /// Normal byte stream.
+    #[default]
+    Outside,
+    /// Saw `ESC`; an `]` next begins an OSC string (a `P` begins a DCS
+    /// string while the watchdog is enabled).
+    SawEsc,
+    /// Inside an OSC body of the given length so far.
+    InOsc(usize),
+    /// Inside a DCS body of the given length so far (watchdog only).
+    InDcs(usize),
+    /// The OSC body of the given length overflowed; bytes are dropped until
+    /// its terminator.
+    Overflow(usize),
+}
+
+impl OscGuardState {
+    /// Whether the guard is between sequences, so a chunk without any ESC
+    /// byte can bypass it.
+    pub(crate) fn is_idle(self) -> bool {
+        self == OscGuardState::Outside
+    }
+
+    /// Whether an OSC or DCS string is open (including a cut OSC whose body
+    /// is still being dropped).
+    pub(crate) fn in_string(self) -> bool {
+        matches!(
+            self,
+            OscGuardState::InOsc(_) | OscGuardState::InDcs(_) | OscGuardState::Overflow(_)
+        )
+    }
+}
+
+/// Limits applied by [`filter`]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct GuardLimits {
+    /// `max_osc_data_length`: longer OSC bodies are cut and swallowed
+    pub(crate) max_osc_len: usize,
+    /// Watchdog byte cap for OSC and DCS bodies; longer strings are cut and
+    /// the rest of the stream is parsed normally
+    pub(crate) watchdog_len: Option<usize>,
+}
+
+/// What one [`filter`] pass did
+#[derive(Debug, Default)]
+pub(crate) struct GuardReport {
+    /// Offset of each inserted CAN within the filtered data, with the kind of
+    /// string it cancelled
+    pub(crate) cuts: Vec<(usize, EscapeStringKind)>,
+    /// Strings cancelled by the watchdog
+    pub(crate) aborts: Vec<EscapeAbort>,
+    /// Whether an OSC or DCS string began during the pass
+    pub(crate) began: bool,
+}
+
+/// Filters `data` in place, cutting OSC bodies longer than
+/// `limits.max_osc_len` bytes and, while the watchdog is enabled, OSC/DCS
+/// bodies longer than `limits.watchdog_len`.
+///
+/// With `timed_out` set, a string left open by the previous pass is
+/// cancelled before any of `data` is examined.
+pub(crate) fn filter(
+    state: &mut OscGuardState,
+    data: &mut Vec<u8>,
+    limits: GuardLimits,
+    timed_out: bool,
+    report: &mut GuardReport,
+) {
+    if timed_out {
+        let open = match *state {
+            OscGuardState::InOsc(len) => Some((EscapeStringKind::Osc, len, true)),
+            OscGuardState::InDcs(len) => Some((EscapeStringKind::Dcs, len, true)),
+            // vte already left the string at the earlier cut
+            OscGuardState::Overflow(len) => Some((EscapeStringKind::Osc, len, false)),
+            _ => None,
+        };
+        if let Some((kind, length, cancel)) = open {
+            if cancel {
+                data.insert(0, 0x18);
+                report.cuts.push((0, kind));
+            }
+            report.aborts.push(EscapeAbort {
+                kind,
+                reason: EscapeAbortReason::Timeout,
+                length,
+            });
+            *state = OscGuardState::Outside;
+        }
+    }
+
+    let over_watchdog = |len: usize| limits.watchdog_len.is_some_and(|max| len >= max);
+    let mut write = 0;
+    for read in 0..data.len() {
+        let byte = data[read];
+        let keep = match *state {
+            OscGuardState::Outside => {
+                if byte == 0x1b {
+                    *state = OscGuardState::SawEsc;
+                }
+                Some(byte)
+            }
+            OscGuardState::SawEsc => {
+                *state = match byte {
+                    b']' => {
+                        report.began = true;
+                        OscGuardState::InOsc(0)
+                    }
+                    b'P' if limits.watchdog_len.is_some() => {
+                        report.began = true;
+                        OscGuardState::InDcs(0)
+                    }
+                    0x1b => OscGuardState::SawEsc,
+                    _ => OscGuardState::Outside,
+                };
+                Some(byte)
+            }
+            OscGuardState::InOsc(len) => match byte {
+                0x07 | 0x18 | 0x1a => {
+                    *state = OscGuardState::Outside;
+                    Some(byte)
+                }
+                0x1b => {
+                    *state = OscGuardState::SawEsc;
+                    Some(byte)
+                }
+                _ if over_watchdog(len) => {
+                    *state = OscGuardState::Outside;
+                    report.cuts.push((write, EscapeStringKind::Osc));
+                    report.aborts.push(EscapeAbort {
+                        kind: EscapeStringKind::Osc,
+                        reason: EscapeAbortReason::TooLong,
+                        length: len,
+                    });
+                    Some(0x18)
+                }
+                _ if len >= limits.max_osc_len => {
+                    *state = OscGuardState::Overflow(len);
+                    report.cuts.push((write, EscapeStringKind::Osc));
+                    Some(0x18)
+                }
+                _ => {
+                    *state = OscGuardState::InOsc(len + 1);
+                    Some(byte)
+                }
+            },
+            // DCS passthrough ends on CAN/SUB, ESC and 8-bit ST (BEL is data)
+            OscGuardState::InDcs(len) => match byte {
+                0x18 | 0x1a | 0x9c => {
+                    *state = OscGuardState::Outside;
+                    Some(byte)
+                }
+                0x1b => {
+                    *state = OscGuardState::SawEsc;
+                    Some(byte)
+                }
+                _ if over_watchdog(len) => {
+                    *state = OscGuardState::Outside;
+                    report.cuts.push((write, EscapeStringKind::Dcs));
+                    report.aborts.push(EscapeAbort {
+                        kind: EscapeStringKind::Dcs,
+                        reason: EscapeAbortReason::TooLong,
+                        length: len,
+                    });
+                    Some(0x18)
+                }
+                _ => {
+                    *state = OscGuardState::InDcs(len + 1);
+                    Some(byte)
+                }
+            },
+            OscGuardState::Overflow(len) => match byte {
+                0x07 | 0x18 | 0x1a => {
+                    *state = OscGuardState::Outside;
+                    None
+                }
+                0x1b => {
+                    // vte is back in ground state; `ESC \` is a harmless no-op
+                    *state = OscGuardState::SawEsc;
+                    Some(byte)
+                }
+                _ if over_watchdog(len) => {
+                    // Stop swallowing: the rest is parsed as normal output
+                    *state = OscGuardState::Outside;
+                    report.aborts.push(EscapeAbort {
+                        kind: EscapeStringKind::Osc,
+                        reason: EscapeAbortReason::TooLong,
+                        length: len,
+                    });
+                    Some(byte)
+                }
+                _ => {
+                    *state = OscGuardState::Overflow(len + 1);
+                    None
+                }
+            },
+        };
+        if let Some(byte) = keep {
+            data[write] = byte;
+            write += 1;
+        }
+    }
+    data.truncate(write);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the filtered stream and the cut offsets within it
+    fn run(chunks: &[&[u8]], max_len: usize) -> (Vec<u8>, Vec<usize>) {
+        let (out, cuts, _) = run_with(chunks, max_len, None);
+        (out, cuts)
+    }
+
+    /// Like [`run`] with a watchdog cap; also returns the aborts
+    fn run_with(
+        chunks: &[&[u8]],
+        max_len: usize,
+        watchdog_len: Option<usize>,
+    ) -> (Vec<u8>, Vec<usize>, Vec<EscapeAbort>) {
+        let limits = GuardLimits {
+            max_osc_len: max_len,
+            watchdog_len,
+        };
+        let mut state = OscGuardState::Outside;
+        let mut out = Vec::new();
+        let mut all_cuts = Vec::new();
+        let mut aborts = Vec::new();
+        for chunk in chunks {
+            let mut data = chunk.to_vec();
+            let mut report = GuardReport::default();
+            filter(&mut state, &mut data, limits, false, &mut report);
+            all_cuts.extend(report.cuts.iter().map(|(cut, _)| cut + out.len()));
+            aborts.extend(report.aborts);
+            out.extend_from_slice(&data);
+        }
+        (out, all_cuts, aborts)
+    }
+
+    #[test]
+    fn short_osc_passes_through() {
+        let (out, cuts) = run(&[b"a\x1b]0;title\x07b", b"\x1b]2;x\x1b\\"], 16);
+        assert_eq!(out, b"a\x1b]0;title\x07b\x1b]2;x\x1b\\");
+        assert!(cuts.is_empty());
+    }
+
+    #[test]
+    fn oversized_osc_is_cancelled_and_swallowed() {
+        let (out, cuts) = run(&[b"\x1b]0;abcdef", b"ghij\x07after"], 4);
+        assert_eq!(out, b"\x1b]0;ab\x18after");
+        assert_eq!(cuts, vec![6]);
+    }
+
+    #[test]
+    fn overflow_ends_at_st() {
+        let (out, cuts) = run(&[b"\x1b]0;abcdefgh\x1b\\x\x1b]0;abc\x07"], 2);
+        assert_eq!(out, b"\x1b]0;\x18\x1b\\x\x1b]0;\x18");
+        assert_eq!(cuts, vec![4, 12]);
+    }
+
+    #[test]
+    fn limit_counts_across_chunks() {
+        let chunks: Vec<&[u8]> = vec![b"\x1b]", b"0;a", b"b", b"c"];
+        let (out, cuts) = run(&chunks, 3);
+        assert_eq!(out, b"\x1b]0;a\x18");
+        assert_eq!(cuts, vec![5]);
+    }
+
+    #[test]
+    fn other_escapes_are_untouched() {
+        let (out, cuts) = run(&[b"\x1b[31m\x1b\x1b]x\x07"], 0);
+        assert_eq!(out, b"\x1b[31m\x1b\x1b]\x18");
+        assert_eq!(cuts, vec![8]);
+    }
+
+    #[test]
+    fn watchdog_cuts_osc_and_resumes_parsing() {
+        let (out, cuts, aborts) = run_with(&[b"\x1b]0;abcdef", b"ghi"], 1024, Some(4));
+        assert_eq!(out, b"\x1b]0;ab\x18defghi");
+        assert_eq!(cuts, vec![6]);
+        assert_eq!(
+            aborts,
+            vec![EscapeAbort {
+                kind: EscapeStringKind::Osc,
+                reason: EscapeAbortReason::TooLong,
+                length: 4,
+            }]
+        );
+    }
+
+    #[test]
+    fn watchdog_tracks_dcs_only_when_enabled() {
+        let (out, cuts, _) = run_with(&[b"\x1bPqabcdef"], 2, None);
+        assert_eq!(out, b"\x1bPqabcdef");
+        assert!(cuts.is_empty());
+
+        let (out, cuts, aborts) = run_with(&[b"\x1bPq\x07abc", b"def"], 2, Some(5));
+        assert_eq!(out, b"\x1bPq\x07abc\x18ef");
+        assert_eq!(cuts, vec![7]);
+        assert_eq!(aborts[0].kind, EscapeStringKind::Dcs);
+    }
+
+    #[test]
+    fn watchdog_ends_osc_overflow() {
+        let (out, cuts, aborts) = run_with(&[b"\x1b]0;abcdefgh"], 2, Some(6));
+        assert_eq!(out, b"\x1b]0;\x18fgh");
+        assert_eq!(cuts, vec![4]);
+        assert_eq!(aborts.len(), 1);
+    }
+
+    #[test]
+    fn timeout_cancels_open_string() {
+        let limits = GuardLimits {
+            max_osc_len: 1024,
+            watchdog_len: Some(1024),
+        };
+        let mut state = OscGuardState::Outside;
+        let mut data = b"\x1bPqab".to_vec();
+        let mut report = GuardReport::default();
+        filter(&mut state, &mut data, limits, false, &mut report);
+        assert!(report.began);
+        assert!(state.in_string());
+
+        let mut data = b"text".to_vec();
+        let mut report = GuardReport::default();
+        filter(&mut state, &mut data, limits, true, &mut report);
+        assert_eq!(data, b"\x18text");
+        assert_eq!(report.cuts, vec![(0, EscapeStringKind::Dcs)]);
+        assert_eq!(report.aborts[0].reason, EscapeAbortReason::Timeout);
+        assert_eq!(report.aborts[0].length, 3);
+        assert!(state.is_idle());
+    }
+}
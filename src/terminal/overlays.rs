@@ -0,0 +1,115 @@
+//! Selection and search-match overlays for exported screens
+//!
+//! Frontends draw the selection and the active search matches on top of the
+//! cell colors. To make screenshots and HTML exports show the same thing,
+//! the overlays are baked into the exported copy of the screen: selected
+//! cells take the theme's selection colors and matched cells the match
+//! color, with the selection drawn over matches.
+
+use std::collections::HashMap;
+
+use crate::color::Color;
+use crate::grid::Grid;
+use crate::terminal::Terminal;
+
+/// Text color of search matches on the match color, as in iTerm2
+const MATCH_TEXT_COLOR: Color = Color::Rgb(0, 0, 0);
+
+/// Frontend overlays to bake into an exported screen
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExportOverlays {
+    /// Paint selected cells with the theme's selection colors
+    pub selection: bool,
+    /// Paint the matches of the last [`Terminal::search`] with the theme's
+    /// match color
+    pub search_matches: bool,
+}
+
+impl ExportOverlays {
+    /// Both overlays
+    pub fn all() -> Self {
+        Self {
+            selection: true,
+            search_matches: true,
+        }
+    }
+
+    fn any(self) -> bool {
+        self.selection || self.search_matches
+    }
+}
+
+impl Terminal {
+    /// Recolor the cells of `view`, built by `grid_with_scrollback` with the
+    /// same `scrollback_offset`, that are selected or part of a search match
+    pub(crate) fn apply_export_overlays(
+        &self,
+        view: &mut Grid,
+        scrollback_offset: usize,
+        overlays: ExportOverlays,
+    ) {
+        if !overlays.any() {
+            return;
+        }
+        let scrollback_len = self.active_grid().scrollback_len();
+
+        // Match column ranges by absolute row (scrollback first, then screen)
+        let mut matches: HashMap<usize, Vec<(usize, usize)>> = HashMap::new();
+        if overlays.search_matches {
+            for m in &self.search.regex_matches {
+                // Scrollback rows are stored as negative offsets from the screen top
+                let first = scrollback_len as isize + m.row as isize;
+                let last = scrollback_len as isize + m.end_row as isize;
+                for abs in first.max(0)..=last {
+                    let start = if abs == first { m.col } else { 0 };
+                    let end = if abs == last { m.end_col } else { usize::MAX };
+                    matches.entry(abs as usize).or_default().push((start, end));
+                }
+            }
+        }
+
+        let theme = &self.theme;
+        for row in 0..view.rows() {
+            let abs = (scrollback_len + row).saturating_sub(scrollback_offset);
+            let screen_row = abs.checked_sub(scrollback_len);
+            let row_matches = matches.get(&abs).map(Vec::as_slice).unwrap_or_default();
+            for col in 0..view.cols() {
+                let selected = overlays.selection
+                    && screen_row.is_some_and(|screen_row| self.is_cell_selected(col, screen_row));
+                let matched = row_matches
+                    .iter()
+                    .any(|&(start, end)| (start..end).contains(&col));
+                let Some(cell) = view.get_mut(col, row) else {
+                    continue;
+                };
+                if selected {
+                    cell.flags.set_reverse(false);
+                    cell.bg = theme.selection_bg_color;
+                    if theme.use_selected_text_color {
+                        cell.fg = theme.selection_fg_color;
+                    }
+                } else if matched {
+                    cell.flags.set_reverse(false);
+                    cell.bg = theme.match_color;
+                    cell.fg = MATCH_TEXT_COLOR;
+                }
+            }
+        }
+    }
+
+    /// Export the visible screen as styled HTML in the terminal's runtime
+    /// colors (see [`Terminal::export_html_themed`]) with the selected
+    /// overlays painted in, so the page shows what the frontend shows
+    pub fn export_html_with_overlays(
+        &self,
+        include_styles: bool,
+        overlays: ExportOverlays,
+    ) -> String {
+        let mut view = self.grid_with_scrollback(0);
+        self.apply_export_overlays(&mut view, 0, overlays);
+        self.export_grid_html(
+            &view,
+            &crate::html_export::HtmlExportConfig::new().with_styles(include_styles),
+        )
+    }
+}
@@ -5,11 +5,35 @@
 //! Most methods here delegate to specialized handlers in other modules.
 
 use crate::debug;
-use crate::terminal::{BellEvent, Terminal, TerminalEvent};
+use crate::terminal::{BellEvent, Charset, CursorMoveCause, Terminal, TerminalEvent};
 use vte::{Params, Perform};
 
+/// Longest run of buffered ASCII text before it is written out, bounding the
+/// run buffer when a single chunk carries megabytes of plain text
+const MAX_PRINT_RUN: usize = 64 * 1024;
+
 impl Perform for Terminal {
     fn print(&mut self, c: char) {
+        // Plain printable ASCII is coalesced into a run and written in one
+        // pass by `flush_print_run` before the next non-print callback
+        if (' '..='~').contains(&c)
+            && self.active_charset() == Charset::Ascii
+            && self.charset_state.single_shift.is_none()
+        {
+            self.print_run.push(c as u8);
+            if self.print_run.len() >= MAX_PRINT_RUN {
+                self.flush_print_run();
+            }
+            return;
+        }
+        self.flush_print_run();
+        // A combining mark has no cell of its own, so REP has nothing to repeat
+        let width = crate::unicode_width_config::char_width(c, &self.unicode_state.width_config);
+        self.last_graphic_char = (width > 0).then_some(c);
+        if let Some(buffer) = self.expect_buffer.as_mut() {
+            buffer.push_char(c);
+        }
+
         debug::log_print(c, self.cursor.col, self.cursor.row);
 
         // Fast path for the Kitty TGP placeholder character (U+10EEEE):
@@ -37,7 +61,12 @@ impl Perform for Terminal {
     }
 
     fn execute(&mut self, byte: u8) {
+        self.flush_print_run();
+        self.last_graphic_char = None;
         debug::log_execute(byte);
+        if let (Some(buffer), b'\n' | b'\t') = (self.expect_buffer.as_mut(), byte) {
+            buffer.push_char(byte as char);
+        }
         match byte {
             b'\n' => self.write_char('\n'),
             b'\r' => self.write_char('\r'),
@@ -45,9 +74,8 @@ impl Perform for Terminal {
             b'\x08' => self.write_char('\x08'),
             b'\x05' => {
                 // ENQ (Enquiry) - send answerback string if configured
-                if let Some(ref answerback) = self.title_state.answerback_string {
-                    self.response_buffer
-                        .extend_from_slice(answerback.as_bytes());
+                if let Some(answerback) = self.title_state.answerback_string.clone() {
+                    self.push_response(answerback.as_bytes());
                 }
             }
             b'\x07' => {
@@ -79,26 +107,44 @@ impl Perform for Terminal {
     }
 
     fn hook(&mut self, params: &Params, intermediates: &[u8], ignore: bool, action: char) {
+        self.flush_print_run();
         self.dcs_hook(params, intermediates, ignore, action);
     }
 
     fn put(&mut self, byte: u8) {
+        self.flush_print_run();
         self.dcs_put(byte);
     }
 
     fn unhook(&mut self) {
+        self.flush_print_run();
         self.dcs_unhook();
     }
 
     fn osc_dispatch(&mut self, params: &[&[u8]], bell_terminated: bool) {
+        self.flush_print_run();
         self.osc_dispatch_impl(params, bell_terminated);
     }
 
     fn csi_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, action: char) {
+        self.flush_print_run();
+        // Attribute pending text motion before the sequence moves the cursor
+        self.note_cursor_motion(CursorMoveCause::Text);
         self.csi_dispatch_impl(params, intermediates, ignore, action);
+        self.note_cursor_motion(CursorMoveCause::EscapeSequence);
     }
 
     fn esc_dispatch(&mut self, intermediates: &[u8], ignore: bool, byte: u8) {
+        self.flush_print_run();
+        // Attribute pending text motion before the sequence moves the cursor
+        self.note_cursor_motion(CursorMoveCause::Text);
         self.esc_dispatch_impl(intermediates, ignore, byte);
+        self.note_cursor_motion(CursorMoveCause::EscapeSequence);
+    }
+
+    fn terminated(&self) -> bool {
+        // `advance_parser` diverts the rest of the chunk to the printer or
+        // the VT52 decoder
+        self.printer.controller_active || self.vt52.active
     }
 }
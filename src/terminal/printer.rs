@@ -0,0 +1,210 @@
+//! Media Copy (MC) print jobs
+//!
+//! The terminal never prints. Printer controller mode (`CSI 5 i` ... `CSI 4 i`)
+//! diverts everything the application sends between the two sequences away
+//! from the screen, and print screen (`CSI i` / `CSI 0 i`) renders the screen
+//! as text. Each job is streamed to the embedder's [`PrinterSink`], installed
+//! with [`Terminal::set_printer_sink`], or buffered and delivered in
+//! [`TerminalEvent::PrintJobCompleted`] when no sink is installed.
+
+use crate::terminal::{Terminal, TerminalEvent};
+
+/// Bytes kept for a print job delivered in an event; the rest is dropped
+pub const MAX_PRINT_JOB_BYTES: usize = 1024 * 1024;
+
+/// `CSI 4 i`, which ends printer controller mode
+const CONTROLLER_OFF: &[u8] = b"\x1b[4i";
+
+/// What produced a print job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PrintJobKind {
+    /// Print screen (`CSI 0 i`): the screen as text, one line per row
+    Screen,
+    /// Printer controller mode (`CSI 5 i`): raw bytes from the application
+    Controller,
+}
+
+impl PrintJobKind {
+    /// Lowercase name used in bindings and event dicts
+    pub fn name(self) -> &'static str {
+        match self {
+            PrintJobKind::Screen => "screen",
+            PrintJobKind::Controller => "controller",
+        }
+    }
+}
+
+/// Destination for print jobs, installed with [`Terminal::set_printer_sink`]
+///
+/// Calls for one job arrive in order: `begin`, any number of `write`s, then
+/// `finish`. Printer controller data arrives in chunks as it is received.
+pub trait PrinterSink: Send + Sync {
+    /// A print job started
+    fn begin(&mut self, kind: PrintJobKind);
+    /// Data for the current print job
+    fn write(&mut self, data: &[u8]);
+    /// The current print job ended
+    fn finish(&mut self);
+}
+
+/// Printer controller state and the installed sink
+#[derive(Default)]
+pub(crate) struct PrinterState {
+    sink: Option<Box<dyn PrinterSink>>,
+    /// Printer controller mode is diverting host output
+    pub(crate) controller_active: bool,
+    /// Bytes of `CSI 4 i` matched at the end of the last chunk
+    terminator_matched: usize,
+    /// Job data buffered for the event when no sink is installed
+    buffer: Vec<u8>,
+    /// Bytes in the current job, including any dropped from `buffer`
+    size: usize,
+}
+
+impl std::fmt::Debug for PrinterState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PrinterState")
+            .field("sink", &self.sink.is_some())
+            .field("controller_active", &self.controller_active)
+            .field("size", &self.size)
+            .finish()
+    }
+}
+
+impl PrinterState {
+    /// Take the sink out for a hard reset, ending any job in progress
+    pub(crate) fn take_sink(&mut self) -> Option<Box<dyn PrinterSink>> {
+        let mut sink = self.sink.take();
+        if let (true, Some(sink)) = (self.controller_active, &mut sink) {
+            sink.finish();
+        }
+        sink
+    }
+
+    pub(crate) fn set_sink(&mut self, sink: Option<Box<dyn PrinterSink>>) {
+        self.sink = sink;
+    }
+
+    fn begin(&mut self, kind: PrintJobKind) {
+        self.buffer.clear();
+        self.size = 0;
+        if let Some(sink) = &mut self.sink {
+            sink.begin(kind);
+        }
+    }
+
+    fn write(&mut self, data: &[u8]) {
+        if data.is_empty() {
+            return;
+        }
+        self.size += data.len();
+        if let Some(sink) = &mut self.sink {
+            sink.write(data);
+        } else {
+            let room = MAX_PRINT_JOB_BYTES - self.buffer.len();
+            self.buffer.extend_from_slice(&data[..data.len().min(room)]);
+        }
+    }
+
+    fn finish(&mut self, kind: PrintJobKind) -> TerminalEvent {
+        let sink = match &mut self.sink {
+            Some(sink) => {
+                sink.finish();
+                true
+            }
+            None => false,
+        };
+        TerminalEvent::PrintJobCompleted {
+            kind,
+            size: std::mem::take(&mut self.size),
+            data: std::mem::take(&mut self.buffer),
+            sink,
+        }
+    }
+}
+
+impl Terminal {
+    /// Stream print jobs to `sink` instead of delivering them in
+    /// [`TerminalEvent::PrintJobCompleted`] events.
+    ///
+    /// The sink belongs to the embedder and survives a hard reset (RIS).
+    pub fn set_printer_sink(&mut self, sink: impl PrinterSink + 'static) {
+        self.printer.sink = Some(Box::new(sink));
+    }
+
+    /// Remove the printer sink; print jobs are delivered in events again
+    pub fn clear_printer_sink(&mut self) {
+        self.printer.sink = None;
+    }
+
+    /// Check if a printer sink is installed
+    pub fn has_printer_sink(&self) -> bool {
+        self.printer.sink.is_some()
+    }
+
+    /// Check if printer controller mode is diverting output (`CSI 5 i`)
+    pub fn printer_controller_active(&self) -> bool {
+        self.printer.controller_active
+    }
+
+    /// Handle Media Copy: `CSI Ps i`
+    pub(in crate::terminal) fn handle_media_copy(&mut self, mode: u16) {
+        match mode {
+            0 => self.print_screen(),
+            5 if !self.printer.controller_active => {
+                self.printer.controller_active = true;
+                self.printer.terminator_matched = 0;
+                self.printer.begin(PrintJobKind::Controller);
+            }
+            // CSI 4 i only ends printer controller mode from within the
+            // diverted stream; outside it there is nothing to stop
+            _ => {}
+        }
+    }
+
+    /// Print screen: deliver the visible screen as one text job
+    fn print_screen(&mut self) {
+        self.printer.begin(PrintJobKind::Screen);
+        let grid = self.active_grid();
+        let mut text = String::new();
+        for row in 0..grid.rows() {
+            text.push_str(grid.row_text(row).trim_end());
+            text.push('\n');
+        }
+        self.printer.write(text.as_bytes());
+        let event = self.printer.finish(PrintJobKind::Screen);
+        self.events.terminal_events.push(event);
+    }
+
+    /// Divert host output to the printer until `CSI 4 i`; returns how many
+    /// bytes of `data` were consumed (all of them unless the terminator
+    /// was found)
+    pub(in crate::terminal) fn divert_to_printer(&mut self, data: &[u8]) -> usize {
+        let mut out = Vec::with_capacity(data.len());
+        for (i, &byte) in data.iter().enumerate() {
+            let matched = self.printer.terminator_matched;
+            if byte == CONTROLLER_OFF[matched] {
+                if matched + 1 == CONTROLLER_OFF.len() {
+                    self.printer.write(&out);
+                    self.printer.controller_active = false;
+                    self.printer.terminator_matched = 0;
+                    let event = self.printer.finish(PrintJobKind::Controller);
+                    self.events.terminal_events.push(event);
+                    return i + 1;
+                }
+                self.printer.terminator_matched = matched + 1;
+            } else {
+                // The bytes held as a possible terminator were data after all
+                out.extend_from_slice(&CONTROLLER_OFF[..matched]);
+                if byte == CONTROLLER_OFF[0] {
+                    self.printer.terminator_matched = 1;
+                } else {
+                    self.printer.terminator_matched = 0;
+                    out.push(byte);
+                }
+            }
+        }
+        self.printer.write(&out);
+        data.len()
+    }
+}
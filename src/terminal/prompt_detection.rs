@@ -0,0 +1,250 @@
+//! Heuristic prompt detection
+//!
+//! Builds Prompt/Command/Output zones for shells that do not emit OSC 133
+//! markers. A prompt is assumed when output has gone quiet for
+//! [`PromptDetectionConfig::idle_ms`], the cursor sits just past the last
+//! non-blank cell of its row, and the text before the cursor matches one of
+//! the configured regexes. The command is taken to end at the first hard
+//! line break after the prompt (soft-wrapped rows stay part of it), and
+//! everything after that is output until the next prompt.
+//!
+//! Zones created this way have [`Zone::heuristic`](crate::zone::Zone::heuristic)
+//! set and emit the usual `ZoneOpened` / `ZoneClosed` events. Detection is
+//! disabled by default, is skipped on the alternate screen, and stops for
+//! good once a real OSC 133 marker is seen.
+//!
+//! Idle time is checked before each [`Terminal::process`] call, so a prompt
+//! is noticed when the next output (usually the echo of the first typed key)
+//! arrives. Frontends that want prompts detected as soon as the shell goes
+//! quiet call [`Terminal::update_prompt_detection`] from a timer.
+
+use crate::time::Instant;
+
+use regex::Regex;
+
+use crate::terminal::{Terminal, TerminalEvent};
+use crate::zone::{Zone, ZoneType};
+
+/// Prompt patterns used by [`PromptDetectionConfig::default`]
+///
+/// Each is matched against the text between the start of the row and the
+/// cursor: common `$`/`#`/`%`/`>` shell prompts, Unicode prompt glyphs used
+/// by popular themes, and REPL prompts.
+pub const DEFAULT_PROMPT_PATTERNS: &[&str] =
+    &[r"[$#%>] ?$", r"[❯➜λ»›] ?$", r"^(>>>|\.\.\.|In \[\d+\]:) ?$"];
+
+/// Settings for heuristic prompt detection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptDetectionConfig {
+    /// Whether detection runs
+    pub enabled: bool,
+    /// Regexes matched against the text before the cursor; any match counts
+    pub patterns: Vec<String>,
+    /// How long output must be quiet before the cursor row is examined (ms)
+    pub idle_ms: u64,
+}
+
+impl Default for PromptDetectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            patterns: DEFAULT_PROMPT_PATTERNS
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+            idle_ms: 150,
+        }
+    }
+}
+
+/// Where the detector thinks the shell is
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PromptPhase {
+    /// No prompt seen yet, or the last prompt's command is still running
+    Idle,
+    /// Waiting at a prompt for the command line to be entered
+    AtPrompt {
+        /// Absolute row of the prompt
+        abs_row: usize,
+        /// Prompt text (start of the row up to the cursor)
+        prompt: String,
+    },
+}
+
+impl Terminal {
+    /// Set the heuristic prompt detection config.
+    ///
+    /// Returns an error naming the first invalid regex; the previous config
+    /// is kept in that case.
+    pub fn set_prompt_detection_config(
+        &mut self,
+        config: PromptDetectionConfig,
+    ) -> Result<(), String> {
+        let regexes = config
+            .patterns
+            .iter()
+            .map(|p| Regex::new(p).map_err(|e| format!("Invalid regex pattern {:?}: {}", p, e)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let state = &mut self.prompt_detection;
+        state.regexes = regexes;
+        state.config = config;
+        state.phase = PromptPhase::Idle;
+        Ok(())
+    }
+
+    /// Get the heuristic prompt detection config
+    pub fn prompt_detection_config(&self) -> &PromptDetectionConfig {
+        &self.prompt_detection.config
+    }
+
+    /// Enable or disable heuristic prompt detection, keeping the other settings
+    pub fn set_prompt_detection_enabled(&mut self, enabled: bool) {
+        self.prompt_detection.config.enabled = enabled;
+        self.prompt_detection.phase = PromptPhase::Idle;
+    }
+
+    /// Whether heuristic prompt detection is enabled
+    pub fn is_prompt_detection_enabled(&self) -> bool {
+        self.prompt_detection.config.enabled
+    }
+
+    /// Check for a prompt at the cursor now.
+    ///
+    /// Call periodically (e.g. from a render timer) to open the Prompt zone
+    /// as soon as output has been idle for the configured time, instead of
+    /// waiting for the next [`Terminal::process`] call. Returns `true` if a
+    /// new prompt was detected.
+    pub fn update_prompt_detection(&mut self) -> bool {
+        self.detect_heuristic_prompt(Instant::now())
+    }
+
+    /// Whether detection applies to the current screen
+    fn prompt_detection_active(&self) -> bool {
+        self.prompt_detection.config.enabled
+            && !self.alt_screen_active
+            && self.shell_state.shell_integration.marker().is_none()
+    }
+
+    /// Open a heuristic Prompt zone if the screen has been idle long enough
+    /// and the cursor row looks like a prompt.
+    pub(crate) fn detect_heuristic_prompt(&mut self, now: Instant) -> bool {
+        if !self.prompt_detection_active() {
+            return false;
+        }
+        let state = &self.prompt_detection;
+        let idle = state
+            .last_output
+            .is_none_or(|t| now.duration_since(t).as_millis() >= u128::from(state.config.idle_ms));
+        if !idle {
+            return false;
+        }
+
+        let abs_row = self.grid.scrollback_len() + self.cursor.row;
+        if let PromptPhase::AtPrompt { abs_row: row, .. } = state.phase {
+            if row == abs_row {
+                return false;
+            }
+        }
+
+        // Cursor column analysis: the cursor must sit right after the last
+        // non-blank cell, so a half-drawn line or a cursor parked mid-row
+        // (progress bars, editors) is not taken for a prompt.
+        let col = self.cursor.col;
+        let Some(cells) = self.grid.row(self.cursor.row) else {
+            return false;
+        };
+        if col == 0 || col >= cells.len() || cells[col..].iter().any(|c| c.c != ' ') {
+            return false;
+        }
+        let prompt = crate::terminal::cells_to_text(&cells[..col]);
+        if prompt.trim().is_empty() || !state.regexes.iter().any(|r| r.is_match(&prompt)) {
+            return false;
+        }
+
+        self.open_heuristic_zone(ZoneType::Prompt, abs_row, None);
+        self.prompt_detection.phase = PromptPhase::AtPrompt { abs_row, prompt };
+        true
+    }
+
+    /// After new output, split the pending prompt into Command and Output
+    /// zones once the command line has ended with a hard line break.
+    pub(crate) fn advance_heuristic_prompt(&mut self) {
+        self.prompt_detection.last_output = Some(Instant::now());
+        let PromptPhase::AtPrompt { abs_row, .. } = self.prompt_detection.phase else {
+            return;
+        };
+        if !self.prompt_detection_active() {
+            return;
+        }
+
+        let scrollback_len = self.grid.scrollback_len();
+        let cursor_abs = scrollback_len + self.cursor.row;
+        let is_wrapped = |row: usize| {
+            if row < scrollback_len {
+                self.grid.is_scrollback_wrapped(row)
+            } else {
+                self.grid.is_line_wrapped(row - scrollback_len)
+            }
+        };
+        let Some(command_end) = (abs_row..cursor_abs).find(|&row| !is_wrapped(row)) else {
+            return;
+        };
+
+        let PromptPhase::AtPrompt { prompt, .. } =
+            std::mem::replace(&mut self.prompt_detection.phase, PromptPhase::Idle)
+        else {
+            return;
+        };
+        let line = self
+            .extract_text_from_row_range(abs_row, command_end)
+            .unwrap_or_default();
+        let command = line
+            .strip_prefix(prompt.as_str())
+            .or_else(|| line.strip_prefix(prompt.trim_end()))
+            .unwrap_or("")
+            .trim()
+            .to_string();
+        let command = (!command.is_empty()).then_some(command);
+
+        self.open_heuristic_zone(ZoneType::Command, abs_row, command.clone());
+        self.open_heuristic_zone(ZoneType::Output, command_end + 1, command);
+    }
+
+    /// Close the current zone just above `abs_row` and open a heuristic zone
+    /// there, emitting the same events as OSC 133 zones.
+    fn open_heuristic_zone(
+        &mut self,
+        zone_type: ZoneType,
+        abs_row: usize,
+        command: Option<String>,
+    ) {
+        if let Some(zone) = self.grid.zones_mut().last_mut() {
+            zone.close(abs_row.saturating_sub(1));
+            let event = TerminalEvent::ZoneClosed {
+                zone_id: zone.id,
+                zone_type: zone.zone_type,
+                abs_row_start: zone.abs_row_start,
+                abs_row_end: zone.abs_row_end,
+                exit_code: zone.exit_code,
+            };
+            self.events.terminal_events.push(event);
+        }
+
+        let zone_id = self.events.next_zone_id;
+        self.events.next_zone_id += 1;
+        let mut zone = Zone::new(
+            zone_id,
+            zone_type,
+            abs_row,
+            Some(crate::terminal::unix_millis()),
+        );
+        zone.command = command;
+        zone.heuristic = true;
+        self.grid.push_zone(zone);
+        self.events.terminal_events.push(TerminalEvent::ZoneOpened {
+            zone_id,
+            zone_type,
+            abs_row_start: abs_row,
+        });
+    }
+}
@@ -34,7 +34,7 @@ pub enum RecordingEventType {
 /// A single event in a recording
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecordingEvent {
-    /// Timestamp relative to recording start (microseconds)
+    /// Timestamp relative to recording start (milliseconds)
     pub timestamp: u64,
     /// Type of event
     pub event_type: RecordingEventType,
@@ -57,9 +57,9 @@ pub struct RecordingSession {
     pub events: Vec<RecordingEvent>,
     /// Environment variables at start
     pub env: HashMap<String, String>,
-    /// Total duration in microseconds
+    /// Total duration in milliseconds
     pub duration: u64,
-    /// Creation timestamp
+    /// Creation timestamp (Unix milliseconds)
     pub created_at: u64,
 }
 
@@ -76,6 +76,285 @@ pub enum RecordingExportFormat {
     Html,
 }
 
+/// Error returned when importing a recording fails
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordingImportError {
+    /// The input contains no recording
+    Empty,
+    /// Malformed JSON or an unexpected structure at a 1-based line
+    Parse { line: usize, message: String },
+    /// Asciicast version other than 1 or 2
+    UnsupportedVersion(u64),
+    /// Well-formed input that does not describe a valid session
+    Invalid(String),
+}
+
+impl std::fmt::Display for RecordingImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecordingImportError::Empty => write!(f, "Recording is empty"),
+            RecordingImportError::Parse { line, message } => {
+                write!(f, "Parse error on line {}: {}", line, message)
+            }
+            RecordingImportError::UnsupportedVersion(v) => {
+                write!(f, "Unsupported asciicast version: {}", v)
+            }
+            RecordingImportError::Invalid(msg) => write!(f, "Invalid recording: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for RecordingImportError {}
+
+/// Convert asciicast seconds to event milliseconds
+fn seconds_to_millis(seconds: f64) -> Option<u64> {
+    (seconds.is_finite() && seconds >= 0.0).then(|| (seconds * 1000.0).round() as u64)
+}
+
+/// Decode `data` after the bytes held back from the previous chunk, holding
+/// back a trailing incomplete UTF-8 sequence for the next call
+fn decode_utf8_chunk(pending: &mut Vec<u8>, data: &[u8]) -> String {
+    pending.extend_from_slice(data);
+    let incomplete = match std::str::from_utf8(pending) {
+        Err(e) if e.error_len().is_none() => pending.len() - e.valid_up_to(),
+        _ => 0,
+    };
+    let tail = pending.split_off(pending.len() - incomplete);
+    let text = String::from_utf8_lossy(pending).into_owned();
+    *pending = tail;
+    text
+}
+
+/// Read a positive terminal dimension from an asciicast header
+fn header_dimension(
+    header: &serde_json::Value,
+    key: &str,
+    line: usize,
+) -> Result<usize, RecordingImportError> {
+    match header.get(key).and_then(|v| v.as_u64()) {
+        Some(n) if n > 0 => Ok(n as usize),
+        _ => Err(RecordingImportError::Parse {
+            line,
+            message: format!("header field '{}' must be a positive integer", key),
+        }),
+    }
+}
+
+impl RecordingSession {
+    /// Parse an asciicast recording (v2 newline-delimited, or v1 single
+    /// document), the inverse of [`Terminal::export_asciicast`].
+    ///
+    /// Output, input, resize (`"COLSxROWS"` or the `cols, rows` form written
+    /// by this crate) and marker events are imported; unknown event codes are
+    /// skipped as the format requires. Timestamps must be non-negative and
+    /// non-decreasing.
+    pub fn from_asciicast(data: &str) -> Result<Self, RecordingImportError> {
+        let mut lines = data
+            .lines()
+            .enumerate()
+            .map(|(i, l)| (i + 1, l.trim()))
+            .filter(|(_, l)| !l.is_empty());
+        let (header_line, header_text) = lines.next().ok_or(RecordingImportError::Empty)?;
+
+        // v1 is a single JSON document that may span several lines
+        if let Ok(doc) = serde_json::from_str::<serde_json::Value>(data.trim()) {
+            if doc.get("version").and_then(|v| v.as_u64()) == Some(1) {
+                return Self::from_asciicast_v1(&doc);
+            }
+        }
+
+        let header: serde_json::Value =
+            serde_json::from_str(header_text).map_err(|e| RecordingImportError::Parse {
+                line: header_line,
+                message: e.to_string(),
+            })?;
+        if !header.is_object() {
+            return Err(RecordingImportError::Parse {
+                line: header_line,
+                message: "header must be a JSON object".to_string(),
+            });
+        }
+        match header.get("version").and_then(|v| v.as_u64()) {
+            Some(2) => {}
+            Some(v) => return Err(RecordingImportError::UnsupportedVersion(v)),
+            None => {
+                return Err(RecordingImportError::Parse {
+                    line: header_line,
+                    message: "header is missing 'version'".to_string(),
+                })
+            }
+        }
+
+        let mut session = Self::imported(
+            &header,
+            header_dimension(&header, "width", header_line)?,
+            header_dimension(&header, "height", header_line)?,
+        );
+        for (line, text) in lines {
+            let parse_err = |message: String| RecordingImportError::Parse { line, message };
+            let value: serde_json::Value =
+                serde_json::from_str(text).map_err(|e| parse_err(e.to_string()))?;
+            let event = value
+                .as_array()
+                .filter(|a| a.len() >= 3)
+                .ok_or_else(|| parse_err("event must be an array [time, code, data]".into()))?;
+            let timestamp = event[0]
+                .as_f64()
+                .and_then(seconds_to_millis)
+                .ok_or_else(|| parse_err("event time must be a non-negative number".into()))?;
+            if session
+                .events
+                .last()
+                .is_some_and(|e| e.timestamp > timestamp)
+            {
+                return Err(parse_err("event time goes backwards".into()));
+            }
+            let code = event[1]
+                .as_str()
+                .ok_or_else(|| parse_err("event code must be a string".into()))?;
+            let text_data = || {
+                event[2]
+                    .as_str()
+                    .map(|s| s.as_bytes().to_vec())
+                    .ok_or_else(|| parse_err(format!("'{}' event data must be a string", code)))
+            };
+
+            let (event_type, data, metadata) = match code {
+                "o" => (RecordingEventType::Output, text_data()?, None),
+                "i" => (RecordingEventType::Input, text_data()?, None),
+                "m" => (RecordingEventType::Marker, text_data()?, None),
+                "r" => {
+                    let size = match (event[2].as_str(), event.get(3)) {
+                        (Some(s), _) => s.split_once('x').and_then(|(c, r)| {
+                            Some((c.trim().parse().ok()?, r.trim().parse().ok()?))
+                        }),
+                        (None, Some(rows)) => event[2]
+                            .as_u64()
+                            .zip(rows.as_u64())
+                            .map(|(c, r)| (c as usize, r as usize)),
+                        (None, None) => None,
+                    };
+                    let size = size
+                        .filter(|&(c, r)| c > 0 && r > 0)
+                        .ok_or_else(|| parse_err("resize data must be \"COLSxROWS\"".into()))?;
+                    (RecordingEventType::Resize, Vec::new(), Some(size))
+                }
+                _ => continue,
+            };
+            session.events.push(RecordingEvent {
+                timestamp,
+                event_type,
+                data,
+                metadata,
+            });
+        }
+
+        session.duration = session.events.last().map_or(0, |e| e.timestamp);
+        Ok(session)
+    }
+
+    /// Parse an asciicast v1 document (`stdout` holds `[delay, data]` pairs)
+    fn from_asciicast_v1(doc: &serde_json::Value) -> Result<Self, RecordingImportError> {
+        let mut session = Self::imported(
+            doc,
+            header_dimension(doc, "width", 1)?,
+            header_dimension(doc, "height", 1)?,
+        );
+        let frames = doc
+            .get("stdout")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| RecordingImportError::Invalid("v1 'stdout' must be an array".into()))?;
+
+        let mut elapsed = 0.0;
+        for (i, frame) in frames.iter().enumerate() {
+            let invalid =
+                || RecordingImportError::Invalid(format!("v1 frame {} must be [delay, data]", i));
+            let delay = frame.get(0).and_then(|v| v.as_f64()).ok_or_else(invalid)?;
+            let text = frame.get(1).and_then(|v| v.as_str()).ok_or_else(invalid)?;
+            elapsed += delay;
+            session.events.push(RecordingEvent {
+                timestamp: seconds_to_millis(elapsed).ok_or_else(invalid)?,
+                event_type: RecordingEventType::Output,
+                data: text.as_bytes().to_vec(),
+                metadata: None,
+            });
+        }
+
+        session.duration = session.events.last().map_or(0, |e| e.timestamp);
+        Ok(session)
+    }
+
+    /// Empty session carrying the title, env and start time of an asciicast header
+    fn imported(header: &serde_json::Value, cols: usize, rows: usize) -> Self {
+        let env = header
+            .get("env")
+            .and_then(|v| v.as_object())
+            .map(|env| {
+                env.iter()
+                    .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                    .collect()
+            })
+            .unwrap_or_default();
+        RecordingSession {
+            id: uuid::Uuid::new_v4().to_string(),
+            title: header
+                .get("title")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            initial_size: (cols, rows),
+            events: Vec::new(),
+            env,
+            duration: 0,
+            created_at: header
+                .get("timestamp")
+                .and_then(|v| v.as_u64())
+                .map_or(0, |s| s * 1000),
+        }
+    }
+
+    /// Parse a session written by [`Terminal::export_json`].
+    ///
+    /// Besides the JSON structure, checks that the initial size is non-zero,
+    /// timestamps are non-decreasing and resize events carry a size.
+    pub fn from_json(data: &str) -> Result<Self, RecordingImportError> {
+        if data.trim().is_empty() {
+            return Err(RecordingImportError::Empty);
+        }
+        let session: RecordingSession =
+            serde_json::from_str(data).map_err(|e| RecordingImportError::Parse {
+                line: e.line(),
+                message: e.to_string(),
+            })?;
+
+        let (cols, rows) = session.initial_size;
+        if cols == 0 || rows == 0 {
+            return Err(RecordingImportError::Invalid(format!(
+                "initial size {}x{} must be non-zero",
+                cols, rows
+            )));
+        }
+        for (i, pair) in session.events.windows(2).enumerate() {
+            if pair[1].timestamp < pair[0].timestamp {
+                return Err(RecordingImportError::Invalid(format!(
+                    "event {} is earlier than the event before it",
+                    i + 1
+                )));
+            }
+        }
+        if let Some(i) = session.events.iter().position(|e| {
+            e.event_type == RecordingEventType::Resize
+                && !e.metadata.is_some_and(|(c, r)| c > 0 && r > 0)
+        }) {
+            return Err(RecordingImportError::Invalid(format!(
+                "resize event {} has no size",
+                i
+            )));
+        }
+        Ok(session)
+    }
+}
+
 use crate::terminal::Terminal;
 
 impl Terminal {
@@ -101,15 +380,22 @@ impl Terminal {
             created_at: crate::terminal::unix_millis(),
         });
         self.recording_state.is_recording = true;
-        self.recording_state.recording_start_time = crate::terminal::unix_millis();
+        self.recording_state.recording_clock = Some(crate::time::Instant::now());
+    }
+
+    /// Milliseconds since the current recording started
+    fn recording_elapsed_ms(&self) -> u64 {
+        self.recording_state
+            .recording_clock
+            .map_or(0, |start| start.elapsed().as_millis() as u64)
     }
 
     /// Stop recording terminal session
     pub fn stop_recording(&mut self) -> Option<RecordingSession> {
         self.recording_state.is_recording = false;
         let mut session = self.recording_state.recording_session.take()?;
-        session.duration =
-            crate::terminal::unix_millis() - self.recording_state.recording_start_time;
+        session.duration = self.recording_elapsed_ms();
+        self.recording_state.recording_clock = None;
         Some(session)
     }
 
@@ -119,9 +405,8 @@ impl Terminal {
             return;
         }
 
+        let timestamp = self.recording_elapsed_ms();
         if let Some(ref mut session) = self.recording_state.recording_session {
-            let timestamp =
-                crate::terminal::unix_millis() - self.recording_state.recording_start_time;
             session.events.push(RecordingEvent {
                 timestamp,
                 event_type,
@@ -157,17 +442,26 @@ impl Terminal {
         output.push_str(&header.to_string());
         output.push('\n');
 
-        // 2. Event lines
+        // 2. Event lines. PTY reads can split a UTF-8 sequence across events,
+        // so incomplete trailing bytes are carried into the next event.
+        let mut pending_output = Vec::new();
+        let mut pending_input = Vec::new();
         for event in &session.events {
-            let timestamp = event.timestamp as f64 / 1_000_000.0; // microseconds to seconds
+            let timestamp = event.timestamp as f64 / 1000.0; // milliseconds to seconds
 
             let event_json = match event.event_type {
                 RecordingEventType::Output => {
-                    let text = String::from_utf8_lossy(&event.data);
+                    let text = decode_utf8_chunk(&mut pending_output, &event.data);
+                    if text.is_empty() {
+                        continue;
+                    }
                     serde_json::json!([timestamp, "o", text])
                 }
                 RecordingEventType::Input => {
-                    let text = String::from_utf8_lossy(&event.data);
+                    let text = decode_utf8_chunk(&mut pending_input, &event.data);
+                    if text.is_empty() {
+                        continue;
+                    }
                     serde_json::json!([timestamp, "i", text])
                 }
                 RecordingEventType::Resize => {
@@ -208,9 +502,8 @@ impl Terminal {
             return;
         }
 
+        let timestamp = self.recording_elapsed_ms();
         if let Some(ref mut session) = self.recording_state.recording_session {
-            let timestamp =
-                crate::terminal::unix_millis() - self.recording_state.recording_start_time;
             session.events.push(RecordingEvent {
                 timestamp,
                 event_type: RecordingEventType::Resize,
@@ -270,7 +563,7 @@ mod tests {
         let mut term = Terminal::new(80, 24);
         term.start_recording(None);
 
-        let start_time = term.recording_state.recording_start_time;
+        let start = term.recording_state.recording_clock.unwrap();
 
         // Record events with small delays
         term.record_input(b"echo hello");
@@ -285,11 +578,11 @@ mod tests {
         let ts2 = session.events[1].timestamp;
         assert!(ts2 > ts1, "Second timestamp should be later than first");
 
-        // Timestamps should be in microseconds
-        let now = crate::terminal::unix_millis();
+        // Timestamps are milliseconds on the recording's monotonic clock
+        assert!(ts2 >= ts1 + 10, "Sleep should show up in the timestamps");
         assert!(
-            ts1 < (now - start_time) * 1000 + 1_000_000,
-            "Timestamp should be reasonable"
+            ts2 <= start.elapsed().as_millis() as u64,
+            "Timestamp should not run ahead of the clock"
         );
     }
 
@@ -532,4 +825,161 @@ mod tests {
         // Should have header + 2 events (input + output, marker ignored)
         assert_eq!(lines.len(), 3);
     }
+
+    #[test]
+    fn test_asciicast_round_trip() {
+        let mut term = Terminal::new(80, 24);
+        term.start_recording(Some("Round Trip".to_string()));
+        term.record_input(b"ls\n");
+        term.record_output(b"file.txt\r\n");
+        term.record_resize(100, 30);
+        let session = term.stop_recording().unwrap();
+
+        let imported = RecordingSession::from_asciicast(&term.export_asciicast(&session)).unwrap();
+        assert_eq!(imported.title, "Round Trip");
+        assert_eq!(imported.initial_size, (80, 24));
+        assert_eq!(imported.events.len(), 3);
+        for (a, b) in imported.events.iter().zip(&session.events) {
+            assert_eq!(a.event_type, b.event_type);
+            assert_eq!(a.data, b.data);
+            assert_eq!(a.metadata, b.metadata);
+            assert_eq!(a.timestamp, b.timestamp);
+        }
+    }
+
+    #[test]
+    fn test_from_asciicast_v2_external_file() {
+        let cast = concat!(
+            r#"{"version": 2, "width": 40, "height": 10, "timestamp": 1700000000, "title": "demo", "env": {"SHELL": "/bin/zsh", "TERM": null}}"#,
+            "\n\n",
+            r#"[0.5, "o", "hello"]"#,
+            "\n",
+            r#"[1.25, "r", "60x20"]"#,
+            "\n",
+            r#"[1.5, "m", "chapter"]"#,
+            "\n",
+            r#"[2.0, "x", "unknown code is skipped"]"#,
+            "\n",
+        );
+        let session = RecordingSession::from_asciicast(cast).unwrap();
+        assert_eq!(session.initial_size, (40, 10));
+        assert_eq!(session.created_at, 1_700_000_000_000);
+        assert_eq!(
+            session.env.get("SHELL").map(String::as_str),
+            Some("/bin/zsh")
+        );
+        assert!(!session.env.contains_key("TERM"));
+        assert_eq!(session.events.len(), 3);
+        assert_eq!(session.events[0].timestamp, 500);
+        assert_eq!(session.events[1].metadata, Some((60, 20)));
+        assert_eq!(session.events[2].event_type, RecordingEventType::Marker);
+        assert_eq!(session.events[2].data, b"chapter");
+        assert_eq!(session.duration, 1500);
+    }
+
+    #[test]
+    fn test_from_asciicast_v1() {
+        let cast = r#"{"version": 1, "width": 20, "height": 5, "duration": 1.5,
+            "stdout": [[0.5, "a"], [1.0, "b"]]}"#;
+        let session = RecordingSession::from_asciicast(cast).unwrap();
+        assert_eq!(session.initial_size, (20, 5));
+        let times: Vec<u64> = session.events.iter().map(|e| e.timestamp).collect();
+        assert_eq!(times, vec![500, 1500]);
+    }
+
+    #[test]
+    fn test_from_asciicast_reports_errors() {
+        assert_eq!(
+            RecordingSession::from_asciicast("\n  \n").unwrap_err(),
+            RecordingImportError::Empty
+        );
+        assert_eq!(
+            RecordingSession::from_asciicast(r#"{"version": 3, "width": 1, "height": 1}"#)
+                .unwrap_err(),
+            RecordingImportError::UnsupportedVersion(3)
+        );
+        assert!(matches!(
+            RecordingSession::from_asciicast(r#"{"version": 2, "width": 0, "height": 1}"#),
+            Err(RecordingImportError::Parse { line: 1, .. })
+        ));
+
+        let header = r#"{"version": 2, "width": 80, "height": 24}"#;
+        let bad_lines = [
+            r#"not json"#,
+            r#"[1.0, "o"]"#,
+            r#"[-1.0, "o", "x"]"#,
+            r#"[1.0, "o", 5]"#,
+            r#"[1.0, "r", "wide"]"#,
+        ];
+        for bad in bad_lines {
+            let cast = format!("{}\n[0.1, \"o\", \"ok\"]\n{}\n", header, bad);
+            match RecordingSession::from_asciicast(&cast) {
+                Err(RecordingImportError::Parse { line, .. }) => assert_eq!(line, 3, "{}", bad),
+                other => panic!("expected parse error for {}, got {:?}", bad, other),
+            }
+        }
+
+        let backwards = format!("{}\n[2.0, \"o\", \"a\"]\n[1.0, \"o\", \"b\"]\n", header);
+        let err = RecordingSession::from_asciicast(&backwards).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parse error on line 3: event time goes backwards"
+        );
+    }
+
+    #[test]
+    fn test_json_round_trip_and_validation() {
+        let mut term = Terminal::new(80, 24);
+        term.start_recording(None);
+        term.record_output(b"data");
+        term.record_marker("mark".to_string());
+        let session = term.stop_recording().unwrap();
+
+        let imported = RecordingSession::from_json(&term.export_json(&session)).unwrap();
+        assert_eq!(imported.id, session.id);
+        assert_eq!(imported.events.len(), 2);
+        assert_eq!(imported.events[1].event_type, RecordingEventType::Marker);
+
+        assert_eq!(
+            RecordingSession::from_json("").unwrap_err(),
+            RecordingImportError::Empty
+        );
+        assert!(matches!(
+            RecordingSession::from_json("{\n\"id\": 1\n}"),
+            Err(RecordingImportError::Parse { line: 2, .. })
+        ));
+
+        let mut zero = session.clone();
+        zero.initial_size = (0, 24);
+        assert!(matches!(
+            RecordingSession::from_json(&term.export_json(&zero)),
+            Err(RecordingImportError::Invalid(_))
+        ));
+
+        let mut unordered = session.clone();
+        unordered.events[0].timestamp = unordered.events[1].timestamp + 10;
+        assert!(matches!(
+            RecordingSession::from_json(&term.export_json(&unordered)),
+            Err(RecordingImportError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_export_asciicast_joins_split_utf8() {
+        let mut term = Terminal::new(80, 24);
+        term.start_recording(None);
+        // "é" (C3 A9) split across two PTY reads
+        term.record_output(b"caf\xC3");
+        term.record_output(b"\xA9!");
+        let session = term.stop_recording().unwrap();
+
+        let imported = RecordingSession::from_asciicast(&term.export_asciicast(&session)).unwrap();
+        let text: Vec<u8> = imported
+            .events
+            .iter()
+            .flat_map(|e| e.data.clone())
+            .collect();
+        assert_eq!(String::from_utf8(text).unwrap(), "café!");
+        assert_eq!(imported.events[0].data, b"caf");
+    }
 }
@@ -0,0 +1,341 @@
+//! Standalone players for recordings
+//!
+//! Replays a [`RecordingSession`] into a scratch terminal, samples the
+//! screen into frames, and packages them either as an animated SVG (SMIL,
+//! plays in any browser or `<img>` tag) or as an HTML page with a small
+//! JavaScript player (play/pause, seek, speed). Frames are drawn with the
+//! [`Terminal::export_svg`] renderer, so colors come from the exporting
+//! terminal's theme and fonts from [`RecordingPlayerConfig::style`]. No
+//! asciinema tooling is needed to view the result.
+
+use std::fmt::Write as _;
+
+use crate::svg_export::{hex, svg_style, Layout, SvgExportConfig};
+use crate::terminal::{
+    html_escape, RecordingEventType, RecordingExportFormat, RecordingSession, Terminal,
+};
+
+/// Settings for [`Terminal::export_recording`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordingPlayerConfig {
+    /// Font, size, spacing and padding of the frames (`line_range` is ignored)
+    pub style: SvgExportConfig,
+    /// Output events closer together than this are merged into one frame (ms)
+    pub frame_interval_ms: u64,
+    /// Idle gaps longer than this are shortened to it (ms); `None` keeps the
+    /// recorded timing
+    pub idle_time_limit_ms: Option<u64>,
+    /// How long the last frame stays on screen before the end (ms)
+    pub end_hold_ms: u64,
+    /// Restart from the beginning after the last frame
+    pub loop_playback: bool,
+}
+
+impl Default for RecordingPlayerConfig {
+    fn default() -> Self {
+        Self {
+            style: SvgExportConfig::default(),
+            frame_interval_ms: 33,
+            idle_time_limit_ms: Some(2000),
+            end_hold_ms: 1000,
+            loop_playback: true,
+        }
+    }
+}
+
+/// One sampled screen state
+struct Frame {
+    /// Playback time in milliseconds
+    time: u64,
+    /// SVG content (backgrounds, text, graphics) without the document wrapper
+    body: String,
+}
+
+/// Frames plus the canvas size needed to show all of them
+struct FrameSet {
+    frames: Vec<Frame>,
+    cols: usize,
+    rows: usize,
+    duration: u64,
+}
+
+impl Terminal {
+    /// Replay `session` into a scratch terminal and sample frames
+    fn recording_frames(
+        &self,
+        session: &RecordingSession,
+        config: &RecordingPlayerConfig,
+    ) -> FrameSet {
+        let (cols, rows) = session.initial_size;
+        let mut player = Terminal::with_scrollback(cols.max(1), rows.max(1), 0);
+        player.theme.ansi_palette = self.theme.ansi_palette;
+        player.theme.default_fg = self.theme.default_fg;
+        player.theme.default_bg = self.theme.default_bg;
+
+        let layout = Layout::new(&config.style);
+        let capture = |term: &Terminal, time: u64| {
+            let mut body = String::new();
+            let (_, rows) = term.size();
+            let top = term.active_grid().scrollback_len();
+            term.svg_write_content(
+                &mut body,
+                top..top + rows,
+                &layout,
+                config.style.include_graphics,
+            );
+            Frame { time, body }
+        };
+
+        let events: Vec<_> = session
+            .events
+            .iter()
+            .filter(|e| {
+                matches!(
+                    e.event_type,
+                    RecordingEventType::Output | RecordingEventType::Resize
+                )
+            })
+            .collect();
+        let limit = |gap: u64| config.idle_time_limit_ms.map_or(gap, |l| gap.min(l));
+
+        let mut set = FrameSet {
+            frames: vec![capture(&player, 0)],
+            cols: player.size().0,
+            rows: player.size().1,
+            duration: 0,
+        };
+        let mut clock = 0;
+        let mut prev_ts = 0;
+        let mut pending_since = None;
+        for (i, event) in events.iter().enumerate() {
+            clock += limit(event.timestamp.saturating_sub(prev_ts));
+            prev_ts = event.timestamp;
+
+            match event.event_type {
+                RecordingEventType::Resize => {
+                    if let Some((c, r)) = event.metadata {
+                        player.resize(c.max(1), r.max(1));
+                        set.cols = set.cols.max(c);
+                        set.rows = set.rows.max(r);
+                    }
+                }
+                _ => player.process(&event.data),
+            }
+
+            // Emit when the screen is about to sit idle for a frame interval,
+            // or when a continuous burst has already run that long
+            let burst_start = *pending_since.get_or_insert(clock);
+            let due = match events.get(i + 1) {
+                Some(next) => {
+                    limit(next.timestamp.saturating_sub(event.timestamp))
+                        >= config.frame_interval_ms
+                        || clock - burst_start >= config.frame_interval_ms
+                }
+                None => true,
+            };
+            if due {
+                if set.frames.last().is_some_and(|f| f.time == clock) {
+                    set.frames.pop();
+                }
+                set.frames.push(capture(&player, clock));
+                pending_since = None;
+            }
+        }
+
+        set.duration = set.frames.last().map_or(0, |f| f.time) + config.end_hold_ms.max(1);
+        set
+    }
+
+    /// Export a recording as a standalone animated document.
+    ///
+    /// [`RecordingExportFormat::Svg`] produces a self-animating SVG and
+    /// [`RecordingExportFormat::Html`] an HTML page with an embedded player.
+    /// The session is replayed from its initial size with this terminal's
+    /// color theme. Returns an error for formats that need an external
+    /// encoder (GIF, video).
+    pub fn export_recording(
+        &self,
+        session: &RecordingSession,
+        format: RecordingExportFormat,
+        config: &RecordingPlayerConfig,
+    ) -> Result<String, String> {
+        match format {
+            RecordingExportFormat::Svg => Ok(self.recording_svg(session, config)),
+            RecordingExportFormat::Html => Ok(self.recording_html(session, config)),
+            RecordingExportFormat::Gif | RecordingExportFormat::Video => Err(format!(
+                "{:?} export is not supported; use Svg or Html",
+                format
+            )),
+        }
+    }
+
+    /// Opening `<svg>` tag, font style and background for a frame set
+    fn recording_svg_header(&self, set: &FrameSet, config: &RecordingPlayerConfig) -> String {
+        let layout = Layout::new(&config.style);
+        let pad = config.style.padding as f32;
+        let width = layout.x(set.cols) + pad * 2.0;
+        let height = layout.y(set.rows) + pad * 2.0;
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            w = width,
+            h = height
+        );
+        out.push_str(&svg_style(&config.style));
+        let _ = writeln!(
+            out,
+            r#"<rect width="100%" height="100%" fill="{}"/>"#,
+            hex(self.theme.default_bg.to_rgb())
+        );
+        out
+    }
+
+    fn recording_svg(&self, session: &RecordingSession, config: &RecordingPlayerConfig) -> String {
+        let set = self.recording_frames(session, config);
+        let total = set.duration as f64;
+        let pad = config.style.padding;
+
+        let mut svg = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        svg.push_str(&self.recording_svg_header(&set, config));
+        let _ = writeln!(svg, "<title>{}</title>", html_escape(&session.title));
+        let _ = writeln!(svg, r#"<g transform="translate({}, {})">"#, pad, pad);
+
+        let repeat = if config.loop_playback {
+            "indefinite"
+        } else {
+            "1"
+        };
+        for (i, frame) in set.frames.iter().enumerate() {
+            let start = frame.time as f64 / total;
+            let end = set.frames.get(i + 1).map_or(1.0, |f| f.time as f64 / total);
+            // Discrete opacity keyframes: visible only during [start, end)
+            let keys = match (i == 0, end >= 1.0) {
+                (true, true) => None,
+                (true, false) => Some(("1;0".to_string(), format!("0;{:.6}", end))),
+                (false, true) => Some(("0;1".to_string(), format!("0;{:.6}", start))),
+                (false, false) => Some(("0;1;0".to_string(), format!("0;{:.6};{:.6}", start, end))),
+            };
+            let _ = writeln!(svg, r#"<g opacity="{}">"#, if i == 0 { 1 } else { 0 });
+            if let Some((values, key_times)) = keys {
+                let _ = writeln!(
+                    svg,
+                    r#"<animate attributeName="opacity" calcMode="discrete" dur="{:.3}s" values="{}" keyTimes="{}" repeatCount="{}" fill="freeze"/>"#,
+                    total / 1000.0,
+                    values,
+                    key_times,
+                    repeat
+                );
+            }
+            svg.push_str(&frame.body);
+            svg.push_str("</g>\n");
+        }
+
+        svg.push_str("</g>\n</svg>\n");
+        svg
+    }
+
+    fn recording_html(&self, session: &RecordingSession, config: &RecordingPlayerConfig) -> String {
+        let set = self.recording_frames(session, config);
+        let frames: Vec<_> = set
+            .frames
+            .iter()
+            .map(|f| serde_json::json!({ "t": f.time, "b": f.body }))
+            .collect();
+        // Keep frame markup from closing the <script> element
+        let frames_json = serde_json::to_string(&frames)
+            .unwrap_or_else(|_| "[]".to_string())
+            .replace("</", "<\\/");
+        let title = html_escape(&session.title);
+        let bg = hex(self.theme.default_bg.to_rgb());
+        let fg = hex(self.theme.default_fg.to_rgb());
+        let pad = config.style.padding;
+
+        let mut html = String::new();
+        let _ = write!(
+            html,
+            r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="UTF-8">
+<title>{title}</title>
+<style>
+body {{ margin: 0; padding: 16px; background: {bg}; color: {fg}; font-family: sans-serif; display: flex; flex-direction: column; align-items: center; }}
+.player {{ display: inline-flex; flex-direction: column; gap: 8px; }}
+.controls {{ display: flex; gap: 8px; align-items: center; }}
+.controls input[type=range] {{ flex: 1; }}
+</style>
+</head>
+<body>
+<div class="player">
+"#
+        );
+        html.push_str(&self.recording_svg_header(&set, config).replacen(
+            "<svg ",
+            "<svg id=\"screen\" ",
+            1,
+        ));
+        let _ = write!(
+            html,
+            r#"<g id="frame" transform="translate({pad}, {pad})"></g>
+</svg>
+<div class="controls">
+<button id="play">Pause</button>
+<input id="seek" type="range" min="0" max="{duration}" step="1" value="0">
+<span id="time">0.0s</span>
+<select id="speed"><option value="0.5">0.5x</option><option value="1" selected>1x</option><option value="2">2x</option><option value="4">4x</option></select>
+</div>
+</div>
+<script>
+const frames = {frames_json};
+const duration = {duration};
+const loopPlayback = {loop_playback};
+const frameEl = document.getElementById("frame");
+const playBtn = document.getElementById("play");
+const seek = document.getElementById("seek");
+const timeEl = document.getElementById("time");
+const speedEl = document.getElementById("speed");
+let pos = 0, playing = true, last = null, shown = -1;
+function frameAt(t) {{
+  let lo = 0, hi = frames.length - 1;
+  while (lo < hi) {{
+    const mid = (lo + hi + 1) >> 1;
+    if (frames[mid].t <= t) lo = mid; else hi = mid - 1;
+  }}
+  return lo;
+}}
+function render() {{
+  const i = frameAt(pos);
+  if (i !== shown) {{ frameEl.innerHTML = frames[i].b; shown = i; }}
+  seek.value = pos;
+  timeEl.textContent = (pos / 1000).toFixed(1) + "s";
+}}
+function tick(now) {{
+  if (last !== null && playing) {{
+    pos += (now - last) * parseFloat(speedEl.value);
+    if (pos >= duration) {{
+      if (loopPlayback) {{ pos = 0; }}
+      else {{ pos = duration; playing = false; playBtn.textContent = "Play"; }}
+    }}
+  }}
+  last = now;
+  render();
+  requestAnimationFrame(tick);
+}}
+playBtn.onclick = () => {{
+  if (!playing && pos >= duration) pos = 0;
+  playing = !playing;
+  playBtn.textContent = playing ? "Pause" : "Play";
+}};
+seek.oninput = () => {{ pos = parseFloat(seek.value); render(); }};
+requestAnimationFrame(tick);
+</script>
+</body>
+</html>
+"#,
+            duration = set.duration,
+            loop_playback = config.loop_playback,
+        );
+        html
+    }
+}
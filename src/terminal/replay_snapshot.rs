@@ -3,9 +3,11 @@
 //! These structs capture a complete, clonable snapshot of terminal state
 //! at a point in time, enabling efficient restore for replay navigation.
 
+use crate::annotation::Annotation;
 use crate::cell::{Cell, CellFlags};
 use crate::color::Color;
 use crate::cursor::Cursor;
+use crate::grid::ScrollbackRow;
 use crate::mouse::{MouseEncoding, MouseMode};
 use crate::zone::Zone;
 
@@ -14,8 +16,8 @@ use crate::zone::Zone;
 pub struct GridSnapshot {
     /// Visible screen cells (row-major, cols * rows)
     pub cells: Vec<Cell>,
-    /// Scrollback buffer cells (flat, circular buffer linearized)
-    pub scrollback_cells: Vec<Cell>,
+    /// Scrollback rows in circular-buffer order, shared with the grid
+    pub scrollback_rows: Vec<ScrollbackRow>,
     /// Start index of the circular scrollback buffer
     pub scrollback_start: usize,
     /// Number of lines currently in scrollback
@@ -32,10 +34,23 @@ pub struct GridSnapshot {
     pub scrollback_wrapped: Vec<bool>,
     /// Semantic zones
     pub zones: Vec<Zone>,
+    /// Cell annotations
+    pub annotations: Vec<Annotation>,
+    /// Last write time per absolute row (Unix ms, 0 = never written)
+    pub row_times: std::collections::VecDeque<u64>,
     /// Total number of lines ever scrolled into scrollback
     pub total_lines_scrolled: usize,
 }
 
+impl GridSnapshot {
+    /// Total number of cells held in scrollback rows.
+    ///
+    /// Rows shared with the live grid or other snapshots are counted in full.
+    pub fn scrollback_cell_count(&self) -> usize {
+        self.scrollback_rows.iter().map(|row| row.len()).sum()
+    }
+}
+
 /// Complete snapshot of terminal state at a point in time.
 #[derive(Debug, Clone)]
 pub struct TerminalSnapshot {
@@ -139,7 +154,8 @@ impl TerminalSnapshot {
     /// Estimate the memory footprint of this snapshot in bytes.
     ///
     /// This is a rough estimate covering the dominant cost centres
-    /// (cell Vecs, scrollback, wrapped flags, tab stops, and zones).
+    /// (cell Vecs, scrollback, wrapped flags, row timestamps, tab stops, zones
+    /// and annotations).
     /// Small fixed-size fields are approximated by `size_of::<Self>()`.
     pub fn estimate_size(&self) -> usize {
         let base = std::mem::size_of::<Self>();
@@ -148,9 +164,9 @@ impl TerminalSnapshot {
         // Approximate per-cell overhead as size_of::<Cell>() + 24 bytes for the
         // Vec header (pointer + len + cap) even when empty.
         let cell_size = std::mem::size_of::<Cell>();
-        let grid_cells = (self.grid.cells.len() + self.grid.scrollback_cells.len()) * cell_size;
+        let grid_cells = (self.grid.cells.len() + self.grid.scrollback_cell_count()) * cell_size;
         let alt_grid_cells =
-            (self.alt_grid.cells.len() + self.alt_grid.scrollback_cells.len()) * cell_size;
+            (self.alt_grid.cells.len() + self.alt_grid.scrollback_cell_count()) * cell_size;
 
         let wrapped_size = self.grid.wrapped.len()
             + self.grid.scrollback_wrapped.len()
@@ -160,10 +176,23 @@ impl TerminalSnapshot {
         let zone_size =
             (self.grid.zones.len() + self.alt_grid.zones.len()) * std::mem::size_of::<Zone>();
 
+        let annotation_size = (self.grid.annotations.len() + self.alt_grid.annotations.len())
+            * std::mem::size_of::<Annotation>();
+
+        let row_times_size = (self.grid.row_times.len() + self.alt_grid.row_times.len())
+            * std::mem::size_of::<u64>();
+
         let tab_stops_size = self.tab_stops.len();
         let title_size = self.title.len();
 
-        base + grid_cells + alt_grid_cells + wrapped_size + zone_size + tab_stops_size + title_size
+        base + grid_cells
+            + alt_grid_cells
+            + wrapped_size
+            + zone_size
+            + annotation_size
+            + row_times_size
+            + tab_stops_size
+            + title_size
     }
 }
 
@@ -273,7 +302,7 @@ mod tests {
     fn make_grid_snapshot(cols: usize, rows: usize) -> GridSnapshot {
         GridSnapshot {
             cells: vec![Cell::default(); cols * rows],
-            scrollback_cells: Vec::new(),
+            scrollback_rows: Vec::new(),
             scrollback_start: 0,
             scrollback_lines: 0,
             max_scrollback: 1000,
@@ -282,6 +311,8 @@ mod tests {
             wrapped: vec![false; rows],
             scrollback_wrapped: Vec::new(),
             zones: Vec::new(),
+            annotations: Vec::new(),
+            row_times: std::collections::VecDeque::new(),
             total_lines_scrolled: 0,
         }
     }
@@ -379,7 +410,9 @@ mod tests {
     #[test]
     fn test_replay_snapshot_with_scrollback() {
         let mut grid = make_grid_snapshot(80, 24);
-        grid.scrollback_cells = vec![Cell::default(); 80 * 100];
+        grid.scrollback_rows = (0..100)
+            .map(|_| ScrollbackRow::from(vec![Cell::default(); 80]))
+            .collect();
         grid.scrollback_lines = 100;
 
         let mut snap = make_replay_snapshot(80, 24);
@@ -418,7 +451,7 @@ mod tests {
         assert_eq!(gs.rows, 40);
         assert_eq!(gs.cells.len(), 120 * 40);
         assert_eq!(gs.wrapped.len(), 40);
-        assert_eq!(gs.scrollback_cells.len(), 0);
+        assert_eq!(gs.scrollback_rows.len(), 0);
         assert_eq!(gs.scrollback_lines, 0);
         assert_eq!(gs.max_scrollback, 1000);
         assert_eq!(gs.total_lines_scrolled, 0);
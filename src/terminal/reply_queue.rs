@@ -0,0 +1,292 @@
+//! Prioritized, bounded queue of replies owed to the host.
+//!
+//! Device queries (DA, DSR, DECRQM, color queries, ...) and a few data
+//! transfers produce bytes that must be written back to the PTY. They are
+//! queued here and leave in priority order through
+//! [`Terminal::drain_responses`]: short [`ReplyPriority::Query`] replies go
+//! out before [`ReplyPriority::Bulk`] payloads such as OSC 52 clipboard
+//! contents, so a large reply never delays an application waiting on DA.
+//! Query replies keep their order among themselves, because applications
+//! routinely send a query followed by DA1 and treat the DA reply as "no
+//! answer is coming".
+//!
+//! The queue is capped at [`Terminal::max_reply_queue_bytes`] so a program
+//! that floods queries while nobody drains cannot grow it without bound; the
+//! [`ReplyOverflowPolicy`] decides what gives way. Upload data the embedder
+//! sends with [`Terminal::send_upload_data`] is exempt: it is queued in full
+//! and never evicted. Pending replies and the configuration survive a hard
+//! reset (RIS), since the host is still owed those bytes.
+
+use std::collections::VecDeque;
+
+use crate::debug;
+use crate::terminal::Terminal;
+
+/// Default cap on queued reply bytes (1 MiB)
+pub const DEFAULT_MAX_REPLY_QUEUE_BYTES: usize = 1024 * 1024;
+
+/// Delivery priority of a reply
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ReplyPriority {
+    /// Short replies to device and status queries, delivered first and in
+    /// arrival order
+    Query,
+    /// Large payloads (clipboard contents, file transfer data), delivered
+    /// after all query replies
+    Bulk,
+}
+
+/// What happens when a reply does not fit under the queue cap
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReplyOverflowPolicy {
+    /// Discard the oldest queued replies of lower priority to make room, then
+    /// drop the new reply if it still does not fit
+    #[default]
+    EvictLowerPriority,
+    /// Drop the new reply and keep everything already queued
+    DropNewest,
+}
+
+/// A queued bulk reply; pinned replies ignore the cap and are never evicted
+#[derive(Debug)]
+struct BulkReply {
+    bytes: Vec<u8>,
+    pinned: bool,
+}
+
+#[derive(Debug)]
+pub(crate) struct ReplyQueue {
+    query: Vec<u8>,
+    bulk: VecDeque<BulkReply>,
+    /// Bytes counted against `max_len` (pinned replies excluded)
+    len: usize,
+    max_len: usize,
+    policy: ReplyOverflowPolicy,
+    dropped: u64,
+}
+
+impl Default for ReplyQueue {
+    fn default() -> Self {
+        Self {
+            query: Vec::new(),
+            bulk: VecDeque::new(),
+            len: 0,
+            max_len: DEFAULT_MAX_REPLY_QUEUE_BYTES,
+            policy: ReplyOverflowPolicy::default(),
+            dropped: 0,
+        }
+    }
+}
+
+impl ReplyQueue {
+    /// Queue `bytes`, returning false if the overflow policy dropped them
+    pub(crate) fn push(&mut self, priority: ReplyPriority, bytes: &[u8]) -> bool {
+        if bytes.is_empty() {
+            return true;
+        }
+        if self.len + bytes.len() > self.max_len
+            && !(self.policy == ReplyOverflowPolicy::EvictLowerPriority
+                && self.evict_below(priority, bytes.len()))
+        {
+            self.dropped += 1;
+            debug::log(
+                debug::DebugLevel::Debug,
+                "REPLY",
+                &format!(
+                    "Reply queue full ({} of {} bytes), dropping {} byte {:?} reply",
+                    self.len,
+                    self.max_len,
+                    bytes.len(),
+                    priority
+                ),
+            );
+            return false;
+        }
+
+        self.len += bytes.len();
+        match priority {
+            ReplyPriority::Query => self.query.extend_from_slice(bytes),
+            ReplyPriority::Bulk => self.bulk.push_back(BulkReply {
+                bytes: bytes.to_vec(),
+                pinned: false,
+            }),
+        }
+        true
+    }
+
+    /// Queue bulk bytes regardless of the cap, never to be evicted
+    pub(crate) fn push_pinned(&mut self, bytes: &[u8]) {
+        self.bulk.push_back(BulkReply {
+            bytes: bytes.to_vec(),
+            pinned: true,
+        });
+    }
+
+    /// Drop every queued pinned reply, returning how many bytes were dropped
+    pub(crate) fn discard_pinned(&mut self) -> usize {
+        let before = self.total_len();
+        self.bulk.retain(|reply| !reply.pinned);
+        before - self.total_len()
+    }
+
+    /// Evict the oldest unpinned replies below `priority` until `needed`
+    /// more bytes fit. Returns false, evicting nothing, if that is not enough.
+    fn evict_below(&mut self, priority: ReplyPriority, needed: usize) -> bool {
+        if priority >= ReplyPriority::Bulk {
+            return false;
+        }
+        let evictable: usize = self
+            .bulk
+            .iter()
+            .filter(|reply| !reply.pinned)
+            .map(|reply| reply.bytes.len())
+            .sum();
+        if self.len - evictable + needed > self.max_len {
+            return false;
+        }
+
+        let mut kept = VecDeque::with_capacity(self.bulk.len());
+        while let Some(reply) = self.bulk.pop_front() {
+            if !reply.pinned && self.len + needed > self.max_len {
+                self.len -= reply.bytes.len();
+                self.dropped += 1;
+            } else {
+                kept.push_back(reply);
+            }
+        }
+        self.bulk = kept;
+        true
+    }
+
+    /// Take every queued byte, query replies first
+    pub(crate) fn drain(&mut self) -> Vec<u8> {
+        let mut out = std::mem::take(&mut self.query);
+        for reply in self.bulk.drain(..) {
+            out.extend_from_slice(&reply.bytes);
+        }
+        self.len = 0;
+        out
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.query.is_empty() && self.bulk.is_empty()
+    }
+
+    /// Total queued bytes, pinned replies included
+    pub(crate) fn total_len(&self) -> usize {
+        self.query.len()
+            + self
+                .bulk
+                .iter()
+                .map(|reply| reply.bytes.len())
+                .sum::<usize>()
+    }
+}
+
+impl Terminal {
+    /// Queue a reply to be written back to the PTY with the given priority.
+    ///
+    /// Returns false if the queue was full and the overflow policy dropped
+    /// the reply.
+    pub fn push_reply(&mut self, priority: ReplyPriority, bytes: &[u8]) -> bool {
+        self.reply_queue.push(priority, bytes)
+    }
+
+    /// Queue embedder-originated data that must not be dropped
+    pub(crate) fn push_pinned_reply(&mut self, bytes: &[u8]) {
+        self.reply_queue.push_pinned(bytes);
+    }
+
+    /// Maximum number of queued reply bytes
+    pub fn max_reply_queue_bytes(&self) -> usize {
+        self.reply_queue.max_len
+    }
+
+    /// Set the maximum number of queued reply bytes. Replies already queued
+    /// are kept even if they exceed the new limit.
+    pub fn set_max_reply_queue_bytes(&mut self, max: usize) {
+        self.reply_queue.max_len = max;
+    }
+
+    /// Policy applied when a reply does not fit under the queue cap
+    pub fn reply_overflow_policy(&self) -> ReplyOverflowPolicy {
+        self.reply_queue.policy
+    }
+
+    /// Set the policy applied when a reply does not fit under the queue cap
+    pub fn set_reply_overflow_policy(&mut self, policy: ReplyOverflowPolicy) {
+        self.reply_queue.policy = policy;
+    }
+
+    /// Number of replies dropped or evicted because the queue was full
+    pub fn dropped_reply_count(&self) -> u64 {
+        self.reply_queue.dropped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue(max_len: usize, policy: ReplyOverflowPolicy) -> ReplyQueue {
+        ReplyQueue {
+            max_len,
+            policy,
+            ..ReplyQueue::default()
+        }
+    }
+
+    #[test]
+    fn query_replies_drain_before_bulk_in_order() {
+        let mut q = ReplyQueue::default();
+        q.push(ReplyPriority::Bulk, b"clip1");
+        q.push(ReplyPriority::Query, b"da");
+        q.push(ReplyPriority::Bulk, b"clip2");
+        q.push(ReplyPriority::Query, b"dsr");
+        assert_eq!(q.total_len(), 15);
+        assert_eq!(q.drain(), b"dadsrclip1clip2");
+        assert!(q.is_empty());
+        assert_eq!(q.len, 0);
+    }
+
+    #[test]
+    fn query_evicts_oldest_bulk_when_full() {
+        let mut q = queue(10, ReplyOverflowPolicy::EvictLowerPriority);
+        assert!(q.push(ReplyPriority::Bulk, b"aaaa"));
+        assert!(q.push(ReplyPriority::Bulk, b"bbbb"));
+        assert!(q.push(ReplyPriority::Query, b"qqqq"));
+        assert_eq!(q.dropped, 1);
+        // Bulk never evicts bulk
+        assert!(!q.push(ReplyPriority::Bulk, b"cccc"));
+        assert_eq!(q.dropped, 2);
+        assert_eq!(q.drain(), b"qqqqbbbb");
+    }
+
+    #[test]
+    fn reply_that_cannot_fit_evicts_nothing() {
+        let mut q = queue(8, ReplyOverflowPolicy::EvictLowerPriority);
+        q.push(ReplyPriority::Query, b"qqqq");
+        q.push(ReplyPriority::Bulk, b"bb");
+        assert!(!q.push(ReplyPriority::Query, b"0123456"));
+        assert_eq!(q.drain(), b"qqqqbb");
+    }
+
+    #[test]
+    fn drop_newest_keeps_queued_replies() {
+        let mut q = queue(6, ReplyOverflowPolicy::DropNewest);
+        q.push(ReplyPriority::Bulk, b"bbbb");
+        assert!(!q.push(ReplyPriority::Query, b"qqqq"));
+        assert_eq!(q.dropped, 1);
+        assert_eq!(q.drain(), b"bbbb");
+    }
+
+    #[test]
+    fn pinned_bytes_ignore_cap_and_eviction() {
+        let mut q = queue(4, ReplyOverflowPolicy::EvictLowerPriority);
+        q.push_pinned(b"upload-data");
+        assert!(q.push(ReplyPriority::Query, b"qqqq"));
+        assert!(!q.push(ReplyPriority::Query, b"q"));
+        assert_eq!(q.total_len(), 15);
+        assert_eq!(q.drain(), b"qqqqupload-data");
+    }
+}
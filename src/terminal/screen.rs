@@ -13,6 +13,21 @@ pub enum SelectionMode {
     Block,
 }
 
+/// How row boundaries are treated when copying a selection or joining
+/// wrapped lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JoinMode {
+    /// Join soft-wrapped rows; keep a line break after every other row
+    Always,
+    /// Keep a line break after every row, wrapped or not
+    Never,
+    /// Like `Always`, but also join a full row that breaks mid-identifier
+    /// or right after a hyphen, as when a program wraps its own output at
+    /// the margin (default)
+    #[default]
+    Smart,
+}
+
 /// Selection state
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Selection {
@@ -24,6 +39,32 @@ pub struct Selection {
     pub mode: SelectionMode,
 }
 
+impl Selection {
+    /// Whether (col, row) lies inside this selection, using the same bounds as
+    /// `Terminal::get_selected_text` (end column exclusive).
+    pub fn contains(&self, col: usize, row: usize) -> bool {
+        match self.mode {
+            SelectionMode::Character => {
+                let (start, end) = if (self.start.1, self.start.0) <= (self.end.1, self.end.0) {
+                    (self.start, self.end)
+                } else {
+                    (self.end, self.start)
+                };
+                (row, col) >= (start.1, start.0) && (row, col) < (end.1, end.0)
+            }
+            SelectionMode::Line => {
+                row >= self.start.1.min(self.end.1) && row <= self.start.1.max(self.end.1)
+            }
+            SelectionMode::Block => {
+                row >= self.start.1.min(self.end.1)
+                    && row <= self.start.1.max(self.end.1)
+                    && col >= self.start.0.min(self.end.0)
+                    && col < self.start.0.max(self.end.0)
+            }
+        }
+    }
+}
+
 /// Damage region for incremental rendering
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct DamageRegion {
@@ -263,45 +304,99 @@ pub fn hsl_to_rgb(hsl: ColorHSL) -> (u8, u8, u8) {
     )
 }
 
+use crate::cell::Cell;
+use crate::row_text::RowText;
 use crate::terminal::Terminal;
 
+/// Whether a full row that isn't marked as wrapped looks like it was broken
+/// mid-identifier: it ends in an identifier character or a hyphen
+/// (`--long-` / `option`) and the next row starts with an identifier
+/// character. A leading hyphen doesn't count, so list items stay separate.
+fn splits_identifier(line: &[Cell], next: &[Cell]) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    line.last()
+        .is_some_and(|cell| is_ident(cell.c) || cell.c == '-')
+        && next.first().is_some_and(|cell| is_ident(cell.c))
+}
+
 impl Terminal {
     // === Feature 9: Line Wrapping Utilities ===
 
+    /// How [`Terminal::get_selected_text`] and [`Terminal::join_wrapped_lines`]
+    /// join rows
+    pub fn join_mode(&self) -> JoinMode {
+        self.join_mode
+    }
+
+    /// Set how [`Terminal::get_selected_text`] and
+    /// [`Terminal::join_wrapped_lines`] join rows
+    pub fn set_join_mode(&mut self, mode: JoinMode) {
+        self.join_mode = mode;
+    }
+
+    /// Whether the line break after `row` is dropped under the join mode
+    fn joins_next_row(&self, row: usize) -> bool {
+        let grid = self.active_grid();
+        if row + 1 >= grid.rows() {
+            return false;
+        }
+        match self.join_mode {
+            JoinMode::Never => false,
+            JoinMode::Always => grid.is_line_wrapped(row),
+            JoinMode::Smart => {
+                grid.is_line_wrapped(row)
+                    || match (grid.row(row), grid.row(row + 1)) {
+                        (Some(line), Some(next)) => splits_identifier(line, next),
+                        _ => false,
+                    }
+            }
+        }
+    }
+
+    /// Append one row's text, followed by a line break unless it is the
+    /// `last` row or joins the next one
+    fn push_row_text(&self, text: &mut String, piece: &str, row: usize, last: bool) {
+        text.push_str(piece);
+        if !last && !self.joins_next_row(row) {
+            text.push('\n');
+        }
+    }
+
     /// Join wrapped lines starting from a given row
     ///
-    /// Unwraps soft-wrapped lines into a single logical line.
+    /// Unwraps soft-wrapped lines into a single logical line, following the
+    /// [`JoinMode`] set with [`Terminal::set_join_mode`].
     pub fn join_wrapped_lines(&self, start_row: usize) -> Option<JoinedLines> {
         let grid = self.active_grid();
         if start_row >= grid.rows() {
             return None;
         }
 
-        let mut lines = Vec::new();
+        let mut text = String::new();
         let mut current_row = start_row;
-
-        // Collect the first line
-        if let Some(line) = grid.row(current_row) {
-            lines.push(crate::terminal::cells_to_text(line));
-        } else {
-            return None;
-        }
-
-        // Follow wrapped lines
-        while current_row < grid.rows() - 1 && grid.is_line_wrapped(current_row) {
-            current_row += 1;
-            if let Some(line) = grid.row(current_row) {
-                lines.push(crate::terminal::cells_to_text(line));
-            } else {
+        let mut lines_joined = 0;
+
+        // Follow joined rows
+        while let Some(line) = grid.row(current_row) {
+            lines_joined += 1;
+            let joins = self.joins_next_row(current_row);
+            self.push_row_text(
+                &mut text,
+                &crate::terminal::cells_to_text(line),
+                current_row,
+                !joins,
+            );
+            if !joins {
                 break;
             }
+            current_row += 1;
         }
 
         Some(JoinedLines {
-            text: lines.join(""),
+            text,
             start_row,
             end_row: current_row,
-            lines_joined: lines.len(),
+            lines_joined,
         })
     }
 
@@ -347,16 +442,29 @@ impl Terminal {
         self.join_wrapped_lines(start).map(|j| j.text)
     }
 
+    /// Text of a screen row with its column mapping
+    ///
+    /// Use the returned [`RowText`] to convert between columns and byte or
+    /// char offsets in the text, which differ after wide characters and
+    /// combining marks.
+    pub fn row_text_map(&self, row: usize) -> Option<RowText> {
+        self.active_grid().row(row).map(RowText::new)
+    }
+
     /// Get the word at the given position
     pub fn get_word_at(&self, col: usize, row: usize, word_chars: Option<&str>) -> Option<String> {
-        let grid = self.active_grid();
-        let line = grid.row(row)?;
-        let line_text = crate::terminal::cells_to_text(line);
-
-        if col >= line_text.len() {
-            return None;
-        }
+        self.word_bounds_at(col, row, word_chars)
+            .map(|(_, _, word)| word)
+    }
 
+    /// Columns `[start, end)` and text of the word at the given position
+    fn word_bounds_at(
+        &self,
+        col: usize,
+        row: usize,
+        word_chars: Option<&str>,
+    ) -> Option<(usize, usize, String)> {
+        let row_text = self.row_text_map(row)?;
         let is_word_char = |c: char| {
             if let Some(chars) = word_chars {
                 chars.contains(c)
@@ -364,20 +472,32 @@ impl Terminal {
                 c.is_alphanumeric() || c == '_'
             }
         };
+        // Whole cells are compared, so a base character keeps its combining marks
+        let is_word_cell = |col: usize| {
+            row_text
+                .grapheme_at(col)
+                .and_then(|g| g.chars().next())
+                .is_some_and(is_word_char)
+        };
 
-        let chars: Vec<char> = line_text.chars().collect();
-        let mut start = col;
-        while start > 0 && is_word_char(chars[start - 1]) {
-            start -= 1;
+        let (mut start, _) = row_text.cell_at(col)?;
+        let mut end = start;
+        while let Some((prev, _)) = start.checked_sub(1).and_then(|c| row_text.cell_at(c)) {
+            if !is_word_cell(prev) {
+                break;
+            }
+            start = prev;
         }
-
-        let mut end = col;
-        while end < chars.len() && is_word_char(chars[end]) {
-            end += 1;
+        while let Some((_, next)) = row_text.cell_at(end) {
+            if !is_word_cell(end) {
+                break;
+            }
+            end = next;
         }
 
         if start < end {
-            Some(chars[start..end].iter().collect())
+            let (first, last) = row_text.col_range_to_bytes(start, end);
+            Some((start, end, row_text.text()[first..last].to_string()))
         } else {
             None
         }
@@ -471,14 +591,109 @@ impl Terminal {
         self.selection = Some(Selection { start, end, mode });
     }
 
-    /// Get the current selection
+    /// Get the current (primary) selection
     pub fn get_selection(&self) -> Option<Selection> {
         self.selection.clone()
     }
 
-    /// Get the text content of the current selection
+    /// Add a selection alongside the current one, e.g. for multi-cursor copy
+    /// or highlighting every search match; returns its index in
+    /// [`Terminal::selections`]
+    ///
+    /// With no current selection this becomes the primary selection.
+    /// [`Terminal::set_selection`] and the `select_*` helpers only replace the
+    /// primary selection; [`Terminal::clear_selection`] removes them all.
+    pub fn add_selection(
+        &mut self,
+        start: (usize, usize),
+        end: (usize, usize),
+        mode: SelectionMode,
+    ) -> usize {
+        let sel = Selection { start, end, mode };
+        if self.selection.is_none() {
+            self.selection = Some(sel);
+            return 0;
+        }
+        self.extra_selections.push(sel);
+        self.extra_selections.len()
+    }
+
+    /// All selections, primary first, then in the order they were added
+    pub fn selections(&self) -> Vec<Selection> {
+        self.selection
+            .iter()
+            .chain(&self.extra_selections)
+            .cloned()
+            .collect()
+    }
+
+    /// Remove the selection at `index` in [`Terminal::selections`]; removing
+    /// the primary promotes the next one. Returns false when out of range.
+    pub fn remove_selection(&mut self, index: usize) -> bool {
+        let primary = usize::from(self.selection.is_some());
+        if index < primary {
+            self.selection = if self.extra_selections.is_empty() {
+                None
+            } else {
+                Some(self.extra_selections.remove(0))
+            };
+            true
+        } else if index - primary < self.extra_selections.len() {
+            self.extra_selections.remove(index - primary);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Whether the cell at (col, row) on the active screen lies inside any
+    /// selection
+    pub fn is_cell_selected(&self, col: usize, row: usize) -> bool {
+        self.selection
+            .iter()
+            .chain(&self.extra_selections)
+            .any(|sel| sel.contains(col, row))
+    }
+
+    /// Get the text content of the current selections, with secrets masked
+    /// when redaction is enabled
+    ///
+    /// Character and line selections join rows according to the
+    /// [`JoinMode`]; block selections keep one line per row. With several
+    /// selections their texts are joined with newlines in screen order.
     pub fn get_selected_text(&self) -> Option<String> {
-        let sel = self.selection.as_ref()?;
+        let texts: Vec<String> = self
+            .selections_in_screen_order()
+            .into_iter()
+            .filter_map(|sel| {
+                let top = sel.start.1.min(sel.end.1);
+                let text = self.selected_text_unredacted(sel)?;
+                Some(self.redact_export(text, self.screen_start_row().map(|row| row + top)))
+            })
+            .collect();
+        if texts.is_empty() {
+            None
+        } else {
+            Some(texts.join("\n"))
+        }
+    }
+
+    /// Primary and added selections, sorted by their top-left corner
+    pub(crate) fn selections_in_screen_order(&self) -> Vec<&Selection> {
+        let mut sels: Vec<&Selection> = self
+            .selection
+            .iter()
+            .chain(&self.extra_selections)
+            .collect();
+        sels.sort_by_key(|sel| {
+            let (start, end) = (sel.start, sel.end);
+            (start.1.min(end.1), start.0.min(end.0))
+        });
+        sels
+    }
+
+    /// Selected text before secrets are masked
+    fn selected_text_unredacted(&self, sel: &Selection) -> Option<String> {
         let grid = self.active_grid();
 
         let (start_row, start_col) = (sel.start.1.min(sel.end.1), sel.start.0.min(sel.end.0));
@@ -498,10 +713,12 @@ impl Terminal {
                         };
 
                         if row_start < line_text.len() {
-                            text.push_str(&line_text[row_start..row_end]);
-                            if row < end_row {
-                                text.push('\n');
-                            }
+                            self.push_row_text(
+                                &mut text,
+                                &line_text[row_start..row_end],
+                                row,
+                                row == end_row,
+                            );
                         }
                     }
                 }
@@ -511,10 +728,12 @@ impl Terminal {
                 let mut text = String::new();
                 for row in start_row..=end_row {
                     if let Some(line) = grid.row(row) {
-                        text.push_str(&crate::terminal::cells_to_text(line));
-                        if row < end_row {
-                            text.push('\n');
-                        }
+                        self.push_row_text(
+                            &mut text,
+                            &crate::terminal::cells_to_text(line),
+                            row,
+                            row == end_row,
+                        );
                     }
                 }
                 Some(text)
@@ -542,21 +761,7 @@ impl Terminal {
 
     /// Select the word at the given position
     pub fn select_word_at(&mut self, col: usize, row: usize) {
-        if let Some(word) = self.get_word_at(col, row, None) {
-            // Find word boundaries
-            let grid = self.active_grid();
-            if let Some(line) = grid.row(row) {
-                let line_text = crate::terminal::cells_to_text(line);
-                if let Some(word_start) = line_text.find(word.as_str()) {
-                    let word_end = word_start + word.len();
-                    self.selection = Some(Selection {
-                        start: (word_start, row),
-                        end: (word_end, row),
-                        mode: SelectionMode::Character,
-                    });
-                }
-            }
-        }
+        self.select_word(col, row, None);
     }
 
     /// Select the word at the given position
@@ -566,23 +771,13 @@ impl Terminal {
         row: usize,
         word_chars: Option<&str>,
     ) -> Option<((usize, usize), (usize, usize))> {
-        if let Some(word) = self.get_word_at(col, row, word_chars) {
-            // Find word boundaries in the line
-            let grid = self.active_grid();
-            if let Some(line) = grid.row(row) {
-                let line_text = crate::terminal::cells_to_text(line);
-                if let Some(word_start) = line_text.find(word.as_str()) {
-                    let word_end = word_start + word.len();
-                    self.selection = Some(Selection {
-                        start: (word_start, row),
-                        end: (word_end, row),
-                        mode: SelectionMode::Character,
-                    });
-                    return Some(((word_start, row), (word_end, row)));
-                }
-            }
-        }
-        None
+        let (start, end, _) = self.word_bounds_at(col, row, word_chars)?;
+        self.selection = Some(Selection {
+            start: (start, row),
+            end: (end, row),
+            mode: SelectionMode::Character,
+        });
+        Some(((start, row), (end, row)))
     }
 
     /// Select the entire line at the given row
@@ -596,9 +791,11 @@ impl Terminal {
         });
     }
 
-    /// Clear the current selection
+    /// Clear the current selection and any added with
+    /// [`Terminal::add_selection`]
     pub fn clear_selection(&mut self) {
         self.selection = None;
+        self.extra_selections.clear();
     }
 
     /// Select a semantic region based on delimiters
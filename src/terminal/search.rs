@@ -20,12 +20,33 @@ pub struct SearchMatch {
     pub row: isize,
     /// Column where match starts (0-indexed)
     pub col: usize,
-    /// Length of the match in characters
+    /// Width of the match in columns (a wide character counts as 2)
     pub length: usize,
     /// Matched text
     pub text: String,
 }
 
+/// Options for plain-text search
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextSearchOptions {
+    /// Match case exactly; otherwise the query and the screen text are case
+    /// folded before comparing
+    pub case_sensitive: bool,
+    /// Normalization applied to the query and to each cell before comparing,
+    /// so composed and decomposed forms (or, with NFKC, compatibility
+    /// variants such as ligatures) match each other
+    pub normalization: NormalizationForm,
+}
+
+impl Default for TextSearchOptions {
+    fn default() -> Self {
+        Self {
+            case_sensitive: true,
+            normalization: NormalizationForm::NFC,
+        }
+    }
+}
+
 /// Detected content item (URL, file path, etc.)
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DetectedItem {
@@ -50,9 +71,9 @@ pub struct RegexMatch {
     pub col: usize,
     /// Row where match ends
     pub end_row: usize,
-    /// Column where match ends
+    /// Column where match ends (exclusive)
     pub end_col: usize,
-    /// Length of match in characters
+    /// Width of the match in columns (a wide character counts as 2)
     pub length: usize,
     /// Matched text
     pub text: String,
@@ -87,7 +108,10 @@ impl Default for RegexSearchOptions {
     }
 }
 
+use crate::cell::Cell;
+use crate::row_text::RowText;
 use crate::terminal::Terminal;
+use crate::unicode_normalization_config::NormalizationForm;
 
 impl Terminal {
     // === Feature 15: Regex Search in Scrollback ===
@@ -107,7 +131,6 @@ impl Terminal {
         let re = builder.build().map_err(|e| e.to_string())?;
         self.search.current_regex_pattern = Some(pattern.to_string());
 
-        let mut all_content = Vec::new();
         let grid = self.active_grid();
 
         // Collect content based on options
@@ -117,14 +140,13 @@ impl Terminal {
             0
         };
 
-        for row in start_row..(grid.rows() as isize) {
-            let row_text = grid.row_text(row as usize);
-            all_content.push((row, row_text));
-        }
-
         let mut matches = Vec::new();
-        for (row_idx, text) in all_content {
-            for caps in re.captures_iter(&text) {
+        for row_idx in start_row..(grid.rows() as isize) {
+            let Some(cells) = grid.row(row_idx as usize) else {
+                continue;
+            };
+            let row_text = RowText::new(cells);
+            for caps in re.captures_iter(row_text.text()) {
                 if let Some(m) = caps.get(0) {
                     let mut captures = Vec::new();
                     for i in 0..caps.len() {
@@ -135,15 +157,13 @@ impl Terminal {
                         );
                     }
 
-                    let char_start = text[..m.start()].chars().count();
-                    let char_len = m.as_str().chars().count();
-
+                    let (col, end_col) = row_text.byte_range_to_cols(m.start(), m.end());
                     matches.push(RegexMatch {
                         row: row_idx as usize,
-                        col: char_start,
+                        col,
                         end_row: row_idx as usize,
-                        end_col: char_start + char_len,
-                        length: char_len,
+                        end_col,
+                        length: end_col - col,
                         text: m.as_str().to_string(),
                         captures,
                     });
@@ -238,71 +258,49 @@ impl Terminal {
 
     // === Search Methods ===
 
-    /// Convert a byte offset within a string to a character (grapheme) offset.
-    /// This is needed because `String::find()` returns byte offsets, but terminal
-    /// columns are based on character positions.
-    fn byte_offset_to_char_offset(s: &str, byte_offset: usize) -> usize {
-        s[..byte_offset].chars().count()
-    }
-
     /// Search for text in the visible screen area
     ///
     /// Returns a vector of SearchMatch results containing position and matched text.
     /// Row indices are 0-based, with 0 being the top row of the visible screen.
+    /// The query and screen text are NFC normalized (see
+    /// [`Self::search_text_with_options`]).
     ///
     /// # Arguments
     /// * `query` - The text to search for
     /// * `case_sensitive` - Whether the search should be case-sensitive
     pub fn search_text(&self, query: &str, case_sensitive: bool) -> Vec<SearchMatch> {
-        let mut matches = Vec::new();
-        if query.is_empty() {
-            return matches;
+        self.search_text_with_options(
+            query,
+            &TextSearchOptions {
+                case_sensitive,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Search for text in the visible screen area with explicit case folding
+    /// and normalization
+    ///
+    /// Each cell is compared as a whole grapheme: a match never starts or ends
+    /// inside a cell, and columns account for wide characters and combining
+    /// marks.
+    pub fn search_text_with_options(
+        &self,
+        query: &str,
+        options: &TextSearchOptions,
+    ) -> Vec<SearchMatch> {
+        let needle = fold_text(query, options);
+        if needle.is_empty() {
+            return Vec::new();
         }
 
         let grid = self.active_grid();
-        let search_query = if case_sensitive {
-            query.to_string()
-        } else {
-            query.to_lowercase()
-        };
-
+        let mut matches = Vec::new();
         for row in 0..grid.rows() {
-            if let Some(line) = grid.row(row) {
-                let line_text = crate::terminal::cells_to_text(line);
-                let search_text = if case_sensitive {
-                    line_text.clone()
-                } else {
-                    line_text.to_lowercase()
-                };
-
-                // Use byte offsets for String::find(), but convert to char offsets for results
-                let query_char_len = query.chars().count();
-                let mut start_byte = 0;
-                while let Some(pos) = search_text[start_byte..].find(&search_query) {
-                    let match_byte_offset = start_byte + pos;
-                    let char_column =
-                        Self::byte_offset_to_char_offset(&search_text, match_byte_offset);
-
-                    // Extract matched text using character iteration
-                    let matched_text: String = line_text
-                        .chars()
-                        .skip(char_column)
-                        .take(query_char_len)
-                        .collect();
-
-                    matches.push(SearchMatch {
-                        row: row as isize,
-                        col: char_column,
-                        length: query_char_len,
-                        text: matched_text,
-                    });
-
-                    // Advance by at least 1 byte (or the query length in bytes) to find next match
-                    start_byte = match_byte_offset + search_query.len().max(1);
-                }
+            if let Some(cells) = grid.row(row) {
+                find_in_row(cells, options, &needle, row as isize, &mut matches);
             }
         }
-
         matches
     }
 
@@ -315,66 +313,87 @@ impl Terminal {
     /// * `query` - The text to search for
     /// * `case_sensitive` - Whether the search should be case-sensitive
     /// * `max_lines` - Maximum number of scrollback lines to search (None = search all)
+    ///
+    /// With the scrollback search index enabled (see
+    /// [`Self::set_scrollback_search_index`]) cached line text is searched and
+    /// lines that cannot contain the query are skipped without scanning.
     pub fn search_scrollback(
         &self,
         query: &str,
         case_sensitive: bool,
         max_lines: Option<usize>,
     ) -> Vec<SearchMatch> {
-        let mut matches = Vec::new();
-        if query.is_empty() {
-            return matches;
-        }
+        self.search_scrollback_with_options(
+            query,
+            &TextSearchOptions {
+                case_sensitive,
+                ..Default::default()
+            },
+            max_lines,
+        )
+    }
 
-        let search_query = if case_sensitive {
-            query.to_string()
-        } else {
-            query.to_lowercase()
-        };
+    /// Search the scrollback buffer with explicit case folding and
+    /// normalization (see [`Self::search_text_with_options`])
+    pub fn search_scrollback_with_options(
+        &self,
+        query: &str,
+        options: &TextSearchOptions,
+        max_lines: Option<usize>,
+    ) -> Vec<SearchMatch> {
+        let needle = fold_text(query, options);
+        if needle.is_empty() {
+            return Vec::new();
+        }
 
-        let scrollback_len = self.grid().scrollback_len();
+        let grid = self.grid();
+        let scrollback_len = grid.scrollback_len();
         let lines_to_search = max_lines.unwrap_or(scrollback_len).min(scrollback_len);
+        let query_mask = crate::grid::query_trigram_mask(&needle);
 
+        let mut matches = Vec::new();
         for i in 0..lines_to_search {
-            if let Some(line) = self.grid().scrollback_line(i) {
-                let line_text = crate::terminal::cells_to_text(line);
-                let search_text = if case_sensitive {
-                    line_text.clone()
-                } else {
-                    line_text.to_lowercase()
-                };
-
-                // Use byte offsets for String::find(), but convert to char offsets for results
-                let query_char_len = query.chars().count();
-                let mut start_byte = 0;
-                while let Some(pos) = search_text[start_byte..].find(&search_query) {
-                    let match_byte_offset = start_byte + pos;
-                    let char_column =
-                        Self::byte_offset_to_char_offset(&search_text, match_byte_offset);
-
-                    // Extract matched text using character iteration
-                    let matched_text: String = line_text
-                        .chars()
-                        .skip(char_column)
-                        .take(query_char_len)
-                        .collect();
-
-                    matches.push(SearchMatch {
-                        row: -((i + 1) as isize), // Negative indices for scrollback
-                        col: char_column,
-                        length: query_char_len,
-                        text: matched_text,
-                    });
-
-                    // Advance by at least 1 byte (or the query length in bytes) to find next match
-                    start_byte = match_byte_offset + search_query.len().max(1);
+            let row = -((i + 1) as isize); // Negative indices for scrollback
+
+            // ASCII lines have one cell per byte, so the cached text can be
+            // searched directly; other lines go through their cells
+            if let Some(indexed) = grid.indexed_scrollback_line(i) {
+                if indexed.text().is_ascii() {
+                    if needle.is_ascii() && indexed.may_contain(query_mask) {
+                        let search_text = if options.case_sensitive {
+                            indexed.text()
+                        } else {
+                            indexed.lowercase()
+                        };
+                        find_ascii(indexed.text(), search_text, &needle, row, &mut matches);
+                    }
+                    continue;
                 }
             }
+            if let Some(cells) = grid.scrollback_line(i) {
+                find_in_row(cells, options, &needle, row, &mut matches);
+            }
         }
 
         matches
     }
 
+    /// Enable or disable the scrollback search index
+    ///
+    /// The index caches each scrollback line's text and lowercase text as the
+    /// line enters scrollback, making repeated [`Self::search_scrollback`]
+    /// calls (e.g. find-as-you-type) skip cell conversion and most lines.
+    /// It costs roughly two copies of the scrollback text in memory and is
+    /// rebuilt on reflow and cleared with the scrollback.
+    pub fn set_scrollback_search_index(&mut self, enabled: bool) {
+        self.grid.set_scrollback_search_index(enabled);
+    }
+
+    /// Whether the scrollback search index is enabled
+    pub fn scrollback_search_index_enabled(&self) -> bool {
+        self.grid.scrollback_search_index_enabled()
+    }
+
     // === Content Detection Methods ===
 
     /// Detect URLs in the visible screen
@@ -384,29 +403,16 @@ impl Terminal {
         let mut items = Vec::new();
         let grid = self.active_grid();
 
-        // Simple URL pattern: looks for http://, https://, ftp://, etc.
-        let url_prefixes = ["http://", "https://", "ftp://", "ftps://"];
-
         for row in 0..grid.rows() {
             if let Some(line) = grid.row(row) {
-                let line_text = crate::terminal::cells_to_text(line);
-
-                for prefix in &url_prefixes {
-                    let mut start_col = 0;
-                    while let Some(pos) = line_text[start_col..].to_lowercase().find(prefix) {
-                        let col = start_col + pos;
-                        // Find end of URL (space, newline, or end of line)
-                        let end = line_text[col..]
-                            .find(|c: char| c.is_whitespace())
-                            .map(|p| col + p)
-                            .unwrap_or(line_text.len());
-
-                        if end > col {
-                            let url = line_text[col..end].to_string();
-                            items.push(DetectedItem::Url(url, col, row));
-                        }
-                        start_col = end.max(col + 1);
-                    }
+                let row_text = RowText::new(line);
+                let line_text = row_text.text();
+                for (start, end) in find_urls(line_text) {
+                    items.push(DetectedItem::Url(
+                        line_text[start..end].to_string(),
+                        row_text.byte_to_col(start).unwrap_or_default(),
+                        row,
+                    ));
                 }
             }
         }
@@ -424,7 +430,8 @@ impl Terminal {
 
         for row in 0..grid.rows() {
             if let Some(line) = grid.row(row) {
-                let line_text = crate::terminal::cells_to_text(line);
+                let row_text = RowText::new(line);
+                let line_text = row_text.text();
 
                 // Simple detection: paths starting with / or ./ or ../
                 let path_patterns = ["/", "./", "../"];
@@ -443,9 +450,7 @@ impl Terminal {
                             let path_str = line_text[col..end].to_string();
 
                             // Check for line number suffix (e.g., ":123")
-                            let line_num = if end < line_text.len()
-                                && line_text.chars().nth(end) == Some(':')
-                            {
+                            let line_num = if line_text[end..].starts_with(':') {
                                 let num_start = end + 1;
                                 let num_end = line_text[num_start..]
                                     .find(|c: char| !c.is_numeric())
@@ -461,7 +466,12 @@ impl Terminal {
                                 None
                             };
 
-                            items.push(DetectedItem::FilePath(path_str, col, row, line_num));
+                            items.push(DetectedItem::FilePath(
+                                path_str,
+                                row_text.byte_to_col(col).unwrap_or_default(),
+                                row,
+                                line_num,
+                            ));
                         }
                         start_col = end.max(col + 1);
                     }
@@ -481,13 +491,15 @@ impl Terminal {
 
         for row in 0..grid.rows() {
             if let Some(line) = grid.row(row) {
-                let line_text = crate::terminal::cells_to_text(line);
+                let row_text = RowText::new(line);
+                let line_text = row_text.text();
+                let col_of = |byte: usize| row_text.byte_to_col(byte).unwrap_or_default();
 
                 // Git hash pattern (40 hex chars)
                 for (i, window) in line_text.as_bytes().windows(40).enumerate() {
                     if window.iter().all(|&b| b.is_ascii_hexdigit()) {
                         let hash = String::from_utf8_lossy(window).to_string();
-                        items.push(DetectedItem::GitHash(hash, i, row));
+                        items.push(DetectedItem::GitHash(hash, col_of(i), row));
                     }
                 }
 
@@ -499,7 +511,7 @@ impl Terminal {
                     let nums: Vec<&str> = part.split('.').collect();
                     if nums.len() == 4 && nums.iter().all(|n| n.parse::<u8>().is_ok()) {
                         if let Some(col) = line_text.find(part) {
-                            items.push(DetectedItem::IpAddress(part.to_string(), col, row));
+                            items.push(DetectedItem::IpAddress(part.to_string(), col_of(col), row));
                         }
                     }
                 }
@@ -521,7 +533,7 @@ impl Terminal {
                     if end > start && line_text[start..end].contains('@') {
                         items.push(DetectedItem::Email(
                             line_text[start..end].to_string(),
-                            start,
+                            col_of(start),
                             row,
                         ));
                     }
@@ -538,18 +550,17 @@ impl Terminal {
 
     /// Get the URL at the given position
     pub fn get_url_at(&self, col: usize, row: usize) -> Option<String> {
-        let urls = self.detect_urls();
-        for item in urls {
-            if let DetectedItem::Url(url, c, r) = item {
-                if r == row && col >= c && col < c + url.len() {
-                    return Some(url);
-                }
-            }
-        }
-        None
+        let row_text = self.row_text_map(row)?;
+        let line_text = row_text.text();
+        find_urls(line_text).into_iter().find_map(|(start, end)| {
+            let (first, last) = row_text.byte_range_to_cols(start, end);
+            (first..last)
+                .contains(&col)
+                .then(|| line_text[start..end].to_string())
+        })
     }
 
-    /// Find text in the visible buffer
+    /// Find text in the visible buffer (see [`Self::search_text`])
     pub fn find_text(&self, query: &str, case_sensitive: bool) -> Vec<SearchMatch> {
         self.search_text(query, case_sensitive)
     }
@@ -660,3 +671,126 @@ impl Terminal {
         links.into_values().collect()
     }
 }
+
+/// Byte ranges of the URLs in one line of text
+///
+/// Simple URL pattern: a http://, https://, ftp:// or ftps:// prefix up to the
+/// next whitespace. Ranges are grouped by prefix, not sorted.
+pub(crate) fn find_urls(line_text: &str) -> Vec<(usize, usize)> {
+    let url_prefixes = ["http://", "https://", "ftp://", "ftps://"];
+    let mut ranges = Vec::new();
+
+    let bytes = line_text.as_bytes();
+
+    for prefix in &url_prefixes {
+        let mut start_col = 0;
+        // Compare bytes ASCII-case-insensitively so offsets stay valid in the
+        // original text (lowercasing non-ASCII text can change byte lengths)
+        while let Some(pos) = bytes[start_col..]
+            .windows(prefix.len())
+            .position(|w| w.eq_ignore_ascii_case(prefix.as_bytes()))
+        {
+            let col = start_col + pos;
+            // Find end of URL (space, newline, or end of line)
+            let end = line_text[col..]
+                .find(|c: char| c.is_whitespace())
+                .map(|p| col + p)
+                .unwrap_or(line_text.len());
+
+            if end > col {
+                ranges.push((col, end));
+            }
+            start_col = end.max(col + 1);
+        }
+    }
+
+    ranges
+}
+
+/// Fold text for comparison: normalize, then case fold unless matching case
+///
+/// Case folding lowercases and maps the characters whose full folding differs
+/// from their lowercase form in common text (`ß` and final sigma).
+fn fold_text(text: &str, options: &TextSearchOptions) -> String {
+    let mut out = String::with_capacity(text.len());
+    fold_into(text, options, &mut out);
+    out
+}
+
+fn fold_into(text: &str, options: &TextSearchOptions, out: &mut String) {
+    if text.is_ascii() {
+        if options.case_sensitive {
+            out.push_str(text);
+        } else {
+            out.extend(text.chars().map(|c| c.to_ascii_lowercase()));
+        }
+        return;
+    }
+
+    let normalized = options.normalization.normalize(text);
+    if options.case_sensitive {
+        out.push_str(&normalized);
+        return;
+    }
+    for c in normalized.chars() {
+        match c {
+            '\u{00DF}' | '\u{1E9E}' => out.push_str("ss"),
+            '\u{03C2}' => out.push('\u{03C3}'),
+            _ => out.extend(c.to_lowercase()),
+        }
+    }
+}
+
+/// Append the non-overlapping matches of `needle` in a row that start and end
+/// on cell boundaries
+fn find_in_row(
+    cells: &[Cell],
+    options: &TextSearchOptions,
+    needle: &str,
+    row: isize,
+    matches: &mut Vec<SearchMatch>,
+) {
+    let folded = RowText::with_fold(cells, |grapheme, text| fold_into(grapheme, options, text));
+    let text = folded.text();
+    let mut from = 0;
+    while let Some(pos) = text[from..].find(needle) {
+        let start = from + pos;
+        let end = start + needle.len();
+        if folded.is_cell_boundary(start) && folded.is_cell_boundary(end) {
+            let (col, end_col) = folded.byte_range_to_cols(start, end);
+            matches.push(SearchMatch {
+                row,
+                col,
+                length: end_col - col,
+                text: RowText::new(&cells[col..end_col]).into_text(),
+            });
+            from = end;
+        } else {
+            // Starts or ends inside a grapheme; try the next character
+            from = start + text[start..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+}
+
+/// Append the matches of `needle` in an ASCII line, where byte offsets are
+/// columns
+fn find_ascii(
+    line_text: &str,
+    search_text: &str,
+    needle: &str,
+    row: isize,
+    matches: &mut Vec<SearchMatch>,
+) {
+    let mut from = 0;
+    while let Some(pos) = search_text[from..].find(needle) {
+        let start = from + pos;
+        let end = start + needle.len();
+        matches.push(SearchMatch {
+            row,
+            col: start,
+            length: needle.len(),
+            text: line_text[start..end].to_string(),
+        });
+        from = end;
+    }
+}
@@ -1,10 +1,11 @@
 //! Semantic snapshot data types for terminal state capture
 //!
 //! Provides structured types for capturing terminal state as semantic snapshots,
-//! including visible text, scrollback, shell integration zones, command history,
-//! and working directory tracking. All types support serde serialization for
+//! including visible text, scrollback, shell integration zones, cell
+//! annotations, command history, and working directory tracking. All types support serde serialization for
 //! JSON/YAML interchange.
 
+use crate::cursor::CursorStyle;
 use serde::{Deserialize, Serialize};
 
 /// Scope of a semantic snapshot capture
@@ -40,6 +41,9 @@ pub struct SemanticSnapshot {
     pub cursor_col: usize,
     /// Current cursor row (0-indexed)
     pub cursor_row: usize,
+    /// Cursor style requested by the application (DECSCUSR)
+    #[serde(default)]
+    pub cursor_style: CursorStyle,
     /// Whether the alternate screen buffer is active
     pub alt_screen_active: bool,
     /// Visible screen text content
@@ -50,6 +54,9 @@ pub struct SemanticSnapshot {
     /// Shell integration zones included in the snapshot
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub zones: Vec<ZoneInfo>,
+    /// Cell annotations on the rows covered by the snapshot
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub annotations: Vec<AnnotationInfo>,
     /// Command history entries included in the snapshot
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub commands: Vec<CommandInfo>,
@@ -100,6 +107,31 @@ pub struct ZoneInfo {
     pub timestamp: Option<u64>,
 }
 
+/// Information about a cell annotation
+///
+/// Annotations are notes a frontend attached to a run of cells, e.g. lint
+/// errors or user comments.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnnotationInfo {
+    /// Annotation identifier
+    pub id: usize,
+    /// Absolute row of the annotated cells (in scrollback coordinates)
+    pub abs_row: usize,
+    /// First annotated column
+    pub col: usize,
+    /// Number of annotated cells
+    pub length: usize,
+    /// Annotation text
+    pub text: String,
+    /// Frontend-defined category
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// Text of the annotated cells
+    pub cell_text: String,
+    /// Unix timestamp (milliseconds) when the annotation was added
+    pub timestamp: u64,
+}
+
 /// Information about a command execution
 ///
 /// Tracks command text, timing, exit status, and optionally the output text.
@@ -193,6 +225,7 @@ mod tests {
             title: "bash".to_string(),
             cursor_col: 5,
             cursor_row: 10,
+            cursor_style: CursorStyle::SteadyBar,
             alt_screen_active: false,
             visible_text: "$ ls\nfile1.txt  file2.txt\n$".to_string(),
             scrollback_text: Some("previous output\n".to_string()),
@@ -206,6 +239,16 @@ mod tests {
                 exit_code: Some(0),
                 timestamp: Some(1700000000000),
             }],
+            annotations: vec![AnnotationInfo {
+                id: 3,
+                abs_row: 101,
+                col: 0,
+                length: 9,
+                text: "large file".to_string(),
+                tag: Some("note".to_string()),
+                cell_text: "file1.txt".to_string(),
+                timestamp: 1700000000010,
+            }],
             commands: vec![CommandInfo {
                 command: "ls".to_string(),
                 cwd: Some("/home/user".to_string()),
@@ -241,6 +284,7 @@ mod tests {
         assert_eq!(deserialized.title, "bash");
         assert_eq!(deserialized.cursor_col, 5);
         assert_eq!(deserialized.cursor_row, 10);
+        assert_eq!(deserialized.cursor_style, CursorStyle::SteadyBar);
         assert!(!deserialized.alt_screen_active);
         assert_eq!(deserialized.visible_text, "$ ls\nfile1.txt  file2.txt\n$");
         assert_eq!(
@@ -248,6 +292,8 @@ mod tests {
             Some("previous output\n".to_string())
         );
         assert_eq!(deserialized.zones.len(), 1);
+        assert_eq!(deserialized.annotations.len(), 1);
+        assert_eq!(deserialized.annotations[0].cell_text, "file1.txt");
         assert_eq!(deserialized.commands.len(), 1);
         assert_eq!(deserialized.cwd, Some("/home/user".to_string()));
         assert_eq!(deserialized.hostname, Some("myhost".to_string()));
@@ -267,10 +313,12 @@ mod tests {
             title: String::new(),
             cursor_col: 0,
             cursor_row: 0,
+            cursor_style: CursorStyle::default(),
             alt_screen_active: false,
             visible_text: String::new(),
             scrollback_text: None,
             zones: vec![],
+            annotations: vec![],
             commands: vec![],
             cwd: None,
             hostname: None,
@@ -292,6 +340,7 @@ mod tests {
         // Empty vecs should be omitted (use quoted key format to avoid matching
         // substrings like "total_zones" or "total_commands")
         assert!(!json.contains("\"zones\""));
+        assert!(!json.contains("\"annotations\""));
         assert!(!json.contains("\"commands\""));
         assert!(!json.contains("\"cwd_history\""));
 
@@ -523,6 +572,15 @@ pub struct ScrollbackStats {
     pub memory_bytes: usize,
     /// Whether scrollback has wrapped around
     pub has_wrapped: bool,
+    /// Lines stored compressed
+    #[serde(default)]
+    pub compressed_lines: usize,
+    /// Bytes held by the compressed lines
+    #[serde(default)]
+    pub compressed_bytes: usize,
+    /// Estimated memory usage if no line were compressed
+    #[serde(default)]
+    pub raw_bytes: usize,
 }
 
 /// Bookmark in scrollback
@@ -651,6 +709,7 @@ pub fn diff_screen_lines(old_lines: &[String], new_lines: &[String]) -> Snapshot
 }
 
 use crate::cell::Cell;
+use crate::grid::{ScrollbackPolicy, ScrollbackTrimPolicy, ScrollbackTrimStats};
 use crate::terminal::Terminal;
 
 impl Terminal {
@@ -667,25 +726,36 @@ impl Terminal {
         let scrollback_len = self.grid.scrollback_len();
         let lines_to_export = max_lines.unwrap_or(scrollback_len).min(scrollback_len);
 
+        let lines: Vec<(&[Cell], bool)> = (0..lines_to_export)
+            .rev()
+            .filter_map(|i| self.grid.scrollback_line(i).map(|line| (line, false)))
+            .collect();
+        // Secrets are masked in copies of the exported lines
+        let texts: Vec<String> = match self.redacted_lines(&lines, None) {
+            Some(masked) => masked
+                .iter()
+                .map(|line| crate::terminal::cells_to_text(line))
+                .collect(),
+            None => lines
+                .iter()
+                .map(|(line, _)| crate::terminal::cells_to_text(line))
+                .collect(),
+        };
+
         match format {
             ExportFormat::Plain => {
                 let mut output = String::new();
-                for i in (0..lines_to_export).rev() {
-                    if let Some(line) = self.grid.scrollback_line(i) {
-                        output.push_str(&crate::terminal::cells_to_text(line));
-                        output.push('\n');
-                    }
+                for text in &texts {
+                    output.push_str(text);
+                    output.push('\n');
                 }
                 output
             }
             ExportFormat::Html => {
                 let mut output = String::from("<pre>\n");
-                for i in (0..lines_to_export).rev() {
-                    if let Some(line) = self.grid.scrollback_line(i) {
-                        let text = crate::terminal::cells_to_text(line);
-                        output.push_str(&crate::terminal::html_escape(&text));
-                        output.push('\n');
-                    }
+                for text in &texts {
+                    output.push_str(&crate::terminal::html_escape(text));
+                    output.push('\n');
                 }
                 output.push_str("</pre>");
                 output
@@ -701,7 +771,10 @@ impl Terminal {
     /// Get scrollback statistics
     pub fn scrollback_stats(&self) -> ScrollbackStats {
         let total_lines = self.grid.scrollback_len();
-        let memory_bytes = total_lines * self.grid.cols() * std::mem::size_of::<Cell>();
+        let raw_bytes = total_lines * self.grid.cols() * std::mem::size_of::<Cell>();
+        let compression = self.grid.scrollback_compression_stats();
+        let memory_bytes =
+            raw_bytes - compression.uncompressed_bytes + compression.compressed_bytes;
         // Scrollback has wrapped if we've filled the buffer
         let has_wrapped = total_lines >= self.grid.max_scrollback();
 
@@ -709,6 +782,9 @@ impl Terminal {
             total_lines,
             memory_bytes,
             has_wrapped,
+            compressed_lines: compression.compressed_lines,
+            compressed_bytes: compression.compressed_bytes,
+            raw_bytes,
         }
     }
 
@@ -717,6 +793,89 @@ impl Terminal {
         self.grid.scrollback_len()
     }
 
+    /// Set how scrollback lines are chosen for eviction once the buffer is full
+    ///
+    /// With [`ScrollbackTrimPolicy::Adaptive`], blank lines, low-entropy
+    /// lines and near-duplicate redraws are evicted ahead of older content,
+    /// so meaningful history survives longer in the same line budget. Only
+    /// lines pushed after the policy is enabled are classified.
+    ///
+    /// Trimming a line other than the oldest moves every newer line one
+    /// absolute row up; zones and command output rows are remapped, but
+    /// rows held by the frontend are not.
+    pub fn set_scrollback_trim_policy(&mut self, policy: ScrollbackTrimPolicy) {
+        self.grid.set_trim_policy(policy);
+    }
+
+    /// Get the scrollback trimming policy
+    pub fn scrollback_trim_policy(&self) -> ScrollbackTrimPolicy {
+        self.grid.trim_policy()
+    }
+
+    /// Get scrollback eviction statistics
+    pub fn scrollback_trim_stats(&self) -> ScrollbackTrimStats {
+        self.grid.trim_stats()
+    }
+
+    /// Reset scrollback eviction statistics
+    pub fn reset_scrollback_trim_stats(&mut self) {
+        self.grid.reset_trim_stats();
+    }
+
+    /// Cap scrollback by estimated memory and line age, and shrink it once
+    /// output has been idle, on top of the maximum line count
+    ///
+    /// These limits always evict the oldest lines and are counted in
+    /// [`Self::scrollback_trim_stats`]. They are checked after every
+    /// [`Self::process`] call; call [`Self::trim_scrollback`] periodically so
+    /// age and idle limits also apply while no output arrives. The new limits
+    /// are applied immediately.
+    pub fn set_scrollback_policy(&mut self, policy: ScrollbackPolicy) {
+        self.grid.set_scrollback_policy(policy);
+        self.trim_scrollback();
+    }
+
+    /// Get the memory, age and idle limits for scrollback
+    pub fn scrollback_policy(&self) -> ScrollbackPolicy {
+        self.grid.scrollback_policy()
+    }
+
+    /// Apply the scrollback policy now; returns the number of lines evicted
+    pub fn trim_scrollback(&mut self) -> usize {
+        let evicted = self.enforce_scrollback_policy();
+        self.sync_command_rows();
+        evicted
+    }
+
+    /// Evict scrollback lines that break the policy, leaving row removals for
+    /// [`Self::sync_command_rows`]
+    pub(crate) fn enforce_scrollback_policy(&mut self) -> usize {
+        let policy = self.grid.scrollback_policy();
+        if !policy.is_active() {
+            return 0;
+        }
+        let now = crate::terminal::unix_millis();
+        let last_output = self.activity.last_output();
+        let idle = policy
+            .idle_trim
+            .is_some_and(|trim| now.saturating_sub(last_output) >= trim.idle_ms);
+        self.grid.apply_scrollback_policy(now, idle)
+    }
+
+    /// Compress scrollback lines older than the newest `after_lines` lines
+    ///
+    /// Compressed lines are inflated transparently when read, so search,
+    /// export and rendering are unaffected; `None` stores every line
+    /// uncompressed again. See [`Self::scrollback_stats`] for the savings.
+    pub fn set_scrollback_compression(&mut self, after_lines: Option<usize>) {
+        self.grid.set_scrollback_compression(after_lines);
+    }
+
+    /// Get the scrollback compression threshold
+    pub fn scrollback_compression(&self) -> Option<usize> {
+        self.grid.scrollback_compression()
+    }
+
     /// Capture a semantic snapshot of the terminal state
     pub fn get_semantic_snapshot(&self, scope: SnapshotScope) -> SemanticSnapshot {
         let (cols, rows) = self.size();
@@ -729,10 +888,12 @@ impl Terminal {
             title: self.title().to_string(),
             cursor_col: self.cursor.col,
             cursor_row: self.cursor.row,
+            cursor_style: self.cursor.style,
             alt_screen_active: self.alt_screen_active,
             visible_text: self.content(),
             scrollback_text: None,
             zones: Vec::new(),
+            annotations: Vec::new(),
             commands: Vec::new(),
             cwd: self
                 .shell_state
@@ -751,6 +912,35 @@ impl Terminal {
             snapshot.scrollback_text = Some(self.export_scrollback(ExportFormat::Plain, None));
         }
 
+        // Annotations live on the primary screen; on the alternate screen
+        // only the scrollback (if included) is theirs
+        let first_row = if scope == SnapshotScope::Full {
+            0
+        } else {
+            scrollback_len
+        };
+        let last_row = if self.alt_screen_active {
+            scrollback_len.checked_sub(1)
+        } else {
+            Some(scrollback_len + self.grid.rows() - 1)
+        };
+        if let Some(last_row) = last_row {
+            snapshot.annotations = self
+                .get_annotations_in_rows(first_row, last_row)
+                .into_iter()
+                .map(|a| AnnotationInfo {
+                    id: a.id,
+                    abs_row: a.range.abs_row,
+                    col: a.range.col,
+                    length: a.range.length,
+                    text: a.text.clone(),
+                    tag: a.tag.clone(),
+                    cell_text: self.redact_export(self.get_annotated_text(a), None),
+                    timestamp: a.timestamp,
+                })
+                .collect();
+        }
+
         snapshot
     }
 
@@ -763,6 +953,9 @@ impl Terminal {
 
     /// Add a bookmark at the given scrollback row
     ///
+    /// Bookmark rows are stored as given and do not follow scrolling; use
+    /// [`Terminal::add_mark`] for positions that should stay on their line.
+    ///
     /// # Arguments
     /// * `row` - Row index (negative for scrollback, 0+ for visible screen)
     /// * `label` - Optional label for the bookmark
@@ -809,11 +1002,15 @@ impl Terminal {
 
     /// Get the terminal content as a string (visible area)
     pub fn content(&self) -> String {
-        self.get_logical_lines().join("\n")
+        self.redact_export(self.get_logical_lines().join("\n"), self.screen_start_row())
     }
 
     /// Export the visible screen content with ANSI styling
     pub fn export_visible_screen_styled(&self) -> String {
+        if self.redaction.enabled {
+            // The screenshot view carries the masked screen
+            return self.grid_with_scrollback(0).export_visible_screen_styled();
+        }
         self.active_grid().export_visible_screen_styled()
     }
 
@@ -838,9 +1035,17 @@ impl Terminal {
     pub fn export_html(&self, _include_styles: bool) -> String {
         let (_cols, rows) = self.size();
         let mut output = String::from("<pre style=\"font-family: monospace\">\n");
+        // The screenshot view carries the masked screen
+        let view;
+        let grid = if self.redaction.enabled {
+            view = self.grid_with_scrollback(0);
+            &view
+        } else {
+            self.active_grid()
+        };
 
         for row in 0..rows {
-            if let Some(line) = self.active_grid().row(row) {
+            if let Some(line) = grid.row(row) {
                 let text = crate::terminal::cells_to_text(line);
                 let escaped = crate::terminal::html_escape(&text);
                 output.push_str(&escaped);
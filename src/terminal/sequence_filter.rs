@@ -0,0 +1,151 @@
+//! Embedder hook for intercepting OSC and DCS sequences.
+//!
+//! [`Terminal::set_sequence_filter`] installs a callback that sees every OSC
+//! and DCS sequence before the terminal acts on it, with a parsed
+//! [`Sequence`], and decides with a [`SequenceAction`] whether the terminal
+//! handles it. Security-conscious embedders use this to audit or veto
+//! clipboard (OSC 52), hyperlink (OSC 8) or iTerm2 (OSC 1337) traffic without
+//! patching the parser.
+//!
+//! OSC strings are offered complete, when they are dispatched. DCS strings
+//! are offered when they start: the header (parameters, intermediates and
+//! final character) decides the fate of the whole string, and the payload
+//! that streams in afterwards (e.g. Sixel pixel data) is not buffered for the
+//! filter.
+//!
+//! The filter runs before the built-in checks (`disable_insecure_sequences`,
+//! `max_osc_data_length`), so allowing a sequence never bypasses them. It
+//! survives a hard reset (RIS), which an application can trigger itself.
+
+use crate::terminal::Terminal;
+
+/// What the terminal should do with a filtered sequence
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SequenceAction {
+    /// Handle the sequence normally
+    #[default]
+    Allow,
+    /// Ignore the sequence and log it as blocked
+    Block,
+    /// Ignore the sequence because the embedder handled it itself
+    Consume,
+}
+
+/// An OSC sequence: `ESC ] params ST`
+#[derive(Debug, Clone, Copy)]
+pub struct OscSequence<'a> {
+    /// The `;`-separated fields; the first is the command (e.g. `b"52"`)
+    pub params: &'a [&'a [u8]],
+    /// Terminated by BEL rather than ST
+    pub bell_terminated: bool,
+}
+
+impl<'a> OscSequence<'a> {
+    /// The command field (e.g. `"52"`, `"1337"`), if it is valid UTF-8
+    pub fn command(&self) -> Option<&'a str> {
+        self.params
+            .first()
+            .and_then(|field| std::str::from_utf8(field).ok())
+    }
+
+    /// The fields after the command
+    pub fn args(&self) -> &'a [&'a [u8]] {
+        self.params.get(1..).unwrap_or(&[])
+    }
+}
+
+/// The header of a DCS sequence: `ESC P params intermediates final`
+#[derive(Debug, Clone, Copy)]
+pub struct DcsSequence<'a> {
+    /// Numeric parameters (first value of each `:`-separated group)
+    pub params: &'a [u16],
+    /// Intermediate bytes (e.g. `b"$"` for `DCS $ q`)
+    pub intermediates: &'a [u8],
+    /// Final character selecting the function (`'q'` for Sixel, `'p'` for ReGIS)
+    pub action: char,
+}
+
+/// A parsed sequence offered to the sequence filter
+#[derive(Debug, Clone, Copy)]
+pub enum Sequence<'a> {
+    /// Operating System Command
+    Osc(OscSequence<'a>),
+    /// Device Control String (header only)
+    Dcs(DcsSequence<'a>),
+}
+
+impl Sequence<'_> {
+    /// Short description for logs, e.g. `OSC 52` or `DCS $q`
+    pub fn describe(&self) -> String {
+        match self {
+            Sequence::Osc(osc) => format!("OSC {}", osc.command().unwrap_or("?")),
+            Sequence::Dcs(dcs) => format!(
+                "DCS {}{}",
+                String::from_utf8_lossy(dcs.intermediates),
+                dcs.action
+            ),
+        }
+    }
+}
+
+/// Callback installed with [`Terminal::set_sequence_filter`]
+pub type SequenceFilter = Box<dyn FnMut(&Sequence<'_>) -> SequenceAction + Send + Sync>;
+
+impl Terminal {
+    /// Install a filter that sees each OSC and DCS sequence before the
+    /// terminal handles it, replacing any previous filter.
+    ///
+    /// ```
+    /// use par_term_emu_core_rust::terminal::{Sequence, SequenceAction, Terminal};
+    ///
+    /// let mut term = Terminal::new(80, 24);
+    /// term.set_sequence_filter(|seq| match seq {
+    ///     Sequence::Osc(osc) if osc.command() == Some("52") => SequenceAction::Block,
+    ///     _ => SequenceAction::Allow,
+    /// });
+    /// term.process(b"\x1b]52;c;aGk=\x07\x1b]0;title\x07");
+    /// assert_eq!(term.title(), "title");
+    /// ```
+    pub fn set_sequence_filter<F>(&mut self, filter: F)
+    where
+        F: FnMut(&Sequence<'_>) -> SequenceAction + Send + Sync + 'static,
+    {
+        self.sequence_filter = Some(Box::new(filter));
+    }
+
+    /// Remove the sequence filter; every sequence is handled normally again
+    pub fn clear_sequence_filter(&mut self) {
+        self.sequence_filter = None;
+    }
+
+    /// Check if a sequence filter is installed
+    pub fn has_sequence_filter(&self) -> bool {
+        self.sequence_filter.is_some()
+    }
+
+    /// Ask the installed filter about `sequence` (Allow when none is set).
+    ///
+    /// A panicking filter must not unwind through the parser (ARC-007), so
+    /// the panic is caught and the sequence blocked.
+    pub(crate) fn filter_sequence(&mut self, sequence: &Sequence<'_>) -> SequenceAction {
+        let Some(filter) = self.sequence_filter.as_mut() else {
+            return SequenceAction::Allow;
+        };
+        let action = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| filter(sequence)))
+            .unwrap_or_else(|_| {
+                eprintln!(
+                    "par-term-emu: sequence filter panicked; blocking {}",
+                    sequence.describe()
+                );
+                SequenceAction::Block
+            });
+        if action == SequenceAction::Block {
+            crate::debug::log(
+                crate::debug::DebugLevel::Debug,
+                "SECURITY",
+                &format!("Sequence filter blocked {}", sequence.describe()),
+            );
+        }
+        action
+    }
+}
@@ -22,7 +22,9 @@ impl Terminal {
                     .copied()
                     .unwrap_or(1) as usize;
                 let n = if n == 0 { 1 } else { n };
-                self.cursor.move_up(n);
+                // Stops at the top margin unless the cursor is above it
+                let top = self.vertical_stop_top();
+                self.cursor.row = self.cursor.row.saturating_sub(n).max(top);
                 self.pending_wrap = false;
             }
             'B' => {
@@ -34,7 +36,9 @@ impl Terminal {
                     .copied()
                     .unwrap_or(1) as usize;
                 let n = if n == 0 { 1 } else { n };
-                self.cursor.move_down(n, rows.saturating_sub(1));
+                // Stops at the bottom margin unless the cursor is below it
+                let bottom = self.vertical_stop_bottom();
+                self.cursor.move_down(n, bottom);
                 self.pending_wrap = false;
             }
             'C' => {
@@ -46,7 +50,9 @@ impl Terminal {
                     .copied()
                     .unwrap_or(1) as usize;
                 let n = if n == 0 { 1 } else { n };
-                self.cursor.move_right(n, cols.saturating_sub(1));
+                // Stops at the right margin unless the cursor is right of it
+                let edge = self.wrap_edge();
+                self.cursor.move_right(n, edge - 1);
                 self.pending_wrap = false;
             }
             'D' => {
@@ -58,7 +64,9 @@ impl Terminal {
                     .copied()
                     .unwrap_or(1) as usize;
                 let n = if n == 0 { 1 } else { n };
-                self.cursor.move_left(n);
+                // Stops at the left margin unless the cursor is left of it
+                let left = self.line_start_col();
+                self.cursor.col = self.cursor.col.saturating_sub(n).max(left);
                 self.pending_wrap = false;
             }
             'H' | 'f' => {
@@ -71,14 +79,11 @@ impl Terminal {
                 let row = row.saturating_sub(1);
 
                 if self.modes.origin_mode {
-                    let region_height = self
-                        .margins.scroll_region_bottom
-                        .saturating_sub(self.margins.scroll_region_top)
-                        + 1;
-                    let actual_row =
-                        self.margins.scroll_region_top + row.min(region_height.saturating_sub(1));
-                    let actual_col = col.min(cols.saturating_sub(1));
-                    self.cursor.goto(actual_col, actual_row);
+                    // Relative to the margins and confined to them
+                    let (left, right) = self.margins.columns(cols);
+                    let (top, bottom) =
+                        (self.margins.scroll_region_top, self.margins.scroll_region_bottom);
+                    self.cursor.goto((left + col).min(right), (top + row).min(bottom));
                 } else {
                     self.cursor.goto(
                         col.min(cols.saturating_sub(1)),
@@ -96,8 +101,9 @@ impl Terminal {
                     .copied()
                     .unwrap_or(1) as usize;
                 let n = if n == 0 { 1 } else { n };
-                self.cursor.move_down(n, rows.saturating_sub(1));
-                self.cursor.col = 0;
+                let bottom = self.vertical_stop_bottom();
+                self.cursor.move_down(n, bottom);
+                self.cursor.col = self.line_start_col();
                 self.pending_wrap = false;
             }
             'F' => {
@@ -109,8 +115,9 @@ impl Terminal {
                     .copied()
                     .unwrap_or(1) as usize;
                 let n = if n == 0 { 1 } else { n };
-                self.cursor.move_up(n);
-                self.cursor.col = 0;
+                let top = self.vertical_stop_top();
+                self.cursor.row = self.cursor.row.saturating_sub(n).max(top);
+                self.cursor.col = self.line_start_col();
                 self.pending_wrap = false;
             }
             'G' | '`' => {
@@ -121,7 +128,13 @@ impl Terminal {
                     .and_then(|p| p.first())
                     .copied()
                     .unwrap_or(1) as usize;
-                self.cursor.col = col.saturating_sub(1).min(cols.saturating_sub(1));
+                let col = col.saturating_sub(1);
+                self.cursor.col = if self.modes.origin_mode {
+                    let (left, right) = self.margins.columns(cols);
+                    (left + col).min(right)
+                } else {
+                    col.min(cols.saturating_sub(1))
+                };
                 self.pending_wrap = false;
             }
             'd' => {
@@ -132,7 +145,12 @@ impl Terminal {
                     .and_then(|p| p.first())
                     .copied()
                     .unwrap_or(1) as usize;
-                self.cursor.row = row.saturating_sub(1).min(rows.saturating_sub(1));
+                let row = row.saturating_sub(1);
+                self.cursor.row = if self.modes.origin_mode {
+                    (self.margins.scroll_region_top + row).min(self.margins.scroll_region_bottom)
+                } else {
+                    row.min(rows.saturating_sub(1))
+                };
                 self.pending_wrap = false;
             }
             'I' => {
@@ -157,11 +175,13 @@ impl Terminal {
                     .copied()
                     .unwrap_or(1) as usize;
                 let n = if n == 0 { 1 } else { n };
+                // Stops at the left margin unless the cursor is left of it
+                let left = self.line_start_col();
                 for _ in 0..n {
                     let mut col = self.cursor.col;
-                    if col > 0 {
+                    if col > left {
                         col -= 1;
-                        while col > 0 && !self.tab_stops[col] {
+                        while col > left && !self.tab_stops[col] {
                             col -= 1;
                         }
                         self.cursor.col = col;
@@ -176,16 +196,7 @@ impl Terminal {
                     let n = iter.next().and_then(|p| p.first()).copied().unwrap_or(1);
 
                     // Handle DECSCUSR
-                    use crate::cursor::CursorStyle;
-                    self.cursor.style = match n {
-                        0 | 1 => CursorStyle::BlinkingBlock,
-                        2 => CursorStyle::SteadyBlock,
-                        3 => CursorStyle::BlinkingUnderline,
-                        4 => CursorStyle::SteadyUnderline,
-                        5 => CursorStyle::BlinkingBar,
-                        6 => CursorStyle::SteadyBar,
-                        _ => CursorStyle::BlinkingBlock,
-                    };
+                    self.apply_cursor_style(crate::cursor::CursorStyle::from_decscusr(n));
 
                     // Handle DECSWBV (VT520)
                     self.warning_bell_volume = n.min(8) as u8;
@@ -229,4 +240,24 @@ impl Terminal {
             _ => {}
         }
     }
+
+    /// Row CUU and CPL stop at: the top margin, or row 0 when the cursor
+    /// is already above it
+    fn vertical_stop_top(&self) -> usize {
+        if self.cursor.row >= self.margins.scroll_region_top {
+            self.margins.scroll_region_top
+        } else {
+            0
+        }
+    }
+
+    /// Row CUD and CNL stop at: the bottom margin, or the last row when the
+    /// cursor is already below it
+    fn vertical_stop_bottom(&self) -> usize {
+        if self.cursor.row <= self.margins.scroll_region_bottom {
+            self.margins.scroll_region_bottom
+        } else {
+            self.size().1.saturating_sub(1)
+        }
+    }
 }
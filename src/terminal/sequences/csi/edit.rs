@@ -5,8 +5,8 @@ use vte::Params;
 
 impl Terminal {
     pub(crate) fn handle_csi_edit(&mut self, action: char, params: &Params, _intermediates: &[u8]) {
-        let (_cols, _rows) = self.size();
-        let cursor_row = self.cursor.row;
+        let (cols, _rows) = self.size();
+        let (cursor_col, cursor_row) = (self.cursor.col, self.cursor.row);
         let scroll_top = self.margins.scroll_region_top;
         let scroll_bottom = self.margins.scroll_region_bottom;
 
@@ -20,10 +20,14 @@ impl Terminal {
                     .copied()
                     .unwrap_or(1) as usize;
                 let n = if n == 0 { 1 } else { n };
-                // Insert lines within current scroll region if cursor is inside it
-                if cursor_row >= scroll_top && cursor_row <= scroll_bottom {
-                    self.active_grid_mut()
-                        .insert_lines(n, cursor_row, scroll_bottom);
+                // Insert lines within the margins if the cursor is inside them
+                if self.margins.contains_row(cursor_row) && self.margins.contains_col(cursor_col) {
+                    if self.margins.use_lr_margins {
+                        self.scroll_down_in_margins(n, cursor_row, scroll_bottom);
+                    } else {
+                        self.active_grid_mut()
+                            .insert_lines(n, cursor_row, scroll_bottom);
+                    }
                 }
             }
             'M' => {
@@ -35,10 +39,14 @@ impl Terminal {
                     .copied()
                     .unwrap_or(1) as usize;
                 let n = if n == 0 { 1 } else { n };
-                // Delete lines within current scroll region if cursor is inside it
-                if cursor_row >= scroll_top && cursor_row <= scroll_bottom {
-                    self.active_grid_mut()
-                        .delete_lines(n, cursor_row, scroll_bottom);
+                // Delete lines within the margins if the cursor is inside them
+                if self.margins.contains_row(cursor_row) && self.margins.contains_col(cursor_col) {
+                    if self.margins.use_lr_margins {
+                        self.scroll_up_in_margins(n, cursor_row, scroll_bottom);
+                    } else {
+                        self.active_grid_mut()
+                            .delete_lines(n, cursor_row, scroll_bottom);
+                    }
                 }
             }
             '@' => {
@@ -50,9 +58,18 @@ impl Terminal {
                     .copied()
                     .unwrap_or(1) as usize;
                 let n = if n == 0 { 1 } else { n };
-                let cursor_col = self.cursor.col;
-                self.active_grid_mut()
-                    .insert_characters(cursor_col, cursor_row, n);
+                // Characters shift up to the right margin; nothing happens
+                // with the cursor outside the margins
+                if self.margins.use_lr_margins {
+                    if self.margins.contains_col(cursor_col) {
+                        let right = self.margins.right_margin;
+                        self.active_grid_mut()
+                            .insert_columns(cursor_col, n, cursor_row, cursor_row, right);
+                    }
+                } else {
+                    self.active_grid_mut()
+                        .insert_characters(cursor_col, cursor_row, n);
+                }
             }
             'P' => {
                 // Delete characters (DCH)
@@ -63,9 +80,38 @@ impl Terminal {
                     .copied()
                     .unwrap_or(1) as usize;
                 let n = if n == 0 { 1 } else { n };
-                let cursor_col = self.cursor.col;
-                self.active_grid_mut()
-                    .delete_characters(cursor_col, cursor_row, n);
+                if self.margins.use_lr_margins {
+                    if self.margins.contains_col(cursor_col) {
+                        let right = self.margins.right_margin;
+                        self.active_grid_mut()
+                            .delete_columns(cursor_col, n, cursor_row, cursor_row, right);
+                    }
+                } else {
+                    self.active_grid_mut()
+                        .delete_characters(cursor_col, cursor_row, n);
+                }
+            }
+            '}' | '~' => {
+                // Insert columns (DECIC) / delete columns (DECDC) within the
+                // scroll region; ignored when the cursor is outside it
+                let n = params
+                    .iter()
+                    .next()
+                    .and_then(|p| p.first())
+                    .copied()
+                    .unwrap_or(1) as usize;
+                let n = if n == 0 { 1 } else { n };
+                let (_, right) = self.margins.columns(cols);
+                if !self.margins.contains_row(cursor_row) || !self.margins.contains_col(cursor_col)
+                {
+                    return;
+                }
+                let grid = self.active_grid_mut();
+                if action == '}' {
+                    grid.insert_columns(cursor_col, n, scroll_top, scroll_bottom, right);
+                } else {
+                    grid.delete_columns(cursor_col, n, scroll_top, scroll_bottom, right);
+                }
             }
             _ => {}
         }
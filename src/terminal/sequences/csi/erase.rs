@@ -31,14 +31,13 @@ impl Terminal {
                         .active_grid_mut()
                         .clear_screen_above(cursor_col, cursor_row, bg),
                     2 => {
+                        self.capture_before_clear(false);
                         self.active_grid_mut().clear_with_bg(bg);
                         self.graphics.graphics_store.clear();
                         self.graphics.graphics_store.clear_scrollback_graphics();
-                        self.events.terminal_events.push(
-                            crate::terminal::TerminalEvent::ScreenCleared {
-                                include_scrollback: false,
-                            },
-                        );
+                        self.push_render_event(crate::terminal::TerminalEvent::ScreenCleared {
+                            include_scrollback: false,
+                        });
                         debug::log(
                             debug::DebugLevel::Debug,
                             "CLEAR",
@@ -46,15 +45,14 @@ impl Terminal {
                         );
                     }
                     3 => {
+                        self.capture_before_clear(true);
                         self.active_grid_mut().clear_with_bg(bg);
                         self.active_grid_mut().clear_scrollback();
                         self.graphics.graphics_store.clear();
                         self.graphics.graphics_store.clear_scrollback_graphics();
-                        self.events.terminal_events.push(
-                            crate::terminal::TerminalEvent::ScreenCleared {
-                                include_scrollback: true,
-                            },
-                        );
+                        self.push_render_event(crate::terminal::TerminalEvent::ScreenCleared {
+                            include_scrollback: true,
+                        });
                         debug::log(
                             debug::DebugLevel::Debug,
                             "CLEAR",
@@ -1,6 +1,6 @@
 //! Kitty keyboard protocol CSI sequence handling
 
-use crate::terminal::Terminal;
+use crate::terminal::{Terminal, KEYBOARD_FLAGS_MASK};
 use vte::Params;
 
 impl Terminal {
@@ -18,38 +18,29 @@ impl Terminal {
                 self.push_response(response.as_bytes());
             } else if intermediates.contains(&b'>') {
                 // Push flags: CSI > flags u
-                let mut iter = params.iter();
-                if let Some(param_slice) = iter.next() {
-                    let flags = param_slice.first().copied().unwrap_or(0);
-                    self.keyboard_state
-                        .keyboard_stack
-                        .push(self.keyboard_state.keyboard_flags);
-                    self.keyboard_state.keyboard_flags = flags;
-                }
+                let flags = params
+                    .iter()
+                    .next()
+                    .and_then(|p| p.first())
+                    .copied()
+                    .unwrap_or(0);
+                self.push_keyboard_flags(flags);
             } else if intermediates.contains(&b'<') {
                 // Pop flags: CSI < n u
                 let mut iter = params.iter();
                 let n = iter.next().and_then(|p| p.first()).copied().unwrap_or(1) as usize;
-                for _ in 0..n {
-                    if let Some(flags) = self.keyboard_state.keyboard_stack.pop() {
-                        self.keyboard_state.keyboard_flags = flags;
-                    }
-                }
+                self.pop_keyboard_flags(n.max(1));
             } else {
                 // Set/Unset flags: CSI [=] flags ; mode u
                 let mut iter = params.iter();
                 if let Some(param_slice) = iter.next() {
-                    let flags = param_slice.first().copied().unwrap_or(0);
+                    let flags = param_slice.first().copied().unwrap_or(0) & KEYBOARD_FLAGS_MASK;
                     let mode = iter.next().and_then(|p| p.first()).copied().unwrap_or(1);
 
                     match mode {
                         1 => self.keyboard_state.keyboard_flags = flags, // Set
                         2 => self.keyboard_state.keyboard_flags |= flags, // Add
-                        3 => {
-                            // Report (as per par-term tests)
-                            let response = format!("\x1b[?{}u", self.keyboard_state.keyboard_flags);
-                            self.push_response(response.as_bytes());
-                        }
+                        3 => self.keyboard_state.keyboard_flags &= !flags, // Remove
                         _ => self.keyboard_state.keyboard_flags = flags, // Default to set
                     }
                 }
@@ -7,10 +7,13 @@ mod keyboard;
 mod mode;
 mod report;
 mod scroll;
+mod sgr_stack;
 mod style;
 mod window;
 
 use crate::debug;
+use crate::terminal::action::{params_to_owned, TerminalAction};
+use crate::terminal::unsupported::UnsupportedKind;
 use crate::terminal::Terminal;
 use vte::Params;
 
@@ -32,12 +35,24 @@ impl Terminal {
         debug::log_csi_dispatch(&params_vec, intermediates, action);
 
         match action {
+            '{' | '}' | 'P' | 'Q' if intermediates == b"#" => {
+                // XTPUSHSGR / XTPOPSGR / XTPUSHCOLORS / XTPOPCOLORS
+                self.handle_csi_attribute_stack(action, params);
+            }
+            '@' | 'A' if intermediates == b" " => {
+                // SL / SR - scroll left / right
+                self.handle_csi_scroll(action, params, intermediates);
+            }
             'A' | 'B' | 'C' | 'D' | 'H' | 'f' | 'E' | 'F' | 'G' | '`' | 'd' | 'I' | 'Z' | 'g' => {
                 self.handle_csi_cursor(action, params, intermediates);
             }
             'J' | 'K' | 'X' => {
                 self.handle_csi_erase(action, params, intermediates);
             }
+            'S' if intermediates.contains(&b'?') => {
+                // XTSMGRAPHICS - CSI ? Pi ; Pa ; Pv S
+                self.handle_csi_report(action, params, intermediates);
+            }
             'S' | 'T' => {
                 self.handle_csi_scroll(action, params, intermediates);
             }
@@ -50,6 +65,26 @@ impl Terminal {
             'n' | 'c' => {
                 self.handle_csi_report(action, params, intermediates);
             }
+            'k' if intermediates == b" " => {
+                // SCP - Select Character Path (bidi paragraph direction)
+                let ps = params
+                    .iter()
+                    .next()
+                    .and_then(|p| p.first())
+                    .copied()
+                    .unwrap_or(0);
+                self.handle_scp(ps);
+            }
+            'i' if intermediates.is_empty() => {
+                // MC - Media Copy (print screen, printer controller mode)
+                let mode = params
+                    .iter()
+                    .next()
+                    .and_then(|p| p.first())
+                    .copied()
+                    .unwrap_or(0);
+                self.handle_media_copy(mode);
+            }
             'y' => {
                 if intermediates.contains(&b'*') {
                     // DECRQCRA - Request Checksum of Rectangular Area
@@ -72,28 +107,37 @@ impl Terminal {
                 self.handle_csi_window(action, params, intermediates);
             }
             's' => {
-                // s can be SCOSC (no params) or DECSLRM (with params, only if DECLRMM is set)
-                // We check if there are any parameters to distinguish them
-                if !params_vec.is_empty() && self.margins.use_lr_margins {
+                // s is DECSLRM while DECLRMM is set, SCOSC otherwise
+                if self.margins.use_lr_margins {
                     self.handle_csi_window(action, params, intermediates);
                 } else {
                     self.handle_csi_cursor(action, params, intermediates);
                 }
             }
             'x' => {
-                // x can be DECREQTPARM (no intermediates) or rectangular area operations (with $)
+                // x can be DECREQTPARM (no intermediates), rectangular area
+                // operations (with $), or DECSACE (with *)
                 if intermediates.contains(&b'$') {
                     self.handle_csi_window(action, params, intermediates);
+                } else if intermediates.contains(&b'*') {
+                    self.handle_decsace(params);
                 } else {
                     self.handle_csi_report(action, params, intermediates);
                 }
             }
             'v' | 'z' => {
-                // Rectangular area operations (DECCRA, etc.)
+                // Rectangular area operations (DECCRA, etc.), or DECINVM
+                // (CSI Pid * z) replaying a DECDMAC macro
                 if intermediates.contains(&b'$') {
                     self.handle_csi_window(action, params, intermediates);
+                } else if action == 'z' && intermediates == b"*" {
+                    self.invoke_dec_macro(params);
                 }
             }
+            '|' if intermediates.contains(&b'*') => {
+                // DECSNLS - Set Number of Lines per Screen
+                self.handle_csi_window(action, params, intermediates);
+            }
             '{' => {
                 // { with $ is DECSERA (Selective Erase Rectangular Area)
                 if intermediates.contains(&b'$') {
@@ -103,6 +147,21 @@ impl Terminal {
             'L' | 'M' | '@' | 'P' => {
                 self.handle_csi_edit(action, params, intermediates);
             }
+            '}' | '~' if intermediates == b"'" => {
+                // DECIC / DECDC - insert or delete columns
+                self.handle_csi_edit(action, params, intermediates);
+            }
+            'b' if intermediates.is_empty() => {
+                // REP - repeat the preceding graphic character
+                let n = params
+                    .iter()
+                    .next()
+                    .and_then(|p| p.first())
+                    .copied()
+                    .unwrap_or(1)
+                    .max(1);
+                self.repeat_last_char(n as usize);
+            }
             'p' => {
                 // p can be DECSCL (with "), DECSTR (with !), or DECRQM (with $)
                 if intermediates.contains(&b'"')
@@ -131,6 +190,16 @@ impl Terminal {
                     "CSI",
                     &format!("Unsupported CSI action: {}", action),
                 );
+                self.record_unsupported(
+                    UnsupportedKind::Csi,
+                    format!("CSI {}{}", String::from_utf8_lossy(intermediates), action),
+                    TerminalAction::CsiDispatch {
+                        params: params_to_owned(params),
+                        intermediates: intermediates.to_vec(),
+                        ignore: false,
+                        action,
+                    },
+                );
             }
         }
     }
@@ -2,37 +2,14 @@
 
 use crate::debug;
 use crate::mouse::{MouseEncoding, MouseMode};
+use crate::terminal::action::TerminalAction;
+use crate::terminal::unsupported::UnsupportedKind;
 use crate::terminal::Terminal;
 use vte::Params;
 
 impl Terminal {
     pub(crate) fn handle_csi_mode(&mut self, action: char, params: &Params, intermediates: &[u8]) {
         let private = intermediates.contains(&b'?');
-
-        // Specialized handling for synchronized updates to ensure the sequence itself is processed
-        // even if buffering is active
-        let mut is_sync_update = false;
-        if private {
-            for param_slice in params {
-                if param_slice.first() == Some(&2026) {
-                    is_sync_update = true;
-                    break;
-                }
-            }
-        }
-
-        if is_sync_update {
-            self.sync_state.synchronized_updates = false;
-            self.handle_csi_mode_impl(action, params, intermediates);
-            // We do NOT restore synchronized_updates here because handle_csi_mode_impl
-            // just set it to its new intended value (true for SM, false for RM).
-        } else {
-            self.handle_csi_mode_impl(action, params, intermediates);
-        }
-    }
-
-    fn handle_csi_mode_impl(&mut self, action: char, params: &Params, intermediates: &[u8]) {
-        let private = intermediates.contains(&b'?');
         match action {
             'h' => {
                 // Set Mode (SM / DECSET)
@@ -46,16 +23,18 @@ impl Terminal {
                                 self.modes.insert_mode = true;
                                 self.events.terminal_events.push(
                                     crate::terminal::TerminalEvent::ModeChanged(
-                                        "insert_mode".to_string(),
+                                        crate::terminal::Mode::InsertMode,
                                         true,
                                     ),
                                 );
                             }
+                            // BDSM: implicit bidi (terminal-wg / VTE polarity)
+                            8 => self.set_bidi_implicit(true),
                             20 if !self.modes.line_feed_new_line_mode => {
                                 self.modes.line_feed_new_line_mode = true;
                                 self.events.terminal_events.push(
                                     crate::terminal::TerminalEvent::ModeChanged(
-                                        "line_feed_new_line_mode".to_string(),
+                                        crate::terminal::Mode::LineFeedNewLineMode,
                                         true,
                                     ),
                                 );
@@ -77,16 +56,17 @@ impl Terminal {
                                 self.modes.insert_mode = false;
                                 self.events.terminal_events.push(
                                     crate::terminal::TerminalEvent::ModeChanged(
-                                        "insert_mode".to_string(),
+                                        crate::terminal::Mode::InsertMode,
                                         false,
                                     ),
                                 );
                             }
+                            8 => self.set_bidi_implicit(false),
                             20 if self.modes.line_feed_new_line_mode => {
                                 self.modes.line_feed_new_line_mode = false;
                                 self.events.terminal_events.push(
                                     crate::terminal::TerminalEvent::ModeChanged(
-                                        "line_feed_new_line_mode".to_string(),
+                                        crate::terminal::Mode::LineFeedNewLineMode,
                                         false,
                                     ),
                                 );
@@ -100,6 +80,20 @@ impl Terminal {
         }
     }
 
+    /// Capture an unimplemented private mode as `CSI ? param h/l`
+    fn record_unsupported_mode(&mut self, name: &str, param: u16, action: char) {
+        self.record_unsupported(
+            UnsupportedKind::Csi,
+            format!("{name} {param}"),
+            TerminalAction::CsiDispatch {
+                params: vec![vec![param]],
+                intermediates: vec![b'?'],
+                ignore: false,
+                action,
+            },
+        );
+    }
+
     pub(crate) fn handle_decset(&mut self, param: u16) {
         let old_mode = match param {
             1 => Some(format!("app_cursor:{}", self.modes.application_cursor)),
@@ -116,18 +110,34 @@ impl Terminal {
                 "sync_updates:{}",
                 self.sync_state.synchronized_updates
             )),
+            2027 => Some(format!(
+                "grapheme_clusters:{}",
+                self.grapheme_cluster_mode()
+            )),
+            2031 => Some(format!(
+                "color_scheme_updates:{}",
+                self.modes.color_scheme_updates
+            )),
+            9001 => Some(format!("win32_input:{}", self.modes.win32_input_mode)),
+            7700 => Some(format!(
+                "ambiguous_width_reports:{}",
+                self.modes.ambiguous_width_reports
+            )),
             _ => None,
         };
 
         match param {
             1 => self.modes.application_cursor = true,
+            // DECANM - already in ANSI mode; VT52 mode leaves with ESC <
+            2 => {}
             6 => {
                 self.modes.origin_mode = true;
-                self.cursor.goto(0, 0); // Goto (0,0) within scroll region
+                self.home_cursor();
             }
             7 => self.modes.auto_wrap = true,
             25 => self.cursor.visible = true,
-            69 => self.margins.use_lr_margins = true,
+            42 => self.set_nrcs_mode(true),
+            69 => self.set_lr_margin_mode(true),
             1000 => self.modes.mouse_mode = MouseMode::Normal,
             1002 => self.modes.mouse_mode = MouseMode::ButtonEvent,
             1003 => self.modes.mouse_mode = MouseMode::AnyEvent,
@@ -137,13 +147,20 @@ impl Terminal {
             1049 => self.use_alt_screen(),
             1004 => self.modes.focus_tracking = true,
             2004 => self.modes.bracketed_paste = true,
-            2026 => self.sync_state.synchronized_updates = true,
+            2026 => self.set_synchronized_updates(true),
+            // Takes effect only under the negotiable width policy
+            2027 => self.modes.grapheme_clusters = true,
+            1070 => self.graphics.sixel_private_color_registers = true,
+            2031 => self.modes.color_scheme_updates = true,
+            9001 => self.modes.win32_input_mode = true,
+            7700 => self.modes.ambiguous_width_reports = true,
             _ => {
                 debug::log(
                     debug::DebugLevel::Debug,
                     "CSI",
                     &format!("Unsupported DECSET: {}", param),
                 );
+                self.record_unsupported_mode("DECSET", param, 'h');
             }
         }
 
@@ -162,34 +179,29 @@ impl Terminal {
                 "sync_updates:{}",
                 self.sync_state.synchronized_updates
             )),
+            2027 => Some(format!(
+                "grapheme_clusters:{}",
+                self.grapheme_cluster_mode()
+            )),
+            2031 => Some(format!(
+                "color_scheme_updates:{}",
+                self.modes.color_scheme_updates
+            )),
+            9001 => Some(format!("win32_input:{}", self.modes.win32_input_mode)),
+            7700 => Some(format!(
+                "ambiguous_width_reports:{}",
+                self.modes.ambiguous_width_reports
+            )),
             _ => None,
         };
 
         if old_mode != new_mode && param != 1049 {
-            use crate::terminal::TerminalEvent;
-            let mode_name = match param {
-                1 => "application_cursor",
-                4 => "insert_mode",
-                6 => "origin_mode",
-                7 => "auto_wrap",
-                20 => "line_feed_new_line_mode",
-                25 => "cursor_visible",
-                69 => "lr_margins",
-                1000 => "mouse_normal",
-                1002 => "mouse_button_event",
-                1003 => "mouse_any_event",
-                1004 => "focus_tracking",
-                1005 => "mouse_utf8",
-                1006 => "mouse_sgr",
-                1015 => "mouse_urxvt",
-                1049 => "alternate_screen",
-                2004 => "bracketed_paste",
-                2026 => "synchronized_updates",
-                _ => "unknown",
-            };
-            self.events
-                .terminal_events
-                .push(TerminalEvent::ModeChanged(mode_name.to_string(), true));
+            use crate::terminal::{Mode, TerminalEvent};
+            if let Some(mode) = Mode::from_dec_private(param) {
+                self.events
+                    .terminal_events
+                    .push(TerminalEvent::ModeChanged(mode, true));
+            }
         }
     }
 
@@ -209,34 +221,52 @@ impl Terminal {
                 "sync_updates:{}",
                 self.sync_state.synchronized_updates
             )),
+            2027 => Some(format!(
+                "grapheme_clusters:{}",
+                self.grapheme_cluster_mode()
+            )),
+            2031 => Some(format!(
+                "color_scheme_updates:{}",
+                self.modes.color_scheme_updates
+            )),
+            9001 => Some(format!("win32_input:{}", self.modes.win32_input_mode)),
+            7700 => Some(format!(
+                "ambiguous_width_reports:{}",
+                self.modes.ambiguous_width_reports
+            )),
             _ => None,
         };
 
         match param {
             1 => self.modes.application_cursor = false,
+            // DECANM - switch to VT52 mode
+            2 => self.enter_vt52_mode(),
             6 => {
                 self.modes.origin_mode = false;
-                self.cursor.goto(0, 0);
+                self.home_cursor();
             }
             7 => self.modes.auto_wrap = false,
             25 => self.cursor.visible = false,
-            69 => self.margins.use_lr_margins = false,
+            42 => self.set_nrcs_mode(false),
+            69 => self.set_lr_margin_mode(false),
             1000 | 1002 | 1003 => self.modes.mouse_mode = MouseMode::Off,
             1005 | 1006 | 1015 => self.modes.mouse_encoding = MouseEncoding::Default,
             1049 => self.use_primary_screen(),
             1004 => self.modes.focus_tracking = false,
             2004 => self.modes.bracketed_paste = false,
-            2026 => {
-                self.sync_state.synchronized_updates = false;
-                self.sync_state.sync_update_explicitly_disabled = true;
-                self.flush_synchronized_updates();
-            }
+            2026 => self.set_synchronized_updates(false),
+            2027 => self.modes.grapheme_clusters = false,
+            1070 => self.graphics.sixel_private_color_registers = false,
+            2031 => self.modes.color_scheme_updates = false,
+            9001 => self.modes.win32_input_mode = false,
+            7700 => self.modes.ambiguous_width_reports = false,
             _ => {
                 debug::log(
                     debug::DebugLevel::Debug,
                     "CSI",
                     &format!("Unsupported DECRST: {}", param),
                 );
+                self.record_unsupported_mode("DECRST", param, 'l');
             }
         }
 
@@ -255,34 +285,29 @@ impl Terminal {
                 "sync_updates:{}",
                 self.sync_state.synchronized_updates
             )),
+            2027 => Some(format!(
+                "grapheme_clusters:{}",
+                self.grapheme_cluster_mode()
+            )),
+            2031 => Some(format!(
+                "color_scheme_updates:{}",
+                self.modes.color_scheme_updates
+            )),
+            9001 => Some(format!("win32_input:{}", self.modes.win32_input_mode)),
+            7700 => Some(format!(
+                "ambiguous_width_reports:{}",
+                self.modes.ambiguous_width_reports
+            )),
             _ => None,
         };
 
         if old_mode != new_mode && param != 1049 {
-            use crate::terminal::TerminalEvent;
-            let mode_name = match param {
-                1 => "application_cursor",
-                4 => "insert_mode",
-                6 => "origin_mode",
-                7 => "auto_wrap",
-                20 => "line_feed_new_line_mode",
-                25 => "cursor_visible",
-                69 => "lr_margins",
-                1000 => "mouse_normal",
-                1002 => "mouse_button_event",
-                1003 => "mouse_any_event",
-                1004 => "focus_tracking",
-                1005 => "mouse_utf8",
-                1006 => "mouse_sgr",
-                1015 => "mouse_urxvt",
-                1049 => "alternate_screen",
-                2004 => "bracketed_paste",
-                2026 => "synchronized_updates",
-                _ => "unknown",
-            };
-            self.events
-                .terminal_events
-                .push(TerminalEvent::ModeChanged(mode_name.to_string(), false));
+            use crate::terminal::{Mode, TerminalEvent};
+            if let Some(mode) = Mode::from_dec_private(param) {
+                self.events
+                    .terminal_events
+                    .push(TerminalEvent::ModeChanged(mode, false));
+            }
         }
     }
 }
@@ -1,5 +1,6 @@
 //! Report-related CSI sequence handling (DSR, DA, etc.)
 
+use crate::conformance_level::ConformanceLevel;
 use crate::terminal::Terminal;
 use vte::Params;
 
@@ -26,26 +27,38 @@ impl Terminal {
                         // Status report - response: CSI 0 n (OK)
                         self.push_response(b"\x1b[0n");
                     }
-                    6 => {
-                        // Cursor position report (CPR)
-                        // Response: CSI r ; c R
-                        let (col, row) = if private {
-                            // Private mode CPR (some terminals use this)
-                            (self.cursor.col, self.cursor.row)
-                        } else if self.modes.origin_mode {
-                            // Respect origin mode: report relative to scroll region
-                            (
-                                self.cursor.col,
-                                self.cursor
-                                    .row
-                                    .saturating_sub(self.margins.scroll_region_top),
-                            )
+                    // CPR: CSI 6 n -> CSI r ; c R
+                    // DECXCPR: CSI ? 6 n -> CSI ? r ; c ; page R
+                    6 => self.report_cursor_position(private),
+                    // Printer status: CSI ? 15 n -> CSI ? 10 n (ready) or
+                    // CSI ? 13 n (no printer)
+                    15 if private => {
+                        let response: &[u8] = if self.has_printer_sink() {
+                            b"\x1b[?10n"
                         } else {
-                            (self.cursor.col, self.cursor.row)
+                            b"\x1b[?13n"
                         };
-                        let response = format!("\x1b[{};{}R", row + 1, col + 1);
-                        self.push_response(response.as_bytes());
+                        self.push_response(response);
                     }
+                    // UDK status: CSI ? 25 n -> CSI ? 20 n (UDKs unlocked;
+                    // DECUDK is not supported, so there is nothing to lock)
+                    25 if private => self.push_response(b"\x1b[?20n"),
+                    // Keyboard status: CSI ? 26 n -> CSI ? 27 ; 1 n
+                    26 if private => self.report_keyboard_status(),
+                    // Color scheme query: CSI ? 996 n -> CSI ? 997 ; Ps n
+                    // Macro space report: CSI ? 62 n -> DECMSR
+                    62 if private => self.report_macro_space(),
+                    // Memory checksum: CSI ? 63 ; Pid n -> DECCKSR
+                    63 if private => {
+                        let request_id = params
+                            .iter()
+                            .nth(1)
+                            .and_then(|p| p.first())
+                            .copied()
+                            .unwrap_or(0);
+                        self.report_macro_checksum(request_id);
+                    }
+                    996 if private => self.report_color_scheme(),
                     _ => {}
                 }
             }
@@ -56,11 +69,7 @@ impl Terminal {
                     // (par-term version 82, 10000 = scrollback, 0 = ROM)
                     self.push_response(b"\x1b[>82;10000;0c");
                 } else {
-                    // Primary DA - response: CSI ? <id> ; 1 ; 4 ; 6 ; 9 ; 15 ; 22 ; 52 c
-                    // <id> based on conformance level
-                    let id = self.conformance_level.da_identifier();
-                    let response = format!("\x1b[?{};1;4;6;9;15;22;52c", id);
-                    self.push_response(response.as_bytes());
+                    self.send_primary_device_attributes();
                 }
             }
             'q' => {
@@ -108,6 +117,8 @@ impl Terminal {
                                     2
                                 }
                             }
+                            // DECANM - a DECRQM can only arrive in ANSI mode
+                            2 => 1,
                             6 => {
                                 if self.modes.origin_mode {
                                     1
@@ -129,6 +140,20 @@ impl Terminal {
                                     2
                                 }
                             }
+                            42 => {
+                                if self.modes.nrcs_mode {
+                                    1
+                                } else {
+                                    2
+                                }
+                            }
+                            69 => {
+                                if self.margins.use_lr_margins {
+                                    1
+                                } else {
+                                    2
+                                }
+                            }
                             1000 | 1002 | 1003 => {
                                 if self.modes.mouse_mode != crate::mouse::MouseMode::Off {
                                     1
@@ -157,6 +182,47 @@ impl Terminal {
                                     2
                                 }
                             }
+                            2027 => {
+                                use crate::unicode_width_config::GraphemeClustering;
+                                match self.unicode_state.grapheme_clustering {
+                                    GraphemeClustering::Negotiable
+                                        if self.modes.grapheme_clusters =>
+                                    {
+                                        1
+                                    }
+                                    GraphemeClustering::Negotiable => 2,
+                                    GraphemeClustering::Always => 3,
+                                    GraphemeClustering::Never => 4,
+                                }
+                            }
+                            1070 => {
+                                if self.graphics.sixel_private_color_registers {
+                                    1
+                                } else {
+                                    2
+                                }
+                            }
+                            2031 => {
+                                if self.modes.color_scheme_updates {
+                                    1
+                                } else {
+                                    2
+                                }
+                            }
+                            9001 => {
+                                if self.modes.win32_input_mode {
+                                    1
+                                } else {
+                                    2
+                                }
+                            }
+                            7700 => {
+                                if self.modes.ambiguous_width_reports {
+                                    1
+                                } else {
+                                    2
+                                }
+                            }
                             _ => 0, // Not recognized
                         };
                         (s, "?")
@@ -170,6 +236,13 @@ impl Terminal {
                                     2
                                 }
                             }
+                            8 => {
+                                if self.bidi.implicit {
+                                    1
+                                } else {
+                                    2
+                                }
+                            }
                             20 => {
                                 if self.modes.line_feed_new_line_mode {
                                     1
@@ -204,10 +277,122 @@ impl Terminal {
                 let response = format!("\x1b[{};1;1;120;120;1;0x", sol);
                 self.push_response(response.as_bytes());
             }
+            'S' if private => self.handle_xtsmgraphics(params),
             _ => {}
         }
     }
 
+    /// CPR / DECXCPR - report the cursor position, relative to the margins
+    /// in origin mode. DECXCPR adds the page number (always 1) at
+    /// VT420 and above.
+    fn report_cursor_position(&mut self, extended: bool) {
+        let row = if self.modes.origin_mode {
+            self.cursor
+                .row
+                .saturating_sub(self.margins.scroll_region_top)
+        } else {
+            self.cursor.row
+        };
+        let col = if self.modes.origin_mode {
+            let (cols, _) = self.size();
+            self.cursor.col.saturating_sub(self.margins.columns(cols).0)
+        } else {
+            self.cursor.col
+        };
+        let response = if !extended {
+            format!("\x1b[{};{}R", row + 1, col + 1)
+        } else if self.conformance_level >= ConformanceLevel::VT420 {
+            format!("\x1b[?{};{};1R", row + 1, col + 1)
+        } else {
+            format!("\x1b[?{};{}R", row + 1, col + 1)
+        };
+        self.push_response(response.as_bytes());
+    }
+
+    /// Keyboard status report for CSI ? 26 n: North American keyboard,
+    /// plus "ready" at VT320 and an LK201 keyboard type at VT420 and above
+    fn report_keyboard_status(&mut self) {
+        let response: &[u8] = if self.conformance_level >= ConformanceLevel::VT420 {
+            b"\x1b[?27;1;0;0n"
+        } else if self.conformance_level >= ConformanceLevel::VT320 {
+            b"\x1b[?27;1;0n"
+        } else {
+            b"\x1b[?27;1n"
+        };
+        self.push_response(response);
+    }
+
+    /// Reply to Primary DA (`CSI c`) or DECID (`ESC Z`)
+    ///
+    /// At VT220 and above the reply is `CSI ? <id> ; 1 ; 4 ; 6 ; 9 ; 15 ; 22 ; 52 c`
+    /// with `<id>` taken from the conformance level. At VT100 level it is
+    /// `CSI ? 1 ; 2 c` (VT100 with Advanced Video Option), since the
+    /// extension list is not defined for a VT100.
+    pub(crate) fn send_primary_device_attributes(&mut self) {
+        let response = match self.conformance_level {
+            crate::conformance_level::ConformanceLevel::VT100 => "\x1b[?1;2c".to_string(),
+            level => format!("\x1b[?{};1;4;6;9;15;22;52c", level.da_identifier()),
+        };
+        self.push_response(response.as_bytes());
+    }
+
+    /// XTSMGRAPHICS - query/set graphics attributes: CSI ? Pi ; Pa ; Pv S
+    ///
+    /// Pi: 1 = color registers, 2 = Sixel geometry, 3 = ReGIS geometry
+    /// (width;height in pixels; ReGIS geometry follows the limits, so it
+    /// cannot be set).
+    /// Pa: 1 = read, 2 = reset to default, 3 = set to Pv, 4 = read maximum
+    /// (a missing Pa reads). Response: CSI ? Pi ; Ps ; Pv S, where Ps is
+    /// 0 = success, 1 = unknown Pi, 2 = unknown Pa, 3 = failure.
+    fn handle_xtsmgraphics(&mut self, params: &Params) {
+        let values: Vec<u16> = params.iter().filter_map(|p| p.first().copied()).collect();
+        let item = values.first().copied().unwrap_or(0);
+        let action = values.get(1).copied().filter(|&a| a != 0).unwrap_or(1);
+        let value = &values[values.len().min(2)..];
+
+        let result: Result<Vec<usize>, u8> = match (item, action) {
+            // Color registers: fixed count, so set and reset report it unchanged
+            (1, 1..=4) => Ok(vec![crate::sixel::SIXEL_COLOR_REGISTERS]),
+            (2, 1) => {
+                let (width, height) = self.sixel_geometry();
+                Ok(vec![width, height])
+            }
+            (2, 2) => {
+                self.graphics.sixel_geometry = None;
+                let (width, height) = self.sixel_geometry();
+                Ok(vec![width, height])
+            }
+            (2, 3) => match value {
+                [width, height, ..] if *width > 0 && *height > 0 => {
+                    self.graphics.sixel_geometry = Some((*width as usize, *height as usize));
+                    let (width, height) = self.sixel_geometry();
+                    Ok(vec![width, height])
+                }
+                _ => Err(3),
+            },
+            (2, 4) => {
+                let limits = self.graphics.sixel_limits;
+                Ok(vec![limits.max_width, limits.max_height])
+            }
+            (3, 1 | 2 | 4) => {
+                let limits = self.graphics.regis_limits;
+                Ok(vec![limits.max_width, limits.max_height])
+            }
+            (3, 3) => Err(3),
+            (1..=3, _) => Err(2),
+            _ => Err(1),
+        };
+
+        let response = match result {
+            Ok(values) => {
+                let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+                format!("\x1b[?{};0;{}S", item, values.join(";"))
+            }
+            Err(status) => format!("\x1b[?{};{};0S", item, status),
+        };
+        self.push_response(response.as_bytes());
+    }
+
     /// DECRQCRA - Request Checksum of Rectangular Area
     /// CSI Pi ; Pg ; Pt ; Pl ; Pb ; Pr * y
     /// Response: DCS Pi ! ~ xxxx ST (4 hex-digit checksum)
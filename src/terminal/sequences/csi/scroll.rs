@@ -22,8 +22,7 @@ impl Terminal {
                 let n = if n == 0 { 1 } else { n };
                 let top = self.margins.scroll_region_top;
                 let bottom = self.margins.scroll_region_bottom;
-                self.active_grid_mut().scroll_region_up(n, top, bottom);
-                self.adjust_graphics_for_scroll_up(n, top, bottom);
+                self.scroll_up_in_margins(n, top, bottom);
             }
             'T' => {
                 // Scroll down (SD)
@@ -36,8 +35,18 @@ impl Terminal {
                 let n = if n == 0 { 1 } else { n };
                 let top = self.margins.scroll_region_top;
                 let bottom = self.margins.scroll_region_bottom;
-                self.active_grid_mut().scroll_region_down(n, top, bottom);
-                self.adjust_graphics_for_scroll_down(n, top, bottom);
+                self.scroll_down_in_margins(n, top, bottom);
+            }
+            '@' | 'A' => {
+                // Scroll left (SL) / scroll right (SR) within the margins
+                let n = params
+                    .iter()
+                    .next()
+                    .and_then(|p| p.first())
+                    .copied()
+                    .unwrap_or(1) as usize;
+                let n = if n == 0 { 1 } else { n };
+                self.scroll_columns(n, action == '@');
             }
             _ => {}
         }
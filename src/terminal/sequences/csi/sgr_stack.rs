@@ -0,0 +1,135 @@
+//! xterm attribute and color stacks
+//!
+//! - XTPUSHSGR (`CSI # {` / `CSI Ps ; ... # {`) and XTPOPSGR (`CSI # }`):
+//!   save the current video attributes, or only the selected ones, and
+//!   restore them later
+//! - XTPUSHCOLORS (`CSI # P`) and XTPOPCOLORS (`CSI # Q`): save and restore
+//!   the current foreground, background and underline colors
+//!
+//! Both stacks hold at most ten entries, like xterm; further pushes are
+//! ignored and popping an empty stack leaves the attributes unchanged.
+
+use crate::terminal::{SgrStackEntry, Terminal};
+use vte::Params;
+
+/// Maximum entries in the SGR and color stacks (xterm's limit)
+const MAX_STACK_DEPTH: usize = 10;
+
+// Attributes selectable by XTPUSHSGR parameters
+const BOLD: u16 = 1 << 0;
+const FAINT: u16 = 1 << 1;
+const ITALIC: u16 = 1 << 2;
+const UNDERLINE: u16 = 1 << 3;
+const BLINK: u16 = 1 << 4;
+const INVERSE: u16 = 1 << 5;
+const INVISIBLE: u16 = 1 << 6;
+const CROSSED_OUT: u16 = 1 << 7;
+const FOREGROUND: u16 = 1 << 8;
+const BACKGROUND: u16 = 1 << 9;
+const OVERLINE: u16 = 1 << 10;
+const ALL: u16 = (1 << 11) - 1;
+
+/// Attribute bits selected by XTPUSHSGR parameters (all if none are given)
+fn selected_attributes(params: &Params) -> u16 {
+    let mut mask = 0;
+    for param in params.iter().filter_map(|p| p.first().copied()) {
+        mask |= match param {
+            1 => BOLD,
+            2 => FAINT,
+            3 => ITALIC,
+            // Double underline is an underline style
+            4 | 21 => UNDERLINE,
+            5 => BLINK,
+            7 => INVERSE,
+            8 => INVISIBLE,
+            9 => CROSSED_OUT,
+            30 => FOREGROUND,
+            31 => BACKGROUND,
+            _ => 0,
+        };
+    }
+    if mask == 0 {
+        ALL
+    } else {
+        mask
+    }
+}
+
+impl Terminal {
+    /// Dispatch `CSI # {`, `CSI # }`, `CSI # P` and `CSI # Q`
+    pub(crate) fn handle_csi_attribute_stack(&mut self, action: char, params: &Params) {
+        match action {
+            '{' => self.push_sgr(selected_attributes(params)),
+            '}' => self.pop_sgr(),
+            'P' if self.theme.color_stack.len() < MAX_STACK_DEPTH => {
+                self.theme
+                    .color_stack
+                    .push((self.fg, self.bg, self.underline_color));
+            }
+            'Q' => {
+                if let Some((fg, bg, underline_color)) = self.theme.color_stack.pop() {
+                    self.fg = fg;
+                    self.bg = bg;
+                    self.underline_color = underline_color;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn push_sgr(&mut self, attributes: u16) {
+        if self.saved_state.sgr_stack.len() < MAX_STACK_DEPTH {
+            self.saved_state.sgr_stack.push(SgrStackEntry {
+                attributes,
+                fg: self.fg,
+                bg: self.bg,
+                underline_color: self.underline_color,
+                flags: self.flags,
+            });
+        }
+    }
+
+    fn pop_sgr(&mut self) {
+        let Some(saved) = self.saved_state.sgr_stack.pop() else {
+            return;
+        };
+        let has = |bit: u16| saved.attributes & bit != 0;
+        let from = saved.flags;
+        let flags = &mut self.flags;
+        if has(BOLD) {
+            flags.set_bold(from.bold());
+        }
+        if has(FAINT) {
+            flags.set_dim(from.dim());
+        }
+        if has(ITALIC) {
+            flags.set_italic(from.italic());
+        }
+        if has(UNDERLINE) {
+            flags.set_underline(from.underline());
+            flags.underline_style = from.underline_style;
+            self.underline_color = saved.underline_color;
+        }
+        if has(BLINK) {
+            flags.set_blink(from.blink());
+        }
+        if has(INVERSE) {
+            flags.set_reverse(from.reverse());
+        }
+        if has(INVISIBLE) {
+            flags.set_hidden(from.hidden());
+        }
+        if has(CROSSED_OUT) {
+            flags.set_strikethrough(from.strikethrough());
+        }
+        if has(OVERLINE) {
+            flags.set_overline(from.overline());
+        }
+        if has(FOREGROUND) {
+            self.fg = saved.fg;
+        }
+        if has(BACKGROUND) {
+            self.bg = saved.bg;
+        }
+    }
+}
@@ -516,6 +516,76 @@ fn test_xtwinops_report_size() {
     assert_eq!(response, b"\x1b[8;24;80t");
 }
 
+#[test]
+fn test_xtwinops_report_pixel_and_screen_size() {
+    let mut term = Terminal::new(80, 24);
+    term.set_pixel_size(800, 480);
+
+    term.process(b"\x1b[14t\x1b[14;2t\x1b[15t\x1b[19t");
+    assert_eq!(
+        term.drain_responses(),
+        b"\x1b[4;480;800t\x1b[4;480;800t\x1b[5;480;800t\x1b[9;24;80t"
+    );
+
+    // Unset pixel dimensions fall back to a 10x20 cell
+    term.set_pixel_size(0, 0);
+    term.process(b"\x1b[14t");
+    assert_eq!(term.drain_responses(), b"\x1b[4;480;800t");
+}
+
+fn resize_requests(term: &mut Terminal) -> Vec<(usize, usize, bool)> {
+    term.poll_events()
+        .into_iter()
+        .filter_map(|event| match event {
+            TerminalEvent::ResizeRequested { cols, rows, pixels } => Some((cols, rows, pixels)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_xtwinops_resize_requests_emit_events() {
+    let mut term = Terminal::new(80, 24);
+    term.poll_events();
+
+    term.process(b"\x1b[8;40;132t");
+    term.process(b"\x1b[8;50t");
+    term.process(b"\x1b[8;0;100t");
+    term.process(b"\x1b[4;600;1000t");
+    term.process(b"\x1b[4;;1000t");
+    assert_eq!(
+        resize_requests(&mut term),
+        vec![
+            (132, 40, false),
+            (80, 50, false),
+            (100, 24, false),
+            (1000, 600, true),
+            (1000, 480, true),
+        ]
+    );
+
+    // The terminal never resizes itself and the bell volume is untouched
+    assert_eq!(term.size(), (80, 24));
+    assert_eq!(term.warning_bell_volume, 4);
+}
+
+#[test]
+fn test_decslpp_and_decsnls_request_rows() {
+    let mut term = Terminal::new(80, 24);
+    term.poll_events();
+
+    // DECSLPP only applies from 24 lines up; smaller values are DECSWBV
+    term.process(b"\x1b[36t\x1b[5t");
+    // DECSNLS; a zero count is ignored
+    term.process(b"\x1b[48*|\x1b[0*|");
+    assert_eq!(
+        resize_requests(&mut term),
+        vec![(80, 36, false), (80, 48, false)]
+    );
+    assert_eq!(term.warning_bell_volume, 5);
+    assert!(term.unsupported_sequences().is_empty());
+}
+
 #[test]
 fn test_xtwinops_title_stack() {
     let mut term = Terminal::new(80, 24);
@@ -547,6 +617,101 @@ fn test_insert_mode() {
     assert!(!term.modes.insert_mode);
 }
 
+// ========== REP and Column Editing Tests ==========
+
+/// Helper: text of a screen row with trailing blanks removed
+fn row_text(term: &Terminal, row: usize) -> String {
+    term.active_grid().row_text(row).trim_end().to_string()
+}
+
+#[test]
+fn test_rep_repeats_last_character() {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"ab\x1b[3b");
+    assert_eq!(row_text(&term, 0), "abbbb");
+
+    // Default and zero counts repeat once
+    term.process(b"\x1b[b\x1b[0b");
+    assert_eq!(row_text(&term, 0), "abbbbbb");
+}
+
+#[test]
+fn test_rep_uses_current_attributes_and_wraps() {
+    let mut term = Terminal::new(10, 5);
+    term.process(b"x\x1b[1m\x1b[11b");
+    assert_eq!(row_text(&term, 0), "xxxxxxxxxx");
+    assert_eq!(row_text(&term, 1), "xx");
+    assert!(!term.active_grid().get(0, 0).unwrap().flags.bold());
+    assert!(term.active_grid().get(1, 1).unwrap().flags.bold());
+}
+
+#[test]
+fn test_rep_repeats_non_ascii_and_line_drawing() {
+    let mut term = Terminal::new(80, 24);
+    term.process("é\x1b[2b".as_bytes());
+    assert_eq!(row_text(&term, 0), "ééé");
+
+    term.process(b"\r\n\x1b(0q\x1b[2b\x1b(B");
+    assert_eq!(row_text(&term, 1), "───");
+}
+
+#[test]
+fn test_rep_after_control_is_ignored() {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"a\r\n\x1b[5b");
+    assert_eq!(row_text(&term, 1), "");
+
+    // Escape sequences other than C0 controls keep the character
+    term.process(b"z\x1b[31m\x1b[2b");
+    assert_eq!(row_text(&term, 1), "zzz");
+}
+
+#[test]
+fn test_rep_after_combining_mark_is_ignored() {
+    let mut term = Terminal::new(80, 24);
+    term.process("e\u{301}\x1b[3b".as_bytes());
+    let mut plain = Terminal::new(80, 24);
+    plain.process("e\u{301}".as_bytes());
+    assert_eq!(row_text(&term, 0), row_text(&plain, 0));
+    assert_eq!(term.cursor().col, 1);
+}
+
+#[test]
+fn test_decic_inserts_columns_in_scroll_region() {
+    let mut term = Terminal::new(10, 4);
+    term.process(b"abcdef\r\nghijkl\r\nmnopqr");
+    term.process(b"\x1b[1;2r\x1b[1;3H\x1b[2'}");
+    assert_eq!(row_text(&term, 0), "ab  cdef");
+    assert_eq!(row_text(&term, 1), "gh  ijkl");
+    // Row 3 is below the scroll region
+    assert_eq!(row_text(&term, 2), "mnopqr");
+}
+
+#[test]
+fn test_decdc_deletes_columns_in_scroll_region() {
+    let mut term = Terminal::new(10, 4);
+    term.process(b"abcdef\r\nghijkl\r\nmnopqr");
+    term.process(b"\x1b[2;3r\x1b[2;2H\x1b[3'~");
+    assert_eq!(row_text(&term, 0), "abcdef");
+    assert_eq!(row_text(&term, 1), "gkl");
+    assert_eq!(row_text(&term, 2), "mqr");
+}
+
+#[test]
+fn test_decic_decdc_respect_left_right_margins() {
+    let mut term = Terminal::new(10, 2);
+    term.process(b"0123456789");
+    // DECLRMM on, margins at columns 3..=6, cursor at column 4
+    term.process(b"\x1b[?69h\x1b[3;6s\x1b[1;4H\x1b['}");
+    assert_eq!(row_text(&term, 0), "012 346789");
+    term.process(b"\x1b[2'~");
+    assert_eq!(row_text(&term, 0), "0124  6789");
+
+    // Ignored outside the margins
+    term.process(b"\x1b[1;9H\x1b['}");
+    assert_eq!(row_text(&term, 0), "0124  6789");
+}
+
 // ========== Mode Changed Event Tests ==========
 
 /// Helper: drain events and find ModeChanged events matching a mode name
@@ -554,7 +719,9 @@ fn find_mode_events(term: &mut Terminal, mode: &str) -> Vec<(String, bool)> {
     term.poll_events()
         .into_iter()
         .filter_map(|e| match e {
-            TerminalEvent::ModeChanged(m, enabled) if m == mode => Some((m, enabled)),
+            TerminalEvent::ModeChanged(m, enabled) if m.name() == mode => {
+                Some((m.name().to_string(), enabled))
+            }
             _ => None,
         })
         .collect()
@@ -962,3 +1129,51 @@ fn test_synchronized_updates_mode_toggle() {
         "synchronized_updates should be false after disable"
     );
 }
+
+#[test]
+fn test_xtsmgraphics_sixel_geometry() {
+    let mut term = Terminal::new(80, 24);
+    term.set_sixel_limits(800, 600, 1000);
+
+    term.process(b"\x1b[?2;1S");
+    assert_eq!(term.drain_responses(), b"\x1b[?2;0;800;600S");
+    // Missing Pa reads
+    term.process(b"\x1b[?2S");
+    assert_eq!(term.drain_responses(), b"\x1b[?2;0;800;600S");
+
+    // Set is capped by the configured limits
+    term.process(b"\x1b[?2;3;640;2000S");
+    assert_eq!(term.drain_responses(), b"\x1b[?2;0;640;600S");
+    assert_eq!(term.sixel_geometry(), (640, 600));
+
+    term.process(b"\x1b[?2;4S");
+    assert_eq!(term.drain_responses(), b"\x1b[?2;0;800;600S");
+
+    term.process(b"\x1b[?2;2S");
+    assert_eq!(term.drain_responses(), b"\x1b[?2;0;800;600S");
+    assert_eq!(term.sixel_geometry(), (800, 600));
+
+    // Set without a size fails
+    term.process(b"\x1b[?2;3S");
+    assert_eq!(term.drain_responses(), b"\x1b[?2;3;0S");
+}
+
+#[test]
+fn test_xtsmgraphics_color_registers_and_errors() {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"\x1b[?1;1S");
+    assert_eq!(term.drain_responses(), b"\x1b[?1;0;4096S");
+    term.process(b"\x1b[?1;9S");
+    assert_eq!(term.drain_responses(), b"\x1b[?1;2;0S");
+    term.set_regis_limits(640, 400, 4096);
+    term.process(b"\x1b[?3;1S");
+    assert_eq!(term.drain_responses(), b"\x1b[?3;0;640;400S");
+    term.process(b"\x1b[?3;3;100;100S");
+    assert_eq!(term.drain_responses(), b"\x1b[?3;3;0S");
+    term.process(b"\x1b[?7;1S");
+    assert_eq!(term.drain_responses(), b"\x1b[?7;1;0S");
+
+    // Without '?' CSI S still scrolls and sends nothing
+    term.process(b"\x1b[2S");
+    assert!(term.drain_responses().is_empty());
+}
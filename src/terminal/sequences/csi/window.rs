@@ -1,6 +1,7 @@
 //! Window-related CSI sequence handling (XTWINOPS, etc.)
 
-use crate::terminal::Terminal;
+use crate::debug;
+use crate::terminal::{Terminal, TerminalEvent, TitleKind};
 use vte::Params;
 
 impl Terminal {
@@ -152,20 +153,23 @@ impl Terminal {
                         }
                     }
 
+                    let extent = self.attribute_change_extent;
                     if action == 'r' {
-                        self.active_grid_mut().change_attributes_in_rectangle(
+                        self.active_grid_mut().change_attributes_in_area(
                             top,
                             left,
                             bottom,
                             right,
+                            extent,
                             &attributes,
                         );
                     } else {
-                        self.active_grid_mut().reverse_attributes_in_rectangle(
+                        self.active_grid_mut().reverse_attributes_in_area(
                             top,
                             left,
                             bottom,
                             right,
+                            extent,
                             &attributes,
                         );
                     }
@@ -192,13 +196,44 @@ impl Terminal {
                 }
 
                 match n {
+                    4 | 8 if params.iter().count() > 1 => {
+                        // Resize request: CSI 4 ; height ; width t (pixels) or
+                        // CSI 8 ; rows ; cols t (characters)
+                        let mut dimension = || {
+                            iter.next()
+                                .and_then(|p| p.first())
+                                .copied()
+                                .filter(|&v| v > 0)
+                                .map(usize::from)
+                        };
+                        let (height, width) = (dimension(), dimension());
+                        let pixels = n == 4;
+                        let (current_width, current_height) = if pixels {
+                            self.text_area_pixels()
+                        } else {
+                            (cols, rows)
+                        };
+                        self.request_resize(
+                            width.unwrap_or(current_width),
+                            height.unwrap_or(current_height),
+                            pixels,
+                        );
+                    }
                     0..=8 => {
                         // Already handled above, but kept for match exhaustiveness/structure
                     }
                     14 => {
-                        // Report text area size in pixels
-                        let response =
-                            format!("\x1b[4;{};{}t", self.pixel_height, self.pixel_width);
+                        // Report text area size in pixels (CSI 14 ; 2 t asks for the
+                        // window, which is the text area here)
+                        let (width, height) = self.text_area_pixels();
+                        let response = format!("\x1b[4;{};{}t", height, width);
+                        self.push_response(response.as_bytes());
+                    }
+                    15 => {
+                        // Report screen size in pixels; the embedder owns the
+                        // display, so the text area is the best answer we have
+                        let (width, height) = self.text_area_pixels();
+                        let response = format!("\x1b[5;{};{}t", height, width);
                         self.push_response(response.as_bytes());
                     }
                     16 => {
@@ -227,6 +262,11 @@ impl Terminal {
                         let response = format!("\x1b[8;{};{}t", rows, cols);
                         self.push_response(response.as_bytes());
                     }
+                    19 => {
+                        // Report screen size in characters
+                        let response = format!("\x1b[9;{};{}t", rows, cols);
+                        self.push_response(response.as_bytes());
+                    }
                     22 => {
                         // Push icon name and window title to stack
                         self.title_state.title_stack.push(self.title_state.title.clone());
@@ -234,12 +274,23 @@ impl Terminal {
                     23 => {
                         // Pop icon name and window title from stack
                         if let Some(title) = self.title_state.title_stack.pop() {
-                            self.title_state.title = title;
+                            self.apply_title_change(TitleKind::Title, title);
                         }
                     }
+                    24.. => {
+                        // DECSLPP - Set Lines Per Page: CSI Ps t with Ps >= 24
+                        self.request_resize(cols, n as usize, false);
+                    }
                     _ => {}
                 }
             }
+            '|' if intermediates.contains(&b'*') => {
+                // DECSNLS - Set Number of Lines per Screen: CSI Ps * |
+                let n = params.iter().next().and_then(|p| p.first()).copied();
+                if let Some(lines) = n.filter(|&v| v > 0) {
+                    self.request_resize(cols, lines as usize, false);
+                }
+            }
             'r' => {
                 // Set scrolling region (DECSTBM)
                 let mut iter = params.iter();
@@ -255,30 +306,71 @@ impl Terminal {
                 if top < bottom {
                     self.margins.scroll_region_top = top;
                     self.margins.scroll_region_bottom = bottom;
-                    // Reset cursor to (0,0) relative to region if origin mode
-                    self.cursor.goto(0, if self.modes.origin_mode { top } else { 0 });
+                    self.home_cursor();
                 }
             }
             's'
                 // Set left and right margins (DECSLRM) - only if DECLRMM is set
                 if self.margins.use_lr_margins => {
                     let mut iter = params.iter();
-                    let left = iter.next().and_then(|p| p.first()).copied().unwrap_or(1) as usize;
-                    let right = iter
-                        .next()
-                        .and_then(|p| p.first())
-                        .copied()
-                        .unwrap_or(cols as u16) as usize;
-
-                    let left = left.saturating_sub(1);
-                    let right = right.saturating_sub(1).min(cols.saturating_sub(1));
-
-                    if left < right {
-                        self.margins.left_margin = left;
-                        self.margins.right_margin = right;
-                    }
+                    let left = iter.next().and_then(|p| p.first()).copied().unwrap_or(0) as usize;
+                    let right = iter.next().and_then(|p| p.first()).copied().unwrap_or(0) as usize;
+                    self.set_lr_margins(left, right);
                 }
             _ => {}
         }
     }
+
+    /// Text area size in pixels as (width, height), derived from the default
+    /// 10x20 cell when the host has not set a pixel size
+    fn text_area_pixels(&self) -> (usize, usize) {
+        let (cols, rows) = self.size();
+        let width = if self.pixel_width > 0 {
+            self.pixel_width
+        } else {
+            cols * 10
+        };
+        let height = if self.pixel_height > 0 {
+            self.pixel_height
+        } else {
+            rows * 20
+        };
+        (width, height)
+    }
+
+    /// Report an application's resize request; the host decides whether to act
+    fn request_resize(&mut self, cols: usize, rows: usize, pixels: bool) {
+        debug::log(
+            debug::DebugLevel::Debug,
+            "CSI",
+            &format!(
+                "Resize requested: {}x{}{}",
+                cols,
+                rows,
+                if pixels { " px" } else { "" }
+            ),
+        );
+        self.events
+            .terminal_events
+            .push(TerminalEvent::ResizeRequested { cols, rows, pixels });
+    }
+
+    /// DECSACE - Select Attribute Change Extent: CSI Ps * x
+    ///
+    /// Ps 0 or 1 makes DECCARA/DECRARA follow the text stream, 2 limits
+    /// them to the rectangle; other values are ignored.
+    pub(crate) fn handle_decsace(&mut self, params: &Params) {
+        use crate::grid::AttributeChangeExtent;
+        let ps = params
+            .iter()
+            .next()
+            .and_then(|p| p.first())
+            .copied()
+            .unwrap_or(0);
+        self.attribute_change_extent = match ps {
+            0 | 1 => AttributeChangeExtent::Stream,
+            2 => AttributeChangeExtent::Rectangle,
+            _ => return,
+        };
+    }
 }
@@ -1,10 +1,12 @@
 //! DCS (Device Control String) sequence handling dispatcher
 
+mod regis;
 mod sixel;
 
 use crate::debug;
 use crate::graphics::{next_graphic_id, GraphicProtocol, TerminalGraphic};
-use crate::terminal::Terminal;
+use crate::terminal::action::{params_to_owned, TerminalAction};
+use crate::terminal::{DcsSequence, Sequence, SequenceAction, Terminal, UnsupportedKind};
 use vte::Params;
 
 impl Terminal {
@@ -12,25 +14,68 @@ impl Terminal {
     pub(in crate::terminal) fn dcs_hook(
         &mut self,
         params: &Params,
-        _intermediates: &[u8],
+        intermediates: &[u8],
         _ignore: bool,
         action: char,
     ) {
-        if action == 'q' && self.security_state.disable_insecure_sequences {
+        if self.sequence_filter.is_some() {
+            let filter_params: Vec<u16> = params
+                .iter()
+                .map(|group| group.first().copied().unwrap_or(0))
+                .collect();
+            let sequence = Sequence::Dcs(DcsSequence {
+                params: &filter_params,
+                intermediates,
+                action,
+            });
+            if self.filter_sequence(&sequence) != SequenceAction::Allow {
+                return;
+            }
+        }
+
+        // DCS p without intermediates is ReGIS (DCS $ p is DECRSTS)
+        let is_regis = action == 'p' && intermediates.is_empty();
+        if (action == 'q' || is_regis) && self.security_state.disable_insecure_sequences {
             debug::log(
                 debug::DebugLevel::Debug,
                 "SECURITY",
-                "Blocked Sixel DCS (disable_insecure_sequences=true)",
+                if is_regis {
+                    "Blocked ReGIS DCS (disable_insecure_sequences=true)"
+                } else {
+                    "Blocked Sixel DCS (disable_insecure_sequences=true)"
+                },
             );
             return;
         }
 
+        let is_sixel = action == 'q' && intermediates.is_empty();
+        let is_macro = action == 'z' && intermediates == b"!";
+        // DCS @kitty-cmd{...}: kitty remote control
+        let is_kitty_cmd = action == '@' && intermediates.is_empty();
+        if !is_sixel && !is_regis && !is_macro && !is_kitty_cmd {
+            self.record_unsupported(
+                UnsupportedKind::Dcs,
+                format!("DCS {}{}", String::from_utf8_lossy(intermediates), action),
+                TerminalAction::DcsHook {
+                    params: params_to_owned(params),
+                    intermediates: intermediates.to_vec(),
+                    ignore: false,
+                    action,
+                },
+            );
+        }
+
         self.dcs_state.dcs_active = true;
         self.dcs_state.dcs_action = Some(action);
         self.dcs_state.dcs_buffer.clear();
+        self.dcs_state.dec_macro = None;
 
         if action == 'q' {
             self.handle_sixel_hook(params);
+        } else if is_regis {
+            self.handle_regis_hook(params);
+        } else if is_macro {
+            self.begin_dec_macro(params);
         }
     }
 
@@ -89,24 +134,56 @@ impl Terminal {
                 {
                     self.process_sixel_command();
                 }
+                self.push_dcs_byte(byte);
+            }
+        } else if self.dcs_state.regis_parser.is_some() {
+            // Bytes beyond the limit are dropped
+            if self.dcs_state.dcs_buffer.len() < self.graphics.regis_limits.max_bytes {
                 self.dcs_state.dcs_buffer.push(byte);
             }
         } else {
+            self.push_dcs_byte(byte);
+        }
+    }
+
+    /// Buffer one DCS byte, dropping it once the sequence reaches
+    /// `max_dcs_data_length` (an unterminated DCS must not grow unbounded)
+    fn push_dcs_byte(&mut self, byte: u8) {
+        if self.dcs_state.dcs_buffer.len() < self.security_state.max_dcs_data_length {
             self.dcs_state.dcs_buffer.push(byte);
         }
     }
 
     /// VTE unhook - end of DCS sequence
     pub(in crate::terminal) fn dcs_unhook(&mut self) {
+        if std::mem::take(&mut self.dcs_cut_pending) {
+            // The escape string watchdog cancelled this DCS; drop what it
+            // collected instead of running it
+            debug::log(
+                debug::DebugLevel::Debug,
+                "DCS",
+                "Unterminated DCS cancelled by the watchdog",
+            );
+            self.dcs_state.dcs_active = false;
+            self.dcs_state.dcs_action = None;
+            self.dcs_state.dcs_buffer.clear();
+            self.dcs_state.sixel_parser = None;
+            self.dcs_state.regis_parser = None;
+            self.dcs_state.dec_macro = None;
+            return;
+        }
         if !self.dcs_state.dcs_active {
             return;
         }
 
         if self.dcs_state.dcs_action == Some('q') {
             self.process_sixel_command();
-            if let Some(parser) = self.dcs_state.sixel_parser.take() {
+            if let Some(mut parser) = self.dcs_state.sixel_parser.take() {
                 let position = (self.cursor.col, self.cursor.row);
                 let sixel_graphic = parser.build_graphic(position);
+                if !self.graphics.sixel_private_color_registers {
+                    self.graphics.sixel_shared_palette = Some(parser.take_palette());
+                }
 
                 // Convert SixelGraphic to TerminalGraphic
                 let mut pixels = Vec::with_capacity(sixel_graphic.width * sixel_graphic.height * 4);
@@ -137,9 +214,7 @@ impl Terminal {
 
                 let row = self.cursor.row;
                 self.graphics.graphics_store.add_graphic(graphic);
-                self.events
-                    .terminal_events
-                    .push(crate::terminal::TerminalEvent::GraphicsAdded(row));
+                self.push_render_event(crate::terminal::TerminalEvent::GraphicsAdded(row));
 
                 // Advance cursor to next line(s) as per test expectation
                 if cell_h > 0 {
@@ -149,6 +224,13 @@ impl Terminal {
                     self.cursor.move_down(rows, screen_rows.saturating_sub(1));
                 }
             }
+        } else if self.dcs_state.dcs_action == Some('p') {
+            self.finish_regis();
+        } else if self.dcs_state.dec_macro.is_some() {
+            self.finish_dec_macro();
+        } else if self.dcs_state.dcs_action == Some('@') {
+            let body = std::mem::take(&mut self.dcs_state.dcs_buffer);
+            self.handle_kitty_remote_command(&body);
         }
 
         self.dcs_state.dcs_active = false;
@@ -0,0 +1,75 @@
+//! ReGIS graphics DCS sequence handling
+
+use crate::graphics::{next_graphic_id, GraphicProtocol, TerminalGraphic};
+use crate::regis;
+use crate::terminal::Terminal;
+use vte::Params;
+
+impl Terminal {
+    /// Start a ReGIS sequence: `DCS Pm p`
+    ///
+    /// Drawing continues on the previous plane while its graphic is still
+    /// on screen; otherwise a fresh plane is started. Pm 1 or 3 resets the
+    /// drawing state.
+    pub(crate) fn handle_regis_hook(&mut self, params: &Params) {
+        let mode = params
+            .iter()
+            .next()
+            .and_then(|p| p.first().copied())
+            .unwrap_or(0);
+        let limits = self.graphics.regis_limits;
+        let displayed = self.graphics.regis_graphic_id.is_some_and(|id| {
+            self.graphics
+                .graphics_store
+                .all_graphics()
+                .iter()
+                .any(|g| g.id == id && g.position == (0, 0))
+        });
+
+        let mut parser = match self.graphics.regis_parser.take() {
+            Some(parser)
+                if displayed
+                    && parser.width() == limits.max_width
+                    && parser.height() == limits.max_height =>
+            {
+                parser
+            }
+            _ => regis::RegisParser::new(limits),
+        };
+        if mode & 1 == 1 {
+            parser.reset_state();
+        }
+        self.dcs_state.regis_parser = Some(parser);
+    }
+
+    /// Run the buffered ReGIS commands and show the plane as a graphic
+    /// anchored at the top-left of the screen, replacing the previous one
+    pub(crate) fn finish_regis(&mut self) {
+        let Some(mut parser) = self.dcs_state.regis_parser.take() else {
+            return;
+        };
+        let data = std::mem::take(&mut self.dcs_state.dcs_buffer);
+        parser.parse(&data);
+
+        if let Some(id) = self.graphics.regis_graphic_id.take() {
+            self.graphics.graphics_store.remove_graphic(id);
+        }
+        if !parser.is_blank() {
+            let mut graphic = TerminalGraphic::new(
+                next_graphic_id(),
+                GraphicProtocol::Regis,
+                (0, 0),
+                parser.width(),
+                parser.height(),
+                parser.to_rgba(),
+            );
+            let (cell_w, cell_h) = self.graphics.cell_dimensions;
+            graphic.set_cell_dimensions(cell_w, cell_h);
+
+            self.graphics.regis_graphic_id = Some(graphic.id);
+            self.graphics.graphics_store.add_graphic(graphic);
+            self.push_render_event(crate::terminal::TerminalEvent::GraphicsAdded(0));
+        }
+        self.graphics.regis_parser = Some(parser);
+    }
+}
@@ -66,8 +66,11 @@ impl Terminal {
             '!'
                 // Repeat sequence: !Pn character
                 if buffer_str.len() >= 2 => {
-                    let count_str = &buffer_str[1..buffer_str.len() - 1];
-                    let repeat_char = buffer_str.chars().last().unwrap_or('?');
+                    // Split on a char boundary: lossy decoding may end the
+                    // buffer with a multi-byte replacement character
+                    let (last_idx, repeat_char) =
+                        buffer_str.char_indices().last().unwrap_or((1, '?'));
+                    let count_str = &buffer_str[1..last_idx.max(1)];
                     if let Ok(count) = count_str.parse::<usize>() {
                         parser.parse_repeat(count, repeat_char);
                     }
@@ -79,7 +82,18 @@ impl Terminal {
     }
 
     pub(crate) fn handle_sixel_hook(&mut self, params: &Params) {
-        let mut parser = sixel::SixelParser::new_with_limits(self.graphics.sixel_limits);
+        let (max_width, max_height) = self.sixel_geometry();
+        let limits = sixel::SixelLimits {
+            max_width,
+            max_height,
+            ..self.graphics.sixel_limits
+        };
+        let mut parser = sixel::SixelParser::new_with_limits(limits);
+        if !self.graphics.sixel_private_color_registers {
+            if let Some(palette) = &self.graphics.sixel_shared_palette {
+                parser.set_palette(palette.clone());
+            }
+        }
 
         // Convert Params to Vec<u16> for set_params
         let params_vec: Vec<u16> = params
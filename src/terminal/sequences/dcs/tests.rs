@@ -445,3 +445,130 @@ fn test_dcs_non_sixel_action_ignored() {
     term.dcs_unhook();
     assert!(!term.dcs_state.dcs_active);
 }
+
+#[test]
+fn test_sixel_transparent_background_from_p2() {
+    let mut term = create_test_terminal();
+    // P2=1: raster area outside the drawn pixel stays transparent
+    term.process(b"\x1bP0;1q\"1;1;4;6#15@\x1b\\");
+    // P2 omitted: the raster area is filled with color register 0
+    term.process(b"\x1bPq\"1;1;4;6#15@\x1b\\");
+
+    let graphics = term.all_graphics();
+    assert_eq!(graphics.len(), 2);
+    assert_eq!(graphics[0].get_pixel(0, 0), Some((255, 255, 255, 255)));
+    assert_eq!(graphics[0].get_pixel(3, 5).map(|p| p.3), Some(0));
+    assert_eq!(graphics[1].get_pixel(3, 5), Some((0, 0, 0, 255)));
+}
+
+#[test]
+fn test_sixel_shared_color_registers() {
+    let mut term = create_test_terminal();
+    let red = b"\x1bPq#1;2;100;0;0\x1b\\";
+    let use_reg_1 = b"\x1bPq#1~\x1b\\";
+
+    // Private registers (default): the second image sees the default color 1
+    term.process(red);
+    term.process(use_reg_1);
+    assert_eq!(
+        term.all_graphics()[1].get_pixel(0, 0),
+        Some((51, 102, 179, 255))
+    );
+
+    // Shared registers (DECRST 1070): the definition carries over
+    term.process(b"\x1b[?1070l");
+    assert!(!term.sixel_private_color_registers());
+    term.process(red);
+    term.process(use_reg_1);
+    assert_eq!(
+        term.all_graphics()[3].get_pixel(0, 0),
+        Some((255, 0, 0, 255))
+    );
+}
+
+#[test]
+fn test_sixel_geometry_limits_decoding() {
+    let mut term = create_test_terminal();
+    term.process(b"\x1b[?2;3;2;6S");
+    term.drain_responses();
+    term.process(b"\x1bPq~~~~\x1b\\");
+
+    let graphic = &term.all_graphics()[0];
+    assert_eq!((graphic.width, graphic.height), (2, 6));
+}
+
+#[test]
+fn test_regis_draws_graphic_at_screen_origin() {
+    let mut term = create_test_terminal();
+    term.set_regis_limits(80, 48, 4096);
+    term.process(b"hello\r\n");
+    term.process(b"\x1bPpS(A[0,0][79,47])W(I3)P[10,10]V[20,10]\x1b\\");
+
+    let graphics = term.all_graphics();
+    assert_eq!(graphics.len(), 1);
+    let graphic = &graphics[0];
+    assert_eq!(graphic.protocol, GraphicProtocol::Regis);
+    assert_eq!(graphic.position, (0, 0));
+    assert_eq!((graphic.width, graphic.height), (80, 48));
+    assert_eq!(graphic.get_pixel(15, 10), Some((51, 179, 51, 255)));
+    assert_eq!(graphic.get_pixel(15, 11), Some((0, 0, 0, 0)));
+    // The cursor does not move
+    assert_eq!((term.cursor.col, term.cursor.row), (0, 1));
+}
+
+#[test]
+fn test_regis_resumes_on_displayed_plane() {
+    let mut term = create_test_terminal();
+    term.set_regis_limits(80, 48, 4096);
+    term.process(b"\x1bPpS(A[0,0][79,47])P[1,1]V[]\x1b\\");
+    term.process(b"\x1bPpV[5,1]\x1b\\");
+
+    // The second sequence replaces the graphic and keeps the first drawing
+    let graphics = term.all_graphics();
+    assert_eq!(graphics.len(), 1);
+    assert!(graphics[0].get_pixel(1, 1).is_some_and(|p| p.3 == 255));
+    assert!(graphics[0].get_pixel(5, 1).is_some_and(|p| p.3 == 255));
+
+    // Pm = 1 resets the drawing state (position back to the origin)
+    term.process(b"\x1bP1pV[]\x1b\\");
+    assert_eq!(term.all_graphics()[0].get_pixel(0, 0).unwrap().3, 255);
+
+    // Once the graphic is gone a fresh plane is started
+    term.clear_graphics();
+    term.process(b"\x1bPpP[70,40]V[]\x1b\\");
+    let graphics = term.all_graphics();
+    assert_eq!(graphics.len(), 1);
+    assert_eq!(graphics[0].get_pixel(1, 1), Some((0, 0, 0, 0)));
+}
+
+#[test]
+fn test_regis_blocked_by_security_and_byte_limit() {
+    let mut term = create_test_terminal();
+    term.security_state.disable_insecure_sequences = true;
+    term.process(b"\x1bPpP[1,1]V[]\x1b\\");
+    assert!(term.all_graphics().is_empty());
+
+    let mut term = create_test_terminal();
+    term.set_regis_limits(80, 48, 6);
+    // Only "P[1,1]" arrives within the limit: nothing is drawn
+    term.process(b"\x1bPpP[1,1]V[]\x1b\\");
+    assert!(term.all_graphics().is_empty());
+}
+
+#[test]
+fn test_regis_limits_survive_ris() {
+    let mut term = create_test_terminal();
+    term.set_regis_limits(80, 48, 6);
+    term.process(b"\x1bc");
+    assert_eq!(
+        term.regis_limits(),
+        crate::regis::RegisLimits::new(80, 48, 6)
+    );
+}
+
+#[test]
+fn test_dcs_dollar_p_is_not_regis() {
+    let mut term = create_test_terminal();
+    term.dcs_hook(&create_empty_params(), b"$", false, 'p');
+    assert!(term.dcs_state.regis_parser.is_none());
+}
@@ -4,12 +4,15 @@
 //! - Cursor save/restore (DECSC/DECRC)
 //! - Tab stop management (HTS)
 //! - Cursor movement (IND, RI, NEL)
-//! - Terminal reset (RIS)
+//! - Terminal reset (RIS) and screen alignment test (DECALN)
+//! - Terminal identification (DECID)
 //! - Character protection (SPA/EPA)
-//! - Charset designation (SCS): G0/G1 → ASCII or DEC Line Drawing
+//! - Charset designation (SCS) into G0-G3, including the NRCS sets
+//! - Locking shifts (LS2, LS3, LS1R-LS3R) and single shifts (SS2, SS3)
 
+use crate::cell::Cell;
 use crate::debug;
-use crate::terminal::{Charset, Terminal};
+use crate::terminal::Terminal;
 
 impl Terminal {
     /// VTE ESC dispatch - handle ESC sequences
@@ -21,10 +24,41 @@ impl Terminal {
     ) {
         debug::log_esc_dispatch(intermediates, byte as char);
         match (byte, intermediates) {
+            // SCS — Select Character Set: ESC ( / ) / * / + F designates G0-G3.
+            // Matched first so finals such as `7` or `H` are not taken for
+            // DECSC or HTS.
+            (_, [b'(' | b')' | b'*' | b'+', ..]) => self.designate_charset(intermediates, byte),
             (b'7', _) => {
                 // Save cursor (DECSC)
                 self.save_cursor();
             }
+            (b'8', [b'#']) => {
+                // Screen alignment test (DECALN): fill the screen with 'E',
+                // reset the margins and home the cursor
+                let (cols, rows) = self.size();
+                self.margins.scroll_region_top = 0;
+                self.margins.scroll_region_bottom = rows.saturating_sub(1);
+                self.margins.left_margin = 0;
+                self.margins.right_margin = cols.saturating_sub(1);
+                self.modes.origin_mode = false;
+                self.pending_wrap = false;
+                self.cursor.goto(0, 0);
+                self.active_grid_mut().fill_rectangle(
+                    Cell::new('E'),
+                    0,
+                    0,
+                    rows.saturating_sub(1),
+                    cols.saturating_sub(1),
+                );
+            }
+            (b'6', []) => {
+                // Back index (DECBI)
+                self.back_index();
+            }
+            (b'9', []) => {
+                // Forward index (DECFI)
+                self.forward_index();
+            }
             (b'8', _) => {
                 // Restore cursor (DECRC)
                 self.restore_cursor();
@@ -36,17 +70,15 @@ impl Terminal {
                 }
             (b'M', _) => {
                 // Reverse index (RI) - move cursor up one line, scroll if at top
+                // of the scroll region and inside the left/right margins
                 self.pending_wrap = false;
-                if self.cursor.row > self.margins.scroll_region_top {
-                    self.cursor.row -= 1;
-                } else {
+                if self.cursor.row != self.margins.scroll_region_top {
+                    self.cursor.row = self.cursor.row.saturating_sub(1);
+                } else if self.margins.contains_col(self.cursor.col) {
                     // At top of scroll region, scroll down
                     let scroll_top = self.margins.scroll_region_top;
                     let scroll_bottom = self.margins.scroll_region_bottom;
-                    self.active_grid_mut()
-                        .scroll_region_down(1, scroll_top, scroll_bottom);
-                    // Adjust graphics to scroll with content
-                    self.adjust_graphics_for_scroll_down(1, scroll_top, scroll_bottom);
+                    self.scroll_down_in_margins(1, scroll_top, scroll_bottom);
                 }
             }
             (b'D', _) => {
@@ -54,9 +86,8 @@ impl Terminal {
                 // If outside left/right margins (DECLRMM), ignore scroll-at-bottom to match iTerm2.
                 self.pending_wrap = false;
                 let (_, rows) = self.size();
-                let outside_lr_margin = self.margins.use_lr_margins
-                    && (self.cursor.col < self.margins.left_margin || self.cursor.col > self.margins.right_margin);
-                if outside_lr_margin || self.cursor.row < self.margins.scroll_region_bottom {
+                let outside_lr_margin = !self.margins.contains_col(self.cursor.col);
+                if outside_lr_margin || self.cursor.row != self.margins.scroll_region_bottom {
                     self.cursor.row += 1;
                     if self.cursor.row >= rows {
                         self.cursor.row = rows - 1;
@@ -66,24 +97,16 @@ impl Terminal {
                     let scroll_top = self.margins.scroll_region_top;
                     let scroll_bottom = self.margins.scroll_region_bottom;
                     debug::log_scroll("ind-at-scroll-bottom", scroll_top, scroll_bottom, 1);
-                    self.active_grid_mut()
-                        .scroll_region_up(1, scroll_top, scroll_bottom);
-                    // Adjust graphics to scroll with content
-                    self.adjust_graphics_for_scroll_up(1, scroll_top, scroll_bottom);
+                    self.scroll_up_in_margins(1, scroll_top, scroll_bottom);
                 }
             }
             (b'E', _) => {
                 // Next line (NEL): move to first column of next line; if at bottom of scroll region, scroll the region.
                 self.pending_wrap = false;
-                self.cursor.col = if self.margins.use_lr_margins {
-                    self.margins.left_margin
-                } else {
-                    0
-                };
+                self.cursor.col = self.line_start_col();
                 let (_, rows) = self.size();
-                let outside_lr_margin = self.margins.use_lr_margins
-                    && (self.cursor.col < self.margins.left_margin || self.cursor.col > self.margins.right_margin);
-                if outside_lr_margin || self.cursor.row < self.margins.scroll_region_bottom {
+                let outside_lr_margin = !self.margins.contains_col(self.cursor.col);
+                if outside_lr_margin || self.cursor.row != self.margins.scroll_region_bottom {
                     self.cursor.row += 1;
                     if self.cursor.row >= rows {
                         self.cursor.row = rows - 1;
@@ -93,16 +116,17 @@ impl Terminal {
                     let scroll_top = self.margins.scroll_region_top;
                     let scroll_bottom = self.margins.scroll_region_bottom;
                     debug::log_scroll("nel-at-scroll-bottom", scroll_top, scroll_bottom, 1);
-                    self.active_grid_mut()
-                        .scroll_region_up(1, scroll_top, scroll_bottom);
-                    // Adjust graphics to scroll with content
-                    self.adjust_graphics_for_scroll_up(1, scroll_top, scroll_bottom);
+                    self.scroll_up_in_margins(1, scroll_top, scroll_bottom);
                 }
             }
             (b'c', _) => {
                 // Reset to initial state (RIS)
                 self.reset();
             }
+            (b'Z', []) => {
+                // DECID - Identify terminal: same reply as Primary DA
+                self.send_primary_device_attributes();
+            }
             (b'V', _) => {
                 // SPA - Start of Protected Area (DECSCA)
                 // Enable character protection for subsequent characters
@@ -113,16 +137,16 @@ impl Terminal {
                 // Disable character protection
                 self.modes.char_protected = false;
             }
-            // SCS — Select Character Set (G0 slot)
-            // ESC ( 0  → G0 = DEC Special / Line Drawing
-            (b'0', [b'(']) => self.charset_state.g0_charset = Charset::DecLineDrawing,
-            // ESC ( B  → G0 = ASCII (reset)
-            (b'B', [b'(']) => self.charset_state.g0_charset = Charset::Ascii,
-            // SCS — Select Character Set (G1 slot)
-            // ESC ) 0  → G1 = DEC Special / Line Drawing
-            (b'0', [b')']) => self.charset_state.g1_charset = Charset::DecLineDrawing,
-            // ESC ) B  → G1 = ASCII (reset)
-            (b'B', [b')']) => self.charset_state.g1_charset = Charset::Ascii,
+            // LS2 / LS3 — invoke G2 / G3 into GL
+            (b'n', []) => self.charset_state.active_g = 2,
+            (b'o', []) => self.charset_state.active_g = 3,
+            // LS1R / LS2R / LS3R — invoke G1 / G2 / G3 into GR
+            (b'~', []) => self.charset_state.active_gr = Some(1),
+            (b'}', []) => self.charset_state.active_gr = Some(2),
+            (b'|', []) => self.charset_state.active_gr = Some(3),
+            // SS2 / SS3 — G2 / G3 for the next graphic character only
+            (b'N', []) => self.charset_state.single_shift = Some(2),
+            (b'O', []) => self.charset_state.single_shift = Some(3),
             _ => {}
         }
     }
@@ -643,4 +667,31 @@ mod tests {
             "col should be restored to 9 (0-indexed)"
         );
     }
+
+    #[test]
+    fn test_decaln_fills_screen_and_resets_margins() {
+        let mut term = Terminal::new(10, 4);
+        term.process(b"\x1b[2;3r\x1b[?6h\x1b[31mhello\x1b7\x1b[1;1Hx");
+        term.process(b"\x1b#8");
+
+        let grid = term.active_grid();
+        for row in 0..4 {
+            assert_eq!(grid.row_text(row), "EEEEEEEEEE");
+        }
+        let cell = grid.get(9, 3).unwrap();
+        assert_eq!(cell.fg, Terminal::new(1, 1).fg);
+        assert_eq!((term.cursor.col, term.cursor.row), (0, 0));
+        assert_eq!(term.margins.scroll_region_top, 0);
+        assert_eq!(term.margins.scroll_region_bottom, 3);
+        assert!(!term.modes.origin_mode);
+    }
+
+    #[test]
+    fn test_decaln_is_not_decrc() {
+        let mut term = Terminal::new(10, 4);
+        term.process(b"\x1b[2;5H\x1b7\x1b[4;9H\x1b#8");
+        assert_eq!((term.cursor.col, term.cursor.row), (0, 0));
+        term.process(b"\x1b8");
+        assert_eq!((term.cursor.col, term.cursor.row), (4, 1));
+    }
 }
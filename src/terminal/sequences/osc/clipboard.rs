@@ -1,6 +1,6 @@
 //! Clipboard OSC sequence handling
 
-use crate::terminal::Terminal;
+use crate::terminal::{ReplyPriority, Terminal};
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 
 impl Terminal {
@@ -17,10 +17,10 @@ impl Terminal {
                                 if let Some(content) = &self.clipboard_state.clipboard_content {
                                     let encoded = BASE64.encode(content.as_bytes());
                                     let response = format!("\x1b]52;c;{}\x1b\\", encoded);
-                                    self.push_response(response.as_bytes());
+                                    self.push_reply(ReplyPriority::Bulk, response.as_bytes());
                                 } else {
                                     let response = b"\x1b]52;c;\x1b\\";
-                                    self.push_response(response);
+                                    self.push_reply(ReplyPriority::Bulk, response);
                                 }
                             }
                         } else if !data.is_empty() {
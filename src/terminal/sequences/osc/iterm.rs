@@ -20,6 +20,12 @@ impl Terminal {
                 self.handle_set_user_var(payload);
             } else if let Some(payload) = data.strip_prefix("RemoteHost=") {
                 self.handle_remote_host(payload);
+            } else if let Some(path) = data.strip_prefix("CurrentDir=") {
+                self.handle_current_dir(path);
+            } else if let Some(payload) = data.strip_prefix("ShellIntegrationVersion=") {
+                self.handle_shell_integration_version(payload);
+            } else if let Some(path) = data.strip_prefix("SetBackgroundImageFile=") {
+                self.handle_set_background_image_file(path);
             } else if let Some(payload) = data.strip_prefix("RequestUpload=") {
                 self.handle_request_upload(payload);
             } else {
@@ -119,6 +125,41 @@ impl Terminal {
         });
     }
 
+    /// OSC 1337 CurrentDir=<path> - working directory on the current host
+    pub(crate) fn handle_current_dir(&mut self, path: &str) {
+        if path.is_empty() {
+            return;
+        }
+        let host = &self.shell_state.host_context;
+        let (hostname, username) = (host.hostname.clone(), host.username.clone());
+        self.record_cwd_change(crate::terminal::event::CwdChange {
+            old_cwd: self
+                .shell_state
+                .shell_integration
+                .cwd()
+                .map(|s| s.to_string()),
+            new_cwd: path.to_string(),
+            hostname,
+            username,
+            timestamp: crate::terminal::unix_millis(),
+        });
+    }
+
+    /// OSC 1337 ShellIntegrationVersion=<version>[;shell=<name>]
+    pub(crate) fn handle_shell_integration_version(&mut self, payload: &str) {
+        let mut parts = payload.split(';');
+        let version = parts.next().unwrap_or_default().trim();
+        if !version.is_empty() {
+            self.set_environment_value("shell_integration_version", Some(version.to_string()));
+        }
+        if let Some(shell) = parts.find_map(|part| part.strip_prefix("shell=")) {
+            let shell = shell.trim();
+            if !shell.is_empty() {
+                self.set_environment_value("shell", Some(shell.to_string()));
+            }
+        }
+    }
+
     pub(crate) fn handle_request_upload(&mut self, payload: &str) {
         // payload is e.g. "format=tgz" — extract just the value
         let format = if let Some(val) = payload.strip_prefix("format=") {
@@ -126,8 +167,14 @@ impl Terminal {
         } else {
             payload.to_string()
         };
+        let provided = self.graphics.file_transfer_manager.provide_upload(&format);
         self.events
             .terminal_events
             .push(crate::terminal::TerminalEvent::UploadRequested { format });
+        match provided {
+            Some(Some(data)) => self.send_upload_data(&data),
+            Some(None) => self.cancel_upload(),
+            None => {}
+        }
     }
 }
@@ -8,7 +8,8 @@ mod shell;
 mod title;
 
 use crate::debug;
-use crate::terminal::Terminal;
+use crate::terminal::action::TerminalAction;
+use crate::terminal::{OscSequence, Sequence, SequenceAction, Terminal, UnsupportedKind};
 use std::num::NonZeroU32;
 
 impl Terminal {
@@ -25,13 +26,32 @@ impl Terminal {
     pub(in crate::terminal) fn osc_dispatch_impl(
         &mut self,
         params: &[&[u8]],
-        _bell_terminated: bool,
+        bell_terminated: bool,
     ) {
         debug::log_osc_dispatch(params);
+        if std::mem::take(&mut self.osc_cut_pending) {
+            debug::log(
+                debug::DebugLevel::Debug,
+                "OSC",
+                &format!(
+                    "OSC data exceeded {} bytes or was cancelled by the watchdog, sequence discarded",
+                    self.security_state.max_osc_data_length
+                ),
+            );
+            return;
+        }
         if params.is_empty() {
             return;
         }
 
+        let sequence = Sequence::Osc(OscSequence {
+            params,
+            bell_terminated,
+        });
+        if self.filter_sequence(&sequence) != SequenceAction::Allow {
+            return;
+        }
+
         // Reject excessively large OSC data to prevent memory exhaustion (QA-012).
         let max = self.security_state.max_osc_data_length;
         let total_len: usize = params.iter().map(|p| p.len()).sum();
@@ -61,7 +81,7 @@ impl Terminal {
             }
 
             match command {
-                "0" | "2" | "21" | "22" | "23" => self.handle_osc_title(command, params),
+                "0" | "1" | "2" | "21" | "22" | "23" => self.handle_osc_title(command, params),
                 "7" | "133" => self.handle_osc_shell(command, params),
                 "8" => self.handle_osc_hyperlink(params),
                 "9" | "777" | "934" => self.handle_osc_notify(command, params),
@@ -76,6 +96,14 @@ impl Terminal {
                         "OSC",
                         &format!("Unsupported OSC command: {}", command),
                     );
+                    self.record_unsupported(
+                        UnsupportedKind::Osc,
+                        format!("OSC {}", command),
+                        TerminalAction::OscDispatch {
+                            params: params.iter().map(|p| p.to_vec()).collect(),
+                            bell_terminated,
+                        },
+                    );
                 }
             }
         }
@@ -93,7 +121,9 @@ impl Terminal {
                         .hyperlink_state
                         .hyperlinks
                         .iter()
-                        .find(|(_, v)| v.as_str() == url)
+                        .find(|(k, v)| {
+                            v.as_str() == url && !self.autolink.synthetic_ids.contains(k)
+                        })
                         .map(|(k, _)| *k)
                         .unwrap_or_else(|| {
                             let id = self.hyperlink_state.next_hyperlink_id;
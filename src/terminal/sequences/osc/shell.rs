@@ -35,6 +35,8 @@ impl Terminal {
                 // Shell integration (iTerm2/VSCode)
                 if params.len() >= 2 => {
                     if let Ok(marker) = std::str::from_utf8(params[1]) {
+                        // Rows recorded below must not be shifted by older evictions
+                        self.sync_command_rows();
                         let ts = crate::terminal::unix_millis();
                         let abs_line = self.active_grid().scrollback_len() + self.cursor.row;
                         match marker.chars().next() {
@@ -96,6 +98,7 @@ impl Terminal {
                                             abs_row_start: abs_line,
                                         },
                                     );
+                                    self.add_prompt_mark(abs_line);
                                 }
                             }
                             Some('B') => {
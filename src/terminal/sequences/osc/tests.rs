@@ -740,6 +740,23 @@ fn test_evaluate_badge() {
     assert_eq!(result, Some("alice@server1".to_string()));
 }
 
+#[test]
+fn test_badge_interpolates_host_from_osc7_and_hides_blank() {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+
+    let mut term = Terminal::new(80, 24);
+    let encoded = STANDARD.encode(r"\(username)");
+    term.process(format!("\x1b]1337;SetBadgeFormat={}\x1b\\", encoded).as_bytes());
+    // No username yet: the badge evaluates to blank text
+    assert_eq!(term.evaluate_badge(), Some(String::new()));
+    assert_eq!(term.badge(), None);
+
+    let encoded = STANDARD.encode(r"\(hostname)");
+    term.process(format!("\x1b]1337;SetBadgeFormat={}\x1b\\", encoded).as_bytes());
+    term.process(b"\x1b]7;file://buildbox/srv\x1b\\");
+    assert_eq!(term.badge(), Some("buildbox".to_string()));
+}
+
 #[test]
 fn test_evaluate_badge_with_dimensions() {
     use base64::{engine::general_purpose::STANDARD, Engine};
@@ -1929,3 +1946,141 @@ fn test_environment_changed_hostname() {
         "Expected EnvironmentChanged event for hostname"
     );
 }
+
+#[test]
+fn test_host_context_from_remote_host() {
+    let mut term = Terminal::new(80, 24);
+    assert_eq!(
+        term.host_context(),
+        &crate::terminal::HostContext::default()
+    );
+
+    term.process(b"\x1b]1337;RemoteHost=alice@server1\x1b\\");
+    let ctx = term.host_context();
+    assert_eq!(ctx.hostname.as_deref(), Some("server1"));
+    assert_eq!(ctx.username.as_deref(), Some("alice"));
+    assert!(ctx.is_remote);
+
+    // Returning to localhost clears the remote flag
+    term.process(b"\x1b]1337;RemoteHost=alice@localhost\x1b\\");
+    assert!(!term.host_context().is_remote);
+    assert_eq!(term.host_context().hostname, None);
+}
+
+#[test]
+fn test_host_context_local_hostname_is_not_remote() {
+    let mut term = Terminal::new(80, 24);
+    term.set_local_hostname(Some("workstation".to_string()));
+
+    term.process(b"\x1b]7;file://user@Workstation/home/user\x1b\\");
+    let ctx = term.host_context();
+    assert_eq!(ctx.hostname.as_deref(), Some("Workstation"));
+    assert_eq!(ctx.username.as_deref(), Some("user"));
+    assert!(!ctx.is_remote);
+
+    term.process(b"\x1b]7;file://user@build-box/home/user\x1b\\");
+    assert!(term.host_context().is_remote);
+
+    // Re-classifies the current context when the local hostname changes
+    term.set_local_hostname(Some("build-box".to_string()));
+    assert!(!term.host_context().is_remote);
+}
+
+#[test]
+fn test_local_hostname_survives_ris() {
+    let mut term = Terminal::new(80, 24);
+    term.set_local_hostname(Some("workstation".to_string()));
+    term.process(b"\x1bc\x1b]7;file://workstation/home/user\x1b\\");
+    assert_eq!(term.local_hostname(), Some("workstation"));
+    assert!(!term.host_context().is_remote);
+}
+
+// === Shell Environment Tests ===
+
+#[test]
+fn test_shell_integration_version_sets_shell() {
+    let mut term = Terminal::new(80, 24);
+
+    term.process(b"\x1b]1337;ShellIntegrationVersion=15;shell=fish\x07");
+
+    let env = term.shell_environment();
+    assert_eq!(env.shell_integration_version.as_deref(), Some("15"));
+    assert_eq!(env.shell.as_deref(), Some("fish"));
+    assert!(term.poll_events().iter().any(|e| matches!(
+        e,
+        crate::terminal::TerminalEvent::EnvironmentChanged { key, value, .. }
+        if key == "shell" && value == "fish"
+    )));
+}
+
+#[test]
+fn test_current_dir_keeps_remote_host() {
+    let mut term = Terminal::new(80, 24);
+
+    term.process(b"\x1b]1337;RemoteHost=alice@server1\x07");
+    term.process(b"\x1b]1337;CurrentDir=/srv/app\x07");
+
+    let env = term.shell_environment();
+    assert_eq!(env.cwd.as_deref(), Some("/srv/app"));
+    assert_eq!(env.hostname.as_deref(), Some("server1"));
+    assert_eq!(env.username.as_deref(), Some("alice"));
+    assert!(env.is_remote);
+    assert_eq!(term.get_cwd_changes().last().unwrap().new_cwd, "/srv/app");
+}
+
+#[test]
+fn test_environment_history_records_changes_only() {
+    let mut term = Terminal::new(80, 24);
+
+    term.process(b"\x1b]7;file://server1/home/user\x07");
+    term.process(b"\x1b]7;file://server1/home/user\x07");
+    term.process(b"\x1b]7;file://server1/tmp\x07");
+
+    let keys: Vec<_> = term
+        .get_environment_history()
+        .iter()
+        .map(|c| (c.key.as_str(), c.value.as_deref()))
+        .collect();
+    assert_eq!(
+        keys,
+        [
+            ("cwd", Some("/home/user")),
+            ("hostname", Some("server1")),
+            ("cwd", Some("/tmp")),
+        ]
+    );
+
+    term.set_max_environment_history(1);
+    assert_eq!(term.get_environment_history().len(), 1);
+    assert_eq!(
+        term.get_environment_history()[0].old_value.as_deref(),
+        Some("/home/user")
+    );
+}
+
+#[test]
+fn test_virtualenv_user_vars_captured_by_commands() {
+    let mut term = Terminal::new(80, 24);
+    let encode = |v: &str| base64::engine::general_purpose::STANDARD.encode(v);
+
+    term.process(
+        format!(
+            "\x1b]1337;SetUserVar=VIRTUAL_ENV={}\x07\x1b]1337;SetUserVar=CONDA_PREFIX={}\x07",
+            encode("/home/u/.venv"),
+            encode("/opt/conda/envs/ml")
+        )
+        .as_bytes(),
+    );
+    term.process(b"\x1b]1337;ShellIntegrationVersion=15;shell=zsh\x07");
+    term.start_command_execution("pytest".to_string());
+    term.end_command_execution(Some(0));
+
+    // Clearing the variable clears the environment value
+    term.process(format!("\x1b]1337;SetUserVar=VIRTUAL_ENV={}\x07", encode("")).as_bytes());
+    assert!(term.shell_environment().virtual_env.is_none());
+
+    let cmd = &term.get_command_history()[0];
+    assert_eq!(cmd.virtual_env.as_deref(), Some("/home/u/.venv"));
+    assert_eq!(cmd.conda_prefix.as_deref(), Some("/opt/conda/envs/ml"));
+    assert_eq!(cmd.shell.as_deref(), Some("zsh"));
+}
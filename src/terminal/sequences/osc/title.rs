@@ -1,18 +1,17 @@
 //! Title-related OSC sequence handling
 
-use crate::terminal::Terminal;
+use crate::terminal::{Terminal, TitleKind};
 
 impl Terminal {
     pub(crate) fn handle_osc_title(&mut self, command: &str, params: &[&[u8]]) {
         match command {
-            "0" | "2" if params.len() >= 2 => {
-                if let Ok(title) = std::str::from_utf8(params[1]) {
-                    let new_title = title.to_string();
-                    if self.title_state.title != new_title {
-                        self.title_state.title = new_title.clone();
-                        self.events
-                            .terminal_events
-                            .push(crate::terminal::TerminalEvent::TitleChanged(new_title));
+            "0" | "1" | "2" if params.len() >= 2 => {
+                if let Ok(text) = std::str::from_utf8(params[1]) {
+                    if command != "1" {
+                        self.apply_title_change(TitleKind::Title, text.to_string());
+                    }
+                    if command != "2" {
+                        self.apply_title_change(TitleKind::IconName, text.to_string());
                     }
                 }
             }
@@ -29,7 +28,7 @@ impl Terminal {
             }
             "22" | "23" => {
                 if let Some(title) = self.title_state.title_stack.pop() {
-                    self.title_state.title = title;
+                    self.apply_title_change(TitleKind::Title, title);
                 }
             }
             _ => {}
@@ -23,6 +23,16 @@ pub struct CommandExecution {
     pub output_start_row: Option<usize>,
     /// Absolute end row of the output zone
     pub output_end_row: Option<usize>,
+    /// Hostname the command ran on (None for localhost)
+    pub hostname: Option<String>,
+    /// Username the command ran as (if known)
+    pub username: Option<String>,
+    /// Shell reported by OSC 1337 ShellIntegrationVersion (if known)
+    pub shell: Option<String>,
+    /// Active Python virtualenv (`VIRTUAL_ENV` user variable), if reported
+    pub virtual_env: Option<String>,
+    /// Active conda environment (`CONDA_PREFIX` user variable), if reported
+    pub conda_prefix: Option<String>,
 }
 
 /// Command output record combining execution metadata with extracted output text
@@ -53,6 +63,104 @@ pub struct ShellIntegrationStats {
     pub total_duration_ms: u64,
 }
 
+/// Host/user context derived from OSC 7 URLs and OSC 1337 RemoteHost
+///
+/// Frontends use this to switch theming or badges per host, e.g. when an SSH
+/// session reports a different hostname than the local machine.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HostContext {
+    /// Reported hostname (None when the shell reported localhost or nothing)
+    pub hostname: Option<String>,
+    /// Reported username (if provided)
+    pub username: Option<String>,
+    /// Whether the hostname refers to a machine other than the local one
+    pub is_remote: bool,
+}
+
+impl HostContext {
+    /// Build a context from a reported hostname/username pair.
+    ///
+    /// A hostname matching `local_hostname` (case-insensitive) is considered
+    /// local; any other non-empty hostname is considered remote.
+    pub fn new(
+        hostname: Option<String>,
+        username: Option<String>,
+        local_hostname: Option<&str>,
+    ) -> Self {
+        let is_remote = match (&hostname, local_hostname) {
+            (Some(h), Some(local)) => !h.eq_ignore_ascii_case(local),
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+        Self {
+            hostname,
+            username,
+            is_remote,
+        }
+    }
+}
+
+/// Session environment reported by the shell
+///
+/// Combines OSC 7 and OSC 1337 `CurrentDir` (working directory),
+/// `RemoteHost` (host and user), `ShellIntegrationVersion` (shell and
+/// integration version), and the `VIRTUAL_ENV` / `CONDA_PREFIX` user
+/// variables set with OSC 1337 `SetUserVar`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShellEnvironment {
+    /// Current working directory
+    pub cwd: Option<String>,
+    /// Reported hostname (None for localhost)
+    pub hostname: Option<String>,
+    /// Reported username
+    pub username: Option<String>,
+    /// Whether the hostname refers to another machine (see [`HostContext`])
+    pub is_remote: bool,
+    /// Shell name, e.g. `zsh`
+    pub shell: Option<String>,
+    /// Shell integration script version
+    pub shell_integration_version: Option<String>,
+    /// Active Python virtualenv
+    pub virtual_env: Option<String>,
+    /// Active conda environment prefix
+    pub conda_prefix: Option<String>,
+}
+
+impl ShellEnvironment {
+    fn field_mut(&mut self, key: &str) -> Option<&mut Option<String>> {
+        Some(match key {
+            "cwd" => &mut self.cwd,
+            "hostname" => &mut self.hostname,
+            "username" => &mut self.username,
+            "shell" => &mut self.shell,
+            "shell_integration_version" => &mut self.shell_integration_version,
+            "virtual_env" => &mut self.virtual_env,
+            "conda_prefix" => &mut self.conda_prefix,
+            _ => return None,
+        })
+    }
+}
+
+/// A change to one [`ShellEnvironment`] value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvironmentChange {
+    /// Field that changed: `cwd`, `hostname`, `username`, `shell`,
+    /// `shell_integration_version`, `virtual_env` or `conda_prefix`
+    pub key: String,
+    /// New value (None when cleared)
+    pub value: Option<String>,
+    /// Previous value
+    pub old_value: Option<String>,
+    /// When the change was reported (milliseconds since epoch)
+    pub timestamp: u64,
+}
+
+/// User variables that set [`ShellEnvironment`] values
+const ENVIRONMENT_USER_VARS: [(&str, &str); 2] = [
+    ("VIRTUAL_ENV", "virtual_env"),
+    ("CONDA_PREFIX", "conda_prefix"),
+];
+
 use crate::terminal::Terminal;
 
 impl Terminal {
@@ -74,28 +182,105 @@ impl Terminal {
             success: None,
             output_start_row: None, // Will be set when Output zone opens
             output_end_row: None,
+            hostname: self.shell_state.host_context.hostname.clone(),
+            username: self.shell_state.host_context.username.clone(),
+            shell: self.shell_state.environment.shell.clone(),
+            virtual_env: self.shell_state.environment.virtual_env.clone(),
+            conda_prefix: self.shell_state.environment.conda_prefix.clone(),
         };
         self.command_history_state.current_command = Some(execution);
     }
 
+    /// Shift command output rows for lines that left the primary grid.
+    ///
+    /// Output rows use the same numbering as zones, so they move up when
+    /// scrollback lines are evicted. Output whose first row is gone loses its
+    /// rows, and [`Terminal::get_command_output`] then returns `None` for it.
+    pub(crate) fn sync_command_rows(&mut self) {
+        // Alt screen removals never touch command rows
+        self.alt_grid.drain_row_removals();
+        let removals = self.grid.drain_row_removals();
+        if removals.is_empty() {
+            return;
+        }
+        if let crate::terminal::prompt_detection::PromptPhase::AtPrompt { abs_row: row, .. } =
+            &mut self.prompt_detection.phase
+        {
+            for &(abs_row, count) in &removals {
+                if *row >= abs_row {
+                    *row = row.saturating_sub(count).max(abs_row);
+                }
+            }
+        }
+        self.shift_marks(&removals);
+        self.shift_secret_scan(&removals);
+        self.shift_viewport(&removals);
+
+        let state = &mut self.command_history_state;
+        let executions = state
+            .command_history
+            .iter_mut()
+            .chain(state.current_command.as_mut());
+        for execution in executions {
+            for &(abs_row, count) in &removals {
+                let Some(start) = execution.output_start_row else {
+                    break;
+                };
+                if start >= abs_row && start < abs_row + count {
+                    execution.output_start_row = None;
+                    execution.output_end_row = None;
+                    break;
+                }
+                if start >= abs_row + count {
+                    execution.output_start_row = Some(start - count);
+                }
+                execution.output_end_row = execution.output_end_row.map(|end| match end {
+                    e if e >= abs_row + count => e - count,
+                    e if e >= abs_row => abs_row.saturating_sub(1),
+                    e => e,
+                });
+            }
+        }
+    }
+
     /// End tracking the current command execution
     pub fn end_command_execution(&mut self, exit_code: Option<i32>) {
+        self.sync_command_rows();
         if let Some(mut execution) = self.command_history_state.current_command.take() {
             let now = crate::terminal::unix_millis();
             execution.end_time = Some(now);
             execution.duration_ms = Some(now - execution.start_time);
             execution.exit_code = exit_code;
-            execution.success = exit_code.map(|c| c == 0);
+            // A command already marked failed (e.g. by a trigger) stays failed
+            if execution.success != Some(false) {
+                execution.success = exit_code.map(|c| c == 0);
+            }
 
             // Only set end row if we had a start row (i.e. an Output zone was opened)
             if execution.output_start_row.is_some() {
-                execution.output_end_row = Some(self.grid.total_lines_scrolled() + self.cursor.row);
+                execution.output_end_row = Some(self.grid.scrollback_len() + self.cursor.row);
             }
 
             self.record_command(execution);
         }
     }
 
+    /// Mark the running command as failed, or the most recent finished one
+    /// when nothing is running.
+    ///
+    /// The command keeps its exit code; only `success` is forced to `false`
+    /// (it stays `false` when a running command later exits with 0).
+    pub fn mark_command_failed(&mut self) {
+        let history = &mut self.command_history_state;
+        if let Some(execution) = history
+            .current_command
+            .as_mut()
+            .or(history.command_history.last_mut())
+        {
+            execution.success = Some(false);
+        }
+    }
+
     /// Record a command execution in history
     pub fn record_command(&mut self, execution: CommandExecution) {
         self.command_history_state.command_history.push(execution);
@@ -158,8 +343,8 @@ impl Terminal {
 
     /// Record a CWD change
     pub fn record_cwd_change(&mut self, change: crate::terminal::CwdChange) {
-        let old_hostname = self.shell_state.last_hostname.clone();
-        let old_username = self.shell_state.last_username.clone();
+        let old_hostname = self.shell_state.host_context.hostname.clone();
+        let old_username = self.shell_state.host_context.username.clone();
         let old_cwd = self
             .shell_state
             .shell_integration
@@ -167,8 +352,15 @@ impl Terminal {
             .map(|s| s.to_string());
 
         // Update current state
-        self.shell_state.last_hostname = change.hostname.clone();
-        self.shell_state.last_username = change.username.clone();
+        self.shell_state.host_context = HostContext::new(
+            change.hostname.clone(),
+            change.username.clone(),
+            self.shell_state.local_hostname.as_deref(),
+        );
+        self.shell_state.environment.is_remote = self.shell_state.host_context.is_remote;
+        self.record_environment_value("cwd", Some(change.new_cwd.clone()), change.timestamp);
+        self.record_environment_value("hostname", change.hostname.clone(), change.timestamp);
+        self.record_environment_value("username", change.username.clone(), change.timestamp);
         self.shell_state
             .shell_integration
             .set_cwd(change.new_cwd.clone());
@@ -250,6 +442,101 @@ impl Terminal {
         }
     }
 
+    /// Get the current host/user context
+    pub fn host_context(&self) -> &HostContext {
+        &self.shell_state.host_context
+    }
+
+    /// Get the configured local hostname
+    pub fn local_hostname(&self) -> Option<&str> {
+        self.shell_state.local_hostname.as_deref()
+    }
+
+    /// Set the hostname of the local machine
+    ///
+    /// Shells commonly report the local hostname in OSC 7 URLs; setting it
+    /// here lets the terminal classify such reports as local rather than
+    /// remote. The current host context is re-evaluated immediately.
+    pub fn set_local_hostname(&mut self, hostname: Option<String>) {
+        self.shell_state.local_hostname = hostname.filter(|h| !h.is_empty());
+        let ctx = &self.shell_state.host_context;
+        self.shell_state.host_context = HostContext::new(
+            ctx.hostname.clone(),
+            ctx.username.clone(),
+            self.shell_state.local_hostname.as_deref(),
+        );
+        self.shell_state.environment.is_remote = self.shell_state.host_context.is_remote;
+    }
+
+    /// Get the session environment reported by the shell
+    pub fn shell_environment(&self) -> &ShellEnvironment {
+        &self.shell_state.environment
+    }
+
+    /// Get the history of shell environment changes, oldest first
+    pub fn get_environment_history(&self) -> &[EnvironmentChange] {
+        &self.command_history_state.environment_changes
+    }
+
+    /// Set the maximum number of environment changes to retain
+    pub fn set_max_environment_history(&mut self, max: usize) {
+        self.command_history_state.max_environment_history = max;
+        let history = &mut self.command_history_state.environment_changes;
+        if history.len() > max {
+            history.drain(0..history.len() - max);
+        }
+    }
+
+    /// Store an environment value, recording it in the history if it
+    /// changed; returns the previous value when it did
+    fn record_environment_value(
+        &mut self,
+        key: &str,
+        value: Option<String>,
+        timestamp: u64,
+    ) -> Option<Option<String>> {
+        let field = self.shell_state.environment.field_mut(key)?;
+        if *field == value {
+            return None;
+        }
+        let old_value = std::mem::replace(field, value.clone());
+        let state = &mut self.command_history_state;
+        state.environment_changes.push(EnvironmentChange {
+            key: key.to_string(),
+            value,
+            old_value: old_value.clone(),
+            timestamp,
+        });
+        if state.environment_changes.len() > state.max_environment_history {
+            state.environment_changes.remove(0);
+        }
+        Some(old_value)
+    }
+
+    /// Update an environment value outside the CWD/host path, emitting
+    /// `EnvironmentChanged` when it changes
+    pub(crate) fn set_environment_value(&mut self, key: &str, value: Option<String>) {
+        let timestamp = crate::terminal::unix_millis();
+        if let Some(old_value) = self.record_environment_value(key, value.clone(), timestamp) {
+            self.events
+                .terminal_events
+                .push(crate::terminal::TerminalEvent::EnvironmentChanged {
+                    key: key.to_string(),
+                    value: value.unwrap_or_default(),
+                    old_value,
+                });
+        }
+    }
+
+    /// Mirror `VIRTUAL_ENV` / `CONDA_PREFIX` user variables into the
+    /// environment; an empty value clears it
+    pub(crate) fn sync_environment_user_var(&mut self, name: &str, value: &str) {
+        if let Some((_, key)) = ENVIRONMENT_USER_VARS.iter().find(|(var, _)| *var == name) {
+            let value = (!value.is_empty()).then(|| value.to_string());
+            self.set_environment_value(key, value);
+        }
+    }
+
     /// Get CWD change history
     pub fn get_cwd_history(&self) -> &[crate::terminal::event::CwdChange] {
         &self.command_history_state.cwd_changes
@@ -309,15 +596,6 @@ impl Terminal {
             return None; // Invalid range
         }
 
-        // Check if output has been evicted from scrollback
-        let floor = self
-            .grid
-            .total_lines_scrolled()
-            .saturating_sub(self.grid.max_scrollback());
-        if start < floor {
-            return None; // Output partially or fully evicted
-        }
-
         let output = self
             .extract_text_from_row_range(start, end)
             .unwrap_or_default();
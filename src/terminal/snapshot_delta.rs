@@ -0,0 +1,1280 @@
+//! Compact binary deltas between terminal snapshots for remote mirroring
+//!
+//! A [`SnapshotDelta`] describes how to turn one [`TerminalSnapshot`] into
+//! another: the runs of cells that changed on the primary and alternate
+//! screens, the lines appended to scrollback and any cursor or mode changes.
+//! Deltas encode to a few bytes per changed cell, so a terminal can be
+//! mirrored over a slow link (SSH, serial, a mosh-style UDP channel) by
+//! sending [`Terminal::snapshot_delta`] output to a peer that feeds it to
+//! [`Terminal::apply_snapshot_delta`].
+//!
+//! Every delta records a fingerprint of the state it was computed against,
+//! and applying it to a different state fails with
+//! [`DeltaError::BaseMismatch`]; the sender then calls
+//! [`Terminal::reset_snapshot_delta`] so its next delta is a keyframe that
+//! applies to any terminal. When lines scroll into scrollback the receiver
+//! shifts its screen up before applying cell runs, so scrolling output costs
+//! only the new lines.
+//!
+//! Scrollback contents are assumed to change only by appending; any other
+//! change (clear, reflow on a width change, a lower line count than expected)
+//! resends the whole scrollback. Zones, annotations, row timestamps and
+//! hyperlink URLs are not mirrored; cells keep their hyperlink IDs.
+//!
+//! # Wire format (version 1)
+//!
+//! Integers are unsigned LEB128 varints unless noted.
+//!
+//! ```text
+//! "PTD" version:u8 flags:u8 [base fingerprint: u64 LE, omitted in keyframes]
+//! cols rows
+//! sections...  0x00 (end)
+//!
+//! 0x01 cell runs:   count { screen:u8 row col len cell* }
+//! 0x02 wrap flags:  count { screen:u8 row wrapped:u8 }
+//! 0x03 scrollback:  reset:u8 max_scrollback total_lines_scrolled shift
+//!                   count { wrapped:u8 width len cell* }
+//! 0x04 cursors:     cursor alt_cursor has_saved:u8 [saved_cursor] pending_wrap:u8
+//! 0x05 modes:       current and saved SGR state, title, mode bits, mouse,
+//!                   margins, scroll region, keyboard state, tab stops
+//! ```
+//!
+//! Cells start with a header byte (0x01 same style as the previous cell of
+//! the run, 0x02 has combining marks, 0x04 width other than 1), followed by
+//! the character, the marks, the style and the width as flagged. Scrollback
+//! lines omit trailing blank cells.
+
+use std::fmt;
+use std::num::NonZeroU32;
+
+use crate::cell::{Cell, CellFlags, UnderlineStyle};
+use crate::color::{Color, NamedColor};
+use crate::cursor::{Cursor, CursorStyle};
+use crate::grid::ScrollbackRow;
+use crate::mouse::{MouseEncoding, MouseMode};
+use crate::terminal::replay_snapshot::{GridSnapshot, TerminalSnapshot};
+use crate::terminal::Terminal;
+
+const MAGIC: &[u8; 3] = b"PTD";
+const VERSION: u8 = 1;
+
+const FLAG_KEYFRAME: u8 = 0x01;
+const FLAG_RESET_SCREENS: u8 = 0x02;
+
+const SECTION_END: u8 = 0x00;
+const SECTION_RUNS: u8 = 0x01;
+const SECTION_WRAPS: u8 = 0x02;
+const SECTION_SCROLLBACK: u8 = 0x03;
+const SECTION_CURSORS: u8 = 0x04;
+const SECTION_MODES: u8 = 0x05;
+
+const CELL_SAME_STYLE: u8 = 0x01;
+const CELL_COMBINING: u8 = 0x02;
+const CELL_WIDTH: u8 = 0x04;
+
+/// Widest scrollback line accepted when decoding
+const MAX_LINE_WIDTH: usize = 65_535;
+
+/// Largest screen (cols * rows) accepted when decoding
+const MAX_SCREEN_CELLS: usize = 1 << 22;
+
+/// Unchanged cells merged into a run rather than starting a new one
+const MAX_RUN_GAP: usize = 4;
+
+/// Why a snapshot delta could not be decoded or applied
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeltaError {
+    /// The data is not a snapshot delta, or is truncated or corrupt
+    Malformed(String),
+    /// The delta was written by an unsupported format version
+    UnsupportedVersion(u8),
+    /// The terminal is not in the state the delta was computed against
+    BaseMismatch,
+}
+
+impl fmt::Display for DeltaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeltaError::Malformed(msg) => write!(f, "Malformed snapshot delta: {}", msg),
+            DeltaError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported snapshot delta version: {}", version)
+            }
+            DeltaError::BaseMismatch => {
+                write!(f, "Snapshot delta does not apply to the current state")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DeltaError {}
+
+fn malformed(msg: &str) -> DeltaError {
+    DeltaError::Malformed(msg.to_string())
+}
+
+/// Changed cells on one screen row
+#[derive(Debug, Clone, PartialEq)]
+struct CellRun {
+    alt: bool,
+    row: usize,
+    col: usize,
+    cells: Vec<Cell>,
+}
+
+/// Changed line-wrap flag of a screen row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct WrapChange {
+    alt: bool,
+    row: usize,
+    wrapped: bool,
+}
+
+/// Lines appended to the primary screen's scrollback
+#[derive(Debug, Clone, PartialEq)]
+struct ScrollbackDelta {
+    /// Drop the receiver's scrollback before appending
+    reset: bool,
+    max_scrollback: usize,
+    total_lines_scrolled: usize,
+    /// Rows the primary screen scrolls up before cell runs are applied
+    shift: usize,
+    lines: Vec<(Vec<Cell>, bool)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CursorState {
+    cursor: Cursor,
+    alt_cursor: Cursor,
+    saved_cursor: Option<Cursor>,
+    pending_wrap: bool,
+}
+
+impl CursorState {
+    fn from_snapshot(snap: &TerminalSnapshot) -> Self {
+        Self {
+            cursor: snap.cursor,
+            alt_cursor: snap.alt_cursor,
+            saved_cursor: snap.saved_cursor,
+            pending_wrap: snap.pending_wrap,
+        }
+    }
+
+    fn apply(&self, snap: &mut TerminalSnapshot) {
+        snap.cursor = self.cursor;
+        snap.alt_cursor = self.alt_cursor;
+        snap.saved_cursor = self.saved_cursor;
+        snap.pending_wrap = self.pending_wrap;
+    }
+}
+
+/// Everything in a snapshot outside the grids and cursors
+#[derive(Debug, Clone, PartialEq)]
+struct ModeState {
+    alt_screen_active: bool,
+    fg: Color,
+    bg: Color,
+    underline_color: Option<Color>,
+    flags: CellFlags,
+    saved_fg: Color,
+    saved_bg: Color,
+    saved_underline_color: Option<Color>,
+    saved_flags: CellFlags,
+    title: String,
+    auto_wrap: bool,
+    origin_mode: bool,
+    insert_mode: bool,
+    reverse_video: bool,
+    line_feed_new_line_mode: bool,
+    application_cursor: bool,
+    bracketed_paste: bool,
+    focus_tracking: bool,
+    use_lr_margins: bool,
+    char_protected: bool,
+    bold_brightening: bool,
+    mouse_mode: MouseMode,
+    mouse_encoding: MouseEncoding,
+    left_margin: usize,
+    right_margin: usize,
+    scroll_region_top: usize,
+    scroll_region_bottom: usize,
+    keyboard_flags: u16,
+    modify_other_keys_mode: u8,
+    tab_stops: Vec<bool>,
+}
+
+impl ModeState {
+    fn from_snapshot(snap: &TerminalSnapshot) -> Self {
+        Self {
+            alt_screen_active: snap.alt_screen_active,
+            fg: snap.fg,
+            bg: snap.bg,
+            underline_color: snap.underline_color,
+            flags: snap.flags,
+            saved_fg: snap.saved_fg,
+            saved_bg: snap.saved_bg,
+            saved_underline_color: snap.saved_underline_color,
+            saved_flags: snap.saved_flags,
+            title: snap.title.clone(),
+            auto_wrap: snap.auto_wrap,
+            origin_mode: snap.origin_mode,
+            insert_mode: snap.insert_mode,
+            reverse_video: snap.reverse_video,
+            line_feed_new_line_mode: snap.line_feed_new_line_mode,
+            application_cursor: snap.application_cursor,
+            bracketed_paste: snap.bracketed_paste,
+            focus_tracking: snap.focus_tracking,
+            use_lr_margins: snap.use_lr_margins,
+            char_protected: snap.char_protected,
+            bold_brightening: snap.bold_brightening,
+            mouse_mode: snap.mouse_mode,
+            mouse_encoding: snap.mouse_encoding,
+            left_margin: snap.left_margin,
+            right_margin: snap.right_margin,
+            scroll_region_top: snap.scroll_region_top,
+            scroll_region_bottom: snap.scroll_region_bottom,
+            keyboard_flags: snap.keyboard_flags,
+            modify_other_keys_mode: snap.modify_other_keys_mode,
+            tab_stops: snap.tab_stops.clone(),
+        }
+    }
+
+    fn apply(&self, snap: &mut TerminalSnapshot) {
+        snap.alt_screen_active = self.alt_screen_active;
+        snap.fg = self.fg;
+        snap.bg = self.bg;
+        snap.underline_color = self.underline_color;
+        snap.flags = self.flags;
+        snap.saved_fg = self.saved_fg;
+        snap.saved_bg = self.saved_bg;
+        snap.saved_underline_color = self.saved_underline_color;
+        snap.saved_flags = self.saved_flags;
+        snap.title = self.title.clone();
+        snap.auto_wrap = self.auto_wrap;
+        snap.origin_mode = self.origin_mode;
+        snap.insert_mode = self.insert_mode;
+        snap.reverse_video = self.reverse_video;
+        snap.line_feed_new_line_mode = self.line_feed_new_line_mode;
+        snap.application_cursor = self.application_cursor;
+        snap.bracketed_paste = self.bracketed_paste;
+        snap.focus_tracking = self.focus_tracking;
+        snap.use_lr_margins = self.use_lr_margins;
+        snap.char_protected = self.char_protected;
+        snap.bold_brightening = self.bold_brightening;
+        snap.mouse_mode = self.mouse_mode;
+        snap.mouse_encoding = self.mouse_encoding;
+        snap.left_margin = self.left_margin;
+        snap.right_margin = self.right_margin;
+        snap.scroll_region_top = self.scroll_region_top;
+        snap.scroll_region_bottom = self.scroll_region_bottom;
+        snap.keyboard_flags = self.keyboard_flags;
+        snap.modify_other_keys_mode = self.modify_other_keys_mode;
+        snap.tab_stops = self.tab_stops.clone();
+    }
+
+    fn mode_bits(&self) -> u16 {
+        [
+            self.alt_screen_active,
+            self.auto_wrap,
+            self.origin_mode,
+            self.insert_mode,
+            self.reverse_video,
+            self.line_feed_new_line_mode,
+            self.application_cursor,
+            self.bracketed_paste,
+            self.focus_tracking,
+            self.use_lr_margins,
+            self.char_protected,
+            self.bold_brightening,
+        ]
+        .iter()
+        .enumerate()
+        .fold(0, |bits, (i, &set)| bits | (u16::from(set) << i))
+    }
+}
+
+/// Changes between two terminal snapshots
+///
+/// Build one with [`SnapshotDelta::between`], send [`SnapshotDelta::encode`]
+/// output, and apply it on the other side with
+/// [`TerminalSnapshot::apply_delta`] or [`Terminal::apply_snapshot_delta`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotDelta {
+    keyframe: bool,
+    reset_screens: bool,
+    base_fingerprint: u64,
+    cols: usize,
+    rows: usize,
+    runs: Vec<CellRun>,
+    wraps: Vec<WrapChange>,
+    scrollback: Option<ScrollbackDelta>,
+    cursors: Option<CursorState>,
+    modes: Option<ModeState>,
+}
+
+impl SnapshotDelta {
+    /// Compute the changes from `base` to `current`
+    ///
+    /// With no base the delta is a keyframe: it describes `current` in full
+    /// and applies to any state.
+    pub fn between(base: Option<&TerminalSnapshot>, current: &TerminalSnapshot) -> Self {
+        let cols = current.cols;
+        let rows = current.rows;
+        let reset_screens = base.is_none_or(|base| base.cols != cols || base.rows != rows);
+
+        let scrollback = scrollback_delta(base, current, reset_screens);
+        let shift = scrollback.as_ref().map_or(0, |sb| sb.shift);
+
+        let mut runs = Vec::new();
+        let mut wraps = Vec::new();
+        for (alt, grid, base_grid) in [
+            (false, &current.grid, base.map(|b| &b.grid)),
+            (true, &current.alt_grid, base.map(|b| &b.alt_grid)),
+        ] {
+            let (ref_cells, ref_wrapped) = if reset_screens {
+                (vec![Cell::default(); cols * rows], vec![false; rows])
+            } else {
+                let base_grid = base_grid.expect("base exists unless screens reset");
+                let mut cells = base_grid.cells.clone();
+                let mut wrapped = base_grid.wrapped.clone();
+                if !alt {
+                    shift_screen(&mut cells, &mut wrapped, cols, shift);
+                }
+                (cells, wrapped)
+            };
+            diff_screen(alt, grid, &ref_cells, &mut runs);
+            for row in 0..rows {
+                let wrapped = grid.wrapped.get(row).copied().unwrap_or(false);
+                if ref_wrapped.get(row).copied().unwrap_or(false) != wrapped {
+                    wraps.push(WrapChange { alt, row, wrapped });
+                }
+            }
+        }
+
+        let cursors = CursorState::from_snapshot(current);
+        let cursors = base
+            .is_none_or(|base| CursorState::from_snapshot(base) != cursors)
+            .then_some(cursors);
+        let modes = ModeState::from_snapshot(current);
+        let modes = base
+            .is_none_or(|base| ModeState::from_snapshot(base) != modes)
+            .then_some(modes);
+
+        Self {
+            keyframe: base.is_none(),
+            reset_screens,
+            base_fingerprint: base.map_or(0, fingerprint),
+            cols,
+            rows,
+            runs,
+            wraps,
+            scrollback,
+            cursors,
+            modes,
+        }
+    }
+
+    /// Whether the delta describes a complete state rather than changes
+    pub fn is_keyframe(&self) -> bool {
+        self.keyframe
+    }
+
+    /// Whether applying the delta changes nothing
+    pub fn is_empty(&self) -> bool {
+        !self.keyframe
+            && !self.reset_screens
+            && self.runs.is_empty()
+            && self.wraps.is_empty()
+            && self.scrollback.is_none()
+            && self.cursors.is_none()
+            && self.modes.is_none()
+    }
+
+    /// Terminal size (cols, rows) after the delta is applied
+    pub fn size(&self) -> (usize, usize) {
+        (self.cols, self.rows)
+    }
+
+    /// Number of screen cells the delta writes
+    pub fn changed_cells(&self) -> usize {
+        self.runs.iter().map(|run| run.cells.len()).sum()
+    }
+
+    /// Number of lines the delta appends to scrollback
+    pub fn scrollback_lines(&self) -> usize {
+        self.scrollback.as_ref().map_or(0, |sb| sb.lines.len())
+    }
+
+    /// Whether the delta changes terminal modes, colors, margins or the title
+    pub fn has_mode_changes(&self) -> bool {
+        self.modes.is_some()
+    }
+
+    /// Encode the delta in the binary wire format
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(64 + self.changed_cells() * 3);
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        let mut flags = 0;
+        if self.keyframe {
+            flags |= FLAG_KEYFRAME;
+        }
+        if self.reset_screens {
+            flags |= FLAG_RESET_SCREENS;
+        }
+        out.push(flags);
+        if !self.keyframe {
+            out.extend_from_slice(&self.base_fingerprint.to_le_bytes());
+        }
+        put_uint(&mut out, self.cols);
+        put_uint(&mut out, self.rows);
+
+        if !self.runs.is_empty() {
+            out.push(SECTION_RUNS);
+            put_uint(&mut out, self.runs.len());
+            for run in &self.runs {
+                out.push(u8::from(run.alt));
+                put_uint(&mut out, run.row);
+                put_uint(&mut out, run.col);
+                put_cells(&mut out, &run.cells);
+            }
+        }
+        if !self.wraps.is_empty() {
+            out.push(SECTION_WRAPS);
+            put_uint(&mut out, self.wraps.len());
+            for wrap in &self.wraps {
+                out.push(u8::from(wrap.alt));
+                put_uint(&mut out, wrap.row);
+                out.push(u8::from(wrap.wrapped));
+            }
+        }
+        if let Some(sb) = &self.scrollback {
+            out.push(SECTION_SCROLLBACK);
+            out.push(u8::from(sb.reset));
+            put_uint(&mut out, sb.max_scrollback);
+            put_uint(&mut out, sb.total_lines_scrolled);
+            put_uint(&mut out, sb.shift);
+            put_uint(&mut out, sb.lines.len());
+            for (cells, wrapped) in &sb.lines {
+                out.push(u8::from(*wrapped));
+                put_uint(&mut out, cells.len());
+                let blank = Cell::default();
+                let used = cells
+                    .iter()
+                    .rposition(|cell| *cell != blank)
+                    .map_or(0, |i| i + 1);
+                put_cells(&mut out, &cells[..used]);
+            }
+        }
+        if let Some(cursors) = &self.cursors {
+            out.push(SECTION_CURSORS);
+            put_cursors(&mut out, cursors);
+        }
+        if let Some(modes) = &self.modes {
+            out.push(SECTION_MODES);
+            put_modes(&mut out, modes);
+        }
+        out.push(SECTION_END);
+        out
+    }
+
+    /// Decode a delta written by [`SnapshotDelta::encode`]
+    pub fn decode(data: &[u8]) -> Result<Self, DeltaError> {
+        let mut reader = Reader(data);
+        if reader.bytes::<3>()? != *MAGIC {
+            return Err(malformed("missing PTD header"));
+        }
+        let version = reader.u8()?;
+        if version != VERSION {
+            return Err(DeltaError::UnsupportedVersion(version));
+        }
+        let flags = reader.u8()?;
+        let keyframe = flags & FLAG_KEYFRAME != 0;
+        let base_fingerprint = if keyframe {
+            0
+        } else {
+            u64::from_le_bytes(reader.bytes()?)
+        };
+        let mut delta = Self {
+            keyframe,
+            reset_screens: keyframe || flags & FLAG_RESET_SCREENS != 0,
+            base_fingerprint,
+            cols: reader.uint()?,
+            rows: reader.uint()?,
+            runs: Vec::new(),
+            wraps: Vec::new(),
+            scrollback: None,
+            cursors: None,
+            modes: None,
+        };
+
+        let (cols, rows) = (delta.cols, delta.rows);
+        if cols == 0 || rows == 0 || cols.saturating_mul(rows) > MAX_SCREEN_CELLS {
+            return Err(DeltaError::Malformed(format!("invalid size {cols}x{rows}")));
+        }
+
+        loop {
+            match reader.u8()? {
+                SECTION_END => break,
+                SECTION_RUNS => {
+                    for _ in 0..reader.count()? {
+                        let alt = reader.bool()?;
+                        let row = reader.uint()?;
+                        let col = reader.uint()?;
+                        if row >= rows || col >= cols {
+                            return Err(malformed("cell run outside the screen"));
+                        }
+                        let cells = reader.cells()?;
+                        delta.runs.push(CellRun {
+                            alt,
+                            row,
+                            col,
+                            cells,
+                        });
+                    }
+                }
+                SECTION_WRAPS => {
+                    for _ in 0..reader.count()? {
+                        delta.wraps.push(WrapChange {
+                            alt: reader.bool()?,
+                            row: reader.uint()?,
+                            wrapped: reader.bool()?,
+                        });
+                    }
+                }
+                SECTION_SCROLLBACK => {
+                    let reset = reader.bool()?;
+                    let max_scrollback = reader.uint()?;
+                    let total_lines_scrolled = reader.uint()?;
+                    let shift = reader.uint()?;
+                    let mut lines = Vec::new();
+                    for _ in 0..reader.count()? {
+                        let wrapped = reader.bool()?;
+                        let width = reader.uint()?;
+                        let mut cells = reader.cells()?;
+                        if cells.len() > width || width > MAX_LINE_WIDTH {
+                            return Err(malformed("invalid scrollback line width"));
+                        }
+                        cells.resize(width, Cell::default());
+                        lines.push((cells, wrapped));
+                    }
+                    delta.scrollback = Some(ScrollbackDelta {
+                        reset,
+                        max_scrollback,
+                        total_lines_scrolled,
+                        shift,
+                        lines,
+                    });
+                }
+                SECTION_CURSORS => delta.cursors = Some(reader.cursors()?),
+                SECTION_MODES => delta.modes = Some(reader.modes()?),
+                tag => return Err(DeltaError::Malformed(format!("unknown section {tag}"))),
+            }
+        }
+        if !reader.0.is_empty() {
+            return Err(malformed("trailing data"));
+        }
+        Ok(delta)
+    }
+}
+
+/// Scrollback changes from `base` to `current`, or `None` if there are none
+fn scrollback_delta(
+    base: Option<&TerminalSnapshot>,
+    current: &TerminalSnapshot,
+    reset_screens: bool,
+) -> Option<ScrollbackDelta> {
+    let grid = &current.grid;
+    let appended = base.and_then(|base| {
+        let old = &base.grid;
+        let appended = grid
+            .total_lines_scrolled
+            .checked_sub(old.total_lines_scrolled)?;
+        let expected = (old.scrollback_lines + appended).min(grid.max_scrollback);
+        (old.cols == grid.cols
+            && old.max_scrollback == grid.max_scrollback
+            && grid.scrollback_lines == expected)
+            .then_some(appended)
+    });
+
+    let (reset, count, shift) = match appended {
+        Some(0) => return None,
+        Some(appended) => {
+            let shift = if reset_screens {
+                0
+            } else {
+                appended.min(grid.rows)
+            };
+            (false, appended.min(grid.scrollback_lines), shift)
+        }
+        None => (true, grid.scrollback_lines, 0),
+    };
+
+    let lines = (grid.scrollback_lines - count..grid.scrollback_lines)
+        .map(|logical| {
+            let (row, wrapped) = scrollback_row(grid, logical);
+            (row.to_vec(), wrapped)
+        })
+        .collect();
+    Some(ScrollbackDelta {
+        reset,
+        max_scrollback: grid.max_scrollback,
+        total_lines_scrolled: grid.total_lines_scrolled,
+        shift,
+        lines,
+    })
+}
+
+/// Scrollback line `logical` (0 = oldest) of a grid snapshot
+fn scrollback_row(grid: &GridSnapshot, logical: usize) -> (&ScrollbackRow, bool) {
+    let physical = (grid.scrollback_start + logical) % grid.scrollback_rows.len();
+    let wrapped = grid
+        .scrollback_wrapped
+        .get(physical)
+        .copied()
+        .unwrap_or(false);
+    (&grid.scrollback_rows[physical], wrapped)
+}
+
+/// Scroll `cells` up by `shift` rows, blanking the rows uncovered at the bottom
+fn shift_screen(cells: &mut [Cell], wrapped: &mut [bool], cols: usize, shift: usize) {
+    let rows = wrapped.len();
+    let shift = shift.min(rows);
+    if shift == 0 {
+        return;
+    }
+    cells.rotate_left(shift * cols);
+    cells[(rows - shift) * cols..].fill(Cell::default());
+    wrapped.rotate_left(shift);
+    wrapped[rows - shift..].fill(false);
+}
+
+/// Append the runs of cells that differ between `grid` and `reference`
+fn diff_screen(alt: bool, grid: &GridSnapshot, reference: &[Cell], runs: &mut Vec<CellRun>) {
+    let cols = grid.cols;
+    for row in 0..grid.rows {
+        let new = &grid.cells[row * cols..(row + 1) * cols];
+        let old = &reference[row * cols..(row + 1) * cols];
+        if new == old {
+            continue;
+        }
+        let mut col = 0;
+        while col < cols {
+            if new[col] == old[col] {
+                col += 1;
+                continue;
+            }
+            let start = col;
+            let mut end = col + 1;
+            let mut gap = 0;
+            col += 1;
+            while col < cols && gap <= MAX_RUN_GAP {
+                if new[col] == old[col] {
+                    gap += 1;
+                } else {
+                    gap = 0;
+                    end = col + 1;
+                }
+                col += 1;
+            }
+            runs.push(CellRun {
+                alt,
+                row,
+                col: start,
+                cells: new[start..end].to_vec(),
+            });
+            col = end;
+        }
+    }
+}
+
+/// FNV-1a hash of the state a delta depends on
+///
+/// Covers both screens, the scrollback counters, cursors and modes, but not
+/// scrollback contents.
+fn fingerprint(snap: &TerminalSnapshot) -> u64 {
+    let mut data = Vec::with_capacity((snap.grid.cells.len() + snap.alt_grid.cells.len()) * 3);
+    put_uint(&mut data, snap.cols);
+    put_uint(&mut data, snap.rows);
+    for grid in [&snap.grid, &snap.alt_grid] {
+        put_cells(&mut data, &grid.cells);
+        data.extend(grid.wrapped.iter().map(|&w| u8::from(w)));
+    }
+    put_uint(&mut data, snap.grid.scrollback_lines);
+    put_uint(&mut data, snap.grid.total_lines_scrolled);
+    put_uint(&mut data, snap.grid.max_scrollback);
+    put_cursors(&mut data, &CursorState::from_snapshot(snap));
+    put_modes(&mut data, &ModeState::from_snapshot(snap));
+
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash: u64, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+impl TerminalSnapshot {
+    /// Apply a delta computed against this snapshot's state
+    ///
+    /// Keyframes apply to any snapshot. Fails with
+    /// [`DeltaError::BaseMismatch`] if this snapshot is not the delta's base,
+    /// or [`DeltaError::Malformed`] if the delta writes outside the screen;
+    /// the snapshot is unchanged on error.
+    pub fn apply_delta(&mut self, delta: &SnapshotDelta) -> Result<(), DeltaError> {
+        if !delta.keyframe && fingerprint(self) != delta.base_fingerprint {
+            return Err(DeltaError::BaseMismatch);
+        }
+        let (cols, rows) = (delta.cols, delta.rows);
+        let runs_fit = delta.runs.iter().all(|run| {
+            run.row < rows
+                && run
+                    .col
+                    .checked_add(run.cells.len())
+                    .is_some_and(|end| end <= cols)
+        });
+        if !runs_fit || delta.wraps.iter().any(|wrap| wrap.row >= rows) {
+            return Err(malformed("change outside the screen"));
+        }
+        let cursors_fit = delta.cursors.is_none_or(|c| {
+            [Some(c.cursor), Some(c.alt_cursor), c.saved_cursor]
+                .iter()
+                .flatten()
+                .all(|cursor| cursor.col < cols && cursor.row < rows)
+        });
+        let modes_fit = delta.modes.as_ref().is_none_or(|m| {
+            m.scroll_region_top <= m.scroll_region_bottom
+                && m.scroll_region_bottom < rows
+                && m.left_margin <= m.right_margin
+                && m.right_margin < cols
+                && m.tab_stops.len() == cols
+        });
+        if !cursors_fit || !modes_fit {
+            return Err(malformed("cursor, margins or tab stops outside the screen"));
+        }
+        if delta.keyframe && (delta.cursors.is_none() || delta.modes.is_none()) {
+            return Err(malformed("keyframe without cursors and modes"));
+        }
+
+        if delta.reset_screens {
+            self.cols = cols;
+            self.rows = rows;
+            for grid in [&mut self.grid, &mut self.alt_grid] {
+                grid.cols = cols;
+                grid.rows = rows;
+                grid.cells = vec![Cell::default(); cols * rows];
+                grid.wrapped = vec![false; rows];
+            }
+        }
+        if delta.keyframe {
+            for grid in [&mut self.grid, &mut self.alt_grid] {
+                grid.scrollback_rows.clear();
+                grid.scrollback_wrapped.clear();
+                grid.scrollback_start = 0;
+                grid.scrollback_lines = 0;
+                grid.total_lines_scrolled = 0;
+                grid.zones.clear();
+                grid.annotations.clear();
+                grid.row_times.clear();
+            }
+        }
+        if let Some(sb) = &delta.scrollback {
+            append_scrollback(&mut self.grid, sb);
+            shift_screen(&mut self.grid.cells, &mut self.grid.wrapped, cols, sb.shift);
+        }
+        for run in &delta.runs {
+            let grid = if run.alt {
+                &mut self.alt_grid
+            } else {
+                &mut self.grid
+            };
+            let start = run.row * cols + run.col;
+            grid.cells[start..start + run.cells.len()].clone_from_slice(&run.cells);
+        }
+        for wrap in &delta.wraps {
+            let grid = if wrap.alt {
+                &mut self.alt_grid
+            } else {
+                &mut self.grid
+            };
+            grid.wrapped[wrap.row] = wrap.wrapped;
+        }
+        if let Some(cursors) = &delta.cursors {
+            cursors.apply(self);
+        }
+        if let Some(modes) = &delta.modes {
+            modes.apply(self);
+        }
+        self.estimated_size_bytes = self.estimate_size();
+        Ok(())
+    }
+}
+
+/// Append the delta's lines to a grid's scrollback, evicting the oldest
+fn append_scrollback(grid: &mut GridSnapshot, sb: &ScrollbackDelta) {
+    let mut lines = std::mem::take(&mut grid.scrollback_rows);
+    let mut wrapped = std::mem::take(&mut grid.scrollback_wrapped);
+    if sb.reset {
+        lines.clear();
+        wrapped.clear();
+        grid.row_times.clear();
+    } else {
+        // Linearize the ring oldest-first
+        let start = grid.scrollback_start.min(lines.len());
+        lines.rotate_left(start);
+        wrapped.resize(lines.len(), false);
+        wrapped.rotate_left(start);
+        lines.truncate(grid.scrollback_lines);
+        wrapped.truncate(grid.scrollback_lines);
+    }
+    for (cells, line_wrapped) in &sb.lines {
+        lines.push(ScrollbackRow::from(cells.as_slice()));
+        wrapped.push(*line_wrapped);
+    }
+    let evicted = lines.len().saturating_sub(sb.max_scrollback);
+    lines.drain(..evicted);
+    wrapped.drain(..evicted);
+    grid.row_times.drain(..evicted.min(grid.row_times.len()));
+
+    grid.scrollback_lines = lines.len();
+    grid.scrollback_rows = lines;
+    grid.scrollback_wrapped = wrapped;
+    grid.scrollback_start = 0;
+    grid.max_scrollback = sb.max_scrollback;
+    grid.total_lines_scrolled = sb.total_lines_scrolled;
+}
+
+impl Terminal {
+    /// Encode the changes since the previous call as a binary delta
+    ///
+    /// The first call, and the first after
+    /// [`reset_snapshot_delta`](Self::reset_snapshot_delta), returns a
+    /// keyframe. Feed the bytes, in order, to
+    /// [`apply_snapshot_delta`](Self::apply_snapshot_delta) on the mirror.
+    pub fn snapshot_delta(&mut self) -> Vec<u8> {
+        let current = self.capture_snapshot();
+        let delta = SnapshotDelta::between(self.delta_base.as_deref(), &current);
+        self.delta_base = Some(Box::new(current));
+        delta.encode()
+    }
+
+    /// Make the next [`snapshot_delta`](Self::snapshot_delta) a keyframe
+    ///
+    /// Call this when a mirror connects or reports
+    /// [`DeltaError::BaseMismatch`].
+    pub fn reset_snapshot_delta(&mut self) {
+        self.delta_base = None;
+    }
+
+    /// Apply a delta produced by [`snapshot_delta`](Self::snapshot_delta)
+    ///
+    /// The terminal is unchanged on error.
+    pub fn apply_snapshot_delta(&mut self, data: &[u8]) -> Result<(), DeltaError> {
+        let delta = SnapshotDelta::decode(data)?;
+        let mut snap = self.capture_snapshot();
+        snap.apply_delta(&delta)?;
+        self.restore_from_snapshot(snap);
+        Ok(())
+    }
+}
+
+// --- Encoding ---
+
+fn put_uint(out: &mut Vec<u8>, value: usize) {
+    let mut value = value as u64;
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn put_color(out: &mut Vec<u8>, color: Option<Color>) {
+    match color {
+        None => out.push(0),
+        Some(Color::Named(named)) => out.extend_from_slice(&[1, named as u8]),
+        Some(Color::Indexed(index)) => out.extend_from_slice(&[2, index]),
+        Some(Color::Rgb(r, g, b)) => out.extend_from_slice(&[3, r, g, b]),
+    }
+}
+
+fn put_flags(out: &mut Vec<u8>, flags: &CellFlags) {
+    put_uint(out, usize::from(flags.to_bitflags()));
+    out.push(underline_style_code(flags.underline_style));
+    put_uint(out, flags.hyperlink_id.map_or(0, NonZeroU32::get) as usize);
+}
+
+fn put_cells(out: &mut Vec<u8>, cells: &[Cell]) {
+    put_uint(out, cells.len());
+    let mut previous: Option<&Cell> = None;
+    for cell in cells {
+        let same_style = previous.is_some_and(|prev| {
+            prev.fg == cell.fg
+                && prev.bg == cell.bg
+                && prev.underline_color == cell.underline_color
+                && prev.flags == cell.flags
+        });
+        let mut header = 0;
+        if same_style {
+            header |= CELL_SAME_STYLE;
+        }
+        if !cell.combining.is_empty() {
+            header |= CELL_COMBINING;
+        }
+        if cell.width != 1 {
+            header |= CELL_WIDTH;
+        }
+        out.push(header);
+        put_uint(out, cell.c as usize);
+        if !cell.combining.is_empty() {
+            put_uint(out, cell.combining.len());
+            for &mark in &cell.combining {
+                put_uint(out, mark as usize);
+            }
+        }
+        if !same_style {
+            put_color(out, Some(cell.fg));
+            put_color(out, Some(cell.bg));
+            put_color(out, cell.underline_color);
+            put_flags(out, &cell.flags);
+        }
+        if cell.width != 1 {
+            out.push(cell.width);
+        }
+        previous = Some(cell);
+    }
+}
+
+fn put_cursor(out: &mut Vec<u8>, cursor: &Cursor) {
+    put_uint(out, cursor.col);
+    put_uint(out, cursor.row);
+    out.push(u8::from(cursor.visible));
+    out.push(cursor_style_code(cursor.style));
+}
+
+fn put_cursors(out: &mut Vec<u8>, cursors: &CursorState) {
+    put_cursor(out, &cursors.cursor);
+    put_cursor(out, &cursors.alt_cursor);
+    out.push(u8::from(cursors.saved_cursor.is_some()));
+    if let Some(saved) = &cursors.saved_cursor {
+        put_cursor(out, saved);
+    }
+    out.push(u8::from(cursors.pending_wrap));
+}
+
+fn put_modes(out: &mut Vec<u8>, modes: &ModeState) {
+    put_color(out, Some(modes.fg));
+    put_color(out, Some(modes.bg));
+    put_color(out, modes.underline_color);
+    put_flags(out, &modes.flags);
+    put_color(out, Some(modes.saved_fg));
+    put_color(out, Some(modes.saved_bg));
+    put_color(out, modes.saved_underline_color);
+    put_flags(out, &modes.saved_flags);
+    put_uint(out, modes.title.len());
+    out.extend_from_slice(modes.title.as_bytes());
+    put_uint(out, usize::from(modes.mode_bits()));
+    out.push(mouse_mode_code(modes.mouse_mode));
+    out.push(mouse_encoding_code(modes.mouse_encoding));
+    put_uint(out, modes.left_margin);
+    put_uint(out, modes.right_margin);
+    put_uint(out, modes.scroll_region_top);
+    put_uint(out, modes.scroll_region_bottom);
+    put_uint(out, usize::from(modes.keyboard_flags));
+    out.push(modes.modify_other_keys_mode);
+    put_uint(out, modes.tab_stops.len());
+    for chunk in modes.tab_stops.chunks(8) {
+        out.push(
+            chunk
+                .iter()
+                .enumerate()
+                .fold(0, |byte, (i, &set)| byte | (u8::from(set) << i)),
+        );
+    }
+}
+
+fn underline_style_code(style: UnderlineStyle) -> u8 {
+    match style {
+        UnderlineStyle::None => 0,
+        UnderlineStyle::Straight => 1,
+        UnderlineStyle::Double => 2,
+        UnderlineStyle::Curly => 3,
+        UnderlineStyle::Dotted => 4,
+        UnderlineStyle::Dashed => 5,
+    }
+}
+
+fn underline_style_from_code(code: u8) -> Option<UnderlineStyle> {
+    Some(match code {
+        0 => UnderlineStyle::None,
+        1 => UnderlineStyle::Straight,
+        2 => UnderlineStyle::Double,
+        3 => UnderlineStyle::Curly,
+        4 => UnderlineStyle::Dotted,
+        5 => UnderlineStyle::Dashed,
+        _ => return None,
+    })
+}
+
+/// DECSCUSR parameter of a cursor style
+fn cursor_style_code(style: CursorStyle) -> u8 {
+    match style {
+        CursorStyle::BlinkingBlock => 1,
+        CursorStyle::SteadyBlock => 2,
+        CursorStyle::BlinkingUnderline => 3,
+        CursorStyle::SteadyUnderline => 4,
+        CursorStyle::BlinkingBar => 5,
+        CursorStyle::SteadyBar => 6,
+    }
+}
+
+fn mouse_mode_code(mode: MouseMode) -> u8 {
+    match mode {
+        MouseMode::Off => 0,
+        MouseMode::X10 => 1,
+        MouseMode::Normal => 2,
+        MouseMode::ButtonEvent => 3,
+        MouseMode::AnyEvent => 4,
+    }
+}
+
+fn mouse_mode_from_code(code: u8) -> Option<MouseMode> {
+    Some(match code {
+        0 => MouseMode::Off,
+        1 => MouseMode::X10,
+        2 => MouseMode::Normal,
+        3 => MouseMode::ButtonEvent,
+        4 => MouseMode::AnyEvent,
+        _ => return None,
+    })
+}
+
+fn mouse_encoding_code(encoding: MouseEncoding) -> u8 {
+    match encoding {
+        MouseEncoding::Default => 0,
+        MouseEncoding::Utf8 => 1,
+        MouseEncoding::Sgr => 2,
+        MouseEncoding::Urxvt => 3,
+    }
+}
+
+fn mouse_encoding_from_code(code: u8) -> Option<MouseEncoding> {
+    Some(match code {
+        0 => MouseEncoding::Default,
+        1 => MouseEncoding::Utf8,
+        2 => MouseEncoding::Sgr,
+        3 => MouseEncoding::Urxvt,
+        _ => return None,
+    })
+}
+
+// --- Decoding ---
+
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn bytes<const N: usize>(&mut self) -> Result<[u8; N], DeltaError> {
+        let (head, rest) = self
+            .0
+            .split_first_chunk::<N>()
+            .ok_or_else(|| malformed("truncated"))?;
+        self.0 = rest;
+        Ok(*head)
+    }
+
+    fn u8(&mut self) -> Result<u8, DeltaError> {
+        self.bytes::<1>().map(|[b]| b)
+    }
+
+    fn bool(&mut self) -> Result<bool, DeltaError> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(malformed("invalid flag")),
+        }
+    }
+
+    fn uint(&mut self) -> Result<usize, DeltaError> {
+        let mut value: u64 = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = self.u8()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return usize::try_from(value).map_err(|_| malformed("integer too large"));
+            }
+        }
+        Err(malformed("integer too large"))
+    }
+
+    /// An element count, bounded by the remaining data so corrupt input
+    /// cannot trigger huge allocations
+    fn count(&mut self) -> Result<usize, DeltaError> {
+        let count = self.uint()?;
+        if count > self.0.len() {
+            return Err(malformed("count exceeds data"));
+        }
+        Ok(count)
+    }
+
+    fn char(&mut self) -> Result<char, DeltaError> {
+        u32::try_from(self.uint()?)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or_else(|| malformed("invalid character"))
+    }
+
+    fn color(&mut self) -> Result<Option<Color>, DeltaError> {
+        Ok(match self.u8()? {
+            0 => None,
+            1 => Some(Color::Named(NamedColor::from_u8(self.u8()?))),
+            2 => Some(Color::Indexed(self.u8()?)),
+            3 => {
+                let [r, g, b] = self.bytes()?;
+                Some(Color::Rgb(r, g, b))
+            }
+            _ => return Err(malformed("invalid color")),
+        })
+    }
+
+    fn required_color(&mut self) -> Result<Color, DeltaError> {
+        self.color()?.ok_or_else(|| malformed("missing color"))
+    }
+
+    fn flags(&mut self) -> Result<CellFlags, DeltaError> {
+        let bits = u16::try_from(self.uint()?).map_err(|_| malformed("invalid flags"))?;
+        let mut flags = CellFlags::from_bitflags(bits);
+        flags.underline_style = underline_style_from_code(self.u8()?)
+            .ok_or_else(|| malformed("invalid underline style"))?;
+        let link = u32::try_from(self.uint()?).map_err(|_| malformed("invalid hyperlink"))?;
+        flags.hyperlink_id = NonZeroU32::new(link);
+        Ok(flags)
+    }
+
+    fn cells(&mut self) -> Result<Vec<Cell>, DeltaError> {
+        let count = self.count()?;
+        let mut cells: Vec<Cell> = Vec::with_capacity(count);
+        for _ in 0..count {
+            let header = self.u8()?;
+            let mut cell = Cell {
+                c: self.char()?,
+                ..Cell::default()
+            };
+            if header & CELL_COMBINING != 0 {
+                for _ in 0..self.count()? {
+                    cell.combining.push(self.char()?);
+                }
+            }
+            if header & CELL_SAME_STYLE != 0 {
+                let prev = cells
+                    .last()
+                    .ok_or_else(|| malformed("first cell has no style"))?;
+                cell.fg = prev.fg;
+                cell.bg = prev.bg;
+                cell.underline_color = prev.underline_color;
+                cell.flags = prev.flags;
+            } else {
+                cell.fg = self.required_color()?;
+                cell.bg = self.required_color()?;
+                cell.underline_color = self.color()?;
+                cell.flags = self.flags()?;
+            }
+            if header & CELL_WIDTH != 0 {
+                cell.width = self.u8()?;
+            }
+            cells.push(cell);
+        }
+        Ok(cells)
+    }
+
+    fn cursor(&mut self) -> Result<Cursor, DeltaError> {
+        Ok(Cursor {
+            col: self.uint()?,
+            row: self.uint()?,
+            visible: self.bool()?,
+            style: CursorStyle::from_decscusr(u16::from(self.u8()?)),
+        })
+    }
+
+    fn cursors(&mut self) -> Result<CursorState, DeltaError> {
+        let cursor = self.cursor()?;
+        let alt_cursor = self.cursor()?;
+        let saved_cursor = if self.bool()? {
+            Some(self.cursor()?)
+        } else {
+            None
+        };
+        Ok(CursorState {
+            cursor,
+            alt_cursor,
+            saved_cursor,
+            pending_wrap: self.bool()?,
+        })
+    }
+
+    fn modes(&mut self) -> Result<ModeState, DeltaError> {
+        let fg = self.required_color()?;
+        let bg = self.required_color()?;
+        let underline_color = self.color()?;
+        let flags = self.flags()?;
+        let saved_fg = self.required_color()?;
+        let saved_bg = self.required_color()?;
+        let saved_underline_color = self.color()?;
+        let saved_flags = self.flags()?;
+        let title_len = self.count()?;
+        let (title, rest) = self.0.split_at(title_len);
+        self.0 = rest;
+        let title = String::from_utf8(title.to_vec()).map_err(|_| malformed("invalid title"))?;
+        let bits = self.uint()?;
+        let bit = |i: usize| bits & (1 << i) != 0;
+        let mouse_mode =
+            mouse_mode_from_code(self.u8()?).ok_or_else(|| malformed("invalid mouse mode"))?;
+        let mouse_encoding = mouse_encoding_from_code(self.u8()?)
+            .ok_or_else(|| malformed("invalid mouse encoding"))?;
+        let left_margin = self.uint()?;
+        let right_margin = self.uint()?;
+        let scroll_region_top = self.uint()?;
+        let scroll_region_bottom = self.uint()?;
+        let keyboard_flags =
+            u16::try_from(self.uint()?).map_err(|_| malformed("invalid keyboard flags"))?;
+        let modify_other_keys_mode = self.u8()?;
+        let tab_count = self.uint()?;
+        if tab_count.div_ceil(8) > self.0.len() {
+            return Err(malformed("truncated"));
+        }
+        let mut tab_stops = Vec::with_capacity(tab_count);
+        for _ in 0..tab_count.div_ceil(8) {
+            let byte = self.u8()?;
+            tab_stops.extend((0..8).map(|i| byte & (1 << i) != 0));
+        }
+        tab_stops.truncate(tab_count);
+
+        Ok(ModeState {
+            alt_screen_active: bit(0),
+            fg,
+            bg,
+            underline_color,
+            flags,
+            saved_fg,
+            saved_bg,
+            saved_underline_color,
+            saved_flags,
+            title,
+            auto_wrap: bit(1),
+            origin_mode: bit(2),
+            insert_mode: bit(3),
+            reverse_video: bit(4),
+            line_feed_new_line_mode: bit(5),
+            application_cursor: bit(6),
+            bracketed_paste: bit(7),
+            focus_tracking: bit(8),
+            use_lr_margins: bit(9),
+            char_protected: bit(10),
+            bold_brightening: bit(11),
+            mouse_mode,
+            mouse_encoding,
+            left_margin,
+            right_margin,
+            scroll_region_top,
+            scroll_region_bottom,
+            keyboard_flags,
+            modify_other_keys_mode,
+            tab_stops,
+        })
+    }
+}
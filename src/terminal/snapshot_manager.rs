@@ -7,8 +7,9 @@
 
 use super::replay_snapshot::TerminalSnapshot;
 use super::Terminal;
+use crate::time::Instant;
 use std::collections::VecDeque;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 /// Default maximum memory budget for stored snapshots (4 MiB).
 pub const DEFAULT_MAX_MEMORY_BYTES: usize = 4 * 1024 * 1024;
@@ -0,0 +1,154 @@
+//! Immutable state handles for multi-reader access
+//!
+//! [`Terminal::state_handle`] copies the visible cells, the cursor and the
+//! modes a renderer needs into a [`TerminalStateHandle`]. A handle never
+//! changes after it is taken and is cheap to clone (the state sits behind an
+//! `Arc`), so a rendering thread can keep one and read a consistent frame
+//! without holding the terminal lock while new output is processed. Take a
+//! new handle to see later output.
+//!
+//! `PtySession::state_handle` builds on this: once a handle has been
+//! requested, the reader thread publishes a fresh one after every chunk of
+//! PTY output, and callers get the newest published handle instead of
+//! waiting for the chunk being processed.
+
+use std::sync::Arc;
+
+use crate::cursor::Cursor;
+use crate::grid::Grid;
+use crate::mouse::{MouseEncoding, MouseMode};
+use crate::terminal::Terminal;
+
+/// Modes captured in a [`TerminalStateHandle`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateModes {
+    /// Whether the alternate screen was showing
+    pub alt_screen: bool,
+    /// Auto-wrap mode (DECAWM)
+    pub auto_wrap: bool,
+    /// Origin mode (DECOM)
+    pub origin_mode: bool,
+    /// Insert mode (IRM)
+    pub insert_mode: bool,
+    /// Reverse video (DECSCNM)
+    pub reverse_video: bool,
+    /// Application cursor keys (DECCKM)
+    pub application_cursor: bool,
+    /// Bracketed paste (DEC 2004)
+    pub bracketed_paste: bool,
+    /// Focus tracking (DEC 1004)
+    pub focus_tracking: bool,
+    /// Synchronized update in progress (DEC 2026)
+    pub synchronized_updates: bool,
+    /// Mouse tracking mode
+    pub mouse_mode: MouseMode,
+    /// Mouse report encoding
+    pub mouse_encoding: MouseEncoding,
+}
+
+#[derive(Debug)]
+struct StateInner {
+    grid: Grid,
+    cursor: Cursor,
+    modes: StateModes,
+    title: String,
+    bytes_processed: u64,
+}
+
+/// Immutable view of the visible screen, cursor and modes
+///
+/// Cheap to clone: clones share the captured state.
+#[derive(Debug, Clone)]
+pub struct TerminalStateHandle {
+    inner: Arc<StateInner>,
+}
+
+impl TerminalStateHandle {
+    /// The screen cells (no scrollback)
+    pub fn grid(&self) -> &Grid {
+        &self.inner.grid
+    }
+
+    /// Screen width in columns
+    pub fn cols(&self) -> usize {
+        self.inner.grid.cols()
+    }
+
+    /// Screen height in rows
+    pub fn rows(&self) -> usize {
+        self.inner.grid.rows()
+    }
+
+    /// Cursor when the handle was taken
+    pub fn cursor(&self) -> &Cursor {
+        &self.inner.cursor
+    }
+
+    /// Modes when the handle was taken
+    pub fn modes(&self) -> &StateModes {
+        &self.inner.modes
+    }
+
+    /// Window title when the handle was taken
+    pub fn title(&self) -> &str {
+        &self.inner.title
+    }
+
+    /// Total bytes the terminal had processed when the handle was taken
+    ///
+    /// The count only grows, across RIS too. Two handles with the same count
+    /// were taken between the same chunks of output, though API calls such as
+    /// a resize may still set them apart.
+    pub fn bytes_processed(&self) -> u64 {
+        self.inner.bytes_processed
+    }
+
+    /// Text of screen row `row`, or `None` if out of range
+    pub fn line(&self, row: usize) -> Option<String> {
+        self.inner.grid.row(row).map(crate::terminal::cells_to_text)
+    }
+
+    /// Screen text, one line per row with trailing spaces trimmed
+    pub fn text(&self) -> String {
+        (0..self.rows())
+            .filter_map(|row| self.line(row))
+            .map(|line| line.trim_end().to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Whether both handles share the same captured state
+    pub fn ptr_eq(&self, other: &TerminalStateHandle) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Terminal {
+    /// Take an immutable handle on the visible screen, cursor and modes
+    ///
+    /// The handle is detached from the terminal: it can be sent to another
+    /// thread and read while the terminal keeps processing output.
+    pub fn state_handle(&self) -> TerminalStateHandle {
+        TerminalStateHandle {
+            inner: Arc::new(StateInner {
+                grid: self.grid_with_scrollback(0),
+                cursor: self.cursor,
+                modes: StateModes {
+                    alt_screen: self.alt_screen_active,
+                    auto_wrap: self.modes.auto_wrap,
+                    origin_mode: self.modes.origin_mode,
+                    insert_mode: self.modes.insert_mode,
+                    reverse_video: self.modes.reverse_video,
+                    application_cursor: self.modes.application_cursor,
+                    bracketed_paste: self.modes.bracketed_paste,
+                    focus_tracking: self.modes.focus_tracking,
+                    synchronized_updates: self.sync_state.synchronized_updates,
+                    mouse_mode: self.modes.mouse_mode,
+                    mouse_encoding: self.modes.mouse_encoding,
+                },
+                title: self.title().to_string(),
+                bytes_processed: self.bytes_processed,
+            }),
+        }
+    }
+}
@@ -0,0 +1,95 @@
+// Output activity histogram and row timestamp tests
+use crate::terminal::*;
+
+#[test]
+fn process_counts_bytes_and_lines() {
+    let mut term = Terminal::new(80, 24);
+    assert!(term.activity_histogram(60).is_empty());
+
+    term.process(b"one\r\ntwo\r\n");
+    term.process(b"three");
+    let total: (u64, u64) = term
+        .activity_histogram(3600)
+        .iter()
+        .fold((0, 0), |(b, l), bucket| {
+            (b + bucket.bytes, l + bucket.lines)
+        });
+    assert_eq!(total, (15, 2));
+
+    term.clear_activity();
+    assert!(term.activity_histogram(60).is_empty());
+}
+
+#[test]
+fn written_rows_get_timestamps() {
+    let mut term = Terminal::new(20, 5);
+    let before = unix_millis();
+    term.process(b"first\r\n\r\nthird");
+
+    let stamp = term.row_timestamp(0).expect("row 0 written");
+    assert!(stamp >= before && stamp <= unix_millis());
+    assert_eq!(term.row_timestamp(1), None);
+    assert_eq!(term.row_timestamp(2), Some(stamp));
+    assert_eq!(term.row_timestamp(3), None);
+}
+
+#[test]
+fn timestamps_follow_rows_into_scrollback_and_eviction() {
+    let mut term = Terminal::with_scrollback(20, 2, 2);
+    term.process(b"a\r\nb\r\n");
+    term.grid.stamp_row(0, 1);
+    term.grid.stamp_row(1, 2);
+
+    // "a" is evicted when "c" scrolls into the full scrollback
+    term.process(b"c\r\nd\r\ne");
+    assert_eq!(term.grid.scrollback_len(), 2);
+    assert_eq!(term.row_timestamp(0), Some(2));
+    let recent = term.row_timestamp(1).unwrap();
+    assert!(recent > 2);
+    assert_eq!(term.row_timestamp(2), Some(recent));
+    assert_eq!(term.row_timestamp(3), Some(recent));
+    assert_eq!(term.row_timestamp(4), None);
+}
+
+#[test]
+fn alternate_screen_writes_are_not_stamped() {
+    let mut term = Terminal::new(20, 5);
+    term.process(b"\x1b[?1049h\x1b[3;1Hvim");
+    assert_eq!(term.row_timestamp(2), None);
+    term.process(b"\x1b[?1049l");
+    assert_eq!(term.row_timestamp(2), None);
+}
+
+#[test]
+fn reset_clears_row_timestamps_but_keeps_activity() {
+    let mut term = Terminal::new(20, 5);
+    term.process(b"text");
+    term.process(b"\x1bc");
+    assert_eq!(term.row_timestamp(0), None);
+    assert!(!term.activity_histogram(3600).is_empty());
+}
+
+#[test]
+fn blank_rows_are_stamped_entering_scrollback() {
+    let mut term = Terminal::with_scrollback(20, 2, 10);
+    term.process(b"\r\n\r\n\r\n");
+    assert_eq!(term.grid.scrollback_len(), 2);
+    assert!(term.row_timestamp(0).is_some());
+    assert!(term.row_timestamp(1).is_some());
+    // Still on screen and never written
+    assert_eq!(term.row_timestamp(2), None);
+}
+
+#[test]
+fn find_row_at_time_returns_first_row_at_or_after() {
+    let mut term = Terminal::new(20, 5);
+    term.process(b"a\r\nb\r\nc\r\nd");
+    for (row, stamp) in [(0, 100), (1, 200), (2, 200), (3, 300)] {
+        term.grid.stamp_row(row, stamp);
+    }
+    assert_eq!(term.find_row_at_time(0), Some(0));
+    assert_eq!(term.find_row_at_time(150), Some(1));
+    assert_eq!(term.find_row_at_time(200), Some(1));
+    assert_eq!(term.find_row_at_time(300), Some(3));
+    assert_eq!(term.find_row_at_time(301), None);
+}
@@ -0,0 +1,93 @@
+// Cell annotation tests
+use crate::annotation::AnnotationRange;
+use crate::terminal::*;
+
+#[test]
+fn test_add_and_query_annotations() {
+    let mut term = Terminal::new(20, 5);
+    term.process(b"error: bad thing");
+    let id = term
+        .add_annotation(
+            AnnotationRange::new(0, 0, 5),
+            "E0001".to_string(),
+            Some("lint".to_string()),
+        )
+        .unwrap();
+
+    let at = term.get_annotations_at(0, 4);
+    assert_eq!(at.len(), 1);
+    assert_eq!(at[0].id, id);
+    assert_eq!(at[0].tag.as_deref(), Some("lint"));
+    assert!(term.get_annotations_at(0, 5).is_empty());
+    assert_eq!(term.get_annotated_text(at[0]), "error");
+
+    assert!(term.remove_annotation(id));
+    assert!(!term.remove_annotation(id));
+    assert!(term.get_annotations().is_empty());
+}
+
+#[test]
+fn test_add_annotation_rejects_and_clips_ranges() {
+    let mut term = Terminal::new(10, 3);
+    assert!(term
+        .add_annotation(AnnotationRange::new(0, 0, 0), "x".to_string(), None)
+        .is_none());
+    assert!(term
+        .add_annotation(AnnotationRange::new(3, 0, 1), "x".to_string(), None)
+        .is_none());
+    assert!(term
+        .add_annotation(AnnotationRange::new(0, 10, 1), "x".to_string(), None)
+        .is_none());
+
+    term.add_annotation(AnnotationRange::new(1, 8, 5), "x".to_string(), None)
+        .unwrap();
+    assert_eq!(term.get_annotations()[0].range.length, 2);
+}
+
+#[test]
+fn test_annotations_follow_text_into_scrollback() {
+    let mut term = Terminal::with_scrollback(20, 3, 4);
+    term.process(b"warning here\r\n");
+    term.add_annotation(AnnotationRange::new(0, 0, 7), "note".to_string(), None)
+        .unwrap();
+    for i in 0..4 {
+        term.process(format!("line {}\r\n", i).as_bytes());
+    }
+    let annotation = &term.get_annotations()[0];
+    assert!(annotation.range.abs_row < term.get_scrollback_usage());
+    assert_eq!(term.get_annotated_text(annotation), "warning");
+
+    // Scrollback is full; once the line is evicted the annotation goes too
+    for i in 0..4 {
+        term.process(format!("more {}\r\n", i).as_bytes());
+    }
+    assert!(term.get_annotations().is_empty());
+}
+
+#[test]
+fn test_semantic_snapshot_includes_annotations() {
+    let mut term = Terminal::with_scrollback(20, 2, 10);
+    term.process(b"old\r\nnew\r\n");
+    let old_row = 0;
+    let new_row = term.get_scrollback_usage();
+    term.add_annotation(AnnotationRange::new(old_row, 0, 3), "a".to_string(), None)
+        .unwrap();
+    term.add_annotation(
+        AnnotationRange::new(new_row, 0, 3),
+        "b".to_string(),
+        Some("tag".to_string()),
+    )
+    .unwrap();
+
+    let visible = term.get_semantic_snapshot(SnapshotScope::Visible);
+    assert_eq!(visible.annotations.len(), 1);
+    assert_eq!(visible.annotations[0].text, "b");
+
+    let full = term.get_semantic_snapshot(SnapshotScope::Full);
+    let texts: Vec<_> = full.annotations.iter().map(|a| a.text.as_str()).collect();
+    assert_eq!(texts, vec!["a", "b"]);
+    assert_eq!(full.annotations[0].cell_text, "old");
+
+    let json = term.get_semantic_snapshot_json(SnapshotScope::Full);
+    assert!(json.contains("\"annotations\""));
+}
@@ -0,0 +1,144 @@
+// Dark/light appearance switching tests
+use crate::color::Color;
+use crate::terminal::*;
+use crate::theme::Theme;
+
+fn theme(name: &str, bg: Color, red: Color) -> Theme {
+    let mut palette = [None; 16];
+    palette[1] = Some(red);
+    Theme {
+        name: Some(name.to_string()),
+        background: Some(bg),
+        palette,
+        ..Theme::default()
+    }
+}
+
+#[test]
+fn test_set_appearance_swaps_themes() {
+    let mut term = Terminal::new(20, 5);
+    assert_eq!(term.appearance(), Appearance::Dark);
+    term.set_appearance_theme(
+        Appearance::Dark,
+        Some(theme("dark", Color::Rgb(0, 0, 0), Color::Rgb(200, 0, 0))),
+    );
+    term.set_appearance_theme(
+        Appearance::Light,
+        Some(theme(
+            "light",
+            Color::Rgb(255, 255, 255),
+            Color::Rgb(150, 0, 0),
+        )),
+    );
+    // The dark theme applies immediately because dark is current
+    assert_eq!(term.default_bg(), Color::Rgb(0, 0, 0));
+    term.poll_events();
+
+    term.set_appearance(Appearance::Light);
+    assert_eq!(term.appearance(), Appearance::Light);
+    assert_eq!(term.default_bg(), Color::Rgb(255, 255, 255));
+    assert_eq!(term.get_ansi_color(1), Some(Color::Rgb(150, 0, 0)));
+    assert_eq!(
+        term.poll_events(),
+        vec![TerminalEvent::ThemeChanged {
+            name: Some("light".to_string())
+        }]
+    );
+
+    term.set_appearance(Appearance::Dark);
+    assert_eq!(term.default_bg(), Color::Rgb(0, 0, 0));
+    assert_eq!(term.get_ansi_color(1), Some(Color::Rgb(200, 0, 0)));
+}
+
+#[test]
+fn test_set_appearance_same_value_is_noop() {
+    let mut term = Terminal::new(20, 5);
+    term.process(b"\x1b[?2031h");
+    term.drain_responses();
+    term.poll_events();
+
+    term.set_appearance(Appearance::Dark);
+    assert!(term.drain_responses().is_empty());
+    assert!(term.poll_events().is_empty());
+}
+
+#[test]
+fn test_mode_2031_reports_appearance_changes() {
+    let mut term = Terminal::new(20, 5);
+
+    // Not reported until the application opts in
+    term.set_appearance(Appearance::Light);
+    assert!(term.drain_responses().is_empty());
+
+    term.process(b"\x1b[?2031h");
+    assert!(term
+        .poll_events()
+        .contains(&TerminalEvent::ModeChanged(Mode::ColorSchemeUpdates, true)));
+    term.set_appearance(Appearance::Dark);
+    assert_eq!(term.drain_responses(), b"\x1b[?997;1n");
+    term.set_appearance(Appearance::Light);
+    assert_eq!(term.drain_responses(), b"\x1b[?997;2n");
+
+    term.process(b"\x1b[?2031l");
+    term.set_appearance(Appearance::Dark);
+    assert!(term.drain_responses().is_empty());
+}
+
+#[test]
+fn test_color_scheme_query_and_decrqm() {
+    let mut term = Terminal::new(20, 5);
+    term.process(b"\x1b[?996n");
+    assert_eq!(term.drain_responses(), b"\x1b[?997;1n");
+    term.set_appearance(Appearance::Light);
+    term.process(b"\x1b[?996n");
+    assert_eq!(term.drain_responses(), b"\x1b[?997;2n");
+
+    term.process(b"\x1b[?2031$p");
+    assert_eq!(term.drain_responses(), b"\x1b[?2031;2$y");
+    term.process(b"\x1b[?2031h\x1b[?2031$p");
+    assert_eq!(term.drain_responses(), b"\x1b[?2031;1$y");
+}
+
+#[test]
+fn test_contrast_follows_appearance() {
+    let mut term = Terminal::new(20, 5);
+    term.set_minimum_contrast_ratio(4.5);
+    term.set_appearance_theme(
+        Appearance::Light,
+        Some(theme(
+            "light",
+            Color::Rgb(255, 255, 255),
+            Color::Rgb(250, 250, 250),
+        )),
+    );
+    term.set_appearance(Appearance::Light);
+
+    let mut cell = crate::cell::Cell::new('x');
+    cell.fg = term.get_ansi_color(1).unwrap();
+    cell.bg = term.default_bg();
+    let (fg, bg) = term.resolve_cell_colors(&cell);
+    let ratio = Color::Rgb(fg.0, fg.1, fg.2).contrast_ratio(&Color::Rgb(bg.0, bg.1, bg.2));
+    assert!(ratio >= 4.5, "{ratio}");
+}
+
+#[test]
+fn test_reset_keeps_appearance_and_its_theme() {
+    let mut term = Terminal::new(20, 5);
+    term.set_appearance_theme(
+        Appearance::Light,
+        Some(theme(
+            "light",
+            Color::Rgb(255, 255, 255),
+            Color::Rgb(150, 0, 0),
+        )),
+    );
+    term.set_appearance(Appearance::Light);
+    term.process(b"\x1b]11;rgb:00/00/00\x1b\\");
+
+    term.process(b"\x1bc");
+    assert_eq!(term.appearance(), Appearance::Light);
+    assert_eq!(term.default_bg(), Color::Rgb(255, 255, 255));
+    assert!(term.appearance_theme(Appearance::Light).is_some());
+    term.process(b"\x1b[?996n");
+    assert_eq!(term.drain_responses(), b"\x1b[?997;2n");
+}
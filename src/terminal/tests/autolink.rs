@@ -0,0 +1,166 @@
+use crate::terminal::screen::{UpdatePriority, ZLayer};
+use crate::terminal::{Terminal, TerminalEvent};
+
+fn autolink_terminal() -> Terminal {
+    let mut term = Terminal::new(40, 5);
+    term.set_url_autolink(true);
+    term
+}
+
+fn link_id_at(term: &Terminal, col: usize, row: usize) -> Option<u32> {
+    term.active_grid()
+        .get(col, row)
+        .and_then(|cell| cell.flags.hyperlink_id)
+        .map(|id| id.get())
+}
+
+#[test]
+fn test_autolink_disabled_by_default() {
+    let mut term = Terminal::new(40, 5);
+    term.process(b"see https://example.com now");
+    assert!(!term.url_autolink_enabled());
+    assert!(term.get_all_hyperlinks().is_empty());
+}
+
+#[test]
+fn test_autolink_promotes_typed_url() {
+    let mut term = autolink_terminal();
+    term.process(b"see https://example.com now");
+
+    let links = term.get_all_hyperlinks();
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].url, "https://example.com");
+    let cols: Vec<usize> = links[0].positions.iter().map(|&(col, _)| col).collect();
+    assert_eq!(cols, (4..23).collect::<Vec<_>>());
+
+    let id = link_id_at(&term, 4, 0).unwrap();
+    assert!(term.is_autolink(id));
+    assert_eq!(
+        term.get_hyperlink_url(id).as_deref(),
+        Some("https://example.com")
+    );
+    assert_eq!(link_id_at(&term, 3, 0), None);
+    assert_eq!(link_id_at(&term, 23, 0), None);
+    assert!(term.poll_events().contains(&TerminalEvent::HyperlinkAdded {
+        url: "https://example.com".to_string(),
+        row: 0,
+        col: 4,
+        id: Some(id),
+    }));
+}
+
+#[test]
+fn test_autolink_after_text_that_changes_length_when_lowercased() {
+    // "İ" lowercases to two chars, so offsets into a lowercased copy would
+    // land inside a character of the original line
+    let mut term = autolink_terminal();
+    term.process("İİİİİİİİ HTTP://ééé".as_bytes());
+
+    let links = term.get_all_hyperlinks();
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].url, "HTTP://ééé");
+    assert_eq!(links[0].positions.first().map(|&(col, _)| col), Some(9));
+}
+
+#[test]
+fn test_autolink_follows_edits_and_reports_once() {
+    let mut term = autolink_terminal();
+    term.process(b"https://a.example");
+    let added = |term: &mut Terminal| {
+        term.poll_events()
+            .into_iter()
+            .filter(|event| matches!(event, TerminalEvent::HyperlinkAdded { .. }))
+            .count()
+    };
+    assert_eq!(added(&mut term), 1);
+
+    // Other output on the row keeps the link without a new event
+    term.process(b" and more");
+    assert_eq!(added(&mut term), 0);
+
+    // Editing the URL relinks it to the new address
+    term.process(b"\r\x1b[17C\x1b[K/path");
+    assert_eq!(added(&mut term), 1);
+    let links = term.get_all_hyperlinks();
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].url, "https://a.example/path");
+
+    // Erasing the line removes it
+    term.process(b"\r\x1b[2K");
+    assert!(term.get_all_hyperlinks().is_empty());
+}
+
+#[test]
+fn test_autolink_leaves_osc8_links_alone() {
+    let mut term = autolink_terminal();
+    term.process(b"\x1b]8;;https://other.example\x1b\\https://shown.example\x1b]8;;\x1b\\");
+
+    let id = link_id_at(&term, 0, 0).unwrap();
+    assert!(!term.is_autolink(id));
+    assert_eq!(
+        term.get_hyperlink_url(id).as_deref(),
+        Some("https://other.example")
+    );
+
+    // An OSC 8 link to a URL that was autolinked gets its own ID
+    term.process(b"\r\nhttps://x.example ");
+    let auto = link_id_at(&term, 0, 1).unwrap();
+    term.process(b"\x1b]8;;https://x.example\x1b\\link\x1b]8;;\x1b\\");
+    let explicit = link_id_at(&term, 18, 1).unwrap();
+    assert_ne!(auto, explicit);
+    assert!(!term.is_autolink(explicit));
+}
+
+#[test]
+fn test_autolink_toggle_scans_and_clears_screen() {
+    let mut term = Terminal::new(40, 5);
+    term.process(b"ftp://files.example");
+    term.set_url_autolink(true);
+    assert_eq!(term.get_all_hyperlinks().len(), 1);
+
+    term.set_url_autolink(false);
+    assert!(term.get_all_hyperlinks().is_empty());
+}
+
+#[test]
+fn test_autolink_survives_ris() {
+    let mut term = autolink_terminal();
+    term.process(b"\x1bcsee https://example.com");
+    assert!(term.url_autolink_enabled());
+    assert_eq!(term.get_all_hyperlinks().len(), 1);
+}
+
+#[test]
+fn test_link_hover_queues_rendering_hints() {
+    let mut term = autolink_terminal();
+    term.process(b"go https://example.com");
+    let id = link_id_at(&term, 3, 0);
+
+    assert_eq!(term.update_link_hover(5, 0), id);
+    assert_eq!(term.hovered_hyperlink(), id);
+    let hints = term.poll_rendering_hints();
+    assert_eq!(hints.len(), 1);
+    assert_eq!(hints[0].layer, ZLayer::Overlay);
+    assert_eq!(hints[0].priority, UpdatePriority::High);
+    assert_eq!(
+        (
+            hints[0].damage.left,
+            hints[0].damage.right,
+            hints[0].damage.top
+        ),
+        (3, 22, 0)
+    );
+
+    // Moving within the same link queues nothing
+    term.update_link_hover(10, 0);
+    assert!(term.poll_rendering_hints().is_empty());
+
+    // Leaving it repaints the old link
+    assert_eq!(term.update_link_hover(0, 0), None);
+    assert_eq!(term.poll_rendering_hints().len(), 1);
+    term.update_link_hover(5, 0);
+    term.poll_rendering_hints();
+    term.clear_link_hover();
+    assert_eq!(term.hovered_hyperlink(), None);
+    assert_eq!(term.poll_rendering_hints().len(), 1);
+}
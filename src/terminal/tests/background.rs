@@ -0,0 +1,159 @@
+// Background image and opacity request tests
+use crate::terminal::*;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+
+fn background_events(term: &mut Terminal) -> Vec<BackgroundChange> {
+    term.poll_events()
+        .into_iter()
+        .filter_map(|event| match event {
+            TerminalEvent::BackgroundChanged { change } => Some(change),
+            _ => None,
+        })
+        .collect()
+}
+
+fn allowed_terminal() -> Terminal {
+    let mut term = Terminal::new(20, 5);
+    term.set_allow_remote_background(true);
+    term
+}
+
+fn iterm_set_file(path: &str) -> Vec<u8> {
+    format!(
+        "\x1b]1337;SetBackgroundImageFile={}\x07",
+        BASE64.encode(path)
+    )
+    .into_bytes()
+}
+
+fn kitty_cmd(cmd: &str, payload: &str) -> Vec<u8> {
+    format!(
+        "\x1bP@kitty-cmd{{\"cmd\":\"{}\",\"version\":[0,30,0],\"payload\":{}}}\x1b\\",
+        cmd, payload
+    )
+    .into_bytes()
+}
+
+#[test]
+fn test_background_requests_blocked_by_default() {
+    let mut term = Terminal::new(20, 5);
+    assert!(!term.allow_remote_background());
+    term.process(&iterm_set_file("/tmp/bg.png"));
+    term.process(&kitty_cmd("set-background-opacity", r#"{"opacity":0.5}"#));
+
+    assert_eq!(term.background_image(), None);
+    assert_eq!(term.background_opacity(), None);
+    assert!(background_events(&mut term).is_empty());
+    let reply = String::from_utf8(term.drain_responses()).unwrap();
+    assert!(reply.starts_with("\x1bP@kitty-cmd{"));
+    assert!(reply.contains(r#""ok":false"#));
+}
+
+#[test]
+fn test_insecure_sequence_filter_blocks_background_requests() {
+    let mut term = allowed_terminal();
+    term.set_disable_insecure_sequences(true);
+    term.process(&iterm_set_file("/tmp/bg.png"));
+    assert_eq!(term.background_image(), None);
+}
+
+#[test]
+fn test_iterm_background_image_file() {
+    let mut term = allowed_terminal();
+    term.process(&iterm_set_file("/home/user/bg.png"));
+    assert_eq!(
+        term.background_image(),
+        Some(&BackgroundImage::File("/home/user/bg.png".to_string()))
+    );
+    assert_eq!(background_events(&mut term), [BackgroundChange::Image]);
+
+    // An empty path removes the image
+    term.process(b"\x1b]1337;SetBackgroundImageFile=\x07");
+    assert_eq!(term.background_image(), None);
+    assert_eq!(background_events(&mut term), [BackgroundChange::Image]);
+    // iTerm2 sends no reply
+    assert!(term.drain_responses().is_empty());
+}
+
+#[test]
+fn test_kitty_background_image_streams_chunks() {
+    let mut term = allowed_terminal();
+    let image = b"\x89PNG fake image data";
+    let (first, second) = image.split_at(8);
+    for chunk in [BASE64.encode(first), BASE64.encode(second)] {
+        term.process(&kitty_cmd(
+            "set-background-image",
+            &format!(r#"{{"data":"{}","img_id":"a1","layout":"cscaled"}}"#, chunk),
+        ));
+    }
+    // Not published until the empty chunk ends the stream
+    assert_eq!(term.background_image(), None);
+    assert!(term.drain_responses().is_empty());
+
+    term.process(&kitty_cmd(
+        "set-background-image",
+        r#"{"data":"","img_id":"a1","layout":"cscaled"}"#,
+    ));
+    assert_eq!(
+        term.background_image(),
+        Some(&BackgroundImage::Data {
+            data: image.to_vec(),
+            layout: Some(BackgroundLayout::CenterScaled),
+        })
+    );
+    assert_eq!(background_events(&mut term), [BackgroundChange::Image]);
+    assert_eq!(
+        term.drain_responses(),
+        b"\x1bP@kitty-cmd{\"ok\":true}\x1b\\"
+    );
+
+    term.process(&kitty_cmd("set-background-image", r#"{"data":"-"}"#));
+    assert_eq!(term.background_image(), None);
+}
+
+#[test]
+fn test_kitty_background_opacity() {
+    let mut term = allowed_terminal();
+    term.process(&kitty_cmd("set-background-opacity", r#"{"opacity":0.25}"#));
+    assert_eq!(term.background_opacity(), Some(0.25));
+    assert_eq!(background_events(&mut term), [BackgroundChange::Opacity]);
+
+    // Clamped to 0.0-1.0
+    term.process(&kitty_cmd("set-background-opacity", r#"{"opacity":3}"#));
+    assert_eq!(term.background_opacity(), Some(1.0));
+
+    // Toggling to the current value restores the default
+    term.process(&kitty_cmd(
+        "set-background-opacity",
+        r#"{"opacity":1.0,"toggle":true}"#,
+    ));
+    assert_eq!(term.background_opacity(), None);
+}
+
+#[test]
+fn test_kitty_command_replies() {
+    let mut term = allowed_terminal();
+    term.process(
+        b"\x1bP@kitty-cmd{\"cmd\":\"set-background-opacity\",\"no_response\":true,\"payload\":{\"opacity\":0.5}}\x1b\\",
+    );
+    assert_eq!(term.background_opacity(), Some(0.5));
+    assert!(term.drain_responses().is_empty());
+
+    term.process(&kitty_cmd("launch", "{}"));
+    let reply = String::from_utf8(term.drain_responses()).unwrap();
+    assert!(reply.contains("Unknown command: launch"));
+    // The DCS is handled, not reported as unsupported
+    assert!(term.unsupported_sequences().is_empty());
+}
+
+#[test]
+fn test_background_survives_reset() {
+    let mut term = allowed_terminal();
+    term.process(&iterm_set_file("/tmp/bg.png"));
+    term.process(b"\x1bc");
+    assert!(term.allow_remote_background());
+    assert!(term.background_image().is_some());
+
+    term.clear_background_requests();
+    assert_eq!(term.background_image(), None);
+}
@@ -0,0 +1,103 @@
+use crate::terminal::{BidiDirection, Terminal};
+
+fn bidi_terminal(cols: usize) -> Terminal {
+    let mut term = Terminal::new(cols, 3);
+    term.set_bidi_enabled(true);
+    term
+}
+
+#[test]
+fn test_bidi_disabled_keeps_logical_order() {
+    let mut term = Terminal::new(20, 3);
+    term.process("abc שלום".as_bytes());
+    assert_eq!(term.visual_line(0).unwrap().trim_end(), "abc שלום");
+    assert!(term.bidi_line(0).unwrap().is_identity());
+}
+
+#[test]
+fn test_bidi_reverses_rtl_run_in_ltr_line() {
+    let mut term = bidi_terminal(20);
+    term.process("abc שלום def".as_bytes());
+
+    assert_eq!(term.visual_line(0).unwrap().trim_end(), "abc םולש def");
+    let line = term.bidi_line(0).unwrap();
+    assert!(!line.rtl);
+    assert_eq!(line.logical_to_visual[4], 7);
+    assert_eq!(line.visual_to_logical[7], 4);
+    assert!(line.is_rtl_at(4));
+    assert!(!line.is_rtl_at(0));
+}
+
+#[test]
+fn test_bidi_auto_detects_rtl_paragraph_per_line() {
+    let mut term = bidi_terminal(10);
+    term.process("שלום\r\nhello".as_bytes());
+
+    // The RTL line is right-aligned, the LTR line is untouched
+    assert_eq!(term.visual_line(0).unwrap(), "      םולש");
+    assert!(term.bidi_line(0).unwrap().rtl);
+    assert_eq!(term.visual_line(1).unwrap().trim_end(), "hello");
+    assert!(term.bidi_line(1).unwrap().is_identity());
+}
+
+#[test]
+fn test_bidi_numbers_and_mirroring_in_rtl_line() {
+    let mut term = bidi_terminal(12);
+    term.process("(שלום) 123".as_bytes());
+
+    // Digits keep their order, brackets are mirrored
+    assert_eq!(term.visual_line(0).unwrap().trim_start(), "123 (םולש)");
+}
+
+#[test]
+fn test_bdsm_explicit_mode_disables_reordering() {
+    let mut term = bidi_terminal(10);
+    term.process("שלום".as_bytes());
+    assert!(term.bidi_active());
+
+    term.process(b"\x1b[8l");
+    assert!(!term.bidi_active());
+    assert_eq!(term.visual_line(0).unwrap().trim_end(), "שלום");
+    term.process(b"\x1b[8$p");
+    assert_eq!(term.drain_responses(), b"\x1b[8;2$y");
+
+    term.process(b"\x1b[8h");
+    assert!(term.bidi_active());
+    term.process(b"\x1b[8$p");
+    assert_eq!(term.drain_responses(), b"\x1b[8;1$y");
+}
+
+#[test]
+fn test_scp_selects_paragraph_direction() {
+    let mut term = bidi_terminal(10);
+    term.process(b"hello");
+
+    term.process(b"\x1b[2 k");
+    assert_eq!(term.bidi_direction(), BidiDirection::Rtl);
+    assert_eq!(term.visual_line(0).unwrap(), "     hello");
+
+    term.process(b"\x1b[1 k");
+    assert_eq!(term.bidi_direction(), BidiDirection::Ltr);
+    term.process(b"\x1b[0 k");
+    assert_eq!(term.bidi_direction(), BidiDirection::Auto);
+}
+
+#[test]
+fn test_bidi_wide_chars_move_with_their_spacer() {
+    let mut term = bidi_terminal(10);
+    term.process("中א".as_bytes());
+    term.process(b"\x1b[2 k");
+
+    let line = term.bidi_line(0).unwrap();
+    let wide = line.logical_to_visual[0];
+    assert_eq!(line.logical_to_visual[1], wide + 1);
+}
+
+#[test]
+fn test_bidi_enabled_survives_reset() {
+    let mut term = bidi_terminal(10);
+    term.process(b"\x1b[8l\x1b[2 k\x1bc");
+    assert!(term.bidi_enabled());
+    assert!(term.bidi_active());
+    assert_eq!(term.bidi_direction(), BidiDirection::Auto);
+}
@@ -0,0 +1,129 @@
+use crate::terminal::{CheckpointConfig, Terminal};
+
+fn byte_checkpoints(interval_bytes: usize, max_checkpoints: usize) -> CheckpointConfig {
+    CheckpointConfig {
+        enabled: true,
+        interval_ms: 0,
+        interval_bytes,
+        max_checkpoints,
+    }
+}
+
+#[test]
+fn test_checkpoints_disabled_by_default() {
+    let mut term = Terminal::new(20, 3);
+    term.process(b"hello");
+    assert!(!term.checkpoint_config().enabled);
+    assert!(term.checkpoints().is_empty());
+}
+
+#[test]
+fn test_checkpoint_every_n_bytes() {
+    let mut term = Terminal::new(20, 3);
+    term.set_checkpoint_config(byte_checkpoints(10, 8));
+
+    // The first output is always checkpointed
+    term.process(b"one");
+    assert_eq!(term.checkpoints().len(), 1);
+    term.process(b" two");
+    assert_eq!(term.checkpoints().len(), 1);
+    term.process(b" three");
+    let checkpoints = term.checkpoints();
+    assert_eq!(checkpoints.len(), 2);
+    assert_eq!(checkpoints[1].bytes_processed, 13);
+
+    // Empty reads never trigger a checkpoint
+    term.process(b"");
+    assert_eq!(term.checkpoints().len(), 2);
+}
+
+#[test]
+fn test_checkpoint_time_interval() {
+    let mut term = Terminal::new(20, 3);
+    term.set_checkpoint_config(CheckpointConfig {
+        enabled: true,
+        interval_ms: 20,
+        interval_bytes: 0,
+        max_checkpoints: 8,
+    });
+    term.process(b"a");
+    term.process(b"b");
+    assert_eq!(term.checkpoints().len(), 1);
+    std::thread::sleep(std::time::Duration::from_millis(30));
+    term.process(b"c");
+    assert_eq!(term.checkpoints().len(), 2);
+}
+
+#[test]
+fn test_rewind_view_shows_past_screen_without_touching_live_state() {
+    let mut term = Terminal::new(20, 3);
+    term.process(b"before");
+    let id = term.checkpoint_now();
+    term.process(b"\x1b[2J\x1b[Hafter");
+
+    let view = term.rewind_view(id).unwrap();
+    assert_eq!(view.info.id, id);
+    assert_eq!(view.text().trim_end(), "before");
+    assert_eq!(view.cursor.col, 6);
+    assert_eq!((view.cols(), view.rows()), (20, 3));
+
+    assert_eq!(term.active_grid().row_text(0).trim_end(), "after");
+    assert!(term.rewind_view(id + 1).is_none());
+}
+
+#[test]
+fn test_checkpoint_ring_is_bounded() {
+    let mut term = Terminal::new(20, 3);
+    term.set_checkpoint_config(byte_checkpoints(1, 3));
+    for i in 0..5 {
+        term.process(format!("{i}").as_bytes());
+    }
+    let ids: Vec<u64> = term.checkpoints().iter().map(|info| info.id).collect();
+    assert_eq!(ids, vec![2, 3, 4]);
+    assert!(term.rewind_view(0).is_none());
+    assert_eq!(term.rewind_view(2).unwrap().text().trim_end(), "012");
+
+    term.set_checkpoint_config(byte_checkpoints(1, 1));
+    assert_eq!(term.checkpoints().len(), 1);
+    term.clear_checkpoints();
+    assert!(term.checkpoints().is_empty());
+}
+
+#[test]
+fn test_checkpoint_at_timestamp() {
+    let mut term = Terminal::new(20, 3);
+    let first = term.checkpoint_now();
+    let taken = term.checkpoints()[0].timestamp;
+    assert_eq!(term.checkpoint_at(taken), Some(first));
+    assert_eq!(term.checkpoint_at(taken + 60_000), Some(first));
+    assert_eq!(term.checkpoint_at(taken - 1), None);
+}
+
+#[test]
+fn test_checkpoint_records_alt_screen() {
+    let mut term = Terminal::new(20, 3);
+    term.process(b"\x1b[?1049hfull screen app");
+    let id = term.checkpoint_now();
+    term.process(b"\x1b[?1049l");
+
+    let view = term.rewind_view(id).unwrap();
+    assert!(view.info.alt_screen);
+    assert_eq!(view.line(0).unwrap().trim_end(), "full screen app");
+}
+
+#[test]
+fn test_checkpoints_survive_reset() {
+    let mut term = Terminal::new(20, 3);
+    term.set_checkpoint_config(byte_checkpoints(1, 8));
+    term.process(b"before");
+    let id = term.checkpoints()[0].id;
+
+    term.process(b"\x1bc");
+    assert!(term.checkpoint_config().enabled);
+    assert_eq!(term.checkpoints().len(), 2);
+    assert_eq!(
+        term.rewind_view(id).unwrap().line(0).unwrap().trim_end(),
+        "before"
+    );
+    assert_eq!(term.checkpoints()[1].bytes_processed, 8);
+}
@@ -0,0 +1,74 @@
+// Undo for destructive screen clears (ED 2 / ED 3)
+use crate::terminal::*;
+
+#[test]
+fn clears_are_not_captured_by_default() {
+    let mut term = Terminal::new(20, 5);
+    term.process(b"hello\x1b[2J");
+    assert_eq!(term.clear_undo_limit(), 0);
+    assert!(term.cleared_screens().is_empty());
+    assert!(!term.restore_last_cleared());
+}
+
+#[test]
+fn restore_brings_back_screen_and_cursor() {
+    let mut term = Terminal::new(20, 5);
+    term.set_clear_undo_limit(4);
+    term.process(b"first\r\nsecond\x1b[2J");
+    assert_eq!(term.grid().row_text(0).trim_end(), "");
+    assert_eq!(term.cleared_screens().len(), 1);
+    assert_eq!(
+        term.cleared_screens()[0].screen_text(),
+        "first\nsecond\n\n\n"
+    );
+
+    assert!(term.restore_last_cleared());
+    assert_eq!(term.grid().row_text(0).trim_end(), "first");
+    assert_eq!(term.grid().row_text(1).trim_end(), "second");
+    assert_eq!((term.cursor().col, term.cursor().row), (6, 1));
+    assert!(term.cleared_screens().is_empty());
+    assert!(!term.restore_last_cleared());
+}
+
+#[test]
+fn clear_command_is_one_undo_step_including_scrollback() {
+    let mut term = Terminal::new(20, 3);
+    term.set_clear_undo_limit(4);
+    term.process(b"a\r\nb\r\nc\r\nd\r\ne");
+    assert_eq!(term.grid().scrollback_len(), 2);
+
+    // What `clear` emits
+    term.process(b"\x1b[H\x1b[2J\x1b[3J");
+    assert_eq!(term.grid().scrollback_len(), 0);
+    assert_eq!(term.cleared_screens().len(), 1);
+    assert!(term.cleared_screens()[0].include_scrollback);
+
+    assert!(term.restore_last_cleared());
+    assert_eq!(term.grid().scrollback_len(), 2);
+    assert_eq!(term.grid().row_text(2).trim_end(), "e");
+}
+
+#[test]
+fn history_is_bounded_and_ignores_blank_and_alt_screens() {
+    let mut term = Terminal::new(20, 3);
+    term.set_clear_undo_limit(2);
+    for text in ["one", "two", "three"] {
+        term.process(format!("\x1b[H{text}\x1b[2J").as_bytes());
+    }
+    // Clearing an already blank screen loses nothing
+    term.process(b"\x1b[2J");
+    // Full-screen applications redraw with ED 2 constantly
+    term.process(b"\x1b[?1049hvim\x1b[2J\x1b[?1049l");
+
+    let texts: Vec<_> = term
+        .cleared_screens()
+        .iter()
+        .map(|c| c.screen_text())
+        .collect();
+    assert_eq!(texts, ["two\n\n", "three\n\n"]);
+
+    term.set_clear_undo_limit(1);
+    assert_eq!(term.cleared_screens().len(), 1);
+    term.set_clear_undo_limit(0);
+    assert!(term.cleared_screens().is_empty());
+}
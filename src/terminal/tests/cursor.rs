@@ -178,3 +178,138 @@ fn test_cursor_style_saved_restored() {
     term.process(b"\x1b8");
     assert_eq!(term.cursor().style(), CursorStyle::BlinkingBar);
 }
+
+fn cursor_style_events(term: &mut Terminal) -> Vec<crate::cursor::CursorStyle> {
+    term.poll_events()
+        .into_iter()
+        .filter_map(|e| match e {
+            TerminalEvent::CursorStyleChanged { style } => Some(style),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_cursor_style_changes_emit_events() {
+    use crate::cursor::CursorStyle;
+    let mut term = Terminal::new(80, 24);
+    term.process(b"[6 q[6 q[0 q");
+    assert_eq!(
+        cursor_style_events(&mut term),
+        [CursorStyle::SteadyBar, CursorStyle::BlinkingBlock]
+    );
+
+    // DECRC reports a restored style that differs
+    term.process(b"[3 q7[2 q");
+    cursor_style_events(&mut term);
+    term.process(b"878");
+    assert_eq!(
+        cursor_style_events(&mut term),
+        [CursorStyle::BlinkingUnderline]
+    );
+
+    // Host-side changes are not application requests
+    term.set_cursor_style(CursorStyle::SteadyBlock);
+    assert!(cursor_style_events(&mut term).is_empty());
+}
+
+#[test]
+fn test_cursor_style_in_pane_state_and_semantic_snapshot() {
+    use crate::cursor::CursorStyle;
+    let mut term = Terminal::new(80, 24);
+    term.process(b"[4 q");
+    let state = term.capture_pane_state("p".to_string(), None);
+    assert_eq!(state.cursor_style, CursorStyle::SteadyUnderline);
+    assert_eq!(
+        term.get_semantic_snapshot(SnapshotScope::Visible)
+            .cursor_style,
+        CursorStyle::SteadyUnderline
+    );
+
+    let mut other = Terminal::new(80, 24);
+    other.restore_pane_state(&state);
+    assert_eq!(other.cursor().style(), CursorStyle::SteadyUnderline);
+}
+
+#[test]
+fn test_cursor_trail_disabled_by_default() {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"hello\x1b[5;10H");
+    assert!(!term.is_cursor_trail_enabled());
+    assert!(term.cursor_motion_history(10).is_empty());
+    assert!(!term
+        .poll_events()
+        .iter()
+        .any(|e| matches!(e, TerminalEvent::CursorMoved { .. })));
+}
+
+#[test]
+fn test_cursor_trail_distinguishes_text_from_jumps() {
+    let mut term = Terminal::new(80, 24);
+    term.set_cursor_trail_enabled(true);
+    term.process(b"abc\x1b[5;10Hxy");
+
+    let history = term.cursor_motion_history(10);
+    let summary: Vec<_> = history.iter().map(|m| (m.from, m.to, m.cause)).collect();
+    assert_eq!(
+        summary,
+        vec![
+            ((0, 0), (3, 0), CursorMoveCause::Text),
+            ((3, 0), (9, 4), CursorMoveCause::EscapeSequence),
+            ((9, 4), (11, 4), CursorMoveCause::Text),
+        ]
+    );
+
+    let events: Vec<_> = term
+        .poll_events()
+        .into_iter()
+        .filter_map(|e| match e {
+            TerminalEvent::CursorMoved { from, to, cause } => Some((from, to, cause)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(events, summary);
+}
+
+#[test]
+fn test_cursor_trail_ignores_non_moving_sequences_and_resize() {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"\x1b[20;70H");
+    term.set_cursor_trail_enabled(true);
+    term.process(b"\x1b[1m\x1b[?25l");
+    term.resize(40, 10);
+    term.process(b"\x1b[0m");
+    assert!(term.cursor_motion_history(10).is_empty());
+}
+
+#[test]
+fn test_cursor_trail_settings_survive_ris() {
+    let mut term = Terminal::new(80, 24);
+    term.set_cursor_trail_enabled(true);
+    term.set_max_cursor_motion_history(3);
+    term.process(b"\x1bc\x1b[5;10H");
+    assert!(term.is_cursor_trail_enabled());
+    assert_eq!(term.max_cursor_motion_history(), 3);
+    let history = term.cursor_motion_history(10);
+    assert_eq!(
+        history.last().map(|m| (m.from, m.to)),
+        Some(((0, 0), (9, 4)))
+    );
+}
+
+#[test]
+fn test_cursor_trail_history_is_bounded() {
+    let mut term = Terminal::new(80, 24);
+    term.set_cursor_trail_enabled(true);
+    term.set_max_cursor_motion_history(3);
+    for row in 1..=6 {
+        term.process(format!("\x1b[{};1H", row + 1).as_bytes());
+    }
+    let history = term.cursor_motion_history(10);
+    assert_eq!(history.len(), 3);
+    assert_eq!(history[2].to, (0, 6));
+    assert_eq!(term.cursor_motion_history(1), vec![history[2]]);
+
+    term.clear_cursor_motion_history();
+    assert!(term.cursor_motion_history(10).is_empty());
+}
@@ -0,0 +1,97 @@
+// Double buffering tests
+use crate::terminal::*;
+
+fn front_line(term: &Terminal, row: usize) -> String {
+    term.front_buffer()
+        .line(row)
+        .unwrap()
+        .trim_end()
+        .to_string()
+}
+
+#[test]
+fn front_buffer_is_live_while_double_buffering_is_off() {
+    let mut term = Terminal::new(20, 5);
+    assert!(!term.double_buffering());
+    term.process(b"\x1b[?2026hhalf");
+    assert_eq!(front_line(&term, 0), "half");
+    assert_eq!(term.committed_frames(), 0);
+}
+
+#[test]
+fn each_chunk_commits_a_frame() {
+    let mut term = Terminal::new(20, 5);
+    term.set_double_buffering(true);
+    assert_eq!(term.committed_frames(), 1);
+    term.process(b"one");
+    assert_eq!(front_line(&term, 0), "one");
+    term.process(b" two");
+    assert_eq!(front_line(&term, 0), "one two");
+    assert_eq!(term.committed_frames(), 3);
+}
+
+#[test]
+fn synchronized_update_holds_the_front_buffer() {
+    let mut term = Terminal::new(20, 5);
+    term.set_double_buffering(true);
+    term.process(b"old frame");
+    term.process(b"\x1b[?2026h\x1b[2J\x1b[Hnew");
+    term.process(b" frame");
+    // Readers still see the frame from before the update
+    assert_eq!(front_line(&term, 0), "old frame");
+    assert!(!term.front_buffer().modes().synchronized_updates);
+
+    term.process(b"\x1b[?2026l");
+    assert_eq!(front_line(&term, 0), "new frame");
+}
+
+#[test]
+fn flushing_a_synchronized_update_commits_the_frame() {
+    let mut term = Terminal::new(20, 5);
+    term.set_double_buffering(true);
+    term.process(b"\x1b[?2026hpart one");
+    assert_eq!(front_line(&term, 0), "");
+    term.flush_synchronized_updates();
+    assert_eq!(front_line(&term, 0), "part one");
+    assert!(term.synchronized_updates());
+}
+
+#[test]
+fn commit_frame_publishes_api_changes() {
+    let mut term = Terminal::new(20, 5);
+    term.set_double_buffering(true);
+    term.process(b"text");
+    term.resize(30, 6);
+    assert_eq!(term.front_buffer().cols(), 20);
+    term.commit_frame();
+    assert_eq!(term.front_buffer().cols(), 30);
+}
+
+#[test]
+fn front_buffer_handles_are_shared_until_the_next_commit() {
+    let mut term = Terminal::new(20, 5);
+    term.set_double_buffering(true);
+    term.process(b"text");
+    let first = term.front_buffer();
+    assert!(term.front_buffer().ptr_eq(&first));
+    term.process(b"more");
+    assert!(!term.front_buffer().ptr_eq(&first));
+}
+
+#[test]
+fn double_buffering_survives_ris() {
+    let mut term = Terminal::new(20, 5);
+    term.set_double_buffering(true);
+    term.process(b"before\x1bcafter");
+    assert!(term.double_buffering());
+    assert_eq!(front_line(&term, 0), "after");
+}
+
+#[test]
+fn turning_double_buffering_off_shows_the_live_screen() {
+    let mut term = Terminal::new(20, 5);
+    term.set_double_buffering(true);
+    term.process(b"\x1b[?2026hpending");
+    term.set_double_buffering(false);
+    assert_eq!(front_line(&term, 0), "pending");
+}
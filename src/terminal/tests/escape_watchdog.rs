@@ -0,0 +1,126 @@
+//! Unterminated OSC/DCS string watchdog tests
+
+use crate::terminal::{
+    EscapeAbortReason, EscapeStringKind, EscapeWatchdogConfig, Terminal, TerminalEvent,
+};
+
+fn line(term: &Terminal) -> String {
+    term.export_text().lines().next().unwrap_or("").to_string()
+}
+
+fn watchdog(max_bytes: usize, timeout_ms: u64) -> EscapeWatchdogConfig {
+    EscapeWatchdogConfig {
+        enabled: true,
+        max_bytes,
+        timeout_ms,
+    }
+}
+
+fn aborts(term: &mut Terminal) -> Vec<(EscapeStringKind, EscapeAbortReason, usize)> {
+    term.poll_events()
+        .into_iter()
+        .filter_map(|event| match event {
+            TerminalEvent::EscapeStringAborted {
+                kind,
+                reason,
+                length,
+            } => Some((kind, reason, length)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn disabled_by_default_unterminated_osc_swallows_output() {
+    let mut term = Terminal::new(80, 24);
+    assert!(!term.escape_watchdog_config().enabled);
+    term.process(b"\x1b]0;title");
+    term.process(b"hidden");
+    assert_eq!(line(&term), "");
+    assert!(!term.update_escape_watchdog());
+}
+
+#[test]
+fn terminated_strings_under_the_cap_are_handled() {
+    let mut term = Terminal::new(80, 24);
+    term.set_escape_watchdog_config(watchdog(64, 60_000));
+    term.process(b"\x1b]0;hello\x07ok");
+    assert_eq!(term.title(), "hello");
+    assert_eq!(line(&term), "ok");
+    assert!(aborts(&mut term).is_empty());
+}
+
+#[test]
+fn oversized_osc_is_cancelled_and_parsing_resumes() {
+    let mut term = Terminal::new(80, 24);
+    term.set_escape_watchdog_config(watchdog(8, 60_000));
+    term.process(b"\x1b]0;abcdef");
+    term.process(b"XYZ \x1b[1mbold");
+    assert_eq!(term.title(), "");
+    assert_eq!(line(&term), "YZ bold");
+    assert!(term.active_grid().get(3, 0).unwrap().flags.bold());
+    assert_eq!(
+        aborts(&mut term),
+        vec![(EscapeStringKind::Osc, EscapeAbortReason::TooLong, 8)]
+    );
+}
+
+#[test]
+fn oversized_dcs_is_discarded() {
+    let mut term = Terminal::new(80, 24);
+    term.set_escape_watchdog_config(watchdog(16, 60_000));
+    term.process(b"\x1bPq#0;2;100;0;0#0~~~~~~~~~~~~~~~~~~~~");
+    term.process(b"\r\x1b[Kafter");
+    assert_eq!(term.graphics_count(), 0);
+    assert_eq!(line(&term), "after");
+    assert_eq!(
+        aborts(&mut term),
+        vec![(EscapeStringKind::Dcs, EscapeAbortReason::TooLong, 16)]
+    );
+}
+
+#[test]
+fn timeout_cancels_string_on_next_output() {
+    let mut term = Terminal::new(80, 24);
+    term.set_escape_watchdog_config(watchdog(1 << 20, 0));
+    term.process(b"\x1b]0;stalled");
+    term.process(b"visible");
+    assert_eq!(term.title(), "");
+    assert_eq!(line(&term), "visible");
+    assert_eq!(
+        aborts(&mut term),
+        vec![(EscapeStringKind::Osc, EscapeAbortReason::Timeout, 9)]
+    );
+}
+
+#[test]
+fn update_cancels_stalled_string_without_output() {
+    let mut term = Terminal::new(80, 24);
+    term.set_escape_watchdog_config(watchdog(1 << 20, 0));
+    assert!(!term.update_escape_watchdog());
+    term.process(b"\x1bP1$rstalled");
+    assert!(term.update_escape_watchdog());
+    assert!(!term.update_escape_watchdog());
+    assert_eq!(aborts(&mut term).len(), 1);
+    term.process(b"text");
+    assert_eq!(line(&term), "text");
+    assert!(term.drain_responses().is_empty());
+}
+
+#[test]
+fn length_guard_overflow_ends_at_the_watchdog_cap() {
+    let mut term = Terminal::new(80, 24);
+    term.set_max_osc_data_length(4);
+    term.set_escape_watchdog_config(watchdog(10, 60_000));
+    term.process(b"\x1b]0;abcdefghijklmn");
+    assert_eq!(line(&term), "jklmn");
+    assert_eq!(aborts(&mut term).len(), 1);
+}
+
+#[test]
+fn config_survives_ris() {
+    let mut term = Terminal::new(80, 24);
+    term.set_escape_watchdog_config(watchdog(32, 500));
+    term.process(b"\x1bc");
+    assert_eq!(term.escape_watchdog_config(), watchdog(32, 500));
+}
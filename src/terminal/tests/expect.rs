@@ -0,0 +1,97 @@
+// Expect-style matching tests
+use crate::terminal::*;
+
+fn expecting() -> Terminal {
+    let mut term = Terminal::new(40, 10);
+    term.set_expect_buffer(Some(DEFAULT_EXPECT_BUFFER_BYTES));
+    term
+}
+
+#[test]
+fn test_expect_disabled_by_default() {
+    let mut term = Terminal::new(40, 10);
+    term.process(b"login: ");
+    let patterns = ExpectPatterns::new(["login:"]).unwrap();
+    assert!(!term.expect_buffer_enabled());
+    assert!(term.expect_match(&patterns).is_none());
+}
+
+#[test]
+fn test_expect_records_printed_text_without_escapes() {
+    let mut term = expecting();
+    term.process(b"\x1b[1;32mok\x1b[0m\r\n\tdone\x1b]0;title\x07 caf\xc3\xa9");
+    assert_eq!(term.expect_buffer(), Some("ok\n\tdone caf\u{e9}"));
+}
+
+#[test]
+fn test_expect_match_returns_before_groups_and_consumes() {
+    let mut term = expecting();
+    term.process(b"Building...\r\nexit code 3\r\n$ ");
+
+    let patterns = ExpectPatterns::new([r"exit code (\d+)(x)?", r"\$ $"]).unwrap();
+    let found = term.expect_match(&patterns).unwrap();
+    assert_eq!(found.index, 0);
+    assert_eq!(found.before, "Building...\n");
+    assert_eq!(found.matched, "exit code 3");
+    assert_eq!(found.groups, vec![Some("3".to_string()), None]);
+    assert_eq!(term.expect_buffer(), Some("\n$ "));
+
+    let found = term.expect_match(&patterns).unwrap();
+    assert_eq!(found.index, 1);
+    assert_eq!(found.before, "\n");
+    assert_eq!(term.expect_buffer(), Some(""));
+    assert!(term.expect_match(&patterns).is_none());
+}
+
+#[test]
+fn test_expect_prefers_earliest_match_then_lowest_index() {
+    let mut term = expecting();
+    term.process(b"warning then error");
+
+    let found = term
+        .expect_match(&ExpectPatterns::new(["error", "warning"]).unwrap())
+        .unwrap();
+    assert_eq!((found.index, found.matched.as_str()), (1, "warning"));
+
+    term.process(b" fail");
+    let found = term
+        .expect_match(&ExpectPatterns::new(["fail", "f(ai)l"]).unwrap())
+        .unwrap();
+    assert_eq!(found.index, 0);
+}
+
+#[test]
+fn test_expect_buffer_keeps_newest_text_within_limit() {
+    let mut term = Terminal::new(40, 10);
+    term.set_expect_buffer(Some(8));
+    term.process("0123456789é".as_bytes());
+    // 'é' is two bytes; the oldest text is dropped on a char boundary
+    assert_eq!(term.expect_buffer(), Some("456789é"));
+
+    term.set_expect_buffer(Some(3));
+    assert_eq!(term.expect_buffer(), Some("9é"));
+    term.clear_expect_buffer();
+    assert_eq!(term.expect_buffer(), Some(""));
+    term.set_expect_buffer(None);
+    assert_eq!(term.expect_buffer(), None);
+}
+
+#[test]
+fn test_expect_patterns_reject_invalid_and_empty() {
+    assert!(matches!(
+        ExpectPatterns::new(["("]),
+        Err(ExpectError::InvalidPattern(_))
+    ));
+    assert!(matches!(
+        ExpectPatterns::new(Vec::<&str>::new()),
+        Err(ExpectError::InvalidPattern(_))
+    ));
+}
+
+#[test]
+fn test_expect_buffer_survives_reset() {
+    let mut term = expecting();
+    term.process(b"before\x1bcafter");
+    assert!(term.expect_buffer_enabled());
+    assert_eq!(term.expect_buffer(), Some("beforeafter"));
+}
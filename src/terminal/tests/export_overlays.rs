@@ -0,0 +1,106 @@
+// Selection and search-match overlay tests for screenshots and HTML export
+use crate::color::Color;
+use crate::screenshot::{ImageFormat, ScreenshotConfig};
+use crate::terminal::*;
+
+fn overlay_term() -> Terminal {
+    let mut term = Terminal::new(20, 3);
+    term.set_selection_bg_color(Color::Rgb(10, 20, 30));
+    term.set_match_color(Color::Rgb(200, 100, 50));
+    term.process(b"alpha beta gamma");
+    term
+}
+
+#[test]
+fn test_html_overlays_disabled_by_default() {
+    let mut term = overlay_term();
+    term.set_selection((0, 0), (5, 0), SelectionMode::Character);
+    term.search("beta", RegexSearchOptions::default()).unwrap();
+
+    let html = term.export_html_with_overlays(false, ExportOverlays::default());
+    assert!(!html.contains("rgb(10, 20, 30)"));
+    assert!(!html.contains("rgb(200, 100, 50)"));
+}
+
+#[test]
+fn test_html_overlays_paint_selection_and_matches() {
+    let mut term = overlay_term();
+    term.set_selection((0, 0), (5, 0), SelectionMode::Character);
+    term.search("gamma", RegexSearchOptions::default()).unwrap();
+
+    let html = term.export_html_with_overlays(false, ExportOverlays::all());
+    assert!(html.contains("background-color: rgb(10, 20, 30)"));
+    assert!(html.contains("background-color: rgb(200, 100, 50)"));
+
+    let selection_only = term.export_html_with_overlays(
+        false,
+        ExportOverlays {
+            selection: true,
+            search_matches: false,
+        },
+    );
+    assert!(selection_only.contains("rgb(10, 20, 30)"));
+    assert!(!selection_only.contains("rgb(200, 100, 50)"));
+}
+
+#[test]
+fn test_selection_wins_over_search_match() {
+    let mut term = overlay_term();
+    term.set_selection((0, 0), (5, 0), SelectionMode::Character);
+    term.search("alpha", RegexSearchOptions::default()).unwrap();
+
+    let html = term.export_html_with_overlays(false, ExportOverlays::all());
+    assert!(html.contains("rgb(10, 20, 30)"));
+    assert!(!html.contains("rgb(200, 100, 50)"));
+}
+
+#[test]
+fn test_screenshot_paints_selection() {
+    let mut term = overlay_term();
+    // Select the blank tail of the first row so no glyph covers the probe
+    term.set_selection((16, 0), (20, 0), SelectionMode::Character);
+    term.search("beta", RegexSearchOptions::default()).unwrap();
+
+    let config = ScreenshotConfig {
+        format: ImageFormat::Png,
+        padding_px: 0,
+        ..Default::default()
+    };
+    let pixel = |config: ScreenshotConfig| {
+        let png = term.screenshot(config, 0).unwrap();
+        let image = ::image::load_from_memory(&png).unwrap().to_rgba8();
+        let (width, height) = image.dimensions();
+        let cell_w = width / 20;
+        let cell_h = height / 3;
+        let p = image.get_pixel(18 * cell_w + cell_w / 2, cell_h / 2);
+        (p[0], p[1], p[2])
+    };
+
+    assert_ne!(pixel(config.clone()), (10, 20, 30));
+    assert_eq!(pixel(config.with_selection(true)), (10, 20, 30));
+}
+
+#[test]
+fn test_screenshot_cursor_guide_follows_terminal_unless_set() {
+    let mut term = Terminal::new(20, 3);
+    term.set_use_cursor_guide(true);
+    term.set_cursor_guide_color(Color::Rgb(255, 0, 0));
+    term.process(b"ab");
+
+    let config = ScreenshotConfig {
+        format: ImageFormat::Png,
+        padding_px: 0,
+        background_color: Some((0, 0, 0)),
+        ..Default::default()
+    };
+    let red_on_cursor_line = |config: ScreenshotConfig| {
+        let png = term.screenshot(config, 0).unwrap();
+        let image = ::image::load_from_memory(&png).unwrap().to_rgba8();
+        let (width, height) = image.dimensions();
+        let p = image.get_pixel(10 * (width / 20), height / 6);
+        p[0]
+    };
+
+    assert!(red_on_cursor_line(config.clone()) > 0);
+    assert_eq!(red_on_cursor_line(config.with_cursor_guide(false, None)), 0);
+}
@@ -1,4 +1,7 @@
-use crate::ffi::SharedState;
+use crate::ffi::{
+    terminal_drain_responses, terminal_free, terminal_new, terminal_poll_events, terminal_process,
+    terminal_resize, terminal_snapshot_json, terminal_string_free, SharedState,
+};
 use crate::terminal::Terminal;
 use std::ffi::CStr;
 
@@ -63,3 +66,60 @@ fn test_shared_state_drop_safety() {
     let state = SharedState::from_terminal(&term);
     drop(state); // Should not panic or leak
 }
+
+#[test]
+fn test_c_api_lifecycle() {
+    let term = terminal_new(20, 3);
+    assert!(!term.is_null());
+    assert!(terminal_new(0, 3).is_null());
+
+    unsafe {
+        let input = b"\x1b]2;demo\x07hello\x1b[6n";
+        terminal_process(term, input.as_ptr(), input.len());
+        assert!(terminal_resize(term, 30, 4));
+        assert!(!terminal_resize(term, 0, 4));
+        assert_eq!((*term).size(), (30, 4));
+
+        let snapshot = terminal_snapshot_json(term);
+        let json = CStr::from_ptr(snapshot).to_str().unwrap().to_string();
+        terminal_string_free(snapshot);
+        assert!(json.contains(r#""type":"screen_snapshot""#));
+        assert!(json.contains(r#""text":"hello""#));
+
+        let events = terminal_poll_events(term);
+        let json = CStr::from_ptr(events).to_str().unwrap().to_string();
+        terminal_string_free(events);
+        assert!(json.contains(r#""type":"title""#));
+
+        // Too small a buffer reports the size and keeps the reply queued
+        let mut buf = [0u8; 16];
+        assert_eq!(terminal_drain_responses(term, buf.as_mut_ptr(), 2), 6);
+        assert_eq!(
+            terminal_drain_responses(term, buf.as_mut_ptr(), buf.len()),
+            6
+        );
+        assert_eq!(&buf[..6], b"\x1b[1;6R");
+        assert_eq!(
+            terminal_drain_responses(term, buf.as_mut_ptr(), buf.len()),
+            0
+        );
+
+        terminal_free(term);
+    }
+}
+
+#[test]
+fn test_c_header_declares_every_export() {
+    let source = include_str!("../../ffi.rs");
+    let header = include_str!("../../../include/par_term_emu.h");
+    for line in source.lines() {
+        let Some(rest) = line.split("extern \"C\" fn ").nth(1) else {
+            continue;
+        };
+        let name = rest.split('(').next().unwrap();
+        assert!(
+            header.contains(&format!(" {name}(")) || header.contains(&format!("*{name}(")),
+            "{name} missing from include/par_term_emu.h; run `make ffi-header`"
+        );
+    }
+}
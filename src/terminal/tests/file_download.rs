@@ -0,0 +1,216 @@
+//! Tests for iTerm2 OSC 1337 file downloads, the download sink and FileReceived
+
+use std::io;
+use std::sync::{Arc, Mutex};
+
+use base64::Engine;
+
+use crate::terminal::file_transfer::{DirectoryDownloadSink, DownloadSink, TransferId};
+use crate::terminal::{Terminal, TerminalEvent};
+
+fn b64(data: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(data)
+}
+
+/// OSC 1337 single-sequence download, as sent by `it2dl`
+fn download(name: &str, data: &[u8]) -> Vec<u8> {
+    format!(
+        "\x1b]1337;File=name={};size={}:{}\x07",
+        b64(name.as_bytes()),
+        data.len(),
+        b64(data)
+    )
+    .into_bytes()
+}
+
+fn received(term: &mut Terminal) -> Vec<(String, usize, bool)> {
+    term.poll_events()
+        .into_iter()
+        .filter_map(|e| match e {
+            TerminalEvent::FileReceived {
+                name, size, sink, ..
+            } => Some((name, size, sink)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Records sink calls; fails writes once `fail_after` bytes were written
+#[derive(Default)]
+struct RecordingSink {
+    log: Arc<Mutex<Vec<String>>>,
+    written: usize,
+    fail_after: Option<usize>,
+}
+
+impl DownloadSink for RecordingSink {
+    fn begin(&mut self, id: TransferId, filename: &str, total: Option<usize>) -> io::Result<()> {
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("begin {id} {filename} {total:?}"));
+        Ok(())
+    }
+
+    fn write(&mut self, id: TransferId, data: &[u8]) -> io::Result<()> {
+        self.written += data.len();
+        if self.fail_after.is_some_and(|limit| self.written > limit) {
+            return Err(io::Error::other("disk full"));
+        }
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("write {id} {}", String::from_utf8_lossy(data)));
+        Ok(())
+    }
+
+    fn finish(&mut self, id: TransferId) -> io::Result<()> {
+        self.log.lock().unwrap().push(format!("finish {id}"));
+        Ok(())
+    }
+
+    fn abort(&mut self, id: TransferId) {
+        self.log.lock().unwrap().push(format!("abort {id}"));
+    }
+}
+
+#[test]
+fn download_without_sink_is_buffered() {
+    let mut term = Terminal::new(80, 24);
+    term.process(&download("notes.txt", b"hello"));
+
+    assert_eq!(
+        received(&mut term),
+        vec![("notes.txt".to_string(), 5, false)]
+    );
+    let transfer = term.get_completed_transfers().pop().unwrap();
+    assert_eq!(transfer.size, 5);
+    assert_eq!(
+        term.take_completed_transfer(transfer.id).unwrap().data,
+        b"hello"
+    );
+}
+
+#[test]
+fn multipart_download_streams_chunks_to_sink() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut term = Terminal::new(80, 24);
+    term.set_download_sink(RecordingSink {
+        log: Arc::clone(&log),
+        ..Default::default()
+    });
+
+    term.process(format!("\x1b]1337;MultipartFile=name={};size=10\x07", b64(b"a.bin")).as_bytes());
+    term.process(format!("\x1b]1337;FilePart={}\x07", b64(b"hello")).as_bytes());
+    term.process(format!("\x1b]1337;FilePart={}\x07", b64(b"world")).as_bytes());
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            "begin 1 a.bin Some(10)",
+            "write 1 hello",
+            "write 1 world",
+            "finish 1"
+        ]
+    );
+    assert_eq!(received(&mut term), vec![("a.bin".to_string(), 10, true)]);
+    let transfer = term.get_completed_transfers().pop().unwrap();
+    assert!(transfer.data.is_empty());
+    assert_eq!(transfer.size, 10);
+}
+
+#[test]
+fn sink_error_fails_the_download() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut term = Terminal::new(80, 24);
+    term.set_download_sink(RecordingSink {
+        log: Arc::clone(&log),
+        fail_after: Some(3),
+        ..Default::default()
+    });
+
+    term.process(&download("big.bin", b"0123456789"));
+
+    let events = term.poll_events();
+    assert!(events.iter().any(|e| matches!(
+        e,
+        TerminalEvent::FileTransferFailed { reason, .. } if reason.contains("disk full")
+    )));
+    assert!(!events
+        .iter()
+        .any(|e| matches!(e, TerminalEvent::FileReceived { .. })));
+    assert_eq!(log.lock().unwrap().last().unwrap(), "abort 1");
+}
+
+#[test]
+fn size_limit_applies_to_sink_downloads() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut term = Terminal::new(80, 24);
+    term.set_max_transfer_size(4);
+    term.set_download_sink(RecordingSink {
+        log: Arc::clone(&log),
+        ..Default::default()
+    });
+
+    term.process(&download("big.bin", b"0123456789"));
+
+    assert!(received(&mut term).is_empty());
+    assert!(log.lock().unwrap().is_empty(), "nothing reaches the sink");
+}
+
+#[test]
+fn directory_sink_saves_files_without_overwriting() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut term = Terminal::new(80, 24);
+    term.set_download_sink(DirectoryDownloadSink::new(dir.path()));
+
+    term.process(&download("../report.txt", b"first"));
+    term.process(&download("report.txt", b"second"));
+    term.process(&download("", b""));
+
+    let read = |name: &str| std::fs::read(dir.path().join(name)).unwrap();
+    assert_eq!(read("report.txt"), b"first");
+    assert_eq!(read("report (1).txt"), b"second");
+    assert_eq!(read("download-3"), b"");
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 3);
+}
+
+#[test]
+fn directory_sink_removes_partial_file_on_failure() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut term = Terminal::new(80, 24);
+    term.set_download_sink(DirectoryDownloadSink::new(dir.path()));
+
+    term.process(
+        format!(
+            "\x1b]1337;MultipartFile=name={};size=10\x07",
+            b64(b"partial.bin")
+        )
+        .as_bytes(),
+    );
+    term.process(format!("\x1b]1337;FilePart={}\x07", b64(b"hello")).as_bytes());
+    assert!(dir.path().join("partial.bin.part").exists());
+
+    assert!(term.cancel_file_transfer(1));
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn replacing_sink_fails_streaming_downloads_and_reset_keeps_sink() {
+    let mut term = Terminal::new(80, 24);
+    term.set_download_sink(RecordingSink::default());
+    term.process(b"\x1b]1337;MultipartFile=size=10\x07");
+    term.process(format!("\x1b]1337;FilePart={}\x07", b64(b"hello")).as_bytes());
+    term.poll_events();
+
+    term.set_download_sink(RecordingSink::default());
+    assert!(term.poll_events().iter().any(|e| matches!(
+        e,
+        TerminalEvent::FileTransferFailed { id: 1, reason } if reason == "download sink replaced"
+    )));
+
+    term.process(b"\x1bc");
+    assert!(term.has_download_sink());
+    term.clear_download_sink();
+    assert!(!term.has_download_sink());
+}
@@ -0,0 +1,122 @@
+// Command output folding tests
+use crate::terminal::*;
+
+/// Run one OSC 133 command cycle that prints `lines` output lines
+fn run_command(term: &mut Terminal, command: &str, lines: usize) {
+    term.process(b"\x1b]133;A\x07$ \x1b]133;B\x07");
+    term.process(command.as_bytes());
+    term.process(format!("\r\n\x1b]133;C;{}\x07", command).as_bytes());
+    for i in 0..lines {
+        term.process(format!("{} line {}\r\n", command, i).as_bytes());
+    }
+    term.process(b"\x1b]133;D;0\x07");
+}
+
+#[test]
+fn test_fold_command_counts_from_most_recent() {
+    let mut term = Terminal::new(40, 24);
+    run_command(&mut term, "first", 3);
+    run_command(&mut term, "second", 2);
+
+    assert!(term.fold_command(1));
+    assert!(term.is_command_folded(1));
+    assert!(!term.is_command_folded(0));
+    assert!(!term.fold_command(2));
+
+    let ranges = term.folded_ranges();
+    assert_eq!(ranges.len(), 1);
+    assert_eq!(ranges[0].command.as_deref(), Some("first"));
+    assert_eq!((ranges[0].abs_row_start, ranges[0].abs_row_end), (1, 3));
+    assert_eq!(ranges[0].summary(), "⋯ 3 lines folded (first)");
+
+    assert!(term.unfold_command(1));
+    assert!(term.folded_ranges().is_empty());
+}
+
+#[test]
+fn test_set_zone_folded_only_accepts_output_zones() {
+    let mut term = Terminal::new(40, 24);
+    run_command(&mut term, "ls", 1);
+    let zones = term.get_zones().to_vec();
+    let prompt = zones
+        .iter()
+        .find(|z| z.zone_type == crate::zone::ZoneType::Prompt);
+    let output = zones
+        .iter()
+        .find(|z| z.zone_type == crate::zone::ZoneType::Output);
+
+    assert!(!term.set_zone_folded(prompt.unwrap().id, true));
+    assert!(term.set_zone_folded(output.unwrap().id, true));
+    assert!(term.get_zones().iter().any(|z| z.folded));
+    assert!(!term.set_zone_folded(9999, true));
+
+    term.unfold_all();
+    assert!(term.folded_ranges().is_empty());
+}
+
+#[test]
+fn test_export_text_folded_replaces_output_with_summary() {
+    let mut term = Terminal::new(40, 24);
+    run_command(&mut term, "build", 3);
+    run_command(&mut term, "test", 1);
+    term.fold_command(1);
+
+    let text = term.export_text_folded();
+    assert!(!text.contains("build line"));
+    assert!(text.contains("⋯ 3 lines folded (build)"));
+    assert!(text.contains("test line 0"));
+    // Unfolded exports are unchanged
+    assert!(term.export_text().contains("build line 2"));
+}
+
+#[test]
+fn test_folds_survive_scrolling() {
+    let mut term = Terminal::with_scrollback(40, 5, 100);
+    run_command(&mut term, "cat", 3);
+    term.fold_command(0);
+    run_command(&mut term, "ls", 10);
+
+    let ranges = term.folded_ranges();
+    assert_eq!(ranges.len(), 1);
+    assert_eq!(ranges[0].command.as_deref(), Some("cat"));
+    // Now in scrollback, still elided
+    assert!(ranges[0].abs_row_end < term.get_scrollback_usage());
+    let snapshot = term.get_semantic_snapshot_folded(SnapshotScope::Full);
+    let scrollback = snapshot.scrollback_text.unwrap();
+    assert!(scrollback.contains("folded (cat)"));
+    assert!(!scrollback.contains("cat line"));
+    assert!(scrollback.contains("ls line 0"));
+}
+
+#[test]
+fn test_zones_and_folds_survive_full_scrollback() {
+    let mut term = Terminal::with_scrollback(40, 5, 10);
+    for i in 0..100 {
+        term.process(format!("filler {}\r\n", i).as_bytes());
+    }
+    run_command(&mut term, "make", 3);
+    assert!(term.fold_command(0));
+    term.start_command_execution("ls".to_string());
+    run_command(&mut term, "ls", 2);
+    term.end_command_execution(Some(0));
+
+    let ranges = term.folded_ranges();
+    assert_eq!(ranges.len(), 1);
+    let text = term
+        .get_zone_text(ranges[0].abs_row_start)
+        .expect("zone still in scrollback");
+    assert!(text.starts_with("make line 0"));
+    assert!(text.contains("make line 2"));
+    assert!(term
+        .get_command_output(0)
+        .unwrap()
+        .output
+        .starts_with("ls line 0\nls line 1"));
+
+    // Once the output scrolls out for good, the zone and fold go with it
+    for i in 0..30 {
+        term.process(format!("filler {}\r\n", i).as_bytes());
+    }
+    assert!(term.folded_ranges().is_empty());
+    assert!(term.get_command_output(0).is_none());
+}
@@ -0,0 +1,115 @@
+// Pixel hit-testing tests
+use crate::terminal::*;
+
+fn term_with_metrics() -> Terminal {
+    let mut term = Terminal::with_scrollback(20, 4, 100);
+    term.set_cell_dimensions(10, 20);
+    term
+}
+
+#[test]
+fn test_hit_test_maps_pixels_to_cell() {
+    let mut term = term_with_metrics();
+    term.process(b"abc");
+
+    let hit = term.hit_test(25.0, 5.0, 0).unwrap();
+    assert_eq!((hit.col, hit.row), (2, 0));
+    assert_eq!(hit.screen_row, Some(0));
+    assert_eq!(hit.cell.c, 'c');
+    assert!(hit.hyperlink_id.is_none());
+    assert!(hit.detected_item.is_none());
+    assert!(!hit.selected);
+}
+
+#[test]
+fn test_hit_test_outside_grid_is_none() {
+    let term = term_with_metrics();
+    assert!(term.hit_test(-1.0, 0.0, 0).is_none());
+    assert!(term.hit_test(200.0, 0.0, 0).is_none());
+    assert!(term.hit_test(0.0, 80.0, 0).is_none());
+    assert!(term.hit_test(f64::NAN, 0.0, 0).is_none());
+}
+
+#[test]
+fn test_hit_test_wide_char_right_half() {
+    let mut term = term_with_metrics();
+    term.process("x中".as_bytes());
+
+    let hit = term.hit_test(25.0, 0.0, 0).unwrap();
+    assert_eq!(hit.col, 1);
+    assert_eq!(hit.cell.c, '中');
+}
+
+#[test]
+fn test_hit_test_hyperlink_and_detected_url() {
+    let mut term = term_with_metrics();
+    term.process(b"\x1b]8;;https://a.test\x1b\\link\x1b]8;;\x1b\\\r\n");
+    term.process(b"go https://b.test/x");
+
+    let hit = term.hit_test(15.0, 0.0, 0).unwrap();
+    assert!(hit.hyperlink_id.is_some());
+    assert_eq!(hit.hyperlink_url.as_deref(), Some("https://a.test"));
+
+    let hit = term.hit_test(75.0, 25.0, 0).unwrap();
+    assert!(matches!(
+        hit.detected_item,
+        Some(DetectedItem::Url(ref url, 3, 1)) if url == "https://b.test/x"
+    ));
+    let hit = term.hit_test(15.0, 25.0, 0).unwrap();
+    assert!(hit.detected_item.is_none());
+}
+
+#[test]
+fn test_hit_test_detected_item_ends_at_its_last_column() {
+    let mut term = term_with_metrics();
+    // 15 columns but 18 bytes of URL
+    term.process("https://b.t/\u{e9}\u{e9}\u{e9} xy".as_bytes());
+
+    let hit = term.hit_test(145.0, 5.0, 0).unwrap();
+    assert!(matches!(hit.detected_item, Some(DetectedItem::Url(..))));
+    let hit = term.hit_test(165.0, 5.0, 0).unwrap();
+    assert_eq!(hit.cell.c, 'x');
+    assert!(hit.detected_item.is_none());
+}
+
+#[test]
+fn test_hit_test_selection() {
+    let mut term = term_with_metrics();
+    term.process(b"hello world");
+    term.set_selection((0, 0), (5, 0), SelectionMode::Character);
+
+    assert!(term.hit_test(45.0, 0.0, 0).unwrap().selected);
+    assert!(!term.hit_test(55.0, 0.0, 0).unwrap().selected);
+}
+
+#[test]
+fn test_hit_test_scrolled_into_scrollback() {
+    let mut term = term_with_metrics();
+    term.process(b"one\r\ntwo\r\nthree\r\nfour\r\nfive\r\nsix");
+    assert_eq!(term.active_grid().scrollback_len(), 2);
+
+    // Viewport scrolled back 2 lines: top row is the oldest scrollback line
+    let hit = term.hit_test(0.0, 0.0, 2).unwrap();
+    assert_eq!(hit.screen_row, None);
+    assert_eq!(hit.abs_row, 0);
+    assert_eq!(hit.cell.c, 'o');
+
+    let hit = term.hit_test(0.0, 45.0, 2).unwrap();
+    assert_eq!(hit.screen_row, Some(0));
+    assert_eq!(hit.abs_row, 2);
+    assert_eq!(hit.cell.c, 't');
+
+    // Offsets beyond the available scrollback are clamped
+    let hit = term.hit_test(0.0, 0.0, 50).unwrap();
+    assert_eq!(hit.cell.c, 'o');
+}
+
+#[test]
+fn test_hit_test_zone() {
+    let mut term = term_with_metrics();
+    term.process(b"\x1b]133;A\x07$ ");
+
+    let hit = term.hit_test(0.0, 0.0, 0).unwrap();
+    let zone = hit.zone.expect("prompt zone");
+    assert_eq!(zone.zone_type, crate::zone::ZoneType::Prompt);
+}
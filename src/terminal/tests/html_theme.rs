@@ -0,0 +1,72 @@
+// Themed HTML export tests
+use crate::color::Color;
+use crate::html_export::HtmlExportConfig;
+use crate::terminal::*;
+
+#[test]
+fn test_themed_html_uses_runtime_palette() {
+    let mut term = Terminal::new(20, 2);
+    // OSC 4: index 1 becomes #102030
+    term.process(b"\x1b]4;1;rgb:10/20/30\x1b\\\x1b[31mred");
+
+    let html = term.export_html_themed(&HtmlExportConfig::new());
+    assert!(html.contains("--term-color-1: rgb(16, 32, 48);"));
+    assert!(html.contains("color: var(--term-color-1, rgb(16, 32, 48))\">red</span>"));
+
+    let plain = term.export_html_themed(&HtmlExportConfig::new().with_css_variables(false));
+    assert!(!plain.contains("--term-"));
+    assert!(plain.contains("color: rgb(16, 32, 48)\">red</span>"));
+}
+
+#[test]
+fn test_themed_html_uses_default_colors() {
+    let mut term = Terminal::new(20, 2);
+    // OSC 10/11: default foreground and background
+    term.process(b"\x1b]10;rgb:ee/dd/cc\x1b\\\x1b]11;rgb:01/02/03\x1b\\plain");
+
+    let html = term.export_html_themed(&HtmlExportConfig::new());
+    assert!(html.contains("--term-fg: rgb(238, 221, 204);"));
+    assert!(html.contains("--term-bg: rgb(1, 2, 3);"));
+    assert!(html.contains("background-color: var(--term-bg, rgb(1, 2, 3))"));
+    // Cells in the default colors inherit them
+    assert!(html.contains("\nplain "));
+}
+
+#[test]
+fn test_themed_html_config_overrides_terminal_theme() {
+    let mut term = Terminal::new(20, 2);
+    term.process(b"\x1b]4;2;rgb:10/20/30\x1b\\\x1b[42mgreen");
+
+    let mut palette = *term.get_ansi_palette();
+    palette[2] = Color::Rgb(1, 200, 3);
+    let config = HtmlExportConfig::new()
+        .with_styles(false)
+        .with_palette(palette)
+        .with_default_bg(Color::Rgb(9, 9, 9));
+    let html = term.export_html_themed(&config);
+    assert!(html.contains("background-color: var(--term-color-2, rgb(1, 200, 3))"));
+    assert!(!html.contains("rgb(16, 32, 48)"));
+    assert!(!html.contains("<style>"));
+}
+
+#[test]
+fn test_themed_html_inlines_hyperlinks() {
+    let mut term = Terminal::new(30, 2);
+    term.process(b"see \x1b]8;;https://example.com/?a=1&b=2\x1b\\docs\x1b]8;;\x1b\\ end");
+
+    let html = term.export_html_themed(&HtmlExportConfig::new().with_styles(false));
+    assert!(!html.contains("<a "));
+
+    let html = term.export_html_themed(&HtmlExportConfig::new().with_hyperlinks(true));
+    assert!(html.contains("see <a href=\"https://example.com/?a=1&amp;b=2\">docs</a> end"));
+}
+
+#[test]
+fn test_selected_rich_text_uses_runtime_palette() {
+    let mut term = Terminal::new(20, 2);
+    term.process(b"\x1b]4;4;rgb:0a/0b/0c\x1b\\\x1b[44mblue\x1b[0m");
+    term.set_selection((0, 0), (4, 0), SelectionMode::Character);
+
+    let rich = term.get_selected_rich_text().unwrap();
+    assert!(rich.html.contains("background-color: rgb(10, 11, 12)"));
+}
@@ -21,3 +21,42 @@ fn test_modify_other_keys_mode_setting() {
     term.process(b"\x1b[>4;2m");
     assert_eq!(term.modify_other_keys_mode(), 2);
 }
+
+#[test]
+fn test_win32_input_mode_toggle_and_report() {
+    let mut term = Terminal::new(80, 24);
+    assert!(!term.win32_input_mode());
+
+    term.process(b"\x1b[?9001h");
+    assert!(term.win32_input_mode());
+    assert!(term
+        .poll_events()
+        .contains(&TerminalEvent::ModeChanged(Mode::Win32Input, true)));
+    term.process(b"\x1b[?9001$p");
+    assert_eq!(term.drain_responses(), b"\x1b[?9001;1$y");
+
+    term.process(b"\x1b[?9001l");
+    assert!(!term.win32_input_mode());
+    term.process(b"\x1b[?9001$p");
+    assert_eq!(term.drain_responses(), b"\x1b[?9001;2$y");
+}
+
+#[test]
+fn test_win32_input_mode_encodes_key_records() {
+    use crate::kitty_keyboard::{Key, KeyEvent, KeyEventType};
+
+    let mut term = Terminal::new(80, 24);
+    let release = KeyEvent::new(Key::Char('a')).with_event_type(KeyEventType::Release);
+    assert!(term.encode_key(&release).is_empty());
+
+    // Takes precedence over the Kitty keyboard flags
+    term.process(b"\x1b[>1u\x1b[?9001h");
+    assert_eq!(
+        term.encode_key(&KeyEvent::new(Key::Char('a'))),
+        b"\x1b[65;30;97;1;0;1_"
+    );
+    assert_eq!(term.encode_key(&release), b"\x1b[65;30;97;0;0;1_");
+
+    term.reset();
+    assert!(!term.win32_input_mode());
+}
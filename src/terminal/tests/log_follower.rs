@@ -0,0 +1,115 @@
+//! Log follower mode tests
+
+use crate::color::{Color, NamedColor};
+use crate::terminal::{LogSearch, Terminal, MAX_LOG_LINE_LENGTH};
+
+fn texts(term: &Terminal) -> Vec<&str> {
+    term.log_lines(0, usize::MAX)
+        .into_iter()
+        .map(|line| line.text())
+        .collect()
+}
+
+#[test]
+fn lines_keep_text_and_sgr_runs() {
+    let mut term = Terminal::new_log_follower(80, 100);
+    assert!(term.is_log_follower());
+    term.process(b"ok \x1b[1;31merror\x1b[0m done\r\nplain\n");
+
+    assert_eq!(texts(&term), ["ok error done", "plain"]);
+    let first = term.log_line(0).unwrap();
+    let runs = first.runs();
+    assert_eq!(runs.len(), 3);
+    assert_eq!(runs[0], ("ok ", None));
+    let (text, style) = runs[1];
+    assert_eq!(text, "error");
+    let style = style.unwrap();
+    assert_eq!(style.fg, Color::Named(NamedColor::Red));
+    assert!(style.flags.bold());
+    assert_eq!(runs[2], (" done", None));
+    assert!(term.log_line(1).unwrap().spans().is_empty());
+}
+
+#[test]
+fn cursor_addressing_is_ignored_and_the_grid_stays_blank() {
+    let mut term = Terminal::new_log_follower(80, 100);
+    term.process(b"\x1b[2J\x1b[10;10Hbuild\x1b[K step\n");
+    assert_eq!(texts(&term), ["build step"]);
+    assert!(term.export_text().trim().is_empty());
+}
+
+#[test]
+fn carriage_return_rewrites_and_controls_edit_the_line() {
+    let mut term = Terminal::new_log_follower(80, 100);
+    term.process(b"10%\r50%\r100%\nab\x08c\td\n");
+    assert_eq!(texts(&term), ["100%", "ac      d"]);
+
+    term.process(b"partial");
+    assert_eq!(term.log_partial_line(), "partial");
+    assert_eq!(term.log_total_lines(), 2);
+}
+
+#[test]
+fn eviction_keeps_line_numbers_stable() {
+    let mut term = Terminal::new_log_follower(80, 3);
+    for i in 0..5 {
+        term.process(format!("line {i}\n").as_bytes());
+    }
+    assert_eq!(term.log_first_line(), 2);
+    assert_eq!(term.log_total_lines(), 5);
+    assert_eq!(term.log_line_count(), 3);
+    assert!(term.log_line(1).is_none());
+    assert_eq!(term.log_line(4).unwrap().text(), "line 4");
+
+    term.clear_log();
+    assert_eq!(term.log_line_count(), 0);
+    assert_eq!(term.log_first_line(), 5);
+}
+
+#[test]
+fn long_lines_are_split_into_continuations() {
+    let mut term = Terminal::new_log_follower(80, 10);
+    let mut data = vec![b'x'; MAX_LOG_LINE_LENGTH + 5];
+    data.push(b'\n');
+    term.process(&data);
+    assert_eq!(term.log_line_count(), 2);
+    assert!(!term.log_line(0).unwrap().is_continuation());
+    let rest = term.log_line(1).unwrap();
+    assert!(rest.is_continuation());
+    assert_eq!(rest.text().len(), 5);
+}
+
+#[test]
+fn search_is_incremental_and_skips_evicted_lines() {
+    let mut term = Terminal::new_log_follower(80, 2);
+    term.process(b"Error one\nfine\nerror two ERROR\n");
+
+    let mut search = LogSearch::new("error", false);
+    let found = search_positions(&term, &mut search);
+    // Line 0 was evicted before the search started
+    assert_eq!(found, [(2, 0, 5), (2, 10, 15)]);
+    assert_eq!(search.next_line(), 3);
+    assert!(term.search_log(&mut search).is_empty());
+
+    term.process(b"no match\nfinal error\n");
+    assert_eq!(search_positions(&term, &mut search), [(4, 6, 11)]);
+
+    let mut exact = LogSearch::new("ERROR", true);
+    assert_eq!(search_positions(&term, &mut exact), []);
+}
+
+fn search_positions(term: &Terminal, search: &mut LogSearch) -> Vec<(u64, usize, usize)> {
+    term.search_log(search)
+        .into_iter()
+        .map(|m| (m.line, m.start, m.end))
+        .collect()
+}
+
+#[test]
+fn bells_and_titles_are_still_handled() {
+    let mut term = Terminal::new_log_follower(80, 10);
+    term.process(b"\x1b]0;ci job\x07ding\x07\n");
+    assert_eq!(term.title(), "ci job");
+    assert_eq!(texts(&term), ["ding"]);
+    assert_eq!(term.bell_count(), 1);
+}
@@ -0,0 +1,116 @@
+// DECDMAC/DECINVM macro space tests
+use crate::terminal::macro_space::{MACRO_SPACE_BYTES, MAX_REPLAY_BYTES};
+use crate::terminal::*;
+
+#[test]
+fn defined_macro_replays_as_host_input() {
+    let mut term = Terminal::new(20, 5);
+    // Control sequences need the hex encoding: ESC would end the DCS
+    term.process(b"\x1bP3;0;1!z68691B5B316D7468657265\x1b\\");
+    assert_eq!(term.dec_macro(3), Some(&b"hi\x1b[1mthere"[..]));
+    assert_eq!(term.active_grid().row_text(0).trim_end(), "");
+
+    term.process(b"\x1b[3*z");
+    assert_eq!(term.active_grid().row_text(0).trim_end(), "hithere");
+    assert!(term.flags.bold());
+    assert!(term.unsupported_sequences().is_empty());
+}
+
+#[test]
+fn hex_encoded_macro_is_decoded() {
+    let mut term = Terminal::new(20, 5);
+    term.process(b"\x1bP1;0;1!z41!3;42;43\x1b\\");
+    assert_eq!(term.dec_macro(1), Some(&b"ABBBC"[..]));
+
+    // Malformed hex leaves the slot empty
+    term.process(b"\x1bP2;0;1!z4G\x1b\\");
+    assert_eq!(term.dec_macro(2), None);
+}
+
+#[test]
+fn empty_body_deletes_and_pdt_clears_all() {
+    let mut term = Terminal::new(20, 5);
+    term.process(b"\x1bP1!zone\x1b\\\x1bP2!ztwo\x1b\\");
+    term.process(b"\x1bP1!z\x1b\\");
+    assert_eq!(term.dec_macro(1), None);
+    assert_eq!(term.dec_macro(2), Some(&b"two"[..]));
+
+    term.process(b"\x1bP5;1!zfive\x1b\\");
+    assert_eq!(term.dec_macro(2), None);
+    assert_eq!(term.dec_macro(5), Some(&b"five"[..]));
+    assert_eq!(term.dec_macro_space_available(), MACRO_SPACE_BYTES - 4);
+}
+
+#[test]
+fn definitions_beyond_the_space_limit_are_discarded() {
+    let mut term = Terminal::new(20, 5);
+    let mut big = b"\x1bP0!z".to_vec();
+    big.extend(std::iter::repeat_n(b'x', MACRO_SPACE_BYTES));
+    big.extend_from_slice(b"\x1b\\");
+    term.process(&big);
+    assert_eq!(term.dec_macro_space_available(), 0);
+
+    term.process(b"\x1bP1!zy\x1b\\");
+    assert_eq!(term.dec_macro(1), None);
+
+    // Ids past 63 are ignored
+    term.clear_dec_macros();
+    term.process(b"\x1bP64!zy\x1b\\");
+    assert_eq!(term.dec_macro(64), None);
+    assert_eq!(term.dec_macro_space_available(), MACRO_SPACE_BYTES);
+}
+
+#[test]
+fn self_invoking_macro_stops_at_depth_limit() {
+    let mut term = Terminal::new(40, 5);
+    term.process(b"\x1bP0;0;1!z611B5B302A7A\x1b\\");
+    term.process(b"\x1b[0*z");
+    assert_eq!(term.active_grid().row_text(0).trim_end(), "aaaaaaaa");
+}
+
+#[test]
+fn fan_out_macro_stops_at_the_replay_budget() {
+    // Macro 0 is DSR 5 followed by six DECINVMs of itself: without a budget
+    // that is ~6^8 replays from a few dozen bytes of host input
+    let body = [&b"\x1b[5n"[..], &b"\x1b[0*z".repeat(6)].concat();
+    let hex: String = body.iter().map(|b| format!("{b:02X}")).collect();
+    let mut term = Terminal::new(20, 5);
+    term.process(format!("\x1bP0;0;1!z{hex}\x1b\\").as_bytes());
+
+    let replays = |term: &mut Terminal| {
+        term.process(b"\x1b[0*z");
+        term.drain_responses()
+            .windows(4)
+            .filter(|w| w == b"\x1b[0n")
+            .count()
+    };
+    let first = replays(&mut term);
+    assert!(first > 0);
+    assert!(first <= MAX_REPLAY_BYTES / body.len());
+
+    // The budget is per top-level invocation
+    assert_eq!(replays(&mut term), first);
+}
+
+#[test]
+fn macro_space_and_checksum_reports() {
+    let mut term = Terminal::new(20, 5);
+    term.process(b"\x1b[?62n");
+    assert_eq!(term.drain_responses(), b"\x1b[256*{");
+
+    term.process(b"\x1bP0!zAB\x1b\\");
+    term.process(b"\x1b[?62n");
+    assert_eq!(term.drain_responses(), b"\x1b[255*{");
+
+    // 0x41 + 0x42 = 0x83, negated to 0xFF7D
+    term.process(b"\x1b[?63;7n");
+    assert_eq!(term.drain_responses(), b"\x1bP7!~FF7D\x1b\\");
+}
+
+#[test]
+fn reset_clears_macros() {
+    let mut term = Terminal::new(20, 5);
+    term.process(b"\x1bP0!zAB\x1b\\");
+    term.process(b"\x1bc");
+    assert_eq!(term.dec_macro(0), None);
+}
@@ -0,0 +1,190 @@
+//! Malformed and pathological input tests.
+//!
+//! One test per class of hostile input found while fuzzing
+//! [`Terminal::process_fuzz`]: oversized or unterminated strings must stay
+//! within their configured ceilings, huge parameters must be clamped, and the
+//! terminal must keep parsing normally afterwards.
+
+use crate::terminal::{
+    Terminal, DEFAULT_MAX_APC_DATA_LENGTH, DEFAULT_MAX_DCS_DATA_LENGTH, DEFAULT_MAX_OSC_DATA_LENGTH,
+};
+
+const MIB: usize = 1024 * 1024;
+
+fn row_text(term: &Terminal, row: usize) -> String {
+    term.active_grid().row_text(row).trim_end().to_string()
+}
+
+#[test]
+fn sequence_limits_default_and_configure() {
+    let mut term = Terminal::new(80, 24);
+    assert_eq!(term.max_osc_data_length(), DEFAULT_MAX_OSC_DATA_LENGTH);
+    assert_eq!(term.max_dcs_data_length(), DEFAULT_MAX_DCS_DATA_LENGTH);
+    assert_eq!(term.max_apc_data_length(), DEFAULT_MAX_APC_DATA_LENGTH);
+
+    term.set_max_dcs_data_length(64);
+    term.set_max_apc_data_length(128);
+    assert_eq!(term.max_dcs_data_length(), 64);
+    assert_eq!(term.max_apc_data_length(), 128);
+}
+
+#[test]
+fn sequence_limits_survive_ris() {
+    let mut term = Terminal::new(80, 24);
+    term.set_max_dcs_data_length(64);
+    term.set_max_apc_data_length(128);
+    term.process(b"\x1bc");
+    assert_eq!(term.max_dcs_data_length(), 64);
+    assert_eq!(term.max_apc_data_length(), 128);
+}
+
+#[test]
+fn unterminated_dcs_spanning_megabytes_is_capped() {
+    let mut term = Terminal::new(80, 24);
+    term.process_fuzz(b"\x1bP1$r");
+    let chunk = vec![b'x'; MIB];
+    for _ in 0..4 {
+        term.process_fuzz(&chunk);
+    }
+    assert_eq!(term.dcs_state.dcs_buffer.len(), DEFAULT_MAX_DCS_DATA_LENGTH);
+
+    term.process_fuzz(b"\x1b\\ok");
+    assert!(term.dcs_state.dcs_buffer.is_empty());
+    assert_eq!(row_text(&term, 0), "ok");
+}
+
+#[test]
+fn sixel_parameter_flood_is_capped() {
+    let mut term = Terminal::new(80, 24);
+    term.set_max_dcs_data_length(4096);
+    let mut data = b"\x1bPq#".to_vec();
+    data.extend(std::iter::repeat_n(b'9', 2 * MIB));
+    term.process_fuzz(&data);
+    assert_eq!(term.dcs_state.dcs_buffer.len(), 4096);
+
+    // The garbage color command is ignored; the image still completes
+    term.process_fuzz(b"#1~\x1b\\");
+    assert_eq!(term.graphics_count(), 1);
+    assert!(term.dcs_state.dcs_buffer.is_empty());
+}
+
+#[test]
+fn nested_sixel_repeats_are_bounded() {
+    let mut term = Terminal::new(80, 24);
+    term.process_fuzz(b"\x1bPq!4294967296!99999!!99999~!\xc3\xa9~\x1b\\");
+    assert_eq!(term.graphics_count(), 1);
+    let max_repeat = term.sixel_limits().max_repeat;
+    assert!(term.all_graphics()[0].width <= max_repeat + 1);
+}
+
+#[test]
+fn oversized_parameters_are_clamped() {
+    let mut term = Terminal::new(80, 24);
+    let mut digits = b"\x1b[".to_vec();
+    digits.extend(std::iter::repeat_n(b'9', MIB));
+    digits.push(b'm');
+    term.process_fuzz(&digits);
+
+    let mut separators = b"\x1b[".to_vec();
+    separators.extend(std::iter::repeat_n(b';', MIB));
+    separators.push(b'H');
+    term.process_fuzz(&separators);
+
+    for seq in [
+        &b"A\x1b[65535b"[..],
+        b"\x1b[65535S",
+        b"\x1b[65535T",
+        b"\x1b[65535L",
+        b"\x1b[65535M",
+        b"\x1b[65535@",
+        b"\x1b[65535P",
+        b"\x1b[65535X",
+        b"\x1b[65535;65535H",
+        b"\x1b[65535;65535;65535;65535r",
+        b"\x1b[8;65535;65535t",
+        b"\x1b[1;65535;65535;65535;65535*y",
+    ] {
+        term.process_fuzz(seq);
+    }
+    assert!(term.size().0 <= 65535 && term.size().1 <= 65535);
+
+    term.process_fuzz(b"\x1b[!p\x1b[H\x1b[2Jok");
+    assert_eq!(row_text(&term, 0), "ok");
+}
+
+#[test]
+fn delete_and_insert_lines_spanning_screen_from_top() {
+    let mut term = Terminal::new(10, 4);
+    term.process_fuzz(b"a\r\nb\r\nc\r\nd\x1b[H\x1b[4M");
+    assert!((0..4).all(|row| row_text(&term, row).is_empty()));
+
+    term.process_fuzz(b"a\r\nb\r\nc\r\nd\x1b[H\x1b[65535L");
+    assert!((0..4).all(|row| row_text(&term, row).is_empty()));
+}
+
+#[test]
+fn unterminated_osc_is_cancelled_at_limit() {
+    let mut term = Terminal::new(80, 24);
+    term.set_max_osc_data_length(1024);
+    term.process_fuzz(b"\x1b]0;");
+    let chunk = vec![b'x'; 64 * 1024];
+    for _ in 0..16 {
+        term.process_fuzz(&chunk);
+    }
+    term.process_fuzz(b"\x07ok");
+    assert_eq!(term.title(), "");
+    assert_eq!(row_text(&term, 0), "ok");
+
+    // Sequences within the limit still work, including across chunks
+    term.process_fuzz(b"\x1b]0;short");
+    term.process_fuzz(b" title\x1b\\");
+    assert_eq!(term.title(), "short title");
+}
+
+#[test]
+fn oversized_kitty_apc_is_dropped() {
+    let mut term = Terminal::new(80, 24);
+    term.set_max_apc_data_length(64);
+    term.process_fuzz(b"\x1b_Ga=t,f=24,i=5,s=2,v=2;");
+    let chunk = vec![b'A'; 64 * 1024];
+    for _ in 0..8 {
+        term.process_fuzz(&chunk);
+    }
+    assert!(term.apc_buffer.len() <= 65);
+    term.process_fuzz(b"\x1b\\ok");
+    assert!(term.graphics.graphics_store.get_kitty_image(5).is_none());
+    assert_eq!(row_text(&term, 0), "ok");
+
+    // A payload within the limit is still accepted
+    term.process_fuzz(b"\x1b_Ga=t,f=24,i=6,s=2,v=2;AAAAAAAAAAAAAAAA\x1b\\");
+    assert!(term.graphics.graphics_store.get_kitty_image(6).is_some());
+}
+
+#[test]
+fn random_escape_soup_keeps_invariants() {
+    // Space-separated fragments of escape sequences, parameters and text
+    const PIECES: &[u8] =
+        b"\x1b[ \x1b] \x1bP \x1b_G \x1b\\ \x07 \x1b ; ? ! # \" $ - q p 65535 0 9 \
+        m H r s t L M @ b h l \xff \xe2\x82 \r\n \x1bc \x1b#8 \x1b[?1049h \x1b[?69h \x1b[8;3;3t A";
+    let pieces: Vec<&[u8]> = PIECES
+        .split(|&b| b == b' ')
+        .filter(|piece| !piece.is_empty())
+        .collect();
+    let mut seed: u64 = 0x5eed;
+    let mut next = || {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (seed >> 33) as usize
+    };
+    for _ in 0..200 {
+        let mut data = Vec::new();
+        for _ in 0..1 + next() % 100 {
+            data.extend_from_slice(pieces[next() % pieces.len()]);
+        }
+        let mut term = Terminal::new(20, 8);
+        let (head, tail) = data.split_at(next() % (data.len() + 1));
+        term.process_fuzz(head);
+        term.process_fuzz(tail);
+    }
+}
@@ -0,0 +1,342 @@
+// Left/right margin (DECLRMM / DECSLRM) tests
+use crate::terminal::*;
+
+/// A row of twelve distinct characters
+const LETTERS: &[u8] = b"abcdefghijkl";
+
+/// Every row filled with its own digit: row 0 is "000000000000" and so on
+const DIGIT_ROWS: &[u8] =
+    b"000000000000\r\n111111111111\r\n222222222222\r\n333333333333\r\n444444444444";
+
+/// 12x5 terminal showing `content`, then DECLRMM set with margins at
+/// columns 3..=6 (1-based)
+fn margin_terminal(content: &[u8]) -> Terminal {
+    let mut term = Terminal::new(12, 5);
+    term.process(content);
+    term.process(b"\x1b[?69h\x1b[3;6s");
+    assert_eq!(term.left_right_margins(), (2, 5));
+    term
+}
+
+fn row_text(term: &Terminal, row: usize) -> String {
+    term.active_grid().row_text(row)
+}
+
+fn rows(term: &Terminal) -> Vec<String> {
+    (0..5).map(|row| row_text(term, row)).collect()
+}
+
+fn cursor(term: &Terminal) -> (usize, usize) {
+    (term.cursor.col, term.cursor.row)
+}
+
+#[test]
+fn text_wraps_from_the_right_margin_to_the_left_margin() {
+    let mut term = margin_terminal(b"");
+    term.process(b"\x1b[1;3Habcdefgh");
+    assert_eq!(row_text(&term, 0).trim_end(), "  abcd");
+    assert_eq!(row_text(&term, 1).trim_end(), "  efgh");
+    assert!(term.active_grid().is_line_wrapped(0));
+}
+
+#[test]
+fn text_right_of_the_right_margin_runs_to_the_screen_edge() {
+    let mut term = margin_terminal(b"");
+    term.process(b"\x1b[1;10Habc");
+    assert_eq!(row_text(&term, 0), "         abc");
+    assert_eq!(cursor(&term), (11, 0));
+}
+
+#[test]
+fn wide_characters_wrap_before_the_right_margin() {
+    let mut term = margin_terminal(b"");
+    term.process("\x1b[1;6H中".as_bytes());
+    assert_eq!(row_text(&term, 0).trim_end(), "");
+    assert_eq!(term.active_grid().get(2, 1).unwrap().c, '中');
+}
+
+#[test]
+fn without_autowrap_the_right_margin_is_overwritten() {
+    let mut term = margin_terminal(b"");
+    term.process(b"\x1b[?7l\x1b[1;5Habcd");
+    assert_eq!(row_text(&term, 0).trim_end(), "    ad");
+    assert_eq!(cursor(&term), (5, 0));
+}
+
+#[test]
+fn insert_mode_shifts_only_up_to_the_right_margin() {
+    let mut term = margin_terminal(LETTERS);
+    term.process(b"\x1b[1;3H\x1b[4hX");
+    assert_eq!(row_text(&term, 0), "abXcdeghijkl");
+}
+
+#[test]
+fn ich_and_dch_stop_at_the_right_margin() {
+    let mut term = margin_terminal(LETTERS);
+    term.process(b"\x1b[1;3H\x1b[2@");
+    assert_eq!(row_text(&term, 0), "ab  cdghijkl");
+    term.process(b"\x1b[3P");
+    assert_eq!(row_text(&term, 0), "abd   ghijkl");
+}
+
+#[test]
+fn ich_and_dch_outside_the_margins_do_nothing() {
+    let mut term = margin_terminal(LETTERS);
+    term.process(b"\x1b[1;1H\x1b[2@\x1b[1;8H\x1b[2P");
+    assert_eq!(row_text(&term, 0), "abcdefghijkl");
+}
+
+#[test]
+fn il_inserts_lines_between_the_margins() {
+    let mut term = margin_terminal(DIGIT_ROWS);
+    term.process(b"\x1b[2;3H\x1b[L");
+    assert_eq!(
+        rows(&term),
+        [
+            "000000000000",
+            "11    111111",
+            "221111222222",
+            "332222333333",
+            "443333444444"
+        ]
+    );
+}
+
+#[test]
+fn dl_deletes_lines_between_the_margins() {
+    let mut term = margin_terminal(DIGIT_ROWS);
+    term.process(b"\x1b[2;4H\x1b[M");
+    assert_eq!(
+        rows(&term),
+        [
+            "000000000000",
+            "112222111111",
+            "223333222222",
+            "334444333333",
+            "44    444444"
+        ]
+    );
+}
+
+#[test]
+fn il_and_dl_outside_the_margins_do_nothing() {
+    let mut term = margin_terminal(DIGIT_ROWS);
+    let before = rows(&term);
+    term.process(b"\x1b[2;1H\x1b[L\x1b[2;7H\x1b[M");
+    assert_eq!(rows(&term), before);
+}
+
+#[test]
+fn su_and_sd_scroll_between_the_margins() {
+    let mut term = margin_terminal(DIGIT_ROWS);
+    term.process(b"\x1b[S");
+    assert_eq!(
+        rows(&term),
+        [
+            "001111000000",
+            "112222111111",
+            "223333222222",
+            "334444333333",
+            "44    444444"
+        ]
+    );
+    term.process(b"\x1b[2T");
+    assert_eq!(row_text(&term, 0), "00    000000");
+    assert_eq!(row_text(&term, 1), "11    111111");
+    assert_eq!(row_text(&term, 2), "221111222222");
+    assert_eq!(term.active_grid().scrollback_len(), 0);
+}
+
+#[test]
+fn su_and_dl_covering_the_whole_region_blank_the_band() {
+    let mut term = margin_terminal(DIGIT_ROWS);
+    term.process(b"\x1b[9S");
+    assert_eq!(row_text(&term, 0), "00    000000");
+    assert_eq!(row_text(&term, 4), "44    444444");
+
+    let mut term = margin_terminal(DIGIT_ROWS);
+    term.process(b"\x1b[1;3H\x1b[9M");
+    assert_eq!(
+        rows(&term),
+        [
+            "00    000000",
+            "11    111111",
+            "22    222222",
+            "33    333333",
+            "44    444444"
+        ]
+    );
+}
+
+#[test]
+fn line_feed_at_the_bottom_margin_scrolls_the_band() {
+    let mut term = margin_terminal(DIGIT_ROWS);
+    term.process(b"\x1b[5;3H\n");
+    assert_eq!(row_text(&term, 0), "001111000000");
+    assert_eq!(row_text(&term, 4), "44    444444");
+    assert_eq!(cursor(&term), (2, 4));
+    // Only the band moved, so nothing went to scrollback
+    assert_eq!(term.active_grid().scrollback_len(), 0);
+}
+
+#[test]
+fn line_feed_outside_the_margins_does_not_scroll() {
+    let mut term = margin_terminal(DIGIT_ROWS);
+    let before = rows(&term);
+    term.process(b"\x1b[5;1H\n\x1bD\x1b[5;8H\n");
+    assert_eq!(rows(&term), before);
+    assert_eq!(cursor(&term), (7, 4));
+}
+
+#[test]
+fn reverse_index_at_the_top_margin_scrolls_the_band() {
+    let mut term = margin_terminal(DIGIT_ROWS);
+    term.process(b"\x1b[1;4H\x1bM");
+    assert_eq!(row_text(&term, 0), "00    000000");
+    assert_eq!(row_text(&term, 1), "110000111111");
+    // Outside the margins RI only stops at the top
+    let before = rows(&term);
+    term.process(b"\x1b[1;1H\x1bM");
+    assert_eq!(rows(&term), before);
+}
+
+#[test]
+fn full_width_margins_still_feed_scrollback() {
+    let mut term = Terminal::new(12, 5);
+    term.process(b"\x1b[?69htop\x1b[5;1H\n");
+    assert_eq!(term.active_grid().scrollback_len(), 1);
+}
+
+#[test]
+fn carriage_return_goes_to_the_left_margin() {
+    let mut term = margin_terminal(b"");
+    term.process(b"\x1b[1;5H\r");
+    assert_eq!(cursor(&term), (2, 0));
+    // From left of the margin CR goes to column 0
+    term.process(b"\x1b[1;2H\r");
+    assert_eq!(cursor(&term), (0, 0));
+    term.process(b"\x1b[1;5H\x1b[20h\n");
+    assert_eq!(cursor(&term), (2, 1));
+}
+
+#[test]
+fn horizontal_cursor_motion_stops_at_the_margins() {
+    let mut term = margin_terminal(b"");
+    term.process(b"\x1b[1;4H\x1b[9C");
+    assert_eq!(cursor(&term), (5, 0));
+    term.process(b"\x1b[9D");
+    assert_eq!(cursor(&term), (2, 0));
+    // Starting outside the margins only the screen edges stop the cursor
+    term.process(b"\x1b[1;8H\x1b[9C");
+    assert_eq!(cursor(&term), (11, 0));
+    term.process(b"\x1b[1;2H\x1b[9D");
+    assert_eq!(cursor(&term), (0, 0));
+}
+
+#[test]
+fn backspace_and_tabs_stop_at_the_margins() {
+    let mut term = margin_terminal(b"");
+    term.process(b"\x1b[1;3H\x08");
+    assert_eq!(cursor(&term), (2, 0));
+    term.process(b"\t");
+    assert_eq!(cursor(&term), (5, 0));
+    term.process(b"\x1b[Z");
+    assert_eq!(cursor(&term), (2, 0));
+    term.process(b"\x1b[1;8H\t");
+    assert_eq!(cursor(&term), (8, 0));
+}
+
+#[test]
+fn cnl_and_cpl_return_to_the_left_margin() {
+    let mut term = margin_terminal(b"");
+    term.process(b"\x1b[2;5H\x1b[E");
+    assert_eq!(cursor(&term), (2, 2));
+    term.process(b"\x1b[2;5H\x1b[F");
+    assert_eq!(cursor(&term), (2, 0));
+}
+
+#[test]
+fn vertical_cursor_motion_stops_at_the_scroll_margins() {
+    let mut term = Terminal::new(12, 5);
+    term.process(b"\x1b[2;4r\x1b[3;1H\x1b[9A");
+    assert_eq!(cursor(&term), (0, 1));
+    term.process(b"\x1b[9B");
+    assert_eq!(cursor(&term), (0, 3));
+    // Outside the region only the screen edges stop the cursor
+    term.process(b"\x1b[5;1H\x1b[9A");
+    assert_eq!(cursor(&term), (0, 1));
+    term.process(b"\x1b[1;1H\x1b[9A");
+    assert_eq!(cursor(&term), (0, 0));
+}
+
+#[test]
+fn origin_mode_addresses_relative_to_the_margins() {
+    let mut term = margin_terminal(b"");
+    term.process(b"\x1b[2;4r\x1b[?6h");
+    assert_eq!(cursor(&term), (2, 1));
+    term.process(b"\x1b[2;3H");
+    assert_eq!(cursor(&term), (4, 2));
+    term.process(b"\x1b[9;9H");
+    assert_eq!(cursor(&term), (5, 3));
+    term.process(b"\x1b[2G\x1b[1d");
+    assert_eq!(cursor(&term), (3, 1));
+    term.process(b"\x1b[6n");
+    assert_eq!(term.drain_responses(), b"\x1b[1;2R");
+}
+
+#[test]
+fn decslrm_homes_the_cursor_and_ignores_bad_margins() {
+    let mut term = margin_terminal(b"");
+    term.process(b"\x1b[3;3H\x1b[2;8s");
+    assert_eq!(term.left_right_margins(), (1, 7));
+    assert_eq!(cursor(&term), (0, 0));
+    term.process(b"\x1b[5;5s\x1b[9;4s");
+    assert_eq!(term.left_right_margins(), (1, 7));
+    // Missing and zero parameters select the screen edges
+    term.process(b"\x1b[0;0s");
+    assert_eq!(term.left_right_margins(), (0, 11));
+    term.process(b"\x1b[4s");
+    assert_eq!(term.left_right_margins(), (3, 11));
+}
+
+#[test]
+fn csi_s_without_parameters_resets_margins_under_declrmm() {
+    let mut term = margin_terminal(b"");
+    term.process(b"\x1b[s");
+    assert_eq!(term.left_right_margins(), (0, 11));
+}
+
+#[test]
+fn resetting_declrmm_clears_the_margins() {
+    let mut term = margin_terminal(b"");
+    term.process(b"\x1b[?69$p");
+    assert_eq!(term.drain_responses(), b"\x1b[?69;1$y");
+    term.process(b"\x1b[?69l");
+    assert!(!term.lr_margin_mode());
+    assert_eq!(term.left_right_margins(), (0, 11));
+    term.process(b"\x1b[1;3Habcdefghijkl");
+    assert_eq!(row_text(&term, 0), "  abcdefghij");
+}
+
+#[test]
+fn back_and_forward_index_scroll_between_the_margins() {
+    let mut term = margin_terminal(LETTERS);
+    term.process(b"\x1b[1;3H\x1b6");
+    assert_eq!(row_text(&term, 0), "ab cdeghijkl");
+    assert_eq!(cursor(&term), (2, 0));
+    term.process(b"\x1b[1;6H\x1b9");
+    assert_eq!(row_text(&term, 0), "abcde ghijkl");
+    assert_eq!(cursor(&term), (5, 0));
+    // Away from the margins they just move the cursor
+    term.process(b"\x1b[1;4H\x1b6\x1b9\x1b9");
+    assert_eq!(cursor(&term), (4, 0));
+}
+
+#[test]
+fn sl_and_sr_scroll_between_the_margins() {
+    let mut term = margin_terminal(LETTERS);
+    term.process(b"\x1b[2 @");
+    assert_eq!(row_text(&term, 0), "abef  ghijkl");
+    term.process(b"\x1b[ A");
+    assert_eq!(row_text(&term, 0), "ab ef ghijkl");
+}
@@ -0,0 +1,63 @@
+// Prompt marks and jump-list navigation tests
+use crate::terminal::*;
+
+#[test]
+fn prompts_create_marks_for_navigation() {
+    let mut term = Terminal::new(20, 10);
+    term.process(b"\x1b]133;A\x07$ ls\r\n\x1b]133;C\x07a\r\nb\r\n");
+    term.process(b"\x1b]133;D;0\x07\x1b]133;A\x07$ pwd\r\n/\r\n\x1b]133;A\x07$ ");
+
+    let rows: Vec<_> = term.marks().iter().map(|m| m.abs_row).collect();
+    assert_eq!(rows, [0, 3, 5]);
+    assert!(term.marks().iter().all(|m| m.kind == MarkKind::Prompt));
+
+    assert_eq!(term.next_mark(0), Some(3));
+    assert_eq!(term.next_mark(4), Some(5));
+    assert_eq!(term.next_mark(5), None);
+    assert_eq!(term.prev_mark(5), Some(3));
+    assert_eq!(term.prev_mark(3), Some(0));
+    assert_eq!(term.prev_mark(0), None);
+}
+
+#[test]
+fn redrawn_prompt_keeps_one_mark() {
+    let mut term = Terminal::new(20, 5);
+    term.process(b"\x1b]133;A\x07$ \r\x1b]133;A\x07$ ");
+    assert_eq!(term.marks().len(), 1);
+}
+
+#[test]
+fn marks_follow_scrolling_and_drop_on_eviction() {
+    let mut term = Terminal::with_scrollback(20, 3, 2);
+    term.process(b"\x1b]133;A\x07$ one\r\n");
+    term.process(b"\x1b]133;A\x07$ two\r\n");
+    assert_eq!(term.next_mark(0), Some(1));
+
+    // Scroll both prompts into scrollback; the marks keep their lines
+    term.process(b"x\r\ny\r\n");
+    let rows: Vec<_> = term.marks().iter().map(|m| m.abs_row).collect();
+    assert_eq!(rows, [0, 1]);
+
+    // The first prompt line leaves the 2-line scrollback
+    term.process(b"z\r\n");
+    let rows: Vec<_> = term.marks().iter().map(|m| m.abs_row).collect();
+    assert_eq!(rows, [0]);
+}
+
+#[test]
+fn named_marks_and_alt_screen() {
+    let mut term = Terminal::new(20, 5);
+    let id = term.add_mark(2, Some("build".to_string()));
+    assert_eq!(term.mark_by_name("build").map(|m| m.id), Some(id));
+    assert_eq!(term.marks()[0].kind, MarkKind::User);
+
+    // Prompts on the alternate screen are not marked
+    term.process(b"\x1b[?1049h\x1b]133;A\x07$ ");
+    assert_eq!(term.marks().len(), 1);
+
+    assert!(term.remove_mark(id));
+    assert!(!term.remove_mark(id));
+    term.process(b"\x1b[?1049l\x1b]133;A\x07$ ");
+    term.clear_marks();
+    assert!(term.marks().is_empty());
+}
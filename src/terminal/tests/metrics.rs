@@ -263,3 +263,73 @@ fn test_get_stats_initial_empty() {
     assert_eq!(stats.graphics_count, 0);
     assert_eq!(stats.hyperlink_count, 0);
 }
+
+#[test]
+fn test_latency_stats_percentiles() {
+    let samples: Vec<u64> = (1..=100).collect();
+    let stats = crate::terminal::LatencyStats::from_samples(&samples);
+    assert_eq!(stats.count, 100);
+    assert_eq!(stats.min_us, 1);
+    assert_eq!(stats.p50_us, 50);
+    assert_eq!(stats.p90_us, 90);
+    assert_eq!(stats.p99_us, 99);
+    assert_eq!(stats.max_us, 100);
+    assert!((stats.mean_us - 50.5).abs() < f64::EPSILON);
+    assert_eq!(
+        crate::terminal::LatencyStats::from_samples(&[]),
+        Default::default()
+    );
+}
+
+#[test]
+fn test_latency_tracker_stages() {
+    use crate::terminal::metrics::LatencyTracker;
+    use std::time::Duration;
+
+    let t0 = crate::time::Instant::now();
+    let ms = |n| t0 + Duration::from_millis(n);
+    let mut tracker = LatencyTracker::default();
+
+    // Keystroke at 0, echo at 3 and 4, frame drawn at 10
+    tracker.input(ms(0));
+    tracker.output(ms(3));
+    tracker.output(ms(4));
+    tracker.render(ms(10));
+    // Output with no input behind it, drawn at 26
+    tracker.output(ms(20));
+    tracker.render(ms(26));
+    // Nothing new to draw: no sample
+    tracker.render(ms(30));
+
+    let m = tracker.metrics();
+    assert_eq!(m.input_to_output.count, 1);
+    assert_eq!(m.input_to_output.max_us, 3_000);
+    assert_eq!(m.output_to_render.count, 2);
+    assert_eq!(m.output_to_render.min_us, 6_000);
+    assert_eq!(m.output_to_render.max_us, 7_000);
+    assert_eq!(m.input_to_render.count, 1);
+    assert_eq!(m.input_to_render.max_us, 10_000);
+    assert_eq!(m.frame_interval.count, 1);
+    assert_eq!(m.frame_interval.max_us, 16_000);
+}
+
+#[test]
+fn test_latency_sample_window_and_reset() {
+    let mut term = Terminal::new(80, 24);
+    term.set_max_latency_samples(2);
+    for _ in 0..5 {
+        term.mark_input_written();
+        term.process(b"x");
+        term.mark_clean();
+    }
+    let m = term.get_performance_metrics();
+    assert_eq!(m.latency.input_to_output.count, 2);
+    assert_eq!(m.latency.input_to_render.count, 2);
+    assert_eq!(m.latency.frame_interval.count, 2);
+
+    term.reset_performance_metrics();
+    assert_eq!(term.get_latency_metrics(), Default::default());
+    term.mark_input_written();
+    term.process(b"y");
+    assert_eq!(term.get_latency_metrics().input_to_output.count, 1);
+}
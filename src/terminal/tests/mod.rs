@@ -1,12 +1,30 @@
 //! Terminal implementation tests
 
+#[cfg(test)]
+mod activity;
+#[cfg(test)]
+mod annotation;
+#[cfg(test)]
+mod appearance;
 #[cfg(test)]
 mod attributes;
 #[cfg(test)]
+mod autolink;
+#[cfg(test)]
+mod background;
+#[cfg(test)]
 mod basic;
 #[cfg(test)]
+mod bidi;
+#[cfg(test)]
+mod block_export;
+#[cfg(test)]
 mod bookmarks;
 #[cfg(test)]
+mod checkpoint;
+#[cfg(test)]
+mod clear_undo;
+#[cfg(test)]
 mod clipboard;
 #[cfg(test)]
 mod coverage_gaps;
@@ -15,40 +33,104 @@ mod cursor;
 #[cfg(test)]
 mod detection;
 #[cfg(test)]
+mod double_buffer;
+#[cfg(test)]
 mod editing;
 #[cfg(test)]
+mod escape_watchdog;
+#[cfg(test)]
+mod expect;
+#[cfg(test)]
+mod export_overlays;
+#[cfg(test)]
 mod ffi_tests;
 #[cfg(test)]
+mod file_download;
+#[cfg(test)]
+mod folding;
+#[cfg(test)]
 mod grid_integration_tests;
 #[cfg(test)]
+mod hit_test;
+#[cfg(test)]
+mod html_theme;
+#[cfg(test)]
 mod keyboard;
 #[cfg(test)]
 mod kitty_apc;
 #[cfg(test)]
+mod log_follower;
+#[cfg(test)]
+mod macro_space;
+#[cfg(test)]
+mod margins;
+#[cfg(test)]
+mod marks;
+#[cfg(test)]
 mod modes;
 #[cfg(test)]
+mod nrcs;
+#[cfg(test)]
 mod observer_tests;
 #[cfg(test)]
+mod printer;
+#[cfg(test)]
+mod prompt_detection;
+#[cfg(test)]
 mod queries;
 #[cfg(test)]
+mod recording_player;
+#[cfg(test)]
 mod rectangles;
 #[cfg(test)]
+mod redaction;
+#[cfg(test)]
 mod replay_snapshot_tests;
 #[cfg(test)]
+mod reply_queue;
+#[cfg(test)]
+mod save_restore;
+#[cfg(test)]
 mod scrolling;
 #[cfg(test)]
 mod search;
 #[cfg(test)]
+mod sequence_filter;
+#[cfg(test)]
+mod sgr_stack;
+#[cfg(test)]
 mod shell_integration;
 #[cfg(test)]
+mod snapshot_delta;
+#[cfg(test)]
+mod state_handle;
+#[cfg(test)]
 mod terminal_tests;
 #[cfg(test)]
+mod text_export;
+#[cfg(test)]
+mod theme;
+#[cfg(test)]
+mod title;
+#[cfg(test)]
 mod tmux;
 #[cfg(test)]
 mod tui;
 #[cfg(test)]
+mod unsupported_sequences;
+#[cfg(test)]
+mod upload;
+#[cfg(test)]
+mod utf8_resilience;
+#[cfg(test)]
+mod viewport;
+#[cfg(test)]
+mod vt52;
+#[cfg(test)]
 mod vt520;
 
+#[cfg(test)]
+mod malformed_input;
 #[cfg(test)]
 mod metrics;
 #[cfg(test)]
@@ -65,7 +147,7 @@ mod arc006_events {
         for i in 0..=MAX_TERMINAL_EVENTS {
             term.events
                 .terminal_events
-                .push(TerminalEvent::ModeChanged(i.to_string(), true));
+                .push(TerminalEvent::GraphicsAdded(i));
         }
         assert_eq!(
             term.events.terminal_events.len(),
@@ -82,8 +164,8 @@ mod arc006_events {
         assert_eq!(term.events.terminal_events.len(), MAX_TERMINAL_EVENTS);
         // Oldest (i=0) evicted; the new front is the former i=1 event.
         match &term.events.terminal_events[0] {
-            TerminalEvent::ModeChanged(name, _) => assert_eq!(name.as_str(), "1"),
-            _ => panic!("expected ModeChanged at the front after eviction"),
+            TerminalEvent::GraphicsAdded(tag) => assert_eq!(*tag, 1),
+            _ => panic!("expected GraphicsAdded at the front after eviction"),
         }
         // The dispatch index shifts down by exactly the number evicted.
         assert_eq!(
@@ -98,12 +180,12 @@ mod arc006_events {
         for i in 0..10 {
             term.events
                 .terminal_events
-                .push(TerminalEvent::ModeChanged(i.to_string(), true));
+                .push(TerminalEvent::GraphicsAdded(i));
         }
         term.cap_terminal_events();
         assert_eq!(term.events.terminal_events.len(), 10);
         match &term.events.terminal_events[0] {
-            TerminalEvent::ModeChanged(name, _) => assert_eq!(name.as_str(), "0"),
+            TerminalEvent::GraphicsAdded(tag) => assert_eq!(*tag, 0),
             _ => panic!("expected first event preserved"),
         }
     }
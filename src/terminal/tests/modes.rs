@@ -84,20 +84,17 @@ fn test_synchronized_updates() {
     // Enable synchronized updates
     term.process(b"\x1b[?2026h");
     assert!(term.synchronized_updates());
+    term.mark_clean();
 
-    // Process some content - it should be buffered
+    // Content is parsed immediately, but its dirty rows are held back
     term.process(b"Buffered");
-    let content = term.content();
-    // Content should be empty because it's buffered
-    assert!(!content.contains("Buffered"));
+    assert!(term.content().contains("Buffered"));
+    assert!(term.get_dirty_rows().is_empty());
 
-    // Disable synchronized updates - this should flush the buffer
+    // Disabling commits the frame
     term.process(b"\x1b[?2026l");
     assert!(!term.synchronized_updates());
-
-    // Now content should appear
-    let content = term.content();
-    assert!(content.contains("Buffered"));
+    assert_eq!(term.get_dirty_rows(), vec![0]);
 }
 
 #[test]
@@ -106,22 +103,21 @@ fn test_synchronized_updates_multiple_updates() {
 
     // Enable synchronized updates
     term.process(b"\x1b[?2026h");
+    term.mark_clean();
 
     // Send multiple updates
     term.process(b"Line1\r\n");
     term.process(b"Line2\r\n");
     term.process(b"Line3");
 
-    // All should be buffered
-    let content = term.content();
-    assert!(!content.contains("Line1"));
-    assert!(!content.contains("Line2"));
-    assert!(!content.contains("Line3"));
+    // Nothing is reported dirty until the update ends
+    assert!(term.get_dirty_rows().is_empty());
 
-    // Disable and flush
+    // Disable and commit
     term.process(b"\x1b[?2026l");
 
-    // All lines should appear
+    // All rows arrive as one frame
+    assert_eq!(term.get_dirty_rows(), vec![0, 1, 2]);
     let content = term.content();
     assert!(content.contains("Line1"));
     assert!(content.contains("Line2"));
@@ -134,19 +130,157 @@ fn test_synchronized_updates_manual_flush() {
 
     // Enable synchronized updates
     term.process(b"\x1b[?2026h");
+    term.mark_clean();
     term.process(b"Test");
-
-    // Content buffered
-    assert!(!term.content().contains("Test"));
+    assert!(term.get_dirty_rows().is_empty());
 
     // Manual flush
     term.flush_synchronized_updates();
 
-    // Content should appear, mode still enabled
-    assert!(term.content().contains("Test"));
+    // Rows committed, mode still enabled
+    assert_eq!(term.get_dirty_rows(), vec![0]);
     assert!(term.synchronized_updates());
 }
 
+fn is_render_event(event: &TerminalEvent) -> bool {
+    matches!(
+        event,
+        TerminalEvent::DirtyRegion(..)
+            | TerminalEvent::GraphicsAdded(_)
+            | TerminalEvent::ScreenCleared { .. }
+            | TerminalEvent::CursorMoved { .. }
+    )
+}
+
+#[test]
+fn test_synchronized_update_frame_emits_one_dirty_region() {
+    let mut term = Terminal::new(40, 10);
+    term.poll_events();
+
+    // A full TUI repaint: clear, draw every row, park the cursor
+    let mut frame = b"\x1b[?2026h\x1b[H\x1b[2J".to_vec();
+    for row in 1..=10 {
+        frame.extend_from_slice(format!("\x1b[{row};1Hrow {row}").as_bytes());
+    }
+    frame.extend_from_slice(b"\x1b[1;1H");
+    term.process(&frame);
+    assert!(
+        !term.poll_events().iter().any(is_render_event),
+        "withheld until the commit"
+    );
+
+    term.process(b"\x1b[?2026l");
+    let events: Vec<_> = term
+        .poll_events()
+        .into_iter()
+        .filter(is_render_event)
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            TerminalEvent::ScreenCleared {
+                include_scrollback: false
+            },
+            TerminalEvent::DirtyRegion(0, 9),
+        ]
+    );
+
+    // No synchronized update, no DirtyRegion event
+    term.process(b"plain");
+    assert!(!term
+        .poll_events()
+        .iter()
+        .any(|e| matches!(e, TerminalEvent::DirtyRegion(..))));
+}
+
+#[test]
+fn test_synchronized_update_defers_render_events() {
+    let mut term = Terminal::new(40, 10);
+    term.set_cursor_trail_enabled(true);
+    term.poll_events();
+
+    term.process(b"\x1b[?2026h\x1b[3;3Hab\x1b[5;1H");
+    term.process(b"\x1bPq#0;2;100;0;0#0~~\x1b\\");
+    assert!(!term.poll_events().iter().any(is_render_event));
+
+    term.flush_synchronized_updates();
+    let events = term.poll_events();
+    let moves: Vec<_> = events
+        .iter()
+        .filter(|e| matches!(e, TerminalEvent::CursorMoved { .. }))
+        .collect();
+    assert_eq!(moves.len(), 1, "cursor moves are merged: {events:?}");
+    assert!(matches!(
+        moves[0],
+        TerminalEvent::CursorMoved { from: (0, 0), .. }
+    ));
+    assert!(events
+        .iter()
+        .any(|e| matches!(e, TerminalEvent::GraphicsAdded(4))));
+    let render: Vec<_> = events.iter().filter(|e| is_render_event(e)).collect();
+    assert!(matches!(
+        render.last(),
+        Some(TerminalEvent::DirtyRegion(..))
+    ));
+}
+
+#[test]
+fn test_synchronized_update_times_out() {
+    let mut term = Terminal::new(40, 10);
+    assert_eq!(
+        term.synchronized_update_timeout(),
+        DEFAULT_SYNC_UPDATE_TIMEOUT_MS
+    );
+    term.set_synchronized_update_timeout(1);
+    term.process(b"\x1b[?2026hframe");
+    assert!(term.get_dirty_rows().is_empty());
+
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    assert!(term.update_synchronized_updates());
+    assert!(!term.synchronized_updates());
+    assert_eq!(term.get_dirty_rows(), vec![0]);
+    assert!(!term.update_synchronized_updates());
+
+    // Also noticed when the next output arrives
+    term.process(b"\x1b[?2026h");
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    term.process(b"\r\nmore");
+    assert!(!term.synchronized_updates());
+
+    // The limit is the embedder's and survives RIS; 0 disables it
+    term.process(b"\x1bc");
+    assert_eq!(term.synchronized_update_timeout(), 1);
+    term.set_synchronized_update_timeout(0);
+    term.process(b"\x1b[?2026h");
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    assert!(!term.update_synchronized_updates());
+    assert!(term.synchronized_updates());
+}
+
+#[test]
+fn test_synchronized_updates_end_split_across_reads() {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"\x1b[?2026h");
+    term.mark_clean();
+    term.process(b"frame\x1b[?20");
+    term.process(b"26l");
+    assert!(!term.synchronized_updates());
+    assert_eq!(term.get_dirty_rows(), vec![0]);
+}
+
+#[test]
+fn test_synchronized_updates_end_with_other_params() {
+    for end in [&b"\x1b[?25;2026l"[..], b"\x1b[?2026;1l", b"\x1b[?02026l"] {
+        let mut term = Terminal::new(80, 24);
+        term.process(b"\x1b[?2026h");
+        term.mark_clean();
+        term.process(b"frame");
+        term.process(end);
+        assert!(!term.synchronized_updates(), "{end:?}");
+        assert_eq!(term.get_dirty_rows(), vec![0], "{end:?}");
+    }
+}
+
 #[test]
 fn test_mouse_event_encoding() {
     let mut term = Terminal::new(80, 24);
@@ -158,3 +292,126 @@ fn test_mouse_event_encoding() {
 
     assert_eq!(encoded, b"\x1b[<0;11;6M");
 }
+
+#[test]
+fn test_grapheme_cluster_mode_2027_decrqm() {
+    use crate::unicode_width_config::{GraphemeClustering, UnicodeVersion, UnicodeWidthPolicy};
+
+    let mut term = Terminal::new(80, 24);
+    assert!(term.grapheme_cluster_mode());
+    term.process(b"\x1b[?2027$p");
+    assert_eq!(term.drain_responses(), b"\x1b[?2027;1$y");
+
+    term.process(b"\x1b[?2027l\x1b[?2027$p");
+    assert!(!term.grapheme_cluster_mode());
+    assert_eq!(term.drain_responses(), b"\x1b[?2027;2$y");
+
+    // Fixed policies report permanently set/reset and ignore DECSET/DECRST
+    term.set_unicode_width_policy(UnicodeWidthPolicy::new(
+        UnicodeVersion::Auto,
+        GraphemeClustering::Always,
+    ));
+    term.process(b"\x1b[?2027l\x1b[?2027$p");
+    assert!(term.grapheme_cluster_mode());
+    assert_eq!(term.drain_responses(), b"\x1b[?2027;3$y");
+
+    term.set_unicode_width_policy(UnicodeWidthPolicy::new(
+        UnicodeVersion::Auto,
+        GraphemeClustering::Never,
+    ));
+    term.process(b"\x1b[?2027h\x1b[?2027$p");
+    assert!(!term.grapheme_cluster_mode());
+    assert_eq!(term.drain_responses(), b"\x1b[?2027;4$y");
+}
+
+#[test]
+fn test_unicode_width_policy_survives_ris() {
+    use crate::unicode_width_config::{GraphemeClustering, UnicodeVersion, UnicodeWidthPolicy};
+
+    let mut term = Terminal::new(80, 24);
+    let policy = UnicodeWidthPolicy::new(UnicodeVersion::Unicode9, GraphemeClustering::Never);
+    term.set_unicode_width_policy(policy);
+    term.process(b"\x1bc");
+    assert_eq!(term.unicode_width_policy(), policy);
+}
+
+#[test]
+fn test_grapheme_cluster_mode_2027_emits_mode_changed() {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"\x1b[?2027h");
+    assert!(term.poll_events().is_empty());
+
+    term.process(b"\x1b[?2027l");
+    assert!(term
+        .poll_events()
+        .contains(&TerminalEvent::ModeChanged(Mode::GraphemeClusters, false)));
+}
+
+#[test]
+fn test_ambiguous_width_affects_printing_and_cpr() {
+    use crate::unicode_width_config::AmbiguousWidth;
+
+    let mut term = Terminal::new(80, 24);
+    assert_eq!(term.ambiguous_width(), AmbiguousWidth::Narrow);
+    // Ambiguous character followed by a cursor position probe
+    term.process("\u{2592}\x1b[6n".as_bytes());
+    assert_eq!(term.drain_responses(), b"\x1b[1;2R");
+
+    term.set_ambiguous_width(AmbiguousWidth::Wide);
+    term.process("\r\u{2592}\x1b[6n".as_bytes());
+    assert_eq!(term.drain_responses(), b"\x1b[1;3R");
+    assert!(term.active_grid().get(0, 0).unwrap().flags.wide_char());
+}
+
+#[test]
+fn test_ambiguous_width_reports_mode_7700() {
+    use crate::unicode_width_config::AmbiguousWidth;
+
+    let mut term = Terminal::new(80, 24);
+    // No reports until the application asks for them
+    term.set_ambiguous_width(AmbiguousWidth::Wide);
+    assert!(term.drain_responses().is_empty());
+
+    term.process(b"\x1b[?7700h\x1b[?7700$p");
+    assert_eq!(term.drain_responses(), b"\x1b[?7700;1$y");
+    assert!(term.poll_events().contains(&TerminalEvent::ModeChanged(
+        Mode::AmbiguousWidthReports,
+        true
+    )));
+
+    term.set_ambiguous_width(AmbiguousWidth::Narrow);
+    assert_eq!(term.drain_responses(), b"\x1b[1W");
+    // Unchanged setting: no report
+    term.set_ambiguous_width(AmbiguousWidth::Narrow);
+    assert!(term.drain_responses().is_empty());
+    term.set_width_config(crate::unicode_width_config::WidthConfig::cjk());
+    assert_eq!(term.drain_responses(), b"\x1b[2W");
+
+    term.process(b"\x1b[?7700l\x1b[?7700$p");
+    assert_eq!(term.drain_responses(), b"\x1b[?7700;2$y");
+    term.set_ambiguous_width(AmbiguousWidth::Narrow);
+    assert!(term.drain_responses().is_empty());
+}
+
+#[test]
+fn test_grapheme_cluster_mode_off_uses_code_point_widths() {
+    // Woman + ZWJ + laptop
+    let zwj = "\u{1F469}\u{200D}\u{1F4BB}";
+
+    let mut term = Terminal::new(80, 24);
+    term.process(zwj.as_bytes());
+    assert_eq!(term.cursor().col, 2);
+
+    let mut term = Terminal::new(80, 24);
+    term.process(b"\x1b[?2027l");
+    term.process(zwj.as_bytes());
+    // Each emoji takes its own wide cell; the joiner stays on the first
+    assert_eq!(term.cursor().col, 4);
+    let grid = term.active_grid();
+    assert_eq!(grid.get(0, 0).unwrap().get_grapheme(), "\u{1F469}\u{200D}");
+    assert_eq!(grid.get(2, 0).unwrap().c, '\u{1F4BB}');
+
+    // Combining marks still attach, but VS16 no longer widens the base
+    term.process("\r\n\u{263A}\u{FE0F}x".as_bytes());
+    assert_eq!(term.active_grid().get(1, 1).unwrap().c, 'x');
+}
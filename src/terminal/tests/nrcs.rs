@@ -0,0 +1,125 @@
+// National Replacement Character Set (NRCS) and charset shift tests
+use crate::terminal::*;
+
+fn row_text(term: &Terminal, row: usize) -> String {
+    term.active_grid().row_text(row).trim_end().to_string()
+}
+
+fn nrcs_terminal() -> Terminal {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"\x1b[?42h");
+    assert!(term.nrcs_mode());
+    term
+}
+
+#[test]
+fn german_nrcs_replaces_brackets_and_braces() {
+    let mut term = nrcs_terminal();
+    term.process(b"\x1b(K@[\\]{|}~#");
+    assert_eq!(row_text(&term, 0), "§ÄÖÜäöüß#");
+}
+
+#[test]
+fn each_nrcs_final_byte_selects_its_set() {
+    let cases: [(&[u8], &str); 12] = [
+        (b"A", "£"),
+        (b"4", "£"),
+        (b"5", "Ä"),
+        (b"R", "°"),
+        (b"9", "â"),
+        (b"K", "Ä"),
+        (b"Y", "°"),
+        (b"`", "Æ"),
+        (b"%6", "Ã"),
+        (b"Z", "¡"),
+        (b"7", "Ä"),
+        (b"=", "é"),
+    ];
+    for (designator, expected) in cases {
+        let mut term = nrcs_terminal();
+        let mut seq = b"\x1b(".to_vec();
+        seq.extend_from_slice(designator);
+        // '[' for every set except British and Dutch, which replace '#'
+        let probe = if expected == "£" { b'#' } else { b'[' };
+        seq.push(probe);
+        term.process(&seq);
+        assert_eq!(row_text(&term, 0), expected, "ESC ( {:?}", designator);
+    }
+}
+
+#[test]
+fn nrcs_needs_decnrcm_except_british() {
+    let mut term = Terminal::new(80, 24);
+    assert!(!term.nrcs_mode());
+    term.process(b"\x1b(K[");
+    assert_eq!(row_text(&term, 0), "[");
+    term.process(b"\x1b(A#");
+    assert_eq!(row_text(&term, 0), "[£");
+}
+
+#[test]
+fn resetting_decnrcm_drops_national_sets() {
+    let mut term = nrcs_terminal();
+    term.process(b"\x1b(K\x1b)0\x1b[?42l[\x0eq\x0f");
+    assert!(!term.nrcs_mode());
+    // G0 is back to ASCII, the line drawing set in G1 stays
+    assert_eq!(row_text(&term, 0), "[─");
+}
+
+#[test]
+fn scs_finals_are_not_taken_for_other_escapes() {
+    let mut term = nrcs_terminal();
+    term.process(b"\x1b[5;5H\x1b7\x1b[1;1H");
+    // ESC ( 7 designates Swedish, it does not save the cursor
+    term.process(b"\x1b(7\x1b8");
+    assert_eq!((term.cursor.col, term.cursor.row), (4, 4));
+    // ESC ( H designates Swedish, it does not set a tab stop
+    term.process(b"\x1b(H");
+    assert!(!term.tab_stops[4]);
+}
+
+#[test]
+fn locking_shifts_invoke_g2_and_g3_into_gl() {
+    let mut term = nrcs_terminal();
+    term.process(b"\x1b*0\x1b+K\x1bnq\x1bo[\x0fq");
+    assert_eq!(row_text(&term, 0), "─Äq");
+}
+
+#[test]
+fn single_shift_applies_to_one_character() {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"\x1b*0\x1bNqq\x1b+A\x1bO##");
+    assert_eq!(row_text(&term, 0), "─q£#");
+}
+
+#[test]
+fn gr_is_only_mapped_after_a_locking_shift() {
+    let mut term = Terminal::new(80, 24);
+    // G2 holds line drawing for single shifts; Latin-1 text is untouched
+    term.process(b"\x1b*0");
+    term.process("ñ".as_bytes());
+    // LS2R invokes G2 into GR: U+00F1 stands for byte 0xF1 ('q' in GL)
+    term.process(b"\x1b}");
+    term.process("ñ¿".as_bytes());
+    // '?' has no line drawing glyph, so U+00BF stays as it is
+    assert_eq!(row_text(&term, 0), "ñ─¿");
+}
+
+#[test]
+fn decrqm_reports_decnrcm() {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"\x1b[?42$p");
+    assert_eq!(term.drain_responses(), b"\x1b[?42;2$y");
+    term.process(b"\x1b[?42h\x1b[?42$p");
+    assert_eq!(term.drain_responses(), b"\x1b[?42;1$y");
+}
+
+#[test]
+fn ris_clears_designations_and_shifts() {
+    let mut term = nrcs_terminal();
+    term.process(b"\x1b(K\x1b*0\x1bn\x1b}\x1bc");
+    assert!(!term.nrcs_mode());
+    term.process(b"[q");
+    term.process("ñ".as_bytes());
+    assert_eq!(row_text(&term, 0), "[qñ");
+}
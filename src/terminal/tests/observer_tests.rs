@@ -37,7 +37,7 @@ impl MockObserver {
     fn event_type_str(event: &TerminalEvent) -> String {
         match event {
             TerminalEvent::BellRang(_) => "bell".to_string(),
-            TerminalEvent::TitleChanged(t) => format!("title:{t}"),
+            TerminalEvent::TitleChanged { title, .. } => format!("title:{title}"),
             TerminalEvent::ZoneOpened { zone_id, .. } => format!("zone_opened:{zone_id}"),
             TerminalEvent::ZoneClosed { zone_id, .. } => format!("zone_closed:{zone_id}"),
             TerminalEvent::ZoneScrolledOut { zone_id, .. } => {
@@ -0,0 +1,126 @@
+// Media Copy (printer controller mode and print screen) tests
+use crate::terminal::printer::{PrintJobKind, PrinterSink, MAX_PRINT_JOB_BYTES};
+use crate::terminal::*;
+use std::sync::{Arc, Mutex};
+
+fn print_jobs(term: &mut Terminal) -> Vec<(PrintJobKind, usize, Vec<u8>, bool)> {
+    term.poll_events()
+        .into_iter()
+        .filter_map(|event| match event {
+            TerminalEvent::PrintJobCompleted {
+                kind,
+                size,
+                data,
+                sink,
+            } => Some((kind, size, data, sink)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn controller_mode_diverts_output_from_the_screen() {
+    let mut term = Terminal::new(20, 5);
+    term.process(b"ab\x1b[5ihidden\x1b[31m text\x1b[4icd");
+
+    assert_eq!(term.active_grid().row_text(0).trim_end(), "abcd");
+    assert_eq!(term.fg, term.theme.default_fg);
+    assert!(!term.printer_controller_active());
+    assert_eq!(
+        print_jobs(&mut term),
+        vec![(
+            PrintJobKind::Controller,
+            16,
+            b"hidden\x1b[31m text".to_vec(),
+            false
+        )]
+    );
+}
+
+#[test]
+fn controller_mode_spans_chunks_and_split_terminators() {
+    let mut term = Terminal::new(20, 5);
+    term.process(b"\x1b[5ione");
+    assert!(term.printer_controller_active());
+    assert!(print_jobs(&mut term).is_empty());
+
+    // An ESC that does not start CSI 4 i is data
+    term.process(b" \x1b\x1b[3i two \x1b[");
+    term.process(b"4");
+    term.process(b"ixy");
+
+    assert!(!term.printer_controller_active());
+    assert_eq!(term.active_grid().row_text(0).trim_end(), "xy");
+    let jobs = print_jobs(&mut term);
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].2, b"one \x1b\x1b[3i two ".to_vec());
+}
+
+#[test]
+fn print_screen_delivers_screen_text() {
+    let mut term = Terminal::new(10, 3);
+    term.process(b"hello\r\n  world\x1b[i");
+
+    assert_eq!(
+        print_jobs(&mut term),
+        vec![(
+            PrintJobKind::Screen,
+            15,
+            b"hello\n  world\n\n".to_vec(),
+            false
+        )]
+    );
+}
+
+#[test]
+fn event_data_is_capped() {
+    let mut term = Terminal::new(20, 5);
+    term.process(b"\x1b[5i");
+    term.process(&vec![b'x'; MAX_PRINT_JOB_BYTES + 10]);
+    term.process(b"\x1b[4i");
+
+    let jobs = print_jobs(&mut term);
+    assert_eq!(jobs[0].1, MAX_PRINT_JOB_BYTES + 10);
+    assert_eq!(jobs[0].2.len(), MAX_PRINT_JOB_BYTES);
+}
+
+#[derive(Clone, Default)]
+struct RecordingSink(Arc<Mutex<Vec<String>>>);
+
+impl PrinterSink for RecordingSink {
+    fn begin(&mut self, kind: PrintJobKind) {
+        self.0
+            .lock()
+            .unwrap()
+            .push(format!("begin {}", kind.name()));
+    }
+    fn write(&mut self, data: &[u8]) {
+        self.0
+            .lock()
+            .unwrap()
+            .push(String::from_utf8_lossy(data).into_owned());
+    }
+    fn finish(&mut self) {
+        self.0.lock().unwrap().push("finish".to_string());
+    }
+}
+
+#[test]
+fn sink_receives_jobs_and_survives_reset() {
+    let mut term = Terminal::new(20, 5);
+    let sink = RecordingSink::default();
+    term.set_printer_sink(sink.clone());
+    term.reset();
+    assert!(term.has_printer_sink());
+
+    term.process(b"\x1b[5ione");
+    term.process(b"two\x1b[4i");
+    assert_eq!(
+        *sink.0.lock().unwrap(),
+        ["begin controller", "one", "two", "finish"]
+    );
+    assert_eq!(
+        print_jobs(&mut term),
+        vec![(PrintJobKind::Controller, 6, Vec::new(), true)]
+    );
+}
@@ -0,0 +1,201 @@
+// Heuristic prompt detection tests
+use crate::terminal::*;
+use crate::zone::ZoneType;
+
+/// Terminal with detection enabled and no idle wait
+fn detecting_terminal() -> Terminal {
+    let mut term = Terminal::new(40, 10);
+    term.set_prompt_detection_config(PromptDetectionConfig {
+        enabled: true,
+        idle_ms: 0,
+        ..Default::default()
+    })
+    .unwrap();
+    term
+}
+
+fn zone_summary(term: &Terminal) -> Vec<(ZoneType, usize, usize, Option<String>)> {
+    term.get_zones()
+        .iter()
+        .map(|z| {
+            assert!(z.heuristic);
+            (
+                z.zone_type,
+                z.abs_row_start,
+                z.abs_row_end,
+                z.command.clone(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_prompt_detection_disabled_by_default() {
+    let mut term = Terminal::new(40, 10);
+    assert!(!term.is_prompt_detection_enabled());
+    term.process(b"user@host:~$ ");
+    assert!(!term.update_prompt_detection());
+    assert!(term.get_zones().is_empty());
+}
+
+#[test]
+fn test_prompt_detection_builds_prompt_command_output_zones() {
+    let mut term = detecting_terminal();
+    term.process(b"user@host:~$ ");
+    assert!(term.update_prompt_detection());
+    // Same prompt is not detected twice
+    assert!(!term.update_prompt_detection());
+
+    term.process(b"ls -l");
+    term.process(b"\r\nfile1\r\nfile2\r\n");
+    term.process(b"user@host:~$ ");
+    assert!(term.update_prompt_detection());
+
+    let ls = Some("ls -l".to_string());
+    assert_eq!(
+        zone_summary(&term),
+        vec![
+            (ZoneType::Prompt, 0, 0, None),
+            (ZoneType::Command, 0, 0, ls.clone()),
+            (ZoneType::Output, 1, 2, ls),
+            (ZoneType::Prompt, 3, 3, None),
+        ]
+    );
+}
+
+#[test]
+fn test_prompt_detection_emits_zone_events() {
+    let mut term = detecting_terminal();
+    term.process(b"$ ");
+    term.update_prompt_detection();
+    term.process(b"true\r\n");
+
+    let opened: Vec<_> = term
+        .poll_events()
+        .into_iter()
+        .filter_map(|e| match e {
+            TerminalEvent::ZoneOpened { zone_type, .. } => Some(zone_type),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        opened,
+        vec![ZoneType::Prompt, ZoneType::Command, ZoneType::Output]
+    );
+}
+
+#[test]
+fn test_prompt_detection_waits_for_idle_output() {
+    let mut term = Terminal::new(40, 10);
+    term.set_prompt_detection_config(PromptDetectionConfig {
+        enabled: true,
+        idle_ms: 60_000,
+        ..Default::default()
+    })
+    .unwrap();
+    term.process(b"$ ");
+    assert!(!term.update_prompt_detection());
+    assert!(term.get_zones().is_empty());
+}
+
+#[test]
+fn test_prompt_detection_requires_cursor_at_end_of_line() {
+    let mut term = detecting_terminal();
+    // Cursor parked before existing text
+    term.process(b"$ partial\x1b[3G");
+    assert!(!term.update_prompt_detection());
+    // Text that does not end like a prompt
+    term.process(b"\r\nBuilding crate");
+    assert!(!term.update_prompt_detection());
+    assert!(term.get_zones().is_empty());
+}
+
+#[test]
+fn test_prompt_detection_keeps_wrapped_command_together() {
+    let mut term = Terminal::new(10, 10);
+    term.set_prompt_detection_config(PromptDetectionConfig {
+        enabled: true,
+        idle_ms: 0,
+        ..Default::default()
+    })
+    .unwrap();
+    term.process(b"$ ");
+    term.update_prompt_detection();
+    // Typing wraps onto a second row without finishing the command
+    term.process(b"echo 12345");
+    assert_eq!(term.get_zones().len(), 1);
+
+    term.process(b"\r\nout\r\n");
+    let zones = term.get_zones();
+    assert_eq!(zones[1].zone_type, ZoneType::Command);
+    assert_eq!(zones[1].abs_row_end, 1);
+    assert_eq!(zones[1].command.as_deref(), Some("echo 12345"));
+    assert_eq!(zones[2].zone_type, ZoneType::Output);
+    assert_eq!(zones[2].abs_row_start, 2);
+}
+
+#[test]
+fn test_prompt_detection_stops_after_osc_133() {
+    let mut term = detecting_terminal();
+    term.process(b"\x1b]133;A\x07$ ");
+    assert!(!term.update_prompt_detection());
+    assert!(term.get_zones().iter().all(|z| !z.heuristic));
+}
+
+#[test]
+fn test_prompt_detection_skips_alt_screen() {
+    let mut term = detecting_terminal();
+    term.process(b"\x1b[?1049h$ ");
+    assert!(!term.update_prompt_detection());
+    assert!(term.get_zones().is_empty());
+}
+
+#[test]
+fn test_prompt_detection_custom_patterns() {
+    let mut term = Terminal::new(40, 10);
+    let config = PromptDetectionConfig {
+        enabled: true,
+        patterns: vec![r"^mysql> $".to_string()],
+        idle_ms: 0,
+    };
+    term.set_prompt_detection_config(config.clone()).unwrap();
+    assert_eq!(term.prompt_detection_config(), &config);
+
+    term.process(b"$ ");
+    assert!(!term.update_prompt_detection());
+    term.process(b"\r\nmysql> ");
+    assert!(term.update_prompt_detection());
+
+    let err = term
+        .set_prompt_detection_config(PromptDetectionConfig {
+            patterns: vec!["(".to_string()],
+            ..Default::default()
+        })
+        .unwrap_err();
+    assert!(err.contains("Invalid regex"));
+    // Rejected config leaves the old one in place
+    assert_eq!(term.prompt_detection_config(), &config);
+}
+
+#[test]
+fn test_prompt_detection_runs_on_next_output_without_polling() {
+    let mut term = detecting_terminal();
+    term.process(b"$ ");
+    assert!(term.get_zones().is_empty());
+    // Echo of the first typed key finds the idle prompt
+    term.process(b"p");
+    assert_eq!(zone_summary(&term), vec![(ZoneType::Prompt, 0, 0, None)]);
+}
+
+#[test]
+fn test_prompt_detection_survives_reset() {
+    let mut term = detecting_terminal();
+    term.process(b"user@host:~$ ");
+    assert!(term.update_prompt_detection());
+
+    term.process(b"\x1bc");
+    assert!(term.is_prompt_detection_enabled());
+    assert_eq!(term.prompt_detection_config().idle_ms, 0);
+    term.process(b"user@host:~$ ");
+    assert!(term.update_prompt_detection());
+}
@@ -29,6 +29,69 @@ fn test_dsr_cursor_position() {
     assert_eq!(response, b"\x1b[10;20R");
 }
 
+#[test]
+fn test_decxcpr_reports_page() {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"\x1b[10;20H\x1b[?6n");
+    assert_eq!(term.drain_responses(), b"\x1b[?10;20;1R");
+    // No page number before VT420
+    term.process(b"\x1b[63\"p\x1b[?6n");
+    assert_eq!(term.drain_responses(), b"\x1b[?10;20R");
+}
+
+#[test]
+fn test_decxcpr_respects_origin_mode() {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"\x1b[5;20r\x1b[?6h\x1b[3;4H\x1b[?6n\x1b[6n");
+    assert_eq!(term.drain_responses(), b"\x1b[?3;4;1R\x1b[3;4R");
+}
+
+#[test]
+fn test_dsr_printer_status() {
+    struct NullPrinter;
+    impl crate::terminal::printer::PrinterSink for NullPrinter {
+        fn begin(&mut self, _kind: crate::terminal::printer::PrintJobKind) {}
+        fn write(&mut self, _data: &[u8]) {}
+        fn finish(&mut self) {}
+    }
+
+    let mut term = Terminal::new(80, 24);
+    term.process(b"\x1b[?15n");
+    assert_eq!(term.drain_responses(), b"\x1b[?13n");
+    term.set_printer_sink(NullPrinter);
+    term.process(b"\x1b[?15n");
+    assert_eq!(term.drain_responses(), b"\x1b[?10n");
+}
+
+#[test]
+fn test_dsr_udk_status() {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"\x1b[?25n");
+    assert_eq!(term.drain_responses(), b"\x1b[?20n");
+}
+
+#[test]
+fn test_dsr_keyboard_status_by_level() {
+    let mut term = Terminal::new(80, 24);
+    for (level, expected) in [
+        (&b"62"[..], &b"\x1b[?27;1n"[..]),
+        (b"63", b"\x1b[?27;1;0n"),
+        (b"65", b"\x1b[?27;1;0;0n"),
+    ] {
+        term.process(b"\x1b[");
+        term.process(level);
+        term.process(b"\"p\x1b[?26n");
+        assert_eq!(term.drain_responses(), expected);
+    }
+}
+
+#[test]
+fn test_dsr_private_forms_need_the_question_mark() {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"\x1b[15n\x1b[25n\x1b[26n");
+    assert!(term.drain_responses().is_empty());
+}
+
 #[test]
 fn test_decrqm_application_cursor() {
     let mut term = Terminal::new(80, 24);
@@ -36,6 +99,29 @@ fn test_decrqm_application_cursor() {
     assert_eq!(term.drain_responses(), b"\x1b[?1;2$y");
 }
 
+#[test]
+fn test_da_primary_vt100_level() {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"\x1b[61\"p\x1b[c");
+    assert_eq!(term.drain_responses(), b"\x1b[?1;2c");
+}
+
+#[test]
+fn test_decid_matches_primary_da() {
+    let mut term = Terminal::new(80, 24);
+    for level in [b"61", b"62", b"64"] {
+        term.process(b"\x1b[");
+        term.process(level);
+        term.process(b"\"p\x1b[c");
+        let da = term.drain_responses();
+        term.process(b"\x1bZ");
+        assert_eq!(term.drain_responses(), da);
+    }
+    // With an intermediate it is not DECID
+    term.process(b"\x1b#Z");
+    assert!(term.drain_responses().is_empty());
+}
+
 #[test]
 fn test_enq_answerback_string() {
     let mut term = Terminal::new(80, 24);
@@ -44,6 +130,23 @@ fn test_enq_answerback_string() {
     assert_eq!(term.drain_responses(), b"par-term");
 }
 
+#[test]
+fn test_enq_silent_by_default() {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"a\x05b");
+    assert!(term.drain_responses().is_empty());
+    assert_eq!(term.export_text().trim_end(), "ab");
+}
+
+#[test]
+fn test_answerback_survives_ris() {
+    let mut term = Terminal::new(80, 24);
+    term.set_answerback_string(Some("par-term".to_string()));
+    term.process(b"\x1bc\x05");
+    assert_eq!(term.answerback_string(), Some("par-term"));
+    assert_eq!(term.drain_responses(), b"par-term");
+}
+
 #[test]
 fn test_osc_10_query_default_fg() {
     let mut term = Terminal::new(80, 24);
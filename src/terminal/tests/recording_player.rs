@@ -0,0 +1,154 @@
+// Recording player (animated SVG / HTML) export tests
+use crate::terminal::*;
+
+fn output(timestamp: u64, data: &[u8]) -> RecordingEvent {
+    RecordingEvent {
+        timestamp,
+        event_type: RecordingEventType::Output,
+        data: data.to_vec(),
+        metadata: None,
+    }
+}
+
+fn session(events: Vec<RecordingEvent>) -> RecordingSession {
+    RecordingSession {
+        id: "test".to_string(),
+        title: "demo <1>".to_string(),
+        initial_size: (20, 3),
+        duration: events.last().map_or(0, |e| e.timestamp),
+        events,
+        env: Default::default(),
+        created_at: 0,
+    }
+}
+
+#[test]
+fn test_export_recording_svg_animates_frames() {
+    let term = Terminal::new(20, 3);
+    let session = session(vec![output(100, b"hello"), output(500, b" world")]);
+    let svg = term
+        .export_recording(
+            &session,
+            RecordingExportFormat::Svg,
+            &RecordingPlayerConfig::default(),
+        )
+        .unwrap();
+
+    assert!(svg.starts_with("<?xml"));
+    assert!(svg.contains("<title>demo &lt;1&gt;</title>"));
+    // Initial blank frame plus one per output event
+    assert_eq!(svg.matches("<animate ").count(), 3);
+    assert!(svg.contains("hello"));
+    assert!(svg.contains("hello world"));
+    assert!(svg.contains(r#"repeatCount="indefinite""#));
+    // 500 ms of events plus the default 1 s hold
+    assert!(svg.contains(r#"dur="1.500s""#));
+}
+
+#[test]
+fn test_export_recording_merges_close_events() {
+    let term = Terminal::new(20, 3);
+    let session = session(vec![
+        output(100, b"a"),
+        output(110, b"b"),
+        output(400, b"c"),
+    ]);
+    let svg = term
+        .export_recording(
+            &session,
+            RecordingExportFormat::Svg,
+            &RecordingPlayerConfig::default(),
+        )
+        .unwrap();
+
+    // "a" and "b" land within one frame interval
+    assert_eq!(svg.matches("<animate ").count(), 3);
+    assert!(!svg.contains(">a<"));
+}
+
+#[test]
+fn test_export_recording_idle_limit_compresses_gaps() {
+    let term = Terminal::new(20, 3);
+    let session = session(vec![output(100, b"a"), output(60_100, b"b")]);
+
+    let limited = RecordingPlayerConfig {
+        idle_time_limit_ms: Some(500),
+        ..Default::default()
+    };
+    let svg = term
+        .export_recording(&session, RecordingExportFormat::Svg, &limited)
+        .unwrap();
+    assert!(svg.contains(r#"dur="1.600s""#));
+
+    let unlimited = RecordingPlayerConfig {
+        idle_time_limit_ms: None,
+        loop_playback: false,
+        ..Default::default()
+    };
+    let svg = term
+        .export_recording(&session, RecordingExportFormat::Svg, &unlimited)
+        .unwrap();
+    assert!(svg.contains(r#"dur="61.100s""#));
+    assert!(svg.contains(r#"repeatCount="1""#));
+}
+
+#[test]
+fn test_export_recording_html_embeds_player() {
+    let term = Terminal::new(20, 3);
+    let session = session(vec![output(100, b"\x1b[31mred</script>")]);
+    let html = term
+        .export_recording(
+            &session,
+            RecordingExportFormat::Html,
+            &RecordingPlayerConfig::default(),
+        )
+        .unwrap();
+
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<title>demo &lt;1&gt;</title>"));
+    assert!(html.contains(r#"<svg id="screen""#));
+    assert!(html.contains("requestAnimationFrame"));
+    assert!(html.contains(r#""t":100"#));
+    // Frame markup cannot terminate the player script early
+    assert_eq!(html.matches("</script>").count(), 1);
+}
+
+#[test]
+fn test_export_recording_follows_resize() {
+    let term = Terminal::new(20, 3);
+    let mut events = vec![output(100, b"x")];
+    events.push(RecordingEvent {
+        timestamp: 200,
+        event_type: RecordingEventType::Resize,
+        data: Vec::new(),
+        metadata: Some((40, 5)),
+    });
+    let resized = session(events);
+    let config = RecordingPlayerConfig::default();
+    let svg = term
+        .export_recording(&resized, RecordingExportFormat::Svg, &config)
+        .unwrap();
+
+    let narrow = Terminal::new(20, 3)
+        .export_recording(&session(vec![]), RecordingExportFormat::Svg, &config)
+        .unwrap();
+    let width = |s: &str| {
+        let start = s.find("width=\"").unwrap() + 7;
+        let end = s[start..].find('"').unwrap() + start;
+        s[start..end].parse::<f32>().unwrap()
+    };
+    assert!(width(&svg) > width(&narrow));
+}
+
+#[test]
+fn test_export_recording_rejects_gif_and_video() {
+    let term = Terminal::new(20, 3);
+    let session = session(vec![output(0, b"x")]);
+    let config = RecordingPlayerConfig::default();
+    assert!(term
+        .export_recording(&session, RecordingExportFormat::Gif, &config)
+        .is_err());
+    assert!(term
+        .export_recording(&session, RecordingExportFormat::Video, &config)
+        .is_err());
+}
@@ -12,3 +12,87 @@ fn test_decfra_fill_rectangle() {
         }
     }
 }
+
+fn bold_cells(term: &Terminal, row: usize) -> Vec<usize> {
+    (0..term.grid().cols())
+        .filter(|&col| term.grid().get(col, row).unwrap().flags.bold())
+        .collect()
+}
+
+#[test]
+fn test_deccara_rectangle_mode_by_default() {
+    use crate::grid::AttributeChangeExtent;
+    let mut term = Terminal::new(10, 5);
+    assert_eq!(
+        term.attribute_change_extent(),
+        AttributeChangeExtent::Rectangle
+    );
+    term.process(b"\x1b[2;3;3;4;1;4$r");
+
+    assert!(bold_cells(&term, 0).is_empty());
+    assert_eq!(bold_cells(&term, 1), [2, 3]);
+    assert_eq!(bold_cells(&term, 2), [2, 3]);
+    assert!(term.grid().get(2, 2).unwrap().flags.underline());
+}
+
+#[test]
+fn test_deccara_stream_mode_wraps_between_rows() {
+    use crate::grid::AttributeChangeExtent;
+    let mut term = Terminal::new(10, 5);
+    term.process(b"\x1b[1*x");
+    assert_eq!(
+        term.attribute_change_extent(),
+        AttributeChangeExtent::Stream
+    );
+    // No DECREQTPARM report for DECSACE
+    assert!(term.drain_responses().is_empty());
+
+    term.process(b"\x1b[2;8;4;2;1$r");
+    assert_eq!(bold_cells(&term, 1), [7, 8, 9]);
+    assert_eq!(bold_cells(&term, 2), (0..10).collect::<Vec<_>>());
+    assert_eq!(bold_cells(&term, 3), [0, 1]);
+    assert!(bold_cells(&term, 4).is_empty());
+
+    term.process(b"\x1b[2*x");
+    assert_eq!(
+        term.attribute_change_extent(),
+        AttributeChangeExtent::Rectangle
+    );
+}
+
+#[test]
+fn test_deccara_clears_attributes() {
+    let mut term = Terminal::new(10, 5);
+    term.process(b"\x1b[1;7mabc\x1b[m");
+    term.process(b"\x1b[1;1;1;2;22;27$r");
+
+    let grid = term.grid();
+    assert!(!grid.get(0, 0).unwrap().flags.bold());
+    assert!(!grid.get(1, 0).unwrap().flags.reverse());
+    assert!(grid.get(2, 0).unwrap().flags.bold());
+
+    // No attributes means 0: everything off
+    term.process(b"\x1b[1;3;1;3$r");
+    assert!(!term.grid().get(2, 0).unwrap().flags.reverse());
+}
+
+#[test]
+fn test_decrara_toggles_in_both_extents() {
+    let mut term = Terminal::new(10, 5);
+    term.process(b"\x1b[1;2;2;3;7$t");
+    assert!(term.grid().get(1, 0).unwrap().flags.reverse());
+    term.process(b"\x1b[1;2;2;3;7$t");
+    assert!(!term.grid().get(1, 0).unwrap().flags.reverse());
+
+    // Left of right edge is an empty rectangle but a valid stream
+    term.process(b"\x1b[1;9;2;2;7$t");
+    assert!(!term.grid().get(8, 0).unwrap().flags.reverse());
+    term.process(b"\x1b[0*x\x1b[1;9;2;2;7$t");
+    let grid = term.grid();
+    assert!(!grid.get(7, 0).unwrap().flags.reverse());
+    assert!(grid.get(8, 0).unwrap().flags.reverse());
+    assert!(grid.get(9, 0).unwrap().flags.reverse());
+    assert!(grid.get(0, 1).unwrap().flags.reverse());
+    assert!(grid.get(1, 1).unwrap().flags.reverse());
+    assert!(!grid.get(2, 1).unwrap().flags.reverse());
+}
@@ -0,0 +1,92 @@
+//! Reply queue priority and backpressure tests
+
+use crate::terminal::{
+    ReplyOverflowPolicy, ReplyPriority, Terminal, DEFAULT_MAX_REPLY_QUEUE_BYTES,
+};
+
+fn clipboard_terminal(content: &str) -> Terminal {
+    let mut term = Terminal::new(80, 24);
+    term.set_allow_clipboard_read(true);
+    term.set_clipboard(Some(content.to_string()));
+    term
+}
+
+#[test]
+fn clipboard_reply_is_delivered_after_device_queries() {
+    let mut term = clipboard_terminal("hi");
+    term.process(b"\x1b]52;c;?\x07\x1b[6n\x1b]11;?\x07\x1b[c");
+
+    let replies = String::from_utf8(term.drain_responses()).unwrap();
+    let clipboard = replies.find("\x1b]52;c;aGk=").unwrap();
+    let cpr = replies.find("\x1b[1;1R").unwrap();
+    let color = replies.find("\x1b]11;rgb:").unwrap();
+    let da = replies.find("\x1b[?").unwrap();
+    // Query replies keep their order, so DA still follows the OSC 11 reply
+    assert!(cpr < color && color < da && da < clipboard);
+    assert!(!term.has_pending_responses());
+}
+
+#[test]
+fn query_flood_is_capped() {
+    let mut term = Terminal::new(80, 24);
+    assert_eq!(term.max_reply_queue_bytes(), DEFAULT_MAX_REPLY_QUEUE_BYTES);
+    term.set_max_reply_queue_bytes(64);
+    term.process(&b"\x1b[5n".repeat(100));
+
+    // Each DSR reply is 4 bytes
+    assert_eq!(term.drain_responses().len(), 64);
+    assert_eq!(term.dropped_reply_count(), 84);
+
+    // Draining makes room again
+    term.process(b"\x1b[5n");
+    assert_eq!(term.drain_responses(), b"\x1b[0n");
+}
+
+#[test]
+fn query_replies_evict_bulk_replies_when_full() {
+    let mut term = clipboard_terminal(&"x".repeat(30));
+    term.set_max_reply_queue_bytes(60);
+    term.process(b"\x1b]52;c;?\x07");
+    assert!(term.has_pending_responses());
+    term.process(&b"\x1b[5n".repeat(5));
+
+    assert_eq!(term.drain_responses(), b"\x1b[0n".repeat(5));
+    assert_eq!(term.dropped_reply_count(), 1);
+}
+
+#[test]
+fn drop_newest_policy_keeps_bulk_replies() {
+    let mut term = clipboard_terminal(&"x".repeat(30));
+    term.set_max_reply_queue_bytes(60);
+    term.set_reply_overflow_policy(ReplyOverflowPolicy::DropNewest);
+    term.process(b"\x1b]52;c;?\x07");
+    term.process(&b"\x1b[5n".repeat(5));
+
+    // The 49-byte clipboard reply leaves room for two DSR replies
+    let replies = term.drain_responses();
+    assert!(replies.starts_with(b"\x1b[0n\x1b[0n\x1b]52;c;"));
+    assert_eq!(replies.len(), 57);
+    assert_eq!(term.dropped_reply_count(), 3);
+}
+
+#[test]
+fn upload_data_ignores_the_cap() {
+    let mut term = Terminal::new(80, 24);
+    term.set_max_reply_queue_bytes(8);
+    term.send_upload_data(&[0u8; 64]);
+    assert!(term.push_reply(ReplyPriority::Query, b"\x1b[0n"));
+
+    let replies = term.drain_responses();
+    assert!(replies.starts_with(b"\x1b[0nok\n"));
+    assert!(replies.ends_with(b"\n\n"));
+    assert_eq!(term.dropped_reply_count(), 0);
+}
+
+#[test]
+fn pending_replies_and_limits_survive_reset() {
+    let mut term = Terminal::new(80, 24);
+    term.set_max_reply_queue_bytes(128);
+    term.process(b"\x1b[5n\x1bc");
+    assert_eq!(term.max_reply_queue_bytes(), 128);
+    assert_eq!(term.drain_responses(), b"\x1b[0n");
+}
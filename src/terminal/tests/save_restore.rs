@@ -0,0 +1,100 @@
+// DECSC/DECRC save and restore tests
+use crate::terminal::*;
+
+fn row_text(term: &Terminal, row: usize) -> String {
+    term.active_grid().row_text(row).trim_end().to_string()
+}
+
+#[test]
+fn decrc_restores_origin_mode() {
+    let mut term = Terminal::new(20, 10);
+    term.process(b"\x1b[3;8r\x1b[?6h\x1b7\x1b[?6l\x1b[H\x1b8");
+    assert!(term.origin_mode());
+    // Homing is relative to the top margin again
+    term.process(b"\x1b[1;1H");
+    assert_eq!((term.cursor.col, term.cursor.row), (0, 2));
+}
+
+#[test]
+fn decrc_restores_origin_mode_off() {
+    let mut term = Terminal::new(20, 10);
+    term.process(b"\x1b[3;8r\x1b7\x1b[?6h\x1b8\x1b[1;1H");
+    assert!(!term.origin_mode());
+    assert_eq!((term.cursor.col, term.cursor.row), (0, 0));
+}
+
+#[test]
+fn decrc_restores_the_pending_wrap() {
+    let mut term = Terminal::new(10, 5);
+    term.process(b"abcdefghij\x1b7\x1b[3;1H\x1b8X");
+    // The saved wrap is still pending, so X lands on the next line
+    assert_eq!(row_text(&term, 0), "abcdefghij");
+    assert_eq!(row_text(&term, 1), "X");
+}
+
+#[test]
+fn decrc_without_pending_wrap_overwrites_last_column() {
+    let mut term = Terminal::new(10, 5);
+    term.process(b"\x1b[1;10H\x1b7abcdefghij\x1b8X");
+    // The wrap pending after "a" was not saved, so X replaces it in place
+    assert_eq!(term.active_grid().get(9, 0).unwrap().c, 'X');
+    assert_eq!(row_text(&term, 1), "bcdefghij");
+    assert_eq!(row_text(&term, 2), "");
+}
+
+#[test]
+fn decrc_restores_charset_designations_and_shifts() {
+    let mut term = Terminal::new(20, 5);
+    // Line drawing in G1 invoked into GL with SO
+    term.process(b"\x1b)0\x0e\x1b7\x0f\x1b(B\x1b)B\x1b8q");
+    assert_eq!(row_text(&term, 0), "─");
+}
+
+#[test]
+fn decrc_restores_a_pending_single_shift() {
+    let mut term = Terminal::new(20, 5);
+    term.process(b"\x1b*0\x1bN\x1b7q\x1b8q");
+    // SS2 applied to the first q and is pending again after DECRC
+    assert_eq!(row_text(&term, 0), "─");
+    term.process(b"q");
+    assert_eq!(row_text(&term, 0), "─q");
+}
+
+#[test]
+fn decrc_restores_selective_erase_protection() {
+    let mut term = Terminal::new(20, 5);
+    term.process(b"\x1b[1\"q\x1b7\x1b[0\"q\x1b8A");
+    assert!(term.active_grid().get(0, 0).unwrap().flags.guarded());
+    term.process(b"\x1b[0\"q\x1b7\x1b[1\"q\x1b8B");
+    assert!(!term.active_grid().get(1, 0).unwrap().flags.guarded());
+}
+
+#[test]
+fn decrc_restores_sgr_attributes() {
+    let mut term = Terminal::new(20, 5);
+    term.process(b"\x1b[1;4;31m\x1b7\x1b[0m\x1b8X");
+    let cell = term.active_grid().get(0, 0).unwrap();
+    assert!(cell.flags.bold());
+    assert!(cell.flags.underline());
+    assert_eq!(
+        cell.fg,
+        crate::color::Color::Named(crate::color::NamedColor::Red)
+    );
+}
+
+#[test]
+fn decrc_clamps_the_cursor_after_a_resize() {
+    let mut term = Terminal::new(20, 10);
+    term.process(b"\x1b[9;18H\x1b7");
+    term.resize(10, 5);
+    term.process(b"\x1b8");
+    assert_eq!((term.cursor.col, term.cursor.row), (9, 4));
+}
+
+#[test]
+fn ris_forgets_the_saved_state() {
+    let mut term = Terminal::new(20, 10);
+    term.process(b"\x1b[?6h\x1b[5;5H\x1b7\x1bc\x1b[3;3H\x1b8");
+    assert!(!term.origin_mode());
+    assert_eq!((term.cursor.col, term.cursor.row), (2, 2));
+}
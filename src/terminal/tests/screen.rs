@@ -521,3 +521,107 @@ fn test_is_line_start() {
     let term = Terminal::new(80, 24);
     assert!(term.is_line_start(0));
 }
+
+// ─── Copy Join Modes ───────────────────────────────────────────────────────
+
+use crate::terminal::screen::JoinMode;
+
+#[test]
+fn test_selection_joins_wrapped_rows_by_default() {
+    let mut term = Terminal::new(10, 5);
+    term.process(b"let result_value = 1;\r\nnext");
+    assert_eq!(term.join_mode(), JoinMode::Smart);
+    term.set_selection((0, 0), (4, 3), SelectionMode::Character);
+    assert_eq!(
+        term.get_selected_text().unwrap(),
+        "let result_value = 1;         \nnext"
+    );
+
+    term.set_join_mode(JoinMode::Never);
+    assert_eq!(
+        term.get_selected_text().unwrap(),
+        "let result\n_value = 1\n;         \nnext"
+    );
+}
+
+#[test]
+fn test_smart_join_mends_identifiers_broken_at_the_margin() {
+    let mut term = Terminal::new(10, 5);
+    // A program that wraps its own output: explicit CRLF at column 10
+    term.process(b"let result\r\n_value = 1\r\n;");
+    term.set_join_mode(JoinMode::Always);
+    assert_eq!(term.join_wrapped_lines(0).unwrap().lines_joined, 1);
+
+    term.set_join_mode(JoinMode::Smart);
+    let joined = term.join_wrapped_lines(0).unwrap();
+    assert_eq!(joined.text, "let result_value = 1");
+    assert_eq!((joined.end_row, joined.lines_joined), (1, 2));
+}
+
+#[test]
+fn test_smart_join_keeps_hyphen_and_list_items() {
+    let mut term = Terminal::new(10, 5);
+    term.process(b"run --all-\r\nfiles\r\nitems: one\r\n- two");
+    term.set_selection((0, 0), (5, 3), SelectionMode::Line);
+    assert_eq!(
+        term.get_selected_text().unwrap(),
+        "run --all-files     \nitems: one\n- two     "
+    );
+}
+
+#[test]
+fn test_multiple_selections_merge_in_screen_order() {
+    let mut term = Terminal::new(20, 5);
+    term.process(b"alpha beta\r\ngamma delta\r\nomega");
+    assert_eq!(
+        term.add_selection((6, 1), (11, 1), SelectionMode::Character),
+        0
+    );
+    assert_eq!(
+        term.add_selection((0, 0), (5, 0), SelectionMode::Character),
+        1
+    );
+    assert_eq!(
+        term.add_selection((0, 2), (5, 2), SelectionMode::Character),
+        2
+    );
+    assert_eq!(term.selections().len(), 3);
+    assert_eq!(term.get_selection().unwrap().start, (6, 1));
+    assert_eq!(term.get_selected_text().unwrap(), "alpha\ndelta\nomega");
+
+    assert!(term.is_cell_selected(0, 0));
+    assert!(term.is_cell_selected(7, 1));
+    assert!(!term.is_cell_selected(7, 0));
+
+    // Removing the primary promotes the next selection
+    assert!(term.remove_selection(0));
+    assert_eq!(term.get_selection().unwrap().start, (0, 0));
+    assert!(!term.remove_selection(2));
+    assert_eq!(term.get_selected_text().unwrap(), "alpha\nomega");
+
+    term.clear_selection();
+    assert!(term.selections().is_empty());
+    assert_eq!(term.get_selected_text(), None);
+}
+
+#[test]
+fn test_select_word_after_wide_chars() {
+    let mut term = Terminal::new(40, 3);
+    term.process("日本 foo foo".as_bytes());
+
+    // The second "foo", not the first occurrence of the same word
+    assert_eq!(term.select_word(10, 0, None), Some(((9, 0), (12, 0))));
+    assert_eq!(term.get_word_at(6, 0, None).as_deref(), Some("foo"));
+    // Either column of a wide character selects it
+    assert_eq!(term.select_word(1, 0, None), Some(((0, 0), (4, 0))));
+}
+
+#[test]
+fn test_get_word_at_keeps_combining_marks() {
+    let mut term = Terminal::new(40, 3);
+    term.set_normalization_form(crate::NormalizationForm::None);
+    term.process("cafe\u{301} ok".as_bytes());
+
+    assert_eq!(term.get_word_at(1, 0, None).as_deref(), Some("cafe\u{301}"));
+    assert_eq!(term.get_word_at(6, 0, None).as_deref(), Some("ok"));
+}
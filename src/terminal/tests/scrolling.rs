@@ -234,3 +234,44 @@ fn test_nel_scrolls_within_region_not_screen() {
     assert_eq!(status_c2, '3');
     assert_eq!(term.cursor.row, 22);
 }
+
+#[test]
+fn test_scrollback_policy_applies_during_output() {
+    use crate::grid::{AdaptiveTrimConfig, IdleTrim, ScrollbackPolicy, ScrollbackTrimPolicy};
+
+    let mut term = Terminal::new(10, 2);
+    let line_bytes = 10 * std::mem::size_of::<crate::cell::Cell>();
+    term.set_scrollback_policy(ScrollbackPolicy {
+        max_bytes: Some(3 * line_bytes),
+        ..ScrollbackPolicy::default()
+    });
+    term.process(b"\x1b]133;A\x07$ a\r\n");
+    for i in 0..8 {
+        term.process(format!("out {i}\r\n").as_bytes());
+    }
+    assert_eq!(term.grid().scrollback_len(), 3);
+    assert_eq!(term.scrollback_trim_stats().memory_evictions, 5);
+    // The prompt's line was evicted, and its mark with it
+    assert!(term.marks().is_empty());
+
+    // RIS keeps the embedder's limits and trim policy
+    let trim = ScrollbackTrimPolicy::Adaptive(AdaptiveTrimConfig::default());
+    term.set_scrollback_trim_policy(trim);
+    term.reset();
+    assert_eq!(term.scrollback_policy().max_bytes, Some(3 * line_bytes));
+    assert_eq!(term.scrollback_trim_policy(), trim);
+
+    // Idle trimming waits for trim_scrollback(); zero idle time is always idle
+    for i in 0..3 {
+        term.process(format!("more {i}\r\n").as_bytes());
+    }
+    term.set_scrollback_policy(ScrollbackPolicy {
+        idle_trim: Some(IdleTrim {
+            idle_ms: 0,
+            keep_lines: 1,
+        }),
+        ..ScrollbackPolicy::default()
+    });
+    assert_eq!(term.grid().scrollback_len(), 1);
+    assert_eq!(term.trim_scrollback(), 0);
+}
@@ -1,5 +1,6 @@
 // Search functionality tests
 use crate::terminal::*;
+use crate::unicode_normalization_config::NormalizationForm;
 
 #[test]
 fn test_search_case_sensitive() {
@@ -414,3 +415,227 @@ fn test_clear_regex_matches_alias() {
     term.clear_regex_matches();
     assert_eq!(term.get_search_matches().len(), 0);
 }
+
+/// Run the same scrollback searches with and without the search index
+fn assert_indexed_search_matches(term: &mut Terminal) {
+    let queries = [
+        ("line", false),
+        ("Line 1", true),
+        ("LINE 2", false),
+        ("zzz", false),
+        ("é", false),
+        ("Ñ", true),
+        ("e", false),
+    ];
+    term.set_scrollback_search_index(false);
+    let expected: Vec<_> = queries
+        .iter()
+        .map(|&(q, cs)| term.search_scrollback(q, cs, None))
+        .collect();
+    term.set_scrollback_search_index(true);
+    for (&(q, cs), expected) in queries.iter().zip(&expected) {
+        assert_eq!(
+            &term.search_scrollback(q, cs, None),
+            expected,
+            "query {q:?}"
+        );
+    }
+}
+
+#[test]
+fn test_search_scrollback_index_matches_unindexed() {
+    let mut term = Terminal::with_scrollback(20, 3, 50);
+    term.set_scrollback_search_index(true);
+    assert!(term.scrollback_search_index_enabled());
+
+    // Wrap the scrollback ring so slots are reused
+    for i in 0..80 {
+        term.process(format!("Line {} Ñandú café\r\n", i).as_bytes());
+    }
+    assert_eq!(term.grid().scrollback_len(), 50);
+    assert_eq!(term.search_scrollback("line 70", false, None).len(), 1);
+    assert!(term.search_scrollback("line 5 ", false, None).is_empty());
+    assert_indexed_search_matches(&mut term);
+
+    // Reflow rewraps scrollback; the index must follow
+    term.resize(12, 3);
+    assert_indexed_search_matches(&mut term);
+
+    term.set_scrollback_compression(Some(5));
+    assert_indexed_search_matches(&mut term);
+}
+
+#[test]
+fn test_search_scrollback_index_cleared_with_scrollback() {
+    let mut term = Terminal::with_scrollback(20, 3, 50);
+    term.set_scrollback_search_index(true);
+    for i in 0..10 {
+        term.process(format!("Line {}\r\n", i).as_bytes());
+    }
+    assert!(!term.search_scrollback("line", false, None).is_empty());
+
+    term.process(b"\x1b[3J");
+    assert!(term.search_scrollback("line", false, None).is_empty());
+
+    term.process(b"again\r\nx\r\ny\r\nz\r\n");
+    assert_eq!(term.search_scrollback("again", true, None).len(), 1);
+}
+
+#[test]
+fn test_search_scrollback_index_survives_reset() {
+    let mut term = Terminal::with_scrollback(20, 3, 50);
+    term.set_scrollback_search_index(true);
+    term.process(b"\x1bc");
+    assert!(term.scrollback_search_index_enabled());
+
+    term.process(b"needle\r\nx\r\ny\r\nz\r\n");
+    assert!(term.grid().indexed_scrollback_line(0).is_some());
+    assert_eq!(term.search_scrollback("needle", true, None).len(), 1);
+}
+
+#[test]
+fn test_search_text_columns_after_wide_chars() {
+    let mut term = Terminal::new(40, 3);
+    term.process("日本語 error 日本".as_bytes());
+
+    let matches = term.search_text("error", true);
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].col, 7);
+    assert_eq!(matches[0].length, 5);
+
+    let matches = term.search_text("語 e", true);
+    assert_eq!(matches.len(), 1);
+    assert_eq!((matches[0].col, matches[0].length), (4, 4));
+    assert_eq!(matches[0].text, "語 e");
+
+    // Adjacent wide characters match without the spacer cells in between
+    let matches = term.search_text("日本", true);
+    assert_eq!(matches.len(), 2);
+    assert_eq!((matches[1].col, matches[1].length), (13, 4));
+}
+
+#[test]
+fn test_search_text_columns_after_combining_marks() {
+    let mut term = Terminal::new(40, 3);
+    term.set_normalization_form(NormalizationForm::None);
+    term.process("e\u{301}e\u{301} cafe".as_bytes());
+
+    let matches = term.search_text("cafe", true);
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].col, 3);
+}
+
+#[test]
+fn test_search_text_normalizes_query_and_screen() {
+    let mut term = Terminal::new(40, 3);
+    term.set_normalization_form(NormalizationForm::None);
+    term.process("caf\u{e9} cafe\u{301}".as_bytes());
+
+    // NFC makes the precomposed and decomposed forms match each other
+    let matches = term.search_text("cafe\u{301}", true);
+    assert_eq!(matches.len(), 2);
+    assert_eq!((matches[0].col, matches[0].length), (0, 4));
+    assert_eq!((matches[1].col, matches[1].length), (5, 4));
+    assert_eq!(matches[1].text, "cafe\u{301}");
+
+    let raw = TextSearchOptions {
+        case_sensitive: true,
+        normalization: NormalizationForm::None,
+    };
+    assert_eq!(term.search_text_with_options("caf\u{e9}", &raw).len(), 1);
+}
+
+#[test]
+fn test_search_text_never_splits_a_grapheme() {
+    let mut term = Terminal::new(40, 3);
+    term.set_normalization_form(NormalizationForm::None);
+    term.process("cafe\u{301} cafe".as_bytes());
+
+    // "cafe" inside "café" would end between the base and its accent
+    let matches = term.search_text("cafe", true);
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].col, 5);
+}
+
+#[test]
+fn test_search_text_nfkc_matches_compatibility_forms() {
+    let mut term = Terminal::new(40, 3);
+    term.process("\u{FB01}le".as_bytes());
+
+    let nfkc = TextSearchOptions {
+        case_sensitive: true,
+        normalization: NormalizationForm::NFKC,
+    };
+    let matches = term.search_text_with_options("file", &nfkc);
+    assert_eq!(matches.len(), 1);
+    assert_eq!((matches[0].col, matches[0].length), (0, 3));
+    assert_eq!(matches[0].text, "\u{FB01}le");
+    assert!(term.search_text("file", true).is_empty());
+}
+
+#[test]
+fn test_search_text_case_folding() {
+    let mut term = Terminal::new(40, 3);
+    term.process("STRASSE \u{39F}\u{394}\u{3A5}\u{3A3}\u{3A3}\u{395}\u{3A5}\u{3A3}".as_bytes());
+
+    assert_eq!(term.search_text("stra\u{df}e", false).len(), 1);
+    // Final sigma folds to the ordinary sigma
+    assert_eq!(
+        term.search_text(
+            "\u{3bf}\u{3b4}\u{3c5}\u{3c3}\u{3c3}\u{3b5}\u{3c5}\u{3c2}",
+            false
+        )
+        .len(),
+        1
+    );
+    assert!(term.search_text("strasse", true).is_empty());
+}
+
+#[test]
+fn test_search_scrollback_wide_chars_with_and_without_index() {
+    let mut term = Terminal::with_scrollback(20, 2, 20);
+    term.process("日本 log\r\nplain log\r\nx\r\ny\r\n".as_bytes());
+
+    let expected = term.search_scrollback("LOG", false, None);
+    assert_eq!(expected.len(), 2);
+    let wide = expected.iter().find(|m| m.text == "log" && m.col == 5);
+    assert!(wide.is_some(), "{expected:?}");
+
+    term.set_scrollback_search_index(true);
+    assert_eq!(term.search_scrollback("LOG", false, None), expected);
+}
+
+#[test]
+fn test_regex_search_columns_after_wide_chars() {
+    let mut term = Terminal::new(40, 3);
+    term.process("日本 abc".as_bytes());
+
+    let matches = term
+        .search(r"[a-c]+", RegexSearchOptions::default())
+        .unwrap();
+    assert_eq!(matches.len(), 1);
+    assert_eq!((matches[0].col, matches[0].end_col), (5, 8));
+}
+
+#[test]
+fn test_detectors_report_columns_after_wide_chars() {
+    let mut term = Terminal::new(60, 3);
+    term.process("日本 https://example.com ./src/main.rs:12".as_bytes());
+
+    let url = term.detect_urls();
+    assert!(matches!(&url[..], [DetectedItem::Url(u, 5, 0)] if u == "https://example.com"));
+    assert_eq!(
+        term.get_url_at(5, 0).as_deref(),
+        Some("https://example.com")
+    );
+    assert_eq!(
+        term.get_url_at(23, 0).as_deref(),
+        Some("https://example.com")
+    );
+    assert_eq!(term.get_url_at(24, 0), None);
+
+    let paths = term.detect_file_paths();
+    assert!(paths.iter().any(
+        |p| matches!(p, DetectedItem::FilePath(path, 25, 0, Some(12)) if path == "./src/main.rs")
+    ));
+}
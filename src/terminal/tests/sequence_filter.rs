@@ -0,0 +1,108 @@
+//! Sequence filter hook tests
+
+use std::sync::{Arc, Mutex};
+
+use crate::terminal::{Sequence, SequenceAction, Terminal};
+
+#[test]
+fn blocks_selected_osc_and_allows_the_rest() {
+    let mut term = Terminal::new(80, 24);
+    term.set_sequence_filter(|seq| match seq {
+        Sequence::Osc(osc) if osc.command() == Some("52") => SequenceAction::Block,
+        _ => SequenceAction::Allow,
+    });
+    term.process(b"\x1b]52;c;SGVsbG8=\x07\x1b]0;title\x1b\\");
+    assert_eq!(term.clipboard(), None);
+    assert_eq!(term.title(), "title");
+}
+
+#[test]
+fn filter_sees_parsed_osc_fields() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let log = Arc::clone(&seen);
+    let mut term = Terminal::new(80, 24);
+    term.set_sequence_filter(move |seq| {
+        if let Sequence::Osc(osc) = seq {
+            let args: Vec<String> = osc
+                .args()
+                .iter()
+                .map(|arg| String::from_utf8_lossy(arg).into_owned())
+                .collect();
+            log.lock().unwrap().push((
+                osc.command().map(str::to_string),
+                args,
+                osc.bell_terminated,
+            ));
+        }
+        SequenceAction::Consume
+    });
+    term.process(b"\x1b]8;id=1;https://a.test\x1b\\link\x1b]8;;\x07");
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 2);
+    assert_eq!(seen[0].0.as_deref(), Some("8"));
+    assert_eq!(seen[0].1, vec!["id=1", "https://a.test"]);
+    assert!(!seen[0].2);
+    assert!(seen[1].2);
+    // Consumed: the terminal did not create the hyperlink
+    let cell = term.active_grid().get(0, 0).unwrap();
+    assert!(cell.flags().hyperlink_id.is_none());
+}
+
+#[test]
+fn dcs_filtered_by_header() {
+    let headers = Arc::new(Mutex::new(Vec::new()));
+    let log = Arc::clone(&headers);
+    let mut term = Terminal::new(80, 24);
+    term.set_sequence_filter(move |seq| match seq {
+        Sequence::Dcs(dcs) => {
+            log.lock()
+                .unwrap()
+                .push((dcs.params.to_vec(), dcs.intermediates.to_vec(), dcs.action));
+            SequenceAction::Block
+        }
+        Sequence::Osc(_) => SequenceAction::Allow,
+    });
+    term.process(b"\x1bP0;1q#1~~\x1b\\after");
+
+    assert_eq!(term.graphics_count(), 0);
+    assert_eq!(*headers.lock().unwrap(), vec![(vec![0, 1], vec![], 'q')]);
+    assert_eq!(term.active_grid().row_text(0).trim_end(), "after");
+}
+
+#[test]
+fn filter_survives_reset_and_can_be_cleared() {
+    let mut term = Terminal::new(80, 24);
+    term.set_sequence_filter(|_| SequenceAction::Block);
+    assert!(term.has_sequence_filter());
+
+    term.process(b"\x1bc\x1b]0;blocked\x07");
+    assert!(term.has_sequence_filter());
+    assert_eq!(term.title(), "");
+
+    term.clear_sequence_filter();
+    assert!(!term.has_sequence_filter());
+    term.process(b"\x1b]0;allowed\x07");
+    assert_eq!(term.title(), "allowed");
+}
+
+#[test]
+fn allow_does_not_bypass_builtin_checks() {
+    let mut term = Terminal::new(80, 24);
+    term.set_disable_insecure_sequences(true);
+    term.set_sequence_filter(|_| SequenceAction::Allow);
+    term.process(b"\x1b]52;c;SGVsbG8=\x07");
+    assert_eq!(term.clipboard(), None);
+}
+
+#[test]
+fn panicking_filter_blocks_and_terminal_keeps_working() {
+    let mut term = Terminal::new(80, 24);
+    term.set_sequence_filter(|seq| match seq {
+        Sequence::Osc(osc) if osc.command() == Some("2") => panic!("filter bug"),
+        _ => SequenceAction::Allow,
+    });
+    term.process(b"\x1b]2;bad\x07ok\x1b]0;good\x07");
+    assert_eq!(term.title(), "good");
+    assert_eq!(term.active_grid().row_text(0).trim_end(), "ok");
+}
@@ -0,0 +1,90 @@
+// XTPUSHSGR/XTPOPSGR and XTPUSHCOLORS/XTPOPCOLORS tests
+use crate::color::{Color, NamedColor};
+use crate::terminal::*;
+
+const RED: Color = Color::Named(NamedColor::Red);
+const GREEN: Color = Color::Named(NamedColor::Green);
+const BLUE: Color = Color::Named(NamedColor::Blue);
+
+#[test]
+fn pop_restores_all_pushed_attributes() {
+    let mut term = Terminal::new(20, 5);
+    term.process(b"\x1b[1;4:3;31;58:5:2m\x1b[#{");
+    term.process(b"\x1b[0;3;7;44m");
+    term.process(b"\x1b[#}");
+
+    assert!(term.flags.bold());
+    assert!(term.flags.underline());
+    assert_eq!(
+        term.flags.underline_style,
+        crate::cell::UnderlineStyle::Curly
+    );
+    assert!(!term.flags.italic());
+    assert!(!term.flags.reverse());
+    assert_eq!(term.fg, RED);
+    assert_eq!(term.bg, term.theme.default_bg);
+    assert!(term.underline_color.is_some());
+}
+
+#[test]
+fn selective_push_only_restores_selected_attributes() {
+    let mut term = Terminal::new(20, 5);
+    term.process(b"\x1b[1;32m\x1b[1;31#{");
+    term.process(b"\x1b[22;34;41m");
+    term.process(b"\x1b[#}");
+
+    assert!(term.flags.bold());
+    assert_eq!(term.fg, BLUE);
+    assert_eq!(term.bg, term.theme.default_bg);
+}
+
+#[test]
+fn written_cells_use_restored_attributes() {
+    let mut term = Terminal::new(20, 5);
+    term.process(b"\x1b[7m\x1b[#{\x1b[27mA\x1b[#}B");
+
+    let grid = term.active_grid();
+    assert!(!grid.get(0, 0).unwrap().flags.reverse());
+    assert!(grid.get(1, 0).unwrap().flags.reverse());
+}
+
+#[test]
+fn sgr_and_color_stacks_nest_independently() {
+    let mut term = Terminal::new(20, 5);
+    term.process(b"\x1b[31m\x1b[#P");
+    term.process(b"\x1b[7;34m\x1b[#{");
+    term.process(b"\x1b[27;32m");
+    assert_eq!(term.fg, GREEN);
+    assert_eq!(term.get_stats().sgr_stack_depth, 1);
+    assert_eq!(term.get_stats().color_stack_depth, 1);
+
+    term.process(b"\x1b[#}");
+    assert!(term.flags.reverse());
+    assert_eq!(term.fg, BLUE);
+
+    // The color stack restores colors but leaves reverse video alone
+    term.process(b"\x1b[32m\x1b[#Q");
+    assert_eq!(term.fg, RED);
+    assert!(term.flags.reverse());
+}
+
+#[test]
+fn push_colors_does_not_delete_characters() {
+    let mut term = Terminal::new(20, 5);
+    term.process(b"abc\x1b[1G\x1b[#P");
+    assert_eq!(term.active_grid().row_text(0).trim_end(), "abc");
+}
+
+#[test]
+fn empty_pops_are_ignored_and_depth_is_capped() {
+    let mut term = Terminal::new(20, 5);
+    term.process(b"\x1b[1;31m\x1b[#}\x1b[#Q");
+    assert!(term.flags.bold());
+    assert_eq!(term.fg, RED);
+
+    for _ in 0..12 {
+        term.process(b"\x1b[#{\x1b[#P");
+    }
+    let stats = term.get_stats();
+    assert_eq!((stats.sgr_stack_depth, stats.color_stack_depth), (10, 10));
+}
@@ -0,0 +1,227 @@
+use crate::terminal::{DeltaError, SnapshotDelta, Terminal};
+
+/// Assert that `mirror` shows exactly what `source` shows
+fn assert_mirrors(source: &Terminal, mirror: &Terminal) {
+    assert_eq!(mirror.size(), source.size());
+    let (_, rows) = source.size();
+    for row in 0..rows {
+        assert_eq!(mirror.grid().row(row), source.grid().row(row), "row {row}");
+        assert_eq!(
+            mirror.alt_grid().row(row),
+            source.alt_grid().row(row),
+            "alt row {row}"
+        );
+        assert_eq!(
+            mirror.grid().is_line_wrapped(row),
+            source.grid().is_line_wrapped(row)
+        );
+    }
+    assert_eq!(
+        mirror.grid().scrollback_len(),
+        source.grid().scrollback_len()
+    );
+    for line in 0..source.grid().scrollback_len() {
+        assert_eq!(
+            mirror.grid().scrollback_line(line),
+            source.grid().scrollback_line(line),
+            "scrollback line {line}"
+        );
+    }
+    assert_eq!(
+        mirror.grid().total_lines_scrolled(),
+        source.grid().total_lines_scrolled()
+    );
+    assert_eq!(mirror.cursor(), source.cursor());
+    assert_eq!(mirror.title(), source.title());
+    assert_eq!(mirror.is_alt_screen_active(), source.is_alt_screen_active());
+}
+
+/// Send the source's next delta to the mirror, returning its size in bytes
+fn sync(source: &mut Terminal, mirror: &mut Terminal) -> usize {
+    let delta = source.snapshot_delta();
+    mirror.apply_snapshot_delta(&delta).expect("delta applies");
+    assert_mirrors(source, mirror);
+    delta.len()
+}
+
+#[test]
+fn test_first_delta_is_a_keyframe_that_mirrors_everything() {
+    let mut source = Terminal::with_scrollback(20, 4, 100);
+    source.process(b"\x1b]2;remote\x07");
+    source.process(b"\x1b[1;31mred\x1b[0m \x1b[48;2;1;2;3mrgb\x1b[0m e\xcc\x81 \xe4\xb8\xad\r\n");
+    for i in 0..6 {
+        source.process(format!("line {i}\r\n").as_bytes());
+    }
+    source.process(b"\x1b[?2004h\x1b[?1000h\x1b[3 q");
+
+    let mut mirror = Terminal::new(80, 24);
+    let delta = SnapshotDelta::decode(&source.snapshot_delta()).unwrap();
+    assert!(delta.is_keyframe());
+    assert_eq!(delta.size(), (20, 4));
+
+    source.reset_snapshot_delta();
+    sync(&mut source, &mut mirror);
+    assert_eq!(mirror.title(), "remote");
+    assert!(mirror.bracketed_paste());
+    assert_eq!(mirror.grid().scrollback_len(), 4);
+}
+
+#[test]
+fn test_scrolling_output_sends_only_new_lines() {
+    let mut source = Terminal::with_scrollback(80, 24, 1000);
+    let mut mirror = Terminal::new(80, 24);
+    for i in 0..40 {
+        source.process(format!("initial output line {i}\r\n").as_bytes());
+    }
+    sync(&mut source, &mut mirror);
+
+    source.process(b"one more line\r\n");
+    let delta = source.snapshot_delta();
+    let decoded = SnapshotDelta::decode(&delta).unwrap();
+    assert_eq!(decoded.scrollback_lines(), 1);
+    assert!(
+        decoded.changed_cells() < 20,
+        "{} cells changed",
+        decoded.changed_cells()
+    );
+    assert!(delta.len() < 150, "delta is {} bytes", delta.len());
+    mirror.apply_snapshot_delta(&delta).unwrap();
+    assert_mirrors(&source, &mirror);
+}
+
+#[test]
+fn test_unchanged_terminal_produces_empty_delta() {
+    let mut source = Terminal::new(20, 4);
+    let mut mirror = Terminal::new(20, 4);
+    source.process(b"static");
+    sync(&mut source, &mut mirror);
+
+    let delta = source.snapshot_delta();
+    assert!(SnapshotDelta::decode(&delta).unwrap().is_empty());
+    mirror.apply_snapshot_delta(&delta).unwrap();
+    assert_mirrors(&source, &mirror);
+}
+
+#[test]
+fn test_cursor_modes_and_alt_screen_changes_are_mirrored() {
+    let mut source = Terminal::new(20, 5);
+    let mut mirror = Terminal::new(20, 5);
+    source.process(b"shell prompt $ ");
+    sync(&mut source, &mut mirror);
+
+    source.process(b"\x1b[?1049h\x1b[2J\x1b[Hfull screen app\x1b[3;4H");
+    let decoded = SnapshotDelta::decode(&source.snapshot_delta()).unwrap();
+    assert!(decoded.has_mode_changes());
+    source.reset_snapshot_delta();
+    sync(&mut source, &mut mirror);
+    assert!(mirror.is_alt_screen_active());
+
+    source.process(b"\x1b[?1049l");
+    sync(&mut source, &mut mirror);
+    assert!(!mirror.is_alt_screen_active());
+    assert!(mirror.grid().row_text(0).starts_with("shell prompt $"));
+}
+
+#[test]
+fn test_resize_and_scrollback_clear_are_mirrored() {
+    let mut source = Terminal::with_scrollback(30, 6, 50);
+    let mut mirror = Terminal::new(30, 6);
+    for i in 0..20 {
+        source.process(format!("some wrapped output {i} that is long\r\n").as_bytes());
+    }
+    sync(&mut source, &mut mirror);
+
+    source.resize(24, 8);
+    sync(&mut source, &mut mirror);
+
+    source.process(b"\x1b[3J\x1b[2Jcleared");
+    sync(&mut source, &mut mirror);
+    assert_eq!(mirror.grid().scrollback_len(), 0);
+}
+
+#[test]
+fn test_delta_for_another_state_is_rejected() {
+    let mut source = Terminal::new(20, 4);
+    let mut mirror = Terminal::new(20, 4);
+    source.process(b"hello");
+    sync(&mut source, &mut mirror);
+
+    mirror.process(b"local edit");
+    source.process(b" world");
+    let delta = source.snapshot_delta();
+    assert_eq!(
+        mirror.apply_snapshot_delta(&delta),
+        Err(DeltaError::BaseMismatch)
+    );
+    assert!(mirror.grid().row_text(0).contains("local edit"));
+
+    source.reset_snapshot_delta();
+    sync(&mut source, &mut mirror);
+}
+
+#[test]
+fn test_malformed_deltas_are_rejected() {
+    let mut source = Terminal::new(20, 4);
+    source.process(b"\x1b[32mgreen text");
+    let delta = source.snapshot_delta();
+    let mut mirror = Terminal::new(20, 4);
+
+    assert!(matches!(
+        mirror.apply_snapshot_delta(b"not a delta"),
+        Err(DeltaError::Malformed(_))
+    ));
+    for len in 0..delta.len() {
+        assert!(
+            mirror.apply_snapshot_delta(&delta[..len]).is_err(),
+            "truncated to {len} bytes"
+        );
+    }
+    let mut future = delta.clone();
+    future[3] = 99;
+    assert_eq!(
+        mirror.apply_snapshot_delta(&future),
+        Err(DeltaError::UnsupportedVersion(99))
+    );
+    // Corrupt bytes are rejected or produce some state, but never panic
+    for i in 0..delta.len() {
+        let mut corrupt = delta.clone();
+        corrupt[i] ^= 0xff;
+        let _ = Terminal::new(20, 4).apply_snapshot_delta(&corrupt);
+    }
+    assert_eq!(mirror.grid().row_text(0).trim_end(), "");
+}
+
+#[test]
+fn test_cell_run_past_the_screen_edge_is_rejected() {
+    let mut source = Terminal::new(20, 4);
+    source.process(b"x");
+    let delta = source.snapshot_delta();
+    // "PTD" version flags cols rows, then the runs section: id count alt row col
+    assert_eq!(&delta[7..12], &[0x01, 1, 0, 0, 0]);
+
+    let mut huge_col = delta[..11].to_vec();
+    huge_col.extend_from_slice(&[0xff; 9]);
+    huge_col.push(0x01);
+    huge_col.extend_from_slice(&delta[12..]);
+    let mut mirror = Terminal::new(20, 4);
+    assert!(matches!(
+        mirror.apply_snapshot_delta(&huge_col),
+        Err(DeltaError::Malformed(_))
+    ));
+    assert_eq!(mirror.grid().row_text(0).trim_end(), "");
+}
+
+#[test]
+fn test_scrollback_eviction_is_mirrored() {
+    let mut source = Terminal::with_scrollback(20, 3, 5);
+    let mut mirror = Terminal::new(20, 3);
+    for i in 0..30 {
+        source.process(format!("line {i}\r\n").as_bytes());
+        if i % 4 == 0 {
+            sync(&mut source, &mut mirror);
+        }
+    }
+    sync(&mut source, &mut mirror);
+    assert_eq!(mirror.grid().scrollback_len(), 5);
+    assert_eq!(mirror.grid().total_lines_scrolled(), 28);
+}
@@ -0,0 +1,70 @@
+// Immutable state handle tests
+use crate::terminal::*;
+
+#[test]
+fn state_handle_captures_screen_cursor_and_modes() {
+    let mut term = Terminal::new(20, 5);
+    term.process(b"\x1b]2;title\x07hello\r\nworld\x1b[?1h\x1b[?2004h\x1b[?1000h\x1b[?1006h");
+    let handle = term.state_handle();
+    assert_eq!((handle.cols(), handle.rows()), (20, 5));
+    assert_eq!(handle.text(), "hello\nworld\n\n\n");
+    assert_eq!((handle.cursor().col, handle.cursor().row), (5, 1));
+    assert_eq!(handle.title(), "title");
+    let modes = handle.modes();
+    assert!(modes.application_cursor);
+    assert!(modes.bracketed_paste);
+    assert!(modes.auto_wrap);
+    assert!(!modes.alt_screen);
+    assert_eq!(modes.mouse_mode, crate::mouse::MouseMode::Normal);
+    assert_eq!(modes.mouse_encoding, crate::mouse::MouseEncoding::Sgr);
+}
+
+#[test]
+fn state_handle_does_not_follow_later_output() {
+    let mut term = Terminal::new(20, 5);
+    term.process(b"before");
+    let handle = term.state_handle();
+    let bytes = handle.bytes_processed();
+    term.process(b"\x1b[2J\x1b[Hafter\x1b[?1049h");
+    assert_eq!(handle.line(0).unwrap().trim_end(), "before");
+    assert!(!handle.modes().alt_screen);
+    assert_eq!(bytes, 6);
+
+    let later = term.state_handle();
+    assert!(later.modes().alt_screen);
+    assert!(later.bytes_processed() > bytes);
+    assert!(!later.ptr_eq(&handle));
+}
+
+#[test]
+fn state_handle_shows_the_visible_screen_only() {
+    let mut term = Terminal::new(10, 2);
+    term.process(b"one\r\ntwo\r\nthree");
+    let handle = term.state_handle();
+    assert_eq!(handle.rows(), 2);
+    assert_eq!(handle.line(0).unwrap().trim_end(), "two");
+    assert_eq!(handle.line(1).unwrap().trim_end(), "three");
+    assert_eq!(handle.line(2), None);
+    assert_eq!(handle.grid().scrollback_len(), 0);
+}
+
+#[test]
+fn state_handle_clones_share_state_across_threads() {
+    let mut term = Terminal::new(10, 2);
+    term.process(b"shared");
+    let handle = term.state_handle();
+    let clone = handle.clone();
+    assert!(clone.ptr_eq(&handle));
+    let text = std::thread::spawn(move || clone.text()).join().unwrap();
+    assert_eq!(text, "shared\n");
+}
+
+#[test]
+fn state_handle_byte_count_keeps_growing_across_reset() {
+    let mut term = Terminal::new(10, 2);
+    term.process(b"before");
+    let before = term.state_handle().bytes_processed();
+    term.process(b"\x1bc");
+    let after = term.state_handle().bytes_processed();
+    assert_eq!(after, before + 2);
+}
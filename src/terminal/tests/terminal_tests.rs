@@ -874,10 +874,10 @@ fn test_decrqm_synchronized_updates() {
     term.process(b"\x1b[?2026$p");
     assert_eq!(term.drain_responses(), b"\x1b[?2026;2$y");
 
-    // Enable synchronized updates
+    // Query while set
     term.process(b"\x1b[?2026h");
-    // Note: Can't query while synchronized mode is active because
-    // the query itself gets buffered. This is expected behavior.
+    term.process(b"\x1b[?2026$p");
+    assert_eq!(term.drain_responses(), b"\x1b[?2026;1$y");
 
     // Disable synchronized updates, then query
     term.process(b"\x1b[?2026l");
@@ -965,20 +965,17 @@ fn test_synchronized_updates() {
     // Enable synchronized updates
     term.process(b"\x1b[?2026h");
     assert!(term.synchronized_updates());
+    term.mark_clean();
 
-    // Process some content - it should be buffered
+    // Content is parsed immediately, but its dirty rows are held back
     term.process(b"Buffered");
-    let content = term.content();
-    // Content should be empty because it's buffered
-    assert!(!content.contains("Buffered"));
+    assert!(term.content().contains("Buffered"));
+    assert!(term.get_dirty_rows().is_empty());
 
-    // Disable synchronized updates - this should flush the buffer
+    // Disabling commits the frame
     term.process(b"\x1b[?2026l");
     assert!(!term.synchronized_updates());
-
-    // Now content should appear
-    let content = term.content();
-    assert!(content.contains("Buffered"));
+    assert_eq!(term.get_dirty_rows(), vec![0]);
 }
 
 #[test]
@@ -987,22 +984,21 @@ fn test_synchronized_updates_multiple_updates() {
 
     // Enable synchronized updates
     term.process(b"\x1b[?2026h");
+    term.mark_clean();
 
     // Send multiple updates
     term.process(b"Line1\r\n");
     term.process(b"Line2\r\n");
     term.process(b"Line3");
 
-    // All should be buffered
-    let content = term.content();
-    assert!(!content.contains("Line1"));
-    assert!(!content.contains("Line2"));
-    assert!(!content.contains("Line3"));
+    // Nothing is reported dirty until the update ends
+    assert!(term.get_dirty_rows().is_empty());
 
-    // Disable and flush
+    // Disable and commit
     term.process(b"\x1b[?2026l");
 
-    // All lines should appear
+    // All rows arrive as one frame
+    assert_eq!(term.get_dirty_rows(), vec![0, 1, 2]);
     let content = term.content();
     assert!(content.contains("Line1"));
     assert!(content.contains("Line2"));
@@ -1015,16 +1011,15 @@ fn test_synchronized_updates_manual_flush() {
 
     // Enable synchronized updates
     term.process(b"\x1b[?2026h");
+    term.mark_clean();
     term.process(b"Test");
-
-    // Content buffered
-    assert!(!term.content().contains("Test"));
+    assert!(term.get_dirty_rows().is_empty());
 
     // Manual flush
     term.flush_synchronized_updates();
 
-    // Content should appear, mode still enabled
-    assert!(term.content().contains("Test"));
+    // Rows committed, mode still enabled
+    assert_eq!(term.get_dirty_rows(), vec![0]);
     assert!(term.synchronized_updates());
 }
 
@@ -1145,9 +1140,9 @@ fn test_kitty_keyboard_push_pop() {
     term.process(b"\x1b[<1u");
     assert_eq!(term.keyboard_flags(), 1);
 
-    // Popping empty stack should not change flags
+    // Popping an empty stack resets flags
     term.process(b"\x1b[<1u");
-    assert_eq!(term.keyboard_flags(), 1);
+    assert_eq!(term.keyboard_flags(), 0);
 }
 
 #[test]
@@ -1169,16 +1164,59 @@ fn test_kitty_keyboard_pop_multiple() {
 }
 
 #[test]
-fn test_kitty_keyboard_report_mode() {
+fn test_kitty_keyboard_update_modes() {
     let mut term = Terminal::new(80, 24);
 
     // Set flags
     term.process(b"\x1b[=5;1u");
     assert_eq!(term.keyboard_flags(), 5);
 
-    // Use mode 3 to report current flags
-    term.process(b"\x1b[=0;3u");
-    assert_eq!(term.drain_responses(), b"\x1b[?5u");
+    // Mode 2 sets the given bits
+    term.process(b"\x1b[=10;2u");
+    assert_eq!(term.keyboard_flags(), 15);
+
+    // Mode 3 resets the given bits and sends no reply
+    term.process(b"\x1b[=6;3u");
+    assert_eq!(term.keyboard_flags(), 9);
+    assert!(term.drain_responses().is_empty());
+
+    // Undefined bits are ignored
+    term.process(b"\x1b[=255u");
+    assert_eq!(term.keyboard_flags(), 31);
+}
+
+#[test]
+fn test_kitty_keyboard_stack_limit() {
+    let mut term = Terminal::new(80, 24);
+
+    for flags in 1..=20 {
+        term.process(format!("\x1b[>{}u", flags).as_bytes());
+    }
+    assert_eq!(term.keyboard_flags(), 20);
+
+    // Only the newest 16 entries survive (pushed values 4..=19)
+    term.process(b"\x1b[<16u");
+    assert_eq!(term.keyboard_flags(), 4);
+    term.process(b"\x1b[<u");
+    assert_eq!(term.keyboard_flags(), 0);
+}
+
+#[test]
+fn test_kitty_keyboard_encode_key_follows_flags() {
+    use crate::kitty_keyboard::{Key, KeyEvent, KeyModifiers};
+
+    let mut term = Terminal::new(80, 24);
+    let escape = KeyEvent::new(Key::Escape);
+    let ctrl_i = KeyEvent::new(Key::Char('i')).with_modifiers(KeyModifiers::CTRL);
+    assert_eq!(term.encode_key(&escape), b"\x1b");
+    assert_eq!(term.encode_key(&ctrl_i), b"\x09");
+
+    term.process(b"\x1b[>1u");
+    assert_eq!(term.encode_key(&escape), b"\x1b[27u");
+    assert_eq!(term.encode_key(&ctrl_i), b"\x1b[105;5u");
+
+    term.process(b"\x1b[<u\x1b[?1h");
+    assert_eq!(term.encode_key(&KeyEvent::new(Key::Up)), b"\x1bOA");
 }
 
 #[test]
@@ -2851,8 +2889,8 @@ fn test_search_scrollback_with_limit() {
 
 #[test]
 fn test_search_unicode_byte_offset() {
-    // Test that search returns character offsets, not byte offsets
-    // Japanese "Hello World" has multi-byte characters
+    // Test that search returns columns, not byte or character offsets
+    // Japanese "Hello World" has multi-byte, double-width characters
     let mut term = Terminal::new(80, 24);
     // "こんにちは World" - 5 Japanese chars (3 bytes each) + space + "World"
     term.process("こんにちは World".as_bytes());
@@ -2864,9 +2902,9 @@ fn test_search_unicode_byte_offset() {
     let matches = term.search("World", options).unwrap();
     assert_eq!(matches.len(), 1);
     assert_eq!(matches[0].text, "World");
-    // "World" should be at character position 6 (5 Japanese chars + 1 space)
+    // "World" should be at column 11 (5 wide Japanese chars + 1 space)
     // NOT byte position 16 (15 bytes for Japanese + 1 for space)
-    assert_eq!(matches[0].col, 6);
+    assert_eq!(matches[0].col, 11);
     assert_eq!(matches[0].length, 5);
 }
 
@@ -2884,7 +2922,7 @@ fn test_search_unicode_query() {
     assert_eq!(matches.len(), 1);
     assert_eq!(matches[0].text, "こんにちは");
     assert_eq!(matches[0].col, 6); // After "Hello "
-    assert_eq!(matches[0].length, 5); // 5 characters, not 15 bytes
+    assert_eq!(matches[0].length, 10); // 5 wide characters, not 15 bytes
 }
 
 #[test]
@@ -2900,10 +2938,10 @@ fn test_search_unicode_multiple_matches() {
     let matches = term.search("日本", options).unwrap();
     assert_eq!(matches.len(), 2);
     assert_eq!(matches[0].col, 0); // First occurrence at start
-    assert_eq!(matches[0].length, 2);
-    // "日本 is Japan " = 2 + 1 + 2 + 1 + 5 + 1 = 12 chars, so second "日本" is at col 12
-    assert_eq!(matches[1].col, 12);
-    assert_eq!(matches[1].length, 2);
+    assert_eq!(matches[0].length, 4);
+    // "日本 is Japan " = 4 + 1 + 2 + 1 + 5 + 1 = 14 columns, so second "日本" is at col 14
+    assert_eq!(matches[1].col, 14);
+    assert_eq!(matches[1].length, 4);
 }
 
 #[test]
@@ -3257,6 +3295,44 @@ fn test_scrollback_stats() {
     assert!(stats.memory_bytes > 0);
 }
 
+#[test]
+fn test_scrollback_compression_is_transparent() {
+    let mut term = Terminal::with_scrollback(80, 5, 100);
+    for i in 0..40 {
+        term.process(format!("\x1b[31mLine {}\x1b[0m\r\n", i).as_bytes());
+    }
+    let before = term.export_scrollback(ExportFormat::Plain, None);
+    let raw = term.scrollback_stats();
+    assert_eq!(raw.compressed_lines, 0);
+    assert_eq!(raw.memory_bytes, raw.raw_bytes);
+
+    term.set_scrollback_compression(Some(10));
+    assert_eq!(term.scrollback_compression(), Some(10));
+    let stats = term.scrollback_stats();
+    assert_eq!(stats.compressed_lines, stats.total_lines - 10);
+    assert!(stats.compressed_bytes > 0);
+    let compressed_raw = stats.compressed_lines * 80 * std::mem::size_of::<crate::cell::Cell>();
+    assert!(stats.compressed_bytes * 20 < compressed_raw);
+    assert!(stats.memory_bytes < stats.raw_bytes / 2);
+    assert_eq!(term.export_scrollback(ExportFormat::Plain, None), before);
+    let line = term.grid().scrollback_line(0).unwrap();
+    assert_eq!(line[0].fg, Color::Named(NamedColor::Red));
+
+    // Lines keep compressing as they age past the threshold
+    term.process(b"more\r\n");
+    assert_eq!(
+        term.scrollback_stats().compressed_lines,
+        term.scrollback_stats().total_lines - 10
+    );
+
+    // The threshold is an embedder setting, so RIS keeps it
+    term.reset();
+    assert_eq!(term.scrollback_compression(), Some(10));
+
+    term.set_scrollback_compression(None);
+    assert_eq!(term.scrollback_stats().compressed_lines, 0);
+}
+
 #[test]
 fn test_join_wrapped_lines() {
     let mut term = Terminal::new(20, 24);
@@ -0,0 +1,106 @@
+// Configurable plain-text export tests
+use crate::terminal::*;
+
+#[test]
+fn test_export_text_opts_defaults_join_and_trim() {
+    let mut term = Terminal::new(10, 5);
+    term.process(b"abcdefghijklm\r\nshort   \r\n");
+
+    let text = term.export_text_opts(&ExportTextOptions::default());
+    assert_eq!(text, "abcdefghijklm\nshort\n");
+}
+
+#[test]
+fn test_export_text_opts_physical_rows_untrimmed_crlf() {
+    let mut term = Terminal::new(10, 5);
+    term.process(b"abcdefghijklm\r\nx");
+
+    let options = ExportTextOptions {
+        join_wrapped: false,
+        trim_trailing_whitespace: false,
+        line_ending: LineEnding::CrLf,
+        ..Default::default()
+    };
+    assert_eq!(
+        term.export_text_opts(&options),
+        "abcdefghij\r\nklm       \r\nx         \r\n"
+    );
+}
+
+#[test]
+fn test_export_text_opts_includes_scrollback_unless_disabled() {
+    let mut term = Terminal::with_scrollback(10, 2, 100);
+    term.process(b"one\r\ntwo\r\nthree\r\nfour");
+
+    assert_eq!(
+        term.export_text_opts(&ExportTextOptions::default()),
+        "one\ntwo\nthree\nfour\n"
+    );
+    let options = ExportTextOptions {
+        include_scrollback: false,
+        ..Default::default()
+    };
+    assert_eq!(term.export_text_opts(&options), "three\nfour\n");
+}
+
+#[test]
+fn test_export_text_opts_reconstructs_tabs_at_tab_stops() {
+    let mut term = Terminal::new(40, 3);
+    term.process(b"a\tb\tc d  e\r\n        x");
+
+    let options = ExportTextOptions {
+        reconstruct_tabs: true,
+        ..Default::default()
+    };
+    // A single space before a tab stop stays a space
+    assert_eq!(term.export_text_opts(&options), "a\tb\tc d  e\n\tx\n");
+}
+
+#[test]
+fn test_export_text_opts_skips_wide_char_spacers() {
+    let mut term = Terminal::new(10, 2);
+    term.process("中文 ok".as_bytes());
+
+    assert_eq!(
+        term.export_text_opts(&ExportTextOptions::default()),
+        "中文 ok\n"
+    );
+}
+
+#[test]
+fn test_export_text_opts_zone_markers() {
+    let mut term = Terminal::new(40, 10);
+    term.process(b"\x1b]133;A\x07$ \x1b]133;B\x07false\r\n\x1b]133;C;false\x07");
+    term.process(b"oops\r\n\x1b]133;D;1\x07");
+
+    let options = ExportTextOptions {
+        zone_markers: true,
+        comment_prefix: "## ".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(
+        term.export_text_opts(&options),
+        "## prompt\n## command\n$ false\n## output: false (exit 1)\noops\n"
+    );
+}
+
+#[test]
+fn test_export_text_opts_timestamps_prefix_lines() {
+    let mut term = Terminal::new(10, 5);
+    term.process(b"abcdefghijklm\r\n\r\nlast");
+    term.grid.stamp_row(0, 951_827_696_789);
+    term.grid.stamp_row(1, 1);
+    term.grid.stamp_row(3, 1_792_239_660_123);
+
+    let options = ExportTextOptions {
+        timestamps: true,
+        ..Default::default()
+    };
+    // The wrapped line takes the time of its first row; the blank row has none
+    assert_eq!(
+        term.export_text_opts(&options),
+        "[2000-02-29T12:34:56.789Z] abcdefghijklm\n\
+         \n\
+         [2026-10-17T12:21:00.123Z] last\n"
+    );
+}
@@ -0,0 +1,63 @@
+// Theme application tests
+use crate::color::Color;
+use crate::terminal::*;
+use crate::theme::Theme;
+
+#[test]
+fn test_apply_theme_sets_defined_colors_only() {
+    let mut term = Terminal::new(20, 5);
+    let original_cursor = term.cursor_color();
+    let original_palette_2 = term.get_ansi_color(2).unwrap();
+
+    let theme = Theme::from_json(
+        r##"{"name": "Test", "foreground": "#102030", "background": "#000000",
+            "selection_background": "#444444", "link": "#00ffff",
+            "palette": ["#111111", "#ff0000"]}"##,
+    )
+    .unwrap();
+    term.apply_theme(&theme);
+
+    assert_eq!(term.default_fg(), Color::Rgb(0x10, 0x20, 0x30));
+    assert_eq!(term.default_bg(), Color::Rgb(0, 0, 0));
+    assert_eq!(term.selection_bg_color(), Color::Rgb(0x44, 0x44, 0x44));
+    assert_eq!(term.link_color(), Color::Rgb(0, 255, 255));
+    assert_eq!(term.get_ansi_color(0), Some(Color::Rgb(0x11, 0x11, 0x11)));
+    assert_eq!(term.get_ansi_color(1), Some(Color::Rgb(255, 0, 0)));
+    assert_eq!(term.get_ansi_color(2), Some(original_palette_2));
+    assert_eq!(term.cursor_color(), original_cursor);
+}
+
+#[test]
+fn test_apply_theme_emits_theme_changed() {
+    let mut term = Terminal::new(20, 5);
+    term.poll_events();
+
+    let theme = Theme {
+        name: Some("Night".to_string()),
+        background: Some(Color::Rgb(1, 2, 3)),
+        ..Theme::default()
+    };
+    term.apply_theme(&theme);
+
+    let events = term.poll_events();
+    assert_eq!(
+        events,
+        vec![TerminalEvent::ThemeChanged {
+            name: Some("Night".to_string())
+        }]
+    );
+}
+
+#[test]
+fn test_osc_queries_report_applied_theme() {
+    let mut term = Terminal::new(20, 5);
+    let theme = Theme {
+        background: Some(Color::Rgb(0x12, 0x34, 0x56)),
+        ..Theme::default()
+    };
+    term.apply_theme(&theme);
+
+    term.process(b"\x1b]11;?\x1b\\");
+    let response = String::from_utf8(term.drain_responses()).unwrap();
+    assert!(response.contains("rgb:1212/3434/5656"), "{response:?}");
+}
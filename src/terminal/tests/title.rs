@@ -0,0 +1,121 @@
+//! Tests for title and icon name tracking (OSC 0/1/2) and the title history
+
+use crate::terminal::{Terminal, TerminalEvent, TitleKind, MAX_TITLE_HISTORY};
+
+fn title_events(term: &mut Terminal) -> Vec<TerminalEvent> {
+    term.poll_events()
+        .into_iter()
+        .filter(|e| {
+            matches!(
+                e,
+                TerminalEvent::TitleChanged { .. } | TerminalEvent::IconNameChanged { .. }
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn osc_1_sets_icon_name_only() {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"\x1b]1;tab label\x07");
+
+    assert_eq!(term.icon_name(), "tab label");
+    assert_eq!(term.title(), "");
+    assert_eq!(
+        title_events(&mut term),
+        vec![TerminalEvent::IconNameChanged {
+            icon_name: "tab label".to_string(),
+            old_icon_name: String::new(),
+        }]
+    );
+    assert!(term.unsupported_sequences().is_empty());
+}
+
+#[test]
+fn osc_0_sets_both_and_osc_2_only_title() {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"\x1b]0;both\x07");
+    assert_eq!(term.title(), "both");
+    assert_eq!(term.icon_name(), "both");
+    assert_eq!(title_events(&mut term).len(), 2);
+
+    term.process(b"\x1b]2;window\x07");
+    assert_eq!(term.title(), "window");
+    assert_eq!(term.icon_name(), "both");
+    assert_eq!(
+        title_events(&mut term),
+        vec![TerminalEvent::TitleChanged {
+            title: "window".to_string(),
+            old_title: "both".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn unchanged_values_emit_nothing() {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"\x1b]0;same\x07");
+    term.poll_events();
+
+    term.process(b"\x1b]0;same\x07\x1b]1;same\x07");
+    assert!(title_events(&mut term).is_empty());
+    assert_eq!(term.title_history().len(), 2);
+}
+
+#[test]
+fn history_records_kind_and_order() {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"\x1b]2;one\x07\x1b]1;icon\x07\x1b]2;two\x07");
+
+    let history: Vec<_> = term
+        .title_history()
+        .iter()
+        .map(|e| (e.kind, e.text.as_str()))
+        .collect();
+    assert_eq!(
+        history,
+        vec![
+            (TitleKind::Title, "one"),
+            (TitleKind::IconName, "icon"),
+            (TitleKind::Title, "two"),
+        ]
+    );
+    assert!(term.title_history().iter().all(|e| e.timestamp > 0));
+
+    term.clear_title_history();
+    assert!(term.title_history().is_empty());
+    assert_eq!(term.title(), "two");
+}
+
+#[test]
+fn history_is_bounded() {
+    let mut term = Terminal::new(80, 24);
+    for i in 0..MAX_TITLE_HISTORY + 5 {
+        term.process(format!("\x1b]2;title {i}\x07").as_bytes());
+    }
+
+    let history = term.title_history();
+    assert_eq!(history.len(), MAX_TITLE_HISTORY);
+    assert_eq!(history.front().unwrap().text, "title 5");
+    assert_eq!(
+        history.back().unwrap().text,
+        format!("title {}", MAX_TITLE_HISTORY + 4)
+    );
+}
+
+#[test]
+fn title_stack_pop_reports_change() {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"\x1b]2;saved\x07\x1b[22t\x1b]2;temporary\x07");
+    term.poll_events();
+
+    term.process(b"\x1b[23t");
+    assert_eq!(term.title(), "saved");
+    assert_eq!(
+        title_events(&mut term),
+        vec![TerminalEvent::TitleChanged {
+            title: "saved".to_string(),
+            old_title: "temporary".to_string(),
+        }]
+    );
+}
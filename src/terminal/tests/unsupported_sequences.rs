@@ -0,0 +1,110 @@
+//! Unsupported-sequence capture tests
+
+use crate::terminal::{
+    Sequence, SequenceAction, Terminal, UnsupportedKind, MAX_UNSUPPORTED_RAW_LENGTH,
+    MAX_UNSUPPORTED_SEQUENCES,
+};
+
+fn names(term: &Terminal) -> Vec<&str> {
+    term.unsupported_sequences()
+        .iter()
+        .map(|entry| entry.name.as_str())
+        .collect()
+}
+
+#[test]
+fn supported_sequences_are_not_captured() {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"\x1b[1;31mA\x1b[0m\x1b[?25l\x1b[?25h\x1b]0;t\x07\x1bPq#0~\x1b\\\x1b[4h");
+    assert!(term.unsupported_sequences().is_empty());
+}
+
+#[test]
+fn captures_csi_with_params_and_raw_bytes() {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"\x1b[1;2:3~\x1b[>5~\x1b[7~");
+
+    let seen = term.unsupported_sequences();
+    assert_eq!(names(&term), ["CSI ~", "CSI >~"]);
+    assert_eq!(seen[0].kind, UnsupportedKind::Csi);
+    assert_eq!(seen[0].count, 2);
+    // The latest occurrence is kept as the example
+    assert_eq!(seen[0].params, vec![vec![7]]);
+    assert_eq!(seen[0].raw, b"\x1b[7~");
+    assert_eq!(seen[1].params, vec![vec![5]]);
+    assert_eq!(seen[1].raw, b"\x1b[>5~");
+}
+
+#[test]
+fn captures_each_unknown_private_mode() {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"\x1b[?25;9999h\x1b[?9999l\x1b[?8888h\x1b[?9999h");
+
+    let seen = term.unsupported_sequences();
+    assert_eq!(names(&term), ["DECSET 9999", "DECRST 9999", "DECSET 8888"]);
+    assert_eq!(seen[0].count, 2);
+    assert_eq!(seen[0].raw, b"\x1b[?9999h");
+    assert_eq!(seen[1].raw, b"\x1b[?9999l");
+    assert!(term.cursor().visible);
+}
+
+#[test]
+fn captures_unknown_osc_and_dcs() {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"\x1b]4242;a;b\x07\x1bP1$tpayload\x1b\\");
+
+    let seen = term.unsupported_sequences();
+    assert_eq!(names(&term), ["OSC 4242", "DCS $t"]);
+    assert_eq!(seen[0].kind, UnsupportedKind::Osc);
+    assert!(seen[0].params.is_empty());
+    assert_eq!(seen[0].raw, b"\x1b]4242;a;b\x07");
+    assert_eq!(seen[1].kind, UnsupportedKind::Dcs);
+    assert_eq!(seen[1].params, vec![vec![1]]);
+    assert_eq!(seen[1].raw, b"\x1bP1$t");
+}
+
+#[test]
+fn capture_is_bounded() {
+    let mut term = Terminal::new(80, 24);
+    let mut osc = b"\x1b]4242;".to_vec();
+    osc.extend(std::iter::repeat_n(b'x', 4096));
+    osc.push(0x07);
+    term.process(&osc);
+    assert_eq!(
+        term.unsupported_sequences()[0].raw.len(),
+        MAX_UNSUPPORTED_RAW_LENGTH
+    );
+
+    for mode in 0..(MAX_UNSUPPORTED_SEQUENCES as u16 + 10) {
+        term.process(format!("\x1b[?{}h", 20000 + mode).as_bytes());
+    }
+    assert_eq!(
+        term.unsupported_sequences().len(),
+        MAX_UNSUPPORTED_SEQUENCES
+    );
+
+    // Known entries keep counting once the capture is full
+    term.process(&osc);
+    assert_eq!(term.unsupported_sequences()[0].count, 2);
+}
+
+#[test]
+fn capture_survives_reset_until_cleared() {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"\x1b[?9999h\x1bc");
+    assert_eq!(names(&term), ["DECSET 9999"]);
+
+    term.clear_unsupported_sequences();
+    assert!(term.unsupported_sequences().is_empty());
+}
+
+#[test]
+fn filtered_sequences_are_not_captured() {
+    let mut term = Terminal::new(80, 24);
+    term.set_sequence_filter(|seq| match seq {
+        Sequence::Osc(osc) if osc.command() == Some("4242") => SequenceAction::Consume,
+        _ => SequenceAction::Allow,
+    });
+    term.process(b"\x1b]4242;x\x07");
+    assert!(term.unsupported_sequences().is_empty());
+}
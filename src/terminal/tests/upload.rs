@@ -0,0 +1,118 @@
+//! Tests for the iTerm2 OSC 1337 RequestUpload handshake and upload providers
+
+use std::sync::{Arc, Mutex};
+
+use base64::Engine;
+
+use crate::terminal::file_transfer::UPLOAD_CHUNK_BYTES;
+use crate::terminal::{Terminal, TerminalEvent};
+
+const REQUEST_TGZ: &[u8] = b"\x1b]1337;RequestUpload=format=tgz\x07";
+
+fn upload_requests(term: &Terminal) -> Vec<String> {
+    term.events
+        .terminal_events
+        .iter()
+        .filter_map(|e| match e {
+            TerminalEvent::UploadRequested { format } => Some(format.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn request_without_provider_only_emits_event() {
+    let mut term = Terminal::new(80, 24);
+    term.process(REQUEST_TGZ);
+    assert_eq!(upload_requests(&term), ["tgz"]);
+    assert!(term.drain_responses().is_empty());
+}
+
+#[test]
+fn provider_answers_request_with_its_data() {
+    let mut term = Terminal::new(80, 24);
+    let formats = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&formats);
+    term.set_upload_provider(move |format: &str| {
+        seen.lock().unwrap().push(format.to_string());
+        Some(b"Hello World".to_vec())
+    });
+    assert!(term.has_upload_provider());
+
+    term.process(REQUEST_TGZ);
+    assert_eq!(*formats.lock().unwrap(), ["tgz"]);
+    assert_eq!(upload_requests(&term), ["tgz"]);
+    assert_eq!(term.drain_responses(), b"ok\nSGVsbG8gV29ybGQ=\n\n");
+}
+
+#[test]
+fn declining_provider_cancels_upload() {
+    let mut term = Terminal::new(80, 24);
+    term.set_upload_provider(|_: &str| None);
+    term.process(REQUEST_TGZ);
+    assert_eq!(term.drain_responses(), b"\x03");
+}
+
+#[test]
+fn panicking_provider_cancels_upload() {
+    let mut term = Terminal::new(80, 24);
+    term.set_upload_provider(|_: &str| -> Option<Vec<u8>> { panic!("provider bug") });
+    term.process(REQUEST_TGZ);
+    assert_eq!(term.drain_responses(), b"\x03");
+}
+
+#[test]
+fn upload_data_is_sent_in_base64_lines() {
+    let mut term = Terminal::new(80, 24);
+    let data: Vec<u8> = (0..UPLOAD_CHUNK_BYTES * 2 + 10)
+        .map(|i| (i % 251) as u8)
+        .collect();
+    term.send_upload_data(&data);
+
+    let response = String::from_utf8(term.drain_responses()).unwrap();
+    let body = response
+        .strip_prefix("ok\n")
+        .and_then(|rest| rest.strip_suffix("\n\n"))
+        .expect("ok header and blank-line terminator");
+    let lines: Vec<&str> = body.split('\n').collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0].len(), UPLOAD_CHUNK_BYTES / 3 * 4);
+
+    let decoded = base64::engine::general_purpose::STANDARD
+        .decode(lines.concat())
+        .unwrap();
+    assert_eq!(decoded, data);
+}
+
+#[test]
+fn cancel_discards_undrained_upload_data() {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"\x1b[5n");
+    term.send_upload_data(b"secret");
+    term.cancel_upload();
+    assert_eq!(term.drain_responses(), b"\x1b[0n\x03");
+}
+
+#[test]
+fn cancel_after_drain_only_sends_abort() {
+    let mut term = Terminal::new(80, 24);
+    term.send_upload_data(b"data");
+    assert!(term.drain_responses().starts_with(b"ok\n"));
+    term.cancel_upload();
+    assert_eq!(term.drain_responses(), b"\x03");
+}
+
+#[test]
+fn provider_survives_reset_and_can_be_cleared() {
+    let mut term = Terminal::new(80, 24);
+    term.set_upload_provider(|_: &str| Some(Vec::new()));
+    term.process(b"\x1bc");
+    assert!(term.has_upload_provider());
+    term.process(REQUEST_TGZ);
+    assert_eq!(term.drain_responses(), b"ok\n\n");
+
+    term.clear_upload_provider();
+    assert!(!term.has_upload_provider());
+    term.process(REQUEST_TGZ);
+    assert!(term.drain_responses().is_empty());
+}
@@ -0,0 +1,106 @@
+//! UTF-8 resilience mode tests
+
+use crate::terminal::{Terminal, TerminalEvent, MAX_UTF8_ERROR_RUNS, MAX_UTF8_ERROR_RUN_LENGTH};
+
+fn line(term: &Terminal) -> String {
+    term.export_text().lines().next().unwrap_or("").to_string()
+}
+
+fn decode_events(term: &mut Terminal) -> Vec<(u64, usize)> {
+    term.poll_events()
+        .into_iter()
+        .filter_map(|event| match event {
+            TerminalEvent::Utf8DecodeError { offset, length } => Some((offset, length)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn disabled_by_default_leaves_c1_bytes_to_the_parser() {
+    let mut term = Terminal::new(80, 24);
+    assert!(!term.utf8_resilience());
+    // The parser hands 0x85 over as a C1 control, which leaves no trace
+    term.process(b"a\x85b");
+    assert_eq!(line(&term), "ab");
+    assert_eq!(term.utf8_decode_stats().errors, 0);
+}
+
+#[test]
+fn each_invalid_sequence_becomes_one_replacement() {
+    let mut term = Terminal::new(80, 24);
+    term.set_utf8_resilience(true);
+    // Latin-1 "é", a lone C1 byte, then an overlong encoding of '/'
+    term.process(b"caf\xe9 a\x85b \xc0\xafz");
+    assert_eq!(line(&term), "caf\u{FFFD} a\u{FFFD}b \u{FFFD}\u{FFFD}z");
+
+    let stats = term.utf8_decode_stats();
+    assert_eq!(stats.errors, 4);
+    assert_eq!(stats.invalid_bytes, 4);
+    assert_eq!(stats.runs, 3);
+    assert_eq!(decode_events(&mut term), vec![(3, 1), (6, 1), (9, 2)]);
+
+    let runs = term.utf8_decode_errors();
+    assert_eq!(runs[2].bytes, b"\xc0\xaf");
+}
+
+#[test]
+fn offsets_span_chunks_and_split_sequences_are_joined() {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"12345");
+    term.set_utf8_resilience(true);
+    // "é" split across two chunks decodes normally
+    term.process(b"\xc3");
+    term.process(b"\xa9\xff");
+    assert_eq!(line(&term), "12345é\u{FFFD}");
+    assert_eq!(term.utf8_decode_errors()[0].offset, 7);
+}
+
+#[test]
+fn disabling_flushes_a_held_back_sequence() {
+    let mut term = Terminal::new(80, 24);
+    term.set_utf8_resilience(true);
+    term.process(b"x\xe2\x82");
+    assert_eq!(line(&term), "x");
+    term.set_utf8_resilience(false);
+    term.process(b"\xac");
+    assert_eq!(line(&term), "x€");
+}
+
+#[test]
+fn recorded_runs_are_bounded_and_survive_reset() {
+    let mut term = Terminal::new(80, 24);
+    term.set_utf8_resilience(true);
+    let mut long_run = vec![b'a'];
+    long_run.extend(std::iter::repeat_n(0xffu8, MAX_UTF8_ERROR_RUN_LENGTH * 2));
+    term.process(&long_run);
+    for _ in 0..MAX_UTF8_ERROR_RUNS {
+        term.process(b"-\xfe");
+    }
+    term.process(b"\x1bc");
+
+    assert!(term.utf8_resilience());
+    let runs = term.utf8_decode_errors();
+    assert_eq!(runs.len(), MAX_UTF8_ERROR_RUNS);
+    assert_eq!(runs[0].bytes, b"\xfe");
+    assert_eq!(
+        term.utf8_decode_stats().invalid_bytes,
+        (MAX_UTF8_ERROR_RUN_LENGTH * 2 + MAX_UTF8_ERROR_RUNS) as u64
+    );
+
+    term.clear_utf8_decode_errors();
+    assert!(term.utf8_decode_errors().is_empty());
+    assert_eq!(term.utf8_decode_stats().errors, 0);
+}
+
+#[test]
+fn long_runs_are_truncated() {
+    let mut term = Terminal::new(80, 24);
+    term.set_utf8_resilience(true);
+    let run = vec![0xffu8; MAX_UTF8_ERROR_RUN_LENGTH + 10];
+    term.process(&run);
+    let runs = term.utf8_decode_errors();
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].length, MAX_UTF8_ERROR_RUN_LENGTH + 10);
+    assert_eq!(runs[0].bytes.len(), MAX_UTF8_ERROR_RUN_LENGTH);
+}
@@ -0,0 +1,81 @@
+// Scrollback viewport anchoring and follow-tail tests
+use crate::terminal::*;
+
+fn lines(term: &mut Terminal, range: std::ops::Range<usize>) {
+    for i in range {
+        term.process(format!("line {i}\r\n").as_bytes());
+    }
+}
+
+#[test]
+fn bottom_viewport_follows_output() {
+    let mut term = Terminal::new(20, 4);
+    lines(&mut term, 0..10);
+    assert!(term.is_following_tail());
+    assert_eq!(term.viewport_offset(), 0);
+    lines(&mut term, 10..15);
+    assert_eq!(term.viewport_offset(), 0);
+}
+
+#[test]
+fn scrolled_back_viewport_stays_on_its_content() {
+    let mut term = Terminal::new(20, 4);
+    lines(&mut term, 0..10);
+    term.scroll_viewport_up(3);
+    let top = term.grid().scrollback_len() - term.viewport_offset();
+    let text = term.grid().scrollback_line(top).map(cells_to_text);
+
+    lines(&mut term, 10..15);
+    assert_eq!(term.viewport_offset(), 8);
+    let top = term.grid().scrollback_len() - term.viewport_offset();
+    assert_eq!(term.grid().scrollback_line(top).map(cells_to_text), text);
+
+    term.scroll_viewport_down(5);
+    assert_eq!(term.viewport_offset(), 3);
+    term.scroll_viewport_to_bottom();
+    assert_eq!(term.viewport_offset(), 0);
+    lines(&mut term, 15..17);
+    assert_eq!(term.viewport_offset(), 0);
+}
+
+#[test]
+fn eviction_above_the_view_shrinks_the_offset() {
+    let mut term = Terminal::with_scrollback(20, 4, 10);
+    lines(&mut term, 0..20);
+    assert_eq!(term.grid().scrollback_len(), 10);
+    term.set_viewport_offset(4);
+    lines(&mut term, 20..23);
+    // Three lines pushed below the view, three evicted above it
+    assert_eq!(term.viewport_offset(), 7);
+    // The top line is evicted: the view stays on the oldest line
+    lines(&mut term, 23..30);
+    assert_eq!(term.viewport_offset(), 10);
+    term.set_viewport_offset(99);
+    assert_eq!(term.viewport_offset(), 10);
+}
+
+#[test]
+fn follow_tail_off_pins_the_bottom_viewport() {
+    let mut term = Terminal::new(20, 4);
+    lines(&mut term, 0..10);
+    term.set_follow_tail(false);
+    lines(&mut term, 10..12);
+    assert_eq!(term.viewport_offset(), 2);
+
+    term.set_follow_tail(true);
+    assert_eq!(term.viewport_offset(), 2);
+    term.scroll_viewport_to_bottom();
+    lines(&mut term, 12..14);
+    assert_eq!(term.viewport_offset(), 0);
+}
+
+#[test]
+fn clearing_scrollback_returns_to_the_tail() {
+    let mut term = Terminal::new(20, 4);
+    lines(&mut term, 0..10);
+    term.scroll_viewport_up(5);
+    term.process(b"\x1b[3J");
+    assert_eq!(term.viewport_offset(), 0);
+    lines(&mut term, 10..15);
+    assert_eq!(term.viewport_offset(), 0);
+}
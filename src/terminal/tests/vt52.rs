@@ -0,0 +1,139 @@
+// VT52 compatibility mode (DECANM) tests
+use crate::kitty_keyboard::{Key, KeyEvent, KeyModifiers};
+use crate::terminal::*;
+
+fn vt52_terminal() -> Terminal {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"\x1b[?2l");
+    assert!(term.vt52_mode());
+    term
+}
+
+fn row_text(term: &Terminal, row: usize) -> String {
+    term.active_grid().row_text(row).trim_end().to_string()
+}
+
+#[test]
+fn decanm_reset_enters_and_esc_lt_leaves() {
+    let mut term = Terminal::new(80, 24);
+    assert!(!term.vt52_mode());
+    term.process(b"\x1b[?2l");
+    assert!(term.vt52_mode());
+    term.process(b"\x1b<");
+    assert!(!term.vt52_mode());
+    // ANSI sequences work again after ESC <
+    term.process(b"\x1b[3;4H");
+    assert_eq!((term.cursor.col, term.cursor.row), (3, 2));
+}
+
+#[test]
+fn csi_is_not_recognized_in_vt52_mode() {
+    let mut term = vt52_terminal();
+    term.process(b"\x1b[5;5H");
+    // ESC [ is not a VT52 sequence; the rest prints as text
+    assert_eq!(row_text(&term, 0), "5;5H");
+}
+
+#[test]
+fn cursor_movement_stops_at_the_edges() {
+    let mut term = vt52_terminal();
+    term.process(b"\x1bA\x1bD");
+    assert_eq!((term.cursor.col, term.cursor.row), (0, 0));
+    term.process(b"\x1bB\x1bB\x1bC\x1bC\x1bC");
+    assert_eq!((term.cursor.col, term.cursor.row), (3, 2));
+    term.process(b"\x1bH");
+    assert_eq!((term.cursor.col, term.cursor.row), (0, 0));
+}
+
+#[test]
+fn direct_cursor_address_is_offset_by_32() {
+    let mut term = vt52_terminal();
+    term.process(b"\x1bY%(X");
+    assert_eq!(row_text(&term, 5), "        X");
+    // Out-of-range coordinates clamp to the screen
+    term.process(b"\x1bY\x7e\x7e");
+    assert_eq!((term.cursor.col, term.cursor.row), (79, 23));
+}
+
+#[test]
+fn direct_cursor_address_split_across_chunks() {
+    let mut term = vt52_terminal();
+    term.process(b"\x1b");
+    term.process(b"Y");
+    term.process(b"\"");
+    term.process(b"#");
+    assert_eq!((term.cursor.col, term.cursor.row), (3, 2));
+}
+
+#[test]
+fn erase_to_end_of_line_and_screen() {
+    let mut term = vt52_terminal();
+    term.process(b"abcdef\r\nghijkl\x1bY  ");
+    term.process(b"\x1bC\x1bC\x1bK");
+    assert_eq!(row_text(&term, 0), "ab");
+    assert_eq!(row_text(&term, 1), "ghijkl");
+    term.process(b"\x1bY!#\x1bJ");
+    assert_eq!(row_text(&term, 0), "ab");
+    assert_eq!(row_text(&term, 1), "ghi");
+}
+
+#[test]
+fn reverse_line_feed_scrolls_at_the_top() {
+    let mut term = vt52_terminal();
+    term.process(b"top\x1bH\x1bI");
+    assert_eq!(row_text(&term, 0), "");
+    assert_eq!(row_text(&term, 1), "top");
+}
+
+#[test]
+fn graphics_mode_uses_line_drawing_until_ansi_mode() {
+    let mut term = vt52_terminal();
+    term.process(b"\x1bFq\x1bGq\x1bFq\x1b<q");
+    assert_eq!(row_text(&term, 0), "─q─q");
+}
+
+#[test]
+fn identify_replies_with_esc_slash_z() {
+    let mut term = vt52_terminal();
+    term.process(b"\x1bZ");
+    assert_eq!(term.drain_responses(), b"\x1b/Z");
+}
+
+#[test]
+fn cursor_keys_send_vt52_codes() {
+    let mut term = vt52_terminal();
+    assert_eq!(term.encode_key(&KeyEvent::new(Key::Up)), b"\x1bA");
+    assert_eq!(term.encode_key(&KeyEvent::new(Key::Left)), b"\x1bD");
+    assert_eq!(term.encode_key(&KeyEvent::new(Key::F(1))), b"\x1bP");
+    // Modified keys keep the ANSI encoding
+    let shifted = KeyEvent::new(Key::Up).with_modifiers(KeyModifiers::SHIFT);
+    assert_eq!(term.encode_key(&shifted), b"\x1b[1;2A");
+    term.process(b"\x1b<");
+    assert_eq!(term.encode_key(&KeyEvent::new(Key::Up)), b"\x1b[A");
+}
+
+#[test]
+fn controls_inside_a_sequence_still_execute() {
+    let mut term = vt52_terminal();
+    term.process(b"ab\x1bY\r$%");
+    assert_eq!((term.cursor.col, term.cursor.row), (5, 4));
+    // CAN abandons the sequence
+    term.process(b"\x1bY\x18#x");
+    assert_eq!(row_text(&term, 4), "     #x");
+}
+
+#[test]
+fn reset_returns_to_ansi_mode() {
+    let mut term = vt52_terminal();
+    term.reset();
+    assert!(!term.vt52_mode());
+    term.process(b"\x1b[2;2H");
+    assert_eq!((term.cursor.col, term.cursor.row), (1, 1));
+}
+
+#[test]
+fn decrqm_reports_ansi_mode_set() {
+    let mut term = Terminal::new(80, 24);
+    term.process(b"\x1b[?2$p");
+    assert_eq!(term.drain_responses(), b"\x1b[?2;1$y");
+}
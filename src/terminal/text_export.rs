@@ -0,0 +1,213 @@
+//! Configurable plain-text export
+//!
+//! [`Terminal::export_text_opts`] renders the primary screen buffer as plain
+//! text in the shape a consumer needs: joined or physical lines, trimmed or
+//! padded, LF or CRLF, spaces or tabs, with or without zone markers and
+//! row timestamps.
+
+use crate::cell::Cell;
+use crate::terminal::Terminal;
+use crate::zone::{Zone, ZoneType};
+
+/// Line terminator used by [`Terminal::export_text_opts`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineEnding {
+    /// `\n`
+    #[default]
+    Lf,
+    /// `\r\n`
+    CrLf,
+}
+
+impl LineEnding {
+    /// The terminator characters
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+}
+
+/// Options for [`Terminal::export_text_opts`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportTextOptions {
+    /// Include scrollback before the visible screen
+    pub include_scrollback: bool,
+    /// Join soft-wrapped rows into one logical line
+    pub join_wrapped: bool,
+    /// Trim trailing whitespace from each line
+    pub trim_trailing_whitespace: bool,
+    /// Terminator written after every line
+    pub line_ending: LineEnding,
+    /// Replace runs of two or more spaces that end at a tab stop with `\t`
+    pub reconstruct_tabs: bool,
+    /// Write a comment line before each line where a shell integration zone
+    /// starts, e.g. `# prompt` or `# output: make test (exit 1)`
+    pub zone_markers: bool,
+    /// Prefix of zone marker lines
+    pub comment_prefix: String,
+    /// Prefix each line with the UTC time its first row was written, e.g.
+    /// `[2026-10-17T09:30:00.000Z] `; lines without a timestamp get blank
+    /// padding of the same width
+    pub timestamps: bool,
+}
+
+impl Default for ExportTextOptions {
+    fn default() -> Self {
+        Self {
+            include_scrollback: true,
+            join_wrapped: true,
+            trim_trailing_whitespace: true,
+            line_ending: LineEnding::Lf,
+            reconstruct_tabs: false,
+            zone_markers: false,
+            comment_prefix: "# ".to_string(),
+            timestamps: false,
+        }
+    }
+}
+
+/// Text of one physical row, skipping wide-character spacers and optionally
+/// folding space runs that end at a tab stop into tabs
+fn row_text(cells: &[Cell], tab_stops: Option<&[bool]>) -> String {
+    let mut text = String::with_capacity(cells.len());
+    let mut blank_start = None;
+    for (col, cell) in cells.iter().enumerate() {
+        if cell.flags.wide_char_spacer() {
+            continue;
+        }
+        if let Some(stops) = tab_stops {
+            if cell.c == ' ' && cell.combining.is_empty() {
+                let start = *blank_start.get_or_insert(col);
+                if col + 1 - start >= 2 && stops.get(col + 1).copied().unwrap_or(false) {
+                    text.push('\t');
+                    blank_start = None;
+                }
+                continue;
+            }
+            if let Some(start) = blank_start.take() {
+                text.extend(std::iter::repeat_n(' ', col - start));
+            }
+        }
+        cell.push_grapheme(&mut text);
+    }
+    if let Some(start) = blank_start {
+        text.extend(std::iter::repeat_n(' ', cells.len() - start));
+    }
+    text
+}
+
+/// Width of a [`timestamp_prefix`]
+const TIMESTAMP_PREFIX_WIDTH: usize = 27;
+
+/// `[YYYY-MM-DDTHH:MM:SS.mmmZ] ` for Unix epoch milliseconds `millis`
+fn timestamp_prefix(millis: u64) -> String {
+    let secs = millis / 1000;
+    let (days, secs_of_day) = ((secs / 86_400) as i64, secs % 86_400);
+    // Civil date from days since 1970-01-01 (proleptic Gregorian)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "[{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z] ",
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+        millis % 1000
+    )
+}
+
+fn zone_marker(zone: &Zone, prefix: &str) -> String {
+    let mut marker = format!("{prefix}{}", zone.zone_type);
+    if zone.zone_type == ZoneType::Prompt {
+        return marker;
+    }
+    if let Some(command) = &zone.command {
+        marker.push_str(": ");
+        marker.push_str(command);
+    }
+    if let Some(code) = zone.exit_code {
+        marker.push_str(&format!(" (exit {code})"));
+    }
+    marker
+}
+
+impl Terminal {
+    /// Export the primary screen buffer as plain text shaped by `options`
+    ///
+    /// Blank rows below the last non-blank row of the screen are omitted.
+    /// Tabs are reconstructed against the current tab stops, so output
+    /// written before the stops changed may not round-trip exactly.
+    pub fn export_text_opts(&self, options: &ExportTextOptions) -> String {
+        let scrollback_len = self.grid.scrollback_len();
+        let start = if options.include_scrollback {
+            0
+        } else {
+            scrollback_len
+        };
+        let last_screen_row = (0..self.grid.rows())
+            .rev()
+            .find(|&row| {
+                self.grid.row(row).is_some_and(|cells| {
+                    cells.iter().any(|c| c.c != ' ' || !c.combining.is_empty())
+                })
+            })
+            .map_or(0, |row| row + 1);
+        let end = scrollback_len + last_screen_row;
+
+        let tab_stops = options.reconstruct_tabs.then_some(&self.tab_stops[..]);
+        let zones = if options.zone_markers {
+            self.grid.zones()
+        } else {
+            &[]
+        };
+        let newline = options.line_ending.as_str();
+
+        let mut output = String::new();
+        let mut line = String::new();
+        let mut markers = Vec::new();
+        let mut line_start = start;
+        for abs_row in start..end {
+            let Some((cells, wrapped)) = self.primary_row(abs_row) else {
+                break;
+            };
+            markers.extend(
+                zones
+                    .iter()
+                    .filter(|z| z.abs_row_start == abs_row)
+                    .map(|z| zone_marker(z, &options.comment_prefix)),
+            );
+            line.push_str(&row_text(cells, tab_stops));
+            if wrapped && options.join_wrapped && abs_row + 1 < end {
+                continue;
+            }
+
+            for marker in markers.drain(..) {
+                output.push_str(&marker);
+                output.push_str(newline);
+            }
+            if options.timestamps {
+                match self.grid.row_timestamp(line_start) {
+                    Some(millis) => line.insert_str(0, &timestamp_prefix(millis)),
+                    None => line.insert_str(0, &" ".repeat(TIMESTAMP_PREFIX_WIDTH)),
+                }
+            }
+            if options.trim_trailing_whitespace {
+                output.push_str(line.trim_end());
+            } else {
+                output.push_str(&line);
+            }
+            output.push_str(newline);
+            line.clear();
+            line_start = abs_row + 1;
+        }
+        self.redact_export(output, Some(start))
+    }
+}
@@ -0,0 +1,108 @@
+//! Window title and icon name tracking.
+//!
+//! OSC 2 sets the window title, OSC 1 the icon name (the label xterm shows
+//! for a minimized window, which frontends typically use for tab titles) and
+//! OSC 0 sets both. Each actual change emits a typed event
+//! ([`TerminalEvent::TitleChanged`] or [`TerminalEvent::IconNameChanged`]) and
+//! is appended to a bounded, timestamped history so a frontend can tell what
+//! a remote session did to its title even if it was not polling at the time.
+
+use std::collections::VecDeque;
+
+use crate::terminal::{unix_millis, Terminal, TerminalEvent};
+
+/// Maximum number of entries kept in the title history; the oldest are
+/// discarded first
+pub const MAX_TITLE_HISTORY: usize = 100;
+
+/// Which label a title change applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TitleKind {
+    /// Window title (OSC 0 / OSC 2)
+    Title,
+    /// Icon name (OSC 0 / OSC 1)
+    IconName,
+}
+
+impl TitleKind {
+    /// Short lowercase name (`"title"`, `"icon_name"`)
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TitleKind::Title => "title",
+            TitleKind::IconName => "icon_name",
+        }
+    }
+}
+
+/// One recorded title or icon name change
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TitleHistoryEntry {
+    /// Which label changed
+    pub kind: TitleKind,
+    /// The new value
+    pub text: String,
+    /// When the change happened (Unix epoch milliseconds)
+    pub timestamp: u64,
+}
+
+impl Terminal {
+    /// Get the icon name set by OSC 0 or OSC 1
+    pub fn icon_name(&self) -> &str {
+        &self.title_state.icon_name
+    }
+
+    /// Title and icon name changes, oldest first, at most
+    /// [`MAX_TITLE_HISTORY`] entries.
+    ///
+    /// ```
+    /// use par_term_emu_core_rust::terminal::{Terminal, TitleKind};
+    ///
+    /// let mut term = Terminal::new(80, 24);
+    /// term.process(b"\x1b]2;vim\x07\x1b]1;main.rs\x07");
+    /// let history: Vec<_> = term.title_history().iter().map(|e| (e.kind, e.text.as_str())).collect();
+    /// assert_eq!(history, [(TitleKind::Title, "vim"), (TitleKind::IconName, "main.rs")]);
+    /// ```
+    pub fn title_history(&self) -> &VecDeque<TitleHistoryEntry> {
+        &self.title_state.title_history
+    }
+
+    /// Forget all recorded title and icon name changes
+    pub fn clear_title_history(&mut self) {
+        self.title_state.title_history.clear();
+    }
+
+    /// Set the title or icon name from the application, emitting an event and
+    /// recording history when the value actually changes
+    pub(crate) fn apply_title_change(&mut self, kind: TitleKind, text: String) {
+        let current = match kind {
+            TitleKind::Title => &mut self.title_state.title,
+            TitleKind::IconName => &mut self.title_state.icon_name,
+        };
+        if *current == text {
+            return;
+        }
+        let old = std::mem::replace(current, text.clone());
+
+        let history = &mut self.title_state.title_history;
+        if history.len() >= MAX_TITLE_HISTORY {
+            history.pop_front();
+        }
+        history.push_back(TitleHistoryEntry {
+            kind,
+            text: text.clone(),
+            timestamp: unix_millis(),
+        });
+
+        let event = match kind {
+            TitleKind::Title => TerminalEvent::TitleChanged {
+                title: text,
+                old_title: old,
+            },
+            TitleKind::IconName => TerminalEvent::IconNameChanged {
+                icon_name: text,
+                old_icon_name: old,
+            },
+        };
+        self.events.terminal_events.push(event);
+    }
+}
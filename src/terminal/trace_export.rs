@@ -0,0 +1,422 @@
+//! Command execution export as OpenTelemetry-style spans
+//!
+//! Converts the shell-integration command history into a trace whose JSON
+//! form follows the OTLP/JSON `ExportTraceServiceRequest` shape
+//! (`resourceSpans` → `scopeSpans` → `spans`), so it can be posted to an
+//! OTLP/HTTP collector or fed into any tool that understands that layout.
+//!
+//! Each export produces one trace: a root `terminal.session` span covering
+//! all exported commands, one `shell.command` child span per command, and a
+//! `shell.output` grandchild span when the command's output zone is known.
+
+use serde::Serialize;
+
+use crate::terminal::Terminal;
+use crate::zone::ZoneType;
+
+/// OTLP span kind: internal operation
+const SPAN_KIND_INTERNAL: u8 = 1;
+/// OTLP status code: unset (command still running or exit code unknown)
+const STATUS_CODE_UNSET: u8 = 0;
+/// OTLP status code: ok
+const STATUS_CODE_OK: u8 = 1;
+/// OTLP status code: error
+const STATUS_CODE_ERROR: u8 = 2;
+
+/// Instrumentation scope name reported on exported spans
+pub const TRACE_SCOPE_NAME: &str = "par-term-emu-core-rust";
+
+/// Top-level OTLP/JSON trace export
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceExport {
+    /// Spans grouped by resource (always one resource per terminal)
+    pub resource_spans: Vec<ResourceSpans>,
+}
+
+/// Spans sharing a resource
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceSpans {
+    /// Resource description (service name, host)
+    pub resource: Resource,
+    /// Spans grouped by instrumentation scope
+    pub scope_spans: Vec<ScopeSpans>,
+}
+
+/// OTLP resource
+#[derive(Debug, Clone, Serialize)]
+pub struct Resource {
+    /// Resource attributes
+    pub attributes: Vec<KeyValue>,
+}
+
+/// Spans emitted by one instrumentation scope
+#[derive(Debug, Clone, Serialize)]
+pub struct ScopeSpans {
+    /// Instrumentation scope
+    pub scope: InstrumentationScope,
+    /// Spans in this scope
+    pub spans: Vec<Span>,
+}
+
+/// OTLP instrumentation scope
+#[derive(Debug, Clone, Serialize)]
+pub struct InstrumentationScope {
+    /// Scope name
+    pub name: String,
+    /// Scope version
+    pub version: String,
+}
+
+/// A single span
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Span {
+    /// 32-char lowercase hex trace ID
+    pub trace_id: String,
+    /// 16-char lowercase hex span ID
+    pub span_id: String,
+    /// Parent span ID (empty for the root span)
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub parent_span_id: String,
+    /// Span name
+    pub name: String,
+    /// Span kind (1 = internal)
+    pub kind: u8,
+    /// Start time in unix nanoseconds (string-encoded per OTLP/JSON)
+    pub start_time_unix_nano: String,
+    /// End time in unix nanoseconds (string-encoded per OTLP/JSON)
+    pub end_time_unix_nano: String,
+    /// Span attributes
+    pub attributes: Vec<KeyValue>,
+    /// Span status
+    pub status: SpanStatus,
+}
+
+/// OTLP span status
+#[derive(Debug, Clone, Serialize)]
+pub struct SpanStatus {
+    /// 0 = unset, 1 = ok, 2 = error
+    pub code: u8,
+    /// Status message (empty unless the span failed)
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub message: String,
+}
+
+/// OTLP key/value attribute
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyValue {
+    /// Attribute key
+    pub key: String,
+    /// Attribute value
+    pub value: AnyValue,
+}
+
+/// OTLP attribute value
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum AnyValue {
+    /// String value
+    #[serde(rename = "stringValue")]
+    String(String),
+    /// Integer value (string-encoded per OTLP/JSON)
+    #[serde(rename = "intValue")]
+    Int(String),
+    /// Boolean value
+    #[serde(rename = "boolValue")]
+    Bool(bool),
+}
+
+impl KeyValue {
+    fn string(key: &str, value: impl Into<String>) -> Self {
+        Self {
+            key: key.to_string(),
+            value: AnyValue::String(value.into()),
+        }
+    }
+
+    fn int(key: &str, value: i64) -> Self {
+        Self {
+            key: key.to_string(),
+            value: AnyValue::Int(value.to_string()),
+        }
+    }
+
+    fn bool(key: &str, value: bool) -> Self {
+        Self {
+            key: key.to_string(),
+            value: AnyValue::Bool(value),
+        }
+    }
+}
+
+impl TraceExport {
+    /// All spans in the export, in emission order (root first)
+    pub fn spans(&self) -> impl Iterator<Item = &Span> {
+        self.resource_spans
+            .iter()
+            .flat_map(|r| r.scope_spans.iter())
+            .flat_map(|s| s.spans.iter())
+    }
+}
+
+fn new_trace_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+fn new_span_id() -> String {
+    let mut id = uuid::Uuid::new_v4().simple().to_string();
+    id.truncate(16);
+    id
+}
+
+fn millis_to_nanos(ms: u64) -> String {
+    (ms as u128 * 1_000_000).to_string()
+}
+
+impl Terminal {
+    /// Export command execution history as an OTLP-shaped trace
+    ///
+    /// # Arguments
+    /// * `service_name` - Value for the `service.name` resource attribute
+    ///   (defaults to `"par-term"`)
+    ///
+    /// Commands still running are exported with their end time set to now
+    /// and an unset status.
+    pub fn export_command_spans(&self, service_name: Option<&str>) -> TraceExport {
+        let now = crate::terminal::unix_millis();
+        let trace_id = new_trace_id();
+        let root_span_id = new_span_id();
+
+        let commands: Vec<_> = self
+            .command_history_state
+            .command_history
+            .iter()
+            .chain(self.command_history_state.current_command.iter())
+            .collect();
+
+        let mut spans = Vec::with_capacity(commands.len() * 2 + 1);
+        let session_start = commands.iter().map(|c| c.start_time).min().unwrap_or(now);
+        let session_end = commands
+            .iter()
+            .map(|c| c.end_time.unwrap_or(now))
+            .max()
+            .unwrap_or(now);
+        let failed = commands.iter().filter(|c| c.success == Some(false)).count();
+
+        spans.push(Span {
+            trace_id: trace_id.clone(),
+            span_id: root_span_id.clone(),
+            parent_span_id: String::new(),
+            name: "terminal.session".to_string(),
+            kind: SPAN_KIND_INTERNAL,
+            start_time_unix_nano: millis_to_nanos(session_start),
+            end_time_unix_nano: millis_to_nanos(session_end),
+            attributes: vec![
+                KeyValue::int("terminal.command.count", commands.len() as i64),
+                KeyValue::int("terminal.command.failed_count", failed as i64),
+            ],
+            status: SpanStatus {
+                code: STATUS_CODE_UNSET,
+                message: String::new(),
+            },
+        });
+
+        for cmd in commands {
+            let span_id = new_span_id();
+            let end_time = cmd.end_time.unwrap_or(now);
+
+            let mut attributes = vec![KeyValue::string("process.command_line", &cmd.command)];
+            if let Some(cwd) = &cmd.cwd {
+                attributes.push(KeyValue::string("process.working_directory", cwd));
+            }
+            if let Some(code) = cmd.exit_code {
+                attributes.push(KeyValue::int("process.exit.code", code as i64));
+            }
+            if let Some(duration) = cmd.duration_ms {
+                attributes.push(KeyValue::int("shell.command.duration_ms", duration as i64));
+            }
+            if let Some(host) = &cmd.hostname {
+                attributes.push(KeyValue::string("host.name", host));
+            }
+            if let Some(user) = &cmd.username {
+                attributes.push(KeyValue::string("user.name", user));
+            }
+
+            let status = match cmd.exit_code {
+                Some(0) => SpanStatus {
+                    code: STATUS_CODE_OK,
+                    message: String::new(),
+                },
+                Some(code) => SpanStatus {
+                    code: STATUS_CODE_ERROR,
+                    message: format!("exit code {}", code),
+                },
+                None => SpanStatus {
+                    code: STATUS_CODE_UNSET,
+                    message: String::new(),
+                },
+            };
+
+            let output_zone = cmd.output_start_row.and_then(|row| {
+                self.grid
+                    .zones()
+                    .iter()
+                    .find(|z| z.zone_type == ZoneType::Output && z.abs_row_start == row)
+            });
+            let output_span = output_zone.map(|zone| {
+                let zone_start = zone.timestamp.unwrap_or(cmd.start_time).max(cmd.start_time);
+                let zone_end = cmd.output_end_row.unwrap_or(zone.abs_row_end);
+                Span {
+                    trace_id: trace_id.clone(),
+                    span_id: new_span_id(),
+                    parent_span_id: span_id.clone(),
+                    name: "shell.output".to_string(),
+                    kind: SPAN_KIND_INTERNAL,
+                    start_time_unix_nano: millis_to_nanos(zone_start),
+                    end_time_unix_nano: millis_to_nanos(end_time.max(zone_start)),
+                    attributes: vec![
+                        KeyValue::int("terminal.zone.id", zone.id as i64),
+                        KeyValue::int("terminal.zone.row_start", zone.abs_row_start as i64),
+                        KeyValue::int("terminal.zone.row_end", zone_end as i64),
+                    ],
+                    status: SpanStatus {
+                        code: STATUS_CODE_UNSET,
+                        message: String::new(),
+                    },
+                }
+            });
+
+            spans.push(Span {
+                trace_id: trace_id.clone(),
+                span_id,
+                parent_span_id: root_span_id.clone(),
+                name: "shell.command".to_string(),
+                kind: SPAN_KIND_INTERNAL,
+                start_time_unix_nano: millis_to_nanos(cmd.start_time),
+                end_time_unix_nano: millis_to_nanos(end_time),
+                attributes,
+                status,
+            });
+            spans.extend(output_span);
+        }
+
+        let mut resource_attributes = vec![KeyValue::string(
+            "service.name",
+            service_name.unwrap_or("par-term"),
+        )];
+        let host = &self.shell_state.host_context;
+        if let Some(name) = &host.hostname {
+            resource_attributes.push(KeyValue::string("host.name", name));
+        }
+        resource_attributes.push(KeyValue::bool("terminal.remote", host.is_remote));
+
+        TraceExport {
+            resource_spans: vec![ResourceSpans {
+                resource: Resource {
+                    attributes: resource_attributes,
+                },
+                scope_spans: vec![ScopeSpans {
+                    scope: InstrumentationScope {
+                        name: TRACE_SCOPE_NAME.to_string(),
+                        version: env!("CARGO_PKG_VERSION").to_string(),
+                    },
+                    spans,
+                }],
+            }],
+        }
+    }
+
+    /// Export command execution history as OTLP/JSON
+    pub fn export_command_spans_json(&self, service_name: Option<&str>) -> String {
+        serde_json::to_string(&self.export_command_spans(service_name)).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attr<'a>(span: &'a Span, key: &str) -> Option<&'a AnyValue> {
+        span.attributes
+            .iter()
+            .find(|kv| kv.key == key)
+            .map(|kv| &kv.value)
+    }
+
+    #[test]
+    fn test_empty_history_exports_root_only() {
+        let term = Terminal::new(80, 24);
+        let export = term.export_command_spans(None);
+        let spans: Vec<_> = export.spans().collect();
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].name, "terminal.session");
+        assert!(spans[0].parent_span_id.is_empty());
+        assert_eq!(spans[0].trace_id.len(), 32);
+        assert_eq!(spans[0].span_id.len(), 16);
+    }
+
+    #[test]
+    fn test_command_spans_from_shell_integration() {
+        let mut term = Terminal::new(80, 24);
+        term.process(b"\x1b]1337;RemoteHost=alice@server1\x1b\\");
+        term.process(b"\x1b]133;A\x07$ \x1b]133;B\x07");
+        term.start_command_execution("false".to_string());
+        term.process(b"\x1b]133;C\x07\r\noops\r\n");
+        term.process(b"\x1b]133;D;1\x07");
+        term.end_command_execution(Some(1));
+
+        let export = term.export_command_spans(Some("ci"));
+        let spans: Vec<_> = export.spans().collect();
+        let root = spans[0];
+        let cmd = spans
+            .iter()
+            .find(|s| s.name == "shell.command")
+            .expect("command span");
+
+        assert_eq!(cmd.parent_span_id, root.span_id);
+        assert_eq!(cmd.trace_id, root.trace_id);
+        assert_eq!(cmd.status.code, STATUS_CODE_ERROR);
+        assert_eq!(
+            attr(cmd, "process.command_line"),
+            Some(&AnyValue::String("false".to_string()))
+        );
+        assert_eq!(
+            attr(cmd, "process.exit.code"),
+            Some(&AnyValue::Int("1".to_string()))
+        );
+        assert_eq!(
+            attr(cmd, "host.name"),
+            Some(&AnyValue::String("server1".to_string()))
+        );
+
+        let output = spans
+            .iter()
+            .find(|s| s.name == "shell.output")
+            .expect("output span");
+        assert_eq!(output.parent_span_id, cmd.span_id);
+
+        let json = term.export_command_spans_json(Some("ci"));
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            value["resourceSpans"][0]["resource"]["attributes"][0]["value"]["stringValue"],
+            "ci"
+        );
+        assert!(
+            value["resourceSpans"][0]["scopeSpans"][0]["spans"][0]["startTimeUnixNano"].is_string()
+        );
+    }
+
+    #[test]
+    fn test_running_command_has_unset_status() {
+        let mut term = Terminal::new(80, 24);
+        term.start_command_execution("sleep 10".to_string());
+        let export = term.export_command_spans(None);
+        let cmd = export
+            .spans()
+            .find(|s| s.name == "shell.command")
+            .expect("command span");
+        assert_eq!(cmd.status.code, STATUS_CODE_UNSET);
+        assert!(attr(cmd, "process.exit.code").is_none());
+    }
+}
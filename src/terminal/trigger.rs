@@ -50,6 +50,12 @@ pub enum TriggerAction {
     },
     /// Set a session variable (reuses existing badge session_variables)
     SetVariable { name: String, value: String },
+    /// Set a user variable, as OSC 1337 SetUserVar would (emits `UserVarChanged`)
+    SetUserVar { name: String, value: String },
+    /// Emit a `TerminalEvent::TriggerFired` carrying `name` and the match
+    EmitEvent { name: String },
+    /// Mark the running command (or the last finished one) as failed
+    MarkCommandFailed,
     /// Run an external command (emitted as event for frontend)
     RunCommand { command: String, args: Vec<String> },
     /// Play a sound (emitted as event for frontend)
@@ -299,6 +305,23 @@ impl Terminal {
                         .custom
                         .insert(name, value);
                 }
+                TriggerAction::SetUserVar { name, value } => {
+                    let name = substitute_captures(name, &trigger_match.captures);
+                    let value = substitute_captures(value, &trigger_match.captures);
+                    self.set_user_var(name, value);
+                }
+                TriggerAction::EmitEvent { name } => {
+                    let name = substitute_captures(name, &trigger_match.captures);
+                    self.events.terminal_events.push(
+                        crate::terminal::TerminalEvent::TriggerFired {
+                            name,
+                            trigger_match: trigger_match.clone(),
+                        },
+                    );
+                }
+                TriggerAction::MarkCommandFailed => {
+                    self.mark_command_failed();
+                }
                 TriggerAction::RunCommand { command, args } => {
                     let command = substitute_captures(command, &trigger_match.captures);
                     let args: Vec<String> = args
@@ -508,8 +531,8 @@ impl TriggerRegistry {
         let id = self.next_id;
         self.next_id += 1;
 
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
+        let now = crate::time::SystemTime::now()
+            .duration_since(crate::time::UNIX_EPOCH)
             .unwrap_or_default()
             .as_millis() as u64;
 
@@ -594,8 +617,8 @@ impl TriggerRegistry {
             None => return Vec::new(),
         };
 
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
+        let now = crate::time::SystemTime::now()
+            .duration_since(crate::time::UNIX_EPOCH)
             .unwrap_or_default()
             .as_millis() as u64;
 
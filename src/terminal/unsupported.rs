@@ -0,0 +1,131 @@
+//! Capture of escape sequences the emulator ignores.
+//!
+//! Every CSI, OSC or DCS sequence that reaches a "not supported" branch is
+//! recorded here as well as in the debug log, so an application developer can
+//! ask [`Terminal::unsupported_sequences`] which sequences their tool emits
+//! that this emulator drops, and report them precisely.
+//!
+//! Occurrences are grouped by [`UnsupportedSequence::name`]: the first one
+//! creates an entry, later ones bump its count and replace its example
+//! parameters and bytes. The capture is bounded
+//! ([`MAX_UNSUPPORTED_SEQUENCES`] entries, [`MAX_UNSUPPORTED_RAW_LENGTH`]
+//! bytes each) because the input is untrusted. Like the sequence filter it
+//! belongs to the embedder, so it survives a hard reset (RIS).
+
+use crate::terminal::action::TerminalAction;
+use crate::terminal::Terminal;
+
+/// Maximum number of distinct unsupported sequences kept; further kinds are
+/// not recorded until the capture is cleared
+pub const MAX_UNSUPPORTED_SEQUENCES: usize = 256;
+
+/// Maximum length of [`UnsupportedSequence::raw`]
+pub const MAX_UNSUPPORTED_RAW_LENGTH: usize = 256;
+
+/// Family of an unsupported sequence
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnsupportedKind {
+    /// Control Sequence Introducer (`ESC [`), including private modes
+    Csi,
+    /// Operating System Command (`ESC ]`)
+    Osc,
+    /// Device Control String (`ESC P`)
+    Dcs,
+}
+
+impl UnsupportedKind {
+    /// Short uppercase name (`"CSI"`, `"OSC"`, `"DCS"`)
+    pub fn as_str(self) -> &'static str {
+        match self {
+            UnsupportedKind::Csi => "CSI",
+            UnsupportedKind::Osc => "OSC",
+            UnsupportedKind::Dcs => "DCS",
+        }
+    }
+}
+
+/// A sequence the emulator ignored, with how often it was seen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedSequence {
+    /// Sequence family
+    pub kind: UnsupportedKind,
+    /// What was not understood: `CSI ?~` (intermediates and final character),
+    /// `DECSET 9999` / `DECRST 9999` (private mode), `OSC 1234` (command) or
+    /// `DCS $q`
+    pub name: String,
+    /// Numeric parameters of the latest occurrence, each with its
+    /// `:`-separated sub-parameters (empty for OSC)
+    pub params: Vec<Vec<u16>>,
+    /// Canonical bytes of the latest occurrence, truncated to
+    /// [`MAX_UNSUPPORTED_RAW_LENGTH`]. DCS entries hold only the header, since
+    /// the payload is not buffered.
+    pub raw: Vec<u8>,
+    /// Number of occurrences
+    pub count: u64,
+}
+
+impl Terminal {
+    /// Unsupported sequences seen since creation or the last
+    /// [`Terminal::clear_unsupported_sequences`], in first-seen order.
+    ///
+    /// ```
+    /// use par_term_emu_core_rust::terminal::Terminal;
+    ///
+    /// let mut term = Terminal::new(80, 24);
+    /// term.process(b"\x1b[?9999h\x1b]4242;x\x07\x1b[?9999h");
+    /// let seen = term.unsupported_sequences();
+    /// assert_eq!(seen[0].name, "DECSET 9999");
+    /// assert_eq!(seen[0].count, 2);
+    /// assert_eq!(seen[1].name, "OSC 4242");
+    /// ```
+    pub fn unsupported_sequences(&self) -> &[UnsupportedSequence] {
+        &self.unsupported_sequences
+    }
+
+    /// Forget all captured unsupported sequences
+    pub fn clear_unsupported_sequences(&mut self) {
+        self.unsupported_sequences.clear();
+    }
+
+    /// Record one occurrence of an unsupported sequence. `action` is the
+    /// dispatched sequence, used for the parameters and raw bytes.
+    pub(crate) fn record_unsupported(
+        &mut self,
+        kind: UnsupportedKind,
+        name: String,
+        action: TerminalAction,
+    ) {
+        let index = match self
+            .unsupported_sequences
+            .iter()
+            .position(|entry| entry.kind == kind && entry.name == name)
+        {
+            Some(index) => index,
+            None if self.unsupported_sequences.len() < MAX_UNSUPPORTED_SEQUENCES => {
+                self.unsupported_sequences.push(UnsupportedSequence {
+                    kind,
+                    name,
+                    params: Vec::new(),
+                    raw: Vec::new(),
+                    count: 0,
+                });
+                self.unsupported_sequences.len() - 1
+            }
+            None => return,
+        };
+
+        let mut raw = action.to_bytes();
+        raw.truncate(MAX_UNSUPPORTED_RAW_LENGTH);
+        let params = match action {
+            TerminalAction::CsiDispatch { params, .. } | TerminalAction::DcsHook { params, .. } => {
+                params
+            }
+            _ => Vec::new(),
+        };
+
+        let entry = &mut self.unsupported_sequences[index];
+        entry.params = params;
+        entry.raw = raw;
+        entry.count = entry.count.saturating_add(1);
+    }
+}
@@ -0,0 +1,211 @@
+//! UTF-8 resilience mode for PTY output.
+//!
+//! By default bytes go straight to the vte parser, which replaces most
+//! invalid UTF-8 with U+FFFD but executes a stray byte in `0x80..=0x9F` as a
+//! C1 control and silently skips the rest of a malformed run. With
+//! [`Terminal::set_utf8_resilience`] enabled, output is validated before
+//! parsing: each invalid sequence becomes exactly one U+FFFD, decoding
+//! resyncs on the next valid lead byte, and every error is counted and
+//! reported with its byte offset in a [`TerminalEvent::Utf8DecodeError`].
+//!
+//! The most recent undecodable runs are kept raw (at most
+//! [`MAX_UTF8_ERROR_RUNS`] runs of [`MAX_UTF8_ERROR_RUN_LENGTH`] bytes) so a
+//! frontend can show what arrived, which usually identifies a remote locale
+//! such as Latin-1 or Shift-JIS. Like the unsupported-sequence capture, the
+//! mode and its telemetry belong to the embedder and survive a hard reset
+//! (RIS).
+//!
+//! [`TerminalEvent::Utf8DecodeError`]: crate::terminal::TerminalEvent::Utf8DecodeError
+
+use std::borrow::Cow;
+use std::collections::VecDeque;
+
+use crate::terminal::{Terminal, TerminalEvent};
+
+/// Maximum number of undecodable runs kept for [`Terminal::utf8_decode_errors`]
+pub const MAX_UTF8_ERROR_RUNS: usize = 64;
+
+/// Maximum length of [`Utf8DecodeError::bytes`]
+pub const MAX_UTF8_ERROR_RUN_LENGTH: usize = 64;
+
+const REPLACEMENT: &[u8] = "\u{FFFD}".as_bytes();
+
+/// A run of consecutive bytes that are not valid UTF-8
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Utf8DecodeError {
+    /// Offset of the first byte in the output stream, counting every byte
+    /// passed to `process()` since the terminal was created
+    pub offset: u64,
+    /// Number of invalid bytes in the run
+    pub length: usize,
+    /// The raw bytes, truncated to [`MAX_UTF8_ERROR_RUN_LENGTH`]
+    pub bytes: Vec<u8>,
+}
+
+/// Counters for [`Terminal::utf8_decode_stats`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Utf8DecodeStats {
+    /// Invalid sequences, each replaced with one U+FFFD
+    pub errors: u64,
+    /// Bytes that were part of an invalid sequence
+    pub invalid_bytes: u64,
+    /// Runs of consecutive invalid sequences (one event each)
+    pub runs: u64,
+}
+
+/// Decoder state behind [`Terminal::set_utf8_resilience`]
+#[derive(Debug, Default)]
+pub(crate) struct Utf8Guard {
+    pub(crate) enabled: bool,
+    /// Stream offset of the next byte passed to `process()`
+    offset: u64,
+    /// Incomplete sequence at the end of the previous chunk
+    pending: Vec<u8>,
+    stats: Utf8DecodeStats,
+    recent: VecDeque<Utf8DecodeError>,
+}
+
+impl Utf8Guard {
+    /// Count bytes that bypass the guard, keeping offsets stream-wide
+    pub(crate) fn skip(&mut self, len: usize) {
+        self.offset += len as u64;
+    }
+
+    /// Take the bytes of an incomplete trailing sequence
+    pub(crate) fn take_pending(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.pending)
+    }
+
+    /// Replace each invalid sequence in `data` with U+FFFD. An incomplete
+    /// sequence at the end is held back until the next chunk. Returns the
+    /// bytes to parse and the runs found in this chunk.
+    pub(crate) fn sanitize<'a>(&mut self, data: &'a [u8]) -> (Cow<'a, [u8]>, Vec<Utf8DecodeError>) {
+        let base = self.offset - self.pending.len() as u64;
+        self.offset += data.len() as u64;
+        let input: Cow<'a, [u8]> = if self.pending.is_empty() {
+            Cow::Borrowed(data)
+        } else {
+            let mut joined = std::mem::take(&mut self.pending);
+            joined.extend_from_slice(data);
+            Cow::Owned(joined)
+        };
+
+        let mut out = Vec::new();
+        let mut runs: Vec<Utf8DecodeError> = Vec::new();
+        let mut copied = 0;
+        let mut pos = 0;
+        let end = loop {
+            let Err(err) = std::str::from_utf8(&input[pos..]) else {
+                break input.len();
+            };
+            let bad = pos + err.valid_up_to();
+            let Some(len) = err.error_len() else {
+                // Incomplete sequence cut off by the end of the chunk
+                self.pending = input[bad..].to_vec();
+                break bad;
+            };
+            self.stats.errors += 1;
+            self.stats.invalid_bytes += len as u64;
+            let offset = base + bad as u64;
+            match runs.last_mut() {
+                Some(run) if run.offset + run.length as u64 == offset => {
+                    run.length += len;
+                    let room = MAX_UTF8_ERROR_RUN_LENGTH.saturating_sub(run.bytes.len());
+                    run.bytes
+                        .extend_from_slice(&input[bad..bad + len.min(room)]);
+                }
+                _ => runs.push(Utf8DecodeError {
+                    offset,
+                    length: len,
+                    bytes: input[bad..bad + len.min(MAX_UTF8_ERROR_RUN_LENGTH)].to_vec(),
+                }),
+            }
+            out.extend_from_slice(&input[copied..bad]);
+            out.extend_from_slice(REPLACEMENT);
+            pos = bad + len;
+            copied = pos;
+        };
+
+        if runs.is_empty() && end == input.len() {
+            return (input, runs);
+        }
+        out.extend_from_slice(&input[copied..end]);
+        self.stats.runs += runs.len() as u64;
+        for run in &runs {
+            if self.recent.len() == MAX_UTF8_ERROR_RUNS {
+                self.recent.pop_front();
+            }
+            self.recent.push_back(run.clone());
+        }
+        (Cow::Owned(out), runs)
+    }
+}
+
+impl Terminal {
+    /// Validate PTY output as UTF-8 before parsing (off by default).
+    ///
+    /// While enabled, each invalid sequence is replaced with one U+FFFD
+    /// (including lone `0x80..=0x9F` bytes, which the parser would otherwise
+    /// run as 8-bit C1 controls), counted in [`Terminal::utf8_decode_stats`],
+    /// kept in [`Terminal::utf8_decode_errors`] and reported as a
+    /// [`TerminalEvent::Utf8DecodeError`]. Disabling flushes a held-back
+    /// incomplete sequence to the parser.
+    ///
+    /// ```
+    /// use par_term_emu_core_rust::terminal::Terminal;
+    ///
+    /// let mut term = Terminal::new(80, 24);
+    /// term.set_utf8_resilience(true);
+    /// term.process(b"caf\xe9!");
+    /// assert_eq!(term.export_text().trim_end(), "caf\u{FFFD}!");
+    /// assert_eq!(term.utf8_decode_errors()[0].offset, 3);
+    /// ```
+    pub fn set_utf8_resilience(&mut self, enabled: bool) {
+        if self.utf8_guard.enabled && !enabled {
+            let pending = self.utf8_guard.take_pending();
+            self.advance_parser(&pending);
+        }
+        self.utf8_guard.enabled = enabled;
+    }
+
+    /// Whether UTF-8 resilience mode is enabled
+    pub fn utf8_resilience(&self) -> bool {
+        self.utf8_guard.enabled
+    }
+
+    /// Decode error counters since creation or the last
+    /// [`Terminal::clear_utf8_decode_errors`]
+    pub fn utf8_decode_stats(&self) -> Utf8DecodeStats {
+        self.utf8_guard.stats
+    }
+
+    /// The most recent undecodable byte runs, oldest first
+    pub fn utf8_decode_errors(&self) -> Vec<Utf8DecodeError> {
+        self.utf8_guard.recent.iter().cloned().collect()
+    }
+
+    /// Reset the decode error counters and forget the recorded runs
+    pub fn clear_utf8_decode_errors(&mut self) {
+        self.utf8_guard.stats = Utf8DecodeStats::default();
+        self.utf8_guard.recent.clear();
+    }
+
+    /// Run `data` through the guard when resilience mode is on, queueing an
+    /// event per undecodable run
+    pub(crate) fn sanitize_utf8<'a>(&mut self, data: &'a [u8]) -> Cow<'a, [u8]> {
+        if !self.utf8_guard.enabled {
+            self.utf8_guard.skip(data.len());
+            return Cow::Borrowed(data);
+        }
+        let (data, runs) = self.utf8_guard.sanitize(data);
+        for run in runs {
+            self.events
+                .terminal_events
+                .push(TerminalEvent::Utf8DecodeError {
+                    offset: run.offset,
+                    length: run.length,
+                });
+        }
+        data
+    }
+}
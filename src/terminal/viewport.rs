@@ -0,0 +1,104 @@
+//! Scrollback viewport with output anchoring
+//!
+//! Frontends that draw scrollback keep a scroll offset: how many lines the
+//! view is scrolled back from the live screen. Tracking it here lets the
+//! offset follow the content instead of the bottom of the buffer: while the
+//! user is scrolled back, lines pushed into scrollback grow the offset so the
+//! same text stays in view, and lines evicted above the view shrink it. When
+//! the viewport's own top line is evicted it stays on the oldest remaining
+//! line.
+//!
+//! A viewport at the bottom follows new output while "follow tail" is on (the
+//! default); with it off, the view stays on the lines it shows as they scroll
+//! up. The offset is relative to the primary screen's scrollback, which is
+//! the same convention as [`Terminal::hit_test`] and
+//! [`Terminal::screenshot`].
+
+use crate::terminal::Terminal;
+
+/// Viewport position and follow-tail setting
+#[derive(Debug)]
+pub(crate) struct ViewportState {
+    /// Absolute row (`scrollback_len + screen row`) of the top visible line
+    /// when the view is not following the tail
+    anchor: Option<usize>,
+    follow_tail: bool,
+}
+
+impl Default for ViewportState {
+    fn default() -> Self {
+        Self {
+            anchor: None,
+            follow_tail: true,
+        }
+    }
+}
+
+impl Terminal {
+    /// Lines the viewport is scrolled back from the live screen (0 = bottom)
+    pub fn viewport_offset(&self) -> usize {
+        let scrollback_len = self.grid.scrollback_len();
+        self.viewport
+            .anchor
+            .map_or(0, |top| scrollback_len.saturating_sub(top))
+    }
+
+    /// Scroll the viewport to `offset` lines back from the live screen,
+    /// clamped to the scrollback length
+    pub fn set_viewport_offset(&mut self, offset: usize) {
+        let scrollback_len = self.grid.scrollback_len();
+        let offset = offset.min(scrollback_len);
+        self.viewport.anchor = if offset == 0 && self.viewport.follow_tail {
+            None
+        } else {
+            Some(scrollback_len - offset)
+        };
+    }
+
+    /// Scroll the viewport `lines` further back into scrollback
+    pub fn scroll_viewport_up(&mut self, lines: usize) {
+        self.set_viewport_offset(self.viewport_offset().saturating_add(lines));
+    }
+
+    /// Scroll the viewport `lines` toward the live screen
+    pub fn scroll_viewport_down(&mut self, lines: usize) {
+        self.set_viewport_offset(self.viewport_offset().saturating_sub(lines));
+    }
+
+    /// Scroll the viewport back to the live screen
+    pub fn scroll_viewport_to_bottom(&mut self) {
+        self.set_viewport_offset(0);
+    }
+
+    /// Whether a viewport at the bottom follows new output
+    pub fn is_following_tail(&self) -> bool {
+        self.viewport.follow_tail
+    }
+
+    /// Follow new output when the viewport is at the bottom (default), or
+    /// keep the view on the lines it shows as output arrives
+    pub fn set_follow_tail(&mut self, follow: bool) {
+        self.viewport.follow_tail = follow;
+        if follow && self.viewport_offset() == 0 {
+            self.viewport.anchor = None;
+        } else if !follow && self.viewport.anchor.is_none() {
+            self.viewport.anchor = Some(self.grid.scrollback_len());
+        }
+    }
+
+    /// Move the viewport anchor up for rows that left the primary grid
+    pub(in crate::terminal) fn shift_viewport(&mut self, removals: &[(usize, usize)]) {
+        let Some(top) = self.viewport.anchor.as_mut() else {
+            return;
+        };
+        for &(abs_row, count) in removals {
+            if *top >= abs_row {
+                *top = top.saturating_sub(count).max(abs_row);
+            }
+        }
+        // Nothing left to hold on to, e.g. after ED 3 cleared scrollback
+        if self.viewport.follow_tail && self.viewport_offset() == 0 {
+            self.viewport.anchor = None;
+        }
+    }
+}
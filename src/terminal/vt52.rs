@@ -0,0 +1,234 @@
+//! VT52 compatibility mode
+//!
+//! Resetting DECANM (`CSI ? 2 l`) switches the terminal into VT52 mode, where
+//! the host speaks the VT52's escape language instead of ANSI: there is no
+//! CSI, OSC or DCS, and every sequence is `ESC` plus one final byte, except
+//! direct cursor addressing (`ESC Y` row column). While the mode is active
+//! [`Terminal::advance_vt52`] owns the stream, handing runs of text to the
+//! vte parser and decoding the escapes itself. `ESC <` returns to ANSI mode.
+//! Cursor and PF keys are encoded the VT52 way (`ESC A`, `ESC P`, ...) while
+//! the mode is active.
+
+use crate::kitty_keyboard::{Key, KeyEvent, KeyEventType, KeyModifiers};
+use crate::terminal::{Charset, CursorMoveCause, Terminal};
+
+/// Reply to VT52 identify (`ESC Z`): "VT100 in VT52 mode"
+const VT52_IDENTIFY_REPLY: &[u8] = b"\x1b/Z";
+
+/// Where the VT52 decoder is within an escape sequence
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Vt52Parse {
+    /// Text and C0 controls
+    #[default]
+    Ground,
+    /// After `ESC`
+    Escape,
+    /// After `ESC Y`, waiting for the row byte
+    CursorRow,
+    /// After `ESC Y` and the row byte, waiting for the column byte
+    CursorColumn(u8),
+}
+
+/// VT52 mode flag and decoder state
+#[derive(Debug, Default)]
+pub(crate) struct Vt52State {
+    /// DECANM is reset: the host is speaking VT52
+    pub(crate) active: bool,
+    parse: Vt52Parse,
+    /// G0 designation when VT52 mode was entered, restored by `ESC <`
+    saved_g0: Charset,
+}
+
+/// VT52 code for an unmodified cursor or PF1-PF4 key, if `event` is one
+pub(crate) fn encode_vt52_key(event: &KeyEvent) -> Option<Vec<u8>> {
+    if event.event_type == KeyEventType::Release
+        || !(event.modifiers - KeyModifiers::LOCKS).is_empty()
+    {
+        return None;
+    }
+    let code = match event.key {
+        Key::Up => b'A',
+        Key::Down => b'B',
+        Key::Right => b'C',
+        Key::Left => b'D',
+        Key::F(1) => b'P',
+        Key::F(2) => b'Q',
+        Key::F(3) => b'R',
+        Key::F(4) => b'S',
+        _ => return None,
+    };
+    Some(vec![0x1b, code])
+}
+
+impl Terminal {
+    /// Whether the terminal is in VT52 compatibility mode (DECANM reset)
+    pub fn vt52_mode(&self) -> bool {
+        self.vt52.active
+    }
+
+    /// Enter VT52 mode (DECRST 2)
+    pub(crate) fn enter_vt52_mode(&mut self) {
+        if self.vt52.active {
+            return;
+        }
+        self.vt52.active = true;
+        self.vt52.parse = Vt52Parse::Ground;
+        self.vt52.saved_g0 = self.charset_state.g0_charset;
+    }
+
+    /// Leave VT52 mode (`ESC <`)
+    fn exit_vt52_mode(&mut self) {
+        self.vt52.active = false;
+        self.vt52.parse = Vt52Parse::Ground;
+        self.charset_state.g0_charset = self.vt52.saved_g0;
+    }
+
+    /// Decode host output as VT52 until `ESC <`; returns how many bytes of
+    /// `data` were consumed (all of them unless ANSI mode was re-entered)
+    pub(in crate::terminal) fn advance_vt52(
+        &mut self,
+        parser: &mut vte::Parser,
+        data: &[u8],
+    ) -> usize {
+        let mut i = 0;
+        while i < data.len() {
+            if !self.vt52.active {
+                return i;
+            }
+            match self.vt52.parse {
+                Vt52Parse::Ground => {
+                    // Text and controls up to the next ESC go through vte,
+                    // which stays in its ground state as it never sees ESC
+                    let end = data[i..]
+                        .iter()
+                        .position(|&b| b == 0x1b)
+                        .map_or(data.len(), |p| i + p);
+                    parser.advance(self, &data[i..end]);
+                    self.flush_print_run();
+                    if end < data.len() {
+                        self.vt52.parse = Vt52Parse::Escape;
+                        i = end + 1;
+                    } else {
+                        i = end;
+                    }
+                }
+                state => {
+                    let byte = data[i];
+                    i += 1;
+                    self.vt52_byte(state, byte);
+                }
+            }
+        }
+        data.len()
+    }
+
+    /// Handle one byte inside a VT52 escape sequence
+    fn vt52_byte(&mut self, state: Vt52Parse, byte: u8) {
+        match byte {
+            // CAN and SUB abort the sequence, ESC starts a new one
+            0x18 | 0x1a => {
+                self.vt52.parse = Vt52Parse::Ground;
+                return;
+            }
+            0x1b => {
+                self.vt52.parse = Vt52Parse::Escape;
+                return;
+            }
+            // Other controls act immediately without ending the sequence
+            0x00..=0x1f | 0x7f => {
+                use vte::Perform;
+                self.execute(byte);
+                return;
+            }
+            _ => {}
+        }
+        self.vt52.parse = Vt52Parse::Ground;
+        // Attribute pending text motion before the sequence moves the cursor
+        self.note_cursor_motion(CursorMoveCause::Text);
+        match state {
+            Vt52Parse::Escape => self.vt52_escape(byte),
+            Vt52Parse::CursorRow => self.vt52.parse = Vt52Parse::CursorColumn(byte),
+            Vt52Parse::CursorColumn(row) => {
+                // Coordinates are offset by 32 (space is row/column 0);
+                // out-of-range values clamp to the last row or column
+                let (cols, rows) = self.size();
+                let row = usize::from(row - 0x20).min(rows.saturating_sub(1));
+                let col = usize::from(byte - 0x20).min(cols.saturating_sub(1));
+                self.pending_wrap = false;
+                self.cursor.goto(col, row);
+            }
+            Vt52Parse::Ground => {}
+        }
+        self.note_cursor_motion(CursorMoveCause::EscapeSequence);
+    }
+
+    /// Dispatch the final byte of a VT52 `ESC` sequence
+    fn vt52_escape(&mut self, byte: u8) {
+        let (cols, rows) = self.size();
+        match byte {
+            b'A' => {
+                // Cursor up, stopping at the top line
+                self.cursor.move_up(1);
+                self.pending_wrap = false;
+            }
+            b'B' => {
+                // Cursor down, stopping at the bottom line
+                self.cursor.move_down(1, rows.saturating_sub(1));
+                self.pending_wrap = false;
+            }
+            b'C' => {
+                // Cursor right, stopping at the right margin
+                self.cursor.move_right(1, cols.saturating_sub(1));
+                self.pending_wrap = false;
+            }
+            b'D' => {
+                // Cursor left, stopping at the left margin
+                self.cursor.move_left(1);
+                self.pending_wrap = false;
+            }
+            b'F' => {
+                // Enter graphics mode: DEC Special Graphics in G0
+                self.charset_state.g0_charset = Charset::DecLineDrawing;
+            }
+            b'G' => {
+                // Exit graphics mode
+                self.charset_state.g0_charset = Charset::Ascii;
+            }
+            b'H' => {
+                // Cursor home
+                self.pending_wrap = false;
+                self.cursor.goto(0, 0);
+            }
+            b'I' => {
+                // Reverse line feed: same as RI, scrolling at the top
+                self.esc_dispatch_impl(&[], false, b'M');
+            }
+            b'J' => {
+                // Erase from the cursor to the end of the screen
+                let (col, row, bg) = (self.cursor.col, self.cursor.row, self.bg);
+                self.active_grid_mut().clear_screen_below(col, row, bg);
+            }
+            b'K' => {
+                // Erase from the cursor to the end of the line
+                let (col, row, bg) = (self.cursor.col, self.cursor.row, self.bg);
+                self.active_grid_mut().clear_line_right(col, row, bg);
+            }
+            b'Y' => {
+                // Direct cursor address: row and column bytes follow
+                self.vt52.parse = Vt52Parse::CursorRow;
+            }
+            b'Z' => {
+                // Identify
+                self.push_response(VT52_IDENTIFY_REPLY);
+            }
+            b'<' => {
+                // Enter ANSI mode (DECANM set)
+                self.exit_vt52_mode();
+            }
+            _ => {
+                // Keypad modes (ESC = / ESC >) and the VT52 printer
+                // sequences are accepted and ignored
+            }
+        }
+    }
+}
@@ -8,24 +8,21 @@
 //! - Insert mode
 //! - Character attributes and hyperlinks
 //! - Grapheme clusters (variation selectors, ZWJ, skin tone modifiers, regional indicators)
+//! - Repeating the preceding character (REP)
 
 use crate::cell::Cell;
 use crate::debug;
 use crate::grapheme;
 use crate::terminal::Terminal;
 use smallvec::SmallVec;
+use vte::Perform;
 
 impl Terminal {
     /// Write a character to the terminal at the current cursor position
     pub(super) fn write_char(&mut self, c: char) {
-        // Apply ACS (Alternate Character Set) translation when the active charset
-        // is DEC Special / Line Drawing.  Only printable ASCII chars are mapped;
-        // control characters pass through unchanged so CR/LF/etc. still work.
-        let c = if c.is_ascii_graphic() {
-            self.active_charset().translate(c)
-        } else {
-            c
-        };
+        // Apply the charsets invoked into GL/GR (DEC Line Drawing, NRCS).
+        // Control characters pass through unchanged so CR/LF/etc. still work.
+        let c = self.map_charset(c);
 
         let (cols, _rows) = self.size();
 
@@ -112,181 +109,27 @@ impl Terminal {
             return self.write_regional_indicator_first(c, cols);
         }
 
-        // Handle combining characters (variation selectors, ZWJ, skin tone modifiers)
-        // These should be added to the previous cell instead of creating a new cell
-        if grapheme::is_variation_selector(c)
-            || grapheme::is_zwj(c)
-            || grapheme::is_skin_tone_modifier(c)
-            || grapheme::is_combining_mark(c)
-        {
-            // Find the previous actual character cell (skip over wide char spacers)
-            let (prev_col, prev_row) = if self.cursor.col > 0 {
-                (self.cursor.col - 1, self.cursor.row)
-            } else if self.cursor.row > 0 {
-                (cols - 1, self.cursor.row - 1)
-            } else {
-                // At position (0, 0), nowhere to add combining char
-                return;
-            };
-
-            // Check if the previous cell is a wide char spacer
-            // If so, the actual wide char is one more cell to the left
-            let (target_col, target_row) =
-                if let Some(cell) = self.active_grid().get(prev_col, prev_row) {
-                    if cell.flags.wide_char_spacer() {
-                        // Previous cell is a spacer, go back one more cell for the wide char
-                        if prev_col > 0 {
-                            (prev_col - 1, prev_row)
-                        } else if prev_row > 0 {
-                            (cols - 1, prev_row - 1)
-                        } else {
-                            // Spacer at start of grid, nowhere to go
-                            return;
-                        }
-                    } else {
-                        (prev_col, prev_row)
-                    }
-                } else {
-                    return;
-                };
-
-            // Copy normalization form before mutable borrow
-            let norm_form = self.unicode_state.normalization_form;
-
-            // Add combining character to the target cell
-            if let Some(target_cell) = self.active_grid_mut().get_mut(target_col, target_row) {
-                target_cell.combining.push(c);
-
-                // Fast path: Kitty TGP placeholder cells (base char U+10EEEE)
-                // encode an image ID via combining marks, not real text. Skip
-                // NFC/NFKC normalization and grapheme width recalc — both
-                // would allocate strings per combining mark and dominate the
-                // cost of rendering an N×M placeholder rectangle (e.g., 40×20
-                // = 800 cells × 3 diacritics = 2400 normalize calls).
-                if target_cell.c == crate::graphics::placeholder::PLACEHOLDER_CHAR {
-                    self.mark_row_dirty(target_row);
-                    return;
-                }
-
-                // Apply Unicode normalization if NFC or NFKC (composition forms)
-                // This composes base + combining into precomposed form when possible
-                if matches!(
-                    norm_form,
-                    crate::unicode_normalization_config::NormalizationForm::NFC
-                        | crate::unicode_normalization_config::NormalizationForm::NFKC
-                ) {
-                    let grapheme = target_cell.get_grapheme();
-                    let normalized = norm_form.normalize(&grapheme);
-                    let mut chars = normalized.chars();
-                    if let Some(base) = chars.next() {
-                        target_cell.c = base;
-                        target_cell.combining = chars.collect();
-                    }
-                }
-
-                // Recalculate width if needed (e.g., emoji with variation selector)
-                let grapheme = target_cell.get_grapheme();
-                let new_width = grapheme::is_wide_grapheme(&grapheme);
-                if new_width && target_cell.width() == 1 {
-                    target_cell.width = 2;
-                    target_cell.flags.set_wide_char(true);
-
-                    if target_col + 1 < cols {
-                        let mut spacer_flags = target_cell.flags;
-                        spacer_flags.set_wide_char(false);
-                        spacer_flags.set_wide_char_spacer(true);
-
-                        let spacer = Cell {
-                            c: ' ',
-                            combining: SmallVec::new(),
-                            fg: target_cell.fg,
-                            bg: target_cell.bg,
-                            underline_color: target_cell.underline_color,
-                            flags: spacer_flags,
-                            width: 1,
-                        };
-                        self.active_grid_mut()
-                            .set(target_col + 1, target_row, spacer);
+        // Code points that continue the previous cell's extended grapheme
+        // cluster (UAX #29) are appended to that cell instead of taking a new
+        // one: combining and spacing marks, variation selectors, emoji
+        // modifiers and tags, the emoji after a ZWJ, Hangul jamo, ... ASCII
+        // never continues a cluster, so it skips the segmentation check.
+        if !c.is_ascii() {
+            match self.cluster_anchor(cols) {
+                Some((target_col, target_row)) => {
+                    if self.extends_cluster_at(target_col, target_row, c) {
+                        self.append_to_cluster(target_col, target_row, c, cols);
+                        return;
                     }
                 }
-
-                self.mark_row_dirty(target_row);
-            }
-            return;
-        }
-
-        // Check if previous cell has ZWJ - if so, this char is part of ZWJ sequence
-        // and should be added as combining character (e.g., 👨 + ZWJ + 💻 = 👨‍💻)
-        // OPTIMIZATION: Only check for emoji characters (most text won't trigger this)
-        let char_code = c as u32;
-        let is_potential_emoji = matches!(char_code,
-            0x2600..=0x27BF  // Misc Symbols (❤️, ☀️, etc.)
-            | 0x1F000..=0x1FFFF // Emoji blocks
-        );
-
-        if is_potential_emoji && (self.cursor.col > 0 || self.cursor.row > 0) {
-            let (prev_col, prev_row) = if self.cursor.col > 0 {
-                (self.cursor.col - 1, self.cursor.row)
-            } else {
-                (cols - 1, self.cursor.row - 1)
-            };
-
-            // Skip spacers to find actual wide char
-            let (target_col, target_row) =
-                if let Some(cell) = self.active_grid().get(prev_col, prev_row) {
-                    if cell.flags.wide_char_spacer() {
-                        if prev_col > 0 {
-                            (prev_col - 1, prev_row)
-                        } else if prev_row > 0 {
-                            (cols - 1, prev_row - 1)
-                        } else {
-                            (prev_col, prev_row) // Fallback
-                        }
-                    } else {
-                        (prev_col, prev_row)
-                    }
-                } else {
-                    (prev_col, prev_row)
-                };
-
-            // Check if target cell has ZWJ in combining chars
-            if let Some(target_cell) = self.active_grid().get(target_col, target_row) {
-                if target_cell.combining.contains(&'\u{200D}') {
-                    // Previous cell has ZWJ, add current char as combining
-                    if let Some(target_cell_mut) =
-                        self.active_grid_mut().get_mut(target_col, target_row)
+                None => {
+                    // Nothing to attach a zero-width extender to
+                    if grapheme::is_variation_selector(c)
+                        || grapheme::is_zwj(c)
+                        || grapheme::is_combining_mark(c)
                     {
-                        target_cell_mut.combining.push(c);
-
-                        // Recalculate width if needed
-                        let grapheme = target_cell_mut.get_grapheme();
-                        let new_width = grapheme::is_wide_grapheme(&grapheme);
-                        if new_width && target_cell_mut.width() == 1 {
-                            target_cell_mut.width = 2;
-                            target_cell_mut.flags.set_wide_char(true);
-
-                            if target_col + 1 < cols {
-                                let mut spacer_flags = target_cell_mut.flags;
-                                spacer_flags.set_wide_char(false);
-                                spacer_flags.set_wide_char_spacer(true);
-
-                                let spacer = Cell {
-                                    c: ' ',
-                                    combining: SmallVec::new(),
-                                    fg: target_cell_mut.fg,
-                                    bg: target_cell_mut.bg,
-                                    underline_color: target_cell_mut.underline_color,
-                                    flags: spacer_flags,
-                                    width: 1,
-                                };
-                                self.active_grid_mut()
-                                    .set(target_col + 1, target_row, spacer);
-                            }
-                        }
-
-                        self.mark_row_dirty(target_row);
+                        return;
                     }
-                    return;
                 }
             }
         }
@@ -295,11 +138,7 @@ impl Terminal {
         match c {
             '\r' => {
                 // Carriage return moves to left margin when DECLRMM is enabled
-                if self.margins.use_lr_margins {
-                    self.cursor.col = self.margins.left_margin.min(cols.saturating_sub(1));
-                } else {
-                    self.cursor.move_to_line_start();
-                }
+                self.cursor.col = self.line_start_col();
                 // CR clears pending wrap
                 self.pending_wrap = false;
                 return;
@@ -308,11 +147,7 @@ impl Terminal {
                 // LNM (Line Feed/New Line Mode): when enabled, LF does CR+LF
                 if self.modes.line_feed_new_line_mode {
                     // Do carriage return first
-                    if self.margins.use_lr_margins {
-                        self.cursor.col = self.margins.left_margin.min(cols.saturating_sub(1));
-                    } else {
-                        self.cursor.move_to_line_start();
-                    }
+                    self.cursor.col = self.line_start_col();
                 }
                 // VT spec behavior: Line feed moves cursor down. If at bottom of scroll region, scroll the region.
                 // Per VT220 manual: "Index (IND) moves the cursor down one line in the same column.
@@ -322,9 +157,7 @@ impl Terminal {
                     && self.cursor.row <= self.margins.scroll_region_bottom;
                 // If DECLRMM is enabled and the cursor is outside left/right margins,
                 // ignore the scroll (match iTerm2 behavior) to avoid corrupting panes/status bars.
-                let outside_lr_margin = self.margins.use_lr_margins
-                    && (self.cursor.col < self.margins.left_margin
-                        || self.cursor.col > self.margins.right_margin);
+                let outside_lr_margin = !self.margins.contains_col(self.cursor.col);
 
                 if in_scroll_region
                     && self.cursor.row == self.margins.scroll_region_bottom
@@ -335,9 +168,7 @@ impl Terminal {
                     let top = self.margins.scroll_region_top;
                     let bottom = self.margins.scroll_region_bottom;
                     debug::log_scroll("newline-at-scroll-bottom", top, bottom, 1);
-                    self.active_grid_mut().scroll_region_up(1, top, bottom);
-                    // Adjust graphics to scroll with content
-                    self.adjust_graphics_for_scroll_up(1, top, bottom);
+                    self.scroll_up_in_margins(1, top, bottom);
                     // Mark all rows in scroll region as dirty
                     for row in top..=bottom {
                         self.mark_row_dirty(row);
@@ -355,22 +186,25 @@ impl Terminal {
                 return;
             }
             '\t' => {
-                // Tab to next tab stop
+                // Tab to next tab stop, stopping at the right margin
+                let edge = self.wrap_edge();
                 let mut next_col = self.cursor.col + 1;
-                while next_col < cols {
+                while next_col < edge {
                     if self.tab_stops.get(next_col).copied().unwrap_or(false) {
                         break;
                     }
                     next_col += 1;
                 }
-                self.cursor.col = next_col.min(cols - 1);
+                self.cursor.col = next_col.min(edge - 1);
                 // Horizontal cursor movement clears pending wrap
                 self.pending_wrap = false;
                 return;
             }
             '\x08' => {
-                // Backspace
-                if self.cursor.col > 0 {
+                // Backspace, stopping at the left margin
+                if self.cursor.col > 0
+                    && !(self.margins.use_lr_margins && self.cursor.col == self.margins.left_margin)
+                {
                     self.cursor.col -= 1;
                 }
                 // Horizontal movement clears pending wrap
@@ -391,9 +225,7 @@ impl Terminal {
         // If a wrap is pending from a prior write at the right margin, perform the wrap now
         if self.pending_wrap {
             let (cols, rows) = self.size();
-            let was_outside_lr = self.margins.use_lr_margins
-                && (self.cursor.col < self.margins.left_margin
-                    || self.cursor.col > self.margins.right_margin);
+            let was_outside_lr = !self.margins.contains_col(self.cursor.col);
 
             // Mark the current row as wrapped (line continues to next row)
             let current_row = self.cursor.row;
@@ -410,10 +242,7 @@ impl Terminal {
                 let scroll_top = self.margins.scroll_region_top;
                 let scroll_bottom = self.margins.scroll_region_bottom;
                 debug::log_scroll("wrap-pending-advance", scroll_top, scroll_bottom, 1);
-                self.active_grid_mut()
-                    .scroll_region_up(1, scroll_top, scroll_bottom);
-                // Adjust graphics to scroll with content
-                self.adjust_graphics_for_scroll_up(1, scroll_top, scroll_bottom);
+                self.scroll_up_in_margins(1, scroll_top, scroll_bottom);
                 // Cursor remains at bottom of region
             } else {
                 self.cursor.row += 1;
@@ -424,31 +253,31 @@ impl Terminal {
             self.pending_wrap = false;
         }
 
+        // Text wraps at the right margin when the cursor is inside the
+        // margins, otherwise at the screen edge
+        let mut edge = self.wrap_edge();
+
         // If wide character won't fit on current line, wrap first
-        if char_width == 2 && self.cursor.col >= cols - 1 && self.modes.auto_wrap {
+        if char_width == 2 && self.cursor.col + 1 >= edge && self.modes.auto_wrap {
             // Mark the current row as wrapped (line continues to next row)
             let current_row = self.cursor.row;
             self.active_grid_mut().set_line_wrapped(current_row, true);
             self.mark_row_dirty(current_row);
 
+            // VT spec behavior: scroll if at scroll region bottom
+            let (_, rows) = self.size();
+            let outside_lr_margin = !self.margins.contains_col(self.cursor.col);
             // Wrap to left margin if DECLRMM is enabled
             self.cursor.col = if self.margins.use_lr_margins {
                 self.margins.left_margin.min(cols.saturating_sub(1))
             } else {
                 0
             };
-            // VT spec behavior: scroll if at scroll region bottom
-            let (_, rows) = self.size();
-            let outside_lr_margin = self.margins.use_lr_margins
-                && (self.cursor.col < self.margins.left_margin
-                    || self.cursor.col > self.margins.right_margin);
+            edge = self.wrap_edge();
             if self.cursor.row == self.margins.scroll_region_bottom && !outside_lr_margin {
                 let scroll_top = self.margins.scroll_region_top;
                 let scroll_bottom = self.margins.scroll_region_bottom;
-                self.active_grid_mut()
-                    .scroll_region_up(1, scroll_top, scroll_bottom);
-                // Adjust graphics to scroll with content
-                self.adjust_graphics_for_scroll_up(1, scroll_top, scroll_bottom);
+                self.scroll_up_in_margins(1, scroll_top, scroll_bottom);
                 // Cursor stays at scroll_region_bottom
             } else {
                 self.cursor.row += 1;
@@ -481,15 +310,23 @@ impl Terminal {
         let cursor_col = self.cursor.col;
         let cursor_row = self.cursor.row;
 
-        // If insert mode (IRM) is enabled, insert space by shifting chars right
+        // If insert mode (IRM) is enabled, insert space by shifting chars
+        // right, up to the right margin
         if self.modes.insert_mode {
-            self.active_grid_mut()
-                .insert_chars(cursor_col, cursor_row, char_width);
+            self.active_grid_mut().insert_columns(
+                cursor_col,
+                char_width,
+                cursor_row,
+                cursor_row,
+                edge - 1,
+            );
         }
 
         self.active_grid_mut().set(cursor_col, cursor_row, cell);
+        self.overwrite_graphics_with_text(cursor_col, cursor_row, char_width);
         // Mark row as dirty for rendering
         self.mark_row_dirty(cursor_row);
+        self.note_row_written(cursor_row);
 
         // Advance cursor by character width
         self.cursor.col += char_width;
@@ -517,16 +354,223 @@ impl Terminal {
         }
 
         // Handle delayed autowrap for width-1 characters
-        if self.modes.auto_wrap && char_width == 1 && self.cursor.col >= cols {
+        if self.modes.auto_wrap && char_width == 1 && self.cursor.col >= edge {
             // Stay at last column and set wrap-pending; do not move yet
-            self.cursor.col = cols - 1;
+            self.cursor.col = edge - 1;
             self.pending_wrap = true;
-        } else if self.cursor.col >= cols {
+        } else if self.cursor.col >= edge {
             // Fallback: if auto-wrap is disabled or some edge case, clamp to last column
-            self.cursor.col = cols - 1;
+            self.cursor.col = edge - 1;
         }
     }
 
+    /// Write the buffered run of printable ASCII to the grid
+    ///
+    /// Equivalent to calling `write_char` for each byte, but each row segment
+    /// is written into the row slice in one pass and marked dirty once.
+    /// Pending wraps and insert mode fall back to `write_char`.
+    pub(super) fn flush_print_run(&mut self) {
+        if self.print_run.is_empty() {
+            return;
+        }
+        let mut run = std::mem::take(&mut self.print_run);
+        self.last_graphic_char = run.last().map(|&byte| byte as char);
+        if let Some(buffer) = self.expect_buffer.as_mut() {
+            buffer.push_ascii(&run);
+        }
+
+        if debug::is_enabled(debug::DebugLevel::Trace) {
+            for &byte in &run {
+                debug::log_print(byte as char, self.cursor.col, self.cursor.row);
+                self.write_char(byte as char);
+            }
+        } else {
+            self.write_ascii_run(&run);
+        }
+
+        run.clear();
+        self.print_run = run;
+    }
+
+    /// Print the last graphic character `n` more times (REP)
+    ///
+    /// Does nothing if no character has been printed since the last C0
+    /// control, or if the last one was zero-width (e.g. a combining mark).
+    /// The repeats go through the normal print path, so they wrap, use the
+    /// current charset and attributes, and can be repeated again.
+    pub(super) fn repeat_last_char(&mut self, n: usize) {
+        let Some(c) = self.last_graphic_char else {
+            return;
+        };
+        for _ in 0..n {
+            Perform::print(self, c);
+        }
+    }
+
+    fn write_ascii_run(&mut self, mut rest: &[u8]) {
+        let mut flags = self.flags;
+        flags.hyperlink_id = self.hyperlink_state.current_hyperlink_id;
+        flags.set_guarded(self.modes.char_protected);
+        let (fg, bg, underline_color) = (self.fg, self.bg, self.underline_color);
+
+        while let Some((&first, tail)) = rest.split_first() {
+            let (col, row) = (self.cursor.col, self.cursor.row);
+            let edge = self.wrap_edge();
+            if self.pending_wrap || self.modes.insert_mode || col >= edge {
+                self.write_char(first as char);
+                rest = tail;
+                continue;
+            }
+
+            let count = rest.len().min(edge - col);
+            if let Some(cells) = self.active_grid_mut().row_mut(row) {
+                for (cell, &byte) in cells[col..col + count].iter_mut().zip(&rest[..count]) {
+                    *cell = Cell {
+                        c: byte as char,
+                        combining: SmallVec::new(),
+                        fg,
+                        bg,
+                        underline_color,
+                        flags,
+                        width: 1,
+                    };
+                }
+            }
+            self.overwrite_graphics_with_text(col, row, count);
+            self.mark_row_dirty(row);
+            self.note_row_written(row);
+            rest = &rest[count..];
+
+            // Same delayed autowrap as `write_char` for width-1 characters
+            self.cursor.col += count;
+            if self.cursor.col >= edge {
+                self.cursor.col = edge - 1;
+                if self.modes.auto_wrap {
+                    self.pending_wrap = true;
+                }
+            }
+        }
+    }
+
+    /// Locate the cell holding the grapheme cluster written just before the
+    /// cursor, skipping a wide-character spacer. Returns `None` at the start
+    /// of a line that does not continue a wrapped one.
+    fn cluster_anchor(&self, cols: usize) -> Option<(usize, usize)> {
+        let (col, row) = (self.cursor.col, self.cursor.row);
+        let (prev_col, prev_row) = if self.pending_wrap {
+            // The last character was written at the cursor itself
+            (col, row)
+        } else if col > 0 {
+            (col - 1, row)
+        } else if row > 0 && self.active_grid().is_line_wrapped(row - 1) {
+            (cols - 1, row - 1)
+        } else {
+            return None;
+        };
+
+        let cell = self.active_grid().get(prev_col, prev_row)?;
+        if cell.flags.wide_char_spacer() {
+            prev_col.checked_sub(1).map(|c| (c, prev_row))
+        } else {
+            Some((prev_col, prev_row))
+        }
+    }
+
+    /// Whether `c` continues the grapheme cluster stored at (col, row)
+    fn extends_cluster_at(&self, col: usize, row: usize, c: char) -> bool {
+        let Some(cell) = self.active_grid().get(col, row) else {
+            return false;
+        };
+        // Fast path: Kitty TGP placeholder cells (base char U+10EEEE) take
+        // their row/column diacritics without running segmentation for each
+        // of the N×M placeholder cells.
+        if cell.c == crate::graphics::placeholder::PLACEHOLDER_CHAR {
+            return grapheme::is_combining_mark(c);
+        }
+        // Mode 2027 off: legacy wcwidth behavior, where only zero-width
+        // code points attach to the previous cell
+        if !self.grapheme_cluster_mode() {
+            return crate::unicode_width_config::char_width(c, &self.unicode_state.width_config)
+                == 0;
+        }
+        grapheme::extends_cluster(
+            std::iter::once(cell.c).chain(cell.combining.iter().copied()),
+            c,
+        )
+    }
+
+    /// Append `c` to the grapheme cluster at (target_col, target_row),
+    /// re-normalizing and widening the cell if the cluster now needs two
+    /// columns.
+    fn append_to_cluster(&mut self, target_col: usize, target_row: usize, c: char, cols: usize) {
+        // Copy configuration before mutable borrow
+        let norm_form = self.unicode_state.normalization_form;
+        let width_config = self.unicode_state.width_config;
+        let cluster_widths = self.grapheme_cluster_mode();
+
+        let Some(target_cell) = self.active_grid_mut().get_mut(target_col, target_row) else {
+            return;
+        };
+        target_cell.combining.push(c);
+
+        // Fast path: Kitty TGP placeholder cells encode an image ID via
+        // combining marks, not real text. Skip NFC/NFKC normalization and
+        // width recalc — both would allocate strings per combining mark and
+        // dominate the cost of rendering an N×M placeholder rectangle (e.g.,
+        // 40×20 = 800 cells × 3 diacritics = 2400 normalize calls).
+        if target_cell.c == crate::graphics::placeholder::PLACEHOLDER_CHAR {
+            self.mark_row_dirty(target_row);
+            return;
+        }
+
+        // Apply Unicode normalization if NFC or NFKC (composition forms)
+        // This composes base + combining into precomposed form when possible
+        if matches!(
+            norm_form,
+            crate::unicode_normalization_config::NormalizationForm::NFC
+                | crate::unicode_normalization_config::NormalizationForm::NFKC
+        ) {
+            let grapheme = target_cell.get_grapheme();
+            let normalized = norm_form.normalize(&grapheme);
+            let mut chars = normalized.chars();
+            if let Some(base) = chars.next() {
+                target_cell.c = base;
+                target_cell.combining = chars.collect();
+            }
+        }
+
+        // Width is decided per cluster (e.g., emoji with variation selector).
+        // The cursor has already advanced, so a cell never narrows here.
+        // Without mode 2027 the base character alone sets the width.
+        let grapheme = target_cell.get_grapheme();
+        let wide = cluster_widths
+            && crate::unicode_width_config::grapheme_width(&grapheme, &width_config) >= 2;
+        if wide && target_cell.width() == 1 {
+            target_cell.width = 2;
+            target_cell.flags.set_wide_char(true);
+
+            if target_col + 1 < cols {
+                let mut spacer_flags = target_cell.flags;
+                spacer_flags.set_wide_char(false);
+                spacer_flags.set_wide_char_spacer(true);
+
+                let spacer = Cell {
+                    c: ' ',
+                    combining: SmallVec::new(),
+                    fg: target_cell.fg,
+                    bg: target_cell.bg,
+                    underline_color: target_cell.underline_color,
+                    flags: spacer_flags,
+                    width: 1,
+                };
+                self.active_grid_mut()
+                    .set(target_col + 1, target_row, spacer);
+            }
+        }
+
+        self.mark_row_dirty(target_row);
+    }
+
     /// Write the first regional indicator of a potential flag pair.
     /// This is written as a width-1 character initially. If followed by another
     /// regional indicator, they will be combined into a width-2 flag emoji.
@@ -534,9 +578,7 @@ impl Terminal {
         // If a wrap is pending from a prior write at the right margin, perform the wrap now
         if self.pending_wrap {
             let (_cols, rows) = self.size();
-            let was_outside_lr = self.margins.use_lr_margins
-                && (self.cursor.col < self.margins.left_margin
-                    || self.cursor.col > self.margins.right_margin);
+            let was_outside_lr = !self.margins.contains_col(self.cursor.col);
 
             // Mark the current row as wrapped (line continues to next row)
             let current_row = self.cursor.row;
@@ -553,10 +595,7 @@ impl Terminal {
                 let scroll_top = self.margins.scroll_region_top;
                 let scroll_bottom = self.margins.scroll_region_bottom;
                 debug::log_scroll("wrap-pending-regional", scroll_top, scroll_bottom, 1);
-                self.active_grid_mut()
-                    .scroll_region_up(1, scroll_top, scroll_bottom);
-                // Adjust graphics to scroll with content
-                self.adjust_graphics_for_scroll_up(1, scroll_top, scroll_bottom);
+                self.scroll_up_in_margins(1, scroll_top, scroll_bottom);
                 // Cursor remains at bottom of region
             } else {
                 self.cursor.row += 1;
@@ -585,11 +624,13 @@ impl Terminal {
 
         let cursor_col = self.cursor.col;
         let cursor_row = self.cursor.row;
+        let edge = self.wrap_edge();
 
-        // If insert mode (IRM) is enabled, insert space by shifting chars right
+        // If insert mode (IRM) is enabled, insert space by shifting chars
+        // right, up to the right margin
         if self.modes.insert_mode {
             self.active_grid_mut()
-                .insert_chars(cursor_col, cursor_row, 1);
+                .insert_columns(cursor_col, 1, cursor_row, cursor_row, edge - 1);
         }
 
         self.active_grid_mut().set(cursor_col, cursor_row, cell);
@@ -599,11 +640,11 @@ impl Terminal {
         self.cursor.col += 1;
 
         // Handle delayed autowrap
-        if self.modes.auto_wrap && self.cursor.col >= cols {
-            self.cursor.col = cols - 1;
+        if self.modes.auto_wrap && self.cursor.col >= edge {
+            self.cursor.col = edge - 1;
             self.pending_wrap = true;
-        } else if self.cursor.col >= cols {
-            self.cursor.col = cols - 1;
+        } else if self.cursor.col >= edge {
+            self.cursor.col = edge - 1;
         }
     }
 }
@@ -1062,4 +1103,162 @@ mod tests {
         assert_eq!(term.cursor.col, 11);
         assert_eq!(term.cursor.row, 1);
     }
+
+    #[test]
+    fn test_write_char_zwj_emoji_is_one_wide_cluster() {
+        let mut term = create_test_terminal();
+        term.process("\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}x".as_bytes()); // 👨‍👩‍👧 then x
+
+        let cell = term.active_grid().get(0, 0).unwrap();
+        assert_eq!(
+            cell.get_grapheme(),
+            "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}"
+        );
+        assert_eq!(cell.width(), 2);
+        assert!(term
+            .active_grid()
+            .get(1, 0)
+            .unwrap()
+            .flags
+            .wide_char_spacer());
+        assert_eq!(term.active_grid().get(2, 0).unwrap().c, 'x');
+        assert_eq!(term.cursor.col, 3);
+    }
+
+    #[test]
+    fn test_write_char_text_after_zwj_starts_new_cluster() {
+        let mut term = create_test_terminal();
+        term.process("\u{1F468}\u{200D}\u{4E2D}".as_bytes()); // 👨 ZWJ 中
+
+        assert_eq!(
+            term.active_grid().get(0, 0).unwrap().get_grapheme(),
+            "\u{1F468}\u{200D}"
+        );
+        assert_eq!(term.active_grid().get(2, 0).unwrap().c, '\u{4E2D}');
+    }
+
+    #[test]
+    fn test_write_char_combining_accents() {
+        let mut term = create_test_terminal();
+        term.process("e\u{0301}\u{0323}a".as_bytes());
+
+        // Default NFC normalization composes what it can: e + dot below -> ẹ
+        let cell = term.active_grid().get(0, 0).unwrap();
+        assert_eq!(cell.get_grapheme(), "\u{1EB9}\u{0301}");
+        assert_eq!(cell.width(), 1);
+        assert_eq!(term.active_grid().get(1, 0).unwrap().c, 'a');
+        assert_eq!(term.cursor.col, 2);
+    }
+
+    #[test]
+    fn test_write_char_combining_accent_at_pending_wrap() {
+        let mut term = create_test_terminal();
+        term.process(b"\x1b[1;80H");
+        term.process("e\u{0301}".as_bytes());
+
+        assert!(term.pending_wrap);
+        assert_eq!(term.active_grid().get(79, 0).unwrap().c, '\u{00E9}'); // NFC é
+        assert!(term.active_grid().get(78, 0).unwrap().combining.is_empty());
+    }
+
+    #[test]
+    fn test_write_char_combining_mark_does_not_cross_hard_newline() {
+        let mut term = create_test_terminal();
+        term.process("abc\r\n\u{0301}".as_bytes());
+
+        assert_eq!(term.active_grid().get(2, 0).unwrap().get_grapheme(), "c");
+        assert_eq!(term.cursor.col, 0);
+    }
+
+    #[test]
+    fn test_write_char_clusters_beyond_special_cases() {
+        let mut term = create_test_terminal();
+        // Devanagari KA + vowel sign AA (a spacing mark), then the Scotland
+        // flag tag sequence (black flag + tag letters + cancel tag)
+        term.process("\u{0915}\u{093E}".as_bytes());
+        term.process("\u{1F3F4}\u{E0067}\u{E0062}\u{E0073}\u{E0063}\u{E0074}\u{E007F}".as_bytes());
+
+        assert_eq!(
+            term.active_grid().get(0, 0).unwrap().get_grapheme(),
+            "\u{0915}\u{093E}"
+        );
+        let flag = term.active_grid().get(1, 0).unwrap();
+        assert_eq!(flag.combining.len(), 6);
+        assert_eq!(flag.width(), 2);
+        assert_eq!(term.cursor.col, 3);
+    }
+
+    /// Grid, cursor and wrap state after feeding `data` in one chunk and
+    /// one byte at a time (runs of length 1 take the per-character path)
+    fn run_both_ways(setup: &[u8], data: &[u8]) -> (Terminal, Terminal) {
+        let mut batched = Terminal::new(10, 4);
+        let mut single = Terminal::new(10, 4);
+        batched.process(setup);
+        single.process(setup);
+        batched.process(data);
+        for byte in data {
+            single.process(std::slice::from_ref(byte));
+        }
+        (batched, single)
+    }
+
+    fn assert_same_state(a: &Terminal, b: &Terminal) {
+        for row in 0..4 {
+            assert_eq!(a.active_grid().row(row), b.active_grid().row(row));
+            assert_eq!(
+                a.active_grid().is_line_wrapped(row),
+                b.active_grid().is_line_wrapped(row)
+            );
+        }
+        assert_eq!((a.cursor.col, a.cursor.row), (b.cursor.col, b.cursor.row));
+        assert_eq!(a.pending_wrap, b.pending_wrap);
+    }
+
+    #[test]
+    fn test_print_run_matches_per_char_writes() {
+        let cases: [(&[u8], &[u8]); 6] = [
+            // Wraps and scrolls
+            (b"", b"abcdefghijklmnopqrstuvwxyz0123456789ABCDEFGHIJKLMN"),
+            // Auto-wrap disabled: the last column is overwritten
+            (b"\x1b[?7l", b"abcdefghijklmnop"),
+            // Insert mode shifts existing text
+            (b"0123456789\r\x1b[4h", b"abc"),
+            // Attributes, hyperlink and protection apply to the whole run
+            (
+                b"\x1b[1;31m\x1b]8;;https://example.com\x1b\\\x1b[1\"q",
+                b"link text",
+            ),
+            // Runs interleaved with controls and non-ASCII text
+            (b"", "ab\u{0301}c\te\u{4E2D}f\rg\nh".as_bytes()),
+            // Line drawing charset is not batched
+            (b"\x1b(0", b"lqqk"),
+        ];
+        for (setup, data) in cases {
+            let (batched, single) = run_both_ways(setup, data);
+            assert_same_state(&batched, &single);
+        }
+    }
+
+    #[test]
+    fn test_print_run_marks_row_dirty_once_written() {
+        let mut term = create_test_terminal();
+        term.mark_clean();
+        term.process(b"hello");
+        assert_eq!(term.get_dirty_rows(), vec![0]);
+        assert_eq!(term.active_grid().row_text(0).trim_end(), "hello");
+        assert!(term.print_run.is_empty());
+    }
+
+    #[test]
+    fn test_print_run_flushed_before_sequences() {
+        let mut term = create_test_terminal();
+        // Cursor report must see the text written before it
+        term.process(b"abc\x1b[6n");
+        assert_eq!(term.drain_responses(), b"\x1b[1;4R");
+        // SGR change applies only to text after it
+        term.process(b"x\x1b[7my");
+        let grid = term.active_grid();
+        assert!(!grid.get(3, 0).unwrap().flags.reverse());
+        assert!(grid.get(4, 0).unwrap().flags.reverse());
+    }
 }
@@ -0,0 +1,710 @@
+//! Golden-screen testing harness
+//!
+//! [`TerminalHarness`] feeds bytes to a [`Terminal`] and compares the active
+//! screen against a [`ScreenDump`]: the size, cursor position, the text of
+//! every row and the style of every cell. Mismatches panic with a readable
+//! row-by-row diff, so frontends can write regression tests against the
+//! emulator without reaching into its internals.
+//!
+//! Dumps serialize to a plain-text golden format meant to be committed and
+//! reviewed:
+//!
+//! ```text
+//! size 12x2
+//! cursor 5,1
+//! text:
+//! |hello world |
+//! |$ ls        |
+//! attrs:
+//! |aaaaa       |
+//! |bb          |
+//! styles:
+//! a fg=red bold
+//! b fg=#00ff00
+//! ```
+//!
+//! Each `attrs` character names the style of the cell below it in the
+//! `styles` legend; a space is the default style. Wide characters take one
+//! text character but two `attrs` characters.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use par_term_emu_core_rust::testing::TerminalHarness;
+//!
+//! let mut harness = TerminalHarness::new(20, 3);
+//! harness.feed(b"\x1b[1mhello\x1b[0m\r\nworld");
+//! harness.assert_text(&["hello", "world"]);
+//! assert_eq!(harness.screen().style_at(0, 0), "bold");
+//! ```
+//!
+//! With [`TerminalHarness::assert_golden`], set `PAR_TERM_UPDATE_GOLDENS=1`
+//! to write (or rewrite) golden files from the current screen.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::cell::{Cell, UnderlineStyle};
+use crate::color::{Color, NamedColor};
+use crate::terminal::Terminal;
+
+/// Environment variable that makes [`TerminalHarness::assert_golden`] write
+/// the golden file instead of comparing against it
+pub const UPDATE_GOLDENS_ENV: &str = "PAR_TERM_UPDATE_GOLDENS";
+
+/// Maximum differing cells listed per row in a [`ScreenMismatch`] report
+const MAX_ATTR_DIFFS_PER_ROW: usize = 5;
+
+/// Snapshot of the visible screen: size, cursor, text and per-cell styles
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScreenDump {
+    /// Screen width in cells
+    pub cols: usize,
+    /// Screen height in rows
+    pub rows: usize,
+    /// Cursor position (col, row)
+    pub cursor: (usize, usize),
+    /// Whether the cursor is visible
+    pub cursor_visible: bool,
+    /// Text of each row, one character per cell (wide-character spacers are
+    /// omitted)
+    pub lines: Vec<String>,
+    /// Style description of each cell, row-major; empty for the default style
+    pub styles: Vec<Vec<String>>,
+}
+
+impl ScreenDump {
+    /// Capture the active screen of `terminal`
+    pub fn capture(terminal: &Terminal) -> Self {
+        let grid = terminal.active_grid();
+        let (cols, rows) = (grid.cols(), grid.rows());
+        let mut lines = Vec::with_capacity(rows);
+        let mut styles = Vec::with_capacity(rows);
+        for row in 0..rows {
+            let cells = grid.row(row).unwrap_or(&[]);
+            let mut line = String::with_capacity(cols);
+            for cell in cells.iter().filter(|c| !c.flags.wide_char_spacer()) {
+                line.push(if cell.c == '\0' { ' ' } else { cell.c });
+                line.extend(cell.combining.iter());
+            }
+            lines.push(line);
+            styles.push(cells.iter().map(describe_style).collect());
+        }
+        let cursor = terminal.cursor();
+        Self {
+            cols,
+            rows,
+            cursor: (cursor.col, cursor.row),
+            cursor_visible: cursor.visible,
+            lines,
+            styles,
+        }
+    }
+
+    /// Style description of the cell at (`col`, `row`), or `""` for the
+    /// default style and out-of-range cells
+    ///
+    /// The description lists the non-default foreground and background
+    /// (`fg=red`, `bg=#102030`, `fg=idx196`) followed by attribute names
+    /// (`bold`, `dim`, `italic`, `underline`, `underline=curly`, `blink`,
+    /// `reverse`, `hidden`, `strikethrough`, `overline`).
+    pub fn style_at(&self, col: usize, row: usize) -> &str {
+        self.styles
+            .get(row)
+            .and_then(|r| r.get(col))
+            .map_or("", String::as_str)
+    }
+
+    /// Text of each row with trailing spaces removed
+    pub fn trimmed_lines(&self) -> Vec<&str> {
+        self.lines.iter().map(|l| l.trim_end()).collect()
+    }
+
+    /// Compare against `expected`, describing every difference
+    pub fn diff(&self, expected: &ScreenDump) -> Result<(), ScreenMismatch> {
+        let mut report = Vec::new();
+        if (self.cols, self.rows) != (expected.cols, expected.rows) {
+            report.push(format!(
+                "size: expected {}x{}, actual {}x{}",
+                expected.cols, expected.rows, self.cols, self.rows
+            ));
+        }
+        if (self.cursor, self.cursor_visible) != (expected.cursor, expected.cursor_visible) {
+            report.push(format!(
+                "cursor: expected {}, actual {}",
+                cursor_line(expected.cursor, expected.cursor_visible),
+                cursor_line(self.cursor, self.cursor_visible)
+            ));
+        }
+
+        let empty = String::new();
+        for row in 0..self.rows.max(expected.rows) {
+            let want = expected.lines.get(row).unwrap_or(&empty);
+            let got = self.lines.get(row).unwrap_or(&empty);
+            if want != got {
+                report.push(text_diff(row, want, got));
+            }
+        }
+
+        for row in 0..self.rows.max(expected.rows) {
+            let cols = self.cols.max(expected.cols);
+            let differing: Vec<usize> = (0..cols)
+                .filter(|&col| self.style_at(col, row) != expected.style_at(col, row))
+                .collect();
+            for &col in differing.iter().take(MAX_ATTR_DIFFS_PER_ROW) {
+                report.push(format!(
+                    "style at row {}, col {}: expected {:?}, actual {:?}",
+                    row,
+                    col,
+                    expected.style_at(col, row),
+                    self.style_at(col, row)
+                ));
+            }
+            if differing.len() > MAX_ATTR_DIFFS_PER_ROW {
+                report.push(format!(
+                    "style at row {}: {} more differing cells",
+                    row,
+                    differing.len() - MAX_ATTR_DIFFS_PER_ROW
+                ));
+            }
+        }
+
+        if report.is_empty() {
+            Ok(())
+        } else {
+            Err(ScreenMismatch { report })
+        }
+    }
+
+    /// Serialize to the golden text format (see the module docs)
+    pub fn to_golden(&self) -> String {
+        let mut legend: Vec<&str> = Vec::new();
+        let mut attrs = String::new();
+        for row in &self.styles {
+            attrs.push('|');
+            for style in row {
+                if style.is_empty() {
+                    attrs.push(' ');
+                    continue;
+                }
+                let index = legend.iter().position(|s| s == style).unwrap_or_else(|| {
+                    legend.push(style);
+                    legend.len() - 1
+                });
+                attrs.push(style_key(index));
+            }
+            attrs.push_str("|\n");
+        }
+
+        let mut out = format!(
+            "size {}x{}\n{}\ntext:\n",
+            self.cols,
+            self.rows,
+            cursor_line(self.cursor, self.cursor_visible)
+        );
+        for line in &self.lines {
+            out.push('|');
+            out.push_str(line);
+            out.push_str("|\n");
+        }
+        out.push_str("attrs:\n");
+        out.push_str(&attrs);
+        out.push_str("styles:\n");
+        for (i, style) in legend.iter().enumerate() {
+            out.push(style_key(i));
+            out.push(' ');
+            out.push_str(style);
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Parse the golden text format
+    pub fn from_golden(golden: &str) -> Result<Self, GoldenParseError> {
+        let mut lines = golden.lines().enumerate();
+        let mut next = |what: &str| {
+            lines
+                .next()
+                .ok_or_else(|| GoldenParseError::new(0, format!("missing {}", what)))
+        };
+
+        let (n, size) = next("size line")?;
+        let (cols, rows) = size
+            .strip_prefix("size ")
+            .and_then(|s| s.split_once('x'))
+            .and_then(|(c, r)| Some((c.parse().ok()?, r.parse().ok()?)))
+            .ok_or_else(|| GoldenParseError::new(n + 1, "expected `size <cols>x<rows>`"))?;
+
+        let (n, cursor) = next("cursor line")?;
+        let (pos, cursor_visible) = match cursor.strip_suffix(" hidden") {
+            Some(pos) => (pos, false),
+            None => (cursor, true),
+        };
+        let cursor = pos
+            .strip_prefix("cursor ")
+            .and_then(|s| s.split_once(','))
+            .and_then(|(c, r)| Some((c.parse().ok()?, r.parse().ok()?)))
+            .ok_or_else(|| GoldenParseError::new(n + 1, "expected `cursor <col>,<row>`"))?;
+
+        let mut section = |name: &str| -> Result<Vec<String>, GoldenParseError> {
+            let (n, header) = next(name)?;
+            if header != format!("{}:", name) {
+                return Err(GoldenParseError::new(
+                    n + 1,
+                    format!("expected `{}:`", name),
+                ));
+            }
+            let mut body = Vec::with_capacity(rows);
+            for _ in 0..rows {
+                let (n, line) = next(name)?;
+                let inner = line
+                    .strip_prefix('|')
+                    .and_then(|l| l.strip_suffix('|'))
+                    .ok_or_else(|| GoldenParseError::new(n + 1, "expected a `|...|` row"))?;
+                body.push(inner.to_string());
+            }
+            Ok(body)
+        };
+        let text = section("text")?;
+        let attrs = section("attrs")?;
+
+        let (n, header) = next("styles")?;
+        if header != "styles:" {
+            return Err(GoldenParseError::new(n + 1, "expected `styles:`"));
+        }
+        let mut legend: Vec<(char, String)> = Vec::new();
+        for (n, line) in lines {
+            if line.is_empty() {
+                continue;
+            }
+            let mut chars = line.chars();
+            let key = chars.next().unwrap_or(' ');
+            let style = chars
+                .as_str()
+                .strip_prefix(' ')
+                .ok_or_else(|| GoldenParseError::new(n + 1, "expected `<key> <style>`"))?;
+            legend.push((key, style.to_string()));
+        }
+
+        let mut styles = Vec::with_capacity(rows);
+        for row in &attrs {
+            let mut row_styles = Vec::with_capacity(cols);
+            for key in row.chars() {
+                if key == ' ' {
+                    row_styles.push(String::new());
+                    continue;
+                }
+                let style = legend
+                    .iter()
+                    .find(|(k, _)| *k == key)
+                    .map(|(_, s)| s.clone())
+                    .ok_or_else(|| {
+                        GoldenParseError::new(0, format!("style key {:?} has no legend entry", key))
+                    })?;
+                row_styles.push(style);
+            }
+            styles.push(row_styles);
+        }
+
+        Ok(Self {
+            cols,
+            rows,
+            cursor,
+            cursor_visible,
+            lines: text,
+            styles,
+        })
+    }
+
+    /// Save in the golden text format
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_golden())
+    }
+
+    /// Load a golden file
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        Self::from_golden(&contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+/// Differences between an actual and an expected [`ScreenDump`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScreenMismatch {
+    /// One entry per difference, in size/cursor/text/style order
+    pub report: Vec<String>,
+}
+
+impl fmt::Display for ScreenMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "screen mismatch:")?;
+        for entry in &self.report {
+            for line in entry.lines() {
+                writeln!(f, "  {}", line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ScreenMismatch {}
+
+/// Malformed golden file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoldenParseError {
+    /// 1-based line number, or 0 when the error is not tied to a line
+    pub line: usize,
+    /// What was wrong
+    pub message: String,
+}
+
+impl GoldenParseError {
+    fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for GoldenParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "invalid golden file: {}", self.message)
+        } else {
+            write!(
+                f,
+                "invalid golden file at line {}: {}",
+                self.line, self.message
+            )
+        }
+    }
+}
+
+impl std::error::Error for GoldenParseError {}
+
+/// A terminal under test
+pub struct TerminalHarness {
+    terminal: Terminal,
+}
+
+impl TerminalHarness {
+    /// Create a harness around a new terminal of the given size
+    pub fn new(cols: usize, rows: usize) -> Self {
+        Self::from_terminal(Terminal::new(cols, rows))
+    }
+
+    /// Create a harness around an existing (for example pre-configured)
+    /// terminal
+    pub fn from_terminal(terminal: Terminal) -> Self {
+        Self { terminal }
+    }
+
+    /// Feed raw bytes through the parser
+    pub fn feed(&mut self, bytes: &[u8]) -> &mut Self {
+        self.terminal.process(bytes);
+        self
+    }
+
+    /// Feed a string through the parser
+    pub fn feed_str(&mut self, s: &str) -> &mut Self {
+        self.feed(s.as_bytes())
+    }
+
+    /// The terminal under test
+    pub fn terminal(&self) -> &Terminal {
+        &self.terminal
+    }
+
+    /// Mutable access to the terminal under test
+    pub fn terminal_mut(&mut self) -> &mut Terminal {
+        &mut self.terminal
+    }
+
+    /// Consume the harness and return the terminal
+    pub fn into_terminal(self) -> Terminal {
+        self.terminal
+    }
+
+    /// Capture the current screen
+    pub fn screen(&self) -> ScreenDump {
+        ScreenDump::capture(&self.terminal)
+    }
+
+    /// Compare the current screen against `expected`
+    pub fn check_screen(&self, expected: &ScreenDump) -> Result<(), ScreenMismatch> {
+        self.screen().diff(expected)
+    }
+
+    /// Assert that the current screen matches `expected`
+    ///
+    /// # Panics
+    /// Panics with a row-by-row diff on mismatch.
+    #[track_caller]
+    pub fn assert_screen(&self, expected: &ScreenDump) {
+        if let Err(mismatch) = self.check_screen(expected) {
+            panic!("{}", mismatch);
+        }
+    }
+
+    /// Assert the screen text only, ignoring trailing spaces
+    ///
+    /// `expected` lists the leading rows; rows past its end must be blank.
+    ///
+    /// # Panics
+    /// Panics with a row-by-row diff on mismatch.
+    #[track_caller]
+    pub fn assert_text(&self, expected: &[&str]) {
+        let screen = self.screen();
+        let actual = screen.trimmed_lines();
+        let report: Vec<String> = (0..actual.len().max(expected.len()))
+            .filter_map(|row| {
+                let want = expected.get(row).map_or("", |l| l.trim_end());
+                let got = actual.get(row).copied().unwrap_or("");
+                (want != got).then(|| text_diff(row, want, got))
+            })
+            .collect();
+        if !report.is_empty() {
+            panic!("{}", ScreenMismatch { report });
+        }
+    }
+
+    /// Assert that the current screen matches the golden file at `path`
+    ///
+    /// When [`UPDATE_GOLDENS_ENV`] is set to a non-empty value other than
+    /// `0`, the golden file is written from the current screen instead.
+    ///
+    /// # Panics
+    /// Panics if the golden file cannot be read or parsed, or with a
+    /// row-by-row diff on mismatch.
+    #[track_caller]
+    pub fn assert_golden<P: AsRef<Path>>(&self, path: P) {
+        let path = path.as_ref();
+        let update = std::env::var(UPDATE_GOLDENS_ENV).is_ok_and(|v| !v.is_empty() && v != "0");
+        if update {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .unwrap_or_else(|e| panic!("cannot create {}: {}", parent.display(), e));
+            }
+            self.screen()
+                .save(path)
+                .unwrap_or_else(|e| panic!("cannot write golden {}: {}", path.display(), e));
+            return;
+        }
+        let expected = ScreenDump::load(path).unwrap_or_else(|e| {
+            panic!(
+                "cannot load golden {}: {} (set {}=1 to create it)",
+                path.display(),
+                e,
+                UPDATE_GOLDENS_ENV
+            )
+        });
+        if let Err(mismatch) = self.check_screen(&expected) {
+            panic!(
+                "{} does not match\n{}(set {}=1 to update it)",
+                path.display(),
+                mismatch,
+                UPDATE_GOLDENS_ENV
+            );
+        }
+    }
+}
+
+/// Canonical description of a cell's style; empty for the default style
+fn describe_style(cell: &Cell) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    if cell.fg != Color::Named(NamedColor::White) {
+        parts.push(format!("fg={}", color_name(cell.fg)));
+    }
+    if cell.bg != Color::Named(NamedColor::Black) {
+        parts.push(format!("bg={}", color_name(cell.bg)));
+    }
+    let flags = &cell.flags;
+    let named = [
+        (flags.bold(), "bold"),
+        (flags.dim(), "dim"),
+        (flags.italic(), "italic"),
+    ];
+    parts.extend(
+        named
+            .iter()
+            .filter(|(on, _)| *on)
+            .map(|(_, n)| n.to_string()),
+    );
+    if flags.underline() {
+        parts.push(match flags.underline_style {
+            UnderlineStyle::None | UnderlineStyle::Straight => "underline".to_string(),
+            UnderlineStyle::Double => "underline=double".to_string(),
+            UnderlineStyle::Curly => "underline=curly".to_string(),
+            UnderlineStyle::Dotted => "underline=dotted".to_string(),
+            UnderlineStyle::Dashed => "underline=dashed".to_string(),
+        });
+    }
+    let named = [
+        (flags.blink(), "blink"),
+        (flags.reverse(), "reverse"),
+        (flags.hidden(), "hidden"),
+        (flags.strikethrough(), "strikethrough"),
+        (flags.overline(), "overline"),
+    ];
+    parts.extend(
+        named
+            .iter()
+            .filter(|(on, _)| *on)
+            .map(|(_, n)| n.to_string()),
+    );
+    parts.join(" ")
+}
+
+fn color_name(color: Color) -> String {
+    match color {
+        Color::Named(named) => {
+            let name = match named {
+                NamedColor::Black => "black",
+                NamedColor::Red => "red",
+                NamedColor::Green => "green",
+                NamedColor::Yellow => "yellow",
+                NamedColor::Blue => "blue",
+                NamedColor::Magenta => "magenta",
+                NamedColor::Cyan => "cyan",
+                NamedColor::White => "white",
+                NamedColor::BrightBlack => "bright-black",
+                NamedColor::BrightRed => "bright-red",
+                NamedColor::BrightGreen => "bright-green",
+                NamedColor::BrightYellow => "bright-yellow",
+                NamedColor::BrightBlue => "bright-blue",
+                NamedColor::BrightMagenta => "bright-magenta",
+                NamedColor::BrightCyan => "bright-cyan",
+                NamedColor::BrightWhite => "bright-white",
+            };
+            name.to_string()
+        }
+        Color::Indexed(i) => format!("idx{}", i),
+        Color::Rgb(r, g, b) => format!("#{:02x}{:02x}{:02x}", r, g, b),
+    }
+}
+
+/// Legend key for the `index`th distinct style: letters, digits, then
+/// single-width Latin Extended letters
+fn style_key(index: usize) -> char {
+    const KEYS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+    KEYS.get(index).map_or_else(
+        || char::from_u32(0x100 + (index - KEYS.len()) as u32).unwrap_or('?'),
+        |&b| b as char,
+    )
+}
+
+fn cursor_line(pos: (usize, usize), visible: bool) -> String {
+    format!(
+        "cursor {},{}{}",
+        pos.0,
+        pos.1,
+        if visible { "" } else { " hidden" }
+    )
+}
+
+/// Expected/actual pair for one row with carets under the differing columns
+fn text_diff(row: usize, want: &str, got: &str) -> String {
+    let want_chars: Vec<char> = want.chars().collect();
+    let got_chars: Vec<char> = got.chars().collect();
+    let carets: String = (0..want_chars.len().max(got_chars.len()))
+        .map(|i| {
+            if want_chars.get(i) == got_chars.get(i) {
+                ' '
+            } else {
+                '^'
+            }
+        })
+        .collect();
+    format!(
+        "text row {}:\n  expected |{}|\n  actual   |{}|\n            {}",
+        row,
+        want,
+        got,
+        carets.trim_end()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_screen_dump_text_cursor_and_styles() {
+        let mut harness = TerminalHarness::new(10, 3);
+        harness.feed(b"\x1b[1;31mab\x1b[0m c\r\n\x1b[38;2;0;255;0;4:3mx");
+        let screen = harness.screen();
+
+        assert_eq!(screen.trimmed_lines(), vec!["ab c", "x", ""]);
+        assert_eq!(screen.cursor, (1, 1));
+        assert_eq!(screen.style_at(0, 0), "fg=red bold");
+        assert_eq!(screen.style_at(2, 0), "");
+        assert_eq!(screen.style_at(0, 1), "fg=#00ff00 underline=curly");
+        harness.assert_text(&["ab c", "x"]);
+    }
+
+    #[test]
+    fn test_golden_round_trip() {
+        let mut harness = TerminalHarness::new(8, 2);
+        harness.feed("\x1b[7mhi\x1b[0m 中|\r\n\x1b[?25l\x1b[48;5;196m!".as_bytes());
+        let screen = harness.screen();
+        let golden = screen.to_golden();
+
+        assert!(golden.starts_with("size 8x2\ncursor 1,1 hidden\ntext:\n"));
+        assert!(golden.contains("|hi 中|  |\n|!       |\nattrs:\n|aa      |\n|b       |\n"));
+        assert!(golden.contains("\na reverse\nb bg=idx196\n"));
+        assert_eq!(ScreenDump::from_golden(&golden).unwrap(), screen);
+        harness.assert_screen(&screen);
+    }
+
+    #[test]
+    fn test_mismatch_report_lists_differences() {
+        let mut expected_harness = TerminalHarness::new(6, 2);
+        expected_harness.feed(b"hello");
+        let expected = expected_harness.screen();
+
+        let mut harness = TerminalHarness::new(6, 2);
+        harness.feed(b"he\x1b[1mlp");
+        let mismatch = harness.check_screen(&expected).unwrap_err();
+        let report = mismatch.to_string();
+
+        assert!(report.contains("cursor: expected cursor 5,0, actual cursor 4,0"));
+        assert!(report.contains("  expected |hello |\n    actual   |help  |\n                 ^^"));
+        assert!(report.contains("style at row 0, col 2: expected \"\", actual \"bold\""));
+    }
+
+    #[test]
+    #[should_panic(expected = "text row 1")]
+    fn test_assert_text_panics_on_mismatch() {
+        let mut harness = TerminalHarness::new(6, 2);
+        harness.feed(b"a\r\nb");
+        harness.assert_text(&["a", "c"]);
+    }
+
+    #[test]
+    fn test_golden_file_save_and_assert() {
+        let path = std::env::temp_dir().join(format!("par-term-golden-{}.txt", std::process::id()));
+        let mut harness = TerminalHarness::new(5, 1);
+        harness.feed(b"\x1b[32mok");
+        harness.screen().save(&path).unwrap();
+
+        harness.assert_golden(&path);
+        harness.feed(b"!");
+        assert!(harness
+            .check_screen(&ScreenDump::load(&path).unwrap())
+            .is_err());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_from_golden_rejects_malformed_input() {
+        let err = ScreenDump::from_golden("size 2x1\ncursor 0,0\ntext:\nab\n").unwrap_err();
+        assert_eq!(err.line, 4);
+        let err =
+            ScreenDump::from_golden("size 2x1\ncursor 0,0\ntext:\n|ab|\nattrs:\n|z |\nstyles:\n")
+                .unwrap_err();
+        assert!(err.message.contains("'z'"));
+    }
+}
@@ -1,6 +1,7 @@
 //! Text extraction and manipulation utilities
 
 use crate::grid::Grid;
+use crate::row_text::RowText;
 use crate::unicode_width_config::{char_width, WidthConfig};
 
 /// Default word characters for word boundary detection (iTerm2-compatible)
@@ -23,31 +24,14 @@ pub fn get_word_at(
         return None;
     }
 
-    let line = grid.row_text(row);
-    if line.is_empty() {
-        return None;
-    }
-
-    // Convert col to character index
-    let mut char_col = 0;
-    let mut byte_pos = 0;
-    for (i, c) in line.char_indices() {
-        if char_col == col {
-            byte_pos = i;
-            break;
-        }
-        char_col += char_width(c, &WidthConfig::default());
-        if char_col > col {
-            return None; // Inside a wide character
-        }
-    }
-
-    let chars: Vec<char> = line.chars().collect();
+    let row_text = RowText::new(grid.row(row)?);
+    let chars: Vec<char> = row_text.text().chars().collect();
     if chars.is_empty() {
         return None;
     }
 
-    let char_idx = line[..byte_pos].chars().count();
+    // Both columns of a wide character give its index
+    let char_idx = row_text.col_to_char(col)?;
     if char_idx >= chars.len() {
         return None;
     }
@@ -83,8 +67,8 @@ pub fn get_url_at(grid: &Grid, col: usize, row: usize) -> Option<String> {
         return None;
     }
 
-    let line = grid.row_text(row);
-    if line.is_empty() {
+    let row_text = RowText::new(grid.row(row)?);
+    if row_text.text().is_empty() {
         return None;
     }
 
@@ -93,23 +77,9 @@ pub fn get_url_at(grid: &Grid, col: usize, row: usize) -> Option<String> {
         "http://", "https://", "ftp://", "file://", "mailto:", "ssh://",
     ];
 
-    // Convert col to byte position
-    let mut char_col = 0;
-    let mut byte_pos = 0;
-    for (i, c) in line.char_indices() {
-        if char_col == col {
-            byte_pos = i;
-            break;
-        }
-        char_col += char_width(c, &WidthConfig::default());
-        if char_col > col {
-            return None;
-        }
-    }
-
     // Search backwards and forwards for URL boundaries
-    let chars: Vec<char> = line.chars().collect();
-    let char_idx = line[..byte_pos].chars().count();
+    let chars: Vec<char> = row_text.text().chars().collect();
+    let char_idx = row_text.col_to_char(col)?;
 
     if char_idx >= chars.len() {
         return None;
@@ -189,27 +159,14 @@ pub fn select_word(
         return None;
     }
 
-    let line = grid.row_text(row);
+    let row_text = RowText::new(grid.row(row)?);
+    let line = row_text.text();
     if line.is_empty() {
         return None;
     }
 
-    // Convert col to character index
-    let mut char_col = 0;
-    let mut byte_pos = 0;
-    for (i, c) in line.char_indices() {
-        if char_col == col {
-            byte_pos = i;
-            break;
-        }
-        char_col += char_width(c, &WidthConfig::default());
-        if char_col > col {
-            return None;
-        }
-    }
-
     let chars: Vec<char> = line.chars().collect();
-    let char_idx = line[..byte_pos].chars().count();
+    let char_idx = row_text.col_to_char(col)?;
 
     if char_idx >= chars.len() {
         return None;
@@ -233,14 +190,8 @@ pub fn select_word(
     }
 
     // Convert character indices to column positions
-    let start_col = chars[..start_idx]
-        .iter()
-        .map(|c| char_width(*c, &WidthConfig::default()))
-        .sum();
-    let end_col = chars[..end_idx]
-        .iter()
-        .map(|c| char_width(*c, &WidthConfig::default()))
-        .sum();
+    let byte_at = |idx: usize| line.char_indices().nth(idx).map_or(line.len(), |(b, _)| b);
+    let (start_col, end_col) = row_text.byte_range_to_cols(byte_at(start_idx), byte_at(end_idx));
 
     Some(((start_col, row), (end_col, row)))
 }
@@ -0,0 +1,648 @@
+//! Color theme loading
+//!
+//! Parses color schemes from the formats frontends commonly ship with:
+//! - iTerm2 `.itermcolors` property lists
+//! - base16 YAML schemes (`base00`..`base0F`, flat or under `palette:`)
+//! - A simple JSON scheme with hex color strings
+//!
+//! A parsed [`Theme`] is applied in one step with
+//! [`Terminal::apply_theme`](crate::terminal::Terminal::apply_theme). Colors
+//! a scheme does not define are left unchanged on the terminal.
+
+use crate::color::Color;
+use std::path::Path;
+
+/// A parsed color scheme
+///
+/// Every color is optional; `None` keeps the terminal's current value.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Theme {
+    /// Scheme name, if the source provides one
+    pub name: Option<String>,
+    /// Default foreground color
+    pub foreground: Option<Color>,
+    /// Default background color
+    pub background: Option<Color>,
+    /// Cursor color
+    pub cursor: Option<Color>,
+    /// Selection background color
+    pub selection_bg: Option<Color>,
+    /// Selection foreground/text color
+    pub selection_fg: Option<Color>,
+    /// Link/hyperlink color
+    pub link: Option<Color>,
+    /// Bold text custom color
+    pub bold: Option<Color>,
+    /// ANSI palette entries 0-15
+    pub palette: [Option<Color>; 16],
+}
+
+/// Source format of a theme
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ThemeFormat {
+    /// iTerm2 `.itermcolors` XML property list
+    ITermColors,
+    /// base16 YAML scheme
+    Base16,
+    /// JSON scheme with hex color strings
+    Json,
+}
+
+impl ThemeFormat {
+    /// Guess the format from a file extension (`itermcolors`, `yaml`/`yml`, `json`)
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_ascii_lowercase().as_str() {
+            "itermcolors" => Some(ThemeFormat::ITermColors),
+            "yaml" | "yml" => Some(ThemeFormat::Base16),
+            "json" => Some(ThemeFormat::Json),
+            _ => None,
+        }
+    }
+
+    /// Parse a format name (`itermcolors`/`iterm2`, `base16`/`yaml`, `json`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "itermcolors" | "iterm2" | "iterm" => Some(ThemeFormat::ITermColors),
+            "base16" | "yaml" | "yml" => Some(ThemeFormat::Base16),
+            "json" => Some(ThemeFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Error returned when loading a theme fails
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ThemeError {
+    /// The theme file could not be read
+    Io(String),
+    /// The file extension does not identify a supported format
+    UnknownFormat(String),
+    /// Malformed input for the chosen format
+    Parse(String),
+    /// A required color is missing
+    MissingColor(String),
+    /// A color value could not be parsed
+    InvalidColor {
+        /// Key the value belongs to
+        key: String,
+        /// The offending value
+        value: String,
+    },
+}
+
+impl std::fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ThemeError::Io(msg) => write!(f, "Failed to read theme: {}", msg),
+            ThemeError::UnknownFormat(ext) => write!(f, "Unknown theme format: {}", ext),
+            ThemeError::Parse(msg) => write!(f, "Failed to parse theme: {}", msg),
+            ThemeError::MissingColor(key) => write!(f, "Theme is missing color: {}", key),
+            ThemeError::InvalidColor { key, value } => {
+                write!(f, "Invalid color for {}: {}", key, value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ThemeError {}
+
+/// base16 slot used for each ANSI palette entry (normal, then bright)
+const BASE16_PALETTE: [&str; 16] = [
+    "base00", "base08", "base0B", "base0A", "base0D", "base0E", "base0C", "base05", "base03",
+    "base08", "base0B", "base0A", "base0D", "base0E", "base0C", "base07",
+];
+
+impl Theme {
+    /// Parse a theme in the given format
+    pub fn parse(text: &str, format: ThemeFormat) -> Result<Self, ThemeError> {
+        match format {
+            ThemeFormat::ITermColors => Self::from_itermcolors(text),
+            ThemeFormat::Base16 => Self::from_base16_yaml(text),
+            ThemeFormat::Json => Self::from_json(text),
+        }
+    }
+
+    /// Load a theme file, choosing the format from its extension
+    ///
+    /// The file stem becomes the theme name when the source does not name itself.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ThemeError> {
+        let path = path.as_ref();
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default();
+        let format = ThemeFormat::from_extension(ext)
+            .ok_or_else(|| ThemeError::UnknownFormat(ext.to_string()))?;
+        let text = std::fs::read_to_string(path).map_err(|e| ThemeError::Io(e.to_string()))?;
+        let mut theme = Self::parse(&text, format)?;
+        if theme.name.is_none() {
+            theme.name = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .map(str::to_string);
+        }
+        Ok(theme)
+    }
+
+    /// Parse an iTerm2 `.itermcolors` property list
+    ///
+    /// Reads the `Ansi 0..15 Color`, `Foreground Color`, `Background Color`,
+    /// `Cursor Color`, `Selection Color`, `Selected Text Color`, `Link Color`
+    /// and `Bold Color` entries. Other keys are ignored.
+    pub fn from_itermcolors(text: &str) -> Result<Self, ThemeError> {
+        let root = plist::parse(text)?;
+        let plist::Value::Dict(entries) = root else {
+            return Err(ThemeError::Parse("top-level value is not a dict".into()));
+        };
+
+        let mut theme = Theme::default();
+        for (key, value) in &entries {
+            let slot = match key.as_str() {
+                "Foreground Color" => &mut theme.foreground,
+                "Background Color" => &mut theme.background,
+                "Cursor Color" => &mut theme.cursor,
+                "Selection Color" => &mut theme.selection_bg,
+                "Selected Text Color" => &mut theme.selection_fg,
+                "Link Color" => &mut theme.link,
+                "Bold Color" => &mut theme.bold,
+                _ => match key
+                    .strip_prefix("Ansi ")
+                    .and_then(|k| k.strip_suffix(" Color"))
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .filter(|&n| n < 16)
+                {
+                    Some(index) => &mut theme.palette[index],
+                    None => continue,
+                },
+            };
+            *slot = Some(itermcolors_color(key, value)?);
+        }
+        Ok(theme)
+    }
+
+    /// Parse a base16 YAML scheme
+    ///
+    /// Accepts both the classic flat layout (`scheme:`, `base00:`..) and the
+    /// newer layout with the colors nested under `palette:`. All sixteen
+    /// `base0X` entries are required.
+    pub fn from_base16_yaml(text: &str) -> Result<Self, ThemeError> {
+        let doc: serde_yaml::Value =
+            serde_yaml::from_str(text).map_err(|e| ThemeError::Parse(e.to_string()))?;
+        let colors = doc.get("palette").unwrap_or(&doc);
+        let name = ["name", "scheme"]
+            .iter()
+            .find_map(|k| doc.get(*k).and_then(|v| v.as_str()))
+            .map(str::to_string);
+
+        let base = |key: &str| -> Result<Color, ThemeError> {
+            let value = colors
+                .get(key)
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| ThemeError::MissingColor(key.to_string()))?;
+            hex_color(key, value)
+        };
+
+        let mut palette = [None; 16];
+        for (slot, key) in palette.iter_mut().zip(BASE16_PALETTE) {
+            *slot = Some(base(key)?);
+        }
+        // Validate the slots the palette mapping does not use
+        for key in ["base01", "base04", "base06", "base09", "base0F"] {
+            base(key)?;
+        }
+
+        Ok(Theme {
+            name,
+            foreground: Some(base("base05")?),
+            background: Some(base("base00")?),
+            cursor: Some(base("base05")?),
+            selection_bg: Some(base("base02")?),
+            selection_fg: Some(base("base05")?),
+            link: Some(base("base0D")?),
+            bold: None,
+            palette,
+        })
+    }
+
+    /// Parse a JSON scheme
+    ///
+    /// ```json
+    /// {
+    ///   "name": "My Theme",
+    ///   "foreground": "#d0d0d0",
+    ///   "background": "#151515",
+    ///   "cursor": "#d0d0d0",
+    ///   "selection_background": "#303030",
+    ///   "selection_foreground": "#d0d0d0",
+    ///   "link": "#6a9fb5",
+    ///   "bold": "#ffffff",
+    ///   "palette": ["#151515", "#ac4142", "..."]
+    /// }
+    /// ```
+    ///
+    /// Every field is optional; `palette` may hold up to 16 entries.
+    pub fn from_json(text: &str) -> Result<Self, ThemeError> {
+        let doc: serde_json::Value =
+            serde_json::from_str(text).map_err(|e| ThemeError::Parse(e.to_string()))?;
+        if !doc.is_object() {
+            return Err(ThemeError::Parse("top-level value is not an object".into()));
+        }
+
+        let color = |key: &str| -> Result<Option<Color>, ThemeError> {
+            match doc.get(key) {
+                None | Some(serde_json::Value::Null) => Ok(None),
+                Some(serde_json::Value::String(s)) => hex_color(key, s).map(Some),
+                Some(other) => Err(ThemeError::InvalidColor {
+                    key: key.to_string(),
+                    value: other.to_string(),
+                }),
+            }
+        };
+
+        let mut palette = [None; 16];
+        match doc.get("palette") {
+            None | Some(serde_json::Value::Null) => {}
+            Some(serde_json::Value::Array(entries)) => {
+                if entries.len() > 16 {
+                    return Err(ThemeError::Parse(format!(
+                        "palette has {} entries, expected at most 16",
+                        entries.len()
+                    )));
+                }
+                for (index, entry) in entries.iter().enumerate() {
+                    let key = format!("palette[{}]", index);
+                    palette[index] = match entry {
+                        serde_json::Value::Null => None,
+                        serde_json::Value::String(s) => Some(hex_color(&key, s)?),
+                        other => {
+                            return Err(ThemeError::InvalidColor {
+                                key,
+                                value: other.to_string(),
+                            })
+                        }
+                    };
+                }
+            }
+            Some(_) => return Err(ThemeError::Parse("palette is not an array".into())),
+        }
+
+        Ok(Theme {
+            name: doc.get("name").and_then(|v| v.as_str()).map(str::to_string),
+            foreground: color("foreground")?,
+            background: color("background")?,
+            cursor: color("cursor")?,
+            selection_bg: color("selection_background")?,
+            selection_fg: color("selection_foreground")?,
+            link: color("link")?,
+            bold: color("bold")?,
+            palette,
+        })
+    }
+}
+
+/// Parse a `#RRGGBB` / `RRGGBB` string
+fn hex_color(key: &str, value: &str) -> Result<Color, ThemeError> {
+    Color::from_hex(value.trim()).ok_or_else(|| ThemeError::InvalidColor {
+        key: key.to_string(),
+        value: value.to_string(),
+    })
+}
+
+/// Convert an iTerm2 color dict (`Red/Green/Blue Component` reals in 0-1)
+fn itermcolors_color(key: &str, value: &plist::Value) -> Result<Color, ThemeError> {
+    let invalid = || ThemeError::InvalidColor {
+        key: key.to_string(),
+        value: "expected a color dict".to_string(),
+    };
+    let plist::Value::Dict(entries) = value else {
+        return Err(invalid());
+    };
+    let component = |name: &str| -> Result<u8, ThemeError> {
+        let v = entries
+            .iter()
+            .find(|(k, _)| k == name)
+            .and_then(|(_, v)| v.as_f64())
+            .ok_or_else(invalid)?;
+        Ok((v.clamp(0.0, 1.0) * 255.0).round() as u8)
+    };
+    Ok(Color::Rgb(
+        component("Red Component")?,
+        component("Green Component")?,
+        component("Blue Component")?,
+    ))
+}
+
+/// Just enough of an XML property list reader for `.itermcolors` files
+mod plist {
+    use super::ThemeError;
+
+    /// A property list value
+    #[derive(Debug, Clone, PartialEq)]
+    pub(super) enum Value {
+        Dict(Vec<(String, Value)>),
+        Array(Vec<Value>),
+        String(String),
+        Real(f64),
+        Integer(i64),
+        Bool(bool),
+        /// `<data>`, `<date>` and other values we never read
+        Other,
+    }
+
+    impl Value {
+        pub(super) fn as_f64(&self) -> Option<f64> {
+            match self {
+                Value::Real(v) => Some(*v),
+                Value::Integer(v) => Some(*v as f64),
+                _ => None,
+            }
+        }
+    }
+
+    /// Parse the document and return the value inside `<plist>`
+    pub(super) fn parse(text: &str) -> Result<Value, ThemeError> {
+        let mut reader = Reader { text, pos: 0 };
+        loop {
+            match reader.next_tag()? {
+                Some(Tag::Open("plist")) => continue,
+                Some(Tag::Open(name)) => return reader.value(name),
+                Some(Tag::Empty(name)) => return reader.empty_value(name),
+                Some(Tag::Close(name)) => {
+                    return Err(err(format!("unexpected </{}>", name)));
+                }
+                None => return Err(err("no value found".to_string())),
+            }
+        }
+    }
+
+    fn err(msg: String) -> ThemeError {
+        ThemeError::Parse(msg)
+    }
+
+    enum Tag<'a> {
+        Open(&'a str),
+        Close(&'a str),
+        Empty(&'a str),
+    }
+
+    struct Reader<'a> {
+        text: &'a str,
+        pos: usize,
+    }
+
+    impl<'a> Reader<'a> {
+        /// Advance to the next element tag, skipping text, comments,
+        /// declarations and processing instructions
+        fn next_tag(&mut self) -> Result<Option<Tag<'a>>, ThemeError> {
+            loop {
+                let Some(start) = self.text[self.pos..].find('<') else {
+                    self.pos = self.text.len();
+                    return Ok(None);
+                };
+                let start = self.pos + start;
+                let rest = &self.text[start..];
+                let terminator = if rest.starts_with("<!--") {
+                    "-->"
+                } else if rest.starts_with("<?") {
+                    "?>"
+                } else {
+                    ">"
+                };
+                let end = rest
+                    .find(terminator)
+                    .ok_or_else(|| err("unterminated tag".to_string()))?;
+                self.pos = start + end + terminator.len();
+                if terminator != ">" || rest.starts_with("<!") {
+                    continue;
+                }
+
+                let inner = rest[1..end].trim();
+                let tag = if let Some(name) = inner.strip_prefix('/') {
+                    Tag::Close(name.trim())
+                } else if let Some(body) = inner.strip_suffix('/') {
+                    Tag::Empty(tag_name(body))
+                } else {
+                    Tag::Open(tag_name(inner))
+                };
+                return Ok(Some(tag));
+            }
+        }
+
+        /// Read character data up to `</name>`
+        fn text_until_close(&mut self, name: &str) -> Result<String, ThemeError> {
+            let close = format!("</{}>", name);
+            let end = self.text[self.pos..]
+                .find(&close)
+                .ok_or_else(|| err(format!("missing {}", close)))?;
+            let raw = &self.text[self.pos..self.pos + end];
+            self.pos += end + close.len();
+            Ok(unescape(raw))
+        }
+
+        fn value(&mut self, name: &'a str) -> Result<Value, ThemeError> {
+            match name {
+                "dict" => {
+                    let mut entries = Vec::new();
+                    loop {
+                        match self.next_tag()? {
+                            Some(Tag::Close("dict")) => return Ok(Value::Dict(entries)),
+                            Some(Tag::Open("key")) => {
+                                let key = self.text_until_close("key")?;
+                                let value = match self.next_tag()? {
+                                    Some(Tag::Open(name)) => self.value(name)?,
+                                    Some(Tag::Empty(name)) => self.empty_value(name)?,
+                                    _ => return Err(err(format!("missing value for {}", key))),
+                                };
+                                entries.push((key, value));
+                            }
+                            _ => return Err(err("expected <key> in dict".to_string())),
+                        }
+                    }
+                }
+                "array" => {
+                    let mut items = Vec::new();
+                    loop {
+                        match self.next_tag()? {
+                            Some(Tag::Close("array")) => return Ok(Value::Array(items)),
+                            Some(Tag::Open(name)) => items.push(self.value(name)?),
+                            Some(Tag::Empty(name)) => items.push(self.empty_value(name)?),
+                            _ => return Err(err("unterminated array".to_string())),
+                        }
+                    }
+                }
+                "string" => Ok(Value::String(self.text_until_close(name)?)),
+                "real" | "integer" => {
+                    let text = self.text_until_close(name)?;
+                    let text = text.trim();
+                    if name == "integer" {
+                        if let Ok(v) = text.parse() {
+                            return Ok(Value::Integer(v));
+                        }
+                    }
+                    text.parse()
+                        .map(Value::Real)
+                        .map_err(|_| err(format!("invalid number: {}", text)))
+                }
+                "true" | "false" => {
+                    self.text_until_close(name)?;
+                    Ok(Value::Bool(name == "true"))
+                }
+                _ => {
+                    self.text_until_close(name)?;
+                    Ok(Value::Other)
+                }
+            }
+        }
+
+        fn empty_value(&mut self, name: &str) -> Result<Value, ThemeError> {
+            Ok(match name {
+                "dict" => Value::Dict(Vec::new()),
+                "array" => Value::Array(Vec::new()),
+                "string" => Value::String(String::new()),
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                _ => Value::Other,
+            })
+        }
+    }
+
+    /// Element name from a tag body, dropping any attributes
+    fn tag_name(body: &str) -> &str {
+        body.split_whitespace().next().unwrap_or_default()
+    }
+
+    fn unescape(text: &str) -> String {
+        text.replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ITERM: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>Ansi 1 Color</key>
+	<dict>
+		<key>Alpha Component</key>
+		<real>1</real>
+		<key>Blue Component</key>
+		<real>0.0</real>
+		<key>Color Space</key>
+		<string>sRGB</string>
+		<key>Green Component</key>
+		<real>0.0</real>
+		<key>Red Component</key>
+		<real>1</real>
+	</dict>
+	<!-- a comment -->
+	<key>Background Color</key>
+	<dict>
+		<key>Blue Component</key>
+		<real>0.5</real>
+		<key>Green Component</key>
+		<integer>0</integer>
+		<key>Red Component</key>
+		<real>0.1</real>
+	</dict>
+	<key>Use Bright Bold</key>
+	<true/>
+</dict>
+</plist>
+"#;
+
+    #[test]
+    fn test_parse_itermcolors() {
+        let theme = Theme::from_itermcolors(ITERM).unwrap();
+        assert_eq!(theme.palette[1], Some(Color::Rgb(255, 0, 0)));
+        assert_eq!(theme.background, Some(Color::Rgb(26, 0, 128)));
+        assert_eq!(theme.foreground, None);
+        assert!(theme.palette[0].is_none());
+    }
+
+    #[test]
+    fn test_itermcolors_missing_component_is_an_error() {
+        let text = "<plist><dict><key>Foreground Color</key><dict>\
+            <key>Red Component</key><real>1</real></dict></dict></plist>";
+        assert!(matches!(
+            Theme::from_itermcolors(text),
+            Err(ThemeError::InvalidColor { key, .. }) if key == "Foreground Color"
+        ));
+    }
+
+    #[test]
+    fn test_parse_base16_flat_and_nested() {
+        let flat = "scheme: \"Test\"\n\
+            base00: \"101010\"\nbase01: \"111111\"\nbase02: \"222222\"\nbase03: \"333333\"\n\
+            base04: \"444444\"\nbase05: \"d0d0d0\"\nbase06: \"666666\"\nbase07: \"ffffff\"\n\
+            base08: \"ac4142\"\nbase09: \"999999\"\nbase0A: \"f4bf75\"\nbase0B: \"90a959\"\n\
+            base0C: \"75b5aa\"\nbase0D: \"6a9fb5\"\nbase0E: \"aa759f\"\nbase0F: \"8f5536\"\n";
+        let theme = Theme::from_base16_yaml(flat).unwrap();
+        assert_eq!(theme.name.as_deref(), Some("Test"));
+        assert_eq!(theme.background, Some(Color::Rgb(0x10, 0x10, 0x10)));
+        assert_eq!(theme.foreground, Some(Color::Rgb(0xd0, 0xd0, 0xd0)));
+        assert_eq!(theme.selection_bg, Some(Color::Rgb(0x22, 0x22, 0x22)));
+        assert_eq!(theme.palette[1], Some(Color::Rgb(0xac, 0x41, 0x42)));
+        assert_eq!(theme.palette[8], Some(Color::Rgb(0x33, 0x33, 0x33)));
+        assert_eq!(theme.palette[15], Some(Color::Rgb(0xff, 0xff, 0xff)));
+
+        let nested = format!(
+            "system: base16\nname: Nested\npalette:\n{}",
+            flat.lines()
+                .skip(1)
+                .map(|l| format!("  {}\n", l))
+                .collect::<String>()
+        );
+        let theme = Theme::from_base16_yaml(&nested).unwrap();
+        assert_eq!(theme.name.as_deref(), Some("Nested"));
+        assert_eq!(theme.palette[4], Some(Color::Rgb(0x6a, 0x9f, 0xb5)));
+
+        let missing = flat.replace("base0F: \"8f5536\"\n", "");
+        assert_eq!(
+            Theme::from_base16_yaml(&missing),
+            Err(ThemeError::MissingColor("base0F".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_json() {
+        let theme = Theme::from_json(
+            r##"{"name": "J", "foreground": "#ffffff", "selection_background": "202020",
+                "palette": ["#000000", null, "#00ff00"]}"##,
+        )
+        .unwrap();
+        assert_eq!(theme.name.as_deref(), Some("J"));
+        assert_eq!(theme.foreground, Some(Color::Rgb(255, 255, 255)));
+        assert_eq!(theme.selection_bg, Some(Color::Rgb(0x20, 0x20, 0x20)));
+        assert_eq!(theme.palette[0], Some(Color::Rgb(0, 0, 0)));
+        assert_eq!(theme.palette[1], None);
+        assert_eq!(theme.palette[2], Some(Color::Rgb(0, 255, 0)));
+        assert!(theme.background.is_none());
+
+        assert!(matches!(
+            Theme::from_json(r##"{"cursor": "#12"}"##),
+            Err(ThemeError::InvalidColor { key, .. }) if key == "cursor"
+        ));
+        assert!(matches!(Theme::from_json("[]"), Err(ThemeError::Parse(_))));
+    }
+
+    #[test]
+    fn test_format_from_extension() {
+        assert_eq!(
+            ThemeFormat::from_extension("itermcolors"),
+            Some(ThemeFormat::ITermColors)
+        );
+        assert_eq!(
+            ThemeFormat::from_extension("YML"),
+            Some(ThemeFormat::Base16)
+        );
+        assert_eq!(ThemeFormat::from_extension("json"), Some(ThemeFormat::Json));
+        assert_eq!(ThemeFormat::from_extension("txt"), None);
+    }
+}
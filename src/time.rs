@@ -0,0 +1,10 @@
+//! Clock types for the terminal core
+//!
+//! `std::time::Instant` and `SystemTime` panic on `wasm32-unknown-unknown`,
+//! so browser builds take them from `web-time` (backed by `performance.now()`
+//! and `Date.now()`). Everywhere else these are the `std` types.
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use std::time::{Instant, SystemTime, UNIX_EPOCH};
+#[cfg(target_arch = "wasm32")]
+pub use web_time::{Instant, SystemTime, UNIX_EPOCH};
@@ -15,6 +15,9 @@
 //!
 //! - [Tmux Control Mode Wiki](https://github.com/tmux/tmux/wiki/Control-Mode)
 
+/// Introducer `tmux -CC` sends before its first line
+const CONTROL_MODE_DCS: &str = "\x1bP1000p";
+
 /// Tmux control protocol notification types
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TmuxNotification {
@@ -147,6 +150,10 @@ pub enum TmuxNotification {
     /// Arguments: buffer_name
     PasteBufferDeleted { name: String },
 
+    /// Line of command output inside a `%begin`/`%end` block
+    /// Arguments: command_number, line
+    CommandOutput { command_number: u32, line: String },
+
     /// Unknown or unrecognized notification
     /// Arguments: notification_line
     Unknown { line: String },
@@ -186,6 +193,7 @@ impl TmuxNotification {
             Self::LayoutChange { .. } => "layout-change",
             Self::PasteBufferChanged { .. } => "paste-buffer-changed",
             Self::PasteBufferDeleted { .. } => "paste-buffer-deleted",
+            Self::CommandOutput { .. } => "command-output",
             Self::Unknown { .. } => "unknown",
             Self::TerminalOutput { .. } => "terminal-output",
         }
@@ -202,6 +210,8 @@ pub struct TmuxControlParser {
     /// This helps handle race conditions where data arrives before
     /// set_control_mode(true) is called
     auto_detect: bool,
+    /// Command number of the `%begin` block being read, if any
+    open_block: Option<u32>,
 }
 
 impl TmuxControlParser {
@@ -211,6 +221,7 @@ impl TmuxControlParser {
             line_buffer: Vec::new(),
             control_mode,
             auto_detect: false,
+            open_block: None,
         }
     }
 
@@ -305,6 +316,12 @@ impl TmuxControlParser {
             return Some(0);
         }
 
+        // `tmux -CC` announces control mode with its DCS introducer
+        let dcs = CONTROL_MODE_DCS.as_bytes();
+        if let Some(pos) = data.windows(dcs.len()).position(|window| window == dcs) {
+            return Some(pos);
+        }
+
         // Look for \n%begin in the data
         for i in 0..data.len().saturating_sub(7) {
             if data[i] == b'\n'
@@ -338,8 +355,11 @@ impl TmuxControlParser {
             }
             let line = String::from_utf8_lossy(&line_bytes[..end]).to_string();
 
+            // `tmux -CC` wraps the session in DCS 1000p ... ST
+            let line = line.strip_prefix(CONTROL_MODE_DCS).unwrap_or(&line);
+
             // Parse the line
-            if let Some(notification) = Self::parse_line(&line) {
+            if let Some(notification) = self.parse_block_line(line) {
                 notifications.push(notification);
             }
         }
@@ -347,6 +367,30 @@ impl TmuxControlParser {
         notifications
     }
 
+    /// Parse a line, tracking `%begin` blocks so that plain command output
+    /// inside one is reported as [`TmuxNotification::CommandOutput`]
+    fn parse_block_line(&mut self, line: &str) -> Option<TmuxNotification> {
+        if let Some(command_number) = self.open_block {
+            if !line.starts_with('%') {
+                return Some(TmuxNotification::CommandOutput {
+                    command_number,
+                    line: line.to_string(),
+                });
+            }
+        }
+        let notification = Self::parse_line(line);
+        match notification {
+            Some(TmuxNotification::Begin { command_number, .. }) => {
+                self.open_block = Some(command_number);
+            }
+            Some(TmuxNotification::End { .. } | TmuxNotification::Error { .. }) => {
+                self.open_block = None;
+            }
+            _ => {}
+        }
+        notification
+    }
+
     /// Parse a single line into a notification
     fn parse_line(line: &str) -> Option<TmuxNotification> {
         // Only trim leading whitespace - trailing whitespace may be significant
@@ -688,6 +732,7 @@ impl TmuxControlParser {
     /// Clear the internal line buffer
     pub fn clear_buffer(&mut self) {
         self.line_buffer.clear();
+        self.open_block = None;
     }
 
     /// Get the current size of the internal line buffer
@@ -1007,6 +1052,49 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_command_output_inside_block() {
+        let mut parser = TmuxControlParser::new(true);
+        let notifications = parser.parse(b"%begin 1 7 1\r\n@1\tzsh\r\n\r\n%end 1 7 1\r\nafter\r\n");
+        assert_eq!(
+            notifications,
+            vec![
+                TmuxNotification::Begin {
+                    timestamp: 1,
+                    command_number: 7,
+                    flags: "1".to_string(),
+                },
+                TmuxNotification::CommandOutput {
+                    command_number: 7,
+                    line: "@1\tzsh".to_string(),
+                },
+                TmuxNotification::CommandOutput {
+                    command_number: 7,
+                    line: String::new(),
+                },
+                TmuxNotification::End {
+                    timestamp: 1,
+                    command_number: 7,
+                    flags: "1".to_string(),
+                },
+                TmuxNotification::TerminalOutput {
+                    data: b"after".to_vec(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_control_mode_dcs_introducer() {
+        let mut parser = TmuxControlParser::new(false);
+        parser.set_auto_detect(true);
+        let notifications = parser.parse(b"\x1bP1000p%begin 1 0 0\n%end 1 0 0\n");
+        assert!(parser.is_control_mode());
+        assert_eq!(notifications.len(), 2);
+        assert_eq!(notifications[0].notification_type(), "begin");
+        assert_eq!(notifications[1].notification_type(), "end");
+    }
+
     #[test]
     fn test_clear_buffer() {
         let mut parser = TmuxControlParser::new(true);
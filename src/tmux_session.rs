@@ -0,0 +1,616 @@
+//! tmux control-mode (`tmux -CC`) bootstrapper for [`PtySession`]
+//!
+//! [`PtySession::spawn_tmux_control`] starts `tmux -CC` in a PTY session with
+//! the terminal's tmux control mode already on, waits for the attach
+//! handshake, sizes the tmux client to the session and queries the windows
+//! and panes of the attached session. [`PtySession::tmux_command`] runs
+//! further tmux commands and returns their output lines.
+//!
+//! Replies are matched to commands by order: the reply to a command is the
+//! first `%begin` block queued after it was sent. Notifications stay queued
+//! on the terminal (see [`Terminal::drain_tmux_notifications`]); draining
+//! them from another thread while a command is pending loses its reply.
+//!
+//! [`Terminal::drain_tmux_notifications`]: crate::terminal::Terminal::drain_tmux_notifications
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::pty_error::PtyError;
+use crate::pty_session::{PtySession, WaitOutcome};
+use crate::pty_spawn::SpawnConfig;
+use crate::tmux_control::TmuxNotification;
+
+/// Default time allowed for the attach handshake and each command
+pub const DEFAULT_TMUX_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Field separator in the `-F` formats of the discovery commands (tmux
+/// replaces control characters such as tab in control-mode output)
+const FIELD_SEPARATOR: &str = "|:|";
+
+/// `list-windows` format: id, index, active, width, height, layout, name.
+/// The name comes last so it may contain the separator.
+const WINDOW_FORMAT: &str = "#{window_id}|:|#{window_index}|:|#{window_active}|:|#{window_width}|:|#{window_height}|:|#{window_layout}|:|#{window_name}";
+
+/// `list-panes` format: window id, pane id, index, active, width, height,
+/// left, top, current command, current path, title. Starts with the window
+/// id since lines starting with `%` (pane ids) read as control messages.
+const PANE_FORMAT: &str = "#{window_id}|:|#{pane_id}|:|#{pane_index}|:|#{pane_active}|:|#{pane_width}|:|#{pane_height}|:|#{pane_left}|:|#{pane_top}|:|#{pane_current_command}|:|#{pane_current_path}|:|#{pane_title}";
+
+/// `display-message` format: session id, session name
+const SESSION_FORMAT: &str = "#{session_id}|:|#{session_name}";
+
+/// How to start `tmux -CC`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TmuxBootstrapConfig {
+    /// tmux executable (default: `tmux`, looked up on `PATH`)
+    pub tmux_path: String,
+    /// Session to attach to, created if missing (None = new unnamed session)
+    pub session_name: Option<String>,
+    /// tmux server socket name (`-L`), None for the default server
+    pub socket_name: Option<String>,
+    /// Time allowed for the attach handshake and each discovery command
+    pub timeout: Duration,
+}
+
+impl Default for TmuxBootstrapConfig {
+    fn default() -> Self {
+        Self {
+            tmux_path: "tmux".to_string(),
+            session_name: None,
+            socket_name: None,
+            timeout: DEFAULT_TMUX_TIMEOUT,
+        }
+    }
+}
+
+impl TmuxBootstrapConfig {
+    /// Attach to (or create) the named session
+    pub fn with_session_name(mut self, name: impl Into<String>) -> Self {
+        self.session_name = Some(name.into());
+        self
+    }
+
+    /// Use a separate tmux server (`tmux -L <name>`)
+    pub fn with_socket_name(mut self, name: impl Into<String>) -> Self {
+        self.socket_name = Some(name.into());
+        self
+    }
+
+    /// Use another tmux executable
+    pub fn with_tmux_path(mut self, path: impl Into<String>) -> Self {
+        self.tmux_path = path.into();
+        self
+    }
+
+    /// Set the handshake and command timeout
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Arguments after the tmux executable
+    pub fn args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(ref socket) = self.socket_name {
+            args.extend(["-L".to_string(), socket.clone()]);
+        }
+        args.extend(["-CC".to_string(), "new-session".to_string()]);
+        if let Some(ref name) = self.session_name {
+            args.extend(["-A".to_string(), "-s".to_string(), name.clone()]);
+        }
+        args
+    }
+}
+
+/// A tmux pane discovered by the bootstrapper
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TmuxPane {
+    /// Pane id, e.g. `%3` (as in `%output` notifications)
+    pub id: String,
+    /// Id of the window holding the pane, e.g. `@1`
+    pub window_id: String,
+    /// Index within the window
+    pub index: u32,
+    /// Whether this is the window's active pane
+    pub active: bool,
+    /// Size in cells
+    pub width: u16,
+    pub height: u16,
+    /// Position of the top-left cell within the window
+    pub left: u16,
+    pub top: u16,
+    /// Foreground command, e.g. `zsh`
+    pub current_command: String,
+    /// Working directory of the foreground process
+    pub current_path: String,
+    /// Pane title (OSC 2 inside the pane)
+    pub title: String,
+}
+
+/// A tmux window discovered by the bootstrapper
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TmuxWindow {
+    /// Window id, e.g. `@1`
+    pub id: String,
+    /// Index in the session's window list
+    pub index: u32,
+    /// Window name
+    pub name: String,
+    /// Whether this is the session's current window
+    pub active: bool,
+    /// Size in cells
+    pub width: u16,
+    pub height: u16,
+    /// Layout string (as in `%layout-change` notifications)
+    pub layout: String,
+    /// Panes by index
+    pub panes: Vec<TmuxPane>,
+}
+
+impl TmuxWindow {
+    /// The window's active pane
+    pub fn active_pane(&self) -> Option<&TmuxPane> {
+        self.panes.iter().find(|pane| pane.active)
+    }
+}
+
+/// The attached tmux session, as discovered by the bootstrapper
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TmuxSessionInfo {
+    /// Session id, e.g. `$0`
+    pub session_id: String,
+    /// Session name
+    pub session_name: String,
+    /// Windows by index
+    pub windows: Vec<TmuxWindow>,
+}
+
+impl TmuxSessionInfo {
+    /// The session's current window
+    pub fn active_window(&self) -> Option<&TmuxWindow> {
+        self.windows.iter().find(|window| window.active)
+    }
+
+    /// Find a window by id
+    pub fn window(&self, id: &str) -> Option<&TmuxWindow> {
+        self.windows.iter().find(|window| window.id == id)
+    }
+
+    /// Find a pane by id in any window
+    pub fn pane(&self, id: &str) -> Option<&TmuxPane> {
+        self.windows
+            .iter()
+            .flat_map(|window| &window.panes)
+            .find(|pane| pane.id == id)
+    }
+
+    /// Build from `list-windows`, `list-panes -s` and `display-message`
+    /// output lines in the formats the bootstrapper requests
+    fn parse(session: &[String], windows: &[String], panes: &[String]) -> Self {
+        let (session_id, session_name) = session
+            .first()
+            .and_then(|line| line.split_once(FIELD_SEPARATOR))
+            .map(|(id, name)| (id.to_string(), name.to_string()))
+            .unwrap_or_default();
+        let mut windows: Vec<TmuxWindow> = windows
+            .iter()
+            .filter_map(|line| parse_window(line))
+            .collect();
+        for pane in panes.iter().filter_map(|line| parse_pane(line)) {
+            if let Some(window) = windows.iter_mut().find(|w| w.id == pane.window_id) {
+                window.panes.push(pane);
+            }
+        }
+        windows.sort_by_key(|window| window.index);
+        for window in &mut windows {
+            window.panes.sort_by_key(|pane| pane.index);
+        }
+        Self {
+            session_id,
+            session_name,
+            windows,
+        }
+    }
+}
+
+fn parse_window(line: &str) -> Option<TmuxWindow> {
+    let fields: Vec<&str> = line.splitn(7, FIELD_SEPARATOR).collect();
+    let [id, index, active, width, height, layout, name] = fields[..] else {
+        return None;
+    };
+    Some(TmuxWindow {
+        id: id.to_string(),
+        index: index.parse().ok()?,
+        name: name.to_string(),
+        active: active == "1",
+        width: width.parse().ok()?,
+        height: height.parse().ok()?,
+        layout: layout.to_string(),
+        panes: Vec::new(),
+    })
+}
+
+fn parse_pane(line: &str) -> Option<TmuxPane> {
+    // The title comes last so it may contain the separator
+    let fields: Vec<&str> = line.splitn(11, FIELD_SEPARATOR).collect();
+    let [window_id, id, index, active, width, height, left, top, command, path, title] = fields[..]
+    else {
+        return None;
+    };
+    Some(TmuxPane {
+        id: id.to_string(),
+        window_id: window_id.to_string(),
+        index: index.parse().ok()?,
+        active: active == "1",
+        width: width.parse().ok()?,
+        height: height.parse().ok()?,
+        left: left.parse().ok()?,
+        top: top.parse().ok()?,
+        current_command: command.to_string(),
+        current_path: path.to_string(),
+        title: title.to_string(),
+    })
+}
+
+/// Errors from bootstrapping or driving `tmux -CC`
+#[derive(Debug)]
+pub enum TmuxBootstrapError {
+    /// tmux could not be spawned or written to
+    Pty(PtyError),
+    /// No reply to `command` (`"attach"` for the handshake) within the timeout
+    Timeout { command: String },
+    /// tmux exited or left control mode before replying to `command`
+    Exited { command: String },
+    /// tmux answered `command` with `%error`; `output` holds its message lines
+    CommandFailed {
+        command: String,
+        output: Vec<String>,
+    },
+}
+
+impl fmt::Display for TmuxBootstrapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TmuxBootstrapError::Pty(err) => write!(f, "tmux PTY error: {}", err),
+            TmuxBootstrapError::Timeout { command } => {
+                write!(f, "timed out waiting for tmux to answer {}", command)
+            }
+            TmuxBootstrapError::Exited { command } => {
+                write!(f, "tmux exited before answering {}", command)
+            }
+            TmuxBootstrapError::CommandFailed { command, output } => {
+                write!(f, "tmux {} failed: {}", command, output.join("; "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for TmuxBootstrapError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TmuxBootstrapError::Pty(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<PtyError> for TmuxBootstrapError {
+    fn from(err: PtyError) -> Self {
+        TmuxBootstrapError::Pty(err)
+    }
+}
+
+/// Outcome of the first complete `%begin` block at or after a queue index
+enum BlockScan {
+    Complete { failed: bool, lines: Vec<String> },
+    Exited,
+    Pending,
+}
+
+fn scan_block(notifications: &[TmuxNotification], from: usize) -> BlockScan {
+    let mut block: Option<(u32, Vec<String>)> = None;
+    for notification in notifications.iter().skip(from) {
+        match (notification, block.as_mut()) {
+            (TmuxNotification::Begin { command_number, .. }, None) => {
+                block = Some((*command_number, Vec::new()));
+            }
+            (
+                TmuxNotification::CommandOutput {
+                    command_number,
+                    line,
+                },
+                Some((number, lines)),
+            ) if command_number == number => lines.push(line.clone()),
+            (
+                TmuxNotification::End { command_number, .. }
+                | TmuxNotification::Error { command_number, .. },
+                Some((number, lines)),
+            ) if command_number == number => {
+                return BlockScan::Complete {
+                    failed: matches!(notification, TmuxNotification::Error { .. }),
+                    lines: std::mem::take(lines),
+                };
+            }
+            (TmuxNotification::Exit, _) => return BlockScan::Exited,
+            _ => {}
+        }
+    }
+    BlockScan::Pending
+}
+
+impl PtySession {
+    /// Start `tmux -CC`, wait for the attach handshake and discover the
+    /// session's windows and panes
+    ///
+    /// Turns on the terminal's tmux control mode before spawning, so no
+    /// control output reaches the screen, and back off if tmux cannot be
+    /// spawned. After the handshake the tmux client is sized to this session
+    /// (`refresh-client -C`).
+    ///
+    /// # Errors
+    /// [`TmuxBootstrapError::Pty`] if tmux cannot be spawned, and the other
+    /// variants if the handshake or a discovery command does not succeed
+    /// within `config.timeout`. tmux is left running on error.
+    pub fn spawn_tmux_control(
+        &mut self,
+        config: &TmuxBootstrapConfig,
+    ) -> Result<TmuxSessionInfo, TmuxBootstrapError> {
+        let start = {
+            let mut term = self.terminal_ref().write();
+            term.set_tmux_control_mode(true);
+            term.tmux_notifications().len()
+        };
+        let spawn = SpawnConfig::new(&config.tmux_path)
+            .with_args(config.args())
+            // Allow bootstrapping from inside another tmux
+            .without_env("TMUX");
+        if let Err(err) = self.spawn_with_config(&spawn) {
+            self.terminal_ref().write().set_tmux_control_mode(false);
+            return Err(err.into());
+        }
+
+        self.wait_tmux_block("attach", start, config.timeout)?;
+        let (cols, rows) = self.size();
+        self.tmux_command(
+            &format!("refresh-client -C {},{}", cols, rows),
+            Some(config.timeout),
+        )?;
+        self.tmux_session_info(Some(config.timeout))
+    }
+
+    /// Run a tmux command over control mode and return its output lines
+    ///
+    /// `timeout` of None waits indefinitely.
+    ///
+    /// # Errors
+    /// [`TmuxBootstrapError::CommandFailed`] with tmux's message if the
+    /// command fails; see [`PtySession::spawn_tmux_control`] for the others.
+    pub fn tmux_command(
+        &mut self,
+        command: &str,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<String>, TmuxBootstrapError> {
+        let start = self.terminal_ref().read().tmux_notifications().len();
+        self.write_str(&format!("{}\n", command))?;
+        self.wait_tmux_block(command, start, timeout.unwrap_or(Duration::MAX))
+    }
+
+    /// Query the attached session's id, name, windows and panes again
+    ///
+    /// # Errors
+    /// See [`PtySession::tmux_command`].
+    pub fn tmux_session_info(
+        &mut self,
+        timeout: Option<Duration>,
+    ) -> Result<TmuxSessionInfo, TmuxBootstrapError> {
+        let session =
+            self.tmux_command(&format!("display-message -p '{SESSION_FORMAT}'"), timeout)?;
+        let windows = self.tmux_command(&format!("list-windows -F '{WINDOW_FORMAT}'"), timeout)?;
+        let panes = self.tmux_command(&format!("list-panes -s -F '{PANE_FORMAT}'"), timeout)?;
+        Ok(TmuxSessionInfo::parse(&session, &windows, &panes))
+    }
+
+    /// Wait for the first complete reply block queued at or after `start`
+    fn wait_tmux_block(
+        &self,
+        command: &str,
+        start: usize,
+        timeout: Duration,
+    ) -> Result<Vec<String>, TmuxBootstrapError> {
+        let deadline = Instant::now().checked_add(timeout);
+        let waiter = self.output_waiter();
+        loop {
+            let generation = waiter.generation();
+            let scan = scan_block(self.terminal_ref().read().tmux_notifications(), start);
+            match scan {
+                BlockScan::Complete {
+                    failed: false,
+                    lines,
+                } => return Ok(lines),
+                BlockScan::Complete {
+                    failed: true,
+                    lines,
+                } => {
+                    return Err(TmuxBootstrapError::CommandFailed {
+                        command: command.to_string(),
+                        output: lines,
+                    });
+                }
+                BlockScan::Exited => {
+                    return Err(TmuxBootstrapError::Exited {
+                        command: command.to_string(),
+                    });
+                }
+                BlockScan::Pending => {}
+            }
+            let remaining = deadline.map(|d| d.saturating_duration_since(Instant::now()));
+            match waiter.wait_for_update(generation, remaining) {
+                WaitOutcome::Updated(_) | WaitOutcome::Interrupted => {}
+                WaitOutcome::TimedOut => {
+                    return Err(TmuxBootstrapError::Timeout {
+                        command: command.to_string(),
+                    });
+                }
+                WaitOutcome::Closed => {
+                    return Err(TmuxBootstrapError::Exited {
+                        command: command.to_string(),
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_config_args() {
+        assert_eq!(
+            TmuxBootstrapConfig::default().args(),
+            ["-CC", "new-session"]
+        );
+        let config = TmuxBootstrapConfig::default()
+            .with_socket_name("test")
+            .with_session_name("work");
+        assert_eq!(
+            config.args(),
+            ["-L", "test", "-CC", "new-session", "-A", "-s", "work"]
+        );
+    }
+
+    #[test]
+    fn test_parse_session_info() {
+        let info = TmuxSessionInfo::parse(
+            &lines(&["$2|:|work"]),
+            &lines(&[
+                "@3|:|1|:|0|:|80|:|24|:|b25f,80x24,0,0,4|:|logs",
+                "@1|:|0|:|1|:|80|:|24|:|layout|:|zsh",
+            ]),
+            &lines(&[
+                "@1|:|%2|:|1|:|1|:|40|:|24|:|41|:|0|:|vim|:|/src|:|main.rs|:|edited",
+                "@1|:|%1|:|0|:|0|:|39|:|24|:|0|:|0|:|zsh|:|/home|:|host",
+                "@3|:|%4|:|0|:|1|:|80|:|24|:|0|:|0|:|tail|:|/var/log|:|",
+                "garbage",
+            ]),
+        );
+        assert_eq!(info.session_id, "$2");
+        assert_eq!(info.session_name, "work");
+        assert_eq!(info.windows.len(), 2);
+        assert_eq!(info.windows[0].id, "@1");
+        assert_eq!(info.active_window().map(|w| w.name.as_str()), Some("zsh"));
+
+        let window = info.window("@1").unwrap();
+        assert_eq!(window.panes.len(), 2);
+        assert_eq!(window.panes[0].id, "%1");
+        assert_eq!(window.active_pane().map(|p| p.id.as_str()), Some("%2"));
+
+        let pane = info.pane("%2").unwrap();
+        assert_eq!(
+            (pane.width, pane.height, pane.left, pane.top),
+            (40, 24, 41, 0)
+        );
+        assert_eq!(pane.current_command, "vim");
+        assert_eq!(pane.title, "main.rs|:|edited");
+        assert_eq!(info.pane("%4").unwrap().title, "");
+    }
+
+    #[test]
+    fn test_scan_block_matches_command_number() {
+        let begin = |n| TmuxNotification::Begin {
+            timestamp: 0,
+            command_number: n,
+            flags: "1".to_string(),
+        };
+        let output = |n, line: &str| TmuxNotification::CommandOutput {
+            command_number: n,
+            line: line.to_string(),
+        };
+        let queue = vec![
+            begin(1),
+            output(1, "first"),
+            TmuxNotification::End {
+                timestamp: 0,
+                command_number: 1,
+                flags: "1".to_string(),
+            },
+            begin(2),
+            output(2, "no such window"),
+        ];
+        assert!(matches!(
+            scan_block(&queue, 0),
+            BlockScan::Complete { failed: false, ref lines } if lines == &["first"]
+        ));
+        assert!(matches!(scan_block(&queue, 3), BlockScan::Pending));
+
+        let mut queue = queue;
+        queue.push(TmuxNotification::Error {
+            timestamp: 0,
+            command_number: 2,
+            flags: "1".to_string(),
+        });
+        assert!(matches!(
+            scan_block(&queue, 3),
+            BlockScan::Complete { failed: true, .. }
+        ));
+        queue.truncate(4);
+        queue.push(TmuxNotification::Exit);
+        assert!(matches!(scan_block(&queue, 3), BlockScan::Exited));
+    }
+
+    #[test]
+    fn test_failed_spawn_leaves_control_mode_off() {
+        let config = TmuxBootstrapConfig::default().with_tmux_path("/nonexistent/tmux");
+        let mut session = PtySession::new(80, 24, 0);
+        assert!(matches!(
+            session.spawn_tmux_control(&config),
+            Err(TmuxBootstrapError::Pty(_))
+        ));
+        assert!(!session.terminal_ref().read().is_tmux_control_mode());
+    }
+
+    #[test]
+    #[ignore = "needs tmux installed"]
+    fn test_bootstrap_real_tmux() {
+        let socket = format!("par-term-test-{}", std::process::id());
+        let config = TmuxBootstrapConfig::default()
+            .with_socket_name(&socket)
+            .with_session_name("bootstrap");
+
+        let mut session = PtySession::new(100, 30, 0);
+        let result = session.spawn_tmux_control(&config);
+        let new_window = session.tmux_command("new-window -n second", Some(DEFAULT_TMUX_TIMEOUT));
+        let refreshed = session.tmux_session_info(Some(DEFAULT_TMUX_TIMEOUT));
+        let bad = session.tmux_command("no-such-command", Some(DEFAULT_TMUX_TIMEOUT));
+        let _ = session.kill();
+        let _ = std::process::Command::new("tmux")
+            .args(["-L", &socket, "kill-server"])
+            .output();
+
+        let info = result.expect("bootstrap");
+        assert_eq!(info.session_name, "bootstrap");
+        assert!(info.session_id.starts_with('$'));
+        assert_eq!(info.windows.len(), 1);
+        let window = &info.windows[0];
+        assert!(window.active);
+        assert_eq!((window.width, window.height), (100, 30));
+        assert_eq!(window.panes.len(), 1);
+        assert!(window.panes[0].id.starts_with('%'));
+
+        new_window.expect("new-window");
+        let refreshed = refreshed.expect("refresh");
+        assert_eq!(refreshed.windows.len(), 2);
+        assert_eq!(refreshed.active_window().unwrap().name, "second");
+
+        assert!(matches!(
+            bad,
+            Err(TmuxBootstrapError::CommandFailed { ref output, .. }) if !output.is_empty()
+        ));
+    }
+}
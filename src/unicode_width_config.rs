@@ -4,6 +4,7 @@
 //! It supports:
 //! - Different Unicode versions for width tables
 //! - Configurable treatment of East Asian Ambiguous width characters
+//! - Grapheme cluster width policy (DEC private mode 2027)
 //!
 //! # Unicode Version Support
 //!
@@ -28,6 +29,7 @@
 //! ```
 
 use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthChar;
 
 /// Unicode version for width calculation tables.
@@ -177,6 +179,125 @@ impl WidthConfig {
     }
 }
 
+/// How grapheme clusters are measured, and how DEC private mode 2027
+/// (grapheme cluster processing) is reported to applications.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphemeClustering {
+    /// Applications toggle cluster processing with DECSET/DECRST 2027
+    /// (enabled until reset); DECRQM reports set (1) or reset (2)
+    #[default]
+    Negotiable,
+    /// Clusters are always measured as a unit; DECRQM reports permanently set (3)
+    Always,
+    /// Legacy per-code-point widths; DECRQM reports permanently reset (4)
+    Never,
+}
+
+/// Width policy negotiated with applications: which Unicode version's
+/// width tables to use and whether grapheme clusters are measured as a unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct UnicodeWidthPolicy {
+    /// Unicode version for width tables
+    #[serde(default)]
+    pub unicode_version: UnicodeVersion,
+    /// Grapheme cluster handling (mode 2027)
+    #[serde(default)]
+    pub grapheme_clustering: GraphemeClustering,
+}
+
+impl UnicodeWidthPolicy {
+    /// Create a new policy
+    pub fn new(unicode_version: UnicodeVersion, grapheme_clustering: GraphemeClustering) -> Self {
+        Self {
+            unicode_version,
+            grapheme_clustering,
+        }
+    }
+}
+
+/// Unicode version that introduced a wide (emoji presentation) code point,
+/// for code points added after Unicode 9.0. Older applications measure
+/// these as narrow, so pinning an older [`UnicodeVersion`] makes them 1 cell.
+fn wide_since(c: char) -> Option<(u8, u8)> {
+    let code = c as u32;
+    if !(0x1F6D5..=0x1FAFF).contains(&code) {
+        return None;
+    }
+    Some(match code {
+        0x1F6F7..=0x1F6F8
+        | 0x1F91F
+        | 0x1F928..=0x1F92F
+        | 0x1F94C
+        | 0x1F95F..=0x1F96B
+        | 0x1F992..=0x1F997
+        | 0x1F9D0..=0x1F9E6 => (10, 0),
+        0x1F6F9
+        | 0x1F94D..=0x1F94F
+        | 0x1F96C..=0x1F970
+        | 0x1F973..=0x1F976
+        | 0x1F97A
+        | 0x1F97C..=0x1F97F
+        | 0x1F998..=0x1F9A2
+        | 0x1F9B0..=0x1F9B9
+        | 0x1F9C1..=0x1F9C2
+        | 0x1F9E7..=0x1F9FF => (11, 0),
+        0x1F6D5
+        | 0x1F6FA
+        | 0x1F7E0..=0x1F7EB
+        | 0x1F90D..=0x1F90F
+        | 0x1F93F
+        | 0x1F971
+        | 0x1F97B
+        | 0x1F9A5..=0x1F9AA
+        | 0x1F9AE..=0x1F9AF
+        | 0x1F9BA..=0x1F9BF
+        | 0x1F9C3..=0x1F9CA
+        | 0x1F9CD..=0x1F9CF
+        | 0x1FA70..=0x1FA73
+        | 0x1FA78..=0x1FA7A
+        | 0x1FA80..=0x1FA82
+        | 0x1FA90..=0x1FA95 => (12, 0),
+        0x1F6D6..=0x1F6D7
+        | 0x1F6FB..=0x1F6FC
+        | 0x1F90C
+        | 0x1F972
+        | 0x1F977..=0x1F978
+        | 0x1F9A3..=0x1F9A4
+        | 0x1F9AB..=0x1F9AD
+        | 0x1F9CB
+        | 0x1FA74
+        | 0x1FA83..=0x1FA86
+        | 0x1FA96..=0x1FAA8
+        | 0x1FAB0..=0x1FAB6
+        | 0x1FAC0..=0x1FAC2
+        | 0x1FAD0..=0x1FAD6 => (13, 0),
+        0x1F6DD..=0x1F6DF
+        | 0x1F7F0
+        | 0x1F979
+        | 0x1F9CC
+        | 0x1FA7B..=0x1FA7C
+        | 0x1FAA9..=0x1FAAC
+        | 0x1FAB7..=0x1FABA
+        | 0x1FAC3..=0x1FAC5
+        | 0x1FAD7..=0x1FAD9
+        | 0x1FAE0..=0x1FAE7
+        | 0x1FAF0..=0x1FAF6 => (14, 0),
+        0x1F6DC
+        | 0x1FA75..=0x1FA77
+        | 0x1FA87..=0x1FA88
+        | 0x1FAAD..=0x1FAAF
+        | 0x1FABB..=0x1FABD
+        | 0x1FABF
+        | 0x1FACE..=0x1FACF
+        | 0x1FADA..=0x1FADB
+        | 0x1FAE8
+        | 0x1FAF7..=0x1FAF8 => (15, 0),
+        0x1FA89 | 0x1FA8F | 0x1FABE | 0x1FAC6 | 0x1FADC | 0x1FADF | 0x1FAE9 => (16, 0),
+        _ => return None,
+    })
+}
+
 /// Check if a character is East Asian Ambiguous.
 ///
 /// East Asian Ambiguous characters are those that have uncertain width,
@@ -350,7 +471,9 @@ pub fn is_east_asian_ambiguous(c: char) -> bool {
 /// Calculate the display width of a character.
 ///
 /// This function calculates how many terminal cells a character occupies,
-/// taking into account the width configuration.
+/// taking into account the width configuration. Emoji introduced after a
+/// pinned `unicode_version` are measured as narrow, as an application using
+/// that version's tables would.
 ///
 /// # Arguments
 ///
@@ -382,6 +505,13 @@ pub fn char_width(c: char, config: &WidthConfig) -> usize {
         return 2;
     }
 
+    // Emoji newer than the selected Unicode version are narrow there
+    if let (Some(selected), Some(since)) = (config.unicode_version.version_tuple(), wide_since(c)) {
+        if since > selected {
+            return 1;
+        }
+    }
+
     // Use unicode-width crate for standard width calculation
     // The crate handles most cases correctly including:
     // - Control characters (0 width)
@@ -399,9 +529,48 @@ pub fn char_width_cjk(c: char) -> usize {
     char_width(c, &WidthConfig::cjk())
 }
 
+/// Calculate the display width of a single grapheme cluster.
+///
+/// A cluster is as wide as its widest code point, so combining marks, spacing
+/// marks, joiners and Hangul jamo never add columns and an emoji ZWJ sequence
+/// is as wide as one emoji. Flags (regional indicator pairs) and clusters
+/// carrying the emoji presentation selector (U+FE0F) are always 2 cells.
+///
+/// # Arguments
+///
+/// * `grapheme` - A single extended grapheme cluster
+/// * `config` - Width configuration settings
+///
+/// # Returns
+///
+/// The display width in cells (0, 1 or 2)
+pub fn grapheme_width(grapheme: &str, config: &WidthConfig) -> usize {
+    let mut chars = grapheme.chars();
+    let Some(first) = chars.next() else {
+        return 0;
+    };
+    if chars.as_str().is_empty() {
+        return char_width(first, config);
+    }
+    if grapheme.contains('\u{FE0F}')
+        || (crate::grapheme::is_regional_indicator(first)
+            && chars.any(crate::grapheme::is_regional_indicator))
+    {
+        return 2;
+    }
+    grapheme
+        .chars()
+        .map(|c| char_width(c, config))
+        .max()
+        .unwrap_or(0)
+        .min(2)
+}
+
 /// Calculate the display width of a string.
 ///
-/// This sums the widths of all characters in the string.
+/// This sums the widths of the string's grapheme clusters (see
+/// [`grapheme_width`]), so multi-code-point clusters such as emoji ZWJ
+/// sequences and accented letters are counted once.
 ///
 /// # Arguments
 ///
@@ -412,7 +581,10 @@ pub fn char_width_cjk(c: char) -> usize {
 ///
 /// The total display width in cells
 pub fn str_width(s: &str, config: &WidthConfig) -> usize {
-    s.chars().map(|c| char_width(c, config)).sum()
+    if s.is_ascii() {
+        return s.chars().map(|c| char_width(c, config)).sum();
+    }
+    s.graphemes(true).map(|g| grapheme_width(g, config)).sum()
 }
 
 /// Calculate the display width of a string with CJK ambiguous width.
@@ -575,6 +747,17 @@ mod tests {
         assert_eq!(str_width("a\u{4E00}b", &config), 4);
     }
 
+    #[test]
+    fn test_str_width_counts_clusters_once() {
+        let config = WidthConfig::default();
+        assert_eq!(str_width("e\u{0301}", &config), 1);
+        assert_eq!(str_width("\u{1F468}\u{200D}\u{1F4BB}x", &config), 3); // 👨‍💻 + x
+        assert_eq!(str_width("\u{1F1FA}\u{1F1F8}", &config), 2); // 🇺🇸
+        assert_eq!(str_width("\u{2764}\u{FE0F}", &config), 2); // ❤️
+        assert_eq!(str_width("\u{1100}\u{1161}\u{11A8}", &config), 2); // 각 (conjoining jamo)
+        assert_eq!(grapheme_width("", &config), 0);
+    }
+
     #[test]
     fn test_str_width_cjk_function() {
         // Test the convenience function
@@ -616,4 +799,25 @@ mod tests {
         assert!(config.unicode_version.is_auto());
         assert!(config.ambiguous_width.is_narrow());
     }
+
+    #[test]
+    fn test_char_width_respects_unicode_version() {
+        // U+1FAE0 MELTING FACE was added in Unicode 14.0
+        let melting = '\u{1FAE0}';
+        assert_eq!(char_width(melting, &WidthConfig::default()), 2);
+        let v13 = WidthConfig::new(UnicodeVersion::Unicode13, AmbiguousWidth::Narrow);
+        assert_eq!(char_width(melting, &v13), 1);
+        let v14 = WidthConfig::new(UnicodeVersion::Unicode14, AmbiguousWidth::Narrow);
+        assert_eq!(char_width(melting, &v14), 2);
+        // Emoji from Unicode 9.0 and earlier are wide in every version
+        let v9 = WidthConfig::new(UnicodeVersion::Unicode9, AmbiguousWidth::Narrow);
+        assert_eq!(char_width('\u{1F600}', &v9), 2);
+    }
+
+    #[test]
+    fn test_width_policy_serde_defaults() {
+        let policy: UnicodeWidthPolicy = serde_json::from_str("{}").unwrap();
+        assert_eq!(policy, UnicodeWidthPolicy::default());
+        assert_eq!(policy.grapheme_clustering, GraphemeClustering::Negotiable);
+    }
 }
@@ -0,0 +1,165 @@
+//! WebAssembly bindings for running the terminal core in the browser
+//!
+//! Built with `--no-default-features --features wasm` for the
+//! `wasm32-unknown-unknown` target (e.g. via `wasm-pack build`). The browser
+//! has no PTY, so the host feeds output bytes to [`WasmTerminal::process`]
+//! and sends [`WasmTerminal::drain_responses`] back to wherever the output
+//! came from (usually a WebSocket).
+//!
+//! Snapshots and events are returned as JSON strings in the same shapes the
+//! streaming protocol uses (`screen_snapshot`, `semantic_snapshot`, and the
+//! event messages), so a frontend can share one decoder between a local and a
+//! remote terminal.
+
+use wasm_bindgen::prelude::*;
+
+use crate::streaming::protocol::{terminal_event_to_server_message, terminal_screen_snapshot};
+use crate::terminal::{SnapshotScope, Terminal};
+
+/// A terminal emulator instance exported to JavaScript
+#[wasm_bindgen]
+pub struct WasmTerminal {
+    inner: Terminal,
+}
+
+#[wasm_bindgen]
+impl WasmTerminal {
+    /// Create a terminal with the default scrollback size
+    #[wasm_bindgen(constructor)]
+    pub fn new(cols: usize, rows: usize) -> WasmTerminal {
+        WasmTerminal {
+            inner: Terminal::new(cols, rows),
+        }
+    }
+
+    /// Create a terminal keeping up to `scrollback` lines of history
+    #[wasm_bindgen(js_name = withScrollback)]
+    pub fn with_scrollback(cols: usize, rows: usize, scrollback: usize) -> WasmTerminal {
+        WasmTerminal {
+            inner: Terminal::with_scrollback(cols, rows, scrollback),
+        }
+    }
+
+    /// Process output bytes (e.g. a `Uint8Array` from a WebSocket)
+    pub fn process(&mut self, data: &[u8]) {
+        self.inner.process(data);
+    }
+
+    /// Process output text
+    #[wasm_bindgen(js_name = processStr)]
+    pub fn process_str(&mut self, text: &str) {
+        self.inner.process(text.as_bytes());
+    }
+
+    /// Resize the terminal
+    pub fn resize(&mut self, cols: usize, rows: usize) {
+        self.inner.resize(cols, rows);
+    }
+
+    /// Terminal width in columns
+    #[wasm_bindgen(getter)]
+    pub fn cols(&self) -> usize {
+        self.inner.size().0
+    }
+
+    /// Terminal height in rows
+    #[wasm_bindgen(getter)]
+    pub fn rows(&self) -> usize {
+        self.inner.size().1
+    }
+
+    /// Current window title
+    #[wasm_bindgen(getter)]
+    pub fn title(&self) -> String {
+        self.inner.title().to_string()
+    }
+
+    /// Visible screen as plain text
+    pub fn content(&self) -> String {
+        self.inner.content()
+    }
+
+    /// Visible screen with ANSI styling
+    #[wasm_bindgen(js_name = styledContent)]
+    pub fn styled_content(&self) -> String {
+        self.inner.export_visible_screen_styled()
+    }
+
+    /// Visible screen as a JSON `screen_snapshot` message: styled cell runs,
+    /// cursor and zones
+    #[wasm_bindgen(js_name = screenSnapshot)]
+    pub fn screen_snapshot(&self) -> String {
+        serde_json::to_string(&terminal_screen_snapshot(&self.inner)).unwrap_or_default()
+    }
+
+    /// Semantic snapshot as JSON
+    ///
+    /// `scope` is `"visible"`, `"recent"` (the last `max_commands` commands)
+    /// or `"full"`.
+    #[wasm_bindgen(js_name = semanticSnapshot)]
+    pub fn semantic_snapshot(&self, scope: &str, max_commands: usize) -> Result<String, JsError> {
+        let scope = match scope {
+            "visible" => SnapshotScope::Visible,
+            "recent" => SnapshotScope::Recent(max_commands),
+            "full" => SnapshotScope::Full,
+            _ => return Err(JsError::new("scope must be 'visible', 'recent', or 'full'")),
+        };
+        Ok(self.inner.get_semantic_snapshot_json(scope))
+    }
+
+    /// Drain pending events as a JSON array of streaming event messages
+    ///
+    /// Events with no streaming representation (such as dirty regions) are
+    /// left out.
+    #[wasm_bindgen(js_name = pollEvents)]
+    pub fn poll_events(&mut self) -> String {
+        let messages: Vec<_> = self
+            .inner
+            .poll_events()
+            .into_iter()
+            .filter_map(terminal_event_to_server_message)
+            .collect();
+        serde_json::to_string(&messages).unwrap_or_else(|_| "[]".to_string())
+    }
+
+    /// Drain replies the terminal wants to send back to the application
+    /// (device attributes, cursor reports, ...)
+    #[wasm_bindgen(js_name = drainResponses)]
+    pub fn drain_responses(&mut self) -> Vec<u8> {
+        self.inner.drain_responses()
+    }
+
+    /// Cursor column (0-indexed)
+    #[wasm_bindgen(getter, js_name = cursorCol)]
+    pub fn cursor_col(&self) -> usize {
+        self.inner.cursor().col
+    }
+
+    /// Cursor row (0-indexed)
+    #[wasm_bindgen(getter, js_name = cursorRow)]
+    pub fn cursor_row(&self) -> usize {
+        self.inner.cursor().row
+    }
+
+    /// Reset the terminal to its initial state
+    pub fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn process_and_snapshot() {
+        let mut term = WasmTerminal::new(20, 3);
+        term.process_str("\x1b]2;demo\x07hello\x1b[6n");
+
+        assert_eq!(term.title(), "demo");
+        assert!(term.content().starts_with("hello"));
+        assert_eq!(term.drain_responses(), b"\x1b[1;6R");
+        assert!(term.screen_snapshot().contains(r#""text":"hello""#));
+        assert!(term.poll_events().contains(r#""type":"title""#));
+    }
+}
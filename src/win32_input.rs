@@ -0,0 +1,419 @@
+//! Windows Terminal win32-input-mode key encoding
+//!
+//! When an application enables DEC private mode 9001, every key press and
+//! release is sent as a serialized Win32 `KEY_EVENT_RECORD`:
+//!
+//! ```text
+//! CSI Vk ; Sc ; Uc ; Kd ; Cs ; Rc _
+//! ```
+//!
+//! | Field | Meaning | Default |
+//! |-------|---------|---------|
+//! | `Vk` | Virtual key code (`wVirtualKeyCode`) | 0 |
+//! | `Sc` | Virtual scan code (`wVirtualScanCode`) | 0 |
+//! | `Uc` | UTF-16 code unit of the character (`uChar.UnicodeChar`) | 0 |
+//! | `Kd` | 1 for key down, 0 for key up (`bKeyDown`) | 0 |
+//! | `Cs` | Control key state (`dwControlKeyState`) | 0 |
+//! | `Rc` | Repeat count (`wRepeatCount`) | 1 |
+//!
+//! ConPTY translates these records back into console input, so applications
+//! running through it on Windows see the same records as a native console.
+//!
+//! # Example
+//!
+//! ```
+//! use par_term_emu_core_rust::kitty_keyboard::{Key, KeyEvent};
+//! use par_term_emu_core_rust::win32_input;
+//!
+//! let bytes = win32_input::encode_key_event(&KeyEvent::new(Key::Char('a')));
+//! assert_eq!(bytes, b"\x1b[65;30;97;1;0;1_");
+//! ```
+
+use crate::kitty_keyboard::{
+    Key, KeyEvent, KeyEventType, KeyModifiers, KeypadKey, MediaKey, ModifierKey,
+};
+
+/// `dwControlKeyState` bits
+pub const RIGHT_ALT_PRESSED: u32 = 0x0001;
+pub const LEFT_ALT_PRESSED: u32 = 0x0002;
+pub const RIGHT_CTRL_PRESSED: u32 = 0x0004;
+pub const LEFT_CTRL_PRESSED: u32 = 0x0008;
+pub const SHIFT_PRESSED: u32 = 0x0010;
+pub const NUMLOCK_ON: u32 = 0x0020;
+pub const SCROLLLOCK_ON: u32 = 0x0040;
+pub const CAPSLOCK_ON: u32 = 0x0080;
+pub const ENHANCED_KEY: u32 = 0x0100;
+
+/// A serialized Win32 `KEY_EVENT_RECORD`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Win32KeyEvent {
+    pub virtual_key_code: u16,
+    pub virtual_scan_code: u16,
+    /// UTF-16 code unit (0 when the key produces no character)
+    pub unicode_char: u16,
+    pub key_down: bool,
+    pub control_key_state: u32,
+    pub repeat_count: u16,
+}
+
+impl Default for Win32KeyEvent {
+    fn default() -> Self {
+        Self {
+            virtual_key_code: 0,
+            virtual_scan_code: 0,
+            unicode_char: 0,
+            key_down: false,
+            control_key_state: 0,
+            repeat_count: 1,
+        }
+    }
+}
+
+impl Win32KeyEvent {
+    /// `CSI Vk ; Sc ; Uc ; Kd ; Cs ; Rc _`
+    pub fn encode(&self) -> Vec<u8> {
+        format!(
+            "\x1b[{};{};{};{};{};{}_",
+            self.virtual_key_code,
+            self.virtual_scan_code,
+            self.unicode_char,
+            u8::from(self.key_down),
+            self.control_key_state,
+            self.repeat_count
+        )
+        .into_bytes()
+    }
+}
+
+/// Encode a key event as win32-input-mode records
+///
+/// Characters outside the Basic Multilingual Plane are sent as two records,
+/// one per UTF-16 surrogate, as a Windows console does.
+pub fn encode_key_event(event: &KeyEvent) -> Vec<u8> {
+    to_records(event)
+        .iter()
+        .flat_map(Win32KeyEvent::encode)
+        .collect()
+}
+
+/// Translate a key event into Win32 key records
+pub fn to_records(event: &KeyEvent) -> Vec<Win32KeyEvent> {
+    let (virtual_key_code, virtual_scan_code, enhanced) = key_codes(event.key);
+    let mut control_key_state = control_key_state(event.modifiers);
+    if enhanced {
+        control_key_state |= ENHANCED_KEY;
+    }
+    let record = Win32KeyEvent {
+        virtual_key_code,
+        virtual_scan_code,
+        unicode_char: 0,
+        key_down: event.event_type != KeyEventType::Release,
+        control_key_state,
+        repeat_count: 1,
+    };
+
+    let units: Vec<u16> = match key_char(event) {
+        Some(c) => c.encode_utf16(&mut [0; 2]).to_vec(),
+        None => return vec![record],
+    };
+    units
+        .into_iter()
+        .map(|unit| Win32KeyEvent {
+            unicode_char: unit,
+            ..record
+        })
+        .collect()
+}
+
+fn control_key_state(modifiers: KeyModifiers) -> u32 {
+    let mut state = 0;
+    if modifiers.contains(KeyModifiers::SHIFT) {
+        state |= SHIFT_PRESSED;
+    }
+    if modifiers.contains(KeyModifiers::ALT) {
+        state |= LEFT_ALT_PRESSED;
+    }
+    if modifiers.contains(KeyModifiers::CTRL) {
+        state |= LEFT_CTRL_PRESSED;
+    }
+    if modifiers.contains(KeyModifiers::CAPS_LOCK) {
+        state |= CAPSLOCK_ON;
+    }
+    if modifiers.contains(KeyModifiers::NUM_LOCK) {
+        state |= NUMLOCK_ON;
+    }
+    state
+}
+
+/// Character a console reports for the event (`uChar.UnicodeChar`)
+fn key_char(event: &KeyEvent) -> Option<char> {
+    let mods = event.modifiers;
+    let ctrl = mods.contains(KeyModifiers::CTRL) && !mods.contains(KeyModifiers::ALT);
+    match event.key {
+        Key::Char(c) => {
+            if ctrl {
+                return crate::kitty_keyboard::ctrl_byte(c).map(char::from);
+            }
+            if let Some(text) = &event.text {
+                return text.chars().next();
+            }
+            let upper = mods.contains(KeyModifiers::SHIFT)
+                != (mods.contains(KeyModifiers::CAPS_LOCK) && c.is_alphabetic());
+            match (upper, event.shifted_key) {
+                (true, Some(shifted)) => Some(shifted),
+                (true, None) => c.to_uppercase().next(),
+                (false, _) => Some(c),
+            }
+        }
+        Key::Enter | Key::Keypad(KeypadKey::Enter) => Some(if ctrl { '\n' } else { '\r' }),
+        Key::Tab => Some('\t'),
+        Key::Backspace => Some(if ctrl { '\x7f' } else { '\x08' }),
+        Key::Escape => Some('\x1b'),
+        Key::Keypad(k) => match k {
+            KeypadKey::Digit(d) if mods.contains(KeyModifiers::NUM_LOCK) => {
+                Some((b'0' + d.min(9)) as char)
+            }
+            KeypadKey::Decimal if mods.contains(KeyModifiers::NUM_LOCK) => Some('.'),
+            KeypadKey::Divide => Some('/'),
+            KeypadKey::Multiply => Some('*'),
+            KeypadKey::Subtract => Some('-'),
+            KeypadKey::Add => Some('+'),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Virtual key code, scan code and whether the key is an enhanced key
+fn key_codes(key: Key) -> (u16, u16, bool) {
+    match key {
+        Key::Char(c) => char_codes(c),
+        Key::Escape => (0x1B, 0x01, false),
+        Key::Enter => (0x0D, 0x1C, false),
+        Key::Tab => (0x09, 0x0F, false),
+        Key::Backspace => (0x08, 0x0E, false),
+        Key::Insert => (0x2D, 0x52, true),
+        Key::Delete => (0x2E, 0x53, true),
+        Key::Left => (0x25, 0x4B, true),
+        Key::Right => (0x27, 0x4D, true),
+        Key::Up => (0x26, 0x48, true),
+        Key::Down => (0x28, 0x50, true),
+        Key::PageUp => (0x21, 0x49, true),
+        Key::PageDown => (0x22, 0x51, true),
+        Key::Home => (0x24, 0x47, true),
+        Key::End => (0x23, 0x4F, true),
+        Key::CapsLock => (0x14, 0x3A, false),
+        Key::ScrollLock => (0x91, 0x46, false),
+        Key::NumLock => (0x90, 0x45, true),
+        Key::PrintScreen => (0x2C, 0x37, true),
+        Key::Pause => (0x13, 0x45, false),
+        Key::Menu => (0x5D, 0x5D, true),
+        Key::F(n @ 1..=10) => (0x6F + n as u16, 0x3A + n as u16, false),
+        Key::F(11) => (0x7A, 0x57, false),
+        Key::F(12) => (0x7B, 0x58, false),
+        Key::F(n @ 13..=24) => (0x6F + n as u16, 0, false),
+        Key::F(_) => (0, 0, false),
+        Key::Keypad(k) => keypad_codes(k),
+        Key::Media(k) => {
+            let vk = match k {
+                MediaKey::PlayPause | MediaKey::Play | MediaKey::Pause => 0xB3,
+                MediaKey::Stop => 0xB2,
+                MediaKey::TrackNext | MediaKey::FastForward => 0xB0,
+                MediaKey::TrackPrevious | MediaKey::Rewind | MediaKey::Reverse => 0xB1,
+                MediaKey::MuteVolume => 0xAD,
+                MediaKey::LowerVolume => 0xAE,
+                MediaKey::RaiseVolume => 0xAF,
+                MediaKey::Record => 0,
+            };
+            (vk, 0, true)
+        }
+        // Consoles report the generic VK_SHIFT/VK_CONTROL/VK_MENU codes,
+        // with right-hand keys marked as enhanced
+        Key::Modifier(k) => match k {
+            ModifierKey::LeftShift => (0x10, 0x2A, false),
+            ModifierKey::RightShift => (0x10, 0x36, false),
+            ModifierKey::LeftControl => (0x11, 0x1D, false),
+            ModifierKey::RightControl => (0x11, 0x1D, true),
+            ModifierKey::LeftAlt => (0x12, 0x38, false),
+            ModifierKey::RightAlt | ModifierKey::IsoLevel3Shift => (0x12, 0x38, true),
+            ModifierKey::LeftSuper => (0x5B, 0x5B, true),
+            ModifierKey::RightSuper => (0x5C, 0x5C, true),
+            ModifierKey::LeftHyper
+            | ModifierKey::RightHyper
+            | ModifierKey::LeftMeta
+            | ModifierKey::RightMeta
+            | ModifierKey::IsoLevel5Shift => (0, 0, false),
+        },
+    }
+}
+
+fn keypad_codes(key: KeypadKey) -> (u16, u16, bool) {
+    match key {
+        KeypadKey::Digit(d) => {
+            const SCAN: [u16; 10] = [0x52, 0x4F, 0x50, 0x51, 0x4B, 0x4C, 0x4D, 0x47, 0x48, 0x49];
+            let d = d.min(9);
+            (0x60 + d as u16, SCAN[d as usize], false)
+        }
+        KeypadKey::Decimal => (0x6E, 0x53, false),
+        KeypadKey::Divide => (0x6F, 0x35, true),
+        KeypadKey::Multiply => (0x6A, 0x37, false),
+        KeypadKey::Subtract => (0x6D, 0x4A, false),
+        KeypadKey::Add => (0x6B, 0x4E, false),
+        KeypadKey::Enter => (0x0D, 0x1C, true),
+        KeypadKey::Separator => (0x6C, 0, false),
+        KeypadKey::Equal => (0x92, 0x59, false),
+        KeypadKey::Left => (0x25, 0x4B, false),
+        KeypadKey::Right => (0x27, 0x4D, false),
+        KeypadKey::Up => (0x26, 0x48, false),
+        KeypadKey::Down => (0x28, 0x50, false),
+        KeypadKey::PageUp => (0x21, 0x49, false),
+        KeypadKey::PageDown => (0x22, 0x51, false),
+        KeypadKey::Home => (0x24, 0x47, false),
+        KeypadKey::End => (0x23, 0x4F, false),
+        KeypadKey::Insert => (0x2D, 0x52, false),
+        KeypadKey::Delete => (0x2E, 0x53, false),
+        KeypadKey::Begin => (0x0C, 0x4C, false),
+    }
+}
+
+/// Virtual key and scan codes of a text key on a US layout
+fn char_codes(c: char) -> (u16, u16, bool) {
+    const ROWS: [(&str, u16); 4] = [
+        ("1234567890", 0x02),
+        ("qwertyuiop", 0x10),
+        ("asdfghjkl", 0x1E),
+        ("zxcvbnm", 0x2C),
+    ];
+    let lower = c.to_ascii_lowercase();
+    for (row, first_scan) in ROWS {
+        if let Some(i) = row.find(lower) {
+            return (
+                lower.to_ascii_uppercase() as u16,
+                first_scan + i as u16,
+                false,
+            );
+        }
+    }
+    let (vk, sc) = match lower {
+        ' ' => (0x20, 0x39),
+        '-' => (0xBD, 0x0C),
+        '=' => (0xBB, 0x0D),
+        '[' => (0xDB, 0x1A),
+        ']' => (0xDD, 0x1B),
+        ';' => (0xBA, 0x27),
+        '\'' => (0xDE, 0x28),
+        '`' => (0xC0, 0x29),
+        '\\' => (0xDC, 0x2B),
+        ',' => (0xBC, 0x33),
+        '.' => (0xBE, 0x34),
+        '/' => (0xBF, 0x35),
+        // Characters without a key on a US layout (typed through a
+        // different layout or an input method)
+        _ => (0, 0),
+    };
+    (vk, sc, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(event: KeyEvent) -> String {
+        String::from_utf8(encode_key_event(&event)).unwrap()
+    }
+
+    #[test]
+    fn test_text_keys() {
+        assert_eq!(
+            encode(KeyEvent::new(Key::Char('a'))),
+            "\x1b[65;30;97;1;0;1_"
+        );
+        assert_eq!(
+            encode(KeyEvent::new(Key::Char('a')).with_modifiers(KeyModifiers::SHIFT)),
+            "\x1b[65;30;65;1;16;1_"
+        );
+        assert_eq!(
+            encode(
+                KeyEvent::new(Key::Char('1'))
+                    .with_modifiers(KeyModifiers::SHIFT)
+                    .with_shifted_key('!')
+            ),
+            "\x1b[49;2;33;1;16;1_"
+        );
+        assert_eq!(
+            encode(KeyEvent::new(Key::Char('c')).with_modifiers(KeyModifiers::CTRL)),
+            "\x1b[67;46;3;1;8;1_"
+        );
+        assert_eq!(
+            encode(KeyEvent::new(Key::Char('a')).with_modifiers(KeyModifiers::CAPS_LOCK)),
+            "\x1b[65;30;65;1;128;1_"
+        );
+        assert_eq!(
+            encode(KeyEvent::new(Key::Char(' '))),
+            "\x1b[32;57;32;1;0;1_"
+        );
+    }
+
+    #[test]
+    fn test_key_up_records() {
+        assert_eq!(
+            encode(KeyEvent::new(Key::Char('a')).with_event_type(KeyEventType::Release)),
+            "\x1b[65;30;97;0;0;1_"
+        );
+        assert_eq!(
+            encode(
+                KeyEvent::new(Key::Modifier(ModifierKey::LeftShift))
+                    .with_modifiers(KeyModifiers::SHIFT)
+            ),
+            "\x1b[16;42;0;1;16;1_"
+        );
+        assert_eq!(
+            encode(
+                KeyEvent::new(Key::Modifier(ModifierKey::RightControl))
+                    .with_event_type(KeyEventType::Release)
+            ),
+            "\x1b[17;29;0;0;256;1_"
+        );
+    }
+
+    #[test]
+    fn test_functional_keys() {
+        assert_eq!(encode(KeyEvent::new(Key::Enter)), "\x1b[13;28;13;1;0;1_");
+        assert_eq!(encode(KeyEvent::new(Key::Escape)), "\x1b[27;1;27;1;0;1_");
+        assert_eq!(encode(KeyEvent::new(Key::Backspace)), "\x1b[8;14;8;1;0;1_");
+        assert_eq!(encode(KeyEvent::new(Key::Up)), "\x1b[38;72;0;1;256;1_");
+        assert_eq!(encode(KeyEvent::new(Key::F(1))), "\x1b[112;59;0;1;0;1_");
+        assert_eq!(encode(KeyEvent::new(Key::F(12))), "\x1b[123;88;0;1;0;1_");
+        assert_eq!(
+            encode(
+                KeyEvent::new(Key::Keypad(KeypadKey::Digit(5)))
+                    .with_modifiers(KeyModifiers::NUM_LOCK)
+            ),
+            "\x1b[101;76;53;1;32;1_"
+        );
+        assert_eq!(
+            encode(KeyEvent::new(Key::Keypad(KeypadKey::Enter))),
+            "\x1b[13;28;13;1;256;1_"
+        );
+    }
+
+    #[test]
+    fn test_non_bmp_text_uses_surrogate_records() {
+        let records = to_records(&KeyEvent::new(Key::Char('😀')));
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].unicode_char, 0xD83D);
+        assert_eq!(records[1].unicode_char, 0xDE00);
+        assert_eq!(records[0].virtual_key_code, 0);
+    }
+
+    #[test]
+    fn test_record_encoding() {
+        let record = Win32KeyEvent {
+            virtual_key_code: 0x41,
+            key_down: true,
+            repeat_count: 3,
+            ..Default::default()
+        };
+        assert_eq!(record.encode(), b"\x1b[65;0;0;1;0;3_");
+    }
+}
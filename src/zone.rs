@@ -1,7 +1,8 @@
 //! Semantic buffer zones for tracking logical blocks in terminal output
 //!
 //! Zones segment the scrollback buffer into Prompt, Command, and Output
-//! blocks using FinalTerm/OSC 133 shell integration markers.
+//! blocks using FinalTerm/OSC 133 shell integration markers, or heuristic
+//! prompt detection when the shell sends no markers.
 
 /// Type of semantic zone in the terminal buffer
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -27,7 +28,8 @@ impl std::fmt::Display for ZoneType {
 /// A semantic zone in the terminal buffer
 ///
 /// Zones track logical blocks of terminal content using absolute row numbers.
-/// They are created by OSC 133 shell integration markers and stored in a
+/// They are created by OSC 133 shell integration markers (or by heuristic
+/// prompt detection, see [`Zone::heuristic`]) and stored in a
 /// Vec on the Grid, sorted by `abs_row_start`.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Zone {
@@ -46,6 +48,10 @@ pub struct Zone {
     pub exit_code: Option<i32>,
     /// Timestamp in Unix milliseconds when this zone was created
     pub timestamp: Option<u64>,
+    /// Inferred by heuristic prompt detection rather than from OSC 133 markers
+    pub heuristic: bool,
+    /// Output folded (collapsed) by the frontend; only set on Output zones
+    pub folded: bool,
 }
 
 impl Zone {
@@ -59,6 +65,8 @@ impl Zone {
             command: None,
             exit_code: None,
             timestamp,
+            heuristic: false,
+            folded: false,
         }
     }
 
@@ -87,6 +95,8 @@ mod tests {
         assert!(zone.command.is_none());
         assert!(zone.exit_code.is_none());
         assert_eq!(zone.timestamp, Some(1000));
+        assert!(!zone.heuristic);
+        assert!(!zone.folded);
     }
 
     #[test]
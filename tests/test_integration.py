@@ -225,19 +225,22 @@ class TestFeatureIntegration:
                 assert "X" in line
 
     def test_synchronized_updates(self):
-        """Test synchronized updates buffer correctly."""
+        """Test synchronized updates hold back dirty rows until the update ends."""
         term = Terminal(80, 24)
 
         # Enable synchronized updates
         term.process_str("\x1b[?2026h")
         assert term.synchronized_updates()
 
-        # Write content (should be buffered)
+        # Write content (parsed now, dirty rows held back)
+        term.mark_clean()
         term.process_str("Buffered content")
+        assert term.get_dirty_rows() == []
 
-        # Disable (should flush)
+        # Disable (commits the frame)
         term.process_str("\x1b[?2026l")
         assert not term.synchronized_updates()
+        assert term.get_dirty_rows() == [0]
 
         # Content should now be visible
         content = term.content()
@@ -4,7 +4,7 @@ Tests for par_term_emu
 """
 
 import pytest
-from par_term_emu_core_rust import Terminal
+from par_term_emu_core_rust import NormalizationForm, Terminal
 
 
 def test_terminal_creation():
@@ -642,6 +642,124 @@ def test_dec_save_restore_cursor():
     assert col == 19 and row == 9
 
 
+def test_dec_save_restore_origin_mode_and_charset():
+    """DECRC restores origin mode and the character sets saved by DECSC"""
+    term = Terminal(20, 10)
+    term.process_str("\x1b[3;8r\x1b[?6h\x1b(0\x1b7")
+    term.process_str("\x1b[?6l\x1b(B\x1b8")
+    assert term.origin_mode()
+
+    term.process_str("\x1b[1;1Hq")
+    assert term.get_line(2).rstrip() == "\u2500"
+
+
+def test_state_handle_is_immutable():
+    """A state handle keeps the screen it was taken from"""
+    term = Terminal(20, 5)
+    term.process_str("hello\x1b[?2004h")
+    state = term.state_handle()
+    term.process_str("\x1b[2J\x1b[Hbye\x1b[?1049h")
+
+    assert state.size() == (20, 5)
+    assert state.get_line(0).rstrip() == "hello"
+    assert state.cursor_position() == (5, 0)
+    assert state.modes()["bracketed_paste"]
+    assert not state.is_alt_screen()
+    assert term.state_handle().is_alt_screen()
+
+
+def test_double_buffering_holds_synchronized_update():
+    """The front buffer keeps the last frame until a synchronized update ends"""
+    term = Terminal(20, 5)
+    term.set_double_buffering(True)
+    assert term.double_buffering()
+    term.process_str("old")
+    term.process_str("\x1b[?2026h\x1b[2J\x1b[Hnew")
+
+    assert term.front_buffer().get_line(0).rstrip() == "old"
+    term.process_str("\x1b[?2026l")
+    assert term.front_buffer().get_line(0).rstrip() == "new"
+
+    frames = term.committed_frames()
+    term.commit_frame()
+    assert term.committed_frames() == frames + 1
+
+
+def test_find_text_unicode_columns_and_normalization():
+    """find_text reports columns past wide chars and matches normalized forms"""
+    term = Terminal(40, 3)
+    term.process_str("日本 cafe\u0301 \ufb01le")
+
+    assert term.find_text("caf\u00e9") == [(5, 0)]
+    assert term.find_text("CAFÉ", case_sensitive=False) == [(5, 0)]
+    assert term.find_text("file") == []
+    assert term.find_text("file", normalization=NormalizationForm.NFKC) == [(10, 0)]
+
+
+def test_column_index_mapping():
+    """Columns and row text indices convert both ways around wide characters"""
+    term = Terminal(20, 3)
+    term.process_str("日本 ok")
+    line = term.get_line(0)
+
+    assert line.index("ok") == 3
+    assert term.index_to_column(0, 3) == 5
+    assert term.column_to_index(0, 5) == 3
+    assert term.column_to_index(0, 1) == 0
+    assert term.column_to_index(0, 20) == len(line)
+    assert term.column_to_index(0, 21) is None
+
+
+def test_export_html_themed_uses_runtime_palette():
+    """Themed HTML export follows OSC 4 palette changes and inlines links"""
+    term = Terminal(30, 3)
+    term.process(b"\x1b]4;1;rgb:10/20/30\x1b\\\x1b[31mred\x1b[0m ")
+    term.process(b"\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x1b\\")
+
+    html = term.export_html_themed()
+    assert "--term-color-1: rgb(16, 32, 48);" in html
+    assert "var(--term-color-1, rgb(16, 32, 48))" in html
+    assert "<a href" not in html
+
+    html = term.export_html_themed(include_styles=False, css_variables=False, hyperlinks=True)
+    assert "color: rgb(16, 32, 48)" in html
+    assert '<a href="https://example.com">link</a>' in html
+
+    palette = [(i, i, i) for i in range(16)]
+    html = term.export_html_themed(palette=palette, default_bg=(9, 8, 7))
+    assert "var(--term-color-1, rgb(1, 1, 1))" in html
+    assert "--term-bg: rgb(9, 8, 7);" in html
+
+    with pytest.raises(ValueError):
+        term.export_html_themed(palette=palette[:3])
+
+
+def test_background_image_requests():
+    """Background requests are gated, then recorded with events"""
+    import base64
+
+    term = Terminal(20, 5)
+    path = base64.b64encode(b"/tmp/bg.png").decode()
+    term.process(f"\x1b]1337;SetBackgroundImageFile={path}\x07".encode())
+    assert term.get_background_image() is None
+
+    term.set_allow_remote_background(True)
+    assert term.allow_remote_background()
+    term.poll_events()
+    term.process(f"\x1b]1337;SetBackgroundImageFile={path}\x07".encode())
+    assert term.get_background_image() == {"type": "file", "path": "/tmp/bg.png"}
+    events = [e for e in term.poll_events() if e["type"] == "background_changed"]
+    assert events == [{"type": "background_changed", "change": "image"}]
+
+    term.process(b'\x1bP@kitty-cmd{"cmd":"set-background-opacity","payload":{"opacity":0.5}}\x1b\\')
+    assert term.get_background_opacity() == 0.5
+    assert bytes(term.drain_responses()) == b'\x1bP@kitty-cmd{"ok":true}\x1b\\'
+
+    term.clear_background_requests()
+    assert term.get_background_image() is None
+    assert term.get_background_opacity() is None
+
+
 def test_alternate_screen_no_scrollback():
     """Test that alternate screen doesn't add to scrollback"""
     term = Terminal(80, 5, scrollback=100)
@@ -810,6 +928,67 @@ def test_da_primary_with_param():
     assert response == b"\x1b[?62;1;4;6;9;15;22;52c"
 
 
+def test_decid_matches_primary_da():
+    """ESC Z (DECID) replies like Primary DA"""
+    term = Terminal(80, 24)
+    term.process(b"\x1b[c")
+    da = term.drain_responses()
+
+    term.process(b"\x1bZ")
+    assert term.drain_responses() == da
+
+
+def test_vt52_mode():
+    """CSI ? 2 l enters VT52 mode and ESC < leaves it"""
+    term = Terminal(80, 24)
+    term.process(b"\x1b[?2l")
+    assert term.vt52_mode()
+
+    term.process(b"\x1bY%(X")
+    assert term.get_line(5).rstrip() == "        X"
+    term.process(b"\x1bZ")
+    assert term.drain_responses() == b"\x1b/Z"
+
+    term.process(b"\x1b<")
+    assert not term.vt52_mode()
+
+
+def test_nrcs_german():
+    """With DECNRCM set, ESC ( K selects the German NRCS"""
+    term = Terminal(80, 24)
+    term.process(b"\x1b(K[")
+    assert term.get_line(0).rstrip() == "["
+
+    term.process(b"\x1b[?42h")
+    assert term.nrcs_mode()
+    term.process(b"\x1b(K[]~")
+    assert term.get_line(0).rstrip() == "[ÄÜß"
+
+
+def test_dsr_private_reports():
+    """DECXCPR, printer, UDK and keyboard status reports"""
+    term = Terminal(80, 24)
+    term.process(b"\x1b[10;20H\x1b[?6n")
+    assert term.drain_responses() == b"\x1b[?10;20;1R"
+
+    term.process(b"\x1b[?15n\x1b[?25n\x1b[?26n")
+    assert term.drain_responses() == b"\x1b[?13n\x1b[?20n\x1b[?27;1;0;0n"
+
+
+def test_lr_margins_wrap_and_scroll():
+    """DECSLRM confines wrapping and scrolling to the margins"""
+    term = Terminal(12, 5)
+    term.process(b"\x1b[?69h\x1b[3;6s")
+    assert term.lr_margin_mode()
+    term.process(b"\x1b[1;3Habcdefgh")
+    assert term.get_line(0).rstrip() == "  abcd"
+    assert term.get_line(1).rstrip() == "  efgh"
+
+    term.process(b"\x1b[?69l")
+    assert not term.lr_margin_mode()
+    assert term.left_right_margins() == (0, 11)
+
+
 def test_da_secondary():
     """Test Secondary Device Attributes (DA) query"""
     term = Terminal(80, 24)
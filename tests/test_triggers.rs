@@ -1,6 +1,6 @@
 // Integration tests for trigger system (Feature 18)
 use par_term_emu_core_rust::terminal::trigger::TriggerAction;
-use par_term_emu_core_rust::terminal::Terminal;
+use par_term_emu_core_rust::terminal::{Terminal, TerminalEvent};
 
 #[test]
 fn test_trigger_add_remove() {
@@ -320,3 +320,93 @@ fn test_trigger_frontend_actions() {
     let results = term.poll_action_results();
     assert_eq!(results.len(), 3);
 }
+
+#[test]
+fn test_trigger_action_set_user_var() {
+    let mut term = Terminal::new(80, 24);
+    term.add_trigger(
+        "test".into(),
+        r"branch: (\S+)".into(),
+        vec![TriggerAction::SetUserVar {
+            name: "git_branch".into(),
+            value: "$1".into(),
+        }],
+    )
+    .unwrap();
+
+    term.process(b"branch: main\n");
+    term.process_trigger_scans();
+
+    assert_eq!(term.get_user_var("git_branch"), Some("main"));
+    assert!(term.poll_events().iter().any(|e| matches!(
+        e,
+        TerminalEvent::UserVarChanged { name, .. } if name == "git_branch"
+    )));
+}
+
+#[test]
+fn test_trigger_action_emit_event() {
+    let mut term = Terminal::new(80, 24);
+    let id = term
+        .add_trigger(
+            "test".into(),
+            r"FAILED: (\w+)".into(),
+            vec![TriggerAction::EmitEvent {
+                name: "failed-$1".into(),
+            }],
+        )
+        .unwrap();
+
+    term.process(b"FAILED: build\n");
+    term.process_trigger_scans();
+
+    let fired: Vec<_> = term
+        .poll_events()
+        .into_iter()
+        .filter_map(|e| match e {
+            TerminalEvent::TriggerFired {
+                name,
+                trigger_match,
+            } => Some((name, trigger_match)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(fired.len(), 1);
+    assert_eq!(fired[0].0, "failed-build");
+    assert_eq!(fired[0].1.trigger_id, id);
+    assert_eq!(fired[0].1.captures, vec!["FAILED: build", "build"]);
+}
+
+#[test]
+fn test_trigger_action_mark_command_failed() {
+    let mut term = Terminal::new(80, 24);
+    term.add_trigger(
+        "test".into(),
+        "panicked at".into(),
+        vec![TriggerAction::MarkCommandFailed],
+    )
+    .unwrap();
+
+    // Running command stays failed even when it exits with 0
+    term.start_command_execution("cargo run".into());
+    term.process(b"thread 'main' panicked at src/main.rs\n");
+    term.process_trigger_scans();
+    term.end_command_execution(Some(0));
+    let last = term.get_command_history().last().unwrap();
+    assert_eq!(last.exit_code, Some(0));
+    assert_eq!(last.success, Some(false));
+
+    // With nothing running, the last finished command is marked
+    term.start_command_execution("true".into());
+    term.end_command_execution(Some(0));
+    assert_eq!(
+        term.get_command_history().last().unwrap().success,
+        Some(true)
+    );
+    term.process(b"panicked at later\n");
+    term.process_trigger_scans();
+    assert_eq!(
+        term.get_command_history().last().unwrap().success,
+        Some(false)
+    );
+}